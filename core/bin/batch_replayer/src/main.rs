@@ -0,0 +1,85 @@
+use anyhow::Context as _;
+use clap::Parser;
+use zksync_config::{
+    configs::{chain::NetworkConfig, ObservabilityConfig},
+    PostgresConfig,
+};
+use zksync_dal::ConnectionPool;
+use zksync_env_config::FromEnv;
+use zksync_types::L1BatchNumber;
+
+use crate::replayer::BatchReplayer;
+
+mod replayer;
+
+#[derive(Debug, Parser)]
+#[command(
+    author = "Matter Labs",
+    version,
+    about = "L1 batch replay utility",
+    long_about = "Re-executes L1 batches from transactions and storage recorded in Postgres and compares \
+        transaction statuses, gas usage, events and storage writes with the recorded ones. \
+        Reports the first divergent transaction."
+)]
+struct Cli {
+    /// Number of the L1 batch to replay.
+    #[arg(long = "l1-batch")]
+    l1_batch: u32,
+    /// If specified, L1 batches are replayed sequentially up to and including this batch, stopping
+    /// at the first divergence.
+    #[arg(long = "to-l1-batch")]
+    to_l1_batch: Option<u32>,
+}
+
+impl Cli {
+    async fn run(self, replayer: &BatchReplayer) -> anyhow::Result<()> {
+        let last_l1_batch = self.to_l1_batch.unwrap_or(self.l1_batch);
+        anyhow::ensure!(
+            last_l1_batch >= self.l1_batch,
+            "`--to-l1-batch` must not be less than `--l1-batch`"
+        );
+
+        for number in self.l1_batch..=last_l1_batch {
+            let l1_batch_number = L1BatchNumber(number);
+            let divergence = replayer
+                .replay(l1_batch_number)
+                .await
+                .with_context(|| format!("failed replaying L1 batch #{l1_batch_number}"))?;
+            if let Some(divergence) = divergence {
+                print!("{divergence}");
+                anyhow::bail!("L1 batch #{l1_batch_number} diverges from the recorded execution");
+            }
+            println!("L1 batch #{l1_batch_number} matches the recorded execution");
+        }
+        Ok(())
+    }
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let observability_config =
+        ObservabilityConfig::from_env().context("ObservabilityConfig::from_env()")?;
+    let log_format: vlog::LogFormat = observability_config
+        .log_format
+        .parse()
+        .context("Invalid log format")?;
+    let mut builder = vlog::ObservabilityBuilder::new().with_log_format(log_format);
+    if let Some(sentry_url) = observability_config.sentry_url {
+        builder = builder
+            .with_sentry_url(&sentry_url)
+            .context("Invalid Sentry URL")?
+            .with_sentry_environment(observability_config.sentry_environment);
+    }
+    let _guard = builder.build()?;
+
+    let postgres_config = PostgresConfig::from_env().context("PostgresConfig::from_env()")?;
+    let network_config = NetworkConfig::from_env().context("NetworkConfig::from_env()")?;
+    // The replayer only reads data, so it can use a replica. One connection is used to load recorded data,
+    // and another one is used by the VM storage.
+    let connection_pool = ConnectionPool::builder(postgres_config.replica_url()?, 2)
+        .build()
+        .await
+        .context("failed to build a connection pool")?;
+    let replayer = BatchReplayer::new(connection_pool, network_config.zksync_network_id);
+    Cli::parse().run(&replayer).await
+}
@@ -0,0 +1,456 @@
+//! Deterministic replay of L1 batches persisted in Postgres.
+
+use std::{collections::HashMap, fmt};
+
+use anyhow::Context as _;
+use multivm::interface::{ExecutionResult, L2BlockEnv, VmExecutionResultAndLogs, VmInterface};
+use tokio::runtime::Handle;
+use vm_utils::{create_vm, execute_tx};
+use zksync_dal::ConnectionPool;
+use zksync_types::{
+    api::{Log, TransactionReceipt},
+    block::MiniblockExecutionData,
+    storage_writes_deduplicator::StorageWritesDeduplicator,
+    Address, L1BatchNumber, L2ChainId, MiniblockNumber, StorageKey, StorageLogQuery, Transaction,
+    VmEvent, H256, U256, U64,
+};
+use zksync_utils::u256_to_h256;
+
+/// Event emitted by a transaction in the form comparable between replayed and recorded data.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Event {
+    address: Address,
+    topics: Vec<H256>,
+    data: Vec<u8>,
+}
+
+impl From<&Log> for Event {
+    fn from(log: &Log) -> Self {
+        Self {
+            address: log.address,
+            topics: log.topics.clone(),
+            data: log.data.0.clone(),
+        }
+    }
+}
+
+impl From<&VmEvent> for Event {
+    fn from(event: &VmEvent) -> Self {
+        Self {
+            address: event.address,
+            topics: event.indexed_topics.clone(),
+            data: event.value.clone(),
+        }
+    }
+}
+
+impl fmt::Display for Event {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            formatter,
+            "{:?} topics={:?} data=0x{}",
+            self.address,
+            self.topics,
+            hex::encode(&self.data)
+        )
+    }
+}
+
+/// Mismatch between the replayed and recorded execution of a transaction.
+#[derive(Debug)]
+pub(crate) enum Mismatch {
+    Status {
+        recorded_success: bool,
+        replayed: String,
+    },
+    GasUsed {
+        recorded: Option<U256>,
+        replayed: U256,
+    },
+    Events {
+        recorded: Vec<Event>,
+        replayed: Vec<Event>,
+    },
+    StorageWrite {
+        key: StorageKey,
+        recorded: Option<H256>,
+        replayed: Option<H256>,
+    },
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Status {
+                recorded_success,
+                replayed,
+            } => {
+                let recorded = if *recorded_success {
+                    "success"
+                } else {
+                    "failure"
+                };
+                write!(
+                    formatter,
+                    "status: recorded {recorded}, replayed {replayed}"
+                )
+            }
+            Self::GasUsed { recorded, replayed } => {
+                write!(
+                    formatter,
+                    "gas used: recorded {recorded:?}, replayed {replayed}"
+                )
+            }
+            Self::Events { recorded, replayed } => {
+                writeln!(
+                    formatter,
+                    "events: recorded {}, replayed {}",
+                    recorded.len(),
+                    replayed.len()
+                )?;
+                let max_len = recorded.len().max(replayed.len());
+                for i in 0..max_len {
+                    let (recorded, replayed) = (recorded.get(i), replayed.get(i));
+                    if recorded == replayed {
+                        continue;
+                    }
+                    writeln!(formatter, "    #{i} recorded: {}", OptionalEvent(recorded))?;
+                    writeln!(formatter, "    #{i} replayed: {}", OptionalEvent(replayed))?;
+                }
+                Ok(())
+            }
+            Self::StorageWrite {
+                key,
+                recorded,
+                replayed,
+            } => {
+                write!(
+                    formatter,
+                    "storage write to {:?} / {:?}: recorded {recorded:?}, replayed {replayed:?}",
+                    key.address(),
+                    key.key()
+                )
+            }
+        }
+    }
+}
+
+struct OptionalEvent<'a>(Option<&'a Event>);
+
+impl fmt::Display for OptionalEvent<'_> {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Some(event) => fmt::Display::fmt(event, formatter),
+            None => formatter.write_str("(none)"),
+        }
+    }
+}
+
+/// First divergence between the replayed and recorded execution of an L1 batch.
+#[derive(Debug)]
+pub(crate) struct Divergence {
+    pub miniblock_number: MiniblockNumber,
+    /// Index of the divergent transaction in the L1 batch.
+    pub tx_index_in_batch: usize,
+    /// Hash of the divergent transaction, or `None` if the batch tip diverges.
+    pub tx_hash: Option<H256>,
+    pub mismatches: Vec<Mismatch>,
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.tx_hash {
+            Some(tx_hash) => writeln!(
+                formatter,
+                "Transaction {tx_hash:?} (#{} in L1 batch, miniblock #{}) diverges:",
+                self.tx_index_in_batch, self.miniblock_number
+            )?,
+            None => writeln!(
+                formatter,
+                "Batch tip (miniblock #{}) diverges:",
+                self.miniblock_number
+            )?,
+        }
+        for mismatch in &self.mismatches {
+            writeln!(formatter, "  - {mismatch}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Execution data of an L1 batch recorded in Postgres.
+#[derive(Debug)]
+struct RecordedBatch {
+    miniblocks: Vec<MiniblockExecutionData>,
+    receipts: HashMap<H256, TransactionReceipt>,
+    /// Deduplicated storage writes for each miniblock, grouped by transaction hash.
+    storage_writes: HashMap<MiniblockNumber, HashMap<H256, HashMap<StorageKey, H256>>>,
+}
+
+impl RecordedBatch {
+    fn compare_tx(
+        &self,
+        tx: &Transaction,
+        result: &VmExecutionResultAndLogs,
+    ) -> anyhow::Result<Vec<Mismatch>> {
+        let tx_hash = tx.hash();
+        let receipt = self
+            .receipts
+            .get(&tx_hash)
+            .with_context(|| format!("no receipt recorded for transaction {tx_hash:?}"))?;
+        let mut mismatches = vec![];
+
+        let recorded_success = receipt.status == U64::one();
+        if recorded_success == result.result.is_failed() {
+            let replayed = match &result.result {
+                ExecutionResult::Success { .. } => "success".to_owned(),
+                ExecutionResult::Revert { output } => format!("revert ({output})"),
+                ExecutionResult::Halt { reason } => format!("halt ({reason})"),
+            };
+            mismatches.push(Mismatch::Status {
+                recorded_success,
+                replayed,
+            });
+        }
+
+        let replayed_gas_used = tx.gas_limit() - U256::from(result.refunds.gas_refunded);
+        if receipt.gas_used != Some(replayed_gas_used) {
+            mismatches.push(Mismatch::GasUsed {
+                recorded: receipt.gas_used,
+                replayed: replayed_gas_used,
+            });
+        }
+
+        let recorded_events: Vec<_> = receipt.logs.iter().map(Event::from).collect();
+        let replayed_events: Vec<_> = result.logs.events.iter().map(Event::from).collect();
+        if recorded_events != replayed_events {
+            mismatches.push(Mismatch::Events {
+                recorded: recorded_events,
+                replayed: replayed_events,
+            });
+        }
+        Ok(mismatches)
+    }
+
+    /// Compares storage writes in a miniblock. `storage_logs` are all logs produced by the VM in the miniblock
+    /// (including the batch tip if the miniblock is fictive); `first_tx_index` is the index of the first
+    /// miniblock transaction in the L1 batch. Returns mismatches for each transaction hash; the batch tip
+    /// uses the zero hash.
+    fn compare_storage_writes(
+        &self,
+        miniblock: &MiniblockExecutionData,
+        first_tx_index: usize,
+        storage_logs: &[StorageLogQuery],
+    ) -> HashMap<H256, Vec<Mismatch>> {
+        // Deduplication mirrors the one performed by the state keeper when persisting storage logs.
+        let mut deduplicator = StorageWritesDeduplicator::new();
+        deduplicator.apply(storage_logs.iter().filter(|log| log.log_query.rw_flag));
+        let mut replayed_writes = HashMap::<_, HashMap<_, _>>::new();
+        for (key, slot) in deduplicator.into_modified_key_values() {
+            let tx_hash = (slot.tx_index as usize)
+                .checked_sub(first_tx_index)
+                .and_then(|index| miniblock.txs.get(index))
+                .map_or_else(H256::zero, Transaction::hash);
+            replayed_writes
+                .entry(tx_hash)
+                .or_default()
+                .insert(key, u256_to_h256(slot.value));
+        }
+
+        let empty = HashMap::new();
+        let recorded_writes = self.storage_writes.get(&miniblock.number).unwrap_or(&empty);
+        let tx_hashes = recorded_writes.keys().chain(replayed_writes.keys());
+        let mut mismatches_by_tx = HashMap::new();
+        for &tx_hash in tx_hashes {
+            if mismatches_by_tx.contains_key(&tx_hash) {
+                continue;
+            }
+            let recorded = recorded_writes.get(&tx_hash).unwrap_or(&empty);
+            let replayed = replayed_writes.get(&tx_hash).unwrap_or(&empty);
+            let mut keys: Vec<_> = recorded.keys().chain(replayed.keys()).collect();
+            keys.sort_unstable_by_key(|key| (*key.address(), *key.key()));
+            keys.dedup();
+
+            let mismatches: Vec<_> = keys
+                .into_iter()
+                .filter_map(|key| {
+                    let (recorded, replayed) = (recorded.get(key), replayed.get(key));
+                    (recorded != replayed).then(|| Mismatch::StorageWrite {
+                        key: *key,
+                        recorded: recorded.copied(),
+                        replayed: replayed.copied(),
+                    })
+                })
+                .collect();
+            mismatches_by_tx.insert(tx_hash, mismatches);
+        }
+        mismatches_by_tx
+    }
+}
+
+/// Re-executes L1 batches from transactions and storage recorded in Postgres and compares the execution
+/// results (transaction statuses, gas usage, events and storage writes) with the recorded ones.
+#[derive(Debug)]
+pub(crate) struct BatchReplayer {
+    pool: ConnectionPool,
+    l2_chain_id: L2ChainId,
+}
+
+impl BatchReplayer {
+    pub fn new(pool: ConnectionPool, l2_chain_id: L2ChainId) -> Self {
+        Self { pool, l2_chain_id }
+    }
+
+    /// Replays the specified L1 batch. Returns the first divergence, or `None` if the replayed execution
+    /// matches the recorded one.
+    pub async fn replay(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<Option<Divergence>> {
+        let recorded = self.load_recorded_batch(l1_batch_number).await?;
+        let pool = self.pool.clone();
+        let l2_chain_id = self.l2_chain_id;
+        tokio::task::spawn_blocking(move || {
+            Self::replay_blocking(
+                Handle::current(),
+                &pool,
+                l1_batch_number,
+                l2_chain_id,
+                &recorded,
+            )
+        })
+        .await
+        .context("replay panicked")?
+    }
+
+    async fn load_recorded_batch(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<RecordedBatch> {
+        let mut storage = self.pool.access_storage_tagged("batch_replayer").await?;
+        let miniblocks = storage
+            .transactions_dal()
+            .get_miniblocks_to_execute_for_l1_batch(l1_batch_number)
+            .await
+            .with_context(|| {
+                format!("failed loading miniblocks for L1 batch #{l1_batch_number}")
+            })?;
+        anyhow::ensure!(
+            !miniblocks.is_empty(),
+            "L1 batch #{l1_batch_number} is not sealed or doesn't exist"
+        );
+
+        let tx_hashes: Vec<_> = miniblocks
+            .iter()
+            .flat_map(|miniblock| miniblock.txs.iter().map(Transaction::hash))
+            .collect();
+        let receipts = storage
+            .transactions_web3_dal()
+            .get_transaction_receipts(&tx_hashes)
+            .await
+            .context("failed loading transaction receipts")?;
+        let receipts = receipts
+            .into_iter()
+            .map(|receipt| (receipt.transaction_hash, receipt))
+            .collect();
+
+        let mut storage_writes = HashMap::with_capacity(miniblocks.len());
+        for miniblock in &miniblocks {
+            let logs = storage
+                .storage_logs_dal()
+                .get_miniblock_storage_logs_by_tx(miniblock.number)
+                .await
+                .with_context(|| {
+                    format!(
+                        "failed loading storage logs for miniblock #{}",
+                        miniblock.number
+                    )
+                })?;
+            let writes = logs
+                .into_iter()
+                .map(|(tx_hash, logs)| {
+                    let writes = logs.into_iter().map(|log| (log.key, log.value)).collect();
+                    (tx_hash, writes)
+                })
+                .collect();
+            storage_writes.insert(miniblock.number, writes);
+        }
+
+        Ok(RecordedBatch {
+            miniblocks,
+            receipts,
+            storage_writes,
+        })
+    }
+
+    fn replay_blocking(
+        rt_handle: Handle,
+        pool: &ConnectionPool,
+        l1_batch_number: L1BatchNumber,
+        l2_chain_id: L2ChainId,
+        recorded: &RecordedBatch,
+    ) -> anyhow::Result<Option<Divergence>> {
+        let connection = rt_handle.block_on(pool.access_storage_tagged("batch_replayer"))?;
+        let (mut vm, _) = create_vm(rt_handle.clone(), l1_batch_number, connection, l2_chain_id)
+            .with_context(|| format!("failed creating VM for L1 batch #{l1_batch_number}"))?;
+        tracing::info!("Started replaying L1 batch #{l1_batch_number}");
+
+        let mut tx_index_in_batch = 0;
+        for (i, miniblock) in recorded.miniblocks.iter().enumerate() {
+            if i > 0 {
+                vm.start_new_l2_block(L2BlockEnv::from_miniblock_data(miniblock));
+            }
+            tracing::debug!(
+                "Replaying miniblock #{} with {} transactions",
+                miniblock.number,
+                miniblock.txs.len()
+            );
+
+            let first_tx_index = tx_index_in_batch;
+            let mut tx_mismatches = Vec::with_capacity(miniblock.txs.len());
+            let mut storage_logs = vec![];
+            for tx in &miniblock.txs {
+                let result = execute_tx(tx, &mut vm)
+                    .with_context(|| format!("failed executing transaction {:?}", tx.hash()))?;
+                tx_mismatches.push(recorded.compare_tx(tx, &result)?);
+                storage_logs.extend(result.logs.storage_logs);
+                tx_index_in_batch += 1;
+            }
+
+            let is_last_miniblock = i + 1 == recorded.miniblocks.len();
+            if is_last_miniblock {
+                let finished_batch = vm.finish_batch();
+                storage_logs.extend(finished_batch.block_tip_execution_result.logs.storage_logs);
+            }
+
+            let mut storage_mismatches =
+                recorded.compare_storage_writes(miniblock, first_tx_index, &storage_logs);
+            let txs_with_mismatches = miniblock.txs.iter().zip(tx_mismatches).enumerate();
+            for (local_index, (tx, mut mismatches)) in txs_with_mismatches {
+                let tx_hash = tx.hash();
+                mismatches.extend(storage_mismatches.remove(&tx_hash).unwrap_or_default());
+                if !mismatches.is_empty() {
+                    return Ok(Some(Divergence {
+                        miniblock_number: miniblock.number,
+                        tx_index_in_batch: first_tx_index + local_index,
+                        tx_hash: Some(tx_hash),
+                        mismatches,
+                    }));
+                }
+            }
+
+            let batch_tip_mismatches = storage_mismatches.remove(&H256::zero());
+            if let Some(mismatches) = batch_tip_mismatches.filter(|m| !m.is_empty()) {
+                return Ok(Some(Divergence {
+                    miniblock_number: miniblock.number,
+                    tx_index_in_batch,
+                    tx_hash: None,
+                    mismatches,
+                }));
+            }
+        }
+
+        tracing::info!("Finished replaying L1 batch #{l1_batch_number}");
+        Ok(None)
+    }
+}
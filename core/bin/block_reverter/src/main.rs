@@ -1,9 +1,14 @@
 use anyhow::Context as _;
 use clap::{Parser, Subcommand};
-use tokio::io::{self, AsyncReadExt};
+use prometheus_exporter::PrometheusExporterConfig;
+use tokio::{
+    io::{self, AsyncReadExt},
+    sync::watch,
+    task::JoinHandle,
+};
 use zksync_config::{
-    configs::ObservabilityConfig, ContractsConfig, DBConfig, ETHClientConfig, ETHSenderConfig,
-    PostgresConfig,
+    configs::{ObservabilityConfig, PrometheusConfig},
+    ContractsConfig, DBConfig, ETHClientConfig, ETHSenderConfig, PostgresConfig,
 };
 use zksync_core::block_reverter::{
     BlockReverter, BlockReverterEthConfig, BlockReverterFlags, L1ExecutedBatchesRevert,
@@ -62,6 +67,12 @@ enum Command {
         /// Flag that allows to revert already executed blocks, it's ultra dangerous and required only for fixing external nodes
         #[arg(long)]
         allow_executed_block_reversion: bool,
+        /// Only reports what would be reverted, without changing any data.
+        #[arg(long)]
+        dry_run: bool,
+        /// Displays the dry run report as a JSON object, so that it is machine-readable.
+        #[arg(long, requires = "dry_run")]
+        json: bool,
     },
 
     /// Clears failed L1 transactions.
@@ -69,6 +80,20 @@ enum Command {
     ClearFailedL1Transactions,
 }
 
+/// Starts pushing metrics to the Prometheus push gateway if it is configured. The block reverter finishes
+/// too quickly to be scraped, so pulling metrics is not supported.
+fn maybe_enable_prometheus_metrics(
+    stop_receiver: watch::Receiver<bool>,
+) -> Option<JoinHandle<anyhow::Result<()>>> {
+    let prometheus_config = PrometheusConfig::from_env().ok()?;
+    let exporter_config = PrometheusExporterConfig::push(
+        prometheus_config.gateway_endpoint_for_job("block_reverter"),
+        prometheus_config.push_interval(),
+    );
+    tracing::info!("Starting prometheus exporter with config {prometheus_config:?}");
+    Some(tokio::spawn(exporter_config.run(stop_receiver)))
+}
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     let observability_config =
@@ -85,7 +110,9 @@ async fn main() -> anyhow::Result<()> {
             .context("Invalid Sentry URL")?
             .with_sentry_environment(observability_config.sentry_environment);
     }
-    let _guard = builder.build();
+    let _guard = builder.build()?;
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let prometheus_exporter_task = maybe_enable_prometheus_metrics(stop_receiver);
 
     let eth_sender = ETHSenderConfig::from_env().context("ETHSenderConfig::from_env()")?;
     let db_config = DBConfig::from_env().context("DBConfig::from_env()")?;
@@ -141,7 +168,44 @@ async fn main() -> anyhow::Result<()> {
             rollback_tree,
             rollback_sk_cache,
             allow_executed_block_reversion,
+            dry_run,
+            json,
         } => {
+            let mut flags = BlockReverterFlags::empty();
+            if rollback_postgres {
+                flags |= BlockReverterFlags::POSTGRES;
+            }
+            if rollback_tree {
+                flags |= BlockReverterFlags::TREE;
+            }
+            if rollback_sk_cache {
+                flags |= BlockReverterFlags::SK_CACHE;
+            }
+
+            if dry_run {
+                if allow_executed_block_reversion {
+                    block_reverter.change_rollback_executed_l1_batches_allowance(
+                        L1ExecutedBatchesRevert::Allowed,
+                    );
+                }
+                let report = block_reverter
+                    .revert_impact(L1BatchNumber(l1_batch_number), flags)
+                    .await?;
+                if json {
+                    println!("{}", serde_json::to_string(&report).unwrap());
+                } else {
+                    println!("Revert impact (dry run, no data is changed): {report:#?}");
+                    if report.reverts_executed_batches() && !report.executed_batches_revert_allowed
+                    {
+                        println!(
+                            "Revert would fail: it reverts L1 batches executed on L1, \
+                             which requires `--allow-executed-block-reversion`"
+                        );
+                    }
+                }
+                std::process::exit(0);
+            }
+
             if !rollback_tree && rollback_postgres {
                 println!("You want to rollback Postgres DB without rolling back tree.");
                 println!(
@@ -172,21 +236,18 @@ async fn main() -> anyhow::Result<()> {
                 );
             }
 
-            let mut flags = BlockReverterFlags::empty();
-            if rollback_postgres {
-                flags |= BlockReverterFlags::POSTGRES;
-            }
-            if rollback_tree {
-                flags |= BlockReverterFlags::TREE;
-            }
-            if rollback_sk_cache {
-                flags |= BlockReverterFlags::SK_CACHE;
-            }
             block_reverter
                 .rollback_db(L1BatchNumber(l1_batch_number), flags)
                 .await
         }
         Command::ClearFailedL1Transactions => block_reverter.clear_failed_l1_transactions().await,
     }
+
+    stop_sender.send(true).ok();
+    if let Some(prometheus_exporter_task) = prometheus_exporter_task {
+        prometheus_exporter_task
+            .await?
+            .context("Prometheus did not finish gracefully")?;
+    }
     Ok(())
 }
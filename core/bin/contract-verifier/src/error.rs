@@ -18,6 +18,14 @@ pub enum ContractVerifierError {
     MissingContract(String),
     #[error("There is no {0} source file")]
     MissingSource(String),
+    #[error(
+        "Contract name {0} matches several source files; specify the source file as `path:{0}`"
+    )]
+    AmbiguousContract(String),
+    #[error("Source file path {0} is not a relative path inside the project")]
+    InvalidSourcePath(String),
+    #[error("Unsupported optimizer mode: {0}")]
+    UnsupportedOptimizerMode(String),
     #[error("Contract with {0} name is an abstract and thus is not verifiable")]
     AbstractContract(String),
     #[error("Failed to deserialize standard JSON input")]
@@ -156,7 +156,7 @@ async fn main() -> anyhow::Result<()> {
             .expect("Invalid Sentry URL")
             .with_sentry_environment(observability_config.sentry_environment);
     }
-    let _guard = builder.build();
+    let _guard = builder.build()?;
 
     // Report whether sentry is running after the logging subsystem was initialized.
     if let Some(sentry_url) = observability_config.sentry_url {
@@ -18,7 +18,7 @@ use zksync_queued_job_processor::{async_trait, JobProcessor};
 use zksync_types::{
     contract_verification_api::{
         CompilationArtifacts, CompilerType, DeployContractCalldata, SourceCodeData,
-        VerificationInfo, VerificationRequest,
+        VerificationInfo, VerificationMatch, VerificationRequest,
     },
     Address,
 };
@@ -33,6 +33,9 @@ lazy_static! {
     static ref DEPLOYER_CONTRACT: Contract = zksync_contracts::deployer_contract();
 }
 
+/// Length of the metadata hash appended by `zksolc` to the end of the bytecode.
+const METADATA_HASH_LEN: usize = 32;
+
 #[derive(Debug)]
 enum ConstructorArgs {
     Check(Vec<u8>),
@@ -74,7 +77,10 @@ impl ContractVerifier {
             request.req.contract_address,
         );
 
-        if artifacts.bytecode != deployed_bytecode {
+        let compiler_type = request.req.source_code_data.compiler_type();
+        let Some(match_type) =
+            Self::match_bytecode(compiler_type, &deployed_bytecode, &artifacts.bytecode)
+        else {
             tracing::info!(
                 "Bytecode mismatch req {}, deployed: 0x{}, compiled 0x{}",
                 request.id,
@@ -82,6 +88,12 @@ impl ContractVerifier {
                 hex::encode(artifacts.bytecode)
             );
             return Err(ContractVerifierError::BytecodeMismatch);
+        };
+        if match_type == VerificationMatch::Partial {
+            tracing::info!(
+                "Bytecode for req {} matches deployed bytecode up to the metadata hash",
+                request.id
+            );
         }
 
         match constructor_args {
@@ -99,9 +111,32 @@ impl ContractVerifier {
             request,
             artifacts,
             verified_at: Utc::now(),
+            match_type,
         })
     }
 
+    /// Compares the deployed bytecode with the compiled one. Bytecodes compiled by `zksolc` that only differ
+    /// in the trailing metadata hash are a partial match: the hash covers compiler metadata such as
+    /// source file names and comments, so it may differ for sources with the same logic.
+    fn match_bytecode(
+        compiler_type: CompilerType,
+        deployed_bytecode: &[u8],
+        compiled_bytecode: &[u8],
+    ) -> Option<VerificationMatch> {
+        if deployed_bytecode == compiled_bytecode {
+            return Some(VerificationMatch::Full);
+        }
+        if compiler_type != CompilerType::Solc
+            || deployed_bytecode.len() != compiled_bytecode.len()
+            || deployed_bytecode.len() < METADATA_HASH_LEN
+        {
+            return None;
+        }
+        let code_len = deployed_bytecode.len() - METADATA_HASH_LEN;
+        (deployed_bytecode[..code_len] == compiled_bytecode[..code_len])
+            .then_some(VerificationMatch::Partial)
+    }
+
     async fn compile_zksolc(
         request: VerificationRequest,
         config: ContractVerifierConfig,
@@ -208,14 +243,6 @@ impl ContractVerifier {
         request: VerificationRequest,
         config: ContractVerifierConfig,
     ) -> Result<CompilationArtifacts, ContractVerifierError> {
-        // Users may provide either just contract name or
-        // source file name and contract name joined with ":".
-        let contract_name =
-            if let Some((_file_name, contract_name)) = request.req.contract_name.rsplit_once(':') {
-                contract_name.to_string()
-            } else {
-                request.req.contract_name.clone()
-            };
         let input = Self::build_zkvyper_input(request.clone())?;
 
         let zksync_home = env::var("ZKSYNC_HOME").unwrap_or_else(|_| ".".into());
@@ -249,26 +276,7 @@ impl ContractVerifier {
             .await
             .map_err(|_| ContractVerifierError::CompilationTimeout)??;
 
-        let file_name = format!("{contract_name}.vy");
-        let object = output
-            .as_object()
-            .cloned()
-            .ok_or(ContractVerifierError::InternalError)?;
-        for (path, artifact) in object {
-            let path = Path::new(&path);
-            if path.file_name().unwrap().to_str().unwrap() == file_name {
-                let bytecode_str = artifact["bytecode"]
-                    .as_str()
-                    .ok_or(ContractVerifierError::InternalError)?;
-                let bytecode = hex::decode(bytecode_str).unwrap();
-                return Ok(CompilationArtifacts {
-                    abi: artifact["abi"].clone(),
-                    bytecode,
-                });
-            }
-        }
-
-        Err(ContractVerifierError::MissingContract(contract_name))
+        output.get_contract(&request.req.contract_name)
     }
 
     async fn compile(
@@ -348,6 +356,7 @@ impl ContractVerifier {
         Ok(ZkVyperInput {
             sources,
             optimizer_mode: request.req.optimizer_mode,
+            vyper_optimizer_enabled: request.req.optimization_used,
         })
     }
 
@@ -546,3 +555,40 @@ impl JobProcessor for ContractVerifier {
         Ok(1)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matching_bytecode() {
+        let deployed_bytecode = [[1_u8; 64], [2; 32]].concat();
+        let match_bytecode = |compiler_type, compiled_bytecode: &[u8]| {
+            ContractVerifier::match_bytecode(compiler_type, &deployed_bytecode, compiled_bytecode)
+        };
+
+        assert_eq!(
+            match_bytecode(CompilerType::Solc, &deployed_bytecode),
+            Some(VerificationMatch::Full)
+        );
+        assert_eq!(
+            match_bytecode(CompilerType::Vyper, &deployed_bytecode),
+            Some(VerificationMatch::Full)
+        );
+
+        let other_metadata_hash = [[1_u8; 64], [3; 32]].concat();
+        assert_eq!(
+            match_bytecode(CompilerType::Solc, &other_metadata_hash),
+            Some(VerificationMatch::Partial)
+        );
+        assert_eq!(
+            match_bytecode(CompilerType::Vyper, &other_metadata_hash),
+            None
+        );
+
+        let other_code = [[1_u8; 63], [4; 33]].concat();
+        assert_eq!(match_bytecode(CompilerType::Solc, &other_code), None);
+        let other_len = [1_u8; 64];
+        assert_eq!(match_bytecode(CompilerType::Solc, &other_len), None);
+    }
+}
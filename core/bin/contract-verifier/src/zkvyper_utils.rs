@@ -1,11 +1,151 @@
-use std::{collections::HashMap, fs::File, io::Write, path::PathBuf, process::Stdio};
+use std::{
+    collections::HashMap,
+    ffi::OsStr,
+    fs::File,
+    io::Write,
+    path::{Component, Path, PathBuf},
+    process::Stdio,
+};
+
+use zksync_types::contract_verification_api::CompilationArtifacts;
 
 use crate::error::ContractVerifierError;
 
+/// Optimization levels accepted by the `-O` option of `zkvyper`.
+const OPTIMIZER_MODES: &[&str] = &["0", "1", "2", "3", "s", "z"];
+
 #[derive(Debug)]
 pub struct ZkVyperInput {
     pub sources: HashMap<String, String>,
     pub optimizer_mode: Option<String>,
+    /// Whether the `vyper` optimizer is enabled. Corresponds to `optimization_used` in the verification request.
+    pub vyper_optimizer_enabled: bool,
+}
+
+impl ZkVyperInput {
+    /// Returns the source file name as it's passed to the compiler, i.e., with the `.vy` extension.
+    fn normalize_file_name(name: &str) -> String {
+        if name.ends_with(".vy") {
+            name.to_owned()
+        } else {
+            format!("{name}.vy")
+        }
+    }
+
+    /// Checks that the source file will be written inside the compilation directory.
+    fn validate_file_name(name: &str) -> Result<(), ContractVerifierError> {
+        let path = Path::new(name);
+        let is_valid = path.components().next().is_some()
+            && path
+                .components()
+                .all(|component| matches!(component, Component::Normal(_) | Component::CurDir));
+        if is_valid {
+            Ok(())
+        } else {
+            Err(ContractVerifierError::InvalidSourcePath(name.to_owned()))
+        }
+    }
+
+    fn validate(&self) -> Result<(), ContractVerifierError> {
+        if let Some(mode) = &self.optimizer_mode {
+            if !OPTIMIZER_MODES.contains(&mode.as_str()) {
+                return Err(ContractVerifierError::UnsupportedOptimizerMode(
+                    mode.clone(),
+                ));
+            }
+        }
+        self.sources
+            .keys()
+            .try_for_each(|name| Self::validate_file_name(name))
+    }
+}
+
+/// Output of `zkvyper` in the `combined_json` format, keyed by the source file path
+/// relative to the compilation directory.
+#[derive(Debug)]
+pub struct ZkVyperOutput {
+    contracts: HashMap<String, serde_json::Value>,
+}
+
+impl ZkVyperOutput {
+    fn new(
+        output: serde_json::Value,
+        compilation_dir: &Path,
+    ) -> Result<Self, ContractVerifierError> {
+        let serde_json::Value::Object(output) = output else {
+            tracing::error!("zkvyper returned unexpected output: {output}");
+            return Err(ContractVerifierError::InternalError);
+        };
+        let contracts = output
+            .into_iter()
+            // Besides contracts, the output contains compiler versions
+            .filter(|(_, artifact)| artifact.is_object())
+            .map(|(path, artifact)| {
+                let relative_path = Path::new(&path)
+                    .strip_prefix(compilation_dir)
+                    .map(|path| path.to_string_lossy().into_owned())
+                    .unwrap_or(path);
+                (relative_path, artifact)
+            })
+            .collect();
+        Ok(Self { contracts })
+    }
+
+    /// Returns artifacts for the specified contract. The contract can be specified either by its name, which
+    /// is the source file name without the extension for Vyper, or by the source file path and the contract name
+    /// joined with `:`.
+    pub fn get_contract(
+        &self,
+        contract_name: &str,
+    ) -> Result<CompilationArtifacts, ContractVerifierError> {
+        let (file_name, contract_name) = match contract_name.rsplit_once(':') {
+            Some((file_name, contract_name)) => (Some(file_name), contract_name),
+            None => (None, contract_name),
+        };
+
+        let (path, artifact) = if let Some(file_name) = file_name {
+            let file_name = ZkVyperInput::normalize_file_name(file_name);
+            let file_name = file_name.trim_start_matches("./");
+            self.contracts
+                .get_key_value(file_name)
+                .ok_or_else(|| ContractVerifierError::MissingSource(file_name.to_owned()))?
+        } else {
+            let file_name = ZkVyperInput::normalize_file_name(contract_name);
+            let mut matching_contracts = self
+                .contracts
+                .iter()
+                .filter(|(path, _)| Path::new(path).file_name() == Some(OsStr::new(&file_name)));
+            let contract = matching_contracts
+                .next()
+                .ok_or_else(|| ContractVerifierError::MissingContract(contract_name.to_owned()))?;
+            if matching_contracts.next().is_some() {
+                return Err(ContractVerifierError::AmbiguousContract(
+                    contract_name.to_owned(),
+                ));
+            }
+            contract
+        };
+
+        if Path::new(path).file_stem() != Some(OsStr::new(contract_name)) {
+            return Err(ContractVerifierError::MissingContract(
+                contract_name.to_owned(),
+            ));
+        }
+        let bytecode_str = artifact["bytecode"]
+            .as_str()
+            .ok_or_else(|| ContractVerifierError::AbstractContract(contract_name.to_owned()))?;
+        let bytecode_str = bytecode_str.strip_prefix("0x").unwrap_or(bytecode_str);
+        let bytecode = hex::decode(bytecode_str).map_err(|err| {
+            tracing::error!("zkvyper returned invalid bytecode for {path}: {err}");
+            ContractVerifierError::InternalError
+        })?;
+        let abi = artifact["abi"].clone();
+        if !abi.is_array() {
+            tracing::error!("zkvyper returned unexpected value for ABI of {path}: {abi}");
+            return Err(ContractVerifierError::InternalError);
+        }
+        Ok(CompilationArtifacts { bytecode, abi })
+    }
 }
 
 pub struct ZkVyper {
@@ -24,11 +164,16 @@ impl ZkVyper {
     pub async fn async_compile(
         &self,
         input: ZkVyperInput,
-    ) -> Result<serde_json::Value, ContractVerifierError> {
+    ) -> Result<ZkVyperOutput, ContractVerifierError> {
+        input.validate()?;
+
         let mut command = tokio::process::Command::new(&self.zkvyper_path);
         if let Some(o) = input.optimizer_mode.as_ref() {
             command.arg("-O").arg(o);
         }
+        if !input.vyper_optimizer_enabled {
+            command.arg("--disable-vyper-optimizer");
+        }
         command
             .arg("--vyper")
             .arg(self.vyper_path.to_str().unwrap())
@@ -38,11 +183,10 @@ impl ZkVyper {
             .stderr(Stdio::piped());
 
         let temp_dir = tempfile::tempdir().map_err(|_err| ContractVerifierError::InternalError)?;
-        for (mut name, content) in input.sources {
-            if !name.ends_with(".vy") {
-                name += ".vy";
-            }
-            let path = temp_dir.path().join(name);
+        for (name, content) in input.sources {
+            let path = temp_dir
+                .path()
+                .join(ZkVyperInput::normalize_file_name(&name));
             if let Some(prefix) = path.parent() {
                 std::fs::create_dir_all(prefix)
                     .map_err(|_err| ContractVerifierError::InternalError)?;
@@ -62,7 +206,9 @@ impl ZkVyper {
             .await
             .map_err(|_err| ContractVerifierError::InternalError)?;
         if output.status.success() {
-            Ok(serde_json::from_slice(&output.stdout).expect("Compiler output must be valid JSON"))
+            let output =
+                serde_json::from_slice(&output.stdout).expect("Compiler output must be valid JSON");
+            ZkVyperOutput::new(output, temp_dir.path())
         } else {
             Err(ContractVerifierError::CompilerError(
                 "zkvyper".to_string(),
@@ -71,3 +217,61 @@ impl ZkVyper {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validating_source_file_names() {
+        for name in [
+            "Counter",
+            "Counter.vy",
+            "contracts/Counter.vy",
+            "./Counter.vy",
+        ] {
+            ZkVyperInput::validate_file_name(name).unwrap();
+        }
+        for name in [
+            "",
+            "/etc/Counter.vy",
+            "../Counter.vy",
+            "contracts/../../Counter",
+        ] {
+            let err = ZkVyperInput::validate_file_name(name).unwrap_err();
+            assert!(
+                matches!(err, ContractVerifierError::InvalidSourcePath(_)),
+                "{err}"
+            );
+        }
+    }
+
+    #[test]
+    fn getting_contract_from_output() {
+        let compilation_dir = Path::new("/tmp/zkvyper");
+        let artifact = serde_json::json!({ "abi": [], "bytecode": "0x0102" });
+        let output = serde_json::json!({
+            "/tmp/zkvyper/Counter.vy": artifact,
+            "/tmp/zkvyper/lib/Token.vy": artifact,
+            "/tmp/zkvyper/other/Token.vy": artifact,
+            "version": "0.3.10",
+            "zk_version": "1.3.13",
+        });
+        let output = ZkVyperOutput::new(output, compilation_dir).unwrap();
+
+        let contract = output.get_contract("Counter").unwrap();
+        assert_eq!(contract.bytecode, [1, 2]);
+        output.get_contract("Counter.vy:Counter").unwrap();
+        output.get_contract("lib/Token.vy:Token").unwrap();
+        output.get_contract("other/Token:Token").unwrap();
+
+        let err = output.get_contract("Token").unwrap_err();
+        assert!(matches!(err, ContractVerifierError::AmbiguousContract(_)));
+        let err = output.get_contract("Missing").unwrap_err();
+        assert!(matches!(err, ContractVerifierError::MissingContract(_)));
+        let err = output.get_contract("Missing.vy:Missing").unwrap_err();
+        assert!(matches!(err, ContractVerifierError::MissingSource(_)));
+        let err = output.get_contract("Counter.vy:Token").unwrap_err();
+        assert!(matches!(err, ContractVerifierError::MissingContract(_)));
+    }
+}
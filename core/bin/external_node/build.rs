@@ -0,0 +1,21 @@
+//! Build script embedding the git hash of the build, which is used to tag Sentry events.
+
+use std::{env, process::Command};
+
+fn main() {
+    // Allows to specify the hash explicitly if the build is performed outside of the git repo (e.g., in Docker).
+    println!("cargo:rerun-if-env-changed=ZKSYNC_GIT_HASH");
+    println!("cargo:rerun-if-changed=../../../.git/HEAD");
+
+    let git_hash = env::var("ZKSYNC_GIT_HASH").ok().or_else(|| {
+        let output = Command::new("git")
+            .args(["rev-parse", "--short=12", "HEAD"])
+            .output()
+            .ok()?;
+        let hash = String::from_utf8(output.stdout).ok()?;
+        output.status.success().then(|| hash.trim().to_owned())
+    });
+    if let Some(git_hash) = git_hash {
+        println!("cargo:rustc-env=GIT_HASH={git_hash}");
+    }
+}
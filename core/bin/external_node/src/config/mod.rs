@@ -1,17 +1,28 @@
-use std::{env, time::Duration};
+use std::{num::NonZeroU32, time::Duration};
 
 use anyhow::Context;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use url::Url;
 use zksync_basic_types::{Address, L1ChainId, L2ChainId};
-use zksync_config::ObjectStoreConfig;
+use zksync_config::{
+    configs::{
+        api::{ApiAuditLogConfig, ApiRateLimitConfig, RpcInterfaceConfig},
+        database::{RocksdbCompactionStyle, RocksdbTuning},
+    },
+    ObjectStoreConfig,
+};
 use zksync_consensus_roles::node;
 use zksync_core::{
     api_server::{
         tx_sender::TxSenderConfig,
-        web3::{state::InternalApiConfig, Namespace},
+        web3::{state::InternalApiConfig, MethodFilter, Namespace},
     },
     consensus,
+    sync_layer::MainNodeFailoverConfig,
+};
+use zksync_env_config::{
+    deprecation::{self, Deprecation},
+    env_vars, ConfigDump, ConfigSource,
 };
 use zksync_types::api::BridgeAddresses;
 use zksync_web3_decl::{
@@ -26,6 +37,12 @@ mod tests;
 
 const BYTES_IN_MEGABYTE: usize = 1_024 * 1_024;
 
+/// Env variables that are no longer used by the external node.
+const IGNORED_VARS: &[Deprecation] = &[
+    Deprecation::ignored("EN_TRANSACTIONS_PER_SEC_LIMIT", "2024-06-30"),
+    Deprecation::ignored("EN_THREADS_PER_SERVER", "2024-06-30"),
+];
+
 /// This part of the external node config is fetched directly from the main node.
 #[derive(Debug, Deserialize, Clone, PartialEq)]
 pub struct RemoteENConfig {
@@ -85,7 +102,7 @@ pub enum BlockFetcher {
 /// This part of the external node config is completely optional to provide.
 /// It can tweak limits of the API, delay intervals of certain components, etc.
 /// If any of the fields are not provided, the default values will be used.
-#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct OptionalENConfig {
     // User-facing API limits
     /// Max possible limit of filters to be in the API state at once.
@@ -98,13 +115,14 @@ pub struct OptionalENConfig {
     #[serde(default = "OptionalENConfig::default_req_entities_limit")]
     pub req_entities_limit: usize,
     /// Max possible size of an ABI encoded tx (in bytes).
-    #[serde(default = "OptionalENConfig::default_max_tx_size")]
+    #[serde(
+        default = "OptionalENConfig::default_max_tx_size",
+        deserialize_with = "zksync_config::units::byte_size"
+    )]
     pub max_tx_size: usize,
     /// Max number of cache misses during one VM execution. If the number of cache misses exceeds this value, the API server panics.
     /// This is a temporary solution to mitigate API request resulting in thousands of DB queries.
     pub vm_execution_cache_misses_limit: Option<usize>,
-    /// Note: Deprecated option, no longer in use. Left to display a warning in case someone used them.
-    pub transactions_per_sec_limit: Option<u32>,
     /// Limit for fee history block range.
     #[serde(default = "OptionalENConfig::default_fee_history_limit")]
     pub fee_history_limit: u64,
@@ -112,14 +130,49 @@ pub struct OptionalENConfig {
     #[serde(default = "OptionalENConfig::default_max_batch_request_size")]
     pub max_batch_request_size: usize,
     /// Maximum response body size in MiBs. Default is 10 MiB.
-    #[serde(default = "OptionalENConfig::default_max_response_body_size_mb")]
+    #[serde(
+        default = "OptionalENConfig::default_max_response_body_size_mb",
+        deserialize_with = "zksync_config::units::byte_size_mb"
+    )]
     pub max_response_body_size_mb: usize,
+    /// Maximum request body size in MiBs. Default is 10 MiB.
+    #[serde(
+        default = "OptionalENConfig::default_max_request_body_size_mb",
+        deserialize_with = "zksync_config::units::byte_size_mb"
+    )]
+    pub max_request_body_size_mb: usize,
+    /// Whether to compress large HTTP responses for clients supporting it. Default is `true`.
+    #[serde(default = "OptionalENConfig::default_compress_responses")]
+    pub compress_responses: bool,
+    /// Maximum number of request units per minute for a single API client (identified by the API key or IP address).
+    /// If not set, per-client rate limiting is disabled.
+    pub rate_limit_requests_per_minute: Option<NonZeroU32>,
+    /// HTTP header containing the API key of the client, e.g. `X-Api-Key`.
+    pub rate_limit_api_key_header: Option<String>,
+    /// Weights of RPC methods for rate limiting in the `method:weight` format, e.g. `eth_getLogs:10`.
+    #[serde(default)]
+    pub rate_limit_method_weights: Vec<String>,
+    /// Maximum numbers of concurrently executed calls of RPC methods in the `method:limit` format,
+    /// e.g. `debug_traceCall:4`. Applied even if per-client rate limiting is disabled.
+    #[serde(default)]
+    pub method_concurrency_limits: Vec<String>,
+    /// Fraction of RPC calls recorded in the audit log, in the `(0, 1]` range. If not set, the audit log is disabled.
+    pub audit_log_sampling_ratio: Option<f64>,
+    /// HTTP header containing the API key of the client for the audit log, e.g. `X-Api-Key`.
+    pub audit_log_api_key_header: Option<String>,
+    /// Whether to truncate client IP addresses and hash API keys in the audit log. Default is `true`.
+    #[serde(default = "OptionalENConfig::default_audit_log_redact_client_ids")]
+    pub audit_log_redact_client_ids: bool,
+    /// File to which audit log entries are appended as JSON lines. If not set, entries are emitted
+    /// as log events with the `zksync_api_audit` target.
+    pub audit_log_file_path: Option<String>,
 
     // Other API config settings
     /// Interval between polling DB for pubsub (in ms).
     #[serde(
         rename = "pubsub_polling_interval",
-        default = "OptionalENConfig::default_polling_interval"
+        default = "OptionalENConfig::default_polling_interval",
+        deserialize_with = "zksync_config::units::duration_ms"
     )]
     polling_interval: u64,
     /// Tx nonce: how far ahead from the committed nonce can it be.
@@ -130,17 +183,45 @@ pub struct OptionalENConfig {
     #[serde(default = "OptionalENConfig::default_vm_concurrency_limit")]
     pub vm_concurrency_limit: usize,
     /// Smart contract bytecode cache size for the API server. Default value is 128 MiB.
-    #[serde(default = "OptionalENConfig::default_factory_deps_cache_size_mb")]
+    #[serde(
+        default = "OptionalENConfig::default_factory_deps_cache_size_mb",
+        deserialize_with = "zksync_config::units::byte_size_mb"
+    )]
     factory_deps_cache_size_mb: usize,
     /// Initial writes cache size for the API server. Default value is 32 MiB.
-    #[serde(default = "OptionalENConfig::default_initial_writes_cache_size_mb")]
+    #[serde(
+        default = "OptionalENConfig::default_initial_writes_cache_size_mb",
+        deserialize_with = "zksync_config::units::byte_size_mb"
+    )]
     initial_writes_cache_size_mb: usize,
     /// Latest values cache size in MiBs. The default value is 128 MiB. If set to 0, the latest
     /// values cache will be disabled.
-    #[serde(default = "OptionalENConfig::default_latest_values_cache_size_mb")]
+    #[serde(
+        default = "OptionalENConfig::default_latest_values_cache_size_mb",
+        deserialize_with = "zksync_config::units::byte_size_mb"
+    )]
     latest_values_cache_size_mb: usize,
+    /// Memory budget in MiBs of the cache for immutable API responses (finalized blocks, receipts of transactions
+    /// in finalized blocks and details of executed L1 batches). The default value is 0, which disables the cache.
+    #[serde(default, deserialize_with = "zksync_config::units::byte_size_mb")]
+    response_cache_size_mb: usize,
     /// Enabled JSON RPC API namespaces.
     api_namespaces: Option<Vec<Namespace>>,
+    /// Enabled namespaces for the HTTP JSON RPC server. If not set, `api_namespaces` are used.
+    http_api_namespaces: Option<Vec<Namespace>>,
+    /// Enabled namespaces for the WebSocket JSON RPC server. If not set, `api_namespaces` are used.
+    ws_api_namespaces: Option<Vec<Namespace>>,
+    /// If set, only the matching methods are exposed by the HTTP JSON RPC server. Entries are either method names
+    /// (e.g., `eth_call`) or namespace wildcards (e.g., `eth_*`).
+    http_api_allowed_methods: Option<Vec<String>>,
+    /// Methods not exposed by the HTTP JSON RPC server. Has the same format as `http_api_allowed_methods`.
+    #[serde(default)]
+    http_api_denied_methods: Vec<String>,
+    /// If set, only the matching methods are exposed by the WebSocket JSON RPC server.
+    ws_api_allowed_methods: Option<Vec<String>>,
+    /// Methods not exposed by the WebSocket JSON RPC server.
+    #[serde(default)]
+    ws_api_denied_methods: Vec<String>,
 
     // Gas estimation config
     /// The factor by which to scale the gasLimit
@@ -164,7 +245,10 @@ pub struct OptionalENConfig {
     pub gas_price_scale_factor: f64,
 
     // Merkle tree config
-    #[serde(default = "OptionalENConfig::default_metadata_calculator_delay")]
+    #[serde(
+        default = "OptionalENConfig::default_metadata_calculator_delay",
+        deserialize_with = "zksync_config::units::duration_ms"
+    )]
     metadata_calculator_delay: u64,
     /// Maximum number of L1 batches to be processed by the Merkle tree at a time.
     #[serde(
@@ -178,25 +262,174 @@ pub struct OptionalENConfig {
     pub merkle_tree_multi_get_chunk_size: usize,
     /// Capacity of the block cache for the Merkle tree RocksDB. Reasonable values range from ~100 MiB to several GiB.
     /// The default value is 128 MiB.
-    #[serde(default = "OptionalENConfig::default_merkle_tree_block_cache_size_mb")]
+    #[serde(
+        default = "OptionalENConfig::default_merkle_tree_block_cache_size_mb",
+        deserialize_with = "zksync_config::units::byte_size_mb"
+    )]
     merkle_tree_block_cache_size_mb: usize,
     /// Byte capacity of memtables (recent, non-persisted changes to RocksDB). Setting this to a reasonably
     /// large value (order of 512 MiB) is helpful for large DBs that experience write stalls.
-    #[serde(default = "OptionalENConfig::default_merkle_tree_memtable_capacity_mb")]
+    #[serde(
+        default = "OptionalENConfig::default_merkle_tree_memtable_capacity_mb",
+        deserialize_with = "zksync_config::units::byte_size_mb"
+    )]
     merkle_tree_memtable_capacity_mb: usize,
     /// Timeout to wait for the Merkle tree database to run compaction on stalled writes.
-    #[serde(default = "OptionalENConfig::default_merkle_tree_stalled_writes_timeout_sec")]
+    #[serde(
+        default = "OptionalENConfig::default_merkle_tree_stalled_writes_timeout_sec",
+        deserialize_with = "zksync_config::units::duration_sec"
+    )]
     merkle_tree_stalled_writes_timeout_sec: u64,
+    /// Number of latest Merkle tree versions (= L1 batches) to retain. Older versions are pruned once
+    /// the corresponding L1 batches are executed on L1. If not specified, the tree is not pruned.
+    #[serde(default)]
+    pub merkle_tree_pruning_retained_versions: Option<u64>,
+    /// Delay between consecutive Merkle tree pruning iterations if the pruner has more work to do.
+    #[serde(
+        default = "OptionalENConfig::default_merkle_tree_pruning_iteration_delay_ms",
+        deserialize_with = "zksync_config::units::duration_ms"
+    )]
+    merkle_tree_pruning_iteration_delay_ms: u64,
+    /// Number of threads in a dedicated thread pool used to parallelize Merkle tree updates. If set to 0,
+    /// the number of threads is chosen automatically. If not specified, the global thread pool is used.
+    #[serde(default)]
+    pub merkle_tree_thread_count: Option<usize>,
+    /// Interval between background Merkle tree consistency checks. If not specified, background checks are disabled.
+    #[serde(default, deserialize_with = "zksync_config::units::opt_duration_sec")]
+    merkle_tree_consistency_check_interval_sec: Option<u64>,
+    /// Compaction style for the Merkle tree RocksDB. If not specified, level-style compaction is used.
+    #[serde(default)]
+    merkle_tree_compaction_style: Option<RocksdbCompactionStyle>,
+    /// Size of a single memtable for the Merkle tree RocksDB. If not specified, the size is derived
+    /// from the memtable capacity.
+    #[serde(default, deserialize_with = "zksync_config::units::opt_byte_size_mb")]
+    merkle_tree_write_buffer_size_mb: Option<usize>,
+    /// Maximum number of background jobs for the Merkle tree RocksDB. If not specified, the number is derived
+    /// from the number of CPUs.
+    #[serde(default)]
+    merkle_tree_max_background_jobs: Option<usize>,
+    /// Whether to export statistics for the Merkle tree RocksDB as metrics.
+    #[serde(default)]
+    merkle_tree_rocksdb_statistics: bool,
+
+    // State cache RocksDB config
+    /// Capacity of the block cache for the state cache RocksDB. If not specified, the default RocksDB cache is used.
+    #[serde(default, deserialize_with = "zksync_config::units::opt_byte_size_mb")]
+    state_cache_block_cache_size_mb: Option<usize>,
+    /// Compaction style for the state cache RocksDB. If not specified, level-style compaction is used.
+    #[serde(default)]
+    state_cache_compaction_style: Option<RocksdbCompactionStyle>,
+    /// Size of a single memtable for the state cache RocksDB. If not specified, the RocksDB default is used.
+    #[serde(default, deserialize_with = "zksync_config::units::opt_byte_size_mb")]
+    state_cache_write_buffer_size_mb: Option<usize>,
+    /// Maximum number of background jobs for the state cache RocksDB. If not specified, the number is derived
+    /// from the number of CPUs.
+    #[serde(default)]
+    state_cache_max_background_jobs: Option<usize>,
+    /// Whether to export statistics for the state cache RocksDB as metrics.
+    #[serde(default)]
+    state_cache_rocksdb_statistics: bool,
 
     // Postgres config (new parameters)
     /// Threshold in milliseconds for the DB connection lifetime to denote it as long-living and log its details.
+    #[serde(default, deserialize_with = "zksync_config::units::opt_duration_ms")]
     database_long_connection_threshold_ms: Option<u64>,
     /// Threshold in milliseconds to denote a DB query as "slow" and log its details.
+    #[serde(default, deserialize_with = "zksync_config::units::opt_duration_ms")]
     database_slow_query_threshold_ms: Option<u64>,
+    /// Comma-separated URLs of read replicas of the node database. If specified, read-only API queries that tolerate
+    /// slightly stale data (e.g., historical block and transaction reads) are routed to these replicas.
+    #[serde(default)]
+    pub database_read_replica_urls: Vec<String>,
+    /// Maximum lag in milliseconds of a read replica for it to be used for reads. If not specified,
+    /// the lag is limited to 1 second.
+    #[serde(default, deserialize_with = "zksync_config::units::opt_duration_ms")]
+    database_max_replica_lag_ms: Option<u64>,
+
+    // Pruning config
+    /// Whether to prune historical data from Postgres (miniblocks, transactions, events and overwritten storage logs).
+    /// If enabled, the node is no longer archival: it returns errors for API requests referencing pruned blocks.
+    #[serde(default)]
+    pub pruning_enabled: bool,
+    /// Number of L1 batches pruned at a time.
+    #[serde(default = "OptionalENConfig::default_pruning_chunk_size")]
+    pub pruning_chunk_size: NonZeroU32,
+    /// Delay in seconds between marking L1 batches as pruned and removing their data. During this period,
+    /// the pruned data is not served via the API, but may still be used by in-flight requests.
+    #[serde(default = "OptionalENConfig::default_pruning_removal_delay_sec")]
+    pruning_removal_delay_sec: u64,
+    /// Minimum age of an L1 batch in seconds for its data to be pruned. L1 batches are additionally required
+    /// to be executed on L1 before they are pruned.
+    #[serde(default = "OptionalENConfig::default_pruning_data_retention_sec")]
+    pruning_data_retention_sec: u64,
+    /// Number of the latest L1 batches retained in Postgres and the Merkle tree if pruning is enabled. This allows
+    /// running a node serving only recent state with a fraction of the storage required by an archive node.
+    /// If specified, overrides `pruning_data_retention_sec`: L1 batches outside the window are pruned regardless
+    /// of their age. If not specified, L1 batches are retained based on their age only.
+    #[serde(default)]
+    pub pruning_retained_l1_batches: Option<NonZeroU32>,
+
+    // Reorg detector config
+    /// Maximum number of L1 batches that can be automatically rolled back after a reorg is detected. Deeper reorgs
+    /// stop the node and require a manual rollback using the block reverter. If not specified, the rollback depth
+    /// is not limited.
+    pub reorg_detector_max_rollback_depth: Option<u32>,
+
+    // Main node failover config
+    /// Comma-separated URLs of fallback endpoints used to sync blocks if the main node is unavailable or lagging behind,
+    /// e.g. main node replicas or other external nodes, in the order of preference.
+    #[serde(default)]
+    main_node_fallback_urls: Vec<String>,
+    /// Share of failed requests among recent requests to a main node endpoint after which the endpoint
+    /// is considered unhealthy.
+    #[serde(default = "OptionalENConfig::default_main_node_failover_error_rate_threshold")]
+    pub main_node_failover_error_rate_threshold: f64,
+    /// Number of miniblocks a main node endpoint may lag behind the most recent head among all endpoints
+    /// before it's considered unhealthy.
+    #[serde(default = "OptionalENConfig::default_main_node_failover_max_head_lag")]
+    pub main_node_failover_max_head_lag: u32,
+    /// Interval between health checks of main node endpoints. Only used if fallback URLs are specified.
+    #[serde(
+        default = "OptionalENConfig::default_main_node_failover_health_check_interval_ms",
+        deserialize_with = "zksync_config::units::duration_ms"
+    )]
+    main_node_failover_health_check_interval_ms: u64,
+
+    // Block exporter config
+    /// Comma-separated list of Kafka brokers to which sealed miniblocks, transactions, receipts and L2-to-L1 logs
+    /// are exported. If not specified, the block exporter is disabled.
+    #[serde(default)]
+    pub block_exporter_kafka_brokers: Option<String>,
+    /// Kafka topic to which exported records are published. The topic name also identifies the export checkpoint
+    /// in Postgres, so changing the topic restarts the export.
+    #[serde(default = "OptionalENConfig::default_block_exporter_kafka_topic")]
+    pub block_exporter_kafka_topic: String,
+    /// Number of miniblocks published and checkpointed at a time.
+    #[serde(default = "OptionalENConfig::default_block_exporter_chunk_size")]
+    pub block_exporter_chunk_size: NonZeroU32,
+    /// Timeout for delivering a message to Kafka. If a message is not delivered in time, the entire chunk
+    /// of miniblocks is published again.
+    #[serde(
+        default = "OptionalENConfig::default_block_exporter_message_timeout_ms",
+        deserialize_with = "zksync_config::units::duration_ms"
+    )]
+    block_exporter_message_timeout_ms: u64,
+    /// Miniblock to start the export from if there's no checkpoint for the topic. If not specified, the export
+    /// starts from the first miniblock available in the node storage. Miniblocks pruned from the storage
+    /// cannot be exported, so if pruning is enabled, the exporter must keep up with it.
+    #[serde(default)]
+    pub block_exporter_first_miniblock: Option<u32>,
 
     // Other config settings
     /// Port on which the Prometheus exporter server is listening.
     pub prometheus_port: Option<u16>,
+    /// Maximum age of the last sealed L1 batch (in s) for the node to be considered ready by the healthcheck server.
+    /// If not set, the age is not checked. Should be set so that the node isn't ready while it's syncing.
+    #[serde(default, deserialize_with = "zksync_config::units::opt_duration_sec")]
+    healthcheck_max_sealed_l1_batch_age_sec: Option<u64>,
+    /// Maximum number of sealed L1 batches not processed by the Merkle tree for the node to be considered ready.
+    /// If not set, the tree lag is not checked.
+    pub healthcheck_max_tree_lag: Option<u32>,
     /// Number of keys that is processed by enum_index migration in State Keeper each L1 batch.
     #[serde(default = "OptionalENConfig::default_enum_index_migration_chunk_size")]
     pub enum_index_migration_chunk_size: usize,
@@ -205,6 +438,13 @@ pub struct OptionalENConfig {
     /// 0 means that sealing is synchronous; this is mostly useful for performance comparison, testing etc.
     #[serde(default = "OptionalENConfig::default_miniblock_seal_queue_capacity")]
     pub miniblock_seal_queue_capacity: usize,
+    /// Deadline for node components to stop after a stop signal is received (e.g., on SIGINT or a detected reorg).
+    /// Components that haven't stopped by the deadline are reported and abandoned.
+    #[serde(
+        default = "OptionalENConfig::default_shutdown_timeout_ms",
+        deserialize_with = "zksync_config::units::duration_ms"
+    )]
+    shutdown_timeout_ms: u64,
 }
 
 impl OptionalENConfig {
@@ -291,6 +531,10 @@ impl OptionalENConfig {
         30
     }
 
+    const fn default_merkle_tree_pruning_iteration_delay_ms() -> u64 {
+        100
+    }
+
     const fn default_fee_history_limit() -> u64 {
         1_024
     }
@@ -303,6 +547,18 @@ impl OptionalENConfig {
         10
     }
 
+    const fn default_audit_log_redact_client_ids() -> bool {
+        true
+    }
+
+    const fn default_max_request_body_size_mb() -> usize {
+        10
+    }
+
+    const fn default_compress_responses() -> bool {
+        true
+    }
+
     const fn default_enum_index_migration_chunk_size() -> usize {
         5000
     }
@@ -311,6 +567,46 @@ impl OptionalENConfig {
         10
     }
 
+    const fn default_shutdown_timeout_ms() -> u64 {
+        20_000
+    }
+
+    fn default_pruning_chunk_size() -> NonZeroU32 {
+        NonZeroU32::new(10).unwrap()
+    }
+
+    const fn default_pruning_removal_delay_sec() -> u64 {
+        60
+    }
+
+    const fn default_pruning_data_retention_sec() -> u64 {
+        3_600 // 1 hour
+    }
+
+    const fn default_main_node_failover_error_rate_threshold() -> f64 {
+        0.5
+    }
+
+    const fn default_main_node_failover_max_head_lag() -> u32 {
+        10
+    }
+
+    const fn default_main_node_failover_health_check_interval_ms() -> u64 {
+        5_000
+    }
+
+    fn default_block_exporter_kafka_topic() -> String {
+        "zksync_blocks".to_owned()
+    }
+
+    fn default_block_exporter_chunk_size() -> NonZeroU32 {
+        NonZeroU32::new(10).unwrap()
+    }
+
+    const fn default_block_exporter_message_timeout_ms() -> u64 {
+        30_000
+    }
+
     pub fn polling_interval(&self) -> Duration {
         Duration::from_millis(self.polling_interval)
     }
@@ -334,6 +630,11 @@ impl OptionalENConfig {
         self.latest_values_cache_size_mb * BYTES_IN_MEGABYTE
     }
 
+    /// Returns the memory budget of the API response cache in bytes.
+    pub fn response_cache_size(&self) -> usize {
+        self.response_cache_size_mb * BYTES_IN_MEGABYTE
+    }
+
     /// Returns the size of block cache for Merkle tree in bytes.
     pub fn merkle_tree_block_cache_size(&self) -> usize {
         self.merkle_tree_block_cache_size_mb * BYTES_IN_MEGABYTE
@@ -349,6 +650,97 @@ impl OptionalENConfig {
         Duration::from_secs(self.merkle_tree_stalled_writes_timeout_sec)
     }
 
+    /// Returns the delay between consecutive Merkle tree pruning iterations.
+    pub fn merkle_tree_pruning_iteration_delay(&self) -> Duration {
+        Duration::from_millis(self.merkle_tree_pruning_iteration_delay_ms)
+    }
+
+    pub fn merkle_tree_consistency_check_interval(&self) -> Option<Duration> {
+        self.merkle_tree_consistency_check_interval_sec
+            .map(Duration::from_secs)
+    }
+
+    /// Returns RocksDB tuning options for the Merkle tree.
+    pub fn merkle_tree_rocksdb_tuning(&self) -> RocksdbTuning {
+        RocksdbTuning {
+            compaction_style: self.merkle_tree_compaction_style,
+            write_buffer_size: self
+                .merkle_tree_write_buffer_size_mb
+                .map(|size| size * BYTES_IN_MEGABYTE),
+            max_background_jobs: self.merkle_tree_max_background_jobs,
+            statistics: self.merkle_tree_rocksdb_statistics,
+        }
+    }
+
+    /// Returns the size of block cache for the state cache RocksDB in bytes.
+    pub fn state_cache_block_cache_size(&self) -> Option<usize> {
+        self.state_cache_block_cache_size_mb
+            .map(|size| size * BYTES_IN_MEGABYTE)
+    }
+
+    /// Returns RocksDB tuning options for the state cache.
+    pub fn state_cache_rocksdb_tuning(&self) -> RocksdbTuning {
+        RocksdbTuning {
+            compaction_style: self.state_cache_compaction_style,
+            write_buffer_size: self
+                .state_cache_write_buffer_size_mb
+                .map(|size| size * BYTES_IN_MEGABYTE),
+            max_background_jobs: self.state_cache_max_background_jobs,
+            statistics: self.state_cache_rocksdb_statistics,
+        }
+    }
+
+    pub fn pruning_removal_delay(&self) -> Duration {
+        Duration::from_secs(self.pruning_removal_delay_sec)
+    }
+
+    pub fn pruning_data_retention(&self) -> Duration {
+        Duration::from_secs(self.pruning_data_retention_sec)
+    }
+
+    /// Returns the number of retained Merkle tree versions. If not set explicitly, the tree retains the same L1 batches
+    /// as Postgres in the pruned mode.
+    pub fn merkle_tree_retained_versions(&self) -> Option<u64> {
+        self.merkle_tree_pruning_retained_versions.or_else(|| {
+            let retained_l1_batches = self
+                .pruning_retained_l1_batches
+                .filter(|_| self.pruning_enabled)?;
+            Some(retained_l1_batches.get().into())
+        })
+    }
+
+    pub fn main_node_fallback_urls(&self) -> anyhow::Result<Vec<String>> {
+        self.main_node_fallback_urls
+            .iter()
+            .map(|url| {
+                RequiredENConfig::get_url(url)
+                    .with_context(|| format!("Could not parse main node fallback URL `{url}`"))
+            })
+            .collect()
+    }
+
+    pub fn main_node_failover_config(&self) -> MainNodeFailoverConfig {
+        MainNodeFailoverConfig {
+            error_rate_threshold: self.main_node_failover_error_rate_threshold,
+            max_head_lag: self.main_node_failover_max_head_lag,
+            health_check_interval: Duration::from_millis(
+                self.main_node_failover_health_check_interval_ms,
+            ),
+        }
+    }
+
+    pub fn block_exporter_message_timeout(&self) -> Duration {
+        Duration::from_millis(self.block_exporter_message_timeout_ms)
+    }
+
+    pub fn shutdown_timeout(&self) -> Duration {
+        Duration::from_millis(self.shutdown_timeout_ms)
+    }
+
+    pub fn max_replica_lag(&self) -> Option<Duration> {
+        self.database_max_replica_lag_ms.map(Duration::from_millis)
+    }
+
     pub fn long_connection_threshold(&self) -> Option<Duration> {
         self.database_long_connection_threshold_ms
             .map(Duration::from_millis)
@@ -359,19 +751,76 @@ impl OptionalENConfig {
             .map(Duration::from_millis)
     }
 
+    pub fn healthcheck_max_sealed_l1_batch_age(&self) -> Option<Duration> {
+        self.healthcheck_max_sealed_l1_batch_age_sec
+            .map(Duration::from_secs)
+    }
+
     pub fn api_namespaces(&self) -> Vec<Namespace> {
         self.api_namespaces
             .clone()
             .unwrap_or_else(|| Namespace::DEFAULT.to_vec())
     }
 
+    pub fn http_api_namespaces(&self) -> Vec<Namespace> {
+        self.http_api_namespaces
+            .clone()
+            .unwrap_or_else(|| self.api_namespaces())
+    }
+
+    pub fn ws_api_namespaces(&self) -> Vec<Namespace> {
+        self.ws_api_namespaces
+            .clone()
+            .unwrap_or_else(|| self.api_namespaces())
+    }
+
+    pub fn http_api_method_filter(&self) -> anyhow::Result<MethodFilter> {
+        MethodFilter::new(&RpcInterfaceConfig {
+            namespaces: None,
+            allowed_methods: self.http_api_allowed_methods.clone(),
+            denied_methods: self.http_api_denied_methods.clone(),
+        })
+        .context("invalid HTTP API method filter")
+    }
+
+    pub fn ws_api_method_filter(&self) -> anyhow::Result<MethodFilter> {
+        MethodFilter::new(&RpcInterfaceConfig {
+            namespaces: None,
+            allowed_methods: self.ws_api_allowed_methods.clone(),
+            denied_methods: self.ws_api_denied_methods.clone(),
+        })
+        .context("invalid WebSocket API method filter")
+    }
+
     pub fn max_response_body_size(&self) -> usize {
         self.max_response_body_size_mb * BYTES_IN_MEGABYTE
     }
+
+    pub fn max_request_body_size(&self) -> usize {
+        self.max_request_body_size_mb * BYTES_IN_MEGABYTE
+    }
+
+    pub fn rate_limit(&self) -> ApiRateLimitConfig {
+        ApiRateLimitConfig {
+            requests_per_minute: self.rate_limit_requests_per_minute,
+            api_key_header: self.rate_limit_api_key_header.clone(),
+            method_weights: self.rate_limit_method_weights.clone(),
+            method_concurrency_limits: self.method_concurrency_limits.clone(),
+        }
+    }
+
+    pub fn audit_log(&self) -> ApiAuditLogConfig {
+        ApiAuditLogConfig {
+            sampling_ratio: self.audit_log_sampling_ratio,
+            api_key_header: self.audit_log_api_key_header.clone(),
+            redact_client_ids: self.audit_log_redact_client_ids,
+            file_path: self.audit_log_file_path.clone(),
+        }
+    }
 }
 
 /// This part of the external node config is required for its operation.
-#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct RequiredENConfig {
     /// Port on which the HTTP RPC server is listening.
     pub http_port: u16,
@@ -410,7 +859,7 @@ impl RequiredENConfig {
 /// While also mandatory, it historically used different naming scheme for corresponding
 /// environment variables.
 /// Thus it is kept separately for backward compatibility and ease of deserialization.
-#[derive(Debug, Clone, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct PostgresConfig {
     pub database_url: String,
     pub max_connections: u32,
@@ -419,9 +868,9 @@ pub struct PostgresConfig {
 impl PostgresConfig {
     pub fn from_env() -> anyhow::Result<Self> {
         Ok(Self {
-            database_url: env::var("DATABASE_URL")
+            database_url: zksync_env_config::env_var("DATABASE_URL")
                 .context("DATABASE_URL env variable is not set")?,
-            max_connections: env::var("DATABASE_POOL_SIZE")
+            max_connections: zksync_env_config::env_var("DATABASE_POOL_SIZE")
                 .context("DATABASE_POOL_SIZE env variable is not set")?
                 .parse()
                 .context("Unable to parse DATABASE_POOL_SIZE env variable")?,
@@ -430,7 +879,7 @@ impl PostgresConfig {
 }
 
 pub(crate) fn read_consensus_config() -> anyhow::Result<consensus::FetcherConfig> {
-    let path = std::env::var("EN_CONSENSUS_CONFIG_PATH")
+    let path = zksync_env_config::env_var("EN_CONSENSUS_CONFIG_PATH")
         .context("EN_CONSENSUS_CONFIG_PATH env variable is not set")?;
     let cfg = std::fs::read_to_string(&path).context(path)?;
     let cfg: consensus::config::Config =
@@ -450,13 +899,50 @@ pub struct SnapshotsRecoveryConfig {
 
 pub(crate) fn read_snapshots_recovery_config() -> anyhow::Result<SnapshotsRecoveryConfig> {
     let snapshots_object_store = envy::prefixed("EN_SNAPSHOTS_OBJECT_STORE_")
-        .from_env::<ObjectStoreConfig>()
+        .from_iter::<_, ObjectStoreConfig>(env_vars())
         .context("failed loading snapshot object store config from env variables")?;
     Ok(SnapshotsRecoveryConfig {
         snapshots_object_store,
     })
 }
 
+/// Loads all local configs of the external node (i.e., excluding the ones fetched from the main node)
+/// and returns them as a JSON object with secrets redacted and provenance of each value.
+pub(crate) fn dump_configs(
+    file: Option<&ConfigSource>,
+    resolved_secrets: Vec<String>,
+) -> anyhow::Result<serde_json::Value> {
+    let mut dump = ConfigDump::new(file).redact_vars(resolved_secrets);
+    dump.insert_loaded(
+        "observability",
+        "MISC_",
+        observability::observability_config_from_env(),
+        &[("sentry_environment", "EN_SENTRY_ENVIRONMENT")],
+    )?;
+    let required = envy::prefixed("EN_").from_iter::<_, RequiredENConfig>(env_vars());
+    dump.insert_loaded("required", "EN_", required.map_err(Into::into), &[])?;
+    let optional = envy::prefixed("EN_").from_iter::<_, OptionalENConfig>(env_vars());
+    dump.insert_loaded("optional", "EN_", optional.map_err(Into::into), &[])?;
+    dump.insert_loaded(
+        "postgres",
+        "DATABASE_",
+        PostgresConfig::from_env(),
+        &[
+            ("database_url", "DATABASE_URL"),
+            ("max_connections", "DATABASE_POOL_SIZE"),
+        ],
+    )?;
+    let snapshots_object_store =
+        envy::prefixed("EN_SNAPSHOTS_OBJECT_STORE_").from_iter::<_, ObjectStoreConfig>(env_vars());
+    dump.insert_loaded(
+        "snapshots_object_store",
+        "EN_SNAPSHOTS_OBJECT_STORE_",
+        snapshots_object_store.map_err(Into::into),
+        &[],
+    )?;
+    Ok(dump.into_json())
+}
+
 /// External Node Config contains all the configuration required for the EN operation.
 /// It is split into three parts: required, optional and remote for easier navigation.
 #[derive(Debug, Clone)]
@@ -472,12 +958,13 @@ impl ExternalNodeConfig {
     /// Loads config from the environment variables and
     /// fetches contracts addresses from the main node.
     pub async fn collect() -> anyhow::Result<Self> {
+        deprecation::report_ignored_vars(IGNORED_VARS);
         let required = envy::prefixed("EN_")
-            .from_env::<RequiredENConfig>()
+            .from_iter::<_, RequiredENConfig>(env_vars())
             .context("could not load external node config")?;
 
         let optional = envy::prefixed("EN_")
-            .from_env::<OptionalENConfig>()
+            .from_iter::<_, OptionalENConfig>(env_vars())
             .context("could not load external node config")?;
 
         let client = HttpClientBuilder::default()
@@ -540,7 +1027,7 @@ where
     T: std::str::FromStr,
     T::Err: std::fmt::Debug,
 {
-    env::var(name)
+    zksync_env_config::env_var(name)
         .unwrap_or_else(|_| panic!("{} env variable is not set", name))
         .parse()
         .unwrap_or_else(|_| panic!("unable to parse {} env variable", name))
@@ -581,6 +1068,8 @@ impl From<ExternalNodeConfig> for TxSenderConfig {
                 .unwrap(),
             gas_price_scale_factor: config.optional.gas_price_scale_factor,
             max_nonce_ahead: config.optional.max_nonce_ahead,
+            // Transactions are proxied to the main node, which checks replacement fees.
+            tx_replacement_fee_bump_percent: 0,
             vm_execution_cache_misses_limit: config.optional.vm_execution_cache_misses_limit,
             // We set these values to the maximum since we don't know the actual values
             // and they will be enforced by the main node anyway.
@@ -1,28 +1,21 @@
 use zksync_config::configs::ObservabilityConfig;
+use zksync_env_config::{
+    env_var,
+    observability::{
+        derive_sentry_environment, log_file_max_files_from_env, log_file_max_size_from_env,
+        log_throttle_burst_from_env, log_throttle_interval_from_env, otlp_sampling_ratio_from_env,
+        otlp_url_from_env, sample_rate_from_env, sentry_url_from_env,
+    },
+};
 
 pub fn observability_config_from_env() -> anyhow::Result<ObservabilityConfig> {
-    // The logic in this method mimics the historical logic of loading observability options
-    // This is left intact, since some of the existing deployments may rely on the this behavior.
-    let sentry_url = if let Ok(sentry_url) = std::env::var("MISC_SENTRY_URL") {
-        if sentry_url == "unset" {
-            None
-        } else {
-            Some(sentry_url)
-        }
-    } else {
-        None
-    };
-    let sentry_environment = std::env::var("EN_SENTRY_ENVIRONMENT").ok().or_else(|| {
-        let l1_network = std::env::var("CHAIN_ETH_NETWORK").ok();
-        let l2_network = std::env::var("CHAIN_ETH_ZKSYNC_NETWORK").ok();
-        match (l1_network, l2_network) {
-            (Some(l1_network), Some(l2_network)) => {
-                Some(format!("{} - {}", l1_network, l2_network))
-            }
-            _ => None,
-        }
-    });
-    let log_format = if let Ok(log_format) = std::env::var("MISC_LOG_FORMAT") {
+    // Legacy behavior is still supported, since some of the existing deployments may rely on it,
+    // but its usage is reported as deprecated.
+    let sentry_url = sentry_url_from_env();
+    let sentry_environment = env_var("EN_SENTRY_ENVIRONMENT")
+        .ok()
+        .or_else(|| derive_sentry_environment("EN_SENTRY_ENVIRONMENT"));
+    let log_format = if let Ok(log_format) = env_var("MISC_LOG_FORMAT") {
         if log_format != "plain" && log_format != "json" {
             anyhow::bail!("MISC_LOG_FORMAT has an unexpected value {}", log_format);
         }
@@ -34,6 +27,19 @@ pub fn observability_config_from_env() -> anyhow::Result<ObservabilityConfig> {
     Ok(ObservabilityConfig {
         sentry_url,
         sentry_environment,
+        sentry_sample_rate: sample_rate_from_env("MISC_SENTRY_SAMPLE_RATE")?,
+        sentry_traces_sample_rate: sample_rate_from_env("MISC_SENTRY_TRACES_SAMPLE_RATE")?,
+        sentry_error_filter: env_var("MISC_SENTRY_ERROR_FILTER").ok(),
+        otlp_url: otlp_url_from_env(),
+        otlp_sampling_ratio: otlp_sampling_ratio_from_env()?,
+        otlp_resource_attributes: env_var("MISC_OTLP_RESOURCE_ATTRIBUTES").ok(),
+        log_directives: env_var("MISC_LOG_DIRECTIVES").ok(),
+        log_file_path: env_var("MISC_LOG_FILE_PATH").ok(),
+        log_file_rotation: env_var("MISC_LOG_FILE_ROTATION").ok(),
+        log_file_max_size_bytes: log_file_max_size_from_env()?,
+        log_file_max_files: log_file_max_files_from_env()?,
+        log_throttle_interval_sec: log_throttle_interval_from_env()?,
+        log_throttle_burst: log_throttle_burst_from_env()?,
         log_format,
     })
 }
@@ -1,5 +1,7 @@
 //! Tests for EN configuration.
 
+use std::collections::HashMap;
+
 use super::*;
 
 #[test]
@@ -25,6 +27,27 @@ fn parsing_optional_config_from_empty_env() {
         128 * BYTES_IN_MEGABYTE
     );
     assert_eq!(config.max_response_body_size(), 10 * BYTES_IN_MEGABYTE);
+    assert_eq!(config.max_request_body_size(), 10 * BYTES_IN_MEGABYTE);
+    assert!(config.compress_responses);
+    assert_eq!(config.response_cache_size(), 0);
+    assert_eq!(config.rate_limit(), ApiRateLimitConfig::default());
+    assert_eq!(config.audit_log(), ApiAuditLogConfig::default());
+    assert_eq!(config.http_api_namespaces(), Namespace::DEFAULT);
+    assert_eq!(config.ws_api_namespaces(), Namespace::DEFAULT);
+    assert!(config.http_api_method_filter().unwrap().is_trivial());
+    assert!(config.ws_api_method_filter().unwrap().is_trivial());
+    assert_eq!(config.reorg_detector_max_rollback_depth, None);
+    assert!(config.main_node_fallback_urls().unwrap().is_empty());
+    assert_eq!(config.pruning_retained_l1_batches, None);
+    assert_eq!(config.merkle_tree_retained_versions(), None);
+    assert_eq!(config.shutdown_timeout(), Duration::from_secs(20));
+    let failover_config = config.main_node_failover_config();
+    assert_eq!(failover_config.error_rate_threshold, 0.5);
+    assert_eq!(failover_config.max_head_lag, 10);
+    assert_eq!(
+        failover_config.health_check_interval,
+        Duration::from_secs(5)
+    );
 }
 
 #[test]
@@ -44,6 +67,28 @@ fn parsing_optional_config_from_env() {
         ("EN_MERKLE_TREE_MULTI_GET_CHUNK_SIZE", "1000"),
         ("EN_MERKLE_TREE_BLOCK_CACHE_SIZE_MB", "32"),
         ("EN_MAX_RESPONSE_BODY_SIZE_MB", "1"),
+        ("EN_MAX_REQUEST_BODY_SIZE_MB", "2"),
+        ("EN_COMPRESS_RESPONSES", "false"),
+        ("EN_RESPONSE_CACHE_SIZE_MB", "256"),
+        ("EN_RATE_LIMIT_REQUESTS_PER_MINUTE", "600"),
+        ("EN_RATE_LIMIT_METHOD_WEIGHTS", "eth_getLogs:10,eth_call:5"),
+        ("EN_METHOD_CONCURRENCY_LIMITS", "debug_traceCall:4"),
+        ("EN_API_NAMESPACES", "eth,net,web3,debug"),
+        ("EN_WS_API_NAMESPACES", "eth,pubsub"),
+        ("EN_HTTP_API_DENIED_METHODS", "debug_traceCall"),
+        ("EN_WS_API_ALLOWED_METHODS", "eth_*"),
+        ("EN_AUDIT_LOG_SAMPLING_RATIO", "0.01"),
+        ("EN_AUDIT_LOG_REDACT_CLIENT_IDS", "false"),
+        ("EN_AUDIT_LOG_FILE_PATH", "/var/log/en/audit.log"),
+        ("EN_REORG_DETECTOR_MAX_ROLLBACK_DEPTH", "50"),
+        (
+            "EN_MAIN_NODE_FALLBACK_URLS",
+            "http://replica.example.com,https://en.example.com",
+        ),
+        ("EN_MAIN_NODE_FAILOVER_MAX_HEAD_LAG", "100"),
+        ("EN_MAIN_NODE_FAILOVER_HEALTH_CHECK_INTERVAL_MS", "1000"),
+        ("EN_PRUNING_ENABLED", "true"),
+        ("EN_PRUNING_RETAINED_L1_BATCHES", "1000"),
     ];
     let env_vars = env_vars
         .into_iter()
@@ -70,4 +115,97 @@ fn parsing_optional_config_from_env() {
         32 * BYTES_IN_MEGABYTE
     );
     assert_eq!(config.max_response_body_size(), BYTES_IN_MEGABYTE);
+    assert_eq!(config.max_request_body_size(), 2 * BYTES_IN_MEGABYTE);
+    assert!(!config.compress_responses);
+    assert_eq!(config.response_cache_size(), 256 * BYTES_IN_MEGABYTE);
+    let rate_limit = config.rate_limit();
+    assert_eq!(rate_limit.requests_per_minute, NonZeroU32::new(600));
+    assert_eq!(rate_limit.api_key_header, None);
+    assert_eq!(
+        rate_limit.method_weights().unwrap(),
+        HashMap::from([
+            ("eth_getLogs".to_owned(), NonZeroU32::new(10).unwrap()),
+            ("eth_call".to_owned(), NonZeroU32::new(5).unwrap()),
+        ])
+    );
+    assert_eq!(
+        rate_limit.method_concurrency_limits().unwrap(),
+        HashMap::from([("debug_traceCall".to_owned(), NonZeroU32::new(4).unwrap())])
+    );
+
+    assert_eq!(
+        config.http_api_namespaces(),
+        [
+            Namespace::Eth,
+            Namespace::Net,
+            Namespace::Web3,
+            Namespace::Debug
+        ]
+    );
+    assert_eq!(
+        config.ws_api_namespaces(),
+        [Namespace::Eth, Namespace::Pubsub]
+    );
+    let http_filter = config.http_api_method_filter().unwrap();
+    assert!(http_filter.allows("debug_traceTransaction"));
+    assert!(!http_filter.allows("debug_traceCall"));
+    let ws_filter = config.ws_api_method_filter().unwrap();
+    assert!(ws_filter.allows("eth_subscribe"));
+    assert!(!ws_filter.allows("net_version"));
+
+    let audit_log = config.audit_log();
+    assert_eq!(audit_log.sampling_ratio, Some(0.01));
+    assert_eq!(audit_log.api_key_header, None);
+    assert!(!audit_log.redact_client_ids);
+    assert_eq!(
+        audit_log.file_path.as_deref(),
+        Some("/var/log/en/audit.log")
+    );
+    assert_eq!(config.reorg_detector_max_rollback_depth, Some(50));
+    assert_eq!(
+        config.main_node_fallback_urls().unwrap(),
+        [
+            "http://replica.example.com:80/",
+            "https://en.example.com:443/"
+        ]
+    );
+    let failover_config = config.main_node_failover_config();
+    assert_eq!(failover_config.error_rate_threshold, 0.5);
+    assert_eq!(failover_config.max_head_lag, 100);
+    assert_eq!(
+        failover_config.health_check_interval,
+        Duration::from_secs(1)
+    );
+    assert!(config.pruning_enabled);
+    assert_eq!(config.pruning_retained_l1_batches, NonZeroU32::new(1_000));
+    assert_eq!(config.merkle_tree_retained_versions(), Some(1_000));
+}
+
+#[test]
+fn parsing_optional_config_with_units() {
+    let env_vars = [
+        ("EN_PUBSUB_POLLING_INTERVAL", "1s"),
+        ("EN_MAX_TX_SIZE", "1MiB"),
+        ("EN_METADATA_CALCULATOR_DELAY", "50ms"),
+        ("EN_FACTORY_DEPS_CACHE_SIZE_MB", "1GiB"),
+        ("EN_MERKLE_TREE_STALLED_WRITES_TIMEOUT_SEC", "1m"),
+        ("EN_DATABASE_SLOW_QUERY_THRESHOLD_MS", "2s"),
+    ];
+    let env_vars = env_vars
+        .into_iter()
+        .map(|(name, value)| (name.to_owned(), value.to_owned()));
+
+    let config: OptionalENConfig = envy::prefixed("EN_").from_iter(env_vars).unwrap();
+    assert_eq!(config.polling_interval(), Duration::from_secs(1));
+    assert_eq!(config.max_tx_size, BYTES_IN_MEGABYTE);
+    assert_eq!(
+        config.metadata_calculator_delay(),
+        Duration::from_millis(50)
+    );
+    assert_eq!(config.factory_deps_cache_size(), 1_024 * BYTES_IN_MEGABYTE);
+    assert_eq!(
+        config.merkle_tree_stalled_writes_timeout(),
+        Duration::from_secs(60)
+    );
+    assert_eq!(config.slow_query_threshold(), Some(Duration::from_secs(2)));
 }
@@ -1,12 +1,18 @@
 //! EN initialization logic.
 
+use std::sync::Arc;
+
 use anyhow::Context as _;
-use zksync_basic_types::{L1BatchNumber, L2ChainId};
+use async_trait::async_trait;
+use zksync_basic_types::{Address, L1BatchNumber, L2ChainId, H256, U256};
+use zksync_contracts::zksync_contract;
 use zksync_core::sync_layer::genesis::perform_genesis_if_needed;
 use zksync_dal::ConnectionPool;
+use zksync_eth_client::{CallFunctionArgs, EthInterface};
 use zksync_health_check::AppHealthCheck;
 use zksync_object_store::ObjectStoreFactory;
-use zksync_snapshots_applier::SnapshotsApplierConfig;
+use zksync_snapshots_applier::{SnapshotsApplierConfig, SnapshotsApplierL1Client};
+use zksync_types::web3::contract::tokens::Detokenize;
 use zksync_web3_decl::jsonrpsee::http_client::HttpClient;
 
 use crate::config::read_snapshots_recovery_config;
@@ -19,9 +25,38 @@ enum InitDecision {
     SnapshotRecovery,
 }
 
+/// L1 client used to verify the recovered snapshot against L1 batch commitments stored by the diamond proxy.
+#[derive(Debug)]
+struct SnapshotsL1Client {
+    client: Arc<dyn EthInterface>,
+    diamond_proxy_addr: Address,
+}
+
+#[async_trait]
+impl SnapshotsApplierL1Client for SnapshotsL1Client {
+    async fn fetch_stored_batch_hash(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<Option<H256>> {
+        let args = CallFunctionArgs::new("storedBatchHash", U256::from(l1_batch_number.0))
+            .for_contract(self.diamond_proxy_addr, zksync_contract());
+        let tokens = self
+            .client
+            .call_contract_function(args)
+            .await
+            .context("failed calling `storedBatchHash`")?;
+        let stored_batch_hash =
+            H256::from_tokens(tokens).context("failed decoding `storedBatchHash` output")?;
+        // The diamond proxy returns zero hash for batches that are not committed.
+        Ok((stored_batch_hash != H256::zero()).then_some(stored_batch_hash))
+    }
+}
+
 pub(crate) async fn ensure_storage_initialized(
     pool: &ConnectionPool,
     main_node_client: &HttpClient,
+    l1_client: Arc<dyn EthInterface>,
+    diamond_proxy_addr: Address,
     app_health: &AppHealthCheck,
     l2_chain_id: L2ChainId,
     consider_snapshot_recovery: bool,
@@ -86,7 +121,11 @@ pub(crate) async fn ensure_storage_initialized(
                 .create_store()
                 .await;
 
-            let config = SnapshotsApplierConfig::default();
+            let l1_client = SnapshotsL1Client {
+                client: l1_client,
+                diamond_proxy_addr,
+            };
+            let config = SnapshotsApplierConfig::default().with_l1_client(l1_client);
             app_health.insert_component(config.health_check());
             config
                 .run(pool, main_node_client, &blob_store)
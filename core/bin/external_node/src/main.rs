@@ -1,42 +1,59 @@
-use std::{sync::Arc, time::Duration};
+use std::{
+    iter,
+    path::PathBuf,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use anyhow::Context as _;
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use futures::{future::FusedFuture, FutureExt as _};
 use metrics::EN_METRICS;
 use prometheus_exporter::PrometheusExporterConfig;
-use tokio::{sync::watch, task, time::sleep};
-use zksync_basic_types::{Address, L2ChainId};
+use tokio::{sync::watch, task};
+use tracing::Instrument as _;
+use zksync_basic_types::{Address, L2ChainId, MiniblockNumber};
 use zksync_concurrency::{ctx, scope};
 use zksync_config::configs::database::MerkleTreeMode;
 use zksync_core::{
     api_server::{
         execution_sandbox::VmConcurrencyLimiter,
-        healthcheck::HealthCheckHandle,
+        healthcheck::{HealthCheckHandle, L1ClientHealthCheck},
         tx_sender::{ApiContracts, TxSenderBuilder},
-        web3::{ApiBuilder, Namespace},
+        web3::{ApiBuilder, Namespace, ResponseCache},
     },
+    block_exporter::{BlockExporter, BlockExporterConfig, KafkaSink},
     block_reverter::{BlockReverter, BlockReverterFlags, L1ExecutedBatchesRevert},
     commitment_generator::CommitmentGenerator,
-    consensus,
+    component_span, consensus,
     consistency_checker::ConsistencyChecker,
+    db_pruner::{DbPruner, DbPrunerConfig},
     l1_gas_price::MainNodeFeeParamsFetcher,
     metadata_calculator::{MetadataCalculator, MetadataCalculatorConfig},
+    reload_logs_on_remote_update, reload_logs_on_sighup,
     reorg_detector::ReorgDetector,
     setup_sigint_handler,
+    shutdown::ShutdownTracker,
+    startup::{self, MigrationsAction, StartupPhase},
     state_keeper::{
         seal_criteria::NoopSealer, BatchExecutor, MainBatchExecutor, MiniblockSealer,
-        MiniblockSealerHandle, ZkSyncStateKeeper,
+        MiniblockSealerHandle, PartitionManager, ZkSyncStateKeeper,
     },
     sync_layer::{
         batch_status_updater::BatchStatusUpdater, external_io::ExternalIO,
-        fetcher::MainNodeFetcher, ActionQueue, MainNodeClient, SyncState,
+        fetcher::MainNodeFetcher, ActionQueue, FailoverMainNodeClient, MainNodeClient, SyncState,
     },
 };
-use zksync_dal::{healthcheck::ConnectionPoolHealthCheck, ConnectionPool};
+use zksync_dal::{
+    healthcheck::{ConnectionPoolHealthCheck, L1BatchHealthCheck, L1BatchHealthThresholds},
+    ConnectionPool,
+};
+use zksync_env_config::{
+    deprecation, remote::RemoteConfigSource, secrets::SecretsResolver, ConfigSource,
+};
+use zksync_eth_client::{clients::QueryClient, EthInterface};
 use zksync_health_check::{AppHealthCheck, HealthStatus, ReactiveHealthCheck};
 use zksync_state::PostgresStorageCaches;
-use zksync_storage::RocksDB;
 use zksync_utils::wait_for_tasks::wait_for_tasks;
 use zksync_web3_decl::jsonrpsee::http_client::HttpClient;
 
@@ -61,6 +78,7 @@ async fn build_state_keeper(
     config: &ExternalNodeConfig,
     connection_pool: ConnectionPool,
     sync_state: SyncState,
+    main_node_client: FailoverMainNodeClient,
     l2_erc20_bridge_addr: Address,
     miniblock_sealer_handle: MiniblockSealerHandle,
     stop_receiver: watch::Receiver<bool>,
@@ -73,9 +91,16 @@ async fn build_state_keeper(
     let max_allowed_l2_tx_gas_limit = u32::MAX.into();
     let validation_computational_gas_limit = u32::MAX;
     // We only need call traces on the external node if the `debug_` namespace is enabled.
-    let save_call_traces = config.optional.api_namespaces().contains(&Namespace::Debug);
-
-    let batch_executor_base: Box<dyn BatchExecutor> = Box::new(MainBatchExecutor::new(
+    let save_call_traces = config
+        .optional
+        .http_api_namespaces()
+        .contains(&Namespace::Debug)
+        || config
+            .optional
+            .ws_api_namespaces()
+            .contains(&Namespace::Debug);
+
+    let mut batch_executor_base = MainBatchExecutor::new(
         state_keeper_db_path,
         connection_pool.clone(),
         max_allowed_l2_tx_gas_limit,
@@ -83,11 +108,13 @@ async fn build_state_keeper(
         false,
         config.optional.enum_index_migration_chunk_size,
         true,
-    ));
+    );
+    batch_executor_base.set_db_options(
+        config.optional.state_cache_block_cache_size(),
+        &config.optional.state_cache_rocksdb_tuning(),
+    );
+    let batch_executor_base: Box<dyn BatchExecutor> = Box::new(batch_executor_base);
 
-    let main_node_url = config.required.main_node_url()?;
-    let main_node_client = <dyn MainNodeClient>::json_rpc(&main_node_url)
-        .context("Failed creating JSON-RPC client for main node")?;
     let io = ExternalIO::new(
         miniblock_sealer_handle,
         connection_pool,
@@ -116,6 +143,7 @@ async fn init_tasks(
     task_handles: &mut Vec<task::JoinHandle<anyhow::Result<()>>>,
     app_health: &AppHealthCheck,
     stop_receiver: watch::Receiver<bool>,
+    shutdown_tracker: &ShutdownTracker,
 ) -> anyhow::Result<()> {
     let release_manifest: serde_json::Value = serde_json::from_str(RELEASE_MANIFEST)
         .expect("release manifest is a valid json document; qed");
@@ -131,12 +159,33 @@ async fn init_tasks(
     let sync_state = SyncState::default();
     app_health.insert_custom_component(Arc::new(sync_state.clone()));
     let (action_queue_sender, action_queue) = ActionQueue::new();
+    // Blocks are synced using the main node and fallback endpoints, if any.
+    let main_node_urls = iter::once(config.required.main_node_url()?)
+        .chain(config.optional.main_node_fallback_urls()?)
+        .collect::<Vec<_>>();
+    let sync_client = FailoverMainNodeClient::json_rpc(
+        &main_node_urls,
+        config.optional.main_node_failover_config(),
+    )
+    .context("Failed creating JSON-RPC client for main node endpoints")?;
 
     let (miniblock_sealer, miniblock_sealer_handle) = MiniblockSealer::new(
         connection_pool.clone(),
         config.optional.miniblock_seal_queue_capacity,
     );
-    task_handles.push(tokio::spawn(miniblock_sealer.run()));
+    // The sealer stops once the state keeper (which holds the sealer handle) is stopped
+    // and all submitted miniblocks are persisted.
+    task_handles.push(tokio::spawn(
+        shutdown_tracker
+            .track("miniblock_sealer", miniblock_sealer.run())
+            .instrument(component_span("state_keeper")),
+    ));
+    let partition_manager = PartitionManager::new(connection_pool.clone());
+    task_handles.push(tokio::spawn(
+        partition_manager
+            .run(stop_receiver.clone())
+            .instrument(component_span("state_keeper")),
+    ));
     let pool = connection_pool.clone();
     task_handles.push(tokio::spawn(async move {
         loop {
@@ -161,6 +210,7 @@ async fn init_tasks(
         config,
         connection_pool.clone(),
         sync_state.clone(),
+        sync_client.clone(),
         config.remote.l2_erc20_bridge_addr,
         miniblock_sealer_handle,
         stop_receiver.clone(),
@@ -204,14 +254,14 @@ async fn init_tasks(
         let mut storage = pool.access_storage_tagged("sync_layer").await?;
         let fetcher = MainNodeFetcher::new(
             &mut storage,
-            Box::new(main_node_client.clone()),
+            Box::new(sync_client),
             action_queue_sender,
             sync_state.clone(),
             stop_receiver.clone(),
         )
         .await
         .context("failed initializing main node fetcher")?;
-        tokio::spawn(fetcher.run())
+        tokio::spawn(fetcher.run().instrument(component_span("fetcher")))
     };
 
     let metadata_calculator_config = MetadataCalculatorConfig {
@@ -223,6 +273,11 @@ async fn init_tasks(
         block_cache_capacity: config.optional.merkle_tree_block_cache_size(),
         memtable_capacity: config.optional.merkle_tree_memtable_capacity(),
         stalled_writes_timeout: config.optional.merkle_tree_stalled_writes_timeout(),
+        pruning_retained_versions: config.optional.merkle_tree_retained_versions(),
+        pruning_iteration_delay: config.optional.merkle_tree_pruning_iteration_delay(),
+        thread_count: config.optional.merkle_tree_thread_count,
+        consistency_check_interval: config.optional.merkle_tree_consistency_check_interval(),
+        rocksdb_tuning: config.optional.merkle_tree_rocksdb_tuning(),
     };
     let metadata_calculator = MetadataCalculator::new(metadata_calculator_config, None)
         .await
@@ -258,7 +313,10 @@ async fn init_tasks(
         .build()
         .await
         .context("failed to build a tree_pool")?;
-    let tree_handle = task::spawn(metadata_calculator.run(tree_pool, tree_stop_receiver));
+    let tree_handle = task::spawn(shutdown_tracker.track(
+        "tree",
+        metadata_calculator.run(tree_pool, tree_stop_receiver),
+    ));
 
     let commitment_generator_pool = singleton_pool_builder
         .build()
@@ -266,12 +324,76 @@ async fn init_tasks(
         .context("failed to build a commitment_generator_pool")?;
     let commitment_generator = CommitmentGenerator::new(commitment_generator_pool);
     app_health.insert_component(commitment_generator.health_check());
-    let commitment_generator_handle = tokio::spawn(commitment_generator.run(stop_receiver.clone()));
+    let commitment_generator_handle = tokio::spawn(shutdown_tracker.track(
+        "commitment_generator",
+        commitment_generator.run(stop_receiver.clone()),
+    ));
+
+    let db_pruner_handle = if config.optional.pruning_enabled {
+        let db_pruner_config = DbPrunerConfig {
+            soft_and_hard_pruning_time_delta: config.optional.pruning_removal_delay(),
+            next_iterations_delay: Duration::from_secs(30),
+            pruned_batch_chunk_size: config.optional.pruning_chunk_size,
+            minimum_l1_batch_age: config.optional.pruning_data_retention(),
+            retained_l1_batches: config.optional.pruning_retained_l1_batches,
+        };
+        tracing::info!("Pruning node data with config {db_pruner_config:?}");
+        let db_pruner_pool = singleton_pool_builder
+            .build()
+            .await
+            .context("failed to build a db_pruner_pool")?;
+        let db_pruner = DbPruner::new(db_pruner_config, db_pruner_pool);
+        Some(tokio::spawn(
+            shutdown_tracker.track("db_pruner", db_pruner.run(stop_receiver.clone())),
+        ))
+    } else {
+        None
+    };
+
+    let block_exporter_handle = if let Some(kafka_brokers) =
+        &config.optional.block_exporter_kafka_brokers
+    {
+        let sink = KafkaSink::new(
+            kafka_brokers,
+            config.optional.block_exporter_kafka_topic.clone(),
+            config.optional.block_exporter_message_timeout(),
+        )?;
+        let block_exporter_config = BlockExporterConfig {
+            l2_chain_id: config.remote.l2_chain_id,
+            poll_interval: config.optional.polling_interval(),
+            chunk_size: config.optional.block_exporter_chunk_size,
+            first_miniblock: config
+                .optional
+                .block_exporter_first_miniblock
+                .map(MiniblockNumber),
+        };
+        let block_exporter_pool = singleton_pool_builder
+            .build()
+            .await
+            .context("failed to build a block_exporter_pool")?;
+        let block_exporter = BlockExporter::new(block_exporter_config, block_exporter_pool, sink);
+        Some(tokio::spawn(shutdown_tracker.track(
+            "block_exporter",
+            block_exporter.run(stop_receiver.clone()),
+        )))
+    } else {
+        None
+    };
 
-    let updater_handle = task::spawn(batch_status_updater.run(stop_receiver.clone()));
-    let fee_address_migration_handle =
-        task::spawn(state_keeper.run_fee_address_migration(connection_pool.clone()));
-    let sk_handle = task::spawn(state_keeper.run());
+    let updater_handle = task::spawn(shutdown_tracker.track(
+        "batch_status_updater",
+        batch_status_updater.run(stop_receiver.clone()),
+    ));
+    let fee_address_migration_handle = task::spawn(
+        state_keeper
+            .run_fee_address_migration(connection_pool.clone())
+            .instrument(component_span("state_keeper")),
+    );
+    let sk_handle = task::spawn(
+        shutdown_tracker
+            .track("state_keeper", state_keeper.run())
+            .instrument(component_span("state_keeper")),
+    );
     let fee_params_fetcher_handle =
         tokio::spawn(fee_params_fetcher.clone().run(stop_receiver.clone()));
 
@@ -281,10 +403,6 @@ async fn init_tasks(
                 .with_main_connection_pool(connection_pool.clone())
                 .with_tx_proxy(main_node_client);
 
-        if config.optional.transactions_per_sec_limit.is_some() {
-            tracing::warn!("`transactions_per_sec_limit` option is deprecated and ignored");
-        };
-
         let max_concurrency = config.optional.vm_concurrency_limit;
         let (vm_concurrency_limiter, vm_barrier) = VmConcurrencyLimiter::new(max_concurrency);
         let mut storage_caches = PostgresStorageCaches::new(
@@ -311,15 +429,22 @@ async fn init_tasks(
         (tx_sender, vm_barrier, cache_update_handle)
     };
 
+    let response_cache = ResponseCache::new(config.optional.response_cache_size() as u64);
     let http_server_handles =
         ApiBuilder::jsonrpsee_backend(config.clone().into(), connection_pool.clone())
             .http(config.required.http_port)
             .with_filter_limit(config.optional.filters_limit)
             .with_batch_request_size_limit(config.optional.max_batch_request_size)
             .with_response_body_size_limit(config.optional.max_response_body_size())
+            .with_request_body_size_limit(config.optional.max_request_body_size())
+            .with_response_compression(config.optional.compress_responses)
+            .with_rate_limit(config.optional.rate_limit())
+            .with_audit_log(config.optional.audit_log())
             .with_tx_sender(tx_sender.clone(), vm_barrier.clone())
             .with_sync_state(sync_state.clone())
-            .enable_api_namespaces(config.optional.api_namespaces())
+            .with_response_cache(response_cache.clone())
+            .with_method_filter(config.optional.http_api_method_filter()?)
+            .enable_api_namespaces(config.optional.http_api_namespaces())
             .build(stop_receiver.clone())
             .await
             .context("Failed initializing HTTP JSON-RPC server")?;
@@ -331,10 +456,15 @@ async fn init_tasks(
             .with_subscriptions_limit(config.optional.subscriptions_limit)
             .with_batch_request_size_limit(config.optional.max_batch_request_size)
             .with_response_body_size_limit(config.optional.max_response_body_size())
+            .with_request_body_size_limit(config.optional.max_request_body_size())
             .with_polling_interval(config.optional.polling_interval())
+            .with_rate_limit(config.optional.rate_limit())
+            .with_audit_log(config.optional.audit_log())
             .with_tx_sender(tx_sender, vm_barrier)
             .with_sync_state(sync_state)
-            .enable_api_namespaces(config.optional.api_namespaces())
+            .with_response_cache(response_cache)
+            .with_method_filter(config.optional.ws_api_method_filter()?)
+            .enable_api_namespaces(config.optional.ws_api_namespaces())
             .build(stop_receiver.clone())
             .await
             .context("Failed initializing WS JSON-RPC server")?;
@@ -356,9 +486,21 @@ async fn init_tasks(
         }));
     }
 
-    task_handles.extend(http_server_handles.tasks);
-    task_handles.extend(ws_server_handles.tasks);
+    task_handles.extend(
+        http_server_handles
+            .tasks
+            .into_iter()
+            .map(|task| shutdown_tracker.track_task("http_api", task)),
+    );
+    task_handles.extend(
+        ws_server_handles
+            .tasks
+            .into_iter()
+            .map(|task| shutdown_tracker.track_task("ws_api", task)),
+    );
     task_handles.extend(cache_update_handle);
+    task_handles.extend(db_pruner_handle);
+    task_handles.extend(block_exporter_handle);
     task_handles.extend([
         sk_handle,
         fee_address_migration_handle,
@@ -374,16 +516,19 @@ async fn init_tasks(
 }
 
 async fn shutdown_components(
-    stop_sender: watch::Sender<bool>,
-    healthcheck_handle: HealthCheckHandle,
+    stop_sender: &watch::Sender<bool>,
+    shutdown_tracker: &ShutdownTracker,
+    timeout: Duration,
 ) {
-    stop_sender.send(true).ok();
-    task::spawn_blocking(RocksDB::await_rocksdb_termination)
-        .await
-        .unwrap();
-    // Sleep for some time to let components gracefully stop.
-    sleep(Duration::from_secs(10)).await;
-    healthcheck_handle.stop().await;
+    let report = shutdown_tracker.shutdown(stop_sender, timeout).await;
+    if report.is_complete() {
+        tracing::info!("Node components stopped in {:?}", report.latency);
+    } else {
+        tracing::warn!(
+            "Node components stopped in {:?}; some of them haven't stopped gracefully by the deadline",
+            report.latency
+        );
+    }
 }
 
 /// External node for zkSync Era.
@@ -404,12 +549,70 @@ struct Cli {
     /// This is an experimental and incomplete feature; do not use unless you know what you're doing.
     #[arg(long, conflicts_with = "enable_consensus")]
     enable_snapshots_recovery: bool,
+    /// Path to a YAML or TOML file with configuration. Env variables take precedence over values from the file.
+    #[arg(long)]
+    config_path: Option<PathBuf>,
+    /// Fail if deprecated config options are used instead of logging warnings.
+    #[arg(long)]
+    strict_config: bool,
+    /// Inspect or change the Postgres schema and exit: `plan` prints pending migrations with an estimate of locks
+    /// they take on existing tables, `apply` applies pending migrations, and `revert-to <version>` reverts
+    /// migrations applied after the specified version. Migrations are never applied implicitly on startup.
+    #[arg(long, num_args = 1..=2, value_names = ["ACTION", "VERSION"])]
+    migrations: Option<Vec<String>>,
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Prints the effective local configuration as JSON with secrets redacted, together with the source
+    /// of each value (default, env variable or config file), and exits.
+    #[command(name = "print-config")]
+    PrintConfig,
 }
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     // Initial setup.
     let opt = Cli::parse();
+    let migrations_action = opt
+        .migrations
+        .as_deref()
+        .map(MigrationsAction::from_args)
+        .transpose()?;
+    startup::mark_started();
+    let config_loading_started_at = Instant::now();
+
+    // Must be performed before any configs are loaded.
+    let config_source = opt
+        .config_path
+        .as_ref()
+        .map(ConfigSource::from_file)
+        .transpose()
+        .context("ConfigSource::from_file()")?;
+    let exported_vars_count = config_source.as_ref().map(ConfigSource::export_to_env);
+    // The remote config has lower priority than the local config file, so it must be exported after the file.
+    let remote_config_source =
+        RemoteConfigSource::from_env().context("RemoteConfigSource::from_env()")?;
+    let remote_config = match &remote_config_source {
+        Some(source) => Some(source.fetch().await?),
+        None => None,
+    };
+    let remote_vars_count = remote_config.as_ref().map(ConfigSource::export_to_env);
+    // Secret references may be defined both in env vars and in the config file, so this must be performed
+    // after the file is loaded.
+    let secrets_resolver = SecretsResolver::from_env().context("SecretsResolver::from_env()")?;
+    let resolved_secrets = secrets_resolver
+        .resolve_env()
+        .await
+        .context("failed resolving secrets")?;
+
+    if let Some(Command::PrintConfig) = opt.command {
+        let dump = config::dump_configs(config_source.as_ref(), resolved_secrets)?;
+        println!("{}", serde_json::to_string_pretty(&dump)?);
+        return Ok(());
+    }
 
     let observability_config =
         observability_config_from_env().context("ObservabilityConfig::from_env()")?;
@@ -419,13 +622,75 @@ async fn main() -> anyhow::Result<()> {
         .context("Invalid log format")?;
 
     let mut builder = vlog::ObservabilityBuilder::new().with_log_format(log_format);
+    if let Some(log_directives) = &observability_config.log_directives {
+        builder = builder
+            .with_log_directives(log_directives)
+            .context("Invalid log directives")?;
+    }
+    if let Some(log_file_path) = &observability_config.log_file_path {
+        let rotation = observability_config
+            .log_file_rotation
+            .as_deref()
+            .unwrap_or("never")
+            .parse()
+            .context("Invalid log file rotation")?;
+        let mut options = vlog::LogFileOptions::new(log_file_path.into()).with_rotation(rotation);
+        if let Some(max_size) = observability_config.log_file_max_size_bytes {
+            options = options.with_max_size(max_size);
+        }
+        if let Some(max_files) = observability_config.log_file_max_files {
+            options = options.with_max_files(max_files);
+        }
+        builder = builder.with_log_file(options);
+    }
+    if let Some(interval) = observability_config.log_throttle_interval_sec {
+        let burst = observability_config.log_throttle_burst.unwrap_or(10);
+        builder = builder.with_log_throttling(Duration::from_secs(interval), burst);
+    }
     if let Some(sentry_url) = &observability_config.sentry_url {
+        let error_filter = observability_config
+            .sentry_error_filter
+            .as_deref()
+            .unwrap_or_default()
+            .parse()
+            .context("Invalid Sentry error filter")?;
+        let release =
+            option_env!("GIT_HASH").map(|hash| format!("{}@{hash}", env!("CARGO_PKG_NAME")));
         builder = builder
             .with_sentry_url(sentry_url)
             .expect("Invalid Sentry URL")
-            .with_sentry_environment(observability_config.sentry_environment);
+            .with_sentry_environment(observability_config.sentry_environment)
+            .with_sentry_release(release)
+            .with_sentry_sample_rate(observability_config.sentry_sample_rate)
+            .with_sentry_traces_sample_rate(observability_config.sentry_traces_sample_rate)
+            .with_sentry_error_filter(error_filter);
+    }
+    if let Some(otlp_url) = &observability_config.otlp_url {
+        let options =
+            vlog::OpenTelemetryOptions::new(otlp_url.clone(), env!("CARGO_PKG_NAME").to_owned())
+                .with_sampling_ratio(observability_config.otlp_sampling_ratio.unwrap_or(1.0))
+                .with_resource_attributes(
+                    observability_config
+                        .otlp_resource_attributes
+                        .as_deref()
+                        .unwrap_or_default(),
+                )
+                .context("Invalid OTLP resource attributes")?;
+        builder = builder.with_opentelemetry(options);
+    }
+    let observability_guard = builder.build()?;
+    if let Some(config_path) = opt.config_path.clone() {
+        let log_reload_handle = observability_guard.log_reload_handle();
+        tokio::spawn(reload_logs_on_sighup(config_path, log_reload_handle));
+    }
+    if let (Some(source), Some(config)) = (remote_config_source, remote_config) {
+        let log_reload_handle = observability_guard.log_reload_handle();
+        tokio::spawn(reload_logs_on_remote_update(
+            source,
+            config,
+            log_reload_handle,
+        ));
     }
-    let _guard = builder.build();
 
     // Report whether sentry is running after the logging subsystem was initialized.
     if let Some(sentry_url) = observability_config.sentry_url {
@@ -433,10 +698,24 @@ async fn main() -> anyhow::Result<()> {
     } else {
         tracing::info!("No sentry URL was provided");
     }
+    if let (Some(config_path), Some(count)) = (&opt.config_path, exported_vars_count) {
+        tracing::info!(
+            "Loaded {count} config values from {config_path:?} not overridden by env variables"
+        );
+    }
+    if let Some(count) = remote_vars_count {
+        tracing::info!(
+            "Loaded {count} config values from the remote config not overridden by env variables or config file"
+        );
+    }
+    if !resolved_secrets.is_empty() {
+        tracing::info!("Resolved secrets for env variables: {resolved_secrets:?}");
+    }
 
     let mut config = ExternalNodeConfig::collect()
         .await
         .context("Failed to load external node config")?;
+    deprecation::finish(opt.strict_config)?;
     if opt.enable_consensus {
         // This is more of a sanity check; the mutual exclusion of `enable_consensus` and `enable_snapshots_recovery`
         // should be ensured by `clap`.
@@ -447,6 +726,10 @@ async fn main() -> anyhow::Result<()> {
         config.consensus =
             Some(config::read_consensus_config().context("read_consensus_config()")?);
     }
+    startup::report_phase(
+        StartupPhase::ConfigLoading,
+        config_loading_started_at.elapsed(),
+    );
 
     if let Some(threshold) = config.optional.slow_query_threshold() {
         ConnectionPool::global_config().set_slow_query_threshold(threshold)?;
@@ -459,9 +742,15 @@ async fn main() -> anyhow::Result<()> {
         &config.postgres.database_url,
         config.postgres.max_connections,
     )
+    .set_read_replicas(config.optional.database_read_replica_urls.clone())
+    .set_max_replica_lag(config.optional.max_replica_lag())
     .build()
     .await
     .context("failed to build a connection_pool")?;
+    if let Some(action) = migrations_action {
+        return action.execute(&connection_pool).await;
+    }
+    startup::report_applied_migrations(&connection_pool).await;
 
     if opt.revert_pending_l1_batch {
         tracing::info!("Rolling pending L1 batch back..");
@@ -494,7 +783,7 @@ async fn main() -> anyhow::Result<()> {
         return Ok(());
     }
 
-    let sigint_receiver = setup_sigint_handler();
+    let mut sigint_receiver = setup_sigint_handler();
     tracing::warn!("The external node is in the alpha phase, and should be used with caution.");
     tracing::info!("Started the external node");
     let main_node_url = config
@@ -512,6 +801,18 @@ async fn main() -> anyhow::Result<()> {
     app_health.insert_custom_component(Arc::new(ConnectionPoolHealthCheck::new(
         connection_pool.clone(),
     )));
+    let l1_batch_thresholds = L1BatchHealthThresholds {
+        max_sealed_l1_batch_age: config.optional.healthcheck_max_sealed_l1_batch_age(),
+        max_tree_lag: config.optional.healthcheck_max_tree_lag,
+    };
+    app_health.insert_custom_component(Arc::new(L1BatchHealthCheck::new(
+        connection_pool.clone(),
+        l1_batch_thresholds,
+    )));
+    let l1_client = QueryClient::new(&config.required.eth_client_url()?)
+        .context("Failed creating L1 client")?;
+    let l1_client: Arc<dyn EthInterface> = Arc::new(l1_client);
+    app_health.insert_custom_component(Arc::new(L1ClientHealthCheck::from(l1_client.clone())));
 
     // Start the health check server early into the node lifecycle so that its health can be monitored from the very start.
     let healthcheck_handle = HealthCheckHandle::spawn_server(
@@ -520,84 +821,156 @@ async fn main() -> anyhow::Result<()> {
     );
 
     // Make sure that the node storage is initialized either via genesis or snapshot recovery.
+    let storage_init_started_at = Instant::now();
     ensure_storage_initialized(
         &connection_pool,
         &main_node_client,
+        l1_client,
+        config.remote.diamond_proxy_addr,
         &app_health,
         config.remote.l2_chain_id,
         opt.enable_snapshots_recovery,
     )
     .await?;
+    startup::report_phase(
+        StartupPhase::StorageInitialization,
+        storage_init_started_at.elapsed(),
+    );
 
-    let (stop_sender, stop_receiver) = watch::channel(false);
-    let mut task_handles = vec![];
-    init_tasks(
-        &config,
-        connection_pool.clone(),
-        main_node_client.clone(),
-        &mut task_handles,
-        &app_health,
-        stop_receiver.clone(),
-    )
-    .await
-    .context("init_tasks")?;
-
-    let reorg_detector = ReorgDetector::new(main_node_client, connection_pool.clone());
-    app_health.insert_component(reorg_detector.health_check().clone());
-    let mut reorg_detector_handle = tokio::spawn(reorg_detector.run(stop_receiver)).fuse();
-    let mut reorg_detector_result = None;
-
-    let particular_crypto_alerts = None;
-    let graceful_shutdown = None::<futures::future::Ready<()>>;
-    let tasks_allowed_to_finish = false;
-
-    tokio::select! {
-        _ = wait_for_tasks(task_handles, particular_crypto_alerts, graceful_shutdown, tasks_allowed_to_finish) => {},
-        _ = sigint_receiver => {
-            tracing::info!("Stop signal received, shutting down");
-        },
-        result = &mut reorg_detector_handle => {
-            tracing::info!("Reorg detector terminated, shutting down");
-            reorg_detector_result = Some(result);
+    let mut enabled_components = vec![
+        "fetcher",
+        "state_keeper",
+        "tree",
+        "consistency_checker",
+        "batch_status_updater",
+        "commitment_generator",
+        "http_api",
+        "ws_api",
+        "reorg_detector",
+    ];
+    if config.optional.prometheus_port.is_some() {
+        enabled_components.push("prometheus_exporter");
+    }
+    if config.optional.pruning_enabled {
+        enabled_components.push("db_pruner");
+    }
+    if config.optional.block_exporter_kafka_brokers.is_some() {
+        enabled_components.push("block_exporter");
+    }
+    if opt.enable_consensus {
+        enabled_components.push("consensus");
+    }
+    if opt.enable_snapshots_recovery {
+        enabled_components.push("snapshots_recovery");
+    }
+    startup::report_enabled_components(&enabled_components);
+
+    // Health checks registered so far are retained across component restarts; all other health checks
+    // are removed before a restart, so that they are not registered multiple times.
+    let persistent_health_checks = app_health.component_names();
+    // Node components are restarted after an automatic rollback performed on a detected reorg.
+    loop {
+        let (stop_sender, stop_receiver) = watch::channel(false);
+        let shutdown_tracker = ShutdownTracker::default();
+        let mut task_handles = vec![];
+        let components_init_started_at = Instant::now();
+        init_tasks(
+            &config,
+            connection_pool.clone(),
+            main_node_client.clone(),
+            &mut task_handles,
+            &app_health,
+            stop_receiver.clone(),
+            &shutdown_tracker,
+        )
+        .await
+        .context("init_tasks")?;
+        startup::report_phase(
+            StartupPhase::ComponentsInitialization,
+            components_init_started_at.elapsed(),
+        );
+
+        let mut reorg_detector =
+            ReorgDetector::new(main_node_client.clone(), connection_pool.clone());
+        if let Some(max_depth) = config.optional.reorg_detector_max_rollback_depth {
+            reorg_detector = reorg_detector.with_max_rollback_depth(max_depth);
         }
-    };
+        app_health.insert_component(reorg_detector.health_check().clone());
+        let mut reorg_detector_handle = tokio::spawn(reorg_detector.run(stop_receiver)).fuse();
+        let mut reorg_detector_result = None;
+
+        let particular_crypto_alerts = None;
+        let graceful_shutdown = None::<futures::future::Ready<()>>;
+        let tasks_allowed_to_finish = false;
+        let mut stop_signal_received = false;
+
+        tokio::select! {
+            _ = wait_for_tasks(task_handles, particular_crypto_alerts, graceful_shutdown, tasks_allowed_to_finish) => {},
+            _ = &mut sigint_receiver => {
+                tracing::info!("Stop signal received, shutting down");
+                stop_signal_received = true;
+            },
+            result = &mut reorg_detector_handle => {
+                tracing::info!("Reorg detector terminated, shutting down components");
+                reorg_detector_result = Some(result);
+            }
+        };
 
-    // Reaching this point means that either some actor exited unexpectedly or we received a stop signal.
-    // Broadcast the stop signal to all actors and exit.
-    shutdown_components(stop_sender, healthcheck_handle).await;
+        // Reaching this point means that either some actor exited unexpectedly, we received a stop signal,
+        // or a reorg was detected. Broadcast the stop signal to all actors.
+        shutdown_components(
+            &stop_sender,
+            &shutdown_tracker,
+            config.optional.shutdown_timeout(),
+        )
+        .await;
 
-    if !reorg_detector_handle.is_terminated() {
-        reorg_detector_result = Some(reorg_detector_handle.await);
-    }
-    let reorg_detector_last_correct_batch = reorg_detector_result.and_then(|result| match result {
-        Ok(Ok(last_correct_batch)) => last_correct_batch,
-        Ok(Err(err)) => {
-            tracing::error!("Reorg detector failed: {err:#}");
-            None
-        }
-        Err(err) => {
-            tracing::error!("Reorg detector panicked: {err}");
-            None
+        if !reorg_detector_handle.is_terminated() {
+            reorg_detector_result = Some(reorg_detector_handle.await);
         }
-    });
-
-    if let Some(last_correct_batch) = reorg_detector_last_correct_batch {
+        let reorg_detector_last_correct_batch =
+            reorg_detector_result.and_then(|result| match result {
+                Ok(Ok(last_correct_batch)) => last_correct_batch,
+                Ok(Err(err)) => {
+                    tracing::error!("Reorg detector failed: {err:#}");
+                    None
+                }
+                Err(err) => {
+                    tracing::error!("Reorg detector panicked: {err}");
+                    None
+                }
+            });
+
+        let Some(last_correct_batch) = reorg_detector_last_correct_batch else {
+            break;
+        };
         tracing::info!("Performing rollback to L1 batch #{last_correct_batch}");
-
         let reverter = BlockReverter::new(
-            config.required.state_cache_path,
-            config.required.merkle_tree_path,
+            config.required.state_cache_path.clone(),
+            config.required.merkle_tree_path.clone(),
             None,
-            connection_pool,
+            connection_pool.clone(),
             L1ExecutedBatchesRevert::Allowed,
         );
         reverter
             .rollback_db(last_correct_batch, BlockReverterFlags::all())
             .await;
+
+        if stop_signal_received {
+            tracing::info!("Rollback successfully completed");
+            break;
+        }
         tracing::info!(
-            "Rollback successfully completed, the node has to restart to continue working"
+            "Rollback successfully completed, restarting node components to resume syncing"
         );
+        let restarted_health_checks: Vec<_> = app_health
+            .component_names()
+            .into_iter()
+            .filter(|name| !persistent_health_checks.contains(name))
+            .collect();
+        app_health.remove_components(&restarted_health_checks);
     }
 
+    healthcheck_handle.stop().await;
     Ok(())
 }
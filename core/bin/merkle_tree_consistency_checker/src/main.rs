@@ -2,7 +2,12 @@ use std::{path::Path, time::Instant};
 
 use anyhow::Context as _;
 use clap::Parser;
-use zksync_config::{configs::ObservabilityConfig, DBConfig};
+use zksync_config::{
+    configs::{chain::OperationsManagerConfig, ObservabilityConfig},
+    DBConfig, PostgresConfig,
+};
+use zksync_core::metadata_calculator::{MetadataCalculatorConfig, TreeConsistencyChecker};
+use zksync_dal::ConnectionPool;
 use zksync_env_config::FromEnv;
 use zksync_merkle_tree::domain::ZkSyncTree;
 use zksync_storage::RocksDB;
@@ -20,6 +25,10 @@ struct Cli {
     /// applied to it last. If not specified, the latest tree version is checked.
     #[arg(long = "l1-batch")]
     l1_batch: Option<u32>,
+    /// Cross-checks root hashes of all tree versions with L1 batches in Postgres in addition to verifying
+    /// the tree nodes. If an inconsistency is detected, the earliest inconsistent tree version is searched for.
+    #[arg(long, conflicts_with = "l1_batch")]
+    with_postgres: bool,
 }
 
 impl Cli {
@@ -45,6 +54,32 @@ impl Cli {
         tree.verify_consistency(l1_batch_number);
         tracing::info!("Merkle tree verified in {:?}", start.elapsed());
     }
+
+    async fn run_with_postgres(config: &DBConfig) -> anyhow::Result<()> {
+        let operations_manager_config =
+            OperationsManagerConfig::from_env().context("OperationsManagerConfig::from_env()")?;
+        let postgres_config = PostgresConfig::from_env().context("PostgresConfig::from_env()")?;
+        let pool = ConnectionPool::singleton(postgres_config.master_url()?)
+            .build()
+            .await
+            .context("failed to build a connection pool")?;
+        let config = MetadataCalculatorConfig::for_main_node(
+            &config.merkle_tree,
+            &operations_manager_config,
+        );
+
+        tracing::info!(
+            "Verifying consistency of Merkle tree at {} with Postgres",
+            config.db_path
+        );
+        let start = Instant::now();
+        let checker = TreeConsistencyChecker::new(&config).await?;
+        if let Some(inconsistency) = checker.check(&pool).await? {
+            anyhow::bail!("{inconsistency}");
+        }
+        tracing::info!("Merkle tree verified in {:?}", start.elapsed());
+        Ok(())
+    }
 }
 
 fn main() -> anyhow::Result<()> {
@@ -61,9 +96,16 @@ fn main() -> anyhow::Result<()> {
             .context("Invalid Sentry URL")?
             .with_sentry_environment(observability_config.sentry_environment);
     }
-    let _guard = builder.build();
+    let _guard = builder.build()?;
 
     let db_config = DBConfig::from_env().context("DBConfig::from_env()")?;
-    Cli::parse().run(&db_config);
-    Ok(())
+    let cli = Cli::parse();
+    if cli.with_postgres {
+        tokio::runtime::Runtime::new()
+            .context("failed creating Tokio runtime")?
+            .block_on(Cli::run_with_postgres(&db_config))
+    } else {
+        cli.run(&db_config);
+        Ok(())
+    }
 }
@@ -0,0 +1,102 @@
+use anyhow::Context as _;
+use clap::{Parser, Subcommand};
+use tokio::sync::watch;
+use zksync_config::{
+    configs::{chain::OperationsManagerConfig, ObservabilityConfig},
+    DBConfig, PostgresConfig,
+};
+use zksync_core::metadata_calculator::{MetadataCalculatorConfig, TreeRebuilder};
+use zksync_dal::ConnectionPool;
+use zksync_env_config::FromEnv;
+
+#[derive(Debug, Parser)]
+#[command(
+    author = "Matter Labs",
+    version,
+    about = "Merkle tree rebuild utility",
+    long_about = "Detects loss or corruption of the Merkle tree RocksDB and rebuilds the tree from the storage history \
+        in Postgres, so that the node doesn't need to recompute the tree L1 batch by L1 batch. The rebuild can be \
+        interrupted with Ctrl+C and is resumed on the next run. The node must be stopped while the utility runs, \
+        since the tree RocksDB is opened exclusively."
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Checks whether the tree RocksDB is consistent with Postgres.
+    #[command(name = "check")]
+    Check,
+    /// Rebuilds the tree from Postgres if it's lost or corrupted.
+    #[command(name = "rebuild")]
+    Rebuild {
+        /// Removes the tree RocksDB and rebuilds the tree without checking it first.
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let observability_config =
+        ObservabilityConfig::from_env().context("ObservabilityConfig::from_env()")?;
+    let log_format: vlog::LogFormat = observability_config
+        .log_format
+        .parse()
+        .context("Invalid log format")?;
+    let mut builder = vlog::ObservabilityBuilder::new().with_log_format(log_format);
+    if let Some(sentry_url) = observability_config.sentry_url {
+        builder = builder
+            .with_sentry_url(&sentry_url)
+            .context("Invalid Sentry URL")?
+            .with_sentry_environment(observability_config.sentry_environment);
+    }
+    let _guard = builder.build()?;
+
+    let db_config = DBConfig::from_env().context("DBConfig::from_env()")?;
+    let operations_manager_config =
+        OperationsManagerConfig::from_env().context("OperationsManagerConfig::from_env()")?;
+    let postgres_config = PostgresConfig::from_env().context("PostgresConfig::from_env()")?;
+    let pool = ConnectionPool::builder(
+        postgres_config.master_url()?,
+        postgres_config.max_connections()?,
+    )
+    .build()
+    .await
+    .context("failed to build a connection pool")?;
+    let config =
+        MetadataCalculatorConfig::for_main_node(&db_config.merkle_tree, &operations_manager_config);
+    let rebuilder = TreeRebuilder::new(config);
+
+    match Cli::parse().command {
+        Command::Check => {
+            let state = rebuilder.check(&pool).await?;
+            println!("Merkle tree state: {state:?}");
+            if state.needs_rebuild() {
+                println!("The tree should be rebuilt using the `rebuild` command");
+            }
+        }
+        Command::Rebuild { force } => {
+            let (stop_sender, stop_receiver) = watch::channel(false);
+            tokio::spawn(async move {
+                if tokio::signal::ctrl_c().await.is_ok() {
+                    tracing::info!(
+                        "Stop signal received; the rebuild will be resumed on the next run"
+                    );
+                    stop_sender.send_replace(true);
+                }
+            });
+
+            match rebuilder.rebuild(&pool, &stop_receiver, force).await? {
+                Some(l1_batch) => println!("Rebuilt Merkle tree for L1 batch #{l1_batch}"),
+                None if *stop_receiver.borrow() => println!("Merkle tree rebuild was interrupted"),
+                None => {
+                    println!("Merkle tree is consistent with Postgres; rebuild is not necessary")
+                }
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,104 @@
+use anyhow::Context as _;
+use clap::{Parser, Subcommand};
+use zksync_config::{
+    configs::{chain::OperationsManagerConfig, ObservabilityConfig},
+    DBConfig, PostgresConfig,
+};
+use zksync_core::metadata_calculator::{MetadataCalculatorConfig, TreeSnapshotManager};
+use zksync_dal::ConnectionPool;
+use zksync_env_config::{object_store::SnapshotsObjectStoreConfig, FromEnv};
+use zksync_object_store::ObjectStoreFactory;
+use zksync_types::L1BatchNumber;
+
+#[derive(Debug, Parser)]
+#[command(
+    author = "Matter Labs",
+    version,
+    about = "Merkle tree snapshot utility",
+    long_about = "Exports Merkle tree snapshots to the snapshots object store and imports them on a fresh node, \
+        so that the node doesn't need to recompute the tree from Postgres. The node must be stopped while \
+        the utility runs, since the tree RocksDB is opened exclusively."
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Exports a snapshot of the tree after the specified L1 batch.
+    #[command(name = "export")]
+    Export {
+        /// Number of the L1 batch to export the tree snapshot for.
+        #[arg(long = "l1-batch")]
+        l1_batch: u32,
+        /// Number of tree entries in a single snapshot chunk.
+        #[arg(long)]
+        chunk_size: Option<usize>,
+    },
+    /// Imports a snapshot of the tree after the specified L1 batch into an empty tree.
+    #[command(name = "import")]
+    Import {
+        /// Number of the L1 batch to import the tree snapshot for.
+        #[arg(long = "l1-batch")]
+        l1_batch: u32,
+    },
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let observability_config =
+        ObservabilityConfig::from_env().context("ObservabilityConfig::from_env()")?;
+    let log_format: vlog::LogFormat = observability_config
+        .log_format
+        .parse()
+        .context("Invalid log format")?;
+    let mut builder = vlog::ObservabilityBuilder::new().with_log_format(log_format);
+    if let Some(sentry_url) = observability_config.sentry_url {
+        builder = builder
+            .with_sentry_url(&sentry_url)
+            .context("Invalid Sentry URL")?
+            .with_sentry_environment(observability_config.sentry_environment);
+    }
+    let _guard = builder.build()?;
+
+    let db_config = DBConfig::from_env().context("DBConfig::from_env()")?;
+    let operations_manager_config =
+        OperationsManagerConfig::from_env().context("OperationsManagerConfig::from_env()")?;
+    let object_store_config =
+        SnapshotsObjectStoreConfig::from_env().context("SnapshotsObjectStoreConfig::from_env()")?;
+    let object_store = ObjectStoreFactory::new(object_store_config.0)
+        .create_store()
+        .await;
+    let config =
+        MetadataCalculatorConfig::for_main_node(&db_config.merkle_tree, &operations_manager_config);
+    let mut manager = TreeSnapshotManager::new(config, object_store);
+
+    match Cli::parse().command {
+        Command::Export {
+            l1_batch,
+            chunk_size,
+        } => {
+            if let Some(chunk_size) = chunk_size {
+                anyhow::ensure!(chunk_size > 0, "`--chunk-size` must be positive");
+                manager.set_chunk_size(chunk_size);
+            }
+            let header = manager.export(L1BatchNumber(l1_batch)).await?;
+            println!(
+                "Exported Merkle tree snapshot for L1 batch #{l1_batch}: {} entries in {} chunks, root hash {:?}",
+                header.leaf_count, header.chunk_count, header.root_hash
+            );
+        }
+        Command::Import { l1_batch } => {
+            let postgres_config =
+                PostgresConfig::from_env().context("PostgresConfig::from_env()")?;
+            let pool = ConnectionPool::singleton(postgres_config.master_url()?)
+                .build()
+                .await
+                .context("failed to build a connection pool")?;
+            manager.import(&pool, L1BatchNumber(l1_batch)).await?;
+            println!("Imported Merkle tree snapshot for L1 batch #{l1_batch}");
+        }
+    }
+    Ok(())
+}
@@ -1,6 +1,6 @@
 //! [`SnapshotCreator`] and tightly related types.
 
-use std::sync::Arc;
+use std::{collections::HashSet, sync::Arc};
 
 use anyhow::Context as _;
 use tokio::sync::Semaphore;
@@ -9,13 +9,14 @@ use zksync_dal::{ConnectionPool, StorageProcessor};
 use zksync_object_store::ObjectStore;
 use zksync_types::{
     snapshots::{
-        uniform_hashed_keys_chunk, SnapshotFactoryDependencies, SnapshotFactoryDependency,
-        SnapshotMetadata, SnapshotStorageLogsChunk, SnapshotStorageLogsStorageKey,
+        snapshot_file_checksum, uniform_hashed_keys_chunk, SnapshotFactoryDependencies,
+        SnapshotFactoryDependency, SnapshotMetadata, SnapshotStorageLogsChunk,
+        SnapshotStorageLogsStorageKey, SnapshotTokens, SnapshotVersion,
     },
-    L1BatchNumber, MiniblockNumber,
+    L1BatchNumber, MiniblockNumber, H256,
 };
 
-use crate::metrics::{FactoryDepsStage, StorageChunkStage, METRICS};
+use crate::metrics::{FactoryDepsStage, StorageChunkStage, TokensStage, METRICS};
 #[cfg(test)]
 use crate::tests::HandleEvent;
 
@@ -25,8 +26,12 @@ struct SnapshotProgress {
     l1_batch_number: L1BatchNumber,
     /// `true` if the snapshot is new (i.e., its progress is not recovered from Postgres).
     is_new_snapshot: bool,
+    /// `true` if the tokens file should be produced for the snapshot.
+    needs_tokens: bool,
     chunk_count: u64,
     remaining_chunk_ids: Vec<u64>,
+    /// Snapshot to reuse storage logs chunks from. Only set for new incremental snapshots.
+    base: Option<SnapshotMetadata>,
 }
 
 impl SnapshotProgress {
@@ -34,11 +39,18 @@ impl SnapshotProgress {
         Self {
             l1_batch_number,
             is_new_snapshot: true,
+            needs_tokens: true,
             chunk_count,
             remaining_chunk_ids: (0..chunk_count).collect(),
+            base: None,
         }
     }
 
+    fn with_base(mut self, base: SnapshotMetadata) -> Self {
+        self.base = Some(base);
+        self
+    }
+
     fn from_existing_snapshot(snapshot: &SnapshotMetadata) -> Self {
         let remaining_chunk_ids = snapshot
             .storage_logs_filepaths
@@ -50,8 +62,12 @@ impl SnapshotProgress {
         Self {
             l1_batch_number: snapshot.l1_batch_number,
             is_new_snapshot: false,
+            needs_tokens: snapshot.version.has_tokens_and_checksums()
+                && snapshot.tokens_filepath.is_none(),
             chunk_count: snapshot.storage_logs_filepaths.len() as u64,
             remaining_chunk_ids,
+            // Reused chunks (if any) are recorded when the snapshot is created.
+            base: None,
         }
     }
 }
@@ -107,6 +123,7 @@ impl SnapshotCreator {
         let latency =
             METRICS.storage_logs_processing_duration[&StorageChunkStage::SaveToGcs].start();
         let storage_logs_chunk = SnapshotStorageLogsChunk { storage_logs: logs };
+        let checksum = snapshot_file_checksum(&storage_logs_chunk);
         let key = SnapshotStorageLogsStorageKey {
             l1_batch_number,
             chunk_id,
@@ -128,7 +145,12 @@ impl SnapshotCreator {
             .await?;
         master_conn
             .snapshots_dal()
-            .add_storage_logs_filepath_for_snapshot(l1_batch_number, chunk_id, &output_filepath)
+            .add_storage_logs_filepath_for_snapshot(
+                l1_batch_number,
+                chunk_id,
+                &output_filepath,
+                Some(checksum),
+            )
             .await?;
         #[cfg(test)]
         self.event_listener.on_chunk_saved();
@@ -145,7 +167,7 @@ impl SnapshotCreator {
         &self,
         miniblock_number: MiniblockNumber,
         l1_batch_number: L1BatchNumber,
-    ) -> anyhow::Result<String> {
+    ) -> anyhow::Result<(String, H256)> {
         let mut conn = self.connect_to_replica().await?;
 
         tracing::info!("Loading factory deps from Postgres...");
@@ -169,6 +191,7 @@ impl SnapshotCreator {
             })
             .collect();
         let factory_deps = SnapshotFactoryDependencies { factory_deps };
+        let checksum = snapshot_file_checksum(&factory_deps);
         let filename = self
             .blob_store
             .put(l1_batch_number, &factory_deps)
@@ -184,7 +207,189 @@ impl SnapshotCreator {
             factory_deps.factory_deps.len()
         );
 
-        Ok(output_filepath)
+        Ok((output_filepath, checksum))
+    }
+
+    async fn process_tokens(
+        &self,
+        miniblock_number: MiniblockNumber,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<()> {
+        let mut conn = self.connect_to_replica().await?;
+
+        tracing::info!("Loading tokens from Postgres...");
+        let latency = METRICS.tokens_processing_duration[&TokensStage::LoadFromPostgres].start();
+        let tokens = conn
+            .tokens_web3_dal()
+            .get_all_tokens(Some(miniblock_number))
+            .await?;
+        drop(conn);
+        let latency = latency.observe();
+        tracing::info!("Loaded {} tokens in {latency:?}", tokens.len());
+
+        let latency = METRICS.tokens_processing_duration[&TokensStage::SaveToGcs].start();
+        let tokens = SnapshotTokens { tokens };
+        let checksum = snapshot_file_checksum(&tokens);
+        let filename = self
+            .blob_store
+            .put(l1_batch_number, &tokens)
+            .await
+            .context("Error storing tokens in blob store")?;
+        let output_filepath_prefix = self.blob_store.get_storage_prefix::<SnapshotTokens>();
+        let output_filepath = format!("{output_filepath_prefix}/{filename}");
+        let latency = latency.observe();
+
+        let mut master_conn = self
+            .master_pool
+            .access_storage_tagged("snapshots_creator")
+            .await?;
+        master_conn
+            .snapshots_dal()
+            .add_tokens_filepath_for_snapshot(l1_batch_number, &output_filepath, checksum)
+            .await?;
+        tracing::info!(
+            "Saved {} tokens in {latency:?} to location: {output_filepath}",
+            tokens.tokens.len()
+        );
+        Ok(())
+    }
+
+    /// Checks whether a storage logs chunk not modified since the `base` snapshot can be reused from it,
+    /// i.e., the chunk has a checksum and (if `verify` is set) the chunk object matches this checksum.
+    async fn is_chunk_reusable(
+        &self,
+        semaphore: &Semaphore,
+        base: &SnapshotMetadata,
+        chunk_id: u64,
+        verify: bool,
+    ) -> anyhow::Result<bool> {
+        let _permit = semaphore.acquire().await?;
+        let Some(Some(expected_checksum)) = base.storage_logs_checksums.get(chunk_id as usize)
+        else {
+            return Ok(false);
+        };
+        if !verify {
+            return Ok(true);
+        }
+
+        let latency =
+            METRICS.storage_logs_processing_duration[&StorageChunkStage::VerifyReused].start();
+        let key = SnapshotStorageLogsStorageKey {
+            l1_batch_number: base.storage_logs_chunk_source(chunk_id),
+            chunk_id,
+        };
+        let chunk: SnapshotStorageLogsChunk = match self.blob_store.get(key).await {
+            Ok(chunk) => chunk,
+            Err(err) => {
+                tracing::warn!(
+                    "Failed fetching storage logs chunk {key:?} for verification, it will be produced again: {err}"
+                );
+                return Ok(false);
+            }
+        };
+        let actual_checksum = snapshot_file_checksum(&chunk);
+        latency.observe();
+        if actual_checksum != *expected_checksum {
+            tracing::warn!(
+                "Storage logs chunk {key:?} has unexpected checksum (expected {expected_checksum:?}, \
+                 got {actual_checksum:?}), it will be produced again"
+            );
+            return Ok(false);
+        }
+        Ok(true)
+    }
+
+    /// Returns IDs of storage logs chunks that can be reused from the `base` snapshot.
+    async fn find_reusable_chunks(
+        &self,
+        config: &SnapshotsCreatorConfig,
+        base: &SnapshotMetadata,
+        last_miniblock_number_in_batch: MiniblockNumber,
+    ) -> anyhow::Result<Vec<u64>> {
+        let mut conn = self.connect_to_replica().await?;
+        let (_, base_last_miniblock_number) = conn
+            .blocks_dal()
+            .get_miniblock_range_of_l1_batch(base.l1_batch_number)
+            .await?
+            .context("Error fetching last miniblock number for the base snapshot")?;
+        let new_miniblocks = (base_last_miniblock_number + 1)..=last_miniblock_number_in_batch;
+        let chunk_count = base.storage_logs_filepaths.len() as u64;
+        let modified_chunk_ids: HashSet<_> = conn
+            .snapshots_creator_dal()
+            .get_modified_storage_logs_chunks(new_miniblocks, chunk_count)
+            .await
+            .context("Error fetching modified storage logs chunks")?
+            .into_iter()
+            .collect();
+        drop(conn);
+
+        let semaphore = Semaphore::new(config.concurrent_queries_count as usize);
+        let verify = config.verify_reused_chunks();
+        let unmodified_chunk_ids: Vec<_> = (0..chunk_count)
+            .filter(|chunk_id| !modified_chunk_ids.contains(chunk_id))
+            .collect();
+        let tasks = unmodified_chunk_ids
+            .iter()
+            .map(|&chunk_id| self.is_chunk_reusable(&semaphore, base, chunk_id, verify));
+        let is_reusable = futures::future::try_join_all(tasks).await?;
+        Ok(unmodified_chunk_ids
+            .into_iter()
+            .zip(is_reusable)
+            .filter_map(|(chunk_id, is_reusable)| is_reusable.then_some(chunk_id))
+            .collect())
+    }
+
+    /// Selects the snapshot to base a new incremental snapshot on. Returns `Ok(None)` if a full snapshot
+    /// should be created instead.
+    async fn select_base_snapshot(
+        config: &SnapshotsCreatorConfig,
+        latest_snapshot: Option<&SnapshotMetadata>,
+        conn: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<Option<SnapshotMetadata>> {
+        if !config.incremental {
+            return Ok(None);
+        }
+        let Some(latest_snapshot) = latest_snapshot else {
+            tracing::info!("No previous snapshots; creating a full snapshot");
+            return Ok(None);
+        };
+        // We treat the latest snapshot as a base only if all its chunks have checksums, so that reused chunks
+        // can be verified by snapshot consumers.
+        let has_all_checksums = latest_snapshot.storage_logs_checksums.len()
+            == latest_snapshot.storage_logs_filepaths.len()
+            && latest_snapshot
+                .storage_logs_checksums
+                .iter()
+                .all(Option::is_some);
+        if !latest_snapshot.is_complete() || !has_all_checksums {
+            tracing::info!(
+                "Latest snapshot for L1 batch #{} cannot be used as a base; creating a full snapshot",
+                latest_snapshot.l1_batch_number
+            );
+            return Ok(None);
+        }
+
+        let max_chain_length = config.max_incremental_chain_length();
+        let mut chain_length = 0;
+        let mut snapshot_base = latest_snapshot.base_l1_batch_number;
+        while let Some(base_l1_batch_number) = snapshot_base {
+            chain_length += 1;
+            if chain_length >= max_chain_length {
+                break;
+            }
+            snapshot_base = conn
+                .snapshots_dal()
+                .get_snapshot_metadata(base_l1_batch_number)
+                .await?
+                .and_then(|snapshot| snapshot.base_l1_batch_number);
+        }
+        if chain_length >= max_chain_length {
+            tracing::info!(
+                "Reached max length of incremental snapshots chain ({max_chain_length}); creating a full snapshot"
+            );
+            return Ok(None);
+        }
+        Ok(Some(latest_snapshot.clone()))
     }
 
     /// Returns `Ok(None)` if the created snapshot would coincide with `latest_snapshot`.
@@ -222,6 +427,26 @@ impl SnapshotCreator {
             .div_ceil(chunk_size)
             .max(min_chunk_count);
 
+        let base = Self::select_base_snapshot(config, latest_snapshot, conn).await?;
+        if let Some(base) = base {
+            // Chunks can only be reused if the chunking is the same as in the base snapshot. We keep the base chunking
+            // until chunks become too large compared to the configured size.
+            let base_chunk_count = base.storage_logs_filepaths.len() as u64;
+            if chunk_count <= base_chunk_count * 2 {
+                tracing::info!(
+                    "Creating incremental snapshot for L1 batch {l1_batch_number} based on snapshot \
+                     for L1 batch {} with {base_chunk_count} chunks",
+                    base.l1_batch_number
+                );
+                let progress = SnapshotProgress::new(l1_batch_number, base_chunk_count);
+                return Ok(Some(progress.with_base(base)));
+            }
+            tracing::info!(
+                "Storage logs chunking of the base snapshot ({base_chunk_count} chunks) is outdated; \
+                 creating a full snapshot"
+            );
+        }
+
         tracing::info!(
             "Selected storage logs chunking for L1 batch {l1_batch_number}: \
             {chunk_count} chunks of expected size {chunk_size}"
@@ -262,7 +487,7 @@ impl SnapshotCreator {
     }
 
     pub async fn run(
-        self,
+        &self,
         config: SnapshotsCreatorConfig,
         min_chunk_count: u64,
     ) -> anyhow::Result<()> {
@@ -272,7 +497,7 @@ impl SnapshotCreator {
         );
         let latency = METRICS.snapshot_generation_duration.start();
 
-        let Some(progress) = self
+        let Some(mut progress) = self
             .load_or_initialize_snapshot_progress(&config, min_chunk_count)
             .await?
         else {
@@ -296,21 +521,62 @@ impl SnapshotCreator {
         );
 
         if progress.is_new_snapshot {
-            let factory_deps_output_file = self
+            let (factory_deps_output_file, factory_deps_checksum) = self
                 .process_factory_deps(last_miniblock_number_in_batch, progress.l1_batch_number)
                 .await?;
 
+            let reused_chunk_ids = if let Some(base) = &progress.base {
+                let reused_chunk_ids = self
+                    .find_reusable_chunks(&config, base, last_miniblock_number_in_batch)
+                    .await?;
+                tracing::info!(
+                    "Reusing {} of {} storage logs chunks from snapshot for L1 batch {}",
+                    reused_chunk_ids.len(),
+                    progress.chunk_count,
+                    base.l1_batch_number
+                );
+                progress
+                    .remaining_chunk_ids
+                    .retain(|chunk_id| reused_chunk_ids.binary_search(chunk_id).is_err());
+                reused_chunk_ids
+            } else {
+                vec![]
+            };
+            METRICS
+                .reused_storage_logs_chunks
+                .set(reused_chunk_ids.len());
+
             let mut master_conn = self
                 .master_pool
                 .access_storage_tagged("snapshots_creator")
                 .await?;
-            master_conn
-                .snapshots_dal()
-                .add_snapshot(
-                    progress.l1_batch_number,
-                    progress.chunk_count,
-                    &factory_deps_output_file,
-                )
+            if let Some(base) = &progress.base {
+                master_conn
+                    .snapshots_dal()
+                    .add_incremental_snapshot(
+                        SnapshotVersion::INCREMENTAL,
+                        progress.l1_batch_number,
+                        base,
+                        &reused_chunk_ids,
+                        &factory_deps_output_file,
+                        Some(factory_deps_checksum),
+                    )
+                    .await?;
+            } else {
+                master_conn
+                    .snapshots_dal()
+                    .add_snapshot(
+                        SnapshotVersion::LATEST,
+                        progress.l1_batch_number,
+                        progress.chunk_count,
+                        &factory_deps_output_file,
+                        Some(factory_deps_checksum),
+                    )
+                    .await?;
+            }
+        }
+        if progress.needs_tokens {
+            self.process_tokens(last_miniblock_number_in_batch, progress.l1_batch_number)
                 .await?;
         }
 
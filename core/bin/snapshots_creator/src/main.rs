@@ -1,4 +1,6 @@
 //! Snapshot creator utility. Intended to run on a schedule, with each run creating a new snapshot.
+//! The schedule can be either external (e.g., a Kubernetes cron job), or specified in the creator config
+//! as a cron-like expression; in the latter case, the creator runs until it receives a termination signal.
 //!
 //! # Assumptions
 //!
@@ -20,10 +22,11 @@ use zksync_dal::ConnectionPool;
 use zksync_env_config::{object_store::SnapshotsObjectStoreConfig, FromEnv};
 use zksync_object_store::ObjectStoreFactory;
 
-use crate::creator::SnapshotCreator;
+use crate::{creator::SnapshotCreator, schedule::CronSchedule};
 
 mod creator;
 mod metrics;
+mod schedule;
 #[cfg(test)]
 mod tests;
 
@@ -33,7 +36,7 @@ async fn maybe_enable_prometheus_metrics(
     let prometheus_config = PrometheusConfig::from_env().ok();
     if let Some(prometheus_config) = prometheus_config {
         let exporter_config = PrometheusExporterConfig::push(
-            prometheus_config.gateway_endpoint(),
+            prometheus_config.gateway_endpoint_for_job("snapshots_creator"),
             prometheus_config.push_interval(),
         );
 
@@ -49,6 +52,44 @@ async fn maybe_enable_prometheus_metrics(
 /// Minimum number of storage log chunks to produce.
 const MIN_CHUNK_COUNT: u64 = 10;
 
+/// Runs the snapshot creator according to the `schedule` until a termination signal is received. Errors
+/// creating a particular snapshot are logged and do not stop the creator.
+async fn run_on_schedule(
+    creator: &SnapshotCreator,
+    schedule: &CronSchedule,
+    config: SnapshotsCreatorConfig,
+) -> anyhow::Result<()> {
+    loop {
+        let now = chrono::Utc::now();
+        let next_run = schedule
+            .next_after(now)
+            .context("Snapshot creation schedule never fires")?;
+        tracing::info!("Next snapshot creation is scheduled at {next_run}");
+        let delay = (next_run - now).to_std().unwrap_or_default();
+
+        tokio::select! {
+            () = tokio::time::sleep(delay) => { /* continue */ }
+            _ = tokio::signal::ctrl_c() => {
+                tracing::info!("Received termination signal; stopping snapshot creator");
+                return Ok(());
+            }
+        }
+
+        tokio::select! {
+            res = creator.run(config.clone(), MIN_CHUNK_COUNT) => {
+                if let Err(err) = res {
+                    tracing::error!("Failed creating snapshot: {err:#}");
+                }
+            }
+            _ = tokio::signal::ctrl_c() => {
+                // The snapshot creator is fault-tolerant, so the interrupted snapshot will be continued on restart.
+                tracing::info!("Received termination signal; stopping snapshot creator");
+                return Ok(());
+            }
+        }
+    }
+}
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     let (stop_sender, stop_receiver) = watch::channel(false);
@@ -68,7 +109,7 @@ async fn main() -> anyhow::Result<()> {
             .context("Invalid Sentry URL")?
             .with_sentry_environment(observability_config.sentry_environment);
     }
-    let _guard = builder.build();
+    let _guard = builder.build()?;
     tracing::info!("Starting snapshots creator");
 
     let object_store_config =
@@ -80,6 +121,12 @@ async fn main() -> anyhow::Result<()> {
     let postgres_config = PostgresConfig::from_env().context("PostgresConfig")?;
     let creator_config =
         SnapshotsCreatorConfig::from_env().context("SnapshotsCreatorConfig::from_env")?;
+    let schedule = creator_config
+        .schedule
+        .as_deref()
+        .map(str::parse::<CronSchedule>)
+        .transpose()
+        .context("Invalid snapshot creation schedule")?;
 
     let replica_pool = ConnectionPool::builder(
         postgres_config.replica_url()?,
@@ -99,7 +146,11 @@ async fn main() -> anyhow::Result<()> {
         #[cfg(test)]
         event_listener: Box::new(()),
     };
-    creator.run(creator_config, MIN_CHUNK_COUNT).await?;
+    if let Some(schedule) = schedule {
+        run_on_schedule(&creator, &schedule, creator_config).await?;
+    } else {
+        creator.run(creator_config, MIN_CHUNK_COUNT).await?;
+    }
 
     tracing::info!("Finished running snapshot creator!");
     stop_sender.send(true).ok();
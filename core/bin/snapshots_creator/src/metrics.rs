@@ -16,6 +16,14 @@ pub(crate) enum FactoryDepsStage {
 pub(crate) enum StorageChunkStage {
     LoadFromPostgres,
     SaveToGcs,
+    VerifyReused,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "stage", rename_all = "snake_case")]
+pub(crate) enum TokensStage {
+    LoadFromPostgres,
+    SaveToGcs,
 }
 
 #[derive(Debug, Metrics)]
@@ -25,6 +33,8 @@ pub(crate) struct SnapshotsCreatorMetrics {
     pub storage_logs_chunks_count: Gauge<u64>,
     /// Number of chunks left to process for the snapshot being currently generated.
     pub storage_logs_chunks_left_to_process: Gauge<usize>,
+    /// Number of chunks reused from the base snapshot in the most recently generated incremental snapshot.
+    pub reused_storage_logs_chunks: Gauge<usize>,
     /// Total latency of snapshot generation.
     #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds)]
     pub snapshot_generation_duration: Histogram<Duration>,
@@ -37,6 +47,9 @@ pub(crate) struct SnapshotsCreatorMetrics {
     /// Latency of factory deps processing split by stage.
     #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds)]
     pub factory_deps_processing_duration: Family<FactoryDepsStage, Histogram<Duration>>,
+    /// Latency of tokens processing split by stage.
+    #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds)]
+    pub tokens_processing_duration: Family<TokensStage, Histogram<Duration>>,
 }
 
 #[vise::register]
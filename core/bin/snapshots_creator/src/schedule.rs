@@ -0,0 +1,244 @@
+//! Cron-like schedule for creating snapshots.
+
+use std::{fmt, str::FromStr};
+
+use anyhow::Context as _;
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime, TimeZone, Timelike, Utc};
+
+/// Number of days to look ahead when searching for the next scheduled time. Large enough to cover schedules
+/// firing only on Feb 29 of a specific weekday.
+const MAX_LOOKAHEAD_DAYS: usize = 366 * 28;
+
+/// Set of allowed values for a single schedule field, encoded as a bit mask.
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct FieldValues(u64);
+
+impl fmt::Debug for FieldValues {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let values = (0..64).filter(|&value| self.contains(value));
+        formatter.debug_set().entries(values).finish()
+    }
+}
+
+impl FieldValues {
+    /// Parses a comma-separated list of values, ranges (`1-5`) and steps (`*/15`, `0-30/10`).
+    fn parse(s: &str, min: u32, max: u32) -> anyhow::Result<Self> {
+        let mut mask = 0_u64;
+        for part in s.split(',') {
+            let (range, step) = match part.split_once('/') {
+                Some((range, step)) => {
+                    let step: u32 = step
+                        .parse()
+                        .with_context(|| format!("invalid step `{step}`"))?;
+                    anyhow::ensure!(step > 0, "step must be positive");
+                    (range, step)
+                }
+                None => (part, 1),
+            };
+
+            let (start, end) = if range == "*" {
+                (min, max)
+            } else if let Some((start, end)) = range.split_once('-') {
+                (Self::parse_value(start)?, Self::parse_value(end)?)
+            } else {
+                let value = Self::parse_value(range)?;
+                // `5/10` is interpreted as `5-max/10`, similar to other cron implementations
+                (value, if part.contains('/') { max } else { value })
+            };
+            anyhow::ensure!(
+                min <= start && start <= end && end <= max,
+                "invalid range `{range}`; values must be in {min}..={max}"
+            );
+            for value in (start..=end).step_by(step as usize) {
+                mask |= 1 << value;
+            }
+        }
+        Ok(Self(mask))
+    }
+
+    fn parse_value(s: &str) -> anyhow::Result<u32> {
+        s.parse().with_context(|| format!("invalid value `{s}`"))
+    }
+
+    fn contains(self, value: u32) -> bool {
+        self.0 & (1 << value) != 0
+    }
+}
+
+/// Cron-like schedule consisting of 5 whitespace-separated fields: minute (0-59), hour (0-23), day of month (1-31),
+/// month (1-12) and day of week (0-7; both 0 and 7 correspond to Sunday). Times are interpreted in UTC.
+///
+/// As in cron, if both the day of month and day of week are restricted (i.e., not `*`), a day matches the schedule
+/// if it matches *either* of these fields.
+#[derive(Debug, Clone)]
+pub(crate) struct CronSchedule {
+    minutes: FieldValues,
+    hours: FieldValues,
+    days_of_month: FieldValues,
+    months: FieldValues,
+    days_of_week: FieldValues,
+    is_day_of_month_restricted: bool,
+    is_day_of_week_restricted: bool,
+}
+
+impl FromStr for CronSchedule {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fields: Vec<_> = s.split_whitespace().collect();
+        let [minutes, hours, days_of_month, months, days_of_week] = fields.as_slice() else {
+            anyhow::bail!("schedule must consist of 5 fields, got {}", fields.len());
+        };
+
+        let mut parsed_days_of_week =
+            FieldValues::parse(days_of_week, 0, 7).context("day of week")?;
+        if parsed_days_of_week.contains(7) {
+            // Normalize Sunday to 0
+            parsed_days_of_week.0 = (parsed_days_of_week.0 & !(1 << 7)) | 1;
+        }
+        Ok(Self {
+            minutes: FieldValues::parse(minutes, 0, 59).context("minute")?,
+            hours: FieldValues::parse(hours, 0, 23).context("hour")?,
+            days_of_month: FieldValues::parse(days_of_month, 1, 31).context("day of month")?,
+            months: FieldValues::parse(months, 1, 12).context("month")?,
+            days_of_week: parsed_days_of_week,
+            is_day_of_month_restricted: !days_of_month.starts_with('*'),
+            is_day_of_week_restricted: !days_of_week.starts_with('*'),
+        })
+    }
+}
+
+impl CronSchedule {
+    fn matches_date(&self, date: NaiveDate) -> bool {
+        if !self.months.contains(date.month()) {
+            return false;
+        }
+        let matches_day_of_month = self.days_of_month.contains(date.day());
+        let matches_day_of_week = self
+            .days_of_week
+            .contains(date.weekday().num_days_from_sunday());
+        if self.is_day_of_month_restricted && self.is_day_of_week_restricted {
+            matches_day_of_month || matches_day_of_week
+        } else {
+            matches_day_of_month && matches_day_of_week
+        }
+    }
+
+    /// Returns the earliest matching time of day that is not earlier than `start`.
+    fn first_time_since(&self, start: NaiveTime) -> Option<NaiveTime> {
+        for hour in start.hour()..24 {
+            if !self.hours.contains(hour) {
+                continue;
+            }
+            let start_minute = if hour == start.hour() {
+                start.minute()
+            } else {
+                0
+            };
+            if let Some(minute) = (start_minute..60).find(|&minute| self.minutes.contains(minute)) {
+                return NaiveTime::from_hms_opt(hour, minute, 0);
+            }
+        }
+        None
+    }
+
+    /// Returns the earliest scheduled time strictly after `time`, or `None` if the schedule never fires
+    /// (e.g., `0 0 31 2 *`).
+    pub fn next_after(&self, time: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let midnight = NaiveTime::from_hms_opt(0, 0, 0)?;
+        let start = time.with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+        let mut date = start.date_naive();
+        let mut start_time = start.time();
+        for _ in 0..MAX_LOOKAHEAD_DAYS {
+            if self.matches_date(date) {
+                if let Some(time) = self.first_time_since(start_time) {
+                    return Some(Utc.from_utc_datetime(&date.and_time(time)));
+                }
+            }
+            date = date.succ_opt()?;
+            start_time = midnight;
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn parsing_schedule() {
+        let schedule: CronSchedule = "*/15 0-6/2,12 * * 1-5".parse().unwrap();
+        assert_eq!(
+            schedule.minutes,
+            FieldValues(1 | 1 << 15 | 1 << 30 | 1 << 45)
+        );
+        assert_eq!(
+            schedule.hours,
+            FieldValues(1 | 1 << 2 | 1 << 4 | 1 << 6 | 1 << 12)
+        );
+        assert!(!schedule.is_day_of_month_restricted);
+        assert!(schedule.is_day_of_week_restricted);
+        assert_eq!(schedule.days_of_week, FieldValues(0b11_1110));
+
+        let schedule: CronSchedule = "30 5/6 * * 0,7".parse().unwrap();
+        assert_eq!(schedule.minutes, FieldValues(1 << 30));
+        assert_eq!(
+            schedule.hours,
+            FieldValues(1 << 5 | 1 << 11 | 1 << 17 | 1 << 23)
+        );
+        assert_eq!(schedule.days_of_week, FieldValues(1));
+    }
+
+    #[test]
+    fn parsing_invalid_schedule() {
+        for (schedule, expected_err) in [
+            ("* * * *", "5 fields"),
+            ("60 * * * *", "minute"),
+            ("* * 0 * *", "day of month"),
+            ("* * * 1-13 *", "month"),
+            ("*/0 * * * *", "step must be positive"),
+            ("* 5-3 * * *", "invalid range"),
+            ("* * * * mon", "invalid value"),
+        ] {
+            let err = schedule.parse::<CronSchedule>().unwrap_err();
+            let err = format!("{err:#}");
+            assert!(err.contains(expected_err), "{schedule}: {err}");
+        }
+    }
+
+    #[test]
+    fn computing_next_scheduled_time() {
+        let schedule: CronSchedule = "0 */6 * * *".parse().unwrap();
+        let next = schedule.next_after(utc("2024-02-26T10:15:30Z")).unwrap();
+        assert_eq!(next, utc("2024-02-26T12:00:00Z"));
+        // The next time must be strictly after the provided one.
+        let next = schedule.next_after(next).unwrap();
+        assert_eq!(next, utc("2024-02-26T18:00:00Z"));
+        let next = schedule.next_after(next).unwrap();
+        assert_eq!(next, utc("2024-02-27T00:00:00Z"));
+
+        // Every Monday at 03:30. 2024-02-26 is a Monday.
+        let schedule: CronSchedule = "30 3 * * 1".parse().unwrap();
+        let next = schedule.next_after(utc("2024-02-26T03:30:00Z")).unwrap();
+        assert_eq!(next, utc("2024-03-04T03:30:00Z"));
+
+        // Either the 1st day of month or Sunday.
+        let schedule: CronSchedule = "0 0 1 * 0".parse().unwrap();
+        let next = schedule.next_after(utc("2024-02-26T00:00:00Z")).unwrap();
+        assert_eq!(next, utc("2024-03-01T00:00:00Z"));
+        let next = schedule.next_after(next).unwrap();
+        assert_eq!(next, utc("2024-03-03T00:00:00Z"));
+
+        let schedule: CronSchedule = "0 0 29 2 *".parse().unwrap();
+        let next = schedule.next_after(utc("2024-03-01T00:00:00Z")).unwrap();
+        assert_eq!(next, utc("2028-02-29T00:00:00Z"));
+
+        let schedule: CronSchedule = "0 0 31 2 *".parse().unwrap();
+        assert_eq!(schedule.next_after(utc("2024-03-01T00:00:00Z")), None);
+    }
+}
@@ -15,8 +15,9 @@ use zksync_object_store::ObjectStore;
 use zksync_types::{
     block::{L1BatchHeader, MiniblockHeader},
     snapshots::{
-        SnapshotFactoryDependencies, SnapshotFactoryDependency, SnapshotStorageLog,
-        SnapshotStorageLogsChunk, SnapshotStorageLogsStorageKey,
+        snapshot_file_checksum, SnapshotFactoryDependencies, SnapshotFactoryDependency,
+        SnapshotMetadata, SnapshotStorageLog, SnapshotStorageLogsChunk,
+        SnapshotStorageLogsStorageKey, SnapshotTokens, SnapshotVersion,
     },
     AccountTreeId, Address, L1BatchNumber, MiniblockNumber, ProtocolVersion, StorageKey,
     StorageLog, H256,
@@ -27,10 +28,18 @@ use super::*;
 const TEST_CONFIG: SnapshotsCreatorConfig = SnapshotsCreatorConfig {
     storage_logs_chunk_size: 1_000_000,
     concurrent_queries_count: 10,
+    schedule: None,
+    incremental: false,
+    max_incremental_chain_length: None,
+    verify_reused_chunks: None,
 };
 const SEQUENTIAL_TEST_CONFIG: SnapshotsCreatorConfig = SnapshotsCreatorConfig {
-    storage_logs_chunk_size: 1_000_000,
     concurrent_queries_count: 1,
+    ..TEST_CONFIG
+};
+const INCREMENTAL_TEST_CONFIG: SnapshotsCreatorConfig = SnapshotsCreatorConfig {
+    incremental: true,
+    ..TEST_CONFIG
 };
 
 #[derive(Debug)]
@@ -286,6 +295,56 @@ async fn persisting_snapshot_metadata() {
             .unwrap();
         assert!(path.ends_with(".proto.gzip"));
     }
+    assert_eq!(snapshot_metadata.version, SnapshotVersion::LATEST);
+    let tokens_path = snapshot_metadata.tokens_filepath.as_ref().unwrap();
+    assert!(tokens_path.ends_with("_tokens.proto.gzip"));
+}
+
+#[tokio::test]
+async fn persisting_snapshot_checksums() {
+    let pool = ConnectionPool::test_pool().await;
+    let mut rng = thread_rng();
+    let object_store_factory = ObjectStoreFactory::mock();
+    let object_store = object_store_factory.create_store().await;
+    let mut conn = pool.access_storage().await.unwrap();
+    prepare_postgres(&mut rng, &mut conn, 10).await;
+
+    SnapshotCreator::for_tests(object_store, pool.clone())
+        .run(TEST_CONFIG, MIN_CHUNK_COUNT)
+        .await
+        .unwrap();
+    let snapshot_l1_batch_number = L1BatchNumber(8);
+    let snapshot_metadata = conn
+        .snapshots_dal()
+        .get_snapshot_metadata(snapshot_l1_batch_number)
+        .await
+        .unwrap()
+        .expect("No snapshot metadata");
+
+    let object_store = object_store_factory.create_store().await;
+    let factory_deps: SnapshotFactoryDependencies =
+        object_store.get(snapshot_l1_batch_number).await.unwrap();
+    assert_eq!(
+        snapshot_metadata.factory_deps_checksum,
+        Some(snapshot_file_checksum(&factory_deps))
+    );
+    let tokens: SnapshotTokens = object_store.get(snapshot_l1_batch_number).await.unwrap();
+    assert_eq!(
+        snapshot_metadata.tokens_checksum,
+        Some(snapshot_file_checksum(&tokens))
+    );
+    assert_eq!(
+        snapshot_metadata.storage_logs_checksums.len(),
+        MIN_CHUNK_COUNT as usize
+    );
+    for (chunk_id, checksum) in snapshot_metadata.storage_logs_checksums.iter().enumerate() {
+        let key = SnapshotStorageLogsStorageKey {
+            l1_batch_number: snapshot_l1_batch_number,
+            chunk_id: chunk_id as u64,
+        };
+        let chunk: SnapshotStorageLogsChunk = object_store.get(key).await.unwrap();
+        assert_eq!(*checksum, Some(snapshot_file_checksum(&chunk)));
+    }
 }
 
 #[tokio::test]
@@ -456,3 +515,167 @@ async fn recovery_workflow_with_varying_chunk_size() {
     let object_store = object_store_factory.create_store().await;
     assert_storage_logs(&*object_store, snapshot_l1_batch_number, &expected_outputs).await;
 }
+
+async fn get_all_storage_logs(
+    object_store: &dyn ObjectStore,
+    snapshot: &SnapshotMetadata,
+) -> HashSet<SnapshotStorageLog> {
+    let mut logs = HashSet::new();
+    for chunk_id in 0..snapshot.storage_logs_filepaths.len() as u64 {
+        let key = SnapshotStorageLogsStorageKey {
+            l1_batch_number: snapshot.storage_logs_chunk_source(chunk_id),
+            chunk_id,
+        };
+        let chunk: SnapshotStorageLogsChunk = object_store.get(key).await.unwrap();
+        logs.extend(chunk.storage_logs);
+    }
+    logs
+}
+
+async fn get_snapshot(
+    conn: &mut StorageProcessor<'_>,
+    l1_batch_number: L1BatchNumber,
+) -> SnapshotMetadata {
+    conn.snapshots_dal()
+        .get_snapshot_metadata(l1_batch_number)
+        .await
+        .unwrap()
+        .expect("No snapshot metadata")
+}
+
+#[tokio::test]
+async fn creating_incremental_snapshot() {
+    let pool = ConnectionPool::test_pool().await;
+    let mut rng = thread_rng();
+    let object_store_factory = ObjectStoreFactory::mock();
+    let object_store = object_store_factory.create_store().await;
+    let mut conn = pool.access_storage().await.unwrap();
+    prepare_postgres(&mut rng, &mut conn, 10).await;
+    // Add an empty L1 batch so that the base snapshot covers all miniblocks created by `prepare_postgres()`.
+    create_miniblock(&mut conn, MiniblockNumber(10), vec![]).await;
+    create_l1_batch(&mut conn, L1BatchNumber(10), &[]).await;
+
+    // No base snapshot is present, so a full snapshot must be created.
+    SnapshotCreator::for_tests(object_store, pool.clone())
+        .run(INCREMENTAL_TEST_CONFIG, MIN_CHUNK_COUNT)
+        .await
+        .unwrap();
+    let base_snapshot = get_snapshot(&mut conn, L1BatchNumber(9)).await;
+    assert_eq!(base_snapshot.base_l1_batch_number, None);
+    assert!(base_snapshot.storage_logs_source_l1_batches.is_empty());
+
+    let new_logs = gen_storage_logs(&mut rng, 2);
+    create_miniblock(&mut conn, MiniblockNumber(11), new_logs.clone()).await;
+    create_l1_batch(&mut conn, L1BatchNumber(11), &new_logs).await;
+    create_miniblock(&mut conn, MiniblockNumber(12), vec![]).await;
+    create_l1_batch(&mut conn, L1BatchNumber(12), &[]).await;
+
+    let object_store = object_store_factory.create_store().await;
+    SnapshotCreator::for_tests(object_store, pool.clone())
+        .run(INCREMENTAL_TEST_CONFIG, MIN_CHUNK_COUNT)
+        .await
+        .unwrap();
+    let snapshot = get_snapshot(&mut conn, L1BatchNumber(11)).await;
+    assert!(snapshot.is_complete());
+    assert_eq!(snapshot.version, SnapshotVersion::INCREMENTAL);
+    assert_eq!(snapshot.base_l1_batch_number, Some(L1BatchNumber(9)));
+    assert_eq!(
+        snapshot.storage_logs_filepaths.len(),
+        MIN_CHUNK_COUNT as usize
+    );
+
+    let chunk_sources = &snapshot.storage_logs_source_l1_batches;
+    let reused_chunk_count = chunk_sources
+        .iter()
+        .filter(|&&source| source == L1BatchNumber(9))
+        .count();
+    // New logs can modify at most 2 chunks.
+    assert!(
+        reused_chunk_count >= MIN_CHUNK_COUNT as usize - 2,
+        "{chunk_sources:?}"
+    );
+    for (chunk_id, &source) in chunk_sources.iter().enumerate() {
+        if source == L1BatchNumber(9) {
+            assert_eq!(
+                snapshot.storage_logs_filepaths[chunk_id],
+                base_snapshot.storage_logs_filepaths[chunk_id]
+            );
+            assert_eq!(
+                snapshot.storage_logs_checksums[chunk_id],
+                base_snapshot.storage_logs_checksums[chunk_id]
+            );
+        } else {
+            assert_eq!(source, L1BatchNumber(11));
+        }
+    }
+
+    let object_store = object_store_factory.create_store().await;
+    let mut expected_logs = get_all_storage_logs(&*object_store, &base_snapshot).await;
+    let hashed_keys: Vec<_> = new_logs.iter().map(|log| log.key.hashed_key()).collect();
+    let l1_batches_and_indices = conn
+        .storage_logs_dal()
+        .get_l1_batches_and_indices_for_initial_writes(&hashed_keys)
+        .await
+        .unwrap();
+    expected_logs.extend(new_logs.iter().map(|log| {
+        let (l1_batch_number_of_initial_write, enumeration_index) =
+            l1_batches_and_indices[&log.key.hashed_key()];
+        SnapshotStorageLog {
+            key: log.key,
+            value: log.value,
+            l1_batch_number_of_initial_write,
+            enumeration_index,
+        }
+    }));
+    let actual_logs = get_all_storage_logs(&*object_store, &snapshot).await;
+    assert_eq!(actual_logs, expected_logs);
+}
+
+#[tokio::test]
+async fn corrupted_reused_chunk_is_produced_again() {
+    let pool = ConnectionPool::test_pool().await;
+    let mut rng = thread_rng();
+    let object_store_factory = ObjectStoreFactory::mock();
+    let object_store = object_store_factory.create_store().await;
+    let mut conn = pool.access_storage().await.unwrap();
+    prepare_postgres(&mut rng, &mut conn, 10).await;
+    create_miniblock(&mut conn, MiniblockNumber(10), vec![]).await;
+    create_l1_batch(&mut conn, L1BatchNumber(10), &[]).await;
+
+    SnapshotCreator::for_tests(object_store, pool.clone())
+        .run(TEST_CONFIG, MIN_CHUNK_COUNT)
+        .await
+        .unwrap();
+    let base_snapshot = get_snapshot(&mut conn, L1BatchNumber(9)).await;
+
+    let object_store = object_store_factory.create_store().await;
+    let corrupted_key = SnapshotStorageLogsStorageKey {
+        l1_batch_number: L1BatchNumber(9),
+        chunk_id: 0,
+    };
+    let mut chunk: SnapshotStorageLogsChunk = object_store.get(corrupted_key).await.unwrap();
+    let expected_logs = get_all_storage_logs(&*object_store, &base_snapshot).await;
+    chunk.storage_logs.pop().unwrap();
+    object_store.put(corrupted_key, &chunk).await.unwrap();
+
+    // No storage logs are modified by the new L1 batch, so all chunks except for the corrupted one are reused.
+    create_miniblock(&mut conn, MiniblockNumber(11), vec![]).await;
+    create_l1_batch(&mut conn, L1BatchNumber(11), &[]).await;
+    SnapshotCreator::for_tests(object_store, pool.clone())
+        .run(INCREMENTAL_TEST_CONFIG, MIN_CHUNK_COUNT)
+        .await
+        .unwrap();
+
+    let snapshot = get_snapshot(&mut conn, L1BatchNumber(10)).await;
+    assert!(snapshot.is_complete());
+    assert_eq!(snapshot.storage_logs_chunk_source(0), L1BatchNumber(10));
+    for chunk_id in 1..MIN_CHUNK_COUNT {
+        assert_eq!(
+            snapshot.storage_logs_chunk_source(chunk_id),
+            L1BatchNumber(9)
+        );
+    }
+    let object_store = object_store_factory.create_store().await;
+    let actual_logs = get_all_storage_logs(&*object_store, &snapshot).await;
+    assert_eq!(actual_logs, expected_logs);
+}
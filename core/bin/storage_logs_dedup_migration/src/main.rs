@@ -1,7 +1,9 @@
 use std::collections::hash_map::{Entry, HashMap};
 
 use clap::Parser;
-use zksync_config::PostgresConfig;
+use prometheus_exporter::PrometheusExporterConfig;
+use tokio::sync::watch;
+use zksync_config::{configs::PrometheusConfig, PostgresConfig};
 use zksync_dal::ConnectionPool;
 use zksync_env_config::FromEnv;
 use zksync_types::{MiniblockNumber, H256};
@@ -47,6 +49,16 @@ impl StateCache {
 async fn main() {
     let config = PostgresConfig::from_env().unwrap();
     let opt = Cli::parse();
+    // Metrics are pushed to the Prometheus push gateway (if it is configured), since the migration
+    // may finish before it is scraped.
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let prometheus_exporter_task = PrometheusConfig::from_env().ok().map(|prometheus_config| {
+        let exporter_config = PrometheusExporterConfig::push(
+            prometheus_config.gateway_endpoint_for_job("storage_logs_dedup_migration"),
+            prometheus_config.push_interval(),
+        );
+        tokio::spawn(exporter_config.run(stop_receiver))
+    });
     let pool = ConnectionPool::singleton(config.master_url().unwrap())
         .build()
         .await
@@ -169,4 +181,11 @@ async fn main() {
     }
 
     println!("Finished");
+    stop_sender.send(true).ok();
+    if let Some(prometheus_exporter_task) = prometheus_exporter_task {
+        prometheus_exporter_task
+            .await
+            .unwrap()
+            .expect("Prometheus did not finish gracefully");
+    }
 }
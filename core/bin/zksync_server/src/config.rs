@@ -1,9 +1,31 @@
 use anyhow::Context as _;
+use zksync_config::{
+    configs::{
+        chain::{
+            BaseTokenConfig, CircuitBreakerConfig, FeeInputConfig, MempoolConfig, NetworkConfig,
+            OperationsManagerConfig, StateKeeperConfig,
+        },
+        fri_prover_group::FriProverGroupConfig,
+        house_keeper::HouseKeeperConfig,
+        FriProofCompressorConfig, FriProverConfig, FriWitnessGeneratorConfig, ObservabilityConfig,
+        ProofDataHandlerConfig, ShutdownConfig, WitnessGeneratorConfig,
+    },
+    ApiConfig, ContractsConfig, DBConfig, ETHClientConfig, ETHSenderConfig, ETHWatchConfig,
+    ObjectStoreConfig, PostgresConfig,
+};
 use zksync_consensus_roles::{node, validator};
 use zksync_core::consensus;
+use zksync_env_config::{deprecation::Deprecation, ConfigDump, ConfigSource};
+
+/// Env variables that are no longer used by the server.
+pub(crate) const IGNORED_VARS: &[Deprecation] = &[
+    Deprecation::ignored("API_WEB3_JSON_RPC_THREADS_PER_SERVER", "2024-06-30"),
+    Deprecation::ignored("API_CONTRACT_VERIFICATION_THREADS_PER_SERVER", "2024-06-30"),
+];
 
 pub(crate) fn read_consensus_config() -> anyhow::Result<consensus::MainNodeConfig> {
-    let path = std::env::var("CONSENSUS_CONFIG_PATH").context("CONSENSUS_CONFIG_PATH")?;
+    let path =
+        zksync_env_config::env_var("CONSENSUS_CONFIG_PATH").context("CONSENSUS_CONFIG_PATH")?;
     let cfg = std::fs::read_to_string(&path).context(path)?;
     let cfg: consensus::config::Config =
         consensus::config::decode_json(&cfg).context("failed decoding JSON")?;
@@ -15,3 +37,44 @@ pub(crate) fn read_consensus_config() -> anyhow::Result<consensus::MainNodeConfi
         validator: cfg.validator_config(validator_key),
     })
 }
+
+/// Loads all configs that may be used by the server and returns them as a JSON object
+/// with secrets redacted and provenance of each value.
+pub(crate) fn dump_configs(
+    file: Option<&ConfigSource>,
+    resolved_secrets: Vec<String>,
+) -> anyhow::Result<serde_json::Value> {
+    let mut dump = ConfigDump::new(file).redact_vars(resolved_secrets);
+    dump.load::<ObservabilityConfig>("observability", "MISC_")?;
+    dump.load_with_aliases::<PostgresConfig>(
+        "postgres",
+        "DATABASE_",
+        &[
+            ("master_url", "DATABASE_URL"),
+            ("max_connections", "DATABASE_POOL_SIZE"),
+        ],
+    )?;
+    dump.load::<DBConfig>("database", "DATABASE_")?;
+    dump.load::<ApiConfig>("api", "API_")?;
+    dump.load::<NetworkConfig>("network", "CHAIN_ETH_")?;
+    dump.load::<StateKeeperConfig>("state_keeper", "CHAIN_STATE_KEEPER_")?;
+    dump.load::<OperationsManagerConfig>("operations_manager", "CHAIN_OPERATIONS_MANAGER_")?;
+    dump.load::<CircuitBreakerConfig>("circuit_breaker", "CHAIN_CIRCUIT_BREAKER_")?;
+    dump.load::<MempoolConfig>("mempool", "CHAIN_MEMPOOL_")?;
+    dump.load::<BaseTokenConfig>("base_token", "CHAIN_BASE_TOKEN_")?;
+    dump.load::<FeeInputConfig>("fee_input", "CHAIN_FEE_INPUT_")?;
+    dump.load::<HouseKeeperConfig>("house_keeper", "HOUSE_KEEPER_")?;
+    dump.load::<FriProofCompressorConfig>("fri_proof_compressor", "FRI_PROOF_COMPRESSOR_")?;
+    dump.load::<FriProverConfig>("fri_prover", "FRI_PROVER_")?;
+    dump.load::<FriProverGroupConfig>("fri_prover_group", "FRI_PROVER_GROUP_")?;
+    dump.load::<FriWitnessGeneratorConfig>("fri_witness", "FRI_WITNESS_")?;
+    dump.load::<ProofDataHandlerConfig>("proof_data_handler", "PROOF_DATA_HANDLER_")?;
+    dump.load::<WitnessGeneratorConfig>("witness", "WITNESS_")?;
+    dump.load::<ContractsConfig>("contracts", "CONTRACTS_")?;
+    dump.load::<ETHClientConfig>("eth_client", "ETH_CLIENT_")?;
+    dump.load::<ETHSenderConfig>("eth_sender", "ETH_SENDER_")?;
+    dump.load::<ETHWatchConfig>("eth_watch", "ETH_WATCH_")?;
+    dump.load::<ObjectStoreConfig>("object_store", "OBJECT_STORE_")?;
+    dump.load::<ShutdownConfig>("shutdown", "SHUTDOWN_")?;
+    Ok(dump.into_json())
+}
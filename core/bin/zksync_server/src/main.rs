@@ -1,28 +1,30 @@
-use std::{str::FromStr, time::Duration};
+use std::{
+    path::PathBuf,
+    str::FromStr,
+    time::{Duration, Instant},
+};
 
 use anyhow::Context as _;
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use zksync_config::{
-    configs::{
-        api::{HealthCheckConfig, MerkleTreeApiConfig, Web3JsonRpcConfig},
-        chain::{
-            CircuitBreakerConfig, MempoolConfig, NetworkConfig, OperationsManagerConfig,
-            StateKeeperConfig,
-        },
-        fri_prover_group::FriProverGroupConfig,
-        house_keeper::HouseKeeperConfig,
-        FriProofCompressorConfig, FriProverConfig, FriWitnessGeneratorConfig, ObservabilityConfig,
-        PrometheusConfig, ProofDataHandlerConfig, WitnessGeneratorConfig,
-    },
-    ApiConfig, ContractsConfig, DBConfig, ETHClientConfig, ETHSenderConfig, ETHWatchConfig,
-    GasAdjusterConfig, ObjectStoreConfig, PostgresConfig,
+    configs::{chain::NetworkConfig, ObservabilityConfig, ShutdownConfig},
+    ContractsConfig, ETHClientConfig, ETHSenderConfig,
 };
 use zksync_core::{
-    genesis_init, initialize_components, is_genesis_needed, setup_sigint_handler,
-    temp_config_store::TempConfigStore, Component, Components,
+    genesis::GenesisSpec,
+    genesis_init, initialize_components, is_genesis_needed,
+    pubdata_export::{export_pubdata, PubdataExport, PubdataVerifier},
+    reload_logs_on_remote_update, reload_logs_on_sighup, setup_sigint_handler,
+    startup::{self, MigrationsAction, StartupPhase},
+    temp_config_store::TempConfigStore,
+    Component, Components,
 };
-use zksync_env_config::FromEnv;
-use zksync_storage::RocksDB;
+use zksync_dal::ConnectionPool;
+use zksync_env_config::{
+    deprecation, remote::RemoteConfigSource, secrets::SecretsResolver, ConfigCollector,
+    ConfigSource, FromEnv,
+};
+use zksync_types::{pubdata_da::PubdataDA, L1BatchNumber};
 use zksync_utils::wait_for_tasks::wait_for_tasks;
 
 mod config;
@@ -38,7 +40,7 @@ struct Cli {
     #[arg(long)]
     genesis: bool,
     /// Wait for the `setChainId` event during genesis.
-    /// If `--genesis` is not set, this flag is ignored.
+    /// If neither `--genesis` nor the `genesis` subcommand is used, this flag is ignored.
     #[arg(long)]
     set_chain_id: bool,
     /// Rebuild tree.
@@ -50,6 +52,65 @@ struct Cli {
         default_value = "api,tree,eth,state_keeper,housekeeper,basic_witness_input_producer,commitment_generator"
     )]
     components: ComponentsToRun,
+    /// Path to a YAML or TOML file with configuration. Env variables take precedence over values from the file.
+    #[arg(long)]
+    config_path: Option<PathBuf>,
+    /// Fail if deprecated config options are used instead of logging warnings.
+    #[arg(long)]
+    strict_config: bool,
+    /// Inspect or change the Postgres schema and exit: `plan` prints pending migrations with an estimate of locks
+    /// they take on existing tables, `apply` applies pending migrations, and `revert-to <version>` reverts
+    /// migrations applied after the specified version. Migrations are never applied implicitly on startup.
+    #[arg(long, num_args = 1..=2, value_names = ["ACTION", "VERSION"])]
+    migrations: Option<Vec<String>>,
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Prints the effective configuration as JSON with secrets redacted, together with the source
+    /// of each value (default, env variable or config file), and exits.
+    #[command(name = "print-config")]
+    PrintConfig,
+    /// Prints JSON Schema for all component configs, including env variables for each config field, and exits.
+    #[command(name = "print-config-schema")]
+    PrintConfigSchema,
+    /// Generates the genesis block and exits. Equivalent to `--genesis`, but allows to specify the genesis state
+    /// for custom chains.
+    #[command(name = "genesis")]
+    Genesis {
+        /// Path to a YAML or TOML file specifying the genesis state (chain ID, system contracts, predeployed contracts,
+        /// initial balances and fee params). If not specified, the default genesis state is used.
+        #[arg(long)]
+        spec: Option<PathBuf>,
+    },
+    /// Exports data posted to L1 when committing the specified L1 batches (commit calldata, pubdata and blobs,
+    /// compressed state diffs and meta parameters) as JSON, and exits.
+    #[command(name = "export-pubdata")]
+    ExportPubdata {
+        /// First L1 batch to export.
+        #[arg(long)]
+        from_batch: u32,
+        /// Last L1 batch to export (inclusive).
+        #[arg(long)]
+        to_batch: u32,
+        /// Path to write the export to. If not specified, the export is printed to stdout.
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
+    /// Verifies an export produced by `export-pubdata` without accessing Postgres, and exits. Pubdata commitments
+    /// and blobs are re-derived from the exported pubdata, and commitment data is compared with commit transactions
+    /// on L1. Fails if any L1 batch doesn't pass verification.
+    #[command(name = "verify-pubdata")]
+    VerifyPubdata {
+        /// Path to the export.
+        #[arg(long)]
+        input: PathBuf,
+        /// Only re-derive commitment data from the pubdata without comparing it with L1.
+        #[arg(long)]
+        offline: bool,
+    },
 }
 
 #[derive(Debug, Clone)]
@@ -71,6 +132,48 @@ impl FromStr for ComponentsToRun {
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     let opt = Cli::parse();
+    let migrations_action = opt
+        .migrations
+        .as_deref()
+        .map(MigrationsAction::from_args)
+        .transpose()?;
+    if let Some(Command::PrintConfigSchema) = opt.command {
+        let schema = zksync_config::schema::export();
+        println!("{}", serde_json::to_string_pretty(&schema)?);
+        return Ok(());
+    }
+
+    startup::mark_started();
+    let config_loading_started_at = Instant::now();
+    // Must be performed before any configs are loaded.
+    let config_source = opt
+        .config_path
+        .as_ref()
+        .map(ConfigSource::from_file)
+        .transpose()
+        .context("ConfigSource::from_file()")?;
+    let exported_vars_count = config_source.as_ref().map(ConfigSource::export_to_env);
+    // The remote config has lower priority than the local config file, so it must be exported after the file.
+    let remote_config_source =
+        RemoteConfigSource::from_env().context("RemoteConfigSource::from_env()")?;
+    let remote_config = match &remote_config_source {
+        Some(source) => Some(source.fetch().await?),
+        None => None,
+    };
+    let remote_vars_count = remote_config.as_ref().map(ConfigSource::export_to_env);
+    // Secret references may be defined both in env vars and in the config file, so this must be performed
+    // after the file is loaded.
+    let secrets_resolver = SecretsResolver::from_env().context("SecretsResolver::from_env()")?;
+    let resolved_secrets = secrets_resolver
+        .resolve_env()
+        .await
+        .context("failed resolving secrets")?;
+
+    if let Some(Command::PrintConfig) = opt.command {
+        let dump = config::dump_configs(config_source.as_ref(), resolved_secrets)?;
+        println!("{}", serde_json::to_string_pretty(&dump)?);
+        return Ok(());
+    }
 
     let observability_config =
         ObservabilityConfig::from_env().context("ObservabilityConfig::from_env()")?;
@@ -80,13 +183,75 @@ async fn main() -> anyhow::Result<()> {
         .context("Invalid log format")?;
 
     let mut builder = vlog::ObservabilityBuilder::new().with_log_format(log_format);
+    if let Some(log_directives) = &observability_config.log_directives {
+        builder = builder
+            .with_log_directives(log_directives)
+            .context("Invalid log directives")?;
+    }
+    if let Some(log_file_path) = &observability_config.log_file_path {
+        let rotation = observability_config
+            .log_file_rotation
+            .as_deref()
+            .unwrap_or("never")
+            .parse()
+            .context("Invalid log file rotation")?;
+        let mut options = vlog::LogFileOptions::new(log_file_path.into()).with_rotation(rotation);
+        if let Some(max_size) = observability_config.log_file_max_size_bytes {
+            options = options.with_max_size(max_size);
+        }
+        if let Some(max_files) = observability_config.log_file_max_files {
+            options = options.with_max_files(max_files);
+        }
+        builder = builder.with_log_file(options);
+    }
+    if let Some(interval) = observability_config.log_throttle_interval_sec {
+        let burst = observability_config.log_throttle_burst.unwrap_or(10);
+        builder = builder.with_log_throttling(Duration::from_secs(interval), burst);
+    }
     if let Some(sentry_url) = &observability_config.sentry_url {
+        let error_filter = observability_config
+            .sentry_error_filter
+            .as_deref()
+            .unwrap_or_default()
+            .parse()
+            .context("Invalid Sentry error filter")?;
+        let release =
+            option_env!("GIT_HASH").map(|hash| format!("{}@{hash}", env!("CARGO_PKG_NAME")));
         builder = builder
             .with_sentry_url(sentry_url)
             .expect("Invalid Sentry URL")
-            .with_sentry_environment(observability_config.sentry_environment);
+            .with_sentry_environment(observability_config.sentry_environment)
+            .with_sentry_release(release)
+            .with_sentry_sample_rate(observability_config.sentry_sample_rate)
+            .with_sentry_traces_sample_rate(observability_config.sentry_traces_sample_rate)
+            .with_sentry_error_filter(error_filter);
+    }
+    if let Some(otlp_url) = &observability_config.otlp_url {
+        let options =
+            vlog::OpenTelemetryOptions::new(otlp_url.clone(), env!("CARGO_PKG_NAME").to_owned())
+                .with_sampling_ratio(observability_config.otlp_sampling_ratio.unwrap_or(1.0))
+                .with_resource_attributes(
+                    observability_config
+                        .otlp_resource_attributes
+                        .as_deref()
+                        .unwrap_or_default(),
+                )
+                .context("Invalid OTLP resource attributes")?;
+        builder = builder.with_opentelemetry(options);
+    }
+    let observability_guard = builder.build()?;
+    if let Some(config_path) = opt.config_path.clone() {
+        let log_reload_handle = observability_guard.log_reload_handle();
+        tokio::spawn(reload_logs_on_sighup(config_path, log_reload_handle));
+    }
+    if let (Some(source), Some(config)) = (remote_config_source, remote_config) {
+        let log_reload_handle = observability_guard.log_reload_handle();
+        tokio::spawn(reload_logs_on_remote_update(
+            source,
+            config,
+            log_reload_handle,
+        ));
     }
-    let _guard = builder.build();
 
     // Report whether sentry is running after the logging subsystem was initialized.
     if let Some(sentry_url) = observability_config.sentry_url {
@@ -94,52 +259,162 @@ async fn main() -> anyhow::Result<()> {
     } else {
         tracing::info!("No sentry URL was provided");
     }
+    if let (Some(config_path), Some(count)) = (&opt.config_path, exported_vars_count) {
+        tracing::info!(
+            "Loaded {count} config values from {config_path:?} not overridden by env variables"
+        );
+    }
+    if let Some(count) = remote_vars_count {
+        tracing::info!(
+            "Loaded {count} config values from the remote config not overridden by env variables or config file"
+        );
+    }
+    if !resolved_secrets.is_empty() {
+        tracing::info!("Resolved secrets for env variables: {resolved_secrets:?}");
+    }
+    deprecation::report_ignored_vars(config::IGNORED_VARS);
+    deprecation::finish(opt.strict_config)?;
 
     // TODO (QIT-22): Only deserialize configs on demand.
     // Right now, we are trying to deserialize all the configs that may be needed by `zksync_core`.
     // "May" is the key word here, since some configs are only used by certain component configuration,
     // hence we are using `Option`s.
+    let mut collector = ConfigCollector::default();
     let mut configs: TempConfigStore = TempConfigStore {
-        postgres_config: PostgresConfig::from_env().ok(),
-        health_check_config: HealthCheckConfig::from_env().ok(),
-        merkle_tree_api_config: MerkleTreeApiConfig::from_env().ok(),
-        web3_json_rpc_config: Web3JsonRpcConfig::from_env().ok(),
-        circuit_breaker_config: CircuitBreakerConfig::from_env().ok(),
-        mempool_config: MempoolConfig::from_env().ok(),
-        network_config: NetworkConfig::from_env().ok(),
-        operations_manager_config: OperationsManagerConfig::from_env().ok(),
-        state_keeper_config: StateKeeperConfig::from_env().ok(),
-        house_keeper_config: HouseKeeperConfig::from_env().ok(),
-        fri_proof_compressor_config: FriProofCompressorConfig::from_env().ok(),
-        fri_prover_config: Some(FriProverConfig::from_env().context("fri_prover_config")?),
-        fri_prover_group_config: FriProverGroupConfig::from_env().ok(),
-        fri_witness_generator_config: FriWitnessGeneratorConfig::from_env().ok(),
-        prometheus_config: PrometheusConfig::from_env().ok(),
-        proof_data_handler_config: ProofDataHandlerConfig::from_env().ok(),
-        witness_generator_config: WitnessGeneratorConfig::from_env().ok(),
-        api_config: ApiConfig::from_env().ok(),
-        contracts_config: ContractsConfig::from_env().ok(),
-        db_config: DBConfig::from_env().ok(),
-        eth_client_config: ETHClientConfig::from_env().ok(),
-        eth_sender_config: ETHSenderConfig::from_env().ok(),
-        eth_watch_config: ETHWatchConfig::from_env().ok(),
-        gas_adjuster_config: GasAdjusterConfig::from_env().ok(),
-        object_store_config: ObjectStoreConfig::from_env().ok(),
+        postgres_config: collector.optional("postgres"),
+        health_check_config: collector.optional("healthcheck"),
+        merkle_tree_api_config: collector.optional("merkle_tree_api"),
+        web3_json_rpc_config: collector.optional("web3_json_rpc"),
+        circuit_breaker_config: collector.optional("circuit_breaker"),
+        mempool_config: collector.optional("mempool"),
+        base_token_config: collector.optional("base_token"),
+        fee_input_config: collector.optional("fee_input"),
+        network_config: collector.optional("network"),
+        operations_manager_config: collector.optional("operations_manager"),
+        state_keeper_config: collector.optional("state_keeper"),
+        house_keeper_config: collector.optional("house_keeper"),
+        fri_proof_compressor_config: collector.optional("fri_proof_compressor"),
+        fri_prover_config: collector.required("fri_prover"),
+        fri_prover_group_config: collector.optional("fri_prover_group"),
+        fri_witness_generator_config: collector.optional("fri_witness"),
+        prometheus_config: collector.optional("prometheus"),
+        proof_data_handler_config: collector.optional("proof_data_handler"),
+        witness_generator_config: collector.optional("witness"),
+        api_config: collector.optional("api"),
+        contracts_config: collector.optional("contracts"),
+        db_config: collector.optional("database"),
+        eth_client_config: collector.optional("eth_client"),
+        eth_sender_config: collector.optional("eth_sender"),
+        eth_watch_config: collector.optional("eth_watch"),
+        gas_adjuster_config: collector.optional("eth_sender.gas_adjuster"),
+        object_store_config: collector.optional("object_store"),
         consensus_config: None,
     };
+    let shutdown_config: ShutdownConfig = collector.optional("shutdown").unwrap_or_default();
+    collector.validate(Some(&observability_config));
+    collector.validate(Some(&shutdown_config));
+    collector.validate(configs.postgres_config.as_ref());
+    collector.validate(configs.api_config.as_ref().map(|api| &api.prometheus));
+    collector.validate(configs.api_config.as_ref().map(|api| &api.rate_limit));
+    collector.validate(configs.api_config.as_ref().map(|api| &api.grpc));
+    collector.validate(configs.api_config.as_ref().map(|api| &api.audit_log));
+    collector.validate(configs.api_config.as_ref().map(|api| &api.admin));
+    collector.validate(configs.web3_json_rpc_config.as_ref());
+    collector.validate(configs.mempool_config.as_ref());
+    collector.validate(configs.base_token_config.as_ref());
+    collector.validate(configs.fee_input_config.as_ref());
+    collector.validate(configs.state_keeper_config.as_ref());
+    collector.validate(configs.object_store_config.as_ref());
+    // Report all config errors at once, so that they can be fixed in a single iteration.
+    collector.finish().context("invalid configuration")?;
 
     if opt.components.0.contains(&Component::Consensus) {
         configs.consensus_config =
             Some(config::read_consensus_config().context("read_consensus_config()")?);
     }
+    startup::report_phase(
+        StartupPhase::ConfigLoading,
+        config_loading_started_at.elapsed(),
+    );
+
+    if let Some(Command::VerifyPubdata { input, offline }) = &opt.command {
+        let export = std::fs::read(input)
+            .with_context(|| format!("failed reading pubdata export from {input:?}"))?;
+        let export: PubdataExport =
+            serde_json::from_slice(&export).context("failed parsing pubdata export")?;
+        let web3_url = if *offline {
+            None
+        } else {
+            let eth_client = configs
+                .eth_client_config
+                .as_ref()
+                .context("ETHClientConfig")?;
+            Some(eth_client.web3_url.as_str())
+        };
+        let report = PubdataVerifier::new(web3_url)?.verify(&export).await?;
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        let failed_l1_batches = report.failed_l1_batches();
+        anyhow::ensure!(
+            failed_l1_batches.is_empty(),
+            "L1 batches {failed_l1_batches:?} failed verification"
+        );
+        return Ok(());
+    }
 
     let postgres_config = configs.postgres_config.clone().context("PostgresConfig")?;
+    if let Some(Command::ExportPubdata {
+        from_batch,
+        to_batch,
+        output,
+    }) = &opt.command
+    {
+        let pool = ConnectionPool::singleton(postgres_config.replica_url()?)
+            .build()
+            .await
+            .context("failed to build a connection pool")?;
+        let default_pubdata_da = configs
+            .eth_sender_config
+            .as_ref()
+            .map_or(PubdataDA::Calldata, |config| {
+                config.sender.pubdata_sending_mode.into()
+            });
+        let mut storage = pool.access_storage().await?;
+        let l1_batches = L1BatchNumber(*from_batch)..=L1BatchNumber(*to_batch);
+        let export = export_pubdata(&mut storage, l1_batches, default_pubdata_da).await?;
+        let export = serde_json::to_string_pretty(&export)?;
+        if let Some(output) = output {
+            std::fs::write(output, export)
+                .with_context(|| format!("failed writing pubdata export to {output:?}"))?;
+        } else {
+            println!("{export}");
+        }
+        return Ok(());
+    }
+    if let Some(action) = migrations_action {
+        let pool = ConnectionPool::singleton(postgres_config.master_url()?)
+            .build()
+            .await
+            .context("failed to build a connection pool")?;
+        return action.execute(&pool).await;
+    }
 
-    if opt.genesis || is_genesis_needed(&postgres_config).await {
+    let (genesis_only, genesis_spec) = match &opt.command {
+        Some(Command::Genesis { spec }) => {
+            let spec = spec
+                .as_ref()
+                .map(GenesisSpec::from_file)
+                .transpose()
+                .context("GenesisSpec::from_file()")?;
+            (true, spec)
+        }
+        _ => (opt.genesis, None),
+    };
+    if genesis_only || is_genesis_needed(&postgres_config).await {
         let network = NetworkConfig::from_env().context("NetworkConfig")?;
         let eth_sender = ETHSenderConfig::from_env().context("ETHSenderConfig")?;
         let contracts = ContractsConfig::from_env().context("ContractsConfig")?;
         let eth_client = ETHClientConfig::from_env().context("EthClientConfig")?;
+        let genesis_started_at = Instant::now();
         genesis_init(
             &postgres_config,
             &eth_sender,
@@ -147,10 +422,15 @@ async fn main() -> anyhow::Result<()> {
             &contracts,
             &eth_client.web3_url,
             opt.set_chain_id,
+            genesis_spec.as_ref(),
         )
         .await
         .context("genesis_init")?;
-        if opt.genesis {
+        startup::report_phase(
+            StartupPhase::StorageInitialization,
+            genesis_started_at.elapsed(),
+        );
+        if genesis_only {
             return Ok(());
         }
     }
@@ -162,10 +442,19 @@ async fn main() -> anyhow::Result<()> {
     };
 
     // Run core actors.
-    let (core_task_handles, stop_sender, cb_receiver, health_check_handle) =
-        initialize_components(&configs, components)
-            .await
-            .context("Unable to start Core actors")?;
+    let components_init_started_at = Instant::now();
+    let (core_task_handles, stop_sender, cb_receiver, health_check_handle, shutdown_tracker) =
+        initialize_components(
+            &configs,
+            components,
+            Some(observability_guard.log_reload_handle()),
+        )
+        .await
+        .context("Unable to start Core actors")?;
+    startup::report_phase(
+        StartupPhase::ComponentsInitialization,
+        components_init_started_at.elapsed(),
+    );
 
     tracing::info!("Running {} core task handlers", core_task_handles.len());
     let sigint_receiver = setup_sigint_handler();
@@ -187,13 +476,17 @@ async fn main() -> anyhow::Result<()> {
         },
     }
 
-    stop_sender.send(true).ok();
-    tokio::task::spawn_blocking(RocksDB::await_rocksdb_termination)
-        .await
-        .unwrap();
-    // Sleep for some time to let some components gracefully stop.
-    tokio::time::sleep(Duration::from_secs(5)).await;
+    let shutdown_report = shutdown_tracker
+        .shutdown(&stop_sender, shutdown_config.timeout())
+        .await;
     health_check_handle.stop().await;
-    tracing::info!("Stopped");
+    if shutdown_report.is_complete() {
+        tracing::info!("Stopped in {:?}", shutdown_report.latency);
+    } else {
+        tracing::warn!(
+            "Stopped in {:?}; some components haven't stopped gracefully by the deadline",
+            shutdown_report.latency
+        );
+    }
     Ok(())
 }
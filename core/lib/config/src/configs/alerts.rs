@@ -1,6 +1,7 @@
-use serde::Deserialize;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[derive(Debug, Serialize, JsonSchema, Deserialize, Clone, PartialEq)]
 pub struct AlertsConfig {
     /// List of panics' messages from external crypto code,
     /// that are sporadic and needed to be handled separately
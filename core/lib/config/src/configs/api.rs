@@ -1,12 +1,14 @@
-use std::{net::SocketAddr, num::NonZeroU32, time::Duration};
+use std::{collections::HashMap, net::SocketAddr, num::NonZeroU32, time::Duration};
 
-use serde::Deserialize;
+use anyhow::Context as _;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 use zksync_basic_types::H256;
 
 pub use crate::configs::PrometheusConfig;
 
 /// API configuration.
-#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[derive(Debug, Serialize, JsonSchema, Deserialize, Clone, PartialEq)]
 pub struct ApiConfig {
     /// Configuration options for the Web3 JSON RPC servers.
     pub web3_json_rpc: Web3JsonRpcConfig,
@@ -18,9 +20,27 @@ pub struct ApiConfig {
     pub healthcheck: HealthCheckConfig,
     /// Configuration options for Merkle tree API.
     pub merkle_tree: MerkleTreeApiConfig,
+    /// Configuration options for rate limiting of the Web3 JSON RPC servers.
+    #[serde(default)]
+    pub rate_limit: ApiRateLimitConfig,
+    /// Configuration options for the gRPC streaming API.
+    #[serde(default)]
+    pub grpc: GrpcApiConfig,
+    /// Namespaces and methods exposed by the HTTP Web3 JSON RPC server.
+    #[serde(default)]
+    pub http_interface: RpcInterfaceConfig,
+    /// Namespaces and methods exposed by the WebSocket Web3 JSON RPC server.
+    #[serde(default)]
+    pub ws_interface: RpcInterfaceConfig,
+    /// Audit log for the Web3 JSON RPC servers.
+    #[serde(default)]
+    pub audit_log: ApiAuditLogConfig,
+    /// Configuration options for the admin API.
+    #[serde(default)]
+    pub admin: AdminApiConfig,
 }
 
-#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[derive(Debug, Serialize, JsonSchema, Deserialize, Clone, PartialEq)]
 pub struct Web3JsonRpcConfig {
     /// Port to which the HTTP RPC server is listening.
     pub http_port: u16,
@@ -37,15 +57,23 @@ pub struct Web3JsonRpcConfig {
     /// Max possible limit of subscriptions to be in the state at once.
     pub subscriptions_limit: Option<u32>,
     /// Interval between polling db for pubsub (in ms).
+    #[serde(default, deserialize_with = "crate::units::opt_duration_ms")]
+    #[schemars(with = "Option<crate::schema::WithUnit>")]
     pub pubsub_polling_interval: Option<u64>,
     /// Tx nonce: how far ahead from the committed nonce can it be.
     pub max_nonce_ahead: u32,
+    /// Minimum fee bump (in percent) required to replace a pending transaction with the same nonce. Both `max_fee_per_gas`
+    /// and `max_priority_fee_per_gas` of the replacement must be higher at least by this percentage. Default is 10%.
+    pub tx_replacement_fee_bump_percent: Option<u32>,
     /// The multiplier to use when suggesting gas price. Should be higher than one,
     /// otherwise if the L1 prices soar, the suggested gas price won't be sufficient to be included in block
     pub gas_price_scale_factor: f64,
     /// Timeout for requests (in s)
+    #[serde(default, deserialize_with = "crate::units::opt_duration_sec")]
+    #[schemars(with = "Option<crate::schema::WithUnit>")]
     pub request_timeout: Option<u64>,
     /// Private keys for accounts managed by node
+    #[schemars(with = "Option<Vec<String>>")]
     pub account_pks: Option<Vec<H256>>,
     /// The factor by which to scale the gasLimit
     pub estimate_gas_scale_factor: f64,
@@ -60,6 +88,8 @@ pub struct Web3JsonRpcConfig {
     /// and the new fee model when estimating L1->L2 transactions.  
     pub l1_to_l2_transactions_compatibility_mode: bool,
     ///  Max possible size of an ABI encoded tx (in bytes).
+    #[serde(deserialize_with = "crate::units::byte_size")]
+    #[schemars(with = "crate::schema::WithUnit")]
     pub max_tx_size: usize,
     /// Max number of cache misses during one VM execution. If the number of cache misses exceeds this value, the API server panics.
     /// This is a temporary solution to mitigate API request resulting in thousands of DB queries.
@@ -69,18 +99,38 @@ pub struct Web3JsonRpcConfig {
     /// If not set, the VM concurrency limit will be efficiently disabled.
     pub vm_concurrency_limit: Option<usize>,
     /// Smart contract cache size in MiBs. The default value is 128 MiB.
+    #[serde(default, deserialize_with = "crate::units::opt_byte_size_mb")]
+    #[schemars(with = "Option<crate::schema::WithUnit>")]
     pub factory_deps_cache_size_mb: Option<usize>,
     /// Initial writes cache size in MiBs. The default value is 32 MiB.
+    #[serde(default, deserialize_with = "crate::units::opt_byte_size_mb")]
+    #[schemars(with = "Option<crate::schema::WithUnit>")]
     pub initial_writes_cache_size_mb: Option<usize>,
     /// Latest values cache size in MiBs. The default value is 128 MiB. If set to 0, the latest
     /// values cache will be disabled.
+    #[serde(default, deserialize_with = "crate::units::opt_byte_size_mb")]
+    #[schemars(with = "Option<crate::schema::WithUnit>")]
     pub latest_values_cache_size_mb: Option<usize>,
+    /// Memory budget in MiBs of the cache for immutable API responses (e.g., finalized blocks and receipts
+    /// of transactions in finalized blocks). The default value is 0, which disables the cache.
+    #[serde(default, deserialize_with = "crate::units::opt_byte_size_mb")]
+    #[schemars(with = "Option<crate::schema::WithUnit>")]
+    pub response_cache_size_mb: Option<usize>,
     /// Limit for fee history block range.
     pub fee_history_limit: Option<u64>,
     /// Maximum number of requests in a single batch JSON RPC request. Default is 500.
     pub max_batch_request_size: Option<usize>,
     /// Maximum response body size in MiBs. Default is 10 MiB.
+    #[serde(default, deserialize_with = "crate::units::opt_byte_size_mb")]
+    #[schemars(with = "Option<crate::schema::WithUnit>")]
     pub max_response_body_size_mb: Option<usize>,
+    /// Maximum request body size in MiBs. Default is 10 MiB.
+    #[serde(default, deserialize_with = "crate::units::opt_byte_size_mb")]
+    #[schemars(with = "Option<crate::schema::WithUnit>")]
+    pub max_request_body_size_mb: Option<usize>,
+    /// Whether to compress HTTP responses if the client supports it (as specified in the `Accept-Encoding` header).
+    /// Only responses larger than 1 KiB are compressed. Default is `true`.
+    pub compress_responses: Option<bool>,
     /// Maximum number of requests per minute for the WebSocket server.
     /// The value is per active connection.
     /// Note: For HTTP, rate limiting is expected to be configured on the infra level.
@@ -104,6 +154,7 @@ impl Web3JsonRpcConfig {
             subscriptions_limit: Some(10000),
             pubsub_polling_interval: Some(200),
             max_nonce_ahead: 50,
+            tx_replacement_fee_bump_percent: Default::default(),
             gas_price_scale_factor: 1.2,
             request_timeout: Default::default(),
             account_pks: Default::default(),
@@ -116,9 +167,12 @@ impl Web3JsonRpcConfig {
             factory_deps_cache_size_mb: Default::default(),
             initial_writes_cache_size_mb: Default::default(),
             latest_values_cache_size_mb: Default::default(),
+            response_cache_size_mb: Default::default(),
             fee_history_limit: Default::default(),
             max_batch_request_size: Default::default(),
             max_response_body_size_mb: Default::default(),
+            max_request_body_size_mb: Default::default(),
+            compress_responses: Default::default(),
             websocket_requests_per_minute_limit: Default::default(),
             tree_api_url: None,
         }
@@ -152,6 +206,10 @@ impl Web3JsonRpcConfig {
         Duration::from_secs(self.request_timeout.unwrap_or(10))
     }
 
+    pub fn tx_replacement_fee_bump_percent(&self) -> u32 {
+        self.tx_replacement_fee_bump_percent.unwrap_or(10)
+    }
+
     pub fn account_pks(&self) -> Vec<H256> {
         self.account_pks.clone().unwrap_or_default()
     }
@@ -178,6 +236,11 @@ impl Web3JsonRpcConfig {
         self.latest_values_cache_size_mb.unwrap_or(128) * super::BYTES_IN_MEGABYTE
     }
 
+    /// Returns the memory budget of the API response cache in bytes.
+    pub fn response_cache_size(&self) -> usize {
+        self.response_cache_size_mb.unwrap_or(0) * super::BYTES_IN_MEGABYTE
+    }
+
     pub fn fee_history_limit(&self) -> u64 {
         self.fee_history_limit.unwrap_or(1024)
     }
@@ -191,6 +254,14 @@ impl Web3JsonRpcConfig {
         self.max_response_body_size_mb.unwrap_or(10) * super::BYTES_IN_MEGABYTE
     }
 
+    pub fn max_request_body_size(&self) -> usize {
+        self.max_request_body_size_mb.unwrap_or(10) * super::BYTES_IN_MEGABYTE
+    }
+
+    pub fn compress_responses(&self) -> bool {
+        self.compress_responses.unwrap_or(true)
+    }
+
     pub fn websocket_requests_per_minute_limit(&self) -> NonZeroU32 {
         // The default limit is chosen to be reasonably permissive.
         self.websocket_requests_per_minute_limit
@@ -202,19 +273,130 @@ impl Web3JsonRpcConfig {
     }
 }
 
-#[derive(Debug, Deserialize, Clone, PartialEq)]
+/// Rate limiting for the Web3 JSON RPC servers. Limits are applied per client, which is identified
+/// by the API key (if the API key header is configured and present in the request) or by the client IP address.
+#[derive(Debug, Default, Serialize, JsonSchema, Deserialize, Clone, PartialEq)]
+pub struct ApiRateLimitConfig {
+    /// Maximum number of request units per minute for a single client. If not set, rate limiting is disabled.
+    pub requests_per_minute: Option<NonZeroU32>,
+    /// HTTP header containing the client API key, e.g. `X-Api-Key`. If not set, clients are identified
+    /// by their IP address only.
+    pub api_key_header: Option<String>,
+    /// Weights of RPC methods in the `method:weight` format, e.g. `eth_getLogs:10`. Methods not mentioned here
+    /// have the weight of 1.
+    #[serde(default)]
+    pub method_weights: Vec<String>,
+    /// Maximum numbers of concurrently executed calls of RPC methods (across all clients) in the `method:limit` format,
+    /// e.g. `debug_traceCall:4`. Calls exceeding the limit are rejected. Unlike other options, these limits
+    /// are applied even if `requests_per_minute` is not set.
+    #[serde(default)]
+    pub method_concurrency_limits: Vec<String>,
+}
+
+impl ApiRateLimitConfig {
+    /// Parses [method weights](Self::method_weights).
+    pub fn method_weights(&self) -> anyhow::Result<HashMap<String, NonZeroU32>> {
+        parse_method_values(&self.method_weights, "weight")
+    }
+
+    /// Parses [method concurrency limits](Self::method_concurrency_limits).
+    pub fn method_concurrency_limits(&self) -> anyhow::Result<HashMap<String, NonZeroU32>> {
+        parse_method_values(&self.method_concurrency_limits, "limit")
+    }
+}
+
+/// Structured audit log of RPC calls processed by the Web3 JSON RPC servers.
+#[derive(Debug, Serialize, JsonSchema, Deserialize, Clone, PartialEq)]
+pub struct ApiAuditLogConfig {
+    /// Fraction of RPC calls recorded in the log, in the `(0, 1]` range. If not set, the audit log is disabled.
+    pub sampling_ratio: Option<f64>,
+    /// HTTP header containing the client API key, e.g. `X-Api-Key`. If not set, clients are identified
+    /// by their IP address only.
+    pub api_key_header: Option<String>,
+    /// If set, client IP addresses are truncated (to /24 for IPv4 and to /48 for IPv6), and API keys
+    /// are replaced with their hashes. Enabled by default.
+    #[serde(default = "ApiAuditLogConfig::default_redact_client_ids")]
+    pub redact_client_ids: bool,
+    /// Path to the file to which log entries are appended as JSON lines. If not set, entries are emitted
+    /// as `tracing` events with the `zksync_api_audit` target.
+    pub file_path: Option<String>,
+}
+
+impl Default for ApiAuditLogConfig {
+    fn default() -> Self {
+        Self {
+            sampling_ratio: None,
+            api_key_header: None,
+            redact_client_ids: Self::default_redact_client_ids(),
+            file_path: None,
+        }
+    }
+}
+
+impl ApiAuditLogConfig {
+    const fn default_redact_client_ids() -> bool {
+        true
+    }
+}
+
+/// Namespaces and methods exposed by a Web3 JSON RPC server (HTTP or WebSocket).
+#[derive(Debug, Default, Serialize, JsonSchema, Deserialize, Clone, PartialEq)]
+pub struct RpcInterfaceConfig {
+    /// Enabled API namespaces, e.g. `eth`, `zks` or `debug`. If not set, the default namespaces are enabled.
+    pub namespaces: Option<Vec<String>>,
+    /// If set, only the matching methods are exposed, and all other methods (including ones added in future versions)
+    /// are denied. Entries are either method names (e.g., `eth_call`) or namespace wildcards (e.g., `debug_*`).
+    pub allowed_methods: Option<Vec<String>>,
+    /// Methods that are not exposed even if their namespace is enabled and they are allowed by `allowed_methods`.
+    /// Has the same format as `allowed_methods`.
+    #[serde(default)]
+    pub denied_methods: Vec<String>,
+}
+
+fn parse_method_values(
+    entries: &[String],
+    value_name: &str,
+) -> anyhow::Result<HashMap<String, NonZeroU32>> {
+    entries
+        .iter()
+        .map(|entry| {
+            let (method, value) = entry.split_once(':').with_context(|| {
+                format!("method {value_name} {entry:?} does not have `method:{value_name}` format")
+            })?;
+            let value = value
+                .trim()
+                .parse()
+                .with_context(|| format!("invalid {value_name} in {entry:?}"))?;
+            Ok((method.trim().to_owned(), value))
+        })
+        .collect()
+}
+
+#[derive(Debug, Serialize, JsonSchema, Deserialize, Clone, PartialEq)]
 pub struct HealthCheckConfig {
     /// Port to which the REST server is listening.
     pub port: u16,
+    /// Maximum age of the last sealed L1 batch (in s) for the node to be considered ready.
+    /// If not set, the age is not checked.
+    #[serde(default, deserialize_with = "crate::units::opt_duration_sec")]
+    #[schemars(with = "Option<crate::schema::WithUnit>")]
+    pub max_sealed_l1_batch_age_sec: Option<u64>,
+    /// Maximum number of sealed L1 batches not processed by the Merkle tree for the node to be considered ready.
+    /// If not set, the tree lag is not checked.
+    pub max_tree_lag: Option<u32>,
 }
 
 impl HealthCheckConfig {
     pub fn bind_addr(&self) -> SocketAddr {
         SocketAddr::new("0.0.0.0".parse().unwrap(), self.port)
     }
+
+    pub fn max_sealed_l1_batch_age(&self) -> Option<Duration> {
+        self.max_sealed_l1_batch_age_sec.map(Duration::from_secs)
+    }
 }
 
-#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[derive(Debug, Serialize, JsonSchema, Deserialize, Clone, PartialEq)]
 pub struct ContractVerificationApiConfig {
     /// Port to which the REST server is listening.
     pub port: u16,
@@ -229,7 +411,7 @@ impl ContractVerificationApiConfig {
 }
 
 /// Configuration for the Merkle tree API.
-#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema, Deserialize)]
 pub struct MerkleTreeApiConfig {
     /// Port to bind the Merkle tree API server to.
     #[serde(default = "MerkleTreeApiConfig::default_port")]
@@ -241,3 +423,75 @@ impl MerkleTreeApiConfig {
         3_072
     }
 }
+
+/// Configuration for the gRPC API streaming new miniblocks, logs and L1 batch status updates.
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema, Deserialize)]
+pub struct GrpcApiConfig {
+    /// Port to bind the gRPC server to.
+    #[serde(default = "GrpcApiConfig::default_port")]
+    pub port: u16,
+    /// Interval between polling Postgres for new data (in ms).
+    #[serde(
+        default = "GrpcApiConfig::default_polling_interval_ms",
+        deserialize_with = "crate::units::duration_ms"
+    )]
+    #[schemars(with = "crate::schema::WithUnit")]
+    pub polling_interval_ms: u64,
+}
+
+impl Default for GrpcApiConfig {
+    fn default() -> Self {
+        Self {
+            port: Self::default_port(),
+            polling_interval_ms: Self::default_polling_interval_ms(),
+        }
+    }
+}
+
+impl GrpcApiConfig {
+    const fn default_port() -> u16 {
+        3_080
+    }
+
+    const fn default_polling_interval_ms() -> u64 {
+        200
+    }
+
+    pub fn bind_addr(&self) -> SocketAddr {
+        SocketAddr::new("0.0.0.0".parse().unwrap(), self.port)
+    }
+
+    pub fn polling_interval(&self) -> Duration {
+        Duration::from_millis(self.polling_interval_ms)
+    }
+}
+
+/// Configuration for the admin API allowing node operators to control node components at runtime.
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema, Deserialize)]
+pub struct AdminApiConfig {
+    /// Port to bind the admin API server to. The server only listens on the loopback interface.
+    #[serde(default = "AdminApiConfig::default_port")]
+    pub port: u16,
+    /// Bearer token that must be provided in the `Authorization` header of all requests.
+    /// The admin API cannot be started if the token is not set.
+    pub auth_token: Option<String>,
+}
+
+impl Default for AdminApiConfig {
+    fn default() -> Self {
+        Self {
+            port: Self::default_port(),
+            auth_token: None,
+        }
+    }
+}
+
+impl AdminApiConfig {
+    const fn default_port() -> u16 {
+        3_090
+    }
+
+    pub fn bind_addr(&self) -> SocketAddr {
+        SocketAddr::new("127.0.0.1".parse().unwrap(), self.port)
+    }
+}
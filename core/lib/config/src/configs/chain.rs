@@ -1,17 +1,20 @@
 use std::{str::FromStr, time::Duration};
 
-use serde::Deserialize;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 use zksync_basic_types::{network::Network, Address, L2ChainId};
 
-#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[derive(Debug, Serialize, JsonSchema, Deserialize, Clone, PartialEq)]
 pub struct NetworkConfig {
     /// Name of the used Ethereum network, e.g. `localhost` or `rinkeby`.
+    #[schemars(with = "String")]
     pub network: Network,
     /// Name of current zkSync network
     /// Used for Sentry environment
     pub zksync_network: String,
     /// ID of current zkSync network treated as ETH network ID.
     /// Used to distinguish zkSync from other Web3-capable networks.
+    #[schemars(with = "u64")]
     pub zksync_network_id: L2ChainId,
 }
 
@@ -33,7 +36,7 @@ impl NetworkConfig {
 ///  - `V2`, the second model that was used in zkSync Era. There the pubdata price might be independent from the L1 gas price. Also,
 ///  The fair L2 gas price is expected to both the proving/computation price for the operator and the costs that come from
 ///  processing the batch on L1.
-#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Serialize, JsonSchema, Deserialize, PartialEq, Eq)]
 pub enum FeeModelVersion {
     V1,
     V2,
@@ -45,14 +48,30 @@ impl Default for FeeModelVersion {
     }
 }
 
-#[derive(Debug, Deserialize, Clone, PartialEq, Default)]
+/// Policy ordering executable L2 transactions in the mempool. Regardless of the policy, transactions
+/// of each account are executed in the nonce order, and L1 transactions are executed before L2 ones.
+#[derive(Debug, Clone, Copy, Serialize, JsonSchema, Deserialize, PartialEq, Eq, Default)]
+pub enum TransactionOrdering {
+    /// Transactions are ordered by the time they were received.
+    #[default]
+    ArrivalTime,
+    /// Transactions are ordered by their effective priority fee, i.e., `max_priority_fee_per_gas` capped
+    /// by `max_fee_per_gas`; transactions with the same fee are ordered by the time they were received.
+    FeePriority,
+}
+
+#[derive(Debug, Serialize, JsonSchema, Deserialize, Clone, PartialEq, Default)]
 pub struct StateKeeperConfig {
     /// The max number of slots for txs in a block before it should be sealed by the slots sealer.
     pub transaction_slots: usize,
 
     /// Number of ms after which an L1 batch is going to be unconditionally sealed.
+    #[serde(deserialize_with = "crate::units::duration_ms")]
+    #[schemars(with = "crate::schema::WithUnit")]
     pub block_commit_deadline_ms: u64,
     /// Number of ms after which a miniblock should be sealed by the timeout sealer.
+    #[serde(deserialize_with = "crate::units::duration_ms")]
+    #[schemars(with = "crate::schema::WithUnit")]
     pub miniblock_commit_deadline_ms: u64,
     /// Capacity of the queue for asynchronous miniblock sealing. Once this many miniblocks are queued,
     /// sealing will block until some of the miniblocks from the queue are processed.
@@ -80,6 +99,7 @@ pub struct StateKeeperConfig {
     /// Denotes the percentage of L1 gas used in L2 block that triggers L2 block seal.
     pub close_block_at_gas_percentage: f64,
 
+    #[schemars(with = "String")]
     pub fee_account_addr: Address,
 
     /// The minimal acceptable L2 gas price, i.e. the price that should include the cost of computation/proving as well
@@ -116,6 +136,28 @@ pub struct StateKeeperConfig {
 
     /// Number of keys that is processed by enum_index migration in State Keeper each L1 batch.
     pub enum_index_migration_chunk_size: Option<usize>,
+
+    /// Names of conditional seal criteria that should not be used by the state keeper (e.g., `pub_data_size` or `slots`).
+    /// Built-in criteria enforce protocol limits, so a criterion should only be disabled if it's replaced
+    /// by a custom criterion enforcing the same limit.
+    #[serde(default)]
+    pub disabled_seal_criteria: Vec<String>,
+
+    /// Policy ordering L2 transactions in the mempool.
+    #[serde(default)]
+    pub transaction_ordering: TransactionOrdering,
+
+    /// Max number of sealed L1 batches not committed on L1. If exceeded, the state keeper doesn't open
+    /// new L1 batches until the Ethereum sender catches up. If not set, the lag is not limited.
+    #[serde(default)]
+    pub max_l1_batches_not_committed: Option<u32>,
+    /// Max number of sealed L1 batches without a proof sent to L1. If exceeded, the state keeper doesn't open
+    /// new L1 batches until provers catch up. If not set, the lag is not limited.
+    ///
+    /// Metadata calculator lag needs no threshold: the state keeper always waits for the state root hash
+    /// of the previous L1 batch before opening a new one.
+    #[serde(default)]
+    pub max_l1_batches_not_proven: Option<u32>,
 }
 
 impl StateKeeperConfig {
@@ -150,6 +192,10 @@ impl StateKeeperConfig {
             virtual_blocks_per_miniblock: 1,
             upload_witness_inputs_to_gcs: false,
             enum_index_migration_chunk_size: None,
+            disabled_seal_criteria: vec![],
+            transaction_ordering: TransactionOrdering::ArrivalTime,
+            max_l1_batches_not_committed: None,
+            max_l1_batches_not_proven: None,
         }
     }
 
@@ -158,9 +204,11 @@ impl StateKeeperConfig {
     }
 }
 
-#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[derive(Debug, Serialize, JsonSchema, Deserialize, Clone, PartialEq)]
 pub struct OperationsManagerConfig {
     /// Sleep time in ms when there is no new input data
+    #[serde(deserialize_with = "crate::units::duration_ms")]
+    #[schemars(with = "crate::schema::WithUnit")]
     pub delay_interval: u64,
 }
 
@@ -170,11 +218,17 @@ impl OperationsManagerConfig {
     }
 }
 
-#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[derive(Debug, Serialize, JsonSchema, Deserialize, Clone, PartialEq)]
 pub struct CircuitBreakerConfig {
+    #[serde(deserialize_with = "crate::units::duration_ms")]
+    #[schemars(with = "crate::schema::WithUnit")]
     pub sync_interval_ms: u64,
     pub http_req_max_retry_number: usize,
+    #[serde(deserialize_with = "crate::units::duration_sec")]
+    #[schemars(with = "crate::schema::WithUnit")]
     pub http_req_retry_interval_sec: u8,
+    #[serde(default, deserialize_with = "crate::units::opt_duration_sec")]
+    #[schemars(with = "Option<crate::schema::WithUnit>")]
     pub replication_lag_limit_sec: Option<u32>,
 }
 
@@ -188,14 +242,30 @@ impl CircuitBreakerConfig {
     }
 }
 
-#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[derive(Debug, Serialize, JsonSchema, Deserialize, Clone, PartialEq)]
 pub struct MempoolConfig {
+    #[serde(deserialize_with = "crate::units::duration_ms")]
+    #[schemars(with = "crate::schema::WithUnit")]
     pub sync_interval_ms: u64,
     pub sync_batch_size: usize,
     pub capacity: u64,
+    #[serde(deserialize_with = "crate::units::duration_sec")]
+    #[schemars(with = "crate::schema::WithUnit")]
     pub stuck_tx_timeout: u64,
     pub remove_stuck_txs: bool,
+    #[serde(deserialize_with = "crate::units::duration_ms")]
+    #[schemars(with = "crate::schema::WithUnit")]
     pub delay_interval: u64,
+    /// Max number of L2 transactions per account kept in the mempool. If an account exceeds this limit,
+    /// its transactions with the highest nonces are evicted. If not set, the number of transactions is not limited.
+    #[serde(default)]
+    pub max_transactions_per_account: Option<usize>,
+    /// Max estimated memory occupied by L2 transactions in the mempool (in MiBs). If the mempool exceeds this limit,
+    /// non-executable transactions with the lowest fees are evicted (oldest ones first if fees are equal).
+    /// If not set, the memory is not limited.
+    #[serde(default, deserialize_with = "crate::units::opt_byte_size_mb")]
+    #[schemars(with = "Option<crate::schema::WithUnit>")]
+    pub max_memory_mb: Option<usize>,
 }
 
 impl MempoolConfig {
@@ -210,4 +280,151 @@ impl MempoolConfig {
     pub fn delay_interval(&self) -> Duration {
         Duration::from_millis(self.delay_interval)
     }
+
+    /// Returns the max memory occupied by L2 transactions in bytes.
+    pub fn max_memory(&self) -> Option<usize> {
+        self.max_memory_mb.map(|mb| mb * super::BYTES_IN_MEGABYTE)
+    }
+}
+
+/// Source of the conversion ratio between ETH and the base token.
+#[derive(Debug, Clone, Copy, Serialize, JsonSchema, Deserialize, PartialEq, Eq)]
+pub enum BaseTokenRatioSource {
+    /// The ratio is fixed and specified by the `ratio_numerator` / `ratio_denominator` options.
+    Fixed,
+    /// The ratio is periodically fetched from an HTTP API specified by the `ratio_url` option.
+    /// The API must return a JSON object with `numerator` and `denominator` integer fields.
+    Http,
+}
+
+/// Configuration of the base token used to denominate fees on L2. If not specified, fees are denominated in ETH.
+#[derive(Debug, Serialize, JsonSchema, Deserialize, Clone, PartialEq)]
+pub struct BaseTokenConfig {
+    /// Address of the base token on L1.
+    #[schemars(with = "Option<String>")]
+    pub l1_address: Option<Address>,
+    pub ratio_source: BaseTokenRatioSource,
+    /// Number of base token units worth `ratio_denominator` wei. Used as the initial ratio for the HTTP source.
+    #[serde(default = "BaseTokenConfig::default_ratio_part")]
+    pub ratio_numerator: u64,
+    #[serde(default = "BaseTokenConfig::default_ratio_part")]
+    pub ratio_denominator: u64,
+    /// URL of the API returning the conversion ratio. Required for the HTTP ratio source.
+    pub ratio_url: Option<String>,
+    /// Interval between polling the ratio API.
+    #[serde(default, deserialize_with = "crate::units::opt_duration_ms")]
+    #[schemars(with = "Option<crate::schema::WithUnit>")]
+    pub ratio_poll_interval_ms: Option<u64>,
+}
+
+impl BaseTokenConfig {
+    const fn default_ratio_part() -> u64 {
+        1
+    }
+
+    pub fn ratio_poll_interval(&self) -> Duration {
+        Duration::from_millis(self.ratio_poll_interval_ms.unwrap_or(60_000))
+    }
+}
+
+/// Source of an L1-dependent fee model parameter (the L1 gas price or the L1 pubdata price).
+#[derive(Debug, Clone, Copy, Serialize, JsonSchema, Deserialize, PartialEq, Eq)]
+pub enum FeeInputSource {
+    /// Value estimated by the gas adjuster based on the observed L1 base fees.
+    Oracle,
+    /// Value fetched from an external HTTP API returning a JSON document.
+    Http,
+    /// Fixed value (e.g., for testnets).
+    Fixed,
+}
+
+/// Configuration of a single L1-dependent fee model parameter. All prices are in wei.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeeInputParamConfig {
+    pub source: FeeInputSource,
+    /// Value used by the `Fixed` source.
+    pub fixed_value: Option<u64>,
+    /// URL of the API used by the `Http` source.
+    pub url: Option<String>,
+    /// JSON pointer (RFC 6901) to the value in the API response. If not set, the entire response is used.
+    pub json_pointer: Option<String>,
+    /// Multiplier converting the value returned by the API to wei.
+    pub multiplier: Option<f64>,
+    /// Smoothing factor of the exponential moving average applied to the source values, in the `(0, 1]` range.
+    /// If not set, values are not smoothed.
+    pub smoothing_alpha: Option<f64>,
+    /// Lower bound for the value.
+    pub min: Option<u64>,
+    /// Upper bound for the value.
+    pub max: Option<u64>,
+}
+
+impl FeeInputParamConfig {
+    pub fn json_pointer(&self) -> &str {
+        self.json_pointer.as_deref().unwrap_or("")
+    }
+
+    pub fn multiplier(&self) -> f64 {
+        self.multiplier.unwrap_or(1.0)
+    }
+}
+
+/// Configuration of sources for L1-dependent inputs of the fee model used by the main node. If not specified,
+/// both the L1 gas price and the L1 pubdata price are estimated by the gas adjuster.
+#[derive(Debug, Serialize, JsonSchema, Deserialize, Clone, PartialEq)]
+pub struct FeeInputConfig {
+    pub l1_gas_price_source: FeeInputSource,
+    pub l1_gas_price_fixed_value: Option<u64>,
+    pub l1_gas_price_url: Option<String>,
+    pub l1_gas_price_json_pointer: Option<String>,
+    pub l1_gas_price_multiplier: Option<f64>,
+    pub l1_gas_price_smoothing_alpha: Option<f64>,
+    pub l1_gas_price_min: Option<u64>,
+    pub l1_gas_price_max: Option<u64>,
+
+    pub l1_pubdata_price_source: FeeInputSource,
+    pub l1_pubdata_price_fixed_value: Option<u64>,
+    pub l1_pubdata_price_url: Option<String>,
+    pub l1_pubdata_price_json_pointer: Option<String>,
+    pub l1_pubdata_price_multiplier: Option<f64>,
+    pub l1_pubdata_price_smoothing_alpha: Option<f64>,
+    pub l1_pubdata_price_min: Option<u64>,
+    pub l1_pubdata_price_max: Option<u64>,
+
+    /// Interval between updating parameter values from their sources.
+    #[serde(default, deserialize_with = "crate::units::opt_duration_ms")]
+    #[schemars(with = "Option<crate::schema::WithUnit>")]
+    pub poll_interval_ms: Option<u64>,
+}
+
+impl FeeInputConfig {
+    pub fn l1_gas_price(&self) -> FeeInputParamConfig {
+        FeeInputParamConfig {
+            source: self.l1_gas_price_source,
+            fixed_value: self.l1_gas_price_fixed_value,
+            url: self.l1_gas_price_url.clone(),
+            json_pointer: self.l1_gas_price_json_pointer.clone(),
+            multiplier: self.l1_gas_price_multiplier,
+            smoothing_alpha: self.l1_gas_price_smoothing_alpha,
+            min: self.l1_gas_price_min,
+            max: self.l1_gas_price_max,
+        }
+    }
+
+    pub fn l1_pubdata_price(&self) -> FeeInputParamConfig {
+        FeeInputParamConfig {
+            source: self.l1_pubdata_price_source,
+            fixed_value: self.l1_pubdata_price_fixed_value,
+            url: self.l1_pubdata_price_url.clone(),
+            json_pointer: self.l1_pubdata_price_json_pointer.clone(),
+            multiplier: self.l1_pubdata_price_multiplier,
+            smoothing_alpha: self.l1_pubdata_price_smoothing_alpha,
+            min: self.l1_pubdata_price_min,
+            max: self.l1_pubdata_price_max,
+        }
+    }
+
+    pub fn poll_interval(&self) -> Duration {
+        Duration::from_millis(self.poll_interval_ms.unwrap_or(5_000))
+    }
 }
@@ -1,8 +1,9 @@
 use std::time::Duration;
 
-use serde::Deserialize;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[derive(Debug, Serialize, JsonSchema, Deserialize, Clone, PartialEq)]
 pub struct ContractVerifierConfig {
     /// Max time of a single compilation (in s).
     pub compilation_timeout: u64,
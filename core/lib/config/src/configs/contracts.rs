@@ -1,8 +1,9 @@
 // External uses
-use serde::Deserialize;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 // Workspace uses
 use zksync_basic_types::{Address, H256};
-#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[derive(Debug, Serialize, JsonSchema, Deserialize, Clone, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum ProverAtGenesis {
     Fri,
@@ -10,42 +11,74 @@ pub enum ProverAtGenesis {
 }
 
 /// Data about deployed contracts.
-#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[derive(Debug, Serialize, JsonSchema, Deserialize, Clone, PartialEq)]
 pub struct ContractsConfig {
+    #[schemars(with = "String")]
     pub governance_addr: Address,
+    #[schemars(with = "String")]
     pub mailbox_facet_addr: Address,
+    #[schemars(with = "String")]
     pub executor_facet_addr: Address,
+    #[schemars(with = "String")]
     pub admin_facet_addr: Address,
+    #[schemars(with = "String")]
     pub getters_facet_addr: Address,
+    #[schemars(with = "String")]
     pub verifier_addr: Address,
+    #[schemars(with = "String")]
     pub diamond_init_addr: Address,
+    #[schemars(with = "String")]
     pub diamond_upgrade_init_addr: Address,
+    #[schemars(with = "String")]
     pub diamond_proxy_addr: Address,
+    #[schemars(with = "String")]
     pub validator_timelock_addr: Address,
+    #[schemars(with = "String")]
     pub genesis_tx_hash: H256,
+    #[schemars(with = "String")]
     pub l1_erc20_bridge_proxy_addr: Address,
+    #[schemars(with = "String")]
     pub l1_erc20_bridge_impl_addr: Address,
+    #[schemars(with = "String")]
     pub l2_erc20_bridge_addr: Address,
+    #[schemars(with = "Option<String>")]
     pub l1_weth_bridge_proxy_addr: Option<Address>,
+    #[schemars(with = "Option<String>")]
     pub l2_weth_bridge_addr: Option<Address>,
+    #[schemars(with = "String")]
     pub l1_allow_list_addr: Address,
+    #[schemars(with = "Option<String>")]
     pub l2_testnet_paymaster_addr: Option<Address>,
+    #[schemars(with = "String")]
     pub recursion_scheduler_level_vk_hash: H256,
+    #[schemars(with = "String")]
     pub recursion_node_level_vk_hash: H256,
+    #[schemars(with = "String")]
     pub recursion_leaf_level_vk_hash: H256,
+    #[schemars(with = "String")]
     pub recursion_circuits_set_vks_hash: H256,
+    #[schemars(with = "String")]
     pub l1_multicall3_addr: Address,
+    #[schemars(with = "String")]
     pub fri_recursion_scheduler_level_vk_hash: H256,
+    #[schemars(with = "String")]
     pub fri_recursion_node_level_vk_hash: H256,
+    #[schemars(with = "String")]
     pub fri_recursion_leaf_level_vk_hash: H256,
     pub prover_at_genesis: ProverAtGenesis,
+    #[schemars(with = "String")]
     pub snark_wrapper_vk_hash: H256,
 
     // These contracts will be used after shared bridge integration.
+    #[schemars(with = "Option<String>")]
     pub bridgehub_proxy_addr: Option<Address>,
+    #[schemars(with = "Option<String>")]
     pub bridgehub_impl_addr: Option<Address>,
+    #[schemars(with = "Option<String>")]
     pub state_transition_proxy_addr: Option<Address>,
+    #[schemars(with = "Option<String>")]
     pub state_transition_impl_addr: Option<Address>,
+    #[schemars(with = "Option<String>")]
     pub transparent_proxy_admin_addr: Option<Address>,
 }
 
@@ -1,12 +1,13 @@
 use std::time::Duration;
 
 use anyhow::Context as _;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 /// Mode of operation for the Merkle tree.
 ///
 /// The mode does not influence how tree data is stored; i.e., a mode can be switched on the fly.
-#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, JsonSchema, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum MerkleTreeMode {
     /// In this mode, `MetadataCalculator` will compute commitments and witness inputs for all storage operations
@@ -18,7 +19,33 @@ pub enum MerkleTreeMode {
     Lightweight,
 }
 
-#[derive(Debug, Clone, PartialEq, Deserialize)]
+/// Compaction style for a RocksDB instance.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RocksdbCompactionStyle {
+    /// Level-style compaction. This is the default style, which is a good fit for most workloads.
+    Level,
+    /// Universal compaction. Reduces write amplification at the cost of increased space and read amplification.
+    Universal,
+    /// FIFO compaction, which drops the oldest files once the DB size exceeds a threshold. Only suitable for caches.
+    Fifo,
+}
+
+/// RocksDB tuning options shared by RocksDB instances used by the server (the Merkle tree and the state keeper cache).
+/// Options that are not set are determined by the component using the instance.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RocksdbTuning {
+    /// Compaction style.
+    pub compaction_style: Option<RocksdbCompactionStyle>,
+    /// Byte size of a single memtable.
+    pub write_buffer_size: Option<usize>,
+    /// Maximum number of concurrent background jobs (compactions and flushes).
+    pub max_background_jobs: Option<usize>,
+    /// Whether to collect RocksDB statistics and export them as metrics.
+    pub statistics: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema, Deserialize)]
 pub struct MerkleTreeConfig {
     /// Path to the RocksDB data directory for Merkle tree.
     #[serde(default = "MerkleTreeConfig::default_path")]
@@ -32,18 +59,69 @@ pub struct MerkleTreeConfig {
     pub multi_get_chunk_size: usize,
     /// Capacity of the block cache for the Merkle tree RocksDB. Reasonable values range from ~100 MB to several GB.
     /// The default value is 128 MB.
-    #[serde(default = "MerkleTreeConfig::default_block_cache_size_mb")]
+    #[serde(
+        default = "MerkleTreeConfig::default_block_cache_size_mb",
+        deserialize_with = "crate::units::byte_size_mb"
+    )]
+    #[schemars(with = "crate::schema::WithUnit")]
     pub block_cache_size_mb: usize,
     /// Byte capacity of memtables (recent, non-persisted changes to RocksDB). Setting this to a reasonably
     /// large value (order of 512 MiB) is helpful for large DBs that experience write stalls.
-    #[serde(default = "MerkleTreeConfig::default_memtable_capacity_mb")]
+    #[serde(
+        default = "MerkleTreeConfig::default_memtable_capacity_mb",
+        deserialize_with = "crate::units::byte_size_mb"
+    )]
+    #[schemars(with = "crate::schema::WithUnit")]
     pub memtable_capacity_mb: usize,
     /// Timeout to wait for the Merkle tree database to run compaction on stalled writes.
-    #[serde(default = "MerkleTreeConfig::default_stalled_writes_timeout_sec")]
+    #[serde(
+        default = "MerkleTreeConfig::default_stalled_writes_timeout_sec",
+        deserialize_with = "crate::units::duration_sec"
+    )]
+    #[schemars(with = "crate::schema::WithUnit")]
     pub stalled_writes_timeout_sec: u64,
     /// Maximum number of L1 batches to be processed by the Merkle tree at a time.
     #[serde(default = "MerkleTreeConfig::default_max_l1_batches_per_iter")]
     pub max_l1_batches_per_iter: usize,
+    /// Number of latest tree versions (= L1 batches) retained by the pruner. Older versions are removed from RocksDB,
+    /// but only once the corresponding L1 batches are executed on L1. If not specified, the tree is not pruned.
+    #[serde(default)]
+    pub pruning_retained_versions: Option<u64>,
+    /// Delay between consecutive pruning iterations if the pruner has more work to do. Can be used to throttle pruning
+    /// so that it doesn't compete for I/O with tree updates. The default value is 100 ms.
+    #[serde(
+        default = "MerkleTreeConfig::default_pruning_iteration_delay_ms",
+        deserialize_with = "crate::units::duration_ms"
+    )]
+    #[schemars(with = "crate::schema::WithUnit")]
+    pub pruning_iteration_delay_ms: u64,
+    /// Number of threads in a dedicated `rayon` thread pool used by the tree to parallelize updates
+    /// and hash computations. If set to 0, the number of threads is determined by `rayon`. If not specified,
+    /// the global `rayon` thread pool is used.
+    #[serde(default)]
+    pub thread_count: Option<usize>,
+    /// Interval between background consistency checks of the tree. Each check cross-checks root hashes of new tree
+    /// versions with L1 batches in Postgres and verifies internal node hashes for the latest tree version.
+    /// If not specified, background checks are disabled.
+    #[serde(default, deserialize_with = "crate::units::opt_duration_sec")]
+    #[schemars(with = "Option<crate::schema::WithUnit>")]
+    pub consistency_check_interval_sec: Option<u64>,
+    /// Compaction style for the Merkle tree RocksDB. If not specified, level-style compaction is used.
+    #[serde(default)]
+    pub compaction_style: Option<RocksdbCompactionStyle>,
+    /// Size of a single memtable for the Merkle tree RocksDB. If not specified, the size is derived
+    /// from `memtable_capacity_mb` for large column families.
+    #[serde(default, deserialize_with = "crate::units::opt_byte_size_mb")]
+    #[schemars(with = "Option<crate::schema::WithUnit>")]
+    pub write_buffer_size_mb: Option<usize>,
+    /// Maximum number of concurrent background jobs (compactions and flushes) for the Merkle tree RocksDB.
+    /// If not specified, the number is derived from the number of CPUs.
+    #[serde(default)]
+    pub max_background_jobs: Option<usize>,
+    /// Whether to collect statistics for the Merkle tree RocksDB and export them as metrics. Collecting statistics
+    /// has a moderate performance overhead.
+    #[serde(default)]
+    pub rocksdb_statistics: bool,
 }
 
 impl Default for MerkleTreeConfig {
@@ -56,6 +134,14 @@ impl Default for MerkleTreeConfig {
             memtable_capacity_mb: Self::default_memtable_capacity_mb(),
             stalled_writes_timeout_sec: Self::default_stalled_writes_timeout_sec(),
             max_l1_batches_per_iter: Self::default_max_l1_batches_per_iter(),
+            pruning_retained_versions: None,
+            pruning_iteration_delay_ms: Self::default_pruning_iteration_delay_ms(),
+            thread_count: None,
+            consistency_check_interval_sec: None,
+            compaction_style: None,
+            write_buffer_size_mb: None,
+            max_background_jobs: None,
+            rocksdb_statistics: false,
         }
     }
 }
@@ -85,6 +171,10 @@ impl MerkleTreeConfig {
         20
     }
 
+    const fn default_pruning_iteration_delay_ms() -> u64 {
+        100
+    }
+
     /// Returns the size of block cache size for Merkle tree in bytes.
     pub fn block_cache_size(&self) -> usize {
         self.block_cache_size_mb * super::BYTES_IN_MEGABYTE
@@ -99,14 +189,55 @@ impl MerkleTreeConfig {
     pub fn stalled_writes_timeout(&self) -> Duration {
         Duration::from_secs(self.stalled_writes_timeout_sec)
     }
+
+    /// Returns the delay between consecutive pruning iterations.
+    pub fn pruning_iteration_delay(&self) -> Duration {
+        Duration::from_millis(self.pruning_iteration_delay_ms)
+    }
+
+    /// Returns the interval between background consistency checks, or `None` if checks are disabled.
+    pub fn consistency_check_interval(&self) -> Option<Duration> {
+        self.consistency_check_interval_sec.map(Duration::from_secs)
+    }
+
+    /// Returns RocksDB tuning options for the Merkle tree.
+    pub fn rocksdb_tuning(&self) -> RocksdbTuning {
+        RocksdbTuning {
+            compaction_style: self.compaction_style,
+            write_buffer_size: self
+                .write_buffer_size_mb
+                .map(|size| size * super::BYTES_IN_MEGABYTE),
+            max_background_jobs: self.max_background_jobs,
+            statistics: self.rocksdb_statistics,
+        }
+    }
 }
 
 /// Database configuration.
-#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema, Deserialize)]
 pub struct DBConfig {
     /// Path to the RocksDB data directory that serves state cache.
     #[serde(default = "DBConfig::default_state_keeper_db_path")]
     pub state_keeper_db_path: String,
+    /// Capacity of the block cache for the state keeper RocksDB cache. If not specified, the default RocksDB
+    /// block cache is used.
+    #[serde(default, deserialize_with = "crate::units::opt_byte_size_mb")]
+    #[schemars(with = "Option<crate::schema::WithUnit>")]
+    pub state_keeper_db_block_cache_size_mb: Option<usize>,
+    /// Compaction style for the state keeper RocksDB cache. If not specified, level-style compaction is used.
+    #[serde(default)]
+    pub state_keeper_db_compaction_style: Option<RocksdbCompactionStyle>,
+    /// Size of a single memtable for the state keeper RocksDB cache. If not specified, the RocksDB default is used.
+    #[serde(default, deserialize_with = "crate::units::opt_byte_size_mb")]
+    #[schemars(with = "Option<crate::schema::WithUnit>")]
+    pub state_keeper_db_write_buffer_size_mb: Option<usize>,
+    /// Maximum number of concurrent background jobs (compactions and flushes) for the state keeper RocksDB cache.
+    /// If not specified, the number is derived from the number of CPUs.
+    #[serde(default)]
+    pub state_keeper_db_max_background_jobs: Option<usize>,
+    /// Whether to collect statistics for the state keeper RocksDB cache and export them as metrics.
+    #[serde(default)]
+    pub state_keeper_db_rocksdb_statistics: bool,
     /// Merkle tree configuration.
     #[serde(skip)]
     // ^ Filled in separately in `Self::from_env()`. We cannot use `serde(flatten)` because it
@@ -118,17 +249,41 @@ impl DBConfig {
     fn default_state_keeper_db_path() -> String {
         "./db/state_keeper".to_owned()
     }
+
+    /// Returns the capacity of the block cache for the state keeper RocksDB cache in bytes.
+    pub fn state_keeper_db_block_cache_size(&self) -> Option<usize> {
+        self.state_keeper_db_block_cache_size_mb
+            .map(|size| size * super::BYTES_IN_MEGABYTE)
+    }
+
+    /// Returns RocksDB tuning options for the state keeper cache.
+    pub fn state_keeper_db_tuning(&self) -> RocksdbTuning {
+        RocksdbTuning {
+            compaction_style: self.state_keeper_db_compaction_style,
+            write_buffer_size: self
+                .state_keeper_db_write_buffer_size_mb
+                .map(|size| size * super::BYTES_IN_MEGABYTE),
+            max_background_jobs: self.state_keeper_db_max_background_jobs,
+            statistics: self.state_keeper_db_rocksdb_statistics,
+        }
+    }
 }
 
 /// Collection of different database URLs and general PostgreSQL options.
 /// All the entries are optional, since some components may only require a subset of them,
 /// and any component may have overrides.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema)]
 pub struct PostgresConfig {
     /// URL for the main (sequencer) database.
     pub master_url: Option<String>,
     /// URL for the replica database.
     pub replica_url: Option<String>,
+    /// URLs of read replicas of the replica database. If specified, read-only queries of the API servers
+    /// that tolerate slightly stale data (e.g., historical block and transaction reads) are routed to these replicas.
+    pub read_replica_urls: Vec<String>,
+    /// Maximum lag in milliseconds of a read replica for it to be used for reads. Stale replicas are not used
+    /// until they catch up. If not specified, the lag is limited to 1 second.
+    pub max_replica_lag_ms: Option<u64>,
     /// URL for the prover database.
     pub prover_url: Option<String>,
     /// Maximum size of the connection pool.
@@ -190,4 +345,9 @@ impl PostgresConfig {
     pub fn slow_query_threshold(&self) -> Option<Duration> {
         self.slow_query_threshold_ms.map(Duration::from_millis)
     }
+
+    /// Returns the maximum lag of a read replica for it to be used for reads.
+    pub fn max_replica_lag(&self) -> Option<Duration> {
+        self.max_replica_lag_ms.map(Duration::from_millis)
+    }
 }
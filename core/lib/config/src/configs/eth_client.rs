@@ -1,11 +1,53 @@
+use std::time::Duration;
+
 // External uses
-use serde::Deserialize;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 
 /// Configuration for the Ethereum gateways.
-#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[derive(Debug, Serialize, JsonSchema, Deserialize, Clone, PartialEq)]
 pub struct ETHClientConfig {
     /// Numeric identifier of the L1 network (e.g. `9` for localhost).
     pub chain_id: u64,
     /// Address of the Ethereum node API.
     pub web3_url: String,
+    /// Addresses of fallback Ethereum node APIs in the order of preference. If specified, requests are sent
+    /// to the first healthy node, starting from `web3_url`.
+    #[serde(default)]
+    pub fallback_web3_urls: Vec<String>,
+    /// Share of failed requests among recent requests to a node after which the node is considered unhealthy.
+    pub failover_error_rate_threshold: Option<f64>,
+    /// Number of blocks a node may lag behind the most recent head among all nodes before it's considered unhealthy.
+    pub failover_max_head_lag: Option<u64>,
+    /// Interval between health checks of nodes in milliseconds.
+    pub failover_health_check_interval_ms: Option<u64>,
+}
+
+impl ETHClientConfig {
+    const DEFAULT_FAILOVER_ERROR_RATE_THRESHOLD: f64 = 0.5;
+    const DEFAULT_FAILOVER_MAX_HEAD_LAG: u64 = 5;
+    const DEFAULT_FAILOVER_HEALTH_CHECK_INTERVAL_MS: u64 = 5_000;
+
+    /// Returns all node URLs in the order of preference.
+    pub fn web3_urls(&self) -> impl Iterator<Item = &str> + '_ {
+        std::iter::once(self.web3_url.as_str())
+            .chain(self.fallback_web3_urls.iter().map(String::as_str))
+    }
+
+    pub fn failover_error_rate_threshold(&self) -> f64 {
+        self.failover_error_rate_threshold
+            .unwrap_or(Self::DEFAULT_FAILOVER_ERROR_RATE_THRESHOLD)
+    }
+
+    pub fn failover_max_head_lag(&self) -> u64 {
+        self.failover_max_head_lag
+            .unwrap_or(Self::DEFAULT_FAILOVER_MAX_HEAD_LAG)
+    }
+
+    pub fn failover_health_check_interval(&self) -> Duration {
+        Duration::from_millis(
+            self.failover_health_check_interval_ms
+                .unwrap_or(Self::DEFAULT_FAILOVER_HEALTH_CHECK_INTERVAL_MS),
+        )
+    }
 }
@@ -1,15 +1,31 @@
 use std::time::Duration;
 
-use serde::Deserialize;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 use zksync_basic_types::H256;
 
 /// Configuration for the Ethereum sender crate.
-#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[derive(Debug, Serialize, JsonSchema, Deserialize, Clone, PartialEq)]
 pub struct ETHSenderConfig {
     /// Options related to the Ethereum sender directly.
     pub sender: SenderConfig,
     /// Options related to the `GasAdjuster` submodule.
     pub gas_adjuster: GasAdjusterConfig,
+    /// Signer used for L1 transactions sent on behalf of the operator.
+    #[serde(default)]
+    pub signer: OperatorSignerConfig,
+    /// Path used to submit L1 transactions sent on behalf of the operator.
+    #[serde(default)]
+    pub submission: TxSubmissionConfig,
+    /// Signer for the secondary operator account. If set, the operator can be rotated to this account
+    /// (and back) without stopping the server.
+    #[serde(default)]
+    pub secondary_signer: Option<OperatorSignerConfig>,
+    /// Signer for the account sending commit transactions (i.e., ones publishing pubdata). If set, commit transactions
+    /// are sent from this account with independently tracked nonces, so that pubdata costs are paid from a dedicated
+    /// account. Otherwise, commit transactions are sent from the operator account.
+    #[serde(default)]
+    pub blob_signer: Option<OperatorSignerConfig>,
 }
 
 impl ETHSenderConfig {
@@ -35,6 +51,11 @@ impl ETHSenderConfig {
                 l1_batch_min_age_before_execute_seconds: None,
                 max_acceptable_priority_fee_in_gwei: 100000000000,
                 proof_loading_mode: ProofLoadingMode::OldProofFromDb,
+                pubdata_sending_mode: PubdataSendingMode::Calldata,
+                max_tx_attempts: None,
+                fee_bump_percent: None,
+                max_fee_per_gas_cap: None,
+                min_blocks_between_resends: None,
             },
             gas_adjuster: GasAdjusterConfig {
                 default_priority_fee_per_gas: 1000000000,
@@ -45,25 +66,71 @@ impl ETHSenderConfig {
                 internal_enforced_l1_gas_price: None,
                 poll_period: 5,
                 max_l1_gas_price: None,
+                base_fee_estimation_mode: BaseFeeEstimationMode::Median,
+                base_fee_percentile: None,
+                base_fee_ewma_alpha: None,
+                external_price_api_url: None,
+                external_price_api_json_pointer: None,
+                external_price_api_multiplier: None,
+                external_price_api_max_age_sec: None,
             },
+            signer: OperatorSignerConfig::default(),
+            submission: TxSubmissionConfig::default(),
+            secondary_signer: None,
+            blob_signer: None,
         }
     }
+
+    // Don't load private key, if it's not required.
+    pub fn secondary_operator_private_key(&self) -> Option<H256> {
+        std::env::var("ETH_SENDER_SECONDARY_SIGNER_OPERATOR_PRIVATE_KEY")
+            .ok()
+            .map(|pk| pk.parse().unwrap())
+    }
+
+    // Don't load the PIN, if it's not required.
+    pub fn secondary_signer_pkcs11_pin(&self) -> Option<String> {
+        std::env::var("ETH_SENDER_SECONDARY_SIGNER_PKCS11_PIN").ok()
+    }
+
+    // Don't load private key, if it's not required.
+    pub fn blob_operator_private_key(&self) -> Option<H256> {
+        std::env::var("ETH_SENDER_BLOB_SIGNER_OPERATOR_PRIVATE_KEY")
+            .ok()
+            .map(|pk| pk.parse().unwrap())
+    }
+
+    // Don't load the PIN, if it's not required.
+    pub fn blob_signer_pkcs11_pin(&self) -> Option<String> {
+        std::env::var("ETH_SENDER_BLOB_SIGNER_PKCS11_PIN").ok()
+    }
 }
 
-#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[derive(Debug, Serialize, JsonSchema, Deserialize, Clone, Copy, PartialEq)]
 pub enum ProofSendingMode {
     OnlyRealProofs,
     OnlySampledProofs,
     SkipEveryProof,
 }
 
-#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[derive(Debug, Serialize, JsonSchema, Deserialize, Clone, Copy, PartialEq)]
 pub enum ProofLoadingMode {
     OldProofFromDb,
     FriProofFromGcs,
 }
 
-#[derive(Debug, Deserialize, Clone, PartialEq)]
+/// Where commit transactions put L1 batch pubdata.
+#[derive(Debug, Serialize, JsonSchema, Deserialize, Clone, Copy, PartialEq, Default)]
+pub enum PubdataSendingMode {
+    /// Pubdata is sent as a part of the commit transaction calldata.
+    #[default]
+    Calldata,
+    /// Pubdata is sent in EIP-4844 blobs attached to the commit transaction. Only supported
+    /// for L1 batches with protocol version 1.4.2 or newer; older batches are still committed using calldata.
+    Blobs,
+}
+
+#[derive(Debug, Serialize, JsonSchema, Deserialize, Clone, PartialEq)]
 pub struct SenderConfig {
     pub aggregated_proof_sizes: Vec<usize>,
     /// Amount of confirmations required to consider L1 transaction committed.
@@ -96,6 +163,21 @@ pub struct SenderConfig {
 
     /// The mode in which proofs are loaded, either from DB/GCS for FRI/Old proof.
     pub proof_loading_mode: ProofLoadingMode,
+
+    /// The mode in which L1 batch pubdata is published.
+    #[serde(default)]
+    pub pubdata_sending_mode: PubdataSendingMode,
+
+    /// Maximum number of sent attempts (including the first one) for an L1 transaction. Once it's reached,
+    /// the transaction is no longer resent with bumped fees. If not set, the number of attempts is unlimited.
+    pub max_tx_attempts: Option<u32>,
+    /// Minimum increase of the priority fee for each resent attempt, in percent. Values below 10 are treated as 10,
+    /// since L1 nodes reject replacement transactions with smaller fee bumps.
+    pub fee_bump_percent: Option<u64>,
+    /// Cap on `max_fee_per_gas` of sent L1 transactions, in wei. If not set, fees are not capped.
+    pub max_fee_per_gas_cap: Option<u64>,
+    /// Minimum number of L1 blocks between resent attempts for the same transaction.
+    pub min_blocks_between_resends: Option<u32>,
 }
 
 impl SenderConfig {
@@ -109,6 +191,14 @@ impl SenderConfig {
         Duration::from_secs(self.aggregate_tx_poll_period)
     }
 
+    pub fn fee_bump_percent(&self) -> u64 {
+        self.fee_bump_percent.unwrap_or(20).max(10)
+    }
+
+    pub fn min_blocks_between_resends(&self) -> u32 {
+        self.min_blocks_between_resends.unwrap_or(1).max(1)
+    }
+
     // Don't load private key, if it's not required.
     pub fn private_key(&self) -> Option<H256> {
         std::env::var("ETH_SENDER_SENDER_OPERATOR_PRIVATE_KEY")
@@ -117,7 +207,23 @@ impl SenderConfig {
     }
 }
 
-#[derive(Debug, Deserialize, Copy, Clone, PartialEq)]
+/// Method used by `GasAdjuster` to estimate the L1 base fee.
+#[derive(Debug, Serialize, JsonSchema, Deserialize, Clone, Copy, PartialEq, Default)]
+pub enum BaseFeeEstimationMode {
+    /// Median of base fees over the last `max_base_fee_samples` L1 blocks.
+    #[default]
+    Median,
+    /// Configurable percentile (`base_fee_percentile`) of base fees over the last `max_base_fee_samples` L1 blocks.
+    /// Percentiles above 50 make base fee estimates follow spikes more closely.
+    Percentile,
+    /// Exponentially weighted moving average of base fees with the smoothing factor `base_fee_ewma_alpha`.
+    Ewma,
+    /// Base fee reported by an external gas price API (`external_price_api_*` options). If the API is unavailable,
+    /// falls back to the median of base fees.
+    ExternalApi,
+}
+
+#[derive(Debug, Serialize, JsonSchema, Deserialize, Clone, PartialEq)]
 pub struct GasAdjusterConfig {
     /// Priority Fee to be used by GasAdjuster
     pub default_priority_fee_per_gas: u64,
@@ -135,6 +241,23 @@ pub struct GasAdjusterConfig {
     pub poll_period: u64,
     /// Max number of l1 gas price that is allowed to be used in state keeper.
     pub max_l1_gas_price: Option<u64>,
+    /// Method used to estimate the L1 base fee.
+    #[serde(default)]
+    pub base_fee_estimation_mode: BaseFeeEstimationMode,
+    /// Percentile of base fees used in the `Percentile` estimation mode, in the `0..=100` range.
+    pub base_fee_percentile: Option<f64>,
+    /// Smoothing factor used in the `Ewma` estimation mode, in the `(0, 1]` range. Larger values
+    /// make the estimate react faster to base fee changes.
+    pub base_fee_ewma_alpha: Option<f64>,
+    /// URL of the external gas price API returning a JSON document. Required in the `ExternalApi` estimation mode.
+    pub external_price_api_url: Option<String>,
+    /// JSON pointer (RFC 6901) to the base fee in the API response, e.g. `/result/suggestBaseFee`.
+    /// The value may be a number or a string containing a number. If not set, the entire response is used.
+    pub external_price_api_json_pointer: Option<String>,
+    /// Multiplier converting the value returned by the API to wei, e.g. `1e9` if the API reports base fees in gwei.
+    pub external_price_api_multiplier: Option<f64>,
+    /// Maximum age of the value fetched from the API, in seconds. Older values are not used.
+    pub external_price_api_max_age_sec: Option<u64>,
 }
 
 impl GasAdjusterConfig {
@@ -146,4 +269,106 @@ impl GasAdjusterConfig {
     pub fn max_l1_gas_price(&self) -> u64 {
         self.max_l1_gas_price.unwrap_or(u64::MAX)
     }
+
+    pub fn base_fee_percentile(&self) -> f64 {
+        self.base_fee_percentile.unwrap_or(50.0)
+    }
+
+    pub fn base_fee_ewma_alpha(&self) -> f64 {
+        self.base_fee_ewma_alpha.unwrap_or(0.2)
+    }
+
+    pub fn external_price_api_json_pointer(&self) -> &str {
+        self.external_price_api_json_pointer
+            .as_deref()
+            .unwrap_or("")
+    }
+
+    pub fn external_price_api_multiplier(&self) -> f64 {
+        self.external_price_api_multiplier.unwrap_or(1.0)
+    }
+
+    pub fn external_price_api_max_age(&self) -> Duration {
+        Duration::from_secs(self.external_price_api_max_age_sec.unwrap_or(60))
+    }
+}
+
+/// Backend holding the operator key and signing L1 transactions.
+#[derive(Debug, Serialize, JsonSchema, Deserialize, Clone, Copy, PartialEq, Default)]
+pub enum OperatorSignerBackend {
+    /// Private key loaded from the `ETH_SENDER_SENDER_OPERATOR_PRIVATE_KEY` env variable.
+    #[default]
+    PrivateKey,
+    /// Asymmetric AWS KMS key with the `ECC_SECG_P256K1` key spec.
+    AwsKms,
+    /// GCP Cloud KMS key version with the `EC_SIGN_SECP256K1_SHA256` algorithm.
+    GcpKms,
+    /// Key stored on a hardware token accessible via a PKCS#11 module.
+    Pkcs11,
+}
+
+/// Configuration of the signer for L1 transactions sent on behalf of the operator. Only the options
+/// relevant for the selected `backend` are required.
+#[derive(Debug, Serialize, JsonSchema, Deserialize, Clone, PartialEq, Default)]
+#[serde(default)]
+pub struct OperatorSignerConfig {
+    pub backend: OperatorSignerBackend,
+    /// ID, ARN or alias of the AWS KMS key.
+    pub aws_kms_key_id: Option<String>,
+    /// AWS region of the KMS key.
+    pub aws_region: Option<String>,
+    /// Resource name of the GCP KMS key version, i.e.
+    /// `projects/*/locations/*/keyRings/*/cryptoKeys/*/cryptoKeyVersions/*`.
+    pub gcp_kms_key_version: Option<String>,
+    /// Path to the PKCS#11 module (a shared library provided by the token vendor).
+    pub pkcs11_module_path: Option<String>,
+    /// ID of the PKCS#11 slot with the token.
+    pub pkcs11_slot_id: Option<u64>,
+    /// Label of the private and public key objects on the token.
+    pub pkcs11_key_label: Option<String>,
+}
+
+impl OperatorSignerConfig {
+    // Don't load the PIN, if it's not required.
+    pub fn pkcs11_pin(&self) -> Option<String> {
+        std::env::var("ETH_SENDER_SIGNER_PKCS11_PIN").ok()
+    }
+}
+
+/// Path used to submit L1 transactions.
+#[derive(Debug, Serialize, JsonSchema, Deserialize, Clone, Copy, PartialEq, Default)]
+pub enum TxSubmissionMode {
+    /// Transactions are broadcast to the public mempool via the L1 RPC node.
+    #[default]
+    PublicMempool,
+    /// Transactions are sent with `eth_sendRawTransaction` to a private RPC endpoint that doesn't propagate them
+    /// to the public mempool, such as Flashbots Protect.
+    PrivateRpc,
+    /// Each transaction is sent as a single-transaction bundle with `eth_sendBundle` to a bundle relay, such as
+    /// the Flashbots relay. Bundles are resubmitted on each L1 block until the transaction is mined.
+    Bundle,
+}
+
+/// Configuration of the path used to submit L1 transactions sent on behalf of the operator.
+#[derive(Debug, Serialize, JsonSchema, Deserialize, Clone, PartialEq, Default)]
+#[serde(default)]
+pub struct TxSubmissionConfig {
+    pub mode: TxSubmissionMode,
+    /// URL of the private RPC endpoint or the bundle relay. Required unless `mode` is `PublicMempool`.
+    pub relay_url: Option<String>,
+    /// Number of L1 blocks following the latest one targeted by each bundle submission.
+    pub bundle_target_blocks: Option<u64>,
+}
+
+impl TxSubmissionConfig {
+    pub fn bundle_target_blocks(&self) -> u64 {
+        self.bundle_target_blocks.unwrap_or(3).max(1)
+    }
+
+    // Don't load private key, if it's not required.
+    pub fn relay_auth_private_key(&self) -> Option<H256> {
+        std::env::var("ETH_SENDER_SUBMISSION_RELAY_AUTH_PRIVATE_KEY")
+            .ok()
+            .map(|pk| pk.parse().unwrap())
+    }
 }
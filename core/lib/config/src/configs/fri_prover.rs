@@ -1,15 +1,28 @@
 use std::time::Duration;
 
-use serde::Deserialize;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[derive(Debug, Serialize, JsonSchema, Deserialize, Clone, PartialEq)]
 pub enum SetupLoadMode {
     FromDisk,
     FromMemory,
 }
 
+/// Proving backend used by the prover.
+#[derive(Debug, Default, Serialize, JsonSchema, Deserialize, Clone, Copy, PartialEq)]
+pub enum ProverBackend {
+    /// Use GPU proving if CUDA devices are available, and fall back to CPU proving otherwise.
+    #[default]
+    Auto,
+    /// Always use GPU proving; fail on startup if no CUDA devices are available.
+    Gpu,
+    /// Always use CPU proving.
+    Cpu,
+}
+
 /// Configuration for the fri prover application
-#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[derive(Debug, Serialize, JsonSchema, Deserialize, Clone, PartialEq)]
 pub struct FriProverConfig {
     pub setup_data_path: String,
     pub prometheus_port: u16,
@@ -26,6 +39,9 @@ pub struct FriProverConfig {
 
     // whether to write to public GCS bucket for https://github.com/matter-labs/era-boojum-validator-cli
     pub shall_save_to_public_bucket: bool,
+    /// Proving backend. GPU proving requires the prover to be built with the `gpu` feature.
+    #[serde(default)]
+    pub backend: ProverBackend,
 }
 
 impl FriProverConfig {
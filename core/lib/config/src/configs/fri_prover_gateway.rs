@@ -1,8 +1,9 @@
 use std::time::Duration;
 
-use serde::Deserialize;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[derive(Debug, Serialize, JsonSchema, Deserialize, Clone, PartialEq)]
 pub struct FriProverGatewayConfig {
     pub api_url: String,
     pub api_poll_duration_secs: u16,
@@ -11,10 +12,24 @@ pub struct FriProverGatewayConfig {
     pub prometheus_listener_port: u16,
     pub prometheus_pushgateway_url: String,
     pub prometheus_push_interval_ms: Option<u64>,
+
+    /// Prover jobs for L1 batches that are not proven after this time since the gateway has fetched
+    /// their proof generation data are prioritized over other jobs. If not set, jobs are not prioritized based on their age.
+    pub proof_deadline_secs: Option<u64>,
+    /// L1 batches flagged as urgent by the operator. Prover jobs for these batches have the highest priority.
+    #[serde(default)]
+    pub urgent_l1_batches: Vec<u32>,
+    /// Port of the HTTP server exposing the prover queue state and the estimated time to proof
+    /// for the oldest unproven L1 batch. If not set, the server is not started.
+    pub capacity_api_port: Option<u16>,
 }
 
 impl FriProverGatewayConfig {
     pub fn api_poll_duration(&self) -> Duration {
         Duration::from_secs(self.api_poll_duration_secs as u64)
     }
+
+    pub fn proof_deadline(&self) -> Option<Duration> {
+        self.proof_deadline_secs.map(Duration::from_secs)
+    }
 }
@@ -1,7 +1,10 @@
-use serde::Deserialize;
+use std::time::Duration;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 
 /// Configuration for the house keeper.
-#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[derive(Debug, Serialize, JsonSchema, Deserialize, Clone, PartialEq)]
 pub struct HouseKeeperConfig {
     pub l1_batch_metrics_reporting_interval_ms: u64,
     pub gpu_prover_queue_reporting_interval_ms: u64,
@@ -16,4 +19,39 @@ pub struct HouseKeeperConfig {
     pub fri_prover_stats_reporting_interval_ms: u64,
     pub fri_proof_compressor_job_retrying_interval_ms: u64,
     pub fri_proof_compressor_stats_reporting_interval_ms: u64,
+    /// Retention period for prover artifacts (witness inputs, aggregation artifacts and proofs) in the object store,
+    /// counted from the moment an L1 batch is executed on L1. If not set, prover artifacts are never removed.
+    pub prover_artifacts_retention_period_hours: Option<u64>,
+    /// Interval between garbage collection runs for prover artifacts. Defaults to 1 minute.
+    pub prover_artifacts_gc_interval_ms: Option<u64>,
+    /// If set, garbage collection of prover artifacts only logs artifacts that would be removed.
+    #[serde(default)]
+    pub prover_artifacts_gc_dry_run: bool,
+    /// In-progress FRI prover jobs are returned to the queue if their workers haven't sent a heartbeat
+    /// for this period. Defaults to 1 minute.
+    pub fri_prover_heartbeat_timeout_ms: Option<u64>,
+}
+
+impl HouseKeeperConfig {
+    const DEFAULT_PROVER_ARTIFACTS_GC_INTERVAL_MS: u64 = 60_000;
+    const DEFAULT_FRI_PROVER_HEARTBEAT_TIMEOUT_MS: u64 = 60_000;
+
+    pub fn prover_artifacts_retention_period(&self) -> Option<Duration> {
+        self.prover_artifacts_retention_period_hours
+            .map(|hours| Duration::from_secs(hours * 3_600))
+    }
+
+    pub fn prover_artifacts_gc_interval(&self) -> Duration {
+        Duration::from_millis(
+            self.prover_artifacts_gc_interval_ms
+                .unwrap_or(Self::DEFAULT_PROVER_ARTIFACTS_GC_INTERVAL_MS),
+        )
+    }
+
+    pub fn fri_prover_heartbeat_timeout(&self) -> Duration {
+        Duration::from_millis(
+            self.fri_prover_heartbeat_timeout_ms
+                .unwrap_or(Self::DEFAULT_FRI_PROVER_HEARTBEAT_TIMEOUT_MS),
+        )
+    }
 }
@@ -16,6 +16,7 @@ pub use self::{
     object_store::ObjectStoreConfig,
     observability::ObservabilityConfig,
     proof_data_handler::ProofDataHandlerConfig,
+    shutdown::ShutdownConfig,
     snapshots_creator::SnapshotsCreatorConfig,
     utils::PrometheusConfig,
     witness_generator::WitnessGeneratorConfig,
@@ -40,6 +41,7 @@ pub mod house_keeper;
 pub mod object_store;
 pub mod observability;
 pub mod proof_data_handler;
+pub mod shutdown;
 pub mod snapshots_creator;
 pub mod utils;
 pub mod witness_generator;
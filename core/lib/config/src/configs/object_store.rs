@@ -1,21 +1,123 @@
-use serde::Deserialize;
+use std::time::Duration;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 
 /// Configuration for the object store
-#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema, Deserialize)]
 pub struct ObjectStoreConfig {
     #[serde(flatten)]
     pub mode: ObjectStoreMode,
+    /// Maximum number of retries for a request failing with a transient error.
     #[serde(default = "ObjectStoreConfig::default_max_retries")]
     pub max_retries: u16,
+    /// Codec used to compress blobs on the client side before storing them. Compressed blobs are detected
+    /// and decompressed transparently on read regardless of this setting, so compression can be enabled
+    /// for a store already containing uncompressed blobs.
+    #[serde(default)]
+    pub compression: ObjectStoreCompression,
+    /// Compression level for the codec. If not specified, the default level for the codec is used.
+    pub compression_level: Option<i32>,
+    /// Backoff before the first retry of a failed request. The backoff is doubled (with jitter) for each
+    /// subsequent retry. Defaults to 1 second.
+    pub retry_initial_backoff_ms: Option<u64>,
+    /// Upper bound for the backoff between retries. Defaults to 30 seconds.
+    pub retry_max_backoff_ms: Option<u64>,
+    /// Maximum number of retries that can be performed in a burst. Once the budget is exhausted, retries are limited
+    /// to ~10% of requests. Defaults to 100.
+    pub retry_budget: Option<u32>,
+    /// Number of consecutive failed requests after which the circuit breaker opens, i.e., requests start failing
+    /// immediately. Setting this to 0 disables the circuit breaker. Defaults to 20.
+    pub circuit_breaker_failure_threshold: Option<u32>,
+    /// Duration for which the circuit breaker stays open before allowing a trial request. Defaults to 30 seconds.
+    pub circuit_breaker_reset_timeout_ms: Option<u64>,
+    /// Client-side envelope encryption of blobs. Encrypted blobs are detected and decrypted on read
+    /// regardless of the bucket, so encryption can be enabled for a store already containing plaintext blobs.
+    #[serde(default)]
+    pub encryption: ObjectStoreEncryption,
+    /// Names of buckets (e.g., `witness_inputs`) in which blobs are encrypted. If not specified, blobs
+    /// in all buckets are encrypted.
+    pub encrypted_buckets: Option<Vec<String>>,
+    /// Hex-encoded 256-bit key used to wrap data keys. Required for [`ObjectStoreEncryption::LocalKey`].
+    pub encryption_key: Option<String>,
+    /// ID or ARN of the symmetric AWS KMS key used to wrap data keys. Required for [`ObjectStoreEncryption::AwsKms`].
+    pub encryption_kms_key_id: Option<String>,
+    /// AWS region of the KMS key. If not specified, the region is taken from the default AWS config.
+    pub encryption_kms_region: Option<String>,
+    /// If set, reading a plaintext blob from an encrypted bucket results in an error instead of returning
+    /// the blob as is. Should only be enabled once all blobs in encrypted buckets are encrypted.
+    #[serde(default)]
+    pub encryption_strict: bool,
+}
+
+/// Client-side compression codec for [`ObjectStoreConfig`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, JsonSchema, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ObjectStoreCompression {
+    /// Blobs are stored as is.
+    #[default]
+    None,
+    /// Blobs are compressed using Zstandard.
+    Zstd,
+}
+
+/// Client-side encryption for [`ObjectStoreConfig`]. Blobs are encrypted with AES-256-GCM using data keys,
+/// which are wrapped by the specified key management service.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, JsonSchema, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ObjectStoreEncryption {
+    /// Blobs are stored unencrypted.
+    #[default]
+    None,
+    /// Data keys are wrapped with a static key provided in the config.
+    LocalKey,
+    /// Data keys are generated and wrapped by AWS KMS.
+    AwsKms,
 }
 
 impl ObjectStoreConfig {
+    /// Names of buckets that can be specified in [`Self::encrypted_buckets`].
+    pub const BUCKET_NAMES: &'static [&'static str] = &[
+        "prover_jobs",
+        "witness_inputs",
+        "leaf_aggregation_witness_jobs",
+        "node_aggregation_witness_jobs",
+        "scheduler_witness_jobs",
+        "prover_jobs_fri",
+        "leaf_aggregation_witness_jobs_fri",
+        "node_aggregation_witness_jobs_fri",
+        "scheduler_witness_jobs_fri",
+        "proofs_fri",
+        "storage_logs_snapshots",
+        "merkle_tree_snapshots",
+    ];
+
     const fn default_max_retries() -> u16 {
         5
     }
+
+    pub fn retry_initial_backoff(&self) -> Duration {
+        Duration::from_millis(self.retry_initial_backoff_ms.unwrap_or(1_000))
+    }
+
+    pub fn retry_max_backoff(&self) -> Duration {
+        Duration::from_millis(self.retry_max_backoff_ms.unwrap_or(30_000))
+    }
+
+    pub fn retry_budget(&self) -> u32 {
+        self.retry_budget.unwrap_or(100)
+    }
+
+    pub fn circuit_breaker_failure_threshold(&self) -> u32 {
+        self.circuit_breaker_failure_threshold.unwrap_or(20)
+    }
+
+    pub fn circuit_breaker_reset_timeout(&self) -> Duration {
+        Duration::from_millis(self.circuit_breaker_reset_timeout_ms.unwrap_or(30_000))
+    }
 }
 
-#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema, Deserialize)]
 #[serde(tag = "mode")]
 pub enum ObjectStoreMode {
     GCS {
@@ -31,4 +133,44 @@ pub enum ObjectStoreMode {
     FileBacked {
         file_backed_base_path: String,
     },
+    /// Azure Blob Storage authenticated via a managed identity, or other credentials from the default
+    /// Azure credential chain.
+    AzureBlobWithManagedIdentity {
+        azure_account_name: String,
+        azure_container_name: String,
+    },
+    /// Azure Blob Storage authenticated via a storage account connection string.
+    AzureBlobWithConnectionString {
+        azure_container_name: String,
+        azure_connection_string: String,
+    },
+    /// AWS S3 or an S3-compatible store (e.g., MinIO or Ceph RGW). If static credentials are not specified,
+    /// they are taken from the default AWS credential chain (env variables, profile files, IAM roles etc.).
+    S3 {
+        s3_bucket: String,
+        s3_region: String,
+        /// Custom endpoint URL of an S3-compatible store, e.g. `http://minio:9000`.
+        s3_endpoint: Option<String>,
+        /// Addressing style of buckets. Defaults to virtual-hosted style, which is not supported by most
+        /// self-hosted stores.
+        s3_addressing_style: Option<S3AddressingStyle>,
+        s3_access_key_id: Option<String>,
+        s3_secret_access_key: Option<String>,
+    },
+    /// Read-only HTTP(S) mirror of a store, e.g. a CDN serving published snapshots. Blobs are fetched
+    /// from `{http_mirror_base_url}/{bucket}/{key}`.
+    HttpMirrorReadOnly {
+        http_mirror_base_url: String,
+    },
+}
+
+/// Addressing style of buckets in S3-compatible stores.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, JsonSchema, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum S3AddressingStyle {
+    /// Buckets are addressed as subdomains: `https://{bucket}.{endpoint}/{key}`.
+    #[default]
+    VirtualHosted,
+    /// Buckets are addressed as path segments: `https://{endpoint}/{bucket}/{key}`.
+    Path,
 }
@@ -1,11 +1,52 @@
+use schemars::JsonSchema;
+use serde::Serialize;
+
 /// Configuration for the essential observability stack, like
 /// logging and sentry integration.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, JsonSchema)]
 pub struct ObservabilityConfig {
     /// URL of the Sentry instance to send events to.
     pub sentry_url: Option<String>,
     /// Name of the environment to use in Sentry.
     pub sentry_environment: Option<String>,
+    /// Fraction of error events sent to Sentry (from 0.0 to 1.0). If not set, all events are sent.
+    pub sentry_sample_rate: Option<f32>,
+    /// Fraction of traces (transactions created from `tracing` spans) sent to Sentry (from 0.0 to 1.0).
+    /// If not set, tracing is disabled.
+    pub sentry_traces_sample_rate: Option<f32>,
+    /// Per-component filter for log events reported to Sentry as errors, in the `RUST_LOG`-like format
+    /// (e.g., `error,zksync_core::api_server=off`). If not set, log events are not reported;
+    /// panics and explicitly captured messages are reported regardless of this filter.
+    pub sentry_error_filter: Option<String>,
+    /// OTLP/HTTP endpoint to export traces to (e.g., `http://localhost:4318/v1/traces`).
+    /// If not set, traces are not exported.
+    pub otlp_url: Option<String>,
+    /// Fraction of traces exported via OTLP (from 0.0 to 1.0). If not set, all traces are exported.
+    pub otlp_sampling_ratio: Option<f64>,
+    /// Additional OpenTelemetry resource attributes as comma-separated `key=value` pairs
+    /// (e.g., `deployment.environment=mainnet`).
+    pub otlp_resource_attributes: Option<String>,
+    /// Per-target log directives in the `RUST_LOG` format (e.g., `zksync_state_keeper=debug,zksync_dal=warn`)
+    /// applied on top of `RUST_LOG`. Each directive replaces the `RUST_LOG` directive for the same target.
+    /// Can be changed at runtime by reloading the config.
+    pub log_directives: Option<String>,
+    /// Path to the file logs are written to in addition to stdout. Rotated files are stored next to it
+    /// with numeric suffixes (`.1`, `.2`, ...). If not set, logs are only written to stdout.
+    pub log_file_path: Option<String>,
+    /// Time-based rotation of the log file: `never` (default), `hourly` or `daily`.
+    pub log_file_rotation: Option<String>,
+    /// Size of the log file in bytes after which it is rotated. If not set, the file is not rotated based on size.
+    #[schemars(with = "Option<crate::schema::WithUnit>")]
+    pub log_file_max_size_bytes: Option<u64>,
+    /// Maximum number of rotated log files to retain. Default is 10.
+    pub log_file_max_files: Option<usize>,
+    /// Interval for throttling repetitive warning and error logs, in seconds. Within each interval, at most
+    /// `log_throttle_burst` identical messages are logged, and the number of suppressed messages is reported
+    /// in the `suppressed_count` field of the next logged message. If not set, logs are not throttled.
+    #[schemars(with = "Option<crate::schema::WithUnit>")]
+    pub log_throttle_interval_sec: Option<u64>,
+    /// Number of identical messages logged per throttling interval. Default is 10.
+    pub log_throttle_burst: Option<u32>,
     /// Format of the logs as expected by the `vlog` crate.
     /// Currently must be either `plain` or `json`.
     pub log_format: String,
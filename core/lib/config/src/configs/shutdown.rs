@@ -0,0 +1,23 @@
+use std::time::Duration;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Configuration for the graceful shutdown of the node.
+#[derive(Debug, Default, Serialize, JsonSchema, Deserialize, Clone, PartialEq)]
+pub struct ShutdownConfig {
+    /// Deadline for all components to stop after a stop signal is received. Components that haven't stopped
+    /// by the deadline are reported and abandoned.
+    #[serde(default, deserialize_with = "crate::units::opt_duration_ms")]
+    #[schemars(with = "Option<crate::schema::WithUnit>")]
+    pub timeout_ms: Option<u64>,
+}
+
+impl ShutdownConfig {
+    const DEFAULT_TIMEOUT: Duration = Duration::from_secs(20);
+
+    pub fn timeout(&self) -> Duration {
+        self.timeout_ms
+            .map_or(Self::DEFAULT_TIMEOUT, Duration::from_millis)
+    }
+}
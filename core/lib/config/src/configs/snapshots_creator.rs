@@ -1,12 +1,42 @@
-use serde::Deserialize;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema, Deserialize)]
 pub struct SnapshotsCreatorConfig {
     #[serde(default = "snapshots_creator_storage_logs_chunk_size_default")]
     pub storage_logs_chunk_size: u64,
 
     #[serde(default = "snapshots_creator_concurrent_queries_count")]
     pub concurrent_queries_count: u32,
+
+    /// Cron-like schedule for creating snapshots, e.g. `0 */6 * * *` to create a snapshot every 6 hours.
+    /// Consists of 5 fields (minute, hour, day of month, month, day of week) interpreted in UTC.
+    /// If not set, the creator creates a single snapshot and exits.
+    #[serde(default)]
+    pub schedule: Option<String>,
+    /// If set, snapshots are created incrementally: storage logs chunks not changed since the previous snapshot
+    /// are reused from it instead of being produced again.
+    #[serde(default)]
+    pub incremental: bool,
+    /// Maximum number of consecutive incremental snapshots; after that, a full snapshot is created.
+    /// Bounds the age of objects referenced by incremental snapshots. Defaults to 24.
+    pub max_incremental_chain_length: Option<u32>,
+    /// Whether to download storage logs chunks reused by an incremental snapshot and verify their checksums.
+    /// Chunks failing verification are produced again. Defaults to `true`.
+    pub verify_reused_chunks: Option<bool>,
+}
+
+impl SnapshotsCreatorConfig {
+    const DEFAULT_MAX_INCREMENTAL_CHAIN_LENGTH: u32 = 24;
+
+    pub fn max_incremental_chain_length(&self) -> u32 {
+        self.max_incremental_chain_length
+            .unwrap_or(Self::DEFAULT_MAX_INCREMENTAL_CHAIN_LENGTH)
+    }
+
+    pub fn verify_reused_chunks(&self) -> bool {
+        self.verify_reused_chunks.unwrap_or(true)
+    }
 }
 
 fn snapshots_creator_storage_logs_chunk_size_default() -> u64 {
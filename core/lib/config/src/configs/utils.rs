@@ -1,8 +1,9 @@
-use std::{env, time::Duration};
+use std::{env, fmt::Write as _, time::Duration};
 
-use serde::Deserialize;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[derive(Debug, Serialize, JsonSchema, Deserialize, Clone, PartialEq)]
 pub struct PrometheusConfig {
     /// Port to which the Prometheus exporter server is listening.
     pub listener_port: u16,
@@ -10,6 +11,11 @@ pub struct PrometheusConfig {
     pub pushgateway_url: String,
     /// Push interval in ms.
     pub push_interval_ms: Option<u64>,
+    /// Job name for metrics pushed to the push gateway. If not set, a component-specific default is used.
+    pub pushgateway_job: Option<String>,
+    /// Additional labels grouping metrics pushed to the push gateway, as comma-separated `key=value` pairs
+    /// (e.g., `network=mainnet,run=nightly`). Label values must not contain `/`.
+    pub pushgateway_labels: Option<String>,
 }
 
 impl PrometheusConfig {
@@ -19,11 +25,32 @@ impl PrometheusConfig {
 
     /// Returns the full endpoint URL for the push gateway.
     pub fn gateway_endpoint(&self) -> String {
+        self.gateway_endpoint_for_job("zksync-pushgateway")
+    }
+
+    /// Returns the full endpoint URL for the push gateway using the specified job name, unless it is overridden
+    /// in the config. Besides [configured labels](Self::pushgateway_labels), metrics are grouped by
+    /// the `namespace` and `pod` labels taken from the `POD_NAMESPACE` and `POD_NAME` env variables.
+    pub fn gateway_endpoint_for_job(&self, default_job: &str) -> String {
         let gateway_url = &self.pushgateway_url;
-        let job_id = "zksync-pushgateway";
+        let job_id = self.pushgateway_job.as_deref().unwrap_or(default_job);
         let namespace =
             env::var("POD_NAMESPACE").unwrap_or_else(|_| "UNKNOWN_NAMESPACE".to_owned());
         let pod = env::var("POD_NAME").unwrap_or_else(|_| "UNKNOWN_POD".to_owned());
-        format!("{gateway_url}/metrics/job/{job_id}/namespace/{namespace}/pod/{pod}")
+        let mut endpoint =
+            format!("{gateway_url}/metrics/job/{job_id}/namespace/{namespace}/pod/{pod}");
+        for (name, value) in self.pushgateway_labels() {
+            write!(endpoint, "/{name}/{value}").unwrap();
+        }
+        endpoint
+    }
+
+    /// Returns additional labels for pushed metrics. Malformed labels are skipped.
+    pub fn pushgateway_labels(&self) -> impl Iterator<Item = (&str, &str)> + '_ {
+        let labels = self.pushgateway_labels.as_deref().unwrap_or_default();
+        labels.split(',').filter_map(|label| {
+            let (name, value) = label.split_once('=')?;
+            Some((name.trim(), value.trim()))
+        })
     }
 }
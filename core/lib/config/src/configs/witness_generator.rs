@@ -2,9 +2,10 @@ use std::time::Duration;
 
 // Built-in uses
 // External uses
-use serde::Deserialize;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[derive(Debug, Serialize, JsonSchema, Deserialize, Clone, PartialEq)]
 pub enum BasicWitnessGeneratorDataSource {
     FromPostgres,
     FromPostgresShadowBlob,
@@ -12,7 +13,7 @@ pub enum BasicWitnessGeneratorDataSource {
 }
 
 /// Configuration for the witness generation
-#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[derive(Debug, Serialize, JsonSchema, Deserialize, Clone, PartialEq)]
 pub struct WitnessGeneratorConfig {
     /// Max time for witness to be generated
     pub generation_timeout_in_secs: u16,
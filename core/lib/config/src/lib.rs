@@ -6,4 +6,7 @@ pub use crate::configs::{
 };
 
 pub mod configs;
+pub mod schema;
 pub mod testonly;
+pub mod units;
+pub mod validation;
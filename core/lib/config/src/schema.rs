@@ -0,0 +1,329 @@
+//! JSON Schema for component configs.
+//!
+//! [`export()`] produces a single JSON Schema (draft 7) describing all configs loaded by the server and its components.
+//! Besides standard keywords (types, defaults from `#[serde(default)]`, docs as descriptions, etc.), the schema
+//! contains the following extensions:
+//!
+//! - `x-env-prefix` on each top-level config specifies the prefix of env variables for the config.
+//! - `x-env-var` on each config field specifies the env variable the field is read from. Nested configs
+//!   (e.g., `api.web3_json_rpc`) have `x-env-prefix` instead, obtained by appending `_` to the parent variable name.
+
+use schemars::{
+    gen::{SchemaGenerator, SchemaSettings},
+    schema::{InstanceType, Metadata, NumberValidation, Schema, SchemaObject, SubschemaValidation},
+    JsonSchema,
+};
+use serde_json::{json, Map, Value};
+
+use crate::configs::{
+    chain::{
+        BaseTokenConfig, CircuitBreakerConfig, FeeInputConfig, MempoolConfig, NetworkConfig,
+        OperationsManagerConfig, StateKeeperConfig,
+    },
+    database::MerkleTreeConfig,
+    house_keeper::HouseKeeperConfig,
+    AlertsConfig, ApiConfig, ContractVerifierConfig, ContractsConfig, DBConfig, ETHClientConfig,
+    ETHSenderConfig, ETHWatchConfig, FriProofCompressorConfig, FriProverConfig,
+    FriProverGatewayConfig, FriWitnessGeneratorConfig, FriWitnessVectorGeneratorConfig,
+    ObjectStoreConfig, ObservabilityConfig, PostgresConfig, ProofDataHandlerConfig, ShutdownConfig,
+    SnapshotsCreatorConfig, WitnessGeneratorConfig,
+};
+
+/// Schema for durations and byte sizes parsed using [`crate::units`]: either a bare integer in the field unit,
+/// or a string with an explicit unit.
+#[derive(Debug)]
+pub struct WithUnit(());
+
+impl JsonSchema for WithUnit {
+    fn schema_name() -> String {
+        "WithUnit".to_owned()
+    }
+
+    fn json_schema(_gen: &mut SchemaGenerator) -> Schema {
+        let integer = SchemaObject {
+            instance_type: Some(InstanceType::Integer.into()),
+            number: Some(Box::new(NumberValidation {
+                minimum: Some(0.0),
+                ..NumberValidation::default()
+            })),
+            ..SchemaObject::default()
+        };
+        let string = SchemaObject {
+            instance_type: Some(InstanceType::String.into()),
+            metadata: Some(Box::new(Metadata {
+                description: Some("Value with an explicit unit, e.g. `30s` or `512MiB`".to_owned()),
+                ..Metadata::default()
+            })),
+            ..SchemaObject::default()
+        };
+        let schema = SchemaObject {
+            subschemas: Some(Box::new(SubschemaValidation {
+                any_of: Some(vec![integer.into(), string.into()]),
+                ..SubschemaValidation::default()
+            })),
+            ..SchemaObject::default()
+        };
+        schema.into()
+    }
+}
+
+/// Description of a config included into the exported schema.
+struct ConfigEntry {
+    name: &'static str,
+    env_prefix: &'static str,
+    /// Fields not following the `{env_prefix}{FIELD}` naming. `None` means that the field is not read
+    /// from a dedicated env variable (e.g., it is derived from other variables).
+    aliases: &'static [(&'static str, Option<&'static str>)],
+    schema: fn(&mut SchemaGenerator) -> Value,
+}
+
+impl ConfigEntry {
+    fn new<C: JsonSchema>(name: &'static str, env_prefix: &'static str) -> Self {
+        Self {
+            name,
+            env_prefix,
+            aliases: &[],
+            schema: |gen| {
+                let schema = gen.subschema_for::<C>();
+                serde_json::to_value(schema).expect("failed serializing JSON schema")
+            },
+        }
+    }
+
+    fn with_aliases(mut self, aliases: &'static [(&'static str, Option<&'static str>)]) -> Self {
+        self.aliases = aliases;
+        self
+    }
+}
+
+fn config_entries() -> Vec<ConfigEntry> {
+    vec![
+        ConfigEntry::new::<AlertsConfig>("alerts", "ALERTS_"),
+        ConfigEntry::new::<ApiConfig>("api", "API_"),
+        ConfigEntry::new::<NetworkConfig>("network", "CHAIN_ETH_"),
+        ConfigEntry::new::<StateKeeperConfig>("state_keeper", "CHAIN_STATE_KEEPER_"),
+        ConfigEntry::new::<OperationsManagerConfig>(
+            "operations_manager",
+            "CHAIN_OPERATIONS_MANAGER_",
+        ),
+        ConfigEntry::new::<CircuitBreakerConfig>("circuit_breaker", "CHAIN_CIRCUIT_BREAKER_"),
+        ConfigEntry::new::<MempoolConfig>("mempool", "CHAIN_MEMPOOL_"),
+        ConfigEntry::new::<BaseTokenConfig>("base_token", "CHAIN_BASE_TOKEN_"),
+        ConfigEntry::new::<FeeInputConfig>("fee_input", "CHAIN_FEE_INPUT_"),
+        ConfigEntry::new::<ContractVerifierConfig>("contract_verifier", "CONTRACT_VERIFIER_"),
+        ConfigEntry::new::<ContractsConfig>("contracts", "CONTRACTS_"),
+        ConfigEntry::new::<DBConfig>("database", "DATABASE_"),
+        ConfigEntry::new::<MerkleTreeConfig>("database_merkle_tree", "DATABASE_MERKLE_TREE_"),
+        ConfigEntry::new::<PostgresConfig>("postgres", "DATABASE_").with_aliases(&[
+            ("master_url", Some("DATABASE_URL")),
+            ("max_connections", Some("DATABASE_POOL_SIZE")),
+        ]),
+        ConfigEntry::new::<ETHClientConfig>("eth_client", "ETH_CLIENT_"),
+        ConfigEntry::new::<ETHSenderConfig>("eth_sender", "ETH_SENDER_"),
+        ConfigEntry::new::<ETHWatchConfig>("eth_watch", "ETH_WATCH_"),
+        ConfigEntry::new::<FriProofCompressorConfig>(
+            "fri_proof_compressor",
+            "FRI_PROOF_COMPRESSOR_",
+        ),
+        ConfigEntry::new::<FriProverConfig>("fri_prover", "FRI_PROVER_"),
+        ConfigEntry::new::<FriProverGatewayConfig>("fri_prover_gateway", "FRI_PROVER_GATEWAY_"),
+        ConfigEntry::new::<FriWitnessGeneratorConfig>("fri_witness", "FRI_WITNESS_"),
+        ConfigEntry::new::<FriWitnessVectorGeneratorConfig>(
+            "fri_witness_vector_generator",
+            "FRI_WITNESS_VECTOR_GENERATOR_",
+        ),
+        ConfigEntry::new::<HouseKeeperConfig>("house_keeper", "HOUSE_KEEPER_"),
+        ConfigEntry::new::<ObjectStoreConfig>("object_store", "OBJECT_STORE_"),
+        ConfigEntry::new::<ObjectStoreConfig>("public_object_store", "PUBLIC_OBJECT_STORE_"),
+        ConfigEntry::new::<ObjectStoreConfig>("prover_object_store", "PROVER_OBJECT_STORE_"),
+        ConfigEntry::new::<ObjectStoreConfig>("snapshots_object_store", "SNAPSHOTS_OBJECT_STORE_"),
+        ConfigEntry::new::<ObservabilityConfig>("observability", "MISC_"),
+        ConfigEntry::new::<ProofDataHandlerConfig>("proof_data_handler", "PROOF_DATA_HANDLER_"),
+        ConfigEntry::new::<SnapshotsCreatorConfig>("snapshots_creator", "SNAPSHOTS_CREATOR_"),
+        ConfigEntry::new::<ShutdownConfig>("shutdown", "SHUTDOWN_"),
+        ConfigEntry::new::<WitnessGeneratorConfig>("witness", "WITNESS_"),
+    ]
+}
+
+/// Exports JSON Schema for all component configs. See the [module-level docs](self) for details.
+pub fn export() -> Value {
+    let mut gen = SchemaSettings::draft07()
+        .with(|settings| settings.inline_subschemas = true)
+        .into_generator();
+
+    let mut properties = Map::new();
+    for entry in config_entries() {
+        let mut schema = (entry.schema)(&mut gen);
+        annotate_env_vars(&mut schema, entry.env_prefix, entry.aliases);
+        if let Value::Object(schema) = &mut schema {
+            schema.insert("x-env-prefix".to_owned(), entry.env_prefix.into());
+        }
+        properties.insert(entry.name.to_owned(), schema);
+    }
+
+    json!({
+        "$schema": "http://json-schema.org/draft-07/schema#",
+        "title": "zkSync component configs",
+        "type": "object",
+        "properties": properties,
+    })
+}
+
+fn annotate_env_vars(
+    schema: &mut Value,
+    prefix: &str,
+    aliases: &[(&'static str, Option<&'static str>)],
+) {
+    let Value::Object(schema) = schema else {
+        return;
+    };
+
+    if let Some(Value::Object(properties)) = schema.get_mut("properties") {
+        for (field, field_schema) in properties {
+            let env_var = match aliases.iter().find(|(name, _)| *name == field.as_str()) {
+                Some((_, env_var)) => env_var.map(str::to_owned),
+                None => Some(format!("{prefix}{}", field.to_uppercase())),
+            };
+            let Some(env_var) = env_var else {
+                continue;
+            };
+
+            if field_schema.get("properties").is_some() {
+                let nested_prefix = format!("{env_var}_");
+                annotate_env_vars(field_schema, &nested_prefix, &[]);
+                if let Value::Object(field_schema) = field_schema {
+                    field_schema.insert("x-env-prefix".to_owned(), nested_prefix.into());
+                }
+            } else if let Value::Object(field_schema) = field_schema {
+                field_schema.insert("x-env-var".to_owned(), env_var.into());
+            }
+        }
+    }
+
+    // Flattened enums (e.g., object store modes) are represented as subschemas.
+    for keyword in ["anyOf", "oneOf", "allOf"] {
+        if let Some(Value::Array(subschemas)) = schema.get_mut(keyword) {
+            for subschema in subschemas {
+                annotate_env_vars(subschema, prefix, aliases);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn collect_env_vars(schema: &Value, env_vars: &mut Vec<String>) {
+        match schema {
+            Value::Object(map) => {
+                if let Some(Value::String(env_var)) = map.get("x-env-var") {
+                    env_vars.push(env_var.clone());
+                }
+                for value in map.values() {
+                    collect_env_vars(value, env_vars);
+                }
+            }
+            Value::Array(values) => {
+                for value in values {
+                    collect_env_vars(value, env_vars);
+                }
+            }
+            _ => { /* do nothing */ }
+        }
+    }
+
+    #[test]
+    fn exported_schema_contains_all_configs() {
+        let schema = export();
+        assert_eq!(schema["$schema"], "http://json-schema.org/draft-07/schema#");
+        assert_eq!(schema["type"], "object");
+
+        let properties = schema["properties"].as_object().unwrap();
+        let entries = config_entries();
+        assert_eq!(properties.len(), entries.len());
+        for entry in &entries {
+            let config_schema = &properties[entry.name];
+            assert_eq!(
+                config_schema["x-env-prefix"], entry.env_prefix,
+                "{}",
+                entry.name
+            );
+        }
+    }
+
+    #[test]
+    fn env_vars_are_annotated_in_exported_schema() {
+        let schema = export();
+        let postgres = &schema["properties"]["postgres"]["properties"];
+        assert_eq!(postgres["master_url"]["x-env-var"], "DATABASE_URL");
+        assert_eq!(postgres["replica_url"]["x-env-var"], "DATABASE_REPLICA_URL");
+        assert_eq!(
+            postgres["max_connections"]["x-env-var"],
+            "DATABASE_POOL_SIZE"
+        );
+
+        let web3_json_rpc = &schema["properties"]["api"]["properties"]["web3_json_rpc"];
+        assert_eq!(web3_json_rpc["x-env-prefix"], "API_WEB3_JSON_RPC_");
+        assert_eq!(
+            web3_json_rpc["properties"]["http_port"]["x-env-var"],
+            "API_WEB3_JSON_RPC_HTTP_PORT"
+        );
+        assert!(web3_json_rpc.get("x-env-var").is_none());
+
+        // Fields of flattened enums are annotated as well.
+        let mut env_vars = vec![];
+        collect_env_vars(&schema["properties"]["object_store"], &mut env_vars);
+        for expected_var in [
+            "OBJECT_STORE_MODE",
+            "OBJECT_STORE_BUCKET_BASE_URL",
+            "OBJECT_STORE_FILE_BACKED_BASE_PATH",
+            "OBJECT_STORE_MAX_RETRIES",
+        ] {
+            assert!(
+                env_vars.iter().any(|var| var == expected_var),
+                "{expected_var} is missing: {env_vars:?}"
+            );
+        }
+        assert!(env_vars.iter().all(|var| var.starts_with("OBJECT_STORE_")));
+    }
+
+    #[test]
+    fn annotating_env_vars_with_aliases() {
+        let mut schema = json!({
+            "type": "object",
+            "properties": {
+                "port": { "type": "integer" },
+                "url": { "type": "string" },
+                "derived": { "type": "string" },
+                "nested": {
+                    "type": "object",
+                    "properties": {
+                        "timeout_ms": { "type": "integer" },
+                    },
+                },
+            },
+        });
+        annotate_env_vars(
+            &mut schema,
+            "TEST_",
+            &[("url", Some("TEST_DATABASE_URL")), ("derived", None)],
+        );
+
+        assert_eq!(
+            schema["properties"],
+            json!({
+                "port": { "type": "integer", "x-env-var": "TEST_PORT" },
+                "url": { "type": "string", "x-env-var": "TEST_DATABASE_URL" },
+                "derived": { "type": "string" },
+                "nested": {
+                    "type": "object",
+                    "x-env-prefix": "TEST_NESTED_",
+                    "properties": {
+                        "timeout_ms": { "type": "integer", "x-env-var": "TEST_NESTED_TIMEOUT_MS" },
+                    },
+                },
+            })
+        );
+    }
+}
@@ -154,6 +154,15 @@ impl RandomConfig for configs::chain::FeeModelVersion {
     }
 }
 
+impl RandomConfig for configs::chain::TransactionOrdering {
+    fn sample(g: &mut Gen<impl Rng>) -> Self {
+        match g.rng.gen_range(0..2) {
+            0 => Self::ArrivalTime,
+            _ => Self::FeePriority,
+        }
+    }
+}
+
 impl RandomConfig for configs::AlertsConfig {
     fn sample(g: &mut Gen<impl Rng>) -> Self {
         Self {
@@ -170,6 +179,12 @@ impl RandomConfig for configs::ApiConfig {
             prometheus: g.gen(),
             healthcheck: g.gen(),
             merkle_tree: g.gen(),
+            rate_limit: g.gen(),
+            grpc: g.gen(),
+            http_interface: g.gen(),
+            ws_interface: g.gen(),
+            audit_log: g.gen(),
+            admin: g.gen(),
         }
     }
 }
@@ -186,6 +201,7 @@ impl RandomConfig for configs::api::Web3JsonRpcConfig {
             subscriptions_limit: g.gen(),
             pubsub_polling_interval: g.gen(),
             max_nonce_ahead: g.gen(),
+            tx_replacement_fee_bump_percent: g.gen(),
             gas_price_scale_factor: g.gen(),
             request_timeout: g.gen(),
             account_pks: g.gen(),
@@ -198,9 +214,12 @@ impl RandomConfig for configs::api::Web3JsonRpcConfig {
             factory_deps_cache_size_mb: g.gen(),
             initial_writes_cache_size_mb: g.gen(),
             latest_values_cache_size_mb: g.gen(),
+            response_cache_size_mb: g.gen(),
             fee_history_limit: g.gen(),
             max_batch_request_size: g.gen(),
             max_response_body_size_mb: g.gen(),
+            max_request_body_size_mb: g.gen(),
+            compress_responses: g.gen(),
             websocket_requests_per_minute_limit: g.gen(),
             tree_api_url: g.gen(),
         }
@@ -209,7 +228,11 @@ impl RandomConfig for configs::api::Web3JsonRpcConfig {
 
 impl RandomConfig for configs::api::HealthCheckConfig {
     fn sample(g: &mut Gen<impl Rng>) -> Self {
-        Self { port: g.gen() }
+        Self {
+            port: g.gen(),
+            max_sealed_l1_batch_age_sec: g.gen(),
+            max_tree_lag: g.gen(),
+        }
     }
 }
 
@@ -228,12 +251,64 @@ impl RandomConfig for configs::api::MerkleTreeApiConfig {
     }
 }
 
+impl RandomConfig for configs::api::ApiRateLimitConfig {
+    fn sample(g: &mut Gen<impl Rng>) -> Self {
+        Self {
+            requests_per_minute: g.gen(),
+            api_key_header: g.gen(),
+            method_weights: g.gen(),
+            method_concurrency_limits: g.gen(),
+        }
+    }
+}
+
+impl RandomConfig for configs::api::ApiAuditLogConfig {
+    fn sample(g: &mut Gen<impl Rng>) -> Self {
+        Self {
+            sampling_ratio: g.gen(),
+            api_key_header: g.gen(),
+            redact_client_ids: g.gen(),
+            file_path: g.gen(),
+        }
+    }
+}
+
+impl RandomConfig for configs::api::RpcInterfaceConfig {
+    fn sample(g: &mut Gen<impl Rng>) -> Self {
+        Self {
+            namespaces: g.gen(),
+            allowed_methods: g.gen(),
+            denied_methods: g.gen(),
+        }
+    }
+}
+
+impl RandomConfig for configs::api::GrpcApiConfig {
+    fn sample(g: &mut Gen<impl Rng>) -> Self {
+        Self {
+            port: g.gen(),
+            polling_interval_ms: g.gen(),
+        }
+    }
+}
+
+impl RandomConfig for configs::api::AdminApiConfig {
+    fn sample(g: &mut Gen<impl Rng>) -> Self {
+        Self {
+            port: g.gen(),
+            auth_token: g.gen(),
+        }
+    }
+}
+
 impl RandomConfig for configs::PrometheusConfig {
     fn sample(g: &mut Gen<impl Rng>) -> Self {
         Self {
             listener_port: g.gen(),
             pushgateway_url: g.gen(),
             push_interval_ms: g.gen(),
+            pushgateway_job: g.gen(),
+            pushgateway_labels: g.gen(),
         }
     }
 }
@@ -277,6 +352,10 @@ impl RandomConfig for configs::chain::StateKeeperConfig {
             virtual_blocks_per_miniblock: g.gen(),
             upload_witness_inputs_to_gcs: g.gen(),
             enum_index_migration_chunk_size: g.gen(),
+            disabled_seal_criteria: g.gen(),
+            transaction_ordering: g.gen(),
+            max_l1_batches_not_committed: g.gen(),
+            max_l1_batches_not_proven: g.gen(),
         }
     }
 }
@@ -309,6 +388,64 @@ impl RandomConfig for configs::chain::MempoolConfig {
             stuck_tx_timeout: g.gen(),
             remove_stuck_txs: g.gen(),
             delay_interval: g.gen(),
+            max_transactions_per_account: g.gen(),
+            max_memory_mb: g.gen(),
+        }
+    }
+}
+
+impl RandomConfig for configs::chain::BaseTokenRatioSource {
+    fn sample(g: &mut Gen<impl Rng>) -> Self {
+        match g.rng.gen_range(0..2) {
+            0 => Self::Fixed,
+            _ => Self::Http,
+        }
+    }
+}
+
+impl RandomConfig for configs::chain::BaseTokenConfig {
+    fn sample(g: &mut Gen<impl Rng>) -> Self {
+        Self {
+            l1_address: g.gen(),
+            ratio_source: g.gen(),
+            ratio_numerator: g.gen(),
+            ratio_denominator: g.gen(),
+            ratio_url: g.gen(),
+            ratio_poll_interval_ms: g.gen(),
+        }
+    }
+}
+
+impl RandomConfig for configs::chain::FeeInputSource {
+    fn sample(g: &mut Gen<impl Rng>) -> Self {
+        match g.rng.gen_range(0..3) {
+            0 => Self::Oracle,
+            1 => Self::Http,
+            _ => Self::Fixed,
+        }
+    }
+}
+
+impl RandomConfig for configs::chain::FeeInputConfig {
+    fn sample(g: &mut Gen<impl Rng>) -> Self {
+        Self {
+            l1_gas_price_source: g.gen(),
+            l1_gas_price_fixed_value: g.gen(),
+            l1_gas_price_url: g.gen(),
+            l1_gas_price_json_pointer: g.gen(),
+            l1_gas_price_multiplier: g.gen(),
+            l1_gas_price_smoothing_alpha: g.gen(),
+            l1_gas_price_min: g.gen(),
+            l1_gas_price_max: g.gen(),
+            l1_pubdata_price_source: g.gen(),
+            l1_pubdata_price_fixed_value: g.gen(),
+            l1_pubdata_price_url: g.gen(),
+            l1_pubdata_price_json_pointer: g.gen(),
+            l1_pubdata_price_multiplier: g.gen(),
+            l1_pubdata_price_smoothing_alpha: g.gen(),
+            l1_pubdata_price_min: g.gen(),
+            l1_pubdata_price_max: g.gen(),
+            poll_interval_ms: g.gen(),
         }
     }
 }
@@ -381,6 +518,16 @@ impl RandomConfig for configs::database::MerkleTreeMode {
     }
 }
 
+impl RandomConfig for configs::database::RocksdbCompactionStyle {
+    fn sample(g: &mut Gen<impl Rng>) -> Self {
+        match g.rng.gen_range(0..3) {
+            0 => Self::Level,
+            1 => Self::Universal,
+            _ => Self::Fifo,
+        }
+    }
+}
+
 impl RandomConfig for configs::database::MerkleTreeConfig {
     fn sample(g: &mut Gen<impl Rng>) -> Self {
         Self {
@@ -391,6 +538,14 @@ impl RandomConfig for configs::database::MerkleTreeConfig {
             memtable_capacity_mb: g.gen(),
             stalled_writes_timeout_sec: g.gen(),
             max_l1_batches_per_iter: g.gen(),
+            pruning_retained_versions: g.gen(),
+            pruning_iteration_delay_ms: g.gen(),
+            thread_count: g.gen(),
+            consistency_check_interval_sec: g.gen(),
+            compaction_style: g.gen(),
+            write_buffer_size_mb: g.gen(),
+            max_background_jobs: g.gen(),
+            rocksdb_statistics: g.gen(),
         }
     }
 }
@@ -399,6 +554,11 @@ impl RandomConfig for configs::database::DBConfig {
     fn sample(g: &mut Gen<impl Rng>) -> Self {
         Self {
             state_keeper_db_path: g.gen(),
+            state_keeper_db_block_cache_size_mb: g.gen(),
+            state_keeper_db_compaction_style: g.gen(),
+            state_keeper_db_write_buffer_size_mb: g.gen(),
+            state_keeper_db_max_background_jobs: g.gen(),
+            state_keeper_db_rocksdb_statistics: g.gen(),
             merkle_tree: g.gen(),
         }
     }
@@ -409,6 +569,8 @@ impl RandomConfig for configs::database::PostgresConfig {
         Self {
             master_url: g.gen(),
             replica_url: g.gen(),
+            read_replica_urls: g.gen(),
+            max_replica_lag_ms: g.gen(),
             prover_url: g.gen(),
             max_connections: g.gen(),
             acquire_timeout_sec: g.gen(),
@@ -424,6 +586,10 @@ impl RandomConfig for configs::ETHClientConfig {
         Self {
             chain_id: g.gen(),
             web3_url: g.gen(),
+            fallback_web3_urls: g.gen(),
+            failover_error_rate_threshold: g.gen(),
+            failover_max_head_lag: g.gen(),
+            failover_health_check_interval_ms: g.gen(),
         }
     }
 }
@@ -433,6 +599,10 @@ impl RandomConfig for configs::ETHSenderConfig {
         Self {
             sender: g.gen(),
             gas_adjuster: g.gen(),
+            signer: g.gen(),
+            submission: g.gen(),
+            secondary_signer: g.gen(),
+            blob_signer: g.gen(),
         }
     }
 }
@@ -456,6 +626,71 @@ impl RandomConfig for configs::eth_sender::ProofLoadingMode {
     }
 }
 
+impl RandomConfig for configs::eth_sender::PubdataSendingMode {
+    fn sample(g: &mut Gen<impl Rng>) -> Self {
+        match g.rng.gen_range(0..2) {
+            0 => Self::Calldata,
+            _ => Self::Blobs,
+        }
+    }
+}
+
+impl RandomConfig for configs::eth_sender::BaseFeeEstimationMode {
+    fn sample(g: &mut Gen<impl Rng>) -> Self {
+        match g.rng.gen_range(0..4) {
+            0 => Self::Median,
+            1 => Self::Percentile,
+            2 => Self::Ewma,
+            _ => Self::ExternalApi,
+        }
+    }
+}
+
+impl RandomConfig for configs::eth_sender::OperatorSignerBackend {
+    fn sample(g: &mut Gen<impl Rng>) -> Self {
+        match g.rng.gen_range(0..4) {
+            0 => Self::PrivateKey,
+            1 => Self::AwsKms,
+            2 => Self::GcpKms,
+            _ => Self::Pkcs11,
+        }
+    }
+}
+
+impl RandomConfig for configs::eth_sender::OperatorSignerConfig {
+    fn sample(g: &mut Gen<impl Rng>) -> Self {
+        Self {
+            backend: g.gen(),
+            aws_kms_key_id: g.gen(),
+            aws_region: g.gen(),
+            gcp_kms_key_version: g.gen(),
+            pkcs11_module_path: g.gen(),
+            pkcs11_slot_id: g.gen(),
+            pkcs11_key_label: g.gen(),
+        }
+    }
+}
+
+impl RandomConfig for configs::eth_sender::TxSubmissionMode {
+    fn sample(g: &mut Gen<impl Rng>) -> Self {
+        match g.rng.gen_range(0..3) {
+            0 => Self::PublicMempool,
+            1 => Self::PrivateRpc,
+            _ => Self::Bundle,
+        }
+    }
+}
+
+impl RandomConfig for configs::eth_sender::TxSubmissionConfig {
+    fn sample(g: &mut Gen<impl Rng>) -> Self {
+        Self {
+            mode: g.gen(),
+            relay_url: g.gen(),
+            bundle_target_blocks: g.gen(),
+        }
+    }
+}
+
 impl RandomConfig for configs::eth_sender::SenderConfig {
     fn sample(g: &mut Gen<impl Rng>) -> Self {
         Self {
@@ -476,6 +711,11 @@ impl RandomConfig for configs::eth_sender::SenderConfig {
             l1_batch_min_age_before_execute_seconds: g.gen(),
             max_acceptable_priority_fee_in_gwei: g.gen(),
             proof_loading_mode: g.gen(),
+            pubdata_sending_mode: g.gen(),
+            max_tx_attempts: g.gen(),
+            fee_bump_percent: g.gen(),
+            max_fee_per_gas_cap: g.gen(),
+            min_blocks_between_resends: g.gen(),
         }
     }
 }
@@ -491,6 +731,13 @@ impl RandomConfig for configs::eth_sender::GasAdjusterConfig {
             internal_enforced_l1_gas_price: g.gen(),
             poll_period: g.gen(),
             max_l1_gas_price: g.gen(),
+            base_fee_estimation_mode: g.gen(),
+            base_fee_percentile: g.gen(),
+            base_fee_ewma_alpha: g.gen(),
+            external_price_api_url: g.gen(),
+            external_price_api_json_pointer: g.gen(),
+            external_price_api_multiplier: g.gen(),
+            external_price_api_max_age_sec: g.gen(),
         }
     }
 }
@@ -529,6 +776,16 @@ impl RandomConfig for configs::fri_prover::SetupLoadMode {
     }
 }
 
+impl RandomConfig for configs::fri_prover::ProverBackend {
+    fn sample(g: &mut Gen<impl Rng>) -> Self {
+        match g.rng.gen_range(0..3) {
+            0 => Self::Auto,
+            1 => Self::Gpu,
+            _ => Self::Cpu,
+        }
+    }
+}
+
 impl RandomConfig for configs::FriProverConfig {
     fn sample(g: &mut Gen<impl Rng>) -> Self {
         Self {
@@ -545,6 +802,7 @@ impl RandomConfig for configs::FriProverConfig {
             witness_vector_receiver_port: g.gen(),
             zone_read_url: g.gen(),
             shall_save_to_public_bucket: g.gen(),
+            backend: g.gen(),
         }
     }
 }
@@ -557,6 +815,9 @@ impl RandomConfig for configs::FriProverGatewayConfig {
             prometheus_listener_port: g.gen(),
             prometheus_pushgateway_url: g.gen(),
             prometheus_push_interval_ms: g.gen(),
+            proof_deadline_secs: g.gen(),
+            urgent_l1_batches: g.gen(),
+            capacity_api_port: g.gen(),
         }
     }
 }
@@ -634,13 +895,17 @@ impl RandomConfig for configs::house_keeper::HouseKeeperConfig {
             fri_prover_stats_reporting_interval_ms: g.gen(),
             fri_proof_compressor_job_retrying_interval_ms: g.gen(),
             fri_proof_compressor_stats_reporting_interval_ms: g.gen(),
+            prover_artifacts_retention_period_hours: g.gen(),
+            prover_artifacts_gc_interval_ms: g.gen(),
+            prover_artifacts_gc_dry_run: g.gen(),
+            fri_prover_heartbeat_timeout_ms: g.gen(),
         }
     }
 }
 
 impl RandomConfig for configs::object_store::ObjectStoreMode {
     fn sample(g: &mut Gen<impl Rng>) -> Self {
-        match g.rng.gen_range(0..4) {
+        match g.rng.gen_range(0..8) {
             0 => Self::GCS {
                 bucket_base_url: g.gen(),
             },
@@ -651,6 +916,25 @@ impl RandomConfig for configs::object_store::ObjectStoreMode {
             2 => Self::FileBacked {
                 file_backed_base_path: g.gen(),
             },
+            3 => Self::AzureBlobWithManagedIdentity {
+                azure_account_name: g.gen(),
+                azure_container_name: g.gen(),
+            },
+            4 => Self::AzureBlobWithConnectionString {
+                azure_container_name: g.gen(),
+                azure_connection_string: g.gen(),
+            },
+            5 => Self::S3 {
+                s3_bucket: g.gen(),
+                s3_region: g.gen(),
+                s3_endpoint: g.gen(),
+                s3_addressing_style: g.gen(),
+                s3_access_key_id: g.gen(),
+                s3_secret_access_key: g.gen(),
+            },
+            6 => Self::HttpMirrorReadOnly {
+                http_mirror_base_url: g.gen(),
+            },
             _ => Self::GCSAnonymousReadOnly {
                 bucket_base_url: g.gen(),
             },
@@ -658,11 +942,52 @@ impl RandomConfig for configs::object_store::ObjectStoreMode {
     }
 }
 
+impl RandomConfig for configs::object_store::S3AddressingStyle {
+    fn sample(g: &mut Gen<impl Rng>) -> Self {
+        match g.rng.gen_range(0..2) {
+            0 => Self::VirtualHosted,
+            _ => Self::Path,
+        }
+    }
+}
+
+impl RandomConfig for configs::object_store::ObjectStoreCompression {
+    fn sample(g: &mut Gen<impl Rng>) -> Self {
+        match g.rng.gen_range(0..2) {
+            0 => Self::None,
+            _ => Self::Zstd,
+        }
+    }
+}
+
+impl RandomConfig for configs::object_store::ObjectStoreEncryption {
+    fn sample(g: &mut Gen<impl Rng>) -> Self {
+        match g.rng.gen_range(0..3) {
+            0 => Self::None,
+            1 => Self::LocalKey,
+            _ => Self::AwsKms,
+        }
+    }
+}
+
 impl RandomConfig for configs::ObjectStoreConfig {
     fn sample(g: &mut Gen<impl Rng>) -> Self {
         Self {
             mode: g.gen(),
             max_retries: g.gen(),
+            compression: g.gen(),
+            compression_level: g.gen(),
+            retry_initial_backoff_ms: g.gen(),
+            retry_max_backoff_ms: g.gen(),
+            retry_budget: g.gen(),
+            circuit_breaker_failure_threshold: g.gen(),
+            circuit_breaker_reset_timeout_ms: g.gen(),
+            encryption: g.gen(),
+            encrypted_buckets: g.gen(),
+            encryption_key: g.gen(),
+            encryption_kms_key_id: g.gen(),
+            encryption_kms_region: g.gen(),
+            encryption_strict: g.gen(),
         }
     }
 }
@@ -692,6 +1017,18 @@ impl RandomConfig for configs::SnapshotsCreatorConfig {
         Self {
             storage_logs_chunk_size: g.gen(),
             concurrent_queries_count: g.gen(),
+            schedule: g.gen(),
+            incremental: g.gen(),
+            max_incremental_chain_length: g.gen(),
+            verify_reused_chunks: g.gen(),
+        }
+    }
+}
+
+impl RandomConfig for configs::ShutdownConfig {
+    fn sample(g: &mut Gen<impl Rng>) -> Self {
+        Self {
+            timeout_ms: g.gen(),
         }
     }
 }
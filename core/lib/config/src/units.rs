@@ -0,0 +1,338 @@
+//! Human-friendly durations and byte sizes in configs.
+//!
+//! Config fields holding durations or byte sizes are integers in the unit specified by the field name or docs
+//! (e.g., `block_commit_deadline_ms` or `block_cache_size_mb`). Deserializers in this module allow specifying
+//! such values with an explicit unit, e.g. `30s`, `5m`, `1h 30m` for durations or `512MiB`, `2GB` for byte sizes.
+//! Bare integers are still accepted and are interpreted in the field unit, so that existing configs remain valid.
+//!
+//! Durations are parsed with [`humantime`]. Byte sizes support `B`, decimal (`KB`, `MB`, `GB`, `TB`)
+//! and binary (`KiB`, `MiB`, `GiB`, `TiB`) suffixes.
+
+use std::{convert::TryFrom, fmt, time::Duration};
+
+use serde::{de, Deserialize, Deserializer};
+
+/// Unit of a config value. A value with an explicit unit is converted to this unit; the conversion fails
+/// if the value is not a whole number of units.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Unit {
+    Millis,
+    Seconds,
+    Bytes,
+    Mebibytes,
+}
+
+impl Unit {
+    /// Parses a value either as a bare integer in this unit, or as a value with an explicit unit.
+    pub fn parse(self, value: &str) -> Result<u64, String> {
+        let value = value.trim();
+        if let Ok(value) = value.parse::<u64>() {
+            return Ok(value);
+        }
+
+        match self {
+            Self::Millis | Self::Seconds => {
+                let duration = humantime::parse_duration(value)
+                    .map_err(|err| format!("invalid duration `{value}`: {err}"))?;
+                self.convert_duration(duration)
+            }
+            Self::Bytes | Self::Mebibytes => {
+                let bytes = parse_byte_size(value)?;
+                self.convert_bytes(bytes)
+            }
+        }
+    }
+
+    fn convert_duration(self, duration: Duration) -> Result<u64, String> {
+        let (value, remainder) = match self {
+            Self::Millis => (duration.as_millis(), duration.as_nanos() % 1_000_000),
+            Self::Seconds => (
+                u128::from(duration.as_secs()),
+                duration.as_nanos() % 1_000_000_000,
+            ),
+            Self::Bytes | Self::Mebibytes => unreachable!("not a duration unit"),
+        };
+        if remainder != 0 {
+            return Err(format!(
+                "duration {duration:?} is not a whole number of {self}"
+            ));
+        }
+        u64::try_from(value).map_err(|_| format!("duration {duration:?} is too large"))
+    }
+
+    fn convert_bytes(self, bytes: u64) -> Result<u64, String> {
+        match self {
+            Self::Bytes => Ok(bytes),
+            Self::Mebibytes if bytes % MIB == 0 => Ok(bytes / MIB),
+            Self::Mebibytes => Err(format!("{bytes} bytes is not a whole number of {self}")),
+            Self::Millis | Self::Seconds => unreachable!("not a byte size unit"),
+        }
+    }
+}
+
+impl fmt::Display for Unit {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(match self {
+            Self::Millis => "milliseconds",
+            Self::Seconds => "seconds",
+            Self::Bytes => "bytes",
+            Self::Mebibytes => "MiB",
+        })
+    }
+}
+
+const MIB: u64 = 1 << 20;
+
+fn parse_byte_size(value: &str) -> Result<u64, String> {
+    let digits_end = value
+        .find(|ch: char| !ch.is_ascii_digit())
+        .unwrap_or(value.len());
+    let (number, suffix) = value.split_at(digits_end);
+    let number: u64 = number
+        .parse()
+        .map_err(|_| format!("invalid byte size `{value}`"))?;
+    let multiplier: u64 = match suffix.trim() {
+        "B" => 1,
+        "KB" => 1_000,
+        "MB" => 1_000_000,
+        "GB" => 1_000_000_000,
+        "TB" => 1_000_000_000_000,
+        "KiB" => 1 << 10,
+        "MiB" => MIB,
+        "GiB" => 1 << 30,
+        "TiB" => 1 << 40,
+        _ => return Err(format!("unknown unit in byte size `{value}`")),
+    };
+    number
+        .checked_mul(multiplier)
+        .ok_or_else(|| format!("byte size `{value}` is too large"))
+}
+
+/// Raw config value: either an integer (e.g., from a JSON config) or a string (e.g., from an env variable).
+enum RawValue {
+    Integer(u64),
+    String(String),
+}
+
+impl<'de> Deserialize<'de> for RawValue {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct RawValueVisitor;
+
+        impl<'de> de::Visitor<'de> for RawValueVisitor {
+            type Value = RawValue;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+                formatter.write_str("non-negative integer or string with a unit")
+            }
+
+            fn visit_u64<E: de::Error>(self, value: u64) -> Result<Self::Value, E> {
+                Ok(RawValue::Integer(value))
+            }
+
+            fn visit_i64<E: de::Error>(self, value: i64) -> Result<Self::Value, E> {
+                let value = u64::try_from(value).map_err(|_| E::custom("value is negative"))?;
+                Ok(RawValue::Integer(value))
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+                Ok(RawValue::String(value.to_owned()))
+            }
+        }
+
+        deserializer.deserialize_any(RawValueVisitor)
+    }
+}
+
+fn convert<T: TryFrom<u64>, E: de::Error>(raw: RawValue, unit: Unit) -> Result<T, E> {
+    let value = match raw {
+        RawValue::Integer(value) => value,
+        RawValue::String(value) => unit.parse(&value).map_err(E::custom)?,
+    };
+    T::try_from(value).map_err(|_| E::custom(format!("value {value} {unit} is out of range")))
+}
+
+fn deserialize_in<'de, D, T>(deserializer: D, unit: Unit) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: TryFrom<u64>,
+{
+    convert(RawValue::deserialize(deserializer)?, unit)
+}
+
+fn deserialize_opt_in<'de, D, T>(deserializer: D, unit: Unit) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: TryFrom<u64>,
+{
+    Option::<RawValue>::deserialize(deserializer)?
+        .map(|raw| convert(raw, unit))
+        .transpose()
+}
+
+/// Deserializes a duration in milliseconds.
+pub fn duration_ms<'de, D: Deserializer<'de>, T: TryFrom<u64>>(
+    deserializer: D,
+) -> Result<T, D::Error> {
+    deserialize_in(deserializer, Unit::Millis)
+}
+
+/// Deserializes an optional duration in milliseconds. Must be used together with `#[serde(default)]`.
+pub fn opt_duration_ms<'de, D: Deserializer<'de>, T: TryFrom<u64>>(
+    deserializer: D,
+) -> Result<Option<T>, D::Error> {
+    deserialize_opt_in(deserializer, Unit::Millis)
+}
+
+/// Deserializes a duration in seconds.
+pub fn duration_sec<'de, D: Deserializer<'de>, T: TryFrom<u64>>(
+    deserializer: D,
+) -> Result<T, D::Error> {
+    deserialize_in(deserializer, Unit::Seconds)
+}
+
+/// Deserializes an optional duration in seconds. Must be used together with `#[serde(default)]`.
+pub fn opt_duration_sec<'de, D: Deserializer<'de>, T: TryFrom<u64>>(
+    deserializer: D,
+) -> Result<Option<T>, D::Error> {
+    deserialize_opt_in(deserializer, Unit::Seconds)
+}
+
+/// Deserializes a byte size in bytes.
+pub fn byte_size<'de, D: Deserializer<'de>, T: TryFrom<u64>>(
+    deserializer: D,
+) -> Result<T, D::Error> {
+    deserialize_in(deserializer, Unit::Bytes)
+}
+
+/// Deserializes a byte size in MiB.
+pub fn byte_size_mb<'de, D: Deserializer<'de>, T: TryFrom<u64>>(
+    deserializer: D,
+) -> Result<T, D::Error> {
+    deserialize_in(deserializer, Unit::Mebibytes)
+}
+
+/// Deserializes an optional byte size in MiB. Must be used together with `#[serde(default)]`.
+pub fn opt_byte_size_mb<'de, D: Deserializer<'de>, T: TryFrom<u64>>(
+    deserializer: D,
+) -> Result<Option<T>, D::Error> {
+    deserialize_opt_in(deserializer, Unit::Mebibytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parsing_bare_integers() {
+        assert_eq!(Unit::Millis.parse("500"), Ok(500));
+        assert_eq!(Unit::Seconds.parse(" 30 "), Ok(30));
+        assert_eq!(Unit::Bytes.parse("1024"), Ok(1_024));
+        assert_eq!(Unit::Mebibytes.parse("128"), Ok(128));
+    }
+
+    #[test]
+    fn parsing_durations_with_units() {
+        assert_eq!(Unit::Millis.parse("250ms"), Ok(250));
+        assert_eq!(Unit::Millis.parse("30s"), Ok(30_000));
+        assert_eq!(Unit::Millis.parse("1h 30m"), Ok(5_400_000));
+        assert_eq!(Unit::Seconds.parse("5m"), Ok(300));
+        assert_eq!(Unit::Seconds.parse("2h"), Ok(7_200));
+        assert_eq!(Unit::Seconds.parse("1000ms"), Ok(1));
+    }
+
+    #[test]
+    fn parsing_byte_sizes_with_units() {
+        assert_eq!(Unit::Bytes.parse("100B"), Ok(100));
+        assert_eq!(Unit::Bytes.parse("2KB"), Ok(2_000));
+        assert_eq!(Unit::Bytes.parse("3MB"), Ok(3_000_000));
+        assert_eq!(Unit::Bytes.parse("1GB"), Ok(1_000_000_000));
+        assert_eq!(Unit::Bytes.parse("1TB"), Ok(1_000_000_000_000));
+        assert_eq!(Unit::Bytes.parse("4KiB"), Ok(4_096));
+        assert_eq!(Unit::Bytes.parse("512 MiB"), Ok(512 << 20));
+        assert_eq!(Unit::Mebibytes.parse("512MiB"), Ok(512));
+        assert_eq!(Unit::Mebibytes.parse("2GiB"), Ok(2_048));
+        assert_eq!(Unit::Mebibytes.parse("1TiB"), Ok(1 << 20));
+    }
+
+    #[test]
+    fn parsing_invalid_values() {
+        let err = Unit::Millis.parse("soon").unwrap_err();
+        assert!(err.contains("invalid duration"), "{err}");
+        let err = Unit::Seconds.parse("-5").unwrap_err();
+        assert!(err.contains("invalid duration"), "{err}");
+        let err = Unit::Seconds.parse("1500ms").unwrap_err();
+        assert!(err.contains("not a whole number of seconds"), "{err}");
+        let err = Unit::Millis.parse("1us").unwrap_err();
+        assert!(err.contains("not a whole number of milliseconds"), "{err}");
+
+        let err = Unit::Bytes.parse("MiB").unwrap_err();
+        assert!(err.contains("invalid byte size"), "{err}");
+        let err = Unit::Bytes.parse("10XB").unwrap_err();
+        assert!(err.contains("unknown unit"), "{err}");
+        let err = Unit::Bytes.parse("1.5GB").unwrap_err();
+        assert!(err.contains("unknown unit"), "{err}");
+        let err = Unit::Bytes.parse("20000000TB").unwrap_err();
+        assert!(err.contains("too large"), "{err}");
+        let err = Unit::Mebibytes.parse("1000KB").unwrap_err();
+        assert!(err.contains("not a whole number of MiB"), "{err}");
+        // Byte size suffixes are not valid for durations and vice versa.
+        Unit::Millis.parse("1MB").unwrap_err();
+        Unit::Bytes.parse("1s").unwrap_err();
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct TestConfig {
+        #[serde(deserialize_with = "duration_ms")]
+        timeout_ms: u64,
+        #[serde(default, deserialize_with = "opt_duration_sec")]
+        delay_sec: Option<u32>,
+        #[serde(deserialize_with = "byte_size_mb")]
+        cache_size_mb: u16,
+    }
+
+    #[test]
+    fn deserializing_values_with_units() {
+        let config: TestConfig = serde_json::from_value(serde_json::json!({
+            "timeout_ms": "1s 500ms",
+            "delay_sec": "2m",
+            "cache_size_mb": "1GiB",
+        }))
+        .unwrap();
+        assert_eq!(config.timeout_ms, 1_500);
+        assert_eq!(config.delay_sec, Some(120));
+        assert_eq!(config.cache_size_mb, 1_024);
+
+        let config: TestConfig = serde_json::from_value(serde_json::json!({
+            "timeout_ms": 300,
+            "cache_size_mb": "64",
+        }))
+        .unwrap();
+        assert_eq!(config.timeout_ms, 300);
+        assert_eq!(config.delay_sec, None);
+        assert_eq!(config.cache_size_mb, 64);
+    }
+
+    #[test]
+    fn deserializing_invalid_values() {
+        let err = serde_json::from_value::<TestConfig>(serde_json::json!({
+            "timeout_ms": -1,
+            "cache_size_mb": 64,
+        }))
+        .unwrap_err();
+        assert!(err.to_string().contains("negative"), "{err}");
+
+        let err = serde_json::from_value::<TestConfig>(serde_json::json!({
+            "timeout_ms": 1,
+            "cache_size_mb": "1TiB",
+        }))
+        .unwrap_err();
+        assert!(err.to_string().contains("out of range"), "{err}");
+
+        let err = serde_json::from_value::<TestConfig>(serde_json::json!({
+            "timeout_ms": "later",
+            "cache_size_mb": 64,
+        }))
+        .unwrap_err();
+        assert!(err.to_string().contains("invalid duration"), "{err}");
+    }
+}
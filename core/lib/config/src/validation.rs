@@ -0,0 +1,572 @@
+//! Aggregated validation of configs.
+//!
+//! Configs are validated as a whole, so that all missing or invalid values across all components
+//! are reported at once instead of failing on the first encountered error.
+
+use std::fmt;
+
+use crate::configs::{
+    api::{
+        AdminApiConfig, ApiAuditLogConfig, ApiRateLimitConfig, GrpcApiConfig, Web3JsonRpcConfig,
+    },
+    chain::{
+        BaseTokenConfig, BaseTokenRatioSource, FeeInputConfig, FeeInputParamConfig, FeeInputSource,
+        MempoolConfig, StateKeeperConfig,
+    },
+    object_store::ObjectStoreEncryption,
+    ObjectStoreConfig, ObservabilityConfig, PostgresConfig, PrometheusConfig, ShutdownConfig,
+};
+
+/// Kind of a [`ConfigError`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigErrorKind {
+    /// Config or its field is missing.
+    Missing,
+    /// Config or its field has an invalid value.
+    Invalid,
+}
+
+impl fmt::Display for ConfigErrorKind {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(match self {
+            Self::Missing => "missing",
+            Self::Invalid => "invalid",
+        })
+    }
+}
+
+/// Error related to a single config or its field.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigError {
+    /// Name of the config, e.g. `web3_json_rpc`.
+    pub config: String,
+    /// Name of the field, if the error is related to a specific field.
+    pub field: Option<String>,
+    pub kind: ConfigErrorKind,
+    pub message: String,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(formatter, "[{}] {}", self.kind, self.config)?;
+        if let Some(field) = &self.field {
+            write!(formatter, ".{field}")?;
+        }
+        write!(formatter, ": {}", self.message)
+    }
+}
+
+/// Collection of [`ConfigError`]s.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ConfigErrors {
+    errors: Vec<ConfigError>,
+}
+
+impl ConfigErrors {
+    pub fn push(&mut self, error: ConfigError) {
+        self.errors.push(error);
+    }
+
+    /// Records an error for the entire config.
+    pub fn config_error(
+        &mut self,
+        config: &str,
+        kind: ConfigErrorKind,
+        message: impl Into<String>,
+    ) {
+        self.push(ConfigError {
+            config: config.to_owned(),
+            field: None,
+            kind,
+            message: message.into(),
+        });
+    }
+
+    /// Records a missing config field.
+    pub fn missing_field(&mut self, config: &str, field: &str, message: impl Into<String>) {
+        self.push(ConfigError {
+            config: config.to_owned(),
+            field: Some(field.to_owned()),
+            kind: ConfigErrorKind::Missing,
+            message: message.into(),
+        });
+    }
+
+    /// Records an invalid value of a config field.
+    pub fn invalid_field(&mut self, config: &str, field: &str, message: impl Into<String>) {
+        self.push(ConfigError {
+            config: config.to_owned(),
+            field: Some(field.to_owned()),
+            kind: ConfigErrorKind::Invalid,
+            message: message.into(),
+        });
+    }
+
+    /// Records an invalid value of a config field if `condition` does not hold.
+    pub fn ensure(
+        &mut self,
+        condition: bool,
+        config: &str,
+        field: &str,
+        message: impl FnOnce() -> String,
+    ) {
+        if !condition {
+            self.invalid_field(config, field, message());
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.errors.len()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &ConfigError> + '_ {
+        self.errors.iter()
+    }
+
+    /// Converts this collection into a `Result`, which is an error if there is at least one recorded error.
+    pub fn into_result(self) -> Result<(), Self> {
+        if self.is_empty() {
+            Ok(())
+        } else {
+            Err(self)
+        }
+    }
+}
+
+impl fmt::Display for ConfigErrors {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(formatter, "{} config error(s):", self.errors.len())?;
+        for error in &self.errors {
+            write!(formatter, "\n  {error}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ConfigErrors {}
+
+/// Config that has invariants not expressible via types.
+pub trait Validate {
+    /// Checks config invariants, recording *all* violations into `errors`.
+    fn validate(&self, errors: &mut ConfigErrors);
+}
+
+fn is_fraction(value: f64) -> bool {
+    (0.0..=1.0).contains(&value)
+}
+
+impl Validate for ObservabilityConfig {
+    fn validate(&self, errors: &mut ConfigErrors) {
+        errors.ensure(
+            matches!(self.log_format.as_str(), "plain" | "json"),
+            "observability",
+            "log_format",
+            || format!("expected `plain` or `json`, got {:?}", self.log_format),
+        );
+        let sample_rates = [
+            ("sentry_sample_rate", self.sentry_sample_rate),
+            ("sentry_traces_sample_rate", self.sentry_traces_sample_rate),
+        ];
+        for (field, rate) in sample_rates {
+            if let Some(rate) = rate {
+                errors.ensure(is_fraction(rate.into()), "observability", field, || {
+                    format!("must be in [0, 1], got {rate}")
+                });
+            }
+        }
+        if let Some(rotation) = &self.log_file_rotation {
+            errors.ensure(
+                matches!(rotation.as_str(), "never" | "hourly" | "daily"),
+                "observability",
+                "log_file_rotation",
+                || format!("expected `never`, `hourly` or `daily`, got {rotation:?}"),
+            );
+        }
+        if let Some(ratio) = self.otlp_sampling_ratio {
+            errors.ensure(
+                is_fraction(ratio),
+                "observability",
+                "otlp_sampling_ratio",
+                || format!("must be in [0, 1], got {ratio}"),
+            );
+        }
+        errors.ensure(
+            self.log_throttle_interval_sec != Some(0),
+            "observability",
+            "log_throttle_interval_sec",
+            || "must be positive".to_owned(),
+        );
+        errors.ensure(
+            self.log_throttle_burst != Some(0),
+            "observability",
+            "log_throttle_burst",
+            || "must be positive".to_owned(),
+        );
+    }
+}
+
+impl Validate for PrometheusConfig {
+    fn validate(&self, errors: &mut ConfigErrors) {
+        let labels = self.pushgateway_labels.as_deref().unwrap_or_default();
+        for label in labels.split(',') {
+            let is_valid = label.split_once('=').map_or(false, |(name, value)| {
+                !name.trim().is_empty() && !value.trim().is_empty() && !value.contains('/')
+            });
+            errors.ensure(is_valid, "prometheus", "pushgateway_labels", || {
+                format!("expected `key=value` label with non-empty key and value without `/`, got {label:?}")
+            });
+        }
+    }
+}
+
+impl Validate for ShutdownConfig {
+    fn validate(&self, errors: &mut ConfigErrors) {
+        errors.ensure(self.timeout_ms != Some(0), "shutdown", "timeout_ms", || {
+            "must be positive".to_owned()
+        });
+    }
+}
+
+impl Validate for PostgresConfig {
+    fn validate(&self, errors: &mut ConfigErrors) {
+        errors.ensure(
+            self.max_connections != Some(0),
+            "postgres",
+            "max_connections",
+            || "must be positive".to_owned(),
+        );
+        errors.ensure(
+            self.max_replica_lag_ms != Some(0),
+            "postgres",
+            "max_replica_lag_ms",
+            || "must be positive".to_owned(),
+        );
+    }
+}
+
+impl Validate for Web3JsonRpcConfig {
+    fn validate(&self, errors: &mut ConfigErrors) {
+        const NAME: &str = "web3_json_rpc";
+
+        errors.ensure(self.http_port != self.ws_port, NAME, "ws_port", || {
+            format!("must differ from `http_port` ({})", self.http_port)
+        });
+        errors.ensure(
+            self.gas_price_scale_factor > 0.0,
+            NAME,
+            "gas_price_scale_factor",
+            || format!("must be positive, got {}", self.gas_price_scale_factor),
+        );
+        errors.ensure(
+            self.estimate_gas_scale_factor > 0.0,
+            NAME,
+            "estimate_gas_scale_factor",
+            || format!("must be positive, got {}", self.estimate_gas_scale_factor),
+        );
+        errors.ensure(self.max_tx_size > 0, NAME, "max_tx_size", || {
+            "must be positive".to_owned()
+        });
+    }
+}
+
+impl Validate for ApiRateLimitConfig {
+    fn validate(&self, errors: &mut ConfigErrors) {
+        const NAME: &str = "api.rate_limit";
+
+        match self.method_weights() {
+            Ok(weights) => {
+                if let Some(limit) = self.requests_per_minute {
+                    for (method, weight) in weights {
+                        errors.ensure(weight <= limit, NAME, "method_weights", || {
+                            format!(
+                                "weight of `{method}` ({weight}) exceeds `requests_per_minute` ({limit}); \
+                                 the method could never be called"
+                            )
+                        });
+                    }
+                }
+            }
+            Err(err) => errors.invalid_field(NAME, "method_weights", format!("{err:#}")),
+        }
+        if let Err(err) = self.method_concurrency_limits() {
+            errors.invalid_field(NAME, "method_concurrency_limits", format!("{err:#}"));
+        }
+        if let Some(header) = &self.api_key_header {
+            errors.ensure(
+                !header.is_empty() && header.bytes().all(|ch| ch.is_ascii_graphic() && ch != b':'),
+                NAME,
+                "api_key_header",
+                || format!("invalid HTTP header name: {header:?}"),
+            );
+        }
+    }
+}
+
+impl Validate for ApiAuditLogConfig {
+    fn validate(&self, errors: &mut ConfigErrors) {
+        const NAME: &str = "api.audit_log";
+
+        if let Some(ratio) = self.sampling_ratio {
+            errors.ensure(ratio > 0.0 && ratio <= 1.0, NAME, "sampling_ratio", || {
+                format!("must be in (0, 1] range, got {ratio}")
+            });
+        }
+        if let Some(header) = &self.api_key_header {
+            errors.ensure(
+                !header.is_empty() && header.bytes().all(|ch| ch.is_ascii_graphic() && ch != b':'),
+                NAME,
+                "api_key_header",
+                || format!("invalid HTTP header name: {header:?}"),
+            );
+        }
+    }
+}
+
+impl Validate for GrpcApiConfig {
+    fn validate(&self, errors: &mut ConfigErrors) {
+        const NAME: &str = "api.grpc";
+
+        errors.ensure(
+            self.polling_interval_ms > 0,
+            NAME,
+            "polling_interval_ms",
+            || "must be positive".to_owned(),
+        );
+    }
+}
+
+impl Validate for AdminApiConfig {
+    fn validate(&self, errors: &mut ConfigErrors) {
+        const NAME: &str = "api.admin";
+
+        if let Some(token) = &self.auth_token {
+            errors.ensure(
+                !token.is_empty() && token.bytes().all(|ch| ch.is_ascii_graphic()),
+                NAME,
+                "auth_token",
+                || "must be a non-empty string of printable ASCII chars without spaces".to_owned(),
+            );
+        }
+    }
+}
+
+impl Validate for StateKeeperConfig {
+    fn validate(&self, errors: &mut ConfigErrors) {
+        const NAME: &str = "state_keeper";
+
+        let fractions = [
+            (
+                "reject_tx_at_geometry_percentage",
+                self.reject_tx_at_geometry_percentage,
+            ),
+            (
+                "reject_tx_at_eth_params_percentage",
+                self.reject_tx_at_eth_params_percentage,
+            ),
+            (
+                "reject_tx_at_gas_percentage",
+                self.reject_tx_at_gas_percentage,
+            ),
+            (
+                "close_block_at_geometry_percentage",
+                self.close_block_at_geometry_percentage,
+            ),
+            (
+                "close_block_at_eth_params_percentage",
+                self.close_block_at_eth_params_percentage,
+            ),
+            (
+                "close_block_at_gas_percentage",
+                self.close_block_at_gas_percentage,
+            ),
+            ("compute_overhead_part", self.compute_overhead_part),
+            ("pubdata_overhead_part", self.pubdata_overhead_part),
+        ];
+        for (field, value) in fractions {
+            errors.ensure(is_fraction(value), NAME, field, || {
+                format!("must be in [0, 1], got {value}")
+            });
+        }
+
+        errors.ensure(
+            self.transaction_slots > 0,
+            NAME,
+            "transaction_slots",
+            || "must be positive".to_owned(),
+        );
+        errors.ensure(
+            self.virtual_blocks_per_miniblock > 0,
+            NAME,
+            "virtual_blocks_per_miniblock",
+            || "must be positive".to_owned(),
+        );
+    }
+}
+
+impl Validate for BaseTokenConfig {
+    fn validate(&self, errors: &mut ConfigErrors) {
+        const NAME: &str = "base_token";
+
+        errors.ensure(self.ratio_numerator > 0, NAME, "ratio_numerator", || {
+            "must be positive".to_owned()
+        });
+        errors.ensure(
+            self.ratio_denominator > 0,
+            NAME,
+            "ratio_denominator",
+            || "must be positive".to_owned(),
+        );
+        if self.ratio_source == BaseTokenRatioSource::Http && self.ratio_url.is_none() {
+            errors.push(ConfigError {
+                config: NAME.to_owned(),
+                field: Some("ratio_url".to_owned()),
+                kind: ConfigErrorKind::Missing,
+                message: "required for the `Http` ratio source".to_owned(),
+            });
+        }
+        errors.ensure(
+            self.ratio_poll_interval_ms != Some(0),
+            NAME,
+            "ratio_poll_interval_ms",
+            || "must be positive".to_owned(),
+        );
+    }
+}
+
+fn validate_fee_input_param(config: &FeeInputParamConfig, param: &str, errors: &mut ConfigErrors) {
+    const NAME: &str = "fee_input";
+
+    let required_field = match config.source {
+        FeeInputSource::Oracle => None,
+        FeeInputSource::Fixed => config.fixed_value.is_none().then_some("fixed_value"),
+        FeeInputSource::Http => config.url.is_none().then_some("url"),
+    };
+    if let Some(field) = required_field {
+        errors.push(ConfigError {
+            config: NAME.to_owned(),
+            field: Some(format!("{param}_{field}")),
+            kind: ConfigErrorKind::Missing,
+            message: format!("required for the `{:?}` source", config.source),
+        });
+    }
+
+    let multiplier = config.multiplier();
+    errors.ensure(
+        multiplier.is_finite() && multiplier > 0.0,
+        NAME,
+        &format!("{param}_multiplier"),
+        || format!("must be positive, got {multiplier}"),
+    );
+    if let Some(alpha) = config.smoothing_alpha {
+        errors.ensure(
+            alpha > 0.0 && alpha <= 1.0,
+            NAME,
+            &format!("{param}_smoothing_alpha"),
+            || format!("must be in the (0, 1] range, got {alpha}"),
+        );
+    }
+    if let (Some(min), Some(max)) = (config.min, config.max) {
+        errors.ensure(min <= max, NAME, &format!("{param}_min"), || {
+            format!("must not exceed `{param}_max` ({max}), got {min}")
+        });
+    }
+}
+
+impl Validate for FeeInputConfig {
+    fn validate(&self, errors: &mut ConfigErrors) {
+        validate_fee_input_param(&self.l1_gas_price(), "l1_gas_price", errors);
+        validate_fee_input_param(&self.l1_pubdata_price(), "l1_pubdata_price", errors);
+        errors.ensure(
+            self.poll_interval_ms != Some(0),
+            "fee_input",
+            "poll_interval_ms",
+            || "must be positive".to_owned(),
+        );
+    }
+}
+
+impl Validate for ObjectStoreConfig {
+    fn validate(&self, errors: &mut ConfigErrors) {
+        const NAME: &str = "object_store";
+
+        match self.encryption {
+            ObjectStoreEncryption::None => {
+                errors.ensure(!self.encryption_strict, NAME, "encryption_strict", || {
+                    "cannot be set if encryption is disabled".to_owned()
+                });
+            }
+            ObjectStoreEncryption::LocalKey => match &self.encryption_key {
+                None => errors.missing_field(
+                    NAME,
+                    "encryption_key",
+                    "must be specified for local key encryption",
+                ),
+                Some(key) => {
+                    let key = key.strip_prefix("0x").unwrap_or(key);
+                    errors.ensure(
+                        key.len() == 64 && key.bytes().all(|ch| ch.is_ascii_hexdigit()),
+                        NAME,
+                        "encryption_key",
+                        || "must be a hex-encoded 32-byte key".to_owned(),
+                    );
+                }
+            },
+            ObjectStoreEncryption::AwsKms => match &self.encryption_kms_key_id {
+                None => errors.missing_field(
+                    NAME,
+                    "encryption_kms_key_id",
+                    "must be specified for AWS KMS encryption",
+                ),
+                Some(key_id) => {
+                    errors.ensure(!key_id.is_empty(), NAME, "encryption_kms_key_id", || {
+                        "must not be empty".to_owned()
+                    });
+                }
+            },
+        }
+
+        for bucket in self.encrypted_buckets.iter().flatten() {
+            errors.ensure(
+                Self::BUCKET_NAMES.contains(&bucket.as_str()),
+                NAME,
+                "encrypted_buckets",
+                || {
+                    format!(
+                        "unknown bucket {bucket:?}; expected one of {:?}",
+                        Self::BUCKET_NAMES
+                    )
+                },
+            );
+        }
+    }
+}
+
+impl Validate for MempoolConfig {
+    fn validate(&self, errors: &mut ConfigErrors) {
+        errors.ensure(self.capacity > 0, "mempool", "capacity", || {
+            "must be positive".to_owned()
+        });
+        errors.ensure(
+            self.sync_batch_size > 0,
+            "mempool",
+            "sync_batch_size",
+            || "must be positive".to_owned(),
+        );
+        errors.ensure(
+            self.max_transactions_per_account != Some(0),
+            "mempool",
+            "max_transactions_per_account",
+            || "must be positive".to_owned(),
+        );
+        errors.ensure(
+            self.max_memory_mb != Some(0),
+            "mempool",
+            "max_memory_mb",
+            || "must be positive".to_owned(),
+        );
+    }
+}
@@ -0,0 +1,180 @@
+//! Benchmarks for bulk insertion of data persisted when sealing miniblocks.
+//!
+//! Requires a Postgres instance specified by the `TEST_DATABASE_URL` env variable (same as DAL unit tests).
+//! Each iteration is performed in a DB transaction that is rolled back afterwards, so the database doesn't grow
+//! during the benchmark.
+
+use std::time::{Duration, Instant};
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use tokio::runtime::Runtime;
+use zksync_dal::ConnectionPool;
+use zksync_types::{
+    block::{MiniblockHasher, MiniblockHeader},
+    fee_model::BatchFeeInput,
+    tx::IncludedTxLocation,
+    AccountTreeId, Address, L1BatchNumber, MiniblockNumber, ProtocolVersion, ProtocolVersionId,
+    StorageKey, StorageLog, VmEvent, H256,
+};
+
+const ROW_COUNTS: &[usize] = &[100, 1_000, 10_000];
+/// Number of rows produced by a single transaction.
+const ROWS_PER_TX: usize = 10;
+const MINIBLOCK_NUMBER: MiniblockNumber = MiniblockNumber(1);
+
+fn create_miniblock_header(number: MiniblockNumber) -> MiniblockHeader {
+    let protocol_version = ProtocolVersionId::default();
+    MiniblockHeader {
+        number,
+        timestamp: number.0.into(),
+        hash: MiniblockHasher::new(number, 0, H256::zero()).finalize(protocol_version),
+        l1_tx_count: 0,
+        l2_tx_count: 0,
+        fee_account_address: Address::default(),
+        gas_per_pubdata_limit: 100,
+        base_fee_per_gas: 100,
+        batch_fee_input: BatchFeeInput::l1_pegged(100, 100),
+        base_system_contracts_hashes: Default::default(),
+        protocol_version: Some(protocol_version),
+        virtual_blocks: 1,
+    }
+}
+
+async fn prepare_storage(pool: &ConnectionPool) {
+    let mut storage = pool.access_storage().await.unwrap();
+    storage
+        .protocol_versions_dal()
+        .save_protocol_version_with_tx(ProtocolVersion::default())
+        .await;
+    storage
+        .blocks_dal()
+        .insert_miniblock(&create_miniblock_header(MINIBLOCK_NUMBER))
+        .await
+        .unwrap();
+}
+
+fn tx_hash(index: usize) -> H256 {
+    H256::from_low_u64_be(index as u64)
+}
+
+fn create_storage_logs(count: usize) -> Vec<(H256, Vec<StorageLog>)> {
+    let logs: Vec<_> = (0..count)
+        .map(|i| {
+            let key = StorageKey::new(AccountTreeId::new(Address::random()), H256::random());
+            StorageLog::new_write_log(key, H256::from_low_u64_be(i as u64))
+        })
+        .collect();
+    logs.chunks(ROWS_PER_TX)
+        .enumerate()
+        .map(|(i, chunk)| (tx_hash(i), chunk.to_vec()))
+        .collect()
+}
+
+fn create_events(count: usize) -> Vec<(IncludedTxLocation, Vec<VmEvent>)> {
+    let events: Vec<_> = (0..count)
+        .map(|i| VmEvent {
+            location: (L1BatchNumber(1), i as u32),
+            address: Address::random(),
+            indexed_topics: vec![H256::random(), H256::random(), H256::random()],
+            value: vec![i as u8; 64],
+        })
+        .collect();
+    events
+        .chunks(ROWS_PER_TX)
+        .enumerate()
+        .map(|(i, chunk)| {
+            let location = IncludedTxLocation {
+                tx_hash: tx_hash(i),
+                tx_index_in_miniblock: i as u32,
+                tx_initiator_address: Address::random(),
+            };
+            (location, chunk.to_vec())
+        })
+        .collect()
+}
+
+async fn insert_storage_logs(
+    pool: &ConnectionPool,
+    iters: u64,
+    logs: &[(H256, Vec<StorageLog>)],
+) -> Duration {
+    let mut storage = pool.access_storage().await.unwrap();
+    let mut total = Duration::ZERO;
+    for _ in 0..iters {
+        let mut transaction = storage.start_transaction().await.unwrap();
+        let started_at = Instant::now();
+        transaction
+            .storage_logs_dal()
+            .insert_storage_logs(MINIBLOCK_NUMBER, logs)
+            .await
+            .unwrap();
+        total += started_at.elapsed();
+        // The transaction is rolled back on drop.
+    }
+    total
+}
+
+async fn save_events(
+    pool: &ConnectionPool,
+    iters: u64,
+    events: &[(IncludedTxLocation, Vec<&VmEvent>)],
+) -> Duration {
+    let mut storage = pool.access_storage().await.unwrap();
+    let mut total = Duration::ZERO;
+    for _ in 0..iters {
+        let mut transaction = storage.start_transaction().await.unwrap();
+        let started_at = Instant::now();
+        transaction
+            .events_dal()
+            .save_events(MINIBLOCK_NUMBER, events)
+            .await;
+        total += started_at.elapsed();
+    }
+    total
+}
+
+fn insertion_benches(criterion: &mut Criterion) {
+    let runtime = Runtime::new().unwrap();
+    let pool = runtime.block_on(ConnectionPool::test_pool());
+    runtime.block_on(prepare_storage(&pool));
+
+    let mut group = criterion.benchmark_group("insert_storage_logs");
+    for &count in ROW_COUNTS {
+        let logs = create_storage_logs(count);
+        group
+            .throughput(Throughput::Elements(count as u64))
+            .bench_with_input(
+                BenchmarkId::from_parameter(count),
+                &logs,
+                |bencher, logs| {
+                    bencher.iter_custom(|iters| {
+                        runtime.block_on(insert_storage_logs(&pool, iters, logs))
+                    });
+                },
+            );
+    }
+    group.finish();
+
+    let mut group = criterion.benchmark_group("save_events");
+    for &count in ROW_COUNTS {
+        let events = create_events(count);
+        let events: Vec<(IncludedTxLocation, Vec<&VmEvent>)> = events
+            .iter()
+            .map(|(location, events)| (*location, events.iter().collect()))
+            .collect();
+        group
+            .throughput(Throughput::Elements(count as u64))
+            .bench_with_input(
+                BenchmarkId::from_parameter(count),
+                &events,
+                |bencher, events| {
+                    bencher
+                        .iter_custom(|iters| runtime.block_on(save_events(&pool, iters, events)));
+                },
+            );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, insertion_benches);
+criterion_main!(benches);
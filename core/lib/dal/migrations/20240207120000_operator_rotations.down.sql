@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS eth_operator_rotations;
+ALTER TABLE eth_txs DROP COLUMN IF EXISTS from_addr;
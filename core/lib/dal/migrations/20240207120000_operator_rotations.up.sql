@@ -0,0 +1,8 @@
+ALTER TABLE eth_txs ADD COLUMN IF NOT EXISTS from_addr BYTEA;
+
+CREATE TABLE IF NOT EXISTS eth_operator_rotations (
+    id SERIAL PRIMARY KEY,
+    requested_at TIMESTAMP NOT NULL,
+    completed_at TIMESTAMP,
+    new_operator_address BYTEA
+);
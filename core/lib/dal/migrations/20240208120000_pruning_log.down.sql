@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS pruning_log;
+DROP TYPE IF EXISTS prune_type;
@@ -0,0 +1,35 @@
+-- Merges partitions back into regular tables. Partitions (including the `*_legacy` ones) may have been dropped
+-- by pruning, so the tables are rebuilt from the remaining data rather than by detaching the legacy partitions.
+
+CREATE TABLE events_unpartitioned (LIKE events INCLUDING DEFAULTS);
+INSERT INTO events_unpartitioned SELECT * FROM events;
+DROP TABLE events;
+ALTER TABLE events_unpartitioned RENAME TO events;
+
+ALTER TABLE events ADD PRIMARY KEY (miniblock_number, event_index_in_block);
+ALTER TABLE events ADD CONSTRAINT events_miniblock_number_fkey
+    FOREIGN KEY (miniblock_number) REFERENCES miniblocks (number);
+CREATE INDEX events_address_block_event_index_in_block_index ON events (address, miniblock_number, event_index_in_block);
+CREATE INDEX events_address_idx ON events (address);
+CREATE INDEX events_block_number_tx_index ON events (miniblock_number, tx_index_in_block);
+CREATE INDEX events_topic1_idx ON events (topic1);
+CREATE INDEX events_topic2_idx ON events (topic2);
+CREATE INDEX events_topic3_idx ON events (topic3);
+CREATE INDEX events_topic4_idx ON events (topic4);
+CREATE INDEX events_transfer_from ON events (topic2, miniblock_number, tx_index_in_block)
+    WHERE topic1 = '\xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef'::bytea;
+CREATE INDEX events_transfer_to ON events (topic3, miniblock_number, tx_index_in_block)
+    WHERE topic1 = '\xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef'::bytea;
+CREATE INDEX events_tx_hash_idx ON events USING hash (tx_hash);
+CREATE INDEX events_tx_initiator_address_idx ON events (tx_initiator_address);
+CREATE INDEX ix_events_t1 ON events (topic1, address, tx_hash);
+
+CREATE TABLE storage_logs_unpartitioned (LIKE storage_logs INCLUDING DEFAULTS);
+INSERT INTO storage_logs_unpartitioned SELECT * FROM storage_logs;
+DROP TABLE storage_logs;
+ALTER TABLE storage_logs_unpartitioned RENAME TO storage_logs;
+
+ALTER TABLE storage_logs ADD PRIMARY KEY (hashed_key, miniblock_number, operation_number);
+CREATE INDEX storage_logs_block_number_idx ON storage_logs (miniblock_number);
+CREATE INDEX storage_logs_contract_address_tx_hash_idx_upd ON storage_logs (tx_hash)
+    WHERE address = '\x0000000000000000000000000000000000008002'::bytea;
@@ -0,0 +1,106 @@
+-- Converts `events` and `storage_logs` to tables partitioned by ranges of miniblock numbers. Existing data is not copied;
+-- instead, the existing tables are attached as `*_legacy` partitions covering all miniblocks up to the next partition
+-- boundary. (Attaching a partition requires a single read-only scan of the table to validate partition bounds.)
+-- Further partitions are created by the DAL; their size must be kept in sync with `PartitionsDal::PARTITION_SIZE`.
+
+ALTER TABLE events RENAME TO events_legacy;
+ALTER INDEX events_pkey RENAME TO events_legacy_pkey;
+ALTER INDEX events_address_block_event_index_in_block_index RENAME TO events_legacy_address_block_event_index_in_block_index;
+ALTER INDEX events_address_idx RENAME TO events_legacy_address_idx;
+ALTER INDEX events_block_number_tx_index RENAME TO events_legacy_block_number_tx_index;
+ALTER INDEX events_topic1_idx RENAME TO events_legacy_topic1_idx;
+ALTER INDEX events_topic2_idx RENAME TO events_legacy_topic2_idx;
+ALTER INDEX events_topic3_idx RENAME TO events_legacy_topic3_idx;
+ALTER INDEX events_topic4_idx RENAME TO events_legacy_topic4_idx;
+ALTER INDEX events_transfer_from RENAME TO events_legacy_transfer_from;
+ALTER INDEX events_transfer_to RENAME TO events_legacy_transfer_to;
+ALTER INDEX events_tx_hash_idx RENAME TO events_legacy_tx_hash_idx;
+ALTER INDEX events_tx_initiator_address_idx RENAME TO events_legacy_tx_initiator_address_idx;
+ALTER INDEX ix_events_t1 RENAME TO ix_events_legacy_t1;
+ALTER TABLE events_legacy RENAME CONSTRAINT events_miniblock_number_fkey TO events_legacy_miniblock_number_fkey;
+
+CREATE TABLE events (
+    miniblock_number BIGINT NOT NULL REFERENCES miniblocks (number),
+    tx_hash BYTEA NOT NULL,
+    tx_index_in_block INT NOT NULL,
+    address BYTEA NOT NULL,
+    event_index_in_block INT NOT NULL,
+    event_index_in_tx INT NOT NULL,
+    topic1 BYTEA NOT NULL,
+    topic2 BYTEA NOT NULL,
+    topic3 BYTEA NOT NULL,
+    topic4 BYTEA NOT NULL,
+    value BYTEA NOT NULL,
+    created_at TIMESTAMP NOT NULL,
+    updated_at TIMESTAMP NOT NULL,
+    tx_initiator_address BYTEA NOT NULL DEFAULT '\x0000000000000000000000000000000000000000'::bytea,
+    PRIMARY KEY (miniblock_number, event_index_in_block)
+) PARTITION BY RANGE (miniblock_number);
+
+CREATE INDEX events_address_block_event_index_in_block_index ON events (address, miniblock_number, event_index_in_block);
+CREATE INDEX events_address_idx ON events (address);
+CREATE INDEX events_block_number_tx_index ON events (miniblock_number, tx_index_in_block);
+CREATE INDEX events_topic1_idx ON events (topic1);
+CREATE INDEX events_topic2_idx ON events (topic2);
+CREATE INDEX events_topic3_idx ON events (topic3);
+CREATE INDEX events_topic4_idx ON events (topic4);
+CREATE INDEX events_transfer_from ON events (topic2, miniblock_number, tx_index_in_block)
+    WHERE topic1 = '\xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef'::bytea;
+CREATE INDEX events_transfer_to ON events (topic3, miniblock_number, tx_index_in_block)
+    WHERE topic1 = '\xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef'::bytea;
+CREATE INDEX events_tx_hash_idx ON events USING hash (tx_hash);
+CREATE INDEX events_tx_initiator_address_idx ON events (tx_initiator_address);
+CREATE INDEX ix_events_t1 ON events (topic1, address, tx_hash);
+
+ALTER TABLE storage_logs RENAME TO storage_logs_legacy;
+ALTER INDEX storage_logs_pkey RENAME TO storage_logs_legacy_pkey;
+ALTER INDEX storage_logs_block_number_idx RENAME TO storage_logs_legacy_block_number_idx;
+ALTER INDEX storage_logs_contract_address_tx_hash_idx_upd RENAME TO storage_logs_legacy_contract_address_tx_hash_idx_upd;
+
+CREATE TABLE storage_logs (
+    hashed_key BYTEA NOT NULL,
+    address BYTEA NOT NULL,
+    key BYTEA NOT NULL,
+    value BYTEA NOT NULL,
+    operation_number INT NOT NULL,
+    tx_hash BYTEA NOT NULL,
+    miniblock_number BIGINT NOT NULL,
+    created_at TIMESTAMP NOT NULL,
+    updated_at TIMESTAMP NOT NULL,
+    PRIMARY KEY (hashed_key, miniblock_number, operation_number)
+) PARTITION BY RANGE (miniblock_number);
+
+CREATE INDEX storage_logs_block_number_idx ON storage_logs (miniblock_number);
+CREATE INDEX storage_logs_contract_address_tx_hash_idx_upd ON storage_logs (tx_hash)
+    WHERE address = '\x0000000000000000000000000000000000008002'::bytea;
+
+DO $$
+DECLARE
+    partition_size CONSTANT BIGINT := 1000000;
+    last_miniblock BIGINT;
+    first_partition BIGINT;
+    partitioned_table TEXT;
+BEGIN
+    -- Storage logs may reference a miniblock not present in `miniblocks` after snapshot recovery.
+    SELECT GREATEST(
+        (SELECT MAX(number) FROM miniblocks),
+        (SELECT MAX(miniblock_number) FROM events_legacy),
+        (SELECT MAX(miniblock_number) FROM storage_logs_legacy),
+        (SELECT MAX(miniblock_number) FROM snapshot_recovery)
+    ) INTO last_miniblock;
+    first_partition := COALESCE(last_miniblock / partition_size + 1, 0);
+
+    FOREACH partitioned_table IN ARRAY ARRAY['events', 'storage_logs'] LOOP
+        EXECUTE format(
+            'ALTER TABLE %I ATTACH PARTITION %I FOR VALUES FROM (MINVALUE) TO (%s)',
+            partitioned_table, partitioned_table || '_legacy', first_partition * partition_size
+        );
+        FOR partition_index IN first_partition..first_partition + 1 LOOP
+            EXECUTE format(
+                'CREATE TABLE %I PARTITION OF %I FOR VALUES FROM (%s) TO (%s)',
+                partitioned_table || '_p' || partition_index, partitioned_table,
+                partition_index * partition_size, (partition_index + 1) * partition_size
+            );
+        END LOOP;
+    END LOOP;
+END $$;
@@ -0,0 +1,7 @@
+CREATE TABLE IF NOT EXISTS block_exporter_checkpoints (
+    stream_name TEXT PRIMARY KEY,
+    last_exported_miniblock BIGINT NOT NULL,
+
+    created_at TIMESTAMP NOT NULL,
+    updated_at TIMESTAMP NOT NULL
+);
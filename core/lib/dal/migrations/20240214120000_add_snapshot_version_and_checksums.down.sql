@@ -0,0 +1,6 @@
+ALTER TABLE snapshots
+    DROP COLUMN version,
+    DROP COLUMN factory_deps_checksum,
+    DROP COLUMN storage_logs_checksums,
+    DROP COLUMN tokens_filepath,
+    DROP COLUMN tokens_checksum;
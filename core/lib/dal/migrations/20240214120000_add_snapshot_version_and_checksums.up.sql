@@ -0,0 +1,11 @@
+ALTER TABLE snapshots
+    ADD COLUMN version INT NOT NULL DEFAULT 0,
+    ADD COLUMN factory_deps_checksum BYTEA,
+    ADD COLUMN storage_logs_checksums BYTEA[] NOT NULL DEFAULT '{}',
+    ADD COLUMN tokens_filepath TEXT,
+    ADD COLUMN tokens_checksum BYTEA;
+
+-- Checksums are set by index when storage logs chunks are produced, so the array must have the same length
+-- as `storage_logs_filepaths`; empty values correspond to missing checksums.
+UPDATE snapshots
+SET storage_logs_checksums = ARRAY_FILL(''::BYTEA, ARRAY[CARDINALITY(storage_logs_filepaths)]);
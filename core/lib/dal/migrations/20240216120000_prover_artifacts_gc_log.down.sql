@@ -0,0 +1 @@
+DROP TABLE IF EXISTS prover_artifacts_gc_log;
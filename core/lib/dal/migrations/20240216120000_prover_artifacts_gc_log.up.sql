@@ -0,0 +1,6 @@
+CREATE TABLE IF NOT EXISTS prover_artifacts_gc_log (
+    last_cleaned_l1_batch BIGINT PRIMARY KEY,
+    removed_artifacts BIGINT NOT NULL,
+
+    created_at TIMESTAMP NOT NULL
+);
@@ -0,0 +1,11 @@
+DROP INDEX IF EXISTS idx_prover_jobs_fri_queued_order;
+CREATE INDEX IF NOT EXISTS idx_prover_jobs_fri_queued_order
+    ON prover_jobs_fri (aggregation_round DESC, l1_batch_number ASC, id ASC)
+    WHERE status = 'queued';
+DROP INDEX IF EXISTS idx_prover_jobs_fri_queued_order2;
+CREATE INDEX IF NOT EXISTS idx_prover_jobs_fri_queued_order2
+    ON prover_jobs_fri (l1_batch_number ASC, aggregation_round DESC, id ASC)
+    WHERE status = 'queued';
+
+DROP TABLE IF EXISTS prover_fri_l1_batch_priorities;
+ALTER TABLE prover_jobs_fri DROP COLUMN IF EXISTS priority;
@@ -0,0 +1,18 @@
+ALTER TABLE prover_jobs_fri ADD COLUMN IF NOT EXISTS priority INT NOT NULL DEFAULT 0;
+
+CREATE TABLE IF NOT EXISTS prover_fri_l1_batch_priorities (
+    l1_batch_number BIGINT PRIMARY KEY,
+    priority INT NOT NULL,
+
+    created_at TIMESTAMP NOT NULL,
+    updated_at TIMESTAMP NOT NULL
+);
+
+DROP INDEX IF EXISTS idx_prover_jobs_fri_queued_order;
+CREATE INDEX IF NOT EXISTS idx_prover_jobs_fri_queued_order
+    ON prover_jobs_fri (priority DESC, aggregation_round DESC, l1_batch_number ASC, id ASC)
+    WHERE status = 'queued';
+DROP INDEX IF EXISTS idx_prover_jobs_fri_queued_order2;
+CREATE INDEX IF NOT EXISTS idx_prover_jobs_fri_queued_order2
+    ON prover_jobs_fri (priority DESC, l1_batch_number ASC, aggregation_round DESC, id ASC)
+    WHERE status = 'queued';
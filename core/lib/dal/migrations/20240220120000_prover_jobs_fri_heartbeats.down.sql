@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS idx_prover_jobs_fri_in_progress_heartbeats;
+ALTER TABLE prover_jobs_fri DROP COLUMN IF EXISTS last_heartbeat_at;
@@ -0,0 +1,5 @@
+ALTER TABLE prover_jobs_fri ADD COLUMN IF NOT EXISTS last_heartbeat_at TIMESTAMP;
+
+CREATE INDEX IF NOT EXISTS idx_prover_jobs_fri_in_progress_heartbeats
+    ON prover_jobs_fri (last_heartbeat_at)
+    WHERE status = 'in_progress';
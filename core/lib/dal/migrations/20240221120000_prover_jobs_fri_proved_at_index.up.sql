@@ -0,0 +1,3 @@
+CREATE INDEX IF NOT EXISTS idx_prover_jobs_fri_successful_updated_at
+    ON prover_jobs_fri (updated_at)
+    WHERE status = 'successful';
@@ -0,0 +1,3 @@
+ALTER TABLE snapshots
+    DROP COLUMN base_l1_batch_number,
+    DROP COLUMN storage_logs_source_l1_batches;
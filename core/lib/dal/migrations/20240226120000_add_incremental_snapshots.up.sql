@@ -0,0 +1,3 @@
+ALTER TABLE snapshots
+    ADD COLUMN base_l1_batch_number BIGINT,
+    ADD COLUMN storage_logs_source_l1_batches BIGINT[] NOT NULL DEFAULT '{}';
@@ -62,7 +62,6 @@ impl BasicWitnessInputProducerDal<'_, '_> {
             BasicWitnessInputProducerJobStatus::Queued as BasicWitnessInputProducerJobStatus,
         )
         .instrument("create_basic_witness_input_producer_job")
-        .report_latency()
         .execute(self.storage)
         .await?;
 
@@ -113,7 +112,6 @@ impl BasicWitnessInputProducerDal<'_, '_> {
             JOB_MAX_ATTEMPT,
         )
         .instrument("get_next_basic_witness_input_producer_job")
-        .report_latency()
         .fetch_optional(self.storage)
         .await?
         .map(|job| L1BatchNumber(job.l1_batch_number as u32));
@@ -136,7 +134,9 @@ impl BasicWitnessInputProducerDal<'_, '_> {
             "#,
             l1_batch_number.0 as i64,
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_basic_witness_input_producer_job_attempts")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .fetch_optional(self.storage)
         .await?
         .map(|job| job.attempts as u32);
 
@@ -166,7 +166,6 @@ impl BasicWitnessInputProducerDal<'_, '_> {
             object_path,
         )
         .instrument("mark_job_as_successful")
-        .report_latency()
         .execute(self.storage)
         .await?;
 
@@ -200,7 +199,6 @@ impl BasicWitnessInputProducerDal<'_, '_> {
             BasicWitnessInputProducerJobStatus::Successful as BasicWitnessInputProducerJobStatus,
         )
         .instrument("mark_job_as_failed")
-        .report_latency()
         .fetch_optional(self.storage)
         .await?
         .map(|job| job.attempts as u32);
@@ -217,7 +215,8 @@ impl BasicWitnessInputProducerDal<'_, '_> {
             DELETE FROM basic_witness_input_producer_jobs
             "#
         )
-        .execute(self.storage.conn())
+        .instrument("delete_all_jobs")
+        .execute(self.storage)
         .await?;
         Ok(())
     }
@@ -0,0 +1,106 @@
+//! Checkpoints of block exporters publishing node data to external streams (e.g., Kafka topics).
+
+use zksync_types::MiniblockNumber;
+
+use crate::{instrument::InstrumentExt, StorageProcessor};
+
+#[derive(Debug)]
+pub struct BlockExporterDal<'a, 'c> {
+    pub(crate) storage: &'a mut StorageProcessor<'c>,
+}
+
+impl BlockExporterDal<'_, '_> {
+    /// Returns the last miniblock fully exported to the specified stream, or `None` if nothing was exported yet.
+    pub async fn get_checkpoint(
+        &mut self,
+        stream_name: &str,
+    ) -> sqlx::Result<Option<MiniblockNumber>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                last_exported_miniblock
+            FROM
+                block_exporter_checkpoints
+            WHERE
+                stream_name = $1
+            "#,
+            stream_name
+        )
+        .instrument("get_checkpoint")
+        .with_arg("stream_name", &stream_name)
+        .fetch_optional(self.storage)
+        .await?;
+        Ok(row.map(|row| MiniblockNumber(row.last_exported_miniblock as u32)))
+    }
+
+    /// Saves the last miniblock fully exported to the specified stream.
+    pub async fn save_checkpoint(
+        &mut self,
+        stream_name: &str,
+        last_exported_miniblock: MiniblockNumber,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                block_exporter_checkpoints (
+                    stream_name,
+                    last_exported_miniblock,
+                    created_at,
+                    updated_at
+                )
+            VALUES
+                ($1, $2, NOW(), NOW())
+            ON CONFLICT (stream_name) DO
+            UPDATE
+            SET
+                last_exported_miniblock = excluded.last_exported_miniblock,
+                updated_at = NOW()
+            "#,
+            stream_name,
+            i64::from(last_exported_miniblock.0)
+        )
+        .instrument("save_checkpoint")
+        .with_arg("stream_name", &stream_name)
+        .with_arg("last_exported_miniblock", &last_exported_miniblock)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ConnectionPool;
+
+    #[tokio::test]
+    async fn saving_checkpoints() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        let mut dal = conn.block_exporter_dal();
+
+        assert_eq!(dal.get_checkpoint("blocks").await.unwrap(), None);
+        dal.save_checkpoint("blocks", MiniblockNumber(3))
+            .await
+            .unwrap();
+        dal.save_checkpoint("other", MiniblockNumber(1))
+            .await
+            .unwrap();
+        assert_eq!(
+            dal.get_checkpoint("blocks").await.unwrap(),
+            Some(MiniblockNumber(3))
+        );
+
+        dal.save_checkpoint("blocks", MiniblockNumber(5))
+            .await
+            .unwrap();
+        assert_eq!(
+            dal.get_checkpoint("blocks").await.unwrap(),
+            Some(MiniblockNumber(5))
+        );
+        assert_eq!(
+            dal.get_checkpoint("other").await.unwrap(),
+            Some(MiniblockNumber(1))
+        );
+    }
+}
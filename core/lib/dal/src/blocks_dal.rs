@@ -6,7 +6,7 @@ use std::{
 
 use anyhow::Context as _;
 use bigdecimal::{BigDecimal, FromPrimitive, ToPrimitive};
-use sqlx::Row;
+use sqlx::{types::chrono::NaiveDateTime, Row};
 use zksync_types::{
     aggregated_operations::AggregatedActionType,
     block::{BlockGasCount, L1BatchHeader, L1BatchTreeData, MiniblockHeader},
@@ -37,7 +37,8 @@ impl BlocksDal<'_, '_> {
                 l1_batches
             "#
         )
-        .fetch_one(self.storage.conn())
+        .instrument("is_genesis_needed")
+        .fetch_one(self.storage)
         .await?
         .count;
         Ok(count == 0)
@@ -53,7 +54,6 @@ impl BlocksDal<'_, '_> {
             "#
         )
         .instrument("get_sealed_block_number")
-        .report_latency()
         .fetch_one(self.storage)
         .await?;
 
@@ -70,7 +70,6 @@ impl BlocksDal<'_, '_> {
             "#
         )
         .instrument("get_sealed_miniblock_number")
-        .report_latency()
         .fetch_one(self.storage)
         .await?;
 
@@ -88,7 +87,6 @@ impl BlocksDal<'_, '_> {
             "#
         )
         .instrument("get_earliest_l1_batch_number")
-        .report_latency()
         .fetch_one(self.storage)
         .await?;
 
@@ -109,7 +107,6 @@ impl BlocksDal<'_, '_> {
             "#
         )
         .instrument("get_last_block_number_with_metadata")
-        .report_latency()
         .fetch_one(self.storage)
         .await?;
 
@@ -135,7 +132,6 @@ impl BlocksDal<'_, '_> {
             "#
         )
         .instrument("get_next_l1_batch_ready_for_commitment_generation")
-        .report_latency()
         .fetch_optional(self.storage)
         .await?;
 
@@ -158,7 +154,6 @@ impl BlocksDal<'_, '_> {
             "#
         )
         .instrument("get_earliest_l1_batch_number_with_metadata")
-        .report_latency()
         .fetch_one(self.storage)
         .await?;
 
@@ -312,7 +307,6 @@ impl BlocksDal<'_, '_> {
             number.0 as i64
         )
         .instrument("get_initial_bootloader_heap")
-        .report_latency()
         .with_arg("number", &number)
         .fetch_optional(self.storage)
         .await?
@@ -341,7 +335,6 @@ impl BlocksDal<'_, '_> {
             number.0 as i64
         )
         .instrument("get_storage_refunds")
-        .report_latency()
         .with_arg("number", &number)
         .fetch_optional(self.storage)
         .await?
@@ -372,7 +365,6 @@ impl BlocksDal<'_, '_> {
             number.0 as i64
         )
         .instrument("get_events_queue")
-        .report_latency()
         .with_arg("number", &number)
         .fetch_optional(self.storage)
         .await?
@@ -406,7 +398,9 @@ impl BlocksDal<'_, '_> {
                     number_range.start().0 as i64,
                     number_range.end().0 as i64
                 )
-                .execute(self.storage.conn())
+                .instrument("set_eth_tx_id#1")
+                .with_arg("eth_tx_id", &eth_tx_id)
+                .execute(self.storage)
                 .await?;
             }
             AggregatedActionType::PublishProofOnchain => {
@@ -423,7 +417,9 @@ impl BlocksDal<'_, '_> {
                     number_range.start().0 as i64,
                     number_range.end().0 as i64
                 )
-                .execute(self.storage.conn())
+                .instrument("set_eth_tx_id#2")
+                .with_arg("eth_tx_id", &eth_tx_id)
+                .execute(self.storage)
                 .await?;
             }
             AggregatedActionType::Execute => {
@@ -440,7 +436,9 @@ impl BlocksDal<'_, '_> {
                     number_range.start().0 as i64,
                     number_range.end().0 as i64
                 )
-                .execute(self.storage.conn())
+                .instrument("set_eth_tx_id#3")
+                .with_arg("eth_tx_id", &eth_tx_id)
+                .execute(self.storage)
                 .await?;
             }
         }
@@ -558,7 +556,8 @@ impl BlocksDal<'_, '_> {
             pubdata_input,
             serde_json::to_value(predicted_circuits_by_type).unwrap(),
         )
-        .execute(transaction.conn())
+        .instrument("insert_l1_batch#1")
+        .execute(&mut transaction)
         .await?;
 
         sqlx::query!(
@@ -571,7 +570,8 @@ impl BlocksDal<'_, '_> {
             header.number.0 as i64,
             events_queue
         )
-        .execute(transaction.conn())
+        .instrument("insert_l1_batch#2")
+        .execute(&mut transaction)
         .await?;
         transaction.commit().await?;
 
@@ -632,7 +632,8 @@ impl BlocksDal<'_, '_> {
             miniblock_header.virtual_blocks as i64,
             miniblock_header.batch_fee_input.fair_pubdata_price() as i64,
         )
-        .execute(self.storage.conn())
+        .instrument("insert_miniblock")
+        .execute(self.storage)
         .await?;
         Ok(())
     }
@@ -667,7 +668,8 @@ impl BlocksDal<'_, '_> {
                 1
             "#,
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_last_sealed_miniblock_header")
+        .fetch_optional(self.storage)
         .await?;
 
         let Some(header) = header else {
@@ -712,7 +714,8 @@ impl BlocksDal<'_, '_> {
             "#,
             miniblock_number.0 as i64,
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_miniblock_header")
+        .fetch_optional(self.storage)
         .await?;
 
         let Some(header) = header else {
@@ -741,7 +744,9 @@ impl BlocksDal<'_, '_> {
             "#,
             l1_batch_number.0 as i32,
         )
-        .execute(self.storage.conn())
+        .instrument("mark_miniblocks_as_executed_in_l1_batch")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .execute(self.storage)
         .await?;
         Ok(())
     }
@@ -769,7 +774,6 @@ impl BlocksDal<'_, '_> {
         )
         .instrument("save_batch_tree_data")
         .with_arg("number", &number)
-        .report_latency()
         .execute(self.storage)
         .await?;
 
@@ -792,7 +796,6 @@ impl BlocksDal<'_, '_> {
             )
             .instrument("get_matching_batch_hash")
             .with_arg("number", &number)
-            .report_latency()
             .fetch_one(self.storage)
             .await?
             .count;
@@ -852,7 +855,6 @@ impl BlocksDal<'_, '_> {
         )
         .instrument("save_l1_batch_commitment_artifacts")
         .with_arg("number", &number)
-        .report_latency()
         .execute(&mut transaction)
         .await?;
         if update_result.rows_affected() == 0 {
@@ -876,7 +878,6 @@ impl BlocksDal<'_, '_> {
             )
             .instrument("get_matching_batch_commitment")
             .with_arg("number", &number)
-            .report_latency()
             .fetch_one(&mut transaction)
             .await?
             .count;
@@ -905,7 +906,6 @@ impl BlocksDal<'_, '_> {
         )
         .instrument("save_batch_aux_commitments")
         .with_arg("number", &number)
-        .report_latency()
         .execute(&mut transaction)
         .await?;
 
@@ -997,7 +997,8 @@ impl BlocksDal<'_, '_> {
                 1
             "#
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_number_of_last_l1_batch_committed_on_eth")
+        .fetch_optional(self.storage)
         .await?
         .map(|row| L1BatchNumber(row.number as u32)))
     }
@@ -1014,7 +1015,8 @@ impl BlocksDal<'_, '_> {
                 eth_prove_tx_id IS NOT NULL
             "#
         )
-        .fetch_one(self.storage.conn())
+        .instrument("get_last_l1_batch_with_prove_tx")
+        .fetch_one(self.storage)
         .await?;
 
         Ok(L1BatchNumber(row.number as u32))
@@ -1035,7 +1037,9 @@ impl BlocksDal<'_, '_> {
             "#,
             l1_batch_number.0 as i64
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_eth_commit_tx_id")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .fetch_optional(self.storage)
         .await?;
 
         Ok(row.and_then(|row| row.eth_commit_tx_id.map(|n| n as u64)))
@@ -1060,7 +1064,8 @@ impl BlocksDal<'_, '_> {
                 1
             "#
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_number_of_last_l1_batch_proven_on_eth")
+        .fetch_optional(self.storage)
         .await?
         .map(|record| L1BatchNumber(record.number as u32)))
     }
@@ -1084,7 +1089,37 @@ impl BlocksDal<'_, '_> {
                 1
             "#
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_number_of_last_l1_batch_executed_on_eth")
+        .fetch_optional(self.storage)
+        .await?
+        .map(|row| L1BatchNumber(row.number as u32)))
+    }
+
+    /// Returns the number of the last L1 batch whose execution transaction was confirmed on L1
+    /// no later than `executed_before`.
+    pub async fn get_number_of_last_l1_batch_executed_before(
+        &mut self,
+        executed_before: NaiveDateTime,
+    ) -> sqlx::Result<Option<L1BatchNumber>> {
+        Ok(sqlx::query!(
+            r#"
+            SELECT
+                number
+            FROM
+                l1_batches
+                JOIN eth_txs_history AS execute_tx ON (l1_batches.eth_execute_tx_id = execute_tx.eth_tx_id)
+            WHERE
+                execute_tx.confirmed_at <= $1
+            ORDER BY
+                number DESC
+            LIMIT
+                1
+            "#,
+            executed_before
+        )
+        .instrument("get_number_of_last_l1_batch_executed_before")
+        .with_arg("executed_before", &executed_before)
+        .fetch_optional(self.storage)
         .await?
         .map(|row| L1BatchNumber(row.number as u32)))
     }
@@ -1182,7 +1217,9 @@ impl BlocksDal<'_, '_> {
             "#,
             l1_batch_number.0 as i64
         )
-        .execute(self.storage.conn())
+        .instrument("set_skip_proof_for_l1_batch")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .execute(self.storage)
         .await?;
         Ok(())
     }
@@ -1367,7 +1404,8 @@ impl BlocksDal<'_, '_> {
                 1
             "#
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("raw_ready_for_execute_l1_batches#1")
+        .fetch_optional(self.storage)
         .await?;
 
         let Some(row) = row else { return Ok(vec![]) };
@@ -1399,7 +1437,8 @@ impl BlocksDal<'_, '_> {
             "#,
             max_l1_batch_timestamp_seconds_bd,
         )
-        .fetch_one(self.storage.conn())
+        .instrument("raw_ready_for_execute_l1_batches#2")
+        .fetch_one(self.storage)
         .await?;
 
         Ok(if let Some(max_ready_to_send_block) = row.max {
@@ -1643,7 +1682,9 @@ impl BlocksDal<'_, '_> {
             "#,
             number.0 as i64
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_l1_batch_state_root")
+        .with_arg("number", &number)
+        .fetch_optional(self.storage)
         .await?
         .and_then(|row| row.hash)
         .map(|hash| H256::from_slice(&hash)))
@@ -1665,7 +1706,9 @@ impl BlocksDal<'_, '_> {
             "#,
             number.0 as i64
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_l1_batch_state_root_and_timestamp")
+        .with_arg("number", &number)
+        .fetch_optional(self.storage)
         .await?
         else {
             return Ok(None);
@@ -1708,7 +1751,9 @@ impl BlocksDal<'_, '_> {
             "#,
             number.0 as i64
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_l1_batch_tree_data")
+        .with_arg("number", &number)
+        .fetch_optional(self.storage)
         .await?;
         Ok(row.and_then(|row| {
             Some(L1BatchTreeData {
@@ -1761,7 +1806,9 @@ impl BlocksDal<'_, '_> {
             "#,
             l1_batch_number.0 as i64
         )
-        .fetch_all(self.storage.conn())
+        .instrument("get_l1_batch_factory_deps")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .fetch_all(self.storage)
         .await?
         .into_iter()
         .map(|row| (H256::from_slice(&row.bytecode_hash), row.bytecode))
@@ -1789,7 +1836,8 @@ impl BlocksDal<'_, '_> {
             "#,
             block_number
         )
-        .execute(self.storage.conn())
+        .instrument("delete_initial_writes_inner")
+        .execute(self.storage)
         .await?;
         Ok(())
     }
@@ -1814,7 +1862,8 @@ impl BlocksDal<'_, '_> {
             "#,
             block_number
         )
-        .execute(self.storage.conn())
+        .instrument("delete_l1_batches_inner")
+        .execute(self.storage)
         .await?;
         Ok(())
     }
@@ -1841,7 +1890,8 @@ impl BlocksDal<'_, '_> {
             "#,
             block_number
         )
-        .execute(self.storage.conn())
+        .instrument("delete_miniblocks_inner")
+        .execute(self.storage)
         .await?;
         Ok(())
     }
@@ -1865,7 +1915,10 @@ impl BlocksDal<'_, '_> {
         sqlx::query(&sql_query_str)
             .bind(number_range.start().0 as i64)
             .bind(number_range.end().0 as i64)
-            .fetch_one(self.storage.conn())
+            .instrument("get_l1_batches_predicted_gas")
+            .with_arg("number_range", &number_range)
+            .with_arg("op_type", &op_type)
+            .fetch_one(self.storage)
             .await?
             .get::<BigDecimal, &str>("sum")
             .to_u32()
@@ -1888,7 +1941,9 @@ impl BlocksDal<'_, '_> {
             "#,
             l1_batch_number.0 as i64
         )
-        .fetch_one(self.storage.conn())
+        .instrument("get_miniblock_range_of_l1_batch")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .fetch_one(self.storage)
         .await?;
         let Some(min) = row.min else { return Ok(None) };
         let Some(max) = row.max else { return Ok(None) };
@@ -1904,7 +1959,8 @@ impl BlocksDal<'_, '_> {
         let count = sqlx::query_scalar!(
             "SELECT COUNT(miniblocks.number) FROM miniblocks WHERE l1_batch_number IS NULL"
         )
-        .fetch_one(self.storage.conn())
+        .instrument("pending_batch_exists")
+        .fetch_one(self.storage)
         .await?
         .unwrap_or(0);
 
@@ -1929,7 +1985,8 @@ impl BlocksDal<'_, '_> {
                 1
             "#,
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("oldest_uncommitted_batch_timestamp")
+        .fetch_optional(self.storage)
         .await?
         .map(|row| row.timestamp as u64))
     }
@@ -1950,7 +2007,8 @@ impl BlocksDal<'_, '_> {
                 1
             "#,
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("oldest_unproved_batch_timestamp")
+        .fetch_optional(self.storage)
         .await?
         .map(|row| row.timestamp as u64))
     }
@@ -1971,7 +2029,8 @@ impl BlocksDal<'_, '_> {
                 1
             "#,
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("oldest_unexecuted_batch_timestamp")
+        .fetch_optional(self.storage)
         .await?
         .map(|row| row.timestamp as u64))
     }
@@ -1991,7 +2050,9 @@ impl BlocksDal<'_, '_> {
             "#,
             l1_batch_number.0 as i64
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_batch_protocol_version_id")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .fetch_optional(self.storage)
         .await?
         else {
             return Ok(None);
@@ -2017,7 +2078,9 @@ impl BlocksDal<'_, '_> {
             "#,
             miniblock_number.0 as i64
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_miniblock_protocol_version_id")
+        .with_arg("miniblock_number", &miniblock_number)
+        .fetch_optional(self.storage)
         .await?
         else {
             return Ok(None);
@@ -2043,7 +2106,9 @@ impl BlocksDal<'_, '_> {
             "#,
             miniblock_number.0 as i64,
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_miniblock_timestamp")
+        .with_arg("miniblock_number", &miniblock_number)
+        .fetch_optional(self.storage)
         .await?
         .map(|row| row.timestamp as u64))
     }
@@ -2062,7 +2127,9 @@ impl BlocksDal<'_, '_> {
             "#,
             id as i32,
         )
-        .execute(self.storage.conn())
+        .instrument("set_protocol_version_for_pending_miniblocks")
+        .with_arg("id", &id)
+        .execute(self.storage)
         .await?;
         Ok(())
     }
@@ -2098,7 +2165,9 @@ impl BlocksDal<'_, '_> {
             "#,
             number.0 as i32
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("raw_fee_address_for_miniblock")
+        .with_arg("number", &number)
+        .fetch_optional(self.storage)
         .await?
         else {
             return Ok(None);
@@ -2122,7 +2191,9 @@ impl BlocksDal<'_, '_> {
             "#,
             miniblock_number.0 as i32
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_virtual_blocks_for_miniblock")
+        .with_arg("miniblock_number", &miniblock_number)
+        .fetch_optional(self.storage)
         .await?
         .map(|row| row.virtual_blocks as u32))
     }
@@ -2155,7 +2226,9 @@ impl BlocksDal<'_, '_> {
             "#,
             miniblock_number.0 as i32
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("maybe_load_fee_address")
+        .with_arg("miniblock_number", &miniblock_number)
+        .fetch_optional(self.storage)
         .await?
         else {
             return Ok(());
@@ -2199,7 +2272,8 @@ impl BlocksDal<'_, '_> {
                 AND fee_account_address = '\x0000000000000000000000000000000000000000'::bytea
             "#
         )
-        .execute(self.storage.conn())
+        .instrument("copy_fee_account_address_for_pending_miniblocks")
+        .execute(self.storage)
         .await?;
 
         Ok(execution_result.rows_affected())
@@ -2213,7 +2287,8 @@ impl BlocksDal<'_, '_> {
             WHERE table_name = 'l1_batches' AND column_name = 'fee_account_address'
             "#
         )
-        .fetch_one(self.storage.conn())
+        .instrument("check_l1_batches_have_fee_account_address")
+        .fetch_one(self.storage)
         .await?
         .unwrap_or(0);
 
@@ -2241,7 +2316,8 @@ impl BlocksDal<'_, '_> {
             numbers.start().0 as i64,
             numbers.end().0 as i64
         )
-        .execute(self.storage.conn())
+        .instrument("copy_fee_account_address_for_miniblocks")
+        .execute(self.storage)
         .await?;
 
         Ok(execution_result.rows_affected())
@@ -2264,7 +2340,10 @@ impl BlocksDal<'_, '_> {
             fee_account_address.as_bytes(),
             l1_batch.0 as i64
         )
-        .execute(self.storage.conn())
+        .instrument("set_l1_batch_fee_address")
+        .with_arg("l1_batch", &l1_batch)
+        .with_arg("fee_account_address", &fee_account_address)
+        .execute(self.storage)
         .await?;
         Ok(())
     }
@@ -2289,7 +2368,10 @@ impl BlocksDal<'_, '_> {
             hash.as_bytes(),
             batch_num.0 as i64
         )
-        .execute(self.storage.conn())
+        .instrument("set_l1_batch_hash")
+        .with_arg("batch_num", &batch_num)
+        .with_arg("hash", &hash)
+        .execute(self.storage)
         .await?;
         Ok(())
     }
@@ -17,12 +17,24 @@ use crate::{
             bind_block_where_sql_params, web3_block_number_to_sql, web3_block_where_sql,
             ResolvedL1BatchForMiniblock, StorageBlockDetails, StorageL1BatchDetails,
         },
-        storage_transaction::{extract_web3_transaction, web3_transaction_select_sql, CallTrace},
+        storage_transaction::{
+            extract_web3_transaction, web3_transaction_select_sql, CallTrace, LocalizedCallTrace,
+        },
     },
     StorageProcessor,
 };
 
-const BLOCK_GAS_LIMIT: u32 = u32::MAX;
+/// Gas limit of miniblocks returned by the Web3 API.
+pub const BLOCK_GAS_LIMIT: u32 = u32::MAX;
+
+/// Fee-related data of a transaction executed in a miniblock.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransactionFeeData {
+    pub miniblock_number: MiniblockNumber,
+    pub gas_used: U256,
+    /// May be missing for transactions executed before effective gas prices were persisted.
+    pub effective_gas_price: Option<U256>,
+}
 
 #[derive(Debug)]
 pub struct BlocksWeb3Dal<'a, 'c> {
@@ -68,7 +80,12 @@ impl BlocksWeb3Dal<'_, '_> {
         );
 
         let query = bind_block_where_sql_params(&block_id, sqlx::query(&query));
-        let rows = query.fetch_all(self.storage.conn()).await?.into_iter();
+        let rows = query
+            .instrument("get_block_by_web3_block_id")
+            .with_arg("block_id", &block_id)
+            .fetch_all(self.storage)
+            .await?
+            .into_iter();
 
         let block = rows.fold(None, |prev_block, db_row| {
             let mut block = prev_block.unwrap_or_else(|| {
@@ -132,8 +149,13 @@ impl BlocksWeb3Dal<'_, '_> {
             web3_block_where_sql(block_id, 1)
         );
         let query = bind_block_where_sql_params(&block_id, sqlx::query(&query));
+        let row = query
+            .instrument("get_block_tx_count")
+            .with_arg("block_id", &block_id)
+            .fetch_optional(self.storage)
+            .await?;
 
-        Ok(query.fetch_optional(self.storage.conn()).await?.map(|row| {
+        Ok(row.map(|row| {
             let miniblock_number = row.get::<i64, _>("number") as u32;
             let tx_count = row.get::<i32, _>("tx_count") as u32;
             (MiniblockNumber(miniblock_number), tx_count.into())
@@ -163,7 +185,10 @@ impl BlocksWeb3Dal<'_, '_> {
             from_block.0 as i64,
             limit as i32
         )
-        .fetch_all(self.storage.conn())
+        .instrument("get_block_hashes_since")
+        .with_arg("from_block", &from_block)
+        .with_arg("limit", &limit)
+        .fetch_all(self.storage)
         .await?;
 
         let last_block_number = rows.last().map(|row| MiniblockNumber(row.number as u32));
@@ -191,7 +216,9 @@ impl BlocksWeb3Dal<'_, '_> {
             "#,
             from_block.0 as i64,
         )
-        .fetch_all(self.storage.conn())
+        .instrument("get_block_headers_after")
+        .with_arg("from_block", &from_block)
+        .fetch_all(self.storage)
         .await?;
 
         let blocks = rows.into_iter().map(|row| BlockHeader {
@@ -241,7 +268,9 @@ impl BlocksWeb3Dal<'_, '_> {
             }
         };
         let row = bind_block_where_sql_params(&block_id, sqlx::query(query_str))
-            .fetch_optional(self.storage.conn())
+            .instrument("resolve_block_id")
+            .with_arg("block_id", &block_id)
+            .fetch_optional(self.storage)
             .await?;
 
         let block_number = row
@@ -274,7 +303,8 @@ impl BlocksWeb3Dal<'_, '_> {
                 "#,
                 i64::from(miniblock_l1_batch.0)
             )
-            .fetch_optional(self.storage.conn())
+            .instrument("get_expected_l1_batch_timestamp#1")
+            .fetch_optional(self.storage)
             .await?
             .map(|row| row.timestamp as u64))
         } else {
@@ -315,7 +345,8 @@ impl BlocksWeb3Dal<'_, '_> {
                 "#,
                 i64::from(prev_l1_batch_number.0)
             )
-            .fetch_optional(self.storage.conn())
+            .instrument("get_expected_l1_batch_timestamp#2")
+            .fetch_optional(self.storage)
             .await?
             .map(|row| row.timestamp as u64))
         }
@@ -336,7 +367,9 @@ impl BlocksWeb3Dal<'_, '_> {
             "#,
             block_number.0 as i64
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_miniblock_hash")
+        .with_arg("block_number", &block_number)
+        .fetch_optional(self.storage)
         .await?
         .map(|row| H256::from_slice(&row.hash));
         Ok(hash)
@@ -357,7 +390,9 @@ impl BlocksWeb3Dal<'_, '_> {
             "#,
             block_number.0 as i64
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_l2_to_l1_logs")
+        .with_arg("block_number", &block_number)
+        .fetch_optional(self.storage)
         .await?
         .map(|row| row.l2_to_l1_logs)
         .unwrap_or_default();
@@ -383,7 +418,9 @@ impl BlocksWeb3Dal<'_, '_> {
             "#,
             miniblock_number.0 as i64
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_l1_batch_number_of_miniblock")
+        .with_arg("miniblock_number", &miniblock_number)
+        .fetch_optional(self.storage)
         .await?
         .and_then(|row| row.l1_batch_number);
 
@@ -406,7 +443,9 @@ impl BlocksWeb3Dal<'_, '_> {
             "#,
             l1_batch_number.0 as i64
         )
-        .fetch_one(self.storage.conn())
+        .instrument("get_miniblock_range_of_l1_batch")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .fetch_one(self.storage)
         .await?;
 
         Ok(match (row.min, row.max) {
@@ -434,7 +473,9 @@ impl BlocksWeb3Dal<'_, '_> {
             "#,
             tx_hash.as_bytes()
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_l1_batch_info_for_tx")
+        .with_arg("tx_hash", &tx_hash)
+        .fetch_optional(self.storage)
         .await?;
 
         let result = row.and_then(|row| match (row.l1_batch_number, row.l1_batch_tx_index) {
@@ -447,15 +488,16 @@ impl BlocksWeb3Dal<'_, '_> {
         Ok(result)
     }
 
-    /// Returns call traces for all transactions in the specified miniblock in the order of their execution.
+    /// Returns call traces for all transactions in the specified miniblock in the order of their execution,
+    /// together with the transaction hashes.
     pub async fn get_traces_for_miniblock(
         &mut self,
         block_number: MiniblockNumber,
-    ) -> sqlx::Result<Vec<Call>> {
-        Ok(sqlx::query_as!(
-            CallTrace,
+    ) -> sqlx::Result<Vec<(H256, Call)>> {
+        Ok(sqlx::query!(
             r#"
             SELECT
+                call_traces.tx_hash,
                 call_trace
             FROM
                 call_traces
@@ -467,13 +509,64 @@ impl BlocksWeb3Dal<'_, '_> {
             "#,
             block_number.0 as i64
         )
-        .fetch_all(self.storage.conn())
+        .instrument("get_traces_for_miniblock")
+        .with_arg("block_number", &block_number)
+        .fetch_all(self.storage)
         .await?
         .into_iter()
-        .map(Call::from)
+        .map(|row| {
+            let call_trace = CallTrace {
+                call_trace: row.call_trace,
+            };
+            (H256::from_slice(&row.tx_hash), Call::from(call_trace))
+        })
         .collect())
     }
 
+    /// Returns call traces for transactions in the specified miniblock range (inclusive) in the order
+    /// of their execution, together with the transaction locations. At most `limit` traces are returned.
+    pub async fn get_traces_for_miniblock_range(
+        &mut self,
+        from_block: MiniblockNumber,
+        to_block: MiniblockNumber,
+        limit: usize,
+    ) -> sqlx::Result<Vec<(api::trace::TransactionTraceLocation, Call)>> {
+        let rows = sqlx::query_as!(
+            LocalizedCallTrace,
+            r#"
+            SELECT
+                call_traces.tx_hash,
+                call_trace,
+                transactions.index_in_block AS "index_in_block!",
+                miniblocks.number AS miniblock_number,
+                miniblocks.hash AS miniblock_hash
+            FROM
+                call_traces
+                INNER JOIN transactions ON tx_hash = transactions.hash
+                INNER JOIN miniblocks ON transactions.miniblock_number = miniblocks.number
+            WHERE
+                transactions.miniblock_number BETWEEN $1 AND $2
+            ORDER BY
+                transactions.miniblock_number,
+                transactions.index_in_block
+            LIMIT
+                $3
+            "#,
+            from_block.0 as i64,
+            to_block.0 as i64,
+            limit as i64
+        )
+        .instrument("get_traces_for_miniblock_range")
+        .with_arg("from_block", &from_block)
+        .with_arg("to_block", &to_block)
+        .fetch_all(self.storage)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(LocalizedCallTrace::into_parts)
+            .collect())
+    }
+
     /// Returns `base_fee_per_gas` for miniblock range [min(newest_block - block_count + 1, 0), newest_block]
     /// in descending order of miniblock numbers.
     pub async fn get_fee_history(
@@ -497,7 +590,10 @@ impl BlocksWeb3Dal<'_, '_> {
             newest_block.0 as i64,
             block_count as i64
         )
-        .fetch_all(self.storage.conn())
+        .instrument("get_fee_history")
+        .with_arg("newest_block", &newest_block)
+        .with_arg("block_count", &block_count)
+        .fetch_all(self.storage)
         .await?
         .into_iter()
         .map(|row| bigdecimal_to_u256(row.base_fee_per_gas))
@@ -506,6 +602,46 @@ impl BlocksWeb3Dal<'_, '_> {
         Ok(result)
     }
 
+    /// Returns fee data for all transactions in the miniblock range `[from_block, to_block]` ordered by miniblock number
+    /// and the transaction index within the miniblock.
+    pub async fn get_fee_history_transactions(
+        &mut self,
+        from_block: MiniblockNumber,
+        to_block: MiniblockNumber,
+    ) -> sqlx::Result<Vec<TransactionFeeData>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                miniblock_number AS "miniblock_number!",
+                gas_limit - refunded_gas AS "gas_used!",
+                effective_gas_price
+            FROM
+                transactions
+            WHERE
+                miniblock_number BETWEEN $1 AND $2
+            ORDER BY
+                miniblock_number,
+                index_in_block
+            "#,
+            from_block.0 as i64,
+            to_block.0 as i64
+        )
+        .instrument("get_fee_history_transactions")
+        .with_arg("from_block", &from_block)
+        .with_arg("to_block", &to_block)
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| TransactionFeeData {
+                miniblock_number: MiniblockNumber(row.miniblock_number as u32),
+                gas_used: bigdecimal_to_u256(row.gas_used),
+                effective_gas_price: row.effective_gas_price.map(bigdecimal_to_u256),
+            })
+            .collect())
+    }
+
     pub async fn get_block_details(
         &mut self,
         block_number: MiniblockNumber,
@@ -562,7 +698,6 @@ impl BlocksWeb3Dal<'_, '_> {
         )
         .instrument("get_block_details")
         .with_arg("block_number", &block_number)
-        .report_latency()
         .fetch_optional(self.storage)
         .await?;
 
@@ -637,7 +772,6 @@ impl BlocksWeb3Dal<'_, '_> {
         )
         .instrument("get_l1_batch_details")
         .with_arg("l1_batch_number", &l1_batch_number)
-        .report_latency()
         .fetch_optional(self.storage)
         .await?;
 
@@ -897,9 +1031,22 @@ mod tests {
             .await
             .unwrap();
         assert_eq!(traces.len(), 2);
-        for (trace, tx_result) in traces.iter().zip(&tx_results) {
+        for ((tx_hash, trace), tx_result) in traces.iter().zip(&tx_results) {
             let expected_trace = tx_result.call_trace().unwrap();
+            assert_eq!(*tx_hash, tx_result.hash);
             assert_eq!(*trace, expected_trace);
         }
+
+        let localized_traces = conn
+            .blocks_web3_dal()
+            .get_traces_for_miniblock_range(MiniblockNumber(0), MiniblockNumber(1), 1)
+            .await
+            .unwrap();
+        assert_eq!(localized_traces.len(), 1);
+        let (location, trace) = &localized_traces[0];
+        assert_eq!(location.tx_hash, tx_results[0].hash);
+        assert_eq!(location.tx_index_in_block, 0);
+        assert_eq!(location.block_number, MiniblockNumber(1));
+        assert_eq!(*trace, traces[0].1);
     }
 }
@@ -6,7 +6,7 @@ use std::{
         atomic::{AtomicU64, Ordering},
         Arc,
     },
-    time::Duration,
+    time::{Duration, Instant},
 };
 
 use anyhow::Context as _;
@@ -18,10 +18,11 @@ use sqlx::{
 
 pub use self::processor::StorageProcessor;
 pub(crate) use self::processor::StorageProcessorTags;
-use self::processor::TracedConnections;
+use self::{processor::TracedConnections, replicas::ReadReplicas};
 use crate::metrics::CONNECTION_METRICS;
 
 mod processor;
+mod replicas;
 
 /// Builder for [`ConnectionPool`]s.
 #[derive(Clone)]
@@ -30,6 +31,8 @@ pub struct ConnectionPoolBuilder {
     max_size: u32,
     acquire_timeout: Duration,
     statement_timeout: Option<Duration>,
+    read_replica_urls: Vec<String>,
+    max_replica_lag: Duration,
 }
 
 impl fmt::Debug for ConnectionPoolBuilder {
@@ -40,6 +43,8 @@ impl fmt::Debug for ConnectionPoolBuilder {
             .field("max_size", &self.max_size)
             .field("acquire_timeout", &self.acquire_timeout)
             .field("statement_timeout", &self.statement_timeout)
+            .field("read_replica_count", &self.read_replica_urls.len())
+            .field("max_replica_lag", &self.max_replica_lag)
             .finish()
     }
 }
@@ -63,34 +68,78 @@ impl ConnectionPoolBuilder {
         self
     }
 
+    /// Sets read replicas of the database. Connections for read-only queries acquired via
+    /// [`ConnectionPool::access_storage_for_reads_tagged()`] are routed to the replicas, provided that they
+    /// are not stale. Each replica gets its own pool with the same parameters as the primary database pool.
+    pub fn set_read_replicas(&mut self, replica_urls: Vec<String>) -> &mut Self {
+        self.read_replica_urls = replica_urls;
+        self
+    }
+
+    /// Sets the maximum lag of a read replica behind the primary database for the replica to be used for reads.
+    /// If not specified, the lag is limited to 1 second.
+    pub fn set_max_replica_lag(&mut self, max_lag: Option<Duration>) -> &mut Self {
+        if let Some(max_lag) = max_lag {
+            self.max_replica_lag = max_lag;
+        }
+        self
+    }
+
     /// Returns the maximum number of connections that can be allocated by the pool.
     pub fn max_size(&self) -> u32 {
         self.max_size
     }
 
-    /// Builds a connection pool from this builder.
-    pub async fn build(&self) -> anyhow::Result<ConnectionPool> {
-        let options = PgPoolOptions::new()
+    fn pool_options(&self) -> PgPoolOptions {
+        PgPoolOptions::new()
             .max_connections(self.max_size)
-            .acquire_timeout(self.acquire_timeout);
-        let mut connect_options: PgConnectOptions = self
-            .database_url
-            .parse()
-            .context("Failed parsing database URL")?;
+            .acquire_timeout(self.acquire_timeout)
+    }
+
+    fn connect_options(&self, database_url: &str) -> anyhow::Result<PgConnectOptions> {
+        let mut connect_options: PgConnectOptions = database_url.parse()?;
         if let Some(timeout) = self.statement_timeout {
             let timeout_string = format!("{}s", timeout.as_secs());
             connect_options = connect_options.options([("statement_timeout", timeout_string)]);
         }
-        let pool = options
+        Ok(connect_options)
+    }
+
+    /// Builds a connection pool from this builder.
+    pub async fn build(&self) -> anyhow::Result<ConnectionPool> {
+        let connect_options = self
+            .connect_options(&self.database_url)
+            .context("Failed parsing database URL")?;
+        let pool = self
+            .pool_options()
             .connect_with(connect_options)
             .await
             .context("Failed connecting to database")?;
+
+        let replicas = if self.read_replica_urls.is_empty() {
+            None
+        } else {
+            let replica_pools = self.read_replica_urls.iter().enumerate().map(|(i, url)| {
+                let connect_options = self
+                    .connect_options(url)
+                    .with_context(|| format!("Failed parsing URL for read replica #{i}"))?;
+                // Replicas are connected to lazily, so that an unavailable replica doesn't prevent
+                // the pool from being created. Such a replica will be considered stale.
+                anyhow::Ok(self.pool_options().connect_lazy_with(connect_options))
+            });
+            let replica_pools = replica_pools.collect::<anyhow::Result<_>>()?;
+            Some(Arc::new(ReadReplicas::new(
+                replica_pools,
+                self.max_replica_lag,
+            )))
+        };
         tracing::info!("Created DB pool with parameters {self:?}");
         Ok(ConnectionPool {
             database_url: self.database_url.clone(),
             inner: pool,
             max_size: self.max_size,
             traced_connections: None,
+            replicas,
         })
     }
 
@@ -237,6 +286,7 @@ pub struct ConnectionPool {
     database_url: String,
     max_size: u32,
     traced_connections: Option<Arc<TracedConnections>>,
+    replicas: Option<Arc<ReadReplicas>>,
 }
 
 impl fmt::Debug for ConnectionPool {
@@ -246,6 +296,7 @@ impl fmt::Debug for ConnectionPool {
         formatter
             .debug_struct("ConnectionPool")
             .field("max_size", &self.max_size)
+            .field("has_read_replicas", &self.replicas.is_some())
             .finish_non_exhaustive()
     }
 }
@@ -294,6 +345,8 @@ impl ConnectionPool {
             max_size: max_pool_size,
             acquire_timeout: Duration::from_secs(30), // Default value used by `sqlx`
             statement_timeout: None,
+            read_replica_urls: Vec::new(),
+            max_replica_lag: Duration::from_secs(1),
         }
     }
 
@@ -343,6 +396,41 @@ impl ConnectionPool {
         }
     }
 
+    /// Same as [`Self::access_storage_tagged()`], but for read-only queries that tolerate slightly stale data
+    /// (e.g., historical reads by the API server). If the pool has read replicas, the connection is acquired
+    /// from a replica lagging behind the primary database by no more than the configured maximum lag.
+    /// If all replicas are stale or unavailable, or if the pool has no replicas, the connection is acquired
+    /// from the primary database.
+    ///
+    /// The returned storage processor must not be used for writes.
+    #[track_caller]
+    pub fn access_storage_for_reads_tagged(
+        &self,
+        requester: &'static str,
+    ) -> impl Future<Output = anyhow::Result<StorageProcessor<'_>>> + '_ {
+        let location = Location::caller();
+        async move {
+            let tags = StorageProcessorTags {
+                requester,
+                location,
+            };
+            if let Some(replicas) = &self.replicas {
+                let started_at = Instant::now();
+                if let Some(conn) = replicas.acquire().await {
+                    let elapsed = started_at.elapsed();
+                    CONNECTION_METRICS.acquire.observe(elapsed);
+                    CONNECTION_METRICS.acquire_tagged[&tags.requester].observe(elapsed);
+                    return Ok(StorageProcessor::from_pool(
+                        conn,
+                        Some(tags),
+                        self.traced_connections.as_deref(),
+                    ));
+                }
+            }
+            self.access_storage_inner(Some(tags)).await
+        }
+    }
+
     async fn access_storage_inner(
         &self,
         tags: Option<StorageProcessorTags>,
@@ -448,4 +536,59 @@ mod tests {
             sqlx::Error::Database(db_err) if db_err.message().contains("statement timeout")
         );
     }
+
+    #[tokio::test]
+    async fn routing_reads_to_replica() {
+        let db_url = TestTemplate::empty()
+            .unwrap()
+            .create_db(1)
+            .await
+            .unwrap()
+            .database_url;
+
+        // The "replica" is the primary database itself, so it should never be stale.
+        let pool = ConnectionPool::builder(&db_url, 1)
+            .set_read_replicas(vec![db_url.clone()])
+            .build()
+            .await
+            .unwrap();
+        let mut storage = pool.access_storage_for_reads_tagged("test").await.unwrap();
+        sqlx::query("SELECT 1")
+            .execute(storage.conn())
+            .await
+            .unwrap();
+        drop(storage);
+
+        let replicas = pool.replicas.as_deref().unwrap();
+        assert_eq!(replicas.last_lag(0), Some(Some(Duration::ZERO)));
+    }
+
+    #[tokio::test]
+    async fn falling_back_to_primary_if_replica_is_unavailable() {
+        let db_url = TestTemplate::empty()
+            .unwrap()
+            .create_db(1)
+            .await
+            .unwrap()
+            .database_url;
+
+        let mut unavailable_url: url::Url = db_url.parse().unwrap();
+        unavailable_url.set_port(Some(1)).unwrap();
+        let pool = ConnectionPool::builder(&db_url, 1)
+            .set_acquire_timeout(Some(Duration::from_millis(100)))
+            .set_read_replicas(vec![unavailable_url.to_string()])
+            .build()
+            .await
+            .unwrap();
+        for _ in 0..2 {
+            let mut storage = pool.access_storage_for_reads_tagged("test").await.unwrap();
+            sqlx::query("SELECT 1")
+                .execute(storage.conn())
+                .await
+                .unwrap();
+        }
+
+        let replicas = pool.replicas.as_deref().unwrap();
+        assert_eq!(replicas.last_lag(0), Some(None));
+    }
 }
@@ -0,0 +1,165 @@
+//! Routing of read-only queries to read replicas of the primary database.
+
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use sqlx::{
+    pool::PoolConnection,
+    postgres::{PgConnection, PgPool, Postgres},
+};
+
+use crate::metrics::{ReadTarget, REPLICA_METRICS};
+
+/// Result of the latest replica lag check.
+#[derive(Debug, Clone, Copy)]
+struct LagCheck {
+    checked_at: Instant,
+    /// `None` if the check has failed, or if the replica has never replayed any transactions.
+    lag: Option<Duration>,
+}
+
+#[derive(Debug)]
+struct ReadReplica {
+    index: usize,
+    pool: PgPool,
+    last_check: Mutex<Option<LagCheck>>,
+}
+
+impl ReadReplica {
+    /// Lag of a replica as reported by the replica itself. A replica that has replayed all received WAL is considered
+    /// to have zero lag; otherwise, the lag is the time elapsed since the last replayed transaction was committed
+    /// on the primary. A database not in recovery (i.e., not a replica) is considered to have zero lag as well.
+    const LAG_QUERY: &'static str = "\
+        SELECT \
+            CASE \
+                WHEN NOT pg_is_in_recovery() THEN 0 \
+                WHEN pg_last_wal_receive_lsn() = pg_last_wal_replay_lsn() THEN 0 \
+                ELSE EXTRACT(EPOCH FROM NOW() - pg_last_xact_replay_timestamp()) \
+            END::DOUBLE PRECISION";
+
+    fn is_fresh(check: &LagCheck, max_lag: Duration) -> bool {
+        check.lag.map_or(false, |lag| lag <= max_lag)
+    }
+
+    async fn query_lag(conn: &mut PgConnection) -> sqlx::Result<Option<Duration>> {
+        let lag_sec: Option<f64> = sqlx::query_scalar(Self::LAG_QUERY).fetch_one(conn).await?;
+        Ok(lag_sec.map(|lag_sec| Duration::from_secs_f64(lag_sec.max(0.0))))
+    }
+
+    /// Acquires a connection to this replica if it's fresh, re-checking the replica lag if the previous check
+    /// is outdated. Stale replicas are not connected to until their lag is due to be re-checked.
+    async fn acquire_if_fresh(
+        &self,
+        max_lag: Duration,
+        check_interval: Duration,
+    ) -> Option<PoolConnection<Postgres>> {
+        let last_check = *self.last_check.lock().expect("replica state is poisoned");
+        let needs_check =
+            last_check.map_or(true, |check| check.checked_at.elapsed() >= check_interval);
+        if !needs_check && !last_check.map_or(false, |check| Self::is_fresh(&check, max_lag)) {
+            return None;
+        }
+
+        let conn = self.pool.acquire().await;
+        let mut conn = match conn {
+            Ok(conn) if !needs_check => return Some(conn),
+            Ok(conn) => conn,
+            Err(err) => {
+                tracing::warn!("Failed connecting to read replica #{}: {err}", self.index);
+                self.update_check(None);
+                return None;
+            }
+        };
+
+        let lag = match Self::query_lag(&mut conn).await {
+            Ok(lag) => lag,
+            Err(err) => {
+                tracing::warn!("Failed checking lag of read replica #{}: {err}", self.index);
+                None
+            }
+        };
+        let check = self.update_check(lag);
+        if Self::is_fresh(&check, max_lag) {
+            Some(conn)
+        } else {
+            tracing::info!(
+                "Read replica #{} is stale (lag: {lag:?}, max allowed lag: {max_lag:?})",
+                self.index
+            );
+            None
+        }
+    }
+
+    fn update_check(&self, lag: Option<Duration>) -> LagCheck {
+        let check = LagCheck {
+            checked_at: Instant::now(),
+            lag,
+        };
+        *self.last_check.lock().expect("replica state is poisoned") = Some(check);
+        REPLICA_METRICS.observe_lag(self.index, lag);
+        check
+    }
+}
+
+/// Read replicas of the primary database attached to a [`ConnectionPool`](super::ConnectionPool).
+#[derive(Debug)]
+pub(super) struct ReadReplicas {
+    replicas: Vec<ReadReplica>,
+    max_lag: Duration,
+    check_interval: Duration,
+    next_replica: AtomicUsize,
+}
+
+impl ReadReplicas {
+    /// Maximum interval between consecutive lag checks for a replica.
+    const MAX_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+    pub(super) fn new(pools: Vec<PgPool>, max_lag: Duration) -> Self {
+        let replicas = pools
+            .into_iter()
+            .enumerate()
+            .map(|(index, pool)| ReadReplica {
+                index,
+                pool,
+                last_check: Mutex::new(None),
+            })
+            .collect();
+        Self {
+            replicas,
+            max_lag,
+            check_interval: max_lag.min(Self::MAX_CHECK_INTERVAL),
+            next_replica: AtomicUsize::new(0),
+        }
+    }
+
+    /// Acquires a connection to a fresh replica. Replicas are tried in the round-robin order; returns `None`
+    /// if all replicas are stale or unavailable.
+    pub(super) async fn acquire(&self) -> Option<PoolConnection<Postgres>> {
+        let replica_count = self.replicas.len();
+        let start_idx = self.next_replica.fetch_add(1, Ordering::Relaxed);
+        for i in 0..replica_count {
+            let replica = &self.replicas[(start_idx + i) % replica_count];
+            if let Some(conn) = replica
+                .acquire_if_fresh(self.max_lag, self.check_interval)
+                .await
+            {
+                REPLICA_METRICS.reads[&ReadTarget::Replica].inc();
+                return Some(conn);
+            }
+        }
+        REPLICA_METRICS.reads[&ReadTarget::Primary].inc();
+        None
+    }
+
+    /// Returns the lag of the specified replica as of the latest check.
+    #[cfg(test)]
+    pub(super) fn last_lag(&self, index: usize) -> Option<Option<Duration>> {
+        let last_check = *self.replicas[index].last_check.lock().unwrap();
+        last_check.map(|check| check.lag)
+    }
+}
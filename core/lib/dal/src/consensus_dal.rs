@@ -4,7 +4,7 @@ use zksync_consensus_storage::ReplicaState;
 use zksync_types::MiniblockNumber;
 
 pub use crate::models::storage_sync::Payload;
-use crate::StorageProcessor;
+use crate::{instrument::InstrumentExt, StorageProcessor};
 
 /// Storage access methods for `zksync_core::consensus` module.
 #[derive(Debug)]
@@ -25,7 +25,8 @@ impl ConsensusDal<'_, '_> {
                 fake_key
             "#
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("replica_state")
+        .fetch_optional(self.storage)
         .await?
         else {
             return Ok(None);
@@ -50,7 +51,8 @@ impl ConsensusDal<'_, '_> {
             "#,
             state
         )
-        .execute(self.storage.conn())
+        .instrument("set_replica_state")
+        .execute(self.storage)
         .await?;
         Ok(())
     }
@@ -72,7 +74,8 @@ impl ConsensusDal<'_, '_> {
                 1
             "#
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("first_certificate")
+        .fetch_optional(self.storage)
         .await?
         else {
             return Ok(None);
@@ -96,7 +99,8 @@ impl ConsensusDal<'_, '_> {
                 1
             "#
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("last_certificate")
+        .fetch_optional(self.storage)
         .await?
         else {
             return Ok(None);
@@ -120,7 +124,8 @@ impl ConsensusDal<'_, '_> {
             "#,
             i64::try_from(block_number.0)?
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("certificate")
+        .fetch_optional(self.storage)
         .await?
         else {
             return Ok(None);
@@ -196,7 +201,8 @@ impl ConsensusDal<'_, '_> {
             header.number.0 as i64,
             zksync_protobuf::serde::serialize(cert, serde_json::value::Serializer).unwrap(),
         )
-        .execute(txn.conn())
+        .instrument("insert_certificate")
+        .execute(&mut txn)
         .await?;
         txn.commit().await?;
         Ok(())
@@ -13,7 +13,10 @@ use zksync_types::{
     get_code_key, Address, CONTRACT_DEPLOYER_ADDRESS, FAILED_CONTRACT_DEPLOYMENT_BYTECODE_HASH,
 };
 
-use crate::{models::storage_verification_request::StorageVerificationRequest, StorageProcessor};
+use crate::{
+    instrument::InstrumentExt, models::storage_verification_request::StorageVerificationRequest,
+    StorageProcessor,
+};
 
 #[derive(Debug)]
 pub struct ContractVerificationDal<'a, 'c> {
@@ -51,7 +54,8 @@ impl ContractVerificationDal<'_, '_> {
                 status = 'queued'
             "#
         )
-        .fetch_one(self.storage.conn())
+        .instrument("get_count_of_queued_verification_requests")
+        .fetch_one(self.storage)
         .await
         .map(|row| row.count as usize)
     }
@@ -93,7 +97,8 @@ impl ContractVerificationDal<'_, '_> {
             query.constructor_arguments.0,
             query.is_system,
         )
-        .fetch_one(self.storage.conn())
+        .instrument("add_contract_verification_request")
+        .fetch_one(self.storage)
         .await
         .map(|row| row.id as usize)
     }
@@ -153,13 +158,15 @@ impl ContractVerificationDal<'_, '_> {
             "#,
             &processing_timeout
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_next_queued_verification_request")
+        .fetch_optional(self.storage)
         .await?
         .map(Into::into);
         Ok(result)
     }
 
     /// Updates the verification request status and inserts the verification info upon successful verification.
+    /// Verification info of a fully matched contract is never overwritten by a partial match.
     pub async fn save_verification_info(
         &mut self,
         verification_info: VerificationInfo,
@@ -175,16 +182,20 @@ impl ContractVerificationDal<'_, '_> {
             UPDATE contract_verification_requests
             SET
                 status = 'successful',
-                updated_at = NOW()
+                updated_at = NOW(),
+                match_type = $2
             WHERE
                 id = $1
             "#,
             verification_info.request.id as i64,
+            verification_info.match_type.as_str(),
         )
-        .execute(transaction.conn())
+        .instrument("save_verification_info#1")
+        .execute(&mut transaction)
         .await?;
 
         let address = verification_info.request.req.contract_address;
+        let match_type = verification_info.match_type;
         // Serialization should always succeed.
         let verification_info_json = serde_json::to_value(verification_info)
             .expect("Failed to serialize verification info into serde_json");
@@ -198,11 +209,16 @@ impl ContractVerificationDal<'_, '_> {
             UPDATE
             SET
                 verification_info = $2
+            WHERE
+                $3 = 'full'
+                OR contracts_verification_info.verification_info ->> 'matchType' = 'partial'
             "#,
             address.as_bytes(),
-            &verification_info_json
+            &verification_info_json,
+            match_type.as_str()
         )
-        .execute(transaction.conn())
+        .instrument("save_verification_info#2")
+        .execute(&mut transaction)
         .await?;
 
         transaction.commit().await.context("commit()")?;
@@ -233,7 +249,9 @@ impl ContractVerificationDal<'_, '_> {
             &compilation_errors,
             panic_message
         )
-        .execute(self.storage.conn())
+        .instrument("save_verification_error")
+        .with_arg("id", &id)
+        .execute(self.storage)
         .await?;
         Ok(())
     }
@@ -247,7 +265,8 @@ impl ContractVerificationDal<'_, '_> {
             SELECT
                 status,
                 error,
-                compilation_errors
+                compilation_errors,
+                match_type
             FROM
                 contract_verification_requests
             WHERE
@@ -255,7 +274,9 @@ impl ContractVerificationDal<'_, '_> {
             "#,
             id as i64,
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_verification_request_status")
+        .with_arg("id", &id)
+        .fetch_optional(self.storage)
         .await?
         else {
             return Ok(None);
@@ -267,6 +288,11 @@ impl ContractVerificationDal<'_, '_> {
                 compilation_errors.push(value.as_str().context("expected string")?.to_string());
             }
         }
+        let match_type = row
+            .match_type
+            .map(|match_type| match_type.parse())
+            .transpose()
+            .map_err(anyhow::Error::msg)?;
         Ok(Some(VerificationRequestStatus {
             status: row.status,
             error: row.error,
@@ -275,6 +301,7 @@ impl ContractVerificationDal<'_, '_> {
             } else {
                 Some(compilation_errors)
             },
+            match_type,
         }))
     }
 
@@ -312,7 +339,8 @@ impl ContractVerificationDal<'_, '_> {
             hashed_key.as_bytes(),
             FAILED_CONTRACT_DEPLOYMENT_BYTECODE_HASH.as_bytes()
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_contract_info_for_verification")
+        .fetch_optional(self.storage)
         .await?
         else {
             return Ok(None);
@@ -334,7 +362,8 @@ impl ContractVerificationDal<'_, '_> {
         Ok(Some((row.bytecode, calldata)))
     }
 
-    /// Returns true if the contract has a stored contracts_verification_info.
+    /// Returns true if the contract has a stored contracts_verification_info with a full match.
+    /// Partially matched contracts can be verified again to get a full match.
     pub async fn is_contract_verified(&mut self, address: Address) -> sqlx::Result<bool> {
         let count = sqlx::query!(
             r#"
@@ -344,10 +373,13 @@ impl ContractVerificationDal<'_, '_> {
                 contracts_verification_info
             WHERE
                 address = $1
+                AND COALESCE(verification_info ->> 'matchType', 'full') = 'full'
             "#,
             address.as_bytes()
         )
-        .fetch_one(self.storage.conn())
+        .instrument("is_contract_verified")
+        .with_arg("address", &address)
+        .fetch_one(self.storage)
         .await?
         .count;
         Ok(count > 0)
@@ -368,7 +400,8 @@ impl ContractVerificationDal<'_, '_> {
             "#,
             &compiler
         )
-        .fetch_all(self.storage.conn())
+        .instrument("get_compiler_versions")
+        .fetch_all(self.storage)
         .await?
         .into_iter()
         .map(|row| row.version)
@@ -412,7 +445,8 @@ impl ContractVerificationDal<'_, '_> {
             "#,
             &compiler
         )
-        .execute(transaction.conn())
+        .instrument("set_compiler_versions#1")
+        .execute(&mut transaction)
         .await?;
 
         sqlx::query!(
@@ -431,7 +465,8 @@ impl ContractVerificationDal<'_, '_> {
             &versions,
             &compiler,
         )
-        .execute(transaction.conn())
+        .instrument("set_compiler_versions#2")
+        .execute(&mut transaction)
         .await?;
 
         transaction.commit().await.context("commit()")?;
@@ -478,7 +513,8 @@ impl ContractVerificationDal<'_, '_> {
                 id
             "#,
         )
-        .fetch_all(self.storage.conn())
+        .instrument("get_all_successful_requests")
+        .fetch_all(self.storage)
         .await?
         .into_iter()
         .map(Into::into)
@@ -501,7 +537,9 @@ impl ContractVerificationDal<'_, '_> {
             "#,
             address.as_bytes(),
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_contract_verification_info")
+        .with_arg("address", &address)
+        .fetch_optional(self.storage)
         .await?
         else {
             return Ok(None);
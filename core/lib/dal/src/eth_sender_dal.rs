@@ -7,17 +7,22 @@ use sqlx::{
 };
 use zksync_types::{
     aggregated_operations::AggregatedActionType,
-    eth_sender::{EthTx, TxHistory, TxHistoryToSend},
+    eth_sender::{EthTx, EthTxBlobSidecar, OperatorRotation, TxHistory, TxHistoryToSend},
     Address, L1BatchNumber, H256, U256,
 };
 
 use crate::{
+    instrument::InstrumentExt,
     models::storage_eth_tx::{
-        L1BatchEthSenderStats, StorageEthTx, StorageTxHistory, StorageTxHistoryToSend,
+        L1BatchEthSenderStats, StorageEthTx, StorageOperatorRotation, StorageTxHistory,
+        StorageTxHistoryToSend,
     },
     StorageProcessor,
 };
 
+/// Key of the Postgres advisory lock used to serialize assignment of nonces to `eth_txs`.
+const NONCE_ASSIGNMENT_LOCK_KEY: i64 = 0x6574_685f_6e6f_6e63; // `eth_nonc` in ASCII
+
 #[derive(Debug)]
 pub struct EthSenderDal<'a, 'c> {
     pub(crate) storage: &'a mut StorageProcessor<'c>,
@@ -46,7 +51,8 @@ impl EthSenderDal<'_, '_> {
                 id
             "#
         )
-        .fetch_all(self.storage.conn())
+        .instrument("get_inflight_txs")
+        .fetch_all(self.storage)
         .await?;
         Ok(txs.into_iter().map(|tx| tx.into()).collect())
     }
@@ -62,7 +68,9 @@ impl EthSenderDal<'_, '_> {
                  LIMIT 1",
                 tx_type
             ))
-            .fetch_all(self.storage.conn())
+            .instrument("get_eth_l1_batches#1")
+            .with_arg("tx_type", &tx_type)
+            .fetch_all(self.storage)
             .await?;
 
             records.extend(
@@ -73,7 +81,9 @@ impl EthSenderDal<'_, '_> {
                      LIMIT 1",
                     tx_type
                 ))
-                .fetch_all(self.storage.conn())
+                .instrument("get_eth_l1_batches#2")
+                .with_arg("tx_type", &tx_type)
+                .fetch_all(self.storage)
                 .await?,
             );
 
@@ -108,11 +118,60 @@ impl EthSenderDal<'_, '_> {
             "#,
             eth_tx_id as i32
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_eth_tx")
+        .fetch_optional(self.storage)
         .await?
         .map(Into::into))
     }
 
+    /// Returns unconfirmed transactions committing, proving or executing L1 batches after the specified one.
+    pub async fn get_inflight_txs_for_l1_batches_after(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> sqlx::Result<Vec<EthTx>> {
+        let txs = sqlx::query_as!(
+            StorageEthTx,
+            r#"
+            SELECT
+                *
+            FROM
+                eth_txs
+            WHERE
+                confirmed_eth_tx_history_id IS NULL
+                AND id IN (
+                    SELECT
+                        eth_commit_tx_id
+                    FROM
+                        l1_batches
+                    WHERE
+                        number > $1
+                    UNION
+                    SELECT
+                        eth_prove_tx_id
+                    FROM
+                        l1_batches
+                    WHERE
+                        number > $1
+                    UNION
+                    SELECT
+                        eth_execute_tx_id
+                    FROM
+                        l1_batches
+                    WHERE
+                        number > $1
+                )
+            ORDER BY
+                id
+            "#,
+            l1_batch_number.0 as i64
+        )
+        .instrument("get_inflight_txs_for_l1_batches_after")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .fetch_all(self.storage)
+        .await?;
+        Ok(txs.into_iter().map(|tx| tx.into()).collect())
+    }
+
     pub async fn get_new_eth_txs(&mut self, limit: u64) -> sqlx::Result<Vec<EthTx>> {
         let txs = sqlx::query_as!(
             StorageEthTx,
@@ -135,7 +194,8 @@ impl EthSenderDal<'_, '_> {
             "#,
             limit as i64
         )
-        .fetch_all(self.storage.conn())
+        .instrument("get_new_eth_txs")
+        .fetch_all(self.storage)
         .await?;
         Ok(txs.into_iter().map(|tx| tx.into()).collect())
     }
@@ -162,7 +222,8 @@ impl EthSenderDal<'_, '_> {
                 eth_txs_history.id DESC
             "#,
         )
-        .fetch_all(self.storage.conn())
+        .instrument("get_unsent_txs")
+        .fetch_all(self.storage)
         .await?;
         Ok(txs.into_iter().map(|tx| tx.into()).collect())
     }
@@ -174,8 +235,13 @@ impl EthSenderDal<'_, '_> {
         tx_type: AggregatedActionType,
         contract_address: Address,
         predicted_gas_cost: u32,
+        blob_sidecar: Option<EthTxBlobSidecar>,
+        from_address: Address,
     ) -> sqlx::Result<EthTx> {
         let address = format!("{:#x}", contract_address);
+        let blob_sidecar = blob_sidecar.map(|sidecar| {
+            bincode::serialize(&sidecar).expect("can always bincode serialize EthTxBlobSidecar")
+        });
         let eth_tx = sqlx::query_as!(
             StorageEthTx,
             r#"
@@ -187,10 +253,12 @@ impl EthSenderDal<'_, '_> {
                     contract_address,
                     predicted_gas_cost,
                     created_at,
-                    updated_at
+                    updated_at,
+                    blob_sidecar,
+                    from_addr
                 )
             VALUES
-                ($1, $2, $3, $4, $5, NOW(), NOW())
+                ($1, $2, $3, $4, $5, NOW(), NOW(), $6, $7)
             RETURNING
                 *
             "#,
@@ -198,9 +266,12 @@ impl EthSenderDal<'_, '_> {
             nonce as i64,
             tx_type.to_string(),
             address,
-            predicted_gas_cost as i64
+            predicted_gas_cost as i64,
+            blob_sidecar,
+            from_address.as_bytes()
         )
-        .fetch_one(self.storage.conn())
+        .instrument("save_eth_tx")
+        .fetch_one(self.storage)
         .await?;
         Ok(eth_tx.into())
     }
@@ -210,6 +281,7 @@ impl EthSenderDal<'_, '_> {
         eth_tx_id: u32,
         base_fee_per_gas: u64,
         priority_fee_per_gas: u64,
+        blob_base_fee_per_gas: Option<u64>,
         tx_hash: H256,
         raw_signed_tx: &[u8],
     ) -> anyhow::Result<Option<u32>> {
@@ -217,6 +289,10 @@ impl EthSenderDal<'_, '_> {
             i64::try_from(priority_fee_per_gas).context("Can't convert u64 to i64")?;
         let base_fee_per_gas =
             i64::try_from(base_fee_per_gas).context("Can't convert u64 to i64")?;
+        let blob_base_fee_per_gas = blob_base_fee_per_gas
+            .map(i64::try_from)
+            .transpose()
+            .context("Can't convert u64 to i64")?;
         let tx_hash = format!("{:#x}", tx_hash);
 
         Ok(sqlx::query!(
@@ -229,10 +305,11 @@ impl EthSenderDal<'_, '_> {
                     tx_hash,
                     signed_raw_tx,
                     created_at,
-                    updated_at
+                    updated_at,
+                    blob_base_fee_per_gas
                 )
             VALUES
-                ($1, $2, $3, $4, $5, NOW(), NOW())
+                ($1, $2, $3, $4, $5, NOW(), NOW(), $6)
             ON CONFLICT (tx_hash) DO NOTHING
             RETURNING
                 id
@@ -241,9 +318,13 @@ impl EthSenderDal<'_, '_> {
             base_fee_per_gas,
             priority_fee_per_gas,
             tx_hash,
-            raw_signed_tx
+            raw_signed_tx,
+            blob_base_fee_per_gas
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("insert_tx_history")
+        .with_arg("eth_tx_id", &eth_tx_id)
+        .with_arg("raw_signed_tx", &raw_signed_tx)
+        .fetch_optional(self.storage)
         .await?
         .map(|row| row.id as u32))
     }
@@ -266,7 +347,10 @@ impl EthSenderDal<'_, '_> {
             eth_txs_history_id as i32,
             sent_at_block as i32
         )
-        .execute(self.storage.conn())
+        .instrument("set_sent_at_block")
+        .with_arg("eth_txs_history_id", &eth_txs_history_id)
+        .with_arg("sent_at_block", &sent_at_block)
+        .execute(self.storage)
         .await?;
         Ok(())
     }
@@ -280,7 +364,9 @@ impl EthSenderDal<'_, '_> {
             "#,
             eth_txs_history_id as i64
         )
-        .execute(self.storage.conn())
+        .instrument("remove_tx_history")
+        .with_arg("eth_txs_history_id", &eth_txs_history_id)
+        .execute(self.storage)
         .await?;
         Ok(())
     }
@@ -308,7 +394,8 @@ impl EthSenderDal<'_, '_> {
             "#,
             tx_hash,
         )
-        .fetch_one(transaction.conn())
+        .instrument("confirm_tx#1")
+        .fetch_one(&mut transaction)
         .await?;
 
         sqlx::query!(
@@ -324,7 +411,8 @@ impl EthSenderDal<'_, '_> {
             ids.id,
             ids.eth_tx_id
         )
-        .execute(transaction.conn())
+        .instrument("confirm_tx#2")
+        .execute(&mut transaction)
         .await?;
 
         transaction.commit().await?;
@@ -347,7 +435,9 @@ impl EthSenderDal<'_, '_> {
             "#,
             eth_tx_id as i64
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_confirmed_tx_hash_by_eth_tx_id")
+        .with_arg("eth_tx_id", &eth_tx_id)
+        .fetch_optional(self.storage)
         .await?;
 
         let Some(tx_hash) = tx_hash else {
@@ -388,7 +478,8 @@ impl EthSenderDal<'_, '_> {
             WHERE eth_txs_history.tx_hash = $1",
             tx_hash
         )
-        .fetch_optional(transaction.conn())
+        .instrument("insert_bogus_confirmed_eth_tx#1")
+        .fetch_optional(&mut transaction)
         .await?;
 
         // Check if the transaction with the corresponding hash already exists.
@@ -404,7 +495,9 @@ impl EthSenderDal<'_, '_> {
                 RETURNING id",
                 tx_type.to_string()
             )
-            .fetch_one(transaction.conn())
+            .instrument("insert_bogus_confirmed_eth_tx#2")
+            .with_arg("tx_type", &tx_type)
+            .fetch_one(&mut transaction)
             .await?;
 
             // Insert a "sent transaction".
@@ -417,7 +510,8 @@ impl EthSenderDal<'_, '_> {
                 tx_hash,
                 confirmed_at.naive_utc()
             )
-            .fetch_one(transaction.conn())
+            .instrument("insert_bogus_confirmed_eth_tx#3")
+            .fetch_one(&mut transaction)
             .await?;
 
             // Mark general entry as confirmed.
@@ -432,7 +526,8 @@ impl EthSenderDal<'_, '_> {
                 eth_history_id,
                 eth_tx_id
             )
-            .execute(transaction.conn())
+            .instrument("insert_bogus_confirmed_eth_tx#4")
+            .execute(&mut transaction)
             .await?;
 
             eth_tx_id
@@ -467,7 +562,8 @@ impl EthSenderDal<'_, '_> {
             "#,
             eth_tx_id as i32
         )
-        .fetch_all(self.storage.conn())
+        .instrument("get_tx_history_to_check")
+        .fetch_all(self.storage)
         .await?;
         Ok(tx_history.into_iter().map(|tx| tx.into()).collect())
     }
@@ -480,11 +576,34 @@ impl EthSenderDal<'_, '_> {
             "SELECT sent_at_block FROM eth_txs_history WHERE eth_tx_id = $1 AND sent_at_block IS NOT NULL ORDER BY created_at ASC LIMIT 1",
             eth_tx_id as i32
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_block_number_on_first_sent_attempt")
+        .with_arg("eth_tx_id", &eth_tx_id)
+        .fetch_optional(self.storage)
         .await?;
         Ok(sent_at_block.flatten().map(|block| block as u32))
     }
 
+    /// Returns the number of sent attempts for the specified `eth_tx`.
+    pub async fn get_sent_attempts_count(&mut self, eth_tx_id: u32) -> sqlx::Result<u32> {
+        let count = sqlx::query_scalar!(
+            r#"
+            SELECT
+                COUNT(*) AS "count!"
+            FROM
+                eth_txs_history
+            WHERE
+                eth_tx_id = $1
+                AND sent_at_block IS NOT NULL
+            "#,
+            eth_tx_id as i32
+        )
+        .instrument("get_sent_attempts_count")
+        .with_arg("eth_tx_id", &eth_tx_id)
+        .fetch_one(self.storage)
+        .await?;
+        Ok(count as u32)
+    }
+
     pub async fn get_last_sent_eth_tx(
         &mut self,
         eth_tx_id: u32,
@@ -505,29 +624,332 @@ impl EthSenderDal<'_, '_> {
             "#,
             eth_tx_id as i32
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_last_sent_eth_tx")
+        .fetch_optional(self.storage)
         .await?;
         Ok(history_item.map(|tx| tx.into()))
     }
 
-    pub async fn get_next_nonce(&mut self) -> sqlx::Result<Option<u64>> {
+    /// Returns the nonce following the nonce of the latest `eth_tx` sent from the specified operator account.
+    pub async fn get_next_nonce(&mut self, from_address: Address) -> sqlx::Result<Option<u64>> {
         let row = sqlx::query!(
             r#"
             SELECT
                 nonce
             FROM
                 eth_txs
+            WHERE
+                from_addr = $1
             ORDER BY
                 id DESC
             LIMIT
                 1
-            "#
+            "#,
+            from_address.as_bytes()
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_next_nonce")
+        .with_arg("from_address", &from_address)
+        .fetch_optional(self.storage)
         .await?;
         Ok(row.map(|row| row.nonce as u64 + 1))
     }
 
+    /// Returns the `eth_tx` with the greatest ID among `eth_txs` sent from the specified operator account
+    /// with at least one attempt (sent or not).
+    pub async fn get_last_attempted_eth_tx(
+        &mut self,
+        from_address: Address,
+    ) -> sqlx::Result<Option<EthTx>> {
+        let eth_tx = sqlx::query_as!(
+            StorageEthTx,
+            r#"
+            SELECT
+                *
+            FROM
+                eth_txs
+            WHERE
+                id = (
+                    SELECT
+                        MAX(eth_tx_id)
+                    FROM
+                        eth_txs_history
+                        JOIN eth_txs ON eth_txs.id = eth_txs_history.eth_tx_id
+                    WHERE
+                        eth_txs.from_addr = $1
+                )
+            "#,
+            from_address.as_bytes()
+        )
+        .instrument("get_last_attempted_eth_tx")
+        .fetch_optional(self.storage)
+        .await?;
+        Ok(eth_tx.map(Into::into))
+    }
+
+    /// Removes all attempts of the specified `eth_tx`, so that it will be sent again as a new transaction.
+    pub async fn reset_eth_tx_attempts(&mut self, eth_tx_id: u32) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            DELETE FROM eth_txs_history
+            WHERE
+                eth_tx_id = $1
+            "#,
+            eth_tx_id as i32
+        )
+        .instrument("reset_eth_tx_attempts")
+        .with_arg("eth_tx_id", &eth_tx_id)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    /// Returns the `eth_tx` with the smallest ID among `eth_txs` without attempts sent from the specified
+    /// operator account.
+    pub async fn get_first_unsent_eth_tx(
+        &mut self,
+        from_address: Address,
+    ) -> sqlx::Result<Option<EthTx>> {
+        let eth_tx = sqlx::query_as!(
+            StorageEthTx,
+            r#"
+            SELECT
+                *
+            FROM
+                eth_txs
+            WHERE
+                from_addr = $1
+                AND id > (
+                    SELECT
+                        COALESCE(MAX(eth_tx_id), 0)
+                    FROM
+                        eth_txs_history
+                )
+            ORDER BY
+                id
+            LIMIT
+                1
+            "#,
+            from_address.as_bytes()
+        )
+        .instrument("get_first_unsent_eth_tx")
+        .fetch_optional(self.storage)
+        .await?;
+        Ok(eth_tx.map(Into::into))
+    }
+
+    /// Assigns consecutive nonces starting from `first_nonce` to `eth_txs` without attempts sent from the specified
+    /// operator account, in the order of their IDs. Returns the number of renumbered `eth_txs`.
+    pub async fn renumber_unsent_eth_txs(
+        &mut self,
+        from_address: Address,
+        first_nonce: u64,
+    ) -> sqlx::Result<u64> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE eth_txs
+            SET
+                nonce = $1 + unsent_eth_txs.index - 1,
+                updated_at = NOW()
+            FROM
+                (
+                    SELECT
+                        id,
+                        ROW_NUMBER() OVER (
+                            ORDER BY
+                                id
+                        ) AS index
+                    FROM
+                        eth_txs
+                    WHERE
+                        from_addr = $2
+                        AND id > (
+                            SELECT
+                                COALESCE(MAX(eth_tx_id), 0)
+                            FROM
+                                eth_txs_history
+                        )
+                ) AS unsent_eth_txs
+            WHERE
+                eth_txs.id = unsent_eth_txs.id
+            "#,
+            first_nonce as i64,
+            from_address.as_bytes()
+        )
+        .instrument("renumber_unsent_eth_txs")
+        .with_arg("from_address", &from_address)
+        .with_arg("first_nonce", &first_nonce)
+        .execute(self.storage)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Acquires a lock preventing concurrent assignment of nonces to `eth_txs` until the end of the current
+    /// transaction. Must be called inside a transaction.
+    pub async fn lock_nonce_assignment(&mut self) -> sqlx::Result<()> {
+        sqlx::query("SELECT pg_advisory_xact_lock($1)")
+            .bind(NONCE_ASSIGNMENT_LOCK_KEY)
+            .instrument("lock_nonce_assignment")
+            .execute(self.storage)
+            .await?;
+        Ok(())
+    }
+
+    /// Attributes `eth_txs` created before operator accounts were tracked to the specified operator account.
+    /// Returns the number of updated `eth_txs`.
+    pub async fn set_missing_eth_tx_senders(&mut self, from_address: Address) -> sqlx::Result<u64> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE eth_txs
+            SET
+                from_addr = $1,
+                updated_at = NOW()
+            WHERE
+                from_addr IS NULL
+            "#,
+            from_address.as_bytes()
+        )
+        .instrument("set_missing_eth_tx_senders")
+        .with_arg("from_address", &from_address)
+        .execute(self.storage)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Returns the number of `eth_txs` that are not confirmed yet, including ones that were not sent.
+    pub async fn get_unconfirmed_txs_count(&mut self) -> sqlx::Result<usize> {
+        let count = sqlx::query!(
+            r#"
+            SELECT
+                COUNT(*) AS "count!"
+            FROM
+                eth_txs
+            WHERE
+                confirmed_eth_tx_history_id IS NULL
+            "#
+        )
+        .instrument("get_unconfirmed_txs_count")
+        .fetch_one(self.storage)
+        .await?
+        .count;
+        Ok(count as usize)
+    }
+
+    /// Returns the latest operator rotation, either pending or completed.
+    pub async fn get_latest_operator_rotation(&mut self) -> sqlx::Result<Option<OperatorRotation>> {
+        let rotation = sqlx::query_as!(
+            StorageOperatorRotation,
+            r#"
+            SELECT
+                *
+            FROM
+                eth_operator_rotations
+            ORDER BY
+                id DESC
+            LIMIT
+                1
+            "#
+        )
+        .instrument("get_latest_operator_rotation")
+        .fetch_optional(self.storage)
+        .await?;
+        Ok(rotation.map(Into::into))
+    }
+
+    /// Returns the address of the operator account the latest completed rotation has switched to, or `None`
+    /// if the operator was never rotated.
+    pub async fn get_current_operator_address(&mut self) -> sqlx::Result<Option<Address>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                new_operator_address
+            FROM
+                eth_operator_rotations
+            WHERE
+                completed_at IS NOT NULL
+            ORDER BY
+                id DESC
+            LIMIT
+                1
+            "#
+        )
+        .instrument("get_current_operator_address")
+        .fetch_optional(self.storage)
+        .await?;
+        Ok(row
+            .and_then(|row| row.new_operator_address)
+            .map(|addr| Address::from_slice(&addr)))
+    }
+
+    /// Requests an operator rotation. Returns `None` if another rotation is already pending.
+    pub async fn start_operator_rotation(&mut self) -> sqlx::Result<Option<OperatorRotation>> {
+        let rotation = sqlx::query_as!(
+            StorageOperatorRotation,
+            r#"
+            INSERT INTO
+                eth_operator_rotations (requested_at)
+            SELECT
+                NOW()
+            WHERE
+                NOT EXISTS (
+                    SELECT
+                        1
+                    FROM
+                        eth_operator_rotations
+                    WHERE
+                        completed_at IS NULL
+                )
+            RETURNING
+                *
+            "#
+        )
+        .instrument("start_operator_rotation")
+        .fetch_optional(self.storage)
+        .await?;
+        Ok(rotation.map(Into::into))
+    }
+
+    /// Cancels the pending operator rotation. Returns `false` if there is no pending rotation.
+    pub async fn cancel_operator_rotation(&mut self) -> sqlx::Result<bool> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM eth_operator_rotations
+            WHERE
+                completed_at IS NULL
+            "#
+        )
+        .instrument("cancel_operator_rotation")
+        .execute(self.storage)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Marks the pending operator rotation as completed. Returns `false` if the rotation was cancelled.
+    pub async fn complete_operator_rotation(
+        &mut self,
+        rotation_id: u32,
+        new_operator_address: Address,
+    ) -> sqlx::Result<bool> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE eth_operator_rotations
+            SET
+                completed_at = NOW(),
+                new_operator_address = $2
+            WHERE
+                id = $1
+                AND completed_at IS NULL
+            "#,
+            rotation_id as i32,
+            new_operator_address.as_bytes()
+        )
+        .instrument("complete_operator_rotation")
+        .with_arg("rotation_id", &rotation_id)
+        .with_arg("new_operator_address", &new_operator_address)
+        .execute(self.storage)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
     pub async fn mark_failed_transaction(&mut self, eth_tx_id: u32) -> sqlx::Result<()> {
         sqlx::query!(
             r#"
@@ -539,7 +961,9 @@ impl EthSenderDal<'_, '_> {
             "#,
             eth_tx_id as i32
         )
-        .execute(self.storage.conn())
+        .instrument("mark_failed_transaction")
+        .with_arg("eth_tx_id", &eth_tx_id)
+        .execute(self.storage)
         .await?;
         Ok(())
     }
@@ -555,7 +979,8 @@ impl EthSenderDal<'_, '_> {
                 has_failed = TRUE
             "#
         )
-        .fetch_one(self.storage.conn())
+        .instrument("get_number_of_failed_transactions")
+        .fetch_one(self.storage)
         .await?
         .count
         .context("count field is missing")
@@ -576,7 +1001,8 @@ impl EthSenderDal<'_, '_> {
                 )
             "#
         )
-        .execute(self.storage.conn())
+        .instrument("clear_failed_transactions")
+        .execute(self.storage)
         .await?;
         Ok(())
     }
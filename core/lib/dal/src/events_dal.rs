@@ -1,4 +1,4 @@
-use std::{collections::HashMap, fmt};
+use std::collections::HashMap;
 
 use sqlx::types::chrono::Utc;
 use zksync_system_constants::L1_MESSENGER_ADDRESS;
@@ -11,24 +11,12 @@ use zksync_types::{
 };
 
 use crate::{
+    copy::BinaryCopy,
+    instrument::InstrumentExt,
     models::storage_event::{StorageL2ToL1Log, StorageWeb3Log},
     SqlxError, StorageProcessor,
 };
 
-/// Wrapper around an optional event topic allowing to hex-format it for `COPY` instructions.
-#[derive(Debug)]
-struct EventTopic<'a>(Option<&'a H256>);
-
-impl fmt::LowerHex for EventTopic<'_> {
-    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
-        if let Some(topic) = self.0 {
-            fmt::LowerHex::fmt(topic, formatter)
-        } else {
-            Ok(()) // Don't write anything
-        }
-    }
-}
-
 #[derive(Debug)]
 pub struct EventsDal<'a, 'c> {
     pub(crate) storage: &'a mut StorageProcessor<'c>,
@@ -41,24 +29,26 @@ impl EventsDal<'_, '_> {
         block_number: MiniblockNumber,
         all_block_events: &[(IncludedTxLocation, Vec<&VmEvent>)],
     ) {
-        let mut copy = self
-            .storage
-            .conn()
-            .copy_in_raw(
-                "COPY events(
-                    miniblock_number, tx_hash, tx_index_in_block, address,
-                    event_index_in_block, event_index_in_tx,
-                    topic1, topic2, topic3, topic4, value,
-                    tx_initiator_address,
-                    created_at, updated_at
-                )
-                FROM STDIN WITH (DELIMITER '|')",
-            )
-            .await
-            .unwrap();
-
-        let mut buffer = String::new();
-        let now = Utc::now().naive_utc().to_string();
+        let mut copy = BinaryCopy::new(
+            "events",
+            &[
+                "miniblock_number",
+                "tx_hash",
+                "tx_index_in_block",
+                "address",
+                "event_index_in_block",
+                "event_index_in_tx",
+                "topic1",
+                "topic2",
+                "topic3",
+                "topic4",
+                "value",
+                "tx_initiator_address",
+                "created_at",
+                "updated_at",
+            ],
+        );
+        let now = Utc::now().naive_utc();
         let mut event_index_in_block = 0_u32;
         for (tx_location, events) in all_block_events {
             let IncludedTxLocation {
@@ -68,32 +58,27 @@ impl EventsDal<'_, '_> {
             } = tx_location;
 
             for (event_index_in_tx, event) in events.iter().enumerate() {
-                write_str!(
-                    &mut buffer,
-                    r"{block_number}|\\x{tx_hash:x}|{tx_index_in_miniblock}|\\x{address:x}|",
-                    address = event.address
-                );
-                write_str!(&mut buffer, "{event_index_in_block}|{event_index_in_tx}|");
-                write_str!(
-                    &mut buffer,
-                    r"\\x{topic0:x}|\\x{topic1:x}|\\x{topic2:x}|\\x{topic3:x}|",
-                    topic0 = EventTopic(event.indexed_topics.get(0)),
-                    topic1 = EventTopic(event.indexed_topics.get(1)),
-                    topic2 = EventTopic(event.indexed_topics.get(2)),
-                    topic3 = EventTopic(event.indexed_topics.get(3))
-                );
-                writeln_str!(
-                    &mut buffer,
-                    r"\\x{value}|\\x{tx_initiator_address:x}|{now}|{now}",
-                    value = hex::encode(&event.value)
-                );
+                let mut row = copy.row();
+                row.int8(block_number.0.into())
+                    .bytea(tx_hash.as_bytes())
+                    .int4(*tx_index_in_miniblock as i32)
+                    .bytea(event.address.as_bytes())
+                    .int4(event_index_in_block as i32)
+                    .int4(event_index_in_tx as i32);
+                // Missing topics are stored as empty byte sequences.
+                for topic_index in 0..4 {
+                    let topic = event.indexed_topics.get(topic_index);
+                    row.bytea(topic.map_or(&[][..], H256::as_bytes));
+                }
+                row.bytea(&event.value)
+                    .bytea(tx_initiator_address.as_bytes())
+                    .timestamp(now)
+                    .timestamp(now);
 
                 event_index_in_block += 1;
             }
         }
-        copy.send(buffer.as_bytes()).await.unwrap();
-        // note: all the time spent in this function is spent in `copy.finish()`
-        copy.finish().await.unwrap();
+        copy.execute("save_events", self.storage).await.unwrap();
     }
 
     /// Removes events with a block number strictly greater than the specified `block_number`.
@@ -106,7 +91,9 @@ impl EventsDal<'_, '_> {
             "#,
             block_number.0 as i64
         )
-        .execute(self.storage.conn())
+        .instrument("rollback_events")
+        .with_arg("block_number", &block_number)
+        .execute(self.storage)
         .await
         .unwrap();
     }
@@ -118,23 +105,25 @@ impl EventsDal<'_, '_> {
         block_number: MiniblockNumber,
         all_block_l2_to_l1_logs: &[(IncludedTxLocation, Vec<&UserL2ToL1Log>)],
     ) {
-        let mut copy = self
-            .storage
-            .conn()
-            .copy_in_raw(
-                "COPY l2_to_l1_logs(
-                    miniblock_number, log_index_in_miniblock, log_index_in_tx, tx_hash,
-                    tx_index_in_miniblock, tx_index_in_l1_batch,
-                    shard_id, is_service, sender, key, value,
-                    created_at, updated_at
-                )
-                FROM STDIN WITH (DELIMITER '|')",
-            )
-            .await
-            .unwrap();
-
-        let mut buffer = String::new();
-        let now = Utc::now().naive_utc().to_string();
+        let mut copy = BinaryCopy::new(
+            "l2_to_l1_logs",
+            &[
+                "miniblock_number",
+                "log_index_in_miniblock",
+                "log_index_in_tx",
+                "tx_hash",
+                "tx_index_in_miniblock",
+                "tx_index_in_l1_batch",
+                "shard_id",
+                "is_service",
+                "sender",
+                "key",
+                "value",
+                "created_at",
+                "updated_at",
+            ],
+        );
+        let now = Utc::now().naive_utc();
         let mut log_index_in_miniblock = 0u32;
         for (tx_location, logs) in all_block_l2_to_l1_logs {
             let IncludedTxLocation {
@@ -153,24 +142,27 @@ impl EventsDal<'_, '_> {
                     value,
                 } = log.0;
 
-                write_str!(
-                    &mut buffer,
-                    r"{block_number}|{log_index_in_miniblock}|{log_index_in_tx}|\\x{tx_hash:x}|"
-                );
-                write_str!(
-                    &mut buffer,
-                    r"{tx_index_in_miniblock}|{tx_number_in_block}|{shard_id}|{is_service}|"
-                );
-                writeln_str!(
-                    &mut buffer,
-                    r"\\x{sender:x}|\\x{key:x}|\\x{value:x}|{now}|{now}"
-                );
+                copy.row()
+                    .int8(block_number.0.into())
+                    .int4(log_index_in_miniblock as i32)
+                    .int4(log_index_in_tx as i32)
+                    .bytea(tx_hash.as_bytes())
+                    .int4(*tx_index_in_miniblock as i32)
+                    .int4(tx_number_in_block.into())
+                    .int4(shard_id.into())
+                    .bool(is_service)
+                    .bytea(sender.as_bytes())
+                    .bytea(key.as_bytes())
+                    .bytea(value.as_bytes())
+                    .timestamp(now)
+                    .timestamp(now);
 
                 log_index_in_miniblock += 1;
             }
         }
-        copy.send(buffer.as_bytes()).await.unwrap();
-        copy.finish().await.unwrap();
+        copy.execute("save_user_l2_to_l1_logs", self.storage)
+            .await
+            .unwrap();
     }
 
     /// Removes all L2-to-L1 logs with a miniblock number strictly greater than the specified `block_number`.
@@ -183,7 +175,9 @@ impl EventsDal<'_, '_> {
             "#,
             block_number.0 as i64
         )
-        .execute(self.storage.conn())
+        .instrument("rollback_l2_to_l1_logs")
+        .with_arg("block_number", &block_number)
+        .execute(self.storage)
         .await
         .unwrap();
     }
@@ -223,7 +217,8 @@ impl EventsDal<'_, '_> {
             "#,
             &hashes[..],
         )
-        .fetch_all(self.storage.conn())
+        .instrument("get_logs_by_tx_hashes")
+        .fetch_all(self.storage)
         .await?;
 
         let mut result = HashMap::<H256, Vec<api::Log>>::new();
@@ -268,7 +263,8 @@ impl EventsDal<'_, '_> {
             L1_MESSENGER_ADDRESS.as_bytes(),
             L1_MESSENGER_BYTECODE_PUBLICATION_EVENT_SIGNATURE.as_bytes()
         )
-        .fetch_all(self.storage.conn())
+        .instrument("get_l1_batch_raw_published_bytecode_hashes")
+        .fetch_all(self.storage)
         .await?
         .into_iter()
         .map(|row| H256::from_slice(&row.value))
@@ -312,7 +308,8 @@ impl EventsDal<'_, '_> {
             "#,
             &hashes[..]
         )
-        .fetch_all(self.storage.conn())
+        .instrument("get_l2_to_l1_logs_by_hashes")
+        .fetch_all(self.storage)
         .await?;
 
         let mut result = HashMap::<H256, Vec<api::L2ToL1Log>>::new();
@@ -48,7 +48,6 @@ impl EventsWeb3Dal<'_, '_> {
             query = query.bind(offset as i32);
             let log = query
                 .instrument("get_log_block_number")
-                .report_latency()
                 .with_arg("filter", filter)
                 .with_arg("offset", &offset)
                 .fetch_optional(self.storage)
@@ -101,7 +100,6 @@ impl EventsWeb3Dal<'_, '_> {
 
             let db_logs: Vec<StorageWeb3Log> = query
                 .instrument("get_logs")
-                .report_latency()
                 .with_arg("filter", &filter)
                 .with_arg("limit", &limit)
                 .fetch_all(self.storage)
@@ -183,7 +181,8 @@ impl EventsWeb3Dal<'_, '_> {
                 "#,
                 from_block.0 as i64
             )
-            .fetch_all(self.storage.conn())
+            .instrument("get_all_logs")
+            .fetch_all(self.storage)
             .await?;
             let logs = db_logs.into_iter().map(Into::into).collect();
             Ok(logs)
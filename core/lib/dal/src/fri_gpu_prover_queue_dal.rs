@@ -2,6 +2,7 @@ use std::time::Duration;
 
 use crate::{
     fri_prover_dal::types::{GpuProverInstanceStatus, SocketAddress},
+    instrument::InstrumentExt,
     time_utils::pg_interval_from_duration,
     StorageProcessor,
 };
@@ -56,7 +57,9 @@ impl FriGpuProverQueueDal<'_, '_> {
             specialized_prover_group_id as i16,
             zone
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("lock_available_prover")
+        .with_arg("specialized_prover_group_id", &specialized_prover_group_id)
+        .fetch_optional(self.storage)
         .await
         .unwrap()
         .map(|row| SocketAddress {
@@ -100,7 +103,9 @@ impl FriGpuProverQueueDal<'_, '_> {
             specialized_prover_group_id as i16,
             zone
         )
-        .execute(self.storage.conn())
+        .instrument("insert_prover_instance")
+        .with_arg("specialized_prover_group_id", &specialized_prover_group_id)
+        .execute(self.storage)
         .await
         .unwrap();
     }
@@ -127,7 +132,8 @@ impl FriGpuProverQueueDal<'_, '_> {
             address.port as i32,
             zone
         )
-        .execute(self.storage.conn())
+        .instrument("update_prover_instance_status")
+        .execute(self.storage)
         .await
         .unwrap();
     }
@@ -153,7 +159,8 @@ impl FriGpuProverQueueDal<'_, '_> {
             address.port as i32,
             zone
         )
-        .execute(self.storage.conn())
+        .instrument("update_prover_instance_from_full_to_available")
+        .execute(self.storage)
         .await
         .unwrap();
     }
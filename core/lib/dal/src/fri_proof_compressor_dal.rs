@@ -6,6 +6,7 @@ use zksync_types::L1BatchNumber;
 
 use crate::{
     fri_prover_dal::types::{JobCountStatistics, StuckJobs},
+    instrument::InstrumentExt,
     time_utils::{duration_to_naive_time, pg_interval_from_duration},
     StorageProcessor,
 };
@@ -49,7 +50,10 @@ impl FriProofCompressorDal<'_, '_> {
                 fri_proof_blob_url,
             ProofCompressionJobStatus::Queued.to_string(),
             )
-            .fetch_optional(self.storage.conn())
+            .instrument("insert_proof_compression_job")
+            .with_arg("block_number", &block_number)
+            .with_arg("fri_proof_blob_url", &fri_proof_blob_url)
+            .fetch_optional(self.storage)
             .await
             .unwrap();
     }
@@ -66,7 +70,9 @@ impl FriProofCompressorDal<'_, '_> {
             block_number.0 as i64,
             ProofCompressionJobStatus::Skipped.to_string(),
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("skip_proof_compression_job")
+        .with_arg("block_number", &block_number)
+        .fetch_optional(self.storage)
         .await
         .unwrap();
     }
@@ -106,7 +112,9 @@ impl FriProofCompressorDal<'_, '_> {
             ProofCompressionJobStatus::Queued.to_string(),
             picked_by,
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_next_proof_compression_job")
+        .with_arg("picked_by", &picked_by)
+        .fetch_optional(self.storage)
         .await
         .unwrap()
         .map(|row| L1BatchNumber(row.l1_batch_number as u32))
@@ -127,7 +135,9 @@ impl FriProofCompressorDal<'_, '_> {
             "#,
             l1_batch_number.0 as i64,
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_proof_compression_job_attempts")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .fetch_optional(self.storage)
         .await?
         .map(|row| row.attempts as u32);
 
@@ -156,7 +166,11 @@ impl FriProofCompressorDal<'_, '_> {
             l1_proof_blob_url,
             block_number.0 as i64,
         )
-        .execute(self.storage.conn())
+        .instrument("mark_proof_compression_job_successful")
+        .with_arg("block_number", &block_number)
+        .with_arg("time_taken", &time_taken)
+        .with_arg("l1_proof_blob_url", &l1_proof_blob_url)
+        .execute(self.storage)
         .await
         .unwrap();
     }
@@ -180,7 +194,10 @@ impl FriProofCompressorDal<'_, '_> {
             error,
             block_number.0 as i64
         )
-        .execute(self.storage.conn())
+        .instrument("mark_proof_compression_job_failed")
+        .with_arg("error", &error)
+        .with_arg("block_number", &block_number)
+        .execute(self.storage)
         .await
         .unwrap();
     }
@@ -209,7 +226,8 @@ impl FriProofCompressorDal<'_, '_> {
             ProofCompressionJobStatus::Successful.to_string(),
             ProofCompressionJobStatus::Skipped.to_string()
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_least_proven_block_number_not_sent_to_server")
+        .fetch_optional(self.storage)
         .await
         .ok()?;
         match row {
@@ -234,7 +252,9 @@ impl FriProofCompressorDal<'_, '_> {
             ProofCompressionJobStatus::SentToServer.to_string(),
             block_number.0 as i64
         )
-        .execute(self.storage.conn())
+        .instrument("mark_proof_sent_to_server")
+        .with_arg("block_number", &block_number)
+        .execute(self.storage)
         .await
         .unwrap();
     }
@@ -245,7 +265,8 @@ impl FriProofCompressorDal<'_, '_> {
                  FROM proof_compression_jobs_fri \
                  GROUP BY status",
         )
-        .fetch_all(self.storage.conn())
+        .instrument("get_jobs_stats")
+        .fetch_all(self.storage)
         .await
         .unwrap()
         .into_iter()
@@ -275,7 +296,8 @@ impl FriProofCompressorDal<'_, '_> {
                 1
             "#,
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_oldest_not_compressed_batch")
+        .fetch_optional(self.storage)
         .await
         .unwrap()
         .map(|row| L1BatchNumber(row.l1_batch_number as u32));
@@ -315,7 +337,9 @@ impl FriProofCompressorDal<'_, '_> {
                 &processing_timeout,
                 max_attempts as i32,
             )
-            .fetch_all(self.storage.conn())
+            .instrument("requeue_stuck_compressor_jobs")
+            .with_arg("max_attempts", &max_attempts)
+            .fetch_all(self.storage)
             .await
             .unwrap()
             .into_iter()
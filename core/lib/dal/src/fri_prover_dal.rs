@@ -1,12 +1,16 @@
 use std::{collections::HashMap, convert::TryFrom, time::Duration};
 
+use sqlx::types::chrono::NaiveDateTime;
 use zksync_types::{
     basic_fri_types::{AggregationRound, CircuitIdRoundTuple},
     protocol_version::FriProtocolVersionId,
     L1BatchNumber,
 };
 
-use self::types::{FriProverJobMetadata, JobCountStatistics, StuckJobs};
+use self::types::{
+    FriProverJobMetadata, JobCountStatistics, OldestUnprovenL1Batch, ProvedJobInfo,
+    ProverJobPriority, StuckJobs,
+};
 use crate::{
     instrument::InstrumentExt,
     metrics::MethodLatency,
@@ -18,9 +22,9 @@ use crate::{
 pub mod types {
     //! Types exposed by the prover DAL for general-purpose use.
 
-    use std::{net::IpAddr, ops::Add};
+    use std::{collections::HashMap, net::IpAddr, ops::Add, time::Duration};
 
-    use sqlx::types::chrono::{DateTime, Utc};
+    use sqlx::types::chrono::{DateTime, NaiveDateTime, Utc};
     use zksync_types::{basic_fri_types::AggregationRound, L1BatchNumber};
 
     #[derive(Debug, Clone)]
@@ -34,6 +38,18 @@ pub mod types {
         pub is_node_final_proof: bool,
     }
 
+    /// Priority of prover jobs. Queued jobs with higher priority are picked first, regardless
+    /// of their aggregation round and L1 batch number.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+    pub enum ProverJobPriority {
+        #[default]
+        Normal = 0,
+        /// Jobs for L1 batches that are close to the proof generation deadline.
+        NearDeadline = 1,
+        /// Jobs for L1 batches flagged as urgent by the operator.
+        Urgent = 2,
+    }
+
     #[derive(Debug, Clone, Copy, Default)]
     pub struct JobCountStatistics {
         pub queued: usize,
@@ -55,6 +71,24 @@ pub mod types {
         }
     }
 
+    /// Unfinished prover jobs for the oldest L1 batch that is not proven yet.
+    #[derive(Debug, Clone)]
+    pub struct OldestUnprovenL1Batch {
+        pub l1_batch_number: L1BatchNumber,
+        /// Time elapsed since the prover subsystem has received the batch, if known.
+        pub age: Option<Duration>,
+        /// Numbers of unfinished jobs keyed by `(circuit_id, aggregation_round)`.
+        pub unfinished_jobs: HashMap<(u8, u8), usize>,
+    }
+
+    #[derive(Debug, Clone)]
+    pub struct ProvedJobInfo {
+        pub circuit_id: u8,
+        pub aggregation_round: AggregationRound,
+        pub time_taken: Duration,
+        pub proved_at: NaiveDateTime,
+    }
+
     #[derive(Debug)]
     pub struct StuckJobs {
         pub id: u64,
@@ -168,6 +202,9 @@ pub mod types {
         Skipped,
         #[strum(serialize = "ignored")]
         Ignored,
+        /// The job has exhausted its attempts and is no longer retried.
+        #[strum(serialize = "quarantined")]
+        Quarantined,
     }
 
     #[derive(Debug, strum::Display, strum::EnumString, strum::AsRefStr)]
@@ -289,6 +326,7 @@ impl FriProverDal<'_, '_> {
                         status = 'queued'
                         AND protocol_version = ANY ($1)
                     ORDER BY
+                        priority DESC,
                         aggregation_round DESC,
                         l1_batch_number ASC,
                         id ASC
@@ -309,7 +347,9 @@ impl FriProverDal<'_, '_> {
             &protocol_versions[..],
             picked_by,
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_next_job")
+        .with_arg("picked_by", &picked_by)
+        .fetch_optional(self.storage)
         .await
         .unwrap()
         .map(|row| FriProverJobMetadata {
@@ -369,12 +409,14 @@ impl FriProverDal<'_, '_> {
                                 AND pj.circuit_id = tuple.circuit_id
                                 AND pj.aggregation_round = tuple.round
                             ORDER BY
+                                pj.priority DESC,
                                 pj.l1_batch_number ASC,
                                 pj.id ASC
                             LIMIT
                                 1
                         ) AS pj ON TRUE
                     ORDER BY
+                        pj.priority DESC,
                         pj.l1_batch_number ASC,
                         pj.aggregation_round DESC,
                         pj.id ASC
@@ -397,7 +439,9 @@ impl FriProverDal<'_, '_> {
             &protocol_versions[..],
             picked_by,
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_next_job_for_circuit_id_round")
+        .with_arg("picked_by", &picked_by)
+        .fetch_optional(self.storage)
         .await
         .unwrap()
         .map(|row| FriProverJobMetadata {
@@ -426,12 +470,214 @@ impl FriProverDal<'_, '_> {
                 error,
                 id as i64,
             )
-            .execute(self.storage.conn())
+            .instrument("save_proof_error")
+            .with_arg("id", &id)
+            .execute(self.storage)
             .await
             .unwrap();
         }
     }
 
+    /// Checks whether the specified in-progress aggregation job should be preempted, i.e., whether there's a queued job
+    /// with a higher priority that could be picked by the same prover. Jobs of the basic aggregation round
+    /// are never preempted. If `circuits_to_pick` is empty, the prover is assumed to prove all circuits.
+    pub async fn should_preempt_job(
+        &mut self,
+        id: u32,
+        circuits_to_pick: &[CircuitIdRoundTuple],
+        protocol_versions: &[FriProtocolVersionId],
+    ) -> sqlx::Result<bool> {
+        let circuit_ids: Vec<_> = circuits_to_pick
+            .iter()
+            .map(|tuple| tuple.circuit_id as i16)
+            .collect();
+        let aggregation_rounds: Vec<_> = circuits_to_pick
+            .iter()
+            .map(|tuple| tuple.aggregation_round as i16)
+            .collect();
+        let protocol_versions: Vec<i32> = protocol_versions.iter().map(|&id| id as i32).collect();
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                EXISTS (
+                    SELECT
+                        1
+                    FROM
+                        prover_jobs_fri AS current_job
+                        JOIN prover_jobs_fri AS queued_job ON queued_job.status = 'queued'
+                        AND queued_job.priority > current_job.priority
+                    WHERE
+                        current_job.id = $1
+                        AND current_job.aggregation_round > 0
+                        AND queued_job.protocol_version = ANY ($4)
+                        AND (
+                            CARDINALITY($2::SMALLINT[]) = 0
+                            OR (queued_job.circuit_id, queued_job.aggregation_round) IN (
+                                SELECT
+                                    *
+                                FROM
+                                    UNNEST($2::SMALLINT[], $3::SMALLINT[])
+                            )
+                        )
+                ) AS "should_preempt!"
+            "#,
+            id as i64,
+            &circuit_ids[..],
+            &aggregation_rounds[..],
+            &protocol_versions[..],
+        )
+        .instrument("should_preempt_job")
+        .with_arg("id", &id)
+        .fetch_one(self.storage)
+        .await?;
+        Ok(row.should_preempt)
+    }
+
+    /// Returns a preempted job to the queue. The preempted attempt is not counted towards the job attempts.
+    pub async fn requeue_preempted_job(&mut self, id: u32) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE prover_jobs_fri
+            SET
+                status = 'queued',
+                attempts = GREATEST(attempts - 1, 0),
+                updated_at = NOW(),
+                picked_by = NULL,
+                last_heartbeat_at = NULL
+            WHERE
+                id = $1
+                AND status = 'in_progress'
+            "#,
+            id as i64,
+        )
+        .instrument("requeue_preempted_job")
+        .with_arg("id", &id)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    /// Raises the priority of all current and future jobs for the specified L1 batch. The priority is never lowered.
+    pub async fn prioritize_l1_batch(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+        priority: ProverJobPriority,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            WITH
+                upserted AS (
+                    INSERT INTO
+                        prover_fri_l1_batch_priorities (l1_batch_number, priority, created_at, updated_at)
+                    VALUES
+                        ($1, $2, NOW(), NOW())
+                    ON CONFLICT (l1_batch_number) DO
+                    UPDATE
+                    SET
+                        priority = GREATEST(prover_fri_l1_batch_priorities.priority, EXCLUDED.priority),
+                        updated_at = NOW()
+                    RETURNING
+                        priority
+                )
+            UPDATE prover_jobs_fri
+            SET
+                priority = upserted.priority
+            FROM
+                upserted
+            WHERE
+                prover_jobs_fri.l1_batch_number = $1
+                AND prover_jobs_fri.status <> 'successful'
+                AND prover_jobs_fri.priority < upserted.priority
+            "#,
+            l1_batch_number.0 as i64,
+            priority as i32,
+        )
+        .instrument("prioritize_l1_batch")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .with_arg("priority", &priority)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    /// Raises the priority of jobs for all L1 batches that have been received by the prover subsystem
+    /// at least `age` ago, but are not proven yet. Returns numbers of L1 batches with the raised priority.
+    pub async fn prioritize_l1_batches_older_than(
+        &mut self,
+        age: Duration,
+        priority: ProverJobPriority,
+    ) -> sqlx::Result<Vec<L1BatchNumber>> {
+        let age = pg_interval_from_duration(age);
+        let rows = sqlx::query!(
+            r#"
+            WITH
+                batches AS (
+                    INSERT INTO
+                        prover_fri_l1_batch_priorities (l1_batch_number, priority, created_at, updated_at)
+                    SELECT
+                        w.l1_batch_number,
+                        $2,
+                        NOW(),
+                        NOW()
+                    FROM
+                        witness_inputs_fri AS w
+                    WHERE
+                        w.created_at <= NOW() - $1::INTERVAL
+                        AND NOT EXISTS (
+                            SELECT
+                                1
+                            FROM
+                                proof_compression_jobs_fri AS c
+                            WHERE
+                                c.l1_batch_number = w.l1_batch_number
+                        )
+                        AND NOT EXISTS (
+                            SELECT
+                                1
+                            FROM
+                                prover_fri_l1_batch_priorities AS p
+                            WHERE
+                                p.l1_batch_number = w.l1_batch_number
+                                AND p.priority >= $2
+                        )
+                    ON CONFLICT (l1_batch_number) DO
+                    UPDATE
+                    SET
+                        priority = EXCLUDED.priority,
+                        updated_at = NOW()
+                    RETURNING
+                        l1_batch_number
+                ),
+                updated_jobs AS (
+                    UPDATE prover_jobs_fri
+                    SET
+                        priority = $2
+                    FROM
+                        batches
+                    WHERE
+                        prover_jobs_fri.l1_batch_number = batches.l1_batch_number
+                        AND prover_jobs_fri.status <> 'successful'
+                        AND prover_jobs_fri.priority < $2
+                )
+            SELECT
+                l1_batch_number
+            FROM
+                batches
+            "#,
+            &age,
+            priority as i32,
+        )
+        .instrument("prioritize_l1_batches_older_than")
+        .with_arg("age", &age)
+        .with_arg("priority", &priority)
+        .fetch_all(self.storage)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(|row| L1BatchNumber(row.l1_batch_number as u32))
+            .collect())
+    }
+
     pub async fn get_prover_job_attempts(&mut self, id: u32) -> sqlx::Result<Option<u32>> {
         let attempts = sqlx::query!(
             r#"
@@ -444,7 +690,9 @@ impl FriProverDal<'_, '_> {
             "#,
             id as i64,
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_prover_job_attempts")
+        .with_arg("id", &id)
+        .fetch_optional(self.storage)
         .await?
         .map(|row| row.attempts as u32);
 
@@ -481,7 +729,6 @@ impl FriProverDal<'_, '_> {
             id as i64,
         )
         .instrument("save_fri_proof")
-        .report_latency()
         .with_arg("id", &id)
         .fetch_optional(self.storage)
         .await
@@ -511,7 +758,8 @@ impl FriProverDal<'_, '_> {
                 SET
                     status = 'queued',
                     updated_at = NOW(),
-                    processing_started_at = NOW()
+                    processing_started_at = NOW(),
+                    last_heartbeat_at = NULL
                 WHERE
                     id IN (
                         SELECT
@@ -544,7 +792,9 @@ impl FriProverDal<'_, '_> {
                 &processing_timeout,
                 max_attempts as i32,
             )
-            .fetch_all(self.storage.conn())
+            .instrument("requeue_stuck_prover_jobs")
+            .with_arg("max_attempts", &max_attempts)
+            .fetch_all(self.storage)
             .await
             .unwrap()
             .into_iter()
@@ -557,6 +807,149 @@ impl FriProverDal<'_, '_> {
         }
     }
 
+    /// Records a heartbeat for an in-progress job picked by the specified worker. Returns `false` if the job
+    /// is no longer assigned to the worker (e.g., it was returned to the queue because of missed heartbeats).
+    pub async fn record_heartbeat(&mut self, id: u32, picked_by: &str) -> sqlx::Result<bool> {
+        let row = sqlx::query!(
+            r#"
+            UPDATE prover_jobs_fri
+            SET
+                last_heartbeat_at = NOW()
+            WHERE
+                id = $1
+                AND status = 'in_progress'
+                AND picked_by = $2
+            RETURNING
+                id
+            "#,
+            id as i64,
+            picked_by,
+        )
+        .instrument("record_heartbeat")
+        .with_arg("id", &id)
+        .with_arg("picked_by", &picked_by)
+        .fetch_optional(self.storage)
+        .await?;
+        Ok(row.is_some())
+    }
+
+    /// Returns to the queue in-progress jobs which workers have stopped sending heartbeats, e.g. because a worker
+    /// was terminated mid-proof. Jobs picked by workers that don't send heartbeats are not affected.
+    pub async fn requeue_abandoned_jobs(
+        &mut self,
+        heartbeat_timeout: Duration,
+        max_attempts: u32,
+    ) -> sqlx::Result<Vec<StuckJobs>> {
+        let heartbeat_timeout = pg_interval_from_duration(heartbeat_timeout);
+        let rows = sqlx::query!(
+            r#"
+            UPDATE prover_jobs_fri
+            SET
+                status = 'queued',
+                updated_at = NOW(),
+                processing_started_at = NOW(),
+                last_heartbeat_at = NULL
+            WHERE
+                id IN (
+                    SELECT
+                        id
+                    FROM
+                        prover_jobs_fri
+                    WHERE
+                        status = 'in_progress'
+                        AND last_heartbeat_at IS NOT NULL
+                        AND GREATEST(last_heartbeat_at, processing_started_at) <= NOW() - $1::INTERVAL
+                        AND attempts < $2
+                    FOR UPDATE
+                        SKIP LOCKED
+                )
+            RETURNING
+                id,
+                status,
+                attempts
+            "#,
+            &heartbeat_timeout,
+            max_attempts as i32,
+        )
+        .instrument("requeue_abandoned_jobs")
+        .with_arg("heartbeat_timeout", &heartbeat_timeout)
+        .with_arg("max_attempts", &max_attempts)
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| StuckJobs {
+                id: row.id as u64,
+                status: row.status,
+                attempts: row.attempts as u64,
+            })
+            .collect())
+    }
+
+    /// Quarantines jobs that have exhausted their attempts: failed jobs, and in-progress jobs which workers have
+    /// timed out or stopped sending heartbeats. Such jobs are not retried.
+    pub async fn quarantine_poison_jobs(
+        &mut self,
+        processing_timeout: Duration,
+        heartbeat_timeout: Duration,
+        max_attempts: u32,
+    ) -> sqlx::Result<Vec<StuckJobs>> {
+        let processing_timeout = pg_interval_from_duration(processing_timeout);
+        let heartbeat_timeout = pg_interval_from_duration(heartbeat_timeout);
+        let rows = sqlx::query!(
+            r#"
+            UPDATE prover_jobs_fri
+            SET
+                status = 'quarantined',
+                updated_at = NOW()
+            WHERE
+                id IN (
+                    SELECT
+                        id
+                    FROM
+                        prover_jobs_fri
+                    WHERE
+                        attempts >= $3
+                        AND (
+                            status = 'failed'
+                            OR (
+                                status IN ('in_progress', 'in_gpu_proof')
+                                AND last_heartbeat_at IS NULL
+                                AND processing_started_at <= NOW() - $1::INTERVAL
+                            )
+                            OR (
+                                status = 'in_progress'
+                                AND GREATEST(last_heartbeat_at, processing_started_at) <= NOW() - $2::INTERVAL
+                            )
+                        )
+                    FOR UPDATE
+                        SKIP LOCKED
+                )
+            RETURNING
+                id,
+                status,
+                attempts
+            "#,
+            &processing_timeout,
+            &heartbeat_timeout,
+            max_attempts as i32,
+        )
+        .instrument("quarantine_poison_jobs")
+        .with_arg("max_attempts", &max_attempts)
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| StuckJobs {
+                id: row.id as u64,
+                status: row.status,
+                attempts: row.attempts as u64,
+            })
+            .collect())
+    }
+
     #[allow(clippy::too_many_arguments)]
     pub async fn insert_prover_job(
         &mut self,
@@ -581,12 +974,36 @@ impl FriProverDal<'_, '_> {
                             depth,
                             is_node_final_proof,
                             protocol_version,
+                            priority,
                             status,
                             created_at,
                             updated_at
                         )
                     VALUES
-                        ($1, $2, $3, $4, $5, $6, $7, $8, 'queued', NOW(), NOW())
+                        (
+                            $1,
+                            $2,
+                            $3,
+                            $4,
+                            $5,
+                            $6,
+                            $7,
+                            $8,
+                            COALESCE(
+                                (
+                                    SELECT
+                                        priority
+                                    FROM
+                                        prover_fri_l1_batch_priorities
+                                    WHERE
+                                        l1_batch_number = $1
+                                ),
+                                0
+                            ),
+                            'queued',
+                            NOW(),
+                            NOW()
+                        )
                     ON CONFLICT (l1_batch_number, aggregation_round, circuit_id, depth, sequence_number) DO
                     UPDATE
                     SET
@@ -601,7 +1018,16 @@ impl FriProverDal<'_, '_> {
             is_node_final_proof,
             protocol_version_id as i32,
         )
-            .execute(self.storage.conn())
+            .instrument("insert_prover_job")
+            .with_arg("l1_batch_number", &l1_batch_number)
+            .with_arg("circuit_id", &circuit_id)
+            .with_arg("depth", &depth)
+            .with_arg("sequence_number", &sequence_number)
+            .with_arg("aggregation_round", &aggregation_round)
+            .with_arg("circuit_blob_url", &circuit_blob_url)
+            .with_arg("is_node_final_proof", &is_node_final_proof)
+            .with_arg("protocol_version_id", &protocol_version_id)
+            .execute(self.storage)
             .await
             .unwrap();
     }
@@ -626,7 +1052,8 @@ impl FriProverDal<'_, '_> {
                     status
                 "#
             )
-            .fetch_all(self.storage.conn())
+            .instrument("get_prover_jobs_stats")
+            .fetch_all(self.storage)
             .await
             .unwrap()
             .into_iter()
@@ -651,7 +1078,7 @@ impl FriProverDal<'_, '_> {
                         });
                     match status.as_ref() {
                         "queued" => stats.queued = value,
-                        "in_progress" => stats.in_progress = value,
+                        "in_progress" | "in_gpu_proof" => stats.in_progress += value,
                         "failed" => stats.failed = value,
                         "successful" => stats.successful = value,
                         _ => (),
@@ -662,6 +1089,164 @@ impl FriProverDal<'_, '_> {
         }
     }
 
+    /// Returns jobs proved after `since`, ordered by the time they were proved. At most `limit` jobs are returned.
+    pub async fn get_proved_jobs_since(
+        &mut self,
+        since: NaiveDateTime,
+        limit: usize,
+    ) -> sqlx::Result<Vec<ProvedJobInfo>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                circuit_id,
+                aggregation_round,
+                EXTRACT(
+                    EPOCH
+                    FROM
+                        time_taken
+                )::FLOAT8 AS "time_taken_secs!",
+                updated_at
+            FROM
+                prover_jobs_fri
+            WHERE
+                status = 'successful'
+                AND time_taken IS NOT NULL
+                AND updated_at > $1
+            ORDER BY
+                updated_at
+            LIMIT
+                $2
+            "#,
+            since,
+            limit as i64
+        )
+        .instrument("get_proved_jobs_since")
+        .with_arg("since", &since)
+        .with_arg("limit", &limit)
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| ProvedJobInfo {
+                circuit_id: row.circuit_id as u8,
+                aggregation_round: AggregationRound::from(row.aggregation_round as u8),
+                time_taken: Duration::from_secs_f64(row.time_taken_secs.max(0.0)),
+                proved_at: row.updated_at,
+            })
+            .collect())
+    }
+
+    /// Returns average proving times for jobs proved within the last `window`, keyed by `(circuit_id, aggregation_round)`.
+    pub async fn get_average_proving_times(
+        &mut self,
+        window: Duration,
+    ) -> sqlx::Result<HashMap<(u8, u8), Duration>> {
+        let window = pg_interval_from_duration(window);
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                circuit_id,
+                aggregation_round,
+                AVG(
+                    EXTRACT(
+                        EPOCH
+                        FROM
+                            time_taken
+                    )
+                )::FLOAT8 AS "avg_time_taken_secs!"
+            FROM
+                prover_jobs_fri
+            WHERE
+                status = 'successful'
+                AND time_taken IS NOT NULL
+                AND updated_at >= NOW() - $1::INTERVAL
+            GROUP BY
+                circuit_id,
+                aggregation_round
+            "#,
+            &window
+        )
+        .instrument("get_average_proving_times")
+        .with_arg("window", &window)
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let avg_time = Duration::from_secs_f64(row.avg_time_taken_secs.max(0.0));
+                (
+                    (row.circuit_id as u8, row.aggregation_round as u8),
+                    avg_time,
+                )
+            })
+            .collect())
+    }
+
+    /// Returns unfinished jobs for the oldest L1 batch that has any unfinished prover jobs.
+    pub async fn get_oldest_unproven_l1_batch(
+        &mut self,
+    ) -> sqlx::Result<Option<OldestUnprovenL1Batch>> {
+        let rows = sqlx::query!(
+            r#"
+            WITH
+                oldest AS (
+                    SELECT
+                        MIN(l1_batch_number) AS l1_batch_number
+                    FROM
+                        prover_jobs_fri
+                    WHERE
+                        status IN ('queued', 'in_gpu_proof', 'in_progress', 'failed')
+                )
+            SELECT
+                p.l1_batch_number,
+                p.circuit_id,
+                p.aggregation_round,
+                COUNT(*) AS "count!",
+                EXTRACT(
+                    EPOCH
+                    FROM
+                        NOW() - w.created_at
+                )::FLOAT8 AS age_secs
+            FROM
+                prover_jobs_fri AS p
+                JOIN oldest ON p.l1_batch_number = oldest.l1_batch_number
+                LEFT JOIN witness_inputs_fri AS w ON w.l1_batch_number = p.l1_batch_number
+            WHERE
+                p.status IN ('queued', 'in_gpu_proof', 'in_progress', 'failed')
+            GROUP BY
+                p.l1_batch_number,
+                p.circuit_id,
+                p.aggregation_round,
+                w.created_at
+            "#
+        )
+        .instrument("get_oldest_unproven_l1_batch")
+        .fetch_all(self.storage)
+        .await?;
+
+        let Some(first_row) = rows.first() else {
+            return Ok(None);
+        };
+        let l1_batch_number = L1BatchNumber(first_row.l1_batch_number as u32);
+        let age = first_row
+            .age_secs
+            .map(|secs| Duration::from_secs_f64(secs.max(0.0)));
+        let unfinished_jobs = rows
+            .into_iter()
+            .map(|row| {
+                let key = (row.circuit_id as u8, row.aggregation_round as u8);
+                (key, row.count as usize)
+            })
+            .collect();
+        Ok(Some(OldestUnprovenL1Batch {
+            l1_batch_number,
+            age,
+            unfinished_jobs,
+        }))
+    }
+
     pub async fn min_unproved_l1_batch_number(&mut self) -> HashMap<(u8, u8), L1BatchNumber> {
         {
             sqlx::query!(
@@ -679,7 +1264,8 @@ impl FriProverDal<'_, '_> {
                     aggregation_round
                 "#
             )
-            .fetch_all(self.storage.conn())
+            .instrument("min_unproved_l1_batch_number")
+            .fetch_all(self.storage)
             .await
             .unwrap()
             .into_iter()
@@ -714,7 +1300,9 @@ impl FriProverDal<'_, '_> {
             "#,
             aggregation_round as i16
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("min_unproved_l1_batch_number_for_aggregation_round")
+        .with_arg("aggregation_round", &aggregation_round)
+        .fetch_optional(self.storage)
         .await
         .unwrap()
         .map(|row| L1BatchNumber(row.l1_batch_number as u32))
@@ -733,7 +1321,10 @@ impl FriProverDal<'_, '_> {
             status,
             id as i64,
         )
-        .execute(self.storage.conn())
+        .instrument("update_status")
+        .with_arg("id", &id)
+        .with_arg("status", &status)
+        .execute(self.storage)
         .await
         .unwrap();
     }
@@ -750,7 +1341,9 @@ impl FriProverDal<'_, '_> {
             "#,
             l1_batch_number.0 as i64,
         )
-        .execute(self.storage.conn())
+        .instrument("save_successful_sent_proof")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .execute(self.storage)
         .await
         .unwrap();
     }
@@ -773,7 +1366,9 @@ impl FriProverDal<'_, '_> {
             l1_batch_number.0 as i64,
             AggregationRound::Scheduler as i16,
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_scheduler_proof_job_id")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .fetch_optional(self.storage)
         .await
         .ok()?
         .map(|row| row.id as u32)
@@ -1,6 +1,6 @@
 use zksync_types::L1BatchNumber;
 
-use crate::StorageProcessor;
+use crate::{instrument::InstrumentExt, StorageProcessor};
 
 #[derive(Debug)]
 pub struct FriSchedulerDependencyTrackerDal<'a, 'c> {
@@ -40,7 +40,8 @@ impl FriSchedulerDependencyTrackerDal<'_, '_> {
                 l1_batch_number;
             "#,
         )
-        .fetch_all(self.storage.conn())
+        .instrument("get_l1_batches_ready_for_queuing")
+        .fetch_all(self.storage)
         .await
         .unwrap()
         .into_iter()
@@ -59,7 +60,8 @@ impl FriSchedulerDependencyTrackerDal<'_, '_> {
             "#,
             &l1_batches[..]
         )
-        .execute(self.storage.conn())
+        .instrument("mark_l1_batches_queued")
+        .execute(self.storage)
         .await
         .unwrap();
     }
@@ -81,7 +83,10 @@ impl FriSchedulerDependencyTrackerDal<'_, '_> {
         sqlx::query(&query)
             .bind(final_prover_job_id as i64)
             .bind(l1_batch_number.0 as i64)
-            .execute(self.storage.conn())
+            .instrument("set_final_prover_job_id_for_l1_batch")
+            .with_arg("circuit_id", &circuit_id)
+            .with_arg("l1_batch_number", &l1_batch_number)
+            .execute(self.storage)
             .await
             .unwrap();
     }
@@ -101,7 +106,9 @@ impl FriSchedulerDependencyTrackerDal<'_, '_> {
             "#,
             l1_batch_number.0 as i64,
         )
-        .fetch_all(self.storage.conn())
+        .instrument("get_final_prover_job_ids_for")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .fetch_all(self.storage)
         .await
         .unwrap()
         .into_iter()
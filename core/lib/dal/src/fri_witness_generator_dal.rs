@@ -9,6 +9,7 @@ use crate::{
     fri_prover_dal::types::{
         JobCountStatistics, LeafAggregationJobMetadata, NodeAggregationJobMetadata, StuckJobs,
     },
+    instrument::InstrumentExt,
     metrics::MethodLatency,
     time_utils::{duration_to_naive_time, pg_interval_from_duration},
     StorageProcessor,
@@ -59,7 +60,11 @@ impl FriWitnessGeneratorDal<'_, '_> {
             object_key,
             protocol_version_id as i32,
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("save_witness_inputs")
+        .with_arg("block_number", &block_number)
+        .with_arg("object_key", &object_key)
+        .with_arg("protocol_version_id", &protocol_version_id)
+        .fetch_optional(self.storage)
         .await
         .unwrap();
     }
@@ -104,7 +109,10 @@ impl FriWitnessGeneratorDal<'_, '_> {
             &protocol_versions[..],
             picked_by,
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_next_basic_circuit_witness_job")
+        .with_arg("last_l1_batch_to_process", &last_l1_batch_to_process)
+        .with_arg("picked_by", &picked_by)
+        .fetch_optional(self.storage)
         .await
         .unwrap()
         .map(|row| L1BatchNumber(row.l1_batch_number as u32))
@@ -125,7 +133,9 @@ impl FriWitnessGeneratorDal<'_, '_> {
             "#,
             l1_batch_number.0 as i64,
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_basic_circuit_witness_job_attempts")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .fetch_optional(self.storage)
         .await?
         .map(|row| row.attempts as u32);
 
@@ -149,7 +159,9 @@ impl FriWitnessGeneratorDal<'_, '_> {
             format!("{}", status),
             block_number.0 as i64
         )
-        .execute(self.storage.conn())
+        .instrument("mark_witness_job")
+        .with_arg("block_number", &block_number)
+        .execute(self.storage)
         .await
         .unwrap();
     }
@@ -172,7 +184,10 @@ impl FriWitnessGeneratorDal<'_, '_> {
             duration_to_naive_time(time_taken),
             block_number.0 as i64
         )
-        .execute(self.storage.conn())
+        .instrument("mark_witness_job_as_successful")
+        .with_arg("block_number", &block_number)
+        .with_arg("time_taken", &time_taken)
+        .execute(self.storage)
         .await
         .unwrap();
     }
@@ -191,7 +206,10 @@ impl FriWitnessGeneratorDal<'_, '_> {
             error,
             block_number.0 as i64
         )
-        .execute(self.storage.conn())
+        .instrument("mark_witness_job_failed")
+        .with_arg("error", &error)
+        .with_arg("block_number", &block_number)
+        .execute(self.storage)
         .await
         .unwrap();
     }
@@ -210,7 +228,10 @@ impl FriWitnessGeneratorDal<'_, '_> {
             error,
             id as i64
         )
-        .execute(self.storage.conn())
+        .instrument("mark_leaf_aggregation_job_failed")
+        .with_arg("error", &error)
+        .with_arg("id", &id)
+        .execute(self.storage)
         .await
         .unwrap();
     }
@@ -229,7 +250,10 @@ impl FriWitnessGeneratorDal<'_, '_> {
             duration_to_naive_time(time_taken),
             id as i64
         )
-        .execute(self.storage.conn())
+        .instrument("mark_leaf_aggregation_as_successful")
+        .with_arg("id", &id)
+        .with_arg("time_taken", &time_taken)
+        .execute(self.storage)
         .await
         .unwrap();
     }
@@ -270,7 +294,9 @@ impl FriWitnessGeneratorDal<'_, '_> {
             &processing_timeout,
             max_attempts as i32,
         )
-        .fetch_all(self.storage.conn())
+        .instrument("requeue_stuck_witness_jobs")
+        .with_arg("max_attempts", &max_attempts)
+        .fetch_all(self.storage)
         .await
         .unwrap()
         .into_iter()
@@ -321,7 +347,9 @@ impl FriWitnessGeneratorDal<'_, '_> {
                     *number_of_basic_circuits as i32,
                     protocol_version_id as i32,
                 )
-                .execute(self.storage.conn())
+                .instrument("create_aggregation_jobs#1")
+                .with_arg("block_number", &block_number)
+                .execute(self.storage)
                 .await
                 .unwrap();
 
@@ -358,7 +386,13 @@ impl FriWitnessGeneratorDal<'_, '_> {
                 scheduler_partial_input_blob_url,
                 protocol_version_id as i32,
             )
-            .execute(self.storage.conn())
+            .instrument("create_aggregation_jobs#2")
+            .with_arg("block_number", &block_number)
+            .with_arg(
+                "scheduler_partial_input_blob_url",
+                &scheduler_partial_input_blob_url,
+            )
+            .execute(self.storage)
             .await
             .unwrap();
 
@@ -375,7 +409,9 @@ impl FriWitnessGeneratorDal<'_, '_> {
                 "#,
                 block_number.0 as i64,
             )
-            .execute(self.storage.conn())
+            .instrument("create_aggregation_jobs#3")
+            .with_arg("block_number", &block_number)
+            .execute(self.storage)
             .await
             .unwrap();
 
@@ -421,7 +457,9 @@ impl FriWitnessGeneratorDal<'_, '_> {
             &protocol_versions[..],
             picked_by,
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_next_leaf_aggregation_job")
+        .with_arg("picked_by", &picked_by)
+        .fetch_optional(self.storage)
         .await
         .unwrap()?;
 
@@ -457,7 +495,9 @@ impl FriWitnessGeneratorDal<'_, '_> {
             "#,
             id as i64,
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_leaf_aggregation_job_attempts")
+        .with_arg("id", &id)
+        .fetch_optional(self.storage)
         .await
         .unwrap()
         .map(|row| row.attempts as u32);
@@ -492,7 +532,12 @@ impl FriWitnessGeneratorDal<'_, '_> {
             round as i16,
             depth as i32,
         )
-        .fetch_all(self.storage.conn())
+        .instrument("prover_job_ids_for")
+        .with_arg("block_number", &block_number)
+        .with_arg("circuit_id", &circuit_id)
+        .with_arg("round", &round)
+        .with_arg("depth", &depth)
+        .fetch_all(self.storage)
         .await
         .unwrap()
         .into_iter()
@@ -531,7 +576,8 @@ impl FriWitnessGeneratorDal<'_, '_> {
                     circuit_id;
                 "#,
         )
-        .fetch_all(self.storage.conn())
+        .instrument("move_leaf_aggregation_jobs_from_waiting_to_queued")
+        .fetch_all(self.storage)
         .await
         .unwrap()
         .into_iter()
@@ -565,7 +611,12 @@ impl FriWitnessGeneratorDal<'_, '_> {
             depth as i32,
             number_of_dependent_jobs as i32,
         )
-        .execute(self.storage.conn())
+        .instrument("update_node_aggregation_jobs_url")
+        .with_arg("block_number", &block_number)
+        .with_arg("circuit_id", &circuit_id)
+        .with_arg("number_of_dependent_jobs", &number_of_dependent_jobs)
+        .with_arg("depth", &depth)
+        .execute(self.storage)
         .await
         .unwrap();
     }
@@ -609,7 +660,9 @@ impl FriWitnessGeneratorDal<'_, '_> {
             &protocol_versions[..],
             picked_by,
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_next_node_aggregation_job")
+        .with_arg("picked_by", &picked_by)
+        .fetch_optional(self.storage)
         .await
         .unwrap()?;
         let depth = row.depth as u16;
@@ -649,7 +702,9 @@ impl FriWitnessGeneratorDal<'_, '_> {
             "#,
             id as i64,
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_node_aggregation_job_attempts")
+        .with_arg("id", &id)
+        .fetch_optional(self.storage)
         .await
         .unwrap()
         .map(|row| row.attempts as u32);
@@ -671,7 +726,10 @@ impl FriWitnessGeneratorDal<'_, '_> {
             error,
             id as i64
         )
-        .execute(self.storage.conn())
+        .instrument("mark_node_aggregation_job_failed")
+        .with_arg("error", &error)
+        .with_arg("id", &id)
+        .execute(self.storage)
         .await
         .unwrap();
     }
@@ -690,7 +748,10 @@ impl FriWitnessGeneratorDal<'_, '_> {
             duration_to_naive_time(time_taken),
             id as i64
         )
-        .execute(self.storage.conn())
+        .instrument("mark_node_aggregation_as_successful")
+        .with_arg("id", &id)
+        .with_arg("time_taken", &time_taken)
+        .execute(self.storage)
         .await
         .unwrap();
     }
@@ -732,7 +793,14 @@ impl FriWitnessGeneratorDal<'_, '_> {
             number_of_dependent_jobs,
             protocol_version_id as i32,
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("insert_node_aggregation_jobs")
+        .with_arg("block_number", &block_number)
+        .with_arg("circuit_id", &circuit_id)
+        .with_arg("number_of_dependent_jobs", &number_of_dependent_jobs)
+        .with_arg("depth", &depth)
+        .with_arg("aggregations_url", &aggregations_url)
+        .with_arg("protocol_version_id", &protocol_version_id)
+        .fetch_optional(self.storage)
         .await
         .unwrap();
     }
@@ -773,7 +841,8 @@ impl FriWitnessGeneratorDal<'_, '_> {
                     depth;
                 "#,
         )
-        .fetch_all(self.storage.conn())
+        .instrument("move_depth_zero_node_aggregation_jobs")
+        .fetch_all(self.storage)
         .await
         .unwrap()
         .into_iter()
@@ -816,7 +885,8 @@ impl FriWitnessGeneratorDal<'_, '_> {
                     depth;
                 "#,
         )
-        .fetch_all(self.storage.conn())
+        .instrument("move_depth_non_zero_node_aggregation_jobs")
+        .fetch_all(self.storage)
         .await
         .unwrap()
         .into_iter()
@@ -855,7 +925,9 @@ impl FriWitnessGeneratorDal<'_, '_> {
             &processing_timeout,
             max_attempts as i32,
         )
-        .fetch_all(self.storage.conn())
+        .instrument("requeue_stuck_leaf_aggregations_jobs")
+        .with_arg("max_attempts", &max_attempts)
+        .fetch_all(self.storage)
         .await
         .unwrap()
         .into_iter()
@@ -898,7 +970,9 @@ impl FriWitnessGeneratorDal<'_, '_> {
             &processing_timeout,
             max_attempts as i32,
         )
-        .fetch_all(self.storage.conn())
+        .instrument("requeue_stuck_node_aggregations_jobs")
+        .with_arg("max_attempts", &max_attempts)
+        .fetch_all(self.storage)
         .await
         .unwrap()
         .into_iter()
@@ -923,7 +997,9 @@ impl FriWitnessGeneratorDal<'_, '_> {
             "#,
             l1_batch_number
         )
-        .execute(self.storage.conn())
+        .instrument("mark_scheduler_jobs_as_queued")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .execute(self.storage)
         .await
         .unwrap();
     }
@@ -959,7 +1035,9 @@ impl FriWitnessGeneratorDal<'_, '_> {
             &processing_timeout,
             max_attempts as i32,
         )
-        .fetch_all(self.storage.conn())
+        .instrument("requeue_stuck_scheduler_jobs")
+        .with_arg("max_attempts", &max_attempts)
+        .fetch_all(self.storage)
         .await
         .unwrap()
         .into_iter()
@@ -1008,7 +1086,9 @@ impl FriWitnessGeneratorDal<'_, '_> {
             &protocol_versions[..],
             picked_by,
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_next_scheduler_witness_job")
+        .with_arg("picked_by", &picked_by)
+        .fetch_optional(self.storage)
         .await
         .unwrap()
         .map(|row| L1BatchNumber(row.l1_batch_number as u32))
@@ -1029,7 +1109,9 @@ impl FriWitnessGeneratorDal<'_, '_> {
             "#,
             l1_batch_number.0 as i64,
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_scheduler_witness_job_attempts")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .fetch_optional(self.storage)
         .await?
         .map(|row| row.attempts as u32);
 
@@ -1054,7 +1136,10 @@ impl FriWitnessGeneratorDal<'_, '_> {
             duration_to_naive_time(time_taken),
             block_number.0 as i64
         )
-        .execute(self.storage.conn())
+        .instrument("mark_scheduler_job_as_successful")
+        .with_arg("block_number", &block_number)
+        .with_arg("time_taken", &time_taken)
+        .execute(self.storage)
         .await
         .unwrap();
     }
@@ -1073,7 +1158,10 @@ impl FriWitnessGeneratorDal<'_, '_> {
             error,
             block_number.0 as i64
         )
-        .execute(self.storage.conn())
+        .instrument("mark_scheduler_job_failed")
+        .with_arg("error", &error)
+        .with_arg("block_number", &block_number)
+        .execute(self.storage)
         .await
         .unwrap();
     }
@@ -1092,7 +1180,9 @@ impl FriWitnessGeneratorDal<'_, '_> {
             table_name
         );
         let mut results: HashMap<String, i64> = sqlx::query(&sql)
-            .fetch_all(self.storage.conn())
+            .instrument("get_witness_jobs_stats")
+            .with_arg("table_name", &table_name)
+            .fetch_all(self.storage)
             .await
             .unwrap()
             .into_iter()
@@ -1131,7 +1221,9 @@ impl FriWitnessGeneratorDal<'_, '_> {
             "#,
             l1_batch_number.0 as i64,
         )
-        .fetch_one(self.storage.conn())
+        .instrument("protocol_version_for_l1_batch")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .fetch_one(self.storage)
         .await
         .unwrap()
         .protocol_version
@@ -1,7 +1,10 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
 use serde::Serialize;
 use zksync_health_check::{async_trait, CheckHealth, Health, HealthStatus};
+use zksync_types::L1BatchNumber;
 
-use crate::ConnectionPool;
+use crate::{ConnectionPool, StorageProcessor};
 
 #[derive(Debug, Serialize)]
 struct ConnectionPoolHealthDetails {
@@ -56,3 +59,115 @@ impl CheckHealth for ConnectionPoolHealthCheck {
         }
     }
 }
+
+/// Thresholds for [`L1BatchHealthCheck`]. Thresholds that are not set are not checked.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct L1BatchHealthThresholds {
+    /// Maximum age of the last sealed L1 batch.
+    pub max_sealed_l1_batch_age: Option<Duration>,
+    /// Maximum number of sealed L1 batches not processed by the Merkle tree yet.
+    pub max_tree_lag: Option<u32>,
+}
+
+#[derive(Debug, Serialize)]
+struct L1BatchHealthDetails {
+    sealed_l1_batch: L1BatchNumber,
+    sealed_l1_batch_age_sec: u64,
+    tree_lag: u32,
+}
+
+/// Health check for L1 batch processing: the age of the last sealed L1 batch and the lag of the Merkle tree
+/// (i.e., the number of sealed L1 batches without metadata). The check is not ready if any of the configured
+/// thresholds is exceeded, e.g. if the node is syncing or the state keeper is stuck.
+#[derive(Debug, Clone)]
+pub struct L1BatchHealthCheck {
+    connection_pool: ConnectionPool,
+    thresholds: L1BatchHealthThresholds,
+}
+
+impl L1BatchHealthCheck {
+    pub fn new(connection_pool: ConnectionPool, thresholds: L1BatchHealthThresholds) -> Self {
+        Self {
+            connection_pool,
+            thresholds,
+        }
+    }
+
+    async fn details(
+        storage: &mut StorageProcessor<'_>,
+    ) -> sqlx::Result<Option<L1BatchHealthDetails>> {
+        let mut blocks_dal = storage.blocks_dal();
+        let Some(sealed_l1_batch) = blocks_dal.get_sealed_l1_batch_number().await? else {
+            return Ok(None);
+        };
+        let Some(header) = blocks_dal.get_l1_batch_header(sealed_l1_batch).await? else {
+            return Ok(None); // The batch was reverted concurrently
+        };
+        let tree_lag = match blocks_dal.get_last_l1_batch_number_with_metadata().await? {
+            Some(last_processed_l1_batch) => {
+                sealed_l1_batch.0.saturating_sub(last_processed_l1_batch.0)
+            }
+            None => {
+                // No L1 batches are processed by the tree yet.
+                let earliest_l1_batch = blocks_dal.get_earliest_l1_batch_number().await?;
+                sealed_l1_batch.0 - earliest_l1_batch.unwrap_or(sealed_l1_batch).0 + 1
+            }
+        };
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("current time is before Unix epoch")
+            .as_secs();
+        Ok(Some(L1BatchHealthDetails {
+            sealed_l1_batch,
+            sealed_l1_batch_age_sec: now.saturating_sub(header.timestamp),
+            tree_lag,
+        }))
+    }
+
+    fn status(&self, details: &L1BatchHealthDetails) -> HealthStatus {
+        let is_too_old = self
+            .thresholds
+            .max_sealed_l1_batch_age
+            .map_or(false, |max_age| {
+                details.sealed_l1_batch_age_sec > max_age.as_secs()
+            });
+        let is_tree_lagging = self
+            .thresholds
+            .max_tree_lag
+            .map_or(false, |max_lag| details.tree_lag > max_lag);
+        if is_too_old || is_tree_lagging {
+            HealthStatus::NotReady
+        } else {
+            HealthStatus::Ready
+        }
+    }
+}
+
+#[async_trait]
+impl CheckHealth for L1BatchHealthCheck {
+    fn name(&self) -> &'static str {
+        "l1_batches"
+    }
+
+    async fn check_health(&self) -> Health {
+        let details = match self.connection_pool.access_storage().await {
+            Ok(mut storage) => Self::details(&mut storage).await,
+            Err(err) => {
+                tracing::warn!("Failed acquiring DB connection for health check: {err:?}");
+                return Health::from(HealthStatus::NotReady)
+                    .with_details(serde_json::json!({ "error": format!("{err:?}") }));
+            }
+        };
+        match details {
+            // There are no L1 batches yet (e.g., the node is initializing); thresholds cannot be checked.
+            Ok(None) => HealthStatus::Ready.into(),
+            Ok(Some(details)) => Health::from(self.status(&details)).with_details(details),
+            Err(err) => {
+                tracing::warn!("Failed getting L1 batch info for health check: {err}");
+                Health::from(HealthStatus::NotReady)
+                    .with_details(serde_json::json!({ "error": err.to_string() }))
+            }
+        }
+    }
+}
@@ -8,14 +8,18 @@
 //!
 //! The entry point for instrumentation is the [`InstrumentExt`] trait. After it is imported into the scope,
 //! its `instrument()` method can be placed on the output of `query*` functions or macros. You can then call
-//! [`Instrumented`] methods on the returned struct, e.g. to [report query latency](Instrumented::report_latency())
-//! and/or [to add logged args](Instrumented::with_arg()) for a query.
+//! [`Instrumented`] methods on the returned struct, e.g. [to add logged args](Instrumented::with_arg()) for a query.
+//!
+//! Query names are used as metric labels, so they should be stable and have low cardinality. By convention,
+//! a query is named after the DAL method executing it; if a method executes several queries, they are distinguished
+//! with a `#{index}` suffix (e.g., `insert_l1_batch#2`).
 
 use std::{fmt, future::Future, panic::Location};
 
 use sqlx::{
     postgres::{PgQueryResult, PgRow},
     query::{Map, Query, QueryAs},
+    query_scalar::QueryScalar,
     FromRow, IntoArguments, Postgres,
 };
 use tokio::time::Instant;
@@ -33,6 +37,26 @@ struct QueryArgs<'a> {
     inner: Vec<(&'static str, &'a ThreadSafeDebug<'a>)>,
 }
 
+impl QueryArgs<'_> {
+    /// Maximum length of a logged argument value. Longer values (e.g., large arrays of hashes) are truncated,
+    /// so that queries with bulky args don't flood logs.
+    const MAX_VALUE_LEN: usize = 256;
+
+    fn summarize(value: &ThreadSafeDebug<'_>) -> String {
+        let mut summary = format!("{value:?}");
+        let summary_len = summary.chars().count();
+        if summary_len > Self::MAX_VALUE_LEN {
+            let truncate_at = summary
+                .char_indices()
+                .nth(Self::MAX_VALUE_LEN)
+                .map_or(summary.len(), |(idx, _)| idx);
+            summary.truncate(truncate_at);
+            summary.push_str(&format!("... ({summary_len} chars)"));
+        }
+        summary
+    }
+}
+
 impl fmt::Display for QueryArgs<'_> {
     fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
         if self.inner.is_empty() {
@@ -40,7 +64,8 @@ impl fmt::Display for QueryArgs<'_> {
         } else {
             formatter.write_str("(")?;
             for (i, (name, value)) in self.inner.iter().enumerate() {
-                write!(formatter, "{name}={value:?}")?;
+                let value = Self::summarize(*value);
+                write!(formatter, "{name}={value}")?;
                 if i + 1 < self.inner.len() {
                     formatter.write_str(", ")?;
                 }
@@ -82,6 +107,19 @@ where
     }
 }
 
+impl<'q, O, A> InstrumentExt for QueryScalar<'q, Postgres, O, A>
+where
+    A: 'q + IntoArguments<'q, Postgres>,
+{
+    #[track_caller]
+    fn instrument(self, name: &'static str) -> Instrumented<'static, Self> {
+        Instrumented {
+            query: self,
+            data: InstrumentedData::new(name, Location::caller()),
+        }
+    }
+}
+
 impl<'q, F, O, A> InstrumentExt for Map<'q, Postgres, F, A>
 where
     F: FnMut(PgRow) -> Result<O, sqlx::Error> + Send,
@@ -102,7 +140,6 @@ struct InstrumentedData<'a> {
     name: &'static str,
     location: &'static Location<'static>,
     args: QueryArgs<'a>,
-    report_latency: bool,
 }
 
 impl<'a> InstrumentedData<'a> {
@@ -111,7 +148,6 @@ impl<'a> InstrumentedData<'a> {
             name,
             location,
             args: QueryArgs::default(),
-            report_latency: false,
         }
     }
 
@@ -124,7 +160,6 @@ impl<'a> InstrumentedData<'a> {
             name,
             location,
             args,
-            report_latency,
         } = self;
         let started_at = Instant::now();
         tokio::pin!(query_future);
@@ -149,9 +184,7 @@ impl<'a> InstrumentedData<'a> {
         };
 
         let elapsed = started_at.elapsed();
-        if report_latency {
-            REQUEST_METRICS.request[&name].observe(elapsed);
-        }
+        REQUEST_METRICS.request[&name].observe(elapsed);
 
         let connection_tags = StorageProcessorTags::display(connection_tags);
         if let Err(err) = &output {
@@ -177,8 +210,10 @@ impl<'a> InstrumentedData<'a> {
 ///
 /// The following instrumentation logic is included:
 ///
+/// - Query latency is reported using metrics (`sql.request`), with the query name as a label.
 /// - If the query executes for too long, it is logged with a `WARN` level. The logged info includes
-///   the query name, its args provided via [Self::with_arg()`] and the caller location.
+///   the query name, summaries of its args provided via [Self::with_arg()`] and the caller location.
+///   The threshold for slow queries is configured via [`ConnectionPool::global_config()`].
 /// - If the query returns an error, it is logged with a `WARN` level. The logged info is everything
 ///   included in the case of a slow query, plus the error info.
 /// - Slow and erroneous queries are also reported using metrics (`dal.request.slow` and `dal.request.error`,
@@ -190,14 +225,8 @@ pub(crate) struct Instrumented<'a, Q> {
 }
 
 impl<'a, Q> Instrumented<'a, Q> {
-    /// Indicates that latency should be reported for all calls.
-    pub fn report_latency(mut self) -> Self {
-        self.data.report_latency = true;
-        self
-    }
-
     /// Adds a traced query argument. The argument will be logged (using `Debug`) if the query executes too slow
-    /// or finishes with an error.
+    /// or finishes with an error. Long argument values are truncated in logs.
     pub fn with_arg(mut self, name: &'static str, value: &'a ThreadSafeDebug) -> Self {
         self.data.args.inner.push((name, value));
         self
@@ -222,6 +251,18 @@ where
         let (conn, tags) = storage.conn_and_tags();
         self.data.fetch(tags, self.query.fetch_optional(conn)).await
     }
+
+    /// Fetches a single row using this query.
+    pub async fn fetch_one(self, storage: &mut StorageProcessor<'_>) -> sqlx::Result<PgRow> {
+        let (conn, tags) = storage.conn_and_tags();
+        self.data.fetch(tags, self.query.fetch_one(conn)).await
+    }
+
+    /// Fetches all rows using this query and collects them into a `Vec`.
+    pub async fn fetch_all(self, storage: &mut StorageProcessor<'_>) -> sqlx::Result<Vec<PgRow>> {
+        let (conn, tags) = storage.conn_and_tags();
+        self.data.fetch(tags, self.query.fetch_all(conn)).await
+    }
 }
 
 impl<'q, O, A> Instrumented<'_, QueryAs<'q, Postgres, O, A>>
@@ -236,6 +277,28 @@ where
     }
 }
 
+impl<'q, O, A> Instrumented<'_, QueryScalar<'q, Postgres, O, A>>
+where
+    A: 'q + IntoArguments<'q, Postgres>,
+    O: Send + Unpin,
+    (O,): Send + Unpin + for<'r> FromRow<'r, PgRow>,
+{
+    /// Fetches an optional scalar using this query.
+    pub async fn fetch_optional(
+        self,
+        storage: &mut StorageProcessor<'_>,
+    ) -> sqlx::Result<Option<O>> {
+        let (conn, tags) = storage.conn_and_tags();
+        self.data.fetch(tags, self.query.fetch_optional(conn)).await
+    }
+
+    /// Fetches a single scalar using this query.
+    pub async fn fetch_one(self, storage: &mut StorageProcessor<'_>) -> sqlx::Result<O> {
+        let (conn, tags) = storage.conn_and_tags();
+        self.data.fetch(tags, self.query.fetch_one(conn)).await
+    }
+}
+
 impl<'q, F, O, A> Instrumented<'_, Map<'q, Postgres, F, A>>
 where
     F: FnMut(PgRow) -> Result<O, sqlx::Error> + Send,
@@ -271,6 +334,32 @@ mod tests {
     use super::*;
     use crate::ConnectionPool;
 
+    #[test]
+    fn formatting_query_args() {
+        let miniblock = MiniblockNumber(1);
+        let hashes = vec![H256::zero(); 100];
+        let args = QueryArgs {
+            inner: vec![
+                ("miniblock", &miniblock as &ThreadSafeDebug),
+                ("hashes", &hashes),
+            ],
+        };
+        let args = args.to_string();
+        let (miniblock_arg, hashes_arg) = args.split_once(", ").unwrap();
+        assert_eq!(miniblock_arg, "(miniblock=MiniblockNumber(1)");
+
+        let hashes_arg = hashes_arg.strip_prefix("hashes=[").unwrap();
+        let full_len = format!("{hashes:?}").len();
+        assert!(
+            hashes_arg.ends_with(&format!("... ({full_len} chars))")),
+            "{hashes_arg}"
+        );
+        assert!(
+            hashes_arg.len() < 2 * QueryArgs::MAX_VALUE_LEN,
+            "{hashes_arg}"
+        );
+    }
+
     #[tokio::test]
     async fn instrumenting_erroneous_query() {
         let pool = ConnectionPool::test_pool().await;
@@ -4,33 +4,36 @@ pub use sqlx::{types::BigDecimal, Error as SqlxError};
 
 pub use crate::connection::{ConnectionPool, StorageProcessor};
 use crate::{
-    basic_witness_input_producer_dal::BasicWitnessInputProducerDal, blocks_dal::BlocksDal,
-    blocks_web3_dal::BlocksWeb3Dal, consensus_dal::ConsensusDal,
-    contract_verification_dal::ContractVerificationDal, eth_sender_dal::EthSenderDal,
-    events_dal::EventsDal, events_web3_dal::EventsWeb3Dal, factory_deps_dal::FactoryDepsDal,
-    fri_gpu_prover_queue_dal::FriGpuProverQueueDal,
+    basic_witness_input_producer_dal::BasicWitnessInputProducerDal,
+    block_exporter_dal::BlockExporterDal, blocks_dal::BlocksDal, blocks_web3_dal::BlocksWeb3Dal,
+    consensus_dal::ConsensusDal, contract_verification_dal::ContractVerificationDal,
+    eth_sender_dal::EthSenderDal, events_dal::EventsDal, events_web3_dal::EventsWeb3Dal,
+    factory_deps_dal::FactoryDepsDal, fri_gpu_prover_queue_dal::FriGpuProverQueueDal,
     fri_proof_compressor_dal::FriProofCompressorDal,
     fri_protocol_versions_dal::FriProtocolVersionsDal, fri_prover_dal::FriProverDal,
     fri_scheduler_dependency_tracker_dal::FriSchedulerDependencyTrackerDal,
-    fri_witness_generator_dal::FriWitnessGeneratorDal, proof_generation_dal::ProofGenerationDal,
+    fri_witness_generator_dal::FriWitnessGeneratorDal, migrations_dal::MigrationsDal,
+    partitions_dal::PartitionsDal, proof_generation_dal::ProofGenerationDal,
     protocol_versions_dal::ProtocolVersionsDal,
-    protocol_versions_web3_dal::ProtocolVersionsWeb3Dal,
-    snapshot_recovery_dal::SnapshotRecoveryDal, snapshots_creator_dal::SnapshotsCreatorDal,
-    snapshots_dal::SnapshotsDal, storage_logs_dal::StorageLogsDal,
-    storage_logs_dedup_dal::StorageLogsDedupDal, storage_web3_dal::StorageWeb3Dal,
-    sync_dal::SyncDal, system_dal::SystemDal, tokens_dal::TokensDal,
-    tokens_web3_dal::TokensWeb3Dal, transactions_dal::TransactionsDal,
+    protocol_versions_web3_dal::ProtocolVersionsWeb3Dal, prover_artifacts_dal::ProverArtifactsDal,
+    pruning_dal::PruningDal, snapshot_recovery_dal::SnapshotRecoveryDal,
+    snapshots_creator_dal::SnapshotsCreatorDal, snapshots_dal::SnapshotsDal,
+    storage_logs_dal::StorageLogsDal, storage_logs_dedup_dal::StorageLogsDedupDal,
+    storage_web3_dal::StorageWeb3Dal, sync_dal::SyncDal, system_dal::SystemDal,
+    tokens_dal::TokensDal, tokens_web3_dal::TokensWeb3Dal, transactions_dal::TransactionsDal,
     transactions_web3_dal::TransactionsWeb3Dal,
 };
 
 #[macro_use]
 mod macro_utils;
 pub mod basic_witness_input_producer_dal;
+pub mod block_exporter_dal;
 pub mod blocks_dal;
 pub mod blocks_web3_dal;
 pub mod connection;
 pub mod consensus_dal;
 pub mod contract_verification_dal;
+mod copy;
 pub mod eth_sender_dal;
 pub mod events_dal;
 pub mod events_web3_dal;
@@ -44,10 +47,14 @@ pub mod fri_witness_generator_dal;
 pub mod healthcheck;
 mod instrument;
 mod metrics;
+pub mod migrations_dal;
 mod models;
+pub mod partitions_dal;
 pub mod proof_generation_dal;
 pub mod protocol_versions_dal;
 pub mod protocol_versions_web3_dal;
+pub mod prover_artifacts_dal;
+pub mod pruning_dal;
 pub mod snapshot_recovery_dal;
 pub mod snapshots_creator_dal;
 pub mod snapshots_dal;
@@ -194,4 +201,24 @@ impl<'a> StorageProcessor<'a> {
     pub fn snapshot_recovery_dal(&mut self) -> SnapshotRecoveryDal<'_, 'a> {
         SnapshotRecoveryDal { storage: self }
     }
+
+    pub fn pruning_dal(&mut self) -> PruningDal<'_, 'a> {
+        PruningDal { storage: self }
+    }
+
+    pub fn partitions_dal(&mut self) -> PartitionsDal<'_, 'a> {
+        PartitionsDal { storage: self }
+    }
+
+    pub fn migrations_dal(&mut self) -> MigrationsDal<'_, 'a> {
+        MigrationsDal { storage: self }
+    }
+
+    pub fn block_exporter_dal(&mut self) -> BlockExporterDal<'_, 'a> {
+        BlockExporterDal { storage: self }
+    }
+
+    pub fn prover_artifacts_dal(&mut self) -> ProverArtifactsDal<'_, 'a> {
+        ProverArtifactsDal { storage: self }
+    }
 }
@@ -3,7 +3,7 @@
 use std::{thread, time::Duration};
 
 use vise::{
-    Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Histogram, LabeledFamily,
+    Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, LabeledFamily,
     LatencyObserver, Metrics, Unit,
 };
 
@@ -26,7 +26,7 @@ pub(crate) struct RequestMetrics {
 pub(crate) static REQUEST_METRICS: vise::Global<RequestMetrics> = vise::Global::new();
 
 /// Reporter of latency for DAL methods consisting of multiple DB queries. If there's a single query,
-/// use `.instrument().report_latency()` on it instead.
+/// use `.instrument()` on it instead.
 ///
 /// Should be created at the start of the relevant method and dropped when the latency needs to be reported.
 #[derive(Debug)]
@@ -96,3 +96,39 @@ pub(crate) struct ConnectionMetrics {
 
 #[vise::register]
 pub(crate) static CONNECTION_METRICS: vise::Global<ConnectionMetrics> = vise::Global::new();
+
+/// Target of a read-only query routed by a connection pool with read replicas.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "target", rename_all = "snake_case")]
+pub(crate) enum ReadTarget {
+    Replica,
+    Primary,
+}
+
+/// Metrics related to read replicas.
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "sql_replica")]
+pub(crate) struct ReplicaMetrics {
+    /// Lag of a read replica as of the latest check.
+    #[metrics(unit = Unit::Seconds, labels = ["replica"])]
+    lag: LabeledFamily<String, Gauge<Duration>>,
+    /// Boolean gauge indicating whether the lag of a read replica is unknown (e.g., because the latest check has failed).
+    #[metrics(labels = ["replica"])]
+    check_failed: LabeledFamily<String, Gauge<u64>>,
+    /// Number of read-only connections acquired by the target database. Reads are routed to the primary database
+    /// if all replicas are stale or unavailable.
+    pub reads: Family<ReadTarget, Counter>,
+}
+
+impl ReplicaMetrics {
+    pub fn observe_lag(&self, replica: usize, lag: Option<Duration>) {
+        let label = replica.to_string();
+        self.check_failed[&label].set(lag.is_none().into());
+        if let Some(lag) = lag {
+            self.lag[&label].set(lag);
+        }
+    }
+}
+
+#[vise::register]
+pub(crate) static REPLICA_METRICS: vise::Global<ReplicaMetrics> = vise::Global::new();
@@ -0,0 +1,531 @@
+//! Inspection, application and reversal of Postgres migrations embedded into the binary.
+//!
+//! Migrations are never applied implicitly; node binaries expose them via the `--migrations` command-line option.
+//! Before applying migrations, it's possible to get a [`MigrationsPlan`] listing pending migrations together with
+//! an estimate of locks they take on existing tables. The estimate is based on simple parsing of migration statements
+//! and on planner statistics for the affected tables, so it's not exact, but it's sufficient to notice migrations
+//! that would block writes to a large table for a long time.
+
+use std::{collections::HashMap, fmt};
+
+use anyhow::Context as _;
+use sqlx::migrate::{Migration, Migrator};
+
+use crate::{instrument::InstrumentExt, StorageProcessor};
+
+static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
+
+/// Tables with at least this number of rows are considered large.
+const LARGE_TABLE_ROWS: u64 = 1_000_000;
+
+/// Kind of lock taken by a migration statement on a table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LockKind {
+    /// Doesn't block reads or writes (e.g., `CREATE INDEX CONCURRENTLY`).
+    ShareUpdateExclusive,
+    /// Doesn't block reads or writes, but locks all modified rows (e.g., `UPDATE` or `DELETE`).
+    RowExclusive,
+    /// Blocks writes, but not reads (e.g., `CREATE INDEX`).
+    Share,
+    /// Blocks both reads and writes (e.g., most `ALTER TABLE` forms).
+    AccessExclusive,
+}
+
+impl LockKind {
+    /// Checks whether this lock blocks writes to the table.
+    pub fn blocks_writes(self) -> bool {
+        matches!(self, Self::Share | Self::AccessExclusive)
+    }
+}
+
+impl fmt::Display for LockKind {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(match self {
+            Self::ShareUpdateExclusive => "SHARE UPDATE EXCLUSIVE",
+            Self::RowExclusive => "ROW EXCLUSIVE",
+            Self::Share => "SHARE",
+            Self::AccessExclusive => "ACCESS EXCLUSIVE",
+        })
+    }
+}
+
+/// Lock taken by a single migration statement.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct StatementLock {
+    table: String,
+    kind: LockKind,
+    /// Whether the statement scans or rewrites the entire table, i.e., whether the lock is held for time
+    /// proportional to the table size.
+    scans_table: bool,
+}
+
+impl StatementLock {
+    /// Parses a single SQL statement. Returns `None` for statements not taking locks on existing tables
+    /// (e.g., `CREATE TABLE`) or not recognized.
+    fn parse(statement: &str) -> Option<Self> {
+        let normalized = statement.split_whitespace().collect::<Vec<_>>().join(" ");
+        let upper = normalized.to_ascii_uppercase();
+        let words: Vec<_> = upper.split(' ').collect();
+        let table_at = |idx: usize| -> Option<String> {
+            let skip = ["IF", "EXISTS", "NOT", "ONLY", "TABLE"];
+            let name = normalized
+                .split(' ')
+                .skip(idx)
+                .find(|word| !skip.contains(&word.to_ascii_uppercase().as_str()))?;
+            let name = name.split('(').next()?;
+            // Strip the schema name if present.
+            let name = name
+                .rsplit('.')
+                .next()?
+                .trim_matches('"')
+                .to_ascii_lowercase();
+            (!name.is_empty()).then_some(name)
+        };
+
+        let (table, kind, scans_table) = match words.as_slice() {
+            ["ALTER", "TABLE", ..] => {
+                // `ALTER [COLUMN] column [SET DATA] TYPE ...` rewrites the table.
+                let changes_type = words.windows(3).any(|window| {
+                    window[0] == "ALTER" && window[1] != "TABLE" && window[2] == "TYPE"
+                }) || words.windows(4).any(|window| {
+                    window[0] == "ALTER" && window[1] == "COLUMN" && window[3] == "TYPE"
+                }) || upper.contains("SET DATA TYPE");
+                let scans_table = changes_type
+                    || upper.contains("SET NOT NULL")
+                    || upper.contains("PRIMARY KEY")
+                    || (upper.contains("ADD CONSTRAINT") && !upper.contains("NOT VALID"));
+                (table_at(2)?, LockKind::AccessExclusive, scans_table)
+            }
+            ["CREATE", ..] if upper.contains(" INDEX ") => {
+                let on_idx = words.iter().position(|&word| word == "ON")?;
+                let kind = if upper.contains(" CONCURRENTLY ") {
+                    LockKind::ShareUpdateExclusive
+                } else {
+                    LockKind::Share
+                };
+                (table_at(on_idx + 1)?, kind, true)
+            }
+            ["UPDATE", ..] => (table_at(1)?, LockKind::RowExclusive, true),
+            ["DELETE", "FROM", ..] => (table_at(2)?, LockKind::RowExclusive, true),
+            ["DROP", "TABLE", ..] | ["TRUNCATE", ..] => {
+                (table_at(1)?, LockKind::AccessExclusive, false)
+            }
+            _ => return None,
+        };
+        Some(Self {
+            table,
+            kind,
+            scans_table,
+        })
+    }
+
+    fn parse_migration(sql: &str) -> Vec<Self> {
+        let sql_without_comments: String = sql
+            .lines()
+            .map(|line| line.split("--").next().unwrap_or(""))
+            .collect::<Vec<_>>()
+            .join("\n");
+        sql_without_comments
+            .split(';')
+            .filter_map(Self::parse)
+            .collect()
+    }
+}
+
+/// Estimated impact of a pending migration on an existing table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableLockImpact {
+    pub table: String,
+    /// Strongest lock taken by the migration on the table.
+    pub lock: LockKind,
+    /// Whether the lock is held for time proportional to the table size.
+    pub scans_table: bool,
+    /// Estimated number of rows in the table according to planner statistics.
+    pub estimated_rows: u64,
+    /// Total size of the table including indexes and TOAST data.
+    pub size_bytes: u64,
+}
+
+impl TableLockImpact {
+    /// Checks whether the migration may block writes to a large table for a long time.
+    pub fn is_risky(&self) -> bool {
+        self.lock.blocks_writes() && self.scans_table && self.estimated_rows >= LARGE_TABLE_ROWS
+    }
+}
+
+/// Information about an embedded migration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MigrationInfo {
+    pub version: i64,
+    pub description: String,
+    /// Whether the migration has a down migration and thus can be reverted.
+    pub reversible: bool,
+}
+
+/// Pending migration together with the estimate of its impact on existing tables.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PendingMigration {
+    pub info: MigrationInfo,
+    /// Impact on existing tables. Tables created by preceding pending migrations are not included.
+    pub lock_impact: Vec<TableLockImpact>,
+}
+
+/// Plan of applying embedded migrations to Postgres.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MigrationsPlan {
+    /// Version of the latest successfully applied migration.
+    pub latest_applied_version: Option<i64>,
+    /// Migrations that are not applied yet, in the order of application.
+    pub pending: Vec<PendingMigration>,
+    /// Versions of applied migrations that are not embedded into the binary. These migrations were applied
+    /// by a newer binary and cannot be reverted by this one.
+    pub unknown_applied: Vec<i64>,
+    /// Versions of applied migrations whose SQL differs from the embedded one, or which have failed.
+    pub mismatched_applied: Vec<i64>,
+}
+
+impl MigrationsPlan {
+    /// Checks whether migrations can be applied according to this plan.
+    pub fn is_consistent(&self) -> bool {
+        self.mismatched_applied.is_empty()
+    }
+
+    pub fn has_risky_migrations(&self) -> bool {
+        self.pending
+            .iter()
+            .any(|migration| migration.lock_impact.iter().any(TableLockImpact::is_risky))
+    }
+}
+
+impl fmt::Display for MigrationsPlan {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.latest_applied_version {
+            Some(version) => writeln!(formatter, "Latest applied migration: {version}")?,
+            None => writeln!(formatter, "No migrations are applied")?,
+        }
+        if !self.unknown_applied.is_empty() {
+            writeln!(
+                formatter,
+                "WARNING: applied migrations unknown to this binary: {:?}",
+                self.unknown_applied
+            )?;
+        }
+        if !self.mismatched_applied.is_empty() {
+            writeln!(
+                formatter,
+                "ERROR: applied migrations failed or differ from the embedded ones: {:?}",
+                self.mismatched_applied
+            )?;
+        }
+        if self.pending.is_empty() {
+            return writeln!(formatter, "No pending migrations");
+        }
+
+        writeln!(formatter, "Pending migrations ({}):", self.pending.len())?;
+        for migration in &self.pending {
+            let MigrationInfo {
+                version,
+                description,
+                reversible,
+            } = &migration.info;
+            let reversible = if *reversible { "" } else { " (irreversible)" };
+            writeln!(formatter, "  {version} {description}{reversible}")?;
+            for impact in &migration.lock_impact {
+                let duration = if impact.scans_table {
+                    "held for a full table scan"
+                } else {
+                    "brief"
+                };
+                let risky = if impact.is_risky() { "WARNING: " } else { "" };
+                writeln!(
+                    formatter,
+                    "    {risky}{} lock on `{}` (~{} rows, {} bytes), {duration}",
+                    impact.lock, impact.table, impact.estimated_rows, impact.size_bytes
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+struct AppliedMigration {
+    checksum: Vec<u8>,
+    success: bool,
+}
+
+#[derive(Debug)]
+pub struct MigrationsDal<'a, 'c> {
+    pub(crate) storage: &'a mut StorageProcessor<'c>,
+}
+
+impl MigrationsDal<'_, '_> {
+    fn up_migrations() -> impl Iterator<Item = &'static Migration> {
+        MIGRATOR
+            .iter()
+            .filter(|migration| !migration.migration_type.is_down_migration())
+    }
+
+    fn is_reversible(version: i64) -> bool {
+        MIGRATOR.iter().any(|migration| {
+            migration.version == version && migration.migration_type.is_down_migration()
+        })
+    }
+
+    /// Returns all migrations embedded into the binary in the order of application.
+    pub fn embedded_migrations() -> Vec<MigrationInfo> {
+        Self::up_migrations()
+            .map(|migration| MigrationInfo {
+                version: migration.version,
+                description: migration.description.to_string(),
+                reversible: Self::is_reversible(migration.version),
+            })
+            .collect()
+    }
+
+    async fn get_applied_migrations(&mut self) -> sqlx::Result<HashMap<i64, AppliedMigration>> {
+        let (table_exists,): (bool,) =
+            sqlx::query_as("SELECT to_regclass('_sqlx_migrations') IS NOT NULL")
+                .instrument("get_applied_migrations#table_exists")
+                .fetch_one(self.storage)
+                .await?;
+        if !table_exists {
+            return Ok(HashMap::new());
+        }
+
+        let rows: Vec<(i64, Vec<u8>, bool)> =
+            sqlx::query_as("SELECT version, checksum, success FROM _sqlx_migrations")
+                .instrument("get_applied_migrations")
+                .fetch_all(self.storage)
+                .await?;
+        Ok(rows
+            .into_iter()
+            .map(|(version, checksum, success)| (version, AppliedMigration { checksum, success }))
+            .collect())
+    }
+
+    /// Returns `(estimated_rows, size_bytes)` for the specified tables. For partitioned tables, stats
+    /// are summed over all partitions. Tables that don't exist are omitted.
+    async fn get_table_stats(
+        &mut self,
+        tables: &[String],
+    ) -> sqlx::Result<HashMap<String, (u64, u64)>> {
+        let rows: Vec<(String, i64, i64)> = sqlx::query_as(
+            "SELECT parent.relname::TEXT, \
+                 SUM(GREATEST(rel.reltuples, 0))::BIGINT, \
+                 SUM(pg_total_relation_size(rel.oid))::BIGINT \
+             FROM pg_class parent \
+             LEFT JOIN pg_inherits i ON i.inhparent = parent.oid \
+             JOIN pg_class rel ON rel.oid = COALESCE(i.inhrelid, parent.oid) \
+             WHERE parent.relname = ANY($1) AND parent.relkind IN ('r', 'p') \
+             GROUP BY parent.relname",
+        )
+        .bind(tables)
+        .instrument("get_table_stats")
+        .with_arg("tables.len", &tables.len())
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(name, rows, size)| (name, (rows as u64, size as u64)))
+            .collect())
+    }
+
+    /// Returns the plan of applying embedded migrations without modifying the database.
+    pub async fn plan(&mut self) -> sqlx::Result<MigrationsPlan> {
+        let applied = self.get_applied_migrations().await?;
+        let latest_applied_version = applied
+            .iter()
+            .filter(|(_, migration)| migration.success)
+            .map(|(&version, _)| version)
+            .max();
+
+        let mut unknown_applied: Vec<_> = applied
+            .keys()
+            .copied()
+            .filter(|&version| !Self::up_migrations().any(|m| m.version == version))
+            .collect();
+        unknown_applied.sort_unstable();
+        let mut mismatched_applied: Vec<_> = Self::up_migrations()
+            .filter_map(|migration| {
+                let applied = applied.get(&migration.version)?;
+                let is_mismatched =
+                    !applied.success || applied.checksum[..] != migration.checksum[..];
+                is_mismatched.then_some(migration.version)
+            })
+            .collect();
+        mismatched_applied.sort_unstable();
+
+        let pending_migrations: Vec<_> = Self::up_migrations()
+            .filter(|migration| !applied.contains_key(&migration.version))
+            .collect();
+        let locks: Vec<_> = pending_migrations
+            .iter()
+            .map(|migration| StatementLock::parse_migration(&migration.sql))
+            .collect();
+        let mut tables: Vec<_> = locks
+            .iter()
+            .flatten()
+            .map(|lock| lock.table.clone())
+            .collect();
+        tables.sort_unstable();
+        tables.dedup();
+        let stats = self.get_table_stats(&tables).await?;
+
+        let pending = pending_migrations
+            .into_iter()
+            .zip(locks)
+            .map(|(migration, locks)| {
+                let mut lock_impact: Vec<TableLockImpact> = vec![];
+                for lock in locks {
+                    let Some(&(estimated_rows, size_bytes)) = stats.get(&lock.table) else {
+                        continue; // The table is created by a pending migration
+                    };
+                    if let Some(impact) = lock_impact.iter_mut().find(|i| i.table == lock.table) {
+                        impact.lock = impact.lock.max(lock.kind);
+                        impact.scans_table |= lock.scans_table;
+                    } else {
+                        lock_impact.push(TableLockImpact {
+                            table: lock.table,
+                            lock: lock.kind,
+                            scans_table: lock.scans_table,
+                            estimated_rows,
+                            size_bytes,
+                        });
+                    }
+                }
+                PendingMigration {
+                    info: MigrationInfo {
+                        version: migration.version,
+                        description: migration.description.to_string(),
+                        reversible: Self::is_reversible(migration.version),
+                    },
+                    lock_impact,
+                }
+            })
+            .collect();
+
+        Ok(MigrationsPlan {
+            latest_applied_version,
+            pending,
+            unknown_applied,
+            mismatched_applied,
+        })
+    }
+
+    /// Applies all pending migrations. Concurrent application from multiple processes is prevented
+    /// by an advisory lock taken by `sqlx`.
+    pub async fn apply(&mut self) -> anyhow::Result<()> {
+        MIGRATOR
+            .run(self.storage.conn())
+            .await
+            .context("failed applying migrations")
+    }
+
+    /// Reverts all applied migrations with versions greater than `target_version` (`0` reverts all migrations).
+    /// Returns the reverted migrations in the order of reversal.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error without modifying the database if `target_version` doesn't correspond to an embedded
+    /// migration, or if any of the migrations to revert is irreversible or unknown to this binary.
+    pub async fn revert_to(&mut self, target_version: i64) -> anyhow::Result<Vec<MigrationInfo>> {
+        anyhow::ensure!(
+            target_version == 0 || Self::up_migrations().any(|m| m.version == target_version),
+            "migration {target_version} is not embedded into the binary"
+        );
+        let applied = self.get_applied_migrations().await?;
+        let mut reverted_versions: Vec<_> = applied
+            .keys()
+            .copied()
+            .filter(|&version| version > target_version)
+            .collect();
+        reverted_versions.sort_unstable_by(|a, b| b.cmp(a));
+
+        let embedded = Self::embedded_migrations();
+        let reverted = reverted_versions
+            .into_iter()
+            .map(|version| {
+                let info = embedded
+                    .iter()
+                    .find(|info| info.version == version)
+                    .ok_or_else(|| {
+                        anyhow::anyhow!("applied migration {version} is unknown to this binary")
+                    })?;
+                anyhow::ensure!(info.reversible, "migration {version} is irreversible");
+                Ok(info.clone())
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        MIGRATOR
+            .undo(self.storage.conn(), target_version)
+            .await
+            .with_context(|| format!("failed reverting migrations to {target_version}"))?;
+        Ok(reverted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ConnectionPool;
+
+    #[test]
+    fn parsing_statement_locks() {
+        let locks = StatementLock::parse_migration(
+            "-- Adds a column\nALTER TABLE IF EXISTS snapshots ADD COLUMN version INT;\n\
+             CREATE INDEX IF NOT EXISTS events_idx ON events (address);\n\
+             CREATE INDEX CONCURRENTLY idx ON ONLY \"storage_logs\"(hashed_key);\n\
+             UPDATE transactions SET data = '{}';\n\
+             ALTER TABLE miniblocks ALTER COLUMN hash TYPE TEXT;\n\
+             ALTER TABLE pruning_log ADD COLUMN type TEXT;\n\
+             CREATE TABLE new_table (id INT);",
+        );
+        let locks: Vec<_> = locks
+            .iter()
+            .map(|lock| (lock.table.as_str(), lock.kind, lock.scans_table))
+            .collect();
+        assert_eq!(
+            locks,
+            [
+                ("snapshots", LockKind::AccessExclusive, false),
+                ("events", LockKind::Share, true),
+                ("storage_logs", LockKind::ShareUpdateExclusive, true),
+                ("transactions", LockKind::RowExclusive, true),
+                ("miniblocks", LockKind::AccessExclusive, true),
+                ("pruning_log", LockKind::AccessExclusive, false),
+            ]
+        );
+    }
+
+    #[test]
+    fn embedded_migrations_are_reversible() {
+        let migrations = MigrationsDal::embedded_migrations();
+        assert!(!migrations.is_empty());
+        assert!(migrations
+            .windows(2)
+            .all(|window| window[0].version < window[1].version));
+        assert!(migrations.iter().all(|migration| migration.reversible));
+    }
+
+    #[tokio::test]
+    async fn planning_migrations_for_migrated_database() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        let plan = conn.migrations_dal().plan().await.unwrap();
+
+        assert!(plan.pending.is_empty(), "{plan}");
+        assert!(plan.is_consistent(), "{plan}");
+        let latest_version = MigrationsDal::embedded_migrations().last().unwrap().version;
+        assert_eq!(plan.latest_applied_version, Some(latest_version));
+
+        let stats = conn
+            .migrations_dal()
+            .get_table_stats(&["miniblocks".to_owned(), "events".to_owned()])
+            .await
+            .unwrap();
+        assert!(stats.contains_key("miniblocks"));
+        assert!(stats.contains_key("events"));
+    }
+}
@@ -3,7 +3,7 @@ use std::str::FromStr;
 use sqlx::types::chrono::NaiveDateTime;
 use zksync_types::{
     aggregated_operations::AggregatedActionType,
-    eth_sender::{EthTx, TxHistory, TxHistoryToSend},
+    eth_sender::{EthTx, EthTxBlobSidecar, OperatorRotation, TxHistory, TxHistoryToSend},
     Address, L1BatchNumber, Nonce, H256,
 };
 
@@ -22,6 +22,16 @@ pub struct StorageEthTx {
     pub updated_at: NaiveDateTime,
     // TODO (SMA-1614): remove the field
     pub sent_at_block: Option<i32>,
+    pub blob_sidecar: Option<Vec<u8>>,
+    pub from_addr: Option<Vec<u8>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct StorageOperatorRotation {
+    pub id: i32,
+    pub requested_at: NaiveDateTime,
+    pub completed_at: Option<NaiveDateTime>,
+    pub new_operator_address: Option<Vec<u8>>,
 }
 
 #[derive(Debug, Default)]
@@ -54,6 +64,7 @@ pub struct StorageTxHistory {
     pub updated_at: NaiveDateTime,
     pub signed_raw_tx: Option<Vec<u8>>,
     pub sent_at_block: Option<i32>,
+    pub blob_base_fee_per_gas: Option<i64>,
 }
 
 impl From<StorageEthTx> for EthTx {
@@ -67,6 +78,24 @@ impl From<StorageEthTx> for EthTx {
             tx_type: AggregatedActionType::from_str(&tx.tx_type).expect("Wrong agg type"),
             created_at_timestamp: tx.created_at.timestamp() as u64,
             predicted_gas_cost: tx.predicted_gas_cost as u64,
+            blob_sidecar: tx.blob_sidecar.map(|sidecar| {
+                bincode::deserialize::<EthTxBlobSidecar>(&sidecar)
+                    .expect("Incorrect blob sidecar in db")
+            }),
+            from_addr: tx.from_addr.map(|addr| Address::from_slice(&addr)),
+        }
+    }
+}
+
+impl From<StorageOperatorRotation> for OperatorRotation {
+    fn from(rotation: StorageOperatorRotation) -> OperatorRotation {
+        OperatorRotation {
+            id: rotation.id as u32,
+            requested_at_timestamp: rotation.requested_at.timestamp() as u64,
+            completed_at_timestamp: rotation.completed_at.map(|at| at.timestamp() as u64),
+            new_operator_address: rotation
+                .new_operator_address
+                .map(|addr| Address::from_slice(&addr)),
         }
     }
 }
@@ -78,6 +107,7 @@ impl From<StorageTxHistory> for TxHistory {
             eth_tx_id: history.eth_tx_id as u32,
             base_fee_per_gas: history.base_fee_per_gas as u64,
             priority_fee_per_gas: history.priority_fee_per_gas as u64,
+            blob_base_fee_per_gas: history.blob_base_fee_per_gas.map(|fee| fee as u64),
             tx_hash: H256::from_str(&history.tx_hash).expect("Incorrect hash"),
             signed_raw_tx: history
                 .signed_raw_tx
@@ -10,6 +10,7 @@ use sqlx::{
 use zksync_types::{
     api,
     api::{TransactionDetails, TransactionReceipt, TransactionStatus},
+    circuit::CircuitStatistic,
     fee::Fee,
     l1::{OpProcessingType, PriorityQueueType},
     l2::TransactionType,
@@ -18,8 +19,9 @@ use zksync_types::{
     vm_trace::Call,
     web3::types::U64,
     Address, Bytes, Execute, ExecuteTransactionCommon, L1TxCommonData, L2ChainId, L2TxCommonData,
-    Nonce, PackedEthSignature, PriorityOpId, Transaction, EIP_1559_TX_TYPE, EIP_2930_TX_TYPE,
-    EIP_712_TX_TYPE, H160, H256, PRIORITY_OPERATION_L2_TX_TYPE, PROTOCOL_UPGRADE_TX_TYPE, U256,
+    MiniblockNumber, Nonce, PackedEthSignature, PriorityOpId, Transaction, EIP_1559_TX_TYPE,
+    EIP_2930_TX_TYPE, EIP_712_TX_TYPE, H160, H256, PRIORITY_OPERATION_L2_TX_TYPE,
+    PROTOCOL_UPGRADE_TX_TYPE, U256,
 };
 use zksync_utils::{bigdecimal_to_u256, h256_to_account_address};
 
@@ -556,6 +558,42 @@ impl From<StorageTransactionDetails> for TransactionDetails {
     }
 }
 
+/// Execution metrics persisted in the `execution_info` column of the `transactions` table. Fields missing
+/// in rows produced by older server versions are defaulted.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct StorageTransactionExecutionInfo {
+    pub gas_used: u64,
+    pub computational_gas_used: u32,
+    pub pubdata_published: u32,
+    pub published_bytecode_bytes: u64,
+    pub l2_to_l1_logs: u64,
+    pub vm_events: u64,
+    pub storage_reads: Option<u64>,
+    pub storage_writes: Option<u64>,
+    pub contracts_used: u64,
+    pub cycles_used: u32,
+    pub circuit_statistic: CircuitStatistic,
+}
+
+impl From<StorageTransactionExecutionInfo> for api::TransactionExecutionInfo {
+    fn from(info: StorageTransactionExecutionInfo) -> Self {
+        Self {
+            gas_used: info.gas_used,
+            computational_gas_used: info.computational_gas_used,
+            pubdata_published: info.pubdata_published,
+            published_bytecode_bytes: info.published_bytecode_bytes,
+            l2_to_l1_logs: info.l2_to_l1_logs,
+            vm_events: info.vm_events,
+            storage_reads: info.storage_reads,
+            storage_writes: info.storage_writes,
+            contracts_used: info.contracts_used,
+            cycles_used: info.cycles_used,
+            circuit_statistic: info.circuit_statistic,
+        }
+    }
+}
+
 pub fn web3_transaction_select_sql() -> &'static str {
     r#"
          transactions.hash as tx_hash,
@@ -600,3 +638,28 @@ impl From<CallTrace> for Call {
         bincode::deserialize(&call_trace.call_trace).unwrap()
     }
 }
+
+/// Call trace together with the location of the traced transaction.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub(crate) struct LocalizedCallTrace {
+    pub tx_hash: Vec<u8>,
+    pub call_trace: Vec<u8>,
+    pub index_in_block: i32,
+    pub miniblock_number: i64,
+    pub miniblock_hash: Vec<u8>,
+}
+
+impl LocalizedCallTrace {
+    pub fn into_parts(self) -> (api::trace::TransactionTraceLocation, Call) {
+        let location = api::trace::TransactionTraceLocation {
+            tx_hash: H256::from_slice(&self.tx_hash),
+            tx_index_in_block: self.index_in_block as u32,
+            block_number: MiniblockNumber(self.miniblock_number as u32),
+            block_hash: H256::from_slice(&self.miniblock_hash),
+        };
+        let call = CallTrace {
+            call_trace: self.call_trace,
+        };
+        (location, call.into())
+    }
+}
@@ -0,0 +1,266 @@
+//! Management of partitions for the largest append-only tables (`events` and `storage_logs`).
+//!
+//! Both tables are partitioned by ranges of miniblock numbers. Partitions are named `{table}_p{index}` and cover
+//! [`PartitionsDal::PARTITION_SIZE`] miniblocks each, except for the `{table}_legacy` partition that contains data
+//! created before the tables were partitioned. Partitions containing pruned data can be dropped entirely, which is
+//! much cheaper than deleting rows from them.
+//!
+//! Pruning operates on L1 batches, but the tables are partitioned by miniblocks rather than L1 batches: neither table
+//! stores the L1 batch number (it's assigned to miniblocks only when an L1 batch is sealed), and a partition key must
+//! be a column of the table included into its primary key. Since miniblock numbers increase monotonically with
+//! L1 batch numbers, a pruned L1 batch range always maps to a miniblock range, so partitions are dropped as well.
+//!
+//! Partitions must be created before data is inserted into them. To keep DDL off the hot path of block sealing,
+//! they are created ahead of time by a background task (see `PartitionManager` in the state keeper) and when
+//! recovering from a snapshot; [`PartitionsDal::ensure_partitions()`] keeps a spare partition after the current one.
+
+use std::ops;
+
+use zksync_types::MiniblockNumber;
+
+use crate::{instrument::InstrumentExt, StorageProcessor};
+
+/// Table partitioned by ranges of miniblock numbers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PartitionedTable {
+    Events,
+    StorageLogs,
+}
+
+impl PartitionedTable {
+    pub const ALL: [Self; 2] = [Self::Events, Self::StorageLogs];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Events => "events",
+            Self::StorageLogs => "storage_logs",
+        }
+    }
+}
+
+/// Partition of a [`PartitionedTable`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TablePartition {
+    pub name: String,
+    /// Range of miniblock numbers covered by the partition.
+    pub miniblocks: ops::Range<u64>,
+}
+
+impl TablePartition {
+    /// Parses the range of miniblocks from the partition bound expression as returned by `pg_get_expr()`,
+    /// e.g. `FOR VALUES FROM ('0') TO ('1000000')`.
+    fn parse_bound(expr: &str) -> Option<ops::Range<u64>> {
+        let (start, end) = expr
+            .strip_prefix("FOR VALUES FROM (")?
+            .strip_suffix(')')?
+            .split_once(") TO (")?;
+        let start = match start {
+            "MINVALUE" => 0,
+            _ => start.trim_matches('\'').parse().ok()?,
+        };
+        let end = end.trim_matches('\'').parse().ok()?;
+        Some(start..end)
+    }
+}
+
+#[derive(Debug)]
+pub struct PartitionsDal<'a, 'c> {
+    pub(crate) storage: &'a mut StorageProcessor<'c>,
+}
+
+impl PartitionsDal<'_, '_> {
+    /// Number of miniblocks in a single partition. Must be kept in sync with the migration partitioning the tables.
+    pub const PARTITION_SIZE: u64 = 1_000_000;
+
+    /// Returns partitions of the specified table ordered by the covered miniblock range.
+    pub async fn get_partitions(
+        &mut self,
+        table: PartitionedTable,
+    ) -> sqlx::Result<Vec<TablePartition>> {
+        let rows: Vec<(String, String)> = sqlx::query_as(
+            "SELECT c.relname::TEXT, pg_get_expr(c.relpartbound, c.oid) \
+             FROM pg_inherits i JOIN pg_class c ON c.oid = i.inhrelid \
+             WHERE i.inhparent = $1::TEXT::REGCLASS",
+        )
+        .bind(table.name())
+        .instrument("get_partitions")
+        .with_arg("table", &table)
+        .fetch_all(self.storage)
+        .await?;
+
+        let mut partitions = rows
+            .into_iter()
+            .map(|(name, bound)| {
+                let miniblocks = TablePartition::parse_bound(&bound).ok_or_else(|| {
+                    let message = format!("unexpected bound for partition `{name}`: {bound}");
+                    sqlx::Error::Decode(message.into())
+                })?;
+                Ok(TablePartition { name, miniblocks })
+            })
+            .collect::<sqlx::Result<Vec<_>>>()?;
+        partitions.sort_unstable_by_key(|partition| {
+            (partition.miniblocks.start, partition.miniblocks.end)
+        });
+        Ok(partitions)
+    }
+
+    /// Ensures that all partitioned tables have partitions for the specified miniblock and at least
+    /// [`Self::PARTITION_SIZE`] miniblocks after it, creating missing partitions if necessary.
+    pub async fn ensure_partitions(
+        &mut self,
+        miniblock_number: MiniblockNumber,
+    ) -> sqlx::Result<()> {
+        let current_index = u64::from(miniblock_number.0) / Self::PARTITION_SIZE;
+        for table in PartitionedTable::ALL {
+            let partitions = self.get_partitions(table).await?;
+            for index in current_index..=current_index + 1 {
+                let range = index * Self::PARTITION_SIZE..(index + 1) * Self::PARTITION_SIZE;
+                let is_covered = partitions.iter().any(|partition| {
+                    partition.miniblocks.start < range.end && range.start < partition.miniblocks.end
+                });
+                if !is_covered {
+                    self.create_partition(table, index, range).await?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    async fn create_partition(
+        &mut self,
+        table: PartitionedTable,
+        index: u64,
+        range: ops::Range<u64>,
+    ) -> sqlx::Result<()> {
+        let table_name = table.name();
+        let partition_name = format!("{table_name}_p{index}");
+        let sql = format!(
+            "CREATE TABLE IF NOT EXISTS {partition_name} PARTITION OF {table_name} \
+             FOR VALUES FROM ({}) TO ({})",
+            range.start, range.end
+        );
+
+        // Creating a partition requires an exclusive lock on the table, and a pending lock request blocks all subsequent
+        // queries to the table. Thus, we'd rather fail and retry later than wait for long-running queries to finish.
+        let mut transaction = self.storage.start_transaction().await?;
+        sqlx::query("SET LOCAL lock_timeout = '5s'")
+            .instrument("create_partition#set_lock_timeout")
+            .execute(&mut transaction)
+            .await?;
+        sqlx::query(&sql)
+            .instrument("create_partition")
+            .with_arg("table", &table)
+            .with_arg("index", &index)
+            .execute(&mut transaction)
+            .await?;
+        transaction.commit().await?;
+        tracing::info!("Created partition `{partition_name}` for miniblocks {range:?}");
+        Ok(())
+    }
+
+    /// Drops partitions of the specified table that only contain data for miniblocks before
+    /// `first_retained_miniblock`. Returns names of the dropped partitions.
+    ///
+    /// Dropping a partition briefly locks the entire table, so this method shouldn't be called in a long-running
+    /// transaction.
+    pub async fn drop_partitions_before(
+        &mut self,
+        table: PartitionedTable,
+        first_retained_miniblock: MiniblockNumber,
+    ) -> sqlx::Result<Vec<String>> {
+        let first_retained_miniblock = u64::from(first_retained_miniblock.0);
+        let dropped_partitions: Vec<_> = self
+            .get_partitions(table)
+            .await?
+            .into_iter()
+            .filter(|partition| partition.miniblocks.end <= first_retained_miniblock)
+            .map(|partition| partition.name)
+            .collect();
+
+        for partition_name in &dropped_partitions {
+            let sql = format!("DROP TABLE {partition_name}");
+            sqlx::query(&sql)
+                .instrument("drop_partitions_before")
+                .with_arg("table", &table)
+                .with_arg("partition_name", partition_name)
+                .execute(self.storage)
+                .await?;
+            tracing::info!("Dropped partition `{partition_name}`");
+        }
+        Ok(dropped_partitions)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ConnectionPool;
+
+    #[test]
+    fn parsing_partition_bounds() {
+        assert_eq!(
+            TablePartition::parse_bound("FOR VALUES FROM (MINVALUE) TO ('2000000')"),
+            Some(0..2_000_000)
+        );
+        assert_eq!(
+            TablePartition::parse_bound("FOR VALUES FROM ('2000000') TO ('3000000')"),
+            Some(2_000_000..3_000_000)
+        );
+        assert_eq!(TablePartition::parse_bound("DEFAULT"), None);
+    }
+
+    #[tokio::test]
+    async fn managing_partitions() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        let size = PartitionsDal::PARTITION_SIZE;
+
+        // Partitions for the first miniblocks are created by the migration.
+        conn.partitions_dal()
+            .ensure_partitions(MiniblockNumber(0))
+            .await
+            .unwrap();
+        let partitions = conn
+            .partitions_dal()
+            .get_partitions(PartitionedTable::Events)
+            .await
+            .unwrap();
+        let partition_names: Vec<_> = partitions.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(partition_names, ["events_legacy", "events_p0", "events_p1"]);
+        assert_eq!(partitions[1].miniblocks, 0..size);
+
+        let far_miniblock = MiniblockNumber((5 * size + 1) as u32);
+        conn.partitions_dal()
+            .ensure_partitions(far_miniblock)
+            .await
+            .unwrap();
+        for table in PartitionedTable::ALL {
+            let partitions = conn.partitions_dal().get_partitions(table).await.unwrap();
+            let last_ranges: Vec<_> = partitions[3..]
+                .iter()
+                .map(|p| p.miniblocks.clone())
+                .collect();
+            assert_eq!(last_ranges, [5 * size..6 * size, 6 * size..7 * size]);
+        }
+
+        let dropped = conn
+            .partitions_dal()
+            .drop_partitions_before(PartitionedTable::Events, MiniblockNumber(size as u32 + 1))
+            .await
+            .unwrap();
+        assert_eq!(dropped, ["events_legacy", "events_p0"]);
+        let partitions = conn
+            .partitions_dal()
+            .get_partitions(PartitionedTable::Events)
+            .await
+            .unwrap();
+        assert_eq!(partitions[0].name, "events_p1");
+        // Storage logs partitions are unaffected.
+        let partitions = conn
+            .partitions_dal()
+            .get_partitions(PartitionedTable::StorageLogs)
+            .await
+            .unwrap();
+        assert_eq!(partitions.len(), 5);
+    }
+}
@@ -3,7 +3,9 @@ use std::time::Duration;
 use strum::{Display, EnumString};
 use zksync_types::L1BatchNumber;
 
-use crate::{time_utils::pg_interval_from_duration, SqlxError, StorageProcessor};
+use crate::{
+    instrument::InstrumentExt, time_utils::pg_interval_from_duration, SqlxError, StorageProcessor,
+};
 
 #[derive(Debug)]
 pub struct ProofGenerationDal<'a, 'c> {
@@ -59,7 +61,8 @@ impl ProofGenerationDal<'_, '_> {
             "#,
             &processing_timeout,
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_next_block_to_be_proven")
+        .fetch_optional(self.storage)
         .await
         .unwrap()
         .map(|row| L1BatchNumber(row.l1_batch_number as u32));
@@ -85,7 +88,10 @@ impl ProofGenerationDal<'_, '_> {
             proof_blob_url,
             block_number.0 as i64,
         )
-        .execute(self.storage.conn())
+        .instrument("save_proof_artifacts_metadata")
+        .with_arg("block_number", &block_number)
+        .with_arg("proof_blob_url", &proof_blob_url)
+        .execute(self.storage)
         .await?
         .rows_affected()
         .eq(&1)
@@ -109,7 +115,10 @@ impl ProofGenerationDal<'_, '_> {
             block_number.0 as i64,
             proof_gen_data_blob_url,
         )
-        .execute(self.storage.conn())
+        .instrument("insert_proof_generation_details")
+        .with_arg("block_number", &block_number)
+        .with_arg("proof_gen_data_blob_url", &proof_gen_data_blob_url)
+        .execute(self.storage)
         .await
         .unwrap();
     }
@@ -130,7 +139,9 @@ impl ProofGenerationDal<'_, '_> {
             ProofGenerationJobStatus::Skipped.to_string(),
             block_number.0 as i64,
         )
-        .execute(self.storage.conn())
+        .instrument("mark_proof_generation_job_as_skipped")
+        .with_arg("block_number", &block_number)
+        .execute(self.storage)
         .await?
         .rows_affected()
         .eq(&1)
@@ -153,7 +164,8 @@ impl ProofGenerationDal<'_, '_> {
                 1
             "#,
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_oldest_unpicked_batch")
+        .fetch_optional(self.storage)
         .await
         .unwrap()
         .map(|row| L1BatchNumber(row.l1_batch_number as u32));
@@ -176,7 +188,8 @@ impl ProofGenerationDal<'_, '_> {
                 1
             "#,
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_oldest_not_generated_batch")
+        .fetch_optional(self.storage)
         .await
         .unwrap()
         .map(|row| L1BatchNumber(row.l1_batch_number as u32));
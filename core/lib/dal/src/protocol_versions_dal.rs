@@ -7,6 +7,7 @@ use zksync_types::{
 };
 
 use crate::{
+    instrument::InstrumentExt,
     models::storage_protocol_version::{protocol_version_from_storage, StorageProtocolVersion},
     StorageProcessor,
 };
@@ -67,7 +68,12 @@ impl ProtocolVersionsDal<'_, '_> {
             verifier_address.as_bytes(),
             tx_hash.as_ref().map(H256::as_bytes),
         )
-        .execute(self.storage.conn())
+        .instrument("save_protocol_version")
+        .with_arg("id", &id)
+        .with_arg("timestamp", &timestamp)
+        .with_arg("verifier_address", &verifier_address)
+        .with_arg("tx_hash", &tx_hash)
+        .execute(self.storage)
         .await
         .unwrap();
     }
@@ -110,7 +116,10 @@ impl ProtocolVersionsDal<'_, '_> {
             tx_hash.as_ref().map(H256::as_bytes),
             id as i32,
         )
-        .execute(self.storage.conn())
+        .instrument("save_genesis_upgrade_tx_hash")
+        .with_arg("id", &id)
+        .with_arg("tx_hash", &tx_hash)
+        .execute(self.storage)
         .await
         .unwrap();
     }
@@ -160,7 +169,9 @@ impl ProtocolVersionsDal<'_, '_> {
             "#,
             current_timestamp as i64
         )
-        .fetch_one(self.storage.conn())
+        .instrument("base_system_contracts_by_timestamp")
+        .with_arg("current_timestamp", &current_timestamp)
+        .fetch_one(self.storage)
         .await
         .unwrap();
         let contracts = self
@@ -190,7 +201,9 @@ impl ProtocolVersionsDal<'_, '_> {
             "#,
             version_id as i32
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("load_base_system_contracts_by_version_id")
+        .with_arg("version_id", &version_id)
+        .fetch_optional(self.storage)
         .await
         .unwrap();
         if let Some(row) = row {
@@ -228,7 +241,8 @@ impl ProtocolVersionsDal<'_, '_> {
             "#,
             version_id as i32
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("load_previous_version")
+        .fetch_optional(self.storage)
         .await
         .unwrap()?;
         let tx = self
@@ -254,7 +268,8 @@ impl ProtocolVersionsDal<'_, '_> {
             "#,
             version_id as i32
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_protocol_version")
+        .fetch_optional(self.storage)
         .await
         .unwrap()?;
         let tx = self.get_protocol_upgrade_tx(version_id).await;
@@ -280,7 +295,9 @@ impl ProtocolVersionsDal<'_, '_> {
             "#,
             version_id as i32
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("l1_verifier_config_for_version")
+        .with_arg("version_id", &version_id)
+        .fetch_optional(self.storage)
         .await
         .unwrap()?;
         Some(L1VerifierConfig {
@@ -306,7 +323,8 @@ impl ProtocolVersionsDal<'_, '_> {
                 protocol_versions
             "#
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("last_version_id")
+        .fetch_optional(self.storage)
         .await
         .unwrap()?
         .max?;
@@ -326,7 +344,8 @@ impl ProtocolVersionsDal<'_, '_> {
                 1
             "#
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("last_used_version_id")
+        .fetch_optional(self.storage)
         .await
         .unwrap()?
         .protocol_version?;
@@ -343,7 +362,8 @@ impl ProtocolVersionsDal<'_, '_> {
                 protocol_versions
             "#
         )
-        .fetch_all(self.storage.conn())
+        .instrument("all_version_ids")
+        .fetch_all(self.storage)
         .await
         .unwrap();
         rows.into_iter()
@@ -366,7 +386,9 @@ impl ProtocolVersionsDal<'_, '_> {
             "#,
             protocol_version_id as i32
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_protocol_upgrade_tx")
+        .with_arg("protocol_version_id", &protocol_version_id)
+        .fetch_optional(self.storage)
         .await
         .unwrap()?;
         if let Some(hash) = row.upgrade_tx_hash {
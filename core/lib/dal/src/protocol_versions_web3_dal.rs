@@ -1,6 +1,9 @@
 use zksync_types::api::ProtocolVersion;
 
-use crate::{models::storage_protocol_version::StorageProtocolVersion, StorageProcessor};
+use crate::{
+    instrument::InstrumentExt, models::storage_protocol_version::StorageProtocolVersion,
+    StorageProcessor,
+};
 
 #[derive(Debug)]
 pub struct ProtocolVersionsWeb3Dal<'a, 'c> {
@@ -21,7 +24,8 @@ impl ProtocolVersionsWeb3Dal<'_, '_> {
             "#,
             version_id as i32
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_protocol_version_by_id")
+        .fetch_optional(self.storage)
         .await
         .unwrap();
 
@@ -42,7 +46,8 @@ impl ProtocolVersionsWeb3Dal<'_, '_> {
                 1
             "#,
         )
-        .fetch_one(self.storage.conn())
+        .instrument("get_latest_protocol_version")
+        .fetch_one(self.storage)
         .await
         .unwrap();
 
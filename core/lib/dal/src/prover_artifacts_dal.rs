@@ -0,0 +1,363 @@
+//! Discovery of prover artifacts stored in the object store and tracking of their garbage collection.
+//!
+//! Artifacts are referenced by object keys stored in the server tables (inputs for the prover subsystem
+//! and final proofs) and in the FRI prover tables (witness inputs, aggregation artifacts and proofs).
+//! Server tables are queried via [`ProverArtifactsDal::get_server_artifacts()`], and prover tables
+//! via [`ProverArtifactsDal::get_fri_artifacts()`]; the latter should be used with a connection
+//! to the prover database.
+
+use std::ops;
+
+use zksync_types::L1BatchNumber;
+
+use crate::{instrument::InstrumentExt, StorageProcessor};
+
+/// Kind of a prover artifact.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ProverArtifactKind {
+    /// Merkle paths for an L1 batch used by the basic witness generator.
+    MerklePaths,
+    /// Witness block state produced by the basic witness input producer.
+    WitnessBlockState,
+    /// Inputs for a single prover job.
+    CircuitInputs,
+    /// Proof produced by a single prover job.
+    CircuitProof,
+    /// Closed-form inputs for a leaf aggregation job.
+    LeafAggregationInputs,
+    /// Aggregations used by a node aggregation job.
+    NodeAggregationInputs,
+    /// Partial inputs for a scheduler job.
+    SchedulerInputs,
+    /// Final FRI proof for an L1 batch, or its compressed version sent to L1.
+    FinalProof,
+}
+
+impl ProverArtifactKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::MerklePaths => "merkle_paths",
+            Self::WitnessBlockState => "witness_block_state",
+            Self::CircuitInputs => "circuit_inputs",
+            Self::CircuitProof => "circuit_proof",
+            Self::LeafAggregationInputs => "leaf_aggregation_inputs",
+            Self::NodeAggregationInputs => "node_aggregation_inputs",
+            Self::SchedulerInputs => "scheduler_inputs",
+            Self::FinalProof => "final_proof",
+        }
+    }
+
+    fn parse(s: &str) -> Option<Self> {
+        Some(match s {
+            "merkle_paths" => Self::MerklePaths,
+            "witness_block_state" => Self::WitnessBlockState,
+            "circuit_inputs" => Self::CircuitInputs,
+            "circuit_proof" => Self::CircuitProof,
+            "leaf_aggregation_inputs" => Self::LeafAggregationInputs,
+            "node_aggregation_inputs" => Self::NodeAggregationInputs,
+            "scheduler_inputs" => Self::SchedulerInputs,
+            "final_proof" => Self::FinalProof,
+            _ => return None,
+        })
+    }
+}
+
+/// Prover artifact stored in the object store.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ProverArtifact {
+    pub l1_batch_number: L1BatchNumber,
+    pub kind: ProverArtifactKind,
+    /// Object key of the artifact.
+    pub key: String,
+}
+
+#[derive(Debug)]
+pub struct ProverArtifactsDal<'a, 'c> {
+    pub(crate) storage: &'a mut StorageProcessor<'c>,
+}
+
+impl ProverArtifactsDal<'_, '_> {
+    /// Returns the first L1 batch for which artifacts were not garbage-collected yet, or `None`
+    /// if there are no FRI witness inputs at all.
+    pub async fn get_next_l1_batch_to_clean(&mut self) -> sqlx::Result<Option<L1BatchNumber>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                COALESCE(
+                    (
+                        SELECT
+                            MAX(last_cleaned_l1_batch) + 1
+                        FROM
+                            prover_artifacts_gc_log
+                    ),
+                    (
+                        SELECT
+                            MIN(l1_batch_number)
+                        FROM
+                            witness_inputs_fri
+                    )
+                ) AS "l1_batch_number"
+            "#
+        )
+        .instrument("get_next_l1_batch_to_clean")
+        .fetch_one(self.storage)
+        .await?;
+        Ok(row
+            .l1_batch_number
+            .map(|number| L1BatchNumber(number as u32)))
+    }
+
+    /// Records that artifacts for all L1 batches up to and including `last_cleaned_l1_batch`
+    /// were garbage-collected.
+    pub async fn save_cleaned_l1_batch(
+        &mut self,
+        last_cleaned_l1_batch: L1BatchNumber,
+        removed_artifacts: u64,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                prover_artifacts_gc_log (last_cleaned_l1_batch, removed_artifacts, created_at)
+            VALUES
+                ($1, $2, NOW())
+            ON CONFLICT (last_cleaned_l1_batch) DO NOTHING
+            "#,
+            i64::from(last_cleaned_l1_batch.0),
+            removed_artifacts as i64
+        )
+        .instrument("save_cleaned_l1_batch")
+        .with_arg("last_cleaned_l1_batch", &last_cleaned_l1_batch)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    /// Returns artifacts referenced by the FRI prover tables for the specified L1 batches.
+    pub async fn get_fri_artifacts(
+        &mut self,
+        l1_batches: ops::RangeInclusive<L1BatchNumber>,
+    ) -> sqlx::Result<Vec<ProverArtifact>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                l1_batch_number AS "l1_batch_number!",
+                'merkle_paths' AS "kind!",
+                merkle_tree_paths_blob_url AS "key!"
+            FROM
+                witness_inputs_fri
+            WHERE
+                l1_batch_number BETWEEN $1 AND $2
+                AND merkle_tree_paths_blob_url IS NOT NULL
+            UNION ALL
+            SELECT
+                l1_batch_number,
+                'circuit_inputs',
+                circuit_blob_url
+            FROM
+                prover_jobs_fri
+            WHERE
+                l1_batch_number BETWEEN $1 AND $2
+            UNION ALL
+            SELECT
+                l1_batch_number,
+                'circuit_proof',
+                proof_blob_url
+            FROM
+                prover_jobs_fri
+            WHERE
+                l1_batch_number BETWEEN $1 AND $2
+                AND proof_blob_url IS NOT NULL
+            UNION ALL
+            SELECT
+                l1_batch_number,
+                'leaf_aggregation_inputs',
+                closed_form_inputs_blob_url
+            FROM
+                leaf_aggregation_witness_jobs_fri
+            WHERE
+                l1_batch_number BETWEEN $1 AND $2
+                AND closed_form_inputs_blob_url IS NOT NULL
+            UNION ALL
+            SELECT
+                l1_batch_number,
+                'node_aggregation_inputs',
+                aggregations_url
+            FROM
+                node_aggregation_witness_jobs_fri
+            WHERE
+                l1_batch_number BETWEEN $1 AND $2
+                AND aggregations_url IS NOT NULL
+            UNION ALL
+            SELECT
+                l1_batch_number,
+                'scheduler_inputs',
+                scheduler_partial_input_blob_url
+            FROM
+                scheduler_witness_jobs_fri
+            WHERE
+                l1_batch_number BETWEEN $1 AND $2
+            UNION ALL
+            SELECT
+                l1_batch_number,
+                'final_proof',
+                fri_proof_blob_url
+            FROM
+                proof_compression_jobs_fri
+            WHERE
+                l1_batch_number BETWEEN $1 AND $2
+                AND fri_proof_blob_url IS NOT NULL
+            UNION ALL
+            SELECT
+                l1_batch_number,
+                'final_proof',
+                l1_proof_blob_url
+            FROM
+                proof_compression_jobs_fri
+            WHERE
+                l1_batch_number BETWEEN $1 AND $2
+                AND l1_proof_blob_url IS NOT NULL
+            "#,
+            i64::from(l1_batches.start().0),
+            i64::from(l1_batches.end().0)
+        )
+        .instrument("get_fri_artifacts")
+        .with_arg("l1_batches", &l1_batches)
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .filter_map(|row| {
+                Some(ProverArtifact {
+                    l1_batch_number: L1BatchNumber(row.l1_batch_number as u32),
+                    kind: ProverArtifactKind::parse(&row.kind)?,
+                    key: row.key,
+                })
+            })
+            .collect())
+    }
+
+    /// Returns artifacts referenced by the server tables for the specified L1 batches, i.e., inputs
+    /// for the prover subsystem and final proofs received from it.
+    pub async fn get_server_artifacts(
+        &mut self,
+        l1_batches: ops::RangeInclusive<L1BatchNumber>,
+    ) -> sqlx::Result<Vec<ProverArtifact>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                l1_batch_number AS "l1_batch_number!",
+                'merkle_paths' AS "kind!",
+                proof_gen_data_blob_url AS "key!"
+            FROM
+                proof_generation_details
+            WHERE
+                l1_batch_number BETWEEN $1 AND $2
+            UNION ALL
+            SELECT
+                l1_batch_number,
+                'final_proof',
+                proof_blob_url
+            FROM
+                proof_generation_details
+            WHERE
+                l1_batch_number BETWEEN $1 AND $2
+                AND proof_blob_url IS NOT NULL
+            UNION ALL
+            SELECT
+                l1_batch_number,
+                'witness_block_state',
+                input_blob_url
+            FROM
+                basic_witness_input_producer_jobs
+            WHERE
+                l1_batch_number BETWEEN $1 AND $2
+                AND input_blob_url IS NOT NULL
+            "#,
+            i64::from(l1_batches.start().0),
+            i64::from(l1_batches.end().0)
+        )
+        .instrument("get_server_artifacts")
+        .with_arg("l1_batches", &l1_batches)
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .filter_map(|row| {
+                Some(ProverArtifact {
+                    l1_batch_number: L1BatchNumber(row.l1_batch_number as u32),
+                    kind: ProverArtifactKind::parse(&row.kind)?,
+                    key: row.key,
+                })
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::protocol_version::{FriProtocolVersionId, L1VerifierConfig};
+
+    use super::*;
+    use crate::ConnectionPool;
+
+    #[tokio::test]
+    async fn tracking_garbage_collection() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        assert_eq!(
+            conn.prover_artifacts_dal()
+                .get_next_l1_batch_to_clean()
+                .await
+                .unwrap(),
+            None
+        );
+
+        let protocol_version = FriProtocolVersionId::default();
+        conn.fri_protocol_versions_dal()
+            .save_prover_protocol_version(protocol_version, L1VerifierConfig::default())
+            .await;
+        for number in [2, 3] {
+            conn.fri_witness_generator_dal()
+                .save_witness_inputs(
+                    L1BatchNumber(number),
+                    &format!("merkel_tree_paths_{number}.bin"),
+                    protocol_version,
+                )
+                .await;
+        }
+
+        let mut dal = conn.prover_artifacts_dal();
+        assert_eq!(
+            dal.get_next_l1_batch_to_clean().await.unwrap(),
+            Some(L1BatchNumber(2))
+        );
+        let artifacts = dal
+            .get_fri_artifacts(L1BatchNumber(0)..=L1BatchNumber(2))
+            .await
+            .unwrap();
+        assert_eq!(
+            artifacts,
+            [ProverArtifact {
+                l1_batch_number: L1BatchNumber(2),
+                kind: ProverArtifactKind::MerklePaths,
+                key: "merkel_tree_paths_2.bin".to_owned(),
+            }]
+        );
+
+        dal.save_cleaned_l1_batch(L1BatchNumber(2), 1)
+            .await
+            .unwrap();
+        assert_eq!(
+            dal.get_next_l1_batch_to_clean().await.unwrap(),
+            Some(L1BatchNumber(3))
+        );
+        let artifacts = dal
+            .get_fri_artifacts(L1BatchNumber(3)..=L1BatchNumber(10))
+            .await
+            .unwrap();
+        assert_eq!(artifacts.len(), 1);
+        assert_eq!(artifacts[0].l1_batch_number, L1BatchNumber(3));
+    }
+}
@@ -0,0 +1,622 @@
+//! Pruning of old node data (miniblocks, transactions, events and storage logs) from Postgres.
+//!
+//! Pruning is performed in two steps. First, an L1 batch range is *soft-pruned*, i.e., marked as pruned
+//! without removing any data. The node API starts treating data in the soft-pruned range as unavailable.
+//! Then, after a delay allowing in-flight API requests to finish, the range is *hard-pruned*, i.e., its data
+//! is actually removed. Partitions that only contain pruned data are dropped entirely. Since `storage_logs` partitions
+//! contain the latest values of storage slots, these values are moved to the last pruned miniblock beforehand.
+
+use std::ops;
+
+use zksync_types::{L1BatchNumber, MiniblockNumber};
+
+use crate::{instrument::InstrumentExt, partitions_dal::PartitionedTable, StorageProcessor};
+
+/// Information about pruned data in the node storage.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PruningInfo {
+    /// Last soft-pruned L1 batch. Data for this and earlier L1 batches should be considered unavailable.
+    pub last_soft_pruned_l1_batch: Option<L1BatchNumber>,
+    /// Last soft-pruned miniblock.
+    pub last_soft_pruned_miniblock: Option<MiniblockNumber>,
+    /// Last hard-pruned L1 batch. Data for this and earlier L1 batches is removed from the storage.
+    pub last_hard_pruned_l1_batch: Option<L1BatchNumber>,
+    /// Last hard-pruned miniblock.
+    pub last_hard_pruned_miniblock: Option<MiniblockNumber>,
+}
+
+/// Statistics about a hard pruning operation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HardPruningStats {
+    pub deleted_miniblocks: u64,
+    pub deleted_transactions: u64,
+    pub deleted_events: u64,
+    pub deleted_l2_to_l1_logs: u64,
+    pub deleted_storage_logs: u64,
+    pub dropped_partitions: u64,
+}
+
+#[derive(Debug)]
+pub struct PruningDal<'a, 'c> {
+    pub(crate) storage: &'a mut StorageProcessor<'c>,
+}
+
+impl PruningDal<'_, '_> {
+    pub async fn get_pruning_info(&mut self) -> sqlx::Result<PruningInfo> {
+        let row = sqlx::query!(
+            r#"
+            WITH
+                soft AS (
+                    SELECT
+                        pruned_l1_batch,
+                        pruned_miniblock
+                    FROM
+                        pruning_log
+                    WHERE
+                        TYPE = 'Soft'
+                    ORDER BY
+                        pruned_l1_batch DESC
+                    LIMIT
+                        1
+                ),
+                hard AS (
+                    SELECT
+                        pruned_l1_batch,
+                        pruned_miniblock
+                    FROM
+                        pruning_log
+                    WHERE
+                        TYPE = 'Hard'
+                    ORDER BY
+                        pruned_l1_batch DESC
+                    LIMIT
+                        1
+                )
+            SELECT
+                soft.pruned_l1_batch AS "last_soft_pruned_l1_batch?",
+                soft.pruned_miniblock AS "last_soft_pruned_miniblock?",
+                hard.pruned_l1_batch AS "last_hard_pruned_l1_batch?",
+                hard.pruned_miniblock AS "last_hard_pruned_miniblock?"
+            FROM
+                soft
+                FULL JOIN hard ON TRUE
+            "#
+        )
+        .instrument("get_pruning_info")
+        .fetch_optional(self.storage)
+        .await?;
+
+        let Some(row) = row else {
+            return Ok(PruningInfo::default());
+        };
+        Ok(PruningInfo {
+            last_soft_pruned_l1_batch: row
+                .last_soft_pruned_l1_batch
+                .map(|number| L1BatchNumber(number as u32)),
+            last_soft_pruned_miniblock: row
+                .last_soft_pruned_miniblock
+                .map(|number| MiniblockNumber(number as u32)),
+            last_hard_pruned_l1_batch: row
+                .last_hard_pruned_l1_batch
+                .map(|number| L1BatchNumber(number as u32)),
+            last_hard_pruned_miniblock: row
+                .last_hard_pruned_miniblock
+                .map(|number| MiniblockNumber(number as u32)),
+        })
+    }
+
+    /// Marks all L1 batches up to and including `last_l1_batch_to_prune` (and all miniblocks up to and including
+    /// `last_miniblock_to_prune`) as soft-pruned.
+    pub async fn soft_prune_batches_range(
+        &mut self,
+        last_l1_batch_to_prune: L1BatchNumber,
+        last_miniblock_to_prune: MiniblockNumber,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                pruning_log (
+                    pruned_l1_batch,
+                    pruned_miniblock,
+                    TYPE,
+                    created_at,
+                    updated_at
+                )
+            VALUES
+                ($1, $2, 'Soft', NOW(), NOW())
+            ON CONFLICT DO NOTHING
+            "#,
+            last_l1_batch_to_prune.0 as i64,
+            last_miniblock_to_prune.0 as i64
+        )
+        .instrument("soft_prune_batches_range")
+        .with_arg("last_l1_batch_to_prune", &last_l1_batch_to_prune)
+        .with_arg("last_miniblock_to_prune", &last_miniblock_to_prune)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    /// Removes data for all miniblocks up to and including `last_miniblock_to_prune` that were not hard-pruned yet.
+    ///
+    /// L1 batch headers are retained since they are small and are referenced from many places (e.g., L1 transactions
+    /// and proof generation data). For storage logs, the latest log for each storage key is retained, so that
+    /// the storage state at the last pruned miniblock (and thus at all later miniblocks) can still be queried.
+    /// Retained logs from `storage_logs` partitions that are dropped are moved to `last_miniblock_to_prune`.
+    pub async fn hard_prune_batches_range(
+        &mut self,
+        last_l1_batch_to_prune: L1BatchNumber,
+        last_miniblock_to_prune: MiniblockNumber,
+    ) -> sqlx::Result<HardPruningStats> {
+        // Partitions are dropped outside the transaction since dropping locks the entire table.
+        let mut dropped_partitions = self
+            .storage
+            .partitions_dal()
+            .drop_partitions_before(PartitionedTable::Events, last_miniblock_to_prune + 1)
+            .await?;
+        // `storage_logs` partitions are dropped only if they lie strictly before the last pruned miniblock,
+        // so that retained logs can be moved to a partition that is not dropped.
+        let storage_logs_boundary = self
+            .storage
+            .partitions_dal()
+            .get_partitions(PartitionedTable::StorageLogs)
+            .await?
+            .into_iter()
+            .map(|partition| partition.miniblocks.end)
+            .filter(|&end| end <= u64::from(last_miniblock_to_prune.0))
+            .max();
+
+        let mut transaction = self.storage.start_transaction().await?;
+        let pruning_info = transaction.pruning_dal().get_pruning_info().await?;
+        let first_miniblock_to_prune = pruning_info
+            .last_hard_pruned_miniblock
+            .map_or(MiniblockNumber(0), |number| number + 1);
+        let range = first_miniblock_to_prune..=last_miniblock_to_prune;
+
+        let mut stats = HardPruningStats::default();
+        if !range.is_empty() {
+            let mut dal = transaction.pruning_dal();
+            stats.deleted_storage_logs = dal.prune_storage_logs(&range).await?;
+            stats.deleted_events = dal.delete_events(&range).await?;
+            stats.deleted_l2_to_l1_logs = dal.delete_l2_to_l1_logs(&range).await?;
+            stats.deleted_transactions = dal.delete_transactions(&range).await?;
+            stats.deleted_miniblocks = dal.delete_miniblocks(&range).await?;
+        }
+        if let Some(boundary) = storage_logs_boundary {
+            let boundary = MiniblockNumber(boundary as u32);
+            transaction
+                .pruning_dal()
+                .move_storage_logs(boundary, last_miniblock_to_prune)
+                .await?;
+        }
+
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                pruning_log (
+                    pruned_l1_batch,
+                    pruned_miniblock,
+                    TYPE,
+                    created_at,
+                    updated_at
+                )
+            VALUES
+                ($1, $2, 'Hard', NOW(), NOW())
+            ON CONFLICT DO NOTHING
+            "#,
+            last_l1_batch_to_prune.0 as i64,
+            last_miniblock_to_prune.0 as i64
+        )
+        .instrument("hard_prune_batches_range")
+        .with_arg("last_l1_batch_to_prune", &last_l1_batch_to_prune)
+        .with_arg("last_miniblock_to_prune", &last_miniblock_to_prune)
+        .execute(&mut transaction)
+        .await?;
+
+        transaction.commit().await?;
+
+        if storage_logs_boundary.is_some() {
+            let dropped_storage_logs_partitions = self
+                .storage
+                .partitions_dal()
+                .drop_partitions_before(PartitionedTable::StorageLogs, last_miniblock_to_prune)
+                .await?;
+            dropped_partitions.extend(dropped_storage_logs_partitions);
+        }
+        stats.dropped_partitions = dropped_partitions.len() as u64;
+        Ok(stats)
+    }
+
+    /// Moves storage logs with miniblock numbers before `boundary` to `target_miniblock`, which must be pruned.
+    /// This is only valid after overwritten logs are pruned, so that there's at most one log per storage key
+    /// before the boundary. Existing logs are not removed (they are removed when dropping partitions); conflicts
+    /// are ignored, so that the move can be safely repeated if dropping partitions fails.
+    async fn move_storage_logs(
+        &mut self,
+        boundary: MiniblockNumber,
+        target_miniblock: MiniblockNumber,
+    ) -> sqlx::Result<u64> {
+        let result = sqlx::query!(
+            r#"
+            INSERT INTO
+                storage_logs (
+                    hashed_key,
+                    address,
+                    key,
+                    value,
+                    operation_number,
+                    tx_hash,
+                    miniblock_number,
+                    created_at,
+                    updated_at
+                )
+            SELECT
+                hashed_key,
+                address,
+                key,
+                value,
+                operation_number,
+                tx_hash,
+                $2,
+                created_at,
+                NOW()
+            FROM
+                storage_logs
+            WHERE
+                miniblock_number < $1
+            ON CONFLICT DO NOTHING
+            "#,
+            boundary.0 as i64,
+            target_miniblock.0 as i64
+        )
+        .instrument("move_storage_logs")
+        .with_arg("boundary", &boundary)
+        .with_arg("target_miniblock", &target_miniblock)
+        .execute(self.storage)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Removes storage logs in the specified miniblock range that are overwritten by later logs with a miniblock number
+    /// not exceeding the range end.
+    async fn prune_storage_logs(
+        &mut self,
+        range: &ops::RangeInclusive<MiniblockNumber>,
+    ) -> sqlx::Result<u64> {
+        // Remove logs in the pruned range that are overwritten within the range.
+        let in_range_result = sqlx::query!(
+            r#"
+            DELETE FROM storage_logs USING (
+                SELECT
+                    hashed_key,
+                    MAX(ARRAY[miniblock_number, operation_number]::INT[]) AS op
+                FROM
+                    storage_logs
+                WHERE
+                    miniblock_number BETWEEN $1 AND $2
+                GROUP BY
+                    hashed_key
+            ) AS last_storage_logs
+            WHERE
+                storage_logs.miniblock_number BETWEEN $1 AND $2
+                AND last_storage_logs.hashed_key = storage_logs.hashed_key
+                AND (
+                    storage_logs.miniblock_number != last_storage_logs.op[1]
+                    OR storage_logs.operation_number != last_storage_logs.op[2]
+                )
+            "#,
+            range.start().0 as i64,
+            range.end().0 as i64
+        )
+        .instrument("prune_storage_logs_in_range")
+        .with_arg("range", range)
+        .execute(self.storage)
+        .await?;
+
+        // Remove logs preceding the pruned range that are overwritten within the range.
+        let before_range_result = sqlx::query!(
+            r#"
+            DELETE FROM storage_logs USING (
+                SELECT DISTINCT
+                    hashed_key
+                FROM
+                    storage_logs
+                WHERE
+                    miniblock_number BETWEEN $1 AND $2
+            ) AS overwritten_keys
+            WHERE
+                storage_logs.miniblock_number < $1
+                AND storage_logs.hashed_key = overwritten_keys.hashed_key
+            "#,
+            range.start().0 as i64,
+            range.end().0 as i64
+        )
+        .instrument("prune_storage_logs_before_range")
+        .with_arg("range", range)
+        .execute(self.storage)
+        .await?;
+
+        Ok(in_range_result.rows_affected() + before_range_result.rows_affected())
+    }
+
+    async fn delete_events(
+        &mut self,
+        range: &ops::RangeInclusive<MiniblockNumber>,
+    ) -> sqlx::Result<u64> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM events
+            WHERE
+                miniblock_number BETWEEN $1 AND $2
+            "#,
+            range.start().0 as i64,
+            range.end().0 as i64
+        )
+        .instrument("prune_events")
+        .with_arg("range", range)
+        .execute(self.storage)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    async fn delete_l2_to_l1_logs(
+        &mut self,
+        range: &ops::RangeInclusive<MiniblockNumber>,
+    ) -> sqlx::Result<u64> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM l2_to_l1_logs
+            WHERE
+                miniblock_number BETWEEN $1 AND $2
+            "#,
+            range.start().0 as i64,
+            range.end().0 as i64
+        )
+        .instrument("prune_l2_to_l1_logs")
+        .with_arg("range", range)
+        .execute(self.storage)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Deletes executed transactions in the specified miniblock range. Call traces of the transactions are removed
+    /// as well via a cascading foreign key.
+    async fn delete_transactions(
+        &mut self,
+        range: &ops::RangeInclusive<MiniblockNumber>,
+    ) -> sqlx::Result<u64> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM transactions
+            WHERE
+                miniblock_number BETWEEN $1 AND $2
+            "#,
+            range.start().0 as i64,
+            range.end().0 as i64
+        )
+        .instrument("prune_transactions")
+        .with_arg("range", range)
+        .execute(self.storage)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    async fn delete_miniblocks(
+        &mut self,
+        range: &ops::RangeInclusive<MiniblockNumber>,
+    ) -> sqlx::Result<u64> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM miniblocks
+            WHERE
+                number BETWEEN $1 AND $2
+            "#,
+            range.start().0 as i64,
+            range.end().0 as i64
+        )
+        .instrument("prune_miniblocks")
+        .with_arg("range", range)
+        .execute(self.storage)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_contracts::BaseSystemContractsHashes;
+    use zksync_types::{
+        block::L1BatchHeader, AccountTreeId, Address, ProtocolVersion, ProtocolVersionId,
+        StorageKey, StorageLog, H256,
+    };
+
+    use super::*;
+    use crate::{partitions_dal::PartitionsDal, tests::create_miniblock_header, ConnectionPool};
+
+    async fn insert_l1_batch(conn: &mut StorageProcessor<'_>, number: u32, logs: Vec<StorageLog>) {
+        let header = L1BatchHeader::new(
+            L1BatchNumber(number),
+            number.into(),
+            BaseSystemContractsHashes::default(),
+            ProtocolVersionId::default(),
+        );
+        conn.blocks_dal()
+            .insert_mock_l1_batch(&header)
+            .await
+            .unwrap();
+        conn.blocks_dal()
+            .insert_miniblock(&create_miniblock_header(number))
+            .await
+            .unwrap();
+        conn.storage_logs_dal()
+            .insert_storage_logs(MiniblockNumber(number), &[(H256::zero(), logs)])
+            .await
+            .unwrap();
+        conn.blocks_dal()
+            .mark_miniblocks_as_executed_in_l1_batch(L1BatchNumber(number))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn soft_and_hard_pruning() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+
+        let account = AccountTreeId::new(Address::repeat_byte(1));
+        let key = StorageKey::new(account, H256::zero());
+        let other_key = StorageKey::new(account, H256::repeat_byte(1));
+        let pruned_key = StorageKey::new(account, H256::repeat_byte(2));
+        for number in 0..4 {
+            let mut logs = vec![StorageLog::new_write_log(
+                key,
+                H256::from_low_u64_be(number.into()),
+            )];
+            if number == 1 {
+                logs.push(StorageLog::new_write_log(other_key, H256::repeat_byte(1)));
+                logs.push(StorageLog::new_write_log(pruned_key, H256::repeat_byte(2)));
+            }
+            if number == 2 {
+                logs.push(StorageLog::new_write_log(pruned_key, H256::repeat_byte(3)));
+            }
+            insert_l1_batch(&mut conn, number, logs).await;
+        }
+
+        let info = conn.pruning_dal().get_pruning_info().await.unwrap();
+        assert_eq!(info, PruningInfo::default());
+
+        conn.pruning_dal()
+            .soft_prune_batches_range(L1BatchNumber(2), MiniblockNumber(2))
+            .await
+            .unwrap();
+        let info = conn.pruning_dal().get_pruning_info().await.unwrap();
+        assert_eq!(info.last_soft_pruned_l1_batch, Some(L1BatchNumber(2)));
+        assert_eq!(info.last_soft_pruned_miniblock, Some(MiniblockNumber(2)));
+        assert_eq!(info.last_hard_pruned_l1_batch, None);
+        // Soft pruning doesn't remove data.
+        assert!(conn
+            .blocks_dal()
+            .get_miniblock_header(MiniblockNumber(0))
+            .await
+            .unwrap()
+            .is_some());
+
+        let stats = conn
+            .pruning_dal()
+            .hard_prune_batches_range(L1BatchNumber(2), MiniblockNumber(2))
+            .await
+            .unwrap();
+        assert_eq!(stats.deleted_miniblocks, 3);
+        // Legacy partitions created by migrations for an empty database don't cover any miniblocks.
+        assert_eq!(stats.dropped_partitions, 2);
+        // Logs for `key` in miniblocks 0 and 1, and for `pruned_key` in miniblock 1 should be removed.
+        assert_eq!(stats.deleted_storage_logs, 3);
+
+        let info = conn.pruning_dal().get_pruning_info().await.unwrap();
+        assert_eq!(info.last_hard_pruned_l1_batch, Some(L1BatchNumber(2)));
+        assert_eq!(info.last_hard_pruned_miniblock, Some(MiniblockNumber(2)));
+        for number in 0..=2 {
+            let header = conn
+                .blocks_dal()
+                .get_miniblock_header(MiniblockNumber(number))
+                .await
+                .unwrap();
+            assert!(header.is_none(), "{header:?}");
+        }
+        assert!(conn
+            .blocks_dal()
+            .get_miniblock_header(MiniblockNumber(3))
+            .await
+            .unwrap()
+            .is_some());
+
+        // Check that the storage state is retained.
+        let mut storage_web3_dal = conn.storage_web3_dal();
+        let value = storage_web3_dal.get_value(&key).await.unwrap();
+        assert_eq!(value, H256::from_low_u64_be(3));
+        let value = storage_web3_dal
+            .get_historical_value_unchecked(&key, MiniblockNumber(2))
+            .await
+            .unwrap();
+        assert_eq!(value, H256::from_low_u64_be(2));
+        let value = storage_web3_dal.get_value(&other_key).await.unwrap();
+        assert_eq!(value, H256::repeat_byte(1));
+        let value = storage_web3_dal.get_value(&pruned_key).await.unwrap();
+        assert_eq!(value, H256::repeat_byte(3));
+
+        // Repeated hard pruning shouldn't remove any data.
+        let stats = conn
+            .pruning_dal()
+            .hard_prune_batches_range(L1BatchNumber(2), MiniblockNumber(2))
+            .await
+            .unwrap();
+        assert_eq!(stats, HardPruningStats::default());
+    }
+
+    #[tokio::test]
+    async fn pruning_drops_storage_logs_partitions() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        let size = PartitionsDal::PARTITION_SIZE as u32;
+        conn.partitions_dal()
+            .ensure_partitions(MiniblockNumber(size))
+            .await
+            .unwrap();
+
+        let account = AccountTreeId::new(Address::repeat_byte(1));
+        let key = StorageKey::new(account, H256::zero());
+        let other_key = StorageKey::new(account, H256::repeat_byte(1));
+        insert_l1_batch(
+            &mut conn,
+            0,
+            vec![
+                StorageLog::new_write_log(key, H256::repeat_byte(1)),
+                StorageLog::new_write_log(other_key, H256::repeat_byte(1)),
+            ],
+        )
+        .await;
+        let logs = vec![StorageLog::new_write_log(key, H256::repeat_byte(2))];
+        insert_l1_batch(&mut conn, 1, logs).await;
+        let logs = vec![StorageLog::new_write_log(key, H256::repeat_byte(3))];
+        insert_l1_batch(&mut conn, size + 1, logs).await;
+        let logs = vec![StorageLog::new_write_log(key, H256::repeat_byte(4))];
+        insert_l1_batch(&mut conn, size + 2, logs).await;
+
+        let stats = conn
+            .pruning_dal()
+            .hard_prune_batches_range(L1BatchNumber(size + 1), MiniblockNumber(size + 1))
+            .await
+            .unwrap();
+        // Legacy partitions for both tables, and `events_p0` and `storage_logs_p0` should be dropped.
+        assert_eq!(stats.dropped_partitions, 4);
+        let partitions = conn
+            .partitions_dal()
+            .get_partitions(PartitionedTable::StorageLogs)
+            .await
+            .unwrap();
+        assert_eq!(partitions[0].name, "storage_logs_p1");
+
+        // The latest value of `other_key` should be moved to the last pruned miniblock.
+        let mut storage_web3_dal = conn.storage_web3_dal();
+        let value = storage_web3_dal.get_value(&other_key).await.unwrap();
+        assert_eq!(value, H256::repeat_byte(1));
+        let value = storage_web3_dal
+            .get_historical_value_unchecked(&other_key, MiniblockNumber(size + 1))
+            .await
+            .unwrap();
+        assert_eq!(value, H256::repeat_byte(1));
+        let value = storage_web3_dal
+            .get_historical_value_unchecked(&key, MiniblockNumber(size + 1))
+            .await
+            .unwrap();
+        assert_eq!(value, H256::repeat_byte(3));
+        let value = storage_web3_dal.get_value(&key).await.unwrap();
+        assert_eq!(value, H256::repeat_byte(4));
+    }
+}
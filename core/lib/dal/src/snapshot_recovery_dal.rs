@@ -2,7 +2,7 @@ use zksync_types::{
     snapshots::SnapshotRecoveryStatus, L1BatchNumber, MiniblockNumber, ProtocolVersionId, H256,
 };
 
-use crate::StorageProcessor;
+use crate::{instrument::InstrumentExt, StorageProcessor};
 
 #[derive(Debug)]
 pub struct SnapshotRecoveryDal<'a, 'c> {
@@ -41,7 +41,8 @@ impl SnapshotRecoveryDal<'_, '_> {
             status.protocol_version as i32,
             &status.storage_logs_chunks_processed,
         )
-        .execute(self.storage.conn())
+        .instrument("insert_initial_recovery_status")
+        .execute(self.storage)
         .await?;
         Ok(())
     }
@@ -59,7 +60,9 @@ impl SnapshotRecoveryDal<'_, '_> {
             "#,
             chunk_id as i32 + 1
         )
-        .execute(self.storage.conn())
+        .instrument("mark_storage_logs_chunk_as_processed")
+        .with_arg("chunk_id", &chunk_id)
+        .execute(self.storage)
         .await?;
 
         Ok(())
@@ -83,7 +86,8 @@ impl SnapshotRecoveryDal<'_, '_> {
                 snapshot_recovery
             "#,
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_applied_snapshot_status")
+        .fetch_optional(self.storage)
         .await?;
 
         Ok(record.map(|row| SnapshotRecoveryStatus {
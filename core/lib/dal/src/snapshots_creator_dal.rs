@@ -1,6 +1,6 @@
 use zksync_types::{
-    snapshots::SnapshotStorageLog, AccountTreeId, Address, L1BatchNumber, MiniblockNumber,
-    StorageKey, H256,
+    snapshots::{uniform_hashed_keys_chunk, SnapshotStorageLog},
+    AccountTreeId, Address, L1BatchNumber, MiniblockNumber, StorageKey, H256,
 };
 
 use crate::{instrument::InstrumentExt, StorageProcessor};
@@ -32,7 +32,6 @@ impl SnapshotsCreatorDal<'_, '_> {
             l1_batch_number.0 as i32
         )
         .instrument("get_storage_logs_count")
-        .report_latency()
         .fetch_one(self.storage)
         .await?
         .index;
@@ -82,7 +81,6 @@ impl SnapshotsCreatorDal<'_, '_> {
         .with_arg("miniblock_number", &miniblock_number)
         .with_arg("min_hashed_key", &hashed_keys_range.start())
         .with_arg("max_hashed_key", &hashed_keys_range.end())
-        .report_latency()
         .fetch_all(self.storage)
         .await?
         .iter()
@@ -99,6 +97,48 @@ impl SnapshotsCreatorDal<'_, '_> {
         Ok(storage_logs)
     }
 
+    /// Returns IDs of storage logs chunks (out of `chunk_count` uniform chunks, as per [`uniform_hashed_keys_chunk()`])
+    /// that have storage logs written in any miniblock in `miniblock_range`. IDs are returned in the ascending order.
+    pub async fn get_modified_storage_logs_chunks(
+        &mut self,
+        miniblock_range: std::ops::RangeInclusive<MiniblockNumber>,
+        chunk_count: u64,
+    ) -> sqlx::Result<Vec<u64>> {
+        let chunk_starts: Vec<_> = (0..chunk_count)
+            .map(|chunk_id| {
+                uniform_hashed_keys_chunk(chunk_id, chunk_count)
+                    .start()
+                    .0
+                    .to_vec()
+            })
+            .collect();
+        // `width_bucket()` returns the 1-based index of the last chunk start not exceeding the hashed key.
+        // The first chunk starts at the zero key, so all keys fall into one of the chunks.
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                WIDTH_BUCKET(hashed_key, $3::bytea[]) - 1 AS "chunk_id!"
+            FROM
+                storage_logs
+            WHERE
+                miniblock_number BETWEEN $1 AND $2
+            GROUP BY
+                1
+            ORDER BY
+                1
+            "#,
+            miniblock_range.start().0 as i64,
+            miniblock_range.end().0 as i64,
+            &chunk_starts
+        )
+        .instrument("get_modified_storage_logs_chunks")
+        .with_arg("miniblock_range", &miniblock_range)
+        .with_arg("chunk_count", &chunk_count)
+        .fetch_all(self.storage)
+        .await?;
+        Ok(rows.into_iter().map(|row| row.chunk_id as u64).collect())
+    }
+
     /// Returns all factory dependencies up to and including the specified `miniblock_number`.
     pub async fn get_all_factory_deps(
         &mut self,
@@ -117,7 +157,6 @@ impl SnapshotsCreatorDal<'_, '_> {
             miniblock_number.0 as i64,
         )
         .instrument("get_all_factory_deps")
-        .report_latency()
         .fetch_all(self.storage)
         .await?;
 
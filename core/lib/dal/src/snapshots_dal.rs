@@ -1,28 +1,62 @@
 use zksync_types::{
-    snapshots::{AllSnapshots, SnapshotMetadata},
-    L1BatchNumber,
+    snapshots::{AllSnapshots, SnapshotMetadata, SnapshotVersion},
+    L1BatchNumber, H256,
 };
 
 use crate::{instrument::InstrumentExt, StorageProcessor};
 
 #[derive(Debug, sqlx::FromRow)]
 struct StorageSnapshotMetadata {
+    version: i32,
     l1_batch_number: i64,
     storage_logs_filepaths: Vec<String>,
+    storage_logs_checksums: Vec<Vec<u8>>,
     factory_deps_filepath: String,
+    factory_deps_checksum: Option<Vec<u8>>,
+    tokens_filepath: Option<String>,
+    tokens_checksum: Option<Vec<u8>>,
+    base_l1_batch_number: Option<i64>,
+    storage_logs_source_l1_batches: Vec<i64>,
 }
 
-impl From<StorageSnapshotMetadata> for SnapshotMetadata {
-    fn from(row: StorageSnapshotMetadata) -> Self {
-        Self {
+impl TryFrom<StorageSnapshotMetadata> for SnapshotMetadata {
+    type Error = sqlx::Error;
+
+    fn try_from(row: StorageSnapshotMetadata) -> Result<Self, Self::Error> {
+        let version = u16::try_from(row.version)
+            .ok()
+            .and_then(|version| SnapshotVersion::try_from(version).ok())
+            .ok_or_else(|| {
+                let message = format!("unsupported snapshot version: {}", row.version);
+                sqlx::Error::Decode(message.into())
+            })?;
+
+        Ok(Self {
+            version,
             l1_batch_number: L1BatchNumber(row.l1_batch_number as u32),
             storage_logs_filepaths: row
                 .storage_logs_filepaths
                 .into_iter()
                 .map(|path| (!path.is_empty()).then_some(path))
                 .collect(),
+            storage_logs_checksums: row
+                .storage_logs_checksums
+                .iter()
+                .map(|checksum| (!checksum.is_empty()).then(|| H256::from_slice(checksum)))
+                .collect(),
             factory_deps_filepath: row.factory_deps_filepath,
-        }
+            factory_deps_checksum: row.factory_deps_checksum.as_deref().map(H256::from_slice),
+            tokens_filepath: row.tokens_filepath,
+            tokens_checksum: row.tokens_checksum.as_deref().map(H256::from_slice),
+            base_l1_batch_number: row
+                .base_l1_batch_number
+                .map(|number| L1BatchNumber(number as u32)),
+            storage_logs_source_l1_batches: row
+                .storage_logs_source_l1_batches
+                .into_iter()
+                .map(|number| L1BatchNumber(number as u32))
+                .collect(),
+        })
     }
 }
 
@@ -34,29 +68,119 @@ pub struct SnapshotsDal<'a, 'c> {
 impl SnapshotsDal<'_, '_> {
     pub async fn add_snapshot(
         &mut self,
+        version: SnapshotVersion,
         l1_batch_number: L1BatchNumber,
         storage_logs_chunk_count: u64,
         factory_deps_filepaths: &str,
+        factory_deps_checksum: Option<H256>,
     ) -> sqlx::Result<()> {
         sqlx::query!(
             r#"
             INSERT INTO
                 snapshots (
+                    version,
                     l1_batch_number,
                     storage_logs_filepaths,
+                    storage_logs_checksums,
                     factory_deps_filepath,
+                    factory_deps_checksum,
                     created_at,
                     updated_at
                 )
             VALUES
-                ($1, ARRAY_FILL(''::TEXT, ARRAY[$2::INTEGER]), $3, NOW(), NOW())
+                (
+                    $1,
+                    $2,
+                    ARRAY_FILL(''::TEXT, ARRAY[$3::INTEGER]),
+                    ARRAY_FILL(''::BYTEA, ARRAY[$3::INTEGER]),
+                    $4,
+                    $5,
+                    NOW(),
+                    NOW()
+                )
             "#,
+            version as i32,
             l1_batch_number.0 as i32,
             storage_logs_chunk_count as i32,
             factory_deps_filepaths,
+            factory_deps_checksum.as_ref().map(H256::as_bytes),
         )
         .instrument("add_snapshot")
-        .report_latency()
+        .with_arg("version", &version)
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    /// Adds an incremental snapshot based on the `base` snapshot. Storage logs chunks with IDs in `reused_chunk_ids`
+    /// are taken from the base snapshot; other chunks must be produced in the same way as for a full snapshot.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the reused chunks is not produced or doesn't have a checksum in the base snapshot.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn add_incremental_snapshot(
+        &mut self,
+        version: SnapshotVersion,
+        l1_batch_number: L1BatchNumber,
+        base: &SnapshotMetadata,
+        reused_chunk_ids: &[u64],
+        factory_deps_filepaths: &str,
+        factory_deps_checksum: Option<H256>,
+    ) -> sqlx::Result<()> {
+        let chunk_count = base.storage_logs_filepaths.len();
+        let mut filepaths = vec![String::new(); chunk_count];
+        let mut checksums = vec![Vec::new(); chunk_count];
+        let mut source_l1_batches = vec![i64::from(l1_batch_number.0); chunk_count];
+        for &chunk_id in reused_chunk_ids {
+            let idx = chunk_id as usize;
+            let (Some(filepath), Some(Some(checksum))) = (
+                &base.storage_logs_filepaths[idx],
+                base.storage_logs_checksums.get(idx),
+            ) else {
+                panic!(
+                    "Storage logs chunk {chunk_id} of snapshot for L1 batch #{} cannot be reused",
+                    base.l1_batch_number
+                );
+            };
+            filepaths[idx] = filepath.clone();
+            checksums[idx] = checksum.as_bytes().to_vec();
+            source_l1_batches[idx] = i64::from(base.storage_logs_chunk_source(chunk_id).0);
+        }
+
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                snapshots (
+                    version,
+                    l1_batch_number,
+                    base_l1_batch_number,
+                    storage_logs_filepaths,
+                    storage_logs_checksums,
+                    storage_logs_source_l1_batches,
+                    factory_deps_filepath,
+                    factory_deps_checksum,
+                    created_at,
+                    updated_at
+                )
+            VALUES
+                ($1, $2, $3, $4, $5, $6, $7, $8, NOW(), NOW())
+            "#,
+            version as i32,
+            l1_batch_number.0 as i32,
+            base.l1_batch_number.0 as i32,
+            &filepaths,
+            &checksums,
+            &source_l1_batches,
+            factory_deps_filepaths,
+            factory_deps_checksum.as_ref().map(H256::as_bytes),
+        )
+        .instrument("add_incremental_snapshot")
+        .with_arg("version", &version)
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .with_arg("base_l1_batch_number", &base.l1_batch_number)
+        .with_arg("reused_chunk_count", &reused_chunk_ids.len())
         .execute(self.storage)
         .await?;
         Ok(())
@@ -67,12 +191,14 @@ impl SnapshotsDal<'_, '_> {
         l1_batch_number: L1BatchNumber,
         chunk_id: u64,
         storage_logs_filepath: &str,
+        checksum: Option<H256>,
     ) -> sqlx::Result<()> {
         sqlx::query!(
             r#"
             UPDATE snapshots
             SET
                 storage_logs_filepaths[$2] = $3,
+                storage_logs_checksums[$2] = COALESCE($4, ''::BYTEA),
                 updated_at = NOW()
             WHERE
                 l1_batch_number = $1
@@ -80,8 +206,42 @@ impl SnapshotsDal<'_, '_> {
             l1_batch_number.0 as i32,
             chunk_id as i32 + 1,
             storage_logs_filepath,
+            checksum.as_ref().map(H256::as_bytes),
+        )
+        .instrument("add_storage_logs_filepath_for_snapshot")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .with_arg("chunk_id", &chunk_id)
+        .with_arg("storage_logs_filepath", &storage_logs_filepath)
+        .execute(self.storage)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn add_tokens_filepath_for_snapshot(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+        tokens_filepath: &str,
+        checksum: H256,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE snapshots
+            SET
+                tokens_filepath = $2,
+                tokens_checksum = $3,
+                updated_at = NOW()
+            WHERE
+                l1_batch_number = $1
+            "#,
+            l1_batch_number.0 as i32,
+            tokens_filepath,
+            checksum.as_bytes(),
         )
-        .execute(self.storage.conn())
+        .instrument("add_tokens_filepath_for_snapshot")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .with_arg("tokens_filepath", &tokens_filepath)
+        .execute(self.storage)
         .await?;
 
         Ok(())
@@ -96,12 +256,15 @@ impl SnapshotsDal<'_, '_> {
                 snapshots
             WHERE
                 NOT (''::TEXT = ANY (storage_logs_filepaths))
+                AND (
+                    version = 0
+                    OR tokens_filepath IS NOT NULL
+                )
             ORDER BY
                 l1_batch_number DESC
             "#
         )
         .instrument("get_all_complete_snapshots")
-        .report_latency()
         .fetch_all(self.storage)
         .await?;
 
@@ -120,9 +283,16 @@ impl SnapshotsDal<'_, '_> {
             StorageSnapshotMetadata,
             r#"
             SELECT
+                version,
                 l1_batch_number,
                 factory_deps_filepath,
-                storage_logs_filepaths
+                factory_deps_checksum,
+                storage_logs_filepaths,
+                storage_logs_checksums,
+                tokens_filepath,
+                tokens_checksum,
+                base_l1_batch_number,
+                storage_logs_source_l1_batches
             FROM
                 snapshots
             ORDER BY
@@ -132,11 +302,10 @@ impl SnapshotsDal<'_, '_> {
             "#
         )
         .instrument("get_newest_snapshot_metadata")
-        .report_latency()
         .fetch_optional(self.storage)
         .await?;
 
-        Ok(row.map(Into::into))
+        row.map(SnapshotMetadata::try_from).transpose()
     }
 
     pub async fn get_snapshot_metadata(
@@ -147,9 +316,16 @@ impl SnapshotsDal<'_, '_> {
             StorageSnapshotMetadata,
             r#"
             SELECT
+                version,
                 l1_batch_number,
                 factory_deps_filepath,
-                storage_logs_filepaths
+                factory_deps_checksum,
+                storage_logs_filepaths,
+                storage_logs_checksums,
+                tokens_filepath,
+                tokens_checksum,
+                base_l1_batch_number,
+                storage_logs_source_l1_batches
             FROM
                 snapshots
             WHERE
@@ -158,17 +334,52 @@ impl SnapshotsDal<'_, '_> {
             l1_batch_number.0 as i32
         )
         .instrument("get_snapshot_metadata")
-        .report_latency()
         .fetch_optional(self.storage)
         .await?;
 
-        Ok(row.map(Into::into))
+        row.map(SnapshotMetadata::try_from).transpose()
+    }
+
+    /// Returns metadata for all snapshots (including incomplete ones) created for L1 batches after the specified one.
+    pub async fn get_snapshots_after(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> sqlx::Result<Vec<SnapshotMetadata>> {
+        let rows = sqlx::query_as!(
+            StorageSnapshotMetadata,
+            r#"
+            SELECT
+                version,
+                l1_batch_number,
+                factory_deps_filepath,
+                factory_deps_checksum,
+                storage_logs_filepaths,
+                storage_logs_checksums,
+                tokens_filepath,
+                tokens_checksum,
+                base_l1_batch_number,
+                storage_logs_source_l1_batches
+            FROM
+                snapshots
+            WHERE
+                l1_batch_number > $1
+            ORDER BY
+                l1_batch_number
+            "#,
+            l1_batch_number.0 as i32
+        )
+        .instrument("get_snapshots_after")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .fetch_all(self.storage)
+        .await?;
+
+        rows.into_iter().map(SnapshotMetadata::try_from).collect()
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use zksync_types::L1BatchNumber;
+    use zksync_types::{snapshots::SnapshotVersion, L1BatchNumber, H256};
 
     use crate::ConnectionPool;
 
@@ -178,9 +389,15 @@ mod tests {
         let mut conn = pool.access_storage().await.unwrap();
         let mut dal = conn.snapshots_dal();
         let l1_batch_number = L1BatchNumber(100);
-        dal.add_snapshot(l1_batch_number, 2, "gs:///bucket/factory_deps.bin")
-            .await
-            .expect("Failed to add snapshot");
+        dal.add_snapshot(
+            SnapshotVersion::Version0,
+            l1_batch_number,
+            2,
+            "gs:///bucket/factory_deps.bin",
+            None,
+        )
+        .await
+        .expect("Failed to add snapshot");
 
         let snapshots = dal
             .get_all_complete_snapshots()
@@ -193,6 +410,7 @@ mod tests {
                 l1_batch_number,
                 i,
                 "gs:///bucket/chunk.bin",
+                None,
             )
             .await
             .unwrap();
@@ -218,14 +436,25 @@ mod tests {
         let mut conn = pool.access_storage().await.unwrap();
         let mut dal = conn.snapshots_dal();
         let l1_batch_number = L1BatchNumber(100);
-        dal.add_snapshot(l1_batch_number, 2, "gs:///bucket/factory_deps.bin")
-            .await
-            .expect("Failed to add snapshot");
+        dal.add_snapshot(
+            SnapshotVersion::Version0,
+            l1_batch_number,
+            2,
+            "gs:///bucket/factory_deps.bin",
+            None,
+        )
+        .await
+        .expect("Failed to add snapshot");
 
         let storage_log_filepaths = ["gs:///bucket/test_file1.bin", "gs:///bucket/test_file2.bin"];
-        dal.add_storage_logs_filepath_for_snapshot(l1_batch_number, 1, storage_log_filepaths[1])
-            .await
-            .unwrap();
+        dal.add_storage_logs_filepath_for_snapshot(
+            l1_batch_number,
+            1,
+            storage_log_filepaths[1],
+            None,
+        )
+        .await
+        .unwrap();
 
         let files = dal
             .get_snapshot_metadata(l1_batch_number)
@@ -238,9 +467,14 @@ mod tests {
             [None, Some("gs:///bucket/test_file2.bin".to_string())]
         );
 
-        dal.add_storage_logs_filepath_for_snapshot(l1_batch_number, 0, storage_log_filepaths[0])
-            .await
-            .unwrap();
+        dal.add_storage_logs_filepath_for_snapshot(
+            l1_batch_number,
+            0,
+            storage_log_filepaths[0],
+            None,
+        )
+        .await
+        .unwrap();
 
         let files = dal
             .get_snapshot_metadata(l1_batch_number)
@@ -256,4 +490,157 @@ mod tests {
             ]
         );
     }
+
+    #[tokio::test]
+    async fn adding_snapshot_with_tokens_and_checksums() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        let mut dal = conn.snapshots_dal();
+        let l1_batch_number = L1BatchNumber(100);
+        let factory_deps_checksum = H256::repeat_byte(1);
+        dal.add_snapshot(
+            SnapshotVersion::Version1,
+            l1_batch_number,
+            2,
+            "gs:///bucket/factory_deps.bin",
+            Some(factory_deps_checksum),
+        )
+        .await
+        .unwrap();
+        dal.add_storage_logs_filepath_for_snapshot(
+            l1_batch_number,
+            1,
+            "gs:///bucket/chunk1.bin",
+            Some(H256::repeat_byte(2)),
+        )
+        .await
+        .unwrap();
+
+        let snapshot_metadata = dal
+            .get_snapshot_metadata(l1_batch_number)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(snapshot_metadata.version, SnapshotVersion::Version1);
+        assert_eq!(
+            snapshot_metadata.factory_deps_checksum,
+            Some(factory_deps_checksum)
+        );
+        assert_eq!(
+            snapshot_metadata.storage_logs_checksums,
+            [None, Some(H256::repeat_byte(2))]
+        );
+
+        dal.add_storage_logs_filepath_for_snapshot(
+            l1_batch_number,
+            0,
+            "gs:///bucket/chunk0.bin",
+            Some(H256::repeat_byte(3)),
+        )
+        .await
+        .unwrap();
+        // The snapshot is not complete until the tokens file is produced.
+        let snapshots = dal.get_all_complete_snapshots().await.unwrap();
+        assert_eq!(snapshots.snapshots_l1_batch_numbers, []);
+        let snapshot_metadata = dal.get_newest_snapshot_metadata().await.unwrap().unwrap();
+        assert!(!snapshot_metadata.is_complete());
+
+        dal.add_tokens_filepath_for_snapshot(
+            l1_batch_number,
+            "gs:///bucket/tokens.bin",
+            H256::repeat_byte(4),
+        )
+        .await
+        .unwrap();
+        let snapshots = dal.get_all_complete_snapshots().await.unwrap();
+        assert_eq!(snapshots.snapshots_l1_batch_numbers, [l1_batch_number]);
+        let snapshot_metadata = dal.get_newest_snapshot_metadata().await.unwrap().unwrap();
+        assert!(snapshot_metadata.is_complete());
+        assert_eq!(
+            snapshot_metadata.tokens_filepath.as_deref(),
+            Some("gs:///bucket/tokens.bin")
+        );
+        assert_eq!(
+            snapshot_metadata.tokens_checksum,
+            Some(H256::repeat_byte(4))
+        );
+    }
+
+    #[tokio::test]
+    async fn adding_incremental_snapshot() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        let mut dal = conn.snapshots_dal();
+        let base_l1_batch_number = L1BatchNumber(100);
+        dal.add_snapshot(
+            SnapshotVersion::Version1,
+            base_l1_batch_number,
+            3,
+            "gs:///bucket/factory_deps.bin",
+            Some(H256::repeat_byte(1)),
+        )
+        .await
+        .unwrap();
+        for chunk_id in 0..3 {
+            dal.add_storage_logs_filepath_for_snapshot(
+                base_l1_batch_number,
+                chunk_id,
+                &format!("gs:///bucket/100/chunk{chunk_id}.bin"),
+                Some(H256::repeat_byte(chunk_id as u8 + 2)),
+            )
+            .await
+            .unwrap();
+        }
+        let base = dal
+            .get_snapshot_metadata(base_l1_batch_number)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(base.base_l1_batch_number, None);
+        assert_eq!(base.storage_logs_chunk_source(1), base_l1_batch_number);
+
+        let l1_batch_number = L1BatchNumber(110);
+        dal.add_incremental_snapshot(
+            SnapshotVersion::Version2,
+            l1_batch_number,
+            &base,
+            &[0, 2],
+            "gs:///bucket/factory_deps_110.bin",
+            Some(H256::repeat_byte(10)),
+        )
+        .await
+        .unwrap();
+
+        let snapshot = dal.get_newest_snapshot_metadata().await.unwrap().unwrap();
+        assert_eq!(snapshot.l1_batch_number, l1_batch_number);
+        assert_eq!(snapshot.version, SnapshotVersion::Version2);
+        assert_eq!(snapshot.base_l1_batch_number, Some(base_l1_batch_number));
+        assert_eq!(
+            snapshot.storage_logs_filepaths,
+            [
+                Some("gs:///bucket/100/chunk0.bin".to_owned()),
+                None,
+                Some("gs:///bucket/100/chunk2.bin".to_owned()),
+            ]
+        );
+        assert_eq!(
+            snapshot.storage_logs_checksums,
+            [Some(H256::repeat_byte(2)), None, Some(H256::repeat_byte(4))]
+        );
+        assert_eq!(snapshot.storage_logs_chunk_source(0), base_l1_batch_number);
+        assert_eq!(snapshot.storage_logs_chunk_source(1), l1_batch_number);
+        assert_eq!(snapshot.storage_logs_chunk_source(2), base_l1_batch_number);
+
+        dal.add_storage_logs_filepath_for_snapshot(
+            l1_batch_number,
+            1,
+            "gs:///bucket/110/chunk1.bin",
+            Some(H256::repeat_byte(11)),
+        )
+        .await
+        .unwrap();
+        let snapshot = dal.get_newest_snapshot_metadata().await.unwrap().unwrap();
+        assert!(!snapshot.is_complete()); // the tokens file is missing
+        assert!(snapshot.storage_logs_filepaths.iter().all(Option::is_some));
+    }
 }
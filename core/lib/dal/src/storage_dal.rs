@@ -3,7 +3,7 @@ use std::collections::HashMap;
 use itertools::Itertools;
 use zksync_types::{StorageKey, StorageLog, StorageValue, H256};
 
-use crate::StorageProcessor;
+use crate::{instrument::InstrumentExt, StorageProcessor};
 
 #[derive(Debug)]
 pub struct StorageDal<'a, 'c> {
@@ -71,7 +71,8 @@ impl StorageDal<'_, '_> {
             &values as &[&[u8]],
             &tx_hashes as &[&[u8]],
         )
-        .execute(self.storage.conn())
+        .instrument("apply_storage_logs")
+        .execute(self.storage)
         .await
         .unwrap();
 
@@ -84,7 +85,9 @@ impl StorageDal<'_, '_> {
 
         let row = sqlx::query("SELECT value FROM storage WHERE hashed_key = $1::bytea")
             .bind(key.hashed_key().as_bytes())
-            .fetch_optional(self.storage.conn())
+            .instrument("get_by_key")
+            .with_arg("key", &key)
+            .fetch_optional(self.storage)
             .await?;
         Ok(row.map(|row| {
             let raw_value: Vec<u8> = row.get("value");
@@ -7,7 +7,19 @@ use zksync_types::{
 };
 
 pub use crate::models::storage_log::{DbStorageLog, StorageRecoveryLogEntry};
-use crate::{instrument::InstrumentExt, StorageProcessor};
+use crate::{copy::BinaryCopy, instrument::InstrumentExt, StorageProcessor};
+
+const STORAGE_LOGS_COPY_COLUMNS: &[&str] = &[
+    "hashed_key",
+    "address",
+    "key",
+    "value",
+    "operation_number",
+    "tx_hash",
+    "miniblock_number",
+    "created_at",
+    "updated_at",
+];
 
 #[derive(Debug)]
 pub struct StorageLogsDal<'a, 'c> {
@@ -31,40 +43,24 @@ impl StorageLogsDal<'_, '_> {
         logs: &[(H256, Vec<StorageLog>)],
         mut operation_number: u32,
     ) -> sqlx::Result<()> {
-        let mut copy = self
-            .storage
-            .conn()
-            .copy_in_raw(
-                "COPY storage_logs(
-                    hashed_key, address, key, value, operation_number, tx_hash, miniblock_number,
-                    created_at, updated_at
-                )
-                FROM STDIN WITH (DELIMITER '|')",
-            )
-            .await?;
-
-        let mut buffer = String::new();
-        let now = Utc::now().naive_utc().to_string();
+        let mut copy = BinaryCopy::new("storage_logs", STORAGE_LOGS_COPY_COLUMNS);
+        let now = Utc::now().naive_utc();
         for (tx_hash, logs) in logs {
             for log in logs {
-                write_str!(
-                    &mut buffer,
-                    r"\\x{hashed_key:x}|\\x{address:x}|\\x{key:x}|\\x{value:x}|",
-                    hashed_key = log.key.hashed_key(),
-                    address = log.key.address(),
-                    key = log.key.key(),
-                    value = log.value
-                );
-                writeln_str!(
-                    &mut buffer,
-                    r"{operation_number}|\\x{tx_hash:x}|{block_number}|{now}|{now}"
-                );
-
+                copy.row()
+                    .bytea(log.key.hashed_key().as_bytes())
+                    .bytea(log.key.address().as_bytes())
+                    .bytea(log.key.key().as_bytes())
+                    .bytea(log.value.as_bytes())
+                    .int4(operation_number as i32)
+                    .bytea(tx_hash.as_bytes())
+                    .int8(block_number.0.into())
+                    .timestamp(now)
+                    .timestamp(now);
                 operation_number += 1;
             }
         }
-        copy.send(buffer.as_bytes()).await?;
-        copy.finish().await?;
+        copy.execute("insert_storage_logs", self.storage).await?;
         Ok(())
     }
 
@@ -73,38 +69,24 @@ impl StorageLogsDal<'_, '_> {
         miniblock_number: MiniblockNumber,
         snapshot_storage_logs: &[SnapshotStorageLog],
     ) -> sqlx::Result<()> {
-        let mut copy = self
-            .storage
-            .conn()
-            .copy_in_raw(
-                "COPY storage_logs(
-                    hashed_key, address, key, value, operation_number, tx_hash, miniblock_number,
-                    created_at, updated_at
-                )
-                FROM STDIN WITH (DELIMITER '|')",
-            )
-            .await?;
-
-        let mut buffer = String::new();
-        let now = Utc::now().naive_utc().to_string();
-        for log in snapshot_storage_logs.iter() {
-            write_str!(
-                &mut buffer,
-                r"\\x{hashed_key:x}|\\x{address:x}|\\x{key:x}|\\x{value:x}|",
-                hashed_key = log.key.hashed_key(),
-                address = log.key.address(),
-                key = log.key.key(),
-                value = log.value
-            );
-            writeln_str!(
-                &mut buffer,
-                r"{}|\\x{:x}|{miniblock_number}|{now}|{now}",
-                log.enumeration_index,
-                H256::zero()
-            );
+        let mut copy = BinaryCopy::new("storage_logs", STORAGE_LOGS_COPY_COLUMNS);
+        let now = Utc::now().naive_utc();
+        for log in snapshot_storage_logs {
+            let operation_number = i32::try_from(log.enumeration_index)
+                .map_err(|err| sqlx::Error::Encode(err.into()))?;
+            copy.row()
+                .bytea(log.key.hashed_key().as_bytes())
+                .bytea(log.key.address().as_bytes())
+                .bytea(log.key.key().as_bytes())
+                .bytea(log.value.as_bytes())
+                .int4(operation_number)
+                .bytea(H256::zero().as_bytes())
+                .int8(miniblock_number.0.into())
+                .timestamp(now)
+                .timestamp(now);
         }
-        copy.send(buffer.as_bytes()).await?;
-        copy.finish().await?;
+        copy.execute("insert_storage_logs_from_snapshot", self.storage)
+            .await?;
         Ok(())
     }
 
@@ -124,7 +106,9 @@ impl StorageLogsDal<'_, '_> {
             "#,
             block_number.0 as i64
         )
-        .fetch_one(self.storage.conn())
+        .instrument("append_storage_logs")
+        .with_arg("block_number", &block_number)
+        .fetch_one(self.storage)
         .await?
         .max
         .map(|max| max as u32 + 1)
@@ -187,7 +171,8 @@ impl StorageLogsDal<'_, '_> {
             "#,
             &keys_to_delete as &[&[u8]],
         )
-        .execute(self.storage.conn())
+        .instrument("rollback_storage#1")
+        .execute(self.storage)
         .await?;
 
         tracing::info!(
@@ -210,7 +195,8 @@ impl StorageLogsDal<'_, '_> {
             &keys_to_update as &[&[u8]],
             &values_to_update as &[&[u8]],
         )
-        .execute(self.storage.conn())
+        .instrument("rollback_storage#2")
+        .execute(self.storage)
         .await?;
 
         tracing::info!(
@@ -242,7 +228,9 @@ impl StorageLogsDal<'_, '_> {
             "#,
             miniblock_number.0 as i64
         )
-        .fetch_all(self.storage.conn())
+        .instrument("modified_keys_since_miniblock")
+        .with_arg("miniblock_number", &miniblock_number)
+        .fetch_all(self.storage)
         .await?
         .into_iter()
         .map(|row| H256::from_slice(&row.hashed_key))
@@ -262,7 +250,9 @@ impl StorageLogsDal<'_, '_> {
             "#,
             block_number.0 as i64
         )
-        .execute(self.storage.conn())
+        .instrument("rollback_storage_logs")
+        .with_arg("block_number", &block_number)
+        .execute(self.storage)
         .await?;
         Ok(())
     }
@@ -293,7 +283,8 @@ impl StorageLogsDal<'_, '_> {
             hashed_key.as_bytes(),
             FAILED_CONTRACT_DEPLOYMENT_BYTECODE_HASH.as_bytes(),
         )
-        .fetch_one(self.storage.conn())
+        .instrument("is_contract_deployed_at_address")
+        .fetch_one(self.storage)
         .await
         .unwrap();
 
@@ -335,7 +326,8 @@ impl StorageLogsDal<'_, '_> {
             &bytecode_hashed_keys as &[_],
             max_miniblock_number as i64
         )
-        .fetch_all(self.storage.conn())
+        .instrument("filter_deployed_contracts")
+        .fetch_all(self.storage)
         .await?;
 
         let deployment_data = rows.into_iter().filter_map(|row| {
@@ -385,7 +377,9 @@ impl StorageLogsDal<'_, '_> {
             "#,
             l1_batch_number.0 as i64
         )
-        .fetch_all(self.storage.conn())
+        .instrument("get_touched_slots_for_l1_batch")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .fetch_all(self.storage)
         .await?;
 
         let touched_slots = rows.into_iter().map(|row| {
@@ -398,6 +392,49 @@ impl StorageLogsDal<'_, '_> {
         Ok(touched_slots.collect())
     }
 
+    /// Returns storage logs for the specified miniblock grouped by hashes of the transactions that produced them.
+    /// Logs produced outside of transactions (e.g., by the batch tip in the fictive miniblock) are grouped
+    /// under the zero hash.
+    pub async fn get_miniblock_storage_logs_by_tx(
+        &mut self,
+        miniblock_number: MiniblockNumber,
+    ) -> sqlx::Result<HashMap<H256, Vec<StorageLog>>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                tx_hash,
+                address,
+                key,
+                value
+            FROM
+                storage_logs
+            WHERE
+                miniblock_number = $1
+            ORDER BY
+                operation_number
+            "#,
+            miniblock_number.0 as i64
+        )
+        .instrument("get_miniblock_storage_logs_by_tx")
+        .with_arg("miniblock_number", &miniblock_number)
+        .fetch_all(self.storage)
+        .await?;
+
+        let mut logs_by_tx = HashMap::<_, Vec<_>>::new();
+        for row in rows {
+            let key = StorageKey::new(
+                AccountTreeId::new(Address::from_slice(&row.address)),
+                H256::from_slice(&row.key),
+            );
+            let log = StorageLog::new_write_log(key, H256::from_slice(&row.value));
+            logs_by_tx
+                .entry(H256::from_slice(&row.tx_hash))
+                .or_default()
+                .push(log);
+        }
+        Ok(logs_by_tx)
+    }
+
     /// Returns (hashed) storage keys and the corresponding values that need to be applied to a storage
     /// in order to revert it to the specified L1 batch. Deduplication is taken into account.
     pub async fn get_storage_logs_for_revert(
@@ -505,7 +542,6 @@ impl StorageLogsDal<'_, '_> {
             &hashed_keys as &[&[u8]],
         )
         .instrument("get_l1_batches_and_indices_for_initial_writes")
-        .report_latency()
         .fetch_all(self.storage)
         .await?;
 
@@ -582,7 +618,9 @@ impl StorageLogsDal<'_, '_> {
             &hashed_keys as &[&[u8]],
             miniblock_number.0 as i64
         )
-        .fetch_all(self.storage.conn())
+        .instrument("get_storage_values")
+        .with_arg("miniblock_number", &miniblock_number)
+        .fetch_all(self.storage)
         .await?;
 
         Ok(rows
@@ -611,7 +649,8 @@ impl StorageLogsDal<'_, '_> {
                 storage_logs
             "#
         )
-        .fetch_all(self.storage.conn())
+        .instrument("dump_all_storage_logs_for_tests")
+        .fetch_all(self.storage)
         .await
         .expect("get_all_storage_logs_for_tests");
 
@@ -646,7 +685,9 @@ impl StorageLogsDal<'_, '_> {
             "SELECT COUNT(*) FROM storage_logs WHERE miniblock_number = $1",
             miniblock_number.0 as i32
         )
-        .fetch_one(self.storage.conn())
+        .instrument("count_miniblock_storage_logs")
+        .with_arg("miniblock_number", &miniblock_number)
+        .fetch_one(self.storage)
         .await?;
         Ok(count.unwrap_or(0) as u64)
     }
@@ -696,7 +737,9 @@ impl StorageLogsDal<'_, '_> {
             &start_keys as &[&[u8]],
             &end_keys as &[&[u8]],
         )
-        .fetch_all(self.storage.conn())
+        .instrument("get_chunk_starts_for_miniblock")
+        .with_arg("miniblock_number", &miniblock_number)
+        .fetch_all(self.storage)
         .await?;
 
         let rows = rows.into_iter().map(|row| {
@@ -736,7 +779,140 @@ impl StorageLogsDal<'_, '_> {
             key_range.start().as_bytes(),
             key_range.end().as_bytes()
         )
-        .fetch_all(self.storage.conn())
+        .instrument("get_tree_entries_for_miniblock")
+        .with_arg("miniblock_number", &miniblock_number)
+        .fetch_all(self.storage)
+        .await?;
+
+        let rows = rows.into_iter().map(|row| StorageRecoveryLogEntry {
+            key: H256::from_slice(&row.hashed_key),
+            value: H256::from_slice(&row.value),
+            leaf_index: row.index as u64,
+        });
+        Ok(rows.collect())
+    }
+
+    /// Counts the number of tree entries (i.e., keys with initial writes) after the specified L1 batch.
+    /// This method is used when rebuilding the Merkle tree from Postgres.
+    pub async fn count_tree_entries_for_l1_batch(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> sqlx::Result<u64> {
+        let count = sqlx::query_scalar!(
+            "SELECT COUNT(*) FROM initial_writes WHERE l1_batch_number <= $1",
+            l1_batch_number.0 as i64
+        )
+        .instrument("count_tree_entries_for_l1_batch")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .fetch_one(self.storage)
+        .await?;
+        Ok(count.unwrap_or(0) as u64)
+    }
+
+    /// Gets a starting tree entry for each of the supplied `key_ranges` in the storage state after
+    /// the specified L1 batch. `miniblock_number` must be the last miniblock in the batch.
+    /// This method is used when rebuilding the Merkle tree from Postgres.
+    pub async fn get_chunk_starts_for_l1_batch(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+        miniblock_number: MiniblockNumber,
+        key_ranges: &[ops::RangeInclusive<H256>],
+    ) -> sqlx::Result<Vec<Option<StorageRecoveryLogEntry>>> {
+        let (start_keys, end_keys): (Vec<_>, Vec<_>) = key_ranges
+            .iter()
+            .map(|range| (range.start().as_bytes(), range.end().as_bytes()))
+            .unzip();
+        let rows = sqlx::query!(
+            r#"
+            WITH
+                sl AS (
+                    SELECT
+                        (
+                            SELECT
+                                ARRAY[storage_logs.hashed_key, storage_logs.value] AS kv
+                            FROM
+                                storage_logs
+                                INNER JOIN initial_writes ON storage_logs.hashed_key = initial_writes.hashed_key
+                            WHERE
+                                storage_logs.miniblock_number <= $1
+                                AND initial_writes.l1_batch_number <= $2
+                                AND storage_logs.hashed_key >= u.start_key
+                                AND storage_logs.hashed_key <= u.end_key
+                            ORDER BY
+                                storage_logs.hashed_key,
+                                storage_logs.miniblock_number DESC,
+                                storage_logs.operation_number DESC
+                            LIMIT
+                                1
+                        )
+                    FROM
+                        UNNEST($3::bytea[], $4::bytea[]) AS u (start_key, end_key)
+                )
+            SELECT
+                sl.kv[1] AS "hashed_key?",
+                sl.kv[2] AS "value?",
+                initial_writes.index
+            FROM
+                sl
+                LEFT OUTER JOIN initial_writes ON initial_writes.hashed_key = sl.kv[1]
+            "#,
+            miniblock_number.0 as i64,
+            l1_batch_number.0 as i64,
+            &start_keys as &[&[u8]],
+            &end_keys as &[&[u8]],
+        )
+        .instrument("get_chunk_starts_for_l1_batch")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .with_arg("miniblock_number", &miniblock_number)
+        .fetch_all(self.storage)
+        .await?;
+
+        let rows = rows.into_iter().map(|row| {
+            Some(StorageRecoveryLogEntry {
+                key: H256::from_slice(row.hashed_key.as_ref()?),
+                value: H256::from_slice(row.value.as_ref()?),
+                leaf_index: row.index? as u64,
+            })
+        });
+        Ok(rows.collect())
+    }
+
+    /// Fetches tree entries for the specified `key_range` in the storage state after the specified L1 batch.
+    /// `miniblock_number` must be the last miniblock in the batch. This method is used when rebuilding
+    /// the Merkle tree from Postgres.
+    pub async fn get_tree_entries_for_l1_batch(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+        miniblock_number: MiniblockNumber,
+        key_range: ops::RangeInclusive<H256>,
+    ) -> sqlx::Result<Vec<StorageRecoveryLogEntry>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT DISTINCT
+                ON (storage_logs.hashed_key) storage_logs.hashed_key,
+                storage_logs.value,
+                initial_writes.index
+            FROM
+                storage_logs
+                INNER JOIN initial_writes ON storage_logs.hashed_key = initial_writes.hashed_key
+            WHERE
+                storage_logs.miniblock_number <= $1
+                AND initial_writes.l1_batch_number <= $2
+                AND storage_logs.hashed_key >= $3::bytea
+                AND storage_logs.hashed_key <= $4::bytea
+            ORDER BY
+                storage_logs.hashed_key,
+                storage_logs.miniblock_number DESC,
+                storage_logs.operation_number DESC
+            "#,
+            miniblock_number.0 as i64,
+            l1_batch_number.0 as i64,
+            key_range.start().as_bytes(),
+            key_range.end().as_bytes()
+        )
+        .instrument("get_tree_entries_for_l1_batch")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .fetch_all(self.storage)
         .await?;
 
         let rows = rows.into_iter().map(|row| StorageRecoveryLogEntry {
@@ -762,7 +938,10 @@ impl StorageLogsDal<'_, '_> {
             miniblock_number.0 as i64,
             &operation_numbers
         )
-        .execute(self.storage.conn())
+        .instrument("retain_storage_logs")
+        .with_arg("miniblock_number", &miniblock_number)
+        .with_arg("operation_numbers", &operation_numbers)
+        .execute(self.storage)
         .await
         .unwrap();
     }
@@ -781,7 +960,10 @@ impl StorageLogsDal<'_, '_> {
             ORDER BY operation_number"
         ))
         .bind(miniblock_number.0 as i64)
-        .fetch_all(self.storage.conn())
+        .instrument("get_miniblock_storage_logs_from_table")
+        .with_arg("miniblock_number", &miniblock_number)
+        .with_arg("table_name", &table_name)
+        .fetch_all(self.storage)
         .await
         .unwrap()
         .into_iter()
@@ -811,7 +993,11 @@ impl StorageLogsDal<'_, '_> {
         sqlx::query(&query_str)
             .bind(hashed_key.as_bytes())
             .bind(miniblock_number.0 as i64)
-            .fetch_optional(self.storage.conn())
+            .instrument("get_storage_value_from_table")
+            .with_arg("hashed_key", &hashed_key)
+            .with_arg("miniblock_number", &miniblock_number)
+            .with_arg("table_name", &table_name)
+            .fetch_optional(self.storage)
             .await
             .unwrap()
             .map(|row| H256::from_slice(row.get("value")))
@@ -826,7 +1012,8 @@ impl StorageLogsDal<'_, '_> {
             VACUUM storage_logs
             "#
         )
-        .execute(self.storage.conn())
+        .instrument("vacuum_storage_logs")
+        .execute(self.storage)
         .await
         .unwrap();
     }
@@ -8,7 +8,7 @@ use zksync_types::{
 use zksync_utils::u256_to_h256;
 
 pub use crate::models::storage_log::DbInitialWrite;
-use crate::StorageProcessor;
+use crate::{copy::BinaryCopy, instrument::InstrumentExt, StorageProcessor};
 
 #[derive(Debug)]
 pub struct StorageLogsDedupDal<'a, 'c> {
@@ -21,28 +21,27 @@ impl StorageLogsDedupDal<'_, '_> {
         l1_batch_number: L1BatchNumber,
         read_logs: &[LogQuery],
     ) -> sqlx::Result<()> {
-        let mut copy = self
-            .storage
-            .conn()
-            .copy_in_raw(
-                "COPY protective_reads (l1_batch_number, address, key, created_at, updated_at) \
-                FROM STDIN WITH (DELIMITER '|')",
-            )
-            .await?;
-
-        let mut bytes: Vec<u8> = Vec::new();
-        let now = Utc::now().naive_utc().to_string();
-        for log in read_logs.iter() {
-            let address_str = format!("\\\\x{}", hex::encode(log.address.0));
-            let key_str = format!("\\\\x{}", hex::encode(u256_to_h256(log.key).0));
-            let row = format!(
-                "{}|{}|{}|{}|{}\n",
-                l1_batch_number, address_str, key_str, now, now
-            );
-            bytes.extend_from_slice(row.as_bytes());
+        let mut copy = BinaryCopy::new(
+            "protective_reads",
+            &[
+                "l1_batch_number",
+                "address",
+                "key",
+                "created_at",
+                "updated_at",
+            ],
+        );
+        let now = Utc::now().naive_utc();
+        for log in read_logs {
+            copy.row()
+                .int8(l1_batch_number.0.into())
+                .bytea(log.address.as_bytes())
+                .bytea(u256_to_h256(log.key).as_bytes())
+                .timestamp(now)
+                .timestamp(now);
         }
-        copy.send(bytes).await?;
-        copy.finish().await?;
+        copy.execute("insert_protective_reads", self.storage)
+            .await?;
         Ok(())
     }
 
@@ -52,31 +51,29 @@ impl StorageLogsDedupDal<'_, '_> {
         &mut self,
         snapshot_storage_logs: &[SnapshotStorageLog],
     ) -> sqlx::Result<()> {
-        let mut copy = self
-            .storage
-            .conn()
-            .copy_in_raw(
-                "COPY initial_writes (hashed_key, index, l1_batch_number, created_at, updated_at) \
-                FROM STDIN WITH (DELIMITER '|')",
-            )
-            .await?;
-
-        let mut bytes: Vec<u8> = Vec::new();
-        let now = Utc::now().naive_utc().to_string();
-        for log in snapshot_storage_logs.iter() {
-            let row = format!(
-                "\\\\x{:x}|{}|{}|{}|{}\n",
-                log.key.hashed_key(),
-                log.enumeration_index,
-                log.l1_batch_number_of_initial_write,
-                now,
-                now,
-            );
-            bytes.extend_from_slice(row.as_bytes());
+        let mut copy = BinaryCopy::new(
+            "initial_writes",
+            &[
+                "hashed_key",
+                "index",
+                "l1_batch_number",
+                "created_at",
+                "updated_at",
+            ],
+        );
+        let now = Utc::now().naive_utc();
+        for log in snapshot_storage_logs {
+            let index = i64::try_from(log.enumeration_index)
+                .map_err(|err| sqlx::Error::Encode(err.into()))?;
+            copy.row()
+                .bytea(log.key.hashed_key().as_bytes())
+                .int8(index)
+                .int8(log.l1_batch_number_of_initial_write.0.into())
+                .timestamp(now)
+                .timestamp(now);
         }
-        copy.send(bytes).await?;
-        copy.finish().await?;
-
+        copy.execute("insert_initial_writes_from_snapshot", self.storage)
+            .await?;
         Ok(())
     }
 
@@ -112,7 +109,9 @@ impl StorageLogsDedupDal<'_, '_> {
             &indices,
             l1_batch_number.0 as i64,
         )
-        .execute(self.storage.conn())
+        .instrument("insert_initial_writes")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .execute(self.storage)
         .await?;
 
         Ok(())
@@ -134,7 +133,9 @@ impl StorageLogsDedupDal<'_, '_> {
             "#,
             l1_batch_number.0 as i64
         )
-        .fetch_all(self.storage.conn())
+        .instrument("get_protective_reads_for_l1_batch")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .fetch_all(self.storage)
         .await
         .unwrap()
         .into_iter()
@@ -156,7 +157,8 @@ impl StorageLogsDedupDal<'_, '_> {
                 initial_writes
             "#,
         )
-        .fetch_one(self.storage.conn())
+        .instrument("max_enumeration_index")
+        .fetch_one(self.storage)
         .await
         .unwrap()
         .max
@@ -181,7 +183,9 @@ impl StorageLogsDedupDal<'_, '_> {
             "#,
             l1_batch_number.0 as i64
         )
-        .fetch_all(self.storage.conn())
+        .instrument("initial_writes_for_batch")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .fetch_all(self.storage)
         .await
         .unwrap()
         .into_iter()
@@ -201,7 +205,9 @@ impl StorageLogsDedupDal<'_, '_> {
             "#,
             key.hashed_key().0.to_vec()
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_enumeration_index_for_key")
+        .with_arg("key", &key)
+        .fetch_optional(self.storage)
         .await
         .unwrap()
         .map(|row| row.index as u64)
@@ -221,7 +227,8 @@ impl StorageLogsDedupDal<'_, '_> {
             "#,
             &hashed_keys as &[&[u8]],
         )
-        .fetch_all(self.storage.conn())
+        .instrument("filter_written_slots")
+        .fetch_all(self.storage)
         .await
         .unwrap()
         .into_iter()
@@ -241,7 +248,8 @@ impl StorageLogsDedupDal<'_, '_> {
                 initial_writes
             "#
         )
-        .fetch_all(self.storage.conn())
+        .instrument("dump_all_initial_writes_for_tests")
+        .fetch_all(self.storage)
         .await
         .expect("get_all_initial_writes_for_tests");
 
@@ -1,6 +1,15 @@
 use sqlx::Row;
 
-use crate::StorageProcessor;
+use crate::{instrument::InstrumentExt, StorageProcessor};
+
+/// Information about DB migrations applied to the database.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AppliedMigrations {
+    /// Number of successfully applied migrations.
+    pub count: u64,
+    /// Version of the latest successfully applied migration.
+    pub latest_version: Option<i64>,
+}
 
 pub struct SystemDal<'a, 'c> {
     pub storage: &'a mut StorageProcessor<'c>,
@@ -17,7 +26,8 @@ impl SystemDal<'_, '_> {
                  pg_last_wal_receive_lsn() = pg_last_wal_replay_lsn() AS synced, \
                  EXTRACT(SECONDS FROM now() - pg_last_xact_replay_timestamp())::int AS lag",
         )
-        .fetch_one(self.storage.conn())
+        .instrument("get_replication_lag_sec")
+        .fetch_one(self.storage)
         .await
         .unwrap();
 
@@ -27,4 +37,20 @@ impl SystemDal<'_, '_> {
             _ => 0,
         }
     }
+
+    /// Returns information about DB migrations applied by `sqlx`.
+    pub async fn get_applied_migrations(&mut self) -> sqlx::Result<AppliedMigrations> {
+        let row = sqlx::query(
+            "SELECT COUNT(*) AS count, MAX(version) AS latest_version \
+             FROM _sqlx_migrations WHERE success",
+        )
+        .instrument("get_applied_migrations")
+        .fetch_one(self.storage)
+        .await?;
+
+        Ok(AppliedMigrations {
+            count: row.try_get::<i64, _>("count")? as u64,
+            latest_version: row.try_get("latest_version")?,
+        })
+    }
 }
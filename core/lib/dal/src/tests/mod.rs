@@ -2,6 +2,7 @@ use std::time::Duration;
 
 use zksync_contracts::BaseSystemContractsHashes;
 use zksync_types::{
+    api,
     block::{MiniblockHasher, MiniblockHeader},
     fee::{Fee, TransactionExecutionMetrics},
     fee_model::BatchFeeInput,
@@ -254,7 +255,7 @@ async fn remove_stuck_txs() {
         .remove_stuck_txs(Duration::from_secs(500))
         .await
         .unwrap();
-    assert_eq!(removed_txs, 1);
+    assert_eq!(removed_txs.len(), 1);
     transactions_dal.reset_mempool().await.unwrap();
     let txs = transactions_dal
         .sync_mempool(&[], &[], 0, 0, 1000)
@@ -272,3 +273,49 @@ async fn remove_stuck_txs() {
 
     assert_eq!(receipts.len(), 1);
 }
+
+#[tokio::test]
+async fn marking_evicted_txs_as_rejected() {
+    let connection_pool = ConnectionPool::test_pool().await;
+    let storage = &mut connection_pool.access_storage().await.unwrap();
+    let mut protocol_versions_dal = ProtocolVersionsDal { storage };
+    protocol_versions_dal
+        .save_protocol_version_with_tx(Default::default())
+        .await;
+
+    let storage = protocol_versions_dal.storage;
+    let mut transactions_dal = TransactionsDal { storage };
+    let evicted_tx = mock_l2_transaction();
+    let retained_tx = mock_l2_transaction();
+    for tx in [&evicted_tx, &retained_tx] {
+        transactions_dal
+            .insert_transaction_l2(tx.clone(), mock_tx_execution_metrics())
+            .await;
+    }
+
+    let rejected_count = transactions_dal
+        .mark_evicted_txs_as_rejected(&[(evicted_tx.hash(), "evicted".to_owned())])
+        .await
+        .unwrap();
+    assert_eq!(rejected_count, 1);
+
+    // The rejected transaction must not be loaded into the mempool again.
+    transactions_dal.reset_mempool().await.unwrap();
+    let txs = transactions_dal
+        .sync_mempool(&[], &[], 0, 0, 1000)
+        .await
+        .unwrap();
+    assert_eq!(txs.len(), 1);
+    assert_eq!(txs[0].hash(), retained_tx.hash());
+
+    let storage = transactions_dal.storage;
+    let details = TransactionsWeb3Dal { storage }
+        .get_transaction_details(evicted_tx.hash())
+        .await
+        .unwrap()
+        .expect("evicted transaction was removed");
+    assert!(
+        matches!(details.status, api::TransactionStatus::Failed),
+        "{details:?}"
+    );
+}
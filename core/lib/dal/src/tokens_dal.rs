@@ -1,7 +1,7 @@
 use sqlx::types::chrono::Utc;
 use zksync_types::{tokens::TokenInfo, Address, MiniblockNumber};
 
-use crate::StorageProcessor;
+use crate::{instrument::InstrumentExt, StorageProcessor};
 
 #[derive(Debug)]
 pub struct TokensDal<'a, 'c> {
@@ -53,7 +53,9 @@ impl TokensDal<'_, '_> {
             "#,
             l1_address.as_bytes()
         )
-        .execute(self.storage.conn())
+        .instrument("mark_token_as_well_known")
+        .with_arg("l1_address", &l1_address)
+        .execute(self.storage)
         .await?;
         Ok(())
     }
@@ -67,7 +69,8 @@ impl TokensDal<'_, '_> {
                 tokens
             "#
         )
-        .fetch_all(self.storage.conn())
+        .instrument("get_all_l2_token_addresses")
+        .fetch_all(self.storage)
         .await?;
 
         Ok(rows
@@ -96,7 +99,8 @@ impl TokensDal<'_, '_> {
             "#,
             &token_addresses_to_be_removed as &[_]
         )
-        .execute(self.storage.conn())
+        .instrument("rollback_tokens")
+        .execute(self.storage)
         .await?;
 
         Ok(())
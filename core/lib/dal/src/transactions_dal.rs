@@ -5,6 +5,7 @@ use bigdecimal::BigDecimal;
 use itertools::Itertools;
 use sqlx::{error, types::chrono::NaiveDateTime};
 use zksync_types::{
+    api,
     block::MiniblockExecutionData,
     fee::TransactionExecutionMetrics,
     l1::L1Tx,
@@ -12,14 +13,15 @@ use zksync_types::{
     protocol_version::ProtocolUpgradeTx,
     tx::{tx_execution_info::TxExecutionStatus, TransactionExecutionResult},
     vm_trace::Call,
-    Address, ExecuteTransactionCommon, L1BatchNumber, L1BlockNumber, MiniblockNumber, PriorityOpId,
-    Transaction, H256, PROTOCOL_UPGRADE_TX_TYPE, U256,
+    Address, ExecuteTransactionCommon, L1BatchNumber, L1BlockNumber, MiniblockNumber, Nonce,
+    PriorityOpId, Transaction, H256, PROTOCOL_UPGRADE_TX_TYPE, U256,
 };
-use zksync_utils::u256_to_big_decimal;
+use zksync_utils::{bigdecimal_to_u256, u256_to_big_decimal};
 
 use crate::{
+    copy::BinaryCopy,
     instrument::InstrumentExt,
-    models::storage_transaction::{CallTrace, StorageTransaction},
+    models::storage_transaction::{CallTrace, LocalizedCallTrace, StorageTransaction},
     time_utils::pg_interval_from_duration,
     StorageProcessor,
 };
@@ -149,7 +151,9 @@ impl TransactionsDal<'_, '_> {
                 refund_recipient,
                 received_at,
             )
-            .fetch_optional(self.storage.conn())
+            .instrument("insert_transaction_l1")
+            .with_arg("l1_block_number", &l1_block_number)
+            .fetch_optional(self.storage)
             .await
             .unwrap();
         }
@@ -243,7 +247,8 @@ impl TransactionsDal<'_, '_> {
                 refund_recipient,
                 received_at,
             )
-            .fetch_optional(self.storage.conn())
+            .instrument("insert_system_transaction")
+            .fetch_optional(self.storage)
             .await
             .unwrap();
         }
@@ -267,7 +272,8 @@ impl TransactionsDal<'_, '_> {
                 "#,
                 tx_hash.as_bytes(),
             )
-            .fetch_optional(self.storage.conn())
+            .instrument("insert_transaction_l2#1")
+            .fetch_optional(self.storage)
             .await
             .unwrap()
             .is_some();
@@ -411,7 +417,8 @@ impl TransactionsDal<'_, '_> {
                 exec_info.contracts_used as i32,
                 received_at
             )
-                .fetch_optional(self.storage.conn())
+                .instrument("insert_transaction_l2#2")
+                .fetch_optional(self.storage)
                 .await
                 .map(|option_record| option_record.map(|record| record.is_replaced));
 
@@ -453,6 +460,50 @@ impl TransactionsDal<'_, '_> {
         }
     }
 
+    /// Returns the hash and fee parameters of a pending (i.e., not included into a miniblock and not rejected)
+    /// L2 transaction with the specified initiator and nonce.
+    pub async fn get_pending_l2_tx_fee(
+        &mut self,
+        initiator_address: Address,
+        nonce: Nonce,
+    ) -> sqlx::Result<Option<(H256, U256, U256)>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                hash,
+                max_fee_per_gas,
+                max_priority_fee_per_gas
+            FROM
+                transactions
+            WHERE
+                initiator_address = $1
+                AND nonce = $2
+                AND is_priority = FALSE
+                AND miniblock_number IS NULL
+                AND error IS NULL
+            "#,
+            initiator_address.as_bytes(),
+            i64::from(nonce.0)
+        )
+        .instrument("get_pending_l2_tx_fee")
+        .with_arg("initiator_address", &initiator_address)
+        .with_arg("nonce", &nonce)
+        .fetch_optional(self.storage)
+        .await?;
+
+        Ok(row.map(|row| {
+            (
+                H256::from_slice(&row.hash),
+                row.max_fee_per_gas
+                    .map(bigdecimal_to_u256)
+                    .unwrap_or_default(),
+                row.max_priority_fee_per_gas
+                    .map(bigdecimal_to_u256)
+                    .unwrap_or_default(),
+            )
+        }))
+    }
+
     pub async fn mark_txs_as_executed_in_l1_batch(
         &mut self,
         block_number: L1BatchNumber,
@@ -481,7 +532,9 @@ impl TransactionsDal<'_, '_> {
                 &hashes as &[&[u8]],
                 block_number.0 as i64
             )
-            .execute(self.storage.conn())
+            .instrument("mark_txs_as_executed_in_l1_batch")
+            .with_arg("block_number", &block_number)
+            .execute(self.storage)
             .await
             .unwrap();
         }
@@ -502,13 +555,6 @@ impl TransactionsDal<'_, '_> {
             let mut l1_refunded_gas = Vec::with_capacity(transactions.len());
             let mut l1_effective_gas_prices = Vec::with_capacity(transactions.len());
 
-            let mut upgrade_hashes = Vec::new();
-            let mut upgrade_indices_in_block = Vec::new();
-            let mut upgrade_errors = Vec::new();
-            let mut upgrade_execution_infos = Vec::new();
-            let mut upgrade_refunded_gas = Vec::new();
-            let mut upgrade_effective_gas_prices = Vec::new();
-
             let mut l2_hashes = Vec::with_capacity(transactions.len());
             let mut l2_values = Vec::with_capacity(transactions.len());
             let mut l2_contract_addresses = Vec::with_capacity(transactions.len());
@@ -530,8 +576,7 @@ impl TransactionsDal<'_, '_> {
             let mut l2_gas_per_pubdata_limit = Vec::with_capacity(transactions.len());
             let mut l2_refunded_gas = Vec::with_capacity(transactions.len());
 
-            let mut call_traces_tx_hashes = Vec::with_capacity(transactions.len());
-            let mut bytea_call_traces = Vec::with_capacity(transactions.len());
+            let mut call_traces = BinaryCopy::new("call_traces", &["tx_hash", "call_trace"]);
             transactions
                 .iter()
                 .enumerate()
@@ -555,8 +600,10 @@ impl TransactionsDal<'_, '_> {
                     };
 
                     if let Some(call_trace) = tx_res.call_trace() {
-                        bytea_call_traces.push(bincode::serialize(&call_trace).unwrap());
-                        call_traces_tx_hashes.push(hash.0.to_vec());
+                        call_traces
+                            .row()
+                            .bytea(hash.as_bytes())
+                            .bytea(&bincode::serialize(&call_trace).unwrap());
                     }
 
                     match &transaction.common_data {
@@ -604,14 +651,14 @@ impl TransactionsDal<'_, '_> {
                                 .push(u256_to_big_decimal(common_data.fee.gas_per_pubdata_limit));
                             l2_refunded_gas.push(*refunded_gas as i64);
                         }
+                        // Protocol upgrade transactions are updated in the same way as L1 transactions.
                         ExecuteTransactionCommon::ProtocolUpgrade(common_data) => {
-                            upgrade_hashes.push(hash.0.to_vec());
-                            upgrade_indices_in_block.push(index_in_block as i32);
-                            upgrade_errors.push(error.unwrap_or_default());
-                            upgrade_execution_infos
-                                .push(serde_json::to_value(execution_info).unwrap());
-                            upgrade_refunded_gas.push(*refunded_gas as i64);
-                            upgrade_effective_gas_prices
+                            l1_hashes.push(hash.0.to_vec());
+                            l1_indices_in_block.push(index_in_block as i32);
+                            l1_errors.push(error.unwrap_or_default());
+                            l1_execution_infos.push(serde_json::to_value(execution_info).unwrap());
+                            l1_refunded_gas.push(*refunded_gas as i64);
+                            l1_effective_gas_prices
                                 .push(u256_to_big_decimal(common_data.max_fee_per_gas));
                         }
                     }
@@ -708,12 +755,14 @@ impl TransactionsDal<'_, '_> {
                     &l2_paymaster_input,
                     miniblock_number.0 as i32,
                 )
-                .execute(transaction.conn())
+                .instrument("mark_txs_as_executed_in_miniblock#1")
+                .with_arg("miniblock_number", &miniblock_number)
+                .execute(&mut transaction)
                 .await
                 .unwrap();
             }
 
-            // We can't replace l1 transaction, so we simply write the execution result
+            // We can't replace L1 and protocol upgrade transactions, so we simply write the execution result
             if !l1_hashes.is_empty() {
                 sqlx::query!(
                     r#"
@@ -748,70 +797,17 @@ impl TransactionsDal<'_, '_> {
                     &l1_refunded_gas,
                     &l1_effective_gas_prices,
                 )
-                .execute(transaction.conn())
-                .await
-                .unwrap();
-            }
-
-            if !upgrade_hashes.is_empty() {
-                sqlx::query!(
-                    r#"
-                    UPDATE transactions
-                    SET
-                        miniblock_number = $1,
-                        index_in_block = data_table.index_in_block,
-                        error = NULLIF(data_table.error, ''),
-                        in_mempool = FALSE,
-                        execution_info = execution_info || data_table.new_execution_info,
-                        refunded_gas = data_table.refunded_gas,
-                        effective_gas_price = data_table.effective_gas_price,
-                        updated_at = NOW()
-                    FROM
-                        (
-                            SELECT
-                                UNNEST($2::bytea[]) AS hash,
-                                UNNEST($3::INTEGER[]) AS index_in_block,
-                                UNNEST($4::VARCHAR[]) AS error,
-                                UNNEST($5::jsonb[]) AS new_execution_info,
-                                UNNEST($6::BIGINT[]) AS refunded_gas,
-                                UNNEST($7::NUMERIC[]) AS effective_gas_price
-                        ) AS data_table
-                    WHERE
-                        transactions.hash = data_table.hash
-                    "#,
-                    miniblock_number.0 as i32,
-                    &upgrade_hashes,
-                    &upgrade_indices_in_block,
-                    &upgrade_errors,
-                    &upgrade_execution_infos,
-                    &upgrade_refunded_gas,
-                    &upgrade_effective_gas_prices,
-                )
-                .execute(transaction.conn())
+                .instrument("mark_txs_as_executed_in_miniblock#2")
+                .with_arg("miniblock_number", &miniblock_number)
+                .execute(&mut transaction)
                 .await
                 .unwrap();
             }
 
-            if !bytea_call_traces.is_empty() {
-                sqlx::query!(
-                    r#"
-                    INSERT INTO
-                        call_traces (tx_hash, call_trace)
-                    SELECT
-                        u.tx_hash,
-                        u.call_trace
-                    FROM
-                        UNNEST($1::bytea[], $2::bytea[]) AS u (tx_hash, call_trace)
-                    "#,
-                    &call_traces_tx_hashes,
-                    &bytea_call_traces
-                )
-                .instrument("insert_call_tracer")
-                .report_latency()
-                .execute(&mut transaction)
+            call_traces
+                .execute("insert_call_traces", &mut transaction)
                 .await
                 .unwrap();
-            }
             transaction.commit().await.unwrap();
         }
     }
@@ -833,7 +829,10 @@ impl TransactionsDal<'_, '_> {
                 error,
                 transaction_hash.0.to_vec()
             )
-            .execute(self.storage.conn())
+            .instrument("mark_tx_as_rejected")
+            .with_arg("transaction_hash", &transaction_hash)
+            .with_arg("error", &error)
+            .execute(self.storage)
             .await
             .unwrap();
         }
@@ -857,7 +856,9 @@ impl TransactionsDal<'_, '_> {
                 "#,
                 miniblock_number.0 as i64
             )
-            .fetch_all(self.storage.conn())
+            .instrument("reset_transactions_state#1")
+            .with_arg("miniblock_number", &miniblock_number)
+            .fetch_all(self.storage)
             .await
             .unwrap();
             sqlx::query!(
@@ -871,13 +872,47 @@ impl TransactionsDal<'_, '_> {
                     .map(|tx| tx.hash.clone())
                     .collect::<Vec<Vec<u8>>>()
             )
-            .execute(self.storage.conn())
+            .instrument("reset_transactions_state#2")
+            .execute(self.storage)
             .await
             .unwrap();
         }
     }
 
-    pub async fn remove_stuck_txs(&mut self, stuck_tx_timeout: Duration) -> sqlx::Result<usize> {
+    /// Returns the total number of transactions and the number of L1 transactions included into miniblocks
+    /// after the specified one.
+    pub async fn count_transactions_after_miniblock(
+        &mut self,
+        miniblock_number: MiniblockNumber,
+    ) -> sqlx::Result<(usize, usize)> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                COUNT(*) AS "count!",
+                COUNT(*) FILTER (
+                    WHERE
+                        is_priority
+                ) AS "l1_count!"
+            FROM
+                transactions
+            WHERE
+                miniblock_number > $1
+            "#,
+            miniblock_number.0 as i64
+        )
+        .instrument("count_transactions_after_miniblock")
+        .with_arg("miniblock_number", &miniblock_number)
+        .fetch_one(self.storage)
+        .await?;
+        Ok((row.count as usize, row.l1_count as usize))
+    }
+
+    /// Removes L2 transactions that weren't included into a miniblock for `stuck_tx_timeout`.
+    /// Returns hashes of the removed transactions.
+    pub async fn remove_stuck_txs(
+        &mut self,
+        stuck_tx_timeout: Duration,
+    ) -> sqlx::Result<Vec<H256>> {
         let stuck_tx_timeout = pg_interval_from_duration(stuck_tx_timeout);
         let rows = sqlx::query!(
             r#"
@@ -892,10 +927,110 @@ impl TransactionsDal<'_, '_> {
             "#,
             stuck_tx_timeout
         )
-        .fetch_all(self.storage.conn())
+        .instrument("remove_stuck_txs")
+        .fetch_all(self.storage)
         .await?;
 
-        Ok(rows.len())
+        Ok(rows
+            .into_iter()
+            .map(|row| H256::from_slice(&row.hash))
+            .collect())
+    }
+
+    /// Marks L2 transactions evicted from the mempool as rejected with the specified errors, so that
+    /// they are not loaded into the mempool again. Transactions that were already included into a miniblock
+    /// are not affected. Returns the number of rejected transactions.
+    pub async fn mark_evicted_txs_as_rejected(
+        &mut self,
+        evicted_txs: &[(H256, String)],
+    ) -> sqlx::Result<usize> {
+        let (tx_hashes, errors): (Vec<_>, Vec<_>) = evicted_txs
+            .iter()
+            .map(|(hash, error)| (hash.as_bytes(), error.as_str()))
+            .unzip();
+        let result = sqlx::query!(
+            r#"
+            UPDATE transactions
+            SET
+                error = data_table.error,
+                updated_at = NOW()
+            FROM
+                (
+                    SELECT
+                        UNNEST($1::bytea[]) AS hash,
+                        UNNEST($2::VARCHAR[]) AS error
+                ) AS data_table
+            WHERE
+                transactions.hash = data_table.hash
+                AND transactions.miniblock_number IS NULL
+                AND transactions.is_priority = FALSE
+            "#,
+            &tx_hashes as &[&[u8]],
+            &errors as &[&str]
+        )
+        .instrument("mark_evicted_txs_as_rejected")
+        .with_arg("evicted_txs.len", &evicted_txs.len())
+        .execute(self.storage)
+        .await?;
+        Ok(result.rows_affected() as usize)
+    }
+
+    /// Returns the initiator address and nonce of a pending L2 transaction, i.e. one that isn't included
+    /// into a miniblock yet.
+    pub async fn get_pending_l2_tx_nonce(
+        &mut self,
+        tx_hash: H256,
+    ) -> sqlx::Result<Option<(Address, Nonce)>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                initiator_address,
+                nonce
+            FROM
+                transactions
+            WHERE
+                hash = $1
+                AND miniblock_number IS NULL
+                AND is_priority = FALSE
+            "#,
+            tx_hash.as_bytes()
+        )
+        .instrument("get_pending_l2_tx_nonce")
+        .with_arg("tx_hash", &tx_hash)
+        .fetch_optional(self.storage)
+        .await?;
+
+        Ok(row.and_then(|row| {
+            let nonce = Nonce(row.nonce? as u32);
+            Some((Address::from_slice(&row.initiator_address), nonce))
+        }))
+    }
+
+    /// Removes a pending L2 transaction, e.g. on operator request. The transaction is only removed if it isn't
+    /// included into a miniblock and its `in_mempool` flag is equal to the provided value. Returns `true`
+    /// if the transaction was removed.
+    pub async fn remove_pending_l2_tx(
+        &mut self,
+        tx_hash: H256,
+        in_mempool: bool,
+    ) -> sqlx::Result<bool> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM transactions
+            WHERE
+                hash = $1
+                AND miniblock_number IS NULL
+                AND is_priority = FALSE
+                AND in_mempool = $2
+            "#,
+            tx_hash.as_bytes(),
+            in_mempool
+        )
+        .instrument("remove_pending_l2_tx")
+        .with_arg("tx_hash", &tx_hash)
+        .execute(self.storage)
+        .await?;
+        Ok(result.rows_affected() > 0)
     }
 
     /// Fetches new updates for mempool. Returns new transactions and current nonces for related accounts;
@@ -922,7 +1057,8 @@ impl TransactionsDal<'_, '_> {
             "#,
             &stashed_addresses as &[&[u8]],
         )
-        .execute(self.storage.conn())
+        .instrument("sync_mempool#1")
+        .execute(self.storage)
         .await?;
 
         let purged_addresses: Vec<_> = purged_accounts.iter().map(Address::as_bytes).collect();
@@ -935,7 +1071,8 @@ impl TransactionsDal<'_, '_> {
             "#,
             &purged_addresses as &[&[u8]]
         )
-        .execute(self.storage.conn())
+        .instrument("sync_mempool#2")
+        .execute(self.storage)
         .await?;
 
         // Note, that transactions are updated in order of their hashes to avoid deadlocks with other UPDATE queries.
@@ -987,7 +1124,8 @@ impl TransactionsDal<'_, '_> {
             BigDecimal::from(gas_per_pubdata),
             PROTOCOL_UPGRADE_TX_TYPE as i32,
         )
-        .fetch_all(self.storage.conn())
+        .instrument("sync_mempool#3")
+        .fetch_all(self.storage)
         .await?;
 
         let transactions = transactions.into_iter().map(|tx| tx.into()).collect();
@@ -1004,7 +1142,8 @@ impl TransactionsDal<'_, '_> {
                 in_mempool = TRUE
             "#
         )
-        .execute(self.storage.conn())
+        .instrument("reset_mempool")
+        .execute(self.storage)
         .await?;
         Ok(())
     }
@@ -1025,7 +1164,8 @@ impl TransactionsDal<'_, '_> {
                     1
                 "#
             )
-            .fetch_optional(self.storage.conn())
+            .instrument("get_last_processed_l1_block")
+            .fetch_optional(self.storage)
             .await
             .unwrap()
             .and_then(|x| x.l1_block_number.map(|block| L1BlockNumber(block as u32)))
@@ -1044,7 +1184,8 @@ impl TransactionsDal<'_, '_> {
                     is_priority = TRUE
                 "#
             )
-            .fetch_optional(self.storage.conn())
+            .instrument("last_priority_id")
+            .fetch_optional(self.storage)
             .await
             .unwrap()?
             .op_id?;
@@ -1065,7 +1206,8 @@ impl TransactionsDal<'_, '_> {
                     AND miniblock_number IS NOT NULL
                 "#
             )
-            .fetch_optional(self.storage.conn())
+            .instrument("next_priority_id")
+            .fetch_optional(self.storage)
             .await
             .unwrap()
             .and_then(|row| row.op_id)
@@ -1096,7 +1238,8 @@ impl TransactionsDal<'_, '_> {
                 index_in_block
             "#,
         )
-        .fetch_all(self.storage.conn())
+        .instrument("get_miniblocks_to_reexecute")
+        .fetch_all(self.storage)
         .await?;
 
         self.map_transactions_to_execution_data(transactions).await
@@ -1124,7 +1267,8 @@ impl TransactionsDal<'_, '_> {
             "#,
             l1_batch_number.0 as i64,
         )
-        .fetch_all(self.storage.conn())
+        .instrument("get_miniblocks_to_execute_for_l1_batch")
+        .fetch_all(self.storage)
         .await?;
 
         self.map_transactions_to_execution_data(transactions).await
@@ -1167,7 +1311,8 @@ impl TransactionsDal<'_, '_> {
             from_miniblock.0 as i64,
             to_miniblock.0 as i64,
         )
-        .fetch_all(self.storage.conn())
+        .instrument("map_transactions_to_execution_data#1")
+        .fetch_all(self.storage)
         .await?;
 
         anyhow::ensure!(
@@ -1190,7 +1335,8 @@ impl TransactionsDal<'_, '_> {
             from_miniblock.0 as i64 - 1,
             to_miniblock.0 as i64 - 1,
         )
-        .fetch_all(self.storage.conn())
+        .instrument("map_transactions_to_execution_data#2")
+        .fetch_all(self.storage)
         .await?;
 
         let prev_miniblock_hashes: HashMap<_, _> = prev_miniblock_hashes
@@ -1223,7 +1369,8 @@ impl TransactionsDal<'_, '_> {
                         "#,
                         prev_miniblock_number.0 as i32
                     )
-                    .fetch_optional(self.storage.conn())
+                    .instrument("map_transactions_to_execution_data#3")
+                    .fetch_optional(self.storage)
                     .await?
                     .with_context(|| {
                         format!(
@@ -1265,7 +1412,9 @@ impl TransactionsDal<'_, '_> {
                 "#,
                 l1_batch_number.0 as i64
             )
-            .fetch_all(self.storage.conn())
+            .instrument("get_tx_locations")
+            .with_arg("l1_batch_number", &l1_batch_number)
+            .fetch_all(self.storage)
             .await
             .unwrap()
             .into_iter()
@@ -1301,11 +1450,42 @@ impl TransactionsDal<'_, '_> {
             "#,
             tx_hash.as_bytes()
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_call_trace")
+        .fetch_optional(self.storage)
         .await?
         .map(Into::into))
     }
 
+    /// Returns the call trace for the specified transaction together with its location in the chain.
+    pub async fn get_call_trace_with_location(
+        &mut self,
+        tx_hash: H256,
+    ) -> sqlx::Result<Option<(api::trace::TransactionTraceLocation, Call)>> {
+        let row = sqlx::query_as!(
+            LocalizedCallTrace,
+            r#"
+            SELECT
+                call_traces.tx_hash,
+                call_trace,
+                transactions.index_in_block AS "index_in_block!",
+                miniblocks.number AS miniblock_number,
+                miniblocks.hash AS miniblock_hash
+            FROM
+                call_traces
+                INNER JOIN transactions ON tx_hash = transactions.hash
+                INNER JOIN miniblocks ON transactions.miniblock_number = miniblocks.number
+            WHERE
+                call_traces.tx_hash = $1
+            "#,
+            tx_hash.as_bytes()
+        )
+        .instrument("get_call_trace_with_location")
+        .with_arg("tx_hash", &tx_hash)
+        .fetch_optional(self.storage)
+        .await?;
+        Ok(row.map(LocalizedCallTrace::into_parts))
+    }
+
     pub(crate) async fn get_tx_by_hash(&mut self, hash: H256) -> Option<Transaction> {
         sqlx::query_as!(
             StorageTransaction,
@@ -1319,7 +1499,8 @@ impl TransactionsDal<'_, '_> {
             "#,
             hash.as_bytes()
         )
-        .fetch_optional(self.storage.conn())
+        .instrument("get_tx_by_hash")
+        .fetch_optional(self.storage)
         .await
         .unwrap()
         .map(|tx| tx.into())
@@ -1373,4 +1554,43 @@ mod tests {
             .expect("no call trace");
         assert_eq!(call_trace, expected_call_trace);
     }
+
+    #[tokio::test]
+    async fn removing_pending_l2_transaction() {
+        let connection_pool = ConnectionPool::test_pool().await;
+        let mut conn = connection_pool.access_storage().await.unwrap();
+        let tx = mock_l2_transaction();
+        let tx_hash = tx.hash();
+        conn.transactions_dal()
+            .insert_transaction_l2(tx.clone(), TransactionExecutionMetrics::default())
+            .await;
+
+        let nonce = conn
+            .transactions_dal()
+            .get_pending_l2_tx_nonce(tx_hash)
+            .await
+            .unwrap();
+        assert_eq!(nonce, Some((tx.initiator_account(), tx.nonce())));
+
+        // The transaction isn't loaded into the mempool.
+        let removed = conn
+            .transactions_dal()
+            .remove_pending_l2_tx(tx_hash, true)
+            .await
+            .unwrap();
+        assert!(!removed);
+        let removed = conn
+            .transactions_dal()
+            .remove_pending_l2_tx(tx_hash, false)
+            .await
+            .unwrap();
+        assert!(removed);
+
+        let nonce = conn
+            .transactions_dal()
+            .get_pending_l2_tx_nonce(tx_hash)
+            .await
+            .unwrap();
+        assert_eq!(nonce, None);
+    }
 }
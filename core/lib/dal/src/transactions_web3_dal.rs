@@ -1,7 +1,9 @@
+use anyhow::Context as _;
 use sqlx::types::chrono::NaiveDateTime;
 use zksync_types::{
-    api, api::TransactionReceipt, Address, L2ChainId, MiniblockNumber, Transaction,
-    ACCOUNT_CODE_STORAGE_ADDRESS, FAILED_CONTRACT_DEPLOYMENT_BYTECODE_HASH, H256, U256,
+    api, api::TransactionReceipt, Address, L2ChainId, MiniblockNumber, Nonce, PriorityOpId,
+    Transaction, ACCOUNT_CODE_STORAGE_ADDRESS, FAILED_CONTRACT_DEPLOYMENT_BYTECODE_HASH, H256,
+    U256,
 };
 
 use crate::{
@@ -10,7 +12,7 @@ use crate::{
         storage_block::{bind_block_where_sql_params, web3_block_where_sql},
         storage_transaction::{
             extract_web3_transaction, web3_transaction_select_sql, StorageTransaction,
-            StorageTransactionDetails, StorageTransactionReceipt,
+            StorageTransactionDetails, StorageTransactionExecutionInfo, StorageTransactionReceipt,
         },
     },
     SqlxError, StorageProcessor,
@@ -76,7 +78,8 @@ impl TransactionsWeb3Dal<'_, '_> {
                 .map(|h| h.as_bytes().to_vec())
                 .collect::<Vec<_>>()[..]
         )
-        .fetch_all(self.storage.conn())
+        .instrument("get_transaction_receipts")
+        .fetch_all(self.storage)
         .await?
         .into_iter()
         .map(Into::into)
@@ -160,7 +163,9 @@ impl TransactionsWeb3Dal<'_, '_> {
         };
 
         let tx = query
-            .fetch_optional(self.storage.conn())
+            .instrument("get_transaction")
+            .with_arg("transaction_id", &transaction_id)
+            .fetch_optional(self.storage)
             .await?
             .map(|row| extract_web3_transaction(row, chain_id));
         Ok(tx)
@@ -219,6 +224,38 @@ impl TransactionsWeb3Dal<'_, '_> {
         }
     }
 
+    /// Returns execution metrics collected by the VM for an executed transaction. Returns `None` if the transaction
+    /// is unknown or is not executed yet.
+    pub async fn get_transaction_execution_info(
+        &mut self,
+        hash: H256,
+    ) -> anyhow::Result<Option<api::TransactionExecutionInfo>> {
+        let Some(row) = sqlx::query!(
+            r#"
+            SELECT
+                execution_info
+            FROM
+                transactions
+            WHERE
+                hash = $1
+                AND miniblock_number IS NOT NULL
+            "#,
+            hash.as_bytes()
+        )
+        .instrument("get_transaction_execution_info")
+        .with_arg("hash", &hash)
+        .fetch_optional(self.storage)
+        .await?
+        else {
+            return Ok(None);
+        };
+
+        let execution_info: StorageTransactionExecutionInfo =
+            serde_json::from_value(row.execution_info)
+                .context("invalid value for execution_info in the DB")?;
+        Ok(Some(execution_info.into()))
+    }
+
     /// Returns hashes of txs which were received after `from_timestamp` and the time of receiving the last tx.
     pub async fn get_pending_txs_hashes_after(
         &mut self,
@@ -243,7 +280,9 @@ impl TransactionsWeb3Dal<'_, '_> {
             from_timestamp,
             limit.map(|limit| limit as i64)
         )
-        .fetch_all(self.storage.conn())
+        .instrument("get_pending_txs_hashes_after")
+        .with_arg("limit", &limit)
+        .fetch_all(self.storage)
         .await?;
 
         let last_loc = records.last().map(|record| record.received_at);
@@ -254,6 +293,145 @@ impl TransactionsWeb3Dal<'_, '_> {
         Ok((hashes, last_loc))
     }
 
+    /// Returns priority operations with serial IDs greater than `after_id` (all priority operations if `after_id`
+    /// is `None`), ordered by serial ID.
+    pub async fn get_priority_ops_after(
+        &mut self,
+        after_id: Option<PriorityOpId>,
+        limit: usize,
+    ) -> sqlx::Result<Vec<api::PriorityOpInfo>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                priority_op_id AS "priority_op_id!",
+                hash,
+                l1_block_number
+            FROM
+                transactions
+            WHERE
+                is_priority = TRUE
+                AND priority_op_id > $1
+            ORDER BY
+                priority_op_id
+            LIMIT
+                $2
+            "#,
+            after_id.map_or(-1, |id| id.0 as i64),
+            limit as i64
+        )
+        .instrument("get_priority_ops_after")
+        .with_arg("after_id", &after_id)
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| api::PriorityOpInfo {
+                serial_id: row.priority_op_id as u64,
+                tx_hash: H256::from_slice(&row.hash),
+                eth_block: row.l1_block_number.map(|number| number as u64),
+            })
+            .collect())
+    }
+
+    /// Returns priority operations that are not yet included into a miniblock, ordered by serial ID.
+    pub async fn get_queued_priority_ops(
+        &mut self,
+        limit: usize,
+    ) -> sqlx::Result<Vec<api::PriorityOpInfo>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                priority_op_id AS "priority_op_id!",
+                hash,
+                l1_block_number
+            FROM
+                transactions
+            WHERE
+                is_priority = TRUE
+                AND miniblock_number IS NULL
+            ORDER BY
+                priority_op_id
+            LIMIT
+                $1
+            "#,
+            limit as i64
+        )
+        .instrument("get_queued_priority_ops")
+        .with_arg("limit", &limit)
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| api::PriorityOpInfo {
+                serial_id: row.priority_op_id as u64,
+                tx_hash: H256::from_slice(&row.hash),
+                eth_block: row.l1_block_number.map(|number| number as u64),
+            })
+            .collect())
+    }
+
+    /// Returns initiator addresses and nonces of all L2 transactions in the mempool (i.e., ones that are
+    /// neither included into a miniblock nor rejected).
+    pub async fn get_mempool_nonces(&mut self) -> sqlx::Result<Vec<(Address, Nonce)>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                initiator_address,
+                nonce AS "nonce!"
+            FROM
+                transactions
+            WHERE
+                is_priority = FALSE
+                AND miniblock_number IS NULL
+                AND error IS NULL
+            "#
+        )
+        .instrument("get_mempool_nonces")
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                (
+                    Address::from_slice(&row.initiator_address),
+                    Nonce(row.nonce as u32),
+                )
+            })
+            .collect())
+    }
+
+    /// Returns L2 transactions in the mempool ordered by the initiator address and nonce.
+    pub async fn get_mempool_transactions(
+        &mut self,
+        limit: usize,
+        chain_id: L2ChainId,
+    ) -> sqlx::Result<Vec<api::Transaction>> {
+        let query = format!(
+            "SELECT {}
+            FROM transactions
+            LEFT JOIN miniblocks ON miniblocks.number = transactions.miniblock_number
+            WHERE transactions.is_priority = FALSE
+                AND transactions.miniblock_number IS NULL
+                AND transactions.error IS NULL
+            ORDER BY transactions.initiator_address, transactions.nonce
+            LIMIT $1",
+            web3_transaction_select_sql()
+        );
+        let rows = sqlx::query(&query)
+            .bind(limit as i64)
+            .instrument("get_mempool_transactions")
+            .with_arg("limit", &limit)
+            .fetch_all(self.storage)
+            .await?;
+        Ok(rows
+            .into_iter()
+            .map(|row| extract_web3_transaction(row, chain_id))
+            .collect())
+    }
+
     /// `committed_next_nonce` should equal the nonce for `initiator_address` in the storage.
     pub async fn next_nonce_by_initiator_account(
         &mut self,
@@ -285,7 +463,10 @@ impl TransactionsWeb3Dal<'_, '_> {
             initiator_address.as_bytes(),
             committed_next_nonce as i64
         )
-        .fetch_all(self.storage.conn())
+        .instrument("next_nonce_by_initiator_account")
+        .with_arg("initiator_address", &initiator_address)
+        .with_arg("committed_next_nonce", &committed_next_nonce)
+        .fetch_all(self.storage)
         .await?
         .into_iter()
         .map(|row| row.nonce as u64)
@@ -324,7 +505,8 @@ impl TransactionsWeb3Dal<'_, '_> {
             "#,
             miniblock.0 as i64
         )
-        .fetch_all(self.storage.conn())
+        .instrument("get_raw_miniblock_transactions")
+        .fetch_all(self.storage)
         .await?;
 
         Ok(rows.into_iter().map(Into::into).collect())
@@ -336,8 +518,8 @@ mod tests {
     use std::collections::HashMap;
 
     use zksync_types::{
-        block::MiniblockHasher, fee::TransactionExecutionMetrics, l2::L2Tx, Nonce, ProtocolVersion,
-        ProtocolVersionId,
+        block::MiniblockHasher, fee::TransactionExecutionMetrics, l2::L2Tx, tx::ExecutionMetrics,
+        Nonce, ProtocolVersion, ProtocolVersionId,
     };
 
     use super::*;
@@ -593,4 +775,55 @@ mod tests {
             .unwrap();
         assert_eq!(next_nonce, 2.into());
     }
+
+    #[tokio::test]
+    async fn getting_transaction_execution_info() {
+        let connection_pool = ConnectionPool::test_pool().await;
+        let mut conn = connection_pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        let tx = mock_l2_transaction();
+        let tx_hash = tx.hash();
+        conn.transactions_dal()
+            .insert_transaction_l2(tx.clone(), TransactionExecutionMetrics::default())
+            .await;
+
+        // The transaction is not executed yet.
+        let execution_info = conn
+            .transactions_web3_dal()
+            .get_transaction_execution_info(tx_hash)
+            .await
+            .unwrap();
+        assert_eq!(execution_info, None);
+
+        conn.blocks_dal()
+            .insert_miniblock(&create_miniblock_header(1))
+            .await
+            .unwrap();
+        let mut tx_result = mock_execution_result(tx);
+        tx_result.execution_info = ExecutionMetrics {
+            gas_used: 100_000,
+            computational_gas_used: 60_000,
+            pubdata_published: 200,
+            storage_reads: 10,
+            storage_writes: 3,
+            ..ExecutionMetrics::default()
+        };
+        conn.transactions_dal()
+            .mark_txs_as_executed_in_miniblock(MiniblockNumber(1), &[tx_result], U256::from(1))
+            .await;
+
+        let execution_info = conn
+            .transactions_web3_dal()
+            .get_transaction_execution_info(tx_hash)
+            .await
+            .unwrap()
+            .expect("no execution info");
+        assert_eq!(execution_info.gas_used, 100_000);
+        assert_eq!(execution_info.computational_gas_used, 60_000);
+        assert_eq!(execution_info.pubdata_published, 200);
+        assert_eq!(execution_info.storage_reads, Some(10));
+        assert_eq!(execution_info.storage_writes, Some(3));
+    }
 }
@@ -1,7 +1,9 @@
 use anyhow::Context as _;
 use zksync_config::configs::{
     api::{
-        ContractVerificationApiConfig, HealthCheckConfig, MerkleTreeApiConfig, Web3JsonRpcConfig,
+        AdminApiConfig, ApiAuditLogConfig, ApiRateLimitConfig, ContractVerificationApiConfig,
+        GrpcApiConfig, HealthCheckConfig, MerkleTreeApiConfig, RpcInterfaceConfig,
+        Web3JsonRpcConfig,
     },
     ApiConfig, PrometheusConfig,
 };
@@ -17,6 +19,12 @@ impl FromEnv for ApiConfig {
             prometheus: PrometheusConfig::from_env().context("PrometheusConfig")?,
             healthcheck: HealthCheckConfig::from_env().context("HealthCheckConfig")?,
             merkle_tree: MerkleTreeApiConfig::from_env().context("MerkleTreeApiConfig")?,
+            rate_limit: ApiRateLimitConfig::from_env().context("ApiRateLimitConfig")?,
+            grpc: GrpcApiConfig::from_env().context("GrpcApiConfig")?,
+            http_interface: envy_load("http_interface", "API_HTTP_INTERFACE_")?,
+            ws_interface: envy_load("ws_interface", "API_WS_INTERFACE_")?,
+            audit_log: ApiAuditLogConfig::from_env().context("ApiAuditLogConfig")?,
+            admin: AdminApiConfig::from_env().context("AdminApiConfig")?,
         })
     }
 }
@@ -46,6 +54,30 @@ impl FromEnv for MerkleTreeApiConfig {
     }
 }
 
+impl FromEnv for ApiRateLimitConfig {
+    fn from_env() -> anyhow::Result<Self> {
+        envy_load("rate_limit", "API_RATE_LIMIT_")
+    }
+}
+
+impl FromEnv for ApiAuditLogConfig {
+    fn from_env() -> anyhow::Result<Self> {
+        envy_load("audit_log", "API_AUDIT_LOG_")
+    }
+}
+
+impl FromEnv for GrpcApiConfig {
+    fn from_env() -> anyhow::Result<Self> {
+        envy_load("grpc_api", "API_GRPC_")
+    }
+}
+
+impl FromEnv for AdminApiConfig {
+    fn from_env() -> anyhow::Result<Self> {
+        envy_load("admin_api", "API_ADMIN_")
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::num::NonZeroU32;
@@ -67,6 +99,7 @@ mod tests {
                 subscriptions_limit: Some(10000),
                 pubsub_polling_interval: Some(200),
                 max_nonce_ahead: 5,
+                tx_replacement_fee_bump_percent: Some(15),
                 request_timeout: Some(10),
                 account_pks: Some(vec![
                     hash("0x0000000000000000000000000000000000000000000000000000000000000001"),
@@ -82,9 +115,12 @@ mod tests {
                 factory_deps_cache_size_mb: Some(128),
                 initial_writes_cache_size_mb: Some(32),
                 latest_values_cache_size_mb: Some(256),
+                response_cache_size_mb: Some(64),
                 fee_history_limit: Some(100),
                 max_batch_request_size: Some(200),
                 max_response_body_size_mb: Some(10),
+                max_request_body_size_mb: Some(5),
+                compress_responses: Some(false),
                 websocket_requests_per_minute_limit: Some(NonZeroU32::new(10).unwrap()),
                 tree_api_url: None,
             },
@@ -96,9 +132,45 @@ mod tests {
                 listener_port: 3312,
                 pushgateway_url: "http://127.0.0.1:9091".into(),
                 push_interval_ms: Some(100),
+                pushgateway_job: Some("zksync_server".into()),
+                pushgateway_labels: None,
+            },
+            healthcheck: HealthCheckConfig {
+                port: 8081,
+                max_sealed_l1_batch_age_sec: Some(600),
+                max_tree_lag: Some(10),
             },
-            healthcheck: HealthCheckConfig { port: 8081 },
             merkle_tree: MerkleTreeApiConfig { port: 8082 },
+            rate_limit: ApiRateLimitConfig {
+                requests_per_minute: Some(NonZeroU32::new(600).unwrap()),
+                api_key_header: Some("X-Api-Key".into()),
+                method_weights: vec!["eth_getLogs:10".into(), "eth_call:5".into()],
+                method_concurrency_limits: vec!["debug_traceCall:4".into()],
+            },
+            grpc: GrpcApiConfig {
+                port: 3080,
+                polling_interval_ms: 500,
+            },
+            http_interface: RpcInterfaceConfig {
+                namespaces: Some(vec!["eth".into(), "net".into(), "debug".into()]),
+                allowed_methods: None,
+                denied_methods: vec!["debug_traceCall".into()],
+            },
+            ws_interface: RpcInterfaceConfig {
+                namespaces: None,
+                allowed_methods: Some(vec!["eth_*".into(), "net_version".into()]),
+                denied_methods: vec![],
+            },
+            audit_log: ApiAuditLogConfig {
+                sampling_ratio: Some(0.1),
+                api_key_header: Some("X-Api-Key".into()),
+                redact_client_ids: false,
+                file_path: None,
+            },
+            admin: AdminApiConfig {
+                port: 3090,
+                auth_token: Some("secret".into()),
+            },
         }
     }
 
@@ -115,6 +187,7 @@ mod tests {
             API_WEB3_JSON_RPC_SUBSCRIPTIONS_LIMIT=10000
             API_WEB3_JSON_RPC_PUBSUB_POLLING_INTERVAL=200
             API_WEB3_JSON_RPC_MAX_NONCE_AHEAD=5
+            API_WEB3_JSON_RPC_TX_REPLACEMENT_FEE_BUMP_PERCENT=15
             API_WEB3_JSON_RPC_GAS_PRICE_SCALE_FACTOR=1.2
             API_WEB3_JSON_RPC_REQUEST_TIMEOUT=10
             API_WEB3_JSON_RPC_ACCOUNT_PKS="0x0000000000000000000000000000000000000000000000000000000000000001,0x0000000000000000000000000000000000000000000000000000000000000002"
@@ -126,17 +199,37 @@ mod tests {
             API_WEB3_JSON_RPC_FACTORY_DEPS_CACHE_SIZE_MB=128
             API_WEB3_JSON_RPC_INITIAL_WRITES_CACHE_SIZE_MB=32
             API_WEB3_JSON_RPC_LATEST_VALUES_CACHE_SIZE_MB=256
+            API_WEB3_JSON_RPC_RESPONSE_CACHE_SIZE_MB=64
             API_WEB3_JSON_RPC_FEE_HISTORY_LIMIT=100
             API_WEB3_JSON_RPC_MAX_BATCH_REQUEST_SIZE=200
             API_WEB3_JSON_RPC_WEBSOCKET_REQUESTS_PER_MINUTE_LIMIT=10
             API_CONTRACT_VERIFICATION_PORT="3070"
             API_CONTRACT_VERIFICATION_URL="http://127.0.0.1:3070"
             API_WEB3_JSON_RPC_MAX_RESPONSE_BODY_SIZE_MB=10
+            API_WEB3_JSON_RPC_MAX_REQUEST_BODY_SIZE_MB=5
+            API_WEB3_JSON_RPC_COMPRESS_RESPONSES=false
             API_PROMETHEUS_LISTENER_PORT="3312"
             API_PROMETHEUS_PUSHGATEWAY_URL="http://127.0.0.1:9091"
             API_PROMETHEUS_PUSH_INTERVAL_MS=100
+            API_PROMETHEUS_PUSHGATEWAY_JOB="zksync_server"
             API_HEALTHCHECK_PORT=8081
+            API_HEALTHCHECK_MAX_SEALED_L1_BATCH_AGE_SEC=10m
+            API_HEALTHCHECK_MAX_TREE_LAG=10
             API_MERKLE_TREE_PORT=8082
+            API_RATE_LIMIT_REQUESTS_PER_MINUTE=600
+            API_RATE_LIMIT_API_KEY_HEADER="X-Api-Key"
+            API_RATE_LIMIT_METHOD_WEIGHTS="eth_getLogs:10,eth_call:5"
+            API_RATE_LIMIT_METHOD_CONCURRENCY_LIMITS="debug_traceCall:4"
+            API_GRPC_PORT=3080
+            API_GRPC_POLLING_INTERVAL_MS=500
+            API_HTTP_INTERFACE_NAMESPACES="eth,net,debug"
+            API_HTTP_INTERFACE_DENIED_METHODS="debug_traceCall"
+            API_WS_INTERFACE_ALLOWED_METHODS="eth_*,net_version"
+            API_AUDIT_LOG_SAMPLING_RATIO=0.1
+            API_AUDIT_LOG_API_KEY_HEADER="X-Api-Key"
+            API_AUDIT_LOG_REDACT_CLIENT_IDS=false
+            API_ADMIN_PORT=3090
+            API_ADMIN_AUTH_TOKEN="secret"
         "#;
         lock.set_env(config);
 
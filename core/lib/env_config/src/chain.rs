@@ -1,5 +1,6 @@
 use zksync_config::configs::chain::{
-    CircuitBreakerConfig, MempoolConfig, NetworkConfig, OperationsManagerConfig, StateKeeperConfig,
+    BaseTokenConfig, CircuitBreakerConfig, FeeInputConfig, MempoolConfig, NetworkConfig,
+    OperationsManagerConfig, StateKeeperConfig,
 };
 
 use crate::{envy_load, FromEnv};
@@ -34,10 +35,24 @@ impl FromEnv for MempoolConfig {
     }
 }
 
+impl FromEnv for BaseTokenConfig {
+    fn from_env() -> anyhow::Result<Self> {
+        envy_load("base_token", "CHAIN_BASE_TOKEN_")
+    }
+}
+
+impl FromEnv for FeeInputConfig {
+    fn from_env() -> anyhow::Result<Self> {
+        envy_load("fee_input", "CHAIN_FEE_INPUT_")
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use zksync_basic_types::L2ChainId;
-    use zksync_config::configs::chain::FeeModelVersion;
+    use zksync_config::configs::chain::{
+        BaseTokenRatioSource, FeeInputSource, FeeModelVersion, TransactionOrdering,
+    };
 
     use super::*;
     use crate::test_utils::{addr, EnvMutex};
@@ -94,6 +109,10 @@ mod tests {
             virtual_blocks_per_miniblock: 1,
             upload_witness_inputs_to_gcs: false,
             enum_index_migration_chunk_size: Some(2_000),
+            disabled_seal_criteria: vec!["pub_data_size".to_owned(), "tx_encoding_size".to_owned()],
+            transaction_ordering: TransactionOrdering::FeePriority,
+            max_l1_batches_not_committed: Some(100),
+            max_l1_batches_not_proven: Some(500),
         }
     }
 
@@ -125,6 +144,10 @@ mod tests {
             CHAIN_STATE_KEEPER_SAVE_CALL_TRACES="false"
             CHAIN_STATE_KEEPER_UPLOAD_WITNESS_INPUTS_TO_GCS="false"
             CHAIN_STATE_KEEPER_ENUM_INDEX_MIGRATION_CHUNK_SIZE="2000"
+            CHAIN_STATE_KEEPER_DISABLED_SEAL_CRITERIA="pub_data_size,tx_encoding_size"
+            CHAIN_STATE_KEEPER_TRANSACTION_ORDERING="FeePriority"
+            CHAIN_STATE_KEEPER_MAX_L1_BATCHES_NOT_COMMITTED="100"
+            CHAIN_STATE_KEEPER_MAX_L1_BATCHES_NOT_PROVEN="500"
             CHAIN_STATE_KEEPER_VIRTUAL_BLOCKS_PER_MINIBLOCK="1"
             CHAIN_STATE_KEEPER_VIRTUAL_BLOCKS_INTERVAL="1"
         "#;
@@ -160,6 +183,8 @@ mod tests {
             stuck_tx_timeout: 10,
             remove_stuck_txs: true,
             delay_interval: 100,
+            max_transactions_per_account: Some(64),
+            max_memory_mb: Some(512),
         }
     }
 
@@ -173,6 +198,8 @@ mod tests {
             CHAIN_MEMPOOL_REMOVE_STUCK_TXS="true"
             CHAIN_MEMPOOL_DELAY_INTERVAL="100"
             CHAIN_MEMPOOL_CAPACITY="1000000"
+            CHAIN_MEMPOOL_MAX_TRANSACTIONS_PER_ACCOUNT="64"
+            CHAIN_MEMPOOL_MAX_MEMORY_MB="512"
         "#;
         lock.set_env(config);
 
@@ -180,6 +207,69 @@ mod tests {
         assert_eq!(actual, expected_mempool_config());
     }
 
+    #[test]
+    fn base_token_from_env() {
+        let mut lock = MUTEX.lock();
+        let config = r#"
+            CHAIN_BASE_TOKEN_L1_ADDRESS="0x5e6d086f5ec079adff4fb3774cdf3e8d6a34f7e9"
+            CHAIN_BASE_TOKEN_RATIO_SOURCE="Http"
+            CHAIN_BASE_TOKEN_RATIO_NUMERATOR="3"
+            CHAIN_BASE_TOKEN_RATIO_URL="http://127.0.0.1:8080/ratio"
+            CHAIN_BASE_TOKEN_RATIO_POLL_INTERVAL_MS="5000"
+        "#;
+        lock.set_env(config);
+
+        let actual = BaseTokenConfig::from_env().unwrap();
+        let expected = BaseTokenConfig {
+            l1_address: Some(addr("5e6d086f5ec079adff4fb3774cdf3e8d6a34f7e9")),
+            ratio_source: BaseTokenRatioSource::Http,
+            ratio_numerator: 3,
+            ratio_denominator: 1,
+            ratio_url: Some("http://127.0.0.1:8080/ratio".to_owned()),
+            ratio_poll_interval_ms: Some(5_000),
+        };
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn fee_input_from_env() {
+        let mut lock = MUTEX.lock();
+        let config = r#"
+            CHAIN_FEE_INPUT_L1_GAS_PRICE_SOURCE="Oracle"
+            CHAIN_FEE_INPUT_L1_GAS_PRICE_SMOOTHING_ALPHA="0.5"
+            CHAIN_FEE_INPUT_L1_GAS_PRICE_MAX="100000000000"
+            CHAIN_FEE_INPUT_L1_PUBDATA_PRICE_SOURCE="Http"
+            CHAIN_FEE_INPUT_L1_PUBDATA_PRICE_URL="http://127.0.0.1:8080/pubdata"
+            CHAIN_FEE_INPUT_L1_PUBDATA_PRICE_JSON_POINTER="/result/price"
+            CHAIN_FEE_INPUT_L1_PUBDATA_PRICE_MULTIPLIER="1e9"
+            CHAIN_FEE_INPUT_L1_PUBDATA_PRICE_MIN="1000000000"
+            CHAIN_FEE_INPUT_POLL_INTERVAL_MS="2000"
+        "#;
+        lock.set_env(config);
+
+        let actual = FeeInputConfig::from_env().unwrap();
+        let expected = FeeInputConfig {
+            l1_gas_price_source: FeeInputSource::Oracle,
+            l1_gas_price_fixed_value: None,
+            l1_gas_price_url: None,
+            l1_gas_price_json_pointer: None,
+            l1_gas_price_multiplier: None,
+            l1_gas_price_smoothing_alpha: Some(0.5),
+            l1_gas_price_min: None,
+            l1_gas_price_max: Some(100_000_000_000),
+            l1_pubdata_price_source: FeeInputSource::Http,
+            l1_pubdata_price_fixed_value: None,
+            l1_pubdata_price_url: Some("http://127.0.0.1:8080/pubdata".to_owned()),
+            l1_pubdata_price_json_pointer: Some("/result/price".to_owned()),
+            l1_pubdata_price_multiplier: Some(1e9),
+            l1_pubdata_price_smoothing_alpha: None,
+            l1_pubdata_price_min: Some(1_000_000_000),
+            l1_pubdata_price_max: None,
+            poll_interval_ms: Some(2_000),
+        };
+        assert_eq!(actual, expected);
+    }
+
     fn expected_circuit_breaker_config() -> CircuitBreakerConfig {
         CircuitBreakerConfig {
             sync_interval_ms: 1000,
@@ -0,0 +1,167 @@
+use zksync_config::validation::{ConfigError, ConfigErrorKind, ConfigErrors, Validate};
+
+use crate::FromEnv;
+
+/// Loads multiple configs from the environment, collecting *all* loading and validation errors
+/// instead of failing on the first one.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use zksync_config::configs::{api::Web3JsonRpcConfig, chain::StateKeeperConfig};
+/// # use zksync_env_config::ConfigCollector;
+/// # fn main() -> anyhow::Result<()> {
+/// let mut collector = ConfigCollector::default();
+/// let web3_json_rpc: Option<Web3JsonRpcConfig> = collector.optional("web3_json_rpc");
+/// let state_keeper: Option<StateKeeperConfig> = collector.required("state_keeper");
+/// collector.validate(web3_json_rpc.as_ref());
+/// collector.validate(state_keeper.as_ref());
+/// collector.finish()?; // Reports errors for both configs, if any.
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Default)]
+pub struct ConfigCollector {
+    errors: ConfigErrors,
+}
+
+impl ConfigCollector {
+    /// Loads a required config. Returns `None` and records an error if the config is missing
+    /// or contains invalid values.
+    pub fn required<C: FromEnv>(&mut self, name: &str) -> Option<C> {
+        match C::from_env() {
+            Ok(config) => Some(config),
+            Err(err) => {
+                self.record_loading_error(name, &err);
+                None
+            }
+        }
+    }
+
+    /// Loads an optional config. Unlike [`Self::required()`], a missing config (or a field in it) is not considered
+    /// an error; however, an error is still recorded if the config contains invalid values.
+    pub fn optional<C: FromEnv>(&mut self, name: &str) -> Option<C> {
+        match C::from_env() {
+            Ok(config) => Some(config),
+            Err(err) if missing_field(&err).is_some() => None,
+            Err(err) => {
+                self.record_loading_error(name, &err);
+                None
+            }
+        }
+    }
+
+    /// Validates the config if it was successfully loaded.
+    pub fn validate<C: Validate>(&mut self, config: Option<&C>) {
+        if let Some(config) = config {
+            config.validate(&mut self.errors);
+        }
+    }
+
+    fn record_loading_error(&mut self, name: &str, err: &anyhow::Error) {
+        self.errors.push(if let Some(field) = missing_field(err) {
+            ConfigError {
+                config: name.to_owned(),
+                field: Some(field.to_owned()),
+                kind: ConfigErrorKind::Missing,
+                message: "value is not set".to_owned(),
+            }
+        } else {
+            ConfigError {
+                config: name.to_owned(),
+                field: None,
+                kind: ConfigErrorKind::Invalid,
+                message: format!("{err:#}"),
+            }
+        });
+    }
+
+    /// Returns all collected errors, if any.
+    pub fn finish(self) -> Result<(), ConfigErrors> {
+        self.errors.into_result()
+    }
+}
+
+fn missing_field(err: &anyhow::Error) -> Option<&'static str> {
+    err.chain()
+        .find_map(|err| match err.downcast_ref::<envy::Error>() {
+            Some(envy::Error::MissingValue(field)) => Some(*field),
+            _ => None,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    use super::*;
+    use crate::{envy_load, test_utils::EnvMutex};
+
+    static MUTEX: EnvMutex = EnvMutex::new();
+
+    #[derive(Debug, Deserialize)]
+    struct TestConfig {
+        capacity: u64,
+        #[serde(default)]
+        batch_size: u32,
+    }
+
+    impl FromEnv for TestConfig {
+        fn from_env() -> anyhow::Result<Self> {
+            envy_load("test", "CONFIG_COLLECTOR_TEST_")
+        }
+    }
+
+    impl Validate for TestConfig {
+        fn validate(&self, errors: &mut ConfigErrors) {
+            errors.ensure(self.capacity > 0, "test", "capacity", || {
+                "must be positive".to_owned()
+            });
+            errors.ensure(self.batch_size > 0, "test", "batch_size", || {
+                "must be positive".to_owned()
+            });
+        }
+    }
+
+    #[test]
+    fn collecting_missing_configs() {
+        let mut lock = MUTEX.lock();
+        lock.remove_env(&["CONFIG_COLLECTOR_TEST_CAPACITY"]);
+        lock.set_env("CONFIG_COLLECTOR_TEST_BATCH_SIZE=10");
+
+        let mut collector = ConfigCollector::default();
+        assert!(collector.optional::<TestConfig>("test").is_none());
+        assert!(collector.errors.is_empty());
+
+        assert!(collector.required::<TestConfig>("test").is_none());
+        let errors = collector.finish().unwrap_err();
+        assert_eq!(errors.len(), 1);
+        let err = errors.iter().next().unwrap();
+        assert_eq!(err.config, "test");
+        assert_eq!(err.field.as_deref(), Some("capacity"));
+        assert_eq!(err.kind, ConfigErrorKind::Missing);
+    }
+
+    #[test]
+    fn collecting_invalid_configs() {
+        let mut lock = MUTEX.lock();
+        lock.set_env("CONFIG_COLLECTOR_TEST_CAPACITY=what");
+
+        let mut collector = ConfigCollector::default();
+        assert!(collector.optional::<TestConfig>("test").is_none());
+
+        lock.set_env("CONFIG_COLLECTOR_TEST_CAPACITY=0");
+        lock.remove_env(&["CONFIG_COLLECTOR_TEST_BATCH_SIZE"]);
+        let config = collector.optional::<TestConfig>("test");
+        assert!(config.is_some());
+        collector.validate(config.as_ref());
+
+        let errors = collector.finish().unwrap_err();
+        let errors: Vec<_> = errors.iter().collect();
+        assert_eq!(errors.len(), 3, "{errors:?}");
+        assert_eq!(errors[0].kind, ConfigErrorKind::Invalid);
+        assert!(errors[0].message.contains("what"), "{errors:?}");
+        assert_eq!(errors[1].field.as_deref(), Some("capacity"));
+        assert_eq!(errors[2].field.as_deref(), Some("batch_size"));
+    }
+}
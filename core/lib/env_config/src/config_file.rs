@@ -0,0 +1,268 @@
+//! Loading configuration from YAML / TOML files.
+//!
+//! A config file mirrors the env variable layout: nested sections are joined with `_` and uppercased
+//! to obtain the name of the corresponding env variable. For example,
+//!
+//! ```yaml
+//! api:
+//!   web3_json_rpc:
+//!     http_port: 3050
+//! ```
+//!
+//! is equivalent to `API_WEB3_JSON_RPC_HTTP_PORT=3050`. Lists of scalars are joined with commas,
+//! which is the format expected for list values by [`envy`].
+
+use std::{collections::BTreeMap, env, fs, path::Path};
+
+use anyhow::Context as _;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+/// Format of a config file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigFileFormat {
+    Yaml,
+    Toml,
+}
+
+impl ConfigFileFormat {
+    /// Infers the file format from the file extension.
+    pub fn from_path(path: &Path) -> anyhow::Result<Self> {
+        let extension = path.extension().and_then(|ext| ext.to_str());
+        match extension {
+            Some("yaml" | "yml") => Ok(Self::Yaml),
+            Some("toml") => Ok(Self::Toml),
+            _ => anyhow::bail!(
+                "cannot infer config format for {path:?}; expected `.yaml`, `.yml` or `.toml` extension"
+            ),
+        }
+    }
+
+    /// Deserializes a value from contents in this format.
+    pub fn deserialize<T: DeserializeOwned>(self, contents: &str) -> anyhow::Result<T> {
+        Ok(match self {
+            Self::Yaml => serde_yaml::from_str(contents).context("invalid YAML")?,
+            Self::Toml => toml::from_str(contents).context("invalid TOML")?,
+        })
+    }
+}
+
+/// Source of config values loaded from a file. Values are stored with the names of env variables
+/// they correspond to, so that they can be consumed by the existing [`FromEnv`](crate::FromEnv) implementations.
+///
+/// Env variables always take precedence over the values from the file.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ConfigSource {
+    vars: BTreeMap<String, String>,
+}
+
+impl ConfigSource {
+    /// Reads the config file at the specified path. The format is inferred from the file extension.
+    pub fn from_file(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let format = ConfigFileFormat::from_path(path)?;
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("cannot read config file {path:?}"))?;
+        Self::parse(&contents, format).with_context(|| format!("invalid config file {path:?}"))
+    }
+
+    /// Parses config file contents in the specified format.
+    pub fn parse(contents: &str, format: ConfigFileFormat) -> anyhow::Result<Self> {
+        let value: Value = format.deserialize(contents)?;
+        let mut vars = BTreeMap::new();
+        match value {
+            Value::Object(map) => {
+                for (key, value) in map {
+                    flatten_value(&key.to_uppercase(), value, &mut vars)?;
+                }
+            }
+            Value::Null => { /* empty file */ }
+            _ => anyhow::bail!("config file must contain a map at the top level"),
+        }
+        Ok(Self { vars })
+    }
+
+    /// Returns all variables defined in the file.
+    pub fn vars(&self) -> &BTreeMap<String, String> {
+        &self.vars
+    }
+
+    /// Returns the value of the specified variable, taking env variables into account.
+    pub fn var(&self, name: &str) -> Option<String> {
+        env::var(name).ok().or_else(|| self.vars.get(name).cloned())
+    }
+
+    /// Exports variables from the file to the process environment, skipping variables that are already set.
+    /// Returns the number of exported variables.
+    ///
+    /// This should be called at the very start of a binary, before any config is loaded
+    /// and before any threads that may read the environment are spawned.
+    pub fn export_to_env(&self) -> usize {
+        let mut exported_count = 0;
+        for (name, value) in &self.vars {
+            if env::var_os(name).is_none() {
+                env::set_var(name, value);
+                exported_count += 1;
+            }
+        }
+        exported_count
+    }
+}
+
+fn flatten_value(
+    prefix: &str,
+    value: Value,
+    vars: &mut BTreeMap<String, String>,
+) -> anyhow::Result<()> {
+    let scalar = match value {
+        Value::Null => return Ok(()),
+        Value::Object(map) => {
+            for (key, value) in map {
+                let name = format!("{prefix}_{}", key.to_uppercase());
+                flatten_value(&name, value, vars)?;
+            }
+            return Ok(());
+        }
+        Value::Array(items) => {
+            let items: anyhow::Result<Vec<_>> = items
+                .into_iter()
+                .map(|item| {
+                    scalar_to_string(item).with_context(|| {
+                        format!("`{prefix}` must be a list of scalars to be mapped to an env var")
+                    })
+                })
+                .collect();
+            items?.join(",")
+        }
+        scalar => scalar_to_string(scalar).expect("`value` is a scalar"),
+    };
+
+    if vars.insert(prefix.to_owned(), scalar).is_some() {
+        anyhow::bail!("variable `{prefix}` is defined multiple times in the config file");
+    }
+    Ok(())
+}
+
+fn scalar_to_string(value: Value) -> Option<String> {
+    match value {
+        Value::Bool(value) => Some(value.to_string()),
+        Value::Number(value) => Some(value.to_string()),
+        Value::String(value) => Some(value),
+        Value::Null | Value::Array(_) | Value::Object(_) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::EnvMutex;
+
+    static MUTEX: EnvMutex = EnvMutex::new();
+
+    const YAML_CONFIG: &str = r#"
+        api:
+          healthcheck:
+            port: 8081
+            slow_time_limit_ms: null
+          web3_json_rpc:
+            http_url: "http://127.0.0.1:3050"
+            api_namespaces: [eth, net, web3]
+        chain:
+          state_keeper:
+            save_call_traces: true
+    "#;
+
+    const TOML_CONFIG: &str = r#"
+        [api.healthcheck]
+        port = 8081
+
+        [api.web3_json_rpc]
+        http_url = "http://127.0.0.1:3050"
+        api_namespaces = ["eth", "net", "web3"]
+
+        [chain.state_keeper]
+        save_call_traces = true
+    "#;
+
+    fn expected_vars() -> BTreeMap<String, String> {
+        [
+            ("API_HEALTHCHECK_PORT", "8081"),
+            ("API_WEB3_JSON_RPC_HTTP_URL", "http://127.0.0.1:3050"),
+            ("API_WEB3_JSON_RPC_API_NAMESPACES", "eth,net,web3"),
+            ("CHAIN_STATE_KEEPER_SAVE_CALL_TRACES", "true"),
+        ]
+        .into_iter()
+        .map(|(name, value)| (name.to_owned(), value.to_owned()))
+        .collect()
+    }
+
+    #[test]
+    fn parsing_yaml_config() {
+        let source = ConfigSource::parse(YAML_CONFIG, ConfigFileFormat::Yaml).unwrap();
+        assert_eq!(*source.vars(), expected_vars());
+    }
+
+    #[test]
+    fn parsing_toml_config() {
+        let source = ConfigSource::parse(TOML_CONFIG, ConfigFileFormat::Toml).unwrap();
+        assert_eq!(*source.vars(), expected_vars());
+    }
+
+    #[test]
+    fn parsing_invalid_config() {
+        let err = ConfigSource::parse("api:\n  list: [[1, 2]]", ConfigFileFormat::Yaml)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("API_LIST"), "{err}");
+
+        let err = ConfigSource::parse("- 1\n- 2", ConfigFileFormat::Yaml)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("top level"), "{err}");
+
+        // `API_HEALTHCHECK_PORT` is defined twice.
+        let yaml = "api:\n  healthcheck_port: 1\n  healthcheck:\n    port: 2";
+        let err = ConfigSource::parse(yaml, ConfigFileFormat::Yaml)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("multiple times"), "{err}");
+    }
+
+    #[test]
+    fn inferring_config_format() {
+        let format = ConfigFileFormat::from_path(Path::new("config.yml")).unwrap();
+        assert_eq!(format, ConfigFileFormat::Yaml);
+        let format = ConfigFileFormat::from_path(Path::new("/etc/zksync/config.toml")).unwrap();
+        assert_eq!(format, ConfigFileFormat::Toml);
+        ConfigFileFormat::from_path(Path::new("config.json")).unwrap_err();
+    }
+
+    #[test]
+    fn env_vars_override_file() {
+        let mut lock = MUTEX.lock();
+        // Vars exported from the file will be rolled back by the guard since they are removed via it.
+        lock.remove_env(&["CONFIG_FILE_TEST_PORT", "CONFIG_FILE_TEST_INTERVAL_MS"]);
+        lock.set_env("CONFIG_FILE_TEST_URL=http://127.0.0.1:9091");
+
+        let yaml = r#"
+            config_file_test:
+              port: 3312
+              url: "http://example.com:9091"
+        "#;
+        let source = ConfigSource::parse(yaml, ConfigFileFormat::Yaml).unwrap();
+        assert_eq!(
+            source.var("CONFIG_FILE_TEST_URL").unwrap(),
+            "http://127.0.0.1:9091"
+        );
+        assert_eq!(source.var("CONFIG_FILE_TEST_PORT").unwrap(), "3312");
+        assert_eq!(source.var("CONFIG_FILE_TEST_INTERVAL_MS"), None);
+
+        // `CONFIG_FILE_TEST_URL` is already set, so it shouldn't be exported.
+        assert_eq!(source.export_to_env(), 1);
+        assert_eq!(env::var("CONFIG_FILE_TEST_PORT").unwrap(), "3312");
+        assert_eq!(
+            env::var("CONFIG_FILE_TEST_URL").unwrap(),
+            "http://127.0.0.1:9091"
+        );
+    }
+}
@@ -1,16 +1,16 @@
-use std::{env, error, str::FromStr};
+use std::{error, str::FromStr};
 
 use anyhow::Context as _;
-use zksync_config::{DBConfig, PostgresConfig};
+use zksync_config::{units::Unit, DBConfig, PostgresConfig};
 
-use crate::{envy_load, FromEnv};
+use crate::{env_var, envy_load, FromEnv};
 
 fn parse_optional_var<T>(name: &str) -> anyhow::Result<Option<T>>
 where
     T: FromStr,
     T::Err: 'static + error::Error + Send + Sync,
 {
-    env::var(name)
+    env_var(name)
         .ok()
         .map(|val| {
             val.parse()
@@ -19,6 +19,19 @@ where
         .transpose()
 }
 
+/// Parses an optional duration or byte size, which can be specified either as a bare integer in the specified unit,
+/// or with an explicit unit (e.g., `30s`).
+fn parse_optional_var_in(name: &str, unit: Unit) -> anyhow::Result<Option<u64>> {
+    env_var(name)
+        .ok()
+        .map(|val| {
+            unit.parse(&val)
+                .map_err(anyhow::Error::msg)
+                .with_context(|| format!("failed to parse env variable {name}"))
+        })
+        .transpose()
+}
+
 impl FromEnv for DBConfig {
     fn from_env() -> anyhow::Result<Self> {
         Ok(Self {
@@ -30,23 +43,40 @@ impl FromEnv for DBConfig {
 
 impl FromEnv for PostgresConfig {
     fn from_env() -> anyhow::Result<Self> {
-        let master_url = env::var("DATABASE_URL").ok();
-        let replica_url = env::var("DATABASE_REPLICA_URL")
+        let master_url = env_var("DATABASE_URL").ok();
+        let replica_url = env_var("DATABASE_REPLICA_URL")
             .ok()
             .or_else(|| master_url.clone());
-        let prover_url = env::var("DATABASE_PROVER_URL")
+        let read_replica_urls = env_var("DATABASE_READ_REPLICA_URLS")
+            .ok()
+            .map(|urls| {
+                urls.split(',')
+                    .map(str::trim)
+                    .filter(|url| !url.is_empty())
+                    .map(str::to_owned)
+                    .collect()
+            })
+            .unwrap_or_default();
+        let max_replica_lag_ms =
+            parse_optional_var_in("DATABASE_MAX_REPLICA_LAG_MS", Unit::Millis)?;
+        let prover_url = env_var("DATABASE_PROVER_URL")
             .ok()
             .or_else(|| master_url.clone());
         let max_connections = parse_optional_var("DATABASE_POOL_SIZE")?;
-        let acquire_timeout_sec = parse_optional_var("DATABASE_ACQUIRE_TIMEOUT_SEC")?;
-        let statement_timeout_sec = parse_optional_var("DATABASE_STATEMENT_TIMEOUT_SEC")?;
+        let acquire_timeout_sec =
+            parse_optional_var_in("DATABASE_ACQUIRE_TIMEOUT_SEC", Unit::Seconds)?;
+        let statement_timeout_sec =
+            parse_optional_var_in("DATABASE_STATEMENT_TIMEOUT_SEC", Unit::Seconds)?;
         let long_connection_threshold_ms =
-            parse_optional_var("DATABASE_LONG_CONNECTION_THRESHOLD_MS")?;
-        let slow_query_threshold_ms = parse_optional_var("DATABASE_SLOW_QUERY_THRESHOLD_MS")?;
+            parse_optional_var_in("DATABASE_LONG_CONNECTION_THRESHOLD_MS", Unit::Millis)?;
+        let slow_query_threshold_ms =
+            parse_optional_var_in("DATABASE_SLOW_QUERY_THRESHOLD_MS", Unit::Millis)?;
 
         Ok(Self {
             master_url,
             replica_url,
+            read_replica_urls,
+            max_replica_lag_ms,
             prover_url,
             max_connections,
             acquire_timeout_sec,
@@ -61,7 +91,7 @@ impl FromEnv for PostgresConfig {
 mod tests {
     use std::time::Duration;
 
-    use zksync_config::configs::database::MerkleTreeMode;
+    use zksync_config::configs::database::{MerkleTreeMode, RocksdbCompactionStyle};
 
     use super::*;
     use crate::test_utils::EnvMutex;
@@ -73,23 +103,53 @@ mod tests {
         let mut lock = MUTEX.lock();
         let config = r#"
             DATABASE_STATE_KEEPER_DB_PATH="/db/state_keeper"
+            DATABASE_STATE_KEEPER_DB_BLOCK_CACHE_SIZE_MB=256
+            DATABASE_STATE_KEEPER_DB_MAX_BACKGROUND_JOBS=4
             DATABASE_MERKLE_TREE_PATH="/db/tree"
             DATABASE_MERKLE_TREE_MODE=lightweight
             DATABASE_MERKLE_TREE_MULTI_GET_CHUNK_SIZE=250
             DATABASE_MERKLE_TREE_MEMTABLE_CAPACITY_MB=512
             DATABASE_MERKLE_TREE_STALLED_WRITES_TIMEOUT_SEC=60
             DATABASE_MERKLE_TREE_MAX_L1_BATCHES_PER_ITER=50
+            DATABASE_MERKLE_TREE_PRUNING_RETAINED_VERSIONS=1000
+            DATABASE_MERKLE_TREE_THREAD_COUNT=8
+            DATABASE_MERKLE_TREE_CONSISTENCY_CHECK_INTERVAL_SEC=3600
+            DATABASE_MERKLE_TREE_COMPACTION_STYLE=universal
+            DATABASE_MERKLE_TREE_WRITE_BUFFER_SIZE_MB=64
+            DATABASE_MERKLE_TREE_ROCKSDB_STATISTICS=true
         "#;
         lock.set_env(config);
 
         let db_config = DBConfig::from_env().unwrap();
         assert_eq!(db_config.state_keeper_db_path, "/db/state_keeper");
+        assert_eq!(
+            db_config.state_keeper_db_block_cache_size(),
+            Some(256 * 1_024 * 1_024)
+        );
+        let state_keeper_db_tuning = db_config.state_keeper_db_tuning();
+        assert_eq!(state_keeper_db_tuning.max_background_jobs, Some(4));
+        assert_eq!(state_keeper_db_tuning.compaction_style, None);
+        assert!(!state_keeper_db_tuning.statistics);
         assert_eq!(db_config.merkle_tree.path, "/db/tree");
         assert_eq!(db_config.merkle_tree.mode, MerkleTreeMode::Lightweight);
         assert_eq!(db_config.merkle_tree.multi_get_chunk_size, 250);
         assert_eq!(db_config.merkle_tree.max_l1_batches_per_iter, 50);
         assert_eq!(db_config.merkle_tree.memtable_capacity_mb, 512);
         assert_eq!(db_config.merkle_tree.stalled_writes_timeout_sec, 60);
+        assert_eq!(db_config.merkle_tree.pruning_retained_versions, Some(1000));
+        assert_eq!(db_config.merkle_tree.thread_count, Some(8));
+        assert_eq!(
+            db_config.merkle_tree.consistency_check_interval(),
+            Some(Duration::from_secs(3600))
+        );
+        let tree_tuning = db_config.merkle_tree.rocksdb_tuning();
+        assert_eq!(
+            tree_tuning.compaction_style,
+            Some(RocksdbCompactionStyle::Universal)
+        );
+        assert_eq!(tree_tuning.write_buffer_size, Some(64 * 1_024 * 1_024));
+        assert_eq!(tree_tuning.max_background_jobs, None);
+        assert!(tree_tuning.statistics);
     }
 
     #[test]
@@ -105,6 +165,8 @@ mod tests {
             "DATABASE_MERKLE_TREE_MEMTABLE_CAPACITY_MB",
             "DATABASE_MERKLE_TREE_STALLED_WRITES_TIMEOUT_SEC",
             "DATABASE_MERKLE_TREE_MAX_L1_BATCHES_PER_ITER",
+            "DATABASE_MERKLE_TREE_PRUNING_RETAINED_VERSIONS",
+            "DATABASE_MERKLE_TREE_PRUNING_ITERATION_DELAY_MS",
         ]);
 
         let db_config = DBConfig::from_env().unwrap();
@@ -116,6 +178,13 @@ mod tests {
         assert_eq!(db_config.merkle_tree.block_cache_size_mb, 128);
         assert_eq!(db_config.merkle_tree.memtable_capacity_mb, 256);
         assert_eq!(db_config.merkle_tree.stalled_writes_timeout_sec, 30);
+        assert_eq!(db_config.merkle_tree.pruning_retained_versions, None);
+        assert_eq!(db_config.merkle_tree.pruning_iteration_delay_ms, 100);
+        assert_eq!(db_config.merkle_tree.thread_count, None);
+        assert_eq!(db_config.merkle_tree.consistency_check_interval_sec, None);
+        assert_eq!(db_config.merkle_tree.rocksdb_tuning(), Default::default());
+        assert_eq!(db_config.state_keeper_db_block_cache_size(), None);
+        assert_eq!(db_config.state_keeper_db_tuning(), Default::default());
 
         // Check that new env variable for Merkle tree path is supported
         lock.set_env("DATABASE_MERKLE_TREE_PATH=/db/tree/main");
@@ -135,6 +204,42 @@ mod tests {
         assert_eq!(db_config.merkle_tree.max_l1_batches_per_iter, 50);
     }
 
+    #[test]
+    fn from_env_with_units() {
+        let mut lock = MUTEX.lock();
+        let config = r#"
+            DATABASE_MERKLE_TREE_BLOCK_CACHE_SIZE_MB=1GiB
+            DATABASE_MERKLE_TREE_MEMTABLE_CAPACITY_MB="512 MiB"
+            DATABASE_MERKLE_TREE_STALLED_WRITES_TIMEOUT_SEC=2m
+            DATABASE_MERKLE_TREE_PRUNING_ITERATION_DELAY_MS=1s
+            DATABASE_STATEMENT_TIMEOUT_SEC=5m
+            DATABASE_SLOW_QUERY_THRESHOLD_MS=1s
+        "#;
+        lock.set_env(config);
+
+        let db_config = DBConfig::from_env().unwrap();
+        assert_eq!(db_config.merkle_tree.block_cache_size_mb, 1_024);
+        assert_eq!(db_config.merkle_tree.memtable_capacity_mb, 512);
+        assert_eq!(db_config.merkle_tree.stalled_writes_timeout_sec, 120);
+        assert_eq!(db_config.merkle_tree.pruning_iteration_delay_ms, 1_000);
+        let postgres_config = PostgresConfig::from_env().unwrap();
+        assert_eq!(
+            postgres_config.statement_timeout(),
+            Some(Duration::from_secs(300))
+        );
+        assert_eq!(
+            postgres_config.slow_query_threshold(),
+            Some(Duration::from_secs(1))
+        );
+
+        lock.set_env("DATABASE_MERKLE_TREE_STALLED_WRITES_TIMEOUT_SEC=1500ms");
+        let err = DBConfig::from_env().unwrap_err();
+        assert!(format!("{err:#}").contains("whole number"), "{err:#}");
+        lock.set_env("DATABASE_MERKLE_TREE_STALLED_WRITES_TIMEOUT_SEC=30");
+        lock.set_env("DATABASE_MERKLE_TREE_MEMTABLE_CAPACITY_MB=512KB");
+        DBConfig::from_env().unwrap_err();
+    }
+
     #[test]
     fn postgres_from_env() {
         let mut lock = MUTEX.lock();
@@ -145,6 +250,8 @@ mod tests {
             DATABASE_STATEMENT_TIMEOUT_SEC=300
             DATABASE_LONG_CONNECTION_THRESHOLD_MS=3000
             DATABASE_SLOW_QUERY_THRESHOLD_MS=150
+            DATABASE_READ_REPLICA_URLS=postgres://postgres@replica0/zksync_local,postgres://postgres@replica1/zksync_local
+            DATABASE_MAX_REPLICA_LAG_MS=500
         "#;
         lock.set_env(config);
 
@@ -170,5 +277,16 @@ mod tests {
             postgres_config.slow_query_threshold(),
             Some(Duration::from_millis(150))
         );
+        assert_eq!(
+            postgres_config.read_replica_urls,
+            [
+                "postgres://postgres@replica0/zksync_local",
+                "postgres://postgres@replica1/zksync_local"
+            ]
+        );
+        assert_eq!(
+            postgres_config.max_replica_lag(),
+            Some(Duration::from_millis(500))
+        );
     }
 }
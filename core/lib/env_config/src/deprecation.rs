@@ -0,0 +1,166 @@
+//! Deprecated configuration options.
+//!
+//! Config loaders that support legacy env variables or legacy values [report](report()) their usage
+//! instead of silently accepting it. The reported usages are logged with the modern equivalent and the planned
+//! removal date once the logging subsystem is initialized (see [`finish()`]). In the strict mode
+//! (`--strict-config` command-line flag for the server and the external node), any deprecated usage is an error.
+
+use std::{
+    fmt,
+    sync::{Mutex, PoisonError},
+};
+
+use crate::env_var;
+
+/// Usages reported by config loaders. Loaders are run before logs are initialized and do not have a shared context,
+/// hence the global registry.
+static REPORTED: Mutex<Vec<Deprecation>> = Mutex::new(Vec::new());
+
+/// Kind of a deprecated config option.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeprecationKind {
+    /// The variable is no longer used and is ignored.
+    Ignored,
+    /// The variable has a special value with legacy semantics.
+    LegacyValue {
+        value: &'static str,
+        /// What should be done instead of using the legacy value.
+        replacement: &'static str,
+    },
+    /// The value is derived from other variables for backward compatibility. The variable
+    /// specified as the replacement should be set explicitly instead.
+    Derived { replacement: &'static str },
+}
+
+/// Deprecated config option.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Deprecation {
+    /// Name of the legacy env variable.
+    pub env_var: &'static str,
+    pub kind: DeprecationKind,
+    /// Date (`YYYY-MM-DD`) after which support for the option may be removed.
+    pub removal_date: &'static str,
+}
+
+impl fmt::Display for Deprecation {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let env_var = self.env_var;
+        match self.kind {
+            DeprecationKind::Ignored => {
+                write!(formatter, "{env_var} is no longer used and is ignored")?;
+            }
+            DeprecationKind::LegacyValue { value, replacement } => {
+                write!(formatter, "{env_var}={value} is deprecated; {replacement}")?;
+            }
+            DeprecationKind::Derived { replacement } => {
+                write!(
+                    formatter,
+                    "deriving {replacement} from {env_var} is deprecated; set {replacement} explicitly"
+                )?;
+            }
+        }
+        write!(
+            formatter,
+            " (support will be removed after {})",
+            self.removal_date
+        )
+    }
+}
+
+impl Deprecation {
+    /// Creates a deprecation for an ignored variable.
+    pub const fn ignored(env_var: &'static str, removal_date: &'static str) -> Self {
+        Self {
+            env_var,
+            kind: DeprecationKind::Ignored,
+            removal_date,
+        }
+    }
+}
+
+/// Reports usage of a deprecated config option. Repeated reports of the same option are ignored.
+pub fn report(deprecation: Deprecation) {
+    let mut reported = REPORTED.lock().unwrap_or_else(PoisonError::into_inner);
+    if !reported.contains(&deprecation) {
+        reported.push(deprecation);
+    }
+}
+
+/// Reports deprecations for ignored variables that are set.
+pub fn report_ignored_vars(deprecations: &[Deprecation]) {
+    for deprecation in deprecations {
+        if env_var(deprecation.env_var).is_ok() {
+            report(*deprecation);
+        }
+    }
+}
+
+/// Takes all reported deprecations, logs them and, in the strict mode, returns an error if there is
+/// at least one deprecation. Should be called after the logging subsystem is initialized.
+pub fn finish(strict: bool) -> anyhow::Result<()> {
+    let reported = std::mem::take(&mut *REPORTED.lock().unwrap_or_else(PoisonError::into_inner));
+    for deprecation in &reported {
+        tracing::warn!(
+            env_var = deprecation.env_var,
+            removal_date = deprecation.removal_date,
+            "Deprecated config: {deprecation}"
+        );
+    }
+
+    if strict && !reported.is_empty() {
+        let messages: Vec<_> = reported.iter().map(Deprecation::to_string).collect();
+        anyhow::bail!(
+            "deprecated config options are used in the strict config mode: {}",
+            messages.join("; ")
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::EnvMutex;
+
+    static MUTEX: EnvMutex = EnvMutex::new();
+
+    const IGNORED: &[Deprecation] = &[
+        Deprecation::ignored("DEPRECATION_TEST_THREADS", "2024-06-30"),
+        Deprecation::ignored("DEPRECATION_TEST_UNSET", "2024-06-30"),
+    ];
+
+    #[test]
+    fn reporting_deprecations() {
+        let mut lock = MUTEX.lock();
+        lock.set_env("DEPRECATION_TEST_THREADS=128");
+        lock.remove_env(&["DEPRECATION_TEST_UNSET"]);
+
+        report_ignored_vars(IGNORED);
+        report_ignored_vars(IGNORED);
+        report(Deprecation {
+            env_var: "DEPRECATION_TEST_URL",
+            kind: DeprecationKind::LegacyValue {
+                value: "unset",
+                replacement: "leave DEPRECATION_TEST_URL unset instead",
+            },
+            removal_date: "2024-06-30",
+        });
+
+        let err = finish(true).unwrap_err().to_string();
+        assert!(
+            err.contains("DEPRECATION_TEST_THREADS is no longer used"),
+            "{err}"
+        );
+        assert!(
+            err.contains("DEPRECATION_TEST_URL=unset is deprecated"),
+            "{err}"
+        );
+        assert!(!err.contains("DEPRECATION_TEST_UNSET"), "{err}");
+        assert_eq!(err.matches("DEPRECATION_TEST_THREADS").count(), 1, "{err}");
+
+        // Deprecations are taken by `finish()`.
+        finish(true).unwrap();
+        report_ignored_vars(IGNORED);
+        finish(false).unwrap();
+    }
+}
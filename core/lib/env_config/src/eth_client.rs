@@ -7,7 +7,7 @@ impl FromEnv for ETHClientConfig {
         let config: Self = envy_load("eth_client", "ETH_CLIENT_")?;
         if config.web3_url.find(',').is_some() {
             anyhow::bail!(
-                "Multiple web3 URLs aren't supported anymore. Provided invalid value: {}",
+                "Multiple web3 URLs should be specified using `ETH_CLIENT_FALLBACK_WEB3_URLS`. Provided invalid value: {}",
                 config.web3_url
             );
         }
@@ -26,6 +26,13 @@ mod tests {
         ETHClientConfig {
             chain_id: 9,
             web3_url: "http://127.0.0.1:8545".into(),
+            fallback_web3_urls: vec![
+                "http://127.0.0.1:8546".into(),
+                "http://127.0.0.1:8547".into(),
+            ],
+            failover_error_rate_threshold: Some(0.25),
+            failover_max_head_lag: None,
+            failover_health_check_interval_ms: Some(1_000),
         }
     }
 
@@ -35,6 +42,9 @@ mod tests {
         let config = r#"
             ETH_CLIENT_CHAIN_ID="9"
             ETH_CLIENT_WEB3_URL="http://127.0.0.1:8545"
+            ETH_CLIENT_FALLBACK_WEB3_URLS="http://127.0.0.1:8546,http://127.0.0.1:8547"
+            ETH_CLIENT_FAILOVER_ERROR_RATE_THRESHOLD="0.25"
+            ETH_CLIENT_FAILOVER_HEALTH_CHECK_INTERVAL_MS="1000"
         "#;
         lock.set_env(config);
 
@@ -1,17 +1,43 @@
 use anyhow::Context as _;
-use zksync_config::{configs::eth_sender::SenderConfig, ETHSenderConfig, GasAdjusterConfig};
+use zksync_config::{
+    configs::eth_sender::{OperatorSignerConfig, SenderConfig, TxSubmissionConfig},
+    ETHSenderConfig, GasAdjusterConfig,
+};
 
-use crate::{envy_load, FromEnv};
+use crate::{env_var, envy_load, FromEnv};
 
 impl FromEnv for ETHSenderConfig {
     fn from_env() -> anyhow::Result<Self> {
         Ok(Self {
             sender: SenderConfig::from_env().context("SenderConfig")?,
             gas_adjuster: GasAdjusterConfig::from_env().context("GasAdjusterConfig")?,
+            signer: OperatorSignerConfig::from_env().context("OperatorSignerConfig")?,
+            submission: TxSubmissionConfig::from_env().context("TxSubmissionConfig")?,
+            secondary_signer: optional_signer_from_env(
+                "eth_sender.secondary_signer",
+                "ETH_SENDER_SECONDARY_SIGNER_",
+            )
+            .context("secondary_signer")?,
+            blob_signer: optional_signer_from_env(
+                "eth_sender.blob_signer",
+                "ETH_SENDER_BLOB_SIGNER_",
+            )
+            .context("blob_signer")?,
         })
     }
 }
 
+/// Optional signers are only configured if their backend is specified explicitly.
+fn optional_signer_from_env(
+    name: &str,
+    prefix: &str,
+) -> anyhow::Result<Option<OperatorSignerConfig>> {
+    if env_var(&format!("{prefix}BACKEND")).is_err() {
+        return Ok(None);
+    }
+    envy_load(name, prefix).map(Some)
+}
+
 impl FromEnv for SenderConfig {
     fn from_env() -> anyhow::Result<Self> {
         envy_load("eth_sender", "ETH_SENDER_SENDER_")
@@ -24,9 +50,24 @@ impl FromEnv for GasAdjusterConfig {
     }
 }
 
+impl FromEnv for OperatorSignerConfig {
+    fn from_env() -> anyhow::Result<Self> {
+        envy_load("eth_sender.signer", "ETH_SENDER_SIGNER_")
+    }
+}
+
+impl FromEnv for TxSubmissionConfig {
+    fn from_env() -> anyhow::Result<Self> {
+        envy_load("eth_sender.submission", "ETH_SENDER_SUBMISSION_")
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use zksync_config::configs::eth_sender::{ProofLoadingMode, ProofSendingMode};
+    use zksync_config::configs::eth_sender::{
+        BaseFeeEstimationMode, OperatorSignerBackend, ProofLoadingMode, ProofSendingMode,
+        PubdataSendingMode, TxSubmissionMode,
+    };
 
     use super::*;
     use crate::test_utils::{hash, EnvMutex};
@@ -54,6 +95,11 @@ mod tests {
                 l1_batch_min_age_before_execute_seconds: Some(1000),
                 max_acceptable_priority_fee_in_gwei: 100_000_000_000,
                 proof_loading_mode: ProofLoadingMode::OldProofFromDb,
+                pubdata_sending_mode: PubdataSendingMode::Blobs,
+                max_tx_attempts: Some(10),
+                fee_bump_percent: Some(25),
+                max_fee_per_gas_cap: Some(500_000_000_000),
+                min_blocks_between_resends: Some(3),
             },
             gas_adjuster: GasAdjusterConfig {
                 default_priority_fee_per_gas: 20000000000,
@@ -64,7 +110,40 @@ mod tests {
                 internal_enforced_l1_gas_price: None,
                 poll_period: 15,
                 max_l1_gas_price: Some(100000000),
+                base_fee_estimation_mode: BaseFeeEstimationMode::ExternalApi,
+                base_fee_percentile: None,
+                base_fee_ewma_alpha: None,
+                external_price_api_url: Some("https://api.gas-oracle.test/v1/fees".to_owned()),
+                external_price_api_json_pointer: Some("/result/suggestBaseFee".to_owned()),
+                external_price_api_multiplier: Some(1e9),
+                external_price_api_max_age_sec: Some(30),
+            },
+            signer: OperatorSignerConfig {
+                backend: OperatorSignerBackend::Pkcs11,
+                pkcs11_module_path: Some("/usr/lib/softhsm/libsofthsm2.so".to_owned()),
+                pkcs11_slot_id: Some(1),
+                pkcs11_key_label: Some("operator".to_owned()),
+                ..OperatorSignerConfig::default()
+            },
+            submission: TxSubmissionConfig {
+                mode: TxSubmissionMode::Bundle,
+                relay_url: Some("https://relay.flashbots.net".to_owned()),
+                bundle_target_blocks: Some(5),
             },
+            secondary_signer: Some(OperatorSignerConfig {
+                backend: OperatorSignerBackend::AwsKms,
+                aws_kms_key_id: Some("alias/secondary-operator".to_owned()),
+                aws_region: Some("us-east-1".to_owned()),
+                ..OperatorSignerConfig::default()
+            }),
+            blob_signer: Some(OperatorSignerConfig {
+                backend: OperatorSignerBackend::GcpKms,
+                gcp_kms_key_version: Some(
+                    "projects/zksync/locations/global/keyRings/operator/cryptoKeys/blobs/cryptoKeyVersions/1"
+                        .to_owned(),
+                ),
+                ..OperatorSignerConfig::default()
+            }),
         }
     }
 
@@ -85,6 +164,11 @@ mod tests {
             ETH_SENDER_GAS_ADJUSTER_INTERNAL_L1_PRICING_MULTIPLIER="0.8"
             ETH_SENDER_GAS_ADJUSTER_POLL_PERIOD="15"
             ETH_SENDER_GAS_ADJUSTER_MAX_L1_GAS_PRICE="100000000"
+            ETH_SENDER_GAS_ADJUSTER_BASE_FEE_ESTIMATION_MODE="ExternalApi"
+            ETH_SENDER_GAS_ADJUSTER_EXTERNAL_PRICE_API_URL="https://api.gas-oracle.test/v1/fees"
+            ETH_SENDER_GAS_ADJUSTER_EXTERNAL_PRICE_API_JSON_POINTER="/result/suggestBaseFee"
+            ETH_SENDER_GAS_ADJUSTER_EXTERNAL_PRICE_API_MULTIPLIER="1e9"
+            ETH_SENDER_GAS_ADJUSTER_EXTERNAL_PRICE_API_MAX_AGE_SEC="30"
             ETH_SENDER_WAIT_FOR_PROOFS="false"
             ETH_SENDER_SENDER_AGGREGATED_PROOF_SIZES="1,5"
             ETH_SENDER_SENDER_MAX_AGGREGATED_BLOCKS_TO_COMMIT="3"
@@ -98,6 +182,24 @@ mod tests {
             ETH_SENDER_SENDER_L1_BATCH_MIN_AGE_BEFORE_EXECUTE_SECONDS="1000"
             ETH_SENDER_SENDER_MAX_ACCEPTABLE_PRIORITY_FEE_IN_GWEI="100000000000"
             ETH_SENDER_SENDER_PROOF_LOADING_MODE="OldProofFromDb"
+            ETH_SENDER_SENDER_PUBDATA_SENDING_MODE="Blobs"
+            ETH_SENDER_SENDER_MAX_TX_ATTEMPTS="10"
+            ETH_SENDER_SENDER_FEE_BUMP_PERCENT="25"
+            ETH_SENDER_SENDER_MAX_FEE_PER_GAS_CAP="500000000000"
+            ETH_SENDER_SENDER_MIN_BLOCKS_BETWEEN_RESENDS="3"
+            ETH_SENDER_SIGNER_BACKEND="Pkcs11"
+            ETH_SENDER_SIGNER_PKCS11_MODULE_PATH="/usr/lib/softhsm/libsofthsm2.so"
+            ETH_SENDER_SIGNER_PKCS11_SLOT_ID="1"
+            ETH_SENDER_SIGNER_PKCS11_KEY_LABEL="operator"
+            ETH_SENDER_SIGNER_PKCS11_PIN="1234"
+            ETH_SENDER_SUBMISSION_MODE="Bundle"
+            ETH_SENDER_SUBMISSION_RELAY_URL="https://relay.flashbots.net"
+            ETH_SENDER_SUBMISSION_BUNDLE_TARGET_BLOCKS="5"
+            ETH_SENDER_SECONDARY_SIGNER_BACKEND="AwsKms"
+            ETH_SENDER_SECONDARY_SIGNER_AWS_KMS_KEY_ID="alias/secondary-operator"
+            ETH_SENDER_SECONDARY_SIGNER_AWS_REGION="us-east-1"
+            ETH_SENDER_BLOB_SIGNER_BACKEND="GcpKms"
+            ETH_SENDER_BLOB_SIGNER_GCP_KMS_KEY_VERSION="projects/zksync/locations/global/keyRings/operator/cryptoKeys/blobs/cryptoKeyVersions/1"
         "#;
         lock.set_env(config);
 
@@ -107,5 +209,6 @@ mod tests {
             actual.sender.private_key().unwrap(),
             hash("27593fea79697e947890ecbecce7901b0008345e5d7259710d0dd5e500d040be")
         );
+        assert_eq!(actual.signer.pkcs11_pin().unwrap(), "1234");
     }
 }
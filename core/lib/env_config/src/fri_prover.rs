@@ -10,7 +10,7 @@ impl FromEnv for FriProverConfig {
 
 #[cfg(test)]
 mod tests {
-    use zksync_config::configs::fri_prover::SetupLoadMode;
+    use zksync_config::configs::fri_prover::{ProverBackend, SetupLoadMode};
 
     use super::*;
     use crate::test_utils::EnvMutex;
@@ -33,6 +33,7 @@ mod tests {
             zone_read_url: "http://metadata.google.internal/computeMetadata/v1/instance/zone"
                 .to_string(),
             shall_save_to_public_bucket: true,
+            backend: ProverBackend::Cpu,
         }
     }
 
@@ -53,6 +54,7 @@ mod tests {
             FRI_PROVER_WITNESS_VECTOR_RECEIVER_PORT="3316"
             FRI_PROVER_ZONE_READ_URL="http://metadata.google.internal/computeMetadata/v1/instance/zone"
             FRI_PROVER_SHALL_SAVE_TO_PUBLIC_BUCKET=true
+            FRI_PROVER_BACKEND="Cpu"
         "#;
         lock.set_env(config);
 
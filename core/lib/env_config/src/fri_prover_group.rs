@@ -1,12 +1,9 @@
-use std::{
-    collections::{HashMap, HashSet},
-    env,
-};
+use std::collections::{HashMap, HashSet};
 
 use zksync_basic_types::basic_fri_types::CircuitIdRoundTuple;
 use zksync_config::configs::fri_prover_group::FriProverGroupConfig;
 
-use crate::FromEnv;
+use crate::{env_vars, FromEnv};
 
 fn load_from_env_variable() -> HashMap<String, HashSet<CircuitIdRoundTuple>> {
     // Prepare a hash map to store the mapping of group to a vector of tuples
@@ -17,7 +14,7 @@ fn load_from_env_variable() -> HashMap<String, HashSet<CircuitIdRoundTuple>> {
     // Separate environment variables into Circuit Id and Aggregation Round
     let mut circuit_ids = HashMap::new();
     let mut aggregation_rounds = HashMap::new();
-    for (key, value) in env::vars() {
+    for (key, value) in env_vars() {
         if key.contains("_CIRCUIT_ID") {
             circuit_ids.insert(key, value);
         } else if key.contains("_AGGREGATION_ROUND") {
@@ -75,7 +72,7 @@ impl FromEnv for FriProverGroupConfig {
 
 #[cfg(test)]
 mod tests {
-    use std::collections::HashSet;
+    use std::{collections::HashSet, env};
 
     use super::*;
 
@@ -30,6 +30,10 @@ mod tests {
             fri_prover_stats_reporting_interval_ms: 30_000,
             fri_proof_compressor_job_retrying_interval_ms: 30_000,
             fri_proof_compressor_stats_reporting_interval_ms: 30_000,
+            prover_artifacts_retention_period_hours: Some(720),
+            prover_artifacts_gc_interval_ms: None,
+            prover_artifacts_gc_dry_run: true,
+            fri_prover_heartbeat_timeout_ms: Some(90_000),
         }
     }
 
@@ -50,6 +54,9 @@ mod tests {
             HOUSE_KEEPER_FRI_PROVER_STATS_REPORTING_INTERVAL_MS="30000"
             HOUSE_KEEPER_FRI_PROOF_COMPRESSOR_STATS_REPORTING_INTERVAL_MS="30000"
             HOUSE_KEEPER_FRI_PROOF_COMPRESSOR_JOB_RETRYING_INTERVAL_MS="30000"
+            HOUSE_KEEPER_PROVER_ARTIFACTS_RETENTION_PERIOD_HOURS="720"
+            HOUSE_KEEPER_PROVER_ARTIFACTS_GC_DRY_RUN="true"
+            HOUSE_KEEPER_FRI_PROVER_HEARTBEAT_TIMEOUT_MS="90000"
         "#;
         lock.set_env(config);
 
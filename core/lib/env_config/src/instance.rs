@@ -0,0 +1,77 @@
+//! Instance-specific env variables.
+//!
+//! To run multiple server or external node instances on the same host without env variable collisions,
+//! each process can be assigned an instance name via the `ZKSYNC_INSTANCE` env variable. If an instance name
+//! is set (e.g., `ZKSYNC_INSTANCE=node1`), env variables prefixed with `ZKSYNC_{INSTANCE}__`
+//! (e.g., `ZKSYNC_NODE1__API_WEB3_JSON_RPC_HTTP_PORT`) take precedence over the unprefixed ones
+//! (`API_WEB3_JSON_RPC_HTTP_PORT`). Variables that are the same for all instances can be left unprefixed.
+
+use std::{collections::BTreeMap, env};
+
+/// Name of the env variable specifying the instance name.
+pub const INSTANCE_VAR: &str = "ZKSYNC_INSTANCE";
+
+fn instance_prefix() -> Option<String> {
+    let instance = env::var(INSTANCE_VAR).ok()?;
+    if instance.is_empty() {
+        return None;
+    }
+    Some(format!("ZKSYNC_{}__", instance.to_uppercase()))
+}
+
+/// Drop-in replacement for [`env::var()`] that takes the instance prefix into account.
+pub fn env_var(name: &str) -> Result<String, env::VarError> {
+    if let Some(prefix) = instance_prefix() {
+        if let Ok(value) = env::var(format!("{prefix}{name}")) {
+            return Ok(value);
+        }
+    }
+    env::var(name)
+}
+
+/// Drop-in replacement for [`env::vars()`] that takes the instance prefix into account. Instance-specific variables
+/// are returned both with and without the instance prefix; in the latter case, they override the unprefixed variables.
+pub fn env_vars() -> impl Iterator<Item = (String, String)> {
+    let mut vars: BTreeMap<_, _> = env::vars().collect();
+    if let Some(prefix) = instance_prefix() {
+        let instance_vars: Vec<_> = vars
+            .iter()
+            .filter_map(|(name, value)| {
+                let name = name.strip_prefix(&prefix)?;
+                Some((name.to_owned(), value.clone()))
+            })
+            .collect();
+        vars.extend(instance_vars);
+    }
+    vars.into_iter()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::EnvMutex;
+
+    static MUTEX: EnvMutex = EnvMutex::new();
+
+    #[test]
+    fn instance_specific_vars() {
+        let mut lock = MUTEX.lock();
+        lock.set_env(
+            r#"
+            INSTANCE_TEST_PORT=3050
+            INSTANCE_TEST_HOST=localhost
+            ZKSYNC_INSTANCE_TEST_NODE__INSTANCE_TEST_PORT=3060
+            "#,
+        );
+        lock.remove_env(&[INSTANCE_VAR]);
+        assert_eq!(env_var("INSTANCE_TEST_PORT").unwrap(), "3050");
+
+        lock.set_env("ZKSYNC_INSTANCE=instance_test_node");
+        assert_eq!(env_var("INSTANCE_TEST_PORT").unwrap(), "3060");
+        assert_eq!(env_var("INSTANCE_TEST_HOST").unwrap(), "localhost");
+
+        let vars: BTreeMap<_, _> = env_vars().collect();
+        assert_eq!(vars["INSTANCE_TEST_PORT"], "3060");
+        assert_eq!(vars["INSTANCE_TEST_HOST"], "localhost");
+    }
+}
@@ -4,9 +4,13 @@ use serde::de::DeserializeOwned;
 mod alerts;
 mod api;
 mod chain;
+mod collector;
+mod config_dump;
+mod config_file;
 mod contract_verifier;
 mod contracts;
 mod database;
+pub mod deprecation;
 mod eth_client;
 mod eth_sender;
 mod eth_watch;
@@ -17,13 +21,24 @@ mod fri_prover_group;
 mod fri_witness_generator;
 mod fri_witness_vector_generator;
 mod house_keeper;
+mod instance;
 pub mod object_store;
-mod observability;
+pub mod observability;
 mod proof_data_handler;
+pub mod remote;
+pub mod secrets;
+mod shutdown;
 mod snapshots_creator;
 mod utils;
 mod witness_generator;
 
+pub use crate::{
+    collector::ConfigCollector,
+    config_dump::{ConfigDump, ValueSource},
+    config_file::{ConfigFileFormat, ConfigSource},
+    instance::{env_var, env_vars, INSTANCE_VAR},
+};
+
 #[cfg(test)]
 mod test_utils;
 
@@ -32,9 +47,9 @@ pub trait FromEnv: Sized {
 }
 
 /// Convenience function that loads the structure from the environment variable given the prefix.
-/// Panics if the config cannot be loaded from the environment variables.
+/// Instance-specific env variables (see [`INSTANCE_VAR`]) take precedence over the general ones.
 pub fn envy_load<T: DeserializeOwned>(name: &str, prefix: &str) -> anyhow::Result<T> {
     envy::prefixed(prefix)
-        .from_env()
+        .from_iter(env_vars())
         .with_context(|| format!("Cannot load config <{name}>"))
 }
@@ -42,7 +42,14 @@ impl FromEnv for SnapshotsObjectStoreConfig {
 
 #[cfg(test)]
 mod tests {
-    use zksync_config::{configs::object_store::ObjectStoreMode, ObjectStoreConfig};
+    use std::time::Duration;
+
+    use zksync_config::{
+        configs::object_store::{
+            ObjectStoreCompression, ObjectStoreEncryption, ObjectStoreMode, S3AddressingStyle,
+        },
+        ObjectStoreConfig,
+    };
 
     use super::*;
     use crate::test_utils::EnvMutex;
@@ -56,6 +63,19 @@ mod tests {
                 gcs_credential_file_path: "/path/to/credentials.json".to_owned(),
             },
             max_retries: 5,
+            compression: ObjectStoreCompression::None,
+            compression_level: None,
+            retry_initial_backoff_ms: None,
+            retry_max_backoff_ms: None,
+            retry_budget: None,
+            circuit_breaker_failure_threshold: None,
+            circuit_breaker_reset_timeout_ms: None,
+            encryption: ObjectStoreEncryption::None,
+            encrypted_buckets: None,
+            encryption_key: None,
+            encryption_kms_key_id: None,
+            encryption_kms_region: None,
+            encryption_strict: false,
         }
     }
 
@@ -90,6 +110,152 @@ mod tests {
         );
     }
 
+    #[test]
+    fn azure_configs_from_env() {
+        let mut lock = MUTEX.lock();
+        let config = r#"
+            OBJECT_STORE_MODE="AzureBlobWithManagedIdentity"
+            OBJECT_STORE_AZURE_ACCOUNT_NAME="zksync"
+            OBJECT_STORE_AZURE_CONTAINER_NAME="artifacts"
+        "#;
+        lock.set_env(config);
+        let actual = ObjectStoreConfig::from_env().unwrap();
+        assert_eq!(
+            actual.mode,
+            ObjectStoreMode::AzureBlobWithManagedIdentity {
+                azure_account_name: "zksync".to_owned(),
+                azure_container_name: "artifacts".to_owned(),
+            }
+        );
+
+        lock.remove_env(&[
+            "OBJECT_STORE_AZURE_ACCOUNT_NAME",
+            "OBJECT_STORE_AZURE_CONTAINER_NAME",
+        ]);
+        let config = r#"
+            OBJECT_STORE_MODE="AzureBlobWithConnectionString"
+            OBJECT_STORE_AZURE_CONTAINER_NAME="artifacts"
+            OBJECT_STORE_AZURE_CONNECTION_STRING="AccountName=zksync;AccountKey=dGVzdA=="
+        "#;
+        lock.set_env(config);
+        let actual = ObjectStoreConfig::from_env().unwrap();
+        assert_eq!(
+            actual.mode,
+            ObjectStoreMode::AzureBlobWithConnectionString {
+                azure_container_name: "artifacts".to_owned(),
+                azure_connection_string: "AccountName=zksync;AccountKey=dGVzdA==".to_owned(),
+            }
+        );
+    }
+
+    #[test]
+    fn compression_config_from_env() {
+        let mut lock = MUTEX.lock();
+        let config = r#"
+            PROVER_OBJECT_STORE_MODE="FileBacked"
+            PROVER_OBJECT_STORE_FILE_BACKED_BASE_PATH="artifacts"
+            PROVER_OBJECT_STORE_COMPRESSION="zstd"
+            PROVER_OBJECT_STORE_COMPRESSION_LEVEL="10"
+        "#;
+        lock.set_env(config);
+        let actual = ProverObjectStoreConfig::from_env().unwrap().0;
+        assert_eq!(actual.compression, ObjectStoreCompression::Zstd);
+        assert_eq!(actual.compression_level, Some(10));
+    }
+
+    #[test]
+    fn retry_config_from_env() {
+        let mut lock = MUTEX.lock();
+        let config = r#"
+            PROVER_OBJECT_STORE_MODE="FileBacked"
+            PROVER_OBJECT_STORE_FILE_BACKED_BASE_PATH="artifacts"
+            PROVER_OBJECT_STORE_RETRY_INITIAL_BACKOFF_MS="200"
+            PROVER_OBJECT_STORE_RETRY_MAX_BACKOFF_MS="5000"
+            PROVER_OBJECT_STORE_RETRY_BUDGET="50"
+            PROVER_OBJECT_STORE_CIRCUIT_BREAKER_FAILURE_THRESHOLD="0"
+            PROVER_OBJECT_STORE_CIRCUIT_BREAKER_RESET_TIMEOUT_MS="10000"
+        "#;
+        lock.set_env(config);
+        let actual = ProverObjectStoreConfig::from_env().unwrap().0;
+        assert_eq!(actual.retry_initial_backoff(), Duration::from_millis(200));
+        assert_eq!(actual.retry_max_backoff(), Duration::from_secs(5));
+        assert_eq!(actual.retry_budget(), 50);
+        assert_eq!(actual.circuit_breaker_failure_threshold(), 0);
+        assert_eq!(
+            actual.circuit_breaker_reset_timeout(),
+            Duration::from_secs(10)
+        );
+    }
+
+    #[test]
+    fn encryption_config_from_env() {
+        let mut lock = MUTEX.lock();
+        let config = r#"
+            PROVER_OBJECT_STORE_MODE="FileBacked"
+            PROVER_OBJECT_STORE_FILE_BACKED_BASE_PATH="artifacts"
+            PROVER_OBJECT_STORE_ENCRYPTION="aws_kms"
+            PROVER_OBJECT_STORE_ENCRYPTED_BUCKETS="witness_inputs,proofs_fri"
+            PROVER_OBJECT_STORE_ENCRYPTION_KMS_KEY_ID="alias/prover"
+            PROVER_OBJECT_STORE_ENCRYPTION_KMS_REGION="eu-west-1"
+            PROVER_OBJECT_STORE_ENCRYPTION_STRICT="true"
+        "#;
+        lock.set_env(config);
+        let actual = ProverObjectStoreConfig::from_env().unwrap().0;
+        assert_eq!(actual.encryption, ObjectStoreEncryption::AwsKms);
+        assert_eq!(
+            actual.encrypted_buckets.unwrap(),
+            ["witness_inputs", "proofs_fri"]
+        );
+        assert_eq!(actual.encryption_key, None);
+        assert_eq!(actual.encryption_kms_key_id.unwrap(), "alias/prover");
+        assert_eq!(actual.encryption_kms_region.unwrap(), "eu-west-1");
+        assert!(actual.encryption_strict);
+    }
+
+    #[test]
+    fn s3_config_from_env() {
+        let mut lock = MUTEX.lock();
+        let config = r#"
+            OBJECT_STORE_MODE="S3"
+            OBJECT_STORE_S3_BUCKET="artifacts"
+            OBJECT_STORE_S3_REGION="us-east-1"
+            OBJECT_STORE_S3_ENDPOINT="http://minio:9000"
+            OBJECT_STORE_S3_ADDRESSING_STYLE="path"
+            OBJECT_STORE_S3_ACCESS_KEY_ID="minio"
+            OBJECT_STORE_S3_SECRET_ACCESS_KEY="minio123"
+        "#;
+        lock.set_env(config);
+        let actual = ObjectStoreConfig::from_env().unwrap();
+        assert_eq!(
+            actual.mode,
+            ObjectStoreMode::S3 {
+                s3_bucket: "artifacts".to_owned(),
+                s3_region: "us-east-1".to_owned(),
+                s3_endpoint: Some("http://minio:9000".to_owned()),
+                s3_addressing_style: Some(S3AddressingStyle::Path),
+                s3_access_key_id: Some("minio".to_owned()),
+                s3_secret_access_key: Some("minio123".to_owned()),
+            }
+        );
+    }
+
+    #[test]
+    fn http_mirror_config_from_env() {
+        let mut lock = MUTEX.lock();
+        let config = r#"
+            OBJECT_STORE_MODE="HttpMirrorReadOnly"
+            OBJECT_STORE_HTTP_MIRROR_BASE_URL="https://snapshots.example.com/mainnet"
+        "#;
+        lock.set_env(config);
+        let actual = ObjectStoreConfig::from_env().unwrap();
+        assert_eq!(
+            actual.mode,
+            ObjectStoreMode::HttpMirrorReadOnly {
+                http_mirror_base_url: "https://snapshots.example.com/mainnet".to_owned(),
+            }
+        );
+    }
+
     #[test]
     fn public_bucket_config_from_env() {
         let mut lock = MUTEX.lock();
@@ -1,31 +1,135 @@
-use zksync_config::configs::ObservabilityConfig;
+use anyhow::Context as _;
+use zksync_config::{configs::ObservabilityConfig, units::Unit};
 
-use crate::FromEnv;
+use crate::{
+    deprecation::{self, Deprecation, DeprecationKind},
+    env_var, FromEnv,
+};
+
+/// Date after which legacy observability options may be removed.
+const REMOVAL_DATE: &str = "2024-06-30";
+
+/// Legacy `MISC_SENTRY_URL=unset` value used to disable Sentry.
+const UNSET_SENTRY_URL: Deprecation = Deprecation {
+    env_var: "MISC_SENTRY_URL",
+    kind: DeprecationKind::LegacyValue {
+        value: "unset",
+        replacement: "leave MISC_SENTRY_URL unset to disable Sentry",
+    },
+    removal_date: REMOVAL_DATE,
+};
+
+/// Legacy `MISC_OTLP_URL=unset` value used to disable trace export.
+const UNSET_OTLP_URL: Deprecation = Deprecation {
+    env_var: "MISC_OTLP_URL",
+    kind: DeprecationKind::LegacyValue {
+        value: "unset",
+        replacement: "leave MISC_OTLP_URL unset to disable trace export",
+    },
+    removal_date: REMOVAL_DATE,
+};
+
+/// Loads the Sentry URL, supporting the legacy `unset` value.
+pub fn sentry_url_from_env() -> Option<String> {
+    let sentry_url = env_var("MISC_SENTRY_URL").ok()?;
+    if sentry_url == "unset" {
+        deprecation::report(UNSET_SENTRY_URL);
+        None
+    } else {
+        Some(sentry_url)
+    }
+}
+
+/// Loads the OTLP endpoint URL, supporting the legacy `unset` value.
+pub fn otlp_url_from_env() -> Option<String> {
+    let otlp_url = env_var("MISC_OTLP_URL").ok()?;
+    if otlp_url == "unset" {
+        deprecation::report(UNSET_OTLP_URL);
+        None
+    } else {
+        Some(otlp_url)
+    }
+}
+
+/// Parses an optional OTLP sampling ratio.
+pub fn otlp_sampling_ratio_from_env() -> anyhow::Result<Option<f64>> {
+    env_var("MISC_OTLP_SAMPLING_RATIO")
+        .ok()
+        .map(|ratio| ratio.parse().context("invalid MISC_OTLP_SAMPLING_RATIO"))
+        .transpose()
+}
+
+/// Derives the Sentry environment from the L1 and L2 network names, which is the legacy behavior
+/// if the environment is not set explicitly. `replacement` is the env variable that should be used instead.
+pub fn derive_sentry_environment(replacement: &'static str) -> Option<String> {
+    let l1_network = env_var("CHAIN_ETH_NETWORK").ok()?;
+    let l2_network = env_var("CHAIN_ETH_ZKSYNC_NETWORK").ok()?;
+    deprecation::report(Deprecation {
+        env_var: "CHAIN_ETH_NETWORK",
+        kind: DeprecationKind::Derived { replacement },
+        removal_date: REMOVAL_DATE,
+    });
+    Some(format!("{} - {}", l1_network, l2_network))
+}
+
+/// Parses an optional sample rate for Sentry.
+pub fn sample_rate_from_env(name: &str) -> anyhow::Result<Option<f32>> {
+    env_var(name)
+        .ok()
+        .map(|rate| rate.parse().with_context(|| format!("invalid {name}")))
+        .transpose()
+}
+
+fn parse_with_unit(name: &str, unit: Unit) -> anyhow::Result<Option<u64>> {
+    env_var(name)
+        .ok()
+        .map(|value| {
+            unit.parse(&value)
+                .map_err(|err| anyhow::anyhow!("invalid {name}: {err}"))
+        })
+        .transpose()
+}
+
+fn parse_optional<T>(name: &str) -> anyhow::Result<Option<T>>
+where
+    T: std::str::FromStr,
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    env_var(name)
+        .ok()
+        .map(|value| value.parse().with_context(|| format!("invalid {name}")))
+        .transpose()
+}
+
+/// Parses the optional log file size limit, which can be specified with a unit (e.g., `100MiB`).
+pub fn log_file_max_size_from_env() -> anyhow::Result<Option<u64>> {
+    parse_with_unit("MISC_LOG_FILE_MAX_SIZE_BYTES", Unit::Bytes)
+}
+
+/// Parses the optional number of retained log files.
+pub fn log_file_max_files_from_env() -> anyhow::Result<Option<usize>> {
+    parse_optional("MISC_LOG_FILE_MAX_FILES")
+}
+
+/// Parses the optional log throttling interval, which can be specified with a unit (e.g., `1m`).
+pub fn log_throttle_interval_from_env() -> anyhow::Result<Option<u64>> {
+    parse_with_unit("MISC_LOG_THROTTLE_INTERVAL_SEC", Unit::Seconds)
+}
+
+/// Parses the optional number of identical messages logged per throttling interval.
+pub fn log_throttle_burst_from_env() -> anyhow::Result<Option<u32>> {
+    parse_optional("MISC_LOG_THROTTLE_BURST")
+}
 
 impl FromEnv for ObservabilityConfig {
     fn from_env() -> anyhow::Result<Self> {
-        // The logic in this method mimics the historical logic of loading observability options
-        // This is left intact, since some of the existing deployments may rely on the this behavior.
-        let sentry_url = if let Ok(sentry_url) = std::env::var("MISC_SENTRY_URL") {
-            if sentry_url == "unset" {
-                None
-            } else {
-                Some(sentry_url)
-            }
-        } else {
-            None
-        };
-        let sentry_environment = {
-            let l1_network = std::env::var("CHAIN_ETH_NETWORK").ok();
-            let l2_network = std::env::var("CHAIN_ETH_ZKSYNC_NETWORK").ok();
-            match (l1_network, l2_network) {
-                (Some(l1_network), Some(l2_network)) => {
-                    Some(format!("{} - {}", l1_network, l2_network))
-                }
-                _ => None,
-            }
-        };
-        let log_format = if let Ok(log_format) = std::env::var("MISC_LOG_FORMAT") {
+        // Legacy behavior is still supported, since some of the existing deployments may rely on it,
+        // but its usage is reported as deprecated.
+        let sentry_url = sentry_url_from_env();
+        let sentry_environment = env_var("MISC_SENTRY_ENVIRONMENT")
+            .ok()
+            .or_else(|| derive_sentry_environment("MISC_SENTRY_ENVIRONMENT"));
+        let log_format = if let Ok(log_format) = env_var("MISC_LOG_FORMAT") {
             if log_format != "plain" && log_format != "json" {
                 anyhow::bail!("MISC_LOG_FORMAT has an unexpected value {}", log_format);
             }
@@ -37,6 +141,19 @@ impl FromEnv for ObservabilityConfig {
         Ok(ObservabilityConfig {
             sentry_url,
             sentry_environment,
+            sentry_sample_rate: sample_rate_from_env("MISC_SENTRY_SAMPLE_RATE")?,
+            sentry_traces_sample_rate: sample_rate_from_env("MISC_SENTRY_TRACES_SAMPLE_RATE")?,
+            sentry_error_filter: env_var("MISC_SENTRY_ERROR_FILTER").ok(),
+            otlp_url: otlp_url_from_env(),
+            otlp_sampling_ratio: otlp_sampling_ratio_from_env()?,
+            otlp_resource_attributes: env_var("MISC_OTLP_RESOURCE_ATTRIBUTES").ok(),
+            log_directives: env_var("MISC_LOG_DIRECTIVES").ok(),
+            log_file_path: env_var("MISC_LOG_FILE_PATH").ok(),
+            log_file_rotation: env_var("MISC_LOG_FILE_ROTATION").ok(),
+            log_file_max_size_bytes: log_file_max_size_from_env()?,
+            log_file_max_files: log_file_max_files_from_env()?,
+            log_throttle_interval_sec: log_throttle_interval_from_env()?,
+            log_throttle_burst: log_throttle_burst_from_env()?,
             log_format,
         })
     }
@@ -0,0 +1,379 @@
+//! Remote config source.
+//!
+//! Non-secret configuration shared by multiple nodes can be stored centrally and fetched on startup.
+//! The remote config has the same format as [config files](crate::ConfigSource) and is configured
+//! with the following env variables:
+//!
+//! - `REMOTE_CONFIG_URL`: location of the config. Supported formats are `http(s)://...` (the config is fetched
+//!   with a GET request), `consul://<host>:<port>/<key>` (Consul KV store) and `etcd://<host>:<port>/<key>`
+//!   (etcd v3 KV store via its JSON gateway). Use `consul+https` / `etcd+https` schemes to connect via TLS.
+//! - `REMOTE_CONFIG_FORMAT`: `yaml` (default) or `toml`.
+//! - `REMOTE_CONFIG_TOKEN` (optional): bearer token for HTTP, or ACL token for Consul.
+//! - `REMOTE_CONFIG_POLL_INTERVAL_SEC` (optional): if set, the config is re-fetched with this interval,
+//!   and reloadable settings (e.g., log directives) are updated if they change.
+//! - `REMOTE_CONFIG_CONNECT_TIMEOUT_SEC` (optional, 5 by default): timeout for establishing connections
+//!   to the remote config source.
+//! - `REMOTE_CONFIG_REQUEST_TIMEOUT_SEC` (optional, 30 by default): timeout for a single request
+//!   to the remote config source, including reading the response.
+//!
+//! Both env variables and the local config file take precedence over the remote config.
+
+use std::{fmt, time::Duration};
+
+use anyhow::Context as _;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+
+use crate::{env_var, ConfigFileFormat, ConfigSource};
+
+/// Location of a remote config.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RemoteConfigLocation {
+    /// Config returned by a GET request to the specified URL.
+    Http { url: String },
+    /// Value of a key in the Consul KV store.
+    Consul { address: String, key: String },
+    /// Value of a key in the etcd v3 KV store.
+    Etcd { address: String, key: String },
+}
+
+impl RemoteConfigLocation {
+    /// Parses the location from a URL as described in the [module docs](self).
+    pub fn parse(url: &str) -> anyhow::Result<Self> {
+        let (scheme, rest) = url
+            .split_once("://")
+            .with_context(|| format!("remote config URL `{url}` has no scheme"))?;
+        let (kv_store, http_scheme) = match scheme {
+            "http" | "https" => {
+                return Ok(Self::Http {
+                    url: url.to_owned(),
+                })
+            }
+            "consul" | "etcd" => (scheme, "http"),
+            "consul+https" | "etcd+https" => (&scheme[..scheme.len() - "+https".len()], "https"),
+            _ => anyhow::bail!("unsupported scheme in remote config URL `{url}`"),
+        };
+
+        let (host, key) = rest
+            .split_once('/')
+            .filter(|(host, key)| !host.is_empty() && !key.is_empty())
+            .with_context(|| {
+                format!("remote config URL `{url}` must have `{scheme}://<host>/<key>` format")
+            })?;
+        let address = format!("{http_scheme}://{host}");
+        let key = key.to_owned();
+        Ok(if kv_store == "consul" {
+            Self::Consul { address, key }
+        } else {
+            Self::Etcd { address, key }
+        })
+    }
+}
+
+/// Remote config source.
+pub struct RemoteConfigSource {
+    client: reqwest::Client,
+    location: RemoteConfigLocation,
+    format: ConfigFileFormat,
+    token: Option<String>,
+    poll_interval: Option<Duration>,
+    connect_timeout: Duration,
+    request_timeout: Duration,
+}
+
+impl fmt::Debug for RemoteConfigSource {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("RemoteConfigSource")
+            .field("location", &self.location)
+            .field("format", &self.format)
+            .field("poll_interval", &self.poll_interval)
+            .field("connect_timeout", &self.connect_timeout)
+            .field("request_timeout", &self.request_timeout)
+            .finish_non_exhaustive()
+    }
+}
+
+impl RemoteConfigSource {
+    const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+    const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+    pub fn new(location: RemoteConfigLocation, format: ConfigFileFormat) -> Self {
+        Self {
+            client: Self::build_client(Self::DEFAULT_CONNECT_TIMEOUT),
+            location,
+            format,
+            token: None,
+            poll_interval: None,
+            connect_timeout: Self::DEFAULT_CONNECT_TIMEOUT,
+            request_timeout: Self::DEFAULT_REQUEST_TIMEOUT,
+        }
+    }
+
+    fn build_client(connect_timeout: Duration) -> reqwest::Client {
+        reqwest::Client::builder()
+            .connect_timeout(connect_timeout)
+            .build()
+            .expect("failed building HTTP client")
+    }
+
+    /// Sets the token used to authenticate requests.
+    pub fn with_token(mut self, token: String) -> Self {
+        self.token = Some(token);
+        self
+    }
+
+    /// Sets the interval to poll the remote config with.
+    pub fn with_poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = Some(interval);
+        self
+    }
+
+    /// Sets the timeout for establishing connections to the remote config source.
+    pub fn with_connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = timeout;
+        self.client = Self::build_client(timeout);
+        self
+    }
+
+    /// Sets the timeout for a single request to the remote config source, including reading the response.
+    pub fn with_request_timeout(mut self, timeout: Duration) -> Self {
+        self.request_timeout = timeout;
+        self
+    }
+
+    /// Returns the poll interval, if polling is enabled.
+    pub fn poll_interval(&self) -> Option<Duration> {
+        self.poll_interval
+    }
+
+    /// Creates a source from env variables described in the [module docs](self). Returns `Ok(None)`
+    /// if `REMOTE_CONFIG_URL` is not set.
+    pub fn from_env() -> anyhow::Result<Option<Self>> {
+        let Ok(url) = env_var("REMOTE_CONFIG_URL") else {
+            return Ok(None);
+        };
+        let location = RemoteConfigLocation::parse(&url)?;
+        let format = match env_var("REMOTE_CONFIG_FORMAT").as_deref() {
+            Err(_) | Ok("yaml") => ConfigFileFormat::Yaml,
+            Ok("toml") => ConfigFileFormat::Toml,
+            Ok(other) => anyhow::bail!(
+                "REMOTE_CONFIG_FORMAT has an unexpected value `{other}`; expected `yaml` or `toml`"
+            ),
+        };
+
+        let mut this = Self::new(location, format);
+        if let Ok(token) = env_var("REMOTE_CONFIG_TOKEN") {
+            this = this.with_token(token);
+        }
+        if let Some(interval) = Self::duration_from_env("REMOTE_CONFIG_POLL_INTERVAL_SEC")? {
+            this = this.with_poll_interval(interval);
+        }
+        if let Some(timeout) = Self::duration_from_env("REMOTE_CONFIG_CONNECT_TIMEOUT_SEC")? {
+            this = this.with_connect_timeout(timeout);
+        }
+        if let Some(timeout) = Self::duration_from_env("REMOTE_CONFIG_REQUEST_TIMEOUT_SEC")? {
+            this = this.with_request_timeout(timeout);
+        }
+        Ok(Some(this))
+    }
+
+    fn duration_from_env(var_name: &str) -> anyhow::Result<Option<Duration>> {
+        let Ok(secs) = env_var(var_name) else {
+            return Ok(None);
+        };
+        let secs = secs
+            .parse()
+            .with_context(|| format!("invalid {var_name}"))?;
+        Ok(Some(Duration::from_secs(secs)))
+    }
+
+    async fn fetch_contents(&self) -> anyhow::Result<String> {
+        match &self.location {
+            RemoteConfigLocation::Http { url } => {
+                let mut request = self.client.get(url).timeout(self.request_timeout);
+                if let Some(token) = &self.token {
+                    request = request.bearer_auth(token);
+                }
+                request
+                    .send()
+                    .await
+                    .context("failed sending request")?
+                    .error_for_status()
+                    .context("remote config endpoint returned error response")?
+                    .text()
+                    .await
+                    .context("failed reading response")
+            }
+
+            RemoteConfigLocation::Consul { address, key } => {
+                let mut request = self
+                    .client
+                    .get(format!("{address}/v1/kv/{key}?raw"))
+                    .timeout(self.request_timeout);
+                if let Some(token) = &self.token {
+                    request = request.header("X-Consul-Token", token);
+                }
+                request
+                    .send()
+                    .await
+                    .context("failed sending request to Consul")?
+                    .error_for_status()
+                    .context("Consul returned error response")?
+                    .text()
+                    .await
+                    .context("failed reading Consul response")
+            }
+
+            RemoteConfigLocation::Etcd { address, key } => {
+                let body = serde_json::json!({ "key": BASE64.encode(key) });
+                let response: serde_json::Value = self
+                    .client
+                    .post(format!("{address}/v3/kv/range"))
+                    .timeout(self.request_timeout)
+                    .json(&body)
+                    .send()
+                    .await
+                    .context("failed sending request to etcd")?
+                    .error_for_status()
+                    .context("etcd returned error response")?
+                    .json()
+                    .await
+                    .context("failed parsing etcd response")?;
+                let value = response
+                    .pointer("/kvs/0/value")
+                    .and_then(serde_json::Value::as_str)
+                    .with_context(|| format!("key `{key}` is missing in etcd"))?;
+                let value = BASE64
+                    .decode(value)
+                    .context("invalid base64 in etcd value")?;
+                String::from_utf8(value).context("etcd value is not UTF-8")
+            }
+        }
+    }
+
+    /// Fetches the remote config.
+    pub async fn fetch(&self) -> anyhow::Result<ConfigSource> {
+        let contents = self
+            .fetch_contents()
+            .await
+            .with_context(|| format!("failed fetching remote config from {:?}", self.location))?;
+        ConfigSource::parse(&contents, self.format).context("invalid remote config")
+    }
+
+    /// Polls the remote config with the [configured interval](Self::with_poll_interval()) and calls
+    /// `on_update` each time the config changes compared to the previously fetched one (initially, `current`).
+    /// Fetch errors are logged and do not stop polling. Returns immediately if polling is not enabled.
+    pub async fn poll(&self, mut current: ConfigSource, mut on_update: impl FnMut(&ConfigSource)) {
+        let Some(poll_interval) = self.poll_interval else {
+            return;
+        };
+        loop {
+            tokio::time::sleep(poll_interval).await;
+            match self.fetch().await {
+                Ok(config) if config != current => {
+                    tracing::info!("Remote config was updated");
+                    on_update(&config);
+                    current = config;
+                }
+                Ok(_) => { /* config is not changed */ }
+                Err(err) => {
+                    tracing::warn!("Failed polling remote config: {err:#}");
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::EnvMutex;
+
+    static MUTEX: EnvMutex = EnvMutex::new();
+
+    #[test]
+    fn parsing_remote_config_locations() {
+        let location = RemoteConfigLocation::parse("https://config.example.com/en.yaml").unwrap();
+        assert_eq!(
+            location,
+            RemoteConfigLocation::Http {
+                url: "https://config.example.com/en.yaml".to_owned()
+            }
+        );
+
+        let location = RemoteConfigLocation::parse("consul://localhost:8500/zksync/en").unwrap();
+        assert_eq!(
+            location,
+            RemoteConfigLocation::Consul {
+                address: "http://localhost:8500".to_owned(),
+                key: "zksync/en".to_owned(),
+            }
+        );
+
+        let location = RemoteConfigLocation::parse("etcd+https://etcd:2379/zksync/en").unwrap();
+        assert_eq!(
+            location,
+            RemoteConfigLocation::Etcd {
+                address: "https://etcd:2379".to_owned(),
+                key: "zksync/en".to_owned(),
+            }
+        );
+
+        RemoteConfigLocation::parse("ftp://localhost/config").unwrap_err();
+        RemoteConfigLocation::parse("consul://localhost:8500").unwrap_err();
+        RemoteConfigLocation::parse("localhost:8500").unwrap_err();
+    }
+
+    #[test]
+    fn remote_config_source_from_env() {
+        let mut lock = MUTEX.lock();
+        lock.remove_env(&[
+            "REMOTE_CONFIG_URL",
+            "REMOTE_CONFIG_FORMAT",
+            "REMOTE_CONFIG_TOKEN",
+            "REMOTE_CONFIG_POLL_INTERVAL_SEC",
+            "REMOTE_CONFIG_CONNECT_TIMEOUT_SEC",
+            "REMOTE_CONFIG_REQUEST_TIMEOUT_SEC",
+        ]);
+        assert!(RemoteConfigSource::from_env().unwrap().is_none());
+
+        lock.set_env(
+            r#"
+            REMOTE_CONFIG_URL=consul://localhost:8500/zksync/en
+            REMOTE_CONFIG_FORMAT=toml
+            REMOTE_CONFIG_POLL_INTERVAL_SEC=30
+            "#,
+        );
+        let source = RemoteConfigSource::from_env().unwrap().unwrap();
+        assert_eq!(source.format, ConfigFileFormat::Toml);
+        assert_eq!(source.poll_interval(), Some(Duration::from_secs(30)));
+        assert_eq!(source.token, None);
+        assert_eq!(
+            source.connect_timeout,
+            RemoteConfigSource::DEFAULT_CONNECT_TIMEOUT
+        );
+        assert_eq!(
+            source.request_timeout,
+            RemoteConfigSource::DEFAULT_REQUEST_TIMEOUT
+        );
+
+        lock.set_env(
+            r#"
+            REMOTE_CONFIG_CONNECT_TIMEOUT_SEC=1
+            REMOTE_CONFIG_REQUEST_TIMEOUT_SEC=10
+            "#,
+        );
+        let source = RemoteConfigSource::from_env().unwrap().unwrap();
+        assert_eq!(source.connect_timeout, Duration::from_secs(1));
+        assert_eq!(source.request_timeout, Duration::from_secs(10));
+
+        lock.set_env("REMOTE_CONFIG_REQUEST_TIMEOUT_SEC=soon");
+        let err = RemoteConfigSource::from_env().unwrap_err().to_string();
+        assert!(err.contains("REMOTE_CONFIG_REQUEST_TIMEOUT_SEC"), "{err}");
+        lock.set_env("REMOTE_CONFIG_REQUEST_TIMEOUT_SEC=10");
+
+        lock.set_env("REMOTE_CONFIG_FORMAT=json");
+        let err = RemoteConfigSource::from_env().unwrap_err().to_string();
+        assert!(err.contains("REMOTE_CONFIG_FORMAT"), "{err}");
+    }
+}
@@ -0,0 +1,192 @@
+//! AWS Secrets Manager provider.
+
+use std::{env, fmt};
+
+use anyhow::Context as _;
+use async_trait::async_trait;
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+
+use super::{extract_key, http_client, SecretReference, SecretsProvider};
+
+const SERVICE: &str = "secretsmanager";
+const TARGET: &str = "secretsmanager.GetSecretValue";
+const CONTENT_TYPE: &str = "application/x-amz-json-1.1";
+
+/// Static AWS credentials.
+#[derive(Clone)]
+pub struct AwsCredentials {
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    pub session_token: Option<String>,
+}
+
+impl fmt::Debug for AwsCredentials {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("AwsCredentials")
+            .field("access_key_id", &self.access_key_id)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Provider for secrets stored in AWS Secrets Manager.
+///
+/// References have the `aws-sm://<secret_id>[#<key>]` format. If the key is specified, the secret string
+/// is parsed as a JSON object, and the value for the key is returned; otherwise, the entire secret string
+/// is returned.
+#[derive(Debug)]
+pub struct AwsSecretsManagerProvider {
+    client: reqwest::Client,
+    region: String,
+    credentials: AwsCredentials,
+}
+
+impl AwsSecretsManagerProvider {
+    pub const SCHEME: &'static str = "aws-sm";
+
+    pub fn new(region: String, credentials: AwsCredentials) -> Self {
+        Self {
+            client: http_client(),
+            region,
+            credentials,
+        }
+    }
+
+    /// Env variable enabling the provider.
+    pub const ENABLED_ENV_VAR: &'static str = "SECRETS_AWS_SM_ENABLED";
+
+    /// Creates a provider if it's enabled by setting `SECRETS_AWS_SM_ENABLED=true`; otherwise, returns `Ok(None)`.
+    /// The provider is configured using standard AWS env variables: `AWS_REGION`, `AWS_ACCESS_KEY_ID`,
+    /// `AWS_SECRET_ACCESS_KEY` and (optionally) `AWS_SESSION_TOKEN`.
+    ///
+    /// Only static credentials are supported. Standard AWS variables are not used to enable the provider,
+    /// so that nodes authenticating to AWS in other ways (e.g., via IAM roles for service accounts) are not affected.
+    pub fn from_env() -> anyhow::Result<Option<Self>> {
+        let is_enabled = match env::var(Self::ENABLED_ENV_VAR) {
+            Ok(value) => value
+                .parse::<bool>()
+                .with_context(|| format!("invalid {}", Self::ENABLED_ENV_VAR))?,
+            Err(_) => false,
+        };
+        if !is_enabled {
+            return Ok(None);
+        }
+
+        let region = env::var("AWS_REGION")
+            .context("AWS Secrets Manager provider is enabled, but AWS_REGION is not set")?;
+        let credentials = AwsCredentials {
+            access_key_id: env::var("AWS_ACCESS_KEY_ID").context(
+                "AWS Secrets Manager provider is enabled, but AWS_ACCESS_KEY_ID is not set",
+            )?,
+            secret_access_key: env::var("AWS_SECRET_ACCESS_KEY").context(
+                "AWS Secrets Manager provider is enabled, but AWS_SECRET_ACCESS_KEY is not set",
+            )?,
+            session_token: env::var("AWS_SESSION_TOKEN").ok(),
+        };
+        Ok(Some(Self::new(region, credentials)))
+    }
+
+    fn host(&self) -> String {
+        format!("{SERVICE}.{}.amazonaws.com", self.region)
+    }
+
+    /// Computes the `Authorization` header according to the AWS Signature Version 4 process.
+    fn authorization(&self, host: &str, amz_date: &str, body: &[u8]) -> String {
+        let date = &amz_date[..8];
+        let mut headers = vec![
+            ("content-type", CONTENT_TYPE),
+            ("host", host),
+            ("x-amz-date", amz_date),
+            ("x-amz-target", TARGET),
+        ];
+        if let Some(token) = &self.credentials.session_token {
+            headers.push(("x-amz-security-token", token.as_str()));
+        }
+        headers.sort_unstable_by_key(|(name, _)| *name);
+
+        let canonical_headers: String = headers
+            .iter()
+            .map(|(name, value)| format!("{name}:{value}\n"))
+            .collect();
+        let signed_headers = headers
+            .iter()
+            .map(|(name, _)| *name)
+            .collect::<Vec<_>>()
+            .join(";");
+        let canonical_request = format!(
+            "POST\n/\n\n{canonical_headers}\n{signed_headers}\n{}",
+            hex::encode(Sha256::digest(body))
+        );
+
+        let scope = format!("{date}/{}/{SERVICE}/aws4_request", self.region);
+        let string_to_sign = format!(
+            "AWS4-HMAC-SHA256\n{amz_date}\n{scope}\n{}",
+            hex::encode(Sha256::digest(canonical_request.as_bytes()))
+        );
+        let signing_key = signing_key(&self.credentials.secret_access_key, date, &self.region);
+        let signature = hex::encode(hmac_sha256(&signing_key, string_to_sign.as_bytes()));
+
+        format!(
+            "AWS4-HMAC-SHA256 Credential={}/{scope}, SignedHeaders={signed_headers}, Signature={signature}",
+            self.credentials.access_key_id
+        )
+    }
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any size");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn signing_key(secret_access_key: &str, date: &str, region: &str) -> Vec<u8> {
+    let key = format!("AWS4{secret_access_key}");
+    let key = hmac_sha256(key.as_bytes(), date.as_bytes());
+    let key = hmac_sha256(&key, region.as_bytes());
+    let key = hmac_sha256(&key, SERVICE.as_bytes());
+    hmac_sha256(&key, b"aws4_request")
+}
+
+#[async_trait]
+impl SecretsProvider for AwsSecretsManagerProvider {
+    async fn fetch(&self, reference: &SecretReference) -> anyhow::Result<String> {
+        let host = self.host();
+        let amz_date = chrono::Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+        let body = serde_json::to_vec(&serde_json::json!({ "SecretId": reference.path }))?;
+        let authorization = self.authorization(&host, &amz_date, &body);
+
+        let mut request = self
+            .client
+            .post(format!("https://{host}/"))
+            .header("content-type", CONTENT_TYPE)
+            .header("x-amz-date", &amz_date)
+            .header("x-amz-target", TARGET)
+            .header("authorization", authorization);
+        if let Some(token) = &self.credentials.session_token {
+            request = request.header("x-amz-security-token", token);
+        }
+        let response: serde_json::Value = request
+            .body(body)
+            .send()
+            .await
+            .context("failed sending request to AWS Secrets Manager")?
+            .error_for_status()
+            .context("AWS Secrets Manager returned error response")?
+            .json()
+            .await
+            .context("failed parsing AWS Secrets Manager response")?;
+        let secret_string = response
+            .get("SecretString")
+            .and_then(serde_json::Value::as_str)
+            .context("secret does not contain a string value")?;
+
+        if let Some(key) = &reference.key {
+            let secret: serde_json::Value = serde_json::from_str(secret_string)
+                .context("secret string is not a JSON object")?;
+            extract_key(&secret, key)
+        } else {
+            Ok(secret_string.to_owned())
+        }
+    }
+}
@@ -0,0 +1,220 @@
+//! Pluggable resolution of secret config values.
+//!
+//! Instead of a plain value, an env variable may contain a reference to a secret stored in an external
+//! secrets manager, e.g. `ETH_SENDER_SENDER_OPERATOR_PRIVATE_KEY=vault://secret/zksync/operator#private_key`.
+//! References have the `<scheme>://<path>[#<key>]` format, where `scheme` determines the [`SecretsProvider`]
+//! used to resolve the reference. Supported providers are:
+//!
+//! - `vault://<mount>/<path>#<key>`: HashiCorp Vault KV v2 engine (see [`VaultSecretsProvider`])
+//! - `aws-sm://<secret_id>[#<key>]`: AWS Secrets Manager (see [`AwsSecretsManagerProvider`])
+//!
+//! Secrets are resolved once on node startup (see [`SecretsResolver::resolve_env()`]), and resolved values
+//! are not refreshed afterwards. Thus, rotating a secret requires restarting the node.
+
+use std::{
+    collections::HashMap,
+    env, fmt,
+    sync::{Arc, Mutex, PoisonError},
+    time::Duration,
+};
+
+use anyhow::Context as _;
+use async_trait::async_trait;
+
+pub use self::{aws::AwsSecretsManagerProvider, vault::VaultSecretsProvider};
+
+mod aws;
+#[cfg(test)]
+mod tests;
+mod vault;
+
+/// Reference to a secret in an external secrets manager.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SecretReference {
+    /// Scheme identifying the provider, e.g. `vault`.
+    pub scheme: String,
+    /// Provider-specific path to the secret.
+    pub path: String,
+    /// Optional key within the secret if the secret contains multiple values.
+    pub key: Option<String>,
+}
+
+impl SecretReference {
+    /// Parses a reference from the provided string. Returns `None` if the string is not a reference.
+    pub fn parse(value: &str) -> Option<Self> {
+        let (scheme, rest) = value.split_once("://")?;
+        let is_valid_scheme = !scheme.is_empty()
+            && scheme
+                .chars()
+                .all(|ch| ch.is_ascii_lowercase() || ch.is_ascii_digit() || ch == '-');
+        if !is_valid_scheme || rest.is_empty() {
+            return None;
+        }
+        let (path, key) = match rest.split_once('#') {
+            Some((path, key)) => (path, Some(key.to_owned())),
+            None => (rest, None),
+        };
+        Some(Self {
+            scheme: scheme.to_owned(),
+            path: path.to_owned(),
+            key,
+        })
+    }
+}
+
+impl fmt::Display for SecretReference {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(formatter, "{}://{}", self.scheme, self.path)?;
+        if let Some(key) = &self.key {
+            write!(formatter, "#{key}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Provider of secret values, such as a secrets manager.
+#[async_trait]
+pub trait SecretsProvider: fmt::Debug + Send + Sync {
+    /// Fetches the secret value for the specified reference.
+    async fn fetch(&self, reference: &SecretReference) -> anyhow::Result<String>;
+}
+
+/// Timeout for establishing connections to secrets managers.
+const HTTP_CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+/// Timeout for entire requests to secrets managers.
+const HTTP_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Creates an HTTP client for secrets providers. Secrets are resolved on node startup, so an unresponsive
+/// secrets manager should fail the startup rather than hang it.
+fn http_client() -> reqwest::Client {
+    reqwest::Client::builder()
+        .connect_timeout(HTTP_CONNECT_TIMEOUT)
+        .timeout(HTTP_REQUEST_TIMEOUT)
+        .build()
+        .expect("failed building HTTP client")
+}
+
+/// Resolves secret references in env variables using registered [`SecretsProvider`]s.
+///
+/// Resolved values are cached for the lifetime of the resolver, so that a secret referenced by multiple
+/// env variables is fetched once.
+#[derive(Default)]
+pub struct SecretsResolver {
+    providers: HashMap<String, Arc<dyn SecretsProvider>>,
+    cache: Mutex<HashMap<SecretReference, String>>,
+}
+
+impl fmt::Debug for SecretsResolver {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("SecretsResolver")
+            .field("providers", &self.providers)
+            .finish_non_exhaustive()
+    }
+}
+
+impl SecretsResolver {
+    /// Creates a resolver without any providers.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a resolver with providers configured via env variables:
+    ///
+    /// - Vault is enabled if `VAULT_ADDR` is set (see [`VaultSecretsProvider::from_env()`]).
+    /// - AWS Secrets Manager is enabled if `SECRETS_AWS_SM_ENABLED=true` (see [`AwsSecretsManagerProvider::from_env()`]).
+    ///   Unlike Vault, it is not enabled implicitly, since standard AWS env variables are commonly set
+    ///   for other purposes.
+    pub fn from_env() -> anyhow::Result<Self> {
+        let mut this = Self::new();
+        if let Some(provider) = VaultSecretsProvider::from_env()? {
+            this = this.with_provider(VaultSecretsProvider::SCHEME, provider);
+        }
+        if let Some(provider) = AwsSecretsManagerProvider::from_env()? {
+            this = this.with_provider(AwsSecretsManagerProvider::SCHEME, provider);
+        }
+        Ok(this)
+    }
+
+    /// Registers a provider for the specified reference scheme.
+    pub fn with_provider(mut self, scheme: &str, provider: impl SecretsProvider + 'static) -> Self {
+        self.providers.insert(scheme.to_owned(), Arc::new(provider));
+        self
+    }
+
+    fn provider(&self, reference: &SecretReference) -> Option<&dyn SecretsProvider> {
+        self.providers
+            .get(&reference.scheme)
+            .map(|provider| &**provider)
+    }
+
+    /// Resolves the provided value. Returns `Ok(None)` if the value is not a reference
+    /// or its scheme doesn't have a registered provider.
+    pub async fn resolve(&self, value: &str) -> anyhow::Result<Option<String>> {
+        let Some(reference) = SecretReference::parse(value) else {
+            return Ok(None);
+        };
+        if self.provider(&reference).is_none() {
+            return Ok(None);
+        }
+        self.resolve_reference(&reference).await.map(Some)
+    }
+
+    async fn resolve_reference(&self, reference: &SecretReference) -> anyhow::Result<String> {
+        {
+            let cache = self.cache.lock().unwrap_or_else(PoisonError::into_inner);
+            if let Some(value) = cache.get(reference) {
+                return Ok(value.clone());
+            }
+        }
+
+        let provider = self
+            .provider(reference)
+            .with_context(|| format!("no provider for scheme `{}`", reference.scheme))?;
+        let value = provider
+            .fetch(reference)
+            .await
+            .with_context(|| format!("failed fetching secret `{reference}`"))?;
+        self.cache
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(reference.clone(), value.clone());
+        Ok(value)
+    }
+
+    /// Replaces all env variables containing references with resolved secret values. Returns the names
+    /// of replaced variables.
+    ///
+    /// This should be called at the very start of a binary, before any config is loaded.
+    pub async fn resolve_env(&self) -> anyhow::Result<Vec<String>> {
+        let references: Vec<_> = env::vars()
+            .filter_map(|(name, value)| {
+                let reference = SecretReference::parse(&value)?;
+                self.provider(&reference)?;
+                Some((name, reference))
+            })
+            .collect();
+
+        for (name, reference) in &references {
+            let value = self
+                .resolve_reference(reference)
+                .await
+                .with_context(|| format!("cannot resolve env variable {name}"))?;
+            env::set_var(name, value);
+        }
+
+        Ok(references.into_iter().map(|(name, _)| name).collect())
+    }
+}
+
+/// Extracts a value from a JSON object holding multiple secret values.
+fn extract_key(secret: &serde_json::Value, key: &str) -> anyhow::Result<String> {
+    match secret.get(key) {
+        Some(serde_json::Value::String(value)) => Ok(value.clone()),
+        Some(value @ (serde_json::Value::Number(_) | serde_json::Value::Bool(_))) => {
+            Ok(value.to_string())
+        }
+        Some(_) => anyhow::bail!("value for key `{key}` is not a scalar"),
+        None => anyhow::bail!("key `{key}` is missing in the secret"),
+    }
+}
@@ -0,0 +1,140 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use super::*;
+use crate::test_utils::EnvMutex;
+
+static MUTEX: EnvMutex = EnvMutex::new();
+
+#[derive(Debug, Default)]
+struct MockProvider {
+    secrets: Mutex<HashMap<String, String>>,
+    fetch_count: AtomicUsize,
+}
+
+impl MockProvider {
+    fn set(&self, path: &str, value: &str) {
+        self.secrets
+            .lock()
+            .unwrap()
+            .insert(path.to_owned(), value.to_owned());
+    }
+}
+
+#[async_trait]
+impl SecretsProvider for Arc<MockProvider> {
+    async fn fetch(&self, reference: &SecretReference) -> anyhow::Result<String> {
+        self.fetch_count.fetch_add(1, Ordering::SeqCst);
+        let secrets = self.secrets.lock().unwrap();
+        secrets
+            .get(&reference.path)
+            .cloned()
+            .context("secret not found")
+    }
+}
+
+#[test]
+fn parsing_secret_references() {
+    let reference = SecretReference::parse("vault://secret/zksync/operator#private_key").unwrap();
+    assert_eq!(reference.scheme, "vault");
+    assert_eq!(reference.path, "secret/zksync/operator");
+    assert_eq!(reference.key.as_deref(), Some("private_key"));
+    assert_eq!(
+        reference.to_string(),
+        "vault://secret/zksync/operator#private_key"
+    );
+
+    let reference = SecretReference::parse("aws-sm://prod/database_url").unwrap();
+    assert_eq!(reference.scheme, "aws-sm");
+    assert_eq!(reference.path, "prod/database_url");
+    assert_eq!(reference.key, None);
+
+    // Ordinary values should not be treated as references.
+    assert_eq!(SecretReference::parse("plain"), None);
+    assert_eq!(SecretReference::parse("vault://"), None);
+    assert_eq!(SecretReference::parse("Postgres://user@host/db"), None);
+    assert_eq!(SecretReference::parse("://localhost"), None);
+}
+
+#[tokio::test]
+async fn resolving_and_caching_secrets() {
+    let provider = Arc::<MockProvider>::default();
+    provider.set("db_url", "postgres://localhost/zksync");
+    let resolver = SecretsResolver::new().with_provider("mock", provider.clone());
+
+    let value = resolver.resolve("mock://db_url").await.unwrap();
+    assert_eq!(value.as_deref(), Some("postgres://localhost/zksync"));
+    let value = resolver.resolve("mock://db_url").await.unwrap();
+    assert_eq!(value.as_deref(), Some("postgres://localhost/zksync"));
+    assert_eq!(provider.fetch_count.load(Ordering::SeqCst), 1);
+
+    // Values with unknown schemes should be left as is.
+    let value = resolver.resolve("unknown://db_url").await.unwrap();
+    assert_eq!(value, None);
+    resolver.resolve("mock://missing").await.unwrap_err();
+}
+
+#[tokio::test]
+async fn resolving_env() {
+    let mut lock = MUTEX.lock();
+    lock.set_env(
+        r#"
+        SECRETS_TEST_OPERATOR_KEY=mock://operator_key
+        SECRETS_TEST_OPERATOR_KEY_COPY=mock://operator_key
+        SECRETS_TEST_PLAIN_VALUE=mock
+        "#,
+    );
+
+    let provider = Arc::<MockProvider>::default();
+    provider.set("operator_key", "0x01");
+    let resolver = SecretsResolver::new().with_provider("mock", provider.clone());
+    let mut resolved_vars = resolver.resolve_env().await.unwrap();
+    resolved_vars.sort_unstable();
+    assert_eq!(
+        resolved_vars,
+        [
+            "SECRETS_TEST_OPERATOR_KEY",
+            "SECRETS_TEST_OPERATOR_KEY_COPY"
+        ]
+    );
+    assert_eq!(env::var("SECRETS_TEST_OPERATOR_KEY").unwrap(), "0x01");
+    assert_eq!(env::var("SECRETS_TEST_OPERATOR_KEY_COPY").unwrap(), "0x01");
+    assert_eq!(env::var("SECRETS_TEST_PLAIN_VALUE").unwrap(), "mock");
+    // The secret referenced by both variables should be fetched once.
+    assert_eq!(provider.fetch_count.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn aws_provider_requires_opt_in() {
+    let mut lock = MUTEX.lock();
+    lock.set_env(
+        r#"
+        AWS_REGION=us-east-1
+        "#,
+    );
+    lock.remove_env(&[
+        AwsSecretsManagerProvider::ENABLED_ENV_VAR,
+        "AWS_ACCESS_KEY_ID",
+        "AWS_SECRET_ACCESS_KEY",
+    ]);
+    // Standard AWS variables alone (e.g., set for IAM roles for service accounts) must not enable the provider.
+    assert!(AwsSecretsManagerProvider::from_env().unwrap().is_none());
+
+    lock.set_env(
+        r#"
+        SECRETS_AWS_SM_ENABLED=true
+        "#,
+    );
+    let err = AwsSecretsManagerProvider::from_env().unwrap_err();
+    assert!(err.to_string().contains("AWS_ACCESS_KEY_ID"), "{err}");
+
+    lock.set_env(
+        r#"
+        AWS_ACCESS_KEY_ID=AKIDEXAMPLE
+        AWS_SECRET_ACCESS_KEY=secret
+        "#,
+    );
+    let provider = AwsSecretsManagerProvider::from_env().unwrap().unwrap();
+    let provider = format!("{provider:?}");
+    assert!(provider.contains("us-east-1"), "{provider}");
+    assert!(!provider.contains("secret_access_key"), "{provider}");
+}
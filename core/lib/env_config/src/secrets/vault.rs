@@ -0,0 +1,89 @@
+//! HashiCorp Vault secrets provider.
+
+use std::{env, fmt};
+
+use anyhow::Context as _;
+use async_trait::async_trait;
+
+use super::{extract_key, http_client, SecretReference, SecretsProvider};
+
+/// Provider for secrets stored in the [KV v2] secrets engine of HashiCorp Vault.
+///
+/// References have the `vault://<mount>/<path>#<key>` format; e.g., `vault://secret/zksync/operator#private_key`
+/// refers to the `private_key` in the `zksync/operator` secret of the engine mounted at `secret`.
+///
+/// [KV v2]: https://developer.hashicorp.com/vault/docs/secrets/kv/kv-v2
+pub struct VaultSecretsProvider {
+    client: reqwest::Client,
+    address: String,
+    token: String,
+    namespace: Option<String>,
+}
+
+impl fmt::Debug for VaultSecretsProvider {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("VaultSecretsProvider")
+            .field("address", &self.address)
+            .field("namespace", &self.namespace)
+            .finish_non_exhaustive()
+    }
+}
+
+impl VaultSecretsProvider {
+    pub const SCHEME: &'static str = "vault";
+
+    pub fn new(address: String, token: String, namespace: Option<String>) -> Self {
+        Self {
+            client: http_client(),
+            address: address.trim_end_matches('/').to_owned(),
+            token,
+            namespace,
+        }
+    }
+
+    /// Creates a provider using standard Vault env variables: `VAULT_ADDR`, `VAULT_TOKEN` and (optionally)
+    /// `VAULT_NAMESPACE`. Returns `Ok(None)` if `VAULT_ADDR` is not set.
+    pub fn from_env() -> anyhow::Result<Option<Self>> {
+        let Ok(address) = env::var("VAULT_ADDR") else {
+            return Ok(None);
+        };
+        let token = env::var("VAULT_TOKEN").context("VAULT_ADDR is set, but VAULT_TOKEN is not")?;
+        let namespace = env::var("VAULT_NAMESPACE").ok();
+        Ok(Some(Self::new(address, token, namespace)))
+    }
+}
+
+#[async_trait]
+impl SecretsProvider for VaultSecretsProvider {
+    async fn fetch(&self, reference: &SecretReference) -> anyhow::Result<String> {
+        let (mount, path) = reference
+            .path
+            .split_once('/')
+            .context("Vault reference must have `<mount>/<path>` format")?;
+        let key = reference
+            .key
+            .as_deref()
+            .context("Vault reference must specify a key")?;
+
+        let url = format!("{}/v1/{mount}/data/{path}", self.address);
+        let mut request = self.client.get(url).header("X-Vault-Token", &self.token);
+        if let Some(namespace) = &self.namespace {
+            request = request.header("X-Vault-Namespace", namespace);
+        }
+        let response: serde_json::Value = request
+            .send()
+            .await
+            .context("failed sending request to Vault")?
+            .error_for_status()
+            .context("Vault returned error response")?
+            .json()
+            .await
+            .context("failed parsing Vault response")?;
+        // KV v2 responses wrap secret data as `{ "data": { "data": { .. }, "metadata": { .. } } }`.
+        let data = response
+            .pointer("/data/data")
+            .context("unexpected Vault response format")?;
+        extract_key(data, key)
+    }
+}
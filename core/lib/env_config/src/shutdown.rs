@@ -0,0 +1,33 @@
+use zksync_config::configs::ShutdownConfig;
+
+use crate::{envy_load, FromEnv};
+
+impl FromEnv for ShutdownConfig {
+    fn from_env() -> anyhow::Result<Self> {
+        envy_load("shutdown", "SHUTDOWN_")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::test_utils::EnvMutex;
+
+    static MUTEX: EnvMutex = EnvMutex::new();
+
+    #[test]
+    fn from_env() {
+        let mut lock = MUTEX.lock();
+        lock.remove_env(&["SHUTDOWN_TIMEOUT_MS"]);
+        let config = ShutdownConfig::from_env().unwrap();
+        assert_eq!(config, ShutdownConfig::default());
+        assert_eq!(config.timeout(), Duration::from_secs(20));
+
+        lock.set_env("SHUTDOWN_TIMEOUT_MS=\"30000\"");
+        let config = ShutdownConfig::from_env().unwrap();
+        assert_eq!(config.timeout_ms, Some(30_000));
+        assert_eq!(config.timeout(), Duration::from_secs(30));
+    }
+}
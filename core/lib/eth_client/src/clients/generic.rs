@@ -2,12 +2,13 @@ use std::sync::Arc;
 
 use async_trait::async_trait;
 use zksync_types::{
+    eth_sender::EthTxBlobSidecar,
     web3::{
         contract::Options,
         ethabi,
         types::{
-            Address, Block, BlockId, BlockNumber, Filter, Log, Transaction, TransactionReceipt,
-            H160, H256, U256, U64,
+            Address, Block, BlockId, BlockNumber, Bytes, CallRequest, Filter, Log, Transaction,
+            TransactionReceipt, H160, H256, U256, U64,
         },
     },
     L1ChainId,
@@ -42,6 +43,17 @@ impl<C: EthInterface + ?Sized> EthInterface for Arc<C> {
             .await
     }
 
+    async fn blob_base_fee_history(
+        &self,
+        from_block: usize,
+        block_count: usize,
+        component: &'static str,
+    ) -> Result<Vec<u64>, Error> {
+        self.as_ref()
+            .blob_base_fee_history(from_block, block_count, component)
+            .await
+    }
+
     async fn get_pending_block_base_fee_per_gas(
         &self,
         component: &'static str,
@@ -102,6 +114,15 @@ impl<C: EthInterface + ?Sized> EthInterface for Arc<C> {
         self.as_ref().call_contract_function(call).await
     }
 
+    async fn call(
+        &self,
+        request: CallRequest,
+        block: Option<BlockId>,
+        component: &'static str,
+    ) -> Result<Bytes, Error> {
+        self.as_ref().call(request, block, component).await
+    }
+
     async fn logs(&self, filter: Filter, component: &'static str) -> Result<Vec<Log>, Error> {
         self.as_ref().logs(filter, component).await
     }
@@ -156,6 +177,27 @@ impl<C: BoundEthInterface + ?Sized> BoundEthInterface for Arc<C> {
             .await
     }
 
+    async fn sign_prepared_blob_tx_for_addr(
+        &self,
+        data: Vec<u8>,
+        contract_addr: H160,
+        options: Options,
+        max_fee_per_blob_gas: U256,
+        blob_sidecar: EthTxBlobSidecar,
+        component: &'static str,
+    ) -> Result<SignedCallResult, Error> {
+        self.as_ref()
+            .sign_prepared_blob_tx_for_addr(
+                data,
+                contract_addr,
+                options,
+                max_fee_per_blob_gas,
+                blob_sidecar,
+                component,
+            )
+            .await
+    }
+
     async fn nonce_at(&self, block: BlockNumber, component: &'static str) -> Result<U256, Error> {
         self.as_ref().nonce_at(block, component).await
     }
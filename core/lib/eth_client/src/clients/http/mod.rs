@@ -5,10 +5,16 @@ use vise::{
 };
 
 pub use self::{
+    private_relay::PrivateRelayClient,
     query::QueryClient,
-    signing::{PKSigningClient, SigningClient},
+    signing::{
+        blob_operator_signer, operator_signer, secondary_operator_signer, DynSigningClient,
+        PKSigningClient, SigningClient,
+    },
 };
 
+mod failover;
+mod private_relay;
 mod query;
 mod signing;
 
@@ -20,12 +26,14 @@ enum Method {
     GetGasPrice,
     SendRawTx,
     BaseFeeHistory,
+    BlobBaseFeeHistory,
     #[metrics(name = "get_pending_block_base_fee_per_gas")]
     PendingBlockBaseFee,
     GetTxStatus,
     FailureReason,
     GetTx,
     CallContractFunction,
+    Call,
     TxReceipt,
     EthBalance,
     Logs,
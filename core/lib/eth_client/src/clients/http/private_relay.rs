@@ -0,0 +1,229 @@
+//! Client submitting L1 transactions via a private relay (e.g., Flashbots Protect or a bundle relay)
+//! instead of the public mempool.
+
+use std::{fmt, time::Duration};
+
+use anyhow::Context as _;
+use serde::Deserialize;
+use serde_json::json;
+use zksync_config::configs::eth_sender::{TxSubmissionConfig, TxSubmissionMode};
+use zksync_types::{
+    web3::{self, error::TransportError, signing::keccak256},
+    Address, PackedEthSignature, H256,
+};
+
+use crate::{Error, RawTransactionBytes};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+/// Header used by Flashbots-compatible relays to authenticate requests.
+const SIGNATURE_HEADER: &str = "X-Flashbots-Signature";
+
+#[derive(Debug, Deserialize)]
+struct JsonRpcResponse {
+    #[serde(default)]
+    result: Option<serde_json::Value>,
+    #[serde(default)]
+    error: Option<jsonrpc_core::Error>,
+}
+
+/// Returns the EIP-191 (`personal_sign`) message for the hex-encoded Keccak-256 hash of the request body.
+fn eip191_message(body: &str) -> Vec<u8> {
+    let body_hash = format!("{:?}", H256(keccak256(body.as_bytes())));
+    let mut message = format!("\x19Ethereum Signed Message:\n{}", body_hash.len()).into_bytes();
+    message.extend_from_slice(body_hash.as_bytes());
+    message
+}
+
+fn transport_error(err: impl fmt::Display) -> Error {
+    Error::EthereumGateway(web3::Error::Transport(TransportError::Message(
+        err.to_string(),
+    )))
+}
+
+/// Client sending signed L1 transactions to a private RPC endpoint or a bundle relay.
+///
+/// Requests are signed with a relay authentication key as expected by Flashbots-compatible relays. This key
+/// is only used to build the relay reputation and doesn't need to hold any funds; if it's not configured,
+/// a random key is generated on each start.
+#[derive(Clone)]
+pub struct PrivateRelayClient {
+    client: reqwest::Client,
+    url: String,
+    auth_key: H256,
+    auth_address: Address,
+    mode: TxSubmissionMode,
+    bundle_target_blocks: u64,
+}
+
+impl fmt::Debug for PrivateRelayClient {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // The URL may contain an API key, so it's not output.
+        formatter
+            .debug_struct("PrivateRelayClient")
+            .field("auth_address", &self.auth_address)
+            .field("mode", &self.mode)
+            .field("bundle_target_blocks", &self.bundle_target_blocks)
+            .finish_non_exhaustive()
+    }
+}
+
+impl PrivateRelayClient {
+    /// Creates a client based on the configuration. Returns `None` if transactions should be sent
+    /// to the public mempool.
+    pub fn from_config(config: &TxSubmissionConfig) -> anyhow::Result<Option<Self>> {
+        if config.mode == TxSubmissionMode::PublicMempool {
+            return Ok(None);
+        }
+        let url = config
+            .relay_url
+            .clone()
+            .context("`relay_url` is required for private transaction submission")?;
+        let auth_key = config.relay_auth_private_key().unwrap_or_else(H256::random);
+        let auth_address = PackedEthSignature::address_from_private_key(&auth_key)
+            .context("invalid relay authentication key")?;
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .context("failed creating HTTP client")?;
+        Ok(Some(Self {
+            client,
+            url,
+            auth_key,
+            auth_address,
+            mode: config.mode,
+            bundle_target_blocks: config.bundle_target_blocks(),
+        }))
+    }
+
+    pub fn mode(&self) -> TxSubmissionMode {
+        self.mode
+    }
+
+    /// Submits a signed transaction. In the bundle mode, the transaction is sent as a single-transaction bundle
+    /// targeting each of the next `bundle_target_blocks` blocks after `current_block`; the submission succeeds
+    /// if at least one bundle is accepted. Returns the transaction hash.
+    pub async fn send_raw_tx(
+        &self,
+        raw_tx: &RawTransactionBytes,
+        current_block: u64,
+    ) -> Result<H256, Error> {
+        let raw_tx_hex = format!("0x{}", hex::encode(raw_tx.as_ref()));
+        match self.mode {
+            TxSubmissionMode::PublicMempool => {
+                unreachable!("client is not created for public mempool submission")
+            }
+            TxSubmissionMode::PrivateRpc => {
+                self.request("eth_sendRawTransaction", json!([raw_tx_hex]))
+                    .await?;
+            }
+            TxSubmissionMode::Bundle => {
+                let mut last_error = None;
+                let mut accepted_bundles = 0;
+                for block_number in current_block + 1..=current_block + self.bundle_target_blocks {
+                    let bundle = json!({
+                        "txs": [&raw_tx_hex],
+                        "blockNumber": format!("{block_number:#x}"),
+                    });
+                    match self.request("eth_sendBundle", json!([bundle])).await {
+                        Ok(_) => accepted_bundles += 1,
+                        Err(err) => {
+                            tracing::debug!(
+                                "Bundle targeting L1 block #{block_number} was not accepted: {err}"
+                            );
+                            last_error = Some(err);
+                        }
+                    }
+                }
+                if accepted_bundles == 0 {
+                    return Err(last_error.expect("no bundles were sent"));
+                }
+            }
+        }
+        Ok(H256(keccak256(raw_tx.as_ref())))
+    }
+
+    async fn request(
+        &self,
+        method: &str,
+        params: serde_json::Value,
+    ) -> Result<serde_json::Value, Error> {
+        let body = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": method,
+            "params": params,
+        })
+        .to_string();
+
+        let response = self
+            .client
+            .post(&self.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(SIGNATURE_HEADER, self.signature_header(&body))
+            .body(body)
+            .send()
+            .await
+            .map_err(|err| transport_error(err.without_url()))?;
+        let status = response.status();
+        if !status.is_success() {
+            let message = response.text().await.unwrap_or_default();
+            return Err(transport_error(format_args!(
+                "relay responded with {status}: {message}"
+            )));
+        }
+        let response: JsonRpcResponse = response
+            .json()
+            .await
+            .map_err(|err| transport_error(err.without_url()))?;
+        if let Some(err) = response.error {
+            return Err(Error::EthereumGateway(web3::Error::Rpc(err)));
+        }
+        Ok(response.result.unwrap_or_default())
+    }
+
+    /// Signs the request body as expected by Flashbots-compatible relays: `{address}:{signature}`, where
+    /// the signature is an EIP-191 signature of the hex-encoded Keccak-256 hash of the body.
+    fn signature_header(&self, body: &str) -> String {
+        let message = eip191_message(body);
+        let signature = PackedEthSignature::sign(&self.auth_key, &message)
+            .expect("failed signing relay request");
+        format!(
+            "{:?}:0x{}",
+            self.auth_address,
+            hex::encode(signature.serialize_packed())
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signature_header_can_be_verified() {
+        let config = TxSubmissionConfig {
+            mode: TxSubmissionMode::Bundle,
+            relay_url: Some("http://127.0.0.1:1".to_owned()),
+            bundle_target_blocks: None,
+        };
+        let client = PrivateRelayClient::from_config(&config).unwrap().unwrap();
+        assert_eq!(client.bundle_target_blocks, 3);
+
+        let body = r#"{"jsonrpc":"2.0","id":1,"method":"eth_sendBundle","params":[]}"#;
+        let header = client.signature_header(body);
+        let (address, signature) = header.split_once(':').unwrap();
+        assert_eq!(address, format!("{:?}", client.auth_address));
+
+        let signature = hex::decode(signature.strip_prefix("0x").unwrap()).unwrap();
+        let signature = PackedEthSignature::deserialize_packed(&signature).unwrap();
+        let signed_bytes = PackedEthSignature::message_to_signed_bytes(&eip191_message(body));
+        let recovered = signature.signature_recover_signer(&signed_bytes).unwrap();
+        assert_eq!(recovered, client.auth_address);
+    }
+
+    #[test]
+    fn client_is_not_created_for_public_mempool() {
+        let client = PrivateRelayClient::from_config(&TxSubmissionConfig::default()).unwrap();
+        assert!(client.is_none());
+    }
+}
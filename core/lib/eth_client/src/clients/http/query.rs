@@ -1,35 +1,49 @@
 use std::sync::Arc;
 
 use async_trait::async_trait;
+use serde::Deserialize;
+use zksync_config::ETHClientConfig;
 use zksync_types::web3::{
     self,
     contract::Contract,
     ethabi,
+    helpers::{self, CallFuture},
     transports::Http,
     types::{
-        Address, Block, BlockId, BlockNumber, Bytes, Filter, Log, Transaction, TransactionId,
-        TransactionReceipt, H256, U256, U64,
+        Address, Block, BlockId, BlockNumber, Bytes, CallRequest, Filter, Log, Transaction,
+        TransactionId, TransactionReceipt, H256, U256, U64,
     },
-    Web3,
+    Transport, Web3,
 };
 
 use crate::{
-    clients::http::{Method, COUNTERS, LATENCIES},
+    clients::http::{failover::FailoverTransport, Method, COUNTERS, LATENCIES},
     types::{Error, ExecutedTxStatus, FailureInfo, RawTokens},
     ContractCall, EthInterface, RawTransactionBytes,
 };
 
+/// Subset of the `eth_feeHistory` response. `web3::types::FeeHistory` doesn't contain
+/// blob base fees introduced in EIP-4844, so we parse the response ourselves.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BlobFeeHistory {
+    base_fee_per_gas: Vec<U256>,
+    /// Absent if the L1 node doesn't support EIP-4844.
+    #[serde(default)]
+    base_fee_per_blob_gas: Vec<U256>,
+}
+
 /// An "anonymous" Ethereum client that can invoke read-only methods that aren't
 /// tied to a particular account.
 #[derive(Debug, Clone)]
 pub struct QueryClient {
-    web3: Arc<Web3<Http>>,
+    web3: Arc<Web3<FailoverTransport>>,
 }
 
 impl From<Http> for QueryClient {
     fn from(transport: Http) -> Self {
         Self {
-            web3: Arc::new(Web3::new(transport)),
+            web3: Arc::new(Web3::new(transport.into())),
         }
     }
 }
@@ -40,6 +54,15 @@ impl QueryClient {
         let transport = Http::new(node_url)?;
         Ok(transport.into())
     }
+
+    /// Creates a new HTTP client for all L1 nodes specified in the config, with health-based failover
+    /// among them. If there are fallback nodes, this must be called from within a Tokio runtime.
+    pub fn from_config(config: &ETHClientConfig) -> Result<Self, Error> {
+        let transport = FailoverTransport::from_config(config)?;
+        Ok(Self {
+            web3: Arc::new(Web3::new(transport)),
+        })
+    }
 }
 
 #[async_trait]
@@ -117,6 +140,46 @@ impl EthInterface for QueryClient {
         Ok(history.into_iter().map(|fee| fee.as_u64()).collect())
     }
 
+    async fn blob_base_fee_history(
+        &self,
+        upto_block: usize,
+        block_count: usize,
+        component: &'static str,
+    ) -> Result<Vec<u64>, Error> {
+        const MAX_REQUEST_CHUNK: usize = 1024;
+
+        COUNTERS.call[&(Method::BlobBaseFeeHistory, component)].inc();
+        let latency = LATENCIES.direct[&Method::BlobBaseFeeHistory].start();
+        let mut history = Vec::with_capacity(block_count);
+        let from_block = upto_block.saturating_sub(block_count);
+
+        // Chunking is the same as in `base_fee_history()`.
+        for chunk_start in (from_block..=upto_block).step_by(MAX_REQUEST_CHUNK) {
+            let chunk_end = (chunk_start + MAX_REQUEST_CHUNK).min(upto_block);
+            let chunk_size = chunk_end - chunk_start;
+            let params = vec![
+                helpers::serialize(&U256::from(chunk_size)),
+                helpers::serialize(&BlockNumber::from(chunk_end)),
+                helpers::serialize(&Vec::<f64>::new()),
+            ];
+            let chunk: BlobFeeHistory =
+                CallFuture::new(self.web3.transport().execute("eth_feeHistory", params)).await?;
+
+            if chunk.base_fee_per_blob_gas.is_empty() {
+                history.extend(chunk.base_fee_per_gas.iter().map(|_| U256::zero()));
+            } else {
+                history.extend(chunk.base_fee_per_blob_gas);
+            }
+        }
+
+        latency.observe();
+        // Blob base fee grows exponentially under congestion, so we saturate instead of panicking.
+        Ok(history
+            .into_iter()
+            .map(|fee| fee.min(U256::from(u64::MAX)).as_u64())
+            .collect())
+    }
+
     async fn get_pending_block_base_fee_per_gas(
         &self,
         component: &'static str,
@@ -249,6 +312,19 @@ impl EthInterface for QueryClient {
         Ok(res)
     }
 
+    async fn call(
+        &self,
+        request: CallRequest,
+        block: Option<BlockId>,
+        component: &'static str,
+    ) -> Result<Bytes, Error> {
+        COUNTERS.call[&(Method::Call, component)].inc();
+        let latency = LATENCIES.direct[&Method::Call].start();
+        let output = self.web3.eth().call(request, block).await?;
+        latency.observe();
+        Ok(output)
+    }
+
     async fn tx_receipt(
         &self,
         tx_hash: H256,
@@ -1,21 +1,27 @@
 use std::{fmt, sync::Arc};
 
+use anyhow::Context as _;
 use async_trait::async_trait;
-use zksync_config::{ContractsConfig, ETHClientConfig, ETHSenderConfig};
+use zksync_config::{
+    configs::eth_sender::{OperatorSignerBackend, OperatorSignerConfig},
+    ContractsConfig, ETHClientConfig, ETHSenderConfig,
+};
 use zksync_contracts::zksync_contract;
-use zksync_eth_signer::{raw_ethereum_tx::TransactionParameters, EthereumSigner, PrivateKeySigner};
+use zksync_eth_signer::{
+    raw_ethereum_tx::{signed_tx_hash, TransactionParameters},
+    AwsKmsSigner, EthereumSigner, GcpKmsSigner, Pkcs11Signer, PrivateKeySigner, Signer,
+};
 use zksync_types::{
+    eth_sender::EthTxBlobSidecar,
     web3::{
-        self,
         contract::{tokens::Detokenize, Options},
         ethabi,
-        transports::Http,
         types::{
-            Address, Block, BlockId, BlockNumber, Filter, Log, Transaction, TransactionReceipt,
-            H160, H256, U256, U64,
+            Address, Block, BlockId, BlockNumber, Bytes, CallRequest, Filter, Log, Transaction,
+            TransactionReceipt, H160, H256, U256, U64,
         },
     },
-    L1ChainId, PackedEthSignature, EIP_1559_TX_TYPE,
+    L1ChainId, PackedEthSignature, EIP_1559_TX_TYPE, EIP_4844_TX_TYPE,
 };
 
 use super::{query::QueryClient, Method, LATENCIES};
@@ -35,7 +41,6 @@ impl PKSigningClient {
     ) -> Self {
         // Gather required data from the config.
         // It's done explicitly to simplify getting rid of this function later.
-        let main_node_url = &eth_client.web3_url;
         let operator_private_key = eth_sender
             .sender
             .private_key()
@@ -44,14 +49,15 @@ impl PKSigningClient {
         let default_priority_fee_per_gas = eth_sender.gas_adjuster.default_priority_fee_per_gas;
         let l1_chain_id = eth_client.chain_id;
 
-        let transport = Http::new(main_node_url).expect("Failed to create transport");
+        let query_client =
+            QueryClient::from_config(eth_client).expect("Failed to create transport");
         let operator_address = PackedEthSignature::address_from_private_key(&operator_private_key)
             .expect("Failed to get address from private key");
 
         tracing::info!("Operator address: {:?}", operator_address);
 
         SigningClient::new(
-            transport,
+            query_client,
             zksync_contract(),
             operator_address,
             PrivateKeySigner::new(operator_private_key),
@@ -62,6 +68,201 @@ impl PKSigningClient {
     }
 }
 
+/// HTTP-based Ethereum client backed by the operator signer selected in the configuration.
+pub type DynSigningClient = SigningClient<Arc<dyn Signer>>;
+
+/// Creates the operator signer according to the configuration. For the private key backend, the key is taken
+/// from `ETHSenderConfig.sender`.
+pub async fn operator_signer(eth_sender: &ETHSenderConfig) -> anyhow::Result<Arc<dyn Signer>> {
+    create_signer(
+        &eth_sender.signer,
+        eth_sender.sender.private_key(),
+        eth_sender.signer.pkcs11_pin(),
+    )
+    .await
+}
+
+/// Creates the secondary operator signer according to the configuration, or returns `None` if the secondary signer
+/// is not configured.
+pub async fn secondary_operator_signer(
+    eth_sender: &ETHSenderConfig,
+) -> anyhow::Result<Option<Arc<dyn Signer>>> {
+    let Some(config) = &eth_sender.secondary_signer else {
+        return Ok(None);
+    };
+    let signer = create_signer(
+        config,
+        eth_sender.secondary_operator_private_key(),
+        eth_sender.secondary_signer_pkcs11_pin(),
+    )
+    .await?;
+    Ok(Some(signer))
+}
+
+/// Creates the signer for the account sending commit transactions according to the configuration, or returns `None`
+/// if the blob signer is not configured.
+pub async fn blob_operator_signer(
+    eth_sender: &ETHSenderConfig,
+) -> anyhow::Result<Option<Arc<dyn Signer>>> {
+    let Some(config) = &eth_sender.blob_signer else {
+        return Ok(None);
+    };
+    let signer = create_signer(
+        config,
+        eth_sender.blob_operator_private_key(),
+        eth_sender.blob_signer_pkcs11_pin(),
+    )
+    .await?;
+    Ok(Some(signer))
+}
+
+async fn create_signer(
+    config: &OperatorSignerConfig,
+    private_key: Option<H256>,
+    pkcs11_pin: Option<String>,
+) -> anyhow::Result<Arc<dyn Signer>> {
+    Ok(match config.backend {
+        OperatorSignerBackend::PrivateKey => {
+            let private_key = private_key
+                .context("operator private key is required for the `PrivateKey` signer backend")?;
+            Arc::new(PrivateKeySigner::new(private_key))
+        }
+        OperatorSignerBackend::AwsKms => {
+            let key_id = required_signer_param(&config.aws_kms_key_id, "aws_kms_key_id")?;
+            let region = required_signer_param(&config.aws_region, "aws_region")?;
+            Arc::new(AwsKmsSigner::new(key_id, region))
+        }
+        OperatorSignerBackend::GcpKms => {
+            let key_version =
+                required_signer_param(&config.gcp_kms_key_version, "gcp_kms_key_version")?;
+            Arc::new(
+                GcpKmsSigner::new(key_version)
+                    .await
+                    .context("failed creating GCP KMS signer")?,
+            )
+        }
+        OperatorSignerBackend::Pkcs11 => {
+            let module_path =
+                required_signer_param(&config.pkcs11_module_path, "pkcs11_module_path")?;
+            let slot_id = config
+                .pkcs11_slot_id
+                .context("`pkcs11_slot_id` is required for the `Pkcs11` signer backend")?;
+            let key_label = required_signer_param(&config.pkcs11_key_label, "pkcs11_key_label")?;
+            let pin =
+                pkcs11_pin.context("PKCS#11 PIN is required for the `Pkcs11` signer backend")?;
+            let signer = tokio::task::spawn_blocking(move || {
+                Pkcs11Signer::new(&module_path, slot_id, &key_label, &pin)
+            })
+            .await
+            .context("PKCS#11 signer initialization panicked")?
+            .context("failed creating PKCS#11 signer")?;
+            Arc::new(signer)
+        }
+    })
+}
+
+fn required_signer_param(value: &Option<String>, name: &str) -> anyhow::Result<String> {
+    value
+        .clone()
+        .with_context(|| format!("`{name}` is required for the configured signer backend"))
+}
+
+impl DynSigningClient {
+    /// Creates a signing client with the operator signer selected in `eth_sender.signer`.
+    pub async fn from_config(
+        eth_sender: &ETHSenderConfig,
+        contracts_config: &ContractsConfig,
+        eth_client: &ETHClientConfig,
+    ) -> anyhow::Result<Self> {
+        let signer = operator_signer(eth_sender).await?;
+        let operator_address = signer
+            .address()
+            .await
+            .context("failed getting operator address from signer")?;
+        tracing::info!(
+            "Operator address: {operator_address:?} (signer backend: {:?})",
+            eth_sender.signer.backend
+        );
+        Self::with_signer(
+            signer,
+            operator_address,
+            eth_sender,
+            contracts_config,
+            eth_client,
+        )
+    }
+
+    /// Creates a signing client with the secondary operator signer selected in `eth_sender.secondary_signer`.
+    /// Returns `None` if the secondary signer is not configured.
+    pub async fn secondary_from_config(
+        eth_sender: &ETHSenderConfig,
+        contracts_config: &ContractsConfig,
+        eth_client: &ETHClientConfig,
+    ) -> anyhow::Result<Option<Self>> {
+        let Some(signer) = secondary_operator_signer(eth_sender).await? else {
+            return Ok(None);
+        };
+        let operator_address = signer
+            .address()
+            .await
+            .context("failed getting secondary operator address from signer")?;
+        tracing::info!("Secondary operator address: {operator_address:?}");
+        Self::with_signer(
+            signer,
+            operator_address,
+            eth_sender,
+            contracts_config,
+            eth_client,
+        )
+        .map(Some)
+    }
+
+    /// Creates a signing client for the account sending commit transactions selected in `eth_sender.blob_signer`.
+    /// Returns `None` if the blob signer is not configured.
+    pub async fn blob_from_config(
+        eth_sender: &ETHSenderConfig,
+        contracts_config: &ContractsConfig,
+        eth_client: &ETHClientConfig,
+    ) -> anyhow::Result<Option<Self>> {
+        let Some(signer) = blob_operator_signer(eth_sender).await? else {
+            return Ok(None);
+        };
+        let operator_address = signer
+            .address()
+            .await
+            .context("failed getting blob operator address from signer")?;
+        tracing::info!("Blob operator address: {operator_address:?}");
+        Self::with_signer(
+            signer,
+            operator_address,
+            eth_sender,
+            contracts_config,
+            eth_client,
+        )
+        .map(Some)
+    }
+
+    fn with_signer(
+        signer: Arc<dyn Signer>,
+        operator_address: Address,
+        eth_sender: &ETHSenderConfig,
+        contracts_config: &ContractsConfig,
+        eth_client: &ETHClientConfig,
+    ) -> anyhow::Result<Self> {
+        let query_client =
+            QueryClient::from_config(eth_client).context("failed creating transport")?;
+        Ok(SigningClient::new(
+            query_client,
+            zksync_contract(),
+            operator_address,
+            signer,
+            contracts_config.diamond_proxy_addr,
+            eth_sender.gas_adjuster.default_priority_fee_per_gas.into(),
+            L1ChainId(eth_client.chain_id),
+        ))
+    }
+}
+
 /// Gas limit value to be used in transaction if for some reason
 /// gas limit was not set for it.
 ///
@@ -133,6 +334,17 @@ impl<S: EthereumSigner> EthInterface for SigningClient<S> {
             .await
     }
 
+    async fn blob_base_fee_history(
+        &self,
+        upto_block: usize,
+        block_count: usize,
+        component: &'static str,
+    ) -> Result<Vec<u64>, Error> {
+        self.query_client
+            .blob_base_fee_history(upto_block, block_count, component)
+            .await
+    }
+
     async fn get_pending_block_base_fee_per_gas(
         &self,
         component: &'static str,
@@ -169,6 +381,15 @@ impl<S: EthereumSigner> EthInterface for SigningClient<S> {
         self.query_client.call_contract_function(call).await
     }
 
+    async fn call(
+        &self,
+        request: CallRequest,
+        block: Option<BlockId>,
+        component: &'static str,
+    ) -> Result<Bytes, Error> {
+        self.query_client.call(request, block, component).await
+    }
+
     async fn tx_receipt(
         &self,
         tx_hash: H256,
@@ -218,6 +439,70 @@ impl<S: EthereumSigner> BoundEthInterface for SigningClient<S> {
         contract_addr: H160,
         options: Options,
         component: &'static str,
+    ) -> Result<SignedCallResult, Error> {
+        self.sign_tx(data, contract_addr, options, None, component)
+            .await
+    }
+
+    async fn sign_prepared_blob_tx_for_addr(
+        &self,
+        data: Vec<u8>,
+        contract_addr: H160,
+        options: Options,
+        max_fee_per_blob_gas: U256,
+        blob_sidecar: EthTxBlobSidecar,
+        component: &'static str,
+    ) -> Result<SignedCallResult, Error> {
+        let blob_params = Some((max_fee_per_blob_gas, blob_sidecar));
+        self.sign_tx(data, contract_addr, options, blob_params, component)
+            .await
+    }
+
+    async fn allowance_on_account(
+        &self,
+        token_address: Address,
+        address: Address,
+        erc20_abi: ethabi::Contract,
+    ) -> Result<U256, Error> {
+        let latency = LATENCIES.direct[&Method::Allowance].start();
+        let args = CallFunctionArgs::new("allowance", (self.inner.sender_account, address))
+            .for_contract(token_address, erc20_abi);
+        let res = self.call_contract_function(args).await?;
+        latency.observe();
+        Ok(U256::from_tokens(res)?)
+    }
+}
+
+impl<S: EthereumSigner> SigningClient<S> {
+    pub fn new(
+        query_client: QueryClient,
+        contract: ethabi::Contract,
+        operator_eth_addr: H160,
+        eth_signer: S,
+        contract_eth_addr: H160,
+        default_priority_fee_per_gas: U256,
+        chain_id: L1ChainId,
+    ) -> Self {
+        Self {
+            inner: Arc::new(ETHDirectClientInner {
+                sender_account: operator_eth_addr,
+                eth_signer,
+                contract_addr: contract_eth_addr,
+                chain_id,
+                contract,
+                default_priority_fee_per_gas,
+            }),
+            query_client,
+        }
+    }
+
+    async fn sign_tx(
+        &self,
+        data: Vec<u8>,
+        contract_addr: H160,
+        options: Options,
+        blob_params: Option<(U256, EthTxBlobSidecar)>,
+        component: &'static str,
     ) -> Result<SignedCallResult, Error> {
         let latency = LATENCIES.direct[&Method::SignPreparedTx].start();
         // Fetch current max priority fee per gas
@@ -258,6 +543,12 @@ impl<S: EthereumSigner> BoundEthInterface for SigningClient<S> {
             U256::from(FALLBACK_GAS_LIMIT)
         });
 
+        let (transaction_type, max_fee_per_blob_gas, blob_tx_sidecar) = match blob_params {
+            Some((max_fee_per_blob_gas, sidecar)) => {
+                (EIP_4844_TX_TYPE, Some(max_fee_per_blob_gas), Some(sidecar))
+            }
+            None => (EIP_1559_TX_TYPE, None, None),
+        };
         let tx = TransactionParameters {
             nonce,
             to: Some(contract_addr),
@@ -267,13 +558,15 @@ impl<S: EthereumSigner> BoundEthInterface for SigningClient<S> {
             chain_id: self.inner.chain_id.0,
             max_priority_fee_per_gas,
             gas_price: None,
-            transaction_type: Some(EIP_1559_TX_TYPE.into()),
+            transaction_type: Some(transaction_type.into()),
             access_list: None,
             max_fee_per_gas,
+            max_fee_per_blob_gas,
+            blob_tx_sidecar,
         };
 
         let signed_tx = self.inner.eth_signer.sign_transaction(tx).await?;
-        let hash = web3::signing::keccak256(&signed_tx).into();
+        let hash = signed_tx_hash(&signed_tx);
         latency.observe();
         Ok(SignedCallResult {
             raw_tx: RawTransactionBytes(signed_tx),
@@ -283,42 +576,4 @@ impl<S: EthereumSigner> BoundEthInterface for SigningClient<S> {
             hash,
         })
     }
-
-    async fn allowance_on_account(
-        &self,
-        token_address: Address,
-        address: Address,
-        erc20_abi: ethabi::Contract,
-    ) -> Result<U256, Error> {
-        let latency = LATENCIES.direct[&Method::Allowance].start();
-        let args = CallFunctionArgs::new("allowance", (self.inner.sender_account, address))
-            .for_contract(token_address, erc20_abi);
-        let res = self.call_contract_function(args).await?;
-        latency.observe();
-        Ok(U256::from_tokens(res)?)
-    }
-}
-
-impl<S: EthereumSigner> SigningClient<S> {
-    pub fn new(
-        transport: Http,
-        contract: ethabi::Contract,
-        operator_eth_addr: H160,
-        eth_signer: S,
-        contract_eth_addr: H160,
-        default_priority_fee_per_gas: U256,
-        chain_id: L1ChainId,
-    ) -> Self {
-        Self {
-            inner: Arc::new(ETHDirectClientInner {
-                sender_account: operator_eth_addr,
-                eth_signer,
-                contract_addr: contract_eth_addr,
-                chain_id,
-                contract,
-                default_priority_fee_per_gas,
-            }),
-            query_client: transport.into(),
-        }
-    }
 }
@@ -6,10 +6,14 @@ use std::{
 use async_trait::async_trait;
 use jsonrpc_core::types::error::Error as RpcError;
 use zksync_types::{
+    eth_sender::EthTxBlobSidecar,
     web3::{
         contract::{tokens::Tokenize, Options},
         ethabi,
-        types::{Block, BlockId, BlockNumber, Filter, Log, Transaction, TransactionReceipt, U64},
+        types::{
+            Block, BlockId, BlockNumber, Bytes, CallRequest, Filter, Log, Transaction,
+            TransactionReceipt, U64,
+        },
         Error as Web3Error,
     },
     Address, L1ChainId, ProtocolVersionId, H160, H256, U256,
@@ -73,6 +77,8 @@ struct MockEthereumInner {
     current_nonce: u64,
     pending_nonce: u64,
     nonces: BTreeMap<u64, u64>,
+    /// If set, all raw `eth_call`s revert with this revert data.
+    call_revert_data: Option<Vec<u8>>,
 }
 
 impl MockEthereumInner {
@@ -110,6 +116,14 @@ impl MockEthereumInner {
         };
         self.tx_statuses.insert(tx_hash, status);
     }
+
+    fn execute_external_tx(&mut self, confirmations: u64) {
+        let block_number = self.block_number;
+        self.block_number += confirmations;
+        self.current_nonce += 1;
+        self.pending_nonce = self.pending_nonce.max(self.current_nonce);
+        self.nonces.insert(block_number, self.current_nonce);
+    }
 }
 
 /// Mock Ethereum client is capable of recording all the incoming requests for the further analysis.
@@ -118,10 +132,14 @@ pub struct MockEthereum {
     max_fee_per_gas: U256,
     max_priority_fee_per_gas: U256,
     base_fee_history: Vec<u64>,
+    /// If empty, the mock emulates an L1 node without EIP-4844 support (i.e., blob base fees are zero).
+    blob_base_fee_history: Vec<u64>,
     /// If true, the mock will not check the ordering nonces of the transactions.
     /// This is useful for testing the cases when the transactions are executed out of order.
     non_ordering_confirmations: bool,
     multicall_address: Address,
+    sender_account: Address,
+    balances: HashMap<Address, U256>,
     inner: RwLock<MockEthereumInner>,
 }
 
@@ -131,8 +149,11 @@ impl Default for MockEthereum {
             max_fee_per_gas: 100.into(),
             max_priority_fee_per_gas: 10.into(),
             base_fee_history: vec![],
+            blob_base_fee_history: vec![],
             non_ordering_confirmations: false,
             multicall_address: Address::default(),
+            sender_account: Address::repeat_byte(0x11),
+            balances: HashMap::new(),
             inner: RwLock::default(),
         }
     }
@@ -166,6 +187,20 @@ impl MockEthereum {
         );
     }
 
+    /// Increments the blocks by a provided `confirmations` and emulates a transaction sent from the operator account
+    /// bypassing this client (e.g., manually), which consumes the next operator nonce.
+    pub fn execute_external_tx(&self, confirmations: u64) {
+        self.inner
+            .write()
+            .unwrap()
+            .execute_external_tx(confirmations);
+    }
+
+    /// Makes all subsequent raw `eth_call`s revert with the specified revert data, or succeed if `None` is provided.
+    pub fn set_call_revert_data(&self, revert_data: Option<Vec<u8>>) {
+        self.inner.write().unwrap().call_revert_data = revert_data;
+    }
+
     pub fn sign_prepared_tx(
         &self,
         mut raw_tx: Vec<u8>,
@@ -209,6 +244,13 @@ impl MockEthereum {
         }
     }
 
+    pub fn with_blob_fee_history(self, history: Vec<u64>) -> Self {
+        Self {
+            blob_base_fee_history: history,
+            ..self
+        }
+    }
+
     pub fn with_non_ordering_confirmation(self, non_ordering_confirmations: bool) -> Self {
         Self {
             non_ordering_confirmations,
@@ -222,6 +264,18 @@ impl MockEthereum {
             ..self
         }
     }
+
+    pub fn with_sender_account(self, address: Address) -> Self {
+        Self {
+            sender_account: address,
+            ..self
+        }
+    }
+
+    pub fn with_balance(mut self, address: Address, balance: U256) -> Self {
+        self.balances.insert(address, balance);
+        self
+    }
 }
 
 #[async_trait]
@@ -281,6 +335,19 @@ impl EthInterface for MockEthereum {
         Ok(self.base_fee_history[start_block..=from_block].to_vec())
     }
 
+    async fn blob_base_fee_history(
+        &self,
+        from_block: usize,
+        block_count: usize,
+        _component: &'static str,
+    ) -> Result<Vec<u64>, Error> {
+        let start_block = from_block.saturating_sub(block_count - 1);
+        if self.blob_base_fee_history.is_empty() {
+            return Ok(vec![0; from_block + 1 - start_block]);
+        }
+        Ok(self.blob_base_fee_history[start_block..=from_block].to_vec())
+    }
+
     async fn get_pending_block_base_fee_per_gas(
         &self,
         _component: &'static str,
@@ -345,12 +412,29 @@ impl EthInterface for MockEthereum {
         unimplemented!("Not needed right now")
     }
 
-    async fn eth_balance(
+    async fn eth_balance(&self, address: Address, _component: &'static str) -> Result<U256, Error> {
+        Ok(self.balances.get(&address).copied().unwrap_or_default())
+    }
+
+    async fn call(
         &self,
-        _address: Address,
+        _request: CallRequest,
+        _block: Option<BlockId>,
         _component: &'static str,
-    ) -> Result<U256, Error> {
-        unimplemented!("Not needed right now")
+    ) -> Result<Bytes, Error> {
+        let inner = self.inner.read().unwrap();
+        let Some(revert_data) = &inner.call_revert_data else {
+            return Ok(Bytes::default());
+        };
+        let revert_data_hex: String = revert_data
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect();
+        Err(Error::EthereumGateway(Web3Error::Rpc(RpcError {
+            message: "execution reverted".to_string(),
+            code: 3.into(),
+            data: Some(format!("0x{revert_data_hex}").into()),
+        })))
     }
 
     async fn logs(&self, _filter: Filter, _component: &'static str) -> Result<Vec<Log>, Error> {
@@ -381,7 +465,7 @@ impl BoundEthInterface for MockEthereum {
     }
 
     fn sender_account(&self) -> Address {
-        Address::repeat_byte(0x11)
+        self.sender_account
     }
 
     async fn sign_prepared_tx_for_addr(
@@ -394,6 +478,18 @@ impl BoundEthInterface for MockEthereum {
         self.sign_prepared_tx(data, options)
     }
 
+    async fn sign_prepared_blob_tx_for_addr(
+        &self,
+        data: Vec<u8>,
+        _contract_addr: H160,
+        options: Options,
+        _max_fee_per_blob_gas: U256,
+        _blob_sidecar: EthTxBlobSidecar,
+        _component: &'static str,
+    ) -> Result<SignedCallResult, Error> {
+        self.sign_prepared_tx(data, options)
+    }
+
     async fn allowance_on_account(
         &self,
         _token_address: Address,
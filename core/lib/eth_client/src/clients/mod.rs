@@ -5,6 +5,9 @@ mod http;
 mod mock;
 
 pub use self::{
-    http::{PKSigningClient, QueryClient, SigningClient},
+    http::{
+        blob_operator_signer, operator_signer, secondary_operator_signer, DynSigningClient,
+        PKSigningClient, PrivateRelayClient, QueryClient, SigningClient,
+    },
     mock::MockEthereum,
 };
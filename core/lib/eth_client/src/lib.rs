@@ -2,12 +2,13 @@ use std::fmt;
 
 use async_trait::async_trait;
 use zksync_types::{
+    eth_sender::EthTxBlobSidecar,
     web3::{
         contract::Options,
         ethabi,
         types::{
-            Address, Block, BlockId, BlockNumber, Filter, Log, Transaction, TransactionReceipt,
-            H160, H256, U256, U64,
+            Address, Block, BlockId, BlockNumber, Bytes, CallRequest, Filter, Log, Transaction,
+            TransactionReceipt, H160, H256, U256, U64,
         },
     },
     L1ChainId,
@@ -58,6 +59,17 @@ pub trait EthInterface: 'static + Sync + Send + fmt::Debug {
         component: &'static str,
     ) -> Result<Vec<u64>, Error>;
 
+    /// Collects the blob base fee history for the specified block range. The range semantics
+    /// are the same as for [`Self::base_fee_history()`].
+    ///
+    /// Returns zeros for blocks preceding the activation of EIP-4844 on L1.
+    async fn blob_base_fee_history(
+        &self,
+        from_block: usize,
+        block_count: usize,
+        component: &'static str,
+    ) -> Result<Vec<u64>, Error>;
+
     /// Returns the `base_fee_per_gas` value for the currently pending L1 block.
     async fn get_pending_block_base_fee_per_gas(
         &self,
@@ -112,6 +124,15 @@ pub trait EthInterface: 'static + Sync + Send + fmt::Debug {
     async fn call_contract_function(&self, call: ContractCall)
         -> Result<Vec<ethabi::Token>, Error>;
 
+    /// Executes a raw `eth_call` on the specified block (the latest block if not specified) without creating
+    /// a transaction on L1. A revert is returned as an RPC error with the revert data in its `data` field.
+    async fn call(
+        &self,
+        request: CallRequest,
+        block: Option<BlockId>,
+        component: &'static str,
+    ) -> Result<Bytes, Error>;
+
     /// Returns the logs for the specified filter.
     async fn logs(&self, filter: Filter, component: &'static str) -> Result<Vec<Log>, Error>;
 
@@ -172,6 +193,18 @@ pub trait BoundEthInterface: EthInterface {
         component: &'static str,
     ) -> Result<SignedCallResult, Error>;
 
+    /// Signs an EIP-4844 transaction carrying the blobs from `blob_sidecar`. Apart from the blob gas fee,
+    /// transaction parameters are determined in the same way as in [`Self::sign_prepared_tx_for_addr()`].
+    async fn sign_prepared_blob_tx_for_addr(
+        &self,
+        data: Vec<u8>,
+        contract_addr: H160,
+        options: Options,
+        max_fee_per_blob_gas: U256,
+        blob_sidecar: EthTxBlobSidecar,
+        component: &'static str,
+    ) -> Result<SignedCallResult, Error>;
+
     /// Returns the nonce of the `Self::sender_account()` at the specified block.
     async fn nonce_at(&self, block: BlockNumber, component: &'static str) -> Result<U256, Error> {
         self.nonce_at_for_account(self.sender_account(), block, component)
@@ -0,0 +1,336 @@
+//! Signer backed by an asymmetric AWS KMS key with the `ECC_SECG_P256K1` key spec.
+//!
+//! Requests to the KMS API are signed with the AWS Signature Version 4. Credentials are taken from
+//! the `AWS_ACCESS_KEY_ID`, `AWS_SECRET_ACCESS_KEY` and (optionally) `AWS_SESSION_TOKEN` env variables if they are set;
+//! otherwise, they are requested from the EC2 instance metadata service (IMDSv2) and refreshed before expiration.
+
+use std::{env, fmt, time::Duration};
+
+use async_trait::async_trait;
+use base64::Engine as _;
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use serde_derive::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tokio::sync::{Mutex, OnceCell};
+use zksync_types::{tx::primitives::PackedEthSignature, Address, H256};
+
+use crate::{ecdsa, error::SignerError, Signer};
+
+const SERVICE: &str = "kms";
+const IMDS_URL: &str = "http://169.254.169.254/latest";
+/// Credentials are refreshed if they expire sooner than in this number of minutes.
+const CREDENTIALS_REFRESH_MARGIN_MINUTES: i64 = 5;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Clone)]
+struct Credentials {
+    access_key_id: String,
+    secret_access_key: String,
+    session_token: Option<String>,
+    /// `None` for static credentials.
+    expires_at: Option<DateTime<Utc>>,
+}
+
+impl fmt::Debug for Credentials {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("Credentials")
+            .field("access_key_id", &self.access_key_id)
+            .field("expires_at", &self.expires_at)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Credentials {
+    fn from_env() -> Option<Self> {
+        Some(Self {
+            access_key_id: env::var("AWS_ACCESS_KEY_ID").ok()?,
+            secret_access_key: env::var("AWS_SECRET_ACCESS_KEY").ok()?,
+            session_token: env::var("AWS_SESSION_TOKEN").ok(),
+            expires_at: None,
+        })
+    }
+
+    fn is_fresh(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at.map_or(true, |expires_at| {
+            expires_at - now > chrono::Duration::minutes(CREDENTIALS_REFRESH_MARGIN_MINUTES)
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct ImdsCredentials {
+    access_key_id: String,
+    secret_access_key: String,
+    token: String,
+    expiration: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "PascalCase")]
+struct SignRequest<'a> {
+    key_id: &'a str,
+    message: String,
+    message_type: &'static str,
+    signing_algorithm: &'static str,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct SignResponse {
+    signature: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "PascalCase")]
+struct GetPublicKeyRequest<'a> {
+    key_id: &'a str,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct GetPublicKeyResponse {
+    public_key: String,
+}
+
+fn hmac_sha256(key: &[u8], data: &str) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(data.as_bytes());
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// Computes headers for an AWS KMS request signed according to AWS Signature Version 4.
+fn signed_headers(
+    credentials: &Credentials,
+    region: &str,
+    host: &str,
+    target: &str,
+    body: &[u8],
+    now: DateTime<Utc>,
+) -> Vec<(&'static str, String)> {
+    let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+    let date = now.format("%Y%m%d").to_string();
+
+    // Headers must be sorted by name.
+    let mut headers = vec![
+        ("content-type", "application/x-amz-json-1.1".to_owned()),
+        ("host", host.to_owned()),
+        ("x-amz-date", amz_date.clone()),
+    ];
+    if let Some(token) = &credentials.session_token {
+        headers.push(("x-amz-security-token", token.clone()));
+    }
+    headers.push(("x-amz-target", target.to_owned()));
+
+    let canonical_headers: String = headers
+        .iter()
+        .map(|(name, value)| format!("{name}:{value}\n"))
+        .collect();
+    let signed_header_names = headers
+        .iter()
+        .map(|(name, _)| *name)
+        .collect::<Vec<_>>()
+        .join(";");
+    let canonical_request = format!(
+        "POST\n/\n\n{canonical_headers}\n{signed_header_names}\n{}",
+        hex::encode(Sha256::digest(body))
+    );
+
+    let scope = format!("{date}/{region}/{SERVICE}/aws4_request");
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{amz_date}\n{scope}\n{}",
+        hex::encode(Sha256::digest(canonical_request.as_bytes()))
+    );
+    let signing_key = [region, SERVICE, "aws4_request"].into_iter().fold(
+        hmac_sha256(
+            format!("AWS4{}", credentials.secret_access_key).as_bytes(),
+            &date,
+        ),
+        |key, part| hmac_sha256(&key, part),
+    );
+    let signature = hex::encode(hmac_sha256(&signing_key, &string_to_sign));
+
+    headers.push((
+        "authorization",
+        format!(
+            "AWS4-HMAC-SHA256 Credential={}/{scope}, SignedHeaders={signed_header_names}, Signature={signature}",
+            credentials.access_key_id
+        ),
+    ));
+    headers
+}
+
+fn network_error(err: reqwest::Error) -> SignerError {
+    SignerError::SigningFailed(format!("AWS KMS request failed: {err}"))
+}
+
+/// Signer using an AWS KMS key. The key never leaves KMS; the signer only needs the `kms:Sign`
+/// and `kms:GetPublicKey` permissions for it.
+pub struct AwsKmsSigner {
+    client: reqwest::Client,
+    key_id: String,
+    region: String,
+    credentials: Mutex<Option<Credentials>>,
+    address: OnceCell<Address>,
+}
+
+impl fmt::Debug for AwsKmsSigner {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("AwsKmsSigner")
+            .field("key_id", &self.key_id)
+            .field("region", &self.region)
+            .finish_non_exhaustive()
+    }
+}
+
+impl AwsKmsSigner {
+    /// Creates a signer for the specified key ID, key ARN or alias.
+    pub fn new(key_id: String, region: String) -> Self {
+        Self {
+            client: reqwest::Client::builder()
+                .timeout(REQUEST_TIMEOUT)
+                .build()
+                .expect("failed creating HTTP client"),
+            key_id,
+            region,
+            credentials: Mutex::new(None),
+            address: OnceCell::new(),
+        }
+    }
+
+    async fn credentials(&self) -> Result<Credentials, SignerError> {
+        let mut credentials = self.credentials.lock().await;
+        let now = Utc::now();
+        if let Some(cached) = credentials.as_ref().filter(|creds| creds.is_fresh(now)) {
+            return Ok(cached.clone());
+        }
+
+        let new_credentials = match Credentials::from_env() {
+            Some(creds) => creds,
+            None => self.fetch_imds_credentials().await.map_err(|err| {
+                SignerError::SigningFailed(format!(
+                    "failed getting AWS credentials from instance metadata: {err}"
+                ))
+            })?,
+        };
+        *credentials = Some(new_credentials.clone());
+        Ok(new_credentials)
+    }
+
+    async fn fetch_imds_credentials(&self) -> Result<Credentials, reqwest::Error> {
+        let token = self
+            .client
+            .put(format!("{IMDS_URL}/api/token"))
+            .header("x-aws-ec2-metadata-token-ttl-seconds", "21600")
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+        let credentials_url = format!("{IMDS_URL}/meta-data/iam/security-credentials/");
+        let role = self
+            .client
+            .get(&credentials_url)
+            .header("x-aws-ec2-metadata-token", &token)
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+        let role = role.lines().next().unwrap_or_default().trim();
+        let credentials: ImdsCredentials = self
+            .client
+            .get(format!("{credentials_url}{role}"))
+            .header("x-aws-ec2-metadata-token", &token)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        Ok(Credentials {
+            access_key_id: credentials.access_key_id,
+            secret_access_key: credentials.secret_access_key,
+            session_token: Some(credentials.token),
+            expires_at: Some(credentials.expiration),
+        })
+    }
+
+    async fn call<R: serde::de::DeserializeOwned>(
+        &self,
+        action: &str,
+        request: &impl serde::Serialize,
+    ) -> Result<R, SignerError> {
+        let credentials = self.credentials().await?;
+        let host = format!("{SERVICE}.{}.amazonaws.com", self.region);
+        let body = serde_json::to_vec(request).expect("failed serializing KMS request");
+        let target = format!("TrentService.{action}");
+        let headers = signed_headers(
+            &credentials,
+            &self.region,
+            &host,
+            &target,
+            &body,
+            Utc::now(),
+        );
+
+        let mut request = self.client.post(format!("https://{host}/")).body(body);
+        for (name, value) in headers {
+            // `host` is set by the HTTP client itself.
+            if name != "host" {
+                request = request.header(name, value);
+            }
+        }
+        let response = request.send().await.map_err(network_error)?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let message = response.text().await.unwrap_or_default();
+            return Err(SignerError::SigningFailed(format!(
+                "AWS KMS {action} request failed with {status}: {message}"
+            )));
+        }
+        response.json().await.map_err(network_error)
+    }
+}
+
+#[async_trait]
+impl Signer for AwsKmsSigner {
+    async fn address(&self) -> Result<Address, SignerError> {
+        let address = self
+            .address
+            .get_or_try_init(|| async {
+                let response: GetPublicKeyResponse = self
+                    .call(
+                        "GetPublicKey",
+                        &GetPublicKeyRequest {
+                            key_id: &self.key_id,
+                        },
+                    )
+                    .await?;
+                let public_key = base64::engine::general_purpose::STANDARD
+                    .decode(response.public_key)
+                    .map_err(|_| SignerError::DefineAddress)?;
+                ecdsa::address_from_der_public_key(&public_key)
+            })
+            .await?;
+        Ok(*address)
+    }
+
+    async fn sign_digest(&self, digest: H256) -> Result<PackedEthSignature, SignerError> {
+        let address = self.address().await?;
+        let request = SignRequest {
+            key_id: &self.key_id,
+            message: base64::engine::general_purpose::STANDARD.encode(digest.as_bytes()),
+            message_type: "DIGEST",
+            signing_algorithm: "ECDSA_SHA_256",
+        };
+        let response: SignResponse = self.call("Sign", &request).await?;
+        let signature = base64::engine::general_purpose::STANDARD
+            .decode(response.signature)
+            .map_err(|err| SignerError::SigningFailed(format!("invalid signature: {err}")))?;
+        let signature = ecdsa::parse_der_signature(&signature)?;
+        ecdsa::recoverable_signature(&digest, signature, address)
+    }
+}
@@ -0,0 +1,129 @@
+//! ECDSA helpers shared by signers backed by external key storage (cloud KMS services and hardware tokens).
+//! Such signers return plain `(r, s)` signatures without a recovery ID, and their public keys are encoded
+//! in one of the standard formats rather than as Ethereum addresses.
+
+use secp256k1::{ecdsa, PublicKey};
+use zksync_types::{tx::primitives::PackedEthSignature, web3::signing::keccak256, Address, H256};
+
+use crate::error::SignerError;
+
+/// Length of an uncompressed SEC1-encoded secp256k1 public key (`0x04 || x || y`).
+const UNCOMPRESSED_KEY_LEN: usize = 65;
+
+fn split_signature(mut signature: ecdsa::Signature) -> (H256, H256) {
+    // Ethereum only accepts signatures with the low `s` value (EIP-2), while KMS services may return either form.
+    signature.normalize_s();
+    let compact = signature.serialize_compact();
+    (
+        H256::from_slice(&compact[..32]),
+        H256::from_slice(&compact[32..]),
+    )
+}
+
+/// Parses a DER-encoded ECDSA signature and normalizes it to the low-S form.
+pub(crate) fn parse_der_signature(der: &[u8]) -> Result<(H256, H256), SignerError> {
+    let signature = ecdsa::Signature::from_der(der)
+        .map_err(|err| SignerError::SigningFailed(format!("invalid DER signature: {err}")))?;
+    Ok(split_signature(signature))
+}
+
+/// Parses an ECDSA signature encoded as `r || s` and normalizes it to the low-S form.
+pub(crate) fn parse_compact_signature(bytes: &[u8]) -> Result<(H256, H256), SignerError> {
+    let signature = ecdsa::Signature::from_compact(bytes)
+        .map_err(|err| SignerError::SigningFailed(format!("invalid signature: {err}")))?;
+    Ok(split_signature(signature))
+}
+
+/// Finds the recovery ID for an `(r, s)` signature of `digest`, so that the signature recovers to `address`.
+pub(crate) fn recoverable_signature(
+    digest: &H256,
+    (r, s): (H256, H256),
+    address: Address,
+) -> Result<PackedEthSignature, SignerError> {
+    for v in 0..2 {
+        let signature = PackedEthSignature::from_rsv(&r, &s, v);
+        if signature.signature_recover_signer(digest).ok() == Some(address) {
+            return Ok(signature);
+        }
+    }
+    Err(SignerError::RecoverAddress(format!(
+        "signature does not correspond to signer address {address:?}"
+    )))
+}
+
+/// Computes the Ethereum address for a public key encoded in one of the DER-based formats: a `SubjectPublicKeyInfo`
+/// structure (returned by cloud KMS services), a DER `OCTET STRING` (PKCS#11 `CKA_EC_POINT`), or a raw
+/// SEC1 encoding. All these formats end with the uncompressed SEC1 key.
+pub(crate) fn address_from_der_public_key(der: &[u8]) -> Result<Address, SignerError> {
+    if der.len() < UNCOMPRESSED_KEY_LEN {
+        return Err(SignerError::DefineAddress);
+    }
+    let key_bytes = &der[der.len() - UNCOMPRESSED_KEY_LEN..];
+    let public_key = PublicKey::from_slice(key_bytes).map_err(|_| SignerError::DefineAddress)?;
+    let hash = keccak256(&public_key.serialize_uncompressed()[1..]);
+    Ok(Address::from_slice(&hash[12..]))
+}
+
+/// Same as [`address_from_der_public_key()`], but for a PEM-encoded `SubjectPublicKeyInfo`.
+pub(crate) fn address_from_pem_public_key(pem: &str) -> Result<Address, SignerError> {
+    use base64::Engine as _;
+
+    let base64_contents: String = pem
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with("-----"))
+        .collect();
+    let der = base64::engine::general_purpose::STANDARD
+        .decode(base64_contents)
+        .map_err(|_| SignerError::DefineAddress)?;
+    address_from_der_public_key(&der)
+}
+
+#[cfg(test)]
+mod tests {
+    use secp256k1::{Message, Secp256k1, SecretKey};
+
+    use super::*;
+
+    /// DER prefix of a `SubjectPublicKeyInfo` for a secp256k1 key.
+    const SPKI_PREFIX: &str = "3056301006072a8648ce3d020106052b8104000a034200";
+
+    #[test]
+    fn recovering_signature_from_der() {
+        let private_key = H256::repeat_byte(7);
+        let secret_key = SecretKey::from_slice(private_key.as_bytes()).unwrap();
+        let address = PackedEthSignature::address_from_private_key(&private_key).unwrap();
+        let public_key = PublicKey::from_secret_key(&Secp256k1::new(), &secret_key);
+
+        let spki = [
+            hex::decode(SPKI_PREFIX).unwrap(),
+            public_key.serialize_uncompressed().to_vec(),
+        ]
+        .concat();
+        assert_eq!(address_from_der_public_key(&spki).unwrap(), address);
+        let octet_string = [&[4_u8, 65][..], &public_key.serialize_uncompressed()].concat();
+        assert_eq!(address_from_der_public_key(&octet_string).unwrap(), address);
+
+        for i in 0..4_u8 {
+            let digest = H256::repeat_byte(i + 1);
+            let message = Message::from_slice(digest.as_bytes()).unwrap();
+            let signature = Secp256k1::new().sign_ecdsa(&message, &secret_key);
+
+            let der = signature.serialize_der();
+            let rs = parse_der_signature(&der).unwrap();
+            assert_eq!(
+                rs,
+                parse_compact_signature(&signature.serialize_compact()).unwrap()
+            );
+            let signature = recoverable_signature(&digest, rs, address).unwrap();
+            assert_eq!(
+                signature,
+                PackedEthSignature::sign_raw(&private_key, &digest).unwrap()
+            );
+        }
+
+        let err = recoverable_signature(&H256::zero(), (H256::zero(), H256::zero()), address)
+            .unwrap_err();
+        assert!(matches!(err, SignerError::RecoverAddress(_)), "{err}");
+    }
+}
@@ -0,0 +1,134 @@
+//! Signer backed by a GCP Cloud KMS key version with the `EC_SIGN_SECP256K1_SHA256` algorithm.
+//!
+//! Requests are authenticated with the application default credentials (e.g., a credentials file referenced
+//! by the `GOOGLE_APPLICATION_CREDENTIALS` env variable, or the service account attached to a GCE instance
+//! or a GKE workload).
+
+use std::{fmt, sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use base64::Engine as _;
+use google_cloud_auth::{project::Config, token::DefaultTokenSourceProvider};
+use google_cloud_token::{TokenSource, TokenSourceProvider as _};
+use serde_derive::Deserialize;
+use serde_json::json;
+use tokio::sync::OnceCell;
+use zksync_types::{tx::primitives::PackedEthSignature, Address, H256};
+
+use crate::{ecdsa, error::SignerError, Signer};
+
+const KMS_API_URL: &str = "https://cloudkms.googleapis.com/v1";
+const KMS_SCOPES: &[&str] = &["https://www.googleapis.com/auth/cloudkms"];
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AsymmetricSignResponse {
+    signature: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PublicKeyResponse {
+    pem: String,
+}
+
+fn request_error(err: impl fmt::Display) -> SignerError {
+    SignerError::SigningFailed(format!("GCP KMS request failed: {err}"))
+}
+
+/// Signer using a GCP Cloud KMS key version. The key never leaves KMS; the signer only needs
+/// the `cloudkms.cryptoKeyVersions.useToSign` and `cloudkms.cryptoKeyVersions.viewPublicKey` permissions for it.
+pub struct GcpKmsSigner {
+    client: reqwest::Client,
+    key_version: String,
+    token_source: Arc<dyn TokenSource>,
+    address: OnceCell<Address>,
+}
+
+impl fmt::Debug for GcpKmsSigner {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("GcpKmsSigner")
+            .field("key_version", &self.key_version)
+            .finish_non_exhaustive()
+    }
+}
+
+impl GcpKmsSigner {
+    /// Creates a signer for the specified key version resource name, i.e.
+    /// `projects/*/locations/*/keyRings/*/cryptoKeys/*/cryptoKeyVersions/*`.
+    pub async fn new(key_version: String) -> Result<Self, SignerError> {
+        let config = Config {
+            audience: None,
+            scopes: Some(KMS_SCOPES),
+            sub: None,
+        };
+        let token_source_provider =
+            DefaultTokenSourceProvider::new(config)
+                .await
+                .map_err(|err| {
+                    SignerError::CustomError(format!("failed initializing GCP credentials: {err}"))
+                })?;
+        Ok(Self {
+            client: reqwest::Client::builder()
+                .timeout(REQUEST_TIMEOUT)
+                .build()
+                .expect("failed creating HTTP client"),
+            key_version,
+            token_source: token_source_provider.token_source(),
+            address: OnceCell::new(),
+        })
+    }
+
+    async fn send<R: serde::de::DeserializeOwned>(
+        &self,
+        request: reqwest::RequestBuilder,
+    ) -> Result<R, SignerError> {
+        // The token source caches tokens and refreshes them when necessary.
+        let token = self.token_source.token().await.map_err(request_error)?;
+        let response = request
+            .header(reqwest::header::AUTHORIZATION, token)
+            .send()
+            .await
+            .map_err(request_error)?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let message = response.text().await.unwrap_or_default();
+            return Err(request_error(format_args!("{status}: {message}")));
+        }
+        response.json().await.map_err(request_error)
+    }
+}
+
+#[async_trait]
+impl Signer for GcpKmsSigner {
+    async fn address(&self) -> Result<Address, SignerError> {
+        let address = self
+            .address
+            .get_or_try_init(|| async {
+                let url = format!("{KMS_API_URL}/{}/publicKey", self.key_version);
+                let response: PublicKeyResponse = self.send(self.client.get(url)).await?;
+                ecdsa::address_from_pem_public_key(&response.pem)
+            })
+            .await?;
+        Ok(*address)
+    }
+
+    async fn sign_digest(&self, digest: H256) -> Result<PackedEthSignature, SignerError> {
+        let address = self.address().await?;
+        let url = format!("{KMS_API_URL}/{}:asymmetricSign", self.key_version);
+        // Cloud KMS doesn't support Keccak digests; the digest is only checked to have the length of SHA-256.
+        let body = json!({
+            "digest": {
+                "sha256": base64::engine::general_purpose::STANDARD.encode(digest.as_bytes()),
+            },
+        });
+        let response: AsymmetricSignResponse = self.send(self.client.post(url).json(&body)).await?;
+        let signature = base64::engine::general_purpose::STANDARD
+            .decode(response.signature)
+            .map_err(|err| SignerError::SigningFailed(format!("invalid signature: {err}")))?;
+        let signature = ecdsa::parse_der_signature(&signature)?;
+        ecdsa::recoverable_signature(&digest, signature, address)
+    }
+}
@@ -1,16 +1,27 @@
+use std::{fmt, sync::Arc};
+
 use async_trait::async_trait;
 use error::SignerError;
 pub use json_rpc_signer::JsonRpcSigner;
 pub use pk_signer::PrivateKeySigner;
 use zksync_types::{
-    tx::primitives::PackedEthSignature, Address, EIP712TypedStructure, Eip712Domain,
+    tx::primitives::PackedEthSignature, web3::signing::Signature, Address, EIP712TypedStructure,
+    Eip712Domain, H256,
 };
 
-pub use crate::raw_ethereum_tx::TransactionParameters;
+use crate::raw_ethereum_tx::Transaction;
+pub use crate::{
+    aws_kms::AwsKmsSigner, gcp_kms::GcpKmsSigner, pkcs11::Pkcs11Signer,
+    raw_ethereum_tx::TransactionParameters,
+};
 
+pub mod aws_kms;
+mod ecdsa;
 pub mod error;
+pub mod gcp_kms;
 pub mod json_rpc_signer;
 pub mod pk_signer;
+pub mod pkcs11;
 pub mod raw_ethereum_tx;
 
 #[async_trait]
@@ -25,3 +36,52 @@ pub trait EthereumSigner: 'static + Send + Sync + Clone {
         -> Result<Vec<u8>, SignerError>;
     async fn get_address(&self) -> Result<Address, SignerError>;
 }
+
+/// Low-level signer producing ECDSA signatures for 32-byte digests. Unlike [`EthereumSigner`], this trait
+/// is object-safe, so that the signer backend (e.g., a cloud KMS or a hardware token) can be chosen at runtime.
+/// `Arc<dyn Signer>` implements [`EthereumSigner`].
+#[async_trait]
+pub trait Signer: 'static + fmt::Debug + Send + Sync {
+    /// Returns the Ethereum address corresponding to the signing key.
+    async fn address(&self) -> Result<Address, SignerError>;
+
+    /// Signs the provided digest without any hashing or prefixes. The returned signature must have
+    /// the recovery ID (0 or 1) as `v`.
+    async fn sign_digest(&self, digest: H256) -> Result<PackedEthSignature, SignerError>;
+}
+
+#[async_trait]
+impl EthereumSigner for Arc<dyn Signer> {
+    async fn sign_message(&self, message: &[u8]) -> Result<PackedEthSignature, SignerError> {
+        let digest = PackedEthSignature::message_to_signed_bytes(message);
+        self.sign_digest(digest).await
+    }
+
+    async fn sign_typed_data<S: EIP712TypedStructure + Sync>(
+        &self,
+        domain: &Eip712Domain,
+        typed_struct: &S,
+    ) -> Result<PackedEthSignature, SignerError> {
+        let digest = PackedEthSignature::typed_data_to_signed_bytes(domain, typed_struct);
+        self.sign_digest(digest).await
+    }
+
+    async fn sign_transaction(
+        &self,
+        raw_tx: TransactionParameters,
+    ) -> Result<Vec<u8>, SignerError> {
+        let chain_id = raw_tx.chain_id;
+        let tx = Transaction::from(raw_tx);
+        let signature = self.sign_digest(tx.signing_hash(chain_id)).await?;
+        let signature = Signature {
+            v: signature.v().into(),
+            r: H256::from_slice(signature.r()),
+            s: H256::from_slice(signature.s()),
+        };
+        Ok(tx.into_signed(chain_id, &signature).raw_transaction.0)
+    }
+
+    async fn get_address(&self) -> Result<Address, SignerError> {
+        self.address().await
+    }
+}
@@ -5,7 +5,7 @@ use zksync_types::{
 
 use crate::{
     raw_ethereum_tx::{Transaction, TransactionParameters},
-    EthereumSigner, SignerError,
+    EthereumSigner, Signer, SignerError,
 };
 
 #[derive(Clone)]
@@ -60,36 +60,39 @@ impl EthereumSigner for PrivateKeySigner {
         raw_tx: TransactionParameters,
     ) -> Result<Vec<u8>, SignerError> {
         let key = SecretKey::from_slice(self.private_key.as_bytes()).unwrap();
+        let chain_id = raw_tx.chain_id;
+        let tx = Transaction::from(raw_tx);
+        let signed = tx.sign(&key, chain_id);
+        Ok(signed.raw_transaction.0)
+    }
+}
 
-        // According to the code in web3 <https://docs.rs/web3/latest/src/web3/api/accounts.rs.html#86>
-        // We should use `max_fee_per_gas` as `gas_price` if we use EIP1559
-        let gas_price = raw_tx.max_fee_per_gas;
-
-        let max_priority_fee_per_gas = raw_tx.max_priority_fee_per_gas;
-
-        let tx = Transaction {
-            to: raw_tx.to,
-            nonce: raw_tx.nonce,
-            gas: raw_tx.gas,
-            gas_price,
-            value: raw_tx.value,
-            data: raw_tx.data,
-            transaction_type: raw_tx.transaction_type,
-            access_list: raw_tx.access_list.unwrap_or_default(),
-            max_priority_fee_per_gas,
-        };
+#[async_trait::async_trait]
+impl Signer for PrivateKeySigner {
+    async fn address(&self) -> Result<Address, SignerError> {
+        self.get_address().await
+    }
 
-        let signed = tx.sign(&key, raw_tx.chain_id);
-        Ok(signed.raw_transaction.0)
+    async fn sign_digest(&self, digest: H256) -> Result<PackedEthSignature, SignerError> {
+        PackedEthSignature::sign_raw(&self.private_key, &digest)
+            .map_err(|err| SignerError::SigningFailed(err.to_string()))
     }
 }
 
 #[cfg(test)]
 mod test {
-    use zksync_types::{H160, H256, U256, U64};
+    use rlp::Rlp;
+    use zksync_types::{
+        eth_sender::{EthTxBlobSidecarV1, SidecarBlobV1},
+        web3::signing::keccak256,
+        H160, H256, U256, U64,
+    };
 
     use super::PrivateKeySigner;
-    use crate::{raw_ethereum_tx::TransactionParameters, EthereumSigner};
+    use crate::{
+        raw_ethereum_tx::{signed_tx_hash, TransactionParameters},
+        EthereumSigner,
+    };
 
     #[tokio::test]
     async fn test_generating_signed_raw_transaction() {
@@ -107,6 +110,8 @@ mod test {
             chain_id: 270,
             transaction_type: Some(U64::from(1u32)),
             access_list: None,
+            max_fee_per_blob_gas: None,
+            blob_tx_sidecar: None,
         };
         let raw_tx = signer
             .sign_transaction(raw_transaction.clone())
@@ -124,4 +129,55 @@ mod test {
         ];
         assert_eq!(raw_tx, precalculated_raw_tx);
     }
+
+    #[tokio::test]
+    async fn signing_blob_transaction() {
+        let signer = PrivateKeySigner::new(H256::from([5; 32]));
+        let blob = SidecarBlobV1 {
+            blob: vec![1; 131_072],
+            commitment: vec![2; 48],
+            proof: vec![3; 48],
+            versioned_hash: [&[1_u8][..], &[4; 31]].concat(),
+        };
+        let raw_transaction = TransactionParameters {
+            nonce: U256::from(1u32),
+            to: Some(H160::repeat_byte(0x11)),
+            gas: U256::from(100_000u32),
+            max_fee_per_gas: U256::from(2u32),
+            max_priority_fee_per_gas: U256::from(1u32),
+            data: vec![1, 2, 3],
+            chain_id: 270,
+            transaction_type: Some(U64::from(3u32)),
+            max_fee_per_blob_gas: Some(U256::from(5u32)),
+            blob_tx_sidecar: Some(
+                EthTxBlobSidecarV1 {
+                    blobs: vec![blob.clone()],
+                }
+                .into(),
+            ),
+            ..TransactionParameters::default()
+        };
+        let raw_tx = signer.sign_transaction(raw_transaction).await.unwrap();
+        assert_eq!(raw_tx[0], 3);
+
+        let wrapper = Rlp::new(&raw_tx[1..]);
+        assert_eq!(wrapper.item_count().unwrap(), 4);
+        let payload = wrapper.at(0).unwrap();
+        assert_eq!(payload.item_count().unwrap(), 14);
+        let max_fee_per_blob_gas: U256 = payload.val_at(9).unwrap();
+        assert_eq!(max_fee_per_blob_gas, U256::from(5u32));
+        let versioned_hashes: Vec<H256> = payload.list_at(10).unwrap();
+        assert_eq!(versioned_hashes, [H256::from_slice(&blob.versioned_hash)]);
+
+        let blobs: Vec<Vec<u8>> = wrapper.list_at(1).unwrap();
+        assert_eq!(blobs, [blob.blob]);
+        let commitments: Vec<Vec<u8>> = wrapper.list_at(2).unwrap();
+        assert_eq!(commitments, [blob.commitment]);
+        let proofs: Vec<Vec<u8>> = wrapper.list_at(3).unwrap();
+        assert_eq!(proofs, [blob.proof]);
+
+        // The transaction hash must not depend on the sidecar.
+        let expected_hash = keccak256(&[&[3_u8][..], payload.as_raw()].concat());
+        assert_eq!(signed_tx_hash(&raw_tx), H256(expected_hash));
+    }
 }
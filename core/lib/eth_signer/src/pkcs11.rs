@@ -0,0 +1,390 @@
+//! Signer backed by a secp256k1 key stored on a hardware token (an HSM, a smart card, etc.) accessible
+//! via a PKCS#11 module.
+//!
+//! Only the small subset of the PKCS#11 API necessary for signing is declared here. The module is loaded
+//! at runtime, so the server doesn't link to any vendor-specific libraries.
+
+use std::{
+    ffi::{c_ulong, c_void},
+    fmt, ptr,
+    sync::{Arc, Mutex},
+};
+
+use async_trait::async_trait;
+use libloading::Library;
+use zksync_types::{tx::primitives::PackedEthSignature, Address, H256};
+
+use crate::{ecdsa, error::SignerError, Signer};
+
+type CkUlong = c_ulong;
+type CkRv = CkUlong;
+type CkSessionHandle = CkUlong;
+type CkObjectHandle = CkUlong;
+
+const CKR_OK: CkRv = 0;
+const CKR_USER_ALREADY_LOGGED_IN: CkRv = 0x100;
+const CKR_CRYPTOKI_ALREADY_INITIALIZED: CkRv = 0x191;
+const CKF_SERIAL_SESSION: CkUlong = 0x4;
+const CKU_USER: CkUlong = 1;
+const CKA_CLASS: CkUlong = 0x0;
+const CKA_LABEL: CkUlong = 0x3;
+const CKA_EC_POINT: CkUlong = 0x181;
+const CKO_PUBLIC_KEY: CkUlong = 2;
+const CKO_PRIVATE_KEY: CkUlong = 3;
+const CKM_ECDSA: CkUlong = 0x1041;
+/// Length of a raw secp256k1 ECDSA signature (`r || s`) returned by `C_Sign`.
+const SIGNATURE_LEN: usize = 64;
+
+#[repr(C)]
+struct CkAttribute {
+    attribute_type: CkUlong,
+    value: *mut c_void,
+    value_len: CkUlong,
+}
+
+#[repr(C)]
+struct CkMechanism {
+    mechanism: CkUlong,
+    parameter: *mut c_void,
+    parameter_len: CkUlong,
+}
+
+type Unused = *const c_void;
+
+/// Prefix of `CK_FUNCTION_LIST` up to (and including) `C_Sign`. Fields must be declared in the order
+/// defined by the PKCS#11 specification.
+#[repr(C)]
+struct FunctionList {
+    _version: [u8; 2],
+    initialize: unsafe extern "C" fn(init_args: *mut c_void) -> CkRv,
+    finalize: unsafe extern "C" fn(reserved: *mut c_void) -> CkRv,
+    _get_info: Unused,
+    _get_function_list: Unused,
+    _get_slot_list: Unused,
+    _get_slot_info: Unused,
+    _get_token_info: Unused,
+    _get_mechanism_list: Unused,
+    _get_mechanism_info: Unused,
+    _init_token: Unused,
+    _init_pin: Unused,
+    _set_pin: Unused,
+    open_session: unsafe extern "C" fn(
+        slot_id: CkUlong,
+        flags: CkUlong,
+        application: *mut c_void,
+        notify: Unused,
+        session: *mut CkSessionHandle,
+    ) -> CkRv,
+    close_session: unsafe extern "C" fn(session: CkSessionHandle) -> CkRv,
+    _close_all_sessions: Unused,
+    _get_session_info: Unused,
+    _get_operation_state: Unused,
+    _set_operation_state: Unused,
+    login: unsafe extern "C" fn(
+        session: CkSessionHandle,
+        user_type: CkUlong,
+        pin: *const u8,
+        pin_len: CkUlong,
+    ) -> CkRv,
+    _logout: Unused,
+    _create_object: Unused,
+    _copy_object: Unused,
+    _destroy_object: Unused,
+    _get_object_size: Unused,
+    get_attribute_value: unsafe extern "C" fn(
+        session: CkSessionHandle,
+        object: CkObjectHandle,
+        template: *mut CkAttribute,
+        count: CkUlong,
+    ) -> CkRv,
+    _set_attribute_value: Unused,
+    find_objects_init: unsafe extern "C" fn(
+        session: CkSessionHandle,
+        template: *mut CkAttribute,
+        count: CkUlong,
+    ) -> CkRv,
+    find_objects: unsafe extern "C" fn(
+        session: CkSessionHandle,
+        objects: *mut CkObjectHandle,
+        max_count: CkUlong,
+        count: *mut CkUlong,
+    ) -> CkRv,
+    find_objects_final: unsafe extern "C" fn(session: CkSessionHandle) -> CkRv,
+    _encrypt_init: Unused,
+    _encrypt: Unused,
+    _encrypt_update: Unused,
+    _encrypt_final: Unused,
+    _decrypt_init: Unused,
+    _decrypt: Unused,
+    _decrypt_update: Unused,
+    _decrypt_final: Unused,
+    _digest_init: Unused,
+    _digest: Unused,
+    _digest_update: Unused,
+    _digest_key: Unused,
+    _digest_final: Unused,
+    sign_init: unsafe extern "C" fn(
+        session: CkSessionHandle,
+        mechanism: *mut CkMechanism,
+        key: CkObjectHandle,
+    ) -> CkRv,
+    sign: unsafe extern "C" fn(
+        session: CkSessionHandle,
+        data: *const u8,
+        data_len: CkUlong,
+        signature: *mut u8,
+        signature_len: *mut CkUlong,
+    ) -> CkRv,
+}
+
+type GetFunctionList = unsafe extern "C" fn(list: *mut *const FunctionList) -> CkRv;
+
+fn check(function: &str, rv: CkRv) -> Result<(), SignerError> {
+    if rv == CKR_OK {
+        Ok(())
+    } else {
+        Err(SignerError::SigningFailed(format!(
+            "PKCS#11 call `{function}` failed with code {rv:#x}"
+        )))
+    }
+}
+
+/// Logged-in PKCS#11 session.
+struct Session {
+    functions: *const FunctionList,
+    handle: CkSessionHandle,
+    private_key: CkObjectHandle,
+    // Must be dropped after all other fields, since they point into the library.
+    _library: Library,
+}
+
+// SAFETY: the session is only accessed behind a mutex, i.e., from a single thread at a time, which is allowed
+// for modules initialized without `CK_C_INITIALIZE_ARGS`.
+unsafe impl Send for Session {}
+
+impl Session {
+    fn open(
+        module_path: &str,
+        slot_id: u64,
+        key_label: &str,
+        pin: &str,
+    ) -> Result<(Self, Address), SignerError> {
+        let load_err = |err: libloading::Error| {
+            SignerError::CustomError(format!(
+                "failed loading PKCS#11 module `{module_path}`: {err}"
+            ))
+        };
+        // SAFETY: loading a library runs its initialization routines; we trust the configured module.
+        let library = unsafe { Library::new(module_path) }.map_err(load_err)?;
+        let mut functions = ptr::null();
+        // SAFETY: the signature of `C_GetFunctionList` is defined by the specification.
+        unsafe {
+            let get_function_list = library
+                .get::<GetFunctionList>(b"C_GetFunctionList\0")
+                .map_err(load_err)?;
+            check("C_GetFunctionList", get_function_list(&mut functions))?;
+        }
+        if functions.is_null() {
+            return Err(SignerError::CustomError(
+                "PKCS#11 module returned null function list".to_owned(),
+            ));
+        }
+
+        // SAFETY: `functions` points to a valid function list that lives as long as the library.
+        unsafe {
+            let rv = ((*functions).initialize)(ptr::null_mut());
+            if rv != CKR_CRYPTOKI_ALREADY_INITIALIZED {
+                check("C_Initialize", rv)?;
+            }
+            let slot_id = CkUlong::try_from(slot_id).map_err(|_| {
+                SignerError::CustomError(format!("PKCS#11 slot ID {slot_id} is out of range"))
+            })?;
+            let mut handle = 0;
+            check(
+                "C_OpenSession",
+                ((*functions).open_session)(
+                    slot_id,
+                    CKF_SERIAL_SESSION,
+                    ptr::null_mut(),
+                    ptr::null(),
+                    &mut handle,
+                ),
+            )?;
+            let mut session = Self {
+                functions,
+                handle,
+                private_key: 0,
+                _library: library,
+            };
+
+            let rv = ((*functions).login)(handle, CKU_USER, pin.as_ptr(), pin.len() as CkUlong);
+            if rv != CKR_USER_ALREADY_LOGGED_IN {
+                check("C_Login", rv)?;
+            }
+            session.private_key = session.find_key(CKO_PRIVATE_KEY, key_label)?;
+            let public_key = session.find_key(CKO_PUBLIC_KEY, key_label)?;
+            let ec_point = session.ec_point(public_key)?;
+            let address = ecdsa::address_from_der_public_key(&ec_point)?;
+            Ok((session, address))
+        }
+    }
+
+    fn functions(&self) -> &FunctionList {
+        // SAFETY: the function list lives as long as the library, which is owned by the session.
+        unsafe { &*self.functions }
+    }
+
+    unsafe fn find_key(&self, class: CkUlong, label: &str) -> Result<CkObjectHandle, SignerError> {
+        let mut class = class;
+        let mut template = [
+            CkAttribute {
+                attribute_type: CKA_CLASS,
+                value: (&mut class as *mut CkUlong).cast(),
+                value_len: std::mem::size_of::<CkUlong>() as CkUlong,
+            },
+            CkAttribute {
+                attribute_type: CKA_LABEL,
+                value: label.as_ptr() as *mut c_void,
+                value_len: label.len() as CkUlong,
+            },
+        ];
+        let functions = self.functions();
+        check(
+            "C_FindObjectsInit",
+            (functions.find_objects_init)(
+                self.handle,
+                template.as_mut_ptr(),
+                template.len() as CkUlong,
+            ),
+        )?;
+        let mut object = 0;
+        let mut count = 0;
+        let rv = (functions.find_objects)(self.handle, &mut object, 1, &mut count);
+        check(
+            "C_FindObjectsFinal",
+            (functions.find_objects_final)(self.handle),
+        )?;
+        check("C_FindObjects", rv)?;
+        if count == 0 {
+            return Err(SignerError::CustomError(format!(
+                "key `{label}` (object class {class}) is not found on PKCS#11 token"
+            )));
+        }
+        Ok(object)
+    }
+
+    unsafe fn ec_point(&self, public_key: CkObjectHandle) -> Result<Vec<u8>, SignerError> {
+        let functions = self.functions();
+        let mut attribute = CkAttribute {
+            attribute_type: CKA_EC_POINT,
+            value: ptr::null_mut(),
+            value_len: 0,
+        };
+        // The first call returns the attribute length, and the second one copies the value.
+        check(
+            "C_GetAttributeValue",
+            (functions.get_attribute_value)(self.handle, public_key, &mut attribute, 1),
+        )?;
+        let mut value = vec![0_u8; attribute.value_len as usize];
+        attribute.value = value.as_mut_ptr().cast();
+        check(
+            "C_GetAttributeValue",
+            (functions.get_attribute_value)(self.handle, public_key, &mut attribute, 1),
+        )?;
+        value.truncate(attribute.value_len as usize);
+        Ok(value)
+    }
+
+    fn sign(&self, digest: &H256) -> Result<Vec<u8>, SignerError> {
+        let functions = self.functions();
+        let mut mechanism = CkMechanism {
+            mechanism: CKM_ECDSA,
+            parameter: ptr::null_mut(),
+            parameter_len: 0,
+        };
+        let mut signature = vec![0_u8; SIGNATURE_LEN];
+        let mut signature_len = SIGNATURE_LEN as CkUlong;
+        // SAFETY: all pointers are valid for the duration of the calls.
+        unsafe {
+            check(
+                "C_SignInit",
+                (functions.sign_init)(self.handle, &mut mechanism, self.private_key),
+            )?;
+            check(
+                "C_Sign",
+                (functions.sign)(
+                    self.handle,
+                    digest.as_bytes().as_ptr(),
+                    digest.as_bytes().len() as CkUlong,
+                    signature.as_mut_ptr(),
+                    &mut signature_len,
+                ),
+            )?;
+        }
+        signature.truncate(signature_len as usize);
+        Ok(signature)
+    }
+}
+
+impl Drop for Session {
+    fn drop(&mut self) {
+        let functions = self.functions();
+        // SAFETY: the session handle is valid; errors are ignored since there's no way to handle them.
+        unsafe {
+            (functions.close_session)(self.handle);
+            (functions.finalize)(ptr::null_mut());
+        }
+    }
+}
+
+/// Signer using a private key stored on a PKCS#11 token. The token must contain a private and a public key
+/// object with the same label; the private key must support the `CKM_ECDSA` mechanism.
+pub struct Pkcs11Signer {
+    session: Arc<Mutex<Session>>,
+    address: Address,
+}
+
+impl fmt::Debug for Pkcs11Signer {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("Pkcs11Signer")
+            .field("address", &self.address)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Pkcs11Signer {
+    /// Loads the PKCS#11 module, logs into the token in the specified slot and looks up the key with
+    /// the specified label. This is a blocking operation.
+    pub fn new(
+        module_path: &str,
+        slot_id: u64,
+        key_label: &str,
+        pin: &str,
+    ) -> Result<Self, SignerError> {
+        let (session, address) = Session::open(module_path, slot_id, key_label, pin)?;
+        Ok(Self {
+            session: Arc::new(Mutex::new(session)),
+            address,
+        })
+    }
+}
+
+#[async_trait]
+impl Signer for Pkcs11Signer {
+    async fn address(&self) -> Result<Address, SignerError> {
+        Ok(self.address)
+    }
+
+    async fn sign_digest(&self, digest: H256) -> Result<PackedEthSignature, SignerError> {
+        let session = self.session.clone();
+        let signature = tokio::task::spawn_blocking(move || {
+            let session = session.lock().expect("PKCS#11 session is poisoned");
+            session.sign(&digest)
+        })
+        .await
+        .map_err(|err| SignerError::SigningFailed(format!("PKCS#11 signing panicked: {err}")))??;
+        let signature = ecdsa::parse_compact_signature(&signature)?;
+        ecdsa::recoverable_signature(&digest, signature, self.address)
+    }
+}
@@ -9,19 +9,21 @@
 //! In the case where it will be possible to use only the web3 library without copy-paste, the changes will be small and simple
 //! Link to @Deniallugo's PR to web3: https://github.com/tomusdrw/rust-web3/pull/630
 
-use rlp::RlpStream;
+use rlp::{Rlp, RlpStream};
 use zksync_types::{
+    eth_sender::EthTxBlobSidecar,
     ethabi::Address,
     web3::{
         signing::{self, Signature},
         types::{AccessList, SignedTransaction},
     },
-    U256, U64,
+    H256, U256, U64,
 };
 
 const LEGACY_TX_ID: u64 = 0;
 const ACCESSLISTS_TX_ID: u64 = 1;
 const EIP1559_TX_ID: u64 = 2;
+const EIP4844_TX_ID: u64 = 3;
 
 #[derive(Clone, Debug, PartialEq, Default)]
 pub struct TransactionParameters {
@@ -47,6 +49,10 @@ pub struct TransactionParameters {
     pub max_fee_per_gas: U256,
     /// miner bribe
     pub max_priority_fee_per_gas: U256,
+    /// Max fee per blob gas, only used for EIP-4844 transactions
+    pub max_fee_per_blob_gas: Option<U256>,
+    /// Blobs with their commitments and proofs, only used for EIP-4844 transactions
+    pub blob_tx_sidecar: Option<EthTxBlobSidecar>,
 }
 
 /// A transaction used for RLP encoding, hashing and signing.
@@ -61,6 +67,24 @@ pub struct Transaction {
     pub transaction_type: Option<U64>,
     pub access_list: AccessList,
     pub max_priority_fee_per_gas: U256,
+    pub max_fee_per_blob_gas: U256,
+    pub blob_tx_sidecar: Option<EthTxBlobSidecar>,
+}
+
+/// Computes the hash of a signed raw transaction. For EIP-4844 transactions in the network form
+/// (i.e., wrapped together with the blob sidecar), only the transaction payload is hashed.
+pub fn signed_tx_hash(raw_tx: &[u8]) -> H256 {
+    if raw_tx.first() == Some(&(EIP4844_TX_ID as u8)) {
+        let wrapper = Rlp::new(&raw_tx[1..]);
+        // The network form is `[tx_payload_body, blobs, commitments, proofs]`.
+        if wrapper.item_count() == Ok(4) {
+            if let Ok(payload) = wrapper.at(0) {
+                let payload = [&[EIP4844_TX_ID as u8], payload.as_raw()].concat();
+                return signing::keccak256(&payload).into();
+            }
+        }
+    }
+    signing::keccak256(raw_tx).into()
 }
 
 impl Transaction {
@@ -147,6 +171,75 @@ impl Transaction {
         stream
     }
 
+    fn encode_eip4844_payload(&self, chain_id: u64, signature: Option<&Signature>) -> RlpStream {
+        let mut stream = RlpStream::new();
+
+        let list_size = if signature.is_some() { 14 } else { 11 };
+        stream.begin_list(list_size);
+
+        stream.append(&chain_id);
+        stream.append(&self.nonce);
+        stream.append(&self.max_priority_fee_per_gas);
+        stream.append(&self.gas_price);
+        stream.append(&self.gas);
+        // Unlike other transaction types, EIP-4844 transactions cannot create contracts.
+        let to = self
+            .to
+            .expect("EIP-4844 transactions must have a recipient");
+        stream.append(&to);
+        stream.append(&self.value);
+        stream.append(&self.data);
+
+        self.rlp_append_access_list(&mut stream);
+
+        stream.append(&self.max_fee_per_blob_gas);
+        let versioned_hashes = self
+            .blob_tx_sidecar
+            .as_ref()
+            .map(EthTxBlobSidecar::versioned_hashes)
+            .unwrap_or_default();
+        stream.begin_list(versioned_hashes.len());
+        for hash in &versioned_hashes {
+            stream.append(hash);
+        }
+
+        if let Some(signature) = signature {
+            self.rlp_append_signature(&mut stream, signature);
+        }
+
+        stream
+    }
+
+    /// Encodes a signed EIP-4844 transaction in the network form, i.e. together with the blob sidecar:
+    /// `0x03 || rlp([tx_payload_body, blobs, commitments, proofs])`.
+    fn encode_eip4844_network_form(
+        &self,
+        chain_id: u64,
+        signature: &Signature,
+        sidecar: &EthTxBlobSidecar,
+    ) -> Vec<u8> {
+        let payload = self.encode_eip4844_payload(chain_id, Some(signature));
+        let EthTxBlobSidecar::EthTxBlobSidecarV1(sidecar) = sidecar;
+
+        let mut stream = RlpStream::new();
+        stream.begin_list(4);
+        stream.append_raw(payload.as_raw(), 1);
+        stream.begin_list(sidecar.blobs.len());
+        for blob in &sidecar.blobs {
+            stream.append(&blob.blob);
+        }
+        stream.begin_list(sidecar.blobs.len());
+        for blob in &sidecar.blobs {
+            stream.append(&blob.commitment);
+        }
+        stream.begin_list(sidecar.blobs.len());
+        for blob in &sidecar.blobs {
+            stream.append(&blob.proof);
+        }
+
+        [&[EIP4844_TX_ID as u8], stream.as_raw()].concat()
+    }
+
     fn rlp_append_signature(&self, stream: &mut RlpStream, signature: &Signature) {
         stream.append(&signature.v);
         stream.append(&U256::from_big_endian(signature.r.as_bytes()));
@@ -184,36 +277,64 @@ impl Transaction {
                 [&[tx_id], stream.as_raw()].concat()
             }
 
+            Some(EIP4844_TX_ID) => {
+                let tx_id: u8 = EIP4844_TX_ID as u8;
+                let stream = self.encode_eip4844_payload(chain_id, signature);
+                [&[tx_id], stream.as_raw()].concat()
+            }
+
             _ => {
                 panic!("Unsupported transaction type");
             }
         }
     }
 
-    /// Sign and return a raw signed transaction.
-    pub fn sign(self, sign: impl signing::Key, chain_id: u64) -> SignedTransaction {
-        let adjust_v_value = matches!(
+    fn is_legacy(&self) -> bool {
+        matches!(
             self.transaction_type.map(|t| t.as_u64()),
             Some(LEGACY_TX_ID) | None
-        );
+        )
+    }
 
-        let encoded = self.encode(chain_id, None);
+    /// Returns the hash that should be signed for this transaction.
+    pub fn signing_hash(&self, chain_id: u64) -> H256 {
+        signing::keccak256(&self.encode(chain_id, None)).into()
+    }
 
-        let hash = signing::keccak256(encoded.as_ref());
+    /// Sign and return a raw signed transaction. EIP-4844 transactions with a blob sidecar
+    /// are returned in the network form; the transaction hash doesn't depend on the sidecar.
+    pub fn sign(self, sign: impl signing::Key, chain_id: u64) -> SignedTransaction {
+        let hash = self.signing_hash(chain_id);
+        // `sign_message()` returns the recovery ID as `v`.
+        let signature = sign
+            .sign_message(hash.as_bytes())
+            .expect("hash is non-zero 32-bytes; qed");
+        self.into_signed(chain_id, &signature)
+    }
 
-        let signature = if adjust_v_value {
-            sign.sign(&hash, Some(chain_id))
-                .expect("hash is non-zero 32-bytes; qed")
-        } else {
-            sign.sign_message(&hash)
-                .expect("hash is non-zero 32-bytes; qed")
+    /// Encodes this transaction with the provided signature of its [signing hash](Self::signing_hash()).
+    /// `signature.v` must be the recovery ID (0 or 1); it is adjusted according to EIP-155 for legacy transactions.
+    pub fn into_signed(self, chain_id: u64, signature: &Signature) -> SignedTransaction {
+        let hash = self.signing_hash(chain_id);
+        let mut signature = Signature {
+            v: signature.v,
+            r: signature.r,
+            s: signature.s,
         };
+        if self.is_legacy() {
+            signature.v += 35 + chain_id * 2;
+        }
 
-        let signed = self.encode(chain_id, Some(&signature));
+        let mut signed = self.encode(chain_id, Some(&signature));
         let transaction_hash = signing::keccak256(signed.as_ref()).into();
+        let is_blob_tx = self.transaction_type.map(|t| t.as_u64()) == Some(EIP4844_TX_ID);
+        let sidecar = self.blob_tx_sidecar.as_ref().filter(|_| is_blob_tx);
+        if let Some(sidecar) = sidecar {
+            signed = self.encode_eip4844_network_form(chain_id, &signature, sidecar);
+        }
 
         SignedTransaction {
-            message_hash: hash.into(),
+            message_hash: hash,
             v: signature.v,
             r: signature.r,
             s: signature.s,
@@ -222,3 +343,23 @@ impl Transaction {
         }
     }
 }
+
+impl From<TransactionParameters> for Transaction {
+    fn from(params: TransactionParameters) -> Self {
+        Self {
+            to: params.to,
+            nonce: params.nonce,
+            gas: params.gas,
+            // According to the code in web3 <https://docs.rs/web3/latest/src/web3/api/accounts.rs.html#86>
+            // we should use `max_fee_per_gas` as `gas_price` if we use EIP-1559.
+            gas_price: params.max_fee_per_gas,
+            value: params.value,
+            data: params.data,
+            transaction_type: params.transaction_type,
+            access_list: params.access_list.unwrap_or_default(),
+            max_priority_fee_per_gas: params.max_priority_fee_per_gas,
+            max_fee_per_blob_gas: params.max_fee_per_blob_gas.unwrap_or_default(),
+            blob_tx_sidecar: params.blob_tx_sidecar,
+        }
+    }
+}
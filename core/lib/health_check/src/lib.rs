@@ -35,6 +35,12 @@ impl HealthStatus {
         matches!(self, Self::Ready | Self::Affected)
     }
 
+    /// Checks whether a component is alive according to this status, i.e., whether it is running (perhaps not being
+    /// ready yet) and has not been shut down or interrupted by a panic.
+    pub fn is_alive(self) -> bool {
+        !matches!(self, Self::ShutDown | Self::Panicked)
+    }
+
     fn priority_for_aggregation(self) -> usize {
         match self {
             Self::Ready => 0,
@@ -102,6 +108,19 @@ impl AppHealthCheck {
         guard.push(health_check);
     }
 
+    /// Returns names of all components with registered health checks.
+    pub fn component_names(&self) -> Vec<&'static str> {
+        let guard = self.0.lock().expect("`AppHealthCheck` is poisoned");
+        guard.iter().map(|check| check.name()).collect()
+    }
+
+    /// Removes health checks for the specified components. This should be used if components are restarted
+    /// within the same process, so that their health checks are not registered multiple times.
+    pub fn remove_components(&self, names: &[&str]) {
+        let mut guard = self.0.lock().expect("`AppHealthCheck` is poisoned");
+        guard.retain(|check| !names.contains(&check.name()));
+    }
+
     /// Checks the overall application health. This will query all component checks concurrently.
     pub async fn check_health(&self) -> AppHealth {
         // Clone checks so that we don't hold a lock for them across a wait point.
@@ -169,9 +188,18 @@ impl AppHealth {
         }
     }
 
+    /// Checks whether the application is ready to serve traffic, i.e., all its components are healthy.
     pub fn is_healthy(&self) -> bool {
         self.inner.status.is_healthy()
     }
+
+    /// Checks whether the application is alive, i.e., none of its components is shut down or panicked.
+    /// Unlike [`Self::is_healthy()`], this is true for an application that is still initializing or syncing.
+    pub fn is_alive(&self) -> bool {
+        self.components
+            .values()
+            .all(|health| health.status.is_alive())
+    }
 }
 
 /// Interface to be used for health checks.
@@ -374,6 +402,7 @@ mod tests {
 
         let app_health = AppHealth::new(&checks).await;
         assert!(!app_health.is_healthy());
+        assert!(app_health.is_alive());
         assert_matches!(app_health.inner.status(), HealthStatus::NotReady);
         assert_matches!(
             app_health.components["first"].status,
@@ -399,6 +428,7 @@ mod tests {
 
         let app_health = AppHealth::new(&checks).await;
         assert!(app_health.is_healthy());
+        assert!(app_health.is_alive());
         assert_matches!(app_health.inner.status(), HealthStatus::Affected);
         assert_matches!(app_health.components["first"].status, HealthStatus::Ready);
         assert_matches!(
@@ -410,6 +440,7 @@ mod tests {
 
         let app_health = AppHealth::new(&checks).await;
         assert!(!app_health.is_healthy());
+        assert!(!app_health.is_alive());
         assert_matches!(app_health.inner.status(), HealthStatus::ShutDown);
         assert_matches!(
             app_health.components["first"].status,
@@ -420,4 +451,27 @@ mod tests {
             HealthStatus::Affected
         );
     }
+
+    #[tokio::test]
+    async fn removing_health_checks() {
+        let app_health_check = AppHealthCheck::default();
+        let (first_check, _first_updater) = ReactiveHealthCheck::new("first");
+        let (second_check, second_updater) = ReactiveHealthCheck::new("second");
+        app_health_check.insert_component(first_check);
+        app_health_check.insert_component(second_check);
+        assert_eq!(app_health_check.component_names(), ["first", "second"]);
+
+        // Emulate restarting the second component.
+        drop(second_updater);
+        app_health_check.remove_components(&["second"]);
+        assert_eq!(app_health_check.component_names(), ["first"]);
+        let (second_check, second_updater) = ReactiveHealthCheck::new("second");
+        app_health_check.insert_component(second_check);
+        second_updater.update(HealthStatus::Ready.into());
+
+        assert_eq!(app_health_check.component_names(), ["first", "second"]);
+        let app_health = app_health_check.check_health().await;
+        assert!(app_health.is_alive());
+        assert_matches!(app_health.components["second"].status, HealthStatus::Ready);
+    }
 }
@@ -1,8 +1,6 @@
-// TODO: Remove once integrated into batch commitment
-#![allow(dead_code)]
-
-use std::convert::TryInto;
+use std::{convert::TryInto, path::Path};
 
+use once_cell::sync::Lazy;
 use sha2::Sha256;
 use sha3::{Digest, Keccak256};
 use zkevm_test_harness_1_3_3::ff::{PrimeField, PrimeFieldRepr};
@@ -21,7 +19,8 @@ use zkevm_test_harness_1_4_1::{
     },
 };
 
-const ZK_SYNC_BYTES_PER_BLOB: usize = BLOB_CHUNK_SIZE * ELEMENTS_PER_4844_BLOCK;
+/// Maximum number of pubdata bytes that fit into a single blob.
+pub const ZK_SYNC_BYTES_PER_BLOB: usize = BLOB_CHUNK_SIZE * ELEMENTS_PER_4844_BLOCK;
 const EIP_4844_BYTES_PER_BLOB: usize = 32 * ELEMENTS_PER_4844_BLOCK;
 
 /// Packed pubdata commitments.
@@ -31,6 +30,14 @@ const BYTES_PER_PUBDATA_COMMITMENT: usize = 144;
 
 const VERSIONED_HASH_VERSION_KZG: u8 = 0x01;
 
+/// KZG trusted setup loaded from `$ZKSYNC_HOME/trusted_setup.json` (or `./trusted_setup.json`
+/// if `ZKSYNC_HOME` is not set).
+static KZG_SETTINGS: Lazy<KzgSettings> = Lazy::new(|| {
+    let zksync_home = std::env::var("ZKSYNC_HOME").unwrap_or_else(|_| ".".into());
+    let path = Path::new(&zksync_home).join("trusted_setup.json");
+    KzgSettings::new(path.to_str().expect("path to trusted setup is not UTF-8"))
+});
+
 /// All the info needed for both the network transaction and by our L1 contracts. As part of the network transaction we
 /// need to encode the sidecar which contains the: blob, `kzg` commitment, and the blob proof. The transaction payload
 /// will utilize the versioned hash. The info needed for `commitBatches` is the `kzg` commitment, opening point,
@@ -102,6 +109,16 @@ impl KzgInfo {
         res
     }
 
+    /// Returns the blob commitment used in the batch commitment when pubdata is sent in calldata.
+    /// Return format: keccak(versioned hash || opening point (16 bytes) || claimed value)
+    pub fn to_blob_commitment(&self) -> [u8; 32] {
+        let mut data = [0u8; 80];
+        data[..32].copy_from_slice(&self.versioned_hash);
+        data[32..48].copy_from_slice(&self.opening_point[16..]);
+        data[48..].copy_from_slice(&self.opening_value);
+        Keccak256::digest(data).into()
+    }
+
     /// Deserializes `Self::SERIALIZED_SIZE` bytes into `KzgInfo` struct
     pub fn from_slice(data: &[u8]) -> Self {
         assert_eq!(data.len(), Self::SERIALIZED_SIZE);
@@ -244,6 +261,18 @@ impl KzgInfo {
             blob_proof: commitment_proof,
         }
     }
+
+    /// Splits pubdata into blobs of at most [`ZK_SYNC_BYTES_PER_BLOB`] bytes and constructs KZG info
+    /// for each of them using the global trusted setup. Empty pubdata is mapped to a single empty blob.
+    pub fn for_pubdata(pubdata: &[u8]) -> Vec<Self> {
+        if pubdata.is_empty() {
+            return vec![Self::new(&KZG_SETTINGS, vec![])];
+        }
+        pubdata
+            .chunks(ZK_SYNC_BYTES_PER_BLOB)
+            .map(|chunk| Self::new(&KZG_SETTINGS, chunk.to_vec()))
+            .collect()
+    }
 }
 
 #[cfg(test)]
@@ -1 +1,3 @@
-mod kzg;
+//! Utilities to compute commitments to L1 batch pubdata.
+
+pub mod kzg;
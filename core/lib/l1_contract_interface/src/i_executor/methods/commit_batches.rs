@@ -1,4 +1,4 @@
-use zksync_types::{commitment::L1BatchWithMetadata, ethabi::Token};
+use zksync_types::{commitment::L1BatchWithMetadata, ethabi::Token, pubdata_da::PubdataDA};
 
 use crate::{
     i_executor::structures::{CommitBatchInfo, StoredBatchInfo},
@@ -10,6 +10,7 @@ use crate::{
 pub struct CommitBatches {
     pub last_committed_l1_batch: L1BatchWithMetadata,
     pub l1_batches: Vec<L1BatchWithMetadata>,
+    pub pubdata_da: PubdataDA,
 }
 
 impl Tokenize for CommitBatches {
@@ -18,7 +19,7 @@ impl Tokenize for CommitBatches {
         let l1_batches_to_commit = self
             .l1_batches
             .iter()
-            .map(|batch| CommitBatchInfo(batch).into_token())
+            .map(|batch| CommitBatchInfo::new(batch, self.pubdata_da).into_token())
             .collect();
 
         vec![stored_batch_info, Token::Array(l1_batches_to_commit)]
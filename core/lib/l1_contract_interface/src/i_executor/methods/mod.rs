@@ -1,7 +1,9 @@
 //! Utilities for encoding input data for methods defined in `IExecutor.sol`.
 
 pub use self::{
-    commit_batches::CommitBatches, execute_batches::ExecuteBatches, prove_batches::ProveBatches,
+    commit_batches::CommitBatches,
+    execute_batches::ExecuteBatches,
+    prove_batches::{serialize_l1_batch_proof, ProveBatches},
 };
 
 mod commit_batches;
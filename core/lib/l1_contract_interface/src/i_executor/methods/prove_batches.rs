@@ -15,6 +15,12 @@ pub struct ProveBatches {
     pub should_verify: bool,
 }
 
+/// Serializes the SNARK proof of an L1 batch in the format expected by the L1 verifier contract
+/// (i.e., as `serializedProof` in the `proveBatches` input).
+pub fn serialize_l1_batch_proof(proof: &L1BatchProofForL1) -> Vec<U256> {
+    serialize_proof(&proof.scheduler_proof).1
+}
+
 impl Tokenize for ProveBatches {
     fn into_tokens(self) -> Vec<Token> {
         let prev_l1_batch = StoredBatchInfo(&self.prev_l1_batch).into_token();
@@ -30,12 +36,9 @@ impl Tokenize for ProveBatches {
             assert_eq!(self.proofs.len(), 1);
             assert_eq!(self.l1_batches.len(), 1);
 
-            let L1BatchProofForL1 {
-                aggregation_result_coords,
-                scheduler_proof,
-            } = self.proofs.first().unwrap();
-
-            let (_, proof) = serialize_proof(scheduler_proof);
+            let l1_batch_proof = self.proofs.first().unwrap();
+            let aggregation_result_coords = &l1_batch_proof.aggregation_result_coords;
+            let proof = serialize_l1_batch_proof(l1_batch_proof);
 
             let aggregation_result_coords = if self.l1_batches[0]
                 .header
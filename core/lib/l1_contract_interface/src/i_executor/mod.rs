@@ -1,6 +1,5 @@
 //! Different interfaces exposed by the `IExecutor.sol`.
 
-mod commit;
-
+pub mod commit;
 pub mod methods;
 pub mod structures;
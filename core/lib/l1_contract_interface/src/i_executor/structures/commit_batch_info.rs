@@ -1,15 +1,55 @@
 use zksync_types::{
     commitment::{pre_boojum_serialize_commitments, serialize_commitments, L1BatchWithMetadata},
     ethabi::Token,
+    pubdata_da::PubdataDA,
     web3::{contract::Error as Web3ContractError, error::Error as Web3ApiError},
     U256,
 };
 
-use crate::Tokenizable;
+use crate::{i_executor::commit::kzg::KzgInfo, Tokenizable};
 
 /// Encoding for `CommitBatchInfo` from `IExecutor.sol`
 #[derive(Debug)]
-pub struct CommitBatchInfo<'a>(pub &'a L1BatchWithMetadata);
+pub struct CommitBatchInfo<'a> {
+    l1_batch_with_metadata: &'a L1BatchWithMetadata,
+    /// Source of pubdata. Ignored for protocol versions before 1.4.2, which only support calldata.
+    pubdata_da: PubdataDA,
+}
+
+impl<'a> CommitBatchInfo<'a> {
+    pub fn new(l1_batch_with_metadata: &'a L1BatchWithMetadata, pubdata_da: PubdataDA) -> Self {
+        Self {
+            l1_batch_with_metadata,
+            pubdata_da,
+        }
+    }
+
+    /// Encodes `pubdataCommitments` for protocol versions starting from 1.4.2. The first byte
+    /// is the pubdata source; it's followed either by the pubdata and the commitment to it (for calldata),
+    /// or by the concatenated KZG commitments to the blobs (for blobs).
+    fn pubdata_commitments(&self, pubdata: Vec<u8>) -> Vec<u8> {
+        let mut commitments = vec![self.pubdata_da as u8];
+        match self.pubdata_da {
+            PubdataDA::Calldata => {
+                let kzg_infos = KzgInfo::for_pubdata(&pubdata);
+                assert_eq!(
+                    kzg_infos.len(),
+                    1,
+                    "pubdata sent in calldata must fit into a single blob"
+                );
+                let blob_commitment = kzg_infos[0].to_blob_commitment();
+                commitments.extend(pubdata);
+                commitments.extend_from_slice(&blob_commitment);
+            }
+            PubdataDA::Blobs => {
+                for kzg_info in KzgInfo::for_pubdata(&pubdata) {
+                    commitments.extend_from_slice(&kzg_info.to_pubdata_commitment());
+                }
+            }
+        }
+        commitments
+    }
+}
 
 impl<'a> Tokenizable for CommitBatchInfo<'a> {
     fn from_token(_token: Token) -> Result<Self, Web3ContractError>
@@ -25,28 +65,30 @@ impl<'a> Tokenizable for CommitBatchInfo<'a> {
     }
 
     fn into_token(self) -> Token {
-        if self.0.header.protocol_version.unwrap().is_pre_boojum() {
+        let batch = self.l1_batch_with_metadata;
+        let protocol_version = batch.header.protocol_version.unwrap();
+        if protocol_version.is_pre_boojum() {
             Token::Tuple(vec![
-                Token::Uint(U256::from(self.0.header.number.0)),
-                Token::Uint(U256::from(self.0.header.timestamp)),
-                Token::Uint(U256::from(self.0.metadata.rollup_last_leaf_index)),
-                Token::FixedBytes(self.0.metadata.merkle_root_hash.as_bytes().to_vec()),
-                Token::Uint(U256::from(self.0.header.l1_tx_count)),
-                Token::FixedBytes(self.0.metadata.l2_l1_merkle_root.as_bytes().to_vec()),
+                Token::Uint(U256::from(batch.header.number.0)),
+                Token::Uint(U256::from(batch.header.timestamp)),
+                Token::Uint(U256::from(batch.metadata.rollup_last_leaf_index)),
+                Token::FixedBytes(batch.metadata.merkle_root_hash.as_bytes().to_vec()),
+                Token::Uint(U256::from(batch.header.l1_tx_count)),
+                Token::FixedBytes(batch.metadata.l2_l1_merkle_root.as_bytes().to_vec()),
                 Token::FixedBytes(
-                    self.0
+                    batch
                         .header
                         .priority_ops_onchain_data_hash()
                         .as_bytes()
                         .to_vec(),
                 ),
-                Token::Bytes(self.0.metadata.initial_writes_compressed.clone().unwrap()),
-                Token::Bytes(self.0.metadata.repeated_writes_compressed.clone().unwrap()),
+                Token::Bytes(batch.metadata.initial_writes_compressed.clone().unwrap()),
+                Token::Bytes(batch.metadata.repeated_writes_compressed.clone().unwrap()),
                 Token::Bytes(pre_boojum_serialize_commitments(
-                    &self.0.header.l2_to_l1_logs,
+                    &batch.header.l2_to_l1_logs,
                 )),
                 Token::Array(
-                    self.0
+                    batch
                         .header
                         .l2_to_l1_messages
                         .iter()
@@ -54,7 +96,7 @@ impl<'a> Tokenizable for CommitBatchInfo<'a> {
                         .collect(),
                 ),
                 Token::Array(
-                    self.0
+                    batch
                         .raw_published_factory_deps
                         .iter()
                         .map(|bytecode| Token::Bytes(bytecode.to_vec()))
@@ -62,20 +104,31 @@ impl<'a> Tokenizable for CommitBatchInfo<'a> {
                 ),
             ])
         } else {
+            let pubdata = batch
+                .header
+                .pubdata_input
+                .clone()
+                .unwrap_or_else(|| batch.construct_pubdata());
+            let pubdata = if protocol_version.is_pre_1_4_2() {
+                pubdata
+            } else {
+                self.pubdata_commitments(pubdata)
+            };
+
             Token::Tuple(vec![
                 // `batchNumber`
-                Token::Uint(U256::from(self.0.header.number.0)),
+                Token::Uint(U256::from(batch.header.number.0)),
                 // `timestamp`
-                Token::Uint(U256::from(self.0.header.timestamp)),
+                Token::Uint(U256::from(batch.header.timestamp)),
                 // `indexRepeatedStorageChanges`
-                Token::Uint(U256::from(self.0.metadata.rollup_last_leaf_index)),
+                Token::Uint(U256::from(batch.metadata.rollup_last_leaf_index)),
                 // `newStateRoot`
-                Token::FixedBytes(self.0.metadata.merkle_root_hash.as_bytes().to_vec()),
+                Token::FixedBytes(batch.metadata.merkle_root_hash.as_bytes().to_vec()),
                 // `numberOfLayer1Txs`
-                Token::Uint(U256::from(self.0.header.l1_tx_count)),
+                Token::Uint(U256::from(batch.header.l1_tx_count)),
                 // `priorityOperationsHash`
                 Token::FixedBytes(
-                    self.0
+                    batch
                         .header
                         .priority_ops_onchain_data_hash()
                         .as_bytes()
@@ -83,7 +136,7 @@ impl<'a> Tokenizable for CommitBatchInfo<'a> {
                 ),
                 // `bootloaderHeapInitialContentsHash`
                 Token::FixedBytes(
-                    self.0
+                    batch
                         .metadata
                         .bootloader_initial_content_commitment
                         .unwrap()
@@ -92,7 +145,7 @@ impl<'a> Tokenizable for CommitBatchInfo<'a> {
                 ),
                 // `eventsQueueStateHash`
                 Token::FixedBytes(
-                    self.0
+                    batch
                         .metadata
                         .events_queue_commitment
                         .unwrap()
@@ -100,15 +153,9 @@ impl<'a> Tokenizable for CommitBatchInfo<'a> {
                         .to_vec(),
                 ),
                 // `systemLogs`
-                Token::Bytes(serialize_commitments(&self.0.header.system_logs)),
-                // `totalL2ToL1Pubdata`
-                Token::Bytes(
-                    self.0
-                        .header
-                        .pubdata_input
-                        .clone()
-                        .unwrap_or(self.0.construct_pubdata()),
-                ),
+                Token::Bytes(serialize_commitments(&batch.header.system_logs)),
+                // `totalL2ToL1Pubdata` (`pubdataCommitments` starting from 1.4.2)
+                Token::Bytes(pubdata),
             ])
         }
     }
@@ -1,15 +1,42 @@
-use std::collections::{hash_map, BTreeSet, HashMap, HashSet};
+use std::{
+    cmp::Reverse,
+    collections::{hash_map, BTreeSet, BinaryHeap, HashMap, HashSet},
+};
 
+use zksync_config::configs::chain::TransactionOrdering;
 use zksync_types::{
-    l1::L1Tx, l2::L2Tx, Address, ExecuteTransactionCommon, Nonce, PriorityOpId, Transaction,
+    l1::L1Tx, l2::L2Tx, Address, ExecuteTransactionCommon, Nonce, PriorityOpId, Transaction, H256,
 };
 
-use crate::types::{AccountTransactions, L2TxFilter, MempoolScore};
+use crate::types::{estimated_size, AccountTransactions, L2TxFilter, MempoolScore};
+
+/// Reason for evicting a non-executable L2 transaction from the mempool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EvictionReason {
+    /// The account has more transactions than allowed.
+    AccountLimit,
+    /// Transactions in the mempool occupy more memory than allowed.
+    MemoryLimit,
+}
+
+impl EvictionReason {
+    /// Returns the error message persisted for transactions evicted for this reason.
+    pub fn error_message(self) -> &'static str {
+        match self {
+            Self::AccountLimit => {
+                "evicted from mempool: too many pending transactions for the account"
+            }
+            Self::MemoryLimit => "evicted from mempool: mempool memory limit exceeded",
+        }
+    }
+}
 
 #[derive(Debug)]
 pub struct MempoolInfo {
     pub stashed_accounts: Vec<Address>,
     pub purged_accounts: Vec<Address>,
+    /// Hashes of L2 transactions evicted from the mempool since the last call.
+    pub evicted_transactions: Vec<(H256, EvictionReason)>,
 }
 
 #[derive(Debug)]
@@ -17,6 +44,8 @@ pub struct MempoolStats {
     pub l1_transaction_count: usize,
     pub l2_transaction_count: u64,
     pub l2_priority_queue_size: usize,
+    /// Estimated memory occupied by L2 transactions in bytes.
+    pub l2_memory_usage: usize,
 }
 
 #[derive(Debug)]
@@ -33,6 +62,13 @@ pub struct MempoolStore {
     /// Number of L2 transactions in the mempool.
     size: u64,
     capacity: u64,
+    /// Policy ordering L2 transactions
+    ordering: TransactionOrdering,
+    /// Max number of L2 transactions per account
+    max_transactions_per_account: Option<usize>,
+    /// Max estimated memory occupied by L2 transactions in bytes
+    max_memory_usage: Option<usize>,
+    evicted_transactions: Vec<(H256, EvictionReason)>,
 }
 
 impl MempoolStore {
@@ -45,9 +81,41 @@ impl MempoolStore {
             stashed_accounts: vec![],
             size: 0,
             capacity,
+            ordering: TransactionOrdering::default(),
+            max_transactions_per_account: None,
+            max_memory_usage: None,
+            evicted_transactions: vec![],
         }
     }
 
+    /// Sets the max number of L2 transactions per account. If an account exceeds this limit, its transactions
+    /// with the highest nonces are evicted.
+    #[must_use]
+    pub fn with_account_limit(mut self, max_transactions: usize) -> Self {
+        self.max_transactions_per_account = Some(max_transactions.max(1));
+        self
+    }
+
+    /// Sets the max estimated memory (in bytes) occupied by L2 transactions. If the mempool exceeds this limit,
+    /// non-executable transactions are evicted, starting from transactions with the lowest `max_fee_per_gas`
+    /// and then the oldest ones. Executable transactions are never evicted.
+    #[must_use]
+    pub fn with_memory_limit(mut self, max_memory_usage: usize) -> Self {
+        self.max_memory_usage = Some(max_memory_usage);
+        self
+    }
+
+    /// Sets the policy ordering L2 transactions. Should be called before any transactions are inserted.
+    #[must_use]
+    pub fn with_ordering(mut self, ordering: TransactionOrdering) -> Self {
+        assert!(
+            self.l2_transactions_per_account.is_empty(),
+            "ordering must be set for an empty mempool"
+        );
+        self.ordering = ordering;
+        self
+    }
+
     /// Inserts batch of new transactions to mempool
     /// `initial_nonces` provides current committed nonce information to mempool
     /// variable is used only if account is not present in mempool yet and we have to bootstrap it
@@ -93,6 +161,7 @@ impl MempoolStore {
                 }
             }
         }
+        self.enforce_memory_limit();
     }
 
     fn insert_l2_transaction(
@@ -107,7 +176,7 @@ impl MempoolStore {
             hash_map::Entry::Vacant(entry) => {
                 let account_nonce = initial_nonces.get(&account).cloned().unwrap_or(Nonce(0));
                 entry
-                    .insert(AccountTransactions::new(account_nonce))
+                    .insert(AccountTransactions::new(account_nonce, self.ordering))
                     .insert(transaction)
             }
         };
@@ -120,6 +189,64 @@ impl MempoolStore {
         if metadata.is_new {
             self.size += 1;
         }
+
+        if let Some(max_transactions) = self.max_transactions_per_account {
+            let account_transactions = self
+                .l2_transactions_per_account
+                .get_mut(&account)
+                .expect("account was just inserted");
+            while account_transactions.len() > max_transactions {
+                let Some(evicted) = account_transactions.evict_last() else {
+                    break;
+                };
+                self.size -= 1;
+                self.evicted_transactions
+                    .push((evicted.hash(), EvictionReason::AccountLimit));
+            }
+        }
+    }
+
+    fn enforce_memory_limit(&mut self) {
+        let Some(max_memory_usage) = self.max_memory_usage else {
+            return;
+        };
+        let mut memory_usage = self.l2_memory_usage();
+        if memory_usage <= max_memory_usage {
+            return;
+        }
+
+        let eviction_key = |address: Address, transactions: &AccountTransactions| {
+            let transaction = transactions.last_evictable()?;
+            Some(Reverse((
+                transaction.common_data.fee.max_fee_per_gas,
+                transaction.received_timestamp_ms,
+                address,
+            )))
+        };
+        let mut candidates: BinaryHeap<_> = self
+            .l2_transactions_per_account
+            .iter()
+            .filter_map(|(&address, transactions)| eviction_key(address, transactions))
+            .collect();
+        while memory_usage > max_memory_usage {
+            let Some(Reverse((_, _, address))) = candidates.pop() else {
+                break; // Only executable transactions are left
+            };
+            let transactions = self
+                .l2_transactions_per_account
+                .get_mut(&address)
+                .expect("mempool: dangling eviction candidate");
+            let evicted = transactions
+                .evict_last()
+                .expect("mempool: no transaction to evict");
+            memory_usage -= estimated_size(&evicted);
+            self.size -= 1;
+            self.evicted_transactions
+                .push((evicted.hash(), EvictionReason::MemoryLimit));
+            if let Some(key) = eviction_key(address, transactions) {
+                candidates.push(key);
+            }
+        }
     }
 
     /// Returns `true` if there is a transaction in the mempool satisfying the filter.
@@ -205,21 +332,54 @@ impl MempoolStore {
         }
     }
 
+    /// Removes a pending L2 transaction from the mempool, e.g. on operator request. Returns `true` if the transaction
+    /// was in the mempool. If the removed transaction was executable, subsequent transactions of the same account
+    /// won't be executable until a transaction with the same nonce is inserted.
+    pub fn remove_l2_transaction(&mut self, account: Address, nonce: Nonce, hash: H256) -> bool {
+        let Some(transactions) = self.l2_transactions_per_account.get_mut(&account) else {
+            return false;
+        };
+        let Some((_, score)) = transactions.remove(nonce, hash) else {
+            return false;
+        };
+        if let Some(score) = score {
+            self.l2_priority_queue.remove(&score);
+        }
+        self.size -= 1;
+        true
+    }
+
     pub fn get_mempool_info(&mut self) -> MempoolInfo {
         MempoolInfo {
             stashed_accounts: std::mem::take(&mut self.stashed_accounts),
             purged_accounts: self.gc(),
+            evicted_transactions: std::mem::take(&mut self.evicted_transactions),
         }
     }
 
+    /// Returns evicted transactions taken by [`Self::get_mempool_info()`] back to the store, e.g. if they
+    /// could not be persisted. They will be returned by the next `get_mempool_info()` call.
+    pub fn requeue_evicted_transactions(&mut self, mut evicted: Vec<(H256, EvictionReason)>) {
+        evicted.append(&mut self.evicted_transactions);
+        self.evicted_transactions = evicted;
+    }
+
     pub fn stats(&self) -> MempoolStats {
         MempoolStats {
             l1_transaction_count: self.l1_transactions.len(),
             l2_transaction_count: self.size,
             l2_priority_queue_size: self.l2_priority_queue.len(),
+            l2_memory_usage: self.l2_memory_usage(),
         }
     }
 
+    fn l2_memory_usage(&self) -> usize {
+        self.l2_transactions_per_account
+            .values()
+            .map(AccountTransactions::memory_usage)
+            .sum()
+    }
+
     fn gc(&mut self) -> Vec<Address> {
         if self.size >= self.capacity {
             let index: HashSet<_> = self
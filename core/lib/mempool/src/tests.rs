@@ -3,6 +3,7 @@ use std::{
     iter::FromIterator,
 };
 
+use zksync_config::configs::chain::TransactionOrdering;
 use zksync_types::{
     fee::Fee,
     helpers::unix_timestamp_ms,
@@ -12,7 +13,10 @@ use zksync_types::{
     H256, U256,
 };
 
-use crate::{mempool_store::MempoolStore, types::L2TxFilter};
+use crate::{
+    mempool_store::{EvictionReason, MempoolStore},
+    types::{estimated_size, L2TxFilter},
+};
 
 #[test]
 fn basic_flow() {
@@ -61,6 +65,119 @@ fn basic_flow() {
     assert_eq!(mempool.next_transaction(&L2TxFilter::default()), None);
 }
 
+#[test]
+fn fee_priority_ordering() {
+    let mut mempool =
+        MempoolStore::new(PriorityOpId(0), 100).with_ordering(TransactionOrdering::FeePriority);
+    let account0 = Address::random();
+    let account1 = Address::random();
+    let account2 = Address::random();
+    let transactions = vec![
+        gen_l2_tx_with_priority_fee(account0, Nonce(0), 0, 1),
+        gen_l2_tx_with_priority_fee(account1, Nonce(0), 1, 3),
+        gen_l2_tx_with_priority_fee(account1, Nonce(1), 2, 0),
+        gen_l2_tx_with_priority_fee(account2, Nonce(0), 3, 2),
+        // Same fee as the first transaction; must be ordered after it.
+        gen_l2_tx_with_priority_fee(account2, Nonce(1), 4, 1),
+    ];
+    mempool.insert(transactions, HashMap::new());
+
+    let mut executed = vec![];
+    while let Some(tx) = mempool.next_transaction(&L2TxFilter::default()) {
+        executed.push(view(Some(tx)));
+    }
+    assert_eq!(
+        executed,
+        [
+            (account1, 0),
+            (account2, 0),
+            (account0, 0),
+            (account2, 1),
+            (account1, 1)
+        ]
+    );
+}
+
+#[test]
+fn evicting_transactions_exceeding_account_limit() {
+    let mut mempool = MempoolStore::new(PriorityOpId(0), 100).with_account_limit(2);
+    let account = Address::random();
+    let transactions: Vec<_> = (0..4)
+        .map(|nonce| gen_l2_tx_with_fee(account, Nonce(nonce), 100))
+        .collect();
+    let expected_evicted = vec![
+        (transactions[2].hash(), EvictionReason::AccountLimit),
+        (transactions[3].hash(), EvictionReason::AccountLimit),
+    ];
+    mempool.insert(transactions, HashMap::new());
+    assert_eq!(mempool.stats().l2_transaction_count, 2);
+    assert_eq!(
+        mempool.get_mempool_info().evicted_transactions,
+        expected_evicted
+    );
+
+    assert_eq!(
+        view(mempool.next_transaction(&L2TxFilter::default())),
+        (account, 0)
+    );
+    assert_eq!(
+        view(mempool.next_transaction(&L2TxFilter::default())),
+        (account, 1)
+    );
+    assert_eq!(mempool.next_transaction(&L2TxFilter::default()), None);
+    assert_eq!(mempool.stats().l2_memory_usage, 0);
+}
+
+#[test]
+fn evicting_transactions_exceeding_memory_limit() {
+    let account0 = Address::random();
+    let account1 = Address::random();
+    let account2 = Address::random();
+    let transactions = vec![
+        gen_l2_tx_with_fee(account0, Nonce(0), 1),
+        gen_l2_tx_with_fee(account0, Nonce(1), 10),
+        // Non-executable because of the nonce gap
+        gen_l2_tx_with_fee(account1, Nonce(5), 5),
+        gen_l2_tx_with_fee(account2, Nonce(0), 1),
+    ];
+    let ExecuteTransactionCommon::L2(data) = transactions[0].common_data.clone() else {
+        unreachable!();
+    };
+    let tx_size = estimated_size(&L2Tx {
+        execute: transactions[0].execute.clone(),
+        common_data: data,
+        received_timestamp_ms: 0,
+        raw_bytes: None,
+    });
+    let expected_evicted = vec![(transactions[2].hash(), EvictionReason::MemoryLimit)];
+
+    let mut mempool = MempoolStore::new(PriorityOpId(0), 100).with_memory_limit(3 * tx_size);
+    mempool.insert(transactions, HashMap::new());
+    let stats = mempool.stats();
+    assert_eq!(stats.l2_transaction_count, 3);
+    assert_eq!(stats.l2_memory_usage, 3 * tx_size);
+    let evicted = mempool.get_mempool_info().evicted_transactions;
+    assert_eq!(evicted, expected_evicted);
+    mempool.requeue_evicted_transactions(evicted);
+    assert_eq!(
+        mempool.get_mempool_info().evicted_transactions,
+        expected_evicted
+    );
+    assert!(mempool.get_mempool_info().evicted_transactions.is_empty());
+
+    // Executable transactions are not evicted even if the limit is exceeded.
+    let mut mempool = MempoolStore::new(PriorityOpId(0), 100).with_memory_limit(tx_size);
+    mempool.insert(
+        vec![
+            gen_l2_tx_with_fee(account0, Nonce(0), 1),
+            gen_l2_tx_with_fee(account1, Nonce(0), 1),
+        ],
+        HashMap::new(),
+    );
+    assert_eq!(mempool.stats().l2_transaction_count, 2);
+    assert!(mempool.get_mempool_info().evicted_transactions.is_empty());
+}
+
 #[test]
 fn missing_txns() {
     let mut mempool = MempoolStore::new(PriorityOpId(0), 100);
@@ -239,6 +356,40 @@ fn mempool_size() {
     assert_eq!(mempool.stats().l2_transaction_count, 3);
 }
 
+#[test]
+fn removing_l2_transactions() {
+    let mut mempool = MempoolStore::new(PriorityOpId(0), 100);
+    let account0 = Address::random();
+    let account1 = Address::random();
+    let transactions = vec![
+        gen_l2_tx_with_fee(account0, Nonce(0), 100),
+        gen_l2_tx_with_fee(account0, Nonce(1), 100),
+        gen_l2_tx_with_fee(account1, Nonce(0), 100),
+    ];
+    let hashes: Vec<_> = transactions.iter().map(Transaction::hash).collect();
+    mempool.insert(transactions, HashMap::new());
+
+    // Hash and nonce must match.
+    assert!(!mempool.remove_l2_transaction(account0, Nonce(0), hashes[1]));
+    assert!(!mempool.remove_l2_transaction(account1, Nonce(1), hashes[2]));
+    assert_eq!(mempool.stats().l2_transaction_count, 3);
+
+    // Removing an executable transaction makes subsequent transactions of the account non-executable.
+    assert!(mempool.remove_l2_transaction(account0, Nonce(0), hashes[0]));
+    assert!(!mempool.remove_l2_transaction(account0, Nonce(0), hashes[0]));
+    assert_eq!(mempool.stats().l2_transaction_count, 2);
+    assert_eq!(mempool.stats().l2_priority_queue_size, 1);
+    assert_eq!(
+        view(mempool.next_transaction(&L2TxFilter::default())),
+        (account1, 0)
+    );
+    assert_eq!(mempool.next_transaction(&L2TxFilter::default()), None);
+
+    assert!(mempool.remove_l2_transaction(account0, Nonce(1), hashes[1]));
+    assert_eq!(mempool.stats().l2_transaction_count, 0);
+    assert_eq!(mempool.stats().l2_memory_usage, 0);
+}
+
 /// Checks whether filtering transactions based on their fee works as expected.
 #[test]
 fn filtering() {
@@ -384,6 +535,41 @@ fn gen_l2_tx_with_timestamp(address: Address, nonce: Nonce, received_at_ms: u64)
     txn.into()
 }
 
+fn gen_l2_tx_with_priority_fee(
+    address: Address,
+    nonce: Nonce,
+    received_at_ms: u64,
+    max_priority_fee_per_gas: u64,
+) -> Transaction {
+    let mut tx = gen_l2_tx_with_timestamp(address, nonce, received_at_ms);
+    match &mut tx.common_data {
+        ExecuteTransactionCommon::L2(data) => {
+            data.fee.max_fee_per_gas = U256::from(100);
+            data.fee.max_priority_fee_per_gas = U256::from(max_priority_fee_per_gas);
+        }
+        _ => unreachable!(),
+    }
+    tx
+}
+
+fn gen_l2_tx_with_fee(address: Address, nonce: Nonce, max_fee_per_gas: u64) -> Transaction {
+    let mut txn = L2Tx::new(
+        Address::default(),
+        Vec::new(),
+        nonce,
+        Fee {
+            max_fee_per_gas: U256::from(max_fee_per_gas),
+            ..Fee::default()
+        },
+        address,
+        U256::zero(),
+        None,
+        Default::default(),
+    );
+    txn.set_input(vec![], H256::random());
+    txn.into()
+}
+
 fn gen_l1_tx(priority_id: PriorityOpId) -> Transaction {
     let execute = Execute {
         contract_address: Address::repeat_byte(0x11),
@@ -1,7 +1,8 @@
-use std::{cmp::Ordering, collections::HashMap};
+use std::{cmp::Ordering, collections::HashMap, mem};
 
+use zksync_config::configs::chain::TransactionOrdering;
 use zksync_types::{
-    fee::Fee, fee_model::BatchFeeInput, l2::L2Tx, Address, Nonce, Transaction, U256,
+    fee::Fee, fee_model::BatchFeeInput, l2::L2Tx, Address, Nonce, Transaction, H256, U256,
 };
 
 /// Pending mempool transactions of account
@@ -12,13 +13,19 @@ pub(crate) struct AccountTransactions {
     /// account nonce in mempool
     /// equals to committed nonce in db + number of transactions sent to state keeper
     nonce: Nonce,
+    /// policy used to score transactions
+    ordering: TransactionOrdering,
+    /// estimated memory occupied by transactions in bytes
+    memory_usage: usize,
 }
 
 impl AccountTransactions {
-    pub fn new(nonce: Nonce) -> Self {
+    pub fn new(nonce: Nonce, ordering: TransactionOrdering) -> Self {
         Self {
             transactions: HashMap::new(),
             nonce,
+            ordering,
+            memory_usage: 0,
         }
     }
 
@@ -30,11 +37,12 @@ impl AccountTransactions {
         if nonce < self.nonce {
             return metadata;
         }
-        let new_score = Self::score_for_transaction(&transaction);
-        let previous_score = self
-            .transactions
-            .insert(nonce, transaction)
-            .map(|tx| Self::score_for_transaction(&tx));
+        let new_score = self.score_for_transaction(&transaction);
+        self.memory_usage += estimated_size(&transaction);
+        let previous_score = self.transactions.insert(nonce, transaction).map(|tx| {
+            self.memory_usage -= estimated_size(&tx);
+            self.score_for_transaction(&tx)
+        });
         metadata.is_new = previous_score.is_none();
         if nonce == self.nonce {
             metadata.new_score = Some(new_score);
@@ -50,11 +58,12 @@ impl AccountTransactions {
             .transactions
             .remove(&self.nonce)
             .expect("missing transaction in mempool");
+        self.memory_usage -= estimated_size(&transaction);
         self.nonce += 1;
         let score = self
             .transactions
             .get(&self.nonce)
-            .map(Self::score_for_transaction);
+            .map(|tx| self.score_for_transaction(tx));
         (transaction, score)
     }
 
@@ -67,27 +76,91 @@ impl AccountTransactions {
         self.nonce = self.nonce.min(tx_nonce);
         self.transactions
             .get(&(tx_nonce + 1))
-            .map(Self::score_for_transaction)
+            .map(|tx| self.score_for_transaction(tx))
     }
 
     pub fn len(&self) -> usize {
         self.transactions.len()
     }
 
-    fn score_for_transaction(transaction: &L2Tx) -> MempoolScore {
+    pub fn memory_usage(&self) -> usize {
+        self.memory_usage
+    }
+
+    /// Returns the transaction with the highest nonce, unless it's executable (i.e., its nonce is the account nonce).
+    /// Such a transaction is evicted first, since evicting transactions with lower nonces would make
+    /// all subsequent transactions non-executable.
+    pub fn last_evictable(&self) -> Option<&L2Tx> {
+        let (nonce, transaction) = self.transactions.iter().max_by_key(|(nonce, _)| **nonce)?;
+        (*nonce > self.nonce).then_some(transaction)
+    }
+
+    /// Removes the transaction returned by [`Self::last_evictable()`].
+    pub fn evict_last(&mut self) -> Option<L2Tx> {
+        let nonce = self.last_evictable()?.common_data.nonce;
+        let transaction = self.transactions.remove(&nonce)?;
+        self.memory_usage -= estimated_size(&transaction);
+        Some(transaction)
+    }
+
+    /// Removes the transaction with the specified nonce if it has the specified hash. Returns the removed transaction
+    /// together with its score if the transaction was executable (i.e., its score is in the priority queue).
+    pub fn remove(&mut self, nonce: Nonce, hash: H256) -> Option<(L2Tx, Option<MempoolScore>)> {
+        if self.transactions.get(&nonce)?.hash() != hash {
+            return None;
+        }
+        let transaction = self.transactions.remove(&nonce)?;
+        self.memory_usage -= estimated_size(&transaction);
+        let score = (nonce == self.nonce).then(|| self.score_for_transaction(&transaction));
+        Some((transaction, score))
+    }
+
+    fn score_for_transaction(&self, transaction: &L2Tx) -> MempoolScore {
+        let fee_data = &transaction.common_data.fee;
+        let priority_fee_per_gas = match self.ordering {
+            TransactionOrdering::ArrivalTime => U256::zero(),
+            TransactionOrdering::FeePriority => fee_data
+                .max_priority_fee_per_gas
+                .min(fee_data.max_fee_per_gas),
+        };
         MempoolScore {
             account: transaction.initiator_account(),
+            priority_fee_per_gas,
             received_at_ms: transaction.received_timestamp_ms,
-            fee_data: transaction.common_data.fee.clone(),
+            fee_data: fee_data.clone(),
         }
     }
 }
 
-/// Mempool score of transaction. Used to prioritize L2 transactions in mempool
-/// Currently trivial ordering is used based on received at timestamp
+/// Estimates memory occupied by a transaction in the mempool.
+pub(crate) fn estimated_size(transaction: &L2Tx) -> usize {
+    let execute = &transaction.execute;
+    let factory_deps_size: usize = execute.factory_deps.iter().flatten().map(Vec::len).sum();
+    let common_data = &transaction.common_data;
+    let input_size = common_data
+        .input
+        .as_ref()
+        .map_or(0, |input| input.data.len());
+    let raw_bytes_size = transaction
+        .raw_bytes
+        .as_ref()
+        .map_or(0, |bytes| bytes.0.len());
+    mem::size_of::<L2Tx>()
+        + execute.calldata.len()
+        + factory_deps_size
+        + common_data.signature.len()
+        + input_size
+        + raw_bytes_size
+}
+
+/// Mempool score of transaction. Used to prioritize L2 transactions in mempool.
+/// Transactions are ordered by the priority fee (which is zero unless fee-priority ordering is used),
+/// and then by received at timestamp.
 #[derive(Eq, PartialEq, Clone, Debug, Hash)]
 pub struct MempoolScore {
     pub account: Address,
+    /// Effective priority fee per gas used for ordering.
+    pub priority_fee_per_gas: U256,
     pub received_at_ms: u64,
     // Not used for actual scoring, but state keeper would request
     // transactions that have acceptable fee values (so transactions
@@ -105,6 +178,10 @@ impl MempoolScore {
 
 impl Ord for MempoolScore {
     fn cmp(&self, other: &MempoolScore) -> Ordering {
+        match self.priority_fee_per_gas.cmp(&other.priority_fee_per_gas) {
+            Ordering::Equal => {}
+            ordering => return ordering,
+        }
         match self.received_at_ms.cmp(&other.received_at_ms).reverse() {
             Ordering::Equal => {}
             ordering => return ordering,
@@ -159,6 +236,7 @@ mod tests {
 
         let score = MempoolScore {
             account: Address::random(),
+            priority_fee_per_gas: U256::zero(), // Not important
             received_at_ms: Default::default(), // Not important
             fee_data: Fee {
                 gas_limit: Default::default(), // Not important
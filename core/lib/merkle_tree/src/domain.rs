@@ -1,5 +1,7 @@
 //! Tying the Merkle tree implementation to the problem domain.
 
+use std::ops::ControlFlow;
+
 use rayon::{ThreadPool, ThreadPoolBuilder};
 use zksync_crypto::hasher::blake2::Blake2Hasher;
 use zksync_prover_interface::inputs::{PrepareBasicCircuitsJob, StorageLogMetadata};
@@ -14,7 +16,9 @@ use crate::{
         Key, Root, TreeEntry, TreeEntryWithProof, TreeInstruction, TreeLogEntry, ValueHash,
         TREE_DEPTH,
     },
-    BlockOutput, HashTree, MerkleTree, NoVersionError,
+    unstable::ConsistencyError,
+    BlockOutput, EntryProof, HashTree, MerkleTree, MerkleTreePruner, MerkleTreePrunerHandle,
+    NoVersionError,
 };
 
 /// Metadata for the current tree state.
@@ -111,6 +115,17 @@ impl ZkSyncTree {
         ZkSyncTreeReader(MerkleTree::new(db))
     }
 
+    /// Returns a pruner for the tree retaining `past_versions_to_keep` latest versions together with its handle.
+    /// The pruner should be run on a separate thread (e.g., using [`MerkleTreePruner::run()`]); it can run
+    /// concurrently with tree updates. Like [`Self::reader()`], the pruner only sees changes flushed to RocksDB.
+    pub fn pruner(
+        &self,
+        past_versions_to_keep: u64,
+    ) -> (MerkleTreePruner<RocksDBWrapper>, MerkleTreePrunerHandle) {
+        let db = self.tree.db.inner().clone();
+        MerkleTreePruner::new(db, past_versions_to_keep)
+    }
+
     /// Sets the chunk size for multi-get operations. The requested keys will be split
     /// into chunks of this size and requested in parallel using `rayon`. Setting chunk size
     /// to a large value (e.g., `usize::MAX`) will effectively disable parallelism.
@@ -127,7 +142,7 @@ impl ZkSyncTree {
     }
 
     /// Signals that the tree should use a dedicated `rayon` thread pool for parallel operations
-    /// (for now, hash computations).
+    /// (for now, tree traversal and hash computations during updates).
     ///
     /// If `thread_count` is 0, the default number of threads will be used; see `rayon` docs
     /// for details.
@@ -444,4 +459,52 @@ impl ZkSyncTreeReader {
         let version = u64::from(l1_batch_number.0);
         self.0.entries_with_proofs(version, keys)
     }
+
+    /// Produces inclusion or exclusion proofs for the specified keys in the tree after processing
+    /// the specified L1 batch. See [`MerkleTree::prove()`] for details.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the tree `version` is missing.
+    pub fn prove(
+        &self,
+        keys: &[Key],
+        l1_batch_number: L1BatchNumber,
+    ) -> Result<Vec<EntryProof>, NoVersionError> {
+        self.0.prove(keys, l1_batch_number.0.into())
+    }
+
+    /// Returns the root hash of the tree after processing the specified L1 batch, or `None` if the corresponding
+    /// tree version is missing.
+    pub fn root_hash_for_l1_batch(&self, l1_batch_number: L1BatchNumber) -> Option<ValueHash> {
+        self.0.root_hash(l1_batch_number.0.into())
+    }
+
+    /// Verifies consistency of the tree after processing the specified L1 batch. This includes checking hashes
+    /// of all internal nodes and the uniqueness of leaf indices. The check is expensive since it traverses
+    /// the entire tree.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first detected inconsistency (including a missing tree version).
+    pub fn verify_consistency(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> Result<(), ConsistencyError> {
+        self.0.verify_consistency(l1_batch_number.0.into(), true)
+    }
+
+    /// Visits all entries of the tree after processing the specified L1 batch in the order of increasing keys.
+    /// See [`MerkleTree::visit_entries()`] for details.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the tree `version` is missing.
+    pub fn visit_entries(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        visitor: impl FnMut(TreeEntry) -> ControlFlow<()>,
+    ) -> Result<(), NoVersionError> {
+        self.0.visit_entries(l1_batch_number.0.into(), visitor)
+    }
 }
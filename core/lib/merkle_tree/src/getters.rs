@@ -1,13 +1,36 @@
 //! Getters for the Merkle tree.
 
+use std::ops::ControlFlow;
+
 use crate::{
     hasher::HasherWithStats,
     recovery::MerkleTreeRecovery,
     storage::{LoadAncestorsResult, SortedKeys, WorkingPatchSet},
-    types::{Nibbles, Node, TreeEntry, TreeEntryWithProof},
-    Database, HashTree, Key, MerkleTree, NoVersionError, PruneDatabase, ValueHash,
+    types::{Nibbles, Node, Root, TreeEntry, TreeEntryWithProof},
+    Database, EntryProof, HashTree, Key, MerkleTree, NoVersionError, PruneDatabase, ValueHash,
 };
 
+impl<DB: Database> MerkleTree<DB> {
+    /// Produces proofs for the specified keys at the specified tree `version`. The proofs are returned
+    /// in the same order as requested. For keys present in the tree, the returned proof is an inclusion proof;
+    /// for missing keys, it is an exclusion proof with zero value and leaf index.
+    ///
+    /// Unlike [`Self::entries_with_proofs()`], the returned proofs can be verified using
+    /// the lightweight `zksync_merkle_tree_verifier` crate that doesn't depend on the tree implementation.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the tree `version` is missing (e.g., it was pruned or truncated).
+    pub fn prove(
+        &self,
+        leaf_keys: &[Key],
+        version: u64,
+    ) -> Result<Vec<EntryProof>, NoVersionError> {
+        let entries = self.entries_with_proofs(version, leaf_keys)?;
+        Ok(entries.into_iter().map(EntryProof::from).collect())
+    }
+}
+
 impl<DB: Database, H: HashTree> MerkleTree<DB, H> {
     /// Reads entries with the specified keys from the tree. The entries are returned in the same order
     /// as requested. If a certain key is not present in the tree, the corresponding returned entry
@@ -55,6 +78,69 @@ impl<DB: Database, H: HashTree> MerkleTree<DB, H> {
             },
         )
     }
+
+    /// Visits all entries in the tree at the specified `version` in the order of increasing keys
+    /// (i.e., in the order required by [`MerkleTreeRecovery::extend()`]). Visiting stops early
+    /// if `visitor` returns [`ControlFlow::Break`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the tree `version` is missing.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a tree node referenced by its parent is missing, e.g. because it was pruned concurrently.
+    pub fn visit_entries(
+        &self,
+        version: u64,
+        mut visitor: impl FnMut(TreeEntry) -> ControlFlow<()>,
+    ) -> Result<(), NoVersionError> {
+        let root = self.db.root(version).ok_or_else(|| {
+            let manifest = self.db.manifest().unwrap_or_default();
+            NoVersionError {
+                missing_version: version,
+                version_count: manifest.version_count,
+            }
+        })?;
+        if let Root::Filled { node, .. } = root {
+            visit_node(&self.db, node, Nibbles::EMPTY, &mut visitor);
+        }
+        Ok(())
+    }
+}
+
+fn visit_node(
+    db: &impl Database,
+    node: Node,
+    nibbles: Nibbles,
+    visitor: &mut impl FnMut(TreeEntry) -> ControlFlow<()>,
+) -> ControlFlow<()> {
+    let node = match node {
+        Node::Leaf(leaf) => return visitor(leaf.into()),
+        Node::Internal(node) => node,
+    };
+
+    // Children are iterated in the increasing nibble order, which corresponds to the increasing key order.
+    // Loading all children at once is more efficient than loading them one by one.
+    let child_keys: Vec<_> = node
+        .children()
+        .map(|(nibble, child_ref)| {
+            let child_nibbles = nibbles
+                .push(nibble)
+                .expect("internal node at terminal level");
+            (
+                child_nibbles.with_version(child_ref.version),
+                child_ref.is_leaf,
+            )
+        })
+        .collect();
+    let children = db.tree_nodes(&child_keys);
+    for ((child_key, _), child) in child_keys.iter().zip(children) {
+        let child = child.unwrap_or_else(|| panic!("tree node at {child_key} is missing"));
+        // Recursion here is OK; the tree isn't that deep.
+        visit_node(db, child, child_key.nibbles, visitor)?;
+    }
+    ControlFlow::Continue(())
 }
 
 fn load_and_transform_entries<T>(
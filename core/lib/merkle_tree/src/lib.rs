@@ -47,6 +47,7 @@
 )]
 
 use zksync_crypto::hasher::blake2::Blake2Hasher;
+pub use zksync_merkle_tree_verifier::{EntryProof, ProofError};
 
 pub use crate::{
     errors::NoVersionError,
@@ -81,6 +82,7 @@ mod utils;
 #[doc(hidden)]
 pub mod unstable {
     pub use crate::{
+        consistency::ConsistencyError,
         errors::DeserializeError,
         types::{Manifest, Node, NodeKey, Root},
     };
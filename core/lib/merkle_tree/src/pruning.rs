@@ -1,22 +1,43 @@
 //! Tree pruning logic.
 
-use std::{fmt, sync::mpsc, time::Duration};
+use std::{
+    fmt,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        mpsc, Arc,
+    },
+    time::Duration,
+};
 
 use crate::{
     metrics::{PruningStats, PRUNING_TIMINGS},
     storage::{PruneDatabase, PrunePatchSet},
 };
 
-/// Handle for a [`MerkleTreePruner`] allowing to abort its operation.
+/// Handle for a [`MerkleTreePruner`] allowing to abort its operation and to coordinate it with
+/// finality of tree versions.
 ///
 /// The pruner is aborted once the handle is dropped.
 #[must_use = "Pruner is aborted once handle is dropped"]
 #[derive(Debug)]
 pub struct MerkleTreePrunerHandle {
     aborted_sender: mpsc::Sender<()>,
+    finalized_version: Arc<AtomicU64>,
 }
 
 impl MerkleTreePrunerHandle {
+    /// Notifies the pruner that tree versions up to and including `version` are final (e.g., correspond
+    /// to L1 batches executed on L1). Once this method is called, the pruner always retains this version
+    /// and all newer ones, regardless of the number of past versions to keep. Decreasing the finalized
+    /// version is a no-op.
+    pub fn set_finalized_version(&self, version: u64) {
+        self.finalized_version
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |prev_version| {
+                (prev_version == NO_FINALIZED_VERSION || prev_version < version).then_some(version)
+            })
+            .ok();
+    }
+
     /// Aborts the pruner that this handle is attached to. If the pruner has already terminated
     /// (e.g., due to a panic), this is a no-op.
     pub fn abort(self) {
@@ -42,9 +63,14 @@ pub struct MerkleTreePruner<DB> {
     past_versions_to_keep: u64,
     target_pruned_key_count: usize,
     poll_interval: Duration,
+    iteration_delay: Duration,
+    finalized_version: Arc<AtomicU64>,
     aborted_receiver: mpsc::Receiver<()>,
 }
 
+/// Marker value for [`MerkleTreePruner`] not coordinated with finality of tree versions.
+const NO_FINALIZED_VERSION: u64 = u64::MAX;
+
 impl<DB> fmt::Debug for MerkleTreePruner<DB> {
     fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
         formatter
@@ -52,6 +78,8 @@ impl<DB> fmt::Debug for MerkleTreePruner<DB> {
             .field("past_versions_to_keep", &self.past_versions_to_keep)
             .field("target_pruned_key_count", &self.target_pruned_key_count)
             .field("poll_interval", &self.poll_interval)
+            .field("iteration_delay", &self.iteration_delay)
+            .field("finalized_version", &self.finalized_version)
             .finish_non_exhaustive()
     }
 }
@@ -66,12 +94,18 @@ impl<DB: PruneDatabase> MerkleTreePruner<DB> {
     /// is dropped.*
     pub fn new(db: DB, past_versions_to_keep: u64) -> (Self, MerkleTreePrunerHandle) {
         let (aborted_sender, aborted_receiver) = mpsc::channel();
-        let handle = MerkleTreePrunerHandle { aborted_sender };
+        let finalized_version = Arc::new(AtomicU64::new(NO_FINALIZED_VERSION));
+        let handle = MerkleTreePrunerHandle {
+            aborted_sender,
+            finalized_version: finalized_version.clone(),
+        };
         let this = Self {
             db,
             past_versions_to_keep,
             target_pruned_key_count: 500_000,
             poll_interval: Duration::from_secs(60),
+            iteration_delay: Duration::ZERO,
+            finalized_version,
             aborted_receiver,
         };
         (this, handle)
@@ -94,10 +128,24 @@ impl<DB: PruneDatabase> MerkleTreePruner<DB> {
         self.poll_interval = poll_interval;
     }
 
+    /// Sets the delay between consecutive pruning iterations if the pruner has more work to do.
+    /// Can be used to throttle pruning so that it doesn't compete for I/O with tree updates.
+    ///
+    /// The default value is zero (no throttling).
+    pub fn set_iteration_delay(&mut self, delay: Duration) {
+        self.iteration_delay = delay;
+    }
+
     fn target_retained_version(&self) -> Option<u64> {
         let manifest = self.db.manifest()?;
         let latest_version = manifest.version_count.checked_sub(1)?;
-        latest_version.checked_sub(self.past_versions_to_keep)
+        let target_version = latest_version.checked_sub(self.past_versions_to_keep)?;
+        let finalized_version = self.finalized_version.load(Ordering::Relaxed);
+        if finalized_version == NO_FINALIZED_VERSION {
+            Some(target_version)
+        } else {
+            Some(target_version.min(finalized_version))
+        }
     }
 
     #[doc(hidden)] // Used in integration tests; logically private
@@ -150,7 +198,7 @@ impl<DB: PruneDatabase> MerkleTreePruner<DB> {
                 let has_more_work = stats.has_more_work();
                 stats.report();
                 if has_more_work {
-                    Duration::ZERO
+                    self.iteration_delay
                 } else {
                     self.poll_interval
                 }
@@ -233,6 +281,32 @@ mod tests {
         }
     }
 
+    #[test]
+    fn pruner_respects_finalized_version() {
+        let mut db = create_db();
+        let (mut pruner, handle) = MerkleTreePruner::new(&mut db, 0);
+        handle.set_finalized_version(2);
+
+        let stats = pruner.run_once().unwrap();
+        assert_eq!(stats.deleted_stale_key_versions, 1..3);
+        assert_eq!(stats.target_retained_version, 2);
+        assert!(pruner.run_once().is_none());
+
+        // Decreasing the finalized version should be a no-op.
+        handle.set_finalized_version(1);
+        assert!(pruner.run_once().is_none());
+
+        handle.set_finalized_version(4);
+        let stats = pruner.run_once().unwrap();
+        assert_eq!(stats.deleted_stale_key_versions, 3..5);
+        assert_eq!(stats.target_retained_version, 4);
+
+        for version in 0..4 {
+            assert!(db.root_mut(version).is_none());
+        }
+        assert!(db.root_mut(4).is_some());
+    }
+
     #[test]
     fn pruner_is_aborted_immediately_when_requested() {
         let (mut pruner, pruner_handle) = MerkleTreePruner::new(PatchSet::default(), 0);
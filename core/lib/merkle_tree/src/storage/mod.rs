@@ -1,6 +1,11 @@
 //! Storage-related logic.
 
+use std::time::Instant;
+
+use rayon::prelude::*;
+
 pub(crate) use self::patch::{LoadAncestorsResult, WorkingPatchSet};
+use self::proofs::SUBTREE_COUNT;
 pub use self::{
     database::{Database, NodeKeys, Patched, PruneDatabase, PrunePatchSet},
     patch::PatchSet,
@@ -8,11 +13,12 @@ pub use self::{
 };
 use crate::{
     hasher::HashTree,
-    metrics::{TreeUpdaterStats, BLOCK_TIMINGS, GENERAL_METRICS},
+    metrics::{HashingStats, TreeUpdaterStats, BLOCK_TIMINGS, GENERAL_METRICS},
     types::{
         BlockOutput, ChildRef, InternalNode, Key, LeafNode, Manifest, Nibbles, Node, Root,
         TreeEntry, TreeLogEntry, TreeTags, ValueHash,
     },
+    utils::merge_by_index,
 };
 
 mod database;
@@ -219,6 +225,48 @@ impl TreeUpdater {
         (log, leaf_data)
     }
 
+    /// Sequentially inserts `entries` belonging to a single subtree. Returns logs accompanied
+    /// by the 0-based entry indices.
+    fn extend_part(
+        &mut self,
+        entries: Vec<(usize, TreeEntry, Nibbles)>,
+    ) -> Vec<(usize, TreeLogEntry)> {
+        let logs = entries.into_iter().map(|(index, entry, parent_nibbles)| {
+            let (log, _) = self.insert(entry, &parent_nibbles);
+            (index, log)
+        });
+        logs.collect()
+    }
+
+    /// Updates child refs in the `root` node for the subtrees changed in `parts`, and merges
+    /// all `parts` into a single updater. Each of `parts` must have its subtree hashes computed.
+    fn merge_subtrees(parts: Vec<Self>, mut root: InternalNode) -> Self {
+        for (subtree_idx, part) in parts.iter().enumerate() {
+            let nibble = u8::try_from(subtree_idx).unwrap();
+            if part.patch_set.get(&Nibbles::single(nibble)).is_none() {
+                continue; // The subtree is not changed
+            }
+            let Some(Node::Internal(part_root)) = part.patch_set.get(&Nibbles::EMPTY) else {
+                unreachable!("Root node must be an internal node");
+            };
+            let child_ref = *part_root.child_ref(nibble).unwrap();
+            // ^ `unwrap()` is safe by construction: the root node references all changed subtrees.
+            root.insert_child_ref(nibble, child_ref);
+        }
+
+        let mut updater = parts.into_iter().reduce(Self::merge).unwrap();
+        // ^ `unwrap()` is safe: `parts` is non-empty
+        if root.child_count() == 0 {
+            // We cannot save the empty internal root node because it'll fail deserialization
+            // checks later. By construction, the patch set is guaranteed to be valid (namely empty)
+            // after removal.
+            updater.patch_set.take_root();
+        } else {
+            updater.set_root_node(root.into());
+        }
+        updater
+    }
+
     fn update_moved_leaf_ref(&mut self, leaf_nibbles: &Nibbles) {
         if let Some((parent_nibbles, last_nibble)) = leaf_nibbles.split_last() {
             let child_ref = self
@@ -282,6 +330,10 @@ impl<'a, DB: Database + ?Sized> Storage<'a, DB> {
 
     /// Extends the Merkle tree in the lightweight operation mode, without intermediate hash
     /// computations.
+    ///
+    /// Similarly to [`Self::extend_with_proofs()`], entries are split by the first key nibble, and each
+    /// of the resulting subtrees with the root at level 4 is updated and hashed independently
+    /// using `rayon`. The root node is then assembled from the subtree hashes.
     pub fn extend(mut self, entries: Vec<TreeEntry>) -> (BlockOutput, PatchSet) {
         let load_nodes_latency = BLOCK_TIMINGS.load_nodes.start();
         let sorted_keys = SortedKeys::new(entries.iter().map(|entry| entry.key));
@@ -289,28 +341,86 @@ impl<'a, DB: Database + ?Sized> Storage<'a, DB> {
         let load_nodes_latency = load_nodes_latency.observe();
         tracing::debug!("Load stage took {load_nodes_latency:?}");
 
+        let entry_parts = Self::split_entries(entries, parent_nibbles);
+        let root = self.updater.patch_set.ensure_internal_root_node();
+        let initial_metrics = self.updater.metrics;
+        let storage_parts = self.updater.split();
+
         let extend_patch_latency = BLOCK_TIMINGS.extend_patch.start();
-        let mut logs = Vec::with_capacity(entries.len());
-        for (entry, parent_nibbles) in entries.into_iter().zip(parent_nibbles) {
-            let (log, _) = self.updater.insert(entry, &parent_nibbles);
-            if matches!(log, TreeLogEntry::Inserted) {
-                self.leaf_count += 1;
-            }
-            logs.push(log);
-        }
+        // `into_par_iter()` below uses `rayon` to parallelize tree traversal.
+        let (mut storage_parts, logs): (Vec<_>, Vec<_>) = storage_parts
+            .into_par_iter()
+            .zip_eq(entry_parts)
+            .map(|(mut storage, entries)| {
+                let logs = storage.extend_part(entries);
+                (storage, logs)
+            })
+            .unzip();
         let extend_patch_latency = extend_patch_latency.observe();
         tracing::debug!("Tree traversal stage took {extend_patch_latency:?}");
 
-        let leaf_count = self.leaf_count;
-        let (root_hash, patch) = self.finalize();
+        let finalize_patch_latency = BLOCK_TIMINGS.finalize_patch.start();
+        let mut hashing_stats = HashingStats::default();
+        let started_at = Instant::now();
+        // `par_iter_mut()` below uses `rayon` to parallelize hash computations for subtrees.
+        storage_parts.par_iter_mut().for_each_init(
+            || self.hasher.with_stats(&hashing_stats),
+            |hasher, storage| storage.patch_set.hash_subtrees(hasher),
+        );
+        self.updater = TreeUpdater::merge_subtrees(storage_parts, root);
+        self.updater.metrics += initial_metrics;
+        let logs: Vec<_> = merge_by_index(logs)
+            .into_iter()
+            .map(|(_, log)| log)
+            .collect();
+        self.leaf_count += self.updater.metrics.new_leaves;
+        tracing::debug!(
+            "Finished updating tree; total leaf count: {}, stats: {:?}",
+            self.leaf_count,
+            self.updater.metrics
+        );
+        self.updater.metrics.report();
+
+        let root_hash = match self.updater.patch_set.get(&Nibbles::EMPTY) {
+            Some(root) => root.hash(&mut self.hasher.with_stats(&hashing_stats), 0),
+            None => self.hasher.empty_tree_hash(),
+        };
+        hashing_stats.hashing_duration += started_at.elapsed();
+        let patch = self
+            .updater
+            .patch_set
+            .finalize_without_hashing(self.manifest, self.leaf_count);
+        GENERAL_METRICS.leaf_count.set(self.leaf_count);
+        let finalize_patch_latency = finalize_patch_latency.observe();
+        tracing::debug!(
+            "Tree finalization stage took {finalize_patch_latency:?}; hashed {:?}B in {:?}",
+            hashing_stats.hashed_bytes,
+            hashing_stats.hashing_duration
+        );
+        hashing_stats.report();
+
         let output = BlockOutput {
             root_hash,
-            leaf_count,
+            leaf_count: self.leaf_count,
             logs,
         };
         (output, patch)
     }
 
+    /// Splits `entries` by the first key nibble to be used during parallelized tree traversal.
+    fn split_entries(
+        entries: Vec<TreeEntry>,
+        parent_nibbles: Vec<Nibbles>,
+    ) -> [Vec<(usize, TreeEntry, Nibbles)>; SUBTREE_COUNT] {
+        let mut parts = [(); SUBTREE_COUNT].map(|()| vec![]);
+        let it = entries.into_iter().zip(parent_nibbles);
+        for (index, (entry, parent_nibbles)) in it.enumerate() {
+            let first_nibble = Nibbles::nibble(&entry.key, 0);
+            parts[first_nibble as usize].push((index, entry, parent_nibbles));
+        }
+        parts
+    }
+
     pub fn greatest_key(mut self) -> Option<Key> {
         Some(self.updater.load_greatest_key(self.db)?.0.full_key)
     }
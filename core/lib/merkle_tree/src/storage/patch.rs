@@ -344,6 +344,33 @@ impl WorkingPatchSet {
         }
     }
 
+    /// Computes hashes for all changed nodes except for the root node and stores them in the parent nodes.
+    /// As a result, the root node child refs for changed subtrees (or, if this set is a part obtained
+    /// via [`Self::split()`], the child ref for the part subtree) contain up-to-date hashes.
+    pub fn hash_subtrees(&mut self, hasher: &mut HasherWithStats<'_>) {
+        for nibble_count in (1..self.changes_by_nibble_count.len()).rev() {
+            let tree_level = nibble_count * 4;
+            let (upper_levels, lower_levels) =
+                self.changes_by_nibble_count.split_at_mut(nibble_count);
+            let parent_level = upper_levels.last_mut().unwrap();
+            // ^ `unwrap()` is safe: `upper_levels` contains at least the root level
+
+            for (nibbles, node) in &lower_levels[0] {
+                let node_hash = node.inner.hash(hasher, tree_level);
+                let nibbles = Nibbles::from_parts(*nibbles, nibble_count);
+                let (parent_nibbles, last_nibble) = nibbles.split_last().unwrap();
+                let parent = parent_level.get_mut(parent_nibbles.bytes()).unwrap();
+                let Node::Internal(parent) = &mut parent.inner else {
+                    unreachable!("Node parent must be an internal node");
+                };
+                // ^ `unwrap()`s are safe by construction: the parent of any changed node
+                // is an `InternalNode` that must be in the change set as well, and it must reference
+                // the currently considered child.
+                parent.child_ref_mut(last_nibble).unwrap().hash = node_hash;
+            }
+        }
+    }
+
     /// Computes hashes and serializes this change set.
     pub(super) fn finalize(
         self,
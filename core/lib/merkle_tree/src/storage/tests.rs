@@ -442,6 +442,44 @@ fn replaced_keys_are_correctly_tracked(writes_per_block: usize, with_proofs: boo
     }
 }
 
+#[test_casing(4, [1, 10, 100, 1_000])]
+fn parallel_extend_is_equivalent_to_extend_with_proofs(writes_per_block: u64) {
+    const RNG_SEED: u64 = 42;
+
+    let mut rng = StdRng::seed_from_u64(RNG_SEED);
+    let mut database = PatchSet::default();
+    let mut database_with_proofs = PatchSet::default();
+    for version in 0..10 {
+        let entries: Vec<_> = (0..writes_per_block)
+            .map(|i| {
+                let leaf_index = version * writes_per_block + i + 1;
+                TreeEntry::new(U256(rng.gen()), leaf_index, H256(rng.gen()))
+            })
+            .collect();
+
+        let storage = Storage::new(&database, &Blake2Hasher, version, true);
+        let (output, patch) = storage.extend(entries.clone());
+        let storage = Storage::new(&database_with_proofs, &Blake2Hasher, version, true);
+        let instructions = entries.into_iter().map(TreeInstruction::Write).collect();
+        let (output_with_proofs, patch_with_proofs) = storage.extend_with_proofs(instructions);
+
+        assert_eq!(Some(output.root_hash), output_with_proofs.root_hash());
+        assert_eq!(output.leaf_count, output_with_proofs.leaf_count);
+        let sub_patch = &patch.patches_by_version[&version];
+        let sub_patch_with_proofs = &patch_with_proofs.patches_by_version[&version];
+        assert_eq!(sub_patch.root, sub_patch_with_proofs.root);
+        assert_eq!(sub_patch.nodes, sub_patch_with_proofs.nodes);
+        let stale_keys: HashSet<_> = patch.stale_keys_by_version[&version].iter().collect();
+        let stale_keys_with_proofs: HashSet<_> = patch_with_proofs.stale_keys_by_version[&version]
+            .iter()
+            .collect();
+        assert_eq!(stale_keys, stale_keys_with_proofs);
+
+        database.apply_patch(patch);
+        database_with_proofs.apply_patch(patch_with_proofs);
+    }
+}
+
 fn assert_replaced_keys(db: &PatchSet, patch: &PatchSet) {
     assert_eq!(patch.patches_by_version.len(), 1);
     let (&version, sub_patch) = patch.patches_by_version.iter().next().unwrap();
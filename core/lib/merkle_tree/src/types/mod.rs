@@ -1,5 +1,6 @@
 //! Basic storage types.
 
+use zksync_merkle_tree_verifier::EntryProof;
 use zksync_types::{H256, U256};
 
 pub(crate) use self::internal::{
@@ -125,6 +126,17 @@ pub struct TreeEntryWithProof {
     pub merkle_path: Vec<ValueHash>,
 }
 
+impl From<TreeEntryWithProof> for EntryProof {
+    fn from(entry: TreeEntryWithProof) -> Self {
+        Self {
+            key: entry.base.key,
+            value: entry.base.value,
+            leaf_index: entry.base.leaf_index,
+            merkle_path: entry.merkle_path,
+        }
+    }
+}
+
 /// Output of inserting a block of entries into a Merkle tree.
 #[derive(Debug, PartialEq, Eq)]
 pub struct BlockOutput {
@@ -2,12 +2,13 @@
 
 use std::{cmp, mem};
 
+use assert_matches::assert_matches;
 use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
 use test_casing::test_casing;
 use zksync_crypto::hasher::blake2::Blake2Hasher;
 use zksync_merkle_tree::{
-    Database, HashTree, MerkleTree, PatchSet, Patched, TreeEntry, TreeInstruction, TreeLogEntry,
-    TreeRangeDigest,
+    Database, HashTree, MerkleTree, PatchSet, Patched, ProofError, TreeEntry, TreeInstruction,
+    TreeLogEntry, TreeRangeDigest,
 };
 use zksync_types::{AccountTreeId, Address, StorageKey, H256, U256};
 
@@ -234,6 +235,39 @@ fn entry_proofs_are_computed_correctly_with_intermediate_commits(chunk_size: usi
     }
 }
 
+#[test_casing(3, [10, 17, 42])]
+fn historical_proofs_are_verified_by_verifier(chunk_size: usize) {
+    let (kvs, _) = &*ENTRIES_AND_HASH;
+    let all_keys: Vec<_> = kvs.iter().map(|entry| entry.key).collect();
+    let mut tree = MerkleTree::new(PatchSet::default());
+    let root_hashes: Vec<_> = kvs
+        .chunks(chunk_size)
+        .map(|chunk| tree.extend(chunk.to_vec()).root_hash)
+        .collect();
+
+    for (version, root_hash) in root_hashes.iter().enumerate() {
+        let proofs = tree.prove(&all_keys, version as u64).unwrap();
+        assert_eq!(proofs.len(), all_keys.len());
+        for (i, (key, proof)) in all_keys.iter().zip(&proofs).enumerate() {
+            assert_eq!(proof.key, *key);
+            assert_eq!(proof.is_inclusion(), i < (version + 1) * chunk_size);
+            proof.verify(*root_hash).unwrap();
+        }
+
+        // Proofs must not verify against other versions of the tree.
+        if let Some(next_root_hash) = root_hashes.get(version + 1) {
+            let last_proof = proofs.last().unwrap();
+            assert_matches!(
+                last_proof.verify(*next_root_hash),
+                Err(ProofError::RootHashMismatch { .. })
+            );
+        }
+    }
+
+    let missing_version = root_hashes.len() as u64;
+    tree.prove(&all_keys, missing_version).unwrap_err();
+}
+
 fn test_accumulated_commits<DB: Database>(db: DB, chunk_size: usize) -> DB {
     let (kvs, expected_hash) = &*ENTRIES_AND_HASH;
     let mut db = Patched::new(db);
@@ -1,5 +1,7 @@
 //! Tests for tree recovery.
 
+use std::ops::ControlFlow;
+
 use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
 use test_casing::test_casing;
 use zksync_crypto::hasher::blake2::Blake2Hasher;
@@ -37,6 +39,56 @@ fn recovery_basics() {
     tree.verify_consistency(recovered_version, true).unwrap();
 }
 
+#[test]
+fn recovery_from_visited_entries() {
+    let (kvs, expected_hash) = &*ENTRIES_AND_HASH;
+    let mut tree = MerkleTree::new(PatchSet::default());
+    tree.extend(kvs.clone());
+    let mut updated_kvs = kvs[..50].to_vec();
+    for entry in &mut updated_kvs {
+        entry.value = ValueHash::repeat_byte(1);
+    }
+    let updated_hash = tree.extend(updated_kvs).root_hash;
+
+    let mut visited_entries = vec![];
+    tree.visit_entries(0, |entry| {
+        visited_entries.push(entry);
+        ControlFlow::Continue(())
+    })
+    .unwrap();
+    let mut sorted_kvs = kvs.clone();
+    sorted_kvs.sort_unstable_by_key(|entry| entry.key);
+    assert_eq!(visited_entries, sorted_kvs);
+
+    let mut recovery = MerkleTreeRecovery::new(PatchSet::default(), 0);
+    recovery.extend_linear(visited_entries);
+    assert_eq!(recovery.root_hash(), *expected_hash);
+
+    let mut visited_entries = vec![];
+    tree.visit_entries(1, |entry| {
+        visited_entries.push(entry);
+        ControlFlow::Continue(())
+    })
+    .unwrap();
+    let mut recovery = MerkleTreeRecovery::new(PatchSet::default(), 1);
+    recovery.extend_linear(visited_entries);
+    assert_eq!(recovery.root_hash(), updated_hash);
+
+    let mut visited_count = 0;
+    tree.visit_entries(1, |_| {
+        visited_count += 1;
+        if visited_count == 10 {
+            ControlFlow::Break(())
+        } else {
+            ControlFlow::Continue(())
+        }
+    })
+    .unwrap();
+    assert_eq!(visited_count, 10);
+    tree.visit_entries(2, |_| ControlFlow::Continue(()))
+        .unwrap_err();
+}
+
 fn test_recovery_in_chunks(mut db: impl PruneDatabase, kind: RecoveryKind, chunk_size: usize) {
     let (kvs, expected_hash) = &*ENTRIES_AND_HASH;
     let mut recovery_entries = kvs.clone();
@@ -0,0 +1,138 @@
+//! Verifier for Merkle proofs produced by the zkSync Era Merkle tree.
+//!
+//! The verifier implements the tree hashing specification described in the `zksync_merkle_tree` crate,
+//! but doesn't depend on the tree implementation. Thus, it can be used by external services
+//! (e.g., to build withdrawal or light-client proofs) with minimal dependencies.
+//!
+//! # Proof format
+//!
+//! An [`EntryProof`] proves either the inclusion of an entry into the tree (if the entry
+//! has a non-zero leaf index), or its exclusion (if the leaf index and the value are zero).
+//! The proof consists of the Merkle path, i.e., hashes of the sibling nodes for each level
+//! of the binary tree, ordered from the leaf level to the root level. Hashes at the beginning
+//! of the path corresponding to empty subtrees may be skipped.
+
+// Linter settings.
+#![warn(missing_debug_implementations, missing_docs, bare_trait_objects)]
+#![warn(clippy::all, clippy::pedantic)]
+#![allow(clippy::must_use_candidate, clippy::module_name_repetitions)]
+
+use std::iter;
+
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use zksync_basic_types::{H256, U256};
+use zksync_crypto::hasher::{blake2::Blake2Hasher, Hasher};
+
+#[cfg(test)]
+mod tests;
+
+/// Depth of the Merkle tree.
+pub const TREE_DEPTH: usize = 256;
+
+/// Returns the root hash of the empty tree.
+pub fn empty_tree_hash() -> H256 {
+    EMPTY_SUBTREE_HASHES[TREE_DEPTH]
+}
+
+/// Errors that can occur during proof verification.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ProofError {
+    /// Merkle path is longer than the tree depth.
+    #[error("Merkle path has {0} hashes, while the tree depth is {TREE_DEPTH}")]
+    PathTooLong(usize),
+    /// Leaf index is zero (i.e., the proof is an exclusion proof), but the value is non-zero.
+    #[error("leaf index is zero, but the value is non-zero")]
+    InvalidMissingValue,
+    /// The root hash restored from the proof differs from the trusted one.
+    #[error("root hash mismatch: expected {expected:?}, restored from the proof {actual:?}")]
+    RootHashMismatch {
+        /// Trusted root hash.
+        expected: H256,
+        /// Root hash restored from the proof.
+        actual: H256,
+    },
+}
+
+/// Proof of inclusion or exclusion of an entry in the Merkle tree.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EntryProof {
+    /// Key of the entry.
+    pub key: U256,
+    /// Value of the entry. Zero if the key is missing from the tree.
+    pub value: H256,
+    /// 1-based index of the entry in the tree. Zero if the key is missing from the tree.
+    pub leaf_index: u64,
+    /// Merkle path consisting of up to [`TREE_DEPTH`] hashes ordered starting from the leaf level
+    /// and ending before the root level. Hashes at the beginning of the path corresponding
+    /// to empty subtrees may be skipped.
+    pub merkle_path: Vec<H256>,
+}
+
+impl EntryProof {
+    /// Checks whether this is an inclusion proof (i.e., the entry is present in the tree).
+    pub fn is_inclusion(&self) -> bool {
+        self.leaf_index != 0
+    }
+
+    /// Restores the tree root hash from this proof.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the proof is malformed.
+    pub fn root_hash(&self) -> Result<H256, ProofError> {
+        let path_len = self.merkle_path.len();
+        if path_len > TREE_DEPTH {
+            return Err(ProofError::PathTooLong(path_len));
+        }
+        if self.leaf_index == 0 && !self.value.is_zero() {
+            return Err(ProofError::InvalidMissingValue);
+        }
+
+        let empty_hashes = EMPTY_SUBTREE_HASHES[..TREE_DEPTH - path_len].iter();
+        let full_path = empty_hashes.chain(&self.merkle_path);
+        let mut hash = hash_leaf(&self.value, self.leaf_index);
+        for (depth, adjacent_hash) in full_path.enumerate() {
+            hash = if self.key.bit(depth) {
+                Blake2Hasher.compress(adjacent_hash, &hash)
+            } else {
+                Blake2Hasher.compress(&hash, adjacent_hash)
+            };
+        }
+        Ok(hash)
+    }
+
+    /// Verifies this proof against the trusted root hash of the tree.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the proof is malformed or doesn't verify.
+    pub fn verify(&self, trusted_root_hash: H256) -> Result<(), ProofError> {
+        let root_hash = self.root_hash()?;
+        if root_hash == trusted_root_hash {
+            Ok(())
+        } else {
+            Err(ProofError::RootHashMismatch {
+                expected: trusted_root_hash,
+                actual: root_hash,
+            })
+        }
+    }
+}
+
+/// Hashes of empty subtrees indexed by the subtree depth. The last hash is the hash of the empty tree.
+static EMPTY_SUBTREE_HASHES: Lazy<Vec<H256>> = Lazy::new(|| {
+    let empty_leaf_hash = Blake2Hasher.hash_bytes(&[0_u8; 40]);
+    iter::successors(Some(empty_leaf_hash), |hash| {
+        Some(Blake2Hasher.compress(hash, hash))
+    })
+    .take(TREE_DEPTH + 1)
+    .collect()
+});
+
+fn hash_leaf(value: &H256, leaf_index: u64) -> H256 {
+    let mut bytes = [0_u8; 40];
+    bytes[..8].copy_from_slice(&leaf_index.to_be_bytes());
+    bytes[8..].copy_from_slice(value.as_bytes());
+    Blake2Hasher.hash_bytes(&bytes)
+}
@@ -0,0 +1,103 @@
+//! Tests for the Merkle proof verifier.
+
+use super::*;
+
+const EMPTY_TREE_HASH: H256 = H256([
+    152, 164, 142, 78, 209, 115, 97, 136, 56, 74, 232, 167, 157, 210, 28, 77, 102, 135, 229, 253,
+    34, 202, 24, 20, 137, 6, 215, 135, 54, 192, 216, 106,
+]);
+
+fn exclusion_proof(key: U256) -> EntryProof {
+    EntryProof {
+        key,
+        value: H256::zero(),
+        leaf_index: 0,
+        merkle_path: vec![],
+    }
+}
+
+#[test]
+fn empty_tree_hash_is_as_expected() {
+    assert_eq!(empty_tree_hash(), EMPTY_TREE_HASH);
+}
+
+#[test]
+fn verifying_exclusion_proof_for_empty_tree() {
+    let proof = exclusion_proof(U256::from(123));
+    assert!(!proof.is_inclusion());
+    proof.verify(EMPTY_TREE_HASH).unwrap();
+
+    let full_path_proof = EntryProof {
+        merkle_path: EMPTY_SUBTREE_HASHES[..TREE_DEPTH].to_vec(),
+        ..proof
+    };
+    full_path_proof.verify(EMPTY_TREE_HASH).unwrap();
+}
+
+#[test]
+fn verifying_inclusion_proof_for_single_entry_tree() {
+    let proof = EntryProof {
+        key: U256::from(123),
+        value: H256::repeat_byte(1),
+        leaf_index: 1,
+        merkle_path: vec![],
+    };
+    assert!(proof.is_inclusion());
+    let root_hash = proof.root_hash().unwrap();
+    assert_ne!(root_hash, EMPTY_TREE_HASH);
+    proof.verify(root_hash).unwrap();
+
+    // The exclusion proof for another key has the entry leaf hash in the Merkle path.
+    let other_key = U256::from(122); // differs from the entry key in the lowest bit
+    let mut merkle_path = EMPTY_SUBTREE_HASHES[1..TREE_DEPTH].to_vec();
+    merkle_path.insert(0, hash_leaf(&proof.value, proof.leaf_index));
+    let exclusion_proof = EntryProof {
+        merkle_path,
+        ..exclusion_proof(other_key)
+    };
+    exclusion_proof.verify(root_hash).unwrap();
+}
+
+#[test]
+fn proof_verification_errors() {
+    let mut proof = exclusion_proof(U256::from(123));
+    proof.value = H256::repeat_byte(1);
+    assert_eq!(
+        proof.verify(EMPTY_TREE_HASH),
+        Err(ProofError::InvalidMissingValue)
+    );
+
+    let proof = EntryProof {
+        merkle_path: vec![H256::zero(); TREE_DEPTH + 1],
+        ..exclusion_proof(U256::from(123))
+    };
+    assert_eq!(
+        proof.verify(EMPTY_TREE_HASH),
+        Err(ProofError::PathTooLong(TREE_DEPTH + 1))
+    );
+
+    let proof = EntryProof {
+        key: U256::from(123),
+        value: H256::repeat_byte(1),
+        leaf_index: 1,
+        merkle_path: vec![],
+    };
+    let err = proof.verify(EMPTY_TREE_HASH).unwrap_err();
+    assert!(
+        matches!(err, ProofError::RootHashMismatch { expected, .. } if expected == EMPTY_TREE_HASH),
+        "{err:?}"
+    );
+}
+
+#[test]
+fn proof_serialization() {
+    let proof = EntryProof {
+        key: U256::from(123),
+        value: H256::repeat_byte(1),
+        leaf_index: 1,
+        merkle_path: vec![H256::repeat_byte(2)],
+    };
+    let json = serde_json::to_string(&proof).unwrap();
+    let restored: EntryProof = serde_json::from_str(&json).unwrap();
+    assert_eq!(restored, proof);
+}
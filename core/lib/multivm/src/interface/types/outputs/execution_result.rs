@@ -2,6 +2,7 @@ use zksync_system_constants::PUBLISH_BYTECODE_OVERHEAD;
 use zksync_types::{
     event::{extract_long_l2_to_l1_messages, extract_published_bytecodes},
     l2_to_l1_log::{SystemL2ToL1Log, UserL2ToL1Log},
+    storage_writes_deduplicator::StorageWritesDeduplicator,
     tx::ExecutionMetrics,
     StorageLogQuery, Transaction, VmEvent,
 };
@@ -88,6 +89,13 @@ impl VmExecutionResultAndLogs {
             })
             .sum();
 
+        let storage_logs = &self.logs.storage_logs;
+        let storage_reads = storage_logs
+            .iter()
+            .filter(|log| !log.log_query.rw_flag)
+            .count();
+        let writes_metrics = StorageWritesDeduplicator::apply_on_empty_state(storage_logs);
+
         ExecutionMetrics {
             gas_used: self.statistics.gas_used as usize,
             published_bytecode_bytes,
@@ -96,7 +104,10 @@ impl VmExecutionResultAndLogs {
             contracts_used: self.statistics.contracts_used,
             contracts_deployed,
             vm_events: self.logs.events.len(),
-            storage_logs: self.logs.storage_logs.len(),
+            storage_logs: storage_logs.len(),
+            storage_reads,
+            storage_writes: writes_metrics.initial_storage_writes
+                + writes_metrics.repeated_storage_writes,
             total_log_queries: self.statistics.total_log_queries,
             cycles_used: self.statistics.cycles_used,
             computational_gas_used: self.statistics.computational_gas_used,
@@ -0,0 +1,201 @@
+//! Stable tracer API for external crates.
+//!
+//! VM-specific tracers (e.g., [`crate::vm_latest::VmTracer`]) expose VM internals and change with every VM version.
+//! [`ExternalTracer`] is a VM-agnostic alternative: it receives hooks with data expressed in terms of this module's
+//! types, and can be used with all VM versions supporting custom tracers. Wrap a tracer into an
+//! [`ExternalTracerAdapter`] to pass it to the VM.
+//!
+//! # Stability
+//!
+//! The [`ExternalTracer`] trait and the data types in this module follow semantic versioning of the `multivm` crate:
+//! new hooks are only added with a default no-op implementation, and new fields / variants are only added to
+//! `#[non_exhaustive]` types. Thus, tracers implemented against this module don't need to be updated when
+//! a VM version is added.
+//!
+//! Tracers are not supported by the pre-boojum VMs (`vm_m5`, `vm_m6` and `vm_1_3_2`); the hooks are never called
+//! for them.
+
+use std::{
+    fmt,
+    sync::{Arc, Mutex},
+};
+
+use zksync_types::{Address, U256};
+
+use crate::glue::tracers::IntoOldVmTracer;
+
+pub mod vm_boojum_integration;
+pub mod vm_latest;
+pub mod vm_refunds_enhancement;
+pub mod vm_virtual_blocks;
+
+/// Kind of an executed opcode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum OpcodeKind {
+    Invalid,
+    Nop,
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Jump,
+    Context,
+    Shift,
+    Binop,
+    Ptr,
+    NearCall,
+    /// Storage access, event or L2-to-L1 log emission, or a precompile call.
+    Log,
+    FarCall,
+    Ret,
+    Uma,
+}
+
+/// Information about an opcode passed to [`ExternalTracer::on_opcode()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct OpcodeInfo {
+    pub kind: OpcodeKind,
+    /// Program counter of the opcode.
+    pub pc: u16,
+    /// Address of the contract in whose context the opcode is executed.
+    pub address: Address,
+    /// Depth of the call stack, including near call frames.
+    pub depth: usize,
+    /// Gas remaining in the current frame before executing the opcode.
+    pub gas_remaining: u32,
+}
+
+/// Kind of a far call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum CallKind {
+    Normal,
+    Delegate,
+    /// Call on behalf of another address. Used by system contracts, e.g., to call accounts from the bootloader.
+    Mimic,
+}
+
+/// Information about a far call passed to [`ExternalTracer::on_call()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct CallInfo {
+    pub kind: CallKind,
+    pub caller: Address,
+    /// Address in whose context the callee code is executed. For delegate calls, differs from `code_address`.
+    pub callee: Address,
+    /// Address of the executed code.
+    pub code_address: Address,
+    /// Depth of the call stack in the callee frame, including near call frames.
+    pub depth: usize,
+    /// Gas passed to the callee.
+    pub gas: u32,
+}
+
+/// Information about a contract deployment passed to [`ExternalTracer::on_create()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct CreateInfo {
+    /// Account that has initiated the deployment.
+    pub deployer: Address,
+    /// Address of the deployed contract. The hook is called before running the contract constructor.
+    pub address: Address,
+    /// Depth of the call stack in the constructor frame, including near call frames.
+    pub depth: usize,
+    /// Gas passed to the constructor.
+    pub gas: u32,
+}
+
+/// Outcome of a far call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ReturnKind {
+    Ok,
+    Revert,
+    Panic,
+}
+
+/// Information about returning from a far call passed to [`ExternalTracer::on_return()`]. Returns are reported
+/// both for calls and deployments.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ReturnInfo {
+    pub kind: ReturnKind,
+    /// Address of the returning contract.
+    pub address: Address,
+    /// Depth of the call stack in the returning frame, including near call frames.
+    pub depth: usize,
+    /// Gas remaining in the returning frame.
+    pub gas_remaining: u32,
+}
+
+/// Storage slot access passed to [`ExternalTracer::on_storage_read()`] and [`ExternalTracer::on_storage_write()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct StorageAccess {
+    /// Address of the contract owning the storage slot.
+    pub address: Address,
+    pub key: U256,
+    /// Written value. Always `None` for reads.
+    pub value: Option<U256>,
+}
+
+/// VM-agnostic tracer that can be implemented by external crates. All hooks have no-op default implementations.
+///
+/// Hooks are called synchronously during VM execution, so they should be cheap; in particular,
+/// [`Self::on_opcode()`] is called for every executed opcode. Because VM execution is blocking,
+/// tracers should not perform any I/O.
+pub trait ExternalTracer: Send + 'static {
+    /// Called before executing each opcode.
+    fn on_opcode(&mut self, _opcode: &OpcodeInfo) {}
+
+    /// Called after entering a far call frame, except for deployments, which are reported via [`Self::on_create()`].
+    fn on_call(&mut self, _call: &CallInfo) {}
+
+    /// Called after entering the constructor frame of a deployed contract.
+    fn on_create(&mut self, _create: &CreateInfo) {}
+
+    /// Called before returning from a far call frame.
+    fn on_return(&mut self, _ret: &ReturnInfo) {}
+
+    /// Called before reading a storage slot.
+    fn on_storage_read(&mut self, _access: &StorageAccess) {}
+
+    /// Called before writing a storage slot.
+    fn on_storage_write(&mut self, _access: &StorageAccess) {}
+
+    /// Called once VM execution has finished (e.g., after the executed transaction or batch).
+    fn on_execution_end(&mut self) {}
+}
+
+/// Adapter converting an [`ExternalTracer`] into tracers for all supported VM versions.
+///
+/// The adapter can be cloned; clones share the wrapped tracer.
+#[derive(Clone)]
+pub struct ExternalTracerAdapter {
+    tracer: Arc<Mutex<Box<dyn ExternalTracer>>>,
+}
+
+impl fmt::Debug for ExternalTracerAdapter {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("ExternalTracerAdapter")
+            .finish_non_exhaustive()
+    }
+}
+
+impl ExternalTracerAdapter {
+    pub fn new(tracer: Box<dyn ExternalTracer>) -> Self {
+        Self {
+            tracer: Arc::new(Mutex::new(tracer)),
+        }
+    }
+
+    fn with_tracer(&self, action: impl FnOnce(&mut dyn ExternalTracer)) {
+        let mut tracer = self.tracer.lock().expect("external tracer panicked");
+        action(tracer.as_mut());
+    }
+}
+
+impl IntoOldVmTracer for ExternalTracerAdapter {}
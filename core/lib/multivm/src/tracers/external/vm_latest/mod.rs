@@ -0,0 +1,149 @@
+use zk_evm_1_4_1::{
+    tracing::{AfterExecutionData, BeforeExecutionData, VmLocalStateData},
+    zkevm_opcode_defs::{FarCallOpcode, LogOpcode, Opcode, RetOpcode},
+};
+use zksync_state::{StoragePtr, WriteStorage};
+use zksync_system_constants::CONTRACT_DEPLOYER_ADDRESS;
+
+use crate::{
+    interface::{tracer::VmExecutionStopReason, traits::tracers::dyn_tracers::vm_1_4_1::DynTracer},
+    tracers::external::{
+        CallInfo, CallKind, CreateInfo, ExternalTracerAdapter, OpcodeInfo, OpcodeKind, ReturnInfo,
+        ReturnKind, StorageAccess,
+    },
+    vm_latest::{BootloaderState, HistoryMode, SimpleMemory, VmTracer, ZkSyncVmState},
+};
+
+fn opcode_kind(opcode: Opcode) -> OpcodeKind {
+    match opcode {
+        Opcode::Invalid(_) => OpcodeKind::Invalid,
+        Opcode::Nop(_) => OpcodeKind::Nop,
+        Opcode::Add(_) => OpcodeKind::Add,
+        Opcode::Sub(_) => OpcodeKind::Sub,
+        Opcode::Mul(_) => OpcodeKind::Mul,
+        Opcode::Div(_) => OpcodeKind::Div,
+        Opcode::Jump(_) => OpcodeKind::Jump,
+        Opcode::Context(_) => OpcodeKind::Context,
+        Opcode::Shift(_) => OpcodeKind::Shift,
+        Opcode::Binop(_) => OpcodeKind::Binop,
+        Opcode::Ptr(_) => OpcodeKind::Ptr,
+        Opcode::NearCall(_) => OpcodeKind::NearCall,
+        Opcode::Log(_) => OpcodeKind::Log,
+        Opcode::FarCall(_) => OpcodeKind::FarCall,
+        Opcode::Ret(_) => OpcodeKind::Ret,
+        Opcode::UMA(_) => OpcodeKind::Uma,
+    }
+}
+
+impl<S, H: HistoryMode> DynTracer<S, SimpleMemory<H>> for ExternalTracerAdapter {
+    fn before_execution(
+        &mut self,
+        state: VmLocalStateData<'_>,
+        data: BeforeExecutionData,
+        _memory: &SimpleMemory<H>,
+        _storage: StoragePtr<S>,
+    ) {
+        let callstack = &state.vm_local_state.callstack;
+        let current = &callstack.current;
+        let opcode = data.opcode.variant.opcode;
+        self.with_tracer(|tracer| {
+            tracer.on_opcode(&OpcodeInfo {
+                kind: opcode_kind(opcode),
+                pc: current.pc,
+                address: current.this_address,
+                depth: callstack.depth(),
+                gas_remaining: current.ergs_remaining,
+            });
+
+            match opcode {
+                Opcode::Log(LogOpcode::StorageRead) => {
+                    tracer.on_storage_read(&StorageAccess {
+                        address: current.this_address,
+                        key: data.src0_value.value,
+                        value: None,
+                    });
+                }
+                Opcode::Log(LogOpcode::StorageWrite) => {
+                    tracer.on_storage_write(&StorageAccess {
+                        address: current.this_address,
+                        key: data.src0_value.value,
+                        value: Some(data.src1_value.value),
+                    });
+                }
+                // Near call frames are returned from using the same opcode.
+                Opcode::Ret(ret) if !current.is_local_frame => {
+                    let kind = match ret {
+                        RetOpcode::Ok => ReturnKind::Ok,
+                        RetOpcode::Revert => ReturnKind::Revert,
+                        RetOpcode::Panic => ReturnKind::Panic,
+                    };
+                    tracer.on_return(&ReturnInfo {
+                        kind,
+                        address: current.this_address,
+                        depth: callstack.depth(),
+                        gas_remaining: current.ergs_remaining,
+                    });
+                }
+                _ => {}
+            }
+        });
+    }
+
+    fn after_execution(
+        &mut self,
+        state: VmLocalStateData<'_>,
+        data: AfterExecutionData,
+        _memory: &SimpleMemory<H>,
+        _storage: StoragePtr<S>,
+    ) {
+        let Opcode::FarCall(far_call) = data.opcode.variant.opcode else {
+            return;
+        };
+        let callstack = &state.vm_local_state.callstack;
+        // After executing a far call, the current frame is the callee frame.
+        let current = &callstack.current;
+        let caller_address = callstack.inner.last().map(|frame| frame.this_address);
+
+        // Deployments are performed by the deployer system contract mimicking a call from the deploying account
+        // to the constructor.
+        if matches!(far_call, FarCallOpcode::Mimic)
+            && caller_address == Some(CONTRACT_DEPLOYER_ADDRESS)
+        {
+            self.with_tracer(|tracer| {
+                tracer.on_create(&CreateInfo {
+                    deployer: current.msg_sender,
+                    address: current.this_address,
+                    depth: callstack.depth(),
+                    gas: current.ergs_remaining,
+                });
+            });
+        } else {
+            let kind = match far_call {
+                FarCallOpcode::Normal => CallKind::Normal,
+                FarCallOpcode::Delegate => CallKind::Delegate,
+                FarCallOpcode::Mimic => CallKind::Mimic,
+            };
+            self.with_tracer(|tracer| {
+                tracer.on_call(&CallInfo {
+                    kind,
+                    caller: current.msg_sender,
+                    callee: current.this_address,
+                    code_address: current.code_address,
+                    depth: callstack.depth(),
+                    gas: current.ergs_remaining,
+                });
+            });
+        }
+    }
+}
+
+impl<S: WriteStorage, H: HistoryMode> VmTracer<S, H> for ExternalTracerAdapter {
+    fn after_vm_execution(
+        &mut self,
+        _state: &mut ZkSyncVmState<S, H>,
+        _bootloader_state: &BootloaderState,
+        _stop_reason: VmExecutionStopReason,
+    ) {
+        self.with_tracer(|tracer| tracer.on_execution_end());
+    }
+}
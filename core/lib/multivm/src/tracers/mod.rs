@@ -1,9 +1,13 @@
 pub mod call_tracer;
+pub mod external;
 mod multivm_dispatcher;
 pub mod old_tracers;
 pub mod storage_invocation;
+pub mod struct_logger;
 pub mod validator;
 
 pub use call_tracer::CallTracer;
+pub use external::{ExternalTracer, ExternalTracerAdapter};
 pub use multivm_dispatcher::TracerDispatcher;
 pub use storage_invocation::StorageInvocations;
+pub use struct_logger::StructLogTracer;
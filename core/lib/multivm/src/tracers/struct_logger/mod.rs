@@ -0,0 +1,249 @@
+//! Tracer producing opcode-level logs in the geth-compatible `structLogs` format.
+//!
+//! EraVM differs from EVM in many aspects, so the logs are an approximation of what geth would produce:
+//!
+//! - Only opcodes executed by non-system contracts are logged; the bootloader and system contracts
+//!   (i.e., contracts in the kernel address space) are skipped.
+//! - `pc` is the EraVM program counter, and `op` is the EraVM opcode name; opcodes having EVM counterparts
+//!   are named after them (e.g., `SLOAD` or `CALL`).
+//! - `depth` is the depth of far calls starting from 1 for the first logged frame.
+//! - `stack` is the EraVM stack of the current frame, and `memory` is its heap.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    ops,
+    sync::Arc,
+};
+
+use once_cell::sync::OnceCell;
+use zksync_types::{
+    api::{StructLog, StructLoggerConfig},
+    Address, H256, U256,
+};
+use zksync_utils::u256_to_h256;
+
+use crate::glue::tracers::IntoOldVmTracer;
+
+pub mod vm_boojum_integration;
+pub mod vm_latest;
+pub mod vm_refunds_enhancement;
+pub mod vm_virtual_blocks;
+
+/// Maximum number of logs captured by the tracer regardless of the config.
+pub const MAX_STRUCT_LOGS: usize = 100_000;
+/// Maximum number of 32-byte words in a stack or memory snapshot.
+const MAX_SNAPSHOT_WORDS: u32 = 1_024;
+
+#[derive(Debug, Clone)]
+pub struct StructLogTracer {
+    config: StructLoggerConfig,
+    logs: Vec<StructLog>,
+    /// Storage slots accessed so far, grouped by the contract address.
+    storage: HashMap<Address, BTreeMap<H256, H256>>,
+    /// Far call depth of the first logged frame.
+    base_depth: Option<usize>,
+    /// Storage read for which the value should be recorded after executing the opcode.
+    pending_read: Option<(Address, H256)>,
+    result: Arc<OnceCell<Vec<StructLog>>>,
+}
+
+impl StructLogTracer {
+    pub fn new(config: StructLoggerConfig, result: Arc<OnceCell<Vec<StructLog>>>) -> Self {
+        Self {
+            config,
+            logs: vec![],
+            storage: HashMap::new(),
+            base_depth: None,
+            pending_read: None,
+            result,
+        }
+    }
+
+    fn limit(&self) -> usize {
+        if self.config.limit == 0 {
+            MAX_STRUCT_LOGS
+        } else {
+            self.config.limit.min(MAX_STRUCT_LOGS)
+        }
+    }
+
+    /// Checks whether an opcode executed by the specified code should be logged.
+    fn should_log(&self, code_address: Address) -> bool {
+        let is_kernel_address = code_address.as_bytes()[..18].iter().all(|&byte| byte == 0);
+        !is_kernel_address && self.logs.len() < self.limit()
+    }
+
+    /// Returns the range of stack words to capture; if the stack is too large, only its top is captured.
+    fn stack_range(&self, sp: u32) -> Option<ops::Range<u32>> {
+        (!self.config.disable_stack).then(|| sp.saturating_sub(MAX_SNAPSHOT_WORDS)..sp)
+    }
+
+    fn memory_words(&self, heap_bound: u32) -> Option<u32> {
+        let words = heap_bound / 32 + u32::from(heap_bound % 32 != 0);
+        self.config
+            .enable_memory
+            .then(|| words.min(MAX_SNAPSHOT_WORDS))
+    }
+
+    fn push_log(&mut self, log: RawStructLog) {
+        let base_depth = *self.base_depth.get_or_insert(log.far_call_depth);
+        let depth = (log.far_call_depth + 1).saturating_sub(base_depth) as u64;
+        let gas = u64::from(log.gas);
+
+        // The cost of an opcode is only known once the next opcode is executed in the same frame.
+        if let Some(prev_log) = self.logs.last_mut() {
+            if prev_log.depth == depth {
+                prev_log.gas_cost = prev_log.gas.saturating_sub(gas);
+            }
+        }
+
+        let storage = match log.storage_access {
+            Some((address, key, value)) if !self.config.disable_storage => {
+                let contract_storage = self.storage.entry(address).or_default();
+                if let Some(value) = value {
+                    contract_storage.insert(key, value);
+                }
+                Some(contract_storage.clone())
+            }
+            _ => None,
+        };
+        let memory = log.memory.map(|words| {
+            words
+                .into_iter()
+                .map(|word| hex::encode(u256_to_h256(word)))
+                .collect()
+        });
+
+        self.logs.push(StructLog {
+            pc: log.pc.into(),
+            op: log.op,
+            gas,
+            gas_cost: 0,
+            depth,
+            stack: log.stack,
+            memory,
+            storage,
+        });
+    }
+
+    /// Records the value returned by a storage read logged in `before_execution()`.
+    fn record_read_value(&mut self, value: H256) {
+        let Some((address, key)) = self.pending_read.take() else {
+            return;
+        };
+        if self.config.disable_storage {
+            return;
+        }
+        let contract_storage = self.storage.entry(address).or_default();
+        contract_storage.insert(key, value);
+        if let Some(log) = self.logs.last_mut() {
+            log.storage = Some(contract_storage.clone());
+        }
+    }
+
+    fn store_result(&mut self) {
+        let logs = std::mem::take(&mut self.logs);
+        // The cell may be already set if the tracer was used for several executions; only the first one is kept.
+        self.result.set(logs).ok();
+    }
+}
+
+/// VM-agnostic data about an executed opcode.
+#[derive(Debug)]
+struct RawStructLog {
+    pc: u16,
+    op: String,
+    gas: u32,
+    far_call_depth: usize,
+    stack: Option<Vec<U256>>,
+    memory: Option<Vec<U256>>,
+    /// Address, key and written value (`None` for reads) for storage access opcodes.
+    storage_access: Option<(Address, H256, Option<H256>)>,
+}
+
+/// Converts the debug representation of an opcode (e.g., `Add(Add)`) into an uppercase name (`ADD`).
+fn generic_opcode_name(debug_repr: &str) -> String {
+    let name = debug_repr.split('(').next().unwrap_or(debug_repr);
+    name.to_uppercase()
+}
+
+impl IntoOldVmTracer for StructLogTracer {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn raw_log(op: &str, gas: u32, far_call_depth: usize) -> RawStructLog {
+        RawStructLog {
+            pc: 0,
+            op: op.to_owned(),
+            gas,
+            far_call_depth,
+            stack: None,
+            memory: None,
+            storage_access: None,
+        }
+    }
+
+    #[test]
+    fn depth_and_gas_cost() {
+        let mut tracer = StructLogTracer::new(StructLoggerConfig::default(), Arc::default());
+        tracer.push_log(raw_log("ADD", 100, 3));
+        tracer.push_log(raw_log("CALL", 94, 3));
+        tracer.push_log(raw_log("ADD", 50, 4));
+        tracer.push_log(raw_log("RETURN", 49, 4));
+        tracer.push_log(raw_log("ADD", 80, 3));
+
+        let depths: Vec<_> = tracer.logs.iter().map(|log| log.depth).collect();
+        assert_eq!(depths, [1, 1, 2, 2, 1]);
+        let costs: Vec<_> = tracer.logs.iter().map(|log| log.gas_cost).collect();
+        assert_eq!(costs, [6, 0, 1, 0, 0]);
+    }
+
+    #[test]
+    fn storage_snapshots() {
+        let mut tracer = StructLogTracer::new(StructLoggerConfig::default(), Arc::default());
+        let address = Address::repeat_byte(1);
+        let mut log = raw_log("SSTORE", 100, 1);
+        log.storage_access = Some((address, H256::zero(), Some(H256::repeat_byte(2))));
+        tracer.push_log(log);
+
+        let mut log = raw_log("SLOAD", 90, 1);
+        log.storage_access = Some((address, H256::repeat_byte(3), None));
+        tracer.push_log(log);
+        tracer.pending_read = Some((address, H256::repeat_byte(3)));
+        tracer.record_read_value(H256::from_low_u64_be(5));
+
+        let storage = tracer.logs[1].storage.as_ref().unwrap();
+        assert_eq!(storage.len(), 2);
+        assert_eq!(storage[&H256::zero()], H256::repeat_byte(2));
+        assert_eq!(storage[&H256::repeat_byte(3)], H256::from_low_u64_be(5));
+    }
+
+    #[test]
+    fn limits() {
+        let config = StructLoggerConfig {
+            limit: 1,
+            ..StructLoggerConfig::default()
+        };
+        let mut tracer = StructLogTracer::new(config, Arc::default());
+        let address = Address::repeat_byte(0x10);
+        assert!(tracer.should_log(address));
+        assert!(!tracer.should_log(Address::from_low_u64_be(0x8002)));
+        tracer.push_log(raw_log("ADD", 100, 1));
+        assert!(!tracer.should_log(address));
+
+        assert_eq!(tracer.stack_range(5), Some(0..5));
+        assert_eq!(
+            tracer.stack_range(5_000),
+            Some((5_000 - MAX_SNAPSHOT_WORDS)..5_000)
+        );
+        assert_eq!(tracer.memory_words(64), None);
+    }
+
+    #[test]
+    fn opcode_names() {
+        assert_eq!(generic_opcode_name("Add(Add)"), "ADD");
+        assert_eq!(generic_opcode_name("Nop"), "NOP");
+    }
+}
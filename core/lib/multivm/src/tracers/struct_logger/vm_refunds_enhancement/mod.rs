@@ -0,0 +1,114 @@
+use zk_evm_1_3_3::{
+    tracing::{AfterExecutionData, BeforeExecutionData, VmLocalStateData},
+    zkevm_opcode_defs::{FarCallOpcode, LogOpcode, Opcode, RetOpcode},
+};
+use zksync_state::{StoragePtr, WriteStorage};
+use zksync_types::{AccountTreeId, StorageKey};
+use zksync_utils::u256_to_h256;
+
+use crate::{
+    interface::{tracer::VmExecutionStopReason, traits::tracers::dyn_tracers::vm_1_3_3::DynTracer},
+    tracers::struct_logger::{generic_opcode_name, RawStructLog, StructLogTracer},
+    vm_refunds_enhancement::{BootloaderState, HistoryMode, SimpleMemory, VmTracer, ZkSyncVmState},
+};
+
+fn opcode_name(opcode: Opcode) -> String {
+    let name = match opcode {
+        Opcode::FarCall(FarCallOpcode::Normal) => "CALL",
+        Opcode::FarCall(FarCallOpcode::Delegate) => "DELEGATECALL",
+        Opcode::FarCall(FarCallOpcode::Mimic) => "MIMICCALL",
+        Opcode::Ret(RetOpcode::Ok) => "RETURN",
+        Opcode::Ret(RetOpcode::Revert) => "REVERT",
+        Opcode::Ret(RetOpcode::Panic) => "PANIC",
+        Opcode::Log(LogOpcode::StorageRead) => "SLOAD",
+        Opcode::Log(LogOpcode::StorageWrite) => "SSTORE",
+        Opcode::Log(LogOpcode::Event) => "LOG",
+        Opcode::Log(LogOpcode::ToL1Message) => "L2_TO_L1_LOG",
+        Opcode::Log(LogOpcode::PrecompileCall) => "PRECOMPILE_CALL",
+        Opcode::NearCall(_) => "NEAR_CALL",
+        other => return generic_opcode_name(&format!("{other:?}")),
+    };
+    name.to_owned()
+}
+
+impl<S: WriteStorage, H: HistoryMode> DynTracer<S, SimpleMemory<H>> for StructLogTracer {
+    fn before_execution(
+        &mut self,
+        state: VmLocalStateData<'_>,
+        data: BeforeExecutionData,
+        memory: &SimpleMemory<H>,
+        _storage: StoragePtr<S>,
+    ) {
+        let callstack = &state.vm_local_state.callstack;
+        let current = &callstack.current;
+        if !self.should_log(current.code_address) {
+            return;
+        }
+
+        let opcode = data.opcode.variant.opcode;
+        // Near call frames share the depth with the enclosing far call frame.
+        let far_call_depth = callstack
+            .inner
+            .iter()
+            .chain([current])
+            .filter(|frame| !frame.is_local_frame)
+            .count();
+        // The stack and heap pages of a frame directly follow its base page.
+        let base_page = current.base_memory_page.0;
+        let stack = self
+            .stack_range(u32::from(current.sp))
+            .map(|range| memory.dump_page_content_as_u256_words(base_page + 1, range));
+        let memory = self
+            .memory_words(current.heap_bound)
+            .map(|words| memory.dump_page_content_as_u256_words(base_page + 2, 0..words));
+
+        let key = u256_to_h256(data.src0_value.value);
+        let storage_access = match opcode {
+            Opcode::Log(LogOpcode::StorageRead) => {
+                self.pending_read = Some((current.this_address, key));
+                Some((current.this_address, key, None))
+            }
+            Opcode::Log(LogOpcode::StorageWrite) => {
+                let value = u256_to_h256(data.src1_value.value);
+                Some((current.this_address, key, Some(value)))
+            }
+            _ => None,
+        };
+
+        self.push_log(RawStructLog {
+            pc: current.pc,
+            op: opcode_name(opcode),
+            gas: current.ergs_remaining,
+            far_call_depth,
+            stack,
+            memory,
+            storage_access,
+        });
+    }
+
+    fn after_execution(
+        &mut self,
+        _state: VmLocalStateData<'_>,
+        _data: AfterExecutionData,
+        _memory: &SimpleMemory<H>,
+        storage: StoragePtr<S>,
+    ) {
+        let Some((address, key)) = self.pending_read else {
+            return;
+        };
+        let key = StorageKey::new(AccountTreeId::new(address), key);
+        let value = storage.borrow_mut().read_value(&key);
+        self.record_read_value(value);
+    }
+}
+
+impl<S: WriteStorage, H: HistoryMode> VmTracer<S, H> for StructLogTracer {
+    fn after_vm_execution(
+        &mut self,
+        _state: &mut ZkSyncVmState<S, H>,
+        _bootloader_state: &BootloaderState,
+        _stop_reason: VmExecutionStopReason,
+    ) {
+        self.store_result();
+    }
+}
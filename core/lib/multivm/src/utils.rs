@@ -266,3 +266,21 @@ pub fn get_used_bootloader_memory_words(version: VmVersion) -> usize {
         VmVersion::Vm1_4_1 => crate::vm_latest::constants::USED_BOOTLOADER_MEMORY_WORDS,
     }
 }
+
+pub fn get_batch_gas_limit(version: VmVersion) -> u32 {
+    match version {
+        VmVersion::M5WithRefunds | VmVersion::M5WithoutRefunds => {
+            crate::vm_m5::utils::BLOCK_GAS_LIMIT
+        }
+        VmVersion::M6Initial | VmVersion::M6BugWithCompressionFixed => {
+            crate::vm_m6::utils::BLOCK_GAS_LIMIT
+        }
+        VmVersion::Vm1_3_2 => crate::vm_1_3_2::utils::BLOCK_GAS_LIMIT,
+        VmVersion::VmVirtualBlocks => crate::vm_virtual_blocks::constants::BLOCK_GAS_LIMIT,
+        VmVersion::VmVirtualBlocksRefundsEnhancement => {
+            crate::vm_refunds_enhancement::constants::BLOCK_GAS_LIMIT
+        }
+        VmVersion::VmBoojumIntegration => crate::vm_boojum_integration::constants::BLOCK_GAS_LIMIT,
+        VmVersion::Vm1_4_1 => crate::vm_latest::constants::BLOCK_GAS_LIMIT,
+    }
+}
@@ -0,0 +1,103 @@
+use std::sync::{Arc, Mutex};
+
+use zksync_types::{Address, Execute, U256};
+
+use crate::{
+    interface::{TxExecutionMode, VmExecutionMode, VmInterface},
+    tracers::external::{
+        CallInfo, ExternalTracer, ExternalTracerAdapter, OpcodeInfo, ReturnInfo, ReturnKind,
+        StorageAccess,
+    },
+    vm_latest::{
+        constants::BLOCK_GAS_LIMIT,
+        tests::{tester::VmTesterBuilder, utils::read_test_contract},
+        HistoryEnabled, ToTracerPointer,
+    },
+};
+
+#[derive(Debug, Default)]
+struct TracedEvents {
+    opcode_count: usize,
+    calls: Vec<CallInfo>,
+    returns: Vec<ReturnInfo>,
+    storage_writes: Vec<StorageAccess>,
+    finished: bool,
+}
+
+#[derive(Debug)]
+struct RecordingTracer(Arc<Mutex<TracedEvents>>);
+
+impl ExternalTracer for RecordingTracer {
+    fn on_opcode(&mut self, _opcode: &OpcodeInfo) {
+        self.0.lock().unwrap().opcode_count += 1;
+    }
+
+    fn on_call(&mut self, call: &CallInfo) {
+        self.0.lock().unwrap().calls.push(*call);
+    }
+
+    fn on_return(&mut self, ret: &ReturnInfo) {
+        self.0.lock().unwrap().returns.push(*ret);
+    }
+
+    fn on_storage_write(&mut self, access: &StorageAccess) {
+        self.0.lock().unwrap().storage_writes.push(*access);
+    }
+
+    fn on_execution_end(&mut self) {
+        self.0.lock().unwrap().finished = true;
+    }
+}
+
+#[test]
+fn external_tracer_receives_hooks() {
+    let contract = read_test_contract();
+    let address = Address::random();
+    let mut vm = VmTesterBuilder::new(HistoryEnabled)
+        .with_empty_in_memory_storage()
+        .with_random_rich_accounts(1)
+        .with_deployer()
+        .with_gas_limit(BLOCK_GAS_LIMIT)
+        .with_execution_mode(TxExecutionMode::VerifyExecute)
+        .with_custom_contracts(vec![(contract, address, true)])
+        .build();
+
+    let increment_by_6_calldata =
+        "7cf5dab00000000000000000000000000000000000000000000000000000000000000006";
+    let account = &mut vm.rich_accounts[0];
+    let tx = account.get_l2_tx_for_execute(
+        Execute {
+            contract_address: address,
+            calldata: hex::decode(increment_by_6_calldata).unwrap(),
+            value: Default::default(),
+            factory_deps: None,
+        },
+        None,
+    );
+
+    let events = Arc::new(Mutex::default());
+    let tracer = ExternalTracerAdapter::new(Box::new(RecordingTracer(events.clone())));
+    vm.vm.push_transaction(tx);
+    let res = vm
+        .vm
+        .inspect(tracer.into_tracer_pointer().into(), VmExecutionMode::OneTx);
+    assert!(!res.result.is_failed());
+
+    let events = events.lock().unwrap();
+    assert!(events.finished);
+    assert!(events.opcode_count > 0);
+    let counter_call = events
+        .calls
+        .iter()
+        .find(|call| call.callee == address)
+        .expect("no call to the counter contract");
+    assert_eq!(counter_call.code_address, address);
+    assert!(events
+        .returns
+        .iter()
+        .any(|ret| ret.address == address && ret.kind == ReturnKind::Ok));
+    assert!(events
+        .storage_writes
+        .iter()
+        .any(|write| write.address == address && write.value == Some(U256::from(6))));
+}
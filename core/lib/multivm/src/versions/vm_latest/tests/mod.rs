@@ -6,6 +6,7 @@ mod block_tip;
 mod bytecode_publishing;
 mod call_tracer;
 mod circuits;
+mod external_tracer;
 mod gas_limit;
 mod get_used_contracts;
 mod is_write_initial;
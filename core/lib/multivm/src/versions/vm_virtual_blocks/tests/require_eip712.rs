@@ -1,20 +1,22 @@
 use std::convert::TryInto;
 
 use ethabi::Token;
-
-use zksync_eth_signer::raw_ethereum_tx::TransactionParameters;
-use zksync_eth_signer::EthereumSigner;
+use zksync_eth_signer::{raw_ethereum_tx::TransactionParameters, EthereumSigner};
 use zksync_system_constants::L2_ETH_TOKEN_ADDRESS;
-use zksync_types::fee::Fee;
-use zksync_types::l2::L2Tx;
-use zksync_types::transaction_request::TransactionRequest;
-use zksync_types::utils::storage_key_for_standard_token_balance;
-use zksync_types::{AccountTreeId, Address, Eip712Domain, Execute, Nonce, Transaction, U256};
-
-use crate::interface::{TxExecutionMode, VmExecutionMode, VmInterface};
-use crate::vm_latest::HistoryDisabled;
-use crate::vm_virtual_blocks::tests::tester::{Account, VmTester, VmTesterBuilder};
-use crate::vm_virtual_blocks::tests::utils::read_many_owners_custom_account_contract;
+use zksync_types::{
+    fee::Fee, l2::L2Tx, transaction_request::TransactionRequest,
+    utils::storage_key_for_standard_token_balance, AccountTreeId, Address, Eip712Domain, Execute,
+    Nonce, Transaction, U256,
+};
+
+use crate::{
+    interface::{TxExecutionMode, VmExecutionMode, VmInterface},
+    vm_latest::HistoryDisabled,
+    vm_virtual_blocks::tests::{
+        tester::{Account, VmTester, VmTesterBuilder},
+        utils::read_many_owners_custom_account_contract,
+    },
+};
 
 impl VmTester<HistoryDisabled> {
     pub(crate) fn get_eth_balance(&mut self, address: Address) -> U256 {
@@ -89,6 +91,8 @@ async fn test_require_eip712() {
         access_list: None,
         max_fee_per_gas: U256::from(1000000000),
         max_priority_fee_per_gas: U256::from(1000000000),
+        max_fee_per_blob_gas: None,
+        blob_tx_sidecar: None,
     };
 
     let aa_tx = private_account.sign_legacy_tx(aa_raw_tx).await;
@@ -0,0 +1,226 @@
+//! Azure Blob Storage-based [`ObjectStore`] implementation.
+
+use std::fmt;
+
+use async_trait::async_trait;
+use azure_core::{error::ErrorKind, RetryOptions, StatusCode};
+use azure_storage::{ConnectionString, StorageCredentials};
+use azure_storage_blobs::prelude::{BlobClient, ClientBuilder, ContainerClient};
+
+use crate::{
+    metrics::OBJECT_STORE_METRICS,
+    raw::{Bucket, ObjectStore, ObjectStoreError},
+};
+
+#[derive(Clone)]
+pub enum AzureBlobStorageAuthMode {
+    /// Authenticate using a managed identity, or other credentials from the default Azure credential chain
+    /// (e.g., environment variables or the Azure CLI).
+    ManagedIdentity { account_name: String },
+    /// Authenticate using a storage account connection string.
+    ConnectionString(String),
+}
+
+impl fmt::Debug for AzureBlobStorageAuthMode {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ManagedIdentity { account_name } => formatter
+                .debug_struct("ManagedIdentity")
+                .field("account_name", account_name)
+                .finish(),
+            // The connection string contains the account key, so it's not output.
+            Self::ConnectionString(_) => formatter.debug_tuple("ConnectionString").finish(),
+        }
+    }
+}
+
+/// Object store backed by a container in Azure Blob Storage. Objects are stored as block blobs
+/// named `{bucket}/{key}`.
+pub struct AzureBlobStorage {
+    account_name: String,
+    container_name: String,
+    client: ContainerClient,
+}
+
+impl fmt::Debug for AzureBlobStorage {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("AzureBlobStorage")
+            .field("account_name", &self.account_name)
+            .field("container_name", &self.container_name)
+            .finish_non_exhaustive()
+    }
+}
+
+impl AzureBlobStorage {
+    /// Creates a new store. Retries in the Azure SDK are disabled; retries are performed by the wrapping store
+    /// created by [`ObjectStoreFactory`](crate::ObjectStoreFactory).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the credentials cannot be initialized, e.g. if the connection string is malformed.
+    pub fn new(auth_mode: AzureBlobStorageAuthMode, container_name: String) -> Self {
+        let (account_name, credentials) = match auth_mode {
+            AzureBlobStorageAuthMode::ManagedIdentity { account_name } => {
+                let credential = azure_identity::create_default_credential()
+                    .expect("failed initializing Azure credentials");
+                (
+                    account_name,
+                    StorageCredentials::token_credential(credential),
+                )
+            }
+            AzureBlobStorageAuthMode::ConnectionString(connection_string) => {
+                let connection_string = ConnectionString::new(&connection_string)
+                    .expect("failed parsing Azure storage connection string");
+                let account_name = connection_string
+                    .account_name
+                    .expect("Azure storage connection string doesn't specify account name")
+                    .to_owned();
+                let credentials = connection_string
+                    .storage_credentials()
+                    .expect("failed getting credentials from Azure storage connection string");
+                (account_name, credentials)
+            }
+        };
+
+        let client = ClientBuilder::new(account_name.clone(), credentials)
+            .retry(RetryOptions::none())
+            .container_client(container_name.clone());
+        Self {
+            account_name,
+            container_name,
+            client,
+        }
+    }
+
+    fn blob_client(&self, bucket: Bucket, key: &str) -> BlobClient {
+        self.client.blob_client(format!("{bucket}/{key}"))
+    }
+}
+
+impl From<azure_core::Error> for ObjectStoreError {
+    fn from(err: azure_core::Error) -> Self {
+        let is_not_found = matches!(
+            err.kind(),
+            ErrorKind::HttpResponse {
+                status: StatusCode::NotFound,
+                ..
+            }
+        );
+
+        if is_not_found {
+            ObjectStoreError::KeyNotFound(err.into())
+        } else {
+            ObjectStoreError::Other(err.into())
+        }
+    }
+}
+
+#[async_trait]
+impl ObjectStore for AzureBlobStorage {
+    async fn get_raw(&self, bucket: Bucket, key: &str) -> Result<Vec<u8>, ObjectStoreError> {
+        let fetch_latency = OBJECT_STORE_METRICS.start_fetch(bucket);
+        tracing::trace!(
+            "Fetching data from Azure for key {key} from bucket {bucket} in container {}",
+            self.container_name
+        );
+
+        let blob = self.blob_client(bucket, key).get_content().await;
+
+        let elapsed = fetch_latency.observe();
+        tracing::trace!(
+            "Fetched data from Azure for key {key} from bucket {bucket} and it took: {elapsed:?}"
+        );
+        blob.map_err(ObjectStoreError::from)
+    }
+
+    async fn put_raw(
+        &self,
+        bucket: Bucket,
+        key: &str,
+        value: Vec<u8>,
+    ) -> Result<(), ObjectStoreError> {
+        let store_latency = OBJECT_STORE_METRICS.start_store(bucket);
+        tracing::trace!(
+            "Storing data to Azure for key {key} from bucket {bucket} in container {}",
+            self.container_name
+        );
+
+        let response = self.blob_client(bucket, key).put_block_blob(value).await;
+
+        let elapsed = store_latency.observe();
+        tracing::trace!(
+            "Stored data to Azure for key {key} from bucket {bucket} and it took: {elapsed:?}"
+        );
+        response.map(drop).map_err(ObjectStoreError::from)
+    }
+
+    async fn remove_raw(&self, bucket: Bucket, key: &str) -> Result<(), ObjectStoreError> {
+        tracing::trace!(
+            "Removing data from Azure for key {key} from bucket {bucket} in container {}",
+            self.container_name
+        );
+        self.blob_client(bucket, key)
+            .delete()
+            .await
+            .map(drop)
+            .map_err(ObjectStoreError::from)
+    }
+
+    fn storage_prefix_raw(&self, bucket: Bucket) -> String {
+        format!(
+            "https://{}.blob.core.windows.net/{}/{}",
+            self.account_name,
+            self.container_name,
+            bucket.as_str()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONNECTION_STRING: &str = "DefaultEndpointsProtocol=https;AccountName=zksync;\
+        AccountKey=dGVzdA==;EndpointSuffix=core.windows.net";
+
+    #[test]
+    fn creating_store_from_connection_string() {
+        let auth_mode = AzureBlobStorageAuthMode::ConnectionString(CONNECTION_STRING.to_owned());
+        assert_eq!(format!("{auth_mode:?}"), "ConnectionString");
+
+        let store = AzureBlobStorage::new(auth_mode, "artifacts".to_owned());
+        assert_eq!(store.account_name, "zksync");
+        assert_eq!(
+            store.storage_prefix_raw(Bucket::ProofsFri),
+            "https://zksync.blob.core.windows.net/artifacts/proofs_fri"
+        );
+    }
+
+    #[test]
+    fn converting_azure_errors() {
+        let not_found = azure_core::Error::message(
+            ErrorKind::HttpResponse {
+                status: StatusCode::NotFound,
+                error_code: Some("BlobNotFound".to_owned()),
+            },
+            "blob not found",
+        );
+        assert!(matches!(
+            ObjectStoreError::from(not_found),
+            ObjectStoreError::KeyNotFound(_)
+        ));
+
+        let forbidden = azure_core::Error::message(
+            ErrorKind::HttpResponse {
+                status: StatusCode::Forbidden,
+                error_code: None,
+            },
+            "forbidden",
+        );
+        assert!(matches!(
+            ObjectStoreError::from(forbidden),
+            ObjectStoreError::Other(_)
+        ));
+    }
+}
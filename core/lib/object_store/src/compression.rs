@@ -0,0 +1,221 @@
+//! Transparent client-side compression of blobs.
+
+use std::io::Read;
+
+use async_trait::async_trait;
+use tokio::task;
+
+use crate::{
+    metrics::OBJECT_STORE_METRICS,
+    raw::{Bucket, ObjectStore, ObjectStoreError},
+};
+
+/// Magic number starting each Zstandard frame.
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+/// Default maximum length of a decompressed blob: 4 GiB. Protects against decompression bombs
+/// exhausting the process memory.
+const DEFAULT_MAX_DECOMPRESSED_LEN: u64 = 4 << 30;
+
+/// Decompresses `blob` if it is compressed with Zstandard; otherwise, returns it as is. Previously stored
+/// uncompressed blobs are thus readable, since they are extremely unlikely to start with the Zstandard magic number.
+///
+/// # Errors
+///
+/// Returns an error if a blob with the Zstandard magic number cannot be decompressed, or if the decompressed blob
+/// is longer than `max_len` bytes.
+fn decompress(blob: Vec<u8>, max_len: u64) -> Result<Vec<u8>, ObjectStoreError> {
+    if !blob.starts_with(&ZSTD_MAGIC) {
+        return Ok(blob);
+    }
+
+    let decompression_error = |err: std::io::Error| {
+        ObjectStoreError::Serialization(format!("failed decompressing blob: {err}").into())
+    };
+    let decoder =
+        zstd::stream::read::Decoder::with_buffer(blob.as_slice()).map_err(decompression_error)?;
+    let mut decompressed = vec![];
+    // Read at most 1 byte over the limit to detect oversized blobs without decompressing them fully.
+    decoder
+        .take(max_len + 1)
+        .read_to_end(&mut decompressed)
+        .map_err(decompression_error)?;
+    if decompressed.len() as u64 > max_len {
+        let message = format!("decompressed blob exceeds the maximum length of {max_len} bytes");
+        return Err(ObjectStoreError::Serialization(message.into()));
+    }
+    Ok(decompressed)
+}
+
+/// Compresses `blob` using Zstandard. Returns the blob as is if compression doesn't reduce its size,
+/// e.g. if the blob is already compressed with another codec.
+fn compress(blob: Vec<u8>, level: i32) -> Vec<u8> {
+    match zstd::encode_all(blob.as_slice(), level) {
+        Ok(compressed) if compressed.len() < blob.len() => compressed,
+        Ok(_) => blob,
+        Err(err) => {
+            tracing::warn!("Failed compressing blob, storing it uncompressed: {err}");
+            blob
+        }
+    }
+}
+
+async fn run_blocking<T: Send + 'static>(
+    f: impl FnOnce() -> T + Send + 'static,
+) -> Result<T, ObjectStoreError> {
+    task::spawn_blocking(f)
+        .await
+        .map_err(|err| ObjectStoreError::Other(err.into()))
+}
+
+/// [`ObjectStore`] wrapper that compresses blobs with Zstandard on write and transparently decompresses
+/// them on read. Uncompressed blobs (e.g., ones stored before compression was enabled) are returned as is.
+#[derive(Debug)]
+pub(crate) struct CompressingObjectStore<S> {
+    inner: S,
+    level: Option<i32>,
+    max_decompressed_len: u64,
+}
+
+impl<S: ObjectStore> CompressingObjectStore<S> {
+    /// Creates a store wrapper. If `level` is `None`, blobs are not compressed on write, but are still
+    /// decompressed on read.
+    pub fn new(inner: S, level: Option<i32>) -> Self {
+        Self {
+            inner,
+            level,
+            max_decompressed_len: DEFAULT_MAX_DECOMPRESSED_LEN,
+        }
+    }
+}
+
+#[async_trait]
+impl<S: ObjectStore> ObjectStore for CompressingObjectStore<S> {
+    async fn get_raw(&self, bucket: Bucket, key: &str) -> Result<Vec<u8>, ObjectStoreError> {
+        let blob = self.inner.get_raw(bucket, key).await?;
+        if !blob.starts_with(&ZSTD_MAGIC) {
+            return Ok(blob); // Fast path for uncompressed blobs
+        }
+        let max_len = self.max_decompressed_len;
+        run_blocking(move || decompress(blob, max_len)).await?
+    }
+
+    async fn put_raw(
+        &self,
+        bucket: Bucket,
+        key: &str,
+        value: Vec<u8>,
+    ) -> Result<(), ObjectStoreError> {
+        let value = if let Some(level) = self.level {
+            let uncompressed_len = value.len();
+            let compressed = run_blocking(move || compress(value, level)).await?;
+            OBJECT_STORE_METRICS.report_compression(bucket, uncompressed_len, compressed.len());
+            compressed
+        } else {
+            value
+        };
+        self.inner.put_raw(bucket, key, value).await
+    }
+
+    async fn remove_raw(&self, bucket: Bucket, key: &str) -> Result<(), ObjectStoreError> {
+        self.inner.remove_raw(bucket, key).await
+    }
+
+    fn storage_prefix_raw(&self, bucket: Bucket) -> String {
+        self.inner.storage_prefix_raw(bucket)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockStore;
+
+    #[test]
+    fn compression_roundtrip() {
+        let blob = vec![42_u8; 10_000];
+        let compressed = compress(blob.clone(), 3);
+        assert!(compressed.starts_with(&ZSTD_MAGIC));
+        assert!(compressed.len() < 100, "{}", compressed.len());
+        assert_eq!(decompress(compressed, 10_000).unwrap(), blob);
+    }
+
+    #[test]
+    fn decompressed_length_is_bounded() {
+        let blob = vec![42_u8; 10_000];
+        let compressed = compress(blob, 3);
+        let err = decompress(compressed, 9_999).unwrap_err();
+        assert!(matches!(err, ObjectStoreError::Serialization(_)), "{err}");
+        assert!(err.to_string().contains("maximum length"), "{err}");
+    }
+
+    #[test]
+    fn incompressible_blobs_are_stored_as_is() {
+        let blob: Vec<_> = (0..=255_u8).collect();
+        assert_eq!(compress(blob.clone(), 3), blob);
+    }
+
+    #[test]
+    fn uncompressed_blobs_are_returned_as_is() {
+        let blob = b"test".to_vec();
+        assert_eq!(decompress(blob.clone(), 0).unwrap(), blob);
+    }
+
+    #[test]
+    fn malformed_compressed_blobs_are_rejected() {
+        let mut blob_with_magic = ZSTD_MAGIC.to_vec();
+        blob_with_magic.extend_from_slice(b"not actually compressed");
+        let err = decompress(blob_with_magic, DEFAULT_MAX_DECOMPRESSED_LEN).unwrap_err();
+        assert!(matches!(err, ObjectStoreError::Serialization(_)), "{err}");
+    }
+
+    #[tokio::test]
+    async fn compressing_store() {
+        let store = CompressingObjectStore::new(MockStore::default(), Some(3));
+        let blob = vec![1_u8; 1_000];
+        store
+            .put_raw(Bucket::ProofsFri, "compressed", blob.clone())
+            .await
+            .unwrap();
+        let stored = store
+            .inner
+            .get_raw(Bucket::ProofsFri, "compressed")
+            .await
+            .unwrap();
+        assert!(stored.len() < blob.len());
+        let fetched = store
+            .get_raw(Bucket::ProofsFri, "compressed")
+            .await
+            .unwrap();
+        assert_eq!(fetched, blob);
+
+        // Blobs stored without compression must be readable.
+        store
+            .inner
+            .put_raw(Bucket::ProofsFri, "legacy", blob.clone())
+            .await
+            .unwrap();
+        let fetched = store.get_raw(Bucket::ProofsFri, "legacy").await.unwrap();
+        assert_eq!(fetched, blob);
+    }
+
+    #[tokio::test]
+    async fn compressing_store_with_bounded_decompressed_length() {
+        let store = CompressingObjectStore {
+            max_decompressed_len: 100,
+            ..CompressingObjectStore::new(MockStore::default(), Some(3))
+        };
+        store
+            .put_raw(Bucket::ProofsFri, "small", vec![1_u8; 100])
+            .await
+            .unwrap();
+        store
+            .put_raw(Bucket::ProofsFri, "large", vec![1_u8; 1_000])
+            .await
+            .unwrap();
+
+        let fetched = store.get_raw(Bucket::ProofsFri, "small").await.unwrap();
+        assert_eq!(fetched, [1_u8; 100]);
+        let err = store.get_raw(Bucket::ProofsFri, "large").await.unwrap_err();
+        assert!(matches!(err, ObjectStoreError::Serialization(_)), "{err}");
+    }
+}
@@ -0,0 +1,611 @@
+//! Client-side envelope encryption of blobs.
+//!
+//! Blobs are encrypted with AES-256-GCM using a *data key*, which is itself encrypted (*wrapped*) by a key
+//! management service (KMS) and stored alongside the blob. The object store never sees plaintext data keys.
+//! To limit the number of KMS requests, a data key is reused for encryption during [`DATA_KEY_LIFETIME`],
+//! and unwrapped data keys are cached.
+//!
+//! Encrypted blobs have the following layout:
+//!
+//! ```text
+//! magic (8 bytes) | wrapped data key length (u16, big-endian) | wrapped data key | nonce (12 bytes) | ciphertext
+//! ```
+//!
+//! The full object path (`{bucket}/{key}`) is used as associated data, so an encrypted blob cannot be
+//! substituted for a blob at another path.
+
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use aes_gcm::{
+    aead::{Aead, AeadCore, KeyInit, OsRng, Payload},
+    Aes256Gcm, Key, Nonce,
+};
+use async_trait::async_trait;
+use aws_config::{BehaviorVersion, Region};
+use aws_sdk_kms::{primitives::Blob, types::DataKeySpec, Client as KmsClient};
+
+use crate::raw::{Bucket, ObjectStore, ObjectStoreError};
+
+/// Magic bytes starting each encrypted blob. Chosen so that they cannot start a bincode-serialized length prefix
+/// of a realistic value.
+const MAGIC: [u8; 8] = [0xe7, b'z', b'k', b'e', b'n', b'c', 0x00, 0x01];
+const NONCE_LEN: usize = 12;
+/// Period during which a single data key is used to encrypt blobs.
+const DATA_KEY_LIFETIME: Duration = Duration::from_secs(3_600);
+/// Maximum number of unwrapped data keys cached in memory.
+const MAX_CACHED_DATA_KEYS: usize = 64;
+
+/// Plaintext data key together with its wrapped form.
+#[derive(Clone)]
+pub(crate) struct DataKey {
+    plaintext: [u8; 32],
+    wrapped: Vec<u8>,
+}
+
+impl fmt::Debug for DataKey {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // The plaintext key is intentionally not output.
+        formatter
+            .debug_struct("DataKey")
+            .field("wrapped", &hex::encode(&self.wrapped))
+            .finish_non_exhaustive()
+    }
+}
+
+fn split_checked(bytes: &[u8], mid: usize) -> Option<(&[u8], &[u8])> {
+    (bytes.len() >= mid).then(|| bytes.split_at(mid))
+}
+
+fn plaintext_key(bytes: &[u8]) -> Result<[u8; 32], ObjectStoreError> {
+    bytes.try_into().map_err(|_| {
+        ObjectStoreError::Other(format!("unexpected data key length: {}", bytes.len()).into())
+    })
+}
+
+/// Key management service wrapping data keys.
+#[async_trait]
+pub(crate) trait KeyManagementService: 'static + fmt::Debug + Send + Sync {
+    /// Generates a new 256-bit data key.
+    async fn generate_data_key(&self) -> Result<DataKey, ObjectStoreError>;
+
+    /// Unwraps a data key previously generated by [`Self::generate_data_key()`].
+    async fn unwrap_data_key(&self, wrapped: &[u8]) -> Result<[u8; 32], ObjectStoreError>;
+}
+
+/// Local "KMS" wrapping data keys with a static key encryption key. Intended for testing and for deployments
+/// without access to a managed KMS.
+pub(crate) struct LocalKeyWrapper {
+    cipher: Aes256Gcm,
+}
+
+impl fmt::Debug for LocalKeyWrapper {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("LocalKeyWrapper")
+            .finish_non_exhaustive()
+    }
+}
+
+impl LocalKeyWrapper {
+    /// Creates a wrapper from a hex-encoded 256-bit key.
+    pub fn new(hex_key: &str) -> anyhow::Result<Self> {
+        let key = hex::decode(hex_key.strip_prefix("0x").unwrap_or(hex_key))
+            .map_err(|err| anyhow::anyhow!("encryption key is not a valid hex string: {err}"))?;
+        anyhow::ensure!(
+            key.len() == 32,
+            "encryption key must have 32 bytes, got {}",
+            key.len()
+        );
+        Ok(Self {
+            cipher: Aes256Gcm::new_from_slice(&key).expect("key length is checked"),
+        })
+    }
+}
+
+#[async_trait]
+impl KeyManagementService for LocalKeyWrapper {
+    async fn generate_data_key(&self) -> Result<DataKey, ObjectStoreError> {
+        let mut plaintext = [0_u8; 32];
+        plaintext.copy_from_slice(&Aes256Gcm::generate_key(OsRng));
+        let nonce = Aes256Gcm::generate_nonce(OsRng);
+        let encrypted = self
+            .cipher
+            .encrypt(&nonce, plaintext.as_slice())
+            .map_err(|err| {
+                ObjectStoreError::Other(format!("failed wrapping data key: {err}").into())
+            })?;
+        let mut wrapped = nonce.to_vec();
+        wrapped.extend_from_slice(&encrypted);
+        Ok(DataKey { plaintext, wrapped })
+    }
+
+    async fn unwrap_data_key(&self, wrapped: &[u8]) -> Result<[u8; 32], ObjectStoreError> {
+        if wrapped.len() < NONCE_LEN {
+            return Err(ObjectStoreError::Serialization(
+                "wrapped data key is too short".into(),
+            ));
+        }
+        let (nonce, encrypted) = wrapped.split_at(NONCE_LEN);
+        let plaintext = self
+            .cipher
+            .decrypt(Nonce::from_slice(nonce), encrypted)
+            .map_err(|err| {
+                ObjectStoreError::Serialization(format!("failed unwrapping data key: {err}").into())
+            })?;
+        plaintext_key(&plaintext)
+    }
+}
+
+/// AWS KMS wrapping data keys with a symmetric KMS key.
+#[derive(Debug)]
+pub(crate) struct AwsKms {
+    client: KmsClient,
+    key_id: String,
+}
+
+impl AwsKms {
+    /// Creates a KMS client. Credentials are taken from the default AWS credential chain.
+    pub async fn new(key_id: String, region: Option<String>) -> Self {
+        let mut loader = aws_config::defaults(BehaviorVersion::latest());
+        if let Some(region) = region {
+            loader = loader.region(Region::new(region));
+        }
+        let sdk_config = loader.load().await;
+        Self {
+            client: KmsClient::new(&sdk_config),
+            key_id,
+        }
+    }
+}
+
+#[async_trait]
+impl KeyManagementService for AwsKms {
+    async fn generate_data_key(&self) -> Result<DataKey, ObjectStoreError> {
+        let response = self
+            .client
+            .generate_data_key()
+            .key_id(&self.key_id)
+            .key_spec(DataKeySpec::Aes256)
+            .send()
+            .await
+            .map_err(|err| ObjectStoreError::Other(err.into()))?;
+        let (Some(plaintext), Some(wrapped)) = (response.plaintext(), response.ciphertext_blob())
+        else {
+            return Err(ObjectStoreError::Other(
+                "AWS KMS returned incomplete data key".into(),
+            ));
+        };
+        Ok(DataKey {
+            plaintext: plaintext_key(plaintext.as_ref())?,
+            wrapped: wrapped.as_ref().to_vec(),
+        })
+    }
+
+    async fn unwrap_data_key(&self, wrapped: &[u8]) -> Result<[u8; 32], ObjectStoreError> {
+        let response = self
+            .client
+            .decrypt()
+            .key_id(&self.key_id)
+            .ciphertext_blob(Blob::new(wrapped))
+            .send()
+            .await
+            .map_err(|err| ObjectStoreError::Other(err.into()))?;
+        let plaintext = response
+            .plaintext()
+            .ok_or_else(|| ObjectStoreError::Other("AWS KMS returned no plaintext key".into()))?;
+        plaintext_key(plaintext.as_ref())
+    }
+}
+
+#[derive(Default)]
+struct DataKeyCache {
+    current: Option<(DataKey, Instant)>,
+    unwrapped: HashMap<Vec<u8>, [u8; 32]>,
+}
+
+/// [`ObjectStore`] wrapper encrypting blobs in the specified buckets using envelope encryption.
+///
+/// Encrypted blobs are detected and decrypted on read regardless of the bucket; blobs without the encryption
+/// header are returned as is, so encryption can be enabled for a store already containing plaintext blobs.
+pub(crate) struct EncryptingObjectStore<S> {
+    inner: S,
+    kms: Box<dyn KeyManagementService>,
+    /// Buckets in which blobs are encrypted; `None` means all buckets.
+    buckets: Option<HashSet<Bucket>>,
+    /// Whether plaintext blobs in encrypted buckets are rejected on read.
+    strict: bool,
+    cache: Mutex<DataKeyCache>,
+}
+
+impl<S: fmt::Debug> fmt::Debug for EncryptingObjectStore<S> {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Cached data keys are intentionally not output.
+        formatter
+            .debug_struct("EncryptingObjectStore")
+            .field("inner", &self.inner)
+            .field("kms", &self.kms)
+            .field("buckets", &self.buckets)
+            .field("strict", &self.strict)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<S: ObjectStore> EncryptingObjectStore<S> {
+    pub fn new(
+        inner: S,
+        kms: Box<dyn KeyManagementService>,
+        buckets: Option<HashSet<Bucket>>,
+    ) -> Self {
+        Self {
+            inner,
+            kms,
+            buckets,
+            strict: false,
+            cache: Mutex::default(),
+        }
+    }
+
+    /// Enables or disables the strict mode, in which reading a plaintext blob from an encrypted bucket
+    /// results in an error. By default, plaintext blobs are returned as is, so that encryption can be enabled
+    /// for a store already containing plaintext blobs.
+    pub fn with_strict_mode(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    fn is_encrypted(&self, bucket: Bucket) -> bool {
+        self.buckets
+            .as_ref()
+            .map_or(true, |buckets| buckets.contains(&bucket))
+    }
+
+    async fn current_data_key(&self) -> Result<DataKey, ObjectStoreError> {
+        {
+            let cache = self.cache.lock().expect("data key cache is poisoned");
+            if let Some((key, created_at)) = &cache.current {
+                if created_at.elapsed() < DATA_KEY_LIFETIME {
+                    return Ok(key.clone());
+                }
+            }
+        }
+
+        let key = self.kms.generate_data_key().await?;
+        tracing::debug!("Generated new data key for object store encryption: {key:?}");
+        let mut cache = self.cache.lock().expect("data key cache is poisoned");
+        cache.current = Some((key.clone(), Instant::now()));
+        Ok(key)
+    }
+
+    async fn unwrap_data_key(&self, wrapped: &[u8]) -> Result<[u8; 32], ObjectStoreError> {
+        {
+            let cache = self.cache.lock().expect("data key cache is poisoned");
+            if let Some(key) = cache.unwrapped.get(wrapped) {
+                return Ok(*key);
+            }
+        }
+
+        let key = self.kms.unwrap_data_key(wrapped).await?;
+        let mut cache = self.cache.lock().expect("data key cache is poisoned");
+        if cache.unwrapped.len() >= MAX_CACHED_DATA_KEYS {
+            cache.unwrapped.clear();
+        }
+        cache.unwrapped.insert(wrapped.to_vec(), key);
+        Ok(key)
+    }
+
+    async fn encrypt(
+        &self,
+        bucket: Bucket,
+        key: &str,
+        blob: &[u8],
+    ) -> Result<Vec<u8>, ObjectStoreError> {
+        let data_key = self.current_data_key().await?;
+        let wrapped_len = u16::try_from(data_key.wrapped.len())
+            .map_err(|_| ObjectStoreError::Other("wrapped data key is too long".into()))?;
+        let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&data_key.plaintext));
+        let nonce = Aes256Gcm::generate_nonce(OsRng);
+        let aad = format!("{bucket}/{key}");
+        let ciphertext = cipher
+            .encrypt(
+                &nonce,
+                Payload {
+                    msg: blob,
+                    aad: aad.as_bytes(),
+                },
+            )
+            .map_err(|err| {
+                ObjectStoreError::Other(format!("failed encrypting blob: {err}").into())
+            })?;
+
+        let mut output = Vec::with_capacity(
+            MAGIC.len() + 2 + data_key.wrapped.len() + NONCE_LEN + ciphertext.len(),
+        );
+        output.extend_from_slice(&MAGIC);
+        output.extend_from_slice(&wrapped_len.to_be_bytes());
+        output.extend_from_slice(&data_key.wrapped);
+        output.extend_from_slice(&nonce);
+        output.extend_from_slice(&ciphertext);
+        Ok(output)
+    }
+
+    async fn decrypt(
+        &self,
+        bucket: Bucket,
+        key: &str,
+        blob: &[u8],
+    ) -> Result<Vec<u8>, ObjectStoreError> {
+        let malformed = || ObjectStoreError::Serialization("malformed encrypted blob".into());
+
+        let rest = blob.strip_prefix(MAGIC.as_slice()).ok_or_else(malformed)?;
+        let (wrapped_len, rest) = split_checked(rest, 2).ok_or_else(malformed)?;
+        let wrapped_len = u16::from_be_bytes([wrapped_len[0], wrapped_len[1]]);
+        let (wrapped, rest) = split_checked(rest, wrapped_len.into()).ok_or_else(malformed)?;
+        let (nonce, ciphertext) = split_checked(rest, NONCE_LEN).ok_or_else(malformed)?;
+
+        let data_key = self.unwrap_data_key(wrapped).await?;
+        let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&data_key));
+        let aad = format!("{bucket}/{key}");
+        cipher
+            .decrypt(
+                Nonce::from_slice(nonce),
+                Payload {
+                    msg: ciphertext,
+                    aad: aad.as_bytes(),
+                },
+            )
+            .map_err(|err| {
+                ObjectStoreError::Serialization(format!("failed decrypting blob: {err}").into())
+            })
+    }
+}
+
+#[async_trait]
+impl<S: ObjectStore> ObjectStore for EncryptingObjectStore<S> {
+    async fn get_raw(&self, bucket: Bucket, key: &str) -> Result<Vec<u8>, ObjectStoreError> {
+        let blob = self.inner.get_raw(bucket, key).await?;
+        if blob.starts_with(&MAGIC) {
+            self.decrypt(bucket, key, &blob).await
+        } else if self.strict && self.is_encrypted(bucket) {
+            let message = format!("blob {bucket}/{key} is not encrypted");
+            Err(ObjectStoreError::Serialization(message.into()))
+        } else {
+            Ok(blob)
+        }
+    }
+
+    async fn put_raw(
+        &self,
+        bucket: Bucket,
+        key: &str,
+        value: Vec<u8>,
+    ) -> Result<(), ObjectStoreError> {
+        let value = if self.is_encrypted(bucket) {
+            self.encrypt(bucket, key, &value).await?
+        } else {
+            value
+        };
+        self.inner.put_raw(bucket, key, value).await
+    }
+
+    async fn remove_raw(&self, bucket: Bucket, key: &str) -> Result<(), ObjectStoreError> {
+        self.inner.remove_raw(bucket, key).await
+    }
+
+    fn storage_prefix_raw(&self, bucket: Bucket) -> String {
+        self.inner.storage_prefix_raw(bucket)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_config::{
+        configs::object_store::{
+            ObjectStoreCompression, ObjectStoreConfig, ObjectStoreEncryption, ObjectStoreMode,
+        },
+        validation::{ConfigErrors, Validate},
+    };
+
+    use super::*;
+    use crate::mock::MockStore;
+
+    const KEY: &str = "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f";
+
+    fn encrypting_store(buckets: Option<HashSet<Bucket>>) -> EncryptingObjectStore<MockStore> {
+        let kms = LocalKeyWrapper::new(KEY).unwrap();
+        EncryptingObjectStore::new(MockStore::default(), Box::new(kms), buckets)
+    }
+
+    #[test]
+    fn invalid_local_keys() {
+        let err = LocalKeyWrapper::new("test").unwrap_err().to_string();
+        assert!(err.contains("hex"), "{err}");
+        let err = LocalKeyWrapper::new("0x0011").unwrap_err().to_string();
+        assert!(err.contains("32 bytes"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn wrapping_data_keys() {
+        let kms = LocalKeyWrapper::new(KEY).unwrap();
+        let data_key = kms.generate_data_key().await.unwrap();
+        assert!(!format!("{data_key:?}").contains(&hex::encode(data_key.plaintext)));
+        let unwrapped = kms.unwrap_data_key(&data_key.wrapped).await.unwrap();
+        assert_eq!(unwrapped, data_key.plaintext);
+
+        let other_kms = LocalKeyWrapper::new(&KEY.replace('0', "f")).unwrap();
+        let err = other_kms
+            .unwrap_data_key(&data_key.wrapped)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ObjectStoreError::Serialization(_)), "{err}");
+    }
+
+    #[tokio::test]
+    async fn encryption_roundtrip() {
+        let store = encrypting_store(None);
+        let blob = b"transaction data".to_vec();
+        store
+            .put_raw(Bucket::WitnessInput, "test", blob.clone())
+            .await
+            .unwrap();
+        let stored = store
+            .inner
+            .get_raw(Bucket::WitnessInput, "test")
+            .await
+            .unwrap();
+        assert!(stored.starts_with(&MAGIC));
+        assert!(!stored.windows(blob.len()).any(|window| window == blob));
+
+        let fetched = store.get_raw(Bucket::WitnessInput, "test").await.unwrap();
+        assert_eq!(fetched, blob);
+
+        // The data key should be reused.
+        store
+            .put_raw(Bucket::WitnessInput, "other", blob.clone())
+            .await
+            .unwrap();
+        let other_stored = store
+            .inner
+            .get_raw(Bucket::WitnessInput, "other")
+            .await
+            .unwrap();
+        assert_eq!(other_stored[..60], stored[..60]);
+        assert_ne!(other_stored, stored);
+    }
+
+    #[tokio::test]
+    async fn encrypted_blobs_are_bound_to_path() {
+        let store = encrypting_store(None);
+        store
+            .put_raw(Bucket::WitnessInput, "test", b"data".to_vec())
+            .await
+            .unwrap();
+        let stored = store
+            .inner
+            .get_raw(Bucket::WitnessInput, "test")
+            .await
+            .unwrap();
+        store
+            .inner
+            .put_raw(Bucket::WitnessInput, "moved", stored)
+            .await
+            .unwrap();
+        let err = store
+            .get_raw(Bucket::WitnessInput, "moved")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ObjectStoreError::Serialization(_)), "{err}");
+    }
+
+    #[tokio::test]
+    async fn encryption_for_selected_buckets() {
+        let store = encrypting_store(Some(HashSet::from([Bucket::WitnessInput])));
+        let blob = b"proof".to_vec();
+        store
+            .put_raw(Bucket::ProofsFri, "test", blob.clone())
+            .await
+            .unwrap();
+        let stored = store
+            .inner
+            .get_raw(Bucket::ProofsFri, "test")
+            .await
+            .unwrap();
+        assert_eq!(stored, blob);
+
+        // Plaintext blobs must be readable from encrypted buckets.
+        store
+            .inner
+            .put_raw(Bucket::WitnessInput, "legacy", blob.clone())
+            .await
+            .unwrap();
+        let fetched = store.get_raw(Bucket::WitnessInput, "legacy").await.unwrap();
+        assert_eq!(fetched, blob);
+    }
+
+    #[tokio::test]
+    async fn strict_mode_rejects_plaintext_blobs() {
+        let store =
+            encrypting_store(Some(HashSet::from([Bucket::WitnessInput]))).with_strict_mode(true);
+        let blob = b"proof".to_vec();
+        for bucket in [Bucket::WitnessInput, Bucket::ProofsFri] {
+            store
+                .inner
+                .put_raw(bucket, "legacy", blob.clone())
+                .await
+                .unwrap();
+        }
+
+        let err = store
+            .get_raw(Bucket::WitnessInput, "legacy")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ObjectStoreError::Serialization(_)), "{err}");
+        // Plaintext blobs in non-encrypted buckets are still readable.
+        let fetched = store.get_raw(Bucket::ProofsFri, "legacy").await.unwrap();
+        assert_eq!(fetched, blob);
+
+        store
+            .put_raw(Bucket::WitnessInput, "test", blob.clone())
+            .await
+            .unwrap();
+        let fetched = store.get_raw(Bucket::WitnessInput, "test").await.unwrap();
+        assert_eq!(fetched, blob);
+    }
+
+    fn mock_config() -> ObjectStoreConfig {
+        ObjectStoreConfig {
+            mode: ObjectStoreMode::FileBacked {
+                file_backed_base_path: "artifacts".to_owned(),
+            },
+            max_retries: 5,
+            compression: ObjectStoreCompression::None,
+            compression_level: None,
+            retry_initial_backoff_ms: None,
+            retry_max_backoff_ms: None,
+            retry_budget: None,
+            circuit_breaker_failure_threshold: None,
+            circuit_breaker_reset_timeout_ms: None,
+            encryption: ObjectStoreEncryption::None,
+            encrypted_buckets: None,
+            encryption_key: None,
+            encryption_kms_key_id: None,
+            encryption_kms_region: None,
+            encryption_strict: false,
+        }
+    }
+
+    #[test]
+    fn bucket_names_in_config_match_buckets() {
+        let bucket_names: Vec<_> = Bucket::ALL.iter().map(|bucket| bucket.as_str()).collect();
+        assert_eq!(bucket_names, ObjectStoreConfig::BUCKET_NAMES);
+    }
+
+    #[test]
+    fn validating_encryption_config() {
+        let mut config = ObjectStoreConfig {
+            encryption: ObjectStoreEncryption::LocalKey,
+            encrypted_buckets: Some(vec!["witness_inputs".to_owned(), "unknown".to_owned()]),
+            encryption_strict: true,
+            ..mock_config()
+        };
+        let mut errors = ConfigErrors::default();
+        config.validate(&mut errors);
+        let fields: Vec<_> = errors.iter().map(|err| err.field.as_deref()).collect();
+        assert_eq!(fields, [Some("encryption_key"), Some("encrypted_buckets")]);
+
+        config.encryption_key = Some(KEY.to_owned());
+        config.encrypted_buckets = Some(vec!["witness_inputs".to_owned()]);
+        let mut errors = ConfigErrors::default();
+        config.validate(&mut errors);
+        assert!(errors.is_empty(), "{errors}");
+
+        config.encryption_key = Some("0x0011".to_owned());
+        let mut errors = ConfigErrors::default();
+        config.validate(&mut errors);
+        assert_eq!(errors.len(), 1, "{errors}");
+    }
+}
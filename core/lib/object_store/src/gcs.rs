@@ -1,6 +1,6 @@
 //! GCS-based [`ObjectStore`] implementation.
 
-use std::{fmt, future::Future, time::Duration};
+use std::{fmt, future::Future};
 
 use async_trait::async_trait;
 use google_cloud_auth::{credentials::CredentialsFile, error::Error};
@@ -19,37 +19,13 @@ use google_cloud_storage::{
 use http::StatusCode;
 
 use crate::{
-    metrics::GCS_METRICS,
+    metrics::OBJECT_STORE_METRICS,
     raw::{Bucket, ObjectStore, ObjectStoreError},
+    retries::RetryPolicy,
 };
 
-async fn retry<T, E, Fut, F>(max_retries: u16, mut f: F) -> Result<T, E>
-where
-    E: fmt::Display,
-    Fut: Future<Output = Result<T, E>>,
-    F: FnMut() -> Fut,
-{
-    let mut retries = 1;
-    let mut backoff = 1;
-    loop {
-        match f().await {
-            Ok(result) => return Ok(result),
-            Err(err) => {
-                tracing::warn!(%err, "Failed GCS request {retries}/{max_retries}, retrying.");
-                if retries > max_retries {
-                    return Err(err);
-                }
-                retries += 1;
-                tokio::time::sleep(Duration::from_secs(backoff)).await;
-                backoff *= 2;
-            }
-        }
-    }
-}
-
 pub struct GoogleCloudStorage {
     bucket_prefix: String,
-    max_retries: u16,
     client: Client,
 }
 
@@ -58,7 +34,6 @@ impl fmt::Debug for GoogleCloudStorage {
         formatter
             .debug_struct("GoogleCloudStorage")
             .field("bucket_prefix", &self.bucket_prefix)
-            .field("max_retries", &self.max_retries)
             .finish_non_exhaustive()
     }
 }
@@ -71,19 +46,24 @@ pub enum GoogleCloudStorageAuthMode {
 }
 
 impl GoogleCloudStorage {
-    pub async fn new(
+    /// Creates a new store. Requests are not retried; retries are performed by the wrapping store
+    /// created by [`ObjectStoreFactory`](crate::ObjectStoreFactory). `retry_policy` is only used
+    /// to fetch the client config.
+    pub(crate) async fn new(
         auth_mode: GoogleCloudStorageAuthMode,
         bucket_prefix: String,
-        max_retries: u16,
+        retry_policy: RetryPolicy,
     ) -> Self {
-        let client_config = retry(max_retries, || Self::get_client_config(auth_mode.clone()))
+        let client_config = retry_policy
+            .retry("fetching GCS client config", || {
+                Self::get_client_config(auth_mode.clone())
+            })
             .await
             .expect("failed fetching GCS client config after retries");
 
         Self {
             client: Client::new(client_config),
             bucket_prefix,
-            max_retries,
         }
     }
 
@@ -127,7 +107,8 @@ impl GoogleCloudStorage {
             ..DeleteObjectRequest::default()
         };
         async move {
-            retry(self.max_retries, || self.client.delete_object(&request))
+            self.client
+                .delete_object(&request)
                 .await
                 .map_err(ObjectStoreError::from)
         }
@@ -155,7 +136,7 @@ impl From<HttpError> for ObjectStoreError {
 #[async_trait]
 impl ObjectStore for GoogleCloudStorage {
     async fn get_raw(&self, bucket: Bucket, key: &str) -> Result<Vec<u8>, ObjectStoreError> {
-        let fetch_latency = GCS_METRICS.start_fetch(bucket);
+        let fetch_latency = OBJECT_STORE_METRICS.start_fetch(bucket);
         let filename = Self::filename(bucket.as_str(), key);
         tracing::trace!(
             "Fetching data from GCS for key {filename} from bucket {}",
@@ -168,10 +149,7 @@ impl ObjectStore for GoogleCloudStorage {
             ..GetObjectRequest::default()
         };
         let range = Range::default();
-        let blob = retry(self.max_retries, || {
-            self.client.download_object(&request, &range)
-        })
-        .await;
+        let blob = self.client.download_object(&request, &range).await;
 
         let elapsed = fetch_latency.observe();
         tracing::trace!(
@@ -186,7 +164,7 @@ impl ObjectStore for GoogleCloudStorage {
         key: &str,
         value: Vec<u8>,
     ) -> Result<(), ObjectStoreError> {
-        let store_latency = GCS_METRICS.start_store(bucket);
+        let store_latency = OBJECT_STORE_METRICS.start_store(bucket);
         let filename = Self::filename(bucket.as_str(), key);
         tracing::trace!(
             "Storing data to GCS for key {filename} from bucket {}",
@@ -198,11 +176,10 @@ impl ObjectStore for GoogleCloudStorage {
             bucket: self.bucket_prefix.clone(),
             ..Default::default()
         };
-        let object = retry(self.max_retries, || {
-            self.client
-                .upload_object(&request, value.clone(), &upload_type)
-        })
-        .await;
+        let object = self
+            .client
+            .upload_object(&request, value, &upload_type)
+            .await;
 
         let elapsed = store_latency.observe();
         tracing::trace!(
@@ -223,43 +200,3 @@ impl ObjectStore for GoogleCloudStorage {
         )
     }
 }
-
-#[cfg(test)]
-mod test {
-    use std::sync::atomic::{AtomicU16, Ordering};
-
-    use super::*;
-
-    #[tokio::test]
-    async fn test_retry_success_immediate() {
-        let result = retry(2, || async { Ok::<_, &'static str>(42) }).await;
-        assert_eq!(result, Ok(42));
-    }
-
-    #[tokio::test]
-    async fn test_retry_failure_exhausted() {
-        let result = retry(2, || async { Err::<i32, _>("oops") }).await;
-        assert_eq!(result, Err("oops"));
-    }
-
-    async fn retry_success_after_n_retries(n: u16) -> Result<u32, String> {
-        let retries = AtomicU16::new(0);
-        let result = retry(n, || async {
-            let retries = retries.fetch_add(1, Ordering::Relaxed);
-            if retries + 1 == n {
-                Ok(42)
-            } else {
-                Err("oops")
-            }
-        })
-        .await;
-
-        result.map_err(|_| "Retry failed".to_string())
-    }
-
-    #[tokio::test]
-    async fn test_retry_success_after_retry() {
-        let result = retry(2, || retry_success_after_n_retries(2)).await;
-        assert_eq!(result, Ok(42));
-    }
-}
@@ -0,0 +1,144 @@
+//! Read-only [`ObjectStore`] implementation fetching blobs from an HTTP(S) mirror of another store,
+//! e.g. a CDN or a plain web server serving published snapshots.
+
+use std::{fmt, time::Duration};
+
+use async_trait::async_trait;
+use reqwest::{Client, StatusCode};
+
+use crate::{
+    metrics::OBJECT_STORE_METRICS,
+    raw::{Bucket, ObjectStore, ObjectStoreError},
+};
+
+/// Object store backed by an HTTP mirror. Blobs are fetched using `GET {base_url}/{bucket}/{key}` requests,
+/// i.e., the mirror is expected to use the same layout as [`FileBackedObjectStore`](crate::file::FileBackedObjectStore)
+/// or GCS buckets. Writes and removals are not supported.
+pub(crate) struct HttpMirrorObjectStore {
+    base_url: String,
+    client: Client,
+}
+
+impl fmt::Debug for HttpMirrorObjectStore {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("HttpMirrorObjectStore")
+            .field("base_url", &self.base_url)
+            .finish_non_exhaustive()
+    }
+}
+
+impl HttpMirrorObjectStore {
+    const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+    /// Timeout for the entire request including reading the response body. Should be large enough
+    /// to download the largest blobs (e.g., snapshot chunks).
+    const REQUEST_TIMEOUT: Duration = Duration::from_secs(300);
+
+    /// Creates a new store. Requests are not retried; retries are performed by the wrapping store
+    /// created by [`ObjectStoreFactory`](crate::ObjectStoreFactory). Timed out requests are considered
+    /// transient errors.
+    pub(crate) fn new(base_url: &str) -> Self {
+        let client = Client::builder()
+            .connect_timeout(Self::CONNECT_TIMEOUT)
+            .timeout(Self::REQUEST_TIMEOUT)
+            .build()
+            .expect("failed building HTTP client");
+        Self {
+            base_url: base_url.trim_end_matches('/').to_owned(),
+            client,
+        }
+    }
+
+    fn url(&self, bucket: Bucket, key: &str) -> String {
+        format!("{}/{bucket}/{key}", self.base_url)
+    }
+
+    fn read_only_error(&self, operation: &str) -> ObjectStoreError {
+        let message = format!(
+            "HTTP mirror object store at {} is read-only; {operation} is not supported",
+            self.base_url
+        );
+        ObjectStoreError::Other(message.into())
+    }
+}
+
+#[async_trait]
+impl ObjectStore for HttpMirrorObjectStore {
+    async fn get_raw(&self, bucket: Bucket, key: &str) -> Result<Vec<u8>, ObjectStoreError> {
+        let fetch_latency = OBJECT_STORE_METRICS.start_fetch(bucket);
+        let url = self.url(bucket, key);
+        tracing::trace!("Fetching data from HTTP mirror: {url}");
+
+        let response = self
+            .client
+            .get(&url)
+            .send()
+            .await
+            .map_err(|err| ObjectStoreError::Other(err.into()))?;
+        if response.status() == StatusCode::NOT_FOUND {
+            let message = format!("{url} is not found on HTTP mirror");
+            return Err(ObjectStoreError::KeyNotFound(message.into()));
+        }
+        let response = response
+            .error_for_status()
+            .map_err(|err| ObjectStoreError::Other(err.into()))?;
+        let blob = response
+            .bytes()
+            .await
+            .map_err(|err| ObjectStoreError::Other(err.into()))?;
+
+        let elapsed = fetch_latency.observe();
+        tracing::trace!("Fetched data from HTTP mirror: {url} and it took: {elapsed:?}");
+        Ok(blob.to_vec())
+    }
+
+    async fn put_raw(
+        &self,
+        _bucket: Bucket,
+        _key: &str,
+        _value: Vec<u8>,
+    ) -> Result<(), ObjectStoreError> {
+        Err(self.read_only_error("storing data"))
+    }
+
+    async fn remove_raw(&self, _bucket: Bucket, _key: &str) -> Result<(), ObjectStoreError> {
+        Err(self.read_only_error("removing data"))
+    }
+
+    fn storage_prefix_raw(&self, bucket: Bucket) -> String {
+        format!("{}/{bucket}", self.base_url)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mirror_urls() {
+        let store = HttpMirrorObjectStore::new("https://snapshots.example.com/mainnet/");
+        assert_eq!(
+            store.url(Bucket::StorageSnapshot, "snapshot_l1_batch_1.json"),
+            "https://snapshots.example.com/mainnet/storage_logs_snapshots/snapshot_l1_batch_1.json"
+        );
+        assert_eq!(
+            store.storage_prefix_raw(Bucket::MerkleTreeSnapshot),
+            "https://snapshots.example.com/mainnet/merkle_tree_snapshots"
+        );
+    }
+
+    #[tokio::test]
+    async fn mirror_is_read_only() {
+        let store = HttpMirrorObjectStore::new("https://snapshots.example.com");
+        let err = store
+            .put_raw(Bucket::StorageSnapshot, "test", vec![1, 2, 3])
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("read-only"), "{err}");
+        let err = store
+            .remove_raw(Bucket::StorageSnapshot, "test")
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("read-only"), "{err}");
+    }
+}
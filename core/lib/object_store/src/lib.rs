@@ -3,6 +3,13 @@
 //!
 //! - File-based storage saving blobs as separate files in the local filesystem
 //! - GCS-based storage
+//! - Azure Blob Storage-based storage
+//! - S3-based storage, including S3-compatible stores such as MinIO
+//! - Read-only storage fetching blobs from an HTTP mirror (e.g., a CDN serving published snapshots)
+//!
+//! Blobs can be optionally compressed on the client side (see [`ObjectStoreConfig`](zksync_config::ObjectStoreConfig)).
+//! Compressed blobs are transparently decompressed on read. Similarly, blobs can be encrypted on the client side
+//! using envelope encryption with data keys wrapped by a KMS.
 //!
 //! These implementations are not exposed externally. Instead, a store trait object
 //! can be constructed using an [`ObjectStoreFactory`] based on the configuration.
@@ -23,12 +30,18 @@
     clippy::doc_markdown
 )]
 
+mod azure;
+mod compression;
+mod encryption;
 mod file;
 mod gcs;
+mod http;
 mod metrics;
 mod mock;
 mod objects;
 mod raw;
+mod retries;
+mod s3;
 
 // Re-export `bincode` crate so that client binaries can conveniently use it.
 pub use bincode;
@@ -1,23 +1,93 @@
 //! Metrics for the object storage.
 
-use std::time::Duration;
+use std::{fmt, time::Duration};
 
-use vise::{Buckets, Histogram, LabeledFamily, LatencyObserver, Metrics};
+use vise::{Buckets, Counter, Gauge, Histogram, LabeledFamily, LatencyObserver, Metrics};
 
 use crate::Bucket;
 
+/// Object store operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum Operation {
+    Get,
+    Put,
+    Remove,
+}
+
+impl Operation {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Get => "get",
+            Self::Put => "put",
+            Self::Remove => "remove",
+        }
+    }
+}
+
+impl fmt::Display for Operation {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(self.as_str())
+    }
+}
+
+/// Outcome of an object store operation (including retries).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum OperationOutcome {
+    /// The store has successfully processed the operation. This includes non-transient errors,
+    /// such as a missing key.
+    Success,
+    /// The operation has failed with a transient error after all retries.
+    Failure,
+    /// The operation was rejected by the circuit breaker.
+    Rejected,
+}
+
+impl OperationOutcome {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Success => "success",
+            Self::Failure => "failure",
+            Self::Rejected => "rejected",
+        }
+    }
+}
+
+/// State of the object store circuit breaker.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CircuitBreakerState {
+    Closed = 0,
+    HalfOpen = 1,
+    Open = 2,
+}
+
 #[derive(Debug, Metrics)]
 #[metrics(prefix = "server_object_store")]
-pub(crate) struct GcsMetrics {
-    /// Latency to fetch an object from GCS.
+pub(crate) struct ObjectStoreMetrics {
+    /// Latency to fetch an object from a remote store (GCS or Azure Blob Storage).
     #[metrics(buckets = Buckets::LATENCIES, labels = ["bucket"])]
     fetching_time: LabeledFamily<&'static str, Histogram<Duration>>,
-    /// Latency to store an object in GCS.
+    /// Latency to store an object in a remote store (GCS or Azure Blob Storage).
     #[metrics(buckets = Buckets::LATENCIES, labels = ["bucket"])]
     storing_time: LabeledFamily<&'static str, Histogram<Duration>>,
+    /// Total size of blobs before client-side compression.
+    #[metrics(labels = ["bucket"])]
+    uncompressed_bytes: LabeledFamily<&'static str, Counter>,
+    /// Total size of blobs after client-side compression.
+    #[metrics(labels = ["bucket"])]
+    compressed_bytes: LabeledFamily<&'static str, Counter>,
+    /// Number of completed operations (including retries) by their outcome.
+    #[metrics(labels = ["operation", "outcome"])]
+    operations: LabeledFamily<(&'static str, &'static str), Counter, 2>,
+    /// Number of retried requests.
+    #[metrics(labels = ["operation"])]
+    retries: LabeledFamily<&'static str, Counter>,
+    /// Current state of the circuit breaker: 0 = closed, 1 = half-open, 2 = open.
+    circuit_breaker_state: Gauge<u64>,
+    /// Number of times the circuit breaker was tripped.
+    circuit_breaker_trips: Counter,
 }
 
-impl GcsMetrics {
+impl ObjectStoreMetrics {
     pub fn start_fetch(&self, bucket: Bucket) -> LatencyObserver<'_> {
         self.fetching_time[&bucket.as_str()].start()
     }
@@ -25,7 +95,32 @@ impl GcsMetrics {
     pub fn start_store(&self, bucket: Bucket) -> LatencyObserver<'_> {
         self.storing_time[&bucket.as_str()].start()
     }
+
+    pub fn report_outcome(&self, operation: Operation, outcome: OperationOutcome) {
+        self.operations[&(operation.as_str(), outcome.as_str())].inc();
+    }
+
+    pub fn report_retry(&self, operation: Operation) {
+        self.retries[&operation.as_str()].inc();
+    }
+
+    pub fn set_circuit_breaker_state(&self, state: CircuitBreakerState) {
+        self.circuit_breaker_state.set(state as u64);
+        if state == CircuitBreakerState::Open {
+            self.circuit_breaker_trips.inc();
+        }
+    }
+
+    pub fn report_compression(
+        &self,
+        bucket: Bucket,
+        uncompressed_len: usize,
+        compressed_len: usize,
+    ) {
+        self.uncompressed_bytes[&bucket.as_str()].inc_by(uncompressed_len as u64);
+        self.compressed_bytes[&bucket.as_str()].inc_by(compressed_len as u64);
+    }
 }
 
 #[vise::register]
-pub(crate) static GCS_METRICS: vise::Global<GcsMetrics> = vise::Global::new();
+pub(crate) static OBJECT_STORE_METRICS: vise::Global<ObjectStoreMetrics> = vise::Global::new();
@@ -9,6 +9,7 @@ use zksync_protobuf::{decode, ProtoFmt};
 use zksync_types::{
     snapshots::{
         SnapshotFactoryDependencies, SnapshotStorageLogsChunk, SnapshotStorageLogsStorageKey,
+        SnapshotTokens,
     },
     storage::witness_block_state::WitnessBlockState,
     L1BatchNumber,
@@ -118,6 +119,33 @@ impl StoredObject for SnapshotStorageLogsChunk {
     }
 }
 
+impl StoredObject for SnapshotTokens {
+    const BUCKET: Bucket = Bucket::StorageSnapshot;
+    type Key<'a> = L1BatchNumber;
+
+    fn encode_key(key: Self::Key<'_>) -> String {
+        format!("snapshot_l1_batch_{key}_tokens.proto.gzip")
+    }
+
+    fn serialize(&self) -> Result<Vec<u8>, BoxedError> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        let encoded_bytes = self.build().encode_to_vec();
+        encoder.write_all(&encoded_bytes)?;
+        encoder.finish().map_err(From::from)
+    }
+
+    fn deserialize(bytes: Vec<u8>) -> Result<Self, BoxedError> {
+        let mut decoder = GzDecoder::new(&bytes[..]);
+        let mut decompressed_bytes = Vec::new();
+        decoder
+            .read_to_end(&mut decompressed_bytes)
+            .map_err(BoxedError::from)?;
+        decode(&decompressed_bytes[..])
+            .context("deserialization of Message to SnapshotTokens")
+            .map_err(From::from)
+    }
+}
+
 impl StoredObject for WitnessBlockState {
     const BUCKET: Bucket = Bucket::WitnessInput;
     type Key<'a> = L1BatchNumber;
@@ -168,7 +196,8 @@ impl dyn ObjectStore + '_ {
 mod tests {
     use zksync_types::{
         snapshots::{SnapshotFactoryDependency, SnapshotStorageLog},
-        AccountTreeId, Bytes, StorageKey, H160, H256,
+        tokens::{TokenInfo, TokenMetadata},
+        AccountTreeId, Address, Bytes, StorageKey, H160, H256,
     };
 
     use super::*;
@@ -248,4 +277,25 @@ mod tests {
         let reconstructed_factory_deps = store.get(key).await.unwrap();
         assert_eq!(factory_deps, reconstructed_factory_deps);
     }
+
+    #[tokio::test]
+    async fn test_tokens_can_be_serialized_and_deserialized() {
+        let store = ObjectStoreFactory::mock().create_store().await;
+        let key = L1BatchNumber(123);
+        let tokens = SnapshotTokens {
+            tokens: vec![TokenInfo {
+                l1_address: Address::repeat_byte(1),
+                l2_address: Address::repeat_byte(2),
+                metadata: TokenMetadata {
+                    name: "Test".to_owned(),
+                    symbol: "TST".to_owned(),
+                    decimals: 10,
+                },
+            }],
+        };
+        let filename = store.put(key, &tokens).await.unwrap();
+        assert_eq!(filename, "snapshot_l1_batch_123_tokens.proto.gzip");
+        let reconstructed_tokens = store.get(key).await.unwrap();
+        assert_eq!(tokens, reconstructed_tokens);
+    }
 }
@@ -1,12 +1,24 @@
-use std::{error, fmt, sync::Arc};
+use std::{collections::HashSet, error, fmt, str::FromStr, sync::Arc};
 
 use async_trait::async_trait;
-use zksync_config::configs::object_store::{ObjectStoreConfig, ObjectStoreMode};
+use zksync_config::{
+    configs::object_store::{
+        ObjectStoreCompression, ObjectStoreConfig, ObjectStoreEncryption, ObjectStoreMode,
+        S3AddressingStyle,
+    },
+    validation::{ConfigErrors, Validate},
+};
 
 use crate::{
+    azure::{AzureBlobStorage, AzureBlobStorageAuthMode},
+    compression::CompressingObjectStore,
+    encryption::{AwsKms, EncryptingObjectStore, KeyManagementService, LocalKeyWrapper},
     file::FileBackedObjectStore,
     gcs::{GoogleCloudStorage, GoogleCloudStorageAuthMode},
+    http::HttpMirrorObjectStore,
     mock::MockStore,
+    retries::{CircuitBreaker, RetryPolicy, RetryingObjectStore},
+    s3::{S3Storage, S3StorageOptions},
 };
 
 /// Bucket for [`ObjectStore`] in which objects can be placed.
@@ -24,9 +36,25 @@ pub enum Bucket {
     SchedulerWitnessJobsFri,
     ProofsFri,
     StorageSnapshot,
+    MerkleTreeSnapshot,
 }
 
 impl Bucket {
+    pub(crate) const ALL: [Self; 12] = [
+        Self::ProverJobs,
+        Self::WitnessInput,
+        Self::LeafAggregationWitnessJobs,
+        Self::NodeAggregationWitnessJobs,
+        Self::SchedulerWitnessJobs,
+        Self::ProverJobsFri,
+        Self::LeafAggregationWitnessJobsFri,
+        Self::NodeAggregationWitnessJobsFri,
+        Self::SchedulerWitnessJobsFri,
+        Self::ProofsFri,
+        Self::StorageSnapshot,
+        Self::MerkleTreeSnapshot,
+    ];
+
     pub(crate) fn as_str(self) -> &'static str {
         match self {
             Self::ProverJobs => "prover_jobs",
@@ -40,6 +68,7 @@ impl Bucket {
             Self::SchedulerWitnessJobsFri => "scheduler_witness_jobs_fri",
             Self::ProofsFri => "proofs_fri",
             Self::StorageSnapshot => "storage_logs_snapshots",
+            Self::MerkleTreeSnapshot => "merkle_tree_snapshots",
         }
     }
 }
@@ -50,6 +79,18 @@ impl fmt::Display for Bucket {
     }
 }
 
+impl FromStr for Bucket {
+    type Err = String;
+
+    /// Parses a bucket from its name, e.g. `witness_inputs`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|bucket| bucket.as_str() == s)
+            .ok_or_else(|| format!("unknown object store bucket: `{s}`"))
+    }
+}
+
 /// Thread-safe boxed error.
 pub type BoxedError = Box<dyn error::Error + Send + Sync>;
 
@@ -165,7 +206,11 @@ impl ObjectStoreFactory {
     /// # Panics
     ///
     /// If the GCS-backed implementation is configured, this constructor will panic if called
-    /// outside the Tokio runtime.
+    /// outside the Tokio runtime. If the Azure-backed implementation is configured, creating a store
+    /// will panic if Azure credentials cannot be initialized. If the S3-backed implementation is configured,
+    /// creating a store will panic if only one of static S3 credentials is specified. Creating a store will panic
+    /// if the config does not pass [validation](Validate) (e.g., the encryption config refers to an unknown bucket);
+    /// thus, the config should be validated beforehand.
     pub fn new(config: ObjectStoreConfig) -> Self {
         Self {
             origin: ObjectStoreOrigin::Config(config),
@@ -189,7 +234,79 @@ impl ObjectStoreFactory {
         }
     }
 
+    /// Default compression level for Zstandard. Provides a good trade-off between compression speed and ratio.
+    const DEFAULT_ZSTD_LEVEL: i32 = 3;
+
     async fn create_from_config(config: &ObjectStoreConfig) -> Arc<dyn ObjectStore> {
+        let mut errors = ConfigErrors::default();
+        config.validate(&mut errors);
+        if let Err(errors) = errors.into_result() {
+            panic!("invalid object store config: {errors}");
+        }
+
+        let retry_policy = RetryPolicy {
+            max_retries: config.max_retries,
+            initial_backoff: config.retry_initial_backoff(),
+            max_backoff: config.retry_max_backoff(),
+        };
+        let circuit_breaker = CircuitBreaker::new(
+            config.circuit_breaker_failure_threshold(),
+            config.circuit_breaker_reset_timeout(),
+        );
+        let store = Self::create_uncompressed_store(config, retry_policy).await;
+        let store =
+            RetryingObjectStore::new(store, retry_policy, config.retry_budget(), circuit_breaker);
+        // Blobs are compressed before encryption since ciphertext is incompressible.
+        let store = Self::wrap_with_encryption(config, store).await;
+        let compression_level = match config.compression {
+            ObjectStoreCompression::None => None,
+            ObjectStoreCompression::Zstd => {
+                Some(config.compression_level.unwrap_or(Self::DEFAULT_ZSTD_LEVEL))
+            }
+        };
+        tracing::trace!("Using object store compression level: {compression_level:?}");
+        // Even if compression is disabled, the wrapper is used to decompress blobs stored with compression.
+        Arc::new(CompressingObjectStore::new(store, compression_level))
+    }
+
+    async fn wrap_with_encryption<S: ObjectStore>(
+        config: &ObjectStoreConfig,
+        store: S,
+    ) -> Arc<dyn ObjectStore> {
+        // All `expect()`s below are guaranteed to succeed because the config is validated.
+        let kms: Box<dyn KeyManagementService> = match config.encryption {
+            ObjectStoreEncryption::None => return Arc::new(store),
+            ObjectStoreEncryption::LocalKey => {
+                let key = config.encryption_key.as_deref().expect("validated");
+                Box::new(LocalKeyWrapper::new(key).expect("validated"))
+            }
+            ObjectStoreEncryption::AwsKms => {
+                let key_id = config.encryption_kms_key_id.clone().expect("validated");
+                Box::new(AwsKms::new(key_id, config.encryption_kms_region.clone()).await)
+            }
+        };
+
+        let buckets = config.encrypted_buckets.as_ref().map(|names| {
+            names
+                .iter()
+                .map(|name| name.parse::<Bucket>().expect("validated"))
+                .collect::<HashSet<_>>()
+        });
+        tracing::info!(
+            "Using object store encryption {:?} (strict: {}) for buckets: {buckets:?}",
+            config.encryption,
+            config.encryption_strict
+        );
+        Arc::new(
+            EncryptingObjectStore::new(store, kms, buckets)
+                .with_strict_mode(config.encryption_strict),
+        )
+    }
+
+    async fn create_uncompressed_store(
+        config: &ObjectStoreConfig,
+        retry_policy: RetryPolicy,
+    ) -> Arc<dyn ObjectStore> {
         match &config.mode {
             ObjectStoreMode::GCS { bucket_base_url } => {
                 tracing::trace!(
@@ -198,7 +315,7 @@ impl ObjectStoreFactory {
                 let store = GoogleCloudStorage::new(
                     GoogleCloudStorageAuthMode::Authenticated,
                     bucket_base_url.clone(),
-                    config.max_retries,
+                    retry_policy,
                 )
                 .await;
                 Arc::new(store)
@@ -213,7 +330,7 @@ impl ObjectStoreFactory {
                         gcs_credential_file_path.clone(),
                     ),
                     bucket_base_url.clone(),
-                    config.max_retries,
+                    retry_policy,
                 )
                 .await;
                 Arc::new(store)
@@ -230,11 +347,74 @@ impl ObjectStoreFactory {
                 let store = GoogleCloudStorage::new(
                     GoogleCloudStorageAuthMode::Anonymous,
                     bucket_base_url.clone(),
-                    config.max_retries,
+                    retry_policy,
                 )
                 .await;
                 Arc::new(store)
             }
+            ObjectStoreMode::AzureBlobWithManagedIdentity {
+                azure_account_name,
+                azure_container_name,
+            } => {
+                tracing::trace!("Initialized AzureBlobStorage Object store with managed identity");
+                let store = AzureBlobStorage::new(
+                    AzureBlobStorageAuthMode::ManagedIdentity {
+                        account_name: azure_account_name.clone(),
+                    },
+                    azure_container_name.clone(),
+                );
+                Arc::new(store)
+            }
+            ObjectStoreMode::AzureBlobWithConnectionString {
+                azure_container_name,
+                azure_connection_string,
+            } => {
+                tracing::trace!("Initialized AzureBlobStorage Object store with connection string");
+                let store = AzureBlobStorage::new(
+                    AzureBlobStorageAuthMode::ConnectionString(azure_connection_string.clone()),
+                    azure_container_name.clone(),
+                );
+                Arc::new(store)
+            }
+            ObjectStoreMode::S3 {
+                s3_bucket,
+                s3_region,
+                s3_endpoint,
+                s3_addressing_style,
+                s3_access_key_id,
+                s3_secret_access_key,
+            } => {
+                let static_credentials = match (s3_access_key_id, s3_secret_access_key) {
+                    (Some(key_id), Some(secret)) => Some((key_id.clone(), secret.clone())),
+                    (None, None) => None,
+                    _ => panic!(
+                        "S3 access key ID and secret access key must be either both set or both unset"
+                    ),
+                };
+                tracing::trace!(
+                    "Initialized S3 Object store with {} credentials",
+                    if static_credentials.is_some() {
+                        "static"
+                    } else {
+                        "default"
+                    }
+                );
+                let store = S3Storage::new(S3StorageOptions {
+                    bucket: s3_bucket.clone(),
+                    region: s3_region.clone(),
+                    endpoint: s3_endpoint.clone(),
+                    force_path_style: matches!(s3_addressing_style, Some(S3AddressingStyle::Path)),
+                    static_credentials,
+                })
+                .await;
+                Arc::new(store)
+            }
+            ObjectStoreMode::HttpMirrorReadOnly {
+                http_mirror_base_url,
+            } => {
+                tracing::trace!("Initialized read-only HTTP mirror Object store");
+                Arc::new(HttpMirrorObjectStore::new(http_mirror_base_url))
+            }
         }
     }
 }
@@ -0,0 +1,539 @@
+//! Retry policy and circuit breaking for object store operations.
+
+use std::{
+    fmt,
+    future::Future,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use async_trait::async_trait;
+use rand::Rng;
+
+use crate::{
+    metrics::{CircuitBreakerState, Operation, OperationOutcome, OBJECT_STORE_METRICS},
+    raw::{Bucket, ObjectStore, ObjectStoreError},
+};
+
+/// Retry policy for object store operations.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct RetryPolicy {
+    /// Maximum number of retries for a single operation.
+    pub max_retries: u16,
+    /// Backoff before the first retry. Backoff is doubled with each subsequent retry.
+    pub initial_backoff: Duration,
+    /// Upper bound for the backoff between retries.
+    pub max_backoff: Duration,
+}
+
+impl RetryPolicy {
+    /// Returns a jittered backoff before the specified retry (1-based). The backoff is uniformly
+    /// distributed in `[base / 2, base]`, where `base` grows exponentially.
+    fn backoff(&self, retry: u16) -> Duration {
+        let exponent = u32::from(retry.saturating_sub(1)).min(31);
+        let base = self
+            .initial_backoff
+            .saturating_mul(1 << exponent)
+            .min(self.max_backoff);
+        let jitter = rand::thread_rng().gen_range(0.5..=1.0);
+        base.mul_f64(jitter)
+    }
+
+    /// Retries `f` on all errors according to this policy.
+    pub async fn retry<T, E, Fut, F>(&self, description: &str, mut f: F) -> Result<T, E>
+    where
+        E: fmt::Display,
+        Fut: Future<Output = Result<T, E>>,
+        F: FnMut() -> Fut,
+    {
+        let mut retry = 0;
+        loop {
+            match f().await {
+                Ok(value) => return Ok(value),
+                Err(err) if retry < self.max_retries => {
+                    retry += 1;
+                    tracing::warn!(
+                        %err,
+                        "Failed {description} {retry}/{}, retrying",
+                        self.max_retries
+                    );
+                    tokio::time::sleep(self.backoff(retry)).await;
+                }
+                Err(err) => return Err(err),
+            }
+        }
+    }
+}
+
+/// Budget limiting the share of retried requests. Each operation deposits [`Self::DEPOSIT`] tokens
+/// to the budget (up to its capacity), and each retry withdraws a single token. Thus, during a prolonged outage,
+/// retries are limited to ~10% of the operations once the initial budget is exhausted, which prevents retry storms.
+#[derive(Debug)]
+struct RetryBudget {
+    capacity: f64,
+    tokens: Mutex<f64>,
+}
+
+impl RetryBudget {
+    const DEPOSIT: f64 = 0.1;
+
+    fn new(capacity: u32) -> Self {
+        let capacity = f64::from(capacity);
+        Self {
+            capacity,
+            tokens: Mutex::new(capacity),
+        }
+    }
+
+    fn deposit(&self) {
+        let mut tokens = self.tokens.lock().expect("retry budget is poisoned");
+        *tokens = (*tokens + Self::DEPOSIT).min(self.capacity);
+    }
+
+    fn try_withdraw(&self) -> bool {
+        let mut tokens = self.tokens.lock().expect("retry budget is poisoned");
+        if *tokens >= 1.0 {
+            *tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CircuitState {
+    Closed {
+        consecutive_failures: u32,
+    },
+    Open {
+        until: Instant,
+    },
+    /// A single trial request is allowed; its outcome determines whether the circuit gets closed
+    /// or opened again. If the outcome is not reported in time (e.g., because the request future was dropped),
+    /// another trial request is allowed.
+    HalfOpen {
+        trial_started_at: Instant,
+    },
+}
+
+/// Circuit breaker failing requests fast if the store is persistently unavailable.
+#[derive(Debug)]
+pub(crate) struct CircuitBreaker {
+    failure_threshold: u32,
+    reset_timeout: Duration,
+    state: Mutex<CircuitState>,
+}
+
+impl CircuitBreaker {
+    /// Creates a breaker that opens after `failure_threshold` consecutive failed requests and allows
+    /// a trial request after `reset_timeout`. A zero threshold disables the breaker.
+    pub fn new(failure_threshold: u32, reset_timeout: Duration) -> Self {
+        Self {
+            failure_threshold,
+            reset_timeout,
+            state: Mutex::new(CircuitState::Closed {
+                consecutive_failures: 0,
+            }),
+        }
+    }
+
+    fn allows_request(&self) -> bool {
+        let mut state = self.state.lock().expect("circuit breaker is poisoned");
+        let now = Instant::now();
+        match *state {
+            CircuitState::Closed { .. } => true,
+            CircuitState::Open { until } if now >= until => {
+                *state = CircuitState::HalfOpen {
+                    trial_started_at: now,
+                };
+                OBJECT_STORE_METRICS.set_circuit_breaker_state(CircuitBreakerState::HalfOpen);
+                true
+            }
+            CircuitState::HalfOpen { trial_started_at }
+                if now >= trial_started_at + self.reset_timeout =>
+            {
+                *state = CircuitState::HalfOpen {
+                    trial_started_at: now,
+                };
+                true
+            }
+            CircuitState::Open { .. } | CircuitState::HalfOpen { .. } => false,
+        }
+    }
+
+    fn report_success(&self) {
+        let mut state = self.state.lock().expect("circuit breaker is poisoned");
+        if !matches!(*state, CircuitState::Closed { .. }) {
+            tracing::info!("Object store request succeeded; closing circuit breaker");
+            OBJECT_STORE_METRICS.set_circuit_breaker_state(CircuitBreakerState::Closed);
+        }
+        *state = CircuitState::Closed {
+            consecutive_failures: 0,
+        };
+    }
+
+    fn report_failure(&self) {
+        if self.failure_threshold == 0 {
+            return;
+        }
+        let mut state = self.state.lock().expect("circuit breaker is poisoned");
+        let should_open = match &mut *state {
+            CircuitState::Closed {
+                consecutive_failures,
+            } => {
+                *consecutive_failures += 1;
+                *consecutive_failures >= self.failure_threshold
+            }
+            CircuitState::HalfOpen { .. } => true,
+            CircuitState::Open { .. } => false,
+        };
+        if should_open {
+            tracing::warn!(
+                "Object store is unavailable; opening circuit breaker for {:?}",
+                self.reset_timeout
+            );
+            *state = CircuitState::Open {
+                until: Instant::now() + self.reset_timeout,
+            };
+            OBJECT_STORE_METRICS.set_circuit_breaker_state(CircuitBreakerState::Open);
+        }
+    }
+}
+
+/// [`ObjectStore`] wrapper retrying transient errors according to a [`RetryPolicy`], with retries limited
+/// by a retry budget. Persistent failures trip a [`CircuitBreaker`], after which requests fail immediately
+/// until the store becomes available again.
+///
+/// Only [`ObjectStoreError::Other`] errors are considered transient; e.g., a missing key is never retried.
+#[derive(Debug)]
+pub(crate) struct RetryingObjectStore<S> {
+    inner: S,
+    policy: RetryPolicy,
+    budget: RetryBudget,
+    circuit_breaker: CircuitBreaker,
+}
+
+impl<S: ObjectStore> RetryingObjectStore<S> {
+    pub fn new(
+        inner: S,
+        policy: RetryPolicy,
+        retry_budget: u32,
+        circuit_breaker: CircuitBreaker,
+    ) -> Self {
+        Self {
+            inner,
+            policy,
+            budget: RetryBudget::new(retry_budget),
+            circuit_breaker,
+        }
+    }
+
+    async fn run<T, Fut>(
+        &self,
+        operation: Operation,
+        bucket: Bucket,
+        key: &str,
+        mut f: impl FnMut() -> Fut + Send,
+    ) -> Result<T, ObjectStoreError>
+    where
+        T: Send,
+        Fut: Future<Output = Result<T, ObjectStoreError>> + Send,
+    {
+        self.budget.deposit();
+        let mut retry = 0;
+        loop {
+            if !self.circuit_breaker.allows_request() {
+                OBJECT_STORE_METRICS.report_outcome(operation, OperationOutcome::Rejected);
+                return Err(ObjectStoreError::Other(
+                    format!(
+                        "circuit breaker is open; {operation} request for key {key} \
+                         from bucket {bucket} is rejected"
+                    )
+                    .into(),
+                ));
+            }
+
+            let err = match f().await {
+                Ok(value) => {
+                    self.circuit_breaker.report_success();
+                    OBJECT_STORE_METRICS.report_outcome(operation, OperationOutcome::Success);
+                    return Ok(value);
+                }
+                Err(err @ ObjectStoreError::Other(_)) => err,
+                Err(err) => {
+                    // The store is available, it's just that the request is invalid.
+                    self.circuit_breaker.report_success();
+                    OBJECT_STORE_METRICS.report_outcome(operation, OperationOutcome::Success);
+                    return Err(err);
+                }
+            };
+
+            self.circuit_breaker.report_failure();
+            if retry >= self.policy.max_retries {
+                OBJECT_STORE_METRICS.report_outcome(operation, OperationOutcome::Failure);
+                return Err(err);
+            }
+            if !self.budget.try_withdraw() {
+                tracing::warn!(
+                    %err,
+                    "Failed {operation} request for key {key} from bucket {bucket}; not retrying since retry budget is exhausted"
+                );
+                OBJECT_STORE_METRICS.report_outcome(operation, OperationOutcome::Failure);
+                return Err(err);
+            }
+
+            retry += 1;
+            tracing::warn!(
+                %err,
+                "Failed {operation} request for key {key} from bucket {bucket} {retry}/{}, retrying",
+                self.policy.max_retries
+            );
+            OBJECT_STORE_METRICS.report_retry(operation);
+            tokio::time::sleep(self.policy.backoff(retry)).await;
+        }
+    }
+}
+
+#[async_trait]
+impl<S: ObjectStore> ObjectStore for RetryingObjectStore<S> {
+    async fn get_raw(&self, bucket: Bucket, key: &str) -> Result<Vec<u8>, ObjectStoreError> {
+        self.run(Operation::Get, bucket, key, || {
+            self.inner.get_raw(bucket, key)
+        })
+        .await
+    }
+
+    async fn put_raw(
+        &self,
+        bucket: Bucket,
+        key: &str,
+        value: Vec<u8>,
+    ) -> Result<(), ObjectStoreError> {
+        self.run(Operation::Put, bucket, key, || {
+            self.inner.put_raw(bucket, key, value.clone())
+        })
+        .await
+    }
+
+    async fn remove_raw(&self, bucket: Bucket, key: &str) -> Result<(), ObjectStoreError> {
+        self.run(Operation::Remove, bucket, key, || {
+            self.inner.remove_raw(bucket, key)
+        })
+        .await
+    }
+
+    fn storage_prefix_raw(&self, bucket: Bucket) -> String {
+        self.inner.storage_prefix_raw(bucket)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU16, Ordering};
+
+    use super::*;
+    use crate::mock::MockStore;
+
+    const POLICY: RetryPolicy = RetryPolicy {
+        max_retries: 2,
+        initial_backoff: Duration::from_millis(1),
+        max_backoff: Duration::from_millis(5),
+    };
+
+    #[test]
+    fn backoff_is_bounded_and_jittered() {
+        let policy = RetryPolicy {
+            max_retries: 10,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(30),
+        };
+        for _ in 0..100 {
+            let backoff = policy.backoff(1);
+            assert!(backoff >= Duration::from_millis(500) && backoff <= Duration::from_secs(1));
+            let backoff = policy.backoff(4);
+            assert!(backoff >= Duration::from_secs(4) && backoff <= Duration::from_secs(8));
+            let backoff = policy.backoff(u16::MAX);
+            assert!(backoff >= Duration::from_secs(15) && backoff <= Duration::from_secs(30));
+        }
+    }
+
+    #[tokio::test]
+    async fn retry_success_immediate() {
+        let result = POLICY
+            .retry("test", || async { Ok::<_, &'static str>(42) })
+            .await;
+        assert_eq!(result, Ok(42));
+    }
+
+    #[tokio::test]
+    async fn retry_failure_exhausted() {
+        let calls = AtomicU16::new(0);
+        let result = POLICY
+            .retry("test", || async {
+                calls.fetch_add(1, Ordering::Relaxed);
+                Err::<i32, _>("oops")
+            })
+            .await;
+        assert_eq!(result, Err("oops"));
+        assert_eq!(calls.into_inner(), 3);
+    }
+
+    #[tokio::test]
+    async fn retry_success_after_retries() {
+        let calls = AtomicU16::new(0);
+        let result = POLICY
+            .retry("test", || async {
+                if calls.fetch_add(1, Ordering::Relaxed) == 2 {
+                    Ok(42)
+                } else {
+                    Err("oops")
+                }
+            })
+            .await;
+        assert_eq!(result, Ok(42));
+    }
+
+    #[test]
+    fn retry_budget() {
+        let budget = RetryBudget::new(2);
+        assert!(budget.try_withdraw());
+        assert!(budget.try_withdraw());
+        assert!(!budget.try_withdraw());
+        for _ in 0..10 {
+            budget.deposit();
+        }
+        assert!(budget.try_withdraw());
+        assert!(!budget.try_withdraw());
+    }
+
+    #[test]
+    fn circuit_breaker_lifecycle() {
+        let breaker = CircuitBreaker::new(2, Duration::from_millis(50));
+        assert!(breaker.allows_request());
+        breaker.report_failure();
+        assert!(breaker.allows_request());
+        breaker.report_failure();
+        assert!(!breaker.allows_request());
+
+        std::thread::sleep(Duration::from_millis(60));
+        assert!(breaker.allows_request()); // trial request
+        assert!(!breaker.allows_request());
+        breaker.report_failure();
+        assert!(!breaker.allows_request());
+
+        std::thread::sleep(Duration::from_millis(60));
+        assert!(breaker.allows_request());
+        breaker.report_success();
+        assert!(breaker.allows_request());
+        assert!(breaker.allows_request());
+    }
+
+    /// Store failing the first `failures` requests with a transient error.
+    #[derive(Debug)]
+    struct FlakyStore {
+        inner: MockStore,
+        failures: AtomicU16,
+    }
+
+    impl FlakyStore {
+        fn new(failures: u16) -> Self {
+            Self {
+                inner: MockStore::default(),
+                failures: AtomicU16::new(failures),
+            }
+        }
+
+        fn check(&self) -> Result<(), ObjectStoreError> {
+            let should_fail = self
+                .failures
+                .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| n.checked_sub(1))
+                .is_ok();
+            if should_fail {
+                Err(ObjectStoreError::Other("transient error".into()))
+            } else {
+                Ok(())
+            }
+        }
+    }
+
+    #[async_trait]
+    impl ObjectStore for FlakyStore {
+        async fn get_raw(&self, bucket: Bucket, key: &str) -> Result<Vec<u8>, ObjectStoreError> {
+            self.check()?;
+            self.inner.get_raw(bucket, key).await
+        }
+
+        async fn put_raw(
+            &self,
+            bucket: Bucket,
+            key: &str,
+            value: Vec<u8>,
+        ) -> Result<(), ObjectStoreError> {
+            self.check()?;
+            self.inner.put_raw(bucket, key, value).await
+        }
+
+        async fn remove_raw(&self, bucket: Bucket, key: &str) -> Result<(), ObjectStoreError> {
+            self.check()?;
+            self.inner.remove_raw(bucket, key).await
+        }
+
+        fn storage_prefix_raw(&self, bucket: Bucket) -> String {
+            self.inner.storage_prefix_raw(bucket)
+        }
+    }
+
+    fn retrying_store(failures: u16, failure_threshold: u32) -> RetryingObjectStore<FlakyStore> {
+        RetryingObjectStore::new(
+            FlakyStore::new(failures),
+            POLICY,
+            100,
+            CircuitBreaker::new(failure_threshold, Duration::from_secs(60)),
+        )
+    }
+
+    #[tokio::test]
+    async fn transient_errors_are_retried() {
+        let store = retrying_store(2, 10);
+        store
+            .put_raw(Bucket::ProofsFri, "test", vec![1, 2, 3])
+            .await
+            .unwrap();
+        let blob = store.get_raw(Bucket::ProofsFri, "test").await.unwrap();
+        assert_eq!(blob, [1, 2, 3]);
+    }
+
+    #[tokio::test]
+    async fn missing_keys_are_not_retried() {
+        let store = retrying_store(0, 10);
+        let err = store
+            .get_raw(Bucket::ProofsFri, "missing")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ObjectStoreError::KeyNotFound(_)), "{err}");
+    }
+
+    #[tokio::test]
+    async fn circuit_breaker_rejects_requests() {
+        let store = retrying_store(u16::MAX, 3);
+        let err = store.get_raw(Bucket::ProofsFri, "test").await.unwrap_err();
+        assert!(err.to_string().contains("transient error"), "{err}");
+        // 3 failed attempts (including retries) have tripped the breaker.
+        let err = store.get_raw(Bucket::ProofsFri, "test").await.unwrap_err();
+        assert!(err.to_string().contains("circuit breaker is open"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn retries_are_limited_by_budget() {
+        let store = RetryingObjectStore::new(
+            FlakyStore::new(u16::MAX),
+            POLICY,
+            1,
+            CircuitBreaker::new(0, Duration::from_secs(60)),
+        );
+        store.get_raw(Bucket::ProofsFri, "test").await.unwrap_err();
+        // 1 initial attempt + 1 retry allowed by the budget
+        assert_eq!(store.inner.failures.load(Ordering::Relaxed), u16::MAX - 2);
+    }
+}
@@ -0,0 +1,264 @@
+//! S3-based [`ObjectStore`] implementation. Besides AWS S3, it supports S3-compatible stores
+//! (e.g., MinIO or Ceph RGW) via custom endpoints and path-style addressing.
+
+use std::{error, fmt};
+
+use async_trait::async_trait;
+use aws_config::{retry::RetryConfig, BehaviorVersion, Region};
+use aws_sdk_s3::{
+    config::{Builder as S3ConfigBuilder, Credentials},
+    error::{ProvideErrorMetadata, SdkError},
+    primitives::ByteStream,
+    Client,
+};
+
+use crate::{
+    metrics::OBJECT_STORE_METRICS,
+    raw::{Bucket, ObjectStore, ObjectStoreError},
+};
+
+/// Options for [`S3Storage`].
+#[derive(Clone)]
+pub struct S3StorageOptions {
+    pub bucket: String,
+    pub region: String,
+    /// Custom endpoint URL, e.g. for a self-hosted S3-compatible store.
+    pub endpoint: Option<String>,
+    /// Whether to use path-style addressing of buckets instead of virtual-hosted style.
+    pub force_path_style: bool,
+    /// Static credentials as `(access_key_id, secret_access_key)`. If not specified, credentials
+    /// are taken from the default AWS credential chain.
+    pub static_credentials: Option<(String, String)>,
+}
+
+impl fmt::Debug for S3StorageOptions {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("S3StorageOptions")
+            .field("bucket", &self.bucket)
+            .field("region", &self.region)
+            .field("endpoint", &self.endpoint)
+            .field("force_path_style", &self.force_path_style)
+            // Secret access key is intentionally not output.
+            .field(
+                "static_credentials",
+                &self.static_credentials.as_ref().map(|(key_id, _)| key_id),
+            )
+            .finish()
+    }
+}
+
+pub struct S3Storage {
+    bucket: String,
+    /// URL prefix of the bucket used in [`ObjectStore::storage_prefix_raw()`].
+    bucket_url: String,
+    client: Client,
+}
+
+impl fmt::Debug for S3Storage {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("S3Storage")
+            .field("bucket", &self.bucket)
+            .field("bucket_url", &self.bucket_url)
+            .finish_non_exhaustive()
+    }
+}
+
+impl S3Storage {
+    /// Creates a new store. Retries in the AWS SDK are disabled; retries are performed by the wrapping store
+    /// created by [`ObjectStoreFactory`](crate::ObjectStoreFactory).
+    pub async fn new(options: S3StorageOptions) -> Self {
+        let mut loader = aws_config::defaults(BehaviorVersion::latest())
+            .region(Region::new(options.region.clone()));
+        if let Some((access_key_id, secret_access_key)) = options.static_credentials.clone() {
+            let credentials = Credentials::new(
+                access_key_id,
+                secret_access_key,
+                None,
+                None,
+                "zksync_config",
+            );
+            loader = loader.credentials_provider(credentials);
+        }
+        let sdk_config = loader.load().await;
+
+        let mut config = S3ConfigBuilder::from(&sdk_config)
+            .retry_config(RetryConfig::disabled())
+            .force_path_style(options.force_path_style);
+        if let Some(endpoint) = &options.endpoint {
+            config = config.endpoint_url(endpoint);
+        }
+
+        Self {
+            bucket_url: Self::bucket_url(&options),
+            bucket: options.bucket,
+            client: Client::from_conf(config.build()),
+        }
+    }
+
+    fn bucket_url(options: &S3StorageOptions) -> String {
+        let bucket = &options.bucket;
+        match &options.endpoint {
+            Some(endpoint) => {
+                let endpoint = endpoint.trim_end_matches('/');
+                if options.force_path_style {
+                    format!("{endpoint}/{bucket}")
+                } else if let Some((scheme, host)) = endpoint.split_once("://") {
+                    format!("{scheme}://{bucket}.{host}")
+                } else {
+                    format!("{bucket}.{endpoint}")
+                }
+            }
+            None => {
+                let region = &options.region;
+                if options.force_path_style {
+                    format!("https://s3.{region}.amazonaws.com/{bucket}")
+                } else {
+                    format!("https://{bucket}.s3.{region}.amazonaws.com")
+                }
+            }
+        }
+    }
+
+    fn filename(bucket: Bucket, key: &str) -> String {
+        format!("{bucket}/{key}")
+    }
+}
+
+fn convert_error<E, R>(err: SdkError<E, R>) -> ObjectStoreError
+where
+    E: ProvideErrorMetadata + error::Error + Send + Sync + 'static,
+    R: fmt::Debug + Send + Sync + 'static,
+{
+    let is_not_found = matches!(err.code(), Some("NoSuchKey" | "NotFound"));
+    if is_not_found {
+        ObjectStoreError::KeyNotFound(err.into())
+    } else {
+        ObjectStoreError::Other(err.into())
+    }
+}
+
+#[async_trait]
+impl ObjectStore for S3Storage {
+    async fn get_raw(&self, bucket: Bucket, key: &str) -> Result<Vec<u8>, ObjectStoreError> {
+        let fetch_latency = OBJECT_STORE_METRICS.start_fetch(bucket);
+        let filename = Self::filename(bucket, key);
+        tracing::trace!(
+            "Fetching data from S3 for key {filename} from bucket {}",
+            self.bucket
+        );
+
+        let response = self
+            .client
+            .get_object()
+            .bucket(&self.bucket)
+            .key(filename)
+            .send()
+            .await
+            .map_err(convert_error)?;
+        let blob = response
+            .body
+            .collect()
+            .await
+            .map_err(|err| ObjectStoreError::Other(err.into()))?;
+
+        let elapsed = fetch_latency.observe();
+        tracing::trace!(
+            "Fetched data from S3 for key {key} from bucket {bucket} and it took: {elapsed:?}"
+        );
+        Ok(blob.into_bytes().to_vec())
+    }
+
+    async fn put_raw(
+        &self,
+        bucket: Bucket,
+        key: &str,
+        value: Vec<u8>,
+    ) -> Result<(), ObjectStoreError> {
+        let store_latency = OBJECT_STORE_METRICS.start_store(bucket);
+        let filename = Self::filename(bucket, key);
+        tracing::trace!(
+            "Storing data to S3 for key {filename} from bucket {}",
+            self.bucket
+        );
+
+        let response = self
+            .client
+            .put_object()
+            .bucket(&self.bucket)
+            .key(filename)
+            .body(ByteStream::from(value))
+            .send()
+            .await;
+
+        let elapsed = store_latency.observe();
+        tracing::trace!(
+            "Stored data to S3 for key {key} from bucket {bucket} and it took: {elapsed:?}"
+        );
+        response.map(drop).map_err(convert_error)
+    }
+
+    async fn remove_raw(&self, bucket: Bucket, key: &str) -> Result<(), ObjectStoreError> {
+        let filename = Self::filename(bucket, key);
+        tracing::trace!(
+            "Removing data from S3 for key {filename} from bucket {}",
+            self.bucket
+        );
+        self.client
+            .delete_object()
+            .bucket(&self.bucket)
+            .key(filename)
+            .send()
+            .await
+            .map(drop)
+            .map_err(convert_error)
+    }
+
+    fn storage_prefix_raw(&self, bucket: Bucket) -> String {
+        format!("{}/{}", self.bucket_url, bucket.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(endpoint: Option<&str>, force_path_style: bool) -> S3StorageOptions {
+        S3StorageOptions {
+            bucket: "artifacts".to_owned(),
+            region: "eu-central-1".to_owned(),
+            endpoint: endpoint.map(str::to_owned),
+            force_path_style,
+            static_credentials: Some(("key_id".to_owned(), "secret".to_owned())),
+        }
+    }
+
+    #[test]
+    fn bucket_urls() {
+        let url = S3Storage::bucket_url(&options(None, false));
+        assert_eq!(url, "https://artifacts.s3.eu-central-1.amazonaws.com");
+        let url = S3Storage::bucket_url(&options(None, true));
+        assert_eq!(url, "https://s3.eu-central-1.amazonaws.com/artifacts");
+        let url = S3Storage::bucket_url(&options(Some("http://minio:9000/"), true));
+        assert_eq!(url, "http://minio:9000/artifacts");
+        let url = S3Storage::bucket_url(&options(Some("https://ceph.local"), false));
+        assert_eq!(url, "https://artifacts.ceph.local");
+    }
+
+    #[test]
+    fn options_debug_output_has_no_secrets() {
+        let debug_output = format!("{:?}", options(None, false));
+        assert!(debug_output.contains("key_id"), "{debug_output}");
+        assert!(!debug_output.contains("secret"), "{debug_output}");
+    }
+
+    #[tokio::test]
+    async fn creating_store_with_custom_endpoint() {
+        let store = S3Storage::new(options(Some("http://minio:9000"), true)).await;
+        assert_eq!(
+            store.storage_prefix_raw(Bucket::ProverJobsFri),
+            "http://minio:9000/artifacts/prover_jobs_fri"
+        );
+    }
+}
@@ -6,6 +6,9 @@ use tokio::sync::watch;
 use vise::MetricsCollection;
 use vise_exporter::MetricsExporter;
 
+/// Additional delay for the final metrics push after the stop signal is received.
+const FINAL_PUSH_MARGIN: Duration = Duration::from_millis(100);
+
 fn configure_legacy_exporter(builder: PrometheusBuilder) -> PrometheusBuilder {
     // in seconds
     let default_latency_buckets = [0.001, 0.005, 0.025, 0.1, 0.25, 1.0, 5.0, 30.0, 120.0];
@@ -56,6 +59,9 @@ impl PrometheusExporterConfig {
     }
 
     /// Creates an exporter that will push metrics to the specified Prometheus gateway endpoint.
+    /// This is the preferred mode for short-lived components (e.g., ones performing a one-off job),
+    /// since they may finish before Prometheus scrapes them. Once the stop signal is received, metrics are pushed
+    /// once more before the exporter terminates, so that metrics reported right before stopping are not lost.
     pub const fn push(gateway_uri: String, interval: Duration) -> Self {
         Self {
             transport: PrometheusTransport::Push {
@@ -92,11 +98,17 @@ impl PrometheusExporterConfig {
         self,
         mut stop_receiver: watch::Receiver<bool>,
     ) -> anyhow::Result<()> {
+        // In the push mode, the shutdown is delayed by the push interval to ensure the final push.
+        let shutdown_delay = match &self.transport {
+            PrometheusTransport::Pull { .. } => Duration::ZERO,
+            PrometheusTransport::Push { interval, .. } => *interval + FINAL_PUSH_MARGIN,
+        };
         let registry = MetricsCollection::lazy().collect();
         let metrics_exporter = MetricsExporter::new(registry.into())
             .with_legacy_exporter(configure_legacy_exporter)
             .with_graceful_shutdown(async move {
                 stop_receiver.changed().await.ok();
+                tokio::time::sleep(shutdown_delay).await;
             });
 
         match self.transport {
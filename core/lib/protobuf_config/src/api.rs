@@ -17,6 +17,48 @@ impl ProtoRepr for proto::Api {
             prometheus: read_required_repr(&self.prometheus).context("prometheus")?,
             healthcheck: read_required_repr(&self.healthcheck).context("healthcheck")?,
             merkle_tree: read_required_repr(&self.merkle_tree).context("merkle_tree")?,
+            rate_limit: self
+                .rate_limit
+                .as_ref()
+                .map(ProtoRepr::read)
+                .transpose()
+                .context("rate_limit")?
+                .unwrap_or_default(),
+            grpc: self
+                .grpc
+                .as_ref()
+                .map(ProtoRepr::read)
+                .transpose()
+                .context("grpc")?
+                .unwrap_or_default(),
+            http_interface: self
+                .http_interface
+                .as_ref()
+                .map(ProtoRepr::read)
+                .transpose()
+                .context("http_interface")?
+                .unwrap_or_default(),
+            ws_interface: self
+                .ws_interface
+                .as_ref()
+                .map(ProtoRepr::read)
+                .transpose()
+                .context("ws_interface")?
+                .unwrap_or_default(),
+            audit_log: self
+                .audit_log
+                .as_ref()
+                .map(ProtoRepr::read)
+                .transpose()
+                .context("audit_log")?
+                .unwrap_or_default(),
+            admin: self
+                .admin
+                .as_ref()
+                .map(ProtoRepr::read)
+                .transpose()
+                .context("admin")?
+                .unwrap_or_default(),
         })
     }
 
@@ -27,6 +69,12 @@ impl ProtoRepr for proto::Api {
             prometheus: Some(ProtoRepr::build(&this.prometheus)),
             healthcheck: Some(ProtoRepr::build(&this.healthcheck)),
             merkle_tree: Some(ProtoRepr::build(&this.merkle_tree)),
+            rate_limit: Some(ProtoRepr::build(&this.rate_limit)),
+            grpc: Some(ProtoRepr::build(&this.grpc)),
+            http_interface: Some(ProtoRepr::build(&this.http_interface)),
+            ws_interface: Some(ProtoRepr::build(&this.ws_interface)),
+            audit_log: Some(ProtoRepr::build(&this.audit_log)),
+            admin: Some(ProtoRepr::build(&this.admin)),
         }
     }
 }
@@ -48,6 +96,7 @@ impl ProtoRepr for proto::Web3JsonRpc {
             subscriptions_limit: self.subscriptions_limit,
             pubsub_polling_interval: self.pubsub_polling_interval,
             max_nonce_ahead: *required(&self.max_nonce_ahead).context("max_nonce_ahead")?,
+            tx_replacement_fee_bump_percent: self.tx_replacement_fee_bump_percent,
             gas_price_scale_factor: *required(&self.gas_price_scale_factor)
                 .context("gas_price_scale_factor")?,
             request_timeout: self.request_timeout,
@@ -102,6 +151,11 @@ impl ProtoRepr for proto::Web3JsonRpc {
                 .map(|x| x.try_into())
                 .transpose()
                 .context("latests_values_cache_size_mb")?,
+            response_cache_size_mb: self
+                .response_cache_size_mb
+                .map(|x| x.try_into())
+                .transpose()
+                .context("response_cache_size_mb")?,
             fee_history_limit: self.fee_history_limit,
             max_batch_request_size: self
                 .max_batch_request_size
@@ -113,6 +167,12 @@ impl ProtoRepr for proto::Web3JsonRpc {
                 .map(|x| x.try_into())
                 .transpose()
                 .context("max_response_body_size_mb")?,
+            max_request_body_size_mb: self
+                .max_request_body_size_mb
+                .map(|x| x.try_into())
+                .transpose()
+                .context("max_request_body_size_mb")?,
+            compress_responses: self.compress_responses,
             websocket_requests_per_minute_limit: self
                 .websocket_requests_per_minute_limit
                 .map(|x| x.try_into())
@@ -132,6 +192,7 @@ impl ProtoRepr for proto::Web3JsonRpc {
             subscriptions_limit: this.subscriptions_limit,
             pubsub_polling_interval: this.pubsub_polling_interval,
             max_nonce_ahead: Some(this.max_nonce_ahead),
+            tx_replacement_fee_bump_percent: this.tx_replacement_fee_bump_percent,
             gas_price_scale_factor: Some(this.gas_price_scale_factor),
             request_timeout: this.request_timeout,
             account_pks: this.account_pks.as_ref().map(|keys| proto::PrivateKeys {
@@ -158,11 +219,14 @@ impl ProtoRepr for proto::Web3JsonRpc {
             latest_values_cache_size_mb: this
                 .latest_values_cache_size_mb
                 .map(|x| x.try_into().unwrap()),
+            response_cache_size_mb: this.response_cache_size_mb.map(|x| x.try_into().unwrap()),
             fee_history_limit: this.fee_history_limit,
             max_batch_request_size: this.max_batch_request_size.map(|x| x.try_into().unwrap()),
             max_response_body_size_mb: this
                 .max_response_body_size_mb
                 .map(|x| x.try_into().unwrap()),
+            max_request_body_size_mb: this.max_request_body_size_mb.map(|x| x.try_into().unwrap()),
+            compress_responses: this.compress_responses,
             websocket_requests_per_minute_limit: this
                 .websocket_requests_per_minute_limit
                 .map(|x| x.into()),
@@ -196,11 +260,15 @@ impl ProtoRepr for proto::HealthCheck {
             port: required(&self.port)
                 .and_then(|p| Ok((*p).try_into()?))
                 .context("port")?,
+            max_sealed_l1_batch_age_sec: self.max_sealed_l1_batch_age_sec,
+            max_tree_lag: self.max_tree_lag,
         })
     }
     fn build(this: &Self::Type) -> Self {
         Self {
             port: Some(this.port.into()),
+            max_sealed_l1_batch_age_sec: this.max_sealed_l1_batch_age_sec,
+            max_tree_lag: this.max_tree_lag,
         }
     }
 }
@@ -220,3 +288,103 @@ impl ProtoRepr for proto::MerkleTreeApi {
         }
     }
 }
+
+impl ProtoRepr for proto::ApiRateLimit {
+    type Type = api::ApiRateLimitConfig;
+    fn read(&self) -> anyhow::Result<Self::Type> {
+        Ok(Self::Type {
+            requests_per_minute: self
+                .requests_per_minute
+                .map(|x| x.try_into())
+                .transpose()
+                .context("requests_per_minute")?,
+            api_key_header: self.api_key_header.clone(),
+            method_weights: self.method_weights.clone(),
+            method_concurrency_limits: self.method_concurrency_limits.clone(),
+        })
+    }
+    fn build(this: &Self::Type) -> Self {
+        Self {
+            requests_per_minute: this.requests_per_minute.map(|x| x.into()),
+            api_key_header: this.api_key_header.clone(),
+            method_weights: this.method_weights.clone(),
+            method_concurrency_limits: this.method_concurrency_limits.clone(),
+        }
+    }
+}
+
+impl ProtoRepr for proto::ApiAuditLog {
+    type Type = api::ApiAuditLogConfig;
+    fn read(&self) -> anyhow::Result<Self::Type> {
+        Ok(Self::Type {
+            sampling_ratio: self.sampling_ratio,
+            api_key_header: self.api_key_header.clone(),
+            redact_client_ids: *required(&self.redact_client_ids).context("redact_client_ids")?,
+            file_path: self.file_path.clone(),
+        })
+    }
+    fn build(this: &Self::Type) -> Self {
+        Self {
+            sampling_ratio: this.sampling_ratio,
+            api_key_header: this.api_key_header.clone(),
+            redact_client_ids: Some(this.redact_client_ids),
+            file_path: this.file_path.clone(),
+        }
+    }
+}
+
+impl ProtoRepr for proto::RpcInterface {
+    type Type = api::RpcInterfaceConfig;
+    fn read(&self) -> anyhow::Result<Self::Type> {
+        let non_empty = |values: &Vec<String>| (!values.is_empty()).then(|| values.clone());
+        Ok(Self::Type {
+            namespaces: non_empty(&self.namespaces),
+            allowed_methods: non_empty(&self.allowed_methods),
+            denied_methods: self.denied_methods.clone(),
+        })
+    }
+    fn build(this: &Self::Type) -> Self {
+        Self {
+            namespaces: this.namespaces.clone().unwrap_or_default(),
+            allowed_methods: this.allowed_methods.clone().unwrap_or_default(),
+            denied_methods: this.denied_methods.clone(),
+        }
+    }
+}
+
+impl ProtoRepr for proto::GrpcApi {
+    type Type = api::GrpcApiConfig;
+    fn read(&self) -> anyhow::Result<Self::Type> {
+        Ok(Self::Type {
+            port: required(&self.port)
+                .and_then(|p| Ok((*p).try_into()?))
+                .context("port")?,
+            polling_interval_ms: *required(&self.polling_interval_ms)
+                .context("polling_interval_ms")?,
+        })
+    }
+    fn build(this: &Self::Type) -> Self {
+        Self {
+            port: Some(this.port.into()),
+            polling_interval_ms: Some(this.polling_interval_ms),
+        }
+    }
+}
+
+impl ProtoRepr for proto::AdminApi {
+    type Type = api::AdminApiConfig;
+    fn read(&self) -> anyhow::Result<Self::Type> {
+        Ok(Self::Type {
+            port: required(&self.port)
+                .and_then(|p| Ok((*p).try_into()?))
+                .context("port")?,
+            auth_token: self.auth_token.clone(),
+        })
+    }
+    fn build(this: &Self::Type) -> Self {
+        Self {
+            port: Some(this.port.into()),
+            auth_token: this.auth_token.clone(),
+        }
+    }
+}
@@ -3,7 +3,10 @@ use zksync_basic_types::network::Network;
 use zksync_config::configs;
 use zksync_protobuf::required;
 
-use crate::{parse_h160, proto, repr::ProtoRepr};
+use crate::{
+    parse_h160, proto,
+    repr::{read_required_repr, ProtoRepr},
+};
 
 impl proto::Network {
     fn new(n: &Network) -> Self {
@@ -51,6 +54,24 @@ impl proto::FeeModelVersion {
     }
 }
 
+impl proto::TransactionOrdering {
+    fn new(n: &configs::chain::TransactionOrdering) -> Self {
+        use configs::chain::TransactionOrdering as From;
+        match n {
+            From::ArrivalTime => Self::ArrivalTime,
+            From::FeePriority => Self::FeePriority,
+        }
+    }
+
+    fn parse(&self) -> configs::chain::TransactionOrdering {
+        use configs::chain::TransactionOrdering as To;
+        match self {
+            Self::ArrivalTime => To::ArrivalTime,
+            Self::FeePriority => To::FeePriority,
+        }
+    }
+}
+
 impl ProtoRepr for proto::EthNetwork {
     type Type = configs::chain::NetworkConfig;
     fn read(&self) -> anyhow::Result<Self::Type> {
@@ -140,6 +161,16 @@ impl ProtoRepr for proto::StateKeeper {
                 .map(|x| x.try_into())
                 .transpose()
                 .context("enum_index_migration_chunk_size")?,
+            disabled_seal_criteria: self.disabled_seal_criteria.clone(),
+            transaction_ordering: self
+                .transaction_ordering
+                .map(proto::TransactionOrdering::try_from)
+                .transpose()
+                .context("transaction_ordering")?
+                .map(|ordering| ordering.parse())
+                .unwrap_or_default(),
+            max_l1_batches_not_committed: self.max_l1_batches_not_committed,
+            max_l1_batches_not_proven: self.max_l1_batches_not_proven,
         })
     }
 
@@ -176,6 +207,12 @@ impl ProtoRepr for proto::StateKeeper {
                 .enum_index_migration_chunk_size
                 .as_ref()
                 .map(|x| (*x).try_into().unwrap()),
+            disabled_seal_criteria: this.disabled_seal_criteria.clone(),
+            transaction_ordering: Some(
+                proto::TransactionOrdering::new(&this.transaction_ordering).into(),
+            ),
+            max_l1_batches_not_committed: this.max_l1_batches_not_committed,
+            max_l1_batches_not_proven: this.max_l1_batches_not_proven,
         }
     }
 }
@@ -207,6 +244,16 @@ impl ProtoRepr for proto::Mempool {
             stuck_tx_timeout: *required(&self.stuck_tx_timeout).context("stuck_tx_timeout")?,
             remove_stuck_txs: *required(&self.remove_stuck_txs).context("remove_stuck_txs")?,
             delay_interval: *required(&self.delay_interval).context("delay_interval")?,
+            max_transactions_per_account: self
+                .max_transactions_per_account
+                .map(|x| x.try_into())
+                .transpose()
+                .context("max_transactions_per_account")?,
+            max_memory_mb: self
+                .max_memory_mb
+                .map(|x| x.try_into())
+                .transpose()
+                .context("max_memory_mb")?,
         })
     }
 
@@ -218,6 +265,149 @@ impl ProtoRepr for proto::Mempool {
             stuck_tx_timeout: Some(this.stuck_tx_timeout),
             remove_stuck_txs: Some(this.remove_stuck_txs),
             delay_interval: Some(this.delay_interval),
+            max_transactions_per_account: this
+                .max_transactions_per_account
+                .map(|x| x.try_into().unwrap()),
+            max_memory_mb: this.max_memory_mb.map(|x| x.try_into().unwrap()),
+        }
+    }
+}
+
+impl proto::BaseTokenRatioSource {
+    fn new(n: &configs::chain::BaseTokenRatioSource) -> Self {
+        use configs::chain::BaseTokenRatioSource as From;
+        match n {
+            From::Fixed => Self::Fixed,
+            From::Http => Self::Http,
+        }
+    }
+
+    fn parse(&self) -> configs::chain::BaseTokenRatioSource {
+        use configs::chain::BaseTokenRatioSource as To;
+        match self {
+            Self::Fixed => To::Fixed,
+            Self::Http => To::Http,
+        }
+    }
+}
+
+impl ProtoRepr for proto::BaseToken {
+    type Type = configs::chain::BaseTokenConfig;
+    fn read(&self) -> anyhow::Result<Self::Type> {
+        Ok(Self::Type {
+            l1_address: self
+                .l1_address
+                .as_ref()
+                .map(|x| parse_h160(x))
+                .transpose()
+                .context("l1_address")?,
+            ratio_source: required(&self.ratio_source)
+                .and_then(|x| Ok(proto::BaseTokenRatioSource::try_from(*x)?))
+                .context("ratio_source")?
+                .parse(),
+            ratio_numerator: *required(&self.ratio_numerator).context("ratio_numerator")?,
+            ratio_denominator: *required(&self.ratio_denominator).context("ratio_denominator")?,
+            ratio_url: self.ratio_url.clone(),
+            ratio_poll_interval_ms: self.ratio_poll_interval_ms,
+        })
+    }
+
+    fn build(this: &Self::Type) -> Self {
+        Self {
+            l1_address: this.l1_address.as_ref().map(|x| x.as_bytes().into()),
+            ratio_source: Some(proto::BaseTokenRatioSource::new(&this.ratio_source).into()),
+            ratio_numerator: Some(this.ratio_numerator),
+            ratio_denominator: Some(this.ratio_denominator),
+            ratio_url: this.ratio_url.clone(),
+            ratio_poll_interval_ms: this.ratio_poll_interval_ms,
+        }
+    }
+}
+
+impl proto::FeeInputSource {
+    fn new(n: &configs::chain::FeeInputSource) -> Self {
+        use configs::chain::FeeInputSource as From;
+        match n {
+            From::Oracle => Self::Oracle,
+            From::Http => Self::HttpApi,
+            From::Fixed => Self::FixedValue,
+        }
+    }
+
+    fn parse(&self) -> configs::chain::FeeInputSource {
+        use configs::chain::FeeInputSource as To;
+        match self {
+            Self::Oracle => To::Oracle,
+            Self::HttpApi => To::Http,
+            Self::FixedValue => To::Fixed,
+        }
+    }
+}
+
+impl ProtoRepr for proto::FeeInputParam {
+    type Type = configs::chain::FeeInputParamConfig;
+    fn read(&self) -> anyhow::Result<Self::Type> {
+        Ok(Self::Type {
+            source: required(&self.source)
+                .and_then(|x| Ok(proto::FeeInputSource::try_from(*x)?))
+                .context("source")?
+                .parse(),
+            fixed_value: self.fixed_value,
+            url: self.url.clone(),
+            json_pointer: self.json_pointer.clone(),
+            multiplier: self.multiplier,
+            smoothing_alpha: self.smoothing_alpha,
+            min: self.min,
+            max: self.max,
+        })
+    }
+
+    fn build(this: &Self::Type) -> Self {
+        Self {
+            source: Some(proto::FeeInputSource::new(&this.source).into()),
+            fixed_value: this.fixed_value,
+            url: this.url.clone(),
+            json_pointer: this.json_pointer.clone(),
+            multiplier: this.multiplier,
+            smoothing_alpha: this.smoothing_alpha,
+            min: this.min,
+            max: this.max,
+        }
+    }
+}
+
+impl ProtoRepr for proto::FeeInput {
+    type Type = configs::chain::FeeInputConfig;
+    fn read(&self) -> anyhow::Result<Self::Type> {
+        let l1_gas_price = read_required_repr(&self.l1_gas_price).context("l1_gas_price")?;
+        let l1_pubdata_price =
+            read_required_repr(&self.l1_pubdata_price).context("l1_pubdata_price")?;
+        Ok(Self::Type {
+            l1_gas_price_source: l1_gas_price.source,
+            l1_gas_price_fixed_value: l1_gas_price.fixed_value,
+            l1_gas_price_url: l1_gas_price.url,
+            l1_gas_price_json_pointer: l1_gas_price.json_pointer,
+            l1_gas_price_multiplier: l1_gas_price.multiplier,
+            l1_gas_price_smoothing_alpha: l1_gas_price.smoothing_alpha,
+            l1_gas_price_min: l1_gas_price.min,
+            l1_gas_price_max: l1_gas_price.max,
+            l1_pubdata_price_source: l1_pubdata_price.source,
+            l1_pubdata_price_fixed_value: l1_pubdata_price.fixed_value,
+            l1_pubdata_price_url: l1_pubdata_price.url,
+            l1_pubdata_price_json_pointer: l1_pubdata_price.json_pointer,
+            l1_pubdata_price_multiplier: l1_pubdata_price.multiplier,
+            l1_pubdata_price_smoothing_alpha: l1_pubdata_price.smoothing_alpha,
+            l1_pubdata_price_min: l1_pubdata_price.min,
+            l1_pubdata_price_max: l1_pubdata_price.max,
+            poll_interval_ms: self.poll_interval_ms,
+        })
+    }
+
+    fn build(this: &Self::Type) -> Self {
+        Self {
+            l1_gas_price: Some(ProtoRepr::build(&this.l1_gas_price())),
+            l1_pubdata_price: Some(ProtoRepr::build(&this.l1_pubdata_price())),
+            poll_interval_ms: this.poll_interval_ms,
         }
     }
 }
@@ -25,6 +25,26 @@ impl proto::MerkleTreeMode {
     }
 }
 
+impl proto::RocksdbCompactionStyle {
+    fn new(x: &configs::database::RocksdbCompactionStyle) -> Self {
+        use configs::database::RocksdbCompactionStyle as From;
+        match x {
+            From::Level => Self::Level,
+            From::Universal => Self::Universal,
+            From::Fifo => Self::Fifo,
+        }
+    }
+
+    fn parse(&self) -> configs::database::RocksdbCompactionStyle {
+        use configs::database::RocksdbCompactionStyle as To;
+        match self {
+            Self::Level => To::Level,
+            Self::Universal => To::Universal,
+            Self::Fifo => To::Fifo,
+        }
+    }
+}
+
 impl ProtoRepr for proto::MerkleTree {
     type Type = configs::database::MerkleTreeConfig;
     fn read(&self) -> anyhow::Result<Self::Type> {
@@ -48,6 +68,31 @@ impl ProtoRepr for proto::MerkleTree {
             max_l1_batches_per_iter: required(&self.max_l1_batches_per_iter)
                 .and_then(|x| Ok((*x).try_into()?))
                 .context("max_l1_batches_per_iter")?,
+            pruning_retained_versions: self.pruning_retained_versions,
+            pruning_iteration_delay_ms: *required(&self.pruning_iteration_delay_ms)
+                .context("pruning_iteration_delay_ms")?,
+            thread_count: self
+                .thread_count
+                .map(|x| x.try_into())
+                .transpose()
+                .context("thread_count")?,
+            consistency_check_interval_sec: self.consistency_check_interval_sec,
+            compaction_style: self
+                .compaction_style
+                .map(|x| anyhow::Ok(proto::RocksdbCompactionStyle::try_from(x)?.parse()))
+                .transpose()
+                .context("compaction_style")?,
+            write_buffer_size_mb: self
+                .write_buffer_size_mb
+                .map(|x| x.try_into())
+                .transpose()
+                .context("write_buffer_size_mb")?,
+            max_background_jobs: self
+                .max_background_jobs
+                .map(|x| x.try_into())
+                .transpose()
+                .context("max_background_jobs")?,
+            rocksdb_statistics: self.rocksdb_statistics.unwrap_or_default(),
         })
     }
 
@@ -60,6 +105,17 @@ impl ProtoRepr for proto::MerkleTree {
             memtable_capacity_mb: Some(this.memtable_capacity_mb.try_into().unwrap()),
             stalled_writes_timeout_sec: Some(this.stalled_writes_timeout_sec),
             max_l1_batches_per_iter: Some(this.max_l1_batches_per_iter.try_into().unwrap()),
+            pruning_retained_versions: this.pruning_retained_versions,
+            pruning_iteration_delay_ms: Some(this.pruning_iteration_delay_ms),
+            thread_count: this.thread_count.map(|x| x.try_into().unwrap()),
+            consistency_check_interval_sec: this.consistency_check_interval_sec,
+            compaction_style: this
+                .compaction_style
+                .as_ref()
+                .map(|x| proto::RocksdbCompactionStyle::new(x).into()),
+            write_buffer_size_mb: this.write_buffer_size_mb.map(|x| x.try_into().unwrap()),
+            max_background_jobs: this.max_background_jobs.map(|x| x.try_into().unwrap()),
+            rocksdb_statistics: Some(this.rocksdb_statistics),
         }
     }
 }
@@ -71,6 +127,29 @@ impl ProtoRepr for proto::Db {
             state_keeper_db_path: required(&self.state_keeper_db_path)
                 .context("state_keeper_db_path")?
                 .clone(),
+            state_keeper_db_block_cache_size_mb: self
+                .state_keeper_db_block_cache_size_mb
+                .map(|x| x.try_into())
+                .transpose()
+                .context("state_keeper_db_block_cache_size_mb")?,
+            state_keeper_db_compaction_style: self
+                .state_keeper_db_compaction_style
+                .map(|x| anyhow::Ok(proto::RocksdbCompactionStyle::try_from(x)?.parse()))
+                .transpose()
+                .context("state_keeper_db_compaction_style")?,
+            state_keeper_db_write_buffer_size_mb: self
+                .state_keeper_db_write_buffer_size_mb
+                .map(|x| x.try_into())
+                .transpose()
+                .context("state_keeper_db_write_buffer_size_mb")?,
+            state_keeper_db_max_background_jobs: self
+                .state_keeper_db_max_background_jobs
+                .map(|x| x.try_into())
+                .transpose()
+                .context("state_keeper_db_max_background_jobs")?,
+            state_keeper_db_rocksdb_statistics: self
+                .state_keeper_db_rocksdb_statistics
+                .unwrap_or_default(),
             merkle_tree: read_required_repr(&self.merkle_tree).context("merkle_tree")?,
         })
     }
@@ -78,6 +157,20 @@ impl ProtoRepr for proto::Db {
     fn build(this: &Self::Type) -> Self {
         Self {
             state_keeper_db_path: Some(this.state_keeper_db_path.clone()),
+            state_keeper_db_block_cache_size_mb: this
+                .state_keeper_db_block_cache_size_mb
+                .map(|x| x.try_into().unwrap()),
+            state_keeper_db_compaction_style: this
+                .state_keeper_db_compaction_style
+                .as_ref()
+                .map(|x| proto::RocksdbCompactionStyle::new(x).into()),
+            state_keeper_db_write_buffer_size_mb: this
+                .state_keeper_db_write_buffer_size_mb
+                .map(|x| x.try_into().unwrap()),
+            state_keeper_db_max_background_jobs: this
+                .state_keeper_db_max_background_jobs
+                .map(|x| x.try_into().unwrap()),
+            state_keeper_db_rocksdb_statistics: Some(this.state_keeper_db_rocksdb_statistics),
             merkle_tree: Some(ProtoRepr::build(&this.merkle_tree)),
         }
     }
@@ -90,6 +183,8 @@ impl ProtoRepr for proto::Postgres {
         Ok(Self::Type {
             master_url: self.master_url.clone(),
             replica_url: self.replica_url.clone(),
+            read_replica_urls: self.read_replica_urls.clone(),
+            max_replica_lag_ms: self.max_replica_lag_ms,
             prover_url: self.prover_url.clone(),
             max_connections: self.max_connections,
             acquire_timeout_sec: self.acquire_timeout_sec,
@@ -103,6 +198,8 @@ impl ProtoRepr for proto::Postgres {
         Self {
             master_url: this.master_url.clone(),
             replica_url: this.replica_url.clone(),
+            read_replica_urls: this.read_replica_urls.clone(),
+            max_replica_lag_ms: this.max_replica_lag_ms,
             prover_url: this.prover_url.clone(),
             max_connections: this.max_connections,
             acquire_timeout_sec: this.acquire_timeout_sec,
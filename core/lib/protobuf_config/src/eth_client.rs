@@ -10,6 +10,10 @@ impl ProtoRepr for proto::EthClient {
         Ok(Self::Type {
             chain_id: *required(&self.chain_id).context("chain_id")?,
             web3_url: required(&self.web3_url).context("web3_url")?.clone(),
+            fallback_web3_urls: self.fallback_web3_urls.clone(),
+            failover_error_rate_threshold: self.failover_error_rate_threshold,
+            failover_max_head_lag: self.failover_max_head_lag,
+            failover_health_check_interval_ms: self.failover_health_check_interval_ms,
         })
     }
 
@@ -17,6 +21,10 @@ impl ProtoRepr for proto::EthClient {
         Self {
             chain_id: Some(this.chain_id),
             web3_url: Some(this.web3_url.clone()),
+            fallback_web3_urls: this.fallback_web3_urls.clone(),
+            failover_error_rate_threshold: this.failover_error_rate_threshold,
+            failover_max_head_lag: this.failover_max_head_lag,
+            failover_health_check_interval_ms: this.failover_health_check_interval_ms,
         }
     }
 }
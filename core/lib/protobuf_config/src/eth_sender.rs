@@ -45,12 +45,98 @@ impl proto::ProofLoadingMode {
     }
 }
 
+impl proto::PubdataSendingMode {
+    fn new(x: &configs::eth_sender::PubdataSendingMode) -> Self {
+        use configs::eth_sender::PubdataSendingMode as From;
+        match x {
+            From::Calldata => Self::Calldata,
+            From::Blobs => Self::Blobs,
+        }
+    }
+
+    fn parse(&self) -> configs::eth_sender::PubdataSendingMode {
+        use configs::eth_sender::PubdataSendingMode as To;
+        match self {
+            Self::Calldata => To::Calldata,
+            Self::Blobs => To::Blobs,
+        }
+    }
+}
+
+impl proto::OperatorSignerBackend {
+    fn new(x: &configs::eth_sender::OperatorSignerBackend) -> Self {
+        use configs::eth_sender::OperatorSignerBackend as From;
+        match x {
+            From::PrivateKey => Self::PrivateKey,
+            From::AwsKms => Self::AwsKms,
+            From::GcpKms => Self::GcpKms,
+            From::Pkcs11 => Self::Pkcs11,
+        }
+    }
+
+    fn parse(&self) -> configs::eth_sender::OperatorSignerBackend {
+        use configs::eth_sender::OperatorSignerBackend as To;
+        match self {
+            Self::PrivateKey => To::PrivateKey,
+            Self::AwsKms => To::AwsKms,
+            Self::GcpKms => To::GcpKms,
+            Self::Pkcs11 => To::Pkcs11,
+        }
+    }
+}
+
+impl proto::TxSubmissionMode {
+    fn new(x: &configs::eth_sender::TxSubmissionMode) -> Self {
+        use configs::eth_sender::TxSubmissionMode as From;
+        match x {
+            From::PublicMempool => Self::PublicMempool,
+            From::PrivateRpc => Self::PrivateRpc,
+            From::Bundle => Self::Bundle,
+        }
+    }
+
+    fn parse(&self) -> configs::eth_sender::TxSubmissionMode {
+        use configs::eth_sender::TxSubmissionMode as To;
+        match self {
+            Self::PublicMempool => To::PublicMempool,
+            Self::PrivateRpc => To::PrivateRpc,
+            Self::Bundle => To::Bundle,
+        }
+    }
+}
+
 impl ProtoRepr for proto::EthSender {
     type Type = configs::eth_sender::ETHSenderConfig;
     fn read(&self) -> anyhow::Result<Self::Type> {
         Ok(Self::Type {
             sender: read_required_repr(&self.sender).context("sender")?,
             gas_adjuster: read_required_repr(&self.gas_adjuster).context("gas_adjuster")?,
+            signer: self
+                .signer
+                .as_ref()
+                .map(ProtoRepr::read)
+                .transpose()
+                .context("signer")?
+                .unwrap_or_default(),
+            submission: self
+                .submission
+                .as_ref()
+                .map(ProtoRepr::read)
+                .transpose()
+                .context("submission")?
+                .unwrap_or_default(),
+            secondary_signer: self
+                .secondary_signer
+                .as_ref()
+                .map(ProtoRepr::read)
+                .transpose()
+                .context("secondary_signer")?,
+            blob_signer: self
+                .blob_signer
+                .as_ref()
+                .map(ProtoRepr::read)
+                .transpose()
+                .context("blob_signer")?,
         })
     }
 
@@ -58,6 +144,10 @@ impl ProtoRepr for proto::EthSender {
         Self {
             sender: Some(ProtoRepr::build(&this.sender)),
             gas_adjuster: Some(ProtoRepr::build(&this.gas_adjuster)),
+            signer: Some(ProtoRepr::build(&this.signer)),
+            submission: Some(ProtoRepr::build(&this.submission)),
+            secondary_signer: this.secondary_signer.as_ref().map(ProtoRepr::build),
+            blob_signer: this.blob_signer.as_ref().map(ProtoRepr::build),
         }
     }
 }
@@ -109,6 +199,17 @@ impl ProtoRepr for proto::Sender {
                 .and_then(|x| Ok(proto::ProofLoadingMode::try_from(*x)?))
                 .context("proof_loading_mode")?
                 .parse(),
+            pubdata_sending_mode: self
+                .pubdata_sending_mode
+                .map(proto::PubdataSendingMode::try_from)
+                .transpose()
+                .context("pubdata_sending_mode")?
+                .map(|mode| mode.parse())
+                .unwrap_or_default(),
+            max_tx_attempts: self.max_tx_attempts,
+            fee_bump_percent: self.fee_bump_percent,
+            max_fee_per_gas_cap: self.max_fee_per_gas_cap,
+            min_blocks_between_resends: self.min_blocks_between_resends,
         })
     }
 
@@ -137,6 +238,35 @@ impl ProtoRepr for proto::Sender {
             l1_batch_min_age_before_execute_seconds: this.l1_batch_min_age_before_execute_seconds,
             max_acceptable_priority_fee_in_gwei: Some(this.max_acceptable_priority_fee_in_gwei),
             proof_loading_mode: Some(proto::ProofLoadingMode::new(&this.proof_loading_mode).into()),
+            pubdata_sending_mode: Some(
+                proto::PubdataSendingMode::new(&this.pubdata_sending_mode).into(),
+            ),
+            max_tx_attempts: this.max_tx_attempts,
+            fee_bump_percent: this.fee_bump_percent,
+            max_fee_per_gas_cap: this.max_fee_per_gas_cap,
+            min_blocks_between_resends: this.min_blocks_between_resends,
+        }
+    }
+}
+
+impl proto::BaseFeeEstimationMode {
+    fn new(x: &configs::eth_sender::BaseFeeEstimationMode) -> Self {
+        use configs::eth_sender::BaseFeeEstimationMode as From;
+        match x {
+            From::Median => Self::Median,
+            From::Percentile => Self::Percentile,
+            From::Ewma => Self::Ewma,
+            From::ExternalApi => Self::ExternalApi,
+        }
+    }
+
+    fn parse(&self) -> configs::eth_sender::BaseFeeEstimationMode {
+        use configs::eth_sender::BaseFeeEstimationMode as To;
+        match self {
+            Self::Median => To::Median,
+            Self::Percentile => To::Percentile,
+            Self::Ewma => To::Ewma,
+            Self::ExternalApi => To::ExternalApi,
         }
     }
 }
@@ -159,6 +289,19 @@ impl ProtoRepr for proto::GasAdjuster {
             internal_enforced_l1_gas_price: self.internal_enforced_l1_gas_price,
             poll_period: *required(&self.poll_period).context("poll_period")?,
             max_l1_gas_price: self.max_l1_gas_price,
+            base_fee_estimation_mode: self
+                .base_fee_estimation_mode
+                .map(proto::BaseFeeEstimationMode::try_from)
+                .transpose()
+                .context("base_fee_estimation_mode")?
+                .map(|mode| mode.parse())
+                .unwrap_or_default(),
+            base_fee_percentile: self.base_fee_percentile,
+            base_fee_ewma_alpha: self.base_fee_ewma_alpha,
+            external_price_api_url: self.external_price_api_url.clone(),
+            external_price_api_json_pointer: self.external_price_api_json_pointer.clone(),
+            external_price_api_multiplier: self.external_price_api_multiplier,
+            external_price_api_max_age_sec: self.external_price_api_max_age_sec,
         })
     }
 
@@ -172,6 +315,73 @@ impl ProtoRepr for proto::GasAdjuster {
             internal_enforced_l1_gas_price: this.internal_enforced_l1_gas_price,
             poll_period: Some(this.poll_period),
             max_l1_gas_price: this.max_l1_gas_price,
+            base_fee_estimation_mode: Some(
+                proto::BaseFeeEstimationMode::new(&this.base_fee_estimation_mode).into(),
+            ),
+            base_fee_percentile: this.base_fee_percentile,
+            base_fee_ewma_alpha: this.base_fee_ewma_alpha,
+            external_price_api_url: this.external_price_api_url.clone(),
+            external_price_api_json_pointer: this.external_price_api_json_pointer.clone(),
+            external_price_api_multiplier: this.external_price_api_multiplier,
+            external_price_api_max_age_sec: this.external_price_api_max_age_sec,
+        }
+    }
+}
+
+impl ProtoRepr for proto::OperatorSigner {
+    type Type = configs::eth_sender::OperatorSignerConfig;
+    fn read(&self) -> anyhow::Result<Self::Type> {
+        Ok(Self::Type {
+            backend: self
+                .backend
+                .map(proto::OperatorSignerBackend::try_from)
+                .transpose()
+                .context("backend")?
+                .map(|backend| backend.parse())
+                .unwrap_or_default(),
+            aws_kms_key_id: self.aws_kms_key_id.clone(),
+            aws_region: self.aws_region.clone(),
+            gcp_kms_key_version: self.gcp_kms_key_version.clone(),
+            pkcs11_module_path: self.pkcs11_module_path.clone(),
+            pkcs11_slot_id: self.pkcs11_slot_id,
+            pkcs11_key_label: self.pkcs11_key_label.clone(),
+        })
+    }
+
+    fn build(this: &Self::Type) -> Self {
+        Self {
+            backend: Some(proto::OperatorSignerBackend::new(&this.backend).into()),
+            aws_kms_key_id: this.aws_kms_key_id.clone(),
+            aws_region: this.aws_region.clone(),
+            gcp_kms_key_version: this.gcp_kms_key_version.clone(),
+            pkcs11_module_path: this.pkcs11_module_path.clone(),
+            pkcs11_slot_id: this.pkcs11_slot_id,
+            pkcs11_key_label: this.pkcs11_key_label.clone(),
+        }
+    }
+}
+
+impl ProtoRepr for proto::TxSubmission {
+    type Type = configs::eth_sender::TxSubmissionConfig;
+    fn read(&self) -> anyhow::Result<Self::Type> {
+        Ok(Self::Type {
+            mode: self
+                .mode
+                .map(proto::TxSubmissionMode::try_from)
+                .transpose()
+                .context("mode")?
+                .map(|mode| mode.parse())
+                .unwrap_or_default(),
+            relay_url: self.relay_url.clone(),
+            bundle_target_blocks: self.bundle_target_blocks,
+        })
+    }
+
+    fn build(this: &Self::Type) -> Self {
+        Self {
+            mode: Some(proto::TxSubmissionMode::new(&this.mode).into()),
+            relay_url: this.relay_url.clone(),
+            bundle_target_blocks: this.bundle_target_blocks,
         }
     }
 }
@@ -22,6 +22,26 @@ impl proto::SetupLoadMode {
     }
 }
 
+impl proto::ProverBackend {
+    fn new(x: &configs::fri_prover::ProverBackend) -> Self {
+        use configs::fri_prover::ProverBackend as From;
+        match x {
+            From::Auto => Self::Auto,
+            From::Gpu => Self::Gpu,
+            From::Cpu => Self::Cpu,
+        }
+    }
+
+    fn parse(&self) -> configs::fri_prover::ProverBackend {
+        use configs::fri_prover::ProverBackend as To;
+        match self {
+            Self::Auto => To::Auto,
+            Self::Gpu => To::Gpu,
+            Self::Cpu => To::Cpu,
+        }
+    }
+}
+
 impl ProtoRepr for proto::FriProver {
     type Type = configs::FriProverConfig;
     fn read(&self) -> anyhow::Result<Self::Type> {
@@ -69,6 +89,12 @@ impl ProtoRepr for proto::FriProver {
                 .clone(),
             shall_save_to_public_bucket: *required(&self.shall_save_to_public_bucket)
                 .context("shall_save_to_public_bucket")?,
+            backend: self
+                .backend
+                .map(proto::ProverBackend::try_from)
+                .transpose()
+                .context("backend")?
+                .map_or_else(Default::default, |backend| backend.parse()),
         })
     }
 
@@ -93,6 +119,7 @@ impl ProtoRepr for proto::FriProver {
             witness_vector_receiver_port: Some(this.witness_vector_receiver_port.into()),
             zone_read_url: Some(this.zone_read_url.clone()),
             shall_save_to_public_bucket: Some(this.shall_save_to_public_bucket),
+            backend: Some(proto::ProverBackend::new(&this.backend).into()),
         }
     }
 }
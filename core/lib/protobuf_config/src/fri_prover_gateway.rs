@@ -19,6 +19,13 @@ impl ProtoRepr for proto::FriProverGateway {
                 .context("prometheus_pushgateway_url")?
                 .clone(),
             prometheus_push_interval_ms: self.prometheus_push_interval_ms,
+            proof_deadline_secs: self.proof_deadline_secs,
+            urgent_l1_batches: self.urgent_l1_batches.clone(),
+            capacity_api_port: self
+                .capacity_api_port
+                .map(|x| x.try_into())
+                .transpose()
+                .context("capacity_api_port")?,
         })
     }
 
@@ -29,6 +36,9 @@ impl ProtoRepr for proto::FriProverGateway {
             prometheus_listener_port: Some(this.prometheus_listener_port.into()),
             prometheus_pushgateway_url: Some(this.prometheus_pushgateway_url.clone()),
             prometheus_push_interval_ms: this.prometheus_push_interval_ms,
+            proof_deadline_secs: this.proof_deadline_secs,
+            urgent_l1_batches: this.urgent_l1_batches.clone(),
+            capacity_api_port: this.capacity_api_port.map(Into::into),
         }
     }
 }
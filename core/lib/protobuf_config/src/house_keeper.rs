@@ -50,6 +50,10 @@ impl ProtoRepr for proto::HouseKeeper {
                 &self.fri_proof_compressor_stats_reporting_interval_ms,
             )
             .context("fri_proof_compressor_stats_reporting_interval_ms")?,
+            prover_artifacts_retention_period_hours: self.prover_artifacts_retention_period_hours,
+            prover_artifacts_gc_interval_ms: self.prover_artifacts_gc_interval_ms,
+            prover_artifacts_gc_dry_run: self.prover_artifacts_gc_dry_run.unwrap_or(false),
+            fri_prover_heartbeat_timeout_ms: self.fri_prover_heartbeat_timeout_ms,
         })
     }
 
@@ -82,6 +86,10 @@ impl ProtoRepr for proto::HouseKeeper {
             fri_proof_compressor_stats_reporting_interval_ms: Some(
                 this.fri_proof_compressor_stats_reporting_interval_ms,
             ),
+            prover_artifacts_retention_period_hours: this.prover_artifacts_retention_period_hours,
+            prover_artifacts_gc_interval_ms: this.prover_artifacts_gc_interval_ms,
+            prover_artifacts_gc_dry_run: Some(this.prover_artifacts_gc_dry_run),
+            fri_prover_heartbeat_timeout_ms: this.fri_prover_heartbeat_timeout_ms,
         }
     }
 }
@@ -23,6 +23,7 @@ mod house_keeper;
 mod object_store;
 mod observability;
 mod proof_data_handler;
+mod shutdown;
 mod snapshots_creator;
 mod witness_generator;
 
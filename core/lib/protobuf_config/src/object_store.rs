@@ -1,9 +1,62 @@
 use anyhow::Context as _;
-use zksync_config::configs::object_store::{ObjectStoreConfig, ObjectStoreMode};
+use zksync_config::configs::object_store::{
+    ObjectStoreCompression, ObjectStoreConfig, ObjectStoreEncryption, ObjectStoreMode,
+    S3AddressingStyle,
+};
 use zksync_protobuf::required;
 
 use crate::{proto, repr::ProtoRepr};
 
+impl proto::ObjectStoreCompression {
+    fn new(x: &ObjectStoreCompression) -> Self {
+        match x {
+            ObjectStoreCompression::None => Self::None,
+            ObjectStoreCompression::Zstd => Self::Zstd,
+        }
+    }
+
+    fn parse(&self) -> ObjectStoreCompression {
+        match self {
+            Self::None => ObjectStoreCompression::None,
+            Self::Zstd => ObjectStoreCompression::Zstd,
+        }
+    }
+}
+
+impl proto::ObjectStoreEncryption {
+    fn new(x: &ObjectStoreEncryption) -> Self {
+        match x {
+            ObjectStoreEncryption::None => Self::NoEncryption,
+            ObjectStoreEncryption::LocalKey => Self::LocalKey,
+            ObjectStoreEncryption::AwsKms => Self::AwsKms,
+        }
+    }
+
+    fn parse(&self) -> ObjectStoreEncryption {
+        match self {
+            Self::NoEncryption => ObjectStoreEncryption::None,
+            Self::LocalKey => ObjectStoreEncryption::LocalKey,
+            Self::AwsKms => ObjectStoreEncryption::AwsKms,
+        }
+    }
+}
+
+impl proto::S3AddressingStyle {
+    fn new(x: &S3AddressingStyle) -> Self {
+        match x {
+            S3AddressingStyle::VirtualHosted => Self::VirtualHosted,
+            S3AddressingStyle::Path => Self::Path,
+        }
+    }
+
+    fn parse(&self) -> S3AddressingStyle {
+        match self {
+            Self::VirtualHosted => S3AddressingStyle::VirtualHosted,
+            Self::Path => S3AddressingStyle::Path,
+        }
+    }
+}
+
 impl ProtoRepr for proto::ObjectStore {
     type Type = ObjectStoreConfig;
 
@@ -37,6 +90,45 @@ impl ProtoRepr for proto::ObjectStore {
                     .context("file_backed_base_path")?
                     .clone(),
             },
+            proto::object_store::Mode::AzureBlobWithManagedIdentity(mode) => {
+                ObjectStoreMode::AzureBlobWithManagedIdentity {
+                    azure_account_name: required(&mode.azure_account_name)
+                        .context("azure_account_name")?
+                        .clone(),
+                    azure_container_name: required(&mode.azure_container_name)
+                        .context("azure_container_name")?
+                        .clone(),
+                }
+            }
+            proto::object_store::Mode::AzureBlobWithConnectionString(mode) => {
+                ObjectStoreMode::AzureBlobWithConnectionString {
+                    azure_container_name: required(&mode.azure_container_name)
+                        .context("azure_container_name")?
+                        .clone(),
+                    azure_connection_string: required(&mode.azure_connection_string)
+                        .context("azure_connection_string")?
+                        .clone(),
+                }
+            }
+            proto::object_store::Mode::S3(mode) => ObjectStoreMode::S3 {
+                s3_bucket: required(&mode.s3_bucket).context("s3_bucket")?.clone(),
+                s3_region: required(&mode.s3_region).context("s3_region")?.clone(),
+                s3_endpoint: mode.s3_endpoint.clone(),
+                s3_addressing_style: mode
+                    .s3_addressing_style
+                    .map(|x| Ok::<_, anyhow::Error>(proto::S3AddressingStyle::try_from(x)?.parse()))
+                    .transpose()
+                    .context("s3_addressing_style")?,
+                s3_access_key_id: mode.s3_access_key_id.clone(),
+                s3_secret_access_key: mode.s3_secret_access_key.clone(),
+            },
+            proto::object_store::Mode::HttpMirrorReadOnly(mode) => {
+                ObjectStoreMode::HttpMirrorReadOnly {
+                    http_mirror_base_url: required(&mode.http_mirror_base_url)
+                        .context("http_mirror_base_url")?
+                        .clone(),
+                }
+            }
         };
 
         Ok(Self::Type {
@@ -44,6 +136,32 @@ impl ProtoRepr for proto::ObjectStore {
             max_retries: required(&self.max_retries)
                 .and_then(|x| Ok((*x).try_into()?))
                 .context("max_retries")?,
+            compression: self
+                .compression
+                .map(|x| {
+                    Ok::<_, anyhow::Error>(proto::ObjectStoreCompression::try_from(x)?.parse())
+                })
+                .transpose()
+                .context("compression")?
+                .unwrap_or_default(),
+            compression_level: self.compression_level,
+            retry_initial_backoff_ms: self.retry_initial_backoff_ms,
+            retry_max_backoff_ms: self.retry_max_backoff_ms,
+            retry_budget: self.retry_budget,
+            circuit_breaker_failure_threshold: self.circuit_breaker_failure_threshold,
+            circuit_breaker_reset_timeout_ms: self.circuit_breaker_reset_timeout_ms,
+            encryption: self
+                .encryption
+                .map(|x| Ok::<_, anyhow::Error>(proto::ObjectStoreEncryption::try_from(x)?.parse()))
+                .transpose()
+                .context("encryption")?
+                .unwrap_or_default(),
+            encrypted_buckets: (!self.encrypted_buckets.is_empty())
+                .then(|| self.encrypted_buckets.clone()),
+            encryption_key: self.encryption_key.clone(),
+            encryption_kms_key_id: self.encryption_kms_key_id.clone(),
+            encryption_kms_region: self.encryption_kms_region.clone(),
+            encryption_strict: self.encryption_strict.unwrap_or(false),
         })
     }
 
@@ -75,11 +193,66 @@ impl ProtoRepr for proto::ObjectStore {
             } => proto::object_store::Mode::FileBacked(proto::object_store::FileBacked {
                 file_backed_base_path: Some(file_backed_base_path.clone()),
             }),
+            ObjectStoreMode::AzureBlobWithManagedIdentity {
+                azure_account_name,
+                azure_container_name,
+            } => proto::object_store::Mode::AzureBlobWithManagedIdentity(
+                proto::object_store::AzureBlobWithManagedIdentity {
+                    azure_account_name: Some(azure_account_name.clone()),
+                    azure_container_name: Some(azure_container_name.clone()),
+                },
+            ),
+            ObjectStoreMode::AzureBlobWithConnectionString {
+                azure_container_name,
+                azure_connection_string,
+            } => proto::object_store::Mode::AzureBlobWithConnectionString(
+                proto::object_store::AzureBlobWithConnectionString {
+                    azure_container_name: Some(azure_container_name.clone()),
+                    azure_connection_string: Some(azure_connection_string.clone()),
+                },
+            ),
+            ObjectStoreMode::S3 {
+                s3_bucket,
+                s3_region,
+                s3_endpoint,
+                s3_addressing_style,
+                s3_access_key_id,
+                s3_secret_access_key,
+            } => proto::object_store::Mode::S3(proto::object_store::S3 {
+                s3_bucket: Some(s3_bucket.clone()),
+                s3_region: Some(s3_region.clone()),
+                s3_endpoint: s3_endpoint.clone(),
+                s3_addressing_style: s3_addressing_style
+                    .as_ref()
+                    .map(|x| proto::S3AddressingStyle::new(x).into()),
+                s3_access_key_id: s3_access_key_id.clone(),
+                s3_secret_access_key: s3_secret_access_key.clone(),
+            }),
+            ObjectStoreMode::HttpMirrorReadOnly {
+                http_mirror_base_url,
+            } => proto::object_store::Mode::HttpMirrorReadOnly(
+                proto::object_store::HttpMirrorReadOnly {
+                    http_mirror_base_url: Some(http_mirror_base_url.clone()),
+                },
+            ),
         };
 
         Self {
             mode: Some(mode),
             max_retries: Some(this.max_retries.into()),
+            compression: Some(proto::ObjectStoreCompression::new(&this.compression).into()),
+            compression_level: this.compression_level,
+            retry_initial_backoff_ms: this.retry_initial_backoff_ms,
+            retry_max_backoff_ms: this.retry_max_backoff_ms,
+            retry_budget: this.retry_budget,
+            circuit_breaker_failure_threshold: this.circuit_breaker_failure_threshold,
+            circuit_breaker_reset_timeout_ms: this.circuit_breaker_reset_timeout_ms,
+            encryption: Some(proto::ObjectStoreEncryption::new(&this.encryption).into()),
+            encrypted_buckets: this.encrypted_buckets.clone().unwrap_or_default(),
+            encryption_key: this.encryption_key.clone(),
+            encryption_kms_key_id: this.encryption_kms_key_id.clone(),
+            encryption_kms_region: this.encryption_kms_region.clone(),
+            encryption_strict: Some(this.encryption_strict),
         }
     }
 }
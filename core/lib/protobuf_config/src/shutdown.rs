@@ -0,0 +1,18 @@
+use zksync_config::configs;
+
+use crate::{proto, repr::ProtoRepr};
+
+impl ProtoRepr for proto::Shutdown {
+    type Type = configs::ShutdownConfig;
+    fn read(&self) -> anyhow::Result<Self::Type> {
+        Ok(Self::Type {
+            timeout_ms: self.timeout_ms,
+        })
+    }
+
+    fn build(this: &Self::Type) -> Self {
+        Self {
+            timeout_ms: this.timeout_ms,
+        }
+    }
+}
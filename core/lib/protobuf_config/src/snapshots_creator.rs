@@ -12,6 +12,10 @@ impl ProtoRepr for proto::SnapshotsCreator {
                 .context("storage_logs_chunk_size")?,
             concurrent_queries_count: *required(&self.concurrent_queries_count)
                 .context("concurrent_queries_count")?,
+            schedule: self.schedule.clone(),
+            incremental: self.incremental.unwrap_or(false),
+            max_incremental_chain_length: self.max_incremental_chain_length,
+            verify_reused_chunks: self.verify_reused_chunks,
         })
     }
 
@@ -19,6 +23,10 @@ impl ProtoRepr for proto::SnapshotsCreator {
         Self {
             storage_logs_chunk_size: Some(this.storage_logs_chunk_size),
             concurrent_queries_count: Some(this.concurrent_queries_count),
+            schedule: this.schedule.clone(),
+            incremental: Some(this.incremental),
+            max_incremental_chain_length: this.max_incremental_chain_length,
+            verify_reused_chunks: this.verify_reused_chunks,
         }
     }
 }
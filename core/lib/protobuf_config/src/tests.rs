@@ -65,6 +65,8 @@ fn test_encoding() {
     encode_decode::<proto::StateKeeper>(rng);
     encode_decode::<proto::OperationsManager>(rng);
     encode_decode::<proto::Mempool>(rng);
+    encode_decode::<proto::BaseToken>(rng);
+    encode_decode::<proto::FeeInput>(rng);
     encode_decode::<proto::CircuitBreaker>(rng);
     encode_decode::<proto::ContractVerifier>(rng);
     encode_decode::<proto::Contracts>(rng);
@@ -75,6 +77,8 @@ fn test_encoding() {
     encode_decode::<proto::EthSender>(rng);
     encode_decode::<proto::Sender>(rng);
     encode_decode::<proto::GasAdjuster>(rng);
+    encode_decode::<proto::OperatorSigner>(rng);
+    encode_decode::<proto::TxSubmission>(rng);
     encode_decode::<proto::EthWatch>(rng);
     encode_decode::<proto::FriProofCompressor>(rng);
     encode_decode::<proto::FriProofCompressor>(rng);
@@ -88,5 +92,6 @@ fn test_encoding() {
     encode_decode::<proto::ObjectStore>(rng);
     encode_decode::<proto::ProofDataHandler>(rng);
     encode_decode::<proto::SnapshotsCreator>(rng);
+    encode_decode::<proto::Shutdown>(rng);
     encode_decode::<proto::WitnessGenerator>(rng);
 }
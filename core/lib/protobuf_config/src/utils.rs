@@ -15,6 +15,8 @@ impl ProtoRepr for proto::Prometheus {
                 .context("pushgateway_url")?
                 .clone(),
             push_interval_ms: self.push_interval_ms,
+            pushgateway_job: self.pushgateway_job.clone(),
+            pushgateway_labels: self.pushgateway_labels.clone(),
         })
     }
 
@@ -23,6 +25,8 @@ impl ProtoRepr for proto::Prometheus {
             listener_port: Some(this.listener_port.into()),
             pushgateway_url: Some(this.pushgateway_url.clone()),
             push_interval_ms: this.push_interval_ms,
+            pushgateway_job: this.pushgateway_job.clone(),
+            pushgateway_labels: this.pushgateway_labels.clone(),
         }
     }
 }
@@ -18,6 +18,12 @@ struct JobProcessorMetrics {
     max_attempts_reached: LabeledFamily<(&'static str, String), Counter, 2>,
     #[metrics(labels = ["service_name"], buckets = ATTEMPT_BUCKETS)]
     attempts: LabeledFamily<&'static str, Histogram<usize>>,
+    /// Number of jobs preempted in favor of higher-priority jobs.
+    #[metrics(labels = ["service_name"])]
+    preempted_jobs: LabeledFamily<&'static str, Counter>,
+    /// Number of jobs dropped because they were reassigned while being processed.
+    #[metrics(labels = ["service_name"])]
+    lost_jobs: LabeledFamily<&'static str, Counter>,
 }
 
 #[vise::register]
@@ -32,6 +38,10 @@ pub trait JobProcessor: Sync + Send {
     const POLLING_INTERVAL_MS: u64 = 1000;
     const MAX_BACKOFF_MS: u64 = 60_000;
     const BACKOFF_MULTIPLIER: u64 = 2;
+    /// Interval between checks whether the job being processed should be preempted (see [`Self::should_preempt()`]).
+    const PREEMPTION_CHECK_INTERVAL_MS: u64 = 10_000;
+    /// Interval between heartbeats for the job being processed (see [`Self::send_heartbeat()`]).
+    const HEARTBEAT_INTERVAL_MS: u64 = 10_000;
     const SERVICE_NAME: &'static str;
 
     /// Returns None when there is no pending job
@@ -119,6 +129,10 @@ pub trait JobProcessor: Sync + Send {
             );
         }
 
+        let preemption_check_interval = Duration::from_millis(Self::PREEMPTION_CHECK_INTERVAL_MS);
+        let mut last_preemption_check = Instant::now();
+        let heartbeat_interval = Duration::from_millis(Self::HEARTBEAT_INTERVAL_MS);
+        let mut last_heartbeat = None::<Instant>;
         let result = loop {
             tracing::trace!(
                 "Polling {} task with id {:?}. Is finished: {}",
@@ -129,6 +143,50 @@ pub trait JobProcessor: Sync + Send {
             if task.is_finished() {
                 break task.await;
             }
+            if last_heartbeat.map_or(true, |sent_at| sent_at.elapsed() >= heartbeat_interval) {
+                last_heartbeat = Some(Instant::now());
+                match self.send_heartbeat(&job_id).await {
+                    Ok(true) => { /* The job is still assigned to this worker */ }
+                    Ok(false) => {
+                        tracing::warn!(
+                            "{} job {:?} is no longer assigned to this worker; dropping it",
+                            Self::SERVICE_NAME,
+                            job_id
+                        );
+                        task.abort();
+                        METRICS.lost_jobs[&Self::SERVICE_NAME].inc();
+                        return Ok(());
+                    }
+                    Err(err) => {
+                        // Heartbeats are best-effort; a missed heartbeat may only lead to the job being retried.
+                        tracing::warn!(
+                            "Failed sending heartbeat for {} job {:?}: {err:#}",
+                            Self::SERVICE_NAME,
+                            job_id
+                        );
+                    }
+                }
+            }
+            if last_preemption_check.elapsed() >= preemption_check_interval {
+                last_preemption_check = Instant::now();
+                if self
+                    .should_preempt(&job_id)
+                    .await
+                    .context("should_preempt()")?
+                {
+                    tracing::info!(
+                        "Preempting {} job {:?} in favor of a higher-priority job",
+                        Self::SERVICE_NAME,
+                        job_id
+                    );
+                    task.abort();
+                    METRICS.preempted_jobs[&Self::SERVICE_NAME].inc();
+                    return self
+                        .save_preempted(job_id)
+                        .await
+                        .context("save_preempted()");
+                }
+            }
             sleep(Duration::from_millis(Self::POLLING_INTERVAL_MS)).await;
         };
         let error_message = match result {
@@ -170,4 +228,29 @@ pub trait JobProcessor: Sync + Send {
 
     /// Invoked in `wait_for_task` for in-progress job.
     async fn get_job_attempts(&self, job_id: &Self::JobId) -> anyhow::Result<u32>;
+
+    /// Sends a heartbeat for the in-progress job, so that it's not considered abandoned. Invoked in `wait_for_task`
+    /// every [`Self::HEARTBEAT_INTERVAL_MS`]. Should return `false` if the job is no longer assigned to this worker
+    /// (e.g., it was returned to the queue after missed heartbeats); in this case, processing the job is stopped
+    /// and its result is discarded. By default, heartbeats are not sent.
+    async fn send_heartbeat(&self, _job_id: &Self::JobId) -> anyhow::Result<bool> {
+        Ok(true)
+    }
+
+    /// Checks whether the in-progress job should be preempted, e.g. in favor of a higher-priority job.
+    /// Invoked in `wait_for_task` every [`Self::PREEMPTION_CHECK_INTERVAL_MS`]. By default, jobs are never preempted.
+    async fn should_preempt(&self, _job_id: &Self::JobId) -> anyhow::Result<bool> {
+        Ok(false)
+    }
+
+    /// Invoked when the job is preempted. Should return the job to the queue.
+    ///
+    /// Note that the processing task is aborted, but for tasks spawned with [`tokio::task::spawn_blocking()`]
+    /// this only means that their result is discarded.
+    async fn save_preempted(&self, job_id: Self::JobId) -> anyhow::Result<()> {
+        anyhow::bail!(
+            "{} job {job_id:?} was preempted, but preemption is not supported",
+            Self::SERVICE_NAME
+        )
+    }
 }
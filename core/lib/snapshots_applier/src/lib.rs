@@ -8,15 +8,19 @@ use serde::Serialize;
 use tokio::sync::Semaphore;
 use zksync_dal::{ConnectionPool, SqlxError, StorageProcessor};
 use zksync_health_check::{Health, HealthStatus, HealthUpdater, ReactiveHealthCheck};
+use zksync_l1_contract_interface::{i_executor::structures::StoredBatchInfo, Tokenizable};
 use zksync_object_store::{ObjectStore, ObjectStoreError};
 use zksync_types::{
     api::en::SyncBlock,
+    commitment::L1BatchWithMetadata,
+    ethabi,
     snapshots::{
-        SnapshotFactoryDependencies, SnapshotHeader, SnapshotRecoveryStatus, SnapshotStorageLog,
-        SnapshotStorageLogsChunk, SnapshotStorageLogsStorageKey,
+        snapshot_file_checksum, SnapshotFactoryDependencies, SnapshotHeader,
+        SnapshotRecoveryStatus, SnapshotStorageLog, SnapshotStorageLogsChunk,
+        SnapshotStorageLogsStorageKey, SnapshotTokens, SnapshotVersion,
     },
     tokens::TokenInfo,
-    web3::futures,
+    web3::{futures, signing::keccak256},
     L1BatchNumber, MiniblockNumber, H256,
 };
 use zksync_utils::bytecode::hash_bytecode;
@@ -34,6 +38,7 @@ mod tests;
 
 #[derive(Debug, Serialize)]
 struct SnapshotsApplierHealthDetails {
+    snapshot_version: Option<u16>,
     snapshot_miniblock: MiniblockNumber,
     snapshot_l1_batch: L1BatchNumber,
     factory_deps_recovered: bool,
@@ -98,6 +103,11 @@ pub trait SnapshotsApplierMainNodeClient: fmt::Debug + Send + Sync {
 
     async fn fetch_newest_snapshot(&self) -> EnrichedClientResult<Option<SnapshotHeader>>;
 
+    async fn fetch_snapshot(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> EnrichedClientResult<Option<SnapshotHeader>>;
+
     async fn fetch_tokens(
         &self,
         at_miniblock: MiniblockNumber,
@@ -124,9 +134,16 @@ impl SnapshotsApplierMainNodeClient for HttpClient {
         let Some(newest_snapshot) = snapshots.snapshots_l1_batch_numbers.first() else {
             return Ok(None);
         };
-        self.get_snapshot_by_l1_batch_number(*newest_snapshot)
+        self.fetch_snapshot(*newest_snapshot).await
+    }
+
+    async fn fetch_snapshot(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> EnrichedClientResult<Option<SnapshotHeader>> {
+        self.get_snapshot_by_l1_batch_number(l1_batch_number)
             .rpc_context("get_snapshot_by_l1_batch_number")
-            .with_arg("number", newest_snapshot)
+            .with_arg("number", &l1_batch_number)
             .await
     }
 
@@ -141,12 +158,24 @@ impl SnapshotsApplierMainNodeClient for HttpClient {
     }
 }
 
+/// L1 API used by the [`SnapshotsApplier`] to verify the snapshot L1 batch against commitments on L1.
+#[async_trait]
+pub trait SnapshotsApplierL1Client: fmt::Debug + Send + Sync {
+    /// Returns the hash of `StoredBatchInfo` persisted by the diamond proxy for the specified L1 batch,
+    /// or `None` if the batch is not committed on L1 yet.
+    async fn fetch_stored_batch_hash(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<Option<H256>>;
+}
+
 /// Snapshot applier configuration options.
 #[derive(Debug)]
 pub struct SnapshotsApplierConfig {
     pub retry_count: usize,
     pub initial_retry_backoff: Duration,
     pub retry_backoff_multiplier: f32,
+    l1_client: Option<Box<dyn SnapshotsApplierL1Client>>,
     health_updater: HealthUpdater,
 }
 
@@ -156,6 +185,7 @@ impl Default for SnapshotsApplierConfig {
             retry_count: 5,
             initial_retry_backoff: Duration::from_secs(2),
             retry_backoff_multiplier: 2.0,
+            l1_client: None,
             health_updater: ReactiveHealthCheck::new("snapshot_recovery").1,
         }
     }
@@ -170,6 +200,14 @@ impl SnapshotsApplierConfig {
         }
     }
 
+    /// Enables verifying the snapshot L1 batch against the batch commitment stored on L1. If the snapshot batch
+    /// is not committed yet, recovery is retried; if the commitment doesn't match, recovery fails.
+    #[must_use]
+    pub fn with_l1_client(mut self, l1_client: impl SnapshotsApplierL1Client + 'static) -> Self {
+        self.l1_client = Some(Box::new(l1_client));
+        self
+    }
+
     /// Returns the health check for snapshot recovery.
     pub fn health_check(&self) -> ReactiveHealthCheck {
         self.health_updater.subscribe()
@@ -184,6 +222,7 @@ impl SnapshotsApplierConfig {
     ///
     /// - There are no snapshots on the main node
     /// - Storage contains at least one L1 batch
+    /// - The snapshot L1 batch doesn't match its commitment on L1 (if an L1 client is provided)
     pub async fn run(
         self,
         connection_pool: &ConnectionPool,
@@ -196,6 +235,7 @@ impl SnapshotsApplierConfig {
             let result = SnapshotsApplier::load_snapshot(
                 connection_pool,
                 main_node_client,
+                self.l1_client.as_deref(),
                 blob_store,
                 &self.health_updater,
             )
@@ -231,6 +271,152 @@ impl SnapshotsApplierConfig {
     }
 }
 
+/// Snapshot header together with its parsed version.
+#[derive(Debug)]
+struct CheckedSnapshotHeader {
+    version: SnapshotVersion,
+    inner: SnapshotHeader,
+}
+
+impl CheckedSnapshotHeader {
+    fn new(header: SnapshotHeader) -> Result<Self, SnapshotsApplierError> {
+        let version = SnapshotVersion::try_from(header.version).map_err(|_| {
+            let err = anyhow::anyhow!(
+                "snapshot for L1 batch #{} has unsupported version {}",
+                header.l1_batch_number,
+                header.version
+            );
+            SnapshotsApplierError::Fatal(err)
+        })?;
+
+        for chunk in &header.storage_logs_chunks {
+            let Some(source_l1_batch_number) = chunk.l1_batch_number else {
+                continue;
+            };
+            if !version.supports_reused_chunks() {
+                let err = anyhow::anyhow!(
+                    "snapshot for L1 batch #{} has version {version:?}, which doesn't support reused \
+                     storage logs chunks, but chunk {} is reused",
+                    header.l1_batch_number,
+                    chunk.chunk_id
+                );
+                return Err(SnapshotsApplierError::Fatal(err));
+            }
+            if source_l1_batch_number >= header.l1_batch_number {
+                let err = anyhow::anyhow!(
+                    "storage logs chunk {} of snapshot for L1 batch #{} is reused from a snapshot \
+                     for L1 batch #{source_l1_batch_number}, which is not older than the snapshot",
+                    chunk.chunk_id,
+                    header.l1_batch_number
+                );
+                return Err(SnapshotsApplierError::Fatal(err));
+            }
+        }
+
+        Ok(Self {
+            version,
+            inner: header,
+        })
+    }
+
+    /// Returns the L1 batch number used to fetch the storage logs chunk from the object store. For chunks reused
+    /// by incremental snapshots, this is the L1 batch of the snapshot that has produced the chunk.
+    fn storage_logs_chunk_l1_batch(&self, chunk_id: u64) -> L1BatchNumber {
+        self.inner
+            .storage_logs_chunks
+            .iter()
+            .find(|chunk| chunk.chunk_id == chunk_id)
+            .and_then(|chunk| chunk.l1_batch_number)
+            .unwrap_or(self.inner.l1_batch_number)
+    }
+
+    fn storage_logs_chunk_checksum(&self, chunk_id: u64) -> Option<H256> {
+        self.inner
+            .storage_logs_chunks
+            .iter()
+            .find(|chunk| chunk.chunk_id == chunk_id)
+            .and_then(|chunk| chunk.checksum)
+    }
+
+    /// Checks that the checksum of a snapshot file matches the `expected_checksum` from the header. Snapshot versions
+    /// without checksums are not checked if a checksum is not provided.
+    fn verify_checksum(
+        &self,
+        actual_checksum: impl FnOnce() -> H256,
+        expected_checksum: Option<H256>,
+        file_description: &str,
+    ) -> Result<(), SnapshotsApplierError> {
+        let Some(expected_checksum) = expected_checksum else {
+            if self.version.has_tokens_and_checksums() {
+                let err = anyhow::anyhow!(
+                    "snapshot header doesn't contain a checksum for {file_description}"
+                );
+                return Err(SnapshotsApplierError::Fatal(err));
+            }
+            return Ok(());
+        };
+
+        let actual_checksum = actual_checksum();
+        if actual_checksum != expected_checksum {
+            let err = anyhow::anyhow!(
+                "checksum mismatch for {file_description}: expected {expected_checksum:?}, \
+                 got {actual_checksum:?}"
+            );
+            return Err(SnapshotsApplierError::Fatal(err));
+        }
+        Ok(())
+    }
+
+    /// Verifies the snapshot L1 batch against its commitment on L1. Since `StoredBatchInfo` includes the state root hash
+    /// of the batch, this transitively verifies the root hash used to check the recovered Merkle tree.
+    async fn verify_l1_commitment(
+        &self,
+        l1_client: &dyn SnapshotsApplierL1Client,
+    ) -> Result<(), SnapshotsApplierError> {
+        let l1_batch_number = self.inner.l1_batch_number;
+        let l1_batch = &self.inner.last_l1_batch_with_metadata;
+        if l1_batch.header.number != l1_batch_number {
+            let err = anyhow::anyhow!(
+                "snapshot for L1 batch #{l1_batch_number} contains metadata for L1 batch #{}",
+                l1_batch.header.number
+            );
+            return Err(SnapshotsApplierError::Fatal(err));
+        }
+
+        let stored_batch_hash = l1_client
+            .fetch_stored_batch_hash(l1_batch_number)
+            .await
+            .map_err(|err| {
+                SnapshotsApplierError::Retryable(
+                    err.context("failed fetching stored batch hash from L1"),
+                )
+            })?;
+        let Some(stored_batch_hash) = stored_batch_hash else {
+            let err =
+                anyhow::anyhow!("snapshot L1 batch #{l1_batch_number} is not committed on L1 yet");
+            return Err(SnapshotsApplierError::Retryable(err));
+        };
+        let expected_hash = stored_batch_info_hash(l1_batch);
+        if stored_batch_hash != expected_hash {
+            let err = anyhow::anyhow!(
+                "L1 commitment mismatch for snapshot L1 batch #{l1_batch_number}: stored batch hash on L1 is \
+                 {stored_batch_hash:?}, while snapshot metadata corresponds to {expected_hash:?}"
+            );
+            return Err(SnapshotsApplierError::Fatal(err));
+        }
+        tracing::info!(
+            "Verified snapshot L1 batch #{l1_batch_number} against its commitment on L1"
+        );
+        Ok(())
+    }
+}
+
+/// Computes the hash of `StoredBatchInfo` for the batch in the same way as the diamond proxy does.
+fn stored_batch_info_hash(l1_batch: &L1BatchWithMetadata) -> H256 {
+    let encoded = ethabi::encode(&[StoredBatchInfo(l1_batch).into_token()]);
+    H256(keccak256(&encoded))
+}
+
 /// Applying application-level storage snapshots to the Postgres storage.
 #[derive(Debug)]
 struct SnapshotsApplier<'a> {
@@ -238,6 +424,9 @@ struct SnapshotsApplier<'a> {
     main_node_client: &'a dyn SnapshotsApplierMainNodeClient,
     blob_store: &'a dyn ObjectStore,
     applied_snapshot_status: SnapshotRecoveryStatus,
+    /// Header of the applied snapshot. Fetched from the main node lazily when recovery is resumed, so that
+    /// a node that has fully recovered doesn't depend on the snapshot being available on the main node.
+    snapshot_header: Option<CheckedSnapshotHeader>,
     health_updater: &'a HealthUpdater,
     factory_deps_recovered: bool,
     tokens_recovered: bool,
@@ -248,7 +437,9 @@ impl<'a> SnapshotsApplier<'a> {
     async fn prepare_applied_snapshot_status(
         storage: &mut StorageProcessor<'_>,
         main_node_client: &dyn SnapshotsApplierMainNodeClient,
-    ) -> Result<(SnapshotRecoveryStatus, bool), SnapshotsApplierError> {
+        l1_client: Option<&dyn SnapshotsApplierL1Client>,
+    ) -> Result<(SnapshotRecoveryStatus, Option<CheckedSnapshotHeader>), SnapshotsApplierError>
+    {
         let latency =
             METRICS.initial_stage_duration[&InitialStage::FetchMetadataFromMainNode].start();
 
@@ -264,7 +455,7 @@ impl<'a> SnapshotsApplier<'a> {
             let latency = latency.observe();
             tracing::info!("Re-initialized snapshots applier after reset/failure in {latency:?}");
 
-            Ok((applied_snapshot_status, false))
+            Ok((applied_snapshot_status, None))
         } else {
             let is_genesis_needed =
                 storage
@@ -281,18 +472,18 @@ impl<'a> SnapshotsApplier<'a> {
                 return Err(SnapshotsApplierError::Fatal(err));
             }
 
+            let (status, header) =
+                SnapshotsApplier::create_fresh_recovery_status(main_node_client, l1_client).await?;
             let latency = latency.observe();
             tracing::info!("Initialized fresh snapshots applier in {latency:?}");
-            Ok((
-                SnapshotsApplier::create_fresh_recovery_status(main_node_client).await?,
-                true,
-            ))
+            Ok((status, Some(header)))
         }
     }
 
     async fn load_snapshot(
         connection_pool: &'a ConnectionPool,
         main_node_client: &'a dyn SnapshotsApplierMainNodeClient,
+        l1_client: Option<&dyn SnapshotsApplierL1Client>,
         blob_store: &'a dyn ObjectStore,
         health_updater: &'a HealthUpdater,
     ) -> Result<(), SnapshotsApplierError> {
@@ -305,15 +496,20 @@ impl<'a> SnapshotsApplier<'a> {
             SnapshotsApplierError::db(err, "failed starting initial DB transaction")
         })?;
 
-        let (applied_snapshot_status, created_from_scratch) =
-            Self::prepare_applied_snapshot_status(&mut storage_transaction, main_node_client)
-                .await?;
+        let (applied_snapshot_status, snapshot_header) = Self::prepare_applied_snapshot_status(
+            &mut storage_transaction,
+            main_node_client,
+            l1_client,
+        )
+        .await?;
+        let created_from_scratch = snapshot_header.is_some();
 
         let mut this = Self {
             connection_pool,
             main_node_client,
             blob_store,
             applied_snapshot_status,
+            snapshot_header,
             health_updater,
             factory_deps_recovered: !created_from_scratch,
             tokens_recovered: false,
@@ -347,6 +543,14 @@ impl<'a> SnapshotsApplier<'a> {
         this.factory_deps_recovered = true;
         this.update_health();
 
+        if this
+            .applied_snapshot_status
+            .storage_logs_chunks_left_to_process()
+            > 0
+        {
+            this.ensure_snapshot_header().await?;
+            this.update_health();
+        }
         this.recover_storage_logs().await?;
         this.recover_tokens().await?;
         this.tokens_recovered = true;
@@ -357,17 +561,24 @@ impl<'a> SnapshotsApplier<'a> {
 
     async fn create_fresh_recovery_status(
         main_node_client: &dyn SnapshotsApplierMainNodeClient,
-    ) -> Result<SnapshotRecoveryStatus, SnapshotsApplierError> {
+        l1_client: Option<&dyn SnapshotsApplierL1Client>,
+    ) -> Result<(SnapshotRecoveryStatus, CheckedSnapshotHeader), SnapshotsApplierError> {
         let snapshot_response = main_node_client.fetch_newest_snapshot().await?;
 
         let snapshot = snapshot_response
             .context("no snapshots on main node; snapshot recovery is impossible")?;
+        let checked_snapshot = CheckedSnapshotHeader::new(snapshot)?;
+        let version = checked_snapshot.version;
+        let snapshot = &checked_snapshot.inner;
         let l1_batch_number = snapshot.l1_batch_number;
         let miniblock_number = snapshot.miniblock_number;
         tracing::info!(
-            "Found snapshot with data up to L1 batch #{l1_batch_number}, storage_logs are divided into {} chunk(s)",
+            "Found snapshot ({version:?}) with data up to L1 batch #{l1_batch_number}, storage_logs are divided into {} chunk(s)",
             snapshot.storage_logs_chunks.len()
         );
+        if let Some(l1_client) = l1_client {
+            checked_snapshot.verify_l1_commitment(l1_client).await?;
+        }
 
         let miniblock = main_node_client
             .fetch_l2_block(miniblock_number)
@@ -377,7 +588,7 @@ impl<'a> SnapshotsApplier<'a> {
             .hash
             .context("snapshot miniblock fetched from main node doesn't have hash set")?;
 
-        Ok(SnapshotRecoveryStatus {
+        let status = SnapshotRecoveryStatus {
             l1_batch_number,
             l1_batch_timestamp: snapshot.last_l1_batch_with_metadata.header.timestamp,
             l1_batch_root_hash: snapshot.last_l1_batch_with_metadata.metadata.root_hash,
@@ -390,11 +601,45 @@ impl<'a> SnapshotsApplier<'a> {
                 .protocol_version
                 .unwrap(),
             storage_logs_chunks_processed: vec![false; snapshot.storage_logs_chunks.len()],
-        })
+        };
+        Ok((status, checked_snapshot))
+    }
+
+    /// Fetches the header of the applied snapshot from the main node if it's not fetched yet.
+    async fn ensure_snapshot_header(
+        &mut self,
+    ) -> Result<&CheckedSnapshotHeader, SnapshotsApplierError> {
+        if self.snapshot_header.is_none() {
+            let l1_batch_number = self.applied_snapshot_status.l1_batch_number;
+            let header = self
+                .main_node_client
+                .fetch_snapshot(l1_batch_number)
+                .await?
+                .with_context(|| {
+                    format!(
+                        "snapshot for L1 batch #{l1_batch_number} is not available on main node; \
+                         cannot resume snapshot recovery"
+                    )
+                })?;
+            self.snapshot_header = Some(CheckedSnapshotHeader::new(header)?);
+        }
+        // `unwrap()` is safe: the header was set above
+        Ok(self.snapshot_header.as_ref().unwrap())
+    }
+
+    /// Returns the snapshot header. Must be called after [`Self::ensure_snapshot_header()`].
+    fn snapshot_header(&self) -> &CheckedSnapshotHeader {
+        self.snapshot_header
+            .as_ref()
+            .expect("snapshot header is not fetched")
     }
 
     fn update_health(&self) {
         let details = SnapshotsApplierHealthDetails {
+            snapshot_version: self
+                .snapshot_header
+                .as_ref()
+                .map(|header| header.version as u16),
             snapshot_miniblock: self.applied_snapshot_status.miniblock_number,
             snapshot_l1_batch: self.applied_snapshot_status.l1_batch_number,
             factory_deps_recovered: self.factory_deps_recovered,
@@ -429,6 +674,12 @@ impl<'a> SnapshotsApplier<'a> {
             "Fetched {} factory dependencies from object store",
             factory_deps.factory_deps.len()
         );
+        let header = self.snapshot_header();
+        header.verify_checksum(
+            || snapshot_file_checksum(&factory_deps),
+            header.inner.factory_deps_checksum,
+            "factory deps",
+        )?;
 
         let all_deps_hashmap: HashMap<H256, Vec<u8>> = factory_deps
             .factory_deps
@@ -503,9 +754,10 @@ impl<'a> SnapshotsApplier<'a> {
         let latency =
             METRICS.storage_logs_chunks_duration[&StorageLogsChunksStage::LoadFromGcs].start();
 
+        let header = self.snapshot_header();
         let storage_key = SnapshotStorageLogsStorageKey {
             chunk_id,
-            l1_batch_number: self.applied_snapshot_status.l1_batch_number,
+            l1_batch_number: header.storage_logs_chunk_l1_batch(chunk_id),
         };
         let storage_snapshot_chunk: SnapshotStorageLogsChunk =
             self.blob_store.get(storage_key).await.map_err(|err| {
@@ -513,6 +765,11 @@ impl<'a> SnapshotsApplier<'a> {
                     format!("cannot fetch storage logs {storage_key:?} from object store");
                 SnapshotsApplierError::object_store(err, context)
             })?;
+        header.verify_checksum(
+            || snapshot_file_checksum(&storage_snapshot_chunk),
+            header.storage_logs_chunk_checksum(chunk_id),
+            &format!("storage logs chunk {chunk_id}"),
+        )?;
         let storage_logs = &storage_snapshot_chunk.storage_logs;
         let latency = latency.observe();
         tracing::info!(
@@ -559,6 +816,24 @@ impl<'a> SnapshotsApplier<'a> {
     }
 
     async fn recover_storage_logs(&self) -> Result<(), SnapshotsApplierError> {
+        // Storage logs are inserted for the snapshot miniblock, which may not be covered by existing table partitions.
+        let miniblock_number = self.applied_snapshot_status.miniblock_number;
+        let mut storage = self
+            .connection_pool
+            .access_storage_tagged("snapshots_applier")
+            .await?;
+        storage
+            .partitions_dal()
+            .ensure_partitions(miniblock_number)
+            .await
+            .map_err(|err| {
+                SnapshotsApplierError::db(
+                    err,
+                    format!("failed creating table partitions for miniblock #{miniblock_number}"),
+                )
+            })?;
+        drop(storage);
+
         let semaphore = Semaphore::new(self.connection_pool.max_size() as usize);
         let tasks = self
             .applied_snapshot_status
@@ -574,8 +849,37 @@ impl<'a> SnapshotsApplier<'a> {
         Ok(())
     }
 
+    /// Loads tokens from the snapshot tokens file, or from the main node for snapshot versions without tokens.
+    async fn load_tokens(&mut self) -> Result<Vec<TokenInfo>, SnapshotsApplierError> {
+        let header = self.ensure_snapshot_header().await?;
+        if !header.version.has_tokens_and_checksums() {
+            let snapshot_miniblock_number = self.applied_snapshot_status.miniblock_number;
+            let tokens = self
+                .main_node_client
+                .fetch_tokens(snapshot_miniblock_number)
+                .await?;
+            tracing::info!("Retrieved {} tokens from main node", tokens.len());
+            return Ok(tokens);
+        }
+
+        let l1_batch_number = self.applied_snapshot_status.l1_batch_number;
+        let tokens: SnapshotTokens = self.blob_store.get(l1_batch_number).await.map_err(|err| {
+            let context =
+                format!("cannot fetch tokens for L1 batch #{l1_batch_number} from object store");
+            SnapshotsApplierError::object_store(err, context)
+        })?;
+        let header = self.snapshot_header();
+        header.verify_checksum(
+            || snapshot_file_checksum(&tokens),
+            header.inner.tokens_checksum,
+            "tokens",
+        )?;
+        tracing::info!("Retrieved {} tokens from object store", tokens.tokens.len());
+        Ok(tokens.tokens)
+    }
+
     /// Needs to run after recovering storage logs.
-    async fn recover_tokens(&self) -> Result<(), SnapshotsApplierError> {
+    async fn recover_tokens(&mut self) -> Result<(), SnapshotsApplierError> {
         // Check whether tokens are already recovered.
         let mut storage = self
             .connection_pool
@@ -596,13 +900,9 @@ impl<'a> SnapshotsApplier<'a> {
         drop(storage);
 
         let snapshot_miniblock_number = self.applied_snapshot_status.miniblock_number;
-        let tokens = self
-            .main_node_client
-            .fetch_tokens(snapshot_miniblock_number)
-            .await?;
-        tracing::info!("Retrieved {} tokens from main node", tokens.len());
+        let tokens = self.load_tokens().await?;
 
-        // Check that all tokens returned by the main node were indeed successfully deployed.
+        // Check that all tokens were indeed successfully deployed.
         let l2_addresses = tokens.iter().map(|token| token.l2_address);
         let mut storage = self
             .connection_pool
@@ -623,7 +923,7 @@ impl<'a> SnapshotsApplier<'a> {
         let bogus_tokens: Vec<_> = bogus_tokens.collect();
         if !bogus_tokens.is_empty() {
             let err = anyhow::anyhow!(
-                "Main node returned bogus tokens that are not deployed on L2: {bogus_tokens:?}"
+                "Snapshot contains bogus tokens that are not deployed on L2: {bogus_tokens:?}"
             );
             return Err(SnapshotsApplierError::Retryable(err));
         }
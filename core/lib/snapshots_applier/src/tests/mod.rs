@@ -10,7 +10,8 @@ use zksync_types::{
 };
 
 use self::utils::{
-    mock_recovery_status, prepare_clients, MockMainNodeClient, ObjectStoreWithErrors,
+    mock_recovery_status, prepare_clients, put_tokens, MockL1Client, MockMainNodeClient,
+    ObjectStoreWithErrors,
 };
 use super::*;
 use crate::tests::utils::{mock_tokens, random_storage_logs};
@@ -210,12 +211,203 @@ async fn applier_returns_error_after_too_many_object_store_retries() {
 }
 
 #[tokio::test]
-async fn recovering_tokens() {
+async fn applier_returns_error_on_checksum_mismatch() {
     let pool = ConnectionPool::test_pool().await;
     let expected_status = mock_recovery_status();
-    let tokens = mock_tokens();
-    let mut storage_logs = random_storage_logs(expected_status.l1_batch_number, 200);
-    for token in &tokens {
+    let storage_logs = random_storage_logs(expected_status.l1_batch_number, 100);
+    let (object_store, mut client) = prepare_clients(&expected_status, &storage_logs).await;
+    let snapshot_header = client.fetch_newest_snapshot_response.as_mut().unwrap();
+    snapshot_header.storage_logs_chunks[1].checksum = Some(H256::repeat_byte(1));
+
+    let err = SnapshotsApplierConfig::for_tests()
+        .run(&pool, &client, &object_store)
+        .await
+        .unwrap_err();
+    let err = format!("{err:#}");
+    assert!(
+        err.contains("checksum mismatch for storage logs chunk 1"),
+        "{err}"
+    );
+}
+
+#[tokio::test]
+async fn applier_verifies_snapshot_against_l1_commitment() {
+    let pool = ConnectionPool::test_pool().await;
+    let expected_status = mock_recovery_status();
+    let storage_logs = random_storage_logs(expected_status.l1_batch_number, 100);
+    let (object_store, client) = prepare_clients(&expected_status, &storage_logs).await;
+    let snapshot_header = client.fetch_newest_snapshot_response.as_ref().unwrap();
+    let stored_batch_hash = stored_batch_info_hash(&snapshot_header.last_l1_batch_with_metadata);
+    let l1_client = MockL1Client {
+        stored_batch_hashes: HashMap::from([(expected_status.l1_batch_number, stored_batch_hash)]),
+    };
+
+    SnapshotsApplierConfig::for_tests()
+        .with_l1_client(l1_client)
+        .run(&pool, &client, &object_store)
+        .await
+        .unwrap();
+
+    let mut storage = pool.access_storage().await.unwrap();
+    let current_db_status = storage
+        .snapshot_recovery_dal()
+        .get_applied_snapshot_status()
+        .await
+        .unwrap();
+    assert_eq!(current_db_status.unwrap(), expected_status);
+}
+
+#[tokio::test]
+async fn applier_returns_error_on_l1_commitment_mismatch() {
+    let pool = ConnectionPool::test_pool().await;
+    let expected_status = mock_recovery_status();
+    let storage_logs = random_storage_logs(expected_status.l1_batch_number, 100);
+    let (object_store, client) = prepare_clients(&expected_status, &storage_logs).await;
+    let l1_client = MockL1Client {
+        stored_batch_hashes: HashMap::from([(
+            expected_status.l1_batch_number,
+            H256::repeat_byte(1),
+        )]),
+    };
+
+    let err = SnapshotsApplierConfig::for_tests()
+        .with_l1_client(l1_client)
+        .run(&pool, &client, &object_store)
+        .await
+        .unwrap_err();
+    let err = format!("{err:#}");
+    assert!(err.contains("L1 commitment mismatch"), "{err}");
+
+    let mut storage = pool.access_storage().await.unwrap();
+    let current_db_status = storage
+        .snapshot_recovery_dal()
+        .get_applied_snapshot_status()
+        .await
+        .unwrap();
+    assert!(current_db_status.is_none());
+}
+
+#[tokio::test]
+async fn applier_retries_if_snapshot_is_not_committed_on_l1() {
+    let pool = ConnectionPool::test_pool().await;
+    let expected_status = mock_recovery_status();
+    let storage_logs = random_storage_logs(expected_status.l1_batch_number, 100);
+    let (object_store, client) = prepare_clients(&expected_status, &storage_logs).await;
+
+    let err = SnapshotsApplierConfig::for_tests()
+        .with_l1_client(MockL1Client::default())
+        .run(&pool, &client, &object_store)
+        .await
+        .unwrap_err();
+    let err = format!("{err:#}");
+    assert!(err.contains("is not committed on L1 yet"), "{err}");
+}
+
+#[tokio::test]
+async fn applier_returns_error_on_missing_checksum() {
+    let pool = ConnectionPool::test_pool().await;
+    let expected_status = mock_recovery_status();
+    let storage_logs = random_storage_logs(expected_status.l1_batch_number, 100);
+    let (object_store, mut client) = prepare_clients(&expected_status, &storage_logs).await;
+    let snapshot_header = client.fetch_newest_snapshot_response.as_mut().unwrap();
+    snapshot_header.factory_deps_checksum = None;
+
+    let err = SnapshotsApplierConfig::for_tests()
+        .run(&pool, &client, &object_store)
+        .await
+        .unwrap_err();
+    let err = format!("{err:#}");
+    assert!(
+        err.contains("doesn't contain a checksum for factory deps"),
+        "{err}"
+    );
+}
+
+#[tokio::test]
+async fn applier_returns_error_on_unsupported_snapshot_version() {
+    let pool = ConnectionPool::test_pool().await;
+    let expected_status = mock_recovery_status();
+    let storage_logs = random_storage_logs(expected_status.l1_batch_number, 100);
+    let (object_store, mut client) = prepare_clients(&expected_status, &storage_logs).await;
+    client
+        .fetch_newest_snapshot_response
+        .as_mut()
+        .unwrap()
+        .version = 1_000;
+
+    let err = SnapshotsApplierConfig::for_tests()
+        .run(&pool, &client, &object_store)
+        .await
+        .unwrap_err();
+    let err = format!("{err:#}");
+    assert!(err.contains("unsupported version 1000"), "{err}");
+
+    let mut storage = pool.access_storage().await.unwrap();
+    let status = storage
+        .snapshot_recovery_dal()
+        .get_applied_snapshot_status()
+        .await
+        .unwrap();
+    assert!(status.is_none());
+}
+
+#[tokio::test]
+async fn recovering_from_incremental_snapshot() {
+    let pool = ConnectionPool::test_pool().await;
+    let expected_status = mock_recovery_status();
+    let storage_logs = random_storage_logs(expected_status.l1_batch_number, 200);
+    let (object_store, mut client) = prepare_clients(&expected_status, &storage_logs).await;
+
+    // Move a storage logs chunk to a previous snapshot, and corrupt the chunk stored for the current one.
+    let base_l1_batch_number = expected_status.l1_batch_number - 10;
+    let chunk_key = SnapshotStorageLogsStorageKey {
+        l1_batch_number: expected_status.l1_batch_number,
+        chunk_id: 1,
+    };
+    let chunk: SnapshotStorageLogsChunk = object_store.get(chunk_key).await.unwrap();
+    let base_chunk_key = SnapshotStorageLogsStorageKey {
+        l1_batch_number: base_l1_batch_number,
+        ..chunk_key
+    };
+    object_store.put(base_chunk_key, &chunk).await.unwrap();
+    let empty_chunk = SnapshotStorageLogsChunk {
+        storage_logs: vec![],
+    };
+    object_store.put(chunk_key, &empty_chunk).await.unwrap();
+
+    let snapshot_header = client.fetch_newest_snapshot_response.as_mut().unwrap();
+    snapshot_header.base_l1_batch_number = Some(base_l1_batch_number);
+    snapshot_header.storage_logs_chunks[1].l1_batch_number = Some(base_l1_batch_number);
+
+    // Reused chunks are not supported by the snapshot version.
+    let err = SnapshotsApplierConfig::for_tests()
+        .run(&pool, &client, &object_store)
+        .await
+        .unwrap_err();
+    let err = format!("{err:#}");
+    assert!(err.contains("doesn't support reused"), "{err}");
+
+    let snapshot_header = client.fetch_newest_snapshot_response.as_mut().unwrap();
+    snapshot_header.version = SnapshotVersion::INCREMENTAL as u16;
+    SnapshotsApplierConfig::for_tests()
+        .run(&pool, &client, &object_store)
+        .await
+        .unwrap();
+
+    let mut storage = pool.access_storage().await.unwrap();
+    let all_storage_logs = storage
+        .storage_logs_dal()
+        .dump_all_storage_logs_for_tests()
+        .await;
+    assert_eq!(all_storage_logs.len(), storage_logs.len());
+}
+
+fn storage_logs_with_tokens(
+    l1_batch_number: L1BatchNumber,
+    tokens: &[TokenInfo],
+) -> Vec<SnapshotStorageLog> {
+    let mut storage_logs = random_storage_logs(l1_batch_number, 200);
+    for token in tokens {
         if token.l2_address.is_zero() {
             continue;
         }
@@ -226,15 +418,10 @@ async fn recovering_tokens() {
             enumeration_index: storage_logs.len() as u64 + 1,
         });
     }
-    let (object_store, mut client) = prepare_clients(&expected_status, &storage_logs).await;
-    client.tokens_response = tokens.clone();
-
-    SnapshotsApplierConfig::for_tests()
-        .run(&pool, &client, &object_store)
-        .await
-        .unwrap();
+    storage_logs
+}
 
-    // Check that tokens are successfully restored.
+async fn assert_tokens_recovered(pool: &ConnectionPool, tokens: Vec<TokenInfo>) {
     let mut storage = pool.access_storage().await.unwrap();
     let recovered_tokens = storage
         .tokens_web3_dal()
@@ -251,6 +438,47 @@ async fn recovering_tokens() {
         .map(|token| (token.l2_address, token))
         .collect();
     assert_eq!(token_map, recovered_token_map);
+}
+
+#[tokio::test]
+async fn recovering_tokens_from_legacy_snapshot() {
+    let pool = ConnectionPool::test_pool().await;
+    let expected_status = mock_recovery_status();
+    let tokens = mock_tokens();
+    let storage_logs = storage_logs_with_tokens(expected_status.l1_batch_number, &tokens);
+    let (object_store, mut client) = prepare_clients(&expected_status, &storage_logs).await;
+    let snapshot_header = client.fetch_newest_snapshot_response.as_mut().unwrap();
+    snapshot_header.version = SnapshotVersion::Version0 as u16;
+    snapshot_header.factory_deps_checksum = None;
+    snapshot_header.tokens_filepath = None;
+    snapshot_header.tokens_checksum = None;
+    for chunk in &mut snapshot_header.storage_logs_chunks {
+        chunk.checksum = None;
+    }
+    // Tokens must be fetched from the main node rather than from the object store.
+    client.tokens_response = tokens.clone();
+
+    SnapshotsApplierConfig::for_tests()
+        .run(&pool, &client, &object_store)
+        .await
+        .unwrap();
+    assert_tokens_recovered(&pool, tokens).await;
+}
+
+#[tokio::test]
+async fn recovering_tokens() {
+    let pool = ConnectionPool::test_pool().await;
+    let expected_status = mock_recovery_status();
+    let tokens = mock_tokens();
+    let storage_logs = storage_logs_with_tokens(expected_status.l1_batch_number, &tokens);
+    let (object_store, mut client) = prepare_clients(&expected_status, &storage_logs).await;
+    put_tokens(&*object_store, &mut client, tokens.clone()).await;
+
+    SnapshotsApplierConfig::for_tests()
+        .run(&pool, &client, &object_store)
+        .await
+        .unwrap();
+    assert_tokens_recovered(&pool, tokens).await;
 
     // Check that recovering again works and is a no-op.
     SnapshotsApplierConfig::for_tests()
@@ -258,3 +486,54 @@ async fn recovering_tokens() {
         .await
         .unwrap();
 }
+
+#[tokio::test]
+async fn resuming_recovery_fetches_snapshot_header() {
+    let pool = ConnectionPool::test_pool().await;
+    let expected_status = mock_recovery_status();
+    let storage_logs = random_storage_logs(expected_status.l1_batch_number, 100);
+    let (object_store, mut client) = prepare_clients(&expected_status, &storage_logs).await;
+    // Recover the base token only, since it doesn't require a deployed contract.
+    let base_token = mock_tokens()[0].clone();
+    assert!(base_token.l2_address.is_zero());
+    put_tokens(&*object_store, &mut client, vec![base_token]).await;
+    // Fail on the second storage logs chunk, so that recovery is interrupted.
+    let failing_object_store = ObjectStoreWithErrors::new(object_store.clone(), |key| {
+        if key.contains("storage_logs_part_0001") {
+            Err(ObjectStoreError::KeyNotFound("not found".into()))
+        } else {
+            Ok(())
+        }
+    });
+    SnapshotsApplierConfig::for_tests()
+        .run(&pool, &client, &failing_object_store)
+        .await
+        .unwrap_err();
+
+    // The applier must refuse to resume recovery if the snapshot is no longer available.
+    let client_without_snapshot = MockMainNodeClient::default();
+    let err = SnapshotsApplierConfig::for_tests()
+        .run(&pool, &client_without_snapshot, &object_store)
+        .await
+        .unwrap_err();
+    let err = format!("{err:#}");
+    assert!(err.contains("not available on main node"), "{err}");
+
+    SnapshotsApplierConfig::for_tests()
+        .run(&pool, &client, &object_store)
+        .await
+        .unwrap();
+    let mut storage = pool.access_storage().await.unwrap();
+    let status = storage
+        .snapshot_recovery_dal()
+        .get_applied_snapshot_status()
+        .await
+        .unwrap();
+    assert_eq!(status.unwrap(), expected_status);
+
+    // After recovery is complete, the snapshot header is not needed anymore.
+    SnapshotsApplierConfig::for_tests()
+        .run(&pool, &client_without_snapshot, &object_store)
+        .await
+        .unwrap();
+}
@@ -9,9 +9,10 @@ use zksync_types::{
     block::L1BatchHeader,
     commitment::{L1BatchMetaParameters, L1BatchMetadata, L1BatchWithMetadata},
     snapshots::{
-        SnapshotFactoryDependencies, SnapshotFactoryDependency, SnapshotHeader,
-        SnapshotRecoveryStatus, SnapshotStorageLog, SnapshotStorageLogsChunk,
-        SnapshotStorageLogsChunkMetadata, SnapshotStorageLogsStorageKey,
+        snapshot_file_checksum, SnapshotFactoryDependencies, SnapshotFactoryDependency,
+        SnapshotHeader, SnapshotRecoveryStatus, SnapshotStorageLog, SnapshotStorageLogsChunk,
+        SnapshotStorageLogsChunkMetadata, SnapshotStorageLogsStorageKey, SnapshotTokens,
+        SnapshotVersion,
     },
     tokens::{TokenInfo, TokenMetadata},
     AccountTreeId, Address, Bytes, L1BatchNumber, MiniblockNumber, ProtocolVersionId, StorageKey,
@@ -19,7 +20,7 @@ use zksync_types::{
 };
 use zksync_web3_decl::error::EnrichedClientResult;
 
-use crate::SnapshotsApplierMainNodeClient;
+use crate::{SnapshotsApplierL1Client, SnapshotsApplierMainNodeClient};
 
 #[derive(Debug, Default)]
 pub(super) struct MockMainNodeClient {
@@ -41,6 +42,17 @@ impl SnapshotsApplierMainNodeClient for MockMainNodeClient {
         Ok(self.fetch_newest_snapshot_response.clone())
     }
 
+    async fn fetch_snapshot(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> EnrichedClientResult<Option<SnapshotHeader>> {
+        let snapshot = self
+            .fetch_newest_snapshot_response
+            .as_ref()
+            .filter(|snapshot| snapshot.l1_batch_number == l1_batch_number);
+        Ok(snapshot.cloned())
+    }
+
     async fn fetch_tokens(
         &self,
         _at_miniblock: MiniblockNumber,
@@ -49,6 +61,21 @@ impl SnapshotsApplierMainNodeClient for MockMainNodeClient {
     }
 }
 
+#[derive(Debug, Default)]
+pub(super) struct MockL1Client {
+    pub stored_batch_hashes: HashMap<L1BatchNumber, H256>,
+}
+
+#[async_trait]
+impl SnapshotsApplierL1Client for MockL1Client {
+    async fn fetch_stored_batch_hash(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<Option<H256>> {
+        Ok(self.stored_batch_hashes.get(&l1_batch_number).copied())
+    }
+}
+
 type ValidateFn = dyn Fn(&str) -> Result<(), ObjectStoreError> + Send + Sync;
 
 pub(super) struct ObjectStoreWithErrors {
@@ -223,12 +250,18 @@ pub(super) async fn prepare_clients(
         .put(status.l1_batch_number, &factory_deps)
         .await
         .unwrap();
+    let tokens = SnapshotTokens { tokens: vec![] };
+    object_store
+        .put(status.l1_batch_number, &tokens)
+        .await
+        .unwrap();
 
     let chunk_size = logs
         .len()
         .div_ceil(status.storage_logs_chunks_processed.len());
     assert!(chunk_size > 0);
 
+    let mut storage_logs_chunks = vec![];
     for (chunk_id, chunk) in logs.chunks(chunk_size).enumerate() {
         let chunk_storage_logs = SnapshotStorageLogsChunk {
             storage_logs: chunk.to_vec(),
@@ -241,26 +274,28 @@ pub(super) async fn prepare_clients(
             .put(chunk_key, &chunk_storage_logs)
             .await
             .unwrap();
+        storage_logs_chunks.push(SnapshotStorageLogsChunkMetadata {
+            chunk_id: chunk_id as u64,
+            filepath: format!("file{chunk_id}"),
+            checksum: Some(snapshot_file_checksum(&chunk_storage_logs)),
+            l1_batch_number: None,
+        });
     }
 
     let snapshot_header = SnapshotHeader {
+        version: SnapshotVersion::Version1 as u16,
         l1_batch_number: status.l1_batch_number,
         miniblock_number: status.miniblock_number,
         last_l1_batch_with_metadata: l1_block_metadata(
             status.l1_batch_number,
             status.l1_batch_root_hash,
         ),
-        storage_logs_chunks: vec![
-            SnapshotStorageLogsChunkMetadata {
-                chunk_id: 0,
-                filepath: "file0".to_string(),
-            },
-            SnapshotStorageLogsChunkMetadata {
-                chunk_id: 1,
-                filepath: "file1".to_string(),
-            },
-        ],
+        storage_logs_chunks,
         factory_deps_filepath: "some_filepath".to_string(),
+        factory_deps_checksum: Some(snapshot_file_checksum(&factory_deps)),
+        tokens_filepath: Some("tokens_filepath".to_string()),
+        tokens_checksum: Some(snapshot_file_checksum(&tokens)),
+        base_l1_batch_number: None,
     };
     client.fetch_newest_snapshot_response = Some(snapshot_header);
     client.fetch_l2_block_responses.insert(
@@ -273,3 +308,18 @@ pub(super) async fn prepare_clients(
     );
     (object_store, client)
 }
+
+/// Replaces the tokens file in the snapshot prepared by [`prepare_clients()`].
+pub(super) async fn put_tokens(
+    object_store: &dyn ObjectStore,
+    client: &mut MockMainNodeClient,
+    tokens: Vec<TokenInfo>,
+) {
+    let snapshot_header = client.fetch_newest_snapshot_response.as_mut().unwrap();
+    let tokens = SnapshotTokens { tokens };
+    object_store
+        .put(snapshot_header.l1_batch_number, &tokens)
+        .await
+        .unwrap();
+    snapshot_header.tokens_checksum = Some(snapshot_file_checksum(&tokens));
+}
@@ -31,7 +31,7 @@ use anyhow::Context as _;
 use itertools::{Either, Itertools};
 use tokio::sync::watch;
 use zksync_dal::StorageProcessor;
-use zksync_storage::{db::NamedColumnFamily, RocksDB};
+use zksync_storage::{db::NamedColumnFamily, RocksDB, RocksDBOptions};
 use zksync_types::{L1BatchNumber, StorageKey, StorageValue, H256, U256};
 use zksync_utils::{h256_to_u256, u256_to_h256};
 
@@ -209,15 +209,33 @@ impl RocksdbStorage {
     ///
     /// Propagates RocksDB I/O errors.
     pub async fn builder(path: &Path) -> anyhow::Result<RocksbStorageBuilder> {
-        Self::new(path.to_path_buf())
+        Self::builder_with_options(path, RocksDBOptions::default()).await
+    }
+
+    /// Creates a new storage builder with the provided RocksDB `path` and `options`.
+    ///
+    /// # Errors
+    ///
+    /// Propagates RocksDB I/O errors.
+    pub async fn builder_with_options(
+        path: &Path,
+        options: RocksDBOptions,
+    ) -> anyhow::Result<RocksbStorageBuilder> {
+        Self::with_options(path.to_path_buf(), options)
             .await
             .map(RocksbStorageBuilder)
     }
 
+    #[cfg(test)]
     async fn new(path: PathBuf) -> anyhow::Result<Self> {
+        Self::with_options(path, RocksDBOptions::default()).await
+    }
+
+    async fn with_options(path: PathBuf, options: RocksDBOptions) -> anyhow::Result<Self> {
         tokio::task::spawn_blocking(move || {
             Ok(Self {
-                db: RocksDB::new(&path).context("failed initializing state keeper RocksDB")?,
+                db: RocksDB::with_options(&path, options)
+                    .context("failed initializing state keeper RocksDB")?,
                 pending_patch: InMemoryStorage::default(),
                 enum_index_migration_chunk_size: 100,
                 #[cfg(test)]
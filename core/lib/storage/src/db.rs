@@ -11,11 +11,11 @@ use std::{
 };
 
 use rocksdb::{
-    properties, BlockBasedOptions, Cache, ColumnFamily, ColumnFamilyDescriptor, DBPinnableSlice,
-    Direction, IteratorMode, Options, PrefixRange, ReadOptions, WriteOptions, DB,
+    properties, BlockBasedOptions, Cache, ColumnFamily, ColumnFamilyDescriptor, DBCompactionStyle,
+    DBPinnableSlice, Direction, IteratorMode, Options, PrefixRange, ReadOptions, WriteOptions, DB,
 };
 
-use crate::metrics::{RocksdbLabels, RocksdbSizeMetrics, METRICS};
+use crate::metrics::{RocksdbLabels, RocksdbSizeMetrics, RocksdbTickerLabels, METRICS};
 
 /// Number of active RocksDB instances used to determine if it's safe to exit current process.
 /// Not properly dropped RocksDB instances can lead to DB corruption.
@@ -95,6 +95,7 @@ pub(crate) struct RocksDBInner {
     db: DB,
     db_name: &'static str,
     cf_names: HashSet<&'static str>,
+    statistics_enabled: bool,
     _registry_entry: RegistryEntry,
     // Importantly, `Cache`s must be dropped after `DB`, so we place them as the last field
     // (fields in a struct are dropped in the declaration order).
@@ -157,6 +158,31 @@ impl RocksDBInner {
                 metrics.index_and_filters_size[&labels].set(size);
             }
         }
+
+        if self.statistics_enabled {
+            self.collect_statistics(metrics);
+        }
+    }
+
+    fn collect_statistics(&self, metrics: &RocksdbSizeMetrics) {
+        let statistics = match self.db.property_value(properties::OPTIONS_STATISTICS) {
+            Ok(Some(statistics)) => statistics,
+            Ok(None) => {
+                tracing::warn!(
+                    "RocksDB statistics are not available for DB `{}`",
+                    self.db_name
+                );
+                return;
+            }
+            Err(err) => {
+                tracing::warn!(%err, "Failed getting RocksDB statistics for DB `{}`", self.db_name);
+                return;
+            }
+        };
+        for (ticker, value) in parse_statistics_tickers(&statistics) {
+            let labels = RocksdbTickerLabels::new(self.db_name, ticker);
+            metrics.statistics[&labels].set(value);
+        }
     }
 
     fn int_property(&self, cf: &ColumnFamily, name: &CStr) -> Option<u64> {
@@ -260,6 +286,45 @@ impl StalledWritesRetries {
     }
 }
 
+/// Names of RocksDB statistics tickers exported as metrics (without the `rocksdb.` prefix).
+const EXPORTED_TICKERS: &[&str] = &[
+    "block.cache.hit",
+    "block.cache.miss",
+    "block.cache.add",
+    "bloom.filter.useful",
+    "memtable.hit",
+    "memtable.miss",
+    "number.keys.read",
+    "number.keys.written",
+    "bytes.read",
+    "bytes.written",
+    "compact.read.bytes",
+    "compact.write.bytes",
+    "flush.write.bytes",
+    "wal.bytes",
+    "stall.micros",
+];
+
+/// Parses values of [exported tickers](EXPORTED_TICKERS) from the RocksDB statistics dump. Ticker lines
+/// in the dump have the `rocksdb.block.cache.miss COUNT : 42` format; histogram lines contain percentiles
+/// in addition to the count and are skipped.
+fn parse_statistics_tickers(statistics: &str) -> impl Iterator<Item = (&'static str, u64)> + '_ {
+    statistics.lines().filter_map(|line| {
+        let mut parts = line.split_whitespace();
+        let name = parts.next()?.strip_prefix("rocksdb.")?;
+        let (Some("COUNT"), Some(":"), Some(value), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return None;
+        };
+        let ticker = EXPORTED_TICKERS
+            .iter()
+            .copied()
+            .find(|&ticker| ticker == name)?;
+        Some((ticker, value.parse().ok()?))
+    })
+}
+
 /// [`RocksDB`] options.
 #[derive(Debug, Clone, Copy)]
 pub struct RocksDBOptions {
@@ -274,6 +339,17 @@ pub struct RocksDBOptions {
     /// Timeout to wait for the database to run compaction on stalled writes during startup or
     /// when the corresponding RocksDB error is encountered.
     pub stalled_writes_retries: StalledWritesRetries,
+    /// Compaction style for all CFs. If not set, level-style compaction is used.
+    pub compaction_style: Option<DBCompactionStyle>,
+    /// Byte size of a single memtable for all CFs. If set, overrides the size derived from
+    /// [`Self::large_memtable_capacity`] for large CFs.
+    pub write_buffer_size: Option<usize>,
+    /// Maximum number of concurrent background jobs (compactions and flushes). If not set, the number
+    /// is derived from the number of CPUs.
+    pub max_background_jobs: Option<usize>,
+    /// Enables collecting RocksDB statistics, which are exported as metrics. Collecting statistics
+    /// has a moderate performance overhead.
+    pub enable_statistics: bool,
 }
 
 impl Default for RocksDBOptions {
@@ -282,6 +358,10 @@ impl Default for RocksDBOptions {
             block_cache_capacity: None,
             large_memtable_capacity: None,
             stalled_writes_retries: StalledWritesRetries::new(Duration::from_secs(10)),
+            compaction_style: None,
+            write_buffer_size: None,
+            max_background_jobs: None,
+            enable_statistics: false,
         }
     }
 }
@@ -303,8 +383,12 @@ impl<CF: NamedColumnFamily> RocksDB<CF> {
     }
 
     pub fn with_options(path: &Path, options: RocksDBOptions) -> Result<Self, rocksdb::Error> {
+        let started_at = Instant::now();
         let caches = RocksDBCaches::new(options.block_cache_capacity);
-        let db_options = Self::rocksdb_options(None, None);
+        let mut db_options = Self::rocksdb_options(&options, None, None);
+        if options.enable_statistics {
+            db_options.enable_statistics();
+        }
         let existing_cfs = DB::list_cf(&db_options, path).unwrap_or_else(|err| {
             tracing::warn!(
                 "Failed getting column families for RocksDB `{}` at `{}`, assuming CFs are empty; {err}",
@@ -350,7 +434,8 @@ impl<CF: NamedColumnFamily> RocksDB<CF> {
                 block_based_options.set_block_cache(cache);
             }
             let memtable_capacity = options.large_memtable_capacity.filter(|_| requires_tuning);
-            let cf_options = Self::rocksdb_options(memtable_capacity, Some(block_based_options));
+            let cf_options =
+                Self::rocksdb_options(&options, memtable_capacity, Some(block_based_options));
             ColumnFamilyDescriptor::new(cf_name, cf_options)
         });
 
@@ -359,13 +444,18 @@ impl<CF: NamedColumnFamily> RocksDB<CF> {
             db,
             db_name: CF::DB_NAME,
             cf_names,
+            statistics_enabled: options.enable_statistics,
             _registry_entry: RegistryEntry::new(),
             _caches: caches,
         });
         RocksdbSizeMetrics::register(CF::DB_NAME, Arc::downgrade(&inner));
+        let latency = started_at.elapsed();
+        METRICS.observe_open_latency(CF::DB_NAME, latency);
 
         tracing::info!(
-            "Initialized RocksDB `{}` at `{}` with {options:?}",
+            db = CF::DB_NAME,
+            latency_ms = latency.as_millis() as u64,
+            "Initialized RocksDB `{}` at `{}` with {options:?} in {latency:?}",
             CF::DB_NAME,
             path.display()
         );
@@ -388,6 +478,7 @@ impl<CF: NamedColumnFamily> RocksDB<CF> {
     }
 
     fn rocksdb_options(
+        db_options: &RocksDBOptions,
         memtable_capacity: Option<usize>,
         block_based_options: Option<BlockBasedOptions>,
     ) -> Options {
@@ -398,11 +489,26 @@ impl<CF: NamedColumnFamily> RocksDB<CF> {
         let num_cpus = num_cpus::get() as i32;
         options.increase_parallelism(num_cpus);
         if let Some(memtable_capacity) = memtable_capacity {
-            options.optimize_level_style_compaction(memtable_capacity);
+            if matches!(
+                db_options.compaction_style,
+                Some(DBCompactionStyle::Universal)
+            ) {
+                options.optimize_universal_style_compaction(memtable_capacity);
+            } else {
+                options.optimize_level_style_compaction(memtable_capacity);
+            }
+        }
+        if let Some(compaction_style) = db_options.compaction_style {
+            options.set_compaction_style(compaction_style);
+        }
+        if let Some(write_buffer_size) = db_options.write_buffer_size {
+            options.set_write_buffer_size(write_buffer_size);
         }
         // Settings below are taken as per PingCAP recommendations:
         // https://www.pingcap.com/blog/how-to-troubleshoot-rocksdb-write-stalls-in-tikv/
-        let max_background_jobs = (num_cpus - 1).clamp(1, 8);
+        let max_background_jobs = db_options
+            .max_background_jobs
+            .map_or((num_cpus - 1).clamp(1, 8), |jobs| jobs.max(1) as i32);
         options.set_max_background_jobs(max_background_jobs);
 
         if let Some(block_based_options) = block_based_options {
@@ -662,6 +768,50 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parsing_statistics_tickers() {
+        let statistics = "\
+            rocksdb.block.cache.miss COUNT : 42\n\
+            rocksdb.block.cache.hit COUNT : 100\n\
+            rocksdb.block.cache.unknown COUNT : 3\n\
+            rocksdb.db.get.micros P50 : 1.000000 P95 : 2.000000 P99 : 3.000000 P100 : 4.000000 COUNT : 5 SUM : 6\n\
+            rocksdb.stall.micros COUNT : 0\n";
+        let tickers: Vec<_> = parse_statistics_tickers(statistics).collect();
+        assert_eq!(
+            tickers,
+            [
+                ("block.cache.miss", 42),
+                ("block.cache.hit", 100),
+                ("stall.micros", 0)
+            ]
+        );
+    }
+
+    #[test]
+    fn collecting_statistics() {
+        let temp_dir = TempDir::new().unwrap();
+        let options = RocksDBOptions {
+            enable_statistics: true,
+            ..RocksDBOptions::default()
+        };
+        let db = RocksDB::<NewColumnFamilies>::with_options(temp_dir.path(), options)
+            .unwrap()
+            .with_sync_writes();
+        let mut batch = db.new_write_batch();
+        batch.put_cf(NewColumnFamilies::Default, b"test", b"value");
+        db.write(batch).unwrap();
+
+        let statistics = db
+            .inner
+            .db
+            .property_value(properties::OPTIONS_STATISTICS)
+            .unwrap()
+            .expect("no statistics");
+        let tickers: HashMap<_, _> = parse_statistics_tickers(&statistics).collect();
+        assert_eq!(tickers.len(), EXPORTED_TICKERS.len());
+        assert_eq!(tickers["number.keys.written"], 1);
+    }
+
     #[test]
     fn changing_column_families() {
         let temp_dir = TempDir::new().unwrap();
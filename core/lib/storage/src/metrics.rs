@@ -34,6 +34,18 @@ impl RocksdbLabels {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelSet)]
+pub(crate) struct RocksdbTickerLabels {
+    db: &'static str,
+    ticker: &'static str,
+}
+
+impl RocksdbTickerLabels {
+    pub(crate) fn new(db: &'static str, ticker: &'static str) -> Self {
+        Self { db, ticker }
+    }
+}
+
 const BYTE_SIZE_BUCKETS: Buckets = Buckets::exponential(65_536.0..=16.0 * 1_024.0 * 1_024.0, 2.0);
 
 #[derive(Debug, Metrics)]
@@ -51,6 +63,9 @@ pub(crate) struct RocksdbMetrics {
     /// leads to a panic).
     #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds)]
     stalled_write_duration: Family<DbLabel, Histogram<Duration>>,
+    /// Time spent opening a RocksDB instance (e.g., on node startup).
+    #[metrics(unit = Unit::Seconds)]
+    open_latency: Family<DbLabel, Gauge<Duration>>,
 }
 
 impl RocksdbMetrics {
@@ -69,6 +84,10 @@ impl RocksdbMetrics {
     ) {
         self.stalled_write_duration[&db.into()].observe(stall_duration);
     }
+
+    pub(crate) fn observe_open_latency(&self, db: &'static str, latency: Duration) {
+        self.open_latency[&db.into()].set(latency);
+    }
 }
 
 #[vise::register]
@@ -102,6 +121,9 @@ pub(crate) struct RocksdbSizeMetrics {
     pub block_cache_size: Family<RocksdbLabels, Gauge<u64>>,
     /// Total size of index and Bloom filters in the column family of a RocksDB instance.
     pub index_and_filters_size: Family<RocksdbLabels, Gauge<u64>>,
+    /// Cumulative values of RocksDB statistics tickers (e.g., block cache hits and misses) since the instance
+    /// was opened. Only reported for instances with statistics enabled.
+    pub statistics: Family<RocksdbTickerLabels, Gauge<u64>>,
 }
 
 /// Weak refs to DB instances registered using [`RocksdbSizeMetrics::register()`].
@@ -44,3 +44,51 @@ pub struct SyncBlock {
     /// Version of the protocol used for this block.
     pub protocol_version: ProtocolVersionId,
 }
+
+/// Component of an external node which may stall syncing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncComponent {
+    /// Fetcher getting blocks from the main node.
+    Fetcher,
+    /// State keeper executing and sealing fetched blocks.
+    StateKeeper,
+    /// Merkle tree computing state root hashes for sealed L1 batches.
+    Tree,
+}
+
+/// Stalled external node component together with the stall reason.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncStall {
+    pub component: SyncComponent,
+    /// Human-readable reason of the stall.
+    pub reason: String,
+}
+
+/// Detailed sync status of an external node.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncStatus {
+    /// Whether the node is considered synced with the main node; matches `eth_syncing`.
+    pub is_synced: bool,
+    /// Last miniblock on the main node known to the external node.
+    pub main_node_miniblock: Option<MiniblockNumber>,
+    /// Last miniblock fetched from the main node and queued for execution.
+    pub fetched_miniblock: Option<MiniblockNumber>,
+    /// Last miniblock executed and sealed by the external node.
+    pub applied_miniblock: Option<MiniblockNumber>,
+    /// Last L1 batch sealed by the external node.
+    pub sealed_l1_batch: Option<L1BatchNumber>,
+    /// Last L1 batch processed by the Merkle tree.
+    pub tree_l1_batch: Option<L1BatchNumber>,
+    /// Number of sealed L1 batches not yet processed by the Merkle tree.
+    pub tree_lag: u32,
+    /// Last L1 batch which proof was verified on L1.
+    pub last_verified_l1_batch: Option<L1BatchNumber>,
+    /// Estimated time (in seconds) to catch up with the main node, based on the recent sync speed.
+    /// `None` if the node is synced or the estimate is not available yet.
+    pub estimated_secs_to_head: Option<u64>,
+    /// Components stalling the sync, if any.
+    pub stalls: Vec<SyncStall>,
+}
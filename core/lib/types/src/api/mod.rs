@@ -1,3 +1,5 @@
+use std::collections::{BTreeMap, HashMap};
+
 use chrono::{DateTime, Utc};
 use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 use strum::Display;
@@ -11,13 +13,16 @@ pub use crate::transaction_request::{
     Eip712Meta, SerializationTransactionError, TransactionRequest,
 };
 use crate::{
+    circuit::CircuitStatistic,
     protocol_version::L1VerifierConfig,
     vm_trace::{Call, CallType},
     web3::types::{AccessList, Index, H2048},
+    zk_evm_types::FarCallOpcode,
     Address, MiniblockNumber, ProtocolVersionId,
 };
 
 pub mod en;
+pub mod trace;
 
 /// Block Number
 #[derive(Copy, Clone, Debug, PartialEq, Display)]
@@ -546,6 +551,31 @@ pub struct TransactionDetails {
     pub eth_execute_tx_hash: Option<H256>,
 }
 
+/// Metrics collected by the VM when executing a transaction. These metrics reflect the actual cost drivers
+/// of transactions in zkSync Era.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionExecutionInfo {
+    /// Total gas used by the transaction.
+    pub gas_used: u64,
+    /// Gas spent on computation.
+    pub computational_gas_used: u32,
+    /// Number of pubdata bytes published by the transaction.
+    pub pubdata_published: u32,
+    /// Size of bytecodes published by the transaction, in bytes.
+    pub published_bytecode_bytes: u64,
+    pub l2_to_l1_logs: u64,
+    pub vm_events: u64,
+    /// Number of storage reads performed by the VM. `None` for transactions executed before this metric was collected.
+    pub storage_reads: Option<u64>,
+    /// Number of distinct storage slots written to. `None` for transactions executed before this metric was collected.
+    pub storage_writes: Option<u64>,
+    pub contracts_used: u64,
+    pub cycles_used: u32,
+    /// Estimated number of circuits of each type used to prove the transaction.
+    pub circuit_statistic: CircuitStatistic,
+}
+
 #[derive(Debug, Clone)]
 pub struct GetLogsFilter {
     pub from_block: MiniblockNumber,
@@ -555,16 +585,22 @@ pub struct GetLogsFilter {
 }
 
 /// Result of debugging block
-/// For some reasons geth returns result as {result: DebugCall}
+/// For some reasons geth returns result as {txHash: H256, result: DebugCall}
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct ResultDebugCall {
+    /// Hash of the traced transaction. May be missing in responses of older servers.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tx_hash: Option<H256>,
     pub result: DebugCall,
 }
 
+/// Call type in the geth-compatible format (e.g., `CALL` or `DELEGATECALL`).
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "UPPERCASE")]
 pub enum DebugCallType {
     Call,
+    DelegateCall,
     Create,
 }
 
@@ -588,6 +624,7 @@ impl From<Call> for DebugCall {
     fn from(value: Call) -> Self {
         let calls = value.calls.into_iter().map(DebugCall::from).collect();
         let debug_type = match value.r#type {
+            CallType::Call(FarCallOpcode::Delegate) => DebugCallType::DelegateCall,
             CallType::Call(_) => DebugCallType::Call,
             CallType::Create => DebugCallType::Create,
             CallType::NearCall => unreachable!("We have to filter our near calls before"),
@@ -626,6 +663,8 @@ pub struct ProtocolVersion {
 #[serde(rename_all = "camelCase")]
 pub enum SupportedTracers {
     CallTracer,
+    /// Geth-compatible opcode-level logger producing `structLogs`.
+    StructLogger,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
@@ -640,6 +679,134 @@ pub struct TracerConfig {
     pub tracer: SupportedTracers,
     #[serde(default)]
     pub tracer_config: CallTracerConfig,
+    /// Options for [`SupportedTracers::StructLogger`]. As in geth, these options are specified
+    /// at the top level of the config.
+    #[serde(flatten)]
+    pub struct_logger_config: StructLoggerConfig,
+}
+
+/// Options of the geth-compatible struct logger.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct StructLoggerConfig {
+    /// Disables capturing stack snapshots.
+    #[serde(default)]
+    pub disable_stack: bool,
+    /// Disables capturing storage snapshots.
+    #[serde(default)]
+    pub disable_storage: bool,
+    /// Enables capturing memory (heap) snapshots.
+    #[serde(default)]
+    pub enable_memory: bool,
+    /// Maximum number of logs to capture. 0 means that the server-side limit is used.
+    #[serde(default)]
+    pub limit: usize,
+}
+
+/// Single opcode-level log in the geth-compatible `structLogs` format.
+///
+/// EraVM concepts are mapped to their EVM counterparts: `stack` contains the EraVM stack of the current frame,
+/// `memory` contains the heap of the current frame, and `depth` is the depth of far calls.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StructLog {
+    pub pc: u64,
+    pub op: String,
+    pub gas: u64,
+    /// Gas spent on the opcode; approximate for opcodes changing the execution frame.
+    pub gas_cost: u64,
+    pub depth: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stack: Option<Vec<U256>>,
+    /// Memory as 32-byte words in the hex format without the `0x` prefix.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory: Option<Vec<String>>,
+    /// Storage slots of the current contract accessed so far. Only present for storage access opcodes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub storage: Option<BTreeMap<H256, H256>>,
+}
+
+/// Result of the struct logger in the geth-compatible format.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StructLogsTrace {
+    pub gas: u64,
+    pub failed: bool,
+    pub return_value: Bytes,
+    pub struct_logs: Vec<StructLog>,
+}
+
+/// Result of a `debug_trace*` method, depending on the requested tracer.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum DebugTrace {
+    Call(DebugCall),
+    StructLogs(StructLogsTrace),
+}
+
+/// Structured diagnostics for a transaction that failed when simulated in the API sandbox
+/// (e.g., in `eth_call` or `eth_estimateGas`). Returned in the data field of the corresponding API error.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecutionDiagnostics {
+    /// Total gas used by the transaction.
+    pub gas_used: u32,
+    /// Gas spent on computation.
+    pub computational_gas_used: u32,
+    /// Gas not attributed to computation. This is mostly gas spent on publishing pubdata.
+    pub pubdata_gas_used: u32,
+    /// Number of pubdata bytes published by the transaction.
+    pub pubdata_published: u32,
+    /// Innermost call frame that panicked having spent all gas provided to it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub out_of_gas_frame: Option<DiagnosticCallFrame>,
+    /// Revert reason of the innermost reverted call frame.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub revert_reason: Option<String>,
+    /// Innermost failed call to a system contract.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failed_system_call: Option<DiagnosticCallFrame>,
+}
+
+/// Call frame referenced in [`ExecutionDiagnostics`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiagnosticCallFrame {
+    pub from: Address,
+    pub to: Address,
+    /// Depth of the frame in the call tree; top-level frames have depth 0.
+    pub depth: usize,
+    pub gas: u32,
+    pub gas_used: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub revert_reason: Option<String>,
+}
+
+/// Overrides of the account state applied before executing `eth_call` or gas estimation, keyed by account address.
+/// The overrides are only visible to the executed call and are never persisted.
+pub type StateOverride = HashMap<Address, OverrideAccount>;
+
+/// State override for a single account, in the geth-compatible format.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct OverrideAccount {
+    /// Overridden base token balance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub balance: Option<U256>,
+    /// Overridden account nonce. The deployment nonce of the account is retained.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<U256>,
+    /// Overridden contract bytecode. Must be a valid EraVM bytecode.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<Bytes>,
+    /// Full replacement of the account storage; slots not mentioned are considered to be zero.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state: Option<HashMap<H256, H256>>,
+    /// Overrides of individual storage slots; other slots retain their values.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state_diff: Option<HashMap<H256, H256>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -687,6 +854,54 @@ pub struct L1BatchDetails {
     pub base: BlockDetailsBase,
 }
 
+/// Stage of an L1 batch confirmed on L1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum L1BatchStage {
+    Committed,
+    Proven,
+    Executed,
+}
+
+/// Change of the L1 batch status reported by the `l1BatchStatus` subscription.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct L1BatchStatusUpdate {
+    pub number: L1BatchNumber,
+    pub stage: L1BatchStage,
+    /// Hash of the L1 transaction that moved the batch to the `stage`.
+    pub eth_tx_hash: Option<H256>,
+}
+
+/// Priority operation received from L1 reported by the `newPriorityOperations` subscription.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PriorityOpInfo {
+    pub serial_id: u64,
+    pub tx_hash: H256,
+    /// Number of the L1 block the operation was emitted in.
+    pub eth_block: Option<u64>,
+}
+
+/// SNARK proof of an L1 batch together with the batch data it is verified against, as returned by `zks_getBatchProof`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct L1BatchProof {
+    pub number: L1BatchNumber,
+    pub protocol_version: Option<ProtocolVersionId>,
+    /// Root hash of the state tree after the batch.
+    pub root_hash: H256,
+    pub commitment: H256,
+    /// ABI-encoded `StoredBatchInfo` struct of the batch, as stored by the L1 `IExecutor` contract.
+    pub stored_batch_info: Bytes,
+    /// Aggregation result coordinates. Only used by the pre-boojum verifier.
+    pub aggregation_result_coords: Vec<H256>,
+    /// Proof serialized in the format expected by the L1 verifier contract.
+    pub serialized_proof: Vec<U256>,
+    /// Hash of the L1 transaction that submitted the proof, if any.
+    pub prove_tx_hash: Option<H256>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct StorageProof {
@@ -702,3 +917,98 @@ pub struct Proof {
     pub address: Address,
     pub storage_proof: Vec<StorageProof>,
 }
+
+/// Account and storage proofs returned by `eth_getProof`. The format is similar to [EIP-1186], but is adapted
+/// to the zkSync Merkle tree, which is a single sparse tree for storage slots of all accounts. In particular,
+/// account fields are proven as storage slots of the corresponding system contracts. See
+/// `docs/guides/advanced/state_proofs.md` for details on the proof encoding.
+///
+/// [EIP-1186]: https://eips.ethereum.org/EIPS/eip-1186
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountProof {
+    pub address: Address,
+    /// L1 batch after which the state is proven. This is the L1 batch containing the requested block.
+    pub l1_batch_number: L1BatchNumber,
+    /// Root hash of the Merkle tree after the L1 batch.
+    pub storage_hash: H256,
+    /// Account nonce (i.e., the number of transactions sent by the account).
+    pub nonce: U256,
+    /// ETH balance of the account.
+    pub balance: U256,
+    /// Bytecode hash of the account; zero for accounts without code.
+    pub code_hash: H256,
+    /// Proofs for the nonce, balance and bytecode hash slots, in this order.
+    pub account_proof: Vec<StorageProof>,
+    /// Proofs for the requested storage slots of the account.
+    pub storage_proof: Vec<StorageProof>,
+}
+
+/// Transactions in the mempool grouped by the initiator address and nonce, as returned by `txpool_content`.
+///
+/// Similar to Geth, `pending` transactions are ones executable without nonce gaps, and `queued` transactions
+/// are ones that have a gap between the account nonce and the transaction nonce.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TxpoolContent {
+    pub pending: BTreeMap<Address, BTreeMap<u32, Transaction>>,
+    pub queued: BTreeMap<Address, BTreeMap<u32, Transaction>>,
+}
+
+/// Number of transactions in the mempool, as returned by `txpool_status`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TxpoolStatus {
+    pub pending: U64,
+    pub queued: U64,
+}
+
+/// L1 transaction sent by the Ethereum sender that is not confirmed yet, as returned by `zks_getInflightL1Txs`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InflightL1Tx {
+    /// ID of the transaction in the Ethereum sender.
+    pub id: u32,
+    /// Type of the aggregated operation, e.g. `CommitBlocks`.
+    pub tx_type: String,
+    pub nonce: U64,
+    /// UNIX timestamp (in seconds) when the transaction was created.
+    pub created_at: u64,
+    /// Number of sent attempts for the transaction.
+    pub attempts: u32,
+    /// L1 block at which the first attempt was sent.
+    pub first_sent_at_block: Option<u32>,
+    /// Hash of the latest attempt.
+    pub last_tx_hash: Option<H256>,
+    /// L1 block at which the latest attempt was sent.
+    pub last_sent_at_block: Option<u32>,
+    /// Base fee of the latest attempt, in wei.
+    pub base_fee_per_gas: Option<u64>,
+    /// Priority fee of the latest attempt, in wei.
+    pub priority_fee_per_gas: Option<u64>,
+    /// Blob base fee of the latest attempt, in wei. Only set for blob transactions.
+    pub blob_base_fee_per_gas: Option<u64>,
+}
+
+/// Rotation of the operator account signing L1 transactions, as returned by the admin API.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OperatorRotation {
+    pub id: u32,
+    /// UNIX timestamp (in seconds) when the rotation was requested.
+    pub requested_at: u64,
+    /// UNIX timestamp (in seconds) when the Ethereum sender switched to the new operator account.
+    /// `None` while in-flight transactions of the previous operator are being drained.
+    pub completed_at: Option<u64>,
+    /// Address of the new operator account. Only set for completed rotations.
+    pub new_operator_address: Option<Address>,
+}
+
+impl From<crate::eth_sender::OperatorRotation> for OperatorRotation {
+    fn from(rotation: crate::eth_sender::OperatorRotation) -> Self {
+        Self {
+            id: rotation.id,
+            requested_at: rotation.requested_at_timestamp,
+            completed_at: rotation.completed_at_timestamp,
+            new_operator_address: rotation.new_operator_address,
+        }
+    }
+}
@@ -0,0 +1,319 @@
+//! API types for the OpenEthereum-compatible `trace` namespace.
+
+use serde::{Deserialize, Serialize};
+use zksync_basic_types::{web3::types::Bytes, Address, MiniblockNumber, H256, U256};
+
+use super::BlockNumber;
+use crate::{
+    vm_trace::{Call, CallType},
+    zk_evm_types::FarCallOpcode,
+};
+
+/// Filter for the `trace_filter` method.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TraceFilter {
+    /// Start of the miniblock range (inclusive). Defaults to the latest miniblock.
+    pub from_block: Option<BlockNumber>,
+    /// End of the miniblock range (inclusive). Defaults to the latest miniblock.
+    pub to_block: Option<BlockNumber>,
+    /// If non-empty, only traces with one of these callers are returned.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub from_address: Vec<Address>,
+    /// If non-empty, only traces with one of these callees (or created contracts) are returned.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub to_address: Vec<Address>,
+    /// Number of matching traces to skip.
+    pub after: Option<usize>,
+    /// Maximum number of traces to return.
+    pub count: Option<usize>,
+}
+
+impl TraceFilter {
+    /// Checks whether the trace matches address constraints of this filter.
+    pub fn matches(&self, trace: &LocalizedTrace) -> bool {
+        let (from, to) = match (&trace.action, &trace.result) {
+            (TraceAction::Call(action), _) => (action.from, Some(action.to)),
+            (TraceAction::Create(action), Some(TraceResult::Create(result))) => {
+                (action.from, Some(result.address))
+            }
+            (TraceAction::Create(action), _) => (action.from, None),
+        };
+        let from_matches = self.from_address.is_empty() || self.from_address.contains(&from);
+        let to_matches = self.to_address.is_empty()
+            || to.map_or(false, |address| self.to_address.contains(&address));
+        from_matches && to_matches
+    }
+}
+
+/// Type of [`LocalizedTrace`].
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TraceType {
+    Call,
+    Create,
+}
+
+/// Type of [`CallAction`].
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TraceCallType {
+    Call,
+    DelegateCall,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallAction {
+    pub from: Address,
+    pub to: Address,
+    pub value: U256,
+    pub gas: U256,
+    pub input: Bytes,
+    pub call_type: TraceCallType,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateAction {
+    pub from: Address,
+    pub value: U256,
+    pub gas: U256,
+    pub init: Bytes,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum TraceAction {
+    Call(CallAction),
+    Create(CreateAction),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallResult {
+    pub gas_used: U256,
+    pub output: Bytes,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateResult {
+    pub gas_used: U256,
+    pub code: Bytes,
+    pub address: Address,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum TraceResult {
+    Call(CallResult),
+    Create(CreateResult),
+}
+
+/// Location of a traced transaction in the chain.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TransactionTraceLocation {
+    pub tx_hash: H256,
+    pub tx_index_in_block: u32,
+    pub block_number: MiniblockNumber,
+    pub block_hash: H256,
+}
+
+/// Call trace in the OpenEthereum-compatible format. Unlike [`DebugCall`](super::DebugCall), traces
+/// are flat; the position of a trace in the call tree is specified by its `trace_address`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalizedTrace {
+    pub action: TraceAction,
+    /// Result of the call; `None` if the call has failed.
+    pub result: Option<TraceResult>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Number of direct subcalls.
+    pub subtraces: usize,
+    /// Indices of the call in the call tree, starting from the top-level call of the transaction.
+    pub trace_address: Vec<usize>,
+    pub transaction_hash: H256,
+    pub transaction_position: u64,
+    pub block_number: u64,
+    pub block_hash: H256,
+    #[serde(rename = "type")]
+    pub trace_type: TraceType,
+}
+
+impl LocalizedTrace {
+    /// Flattens the call trace of a transaction into the list of traces in the depth-first order.
+    pub fn flatten(call: Call, location: &TransactionTraceLocation) -> Vec<Self> {
+        let mut traces = vec![];
+        Self::push_traces(call, vec![], location, &mut traces);
+        traces
+    }
+
+    fn push_traces(
+        mut call: Call,
+        trace_address: Vec<usize>,
+        location: &TransactionTraceLocation,
+        traces: &mut Vec<Self>,
+    ) {
+        let subcalls = Self::expand_near_calls(std::mem::take(&mut call.calls));
+        traces.push(Self::new(
+            &call,
+            subcalls.len(),
+            trace_address.clone(),
+            location,
+        ));
+        for (i, subcall) in subcalls.into_iter().enumerate() {
+            let mut subcall_address = trace_address.clone();
+            subcall_address.push(i);
+            Self::push_traces(subcall, subcall_address, location, traces);
+        }
+    }
+
+    /// Replaces near calls with their subcalls, since near calls are not visible in the OpenEthereum format.
+    fn expand_near_calls(calls: Vec<Call>) -> Vec<Call> {
+        calls
+            .into_iter()
+            .flat_map(|call| match call.r#type {
+                CallType::NearCall => Self::expand_near_calls(call.calls),
+                _ => vec![call],
+            })
+            .collect()
+    }
+
+    fn new(
+        call: &Call,
+        subtraces: usize,
+        trace_address: Vec<usize>,
+        location: &TransactionTraceLocation,
+    ) -> Self {
+        let (trace_type, action) = match call.r#type {
+            CallType::Create => {
+                let action = CreateAction {
+                    from: call.from,
+                    value: call.value,
+                    gas: call.gas.into(),
+                    init: Bytes(call.input.clone()),
+                };
+                (TraceType::Create, TraceAction::Create(action))
+            }
+            CallType::Call(opcode) => {
+                let call_type = match opcode {
+                    FarCallOpcode::Delegate => TraceCallType::DelegateCall,
+                    _ => TraceCallType::Call,
+                };
+                let action = CallAction {
+                    from: call.from,
+                    to: call.to,
+                    value: call.value,
+                    gas: call.gas.into(),
+                    input: Bytes(call.input.clone()),
+                    call_type,
+                };
+                (TraceType::Call, TraceAction::Call(action))
+            }
+            CallType::NearCall => unreachable!("Near calls are expanded before"),
+        };
+
+        let error = if call.revert_reason.is_some() {
+            Some("Reverted".to_owned())
+        } else {
+            call.error.clone()
+        };
+        let result = error.is_none().then(|| match trace_type {
+            TraceType::Call => TraceResult::Call(CallResult {
+                gas_used: call.gas_used.into(),
+                output: Bytes(call.output.clone()),
+            }),
+            TraceType::Create => TraceResult::Create(CreateResult {
+                gas_used: call.gas_used.into(),
+                code: Bytes(call.output.clone()),
+                address: call.to,
+            }),
+        });
+
+        Self {
+            action,
+            result,
+            error,
+            subtraces,
+            trace_address,
+            transaction_hash: location.tx_hash,
+            transaction_position: location.tx_index_in_block.into(),
+            block_number: location.block_number.0.into(),
+            block_hash: location.block_hash,
+            trace_type,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call(r#type: CallType, to: u8, calls: Vec<Call>) -> Call {
+        Call {
+            r#type,
+            to: Address::repeat_byte(to),
+            calls,
+            ..Call::default()
+        }
+    }
+
+    #[test]
+    fn flattening_call_trace() {
+        let location = TransactionTraceLocation {
+            tx_hash: H256::repeat_byte(1),
+            tx_index_in_block: 2,
+            block_number: MiniblockNumber(3),
+            block_hash: H256::repeat_byte(4),
+        };
+        let nested_calls = vec![
+            call(CallType::Call(FarCallOpcode::Delegate), 2, vec![]),
+            call(
+                CallType::NearCall,
+                0,
+                vec![call(CallType::Create, 3, vec![])],
+            ),
+        ];
+        let top_call = call(
+            CallType::Call(FarCallOpcode::Normal),
+            1,
+            vec![call(CallType::Call(FarCallOpcode::Normal), 4, nested_calls)],
+        );
+
+        let traces = LocalizedTrace::flatten(top_call, &location);
+        let trace_addresses: Vec<_> = traces.iter().map(|trace| &trace.trace_address).collect();
+        assert_eq!(
+            trace_addresses,
+            [vec![], vec![0], vec![0, 0], vec![0, 1]] as [Vec<usize>; 4]
+        );
+        let subtraces: Vec<_> = traces.iter().map(|trace| trace.subtraces).collect();
+        assert_eq!(subtraces, [1, 2, 0, 0]);
+        assert!(matches!(
+            &traces[2].action,
+            TraceAction::Call(CallAction {
+                call_type: TraceCallType::DelegateCall,
+                ..
+            })
+        ));
+        assert_eq!(traces[3].trace_type, TraceType::Create);
+        assert!(matches!(
+            &traces[3].result,
+            Some(TraceResult::Create(CreateResult { address, .. })) if *address == Address::repeat_byte(3)
+        ));
+        assert!(traces
+            .iter()
+            .all(|trace| trace.transaction_position == 2 && trace.block_number == 3));
+
+        let filter = TraceFilter {
+            to_address: vec![Address::repeat_byte(3)],
+            ..TraceFilter::default()
+        };
+        let matching_traces: Vec<_> = traces
+            .iter()
+            .filter(|trace| filter.matches(trace))
+            .collect();
+        assert_eq!(matching_traces, [&traces[3]]);
+    }
+}
@@ -1,4 +1,4 @@
-use std::{collections::HashMap, fmt};
+use std::{collections::HashMap, fmt, str::FromStr};
 
 use chrono::{DateTime, Utc};
 use serde::{
@@ -215,12 +215,48 @@ pub struct CompilationArtifacts {
     pub abi: serde_json::Value,
 }
 
+/// How the bytecode compiled from the provided sources matches the deployed bytecode.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum VerificationMatch {
+    /// Bytecodes are equal.
+    #[default]
+    Full,
+    /// Bytecodes are equal except for the metadata hash appended by the compiler. Means that the contract
+    /// logic is verified, but the sources may differ from the original ones in comments, file names etc.
+    Partial,
+}
+
+impl VerificationMatch {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Full => "full",
+            Self::Partial => "partial",
+        }
+    }
+}
+
+impl FromStr for VerificationMatch {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "full" => Ok(Self::Full),
+            "partial" => Ok(Self::Partial),
+            _ => Err(format!("unknown verification match type: {s}")),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct VerificationInfo {
     pub request: VerificationRequest,
     pub artifacts: CompilationArtifacts,
     pub verified_at: DateTime<Utc>,
+    /// Absent for contracts verified before partial matches were supported, which are full matches.
+    #[serde(default)]
+    pub match_type: VerificationMatch,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -231,6 +267,9 @@ pub struct VerificationRequestStatus {
     pub error: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub compilation_errors: Option<Vec<String>>,
+    /// Set for successful verification requests.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub match_type: Option<VerificationMatch>,
 }
 
 #[derive(Debug)]
@@ -241,7 +280,20 @@ pub enum DeployContractCalldata {
 
 #[cfg(test)]
 mod tests {
-    use super::SourceCodeData;
+    use super::{SourceCodeData, VerificationMatch};
+
+    #[test]
+    fn verification_match_serialization() {
+        for match_type in [VerificationMatch::Full, VerificationMatch::Partial] {
+            let json = serde_json::to_value(match_type).unwrap();
+            assert_eq!(json, match_type.as_str());
+            assert_eq!(
+                match_type.as_str().parse::<VerificationMatch>(),
+                Ok(match_type)
+            );
+        }
+        assert!("exact".parse::<VerificationMatch>().is_err());
+    }
 
     #[test]
     fn source_code_deserialization() {
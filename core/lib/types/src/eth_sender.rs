@@ -1,5 +1,62 @@
+use serde::{Deserialize, Serialize};
+
 use crate::{aggregated_operations::AggregatedActionType, Address, Nonce, H256};
 
+/// Blob attached to an EIP-4844 transaction, together with its KZG commitment and proof.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SidecarBlobV1 {
+    /// Blob data (128 KiB, i.e. 4,096 field elements).
+    pub blob: Vec<u8>,
+    /// KZG commitment to the blob (48 bytes).
+    pub commitment: Vec<u8>,
+    /// KZG proof that the blob matches the commitment (48 bytes).
+    pub proof: Vec<u8>,
+    /// Versioned hash of the commitment, which is referenced by the transaction payload.
+    pub versioned_hash: Vec<u8>,
+}
+
+impl std::fmt::Debug for SidecarBlobV1 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Do not print the blob itself
+        f.debug_struct("SidecarBlobV1")
+            .field("commitment", &hex::encode(&self.commitment))
+            .field("versioned_hash", &hex::encode(&self.versioned_hash))
+            .finish_non_exhaustive()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EthTxBlobSidecarV1 {
+    pub blobs: Vec<SidecarBlobV1>,
+}
+
+/// Sidecar of an EIP-4844 transaction, i.e. the data that is sent to the network together
+/// with the transaction, but is not a part of the transaction payload. Versioned to be stored in Postgres.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EthTxBlobSidecar {
+    EthTxBlobSidecarV1(EthTxBlobSidecarV1),
+}
+
+impl From<EthTxBlobSidecarV1> for EthTxBlobSidecar {
+    fn from(sidecar: EthTxBlobSidecarV1) -> Self {
+        Self::EthTxBlobSidecarV1(sidecar)
+    }
+}
+
+impl EthTxBlobSidecar {
+    /// Returns versioned hashes of all blobs in the sidecar in the order they should be placed
+    /// in the transaction payload.
+    pub fn versioned_hashes(&self) -> Vec<H256> {
+        match self {
+            Self::EthTxBlobSidecarV1(sidecar) => sidecar
+                .blobs
+                .iter()
+                .map(|blob| H256::from_slice(&blob.versioned_hash))
+                .collect(),
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct EthTx {
     pub id: u32,
@@ -9,6 +66,11 @@ pub struct EthTx {
     pub tx_type: AggregatedActionType,
     pub created_at_timestamp: u64,
     pub predicted_gas_cost: u64,
+    /// If set, the transaction is sent as an EIP-4844 blob transaction with this sidecar.
+    pub blob_sidecar: Option<EthTxBlobSidecar>,
+    /// Address of the operator account the transaction is sent from. `None` for transactions created
+    /// before operator accounts were tracked.
+    pub from_addr: Option<Address>,
 }
 
 impl std::fmt::Debug for EthTx {
@@ -21,16 +83,36 @@ impl std::fmt::Debug for EthTx {
             .field("tx_type", &self.tx_type)
             .field("created_at_timestamp", &self.created_at_timestamp)
             .field("predicted_gas_cost", &self.predicted_gas_cost)
+            .field("blob_sidecar", &self.blob_sidecar)
+            .field("from_addr", &self.from_addr)
             .finish()
     }
 }
 
+/// Rotation of the operator account used to sign L1 transactions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OperatorRotation {
+    pub id: u32,
+    pub requested_at_timestamp: u64,
+    /// Set once the Ethereum sender has switched to the new operator account.
+    pub completed_at_timestamp: Option<u64>,
+    /// Address of the new operator account. Only set for completed rotations.
+    pub new_operator_address: Option<Address>,
+}
+
+impl OperatorRotation {
+    pub fn is_completed(&self) -> bool {
+        self.completed_at_timestamp.is_some()
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct TxHistory {
     pub id: u32,
     pub eth_tx_id: u32,
     pub base_fee_per_gas: u64,
     pub priority_fee_per_gas: u64,
+    pub blob_base_fee_per_gas: Option<u64>,
     pub tx_hash: H256,
     pub signed_raw_tx: Vec<u8>,
     pub sent_at_block: Option<u32>,
@@ -1,8 +1,10 @@
+use std::num::NonZeroU64;
+
 use serde::{Deserialize, Serialize};
 use zksync_config::configs::chain::{FeeModelVersion, StateKeeperConfig};
 use zksync_system_constants::L1_GAS_PER_PUBDATA_BYTE;
 
-use crate::ProtocolVersionId;
+use crate::{ProtocolVersionId, U256};
 
 /// Fee input to be provided into the VM. It contains two options:
 /// - `L1Pegged`: L1 gas price is provided to the VM, and the pubdata price is derived from it. Using this option is required for the
@@ -228,10 +230,44 @@ impl FeeModelConfig {
     }
 }
 
+/// Conversion ratio between the base token used to pay fees and ETH: `numerator` base token units
+/// are worth `denominator` wei. Fee model params (L1 prices and the minimal L2 gas price) are denominated in wei
+/// and are converted to the base token using this ratio. The default ratio (1:1) corresponds to ETH as the base token.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BaseTokenConversionRatio {
+    pub numerator: NonZeroU64,
+    pub denominator: NonZeroU64,
+}
+
+impl Default for BaseTokenConversionRatio {
+    fn default() -> Self {
+        let one = NonZeroU64::new(1).unwrap();
+        Self {
+            numerator: one,
+            denominator: one,
+        }
+    }
+}
+
+impl BaseTokenConversionRatio {
+    /// Converts a price in wei to the base token. Saturates to `u64::MAX` on overflow.
+    pub fn convert(&self, wei: u64) -> u64 {
+        let converted =
+            U256::from(wei) * U256::from(self.numerator.get()) / U256::from(self.denominator.get());
+        if converted > U256::from(u64::MAX) {
+            u64::MAX
+        } else {
+            converted.as_u64()
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct FeeParamsV1 {
     pub config: FeeModelConfigV1,
     pub l1_gas_price: u64,
+    #[serde(default)]
+    pub conversion_ratio: BaseTokenConversionRatio,
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
@@ -239,6 +275,8 @@ pub struct FeeParamsV2 {
     pub config: FeeModelConfigV2,
     pub l1_gas_price: u64,
     pub l1_pubdata_price: u64,
+    #[serde(default)]
+    pub conversion_ratio: BaseTokenConversionRatio,
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
@@ -255,6 +293,15 @@ impl FeeParams {
                 minimal_l2_gas_price: 100_000_000,
             },
             l1_gas_price: 1_000_000_000,
+            conversion_ratio: BaseTokenConversionRatio::default(),
         })
     }
+
+    /// Returns the conversion ratio between the base token and ETH.
+    pub fn conversion_ratio(&self) -> BaseTokenConversionRatio {
+        match self {
+            Self::V1(params) => params.conversion_ratio,
+            Self::V2(params) => params.conversion_ratio,
+        }
+    }
 }
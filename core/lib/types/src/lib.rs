@@ -38,6 +38,7 @@ pub mod l2;
 pub mod l2_to_l1_log;
 pub mod priority_op_onchain_data;
 pub mod protocol_version;
+pub mod pubdata_da;
 pub mod snapshots;
 pub mod storage;
 pub mod storage_writes_deduplicator;
@@ -61,6 +62,9 @@ pub const EIP_712_TX_TYPE: u8 = 0x71;
 /// Denotes the first byte of the `EIP-1559` transaction.
 pub const EIP_1559_TX_TYPE: u8 = 0x02;
 
+/// Denotes the first byte of the `EIP-4844` (blob) transaction.
+pub const EIP_4844_TX_TYPE: u8 = 0x03;
+
 /// Denotes the first byte of the `EIP-2930` transaction.
 pub const EIP_2930_TX_TYPE: u8 = 0x01;
 
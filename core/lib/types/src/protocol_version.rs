@@ -104,6 +104,10 @@ impl ProtocolVersionId {
     pub fn is_post_1_4_1(&self) -> bool {
         self >= &ProtocolVersionId::Version20
     }
+
+    pub fn is_pre_1_4_2(&self) -> bool {
+        self < &ProtocolVersionId::Version21
+    }
 }
 
 impl Default for ProtocolVersionId {
@@ -0,0 +1,22 @@
+use serde::{Deserialize, Serialize};
+use zksync_config::configs::eth_sender::PubdataSendingMode;
+
+/// Source of L1 batch pubdata as encoded in the first byte of `pubdataCommitments`
+/// expected by the L1 contract (for protocol versions starting from 1.4.2).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[repr(u8)]
+pub enum PubdataDA {
+    /// Pubdata is sent in the commit transaction calldata.
+    Calldata = 0,
+    /// Pubdata is sent in EIP-4844 blobs; calldata only contains KZG commitments to the blobs.
+    Blobs = 1,
+}
+
+impl From<PubdataSendingMode> for PubdataDA {
+    fn from(mode: PubdataSendingMode) -> Self {
+        match mode {
+            PubdataSendingMode::Calldata => Self::Calldata,
+            PubdataSendingMode::Blobs => Self::Blobs,
+        }
+    }
+}
@@ -1,15 +1,72 @@
 use std::{convert::TryFrom, ops};
 
 use anyhow::Context;
+use num_enum::TryFromPrimitive;
+use prost::Message;
 use serde::{Deserialize, Serialize};
 use zksync_basic_types::{AccountTreeId, L1BatchNumber, MiniblockNumber, H256};
 use zksync_protobuf::{required, ProtoFmt};
 use zksync_utils::u256_to_h256;
 
 use crate::{
-    commitment::L1BatchWithMetadata, Bytes, ProtocolVersionId, StorageKey, StorageValue, U256,
+    commitment::L1BatchWithMetadata,
+    tokens::{TokenInfo, TokenMetadata},
+    web3::signing::keccak256,
+    Bytes, ProtocolVersionId, StorageKey, StorageValue, U256,
 };
 
+/// Version of the snapshot format. Persisted together with the snapshot metadata and returned in [`SnapshotHeader`],
+/// so that snapshot consumers can reject snapshots they don't know how to apply.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    TryFromPrimitive
+)]
+#[repr(u16)]
+pub enum SnapshotVersion {
+    /// Initial format: factory deps and storage logs chunks without checksums. Tokens are not included
+    /// in the snapshot and must be fetched from the main node.
+    Version0 = 0,
+    /// Adds a tokens file and checksums for all snapshot files.
+    Version1 = 1,
+    /// Allows incremental snapshots, which reuse storage logs chunks produced for previous snapshots.
+    /// Such chunks are marked with [`SnapshotStorageLogsChunkMetadata::l1_batch_number`].
+    Version2 = 2,
+}
+
+impl SnapshotVersion {
+    /// Version of full snapshots produced by the snapshots creator.
+    pub const LATEST: Self = Self::Version1;
+    /// Version of incremental snapshots produced by the snapshots creator.
+    pub const INCREMENTAL: Self = Self::Version2;
+
+    /// Checks whether snapshots of this version include a tokens file and file checksums.
+    pub fn has_tokens_and_checksums(self) -> bool {
+        self >= Self::Version1
+    }
+
+    /// Checks whether snapshots of this version may reuse storage logs chunks from previous snapshots.
+    pub fn supports_reused_chunks(self) -> bool {
+        self >= Self::Version2
+    }
+}
+
+/// Computes the checksum of a snapshot file. The checksum is the `keccak256` digest of the Protobuf encoding
+/// of the file contents (i.e., before compression, which is not guaranteed to be deterministic).
+pub fn snapshot_file_checksum<T>(value: &T) -> H256
+where
+    T: ProtoFmt,
+    T::Proto: Message,
+{
+    H256(keccak256(&value.build().encode_to_vec()))
+}
+
 /// Information about all snapshots persisted by the node.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -22,19 +79,45 @@ pub struct AllSnapshots {
 /// Storage snapshot metadata. Used in DAL to fetch certain snapshot data.
 #[derive(Debug, Clone)]
 pub struct SnapshotMetadata {
+    pub version: SnapshotVersion,
     /// L1 batch for the snapshot. The data in the snapshot captures node storage at the end of this batch.
     pub l1_batch_number: L1BatchNumber,
     /// Path to the factory dependencies blob.
     pub factory_deps_filepath: String,
+    /// Checksum of the factory dependencies blob. Always set for snapshots with [`SnapshotVersion::Version1`]
+    /// or newer.
+    pub factory_deps_checksum: Option<H256>,
     /// Paths to the storage log blobs. Ordered by the chunk ID. If a certain chunk is not produced yet,
     /// the corresponding path is `None`.
     pub storage_logs_filepaths: Vec<Option<String>>,
+    /// Checksums of the storage log blobs, ordered by the chunk ID. Empty for snapshots without checksums.
+    pub storage_logs_checksums: Vec<Option<H256>>,
+    /// Path to the tokens blob. `None` if the tokens file is not produced yet, or if the snapshot version
+    /// doesn't include tokens.
+    pub tokens_filepath: Option<String>,
+    pub tokens_checksum: Option<H256>,
+    /// L1 batch of the snapshot this snapshot is based on. Only set for incremental snapshots.
+    pub base_l1_batch_number: Option<L1BatchNumber>,
+    /// L1 batches of the snapshots that have produced storage log blobs, ordered by the chunk ID.
+    /// Empty for full snapshots, for which all blobs are produced by the snapshot itself.
+    pub storage_logs_source_l1_batches: Vec<L1BatchNumber>,
 }
 
 impl SnapshotMetadata {
     /// Checks whether a snapshot is complete (contains all information to restore from).
     pub fn is_complete(&self) -> bool {
-        self.storage_logs_filepaths.iter().all(Option::is_some)
+        let are_tokens_complete =
+            !self.version.has_tokens_and_checksums() || self.tokens_filepath.is_some();
+        are_tokens_complete && self.storage_logs_filepaths.iter().all(Option::is_some)
+    }
+
+    /// Returns the L1 batch of the snapshot that has produced the storage logs chunk with the specified ID.
+    /// This is the L1 batch of this snapshot unless the chunk is reused from a previous snapshot.
+    pub fn storage_logs_chunk_source(&self, chunk_id: u64) -> L1BatchNumber {
+        self.storage_logs_source_l1_batches
+            .get(chunk_id as usize)
+            .copied()
+            .unwrap_or(self.l1_batch_number)
     }
 }
 
@@ -43,11 +126,25 @@ impl SnapshotMetadata {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SnapshotHeader {
+    /// Raw [`SnapshotVersion`]. Defaults to 0 for main nodes that don't report the version.
+    #[serde(default)]
+    pub version: u16,
     pub l1_batch_number: L1BatchNumber,
     pub miniblock_number: MiniblockNumber,
     /// Ordered by chunk IDs.
     pub storage_logs_chunks: Vec<SnapshotStorageLogsChunkMetadata>,
     pub factory_deps_filepath: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub factory_deps_checksum: Option<H256>,
+    /// Set for snapshots with [`SnapshotVersion::Version1`] or newer.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tokens_filepath: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tokens_checksum: Option<H256>,
+    /// L1 batch of the snapshot this snapshot is based on. Only set for incremental snapshots
+    /// ([`SnapshotVersion::Version2`] or newer).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_l1_batch_number: Option<L1BatchNumber>,
     pub last_l1_batch_with_metadata: L1BatchWithMetadata,
 }
 
@@ -57,6 +154,12 @@ pub struct SnapshotStorageLogsChunkMetadata {
     pub chunk_id: u64,
     // can be either be a file available under HTTP(s) or local filesystem path
     pub filepath: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checksum: Option<H256>,
+    /// L1 batch of the snapshot that has produced this chunk. Only set for chunks reused by incremental snapshots;
+    /// the chunk must be fetched from the object store using this L1 batch number instead of the snapshot one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub l1_batch_number: Option<L1BatchNumber>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -89,6 +192,13 @@ pub struct SnapshotFactoryDependency {
     pub bytecode: Bytes,
 }
 
+/// Tokens deployed on L2 as of the snapshot miniblock. Included into snapshots starting from
+/// [`SnapshotVersion::Version1`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SnapshotTokens {
+    pub tokens: Vec<TokenInfo>,
+}
+
 impl ProtoFmt for SnapshotFactoryDependency {
     type Proto = crate::proto::SnapshotFactoryDependency;
 
@@ -190,6 +300,51 @@ impl ProtoFmt for SnapshotStorageLogsChunk {
     }
 }
 
+fn read_token(r: &crate::proto::SnapshotToken) -> anyhow::Result<TokenInfo> {
+    let decimals = *required(&r.decimals).context("decimals")?;
+    Ok(TokenInfo {
+        l1_address: required(&r.l1_address)
+            .and_then(|bytes| Ok(<[u8; 20]>::try_from(bytes.as_slice())?.into()))
+            .context("l1_address")?,
+        l2_address: required(&r.l2_address)
+            .and_then(|bytes| Ok(<[u8; 20]>::try_from(bytes.as_slice())?.into()))
+            .context("l2_address")?,
+        metadata: TokenMetadata {
+            name: required(&r.name).context("name")?.clone(),
+            symbol: required(&r.symbol).context("symbol")?.clone(),
+            decimals: u8::try_from(decimals).context("decimals")?,
+        },
+    })
+}
+
+fn build_token(token: &TokenInfo) -> crate::proto::SnapshotToken {
+    crate::proto::SnapshotToken {
+        l1_address: Some(token.l1_address.as_bytes().into()),
+        l2_address: Some(token.l2_address.as_bytes().into()),
+        name: Some(token.metadata.name.clone()),
+        symbol: Some(token.metadata.symbol.clone()),
+        decimals: Some(token.metadata.decimals.into()),
+    }
+}
+
+impl ProtoFmt for SnapshotTokens {
+    type Proto = crate::proto::SnapshotTokens;
+
+    fn read(r: &Self::Proto) -> anyhow::Result<Self> {
+        let mut tokens = Vec::with_capacity(r.tokens.len());
+        for (i, token) in r.tokens.iter().enumerate() {
+            tokens.push(read_token(token).with_context(|| format!("tokens[{i}]"))?);
+        }
+        Ok(Self { tokens })
+    }
+
+    fn build(&self) -> Self::Proto {
+        Self::Proto {
+            tokens: self.tokens.iter().map(build_token).collect(),
+        }
+    }
+}
+
 /// Status of snapshot recovery process stored in Postgres.
 #[derive(Debug, PartialEq)]
 pub struct SnapshotRecoveryStatus {
@@ -289,4 +444,45 @@ mod tests {
             assert!(max_chunk_size - min_chunk_size < U256::from(chunks_count));
         }
     }
+
+    #[test]
+    fn snapshot_file_checksum_depends_on_contents() {
+        let factory_deps = SnapshotFactoryDependencies {
+            factory_deps: vec![SnapshotFactoryDependency {
+                bytecode: Bytes(vec![1, 2, 3]),
+            }],
+        };
+        let checksum = snapshot_file_checksum(&factory_deps);
+        let restored = SnapshotFactoryDependencies::read(&factory_deps.build()).unwrap();
+        assert_eq!(snapshot_file_checksum(&restored), checksum);
+
+        let other_factory_deps = SnapshotFactoryDependencies {
+            factory_deps: vec![SnapshotFactoryDependency {
+                bytecode: Bytes(vec![1, 2, 4]),
+            }],
+        };
+        assert_ne!(snapshot_file_checksum(&other_factory_deps), checksum);
+    }
+
+    #[test]
+    fn snapshot_version_conversions() {
+        assert_eq!(
+            SnapshotVersion::try_from(0_u16).unwrap(),
+            SnapshotVersion::Version0
+        );
+        assert_eq!(
+            SnapshotVersion::try_from(1_u16).unwrap(),
+            SnapshotVersion::Version1
+        );
+        assert_eq!(
+            SnapshotVersion::try_from(2_u16).unwrap(),
+            SnapshotVersion::Version2
+        );
+        assert!(SnapshotVersion::try_from(1_000_u16).is_err());
+        assert!(!SnapshotVersion::Version0.has_tokens_and_checksums());
+        assert!(SnapshotVersion::LATEST.has_tokens_and_checksums());
+        assert!(!SnapshotVersion::LATEST.supports_reused_chunks());
+        assert!(SnapshotVersion::INCREMENTAL.has_tokens_and_checksums());
+        assert!(SnapshotVersion::INCREMENTAL.supports_reused_chunks());
+    }
 }
@@ -66,6 +66,10 @@ pub struct ExecutionMetrics {
     pub contracts_deployed: u16,
     pub vm_events: usize,
     pub storage_logs: usize,
+    /// Number of storage reads performed by the VM, including repeated reads of the same slot.
+    pub storage_reads: usize,
+    /// Number of distinct storage slots written to.
+    pub storage_writes: usize,
     pub total_log_queries: usize,
     pub cycles_used: u32,
     pub computational_gas_used: u32,
@@ -83,6 +87,9 @@ impl ExecutionMetrics {
             contracts_used: tx_metrics.contracts_used,
             gas_used: tx_metrics.gas_used,
             storage_logs: tx_metrics.storage_logs,
+            // Storage reads are not tracked in transaction metrics.
+            storage_reads: 0,
+            storage_writes: tx_metrics.initial_storage_writes + tx_metrics.repeated_storage_writes,
             vm_events: tx_metrics.vm_events,
             total_log_queries: tx_metrics.total_log_queries,
             cycles_used: tx_metrics.cycles_used,
@@ -118,6 +125,8 @@ impl Add for ExecutionMetrics {
             gas_used: self.gas_used + other.gas_used,
             vm_events: self.vm_events + other.vm_events,
             storage_logs: self.storage_logs + other.storage_logs,
+            storage_reads: self.storage_reads + other.storage_reads,
+            storage_writes: self.storage_writes + other.storage_writes,
             total_log_queries: self.total_log_queries + other.total_log_queries,
             cycles_used: self.cycles_used + other.cycles_used,
             computational_gas_used: self.computational_gas_used + other.computational_gas_used,
@@ -0,0 +1,59 @@
+//! Sentry breadcrumbs for lifecycle events of node components.
+//!
+//! Regular breadcrumbs created from logs are stored in a single per-hub buffer, so lifecycle events
+//! are quickly displaced by less important logs. Lifecycle breadcrumbs are instead stored in a global per-component
+//! buffer and are attached to all events sent to Sentry.
+
+use std::{
+    collections::{BTreeMap, VecDeque},
+    sync::{Mutex, PoisonError},
+    time::SystemTime,
+};
+
+use sentry::{protocol::Event, Breadcrumb, Level};
+
+/// Maximum number of lifecycle breadcrumbs stored for each component.
+pub const MAX_LIFECYCLE_BREADCRUMBS: usize = 50;
+
+static LIFECYCLE_BREADCRUMBS: Mutex<BTreeMap<&'static str, VecDeque<Breadcrumb>>> =
+    Mutex::new(BTreeMap::new());
+
+/// Records a lifecycle event of a component (e.g., sealing an L1 batch or sending a transaction to L1) as a Sentry
+/// breadcrumb. Only the last [`MAX_LIFECYCLE_BREADCRUMBS`] events are retained for each component.
+pub fn add_lifecycle_breadcrumb(
+    component: &'static str,
+    message: impl Into<String>,
+    data: impl IntoIterator<Item = (&'static str, serde_json::Value)>,
+) {
+    let breadcrumb = Breadcrumb {
+        timestamp: SystemTime::now(),
+        ty: "info".to_owned(),
+        category: Some(component.to_owned()),
+        level: Level::Info,
+        message: Some(message.into()),
+        data: data
+            .into_iter()
+            .map(|(key, value)| (key.to_owned(), value))
+            .collect(),
+    };
+
+    let mut breadcrumbs = LIFECYCLE_BREADCRUMBS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    let component_breadcrumbs = breadcrumbs.entry(component).or_default();
+    if component_breadcrumbs.len() >= MAX_LIFECYCLE_BREADCRUMBS {
+        component_breadcrumbs.pop_front();
+    }
+    component_breadcrumbs.push_back(breadcrumb);
+}
+
+/// Merges lifecycle breadcrumbs into the event. Used as the `before_send` Sentry callback.
+pub(crate) fn attach_lifecycle_breadcrumbs(mut event: Event<'static>) -> Option<Event<'static>> {
+    let breadcrumbs = LIFECYCLE_BREADCRUMBS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    let values = &mut event.breadcrumbs.values;
+    values.extend(breadcrumbs.values().flatten().cloned());
+    values.sort_by_key(|breadcrumb| breadcrumb.timestamp);
+    Some(event)
+}
@@ -0,0 +1,134 @@
+//! JSON log format with a stable schema.
+
+use std::fmt;
+
+use serde_json::{json, Map, Value};
+use tracing::{
+    field::{Field, Visit},
+    Event, Subscriber,
+};
+use tracing_subscriber::{
+    fmt::{
+        format::Writer,
+        time::{FormatTime, UtcTime},
+        FmtContext, FormatEvent, FormatFields, FormattedFields,
+    },
+    registry::LookupSpan,
+};
+
+use crate::{throttle, COMPONENT_SPAN};
+
+/// JSON log format. Each log entry is a single-line JSON object with the following fields, which are always present:
+///
+/// - `timestamp`: RFC 3339 timestamp in UTC.
+/// - `level`: log level (`ERROR`, `WARN`, `INFO`, `DEBUG` or `TRACE`).
+/// - `target`: log target, usually the module path.
+/// - `filename` and `line_number`: location of the log statement, or `null` if unknown.
+/// - `message`: log message.
+/// - `component`: component the log originates from (see [`COMPONENT_SPAN`]), or `null`.
+/// - `fields`: object with structured fields of the log event. If identical messages were suppressed because of
+///   [log throttling](crate::ObservabilityBuilder::with_log_throttling()), it contains the `suppressed_count` field.
+/// - `context`: object with fields of all entered spans (e.g., `l1_batch_number` or `tx_hash`). If several spans
+///   have a field with the same name, the value from the innermost span is used.
+/// - `spans`: names of entered spans, from the outermost to the innermost one.
+///
+/// Span fields must be formatted with [`JsonFields`](tracing_subscriber::fmt::format::JsonFields).
+#[derive(Debug)]
+pub(crate) struct JsonFormat;
+
+impl<S, N> FormatEvent<S, N> for JsonFormat
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    N: for<'a> FormatFields<'a> + 'static,
+{
+    fn format_event(
+        &self,
+        ctx: &FmtContext<'_, S, N>,
+        mut writer: Writer<'_>,
+        event: &Event<'_>,
+    ) -> fmt::Result {
+        let metadata = event.metadata();
+        let mut timestamp = String::new();
+        UtcTime::rfc_3339().format_time(&mut Writer::new(&mut timestamp))?;
+
+        let mut context = Map::new();
+        let mut spans = vec![];
+        if let Some(scope) = ctx.event_scope() {
+            for span in scope.from_root() {
+                spans.push(Value::from(span.name()));
+                let extensions = span.extensions();
+                let fields = extensions
+                    .get::<FormattedFields<N>>()
+                    .and_then(|fields| serde_json::from_str::<Map<String, Value>>(fields).ok());
+                context.extend(fields.into_iter().flatten());
+            }
+        }
+
+        let mut visitor = FieldsVisitor::default();
+        event.record(&mut visitor);
+        let message = visitor.fields.remove("message").unwrap_or_default();
+        let component = context.get("component").cloned().unwrap_or_default();
+        let suppressed_count = throttle::suppressed_count();
+        if suppressed_count > 0 {
+            visitor.fields.insert(
+                throttle::SUPPRESSED_COUNT_FIELD.to_owned(),
+                suppressed_count.into(),
+            );
+        }
+
+        let entry = json!({
+            "timestamp": timestamp,
+            "level": metadata.level().as_str(),
+            "target": metadata.target(),
+            "filename": metadata.file(),
+            "line_number": metadata.line(),
+            "message": message,
+            "component": component,
+            "fields": visitor.fields,
+            "context": context,
+            "spans": spans,
+        });
+        writeln!(writer, "{entry}")
+    }
+}
+
+#[derive(Debug, Default)]
+struct FieldsVisitor {
+    fields: Map<String, Value>,
+}
+
+impl FieldsVisitor {
+    fn insert(&mut self, field: &Field, value: impl Into<Value>) {
+        self.fields.insert(field.name().to_owned(), value.into());
+    }
+}
+
+impl Visit for FieldsVisitor {
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.insert(field, value);
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.insert(field, value);
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.insert(field, value);
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.insert(field, value);
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.insert(field, value);
+    }
+
+    fn record_error(&mut self, field: &Field, value: &(dyn std::error::Error + 'static)) {
+        self.insert(field, value.to_string());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.insert(field, format!("{value:?}"));
+    }
+}
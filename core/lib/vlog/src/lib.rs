@@ -1,19 +1,59 @@
 //! This module contains the observability subsystem.
 //! It is responsible for providing a centralized interface for consistent observability configuration.
 
-use std::{backtrace::Backtrace, borrow::Cow, panic::PanicInfo, str::FromStr};
+use std::{
+    backtrace::Backtrace,
+    borrow::Cow,
+    collections::HashSet,
+    io,
+    panic::PanicInfo,
+    path::PathBuf,
+    str::FromStr,
+    sync::{Arc, Mutex, PoisonError},
+    time::Duration,
+};
 
+use anyhow::Context as _;
+use opentelemetry::{trace::TraceError, KeyValue};
+use opentelemetry_sdk::{
+    trace::{Sampler, Tracer},
+    Resource,
+};
+use rolling_file::{BasicRollingFileAppender, RollingConditionBasic};
 // Temporary re-export of `sentry::capture_message` aiming to simplify the transition from `vlog` to using
 // crates directly.
 pub use sentry::{capture_message, Level as AlertLevel};
-use sentry::{types::Dsn, ClientInitGuard};
-use tracing_subscriber::{fmt, layer::SubscriberExt, util::SubscriberInitExt};
+use sentry::{integrations::tracing::EventFilter, types::Dsn, ClientInitGuard};
+use tracing::Level;
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_subscriber::{
+    filter::{filter_fn, LevelFilter},
+    fmt::{self, format::JsonFields},
+    layer::{Layered, SubscriberExt},
+    reload,
+    util::SubscriberInitExt,
+    EnvFilter, Layer, Registry,
+};
+
+pub use crate::breadcrumbs::{add_lifecycle_breadcrumb, MAX_LIFECYCLE_BREADCRUMBS};
+
+mod breadcrumbs;
+mod json;
+mod throttle;
+
+/// Name of spans attaching the component name to logs via the `component` field, e.g.
+/// `tracing::info_span!("component", component = "state_keeper")`. Such spans usually cover the entire lifetime
+/// of a component task, so they are not exported as traces to Sentry or via OpenTelemetry.
+pub const COMPONENT_SPAN: &str = "component";
 
 /// Specifies the format of the logs in stdout.
 #[derive(Debug, Clone, Copy, Default)]
 pub enum LogFormat {
     #[default]
     Plain,
+    /// Single-line JSON objects with a stable set of fields: `timestamp`, `level`, `target`, `filename`,
+    /// `line_number`, `message`, `component`, `fields` (structured fields of the event), `context` (fields
+    /// of the entered spans) and `spans` (names of the entered spans).
     Json,
 }
 
@@ -40,19 +80,461 @@ impl FromStr for LogFormat {
     }
 }
 
+/// Error returned by [`LogReloadHandle`] methods.
+#[derive(Debug)]
+pub struct LogReloadError(String);
+
+impl std::fmt::Display for LogReloadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for LogReloadError {}
+
+/// Error returned if log directives are invalid.
+#[derive(Debug)]
+pub struct LogDirectivesError(String);
+
+impl std::fmt::Display for LogDirectivesError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for LogDirectivesError {}
+
+/// Error parsing [`SentryErrorFilter`].
+#[derive(Debug)]
+pub struct SentryErrorFilterError(String);
+
+impl std::fmt::Display for SentryErrorFilterError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for SentryErrorFilterError {}
+
+/// Per-target filter for log events reported to Sentry as errors. Has `RUST_LOG`-like format: comma-separated
+/// `target=level` directives and an optional default level, e.g. `error,zksync_core::api_server=off`.
+/// For each event, the directive with the longest matching target is used. If there is no default level,
+/// it is `off`, i.e., only panics and explicitly captured messages are reported.
+///
+/// Events not reported as errors are recorded as Sentry breadcrumbs (if their level is `info` or higher).
+#[derive(Debug, Clone, PartialEq)]
+pub struct SentryErrorFilter {
+    default: LevelFilter,
+    directives: Vec<(String, LevelFilter)>,
+}
+
+impl Default for SentryErrorFilter {
+    fn default() -> Self {
+        Self {
+            default: LevelFilter::OFF,
+            directives: vec![],
+        }
+    }
+}
+
+impl FromStr for SentryErrorFilter {
+    type Err = SentryErrorFilterError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut filter = Self::default();
+        for directive in s.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+            let (target, level) = match directive.split_once('=') {
+                Some((target, level)) => (Some(target.trim()), level.trim()),
+                None => (None, directive),
+            };
+            let level: LevelFilter = level.parse().map_err(|_| {
+                SentryErrorFilterError(format!("invalid level in directive `{directive}`"))
+            })?;
+            match target {
+                Some(target) => filter.directives.push((target.to_owned(), level)),
+                None => filter.default = level,
+            }
+        }
+        Ok(filter)
+    }
+}
+
+impl SentryErrorFilter {
+    fn level_for(&self, target: &str) -> LevelFilter {
+        let matching_directive = self
+            .directives
+            .iter()
+            .filter(|(prefix, _)| {
+                target
+                    .strip_prefix(prefix.as_str())
+                    .map_or(false, |rest| rest.is_empty() || rest.starts_with("::"))
+            })
+            .max_by_key(|(prefix, _)| prefix.len());
+        matching_directive.map_or(self.default, |(_, level)| *level)
+    }
+
+    fn event_filter(&self, metadata: &tracing::Metadata<'_>) -> EventFilter {
+        let level = *metadata.level();
+        if self.level_for(metadata.target()) >= level {
+            EventFilter::Event
+        } else if level <= Level::INFO {
+            EventFilter::Breadcrumb
+        } else {
+            EventFilter::Ignore
+        }
+    }
+}
+
+/// Error parsing [`LogFileRotation`].
+#[derive(Debug)]
+pub struct LogFileRotationError(String);
+
+impl std::fmt::Display for LogFileRotationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for LogFileRotationError {}
+
+/// Time-based rotation of log files.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LogFileRotation {
+    /// The log file is not rotated based on time.
+    #[default]
+    Never,
+    Hourly,
+    Daily,
+}
+
+impl FromStr for LogFileRotation {
+    type Err = LogFileRotationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "never" => Ok(Self::Never),
+            "hourly" => Ok(Self::Hourly),
+            "daily" => Ok(Self::Daily),
+            _ => Err(LogFileRotationError(format!(
+                "invalid log file rotation `{s}`; expected `never`, `hourly` or `daily`"
+            ))),
+        }
+    }
+}
+
+/// Options for writing logs to a rolling file in addition to stdout. The file is rotated based on time
+/// and / or size; rotated files are stored next to it with numeric suffixes (`.1` being the most recent one).
+#[derive(Debug, Clone)]
+pub struct LogFileOptions {
+    path: PathBuf,
+    rotation: LogFileRotation,
+    max_size_bytes: Option<u64>,
+    max_files: usize,
+}
+
+impl LogFileOptions {
+    /// Creates options for the specified log file path. By default, the file is not rotated.
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            rotation: LogFileRotation::Never,
+            max_size_bytes: None,
+            max_files: 10,
+        }
+    }
+
+    /// Sets time-based rotation of the log file.
+    pub fn with_rotation(mut self, rotation: LogFileRotation) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    /// Rotates the log file once its size exceeds the specified value.
+    pub fn with_max_size(mut self, max_size_bytes: u64) -> Self {
+        self.max_size_bytes = Some(max_size_bytes);
+        self
+    }
+
+    /// Sets the maximum number of rotated files to retain; older files are removed. Default is 10.
+    pub fn with_max_files(mut self, max_files: usize) -> Self {
+        self.max_files = max_files;
+        self
+    }
+
+    fn appender(&self) -> io::Result<BasicRollingFileAppender> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut condition = RollingConditionBasic::new();
+        condition = match self.rotation {
+            LogFileRotation::Never => condition,
+            LogFileRotation::Hourly => condition.hourly(),
+            LogFileRotation::Daily => condition.daily(),
+        };
+        if let Some(max_size_bytes) = self.max_size_bytes {
+            condition = condition.max_size(max_size_bytes);
+        }
+        BasicRollingFileAppender::new(&self.path, condition, self.max_files)
+    }
+}
+
+/// Error returned when configuring [`OpenTelemetryOptions`].
+#[derive(Debug)]
+pub struct OpenTelemetryOptionsError(String);
+
+impl std::fmt::Display for OpenTelemetryOptionsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for OpenTelemetryOptionsError {}
+
+/// Options for exporting traces via the OpenTelemetry protocol (OTLP) over HTTP.
+#[derive(Debug, Clone)]
+pub struct OpenTelemetryOptions {
+    endpoint: String,
+    service_name: String,
+    sampling_ratio: f64,
+    resource_attributes: Vec<(String, String)>,
+}
+
+impl OpenTelemetryOptions {
+    /// Creates options for the specified OTLP/HTTP endpoint (e.g., `http://localhost:4318/v1/traces`).
+    /// `service_name` is used as the `service.name` resource attribute.
+    pub fn new(endpoint: String, service_name: String) -> Self {
+        Self {
+            endpoint,
+            service_name,
+            sampling_ratio: 1.0,
+            resource_attributes: vec![],
+        }
+    }
+
+    /// Sets the fraction of traces to export (from 0.0 to 1.0). Default is 1.0, i.e. all traces are exported.
+    /// Traces continuing a remote trace follow the sampling decision of the parent.
+    pub fn with_sampling_ratio(mut self, ratio: f64) -> Self {
+        self.sampling_ratio = ratio;
+        self
+    }
+
+    /// Adds resource attributes specified as comma-separated `key=value` pairs
+    /// (e.g., `deployment.environment=mainnet,service.instance.id=en-1`).
+    pub fn with_resource_attributes(
+        mut self,
+        attributes: &str,
+    ) -> Result<Self, OpenTelemetryOptionsError> {
+        for pair in attributes
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+        {
+            let (key, value) = pair.split_once('=').ok_or_else(|| {
+                OpenTelemetryOptionsError(format!(
+                    "resource attribute `{pair}` is not a `key=value` pair"
+                ))
+            })?;
+            self.resource_attributes
+                .push((key.trim().to_owned(), value.trim().to_owned()));
+        }
+        Ok(self)
+    }
+
+    fn install_tracer(self) -> Result<Tracer, TraceError> {
+        let mut attributes = vec![KeyValue::new("service.name", self.service_name)];
+        attributes.extend(
+            self.resource_attributes
+                .into_iter()
+                .map(|(key, value)| KeyValue::new(key, value)),
+        );
+        let sampler =
+            Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(self.sampling_ratio)));
+        let trace_config = opentelemetry_sdk::trace::config()
+            .with_sampler(sampler)
+            .with_resource(Resource::new(attributes));
+        let exporter = opentelemetry_otlp::new_exporter()
+            .http()
+            .with_endpoint(self.endpoint);
+
+        opentelemetry_otlp::new_pipeline()
+            .tracing()
+            .with_exporter(exporter)
+            .with_trace_config(trace_config)
+            .install_batch(opentelemetry_sdk::runtime::Tokio)
+    }
+}
+
+type FilteredRegistry = Layered<reload::Layer<EnvFilter, Registry>, Registry>;
+type FmtLayer = Box<dyn Layer<FilteredRegistry> + Send + Sync>;
+
+fn fmt_layer(log_format: LogFormat) -> FmtLayer {
+    match log_format {
+        LogFormat::Plain => fmt::Layer::default()
+            .event_format(throttle::PlainFormat::default())
+            .boxed(),
+        LogFormat::Json => fmt::Layer::default()
+            .event_format(json::JsonFormat)
+            .fmt_fields(JsonFields::new())
+            .boxed(),
+    }
+}
+
+fn split_log_directives(directives: &str) -> impl Iterator<Item = &str> {
+    directives
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+}
+
+/// Returns the part of a log directive identifying what it applies to, e.g. `zksync_core` for `zksync_core=debug`.
+/// The default level directive (e.g., `info`) is identified by an empty string.
+fn log_directive_target(directive: &str) -> &str {
+    match directive.rsplit_once('=') {
+        Some((target, _)) => target,
+        None if directive.parse::<LevelFilter>().is_ok() => "",
+        None => directive,
+    }
+}
+
+/// Merges log directives in the `RUST_LOG` format. Directives from `overrides` replace directives from `base`
+/// with the same target; other directives from both sets are retained.
+fn merge_log_directives(base: &str, overrides: Option<&str>) -> String {
+    let Some(overrides) = overrides else {
+        return base.to_owned();
+    };
+    let overridden_targets: HashSet<_> = split_log_directives(overrides)
+        .map(log_directive_target)
+        .collect();
+    let merged: Vec<_> = split_log_directives(base)
+        .filter(|directive| !overridden_targets.contains(log_directive_target(directive)))
+        .chain(split_log_directives(overrides))
+        .collect();
+    merged.join(",")
+}
+
+/// Log directives the log filter is built from.
+#[derive(Debug, Clone, Default)]
+struct LogDirectives {
+    /// Base directives, initially taken from `RUST_LOG`.
+    rust_log: String,
+    /// Per-target directives overriding [`Self::rust_log`].
+    overrides: Option<String>,
+}
+
+impl LogDirectives {
+    fn filter(&self) -> Result<EnvFilter, LogDirectivesError> {
+        let directives = merge_log_directives(&self.rust_log, self.overrides.as_deref());
+        EnvFilter::try_new(&directives).map_err(|err| {
+            LogDirectivesError(format!("invalid log directives `{directives}`: {err}"))
+        })
+    }
+}
+
+/// Handle allowing to change logging configuration at runtime.
+#[derive(Clone)]
+pub struct LogReloadHandle {
+    filter: reload::Handle<EnvFilter, Registry>,
+    format: reload::Handle<FmtLayer, FilteredRegistry>,
+    directives: Arc<Mutex<LogDirectives>>,
+}
+
+impl std::fmt::Debug for LogReloadHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LogReloadHandle").finish_non_exhaustive()
+    }
+}
+
+impl LogReloadHandle {
+    /// Replaces base log directives (in the `RUST_LOG` format, e.g. `zksync_core=debug,info`).
+    /// Per-target directives set via [`Self::set_log_directives()`] are still applied on top of them.
+    pub fn set_rust_log(&self, rust_log: &str) -> Result<(), LogReloadError> {
+        self.update_directives(|directives| directives.rust_log = rust_log.to_owned())
+    }
+
+    /// Replaces per-target log directives overriding `RUST_LOG`
+    /// (see [`ObservabilityBuilder::with_log_directives()`]). `None` removes the overrides.
+    pub fn set_log_directives(&self, overrides: Option<&str>) -> Result<(), LogReloadError> {
+        self.update_directives(|directives| directives.overrides = overrides.map(str::to_owned))
+    }
+
+    /// Returns per-target log directives currently overriding `RUST_LOG`, if any.
+    pub fn log_directives(&self) -> Option<String> {
+        let directives = self
+            .directives
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        directives.overrides.clone()
+    }
+
+    fn update_directives(
+        &self,
+        update: impl FnOnce(&mut LogDirectives),
+    ) -> Result<(), LogReloadError> {
+        let mut directives = self
+            .directives
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        let mut new_directives = directives.clone();
+        update(&mut new_directives);
+        let filter = new_directives
+            .filter()
+            .map_err(|err| LogReloadError(err.to_string()))?;
+        self.filter
+            .reload(filter)
+            .map_err(|err| LogReloadError(format!("failed reloading log filter: {err}")))?;
+        *directives = new_directives;
+        Ok(())
+    }
+
+    /// Replaces the log format. Note that the panic handler installed for the JSON format
+    /// is not changed.
+    pub fn set_log_format(&self, log_format: LogFormat) -> Result<(), LogReloadError> {
+        self.format
+            .reload(fmt_layer(log_format))
+            .map_err(|err| LogReloadError(format!("failed reloading log format: {err}")))
+    }
+}
+
 /// Builder for the observability subsystem.
 /// Currently capable of configuring logging output and sentry integration.
 #[derive(Debug, Default)]
 pub struct ObservabilityBuilder {
     log_format: LogFormat,
+    log_directives: Option<String>,
     sentry_url: Option<Dsn>,
     sentry_environment: Option<String>,
+    sentry_release: Option<String>,
+    sentry_sample_rate: Option<f32>,
+    sentry_traces_sample_rate: Option<f32>,
+    sentry_error_filter: SentryErrorFilter,
+    opentelemetry: Option<OpenTelemetryOptions>,
+    log_file: Option<LogFileOptions>,
+    log_throttling: Option<(Duration, u32)>,
 }
 
 /// Guard for the observability subsystem.
 /// Releases configured integrations upon being dropped.
 pub struct ObservabilityGuard {
     _sentry_guard: Option<ClientInitGuard>,
+    log_reload_handle: LogReloadHandle,
+    opentelemetry_enabled: bool,
+    _log_file_guard: Option<WorkerGuard>,
+}
+
+impl Drop for ObservabilityGuard {
+    fn drop(&mut self) {
+        if self.opentelemetry_enabled {
+            // Flushes the remaining spans.
+            opentelemetry::global::shutdown_tracer_provider();
+        }
+    }
+}
+
+impl ObservabilityGuard {
+    /// Returns a handle allowing to change logging configuration at runtime.
+    pub fn log_reload_handle(&self) -> LogReloadHandle {
+        self.log_reload_handle.clone()
+    }
 }
 
 impl std::fmt::Debug for ObservabilityGuard {
@@ -74,6 +556,34 @@ impl ObservabilityBuilder {
         self
     }
 
+    /// Sets per-target log directives in the `RUST_LOG` format (e.g., `zksync_state_keeper=debug,zksync_dal=warn`).
+    /// The directives are applied on top of `RUST_LOG`: a directive replaces the `RUST_LOG` directive
+    /// with the same target (or the default level if the directive has no target).
+    /// Returns an error if the directives are invalid.
+    pub fn with_log_directives(mut self, directives: &str) -> Result<Self, LogDirectivesError> {
+        EnvFilter::try_new(directives).map_err(|err| {
+            LogDirectivesError(format!("invalid log directives `{directives}`: {err}"))
+        })?;
+        self.log_directives = Some(directives.to_owned());
+        Ok(self)
+    }
+
+    /// Enables writing logs to a rolling file in addition to stdout. File logs use the log format
+    /// specified on initialization (without ANSI colors) and directives of the shared log filter.
+    pub fn with_log_file(mut self, options: LogFileOptions) -> Self {
+        self.log_file = Some(options);
+        self
+    }
+
+    /// Enables throttling of repetitive warning and error logs. Within each `interval`, at most `burst` identical
+    /// messages (i.e., ones emitted by the same log statement with the same message) are logged; the rest
+    /// are suppressed. The number of messages suppressed during an interval is reported in the `suppressed_count`
+    /// field of the first message logged after the interval. Logs with lower levels are never throttled.
+    pub fn with_log_throttling(mut self, interval: Duration, burst: u32) -> Self {
+        self.log_throttling = Some((interval, burst));
+        self
+    }
+
     /// Enables Sentry integration.
     /// Returns an error if the provided Sentry URL is invalid.
     pub fn with_sentry_url(
@@ -92,29 +602,121 @@ impl ObservabilityBuilder {
         self
     }
 
+    /// Sets the Sentry release, e.g. to tag events with the git hash of the build.
+    /// If not set, the release is derived from the version of this crate.
+    pub fn with_sentry_release(mut self, release: Option<String>) -> Self {
+        self.sentry_release = release;
+        self
+    }
+
+    /// Sets the fraction of error events sent to Sentry (from 0.0 to 1.0). Default is 1.0, i.e. all events are sent.
+    pub fn with_sentry_sample_rate(mut self, sample_rate: Option<f32>) -> Self {
+        self.sentry_sample_rate = sample_rate;
+        self
+    }
+
+    /// Sets the fraction of traces (transactions created from `tracing` spans) sent to Sentry (from 0.0 to 1.0).
+    /// Default is 0.0, i.e. tracing is disabled.
+    pub fn with_sentry_traces_sample_rate(mut self, traces_sample_rate: Option<f32>) -> Self {
+        self.sentry_traces_sample_rate = traces_sample_rate;
+        self
+    }
+
+    /// Sets the filter for log events reported to Sentry as errors. By default, no log events are reported.
+    pub fn with_sentry_error_filter(mut self, filter: SentryErrorFilter) -> Self {
+        self.sentry_error_filter = filter;
+        self
+    }
+
+    /// Enables exporting traces via OpenTelemetry protocol. The exporter requires a Tokio runtime,
+    /// so [`Self::build()`] must be called in the context of one.
+    pub fn with_opentelemetry(mut self, options: OpenTelemetryOptions) -> Self {
+        self.opentelemetry = Some(options);
+        self
+    }
+
     /// Initializes the observability subsystem.
-    pub fn build(self) -> ObservabilityGuard {
-        // Initialize logs.
-        match self.log_format {
-            LogFormat::Plain => {
-                tracing_subscriber::registry()
-                    .with(tracing_subscriber::EnvFilter::from_default_env())
-                    .with(fmt::Layer::default())
-                    .init();
-            }
-            LogFormat::Json => {
-                let timer = tracing_subscriber::fmt::time::UtcTime::rfc_3339();
-                tracing_subscriber::registry()
-                    .with(tracing_subscriber::EnvFilter::from_default_env())
-                    .with(
-                        fmt::Layer::default()
-                            .with_file(true)
-                            .with_line_number(true)
-                            .with_timer(timer)
-                            .json(),
-                    )
-                    .init();
-            }
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the log file cannot be opened or the OTLP exporter cannot be initialized.
+    pub fn build(self) -> anyhow::Result<ObservabilityGuard> {
+        // Initialize logs. Both the filter and the formatting layer are wrapped in reloadable layers,
+        // so that they can be changed at runtime via `LogReloadHandle`.
+        let directives = LogDirectives {
+            rust_log: std::env::var(EnvFilter::DEFAULT_ENV).unwrap_or_default(),
+            overrides: self.log_directives.clone(),
+        };
+        let merged_directives =
+            merge_log_directives(&directives.rust_log, directives.overrides.as_deref());
+        // Invalid directives in `RUST_LOG` are ignored, same as in `EnvFilter::from_default_env()`.
+        let filter = EnvFilter::builder().parse_lossy(merged_directives);
+        let (filter, filter_handle) = reload::Layer::new(filter);
+        let (fmt_layer, format_handle) = reload::Layer::new(fmt_layer(self.log_format));
+        let traces_enabled = self.sentry_traces_sample_rate.unwrap_or(0.0) > 0.0;
+        let sentry_layer = self.sentry_url.as_ref().map(|_| {
+            let error_filter = self.sentry_error_filter.clone();
+            sentry::integrations::tracing::layer()
+                .event_filter(move |metadata| error_filter.event_filter(metadata))
+                .span_filter(move |metadata| {
+                    traces_enabled
+                        && *metadata.level() <= Level::INFO
+                        && metadata.name() != COMPONENT_SPAN
+                })
+        });
+        let mut log_file_guard = None;
+        let file_layer = self
+            .log_file
+            .as_ref()
+            .map(|options| {
+                let appender = options
+                    .appender()
+                    .with_context(|| format!("failed opening log file {:?}", options.path))?;
+                let (writer, guard) = tracing_appender::non_blocking(appender);
+                log_file_guard = Some(guard);
+                let layer = fmt::Layer::default().with_writer(writer).with_ansi(false);
+                anyhow::Ok(match self.log_format {
+                    LogFormat::Plain => {
+                        layer.event_format(throttle::PlainFormat::default()).boxed()
+                    }
+                    LogFormat::Json => layer
+                        .event_format(json::JsonFormat)
+                        .fmt_fields(JsonFields::new())
+                        .boxed(),
+                })
+            })
+            .transpose()?;
+        let opentelemetry_enabled = self.opentelemetry.is_some();
+        let opentelemetry_layer = self
+            .opentelemetry
+            .map(|options| {
+                let tracer = options
+                    .install_tracer()
+                    .context("failed initializing OTLP exporter")?;
+                anyhow::Ok(
+                    tracing_opentelemetry::layer()
+                        .with_tracer(tracer)
+                        .with_filter(filter_fn(|metadata| {
+                            !metadata.is_span() || metadata.name() != COMPONENT_SPAN
+                        })),
+                )
+            })
+            .transpose()?;
+        let throttle_layer = self
+            .log_throttling
+            .map(|(interval, burst)| throttle::ThrottleLayer::new(interval, burst));
+        tracing_subscriber::registry()
+            .with(filter)
+            .with(fmt_layer)
+            .with(file_layer)
+            .with(sentry_layer)
+            .with(opentelemetry_layer)
+            .with(throttle_layer)
+            .init();
+        let log_reload_handle = LogReloadHandle {
+            filter: filter_handle,
+            format: format_handle,
+            directives: Arc::new(Mutex::new(directives)),
         };
 
         // Check whether we need to change the default panic handler.
@@ -131,9 +733,15 @@ impl ObservabilityBuilder {
         // Initialize the Sentry.
         let sentry_guard = if let Some(sentry_url) = self.sentry_url {
             let options = sentry::ClientOptions {
-                release: sentry::release_name!(),
+                release: self
+                    .sentry_release
+                    .map(Cow::from)
+                    .or_else(|| sentry::release_name!()),
                 environment: self.sentry_environment.map(Cow::from),
+                sample_rate: self.sentry_sample_rate.unwrap_or(1.0),
+                traces_sample_rate: self.sentry_traces_sample_rate.unwrap_or(0.0),
                 attach_stacktrace: true,
+                before_send: Some(Arc::new(breadcrumbs::attach_lifecycle_breadcrumbs)),
                 ..Default::default()
             };
 
@@ -142,9 +750,12 @@ impl ObservabilityBuilder {
             None
         };
 
-        ObservabilityGuard {
+        Ok(ObservabilityGuard {
             _sentry_guard: sentry_guard,
-        }
+            log_reload_handle,
+            opentelemetry_enabled,
+            _log_file_guard: log_file_guard,
+        })
     }
 }
 
@@ -0,0 +1,170 @@
+//! Throttling of repetitive log messages.
+
+use std::{
+    cell::Cell,
+    collections::HashMap,
+    fmt::{self, Write as _},
+    sync::{Mutex, PoisonError},
+    time::{Duration, Instant},
+};
+
+use tracing::{
+    callsite::Identifier,
+    field::{Field, Visit},
+    Event, Level, Subscriber,
+};
+use tracing_subscriber::{
+    fmt::{format::Writer, FmtContext, FormatEvent, FormatFields},
+    layer::Context,
+    registry::LookupSpan,
+    Layer,
+};
+
+/// Name of the field with the number of suppressed messages.
+pub(crate) const SUPPRESSED_COUNT_FIELD: &str = "suppressed_count";
+
+/// Maximum number of distinct messages tracked by [`ThrottleLayer`]. If it is exceeded, messages
+/// not seen during the current interval are not throttled.
+const MAX_TRACKED_MESSAGES: usize = 10_000;
+
+thread_local! {
+    /// Number of messages suppressed before the event currently being formatted. Set by [`ThrottleLayer`],
+    /// which is always invoked before formatting layers for the same event on the same thread.
+    static SUPPRESSED_COUNT: Cell<u64> = Cell::new(0);
+}
+
+/// Returns the number of identical messages suppressed before the event currently being formatted.
+pub(crate) fn suppressed_count() -> u64 {
+    SUPPRESSED_COUNT.with(Cell::get)
+}
+
+#[derive(Debug)]
+struct ThrottleWindow {
+    started_at: Instant,
+    emitted: u32,
+    suppressed: u64,
+}
+
+impl ThrottleWindow {
+    fn new(started_at: Instant) -> Self {
+        Self {
+            started_at,
+            emitted: 0,
+            suppressed: 0,
+        }
+    }
+}
+
+/// Layer suppressing identical warning and error messages (i.e., ones emitted by the same log statement
+/// with the same message) exceeding the configured number per interval.
+#[derive(Debug)]
+pub(crate) struct ThrottleLayer {
+    interval: Duration,
+    burst: u32,
+    windows: Mutex<HashMap<(Identifier, String), ThrottleWindow>>,
+}
+
+impl ThrottleLayer {
+    pub fn new(interval: Duration, burst: u32) -> Self {
+        Self {
+            interval,
+            burst,
+            windows: Mutex::default(),
+        }
+    }
+
+    /// Returns `None` if the message should be suppressed, or the number of messages suppressed
+    /// during the previous interval otherwise.
+    fn check(&self, key: (Identifier, String), now: Instant) -> Option<u64> {
+        let mut windows = self.windows.lock().unwrap_or_else(PoisonError::into_inner);
+        if windows.len() >= MAX_TRACKED_MESSAGES && !windows.contains_key(&key) {
+            windows.retain(|_, window| now.duration_since(window.started_at) < self.interval);
+            if windows.len() >= MAX_TRACKED_MESSAGES {
+                return Some(0);
+            }
+        }
+
+        let window = windows
+            .entry(key)
+            .or_insert_with(|| ThrottleWindow::new(now));
+        let mut suppressed = 0;
+        if now.duration_since(window.started_at) >= self.interval {
+            suppressed = window.suppressed;
+            *window = ThrottleWindow::new(now);
+        }
+        if window.emitted < self.burst {
+            window.emitted += 1;
+            Some(suppressed)
+        } else {
+            window.suppressed += 1;
+            None
+        }
+    }
+}
+
+impl<S: Subscriber> Layer<S> for ThrottleLayer {
+    fn event_enabled(&self, event: &Event<'_>, _ctx: Context<'_, S>) -> bool {
+        SUPPRESSED_COUNT.with(|count| count.set(0));
+        let metadata = event.metadata();
+        if *metadata.level() > Level::WARN {
+            return true;
+        }
+
+        let mut visitor = MessageVisitor::default();
+        event.record(&mut visitor);
+        match self.check((metadata.callsite(), visitor.0), Instant::now()) {
+            Some(suppressed) => {
+                SUPPRESSED_COUNT.with(|count| count.set(suppressed));
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct MessageVisitor(String);
+
+impl Visit for MessageVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.0.push_str(value);
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            write!(self.0, "{value:?}").ok();
+        }
+    }
+}
+
+/// Plain log format appending the [`SUPPRESSED_COUNT_FIELD`] to messages emitted after suppression.
+#[derive(Debug, Default)]
+pub(crate) struct PlainFormat(tracing_subscriber::fmt::format::Format);
+
+impl<S, N> FormatEvent<S, N> for PlainFormat
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    N: for<'a> FormatFields<'a> + 'static,
+{
+    fn format_event(
+        &self,
+        ctx: &FmtContext<'_, S, N>,
+        mut writer: Writer<'_>,
+        event: &Event<'_>,
+    ) -> fmt::Result {
+        let suppressed = suppressed_count();
+        if suppressed == 0 {
+            return self.0.format_event(ctx, writer, event);
+        }
+
+        let mut line = String::new();
+        self.0.format_event(ctx, Writer::new(&mut line), event)?;
+        writeln!(
+            writer,
+            "{} {SUPPRESSED_COUNT_FIELD}={suppressed}",
+            line.trim_end()
+        )
+    }
+}
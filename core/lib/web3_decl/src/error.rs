@@ -14,7 +14,10 @@ use std::{
 use jsonrpsee::core::ClientError;
 use pin_project_lite::pin_project;
 use thiserror::Error;
-use zksync_types::{api::SerializationTransactionError, L1BatchNumber, MiniblockNumber};
+use zksync_types::{
+    api::{ExecutionDiagnostics, SerializationTransactionError},
+    L1BatchNumber, MiniblockNumber,
+};
 
 #[derive(Debug, Error)]
 pub enum Web3Error {
@@ -36,10 +39,15 @@ pub enum Web3Error {
     InvalidTransactionData(#[from] zksync_types::ethabi::Error),
     #[error("{0}")]
     SubmitTransactionError(String, Vec<u8>),
+    /// Transaction execution failed; the error is accompanied by structured diagnostics.
+    #[error("{0}")]
+    ExecutionFailed(String, Box<ExecutionDiagnostics>),
     #[error("Failed to serialize transaction: {0}")]
     SerializationError(#[from] SerializationTransactionError),
     #[error("Invalid fee parameters: {0}")]
     InvalidFeeParams(String),
+    #[error("Invalid state override: {0}")]
+    InvalidStateOverride(String),
     #[error("More than four topics in filter")]
     TooManyTopics,
     #[error("Your connection time exceeded the limit")]
@@ -50,10 +58,16 @@ pub enum Web3Error {
     NotImplemented,
     #[error("Query returned more than {0} results. Try with this block range [{1:#x}, {2:#x}].")]
     LogsLimitExceeded(usize, u32, u32),
+    #[error("Query returned more than {0} transaction traces; try with a smaller block range")]
+    TraceLimitExceeded(usize),
     #[error("invalid filter: if blockHash is supplied fromBlock and toBlock must not be")]
     InvalidFilterBlockHash,
     #[error("Tree API is not available")]
     TreeApiUnavailable,
+    #[error("Merkle tree data for L1 batch #{0} is not available yet")]
+    TreeDataUnavailable(L1BatchNumber),
+    #[error("L1 batch proofs are not available")]
+    ProofStoreUnavailable,
 }
 
 /// Client RPC error with additional details: the method name and arguments of the called method.
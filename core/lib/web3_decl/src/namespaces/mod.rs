@@ -4,18 +4,23 @@ pub mod eth;
 pub mod eth_subscribe;
 pub mod net;
 pub mod snapshots;
+pub mod trace;
+pub mod txpool;
 pub mod web3;
 pub mod zks;
+pub mod zks_admin;
 
 #[cfg(feature = "client")]
 pub use self::{
     debug::DebugNamespaceClient, en::EnNamespaceClient, eth::EthNamespaceClient,
-    net::NetNamespaceClient, snapshots::SnapshotsNamespaceServer, web3::Web3NamespaceClient,
-    zks::ZksNamespaceClient,
+    net::NetNamespaceClient, snapshots::SnapshotsNamespaceServer, trace::TraceNamespaceClient,
+    txpool::TxpoolNamespaceClient, web3::Web3NamespaceClient, zks::ZksNamespaceClient,
+    zks_admin::ZksAdminNamespaceClient,
 };
 #[cfg(feature = "server")]
 pub use self::{
     debug::DebugNamespaceServer, en::EnNamespaceServer, eth::EthNamespaceServer,
     eth::EthPubSubServer, net::NetNamespaceServer, snapshots::SnapshotsNamespaceClient,
-    web3::Web3NamespaceServer, zks::ZksNamespaceServer,
+    trace::TraceNamespaceServer, txpool::TxpoolNamespaceServer, web3::Web3NamespaceServer,
+    zks::ZksNamespaceServer, zks_admin::ZksAdminNamespaceServer,
 };
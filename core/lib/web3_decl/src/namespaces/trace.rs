@@ -0,0 +1,31 @@
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use zksync_types::{
+    api::{
+        trace::{LocalizedTrace, TraceFilter},
+        BlockNumber,
+    },
+    H256,
+};
+
+#[cfg_attr(
+    all(feature = "client", feature = "server"),
+    rpc(server, client, namespace = "trace")
+)]
+#[cfg_attr(
+    all(feature = "client", not(feature = "server")),
+    rpc(client, namespace = "trace")
+)]
+#[cfg_attr(
+    all(not(feature = "client"), feature = "server"),
+    rpc(server, namespace = "trace")
+)]
+pub trait TraceNamespace {
+    #[method(name = "block")]
+    async fn trace_block(&self, block: BlockNumber) -> RpcResult<Option<Vec<LocalizedTrace>>>;
+
+    #[method(name = "transaction")]
+    async fn trace_transaction(&self, tx_hash: H256) -> RpcResult<Option<Vec<LocalizedTrace>>>;
+
+    #[method(name = "filter")]
+    async fn trace_filter(&self, filter: TraceFilter) -> RpcResult<Vec<LocalizedTrace>>;
+}
@@ -0,0 +1,22 @@
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use zksync_types::api::{TxpoolContent, TxpoolStatus};
+
+#[cfg_attr(
+    all(feature = "client", feature = "server"),
+    rpc(server, client, namespace = "txpool")
+)]
+#[cfg_attr(
+    all(feature = "client", not(feature = "server")),
+    rpc(client, namespace = "txpool")
+)]
+#[cfg_attr(
+    all(not(feature = "client"), feature = "server"),
+    rpc(server, namespace = "txpool")
+)]
+pub trait TxpoolNamespace {
+    #[method(name = "content")]
+    async fn content(&self) -> RpcResult<TxpoolContent>;
+
+    #[method(name = "status")]
+    async fn status(&self) -> RpcResult<TxpoolStatus>;
+}
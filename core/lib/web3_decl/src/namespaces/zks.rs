@@ -3,11 +3,12 @@ use std::collections::HashMap;
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
 use zksync_types::{
     api::{
-        BlockDetails, BridgeAddresses, L1BatchDetails, L2ToL1LogProof, Proof, ProtocolVersion,
-        TransactionDetails,
+        en::SyncStatus, BlockDetails, BlockId, BridgeAddresses, L1BatchDetails, L1BatchProof,
+        L2ToL1LogProof, PriorityOpInfo, Proof, ProtocolVersion, TransactionDetails,
+        TransactionExecutionInfo,
     },
     fee::Fee,
-    fee_model::FeeParams,
+    fee_model::{BaseTokenConversionRatio, FeeParams},
     transaction_request::CallRequest,
     Address, L1BatchNumber, MiniblockNumber, H256, U256, U64,
 };
@@ -86,6 +87,14 @@ pub trait ZksNamespace {
     #[method(name = "getTransactionDetails")]
     async fn get_transaction_details(&self, hash: H256) -> RpcResult<Option<TransactionDetails>>;
 
+    /// Returns metrics collected by the VM when executing the transaction, such as circuit usage, published
+    /// pubdata and storage access counts. Returns `None` if the transaction is not executed yet.
+    #[method(name = "getTransactionExecutionInfo")]
+    async fn get_transaction_execution_info(
+        &self,
+        hash: H256,
+    ) -> RpcResult<Option<TransactionExecutionInfo>>;
+
     #[method(name = "getRawBlockTransactions")]
     async fn get_raw_block_transactions(
         &self,
@@ -105,12 +114,25 @@ pub trait ZksNamespace {
     #[method(name = "getFeeParams")]
     async fn get_fee_params(&self) -> RpcResult<FeeParams>;
 
+    /// Returns the current conversion ratio between the base token used to pay fees and ETH.
+    /// For chains using ETH as the base token, the ratio is 1:1.
+    #[method(name = "getBaseTokenRatio")]
+    async fn get_base_token_ratio(&self) -> RpcResult<BaseTokenConversionRatio>;
+
     #[method(name = "getProtocolVersion")]
     async fn get_protocol_version(
         &self,
         version_id: Option<u16>,
     ) -> RpcResult<Option<ProtocolVersion>>;
 
+    /// Returns the protocol version that was active at the specified block, i.e. the version
+    /// defining the VM semantics used for calls and traces against this block.
+    #[method(name = "getBlockProtocolVersion")]
+    async fn get_block_protocol_version(
+        &self,
+        block: BlockId,
+    ) -> RpcResult<Option<ProtocolVersion>>;
+
     #[method(name = "getProof")]
     async fn get_proof(
         &self,
@@ -118,4 +140,15 @@ pub trait ZksNamespace {
         keys: Vec<H256>,
         l1_batch_number: L1BatchNumber,
     ) -> RpcResult<Proof>;
+
+    #[method(name = "getBatchProof")]
+    async fn get_batch_proof(&self, batch: L1BatchNumber) -> RpcResult<Option<L1BatchProof>>;
+
+    #[method(name = "getQueuedPriorityOps")]
+    async fn get_queued_priority_ops(&self) -> RpcResult<Vec<PriorityOpInfo>>;
+
+    /// Returns the detailed sync status of an external node, including the progress of its components
+    /// and the reasons of stalled syncing. Not supported by the main node.
+    #[method(name = "syncStatus")]
+    async fn sync_status(&self) -> RpcResult<SyncStatus>;
 }
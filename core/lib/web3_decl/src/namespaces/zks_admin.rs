@@ -0,0 +1,21 @@
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use zksync_types::api::InflightL1Tx;
+
+/// Operator-facing methods in the `zks` namespace. Unlike other `zks` methods, these methods are only available
+/// if the `zks_admin` API namespace is explicitly enabled.
+#[cfg_attr(
+    all(feature = "client", feature = "server"),
+    rpc(server, client, namespace = "zks")
+)]
+#[cfg_attr(
+    all(feature = "client", not(feature = "server")),
+    rpc(client, namespace = "zks")
+)]
+#[cfg_attr(
+    all(not(feature = "client"), feature = "server"),
+    rpc(server, namespace = "zks")
+)]
+pub trait ZksAdminNamespace {
+    #[method(name = "getInflightL1Txs")]
+    async fn get_inflight_l1_txs(&self) -> RpcResult<Vec<InflightL1Tx>>;
+}
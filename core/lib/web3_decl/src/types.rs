@@ -15,7 +15,10 @@ use itertools::unfold;
 use rlp::Rlp;
 use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 pub use zksync_types::{
-    api::{Block, BlockNumber, Log, TransactionReceipt, TransactionRequest},
+    api::{
+        Block, BlockNumber, L1BatchDetails, L1BatchStatusUpdate, Log, PriorityOpInfo,
+        TransactionReceipt, TransactionRequest,
+    },
     vm_trace::{ContractSourceDebugInfo, VmDebugTrace, VmExecutionStep},
     web3::{
         ethabi,
@@ -345,6 +348,9 @@ pub enum PubSubResult {
     Log(Log),
     TxHash(H256),
     Syncing(bool),
+    L1Batch(L1BatchDetails),
+    L1BatchStatus(L1BatchStatusUpdate),
+    PriorityOp(PriorityOpInfo),
 }
 
 #[cfg(test)]
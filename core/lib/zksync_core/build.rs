@@ -9,4 +9,20 @@ fn main() {
     }
     .generate()
     .unwrap();
+
+    // gRPC streaming API. Protobufs are compiled with `protox`, so that `protoc` is not required.
+    const GRPC_PROTO_ROOT: &str = "src/api_server/grpc/proto";
+    println!("cargo:rerun-if-changed={GRPC_PROTO_ROOT}");
+    let descriptors = protox::compile(
+        [format!("{GRPC_PROTO_ROOT}/stream.proto")],
+        [GRPC_PROTO_ROOT],
+    )
+    .unwrap();
+    let service_generator = tonic_build::configure()
+        .build_client(false)
+        .service_generator();
+    prost_build::Config::new()
+        .service_generator(service_generator)
+        .compile_fds(descriptors)
+        .unwrap();
 }
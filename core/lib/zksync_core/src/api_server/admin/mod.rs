@@ -0,0 +1,570 @@
+//! Authenticated admin API allowing node operators to control node components at runtime, e.g. to pause opening
+//! new L1 batches or to drop a transaction from the mempool. These operations would otherwise require restarting
+//! the node or modifying Postgres directly.
+//!
+//! The API is served over HTTP on the loopback interface only, and all requests must provide the configured token
+//! in the `Authorization: Bearer <token>` header.
+
+use std::{
+    fmt,
+    future::Future,
+    net::SocketAddr,
+    pin::Pin,
+    sync::{Arc, Mutex},
+};
+
+use anyhow::Context as _;
+use axum::{
+    extract::{Path, State},
+    http::{header, Request, StatusCode},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    routing, Json, Router,
+};
+use futures::{
+    future::{BoxFuture, Shared},
+    FutureExt,
+};
+use serde::{Deserialize, Serialize};
+use tokio::{sync::watch, task::JoinHandle};
+use zksync_config::configs::api::AdminApiConfig;
+use zksync_dal::ConnectionPool;
+use zksync_types::{
+    api::{InflightL1Tx, OperatorRotation},
+    Address, L1BatchNumber, Nonce, H256,
+};
+
+use crate::{
+    api_server::web3::namespaces::zks_admin::get_inflight_l1_txs,
+    metadata_calculator::TreeConsistencyChecker,
+    state_keeper::{L1BatchPause, MempoolGuard},
+};
+
+#[cfg(test)]
+mod tests;
+
+type TreeCheckerFuture = Shared<BoxFuture<'static, TreeConsistencyChecker>>;
+
+/// Handles to node components controlled via the admin API. Handles for components not run by the node are not set;
+/// the corresponding endpoints respond with the 503 Service Unavailable status.
+#[derive(Clone, Default)]
+pub struct AdminHandles {
+    state_keeper: Option<(L1BatchPause, MempoolGuard)>,
+    tree_checker: Option<TreeCheckerFuture>,
+    log_reload_handle: Option<vlog::LogReloadHandle>,
+}
+
+impl fmt::Debug for AdminHandles {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("AdminHandles")
+            .field("state_keeper", &self.state_keeper.is_some())
+            .field("tree_checker", &self.tree_checker.is_some())
+            .field("log_reload_handle", &self.log_reload_handle.is_some())
+            .finish()
+    }
+}
+
+impl AdminHandles {
+    pub(crate) fn set_state_keeper(&mut self, l1_batch_pause: L1BatchPause, mempool: MempoolGuard) {
+        self.state_keeper = Some((l1_batch_pause, mempool));
+    }
+
+    pub(crate) fn set_tree_checker(
+        &mut self,
+        tree_checker: impl Future<Output = TreeConsistencyChecker> + Send + 'static,
+    ) {
+        self.tree_checker = Some(tree_checker.boxed().shared());
+    }
+
+    pub fn set_log_reload_handle(&mut self, handle: vlog::LogReloadHandle) {
+        self.log_reload_handle = Some(handle);
+    }
+}
+
+#[derive(Debug)]
+enum AdminApiError {
+    Unauthorized,
+    Unavailable(&'static str),
+    BadRequest(String),
+    NotFound(String),
+    Conflict(String),
+    Internal(anyhow::Error),
+}
+
+impl From<anyhow::Error> for AdminApiError {
+    fn from(err: anyhow::Error) -> Self {
+        Self::Internal(err)
+    }
+}
+
+impl IntoResponse for AdminApiError {
+    fn into_response(self) -> Response {
+        let (status, title, detail) = match self {
+            Self::Unauthorized => (
+                StatusCode::UNAUTHORIZED,
+                "Unauthorized",
+                "missing or invalid bearer token".to_owned(),
+            ),
+            Self::Unavailable(detail) => (
+                StatusCode::SERVICE_UNAVAILABLE,
+                "Component unavailable",
+                detail.to_owned(),
+            ),
+            Self::BadRequest(detail) => (StatusCode::BAD_REQUEST, "Bad request", detail),
+            Self::NotFound(detail) => (StatusCode::NOT_FOUND, "Not found", detail),
+            Self::Conflict(detail) => (StatusCode::CONFLICT, "Conflict", detail),
+            Self::Internal(err) => {
+                tracing::error!("Admin API request failed: {err:#}");
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "Internal error",
+                    format!("{err:#}"),
+                )
+            }
+        };
+
+        // Loosely conforms to HTTP Problem Details RFC: <https://datatracker.ietf.org/doc/html/rfc7807>
+        let body = serde_json::json!({
+            "title": title,
+            "detail": detail,
+        });
+        let headers = [(header::CONTENT_TYPE, "application/problem+json")];
+        (status, headers, Json(body)).into_response()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+struct L1BatchPauseStatus {
+    paused: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DroppedTransaction {
+    initiator_address: Address,
+    nonce: Nonce,
+    /// Whether the transaction was removed from the in-memory mempool of the state keeper.
+    removed_from_mempool: bool,
+}
+
+/// Status of the Merkle tree consistency check triggered via the admin API.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+enum TreeCheckStatus {
+    NotStarted,
+    Running,
+    Consistent,
+    Inconsistent {
+        l1_batch_number: L1BatchNumber,
+        reason: String,
+    },
+    Failed {
+        error: String,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct LogDirectives {
+    /// Per-target log directives overriding `RUST_LOG`; `null` removes the overrides.
+    directives: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+struct AdminApiState {
+    handles: AdminHandles,
+    pool: ConnectionPool,
+    tree_check_status: Arc<Mutex<TreeCheckStatus>>,
+}
+
+impl AdminApiState {
+    fn state_keeper(&self) -> Result<&(L1BatchPause, MempoolGuard), AdminApiError> {
+        self.handles
+            .state_keeper
+            .as_ref()
+            .ok_or(AdminApiError::Unavailable(
+                "state keeper is not run by this node",
+            ))
+    }
+
+    fn log_reload_handle(&self) -> Result<&vlog::LogReloadHandle, AdminApiError> {
+        self.handles
+            .log_reload_handle
+            .as_ref()
+            .ok_or(AdminApiError::Unavailable(
+                "reloading logs is not supported by this node",
+            ))
+    }
+
+    async fn get_l1_batch_pause(
+        State(this): State<Self>,
+    ) -> Result<Json<L1BatchPauseStatus>, AdminApiError> {
+        let (pause, _) = this.state_keeper()?;
+        Ok(Json(L1BatchPauseStatus {
+            paused: pause.is_paused(),
+        }))
+    }
+
+    async fn pause_l1_batches(
+        State(this): State<Self>,
+    ) -> Result<Json<L1BatchPauseStatus>, AdminApiError> {
+        let (pause, _) = this.state_keeper()?;
+        if pause.pause() {
+            tracing::info!("Opening new L1 batches is paused via admin API");
+        }
+        Ok(Json(L1BatchPauseStatus { paused: true }))
+    }
+
+    async fn resume_l1_batches(
+        State(this): State<Self>,
+    ) -> Result<Json<L1BatchPauseStatus>, AdminApiError> {
+        let (pause, _) = this.state_keeper()?;
+        if pause.resume() {
+            tracing::info!("Opening new L1 batches is resumed via admin API");
+        }
+        Ok(Json(L1BatchPauseStatus { paused: false }))
+    }
+
+    async fn drop_transaction(
+        State(this): State<Self>,
+        Path(tx_hash): Path<H256>,
+    ) -> Result<Json<DroppedTransaction>, AdminApiError> {
+        let (_, mempool) = this.state_keeper()?;
+        let mut storage = this.pool.access_storage_tagged("admin_api").await?;
+        let (initiator_address, nonce) = storage
+            .transactions_dal()
+            .get_pending_l2_tx_nonce(tx_hash)
+            .await
+            .context("failed getting pending L2 transaction")?
+            .ok_or_else(|| {
+                AdminApiError::NotFound(format!("pending L2 transaction {tx_hash:?} not found"))
+            })?;
+
+        // The transaction is removed from the mempool first, so that the state keeper cannot pick it up afterwards.
+        // If the transaction isn't in the mempool, it's only removed from Postgres if it isn't loaded into the mempool,
+        // since otherwise it may be executed by the state keeper at this moment.
+        let removed_from_mempool = mempool.remove_l2_transaction(initiator_address, nonce, tx_hash);
+        let removed = storage
+            .transactions_dal()
+            .remove_pending_l2_tx(tx_hash, removed_from_mempool)
+            .await
+            .context("failed removing pending L2 transaction")?;
+        if !removed {
+            return Err(AdminApiError::Conflict(format!(
+                "L2 transaction {tx_hash:?} is being processed by the state keeper"
+            )));
+        }
+
+        tracing::info!(
+            "Dropped L2 transaction {tx_hash:?} (initiator: {initiator_address:?}, nonce: {nonce}) via admin API"
+        );
+        Ok(Json(DroppedTransaction {
+            initiator_address,
+            nonce,
+            removed_from_mempool,
+        }))
+    }
+
+    async fn get_tree_check_status(State(this): State<Self>) -> Json<TreeCheckStatus> {
+        Json(this.tree_check_status.lock().unwrap().clone())
+    }
+
+    /// Starts a full consistency check of the Merkle tree in the background. The check traverses the entire tree,
+    /// so it may take a long time; its status can be polled via [`Self::get_tree_check_status()`].
+    async fn start_tree_check(
+        State(this): State<Self>,
+    ) -> Result<(StatusCode, Json<TreeCheckStatus>), AdminApiError> {
+        let tree_checker = this
+            .handles
+            .tree_checker
+            .clone()
+            .ok_or(AdminApiError::Unavailable(
+                "Merkle tree is not run by this node",
+            ))?;
+        let tree_checker = tree_checker
+            .now_or_never()
+            .ok_or(AdminApiError::Unavailable(
+                "Merkle tree is not initialized yet",
+            ))?;
+        {
+            let mut status = this.tree_check_status.lock().unwrap();
+            if *status == TreeCheckStatus::Running {
+                return Err(AdminApiError::Conflict(
+                    "Merkle tree consistency check is already running".to_owned(),
+                ));
+            }
+            *status = TreeCheckStatus::Running;
+        }
+
+        tracing::info!("Starting Merkle tree consistency check requested via admin API");
+        tokio::spawn(async move {
+            let new_status = match tree_checker.check(&this.pool).await {
+                Ok(None) => {
+                    tracing::info!("Merkle tree is consistent");
+                    TreeCheckStatus::Consistent
+                }
+                Ok(Some(inconsistency)) => {
+                    tracing::error!("{inconsistency}");
+                    TreeCheckStatus::Inconsistent {
+                        l1_batch_number: inconsistency.l1_batch_number,
+                        reason: inconsistency.reason,
+                    }
+                }
+                Err(err) => {
+                    tracing::warn!("Merkle tree consistency check failed: {err:#}");
+                    TreeCheckStatus::Failed {
+                        error: format!("{err:#}"),
+                    }
+                }
+            };
+            *this.tree_check_status.lock().unwrap() = new_status;
+        });
+        Ok((StatusCode::ACCEPTED, Json(TreeCheckStatus::Running)))
+    }
+
+    async fn get_log_directives(
+        State(this): State<Self>,
+    ) -> Result<Json<LogDirectives>, AdminApiError> {
+        let handle = this.log_reload_handle()?;
+        Ok(Json(LogDirectives {
+            directives: handle.log_directives(),
+        }))
+    }
+
+    async fn set_log_directives(
+        State(this): State<Self>,
+        Json(request): Json<LogDirectives>,
+    ) -> Result<Json<LogDirectives>, AdminApiError> {
+        let handle = this.log_reload_handle()?;
+        handle
+            .set_log_directives(request.directives.as_deref())
+            .map_err(|err| AdminApiError::BadRequest(err.to_string()))?;
+        tracing::info!(
+            "Updated log directives via admin API: {:?}",
+            request.directives
+        );
+        Ok(Json(request))
+    }
+
+    async fn get_inflight_l1_txs(
+        State(this): State<Self>,
+    ) -> Result<Json<Vec<InflightL1Tx>>, AdminApiError> {
+        let mut storage = this.pool.access_storage_tagged("admin_api").await?;
+        let txs = get_inflight_l1_txs(&mut storage)
+            .await
+            .context("failed getting in-flight L1 transactions")?;
+        Ok(Json(txs))
+    }
+
+    async fn get_operator_rotation(
+        State(this): State<Self>,
+    ) -> Result<Json<OperatorRotation>, AdminApiError> {
+        let mut storage = this.pool.access_storage_tagged("admin_api").await?;
+        let rotation = storage
+            .eth_sender_dal()
+            .get_latest_operator_rotation()
+            .await
+            .context("failed getting latest operator rotation")?
+            .ok_or_else(|| AdminApiError::NotFound("no operator rotations".to_owned()))?;
+        Ok(Json(rotation.into()))
+    }
+
+    /// Requests rotating the operator to the other configured operator account (i.e., from the primary account
+    /// to the secondary one, or back). The Ethereum sender stops creating new L1 transactions, waits until all
+    /// transactions sent by the current operator are confirmed, and then switches to the new account.
+    /// If a rotation is already pending, returns it.
+    async fn start_operator_rotation(
+        State(this): State<Self>,
+    ) -> Result<(StatusCode, Json<OperatorRotation>), AdminApiError> {
+        let mut storage = this.pool.access_storage_tagged("admin_api").await?;
+        let mut dal = storage.eth_sender_dal();
+        let new_rotation = dal
+            .start_operator_rotation()
+            .await
+            .context("failed starting operator rotation")?;
+        let rotation = if let Some(rotation) = new_rotation {
+            tracing::info!("Requested operator rotation #{} via admin API", rotation.id);
+            rotation
+        } else {
+            // Another rotation is pending, so we return it.
+            dal.get_latest_operator_rotation()
+                .await
+                .context("failed getting latest operator rotation")?
+                .ok_or_else(|| {
+                    AdminApiError::Conflict("pending operator rotation was cancelled".to_owned())
+                })?
+        };
+        Ok((StatusCode::ACCEPTED, Json(rotation.into())))
+    }
+
+    /// Cancels the pending operator rotation. Responds with 404 if there is no pending rotation.
+    async fn cancel_operator_rotation(
+        State(this): State<Self>,
+    ) -> Result<StatusCode, AdminApiError> {
+        let mut storage = this.pool.access_storage_tagged("admin_api").await?;
+        let cancelled = storage
+            .eth_sender_dal()
+            .cancel_operator_rotation()
+            .await
+            .context("failed cancelling operator rotation")?;
+        if !cancelled {
+            return Err(AdminApiError::NotFound(
+                "no pending operator rotation".to_owned(),
+            ));
+        }
+        tracing::info!("Cancelled pending operator rotation via admin API");
+        Ok(StatusCode::NO_CONTENT)
+    }
+}
+
+/// Compares the provided token with the expected one in constant time.
+fn tokens_match(provided: &[u8], expected: &[u8]) -> bool {
+    provided.len() == expected.len()
+        && provided
+            .iter()
+            .zip(expected)
+            .fold(0, |acc, (x, y)| acc | (x ^ y))
+            == 0
+}
+
+async fn authorize<B>(
+    State(auth_token): State<Arc<str>>,
+    request: Request<B>,
+    next: Next<B>,
+) -> Response {
+    let provided_token = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    let is_authorized = provided_token.map_or(false, |token| {
+        tokens_match(token.as_bytes(), auth_token.as_bytes())
+    });
+    if is_authorized {
+        next.run(request).await
+    } else {
+        AdminApiError::Unauthorized.into_response()
+    }
+}
+
+/// `axum`-powered REST server for the admin API.
+#[must_use = "Server must be `run()`"]
+struct AdminApiServer {
+    local_addr: SocketAddr,
+    server_future: Pin<Box<dyn Future<Output = anyhow::Result<()>> + Send>>,
+}
+
+impl fmt::Debug for AdminApiServer {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("AdminApiServer")
+            .field("local_addr", &self.local_addr)
+            .finish_non_exhaustive()
+    }
+}
+
+impl AdminApiServer {
+    fn new(
+        bind_address: &SocketAddr,
+        auth_token: &str,
+        handles: AdminHandles,
+        pool: ConnectionPool,
+        mut stop_receiver: watch::Receiver<bool>,
+    ) -> anyhow::Result<Self> {
+        tracing::debug!("Starting admin API server on {bind_address}");
+
+        let state = AdminApiState {
+            handles,
+            pool,
+            tree_check_status: Arc::new(Mutex::new(TreeCheckStatus::NotStarted)),
+        };
+        let app = Router::new()
+            .route(
+                "/state_keeper/pause",
+                routing::get(AdminApiState::get_l1_batch_pause)
+                    .post(AdminApiState::pause_l1_batches)
+                    .delete(AdminApiState::resume_l1_batches),
+            )
+            .route(
+                "/mempool/:tx_hash",
+                routing::delete(AdminApiState::drop_transaction),
+            )
+            .route(
+                "/tree/consistency_check",
+                routing::get(AdminApiState::get_tree_check_status)
+                    .post(AdminApiState::start_tree_check),
+            )
+            .route(
+                "/logs/directives",
+                routing::get(AdminApiState::get_log_directives)
+                    .put(AdminApiState::set_log_directives),
+            )
+            .route(
+                "/eth_sender/inflight_txs",
+                routing::get(AdminApiState::get_inflight_l1_txs),
+            )
+            .route(
+                "/eth_sender/operator_rotation",
+                routing::get(AdminApiState::get_operator_rotation)
+                    .post(AdminApiState::start_operator_rotation)
+                    .delete(AdminApiState::cancel_operator_rotation),
+            )
+            .route_layer(middleware::from_fn_with_state(
+                Arc::<str>::from(auth_token),
+                authorize,
+            ))
+            .with_state(state);
+
+        let server = axum::Server::try_bind(bind_address)
+            .with_context(|| format!("Failed binding admin API server to {bind_address}"))?
+            .serve(app.into_make_service());
+        let local_addr = server.local_addr();
+        let server_future = async move {
+            server
+                .with_graceful_shutdown(async move {
+                    if stop_receiver.changed().await.is_err() {
+                        tracing::warn!(
+                            "Stop signal sender for admin API server was dropped without sending a signal"
+                        );
+                    }
+                    tracing::info!("Stop signal received, admin API server is shutting down");
+                })
+                .await
+                .context("Admin API server failed")?;
+
+            tracing::info!("Admin API server shut down");
+            Ok(())
+        };
+
+        Ok(Self {
+            local_addr,
+            server_future: Box::pin(server_future),
+        })
+    }
+
+    async fn run(self) -> anyhow::Result<()> {
+        self.server_future.await
+    }
+}
+
+/// Spawns the admin API server. Fails if the auth token is not configured.
+pub fn spawn_server(
+    config: &AdminApiConfig,
+    handles: AdminHandles,
+    pool: ConnectionPool,
+    stop_receiver: watch::Receiver<bool>,
+) -> anyhow::Result<JoinHandle<anyhow::Result<()>>> {
+    let auth_token = config
+        .auth_token
+        .as_deref()
+        .context("`auth_token` must be set to run the admin API")?;
+    tracing::info!("Admin API components: {handles:?}");
+    let server = AdminApiServer::new(
+        &config.bind_addr(),
+        auth_token,
+        handles,
+        pool,
+        stop_receiver,
+    )?;
+    Ok(tokio::spawn(server.run()))
+}
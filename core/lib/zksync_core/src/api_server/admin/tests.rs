@@ -0,0 +1,335 @@
+//! Tests for the admin API server.
+
+use std::{collections::HashMap, net::Ipv4Addr};
+
+use zksync_types::{fee::TransactionExecutionMetrics, PriorityOpId};
+
+use super::*;
+use crate::utils::testonly::create_l2_transaction;
+
+const AUTH_TOKEN: &str = "test-token";
+
+#[derive(Debug)]
+struct TestServer {
+    client: reqwest::Client,
+    base_url: String,
+    stop_sender: watch::Sender<bool>,
+    server_task: JoinHandle<anyhow::Result<()>>,
+}
+
+impl TestServer {
+    fn spawn(handles: AdminHandles, pool: ConnectionPool) -> Self {
+        let (stop_sender, stop_receiver) = watch::channel(false);
+        let bind_address = (Ipv4Addr::LOCALHOST, 0).into();
+        let server =
+            AdminApiServer::new(&bind_address, AUTH_TOKEN, handles, pool, stop_receiver).unwrap();
+        let base_url = format!("http://{}", server.local_addr);
+        Self {
+            client: reqwest::Client::new(),
+            base_url,
+            stop_sender,
+            server_task: tokio::spawn(server.run()),
+        }
+    }
+
+    fn request(&self, method: reqwest::Method, path: &str) -> reqwest::RequestBuilder {
+        self.client
+            .request(method, format!("{}{path}", self.base_url))
+            .bearer_auth(AUTH_TOKEN)
+    }
+
+    async fn stop(self) {
+        self.stop_sender.send_replace(true);
+        self.server_task.await.unwrap().unwrap();
+    }
+}
+
+fn state_keeper_handles() -> (AdminHandles, L1BatchPause, MempoolGuard) {
+    let pause = L1BatchPause::default();
+    let mempool = MempoolGuard::new(PriorityOpId(0), 100);
+    let mut handles = AdminHandles::default();
+    handles.set_state_keeper(pause.clone(), mempool.clone());
+    (handles, pause, mempool)
+}
+
+#[tokio::test]
+async fn unauthorized_requests_are_rejected() {
+    let pool = ConnectionPool::test_pool().await;
+    let server = TestServer::spawn(AdminHandles::default(), pool);
+    let url = format!("{}/state_keeper/pause", server.base_url);
+
+    let response = server.client.get(&url).send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    let response = server
+        .client
+        .get(&url)
+        .bearer_auth("wrong-token")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(
+        response.headers()[header::CONTENT_TYPE],
+        "application/problem+json"
+    );
+
+    server.stop().await;
+}
+
+#[tokio::test]
+async fn pausing_and_resuming_l1_batches() {
+    let pool = ConnectionPool::test_pool().await;
+    let (handles, pause, _) = state_keeper_handles();
+    let server = TestServer::spawn(handles, pool);
+
+    let status: L1BatchPauseStatus = server
+        .request(reqwest::Method::POST, "/state_keeper/pause")
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert!(status.paused);
+    assert!(pause.is_paused());
+
+    let status: L1BatchPauseStatus = server
+        .request(reqwest::Method::GET, "/state_keeper/pause")
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert!(status.paused);
+
+    let status: L1BatchPauseStatus = server
+        .request(reqwest::Method::DELETE, "/state_keeper/pause")
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert!(!status.paused);
+    assert!(!pause.is_paused());
+
+    server.stop().await;
+}
+
+#[tokio::test]
+async fn dropping_transaction_from_mempool() {
+    let pool = ConnectionPool::test_pool().await;
+    let tx = create_l2_transaction(10, 100);
+    let tx_hash = tx.hash();
+    let initiator_address = tx.initiator_account();
+    let mut storage = pool.access_storage().await.unwrap();
+    storage
+        .transactions_dal()
+        .insert_transaction_l2(tx, TransactionExecutionMetrics::default())
+        .await;
+    let transactions = storage
+        .transactions_dal()
+        .sync_mempool(&[], &[], 0, 0, 10)
+        .await
+        .unwrap();
+    assert_eq!(transactions.len(), 1);
+    drop(storage);
+
+    let (handles, _, mut mempool) = state_keeper_handles();
+    mempool.insert(transactions, HashMap::from([(initiator_address, Nonce(0))]));
+    let server = TestServer::spawn(handles, pool.clone());
+
+    let path = format!("/mempool/{tx_hash:?}");
+    let response = server
+        .request(reqwest::Method::DELETE, &path)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let dropped: DroppedTransaction = response.json().await.unwrap();
+    assert_eq!(
+        dropped,
+        DroppedTransaction {
+            initiator_address,
+            nonce: Nonce(0),
+            removed_from_mempool: true,
+        }
+    );
+    assert_eq!(mempool.stats().l2_transaction_count, 0);
+
+    let mut storage = pool.access_storage().await.unwrap();
+    let nonce = storage
+        .transactions_dal()
+        .get_pending_l2_tx_nonce(tx_hash)
+        .await
+        .unwrap();
+    assert_eq!(nonce, None);
+
+    let response = server
+        .request(reqwest::Method::DELETE, &path)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    server.stop().await;
+}
+
+#[tokio::test]
+async fn unavailable_components() {
+    let pool = ConnectionPool::test_pool().await;
+    let server = TestServer::spawn(AdminHandles::default(), pool);
+
+    let response = server
+        .request(reqwest::Method::POST, "/state_keeper/pause")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    let response = server
+        .request(reqwest::Method::POST, "/tree/consistency_check")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    let response = server
+        .request(reqwest::Method::GET, "/logs/directives")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+    let status: TreeCheckStatus = server
+        .request(reqwest::Method::GET, "/tree/consistency_check")
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(status, TreeCheckStatus::NotStarted);
+
+    server.stop().await;
+}
+
+#[tokio::test]
+async fn getting_inflight_l1_txs() {
+    let pool = ConnectionPool::test_pool().await;
+    let server = TestServer::spawn(AdminHandles::default(), pool);
+
+    let txs: Vec<InflightL1Tx> = server
+        .request(reqwest::Method::GET, "/eth_sender/inflight_txs")
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert!(txs.is_empty());
+
+    server.stop().await;
+}
+
+#[tokio::test]
+async fn rotating_operator() {
+    let pool = ConnectionPool::test_pool().await;
+    let server = TestServer::spawn(AdminHandles::default(), pool.clone());
+    let path = "/eth_sender/operator_rotation";
+
+    let response = server
+        .request(reqwest::Method::GET, path)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    let response = server
+        .request(reqwest::Method::DELETE, path)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let response = server
+        .request(reqwest::Method::POST, path)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::ACCEPTED);
+    let rotation: OperatorRotation = response.json().await.unwrap();
+    assert_eq!(rotation.completed_at, None);
+    assert_eq!(rotation.new_operator_address, None);
+    // Starting a rotation again should return the pending rotation.
+    let pending_rotation: OperatorRotation = server
+        .request(reqwest::Method::POST, path)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(pending_rotation, rotation);
+    let latest_rotation: OperatorRotation = server
+        .request(reqwest::Method::GET, path)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(latest_rotation, rotation);
+
+    let response = server
+        .request(reqwest::Method::DELETE, path)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    let response = server
+        .request(reqwest::Method::GET, path)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let rotation: OperatorRotation = server
+        .request(reqwest::Method::POST, path)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let new_operator_address = Address::repeat_byte(0x22);
+    let mut storage = pool.access_storage().await.unwrap();
+    storage
+        .eth_sender_dal()
+        .complete_operator_rotation(rotation.id, new_operator_address)
+        .await
+        .unwrap();
+    drop(storage);
+
+    let completed_rotation: OperatorRotation = server
+        .request(reqwest::Method::GET, path)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(completed_rotation.id, rotation.id);
+    assert!(completed_rotation.completed_at.is_some());
+    assert_eq!(
+        completed_rotation.new_operator_address,
+        Some(new_operator_address)
+    );
+    // A completed rotation cannot be cancelled.
+    let response = server
+        .request(reqwest::Method::DELETE, path)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    server.stop().await;
+}
@@ -3,7 +3,10 @@ use actix_web::{
     HttpResponse, Result as ActixResult,
 };
 use serde::Serialize;
-use zksync_types::{contract_verification_api::VerificationIncomingRequest, Address};
+use zksync_types::{
+    contract_verification_api::{SourceCodeData, VerificationIncomingRequest},
+    Address,
+};
 
 use super::{api_decl::RestApi, metrics::METRICS};
 
@@ -19,6 +22,21 @@ impl RestApi {
         if query.source_code_data.compiler_type() != query.compiler_versions.compiler_type() {
             return Err(HttpResponse::BadRequest().body("incorrect compiler versions"));
         }
+        if let SourceCodeData::StandardJsonInput(input) = &query.source_code_data {
+            let has_language = matches!(
+                input.get("language").and_then(serde_json::Value::as_str),
+                Some("Solidity" | "Yul")
+            );
+            let has_sources = input
+                .get("sources")
+                .and_then(serde_json::Value::as_object)
+                .map_or(false, |sources| !sources.is_empty());
+            if !has_language || !has_sources {
+                return Err(HttpResponse::BadRequest().body(
+                    "standard JSON input must specify `language` (Solidity or Yul) and non-empty `sources`",
+                ));
+            }
+        }
 
         Ok(())
     }
@@ -11,8 +11,8 @@ use std::time::{Duration, Instant};
 use anyhow::Context as _;
 use multivm::{
     interface::{L1BatchEnv, L2BlockEnv, SystemEnv, VmInterface},
-    utils::adjust_pubdata_price_for_tx,
-    vm_latest::{constants::BLOCK_GAS_LIMIT, HistoryDisabled},
+    utils::{adjust_pubdata_price_for_tx, get_batch_gas_limit},
+    vm_latest::HistoryDisabled,
     VmInstance,
 };
 use tokio::runtime::Handle;
@@ -24,7 +24,7 @@ use zksync_system_constants::{
 };
 use zksync_types::{
     api,
-    block::{pack_block_info, unpack_block_info, MiniblockHasher},
+    block::{pack_block_info, unpack_block_info, MiniblockHasher, MiniblockHeader},
     fee_model::BatchFeeInput,
     get_nonce_key,
     utils::{decompose_full_nonce, nonces_to_full_nonce, storage_key_for_eth_balance},
@@ -34,11 +34,13 @@ use zksync_types::{
 use zksync_utils::{h256_to_u256, time::seconds_since_epoch, u256_to_h256};
 
 use super::{
+    storage::StorageWithOverrides,
     vm_metrics::{self, SandboxStage, SANDBOX_METRICS},
     BlockArgs, TxExecutionArgs, TxSharedArgs, VmPermit,
 };
 
-type BoxedVm<'a> = Box<VmInstance<StorageView<PostgresStorage<'a>>, HistoryDisabled>>;
+type SandboxStorage<'a> = StorageView<StorageWithOverrides<PostgresStorage<'a>>>;
+type BoxedVm<'a> = Box<VmInstance<SandboxStorage<'a>, HistoryDisabled>>;
 
 #[derive(Debug)]
 struct Sandbox<'a> {
@@ -46,7 +48,7 @@ struct Sandbox<'a> {
     l1_batch_env: L1BatchEnv,
     execution_args: &'a TxExecutionArgs,
     l2_block_info_to_reset: Option<StoredL2BlockInfo>,
-    storage_view: StorageView<PostgresStorage<'a>>,
+    storage_view: SandboxStorage<'a>,
 }
 
 impl<'a> Sandbox<'a> {
@@ -90,6 +92,12 @@ impl<'a> Sandbox<'a> {
         .context("cannot create `PostgresStorage`")?
         .with_caches(shared_args.caches.clone());
 
+        let mut storage =
+            StorageWithOverrides::new(storage, execution_args.state_override.as_ref());
+        if let Some(cache) = &execution_args.read_cache {
+            storage =
+                storage.with_read_cache(cache.clone(), resolved_block_info.state_l2_block_number);
+        }
         let storage_view = StorageView::new(storage);
         let (system_env, l1_batch_env) = Self::prepare_env(
             shared_args,
@@ -121,6 +129,17 @@ impl<'a> Sandbox<'a> {
         .await
         .context("failed reading L2 block info")?;
 
+        if let Some(timestamp) = resolved_block_info.replayed_l2_block_timestamp {
+            // The state corresponds to the miniblock preceding the replayed one, so the replayed miniblock is started anew.
+            let next_l2_block_info = L2BlockEnv {
+                number: current_l2_block_info.l2_block_number + 1,
+                timestamp,
+                prev_block_hash: current_l2_block_info.l2_block_hash,
+                max_virtual_blocks_to_create: 1,
+            };
+            return Ok((next_l2_block_info, None));
+        }
+
         let next_l2_block_info = if is_pending_block {
             L2BlockEnv {
                 number: current_l2_block_info.l2_block_number + 1,
@@ -232,12 +251,14 @@ impl<'a> Sandbox<'a> {
         let fee_input = resolved_block_info
             .historical_fee_input
             .unwrap_or(fee_input);
+        // System contracts and batch params must match the VM version that was active at the block.
+        let protocol_version = resolved_block_info.protocol_version;
         let system_env = SystemEnv {
             zk_porter_available: ZKPORTER_IS_AVAILABLE,
-            version: resolved_block_info.protocol_version,
+            version: protocol_version,
             base_system_smart_contracts: base_system_contracts
-                .get_by_protocol_version(resolved_block_info.protocol_version),
-            gas_limit: BLOCK_GAS_LIMIT,
+                .get_by_protocol_version(protocol_version),
+            gas_limit: get_batch_gas_limit(protocol_version.into()),
             execution_mode: execution_args.execution_mode,
             default_validation_computational_gas_limit: validation_computational_gas_limit,
             chain_id,
@@ -259,7 +280,7 @@ impl<'a> Sandbox<'a> {
         mut self,
         tx: &Transaction,
         adjust_pubdata_price: bool,
-    ) -> (BoxedVm<'a>, StoragePtr<StorageView<PostgresStorage<'a>>>) {
+    ) -> (BoxedVm<'a>, StoragePtr<SandboxStorage<'a>>) {
         self.setup_storage_view(tx);
         let protocol_version = self.system_env.version;
         if adjust_pubdata_price {
@@ -293,10 +314,7 @@ pub(super) fn apply_vm_in_sandbox<T>(
     connection_pool: &ConnectionPool,
     tx: Transaction,
     block_args: BlockArgs,
-    apply: impl FnOnce(
-        &mut VmInstance<StorageView<PostgresStorage<'_>>, HistoryDisabled>,
-        Transaction,
-    ) -> T,
+    apply: impl FnOnce(&mut VmInstance<SandboxStorage<'_>, HistoryDisabled>, Transaction) -> T,
 ) -> anyhow::Result<T> {
     let stage_started_at = Instant::now();
     let span = tracing::debug_span!("initialization").entered();
@@ -405,6 +423,8 @@ struct ResolvedBlockInfo {
     l1_batch_timestamp: u64,
     protocol_version: ProtocolVersionId,
     historical_fee_input: Option<BatchFeeInput>,
+    /// Timestamp of the replayed miniblock; `None` unless the block args are created for replay.
+    replayed_l2_block_timestamp: Option<u64>,
 }
 
 impl BlockArgs {
@@ -424,11 +444,43 @@ impl BlockArgs {
         )
     }
 
+    /// Returns the protocol version that was active at the block. Calls and traces against the block
+    /// are executed using the VM version and system contracts corresponding to this protocol version.
+    pub async fn resolve_protocol_version(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<ProtocolVersionId> {
+        let miniblock_header = if self.is_pending_miniblock() {
+            connection
+                .blocks_dal()
+                .get_last_sealed_miniblock_header()
+                .await
+                .context("failed getting sealed miniblock header")?
+                .context("no miniblocks in storage")?
+        } else {
+            connection
+                .blocks_dal()
+                .get_miniblock_header(self.resolved_block_number)
+                .await
+                .context("failed getting header of resolved miniblock")?
+                .context("resolved miniblock disappeared from storage")?
+        };
+        Ok(Self::header_protocol_version(&miniblock_header))
+    }
+
+    fn header_protocol_version(header: &MiniblockHeader) -> ProtocolVersionId {
+        // Blocks without version specified are considered to be of `Version9`.
+        // TODO: remove `unwrap_or` when protocol version ID will be assigned for each block.
+        header
+            .protocol_version
+            .unwrap_or(ProtocolVersionId::last_potentially_undefined())
+    }
+
     async fn resolve_block_info(
         &self,
         connection: &mut StorageProcessor<'_>,
     ) -> anyhow::Result<ResolvedBlockInfo> {
-        let (state_l2_block_number, vm_l1_batch_number, l1_batch_timestamp);
+        let (mut state_l2_block_number, vm_l1_batch_number, l1_batch_timestamp);
 
         let miniblock_header = if self.is_pending_miniblock() {
             vm_l1_batch_number = connection
@@ -468,31 +520,48 @@ impl BlockArgs {
                 .context("resolved miniblock disappeared from storage")?
         };
 
-        let historical_fee_input = if !self.is_estimate_like() {
-            let miniblock_header = connection
-                .blocks_dal()
-                .get_miniblock_header(self.resolved_block_number)
+        // For non-pending blocks, `miniblock_header` is the header of the resolved miniblock.
+        let historical_fee_input =
+            (!self.is_estimate_like()).then_some(miniblock_header.batch_fee_input);
+        let protocol_version = Self::header_protocol_version(&miniblock_header);
+
+        let mut state_l2_block_hash = miniblock_header.hash;
+        let mut replayed_l2_block_timestamp = None;
+        if self.is_replay {
+            // Transactions are replayed on top of the state at the end of the previous miniblock.
+            state_l2_block_number -= 1;
+            let prev_miniblock_hash = connection
+                .blocks_web3_dal()
+                .get_miniblock_hash(state_l2_block_number)
                 .await
-                .context("failed getting resolved miniblock header")?
-                .context("resolved miniblock is not in storage")?;
-            Some(miniblock_header.batch_fee_input)
-        } else {
-            None
-        };
-
-        // Blocks without version specified are considered to be of `Version9`.
-        // TODO: remove `unwrap_or` when protocol version ID will be assigned for each block.
-        let protocol_version = miniblock_header
-            .protocol_version
-            .unwrap_or(ProtocolVersionId::last_potentially_undefined());
+                .context("failed getting hash of the miniblock preceding the replayed one")?;
+            state_l2_block_hash = if let Some(hash) = prev_miniblock_hash {
+                hash
+            } else {
+                // The previous miniblock may be absent if the node was recovered from a snapshot.
+                let snapshot_recovery = connection
+                    .snapshot_recovery_dal()
+                    .get_applied_snapshot_status()
+                    .await
+                    .context("failed getting snapshot recovery status")?
+                    .filter(|recovery| recovery.miniblock_number == state_l2_block_number);
+                snapshot_recovery
+                    .with_context(|| {
+                        format!("miniblock #{state_l2_block_number} preceding the replayed one is not present in storage")
+                    })?
+                    .miniblock_hash
+            };
+            replayed_l2_block_timestamp = Some(miniblock_header.timestamp);
+        }
 
         Ok(ResolvedBlockInfo {
             state_l2_block_number,
-            state_l2_block_hash: miniblock_header.hash,
+            state_l2_block_hash,
             vm_l1_batch_number,
             l1_batch_timestamp,
             protocol_version,
             historical_fee_input,
+            replayed_l2_block_timestamp,
         })
     }
 }
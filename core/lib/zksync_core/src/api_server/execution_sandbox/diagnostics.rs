@@ -0,0 +1,183 @@
+//! Structured diagnostics for transactions failed in the sandbox.
+
+use multivm::interface::{ExecutionResult, VmExecutionResultAndLogs};
+use zksync_types::{
+    api::{DiagnosticCallFrame, ExecutionDiagnostics},
+    vm_trace::Call,
+    Address,
+};
+
+/// Checks whether diagnostics should be collected for the execution result. Reverts with non-empty revert data
+/// are not diagnosed: the revert data is returned to the caller as is, so that it can be decoded according
+/// to the contract ABI.
+pub(crate) fn needs_diagnostics(result: &ExecutionResult) -> bool {
+    match result {
+        ExecutionResult::Success { .. } => false,
+        ExecutionResult::Revert { output } => output.encoded_data().is_empty(),
+        ExecutionResult::Halt { .. } => true,
+    }
+}
+
+/// Collects diagnostics for a failed execution based on its result and call traces.
+pub(crate) fn collect_diagnostics(
+    result: &VmExecutionResultAndLogs,
+    call_traces: &[Call],
+) -> ExecutionDiagnostics {
+    let statistics = &result.statistics;
+    let mut diagnostics = ExecutionDiagnostics {
+        gas_used: statistics.gas_used,
+        computational_gas_used: statistics.computational_gas_used,
+        pubdata_gas_used: statistics
+            .gas_used
+            .saturating_sub(statistics.computational_gas_used),
+        pubdata_published: statistics.pubdata_published,
+        ..ExecutionDiagnostics::default()
+    };
+    let mut innermost_depths = InnermostDepths::default();
+    for call in call_traces {
+        visit_call(call, 0, &mut diagnostics, &mut innermost_depths);
+    }
+    diagnostics
+}
+
+#[derive(Debug, Default)]
+struct InnermostDepths {
+    out_of_gas: Option<usize>,
+    revert: Option<usize>,
+    system_call: Option<usize>,
+}
+
+/// Checks whether a frame at `depth` is not shallower than the frame recorded in `slot_depth`, and records it if so.
+/// For frames with the same depth, the last one wins since it is the one closest to the failure.
+fn is_innermost(depth: usize, slot_depth: &mut Option<usize>) -> bool {
+    if slot_depth.map_or(true, |slot_depth| depth >= slot_depth) {
+        *slot_depth = Some(depth);
+        true
+    } else {
+        false
+    }
+}
+
+fn visit_call(
+    call: &Call,
+    depth: usize,
+    diagnostics: &mut ExecutionDiagnostics,
+    innermost_depths: &mut InnermostDepths,
+) {
+    let panicked = call.error.is_some();
+    let failed = panicked || call.revert_reason.is_some();
+    // Panics burn all gas provided to the frame.
+    if panicked
+        && call.gas_used >= call.gas
+        && is_innermost(depth, &mut innermost_depths.out_of_gas)
+    {
+        diagnostics.out_of_gas_frame = Some(diagnostic_frame(call, depth));
+    }
+    if let Some(revert_reason) = &call.revert_reason {
+        if is_innermost(depth, &mut innermost_depths.revert) {
+            diagnostics.revert_reason = Some(revert_reason.clone());
+        }
+    }
+    if failed
+        && is_system_contract(call.to)
+        && is_innermost(depth, &mut innermost_depths.system_call)
+    {
+        diagnostics.failed_system_call = Some(diagnostic_frame(call, depth));
+    }
+
+    for subcall in &call.calls {
+        visit_call(subcall, depth + 1, diagnostics, innermost_depths);
+    }
+}
+
+/// System contracts are deployed in the kernel space, i.e., at addresses below 2^16.
+fn is_system_contract(address: Address) -> bool {
+    address.as_bytes()[..18].iter().all(|&byte| byte == 0)
+}
+
+fn diagnostic_frame(call: &Call, depth: usize) -> DiagnosticCallFrame {
+    DiagnosticCallFrame {
+        from: call.from,
+        to: call.to,
+        depth,
+        gas: call.gas,
+        gas_used: call.gas_used,
+        error: call.error.clone(),
+        revert_reason: call.revert_reason.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use multivm::interface::{Halt, VmExecutionStatistics, VmRevertReason};
+    use zksync_system_constants::L2_ETH_TOKEN_ADDRESS;
+
+    use super::*;
+
+    fn call(to: Address, gas: u32, gas_used: u32, calls: Vec<Call>) -> Call {
+        Call {
+            from: Address::repeat_byte(0xee),
+            to,
+            gas,
+            gas_used,
+            calls,
+            ..Call::default()
+        }
+    }
+
+    fn failed_result() -> VmExecutionResultAndLogs {
+        VmExecutionResultAndLogs {
+            result: ExecutionResult::Halt {
+                reason: Halt::UnexpectedVMBehavior(String::new()),
+            },
+            logs: Default::default(),
+            statistics: VmExecutionStatistics {
+                gas_used: 1_000,
+                computational_gas_used: 700,
+                pubdata_published: 10,
+                ..VmExecutionStatistics::default()
+            },
+            refunds: Default::default(),
+        }
+    }
+
+    #[test]
+    fn diagnostics_are_collected_only_for_opaque_failures() {
+        assert!(!needs_diagnostics(&ExecutionResult::Success {
+            output: vec![]
+        }));
+        let revert_with_data = VmRevertReason::General {
+            msg: "oops".to_owned(),
+            data: vec![1, 2, 3],
+        };
+        assert!(!needs_diagnostics(&ExecutionResult::Revert {
+            output: revert_with_data
+        }));
+        assert!(needs_diagnostics(&failed_result().result));
+    }
+
+    #[test]
+    fn collecting_diagnostics() {
+        let contract = Address::repeat_byte(1);
+        let mut out_of_gas_call = call(Address::repeat_byte(2), 100, 100, vec![]);
+        out_of_gas_call.error = Some("Panic".to_owned());
+        let mut failed_transfer = call(L2_ETH_TOKEN_ADDRESS, 500, 50, vec![]);
+        failed_transfer.revert_reason = Some("insufficient balance".to_owned());
+        let mut outer_call = call(contract, 900, 900, vec![failed_transfer, out_of_gas_call]);
+        outer_call.revert_reason = Some("outer revert".to_owned());
+
+        let diagnostics = collect_diagnostics(&failed_result(), &[outer_call]);
+        assert_eq!(diagnostics.gas_used, 1_000);
+        assert_eq!(diagnostics.computational_gas_used, 700);
+        assert_eq!(diagnostics.pubdata_gas_used, 300);
+        assert_eq!(diagnostics.pubdata_published, 10);
+        assert_eq!(diagnostics.revert_reason.unwrap(), "insufficient balance");
+
+        let out_of_gas_frame = diagnostics.out_of_gas_frame.unwrap();
+        assert_eq!(out_of_gas_frame.to, Address::repeat_byte(2));
+        assert_eq!(out_of_gas_frame.depth, 1);
+        let failed_system_call = diagnostics.failed_system_call.unwrap();
+        assert_eq!(failed_system_call.to, L2_ETH_TOKEN_ADDRESS);
+        assert_eq!(failed_system_call.depth, 1);
+    }
+}
@@ -2,7 +2,7 @@
 
 use anyhow::Context as _;
 use multivm::{
-    interface::{TxExecutionMode, VmExecutionResultAndLogs, VmInterface},
+    interface::{ExecutionResult, TxExecutionMode, VmExecutionResultAndLogs, VmInterface},
     tracers::StorageInvocations,
     vm_latest::constants::ETH_CALL_GAS_LIMIT,
     MultiVMTracer,
@@ -10,13 +10,15 @@ use multivm::{
 use tracing::{span, Level};
 use zksync_dal::ConnectionPool;
 use zksync_types::{
-    fee::TransactionExecutionMetrics, l2::L2Tx, ExecuteTransactionCommon, Nonce,
-    PackedEthSignature, Transaction, U256,
+    api::StateOverride, fee::TransactionExecutionMetrics, l2::L2Tx, ExecuteTransactionCommon,
+    Nonce, PackedEthSignature, Transaction, U256,
 };
 
 #[cfg(test)]
 use super::testonly::MockTransactionExecutor;
-use super::{apply, vm_metrics, ApiTracer, BlockArgs, TxSharedArgs, VmPermit};
+use super::{
+    apply, storage::SharedReadCache, vm_metrics, ApiTracer, BlockArgs, TxSharedArgs, VmPermit,
+};
 
 #[derive(Debug)]
 pub(crate) struct TxExecutionArgs {
@@ -25,6 +27,10 @@ pub(crate) struct TxExecutionArgs {
     pub added_balance: U256,
     pub enforced_base_fee: Option<u64>,
     pub missed_storage_invocation_limit: usize,
+    /// Overrides of the account state applied before execution. Must be validated beforehand.
+    pub state_override: Option<StateOverride>,
+    /// Cache of storage reads shared with other executions of the same transaction.
+    pub read_cache: Option<SharedReadCache>,
 }
 
 impl TxExecutionArgs {
@@ -35,12 +41,15 @@ impl TxExecutionArgs {
             added_balance: U256::zero(),
             enforced_base_fee: Some(tx.common_data.fee.max_fee_per_gas.as_u64()),
             missed_storage_invocation_limit: usize::MAX,
+            state_override: None,
+            read_cache: None,
         }
     }
 
     fn for_eth_call(
         enforced_base_fee: u64,
         vm_execution_cache_misses_limit: Option<usize>,
+        state_override: Option<StateOverride>,
     ) -> Self {
         let missed_storage_invocation_limit = vm_execution_cache_misses_limit.unwrap_or(usize::MAX);
         Self {
@@ -49,6 +58,22 @@ impl TxExecutionArgs {
             added_balance: U256::zero(),
             enforced_base_fee: Some(enforced_base_fee),
             missed_storage_invocation_limit,
+            state_override,
+            read_cache: None,
+        }
+    }
+
+    /// Arguments to re-execute a transaction from a sealed miniblock. Transactions are executed
+    /// in the same way as in the state keeper, so nonces and balances are not adjusted.
+    fn for_replay(base_fee: u64) -> Self {
+        Self {
+            execution_mode: TxExecutionMode::VerifyExecute,
+            enforced_nonce: None,
+            added_balance: U256::zero(),
+            enforced_base_fee: Some(base_fee),
+            missed_storage_invocation_limit: usize::MAX,
+            state_override: None,
+            read_cache: None,
         }
     }
 
@@ -56,6 +81,7 @@ impl TxExecutionArgs {
         vm_execution_cache_misses_limit: Option<usize>,
         tx: &Transaction,
         base_fee: u64,
+        state_override: Option<StateOverride>,
     ) -> Self {
         let missed_storage_invocation_limit = vm_execution_cache_misses_limit.unwrap_or(usize::MAX);
         // For L2 transactions we need to explicitly put enough balance into the account of the users
@@ -72,6 +98,8 @@ impl TxExecutionArgs {
             enforced_nonce: tx.nonce(),
             added_balance,
             enforced_base_fee: Some(base_fee),
+            state_override,
+            read_cache: None,
         }
     }
 }
@@ -174,10 +202,14 @@ impl TransactionExecutor {
         block_args: BlockArgs,
         vm_execution_cache_misses_limit: Option<usize>,
         custom_tracers: Vec<ApiTracer>,
+        state_override: Option<StateOverride>,
     ) -> anyhow::Result<VmExecutionResultAndLogs> {
         let enforced_base_fee = tx.common_data.fee.max_fee_per_gas.as_u64();
-        let execution_args =
-            TxExecutionArgs::for_eth_call(enforced_base_fee, vm_execution_cache_misses_limit);
+        let execution_args = TxExecutionArgs::for_eth_call(
+            enforced_base_fee,
+            vm_execution_cache_misses_limit,
+            state_override,
+        );
 
         if tx.common_data.signature.is_empty() {
             tx.common_data.signature = PackedEthSignature::default().serialize_packed().into();
@@ -201,4 +233,65 @@ impl TransactionExecutor {
             .await?;
         Ok(output.vm)
     }
+
+    /// Re-executes a transaction from a sealed miniblock with the provided tracers. `preceding_txs` are
+    /// the transactions from the same miniblock preceding `tx`; they are executed without tracers.
+    /// `block_args` must be created with [`BlockArgs::for_replay()`].
+    #[allow(clippy::too_many_arguments)]
+    #[tracing::instrument(skip_all)]
+    pub async fn replay_tx_in_sandbox(
+        &self,
+        vm_permit: VmPermit,
+        shared_args: TxSharedArgs,
+        connection_pool: ConnectionPool,
+        preceding_txs: Vec<Transaction>,
+        tx: Transaction,
+        block_args: BlockArgs,
+        base_fee: u64,
+        custom_tracers: Vec<ApiTracer>,
+    ) -> anyhow::Result<VmExecutionResultAndLogs> {
+        #[cfg(test)]
+        if let Self::Mock(mock_executor) = self {
+            return Ok(mock_executor.execute_tx(&tx, &block_args)?.vm);
+        }
+
+        let execution_args = TxExecutionArgs::for_replay(base_fee);
+        tokio::task::spawn_blocking(move || {
+            let span = span!(Level::DEBUG, "replay_in_sandbox").entered();
+            let result = apply::apply_vm_in_sandbox(
+                vm_permit,
+                shared_args,
+                false,
+                &execution_args,
+                &connection_pool,
+                tx,
+                block_args,
+                |vm, tx| {
+                    for preceding_tx in preceding_txs {
+                        let tx_hash = preceding_tx.hash();
+                        let (_, result) =
+                            vm.execute_transaction_with_bytecode_compression(preceding_tx, true);
+                        // Halted transactions are never included into miniblocks, so this means that replay diverged.
+                        if let ExecutionResult::Halt { reason } = &result.result {
+                            tracing::warn!("Replayed transaction {tx_hash:?} halted: {reason}");
+                        }
+                    }
+                    let custom_tracers: Vec<_> = custom_tracers
+                        .into_iter()
+                        .map(|tracer| tracer.into_boxed())
+                        .collect();
+                    vm.inspect_transaction_with_bytecode_compression(
+                        custom_tracers.into(),
+                        tx,
+                        true,
+                    )
+                    .1
+                },
+            );
+            span.exit();
+            result
+        })
+        .await
+        .context("transaction replay panicked")?
+    }
 }
@@ -10,10 +10,13 @@ use zksync_types::{
 };
 use zksync_utils::bytecode::{compress_bytecode, hash_bytecode};
 
+pub use self::tracers::{SandboxExecutionKind, SandboxTracerFactory};
 use self::vm_metrics::SandboxStage;
 pub(super) use self::{
+    diagnostics::{collect_diagnostics, needs_diagnostics},
     error::SandboxExecutionError,
     execute::{TransactionExecutor, TxExecutionArgs},
+    storage::{validate_state_override, SharedReadCache},
     tracers::ApiTracer,
     validate::ValidationError,
     vm_metrics::{SubmitTxStage, SANDBOX_METRICS},
@@ -22,8 +25,10 @@ use super::tx_sender::MultiVMBaseSystemContracts;
 
 // Note: keep the modules private, and instead re-export functions that make public interface.
 mod apply;
+mod diagnostics;
 mod error;
 mod execute;
+mod storage;
 #[cfg(test)]
 pub(super) mod testonly;
 #[cfg(test)]
@@ -123,6 +128,11 @@ impl VmConcurrencyLimiter {
         (this, barrier)
     }
 
+    /// Checks whether the limiter is closed using [`VmConcurrencyBarrier::close()`], i.e., the API server is shutting down.
+    pub fn is_closed(&self) -> bool {
+        self.limiter.is_closed()
+    }
+
     /// Waits until there is a free slot in the concurrency limiter.
     /// Returns a permit that should be dropped when the VM execution is finished.
     pub async fn acquire(&self) -> Option<VmPermit> {
@@ -240,8 +250,9 @@ impl TxSharedArgs {
     }
 }
 
-/// Information about first L1 batch / miniblock in the node storage.
-#[derive(Debug, Clone, Copy)]
+/// Information about first L1 batch / miniblock in the node storage. Blocks are unavailable if the node state
+/// was recovered from a snapshot, or if the node storage was pruned.
+#[derive(Debug, Clone, Copy, Default)]
 pub(crate) struct BlockStartInfo {
     /// Number of the first locally available miniblock.
     pub first_miniblock: MiniblockNumber,
@@ -257,11 +268,26 @@ impl BlockStartInfo {
             .await
             .context("failed getting snapshot recovery status")?;
         let snapshot_recovery = snapshot_recovery.as_ref();
+        let mut first_miniblock =
+            snapshot_recovery.map_or(MiniblockNumber(0), |recovery| recovery.miniblock_number + 1);
+        let mut first_l1_batch =
+            snapshot_recovery.map_or(L1BatchNumber(0), |recovery| recovery.l1_batch_number + 1);
+
+        // Soft-pruned data is treated as unavailable even if it's not removed from the storage yet.
+        let pruning_info = storage
+            .pruning_dal()
+            .get_pruning_info()
+            .await
+            .context("failed getting pruning info")?;
+        if let Some(last_pruned) = pruning_info.last_soft_pruned_miniblock {
+            first_miniblock = first_miniblock.max(last_pruned + 1);
+        }
+        if let Some(last_pruned) = pruning_info.last_soft_pruned_l1_batch {
+            first_l1_batch = first_l1_batch.max(last_pruned + 1);
+        }
         Ok(Self {
-            first_miniblock: snapshot_recovery
-                .map_or(MiniblockNumber(0), |recovery| recovery.miniblock_number + 1),
-            first_l1_batch: snapshot_recovery
-                .map_or(L1BatchNumber(0), |recovery| recovery.l1_batch_number + 1),
+            first_miniblock,
+            first_l1_batch,
         })
     }
 
@@ -300,6 +326,9 @@ pub(crate) struct BlockArgs {
     block_id: api::BlockId,
     resolved_block_number: MiniblockNumber,
     l1_batch_timestamp_s: Option<u64>,
+    /// If set, the VM starts from the state at the end of the previous miniblock, so that transactions
+    /// from the resolved miniblock can be re-executed in its context.
+    is_replay: bool,
 }
 
 impl BlockArgs {
@@ -309,6 +338,7 @@ impl BlockArgs {
             block_id,
             resolved_block_number,
             l1_batch_timestamp_s: None,
+            is_replay: false,
         })
     }
 
@@ -355,6 +385,25 @@ impl BlockArgs {
             block_id,
             resolved_block_number,
             l1_batch_timestamp_s: Some(l1_batch_timestamp),
+            is_replay: false,
+        })
+    }
+
+    /// Creates block args for re-executing transactions from the specified sealed miniblock. The VM starts
+    /// from the state at the end of the previous miniblock and uses the environment (number, timestamp, fee input etc.)
+    /// of the replayed miniblock.
+    pub async fn for_replay(
+        connection: &mut StorageProcessor<'_>,
+        miniblock_number: MiniblockNumber,
+        start_info: BlockStartInfo,
+    ) -> Result<Self, BlockArgsError> {
+        if miniblock_number == MiniblockNumber(0) {
+            return Err(anyhow::anyhow!("genesis miniblock cannot be replayed").into());
+        }
+        let block_id = api::BlockId::Number(miniblock_number.0.into());
+        Ok(Self {
+            is_replay: true,
+            ..Self::new(connection, block_id, start_info).await?
         })
     }
 
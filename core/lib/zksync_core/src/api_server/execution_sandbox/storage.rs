@@ -0,0 +1,352 @@
+//! VM storage functionality specifically used in the VM sandbox.
+
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex},
+};
+
+use zksync_state::ReadStorage;
+use zksync_types::{
+    api::StateOverride,
+    get_code_key, get_known_code_key, get_nonce_key,
+    utils::{decompose_full_nonce, nonces_to_full_nonce, storage_key_for_eth_balance},
+    AccountTreeId, Address, MiniblockNumber, StorageKey, StorageValue, H256, U256,
+};
+use zksync_utils::{
+    bytecode::{hash_bytecode, validate_bytecode, InvalidBytecodeError},
+    h256_to_u256, u256_to_h256,
+};
+
+/// Errors that can occur when validating a [`StateOverride`].
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum StateOverrideError {
+    #[error("both `state` and `stateDiff` are specified for account {0:?}")]
+    StateAndStateDiff(Address),
+    #[error("nonce override for account {0:?} does not fit into 32 bits")]
+    NonceTooLarge(Address),
+    #[error("invalid code override for account {0:?}: {1}")]
+    InvalidCode(Address, #[source] InvalidBytecodeError),
+}
+
+/// Checks that the provided overrides can be applied by [`StorageWithOverrides`].
+pub(crate) fn validate_state_override(
+    state_override: &StateOverride,
+) -> Result<(), StateOverrideError> {
+    for (&address, account) in state_override {
+        if account.state.is_some() && account.state_diff.is_some() {
+            return Err(StateOverrideError::StateAndStateDiff(address));
+        }
+        if let Some(nonce) = account.nonce {
+            if nonce > U256::from(u32::MAX) {
+                return Err(StateOverrideError::NonceTooLarge(address));
+            }
+        }
+        if let Some(code) = &account.code {
+            // Empty code is used to remove the contract code.
+            if !code.0.is_empty() {
+                validate_bytecode(&code.0)
+                    .map_err(|err| StateOverrideError::InvalidCode(address, err))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[derive(Debug, Default)]
+struct ReadCacheInner {
+    miniblock_number: Option<MiniblockNumber>,
+    values: HashMap<StorageKey, StorageValue>,
+}
+
+/// Cache of storage reads shared among several sandboxed executions of the same transaction, e.g.,
+/// gas estimation iterations. Values are cached for a single miniblock; the cache is reset
+/// if an execution is performed on top of another miniblock.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct SharedReadCache(Arc<Mutex<ReadCacheInner>>);
+
+impl SharedReadCache {
+    fn lock(&self) -> std::sync::MutexGuard<'_, ReadCacheInner> {
+        self.0.lock().expect("storage read cache is poisoned")
+    }
+
+    fn pin_miniblock(&self, miniblock_number: MiniblockNumber) {
+        let mut inner = self.lock();
+        if inner.miniblock_number != Some(miniblock_number) {
+            inner.miniblock_number = Some(miniblock_number);
+            inner.values.clear();
+        }
+    }
+
+    fn get(&self, key: &StorageKey) -> Option<StorageValue> {
+        self.lock().values.get(key).copied()
+    }
+
+    fn insert(&self, key: StorageKey, value: StorageValue) {
+        self.lock().values.insert(key, value);
+    }
+
+    #[cfg(test)]
+    fn len(&self) -> usize {
+        self.lock().values.len()
+    }
+}
+
+/// [`ReadStorage`] implementation layering a [`StateOverride`] over the underlying storage.
+#[derive(Debug)]
+pub(super) struct StorageWithOverrides<S> {
+    storage_handle: S,
+    read_cache: Option<SharedReadCache>,
+    overridden_slots: HashMap<StorageKey, StorageValue>,
+    /// Overridden account nonces. Full nonces are composed on read since they include the deployment nonce.
+    overridden_nonces: HashMap<StorageKey, U256>,
+    overridden_factory_deps: HashMap<H256, Vec<u8>>,
+    /// Accounts with fully replaced storage; slots not present in `overridden_slots` are zero for them.
+    empty_storage_accounts: HashSet<AccountTreeId>,
+}
+
+impl<S: ReadStorage> StorageWithOverrides<S> {
+    /// Creates a storage with the specified overrides. The overrides must be validated
+    /// with [`validate_state_override()`] beforehand.
+    pub fn new(storage: S, state_override: Option<&StateOverride>) -> Self {
+        let mut this = Self {
+            storage_handle: storage,
+            read_cache: None,
+            overridden_slots: HashMap::new(),
+            overridden_nonces: HashMap::new(),
+            overridden_factory_deps: HashMap::new(),
+            empty_storage_accounts: HashSet::new(),
+        };
+        for (address, account) in state_override.into_iter().flatten() {
+            if let Some(balance) = account.balance {
+                let balance_key = storage_key_for_eth_balance(address);
+                this.overridden_slots
+                    .insert(balance_key, u256_to_h256(balance));
+            }
+            if let Some(nonce) = account.nonce {
+                this.overridden_nonces.insert(get_nonce_key(address), nonce);
+            }
+            if let Some(code) = &account.code {
+                this.override_code(address, code.0.clone());
+            }
+
+            let account_id = AccountTreeId::new(*address);
+            if account.state.is_some() {
+                this.empty_storage_accounts.insert(account_id);
+            }
+            let slots = account.state.iter().chain(&account.state_diff).flatten();
+            for (&slot, &value) in slots {
+                let key = StorageKey::new(account_id, slot);
+                this.overridden_slots.insert(key, value);
+            }
+        }
+        this
+    }
+
+    /// Makes reads from the underlying storage at the specified miniblock go through the provided cache.
+    pub fn with_read_cache(
+        mut self,
+        cache: SharedReadCache,
+        miniblock_number: MiniblockNumber,
+    ) -> Self {
+        cache.pin_miniblock(miniblock_number);
+        self.read_cache = Some(cache);
+        self
+    }
+
+    fn read_underlying_value(&mut self, key: &StorageKey) -> StorageValue {
+        let Some(cache) = &self.read_cache else {
+            return self.storage_handle.read_value(key);
+        };
+        if let Some(value) = cache.get(key) {
+            return value;
+        }
+        let value = self.storage_handle.read_value(key);
+        cache.insert(*key, value);
+        value
+    }
+
+    fn override_code(&mut self, address: &Address, code: Vec<u8>) {
+        let code_key = get_code_key(address);
+        if code.is_empty() {
+            self.overridden_slots.insert(code_key, H256::zero());
+            return;
+        }
+
+        let bytecode_hash = hash_bytecode(&code);
+        self.overridden_slots.insert(code_key, bytecode_hash);
+        // Mark the bytecode as known, so that it can be executed.
+        let known_code_key = get_known_code_key(&bytecode_hash);
+        self.overridden_slots
+            .insert(known_code_key, H256::from_low_u64_be(1));
+        self.overridden_factory_deps.insert(bytecode_hash, code);
+    }
+}
+
+impl<S: ReadStorage> ReadStorage for StorageWithOverrides<S> {
+    fn read_value(&mut self, key: &StorageKey) -> StorageValue {
+        if let Some(value) = self.overridden_slots.get(key) {
+            return *value;
+        }
+        if let Some(&nonce) = self.overridden_nonces.get(key) {
+            let full_nonce = h256_to_u256(self.read_underlying_value(key));
+            let (_, deployment_nonce) = decompose_full_nonce(full_nonce);
+            return u256_to_h256(nonces_to_full_nonce(nonce, deployment_nonce));
+        }
+        if self.empty_storage_accounts.contains(key.account()) {
+            return H256::zero();
+        }
+        self.read_underlying_value(key)
+    }
+
+    fn is_write_initial(&mut self, key: &StorageKey) -> bool {
+        self.storage_handle.is_write_initial(key)
+    }
+
+    fn load_factory_dep(&mut self, hash: H256) -> Option<Vec<u8>> {
+        if let Some(code) = self.overridden_factory_deps.get(&hash) {
+            return Some(code.clone());
+        }
+        self.storage_handle.load_factory_dep(hash)
+    }
+
+    fn get_enumeration_index(&mut self, key: &StorageKey) -> Option<u64> {
+        self.storage_handle.get_enumeration_index(key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_state::InMemoryStorage;
+    use zksync_types::api::OverrideAccount;
+
+    use super::*;
+
+    #[test]
+    fn overriding_account_state() {
+        let address = Address::repeat_byte(1);
+        let other_address = Address::repeat_byte(2);
+        let slot =
+            |index: u64| StorageKey::new(AccountTreeId::new(address), H256::from_low_u64_be(index));
+        let deployment_nonce = U256::from(3);
+
+        let mut storage = InMemoryStorage::default();
+        storage.set_value(slot(0), H256::repeat_byte(0xff));
+        storage.set_value(slot(1), H256::repeat_byte(0xff));
+        storage.set_value(
+            get_nonce_key(&address),
+            u256_to_h256(nonces_to_full_nonce(5.into(), deployment_nonce)),
+        );
+        storage.set_value(
+            storage_key_for_eth_balance(&other_address),
+            H256::from_low_u64_be(100),
+        );
+
+        let code = vec![1; 32];
+        let code_hash = hash_bytecode(&code);
+        let state_override = StateOverride::from([(
+            address,
+            OverrideAccount {
+                balance: Some(42.into()),
+                nonce: Some(7.into()),
+                code: Some(code.clone().into()),
+                state_diff: Some(HashMap::from([(
+                    H256::from_low_u64_be(1),
+                    H256::repeat_byte(1),
+                )])),
+                ..OverrideAccount::default()
+            },
+        )]);
+        validate_state_override(&state_override).unwrap();
+        let mut storage = StorageWithOverrides::new(storage, Some(&state_override));
+
+        let balance = storage.read_value(&storage_key_for_eth_balance(&address));
+        assert_eq!(h256_to_u256(balance), 42.into());
+        let other_balance = storage.read_value(&storage_key_for_eth_balance(&other_address));
+        assert_eq!(h256_to_u256(other_balance), 100.into());
+
+        let full_nonce = h256_to_u256(storage.read_value(&get_nonce_key(&address)));
+        assert_eq!(
+            decompose_full_nonce(full_nonce),
+            (7.into(), deployment_nonce)
+        );
+
+        assert_eq!(storage.read_value(&get_code_key(&address)), code_hash);
+        assert!(storage.is_bytecode_known(&code_hash));
+        assert_eq!(storage.load_factory_dep(code_hash), Some(code));
+
+        assert_eq!(storage.read_value(&slot(0)), H256::repeat_byte(0xff));
+        assert_eq!(storage.read_value(&slot(1)), H256::repeat_byte(1));
+    }
+
+    #[test]
+    fn replacing_account_storage() {
+        let address = Address::repeat_byte(1);
+        let slot =
+            |index: u64| StorageKey::new(AccountTreeId::new(address), H256::from_low_u64_be(index));
+        let mut storage = InMemoryStorage::default();
+        storage.set_value(slot(0), H256::repeat_byte(0xff));
+        storage.set_value(slot(1), H256::repeat_byte(0xff));
+
+        let state_override = StateOverride::from([(
+            address,
+            OverrideAccount {
+                state: Some(HashMap::from([(
+                    H256::from_low_u64_be(1),
+                    H256::repeat_byte(1),
+                )])),
+                ..OverrideAccount::default()
+            },
+        )]);
+        let mut storage = StorageWithOverrides::new(storage, Some(&state_override));
+        assert_eq!(storage.read_value(&slot(0)), H256::zero());
+        assert_eq!(storage.read_value(&slot(1)), H256::repeat_byte(1));
+    }
+
+    #[test]
+    fn caching_reads() {
+        let key = StorageKey::new(AccountTreeId::new(Address::repeat_byte(1)), H256::zero());
+        let mut storage = InMemoryStorage::default();
+        storage.set_value(key, H256::repeat_byte(1));
+        let cache = SharedReadCache::default();
+
+        let mut storage_with_cache = StorageWithOverrides::new(storage.clone(), None)
+            .with_read_cache(cache.clone(), MiniblockNumber(1));
+        assert_eq!(storage_with_cache.read_value(&key), H256::repeat_byte(1));
+        assert_eq!(cache.len(), 1);
+
+        // The cached value should be used even if the underlying storage changes.
+        storage.set_value(key, H256::repeat_byte(2));
+        let mut storage_with_cache = StorageWithOverrides::new(storage.clone(), None)
+            .with_read_cache(cache.clone(), MiniblockNumber(1));
+        assert_eq!(storage_with_cache.read_value(&key), H256::repeat_byte(1));
+
+        // ...but not if the miniblock changes.
+        let mut storage_with_cache = StorageWithOverrides::new(storage, None)
+            .with_read_cache(cache.clone(), MiniblockNumber(2));
+        assert_eq!(storage_with_cache.read_value(&key), H256::repeat_byte(2));
+    }
+
+    #[test]
+    fn validating_state_override() {
+        let address = Address::repeat_byte(1);
+        let invalid_overrides = [
+            OverrideAccount {
+                state: Some(HashMap::new()),
+                state_diff: Some(HashMap::new()),
+                ..OverrideAccount::default()
+            },
+            OverrideAccount {
+                nonce: Some(U256::from(u64::MAX)),
+                ..OverrideAccount::default()
+            },
+            OverrideAccount {
+                code: Some(vec![1; 64].into()),
+                ..OverrideAccount::default()
+            },
+        ];
+        for account in invalid_overrides {
+            let state_override = StateOverride::from([(address, account)]);
+            validate_state_override(&state_override).unwrap_err();
+        }
+    }
+}
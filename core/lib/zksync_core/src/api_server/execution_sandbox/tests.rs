@@ -1,6 +1,11 @@
 //! Tests for the VM execution sandbox.
 
 use assert_matches::assert_matches;
+use once_cell::sync::OnceCell;
+use zksync_types::{
+    api::StructLoggerConfig, fee::Fee, l2::L2Tx, transaction_request::PaymasterParams,
+    utils::storage_key_for_eth_balance, Address, Nonce, StorageLog, H256, U256,
+};
 
 use super::*;
 use crate::{
@@ -156,6 +161,44 @@ async fn creating_block_args_after_snapshot_recovery() {
     }
 }
 
+#[tokio::test]
+async fn creating_block_args_after_pruning() {
+    let pool = ConnectionPool::test_pool().await;
+    let mut storage = pool.access_storage().await.unwrap();
+    ensure_genesis_state(&mut storage, L2ChainId::default(), &GenesisParams::mock())
+        .await
+        .unwrap();
+    for number in 1..=3 {
+        storage
+            .blocks_dal()
+            .insert_miniblock(&create_miniblock(number))
+            .await
+            .unwrap();
+    }
+    storage
+        .pruning_dal()
+        .soft_prune_batches_range(L1BatchNumber(0), MiniblockNumber(1))
+        .await
+        .unwrap();
+
+    let start_info = BlockStartInfo::new(&mut storage).await.unwrap();
+    assert_eq!(start_info.first_miniblock, MiniblockNumber(2));
+    assert_eq!(start_info.first_l1_batch, L1BatchNumber(1));
+
+    // Soft-pruned blocks are considered unavailable even though they are still present in the storage.
+    for pruned_block in [api::BlockNumber::Earliest, 0.into(), 1.into()] {
+        let pruned_block = api::BlockId::Number(pruned_block);
+        let err = BlockArgs::new(&mut storage, pruned_block, start_info)
+            .await
+            .unwrap_err();
+        assert_matches!(err, BlockArgsError::Pruned(MiniblockNumber(2)));
+    }
+    let block_args = BlockArgs::new(&mut storage, api::BlockId::Number(2.into()), start_info)
+        .await
+        .unwrap();
+    assert_eq!(block_args.resolved_block_number, MiniblockNumber(2));
+}
+
 #[tokio::test]
 async fn instantiating_vm() {
     let pool = ConnectionPool::test_pool().await;
@@ -183,7 +226,7 @@ async fn test_instantiating_vm(pool: ConnectionPool, block_args: BlockArgs) {
             vm_permit,
             TxSharedArgs::mock(ApiContracts::load_from_disk().estimate_gas, pool.clone()),
             true,
-            &TxExecutionArgs::for_gas_estimate(None, &transaction, 123),
+            &TxExecutionArgs::for_gas_estimate(None, &transaction, 123, None),
             &pool,
             transaction.clone(),
             block_args,
@@ -196,3 +239,83 @@ async fn test_instantiating_vm(pool: ConnectionPool, block_args: BlockArgs) {
     .expect("VM instantiation panicked")
     .expect("VM instantiation errored");
 }
+
+#[tokio::test]
+async fn replaying_transaction_with_struct_logger() {
+    let pool = ConnectionPool::test_pool().await;
+    let mut storage = pool.access_storage().await.unwrap();
+    ensure_genesis_state(&mut storage, L2ChainId::default(), &GenesisParams::mock())
+        .await
+        .unwrap();
+
+    let fee = Fee {
+        gas_limit: 10_000_000_u64.into(),
+        max_fee_per_gas: 100_u64.into(),
+        max_priority_fee_per_gas: 0_u64.into(),
+        gas_per_pubdata_limit: 50_000_u64.into(),
+    };
+    let transaction = L2Tx::new_signed(
+        Address::repeat_byte(1),
+        vec![],
+        Nonce(0),
+        fee,
+        U256::zero(),
+        L2ChainId::default(),
+        &H256::repeat_byte(0x11),
+        None,
+        PaymasterParams::default(),
+    )
+    .unwrap();
+    // Fund the transaction initiator at genesis.
+    let balance_key = storage_key_for_eth_balance(&transaction.initiator_account());
+    let balance_log = StorageLog::new_write_log(balance_key, H256::from_low_u64_be(u64::MAX));
+    storage
+        .storage_logs_dal()
+        .append_storage_logs(MiniblockNumber(0), &[(H256::zero(), vec![balance_log])])
+        .await
+        .unwrap();
+    let miniblock = create_miniblock(1);
+    storage
+        .blocks_dal()
+        .insert_miniblock(&miniblock)
+        .await
+        .unwrap();
+
+    let start_info = BlockStartInfo::new(&mut storage).await.unwrap();
+    let block_args = BlockArgs::for_replay(&mut storage, miniblock.number, start_info)
+        .await
+        .unwrap();
+    assert_eq!(block_args.resolved_block_number, miniblock.number);
+    drop(storage);
+
+    let (vm_concurrency_limiter, _) = VmConcurrencyLimiter::new(1);
+    let vm_permit = vm_concurrency_limiter.acquire().await.unwrap();
+    let struct_logger_result = Arc::new(OnceCell::default());
+    let tracers = vec![ApiTracer::StructLogger(
+        StructLoggerConfig::default(),
+        struct_logger_result.clone(),
+    )];
+    let result = TransactionExecutor::Real
+        .replay_tx_in_sandbox(
+            vm_permit,
+            TxSharedArgs::mock(ApiContracts::load_from_disk().eth_call, pool.clone()),
+            pool,
+            vec![],
+            transaction.into(),
+            block_args,
+            miniblock.base_fee_per_gas,
+            tracers,
+        )
+        .await
+        .unwrap();
+    assert!(!result.result.is_failed(), "{:?}", result.result);
+
+    // Opcodes of the default account executed for the initiator must be logged.
+    let struct_logs = struct_logger_result.get().unwrap();
+    assert!(!struct_logs.is_empty());
+    assert!(
+        struct_logs.iter().all(|log| log.depth >= 1),
+        "{struct_logs:?}"
+    );
+    assert!(struct_logs.iter().all(|log| log.stack.is_some()));
+}
@@ -1,14 +1,26 @@
-use std::sync::Arc;
+use std::{fmt, sync::Arc};
 
-use multivm::{tracers::CallTracer, vm_latest::HistoryMode, MultiVMTracer, MultiVmTracerPointer};
+use multivm::{
+    tracers::{CallTracer, ExternalTracer, ExternalTracerAdapter, StructLogTracer},
+    vm_latest::HistoryMode,
+    MultiVMTracer, MultiVmTracerPointer,
+};
 use once_cell::sync::OnceCell;
 use zksync_state::WriteStorage;
-use zksync_types::vm_trace::Call;
+use zksync_types::{
+    api::{StructLog, StructLoggerConfig},
+    vm_trace::Call,
+    Transaction,
+};
 
 /// Custom tracers supported by our API
 #[derive(Debug)]
 pub(crate) enum ApiTracer {
     CallTracer(Arc<OnceCell<Vec<Call>>>),
+    /// Geth-compatible opcode-level logger.
+    StructLogger(StructLoggerConfig, Arc<OnceCell<Vec<StructLog>>>),
+    /// Tracer created by a [`SandboxTracerFactory`].
+    External(ExternalTracerAdapter),
 }
 
 impl ApiTracer {
@@ -20,6 +32,34 @@ impl ApiTracer {
     ) -> MultiVmTracerPointer<S, H> {
         match self {
             ApiTracer::CallTracer(tracer) => CallTracer::new(tracer.clone()).into_tracer_pointer(),
+            ApiTracer::StructLogger(config, result) => {
+                StructLogTracer::new(config, result).into_tracer_pointer()
+            }
+            ApiTracer::External(tracer) => tracer.into_tracer_pointer(),
         }
     }
 }
+
+/// Kind of a sandboxed transaction execution.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum SandboxExecutionKind {
+    /// Execution of an `eth_call` request.
+    EthCall,
+    /// One of the executions performed during gas estimation.
+    GasEstimation,
+    /// Dry run of a transaction submitted via `eth_sendRawTransaction`.
+    TxSubmission,
+}
+
+/// Factory of [`ExternalTracer`]s attached to sandboxed transaction executions performed by
+/// [`TxSender`](crate::api_server::tx_sender::TxSender). Allows building custom analyzers
+/// on top of the API server without patching it.
+pub trait SandboxTracerFactory: fmt::Debug + Send + Sync {
+    /// Creates a tracer for the specified execution, or returns `None` if the execution should not be traced.
+    fn create_tracer(
+        &self,
+        kind: SandboxExecutionKind,
+        tx: &Transaction,
+    ) -> Option<Box<dyn ExternalTracer>>;
+}
@@ -0,0 +1,235 @@
+//! gRPC API streaming new miniblocks, logs and L1 batch status updates. Intended for high-throughput
+//! indexers that prefer a push-based protocol to polling the Web3 JSON-RPC API.
+//!
+//! Streams are fed by the same notifiers as Web3 pub-sub subscriptions, so the server polls Postgres
+//! once per polling interval regardless of the number of connected clients.
+
+use std::pin::Pin;
+
+use anyhow::Context as _;
+use futures::{stream, Stream, StreamExt};
+use tokio::{
+    sync::{broadcast, watch},
+    task::JoinHandle,
+};
+use tokio_stream::wrappers::{errors::BroadcastStreamRecvError, BroadcastStream};
+use tonic::{Request, Response, Status};
+use zksync_config::configs::api::GrpcApiConfig;
+use zksync_dal::ConnectionPool;
+use zksync_types::{api, Address, H256};
+use zksync_web3_decl::types::{BlockHeader, Log, PubSubFilter, PubSubResult, ValueOrArray};
+
+use self::proto::stream_service_server::{StreamService, StreamServiceServer};
+use crate::api_server::web3::{namespaces::eth::EVENT_TOPIC_NUMBER_LIMIT, pubsub::EthSubscribe};
+
+mod proto;
+#[cfg(test)]
+mod tests;
+
+type ResponseStream<T> = Pin<Box<dyn Stream<Item = Result<T, Status>> + Send>>;
+
+fn parse_address(bytes: &[u8]) -> Result<Address, Status> {
+    if bytes.len() != Address::len_bytes() {
+        let message = format!("invalid address length: {}", bytes.len());
+        return Err(Status::invalid_argument(message));
+    }
+    Ok(Address::from_slice(bytes))
+}
+
+fn parse_h256(bytes: &[u8]) -> Result<H256, Status> {
+    if bytes.len() != H256::len_bytes() {
+        let message = format!("invalid hash length: {}", bytes.len());
+        return Err(Status::invalid_argument(message));
+    }
+    Ok(H256::from_slice(bytes))
+}
+
+impl TryFrom<proto::LogFilter> for PubSubFilter {
+    type Error = Status;
+
+    fn try_from(filter: proto::LogFilter) -> Result<Self, Self::Error> {
+        if filter.topics.len() > EVENT_TOPIC_NUMBER_LIMIT {
+            let message = format!("filter cannot have more than {EVENT_TOPIC_NUMBER_LIMIT} topics");
+            return Err(Status::invalid_argument(message));
+        }
+
+        let addresses = filter
+            .addresses
+            .iter()
+            .map(|address| parse_address(address))
+            .collect::<Result<Vec<_>, _>>()?;
+        let topics = filter
+            .topics
+            .iter()
+            .map(|topics| {
+                let values = topics
+                    .values
+                    .iter()
+                    .map(|topic| parse_h256(topic))
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok((!values.is_empty()).then_some(ValueOrArray(values)))
+            })
+            .collect::<Result<Vec<_>, Status>>()?;
+        Ok(Self {
+            address: (!addresses.is_empty()).then_some(ValueOrArray(addresses)),
+            topics: (!topics.is_empty()).then_some(topics),
+        })
+    }
+}
+
+impl From<BlockHeader> for proto::Miniblock {
+    fn from(header: BlockHeader) -> Self {
+        Self {
+            number: header.number.map(|number| number.as_u32()),
+            hash: header.hash.map(|hash| hash.as_bytes().to_vec()),
+            timestamp: Some(header.timestamp.as_u64()),
+        }
+    }
+}
+
+impl From<Log> for proto::Log {
+    fn from(log: Log) -> Self {
+        Self {
+            address: Some(log.address.as_bytes().to_vec()),
+            topics: log
+                .topics
+                .iter()
+                .map(|topic| topic.as_bytes().to_vec())
+                .collect(),
+            data: Some(log.data.0),
+            miniblock_number: log.block_number.map(|number| number.as_u32()),
+            miniblock_hash: log.block_hash.map(|hash| hash.as_bytes().to_vec()),
+            l1_batch_number: log.l1_batch_number.map(|number| number.as_u32()),
+            transaction_hash: log.transaction_hash.map(|hash| hash.as_bytes().to_vec()),
+            transaction_index: log.transaction_index.map(|index| index.as_u32()),
+            log_index: log.log_index.map(|index| index.as_u32()),
+        }
+    }
+}
+
+impl From<api::L1BatchStatusUpdate> for proto::L1BatchStatusUpdate {
+    fn from(update: api::L1BatchStatusUpdate) -> Self {
+        let stage = match update.stage {
+            api::L1BatchStage::Committed => proto::L1BatchStage::Committed,
+            api::L1BatchStage::Proven => proto::L1BatchStage::Proven,
+            api::L1BatchStage::Executed => proto::L1BatchStage::Executed,
+        };
+        Self {
+            number: Some(update.number.0),
+            stage: Some(stage.into()),
+            eth_tx_hash: update.eth_tx_hash.map(|hash| hash.as_bytes().to_vec()),
+        }
+    }
+}
+
+/// Converts notifications from a pub-sub notifier into a gRPC response stream. If the client cannot keep up
+/// with notifications, the stream is terminated with an error, so that the client doesn't silently miss data.
+fn response_stream<T: Send + 'static>(
+    receiver: broadcast::Receiver<Vec<PubSubResult>>,
+    mut map_item: impl FnMut(PubSubResult) -> Option<T> + Send + 'static,
+) -> ResponseStream<T> {
+    let items = BroadcastStream::new(receiver).flat_map(move |items| {
+        let items: Vec<_> = match items {
+            Ok(items) => items
+                .into_iter()
+                .filter_map(&mut map_item)
+                .map(Ok)
+                .collect(),
+            Err(BroadcastStreamRecvError::Lagged(count)) => {
+                let message = format!("subscriber has lagged behind by {count} notifications");
+                vec![Err(Status::resource_exhausted(message))]
+            }
+        };
+        stream::iter(items)
+    });
+    Box::pin(items)
+}
+
+struct StreamServiceImpl {
+    pub_sub: EthSubscribe,
+}
+
+#[tonic::async_trait]
+impl StreamService for StreamServiceImpl {
+    type SubscribeMiniblocksStream = ResponseStream<proto::Miniblock>;
+
+    async fn subscribe_miniblocks(
+        &self,
+        _request: Request<proto::SubscribeMiniblocksRequest>,
+    ) -> Result<Response<Self::SubscribeMiniblocksStream>, Status> {
+        let receiver = self.pub_sub.subscribe_blocks();
+        Ok(Response::new(response_stream(
+            receiver,
+            |item| match item {
+                PubSubResult::Header(header) => Some(header.into()),
+                _ => None,
+            },
+        )))
+    }
+
+    type SubscribeLogsStream = ResponseStream<proto::Log>;
+
+    async fn subscribe_logs(
+        &self,
+        request: Request<proto::LogFilter>,
+    ) -> Result<Response<Self::SubscribeLogsStream>, Status> {
+        let filter = PubSubFilter::try_from(request.into_inner())?;
+        let receiver = self.pub_sub.subscribe_logs();
+        Ok(Response::new(response_stream(
+            receiver,
+            move |item| match item {
+                PubSubResult::Log(log) if filter.matches(&log) => Some(log.into()),
+                _ => None,
+            },
+        )))
+    }
+
+    type SubscribeL1BatchStatusStream = ResponseStream<proto::L1BatchStatusUpdate>;
+
+    async fn subscribe_l1_batch_status(
+        &self,
+        _request: Request<proto::SubscribeL1BatchStatusRequest>,
+    ) -> Result<Response<Self::SubscribeL1BatchStatusStream>, Status> {
+        let receiver = self.pub_sub.subscribe_l1_batch_statuses();
+        Ok(Response::new(response_stream(
+            receiver,
+            |item| match item {
+                PubSubResult::L1BatchStatus(update) => Some(update.into()),
+                _ => None,
+            },
+        )))
+    }
+}
+
+/// Spawns the gRPC streaming API server together with the notifier tasks feeding it.
+pub fn spawn_server(
+    config: &GrpcApiConfig,
+    connection_pool: ConnectionPool,
+    mut stop_receiver: watch::Receiver<bool>,
+) -> Vec<JoinHandle<anyhow::Result<()>>> {
+    let pub_sub = EthSubscribe::new();
+    let mut tasks = pub_sub.spawn_notifiers(
+        connection_pool,
+        config.polling_interval(),
+        stop_receiver.clone(),
+    );
+    let service = StreamServiceServer::new(StreamServiceImpl { pub_sub });
+
+    let bind_addr = config.bind_addr();
+    tasks.push(tokio::spawn(async move {
+        tracing::info!("Starting gRPC streaming API server on {bind_addr}");
+        tonic::transport::Server::builder()
+            .add_service(service)
+            .serve_with_shutdown(bind_addr, async move {
+                if stop_receiver.changed().await.is_err() {
+                    tracing::warn!(
+                        "Stop signal sender for gRPC streaming API server was dropped without sending a signal"
+                    );
+                }
+                tracing::info!("Stop signal received, gRPC streaming API server is shutting down");
+            })
+            .await
+            .context("gRPC streaming API server failed")
+    }));
+    tasks
+}
@@ -0,0 +1,3 @@
+#![allow(warnings)]
+
+tonic::include_proto!("zksync.core.api.stream");
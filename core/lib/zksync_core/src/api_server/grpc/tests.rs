@@ -0,0 +1,133 @@
+//! Tests for the gRPC streaming API.
+
+use zksync_types::L1BatchNumber;
+
+use super::*;
+
+fn log(address: Address, topics: Vec<H256>, block_number: u32) -> Log {
+    Log {
+        address,
+        topics,
+        data: vec![1, 2, 3].into(),
+        block_hash: Some(H256::repeat_byte(0xbb)),
+        block_number: Some(block_number.into()),
+        l1_batch_number: None,
+        transaction_hash: Some(H256::repeat_byte(0xcc)),
+        transaction_index: Some(0_u32.into()),
+        log_index: Some(1_u32.into()),
+        transaction_log_index: Some(1_u32.into()),
+        log_type: None,
+        removed: Some(false),
+    }
+}
+
+#[test]
+fn converting_log_filter() {
+    let address = Address::repeat_byte(1);
+    let topic = H256::repeat_byte(2);
+    let filter = proto::LogFilter {
+        addresses: vec![address.as_bytes().to_vec()],
+        topics: vec![
+            proto::Topics { values: vec![] },
+            proto::Topics {
+                values: vec![topic.as_bytes().to_vec()],
+            },
+        ],
+    };
+    let filter = PubSubFilter::try_from(filter).unwrap();
+    assert_eq!(filter.address, Some(ValueOrArray(vec![address])));
+    assert_eq!(
+        filter.topics,
+        Some(vec![None, Some(ValueOrArray(vec![topic]))])
+    );
+
+    assert!(filter.matches(&log(address, vec![H256::zero(), topic], 1)));
+    assert!(!filter.matches(&log(address, vec![topic], 1)));
+    assert!(!filter.matches(&log(Address::zero(), vec![H256::zero(), topic], 1)));
+
+    let empty_filter = PubSubFilter::try_from(proto::LogFilter::default()).unwrap();
+    assert_eq!(empty_filter.address, None);
+    assert_eq!(empty_filter.topics, None);
+}
+
+#[test]
+fn converting_invalid_log_filter() {
+    let filter = proto::LogFilter {
+        addresses: vec![vec![1; 32]],
+        topics: vec![],
+    };
+    let err = PubSubFilter::try_from(filter).unwrap_err();
+    assert_eq!(err.code(), tonic::Code::InvalidArgument);
+
+    let filter = proto::LogFilter {
+        addresses: vec![],
+        topics: vec![proto::Topics::default(); EVENT_TOPIC_NUMBER_LIMIT + 1],
+    };
+    let err = PubSubFilter::try_from(filter).unwrap_err();
+    assert_eq!(err.code(), tonic::Code::InvalidArgument);
+}
+
+#[test]
+fn converting_l1_batch_status_update() {
+    let update = api::L1BatchStatusUpdate {
+        number: L1BatchNumber(3),
+        stage: api::L1BatchStage::Proven,
+        eth_tx_hash: Some(H256::repeat_byte(1)),
+    };
+    let update = proto::L1BatchStatusUpdate::from(update);
+    assert_eq!(update.number, Some(3));
+    assert_eq!(update.stage(), proto::L1BatchStage::Proven);
+    assert_eq!(update.eth_tx_hash, Some(vec![1; 32]));
+}
+
+#[tokio::test]
+async fn streaming_filtered_items() {
+    let (sender, receiver) = broadcast::channel(16);
+    let address = Address::repeat_byte(1);
+    let filter = PubSubFilter {
+        address: Some(ValueOrArray(vec![address])),
+        topics: None,
+    };
+    let stream = response_stream(receiver, move |item| match item {
+        PubSubResult::Log(log) if filter.matches(&log) => Some(proto::Log::from(log)),
+        _ => None,
+    });
+
+    let items = vec![
+        PubSubResult::Log(log(address, vec![], 1)),
+        PubSubResult::Log(log(Address::zero(), vec![], 1)),
+        PubSubResult::Log(log(address, vec![], 2)),
+    ];
+    sender.send(items).unwrap();
+    drop(sender);
+
+    let logs: Vec<_> = stream.collect().await;
+    let miniblock_numbers: Vec<_> = logs
+        .into_iter()
+        .map(|log| log.unwrap().miniblock_number.unwrap())
+        .collect();
+    assert_eq!(miniblock_numbers, [1, 2]);
+}
+
+#[tokio::test]
+async fn lagging_subscriber_receives_error() {
+    let (sender, receiver) = broadcast::channel(1);
+    let mut stream = response_stream(receiver, |item| match item {
+        PubSubResult::L1BatchStatus(update) => Some(proto::L1BatchStatusUpdate::from(update)),
+        _ => None,
+    });
+
+    for number in 0..3 {
+        let update = api::L1BatchStatusUpdate {
+            number: L1BatchNumber(number),
+            stage: api::L1BatchStage::Committed,
+            eth_tx_hash: None,
+        };
+        sender
+            .send(vec![PubSubResult::L1BatchStatus(update)])
+            .unwrap();
+    }
+
+    let err = stream.next().await.unwrap().unwrap_err();
+    assert_eq!(err.code(), tonic::Code::ResourceExhausted);
+}
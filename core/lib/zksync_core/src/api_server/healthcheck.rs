@@ -2,9 +2,43 @@ use std::{net::SocketAddr, sync::Arc, time::Duration};
 
 use axum::{extract::State, http::StatusCode, routing::get, Json, Router};
 use tokio::sync::watch;
-use zksync_health_check::{AppHealth, AppHealthCheck};
+use zksync_eth_client::EthInterface;
+use zksync_health_check::{
+    async_trait, AppHealth, AppHealthCheck, CheckHealth, Health, HealthStatus,
+};
 
-async fn check_health(
+/// L1 client health check. The client is considered ready if it can get the latest L1 block number.
+#[derive(Debug)]
+pub struct L1ClientHealthCheck(Arc<dyn EthInterface>);
+
+impl From<Arc<dyn EthInterface>> for L1ClientHealthCheck {
+    fn from(client: Arc<dyn EthInterface>) -> Self {
+        Self(client)
+    }
+}
+
+#[async_trait]
+impl CheckHealth for L1ClientHealthCheck {
+    fn name(&self) -> &'static str {
+        "l1_client"
+    }
+
+    async fn check_health(&self) -> Health {
+        match self.0.block_number("health_check").await {
+            Ok(block_number) => Health::from(HealthStatus::Ready)
+                .with_details(serde_json::json!({ "block_number": block_number })),
+            Err(err) => {
+                tracing::warn!("Health-check call to L1 client failed: {err}");
+                Health::from(HealthStatus::NotReady)
+                    .with_details(serde_json::json!({ "error": err.to_string() }))
+            }
+        }
+    }
+}
+
+/// Readiness check: returns 200 only if all components are healthy. Also served on `/health`
+/// for backward compatibility.
+async fn check_readiness(
     app_health_check: State<Arc<AppHealthCheck>>,
 ) -> (StatusCode, Json<AppHealth>) {
     let response = app_health_check.check_health().await;
@@ -16,6 +50,20 @@ async fn check_health(
     (response_code, Json(response))
 }
 
+/// Liveness check: returns 200 unless some components are shut down or panicked. Unlike readiness,
+/// the app is live while it's initializing or syncing, so it shouldn't be restarted in this case.
+async fn check_liveness(
+    app_health_check: State<Arc<AppHealthCheck>>,
+) -> (StatusCode, Json<AppHealth>) {
+    let response = app_health_check.check_health().await;
+    let response_code = if response.is_alive() {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (response_code, Json(response))
+}
+
 async fn run_server(
     bind_address: &SocketAddr,
     app_health_check: Arc<AppHealthCheck>,
@@ -26,7 +74,9 @@ async fn run_server(
     );
 
     let app = Router::new()
-        .route("/health", get(check_health))
+        .route("/health", get(check_readiness))
+        .route("/health/ready", get(check_readiness))
+        .route("/health/live", get(check_liveness))
         .with_state(app_health_check);
 
     axum::Server::bind(bind_address)
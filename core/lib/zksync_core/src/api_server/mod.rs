@@ -1,7 +1,9 @@
 // Everywhere in this module the word "block" actually means "miniblock".
 
+pub mod admin;
 pub mod contract_verification;
 pub mod execution_sandbox;
+pub mod grpc;
 pub mod healthcheck;
 pub mod tree;
 pub mod tx_sender;
@@ -5,21 +5,25 @@ use std::{cmp, sync::Arc, time::Instant};
 use anyhow::Context as _;
 use multivm::{
     interface::VmExecutionResultAndLogs,
+    tracers::ExternalTracerAdapter,
     utils::{adjust_pubdata_price_for_tx, derive_base_fee_and_gas_per_pubdata, derive_overhead},
     vm_latest::constants::{BLOCK_GAS_LIMIT, MAX_PUBDATA_PER_BLOCK},
 };
+use once_cell::sync::OnceCell;
 use zksync_config::configs::{api::Web3JsonRpcConfig, chain::StateKeeperConfig};
 use zksync_contracts::BaseSystemContracts;
 use zksync_dal::{transactions_dal::L2TxSubmissionResult, ConnectionPool, StorageProcessor};
 use zksync_state::PostgresStorageCaches;
 use zksync_system_constants::DEFAULT_L2_TX_GAS_PER_PUBDATA_BYTE;
 use zksync_types::{
+    api::StateOverride,
     fee::{Fee, TransactionExecutionMetrics},
     fee_model::BatchFeeInput,
     get_code_key, get_intrinsic_constants,
     l1::is_l1_tx_type,
     l2::{error::TxCheckError::TxDuplication, L2Tx},
     utils::storage_key_for_eth_balance,
+    vm_trace::Call,
     AccountTreeId, Address, ExecuteTransactionCommon, L2ChainId, MiniblockNumber, Nonce,
     PackedEthSignature, ProtocolVersionId, Transaction, VmVersion, H160, H256, MAX_L2_TX_GAS_LIMIT,
     MAX_NEW_FACTORY_DEPS, U256,
@@ -31,9 +35,10 @@ pub(super) use self::{proxy::TxProxy, result::SubmitTxError};
 use crate::{
     api_server::{
         execution_sandbox::{
-            get_pubdata_for_factory_deps, BlockArgs, BlockStartInfo, SubmitTxStage,
-            TransactionExecutor, TxExecutionArgs, TxSharedArgs, VmConcurrencyLimiter, VmPermit,
-            SANDBOX_METRICS,
+            collect_diagnostics, get_pubdata_for_factory_deps, needs_diagnostics, ApiTracer,
+            BlockArgs, BlockStartInfo, SandboxExecutionKind, SandboxTracerFactory, SharedReadCache,
+            SubmitTxStage, TransactionExecutor, TxExecutionArgs, TxSharedArgs,
+            VmConcurrencyLimiter, VmPermit, SANDBOX_METRICS,
         },
         tx_sender::result::ApiCallResult,
     },
@@ -48,6 +53,10 @@ mod result;
 #[cfg(test)]
 pub(crate) mod tests;
 
+/// Ratio of the first gas limit tried during gas estimation to the gas used by the transaction
+/// executed with the maximum gas limit.
+const OPTIMISTIC_GAS_LIMIT_SCALE_FACTOR: f64 = 1.2;
+
 #[derive(Debug, Clone)]
 pub struct MultiVMBaseSystemContracts {
     /// Contracts to be used for pre-virtual-blocks protocol versions.
@@ -147,6 +156,8 @@ pub struct TxSenderBuilder {
     proxy: Option<TxProxy>,
     /// Batch sealer used to check whether transaction can be executed by the sequencer.
     sealer: Option<Arc<dyn ConditionalSealer>>,
+    /// Factory of custom tracers attached to sandboxed executions.
+    tracer_factory: Option<Arc<dyn SandboxTracerFactory>>,
 }
 
 impl TxSenderBuilder {
@@ -157,6 +168,7 @@ impl TxSenderBuilder {
             master_connection_pool: None,
             proxy: None,
             sealer: None,
+            tracer_factory: None,
         }
     }
 
@@ -175,6 +187,13 @@ impl TxSenderBuilder {
         self
     }
 
+    /// Attaches tracers created by the provided factory to sandboxed transaction executions
+    /// (`eth_call`, gas estimation and dry runs of submitted transactions).
+    pub fn with_tracer_factory(mut self, factory: Arc<dyn SandboxTracerFactory>) -> Self {
+        self.tracer_factory = Some(factory);
+        self
+    }
+
     pub async fn build(
         self,
         batch_fee_input_provider: Arc<dyn BatchFeeModelInputProvider>,
@@ -200,6 +219,7 @@ impl TxSenderBuilder {
             vm_concurrency_limiter,
             storage_caches,
             sealer,
+            tracer_factory: self.tracer_factory,
             executor: TransactionExecutor::Real,
         }))
     }
@@ -214,6 +234,7 @@ pub struct TxSenderConfig {
     pub fee_account_addr: Address,
     pub gas_price_scale_factor: f64,
     pub max_nonce_ahead: u32,
+    pub tx_replacement_fee_bump_percent: u32,
     pub max_allowed_l2_tx_gas_limit: u32,
     pub vm_execution_cache_misses_limit: Option<usize>,
     pub validation_computational_gas_limit: u32,
@@ -231,6 +252,7 @@ impl TxSenderConfig {
             fee_account_addr: state_keeper_config.fee_account_addr,
             gas_price_scale_factor: web3_json_config.gas_price_scale_factor,
             max_nonce_ahead: web3_json_config.max_nonce_ahead,
+            tx_replacement_fee_bump_percent: web3_json_config.tx_replacement_fee_bump_percent(),
             max_allowed_l2_tx_gas_limit: state_keeper_config.max_allowed_l2_tx_gas_limit,
             vm_execution_cache_misses_limit: web3_json_config.vm_execution_cache_misses_limit,
             validation_computational_gas_limit: state_keeper_config
@@ -257,6 +279,8 @@ pub struct TxSenderInner {
     storage_caches: PostgresStorageCaches,
     /// Batch sealer used to check whether transaction can be executed by the sequencer.
     sealer: Arc<dyn ConditionalSealer>,
+    /// Factory of custom tracers attached to sandboxed executions.
+    tracer_factory: Option<Arc<dyn SandboxTracerFactory>>,
     pub(super) executor: TransactionExecutor,
 }
 
@@ -278,6 +302,20 @@ impl TxSender {
         self.0.storage_caches.clone()
     }
 
+    fn external_tracers<T>(&self, kind: SandboxExecutionKind, tx: &T) -> Vec<ApiTracer>
+    where
+        T: Clone + Into<Transaction>,
+    {
+        let Some(factory) = &self.0.tracer_factory else {
+            return vec![];
+        };
+        let tracer = factory.create_tracer(kind, &tx.clone().into());
+        tracer
+            .map(|tracer| ApiTracer::External(ExternalTracerAdapter::new(tracer)))
+            .into_iter()
+            .collect()
+    }
+
     async fn acquire_replica_connection(&self) -> anyhow::Result<StorageProcessor<'_>> {
         self.0
             .replica_connection_pool
@@ -286,8 +324,14 @@ impl TxSender {
             .context("failed acquiring connection to replica DB")
     }
 
-    #[tracing::instrument(skip(self, tx))]
+    #[tracing::instrument(skip(self, tx), fields(tx_hash = ?tx.hash()))]
     pub async fn submit_tx(&self, tx: L2Tx) -> Result<L2TxSubmissionResult, SubmitTxError> {
+        // Reject writes as soon as the server starts shutting down, so that no transactions are accepted
+        // while the state keeper is persisting its state.
+        if self.0.vm_concurrency_limiter.is_closed() {
+            return Err(SubmitTxError::ServerShuttingDown);
+        }
+
         let stage_latency = SANDBOX_METRICS.submit_tx[&SubmitTxStage::Validate].start();
         self.validate_tx(&tx).await?;
         stage_latency.observe();
@@ -300,6 +344,7 @@ impl TxSender {
         let block_args = BlockArgs::pending(&mut connection).await?;
         drop(connection);
 
+        let custom_tracers = self.external_tracers(SandboxExecutionKind::TxSubmission, &tx);
         let execution_output = self
             .0
             .executor
@@ -311,7 +356,7 @@ impl TxSender {
                 self.0.replica_connection_pool.clone(),
                 tx.clone().into(),
                 block_args,
-                vec![],
+                custom_tracers,
             )
             .await?;
 
@@ -353,11 +398,14 @@ impl TxSender {
             // But before we do that, save the tx to cache in case someone will request it
             // Before it reaches the main node.
             proxy.save_tx(tx.clone()).await;
-            proxy.submit_tx(&tx).await?;
-            // Now, after we are sure that the tx is on the main node, remove it from cache
-            // since we don't want to store txs that might have been replaced or otherwise removed
-            // from the mempool.
-            proxy.forget_tx(tx.hash()).await;
+            if let Err(err) = proxy.submit_tx(&tx).await {
+                // The main node hasn't accepted the transaction, so it shouldn't affect pending nonces.
+                proxy.discard_tx(tx.hash()).await;
+                return Err(err.into());
+            }
+            // The transaction is kept in cache until it's synced back from the main node, so that it's
+            // available via the node API in the meantime. Replaced or otherwise removed transactions
+            // are discarded by the nonce sweeper.
             SANDBOX_METRICS.submit_tx[&SubmitTxStage::TxProxy].observe(stage_started_at.elapsed());
             APP_METRICS.processed_txs[&TxStage::Proxied].inc();
             return Ok(L2TxSubmissionResult::Proxied);
@@ -371,16 +419,19 @@ impl TxSender {
         let nonce = tx.common_data.nonce.0;
         let hash = tx.hash();
         let initiator_account = tx.initiator_account();
-        let submission_res_handle = self
+        let mut storage = self
             .0
             .master_connection_pool
             .as_ref()
             .unwrap() // Checked above
             .access_storage_tagged("api")
-            .await?
+            .await?;
+        self.check_replacement_fee(&mut storage, &tx).await?;
+        let submission_res_handle = storage
             .transactions_dal()
             .insert_transaction_l2(tx, execution_output.metrics)
             .await;
+        drop(storage);
 
         APP_METRICS.processed_txs[&TxStage::Mempool(submission_res_handle)].inc();
 
@@ -407,6 +458,40 @@ impl TxSender {
         }
     }
 
+    /// Checks that `tx` bumps fees sufficiently to replace a pending transaction with the same initiator and nonce,
+    /// if there is such a transaction.
+    async fn check_replacement_fee(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        tx: &L2Tx,
+    ) -> Result<(), SubmitTxError> {
+        let pending_tx = storage
+            .transactions_dal()
+            .get_pending_l2_tx_fee(tx.initiator_account(), tx.common_data.nonce)
+            .await
+            .context("failed getting pending transaction with the same nonce")?;
+        let Some((pending_tx_hash, max_fee_per_gas, max_priority_fee_per_gas)) = pending_tx else {
+            return Ok(());
+        };
+        if pending_tx_hash == tx.hash() {
+            // Duplicate transactions are handled when inserting the transaction.
+            return Ok(());
+        }
+
+        let bump_percent = self.0.sender_config.tx_replacement_fee_bump_percent;
+        let fee = &tx.common_data.fee;
+        let is_underpriced = fee.max_fee_per_gas < bumped_fee(max_fee_per_gas, bump_percent)
+            || fee.max_priority_fee_per_gas < bumped_fee(max_priority_fee_per_gas, bump_percent);
+        if is_underpriced {
+            tracing::debug!(
+                "Rejecting transaction {:?} replacing {pending_tx_hash:?}: fees are not bumped by at least {bump_percent}%",
+                tx.hash()
+            );
+            return Err(SubmitTxError::ReplacementUnderpriced);
+        }
+        Ok(())
+    }
+
     async fn shared_args(&self) -> TxSharedArgs {
         TxSharedArgs {
             operator_account: AccountTreeId::new(self.0.sender_config.fee_account_addr),
@@ -578,7 +663,8 @@ impl TxSender {
     }
 
     /// Given the gas_limit to be used for the body of the transaction,
-    /// returns the result for executing the transaction with such gas_limit
+    /// returns the result for executing the transaction with such gas_limit.
+    /// If `call_traces` are provided, the execution is traced using the call tracer.
     #[allow(clippy::too_many_arguments)]
     async fn estimate_gas_step(
         &self,
@@ -590,6 +676,9 @@ impl TxSender {
         block_args: BlockArgs,
         base_fee: u64,
         vm_version: VmVersion,
+        state_override: Option<&StateOverride>,
+        read_cache: &SharedReadCache,
+        call_traces: Option<Arc<OnceCell<Vec<Call>>>>,
     ) -> anyhow::Result<(VmExecutionResultAndLogs, TransactionExecutionMetrics)> {
         let gas_limit_with_overhead = tx_gas_limit
             + derive_overhead(
@@ -622,8 +711,15 @@ impl TxSender {
 
         let shared_args = self.shared_args_for_gas_estimate(fee_model_params);
         let vm_execution_cache_misses_limit = self.0.sender_config.vm_execution_cache_misses_limit;
-        let execution_args =
-            TxExecutionArgs::for_gas_estimate(vm_execution_cache_misses_limit, &tx, base_fee);
+        let mut execution_args = TxExecutionArgs::for_gas_estimate(
+            vm_execution_cache_misses_limit,
+            &tx,
+            base_fee,
+            state_override.cloned(),
+        );
+        execution_args.read_cache = Some(read_cache.clone());
+        let mut custom_tracers = self.external_tracers(SandboxExecutionKind::GasEstimation, &tx);
+        custom_tracers.extend(call_traces.map(ApiTracer::CallTracer));
         let execution_output = self
             .0
             .executor
@@ -635,7 +731,7 @@ impl TxSender {
                 self.0.replica_connection_pool.clone(),
                 tx.clone(),
                 block_args,
-                vec![],
+                custom_tracers,
             )
             .await?;
         Ok((execution_output.vm, execution_output.metrics))
@@ -660,6 +756,7 @@ impl TxSender {
         mut tx: Transaction,
         estimated_fee_scale_factor: f64,
         acceptable_overestimation: u32,
+        state_override: Option<StateOverride>,
     ) -> Result<Fee, SubmitTxError> {
         let estimation_started_at = Instant::now();
 
@@ -718,7 +815,12 @@ impl TxSender {
                 )
             })?;
 
+        // Overrides for the initiator account may change both its code and balance, so the check is skipped for them.
+        let is_initiator_overridden = state_override.as_ref().map_or(false, |overrides| {
+            overrides.contains_key(&tx.initiator_account())
+        });
         if !tx.is_l1()
+            && !is_initiator_overridden
             && account_code_hash == H256::zero()
             && tx.execute.value > self.get_balance(&tx.initiator_account()).await?
         {
@@ -767,10 +869,6 @@ impl TxSender {
             pubdata_for_factory_deps * (gas_per_pubdata_byte as u32)
         };
 
-        // We are using binary search to find the minimal values of gas_limit under which
-        // the transaction succeeds
-        let mut lower_bound = 0;
-        let mut upper_bound = MAX_L2_TX_GAS_LIMIT as u32;
         let tx_id = format!(
             "{:?}-{}",
             tx.initiator_account(),
@@ -782,9 +880,84 @@ impl TxSender {
             estimation_started_at.elapsed(),
         );
 
-        let mut number_of_iterations = 0usize;
+        // Storage reads are the same for all executions, so they are cached across executions.
+        let read_cache = SharedReadCache::default();
+        // First, we execute the transaction with the maximum gas limit. If it fails, it would fail with any gas limit,
+        // so there's no need to search further. Otherwise, the used gas is a good hint for the search bounds.
+        let max_gas_limit = MAX_L2_TX_GAS_LIMIT as u32;
+        let (initial_result, _) = self
+            .estimate_gas_step(
+                vm_permit.clone(),
+                tx.clone(),
+                gas_for_bytecodes_pubdata + max_gas_limit,
+                gas_per_pubdata_byte as u32,
+                fee_input,
+                block_args,
+                base_fee,
+                protocol_version.into(),
+                state_override.as_ref(),
+                &read_cache,
+                None,
+            )
+            .await
+            .context("initial estimate_gas step failed")?;
+        let initial_gas_used = initial_result
+            .statistics
+            .gas_used
+            .saturating_sub(gas_for_bytecodes_pubdata);
+        let needs_diagnostics = needs_diagnostics(&initial_result.result);
+        if let Err(err) = initial_result.into_api_call_result() {
+            if !needs_diagnostics {
+                return Err(err);
+            }
+            // Re-execute the transaction with the call tracer to find out where it has failed.
+            let call_traces = Arc::new(OnceCell::new());
+            let (result, _) = self
+                .estimate_gas_step(
+                    vm_permit,
+                    tx,
+                    gas_for_bytecodes_pubdata + max_gas_limit,
+                    gas_per_pubdata_byte as u32,
+                    fee_input,
+                    block_args,
+                    base_fee,
+                    protocol_version.into(),
+                    state_override.as_ref(),
+                    &read_cache,
+                    Some(call_traces.clone()),
+                )
+                .await
+                .context("diagnostic estimate_gas step failed")?;
+            let call_traces = call_traces.get().map_or(&[][..], Vec::as_slice);
+            return Err(err.with_diagnostics(collect_diagnostics(&result, call_traces)));
+        }
+        let mut number_of_iterations = 1usize;
+
+        // The used gas includes the overhead, which depends on the gas limit, so it's subtracted to get
+        // a conservative lower bound.
+        let initial_overhead = derive_overhead(
+            gas_for_bytecodes_pubdata + max_gas_limit,
+            gas_per_pubdata_byte as u32,
+            tx.encoding_len(),
+            tx.tx_format() as u8,
+            protocol_version.into(),
+        );
+        // We are using binary search to find the minimal values of gas_limit under which
+        // the transaction succeeds
+        let mut lower_bound = initial_gas_used.saturating_sub(initial_overhead);
+        let mut upper_bound = max_gas_limit;
+        // Most transactions succeed with a gas limit slightly exceeding the gas used in the initial execution,
+        // so such a limit is tried before bisecting the range.
+        let optimistic_gas_limit =
+            (f64::from(initial_gas_used) * OPTIMISTIC_GAS_LIMIT_SCALE_FACTOR) as u32;
+        let mut optimistic_gas_limit = (lower_bound..upper_bound)
+            .contains(&optimistic_gas_limit)
+            .then_some(optimistic_gas_limit);
+
         while lower_bound + acceptable_overestimation < upper_bound {
-            let mid = (lower_bound + upper_bound) / 2;
+            let mid = optimistic_gas_limit
+                .take()
+                .unwrap_or((lower_bound + upper_bound) / 2);
             // There is no way to distinct between errors due to out of gas
             // or normal execution errors, so we just hope that increasing the
             // gas limit will make the transaction successful
@@ -800,6 +973,9 @@ impl TxSender {
                     block_args,
                     base_fee,
                     protocol_version.into(),
+                    state_override.as_ref(),
+                    &read_cache,
+                    None,
                 )
                 .await
                 .context("estimate_gas step failed")?;
@@ -840,6 +1016,9 @@ impl TxSender {
                 block_args,
                 base_fee,
                 protocol_version.into(),
+                state_override.as_ref(),
+                &read_cache,
+                None,
             )
             .await
             .context("final estimate_gas step failed")?;
@@ -894,24 +1073,55 @@ impl TxSender {
         &self,
         block_args: BlockArgs,
         tx: L2Tx,
+        state_override: Option<StateOverride>,
     ) -> Result<Vec<u8>, SubmitTxError> {
         let vm_permit = self.0.vm_concurrency_limiter.acquire().await;
         let vm_permit = vm_permit.ok_or(SubmitTxError::ServerShuttingDown)?;
 
         let vm_execution_cache_misses_limit = self.0.sender_config.vm_execution_cache_misses_limit;
-        self.0
+        let shared_args = self.shared_args().await;
+        let custom_tracers = self.external_tracers(SandboxExecutionKind::EthCall, &tx);
+        let result = self
+            .0
+            .executor
+            .execute_tx_eth_call(
+                vm_permit.clone(),
+                shared_args.clone(),
+                self.0.replica_connection_pool.clone(),
+                tx.clone(),
+                block_args,
+                vm_execution_cache_misses_limit,
+                custom_tracers,
+                state_override.clone(),
+            )
+            .await?;
+        if !needs_diagnostics(&result.result) {
+            return result.into_api_call_result();
+        }
+
+        // Re-execute the call with the call tracer to find out where it has failed.
+        let call_traces = Arc::new(OnceCell::new());
+        let mut custom_tracers = self.external_tracers(SandboxExecutionKind::EthCall, &tx);
+        custom_tracers.push(ApiTracer::CallTracer(call_traces.clone()));
+        let diagnostic_result = self
+            .0
             .executor
             .execute_tx_eth_call(
                 vm_permit,
-                self.shared_args().await,
+                shared_args,
                 self.0.replica_connection_pool.clone(),
                 tx,
                 block_args,
                 vm_execution_cache_misses_limit,
-                vec![],
+                custom_tracers,
+                state_override,
             )
-            .await?
+            .await?;
+        let call_traces = call_traces.get().map_or(&[][..], Vec::as_slice);
+        let diagnostics = collect_diagnostics(&diagnostic_result, call_traces);
+        result
             .into_api_call_result()
+            .map_err(|err| err.with_diagnostics(diagnostics))
     }
 
     pub async fn gas_price(&self) -> anyhow::Result<u64> {
@@ -971,6 +1181,11 @@ impl TxSender {
     }
 }
 
+/// Returns the minimum fee required to replace a transaction paying `fee`.
+fn bumped_fee(fee: U256, bump_percent: u32) -> U256 {
+    fee * (100 + bump_percent) / 100
+}
+
 /// During switch to the 1.4.1 protocol version, there will be a moment of discrepancy, when while
 /// the L2 has already upgraded to 1.4.1 (and thus suggests smaller overhead), the L1 is still on the previous version.
 ///
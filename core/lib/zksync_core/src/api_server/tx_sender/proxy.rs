@@ -2,7 +2,7 @@ use std::{
     collections::{BTreeSet, HashMap},
     future::Future,
     sync::Arc,
-    time::Duration,
+    time::{Duration, Instant},
 };
 
 use tokio::sync::{watch, RwLock};
@@ -18,17 +18,83 @@ use zksync_web3_decl::{
     namespaces::{EthNamespaceClient, ZksNamespaceClient},
 };
 
+/// Interval between checking the status of a forwarded transaction on the main node.
+const STATUS_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
 #[derive(Debug, Clone, Default)]
 pub(crate) struct TxCache {
     inner: Arc<RwLock<TxCacheInner>>,
 }
 
+/// Transaction forwarded to the main node, but not synced back yet.
+#[derive(Debug, Clone)]
+struct ForwardedTx {
+    tx: L2Tx,
+    last_checked_at: Instant,
+}
+
 #[derive(Debug, Default)]
 struct TxCacheInner {
-    tx_cache: HashMap<H256, L2Tx>,
+    tx_cache: HashMap<H256, ForwardedTx>,
     nonces_by_account: HashMap<Address, BTreeSet<Nonce>>,
 }
 
+impl TxCacheInner {
+    /// Removes transactions and nonces that are superseded by nonces stored locally, i.e., transactions
+    /// that are synced back from the main node or replaced.
+    fn prune(&mut self, nonces_for_accounts: &HashMap<Address, Nonce>) {
+        let stored_nonce = |address: &Address| {
+            nonces_for_accounts
+                .get(address)
+                .copied()
+                .unwrap_or(Nonce(0))
+        };
+        self.tx_cache.retain(|_, forwarded| {
+            forwarded.tx.nonce() >= stored_nonce(&forwarded.tx.initiator_account())
+        });
+        self.nonces_by_account.retain(|address, account_nonces| {
+            // Retain only nonces starting from the stored one.
+            *account_nonces = account_nonces.split_off(&stored_nonce(address));
+            // If we've removed all nonces, drop the account entry so we don't request stored nonces for it later.
+            !account_nonces.is_empty()
+        });
+    }
+
+    /// Removes a transaction together with its nonce, e.g. if it was rejected by the main node.
+    fn discard(&mut self, tx_hash: H256) {
+        let Some(forwarded) = self.tx_cache.remove(&tx_hash) else {
+            return;
+        };
+        let (account, nonce) = (forwarded.tx.initiator_account(), forwarded.tx.nonce());
+        let is_nonce_used = self
+            .tx_cache
+            .values()
+            .any(|other| other.tx.initiator_account() == account && other.tx.nonce() == nonce);
+        if !is_nonce_used {
+            if let Some(account_nonces) = self.nonces_by_account.get_mut(&account) {
+                account_nonces.remove(&nonce);
+                if account_nonces.is_empty() {
+                    self.nonces_by_account.remove(&account);
+                }
+            }
+        }
+    }
+
+    /// Returns hashes of transactions which status should be checked on the main node and marks them as checked.
+    fn txs_to_check(&mut self, now: Instant) -> Vec<H256> {
+        self.tx_cache
+            .iter_mut()
+            .filter(|(_, forwarded)| {
+                now.duration_since(forwarded.last_checked_at) >= STATUS_CHECK_INTERVAL
+            })
+            .map(|(&hash, forwarded)| {
+                forwarded.last_checked_at = now;
+                hash
+            })
+            .collect()
+    }
+}
+
 impl TxCache {
     async fn push(&self, tx: L2Tx) {
         let mut inner = self.inner.write().await;
@@ -37,11 +103,19 @@ impl TxCache {
             .entry(tx.initiator_account())
             .or_default()
             .insert(tx.nonce());
-        inner.tx_cache.insert(tx.hash(), tx);
+        let forwarded = ForwardedTx {
+            tx,
+            last_checked_at: Instant::now(),
+        };
+        inner.tx_cache.insert(forwarded.tx.hash(), forwarded);
     }
 
     async fn get_tx(&self, tx_hash: H256) -> Option<L2Tx> {
-        self.inner.read().await.tx_cache.get(&tx_hash).cloned()
+        let inner = self.inner.read().await;
+        inner
+            .tx_cache
+            .get(&tx_hash)
+            .map(|forwarded| forwarded.tx.clone())
     }
 
     async fn get_nonces_for_account(&self, account_address: Address) -> BTreeSet<Nonce> {
@@ -58,9 +132,41 @@ impl TxCache {
         // We intentionally don't change `nonces_by_account`; they should only be changed in response to new miniblocks
     }
 
+    async fn discard_tx(&self, tx_hash: H256) {
+        self.inner.write().await.discard(tx_hash);
+    }
+
+    /// Checks the status of forwarded transactions on the main node and discards transactions unknown to it
+    /// (e.g., rejected by the main node mempool), so that they don't affect pending nonces on this node.
+    async fn check_statuses(&self, client: &HttpClient) {
+        let tx_hashes = self.inner.write().await.txs_to_check(Instant::now());
+        for tx_hash in tx_hashes {
+            let details = client
+                .get_transaction_details(tx_hash)
+                .rpc_context("get_transaction_details")
+                .with_arg("hash", &tx_hash)
+                .await;
+            match details {
+                Ok(Some(_)) => { /* The transaction is known to the main node; keep tracking it */ }
+                Ok(None) => {
+                    tracing::info!(
+                        "Forwarded transaction {tx_hash:?} is unknown to the main node; discarding it"
+                    );
+                    self.discard_tx(tx_hash).await;
+                }
+                Err(err) => {
+                    tracing::warn!(
+                        "Failed checking status of forwarded transaction {tx_hash:?}: {err}"
+                    );
+                }
+            }
+        }
+    }
+
     async fn run_updates(
         self,
         pool: ConnectionPool,
+        client: HttpClient,
         stop_receiver: watch::Receiver<bool>,
     ) -> anyhow::Result<()> {
         const UPDATE_INTERVAL: Duration = Duration::from_secs(1);
@@ -73,7 +179,17 @@ impl TxCache {
             let addresses: Vec<_> = {
                 // Split into 2 statements for readability.
                 let inner = self.inner.read().await;
-                inner.nonces_by_account.keys().copied().collect()
+                let tx_accounts = inner
+                    .tx_cache
+                    .values()
+                    .map(|forwarded| forwarded.tx.initiator_account());
+                let accounts: BTreeSet<_> = inner
+                    .nonces_by_account
+                    .keys()
+                    .copied()
+                    .chain(tx_accounts)
+                    .collect();
+                accounts.into_iter().collect()
             };
             let mut storage = pool.access_storage_tagged("api").await?;
             let nonces_for_accounts = storage
@@ -82,26 +198,18 @@ impl TxCache {
                 .await?;
             drop(storage); // Don't hold both `storage` and lock on `inner` at the same time.
 
-            let mut inner = self.inner.write().await;
-            inner.nonces_by_account.retain(|address, account_nonces| {
-                let stored_nonce = nonces_for_accounts
-                    .get(address)
-                    .copied()
-                    .unwrap_or(Nonce(0));
-                // Retain only nonces starting from the stored one.
-                *account_nonces = account_nonces.split_off(&stored_nonce);
-                // If we've removed all nonces, drop the account entry so we don't request stored nonces for it later.
-                !account_nonces.is_empty()
-            });
-            drop(inner);
+            self.inner.write().await.prune(&nonces_for_accounts);
+            self.check_statuses(&client).await;
 
             tokio::time::sleep(UPDATE_INTERVAL).await;
         }
     }
 }
 
-/// Used by external node to proxy transaction to the main node
-/// and store them while they're not synced back yet
+/// Used by external node to proxy transaction to the main node and store them while they're not synced back yet.
+/// Forwarded transactions are served by the node API as pending, and are taken into account when computing
+/// pending nonces. They are tracked until they are synced back from the main node, replaced, or discarded
+/// by the main node.
 #[derive(Debug)]
 pub struct TxProxy {
     tx_cache: TxCache,
@@ -124,6 +232,11 @@ impl TxProxy {
         self.tx_cache.remove_tx(tx_hash).await;
     }
 
+    /// Discards a transaction together with its nonce, e.g. if it wasn't accepted by the main node.
+    pub async fn discard_tx(&self, tx_hash: H256) {
+        self.tx_cache.discard_tx(tx_hash).await;
+    }
+
     pub async fn save_tx(&self, tx: L2Tx) {
         self.tx_cache.push(tx).await;
     }
@@ -139,7 +252,7 @@ impl TxProxy {
     ) -> Nonce {
         let mut pending_nonce = Nonce(current_nonce);
         let nonces = self.get_nonces_by_account(account_address).await;
-        for nonce in nonces.range(pending_nonce + 1..) {
+        for nonce in nonces.range(pending_nonce..) {
             // If nonce is not sequential, then we should not increment nonce.
             if nonce == &pending_nonce {
                 pending_nonce += 1;
@@ -208,6 +321,109 @@ impl TxProxy {
         stop_receiver: watch::Receiver<bool>,
     ) -> impl Future<Output = anyhow::Result<()>> {
         let tx_cache = self.tx_cache.clone();
-        tx_cache.run_updates(pool, stop_receiver)
+        tx_cache.run_updates(pool, self.client.clone(), stop_receiver)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::testonly::create_l2_transaction;
+
+    fn create_tx(account: Address, nonce: u32) -> L2Tx {
+        let mut tx = create_l2_transaction(10, 100);
+        tx.common_data.initiator_address = account;
+        tx.common_data.nonce = Nonce(nonce);
+        tx
+    }
+
+    #[tokio::test]
+    async fn forwarded_txs_affect_pending_nonces() {
+        let proxy = TxProxy::new(
+            HttpClient::builder()
+                .build("http://localhost:3050")
+                .unwrap(),
+        );
+        let account = Address::repeat_byte(1);
+        let txs: Vec<_> = (0..3).map(|nonce| create_tx(account, nonce)).collect();
+        for tx in &txs {
+            proxy.save_tx(tx.clone()).await;
+        }
+
+        assert_eq!(proxy.find_tx(txs[1].hash()).await.unwrap(), txs[1]);
+        assert_eq!(
+            proxy.next_nonce_by_initiator_account(account, 0).await,
+            Nonce(3)
+        );
+
+        // Only a transaction with the highest nonce is discarded; otherwise, there would be a nonce gap.
+        proxy.discard_tx(txs[2].hash()).await;
+        assert!(proxy.find_tx(txs[2].hash()).await.is_none());
+        assert_eq!(
+            proxy.next_nonce_by_initiator_account(account, 0).await,
+            Nonce(2)
+        );
+    }
+
+    #[test]
+    fn pruning_synced_txs() {
+        let account = Address::repeat_byte(1);
+        let other_account = Address::repeat_byte(2);
+        let mut inner = TxCacheInner::default();
+        for tx in [
+            create_tx(account, 0),
+            create_tx(account, 1),
+            create_tx(other_account, 5),
+        ] {
+            inner
+                .nonces_by_account
+                .entry(tx.initiator_account())
+                .or_default()
+                .insert(tx.nonce());
+            let forwarded = ForwardedTx {
+                tx,
+                last_checked_at: Instant::now(),
+            };
+            inner.tx_cache.insert(forwarded.tx.hash(), forwarded);
+        }
+
+        // The first transaction is synced back.
+        inner.prune(&HashMap::from([
+            (account, Nonce(1)),
+            (other_account, Nonce(5)),
+        ]));
+        assert_eq!(inner.tx_cache.len(), 2);
+        assert_eq!(
+            inner.nonces_by_account[&account],
+            BTreeSet::from([Nonce(1)])
+        );
+
+        inner.prune(&HashMap::from([
+            (account, Nonce(2)),
+            (other_account, Nonce(6)),
+        ]));
+        assert!(inner.tx_cache.is_empty());
+        assert!(inner.nonces_by_account.is_empty());
+    }
+
+    #[test]
+    fn selecting_txs_for_status_checks() {
+        let mut inner = TxCacheInner::default();
+        let started_at = Instant::now();
+        let tx = create_tx(Address::repeat_byte(1), 0);
+        let tx_hash = tx.hash();
+        inner.tx_cache.insert(
+            tx_hash,
+            ForwardedTx {
+                tx,
+                last_checked_at: started_at,
+            },
+        );
+
+        assert!(inner.txs_to_check(started_at).is_empty());
+        let now = started_at + STATUS_CHECK_INTERVAL;
+        assert_eq!(inner.txs_to_check(now), [tx_hash]);
+        // The transaction is not checked again until the interval elapses.
+        assert!(inner.txs_to_check(now).is_empty());
     }
 }
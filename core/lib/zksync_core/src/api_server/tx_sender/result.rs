@@ -1,6 +1,6 @@
 use multivm::interface::{ExecutionResult, VmExecutionResultAndLogs};
 use thiserror::Error;
-use zksync_types::{l2::error::TxCheckError, U256};
+use zksync_types::{api::ExecutionDiagnostics, l2::error::TxCheckError, U256};
 use zksync_web3_decl::error::EnrichedClientError;
 
 use crate::api_server::execution_sandbox::{SandboxExecutionError, ValidationError};
@@ -42,6 +42,9 @@ pub enum SubmitTxError {
     MaxFeePerGasTooLow,
     #[error("max priority fee per gas higher than max fee per gas")]
     MaxPriorityFeeGreaterThanMaxFee,
+    /// Returned if a transaction replacing a pending transaction with the same nonce doesn't bump fees sufficiently.
+    #[error("replacement transaction underpriced")]
+    ReplacementUnderpriced,
     #[error(
         "virtual machine entered unexpected state. please contact developers and provide transaction details \
         that caused this error. Error description: {0}"
@@ -73,6 +76,9 @@ pub enum SubmitTxError {
     /// Catch-all internal error (e.g., database error) that should not be exposed to the caller.
     #[error("internal error")]
     Internal(#[from] anyhow::Error),
+    /// Transaction execution error accompanied by structured diagnostics.
+    #[error("{0}")]
+    WithDiagnostics(Box<SubmitTxError>, Box<ExecutionDiagnostics>),
 }
 
 impl SubmitTxError {
@@ -95,6 +101,7 @@ impl SubmitTxError {
             Self::FromIsNotAnAccount => "from-is-not-an-account",
             Self::MaxFeePerGasTooLow => "max-fee-per-gas-too-low",
             Self::MaxPriorityFeeGreaterThanMaxFee => "max-priority-fee-greater-than-max-fee",
+            Self::ReplacementUnderpriced => "replacement-underpriced",
             Self::UnexpectedVMBehavior(_) => "unexpected-vm-behavior",
             Self::UnrealisticPubdataPriceLimit => "unrealistic-pubdata-price-limit",
             Self::TooManyFactoryDependencies(_, _) => "too-many-factory-dependencies",
@@ -105,16 +112,22 @@ impl SubmitTxError {
             Self::ProxyError(_) => "proxy-error",
             Self::FailedToPublishCompressedBytecodes => "failed-to-publish-compressed-bytecodes",
             Self::Internal(_) => "internal",
+            Self::WithDiagnostics(err, _) => err.prom_error_code(),
         }
     }
 
     pub fn data(&self) -> Vec<u8> {
-        if let Self::ExecutionReverted(_, data) = self {
-            data.clone()
-        } else {
-            Vec::new()
+        match self {
+            Self::ExecutionReverted(_, data) => data.clone(),
+            Self::WithDiagnostics(err, _) => err.data(),
+            _ => Vec::new(),
         }
     }
+
+    /// Attaches execution diagnostics to this error.
+    pub(crate) fn with_diagnostics(self, diagnostics: ExecutionDiagnostics) -> Self {
+        Self::WithDiagnostics(Box::new(self), Box::new(diagnostics))
+    }
 }
 
 impl From<SandboxExecutionError> for SubmitTxError {
@@ -1,12 +1,19 @@
 //! Tests for the transaction sender.
 
-use zksync_types::{get_nonce_key, L1BatchNumber, StorageLog};
+use assert_matches::assert_matches;
+use zksync_types::{
+    fee_model::{BaseTokenConversionRatio, FeeParams},
+    get_nonce_key, L1BatchNumber, StorageLog,
+};
 
 use super::*;
 use crate::{
     api_server::execution_sandbox::{testonly::MockTransactionExecutor, VmConcurrencyBarrier},
     genesis::{ensure_genesis_state, GenesisParams},
-    utils::testonly::{create_miniblock, prepare_recovery_snapshot, MockBatchFeeParamsProvider},
+    utils::testonly::{
+        create_l2_transaction, create_miniblock, prepare_recovery_snapshot,
+        MockBatchFeeParamsProvider,
+    },
 };
 
 pub(crate) async fn create_test_tx_sender(
@@ -146,3 +153,105 @@ async fn getting_nonce_for_account_after_snapshot_recovery() {
     let nonce = tx_sender.get_expected_nonce(missing_address).await.unwrap();
     assert_eq!(nonce, Nonce(0));
 }
+
+#[tokio::test]
+async fn checking_replacement_fee() {
+    let l2_chain_id = L2ChainId::default();
+    let pool = ConnectionPool::test_pool().await;
+    let mut storage = pool.access_storage().await.unwrap();
+    ensure_genesis_state(&mut storage, l2_chain_id, &GenesisParams::mock())
+        .await
+        .unwrap();
+
+    let tx_executor = MockTransactionExecutor::default().into();
+    let (tx_sender, _) = create_test_tx_sender(pool.clone(), l2_chain_id, tx_executor).await;
+    assert_eq!(
+        tx_sender.0.sender_config.tx_replacement_fee_bump_percent,
+        10
+    );
+
+    let mut pending_tx = create_l2_transaction(1_000, 50);
+    pending_tx.common_data.fee.max_priority_fee_per_gas = 100.into();
+    let initiator_address = pending_tx.initiator_account();
+    storage
+        .transactions_dal()
+        .insert_transaction_l2(pending_tx.clone(), TransactionExecutionMetrics::default())
+        .await;
+    // Resubmitting the same transaction is not considered a replacement.
+    tx_sender
+        .check_replacement_fee(&mut storage, &pending_tx)
+        .await
+        .unwrap();
+
+    let replacement_tx = |max_fee_per_gas: u64, max_priority_fee_per_gas: u64| {
+        let mut tx = create_l2_transaction(max_fee_per_gas, 50);
+        tx.common_data.initiator_address = initiator_address;
+        tx.common_data.fee.max_priority_fee_per_gas = max_priority_fee_per_gas.into();
+        tx
+    };
+    for (max_fee_per_gas, max_priority_fee_per_gas) in [(1_000, 200), (1_099, 110), (2_000, 109)] {
+        let tx = replacement_tx(max_fee_per_gas, max_priority_fee_per_gas);
+        let err = tx_sender
+            .check_replacement_fee(&mut storage, &tx)
+            .await
+            .unwrap_err();
+        assert_matches!(err, SubmitTxError::ReplacementUnderpriced);
+    }
+    let tx = replacement_tx(1_100, 110);
+    tx_sender
+        .check_replacement_fee(&mut storage, &tx)
+        .await
+        .unwrap();
+
+    // Transactions with other nonces are not affected.
+    let mut tx = replacement_tx(1, 0);
+    tx.common_data.nonce = Nonce(1);
+    tx_sender
+        .check_replacement_fee(&mut storage, &tx)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn validating_fee_denominated_in_base_token() {
+    let l2_chain_id = L2ChainId::default();
+    let pool = ConnectionPool::test_pool().await;
+    let tx_executor = MockTransactionExecutor::default().into();
+    let (mut tx_sender, _) = create_test_tx_sender(pool, l2_chain_id, tx_executor).await;
+
+    let FeeParams::V1(mut fee_params) = FeeParams::sensible_v1_default() else {
+        unreachable!();
+    };
+    let minimal_l2_gas_price = fee_params.config.minimal_l2_gas_price;
+    // 3 base token units are worth 1 wei.
+    fee_params.conversion_ratio = BaseTokenConversionRatio {
+        numerator: 3.try_into().unwrap(),
+        denominator: 1.try_into().unwrap(),
+    };
+    Arc::get_mut(&mut tx_sender.0)
+        .unwrap()
+        .batch_fee_input_provider = Arc::new(MockBatchFeeParamsProvider(FeeParams::V1(fee_params)));
+
+    // The fee is sufficient if denominated in ETH, but is too low in the base token.
+    let tx = create_l2_transaction(minimal_l2_gas_price * 2, 50);
+    let err = tx_sender.validate_tx(&tx).await.unwrap_err();
+    assert_matches!(err, SubmitTxError::MaxFeePerGasTooLow);
+
+    let tx = create_l2_transaction(minimal_l2_gas_price * 3, 50);
+    let err = tx_sender.validate_tx(&tx).await.unwrap_err();
+    // The transaction passes the fee check and fails on a later one.
+    assert_matches!(err, SubmitTxError::IntrinsicGas);
+}
+
+#[tokio::test]
+async fn rejecting_transactions_on_shutdown() {
+    let l2_chain_id = L2ChainId::default();
+    let pool = ConnectionPool::test_pool().await;
+    let tx_executor = MockTransactionExecutor::default().into();
+    let (tx_sender, vm_barrier) = create_test_tx_sender(pool, l2_chain_id, tx_executor).await;
+
+    vm_barrier.close();
+    let tx = create_l2_transaction(10, 100);
+    let err = tx_sender.submit_tx(tx).await.unwrap_err();
+    assert_matches!(err, SubmitTxError::ServerShuttingDown);
+}
@@ -0,0 +1,459 @@
+//! Structured audit log of RPC calls for the JSON-RPC servers.
+//!
+//! Similar to [rate limiting](super::rate_limit_middleware), clients are identified by an HTTP layer
+//! ([`AuditLogKeyLayer`]) which passes the client key to the RPC middleware ([`AuditLogMiddleware`])
+//! via a task-local variable. Each sampled call is recorded with the method name, a hash of its params,
+//! the client identity, latency and the response size. Params are never logged as is, since they may contain
+//! sensitive data; if configured, client IPs and API keys are redacted as well.
+
+use std::{
+    fs,
+    io::{self, Write as _},
+    net::IpAddr,
+    path::Path,
+    sync::Arc,
+    task::{Context, Poll},
+    time::Instant,
+};
+
+use anyhow::Context as _;
+use axum::http::{header::HeaderName, Request};
+use futures::{future::BoxFuture, FutureExt};
+use rand::Rng;
+use serde::Serialize;
+use tokio::{sync::mpsc, task::futures::TaskLocalFuture};
+use vise::{Counter, Metrics};
+use zksync_config::configs::api::ApiAuditLogConfig;
+use zksync_types::{web3::signing::keccak256, H256};
+use zksync_web3_decl::jsonrpsee::{
+    server::middleware::rpc::RpcServiceT, types::Request as RpcRequest, MethodResponse,
+};
+
+use super::rate_limit_middleware::ClientKey;
+
+/// `tracing` target for audit log entries.
+const TRACING_TARGET: &str = "zksync_api_audit";
+/// Capacity of the channel buffering entries for the file sink.
+const FILE_SINK_CAPACITY: usize = 4_096;
+
+tokio::task_local! {
+    /// Key of the client making the HTTP request currently being processed.
+    static AUDIT_CLIENT_KEY: ClientKey;
+}
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "api_jsonrpc_backend_audit_log")]
+struct AuditLogMetrics {
+    /// Number of recorded audit log entries.
+    entries: Counter,
+    /// Number of audit log entries dropped because the file sink couldn't keep up.
+    dropped_entries: Counter,
+}
+
+#[vise::register]
+static METRICS: vise::Global<AuditLogMetrics> = vise::Global::new();
+
+/// Client identity as recorded in the audit log.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct ClientInfo {
+    kind: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<String>,
+}
+
+impl ClientInfo {
+    fn new(key: &ClientKey, redact: bool) -> Self {
+        match key {
+            ClientKey::ApiKey(key) => Self {
+                kind: "api_key",
+                id: Some(if redact {
+                    Self::hash_id(key)
+                } else {
+                    key.clone()
+                }),
+            },
+            ClientKey::Ip(ip) => Self {
+                kind: "ip",
+                id: Some(if redact {
+                    Self::truncate_ip(ip)
+                } else {
+                    ip.clone()
+                }),
+            },
+            ClientKey::Anonymous => Self {
+                kind: "anonymous",
+                id: None,
+            },
+        }
+    }
+
+    fn hash_id(id: &str) -> String {
+        let hash = keccak256(id.as_bytes());
+        format!("0x{}", hex::encode(&hash[..8]))
+    }
+
+    /// Truncates the IP address to the network prefix. Unparsable addresses are hashed instead.
+    fn truncate_ip(ip: &str) -> String {
+        match ip.parse::<IpAddr>() {
+            Ok(IpAddr::V4(ip)) => {
+                let [a, b, c, _] = ip.octets();
+                format!("{a}.{b}.{c}.0/24")
+            }
+            Ok(IpAddr::V6(ip)) => {
+                let [a, b, c, ..] = ip.segments();
+                format!("{a:x}:{b:x}:{c:x}::/48")
+            }
+            Err(_) => Self::hash_id(ip),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct AuditLogEntry<'a> {
+    transport: &'static str,
+    method: &'a str,
+    params_hash: Option<H256>,
+    client: &'a ClientInfo,
+    latency_ms: u64,
+    response_size: usize,
+    success: bool,
+}
+
+#[derive(Debug)]
+enum AuditLogSink {
+    Tracing,
+    File(mpsc::Sender<String>),
+}
+
+impl AuditLogSink {
+    fn file(path: &Path) -> anyhow::Result<Self> {
+        let file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("cannot open audit log file `{}`", path.display()))?;
+        let (sender, receiver) = mpsc::channel(FILE_SINK_CAPACITY);
+        // The task terminates once all senders are dropped, i.e., when the server is stopped.
+        tokio::task::spawn_blocking(move || {
+            if let Err(err) = Self::write_entries(file, receiver) {
+                tracing::warn!("Failed writing API audit log: {err}");
+            }
+        });
+        Ok(Self::File(sender))
+    }
+
+    fn write_entries(file: fs::File, mut receiver: mpsc::Receiver<String>) -> io::Result<()> {
+        let mut writer = io::BufWriter::new(file);
+        while let Some(entry) = receiver.blocking_recv() {
+            writeln!(writer, "{entry}")?;
+            while let Ok(entry) = receiver.try_recv() {
+                writeln!(writer, "{entry}")?;
+            }
+            writer.flush()?;
+        }
+        Ok(())
+    }
+
+    fn record(&self, entry: &AuditLogEntry<'_>) {
+        METRICS.entries.inc();
+        match self {
+            Self::Tracing => {
+                tracing::info!(
+                    target: TRACING_TARGET,
+                    transport = entry.transport,
+                    method = entry.method,
+                    params_hash = ?entry.params_hash,
+                    client_kind = entry.client.kind,
+                    client_id = entry.client.id.as_deref(),
+                    latency_ms = entry.latency_ms,
+                    response_size = entry.response_size,
+                    success = entry.success,
+                    "RPC call"
+                );
+            }
+            Self::File(sender) => {
+                let entry =
+                    serde_json::to_string(entry).expect("failed serializing audit log entry");
+                if sender.try_send(entry).is_err() {
+                    METRICS.dropped_entries.inc();
+                }
+            }
+        }
+    }
+}
+
+/// Audit logger shared among all sessions of a JSON-RPC server.
+#[derive(Debug)]
+pub(crate) struct AuditLogger {
+    transport: &'static str,
+    sampling_ratio: f64,
+    api_key_header: Option<HeaderName>,
+    redact_client_ids: bool,
+    sink: AuditLogSink,
+}
+
+impl AuditLogger {
+    /// Creates a logger from the config. Returns `Ok(None)` if the audit log is disabled.
+    /// If the file sink is configured, must be called from within a Tokio runtime.
+    pub fn new(
+        config: &ApiAuditLogConfig,
+        transport: &'static str,
+    ) -> anyhow::Result<Option<Self>> {
+        let Some(sampling_ratio) = config.sampling_ratio else {
+            return Ok(None);
+        };
+        anyhow::ensure!(
+            sampling_ratio > 0.0 && sampling_ratio <= 1.0,
+            "sampling ratio must be in (0, 1] range, got {sampling_ratio}"
+        );
+        let api_key_header = config
+            .api_key_header
+            .as_ref()
+            .map(|header| HeaderName::from_bytes(header.as_bytes()))
+            .transpose()
+            .context("invalid API key header")?;
+        let sink = match &config.file_path {
+            Some(path) => AuditLogSink::file(Path::new(path))?,
+            None => AuditLogSink::Tracing,
+        };
+        Ok(Some(Self {
+            transport,
+            sampling_ratio,
+            api_key_header,
+            redact_client_ids: config.redact_client_ids,
+            sink,
+        }))
+    }
+
+    fn should_sample(&self) -> bool {
+        self.sampling_ratio >= 1.0 || rand::thread_rng().gen_bool(self.sampling_ratio)
+    }
+}
+
+/// HTTP layer determining the client key for [`AuditLogMiddleware`].
+#[derive(Debug, Clone)]
+pub(crate) struct AuditLogKeyLayer(Arc<AuditLogger>);
+
+impl AuditLogKeyLayer {
+    pub fn new(logger: Arc<AuditLogger>) -> Self {
+        Self(logger)
+    }
+}
+
+impl<S> tower::Layer<S> for AuditLogKeyLayer {
+    type Service = AuditLogKeyService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        AuditLogKeyService {
+            inner,
+            logger: self.0.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct AuditLogKeyService<S> {
+    inner: S,
+    logger: Arc<AuditLogger>,
+}
+
+impl<S, B> tower::Service<Request<B>> for AuditLogKeyService<S>
+where
+    S: tower::Service<Request<B>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = TaskLocalFuture<ClientKey, S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<B>) -> Self::Future {
+        let key = ClientKey::from_headers(request.headers(), self.logger.api_key_header.as_ref());
+        AUDIT_CLIENT_KEY.scope(key, self.inner.call(request))
+    }
+}
+
+/// RPC middleware recording sampled calls to the audit log.
+///
+/// `jsonrpsee` will allocate the instance of this struct once per session.
+pub(crate) struct AuditLogMiddleware<S> {
+    inner: S,
+    logger: Option<(Arc<AuditLogger>, Arc<ClientInfo>)>,
+}
+
+impl<S> AuditLogMiddleware<S> {
+    pub(crate) fn new(inner: S, logger: Option<Arc<AuditLogger>>) -> Self {
+        let logger = logger.map(|logger| {
+            // If the client key cannot be determined for a session (e.g., if the session is processed
+            // in a separate task), the client is recorded as anonymous.
+            let key = AUDIT_CLIENT_KEY
+                .try_with(ClientKey::clone)
+                .unwrap_or(ClientKey::Anonymous);
+            let client = ClientInfo::new(&key, logger.redact_client_ids);
+            (logger, Arc::new(client))
+        });
+        Self { inner, logger }
+    }
+}
+
+impl<'a, S> RpcServiceT<'a> for AuditLogMiddleware<S>
+where
+    S: Send + Sync + RpcServiceT<'a>,
+    S::Future: 'a,
+{
+    type Future = BoxFuture<'a, MethodResponse>;
+
+    fn call(&self, request: RpcRequest<'a>) -> Self::Future {
+        let logger = self
+            .logger
+            .as_ref()
+            .filter(|(logger, _)| logger.should_sample());
+        let Some((logger, client)) = logger else {
+            return self.inner.call(request).boxed();
+        };
+
+        let (logger, client) = (logger.clone(), client.clone());
+        let method = request.method_name().to_owned();
+        let params_hash = request
+            .params
+            .as_ref()
+            .map(|params| H256(keccak256(params.get().as_bytes())));
+        let started_at = Instant::now();
+        let response = self.inner.call(request);
+        async move {
+            let response = response.await;
+            logger.sink.record(&AuditLogEntry {
+                transport: logger.transport,
+                method: &method,
+                params_hash,
+                client: &client,
+                latency_ms: started_at.elapsed().as_millis() as u64,
+                response_size: response.as_result().len(),
+                success: response.is_success(),
+            });
+            response
+        }
+        .boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn redacting_client_ids() {
+        let key = ClientKey::Ip("10.1.2.3".to_owned());
+        let client = ClientInfo::new(&key, true);
+        assert_eq!(client.kind, "ip");
+        assert_eq!(client.id.as_deref(), Some("10.1.2.0/24"));
+        assert_eq!(ClientInfo::new(&key, false).id.as_deref(), Some("10.1.2.3"));
+
+        let key = ClientKey::Ip("2001:db8:abcd:12::1".to_owned());
+        let client = ClientInfo::new(&key, true);
+        assert_eq!(client.id.as_deref(), Some("2001:db8:abcd::/48"));
+
+        let key = ClientKey::Ip("not-an-ip".to_owned());
+        let client = ClientInfo::new(&key, true);
+        assert_eq!(client.id, Some(ClientInfo::hash_id("not-an-ip")));
+
+        let key = ClientKey::ApiKey("secret".to_owned());
+        let client = ClientInfo::new(&key, true);
+        assert_eq!(client.kind, "api_key");
+        let id = client.id.unwrap();
+        assert!(!id.contains("secret"));
+        assert_eq!(id.len(), 18); // `0x` + 8 bytes in hex
+
+        let client = ClientInfo::new(&ClientKey::Anonymous, true);
+        assert_eq!(client.id, None);
+    }
+
+    #[test]
+    fn serializing_entry() {
+        let client = ClientInfo::new(&ClientKey::Ip("10.1.2.3".to_owned()), true);
+        let entry = AuditLogEntry {
+            transport: "http",
+            method: "eth_call",
+            params_hash: Some(H256::repeat_byte(1)),
+            client: &client,
+            latency_ms: 5,
+            response_size: 100,
+            success: true,
+        };
+        let entry = serde_json::to_value(entry).unwrap();
+        assert_eq!(
+            entry,
+            serde_json::json!({
+                "transport": "http",
+                "method": "eth_call",
+                "params_hash": format!("0x{}", "01".repeat(32)),
+                "client": { "kind": "ip", "id": "10.1.2.0/24" },
+                "latency_ms": 5,
+                "response_size": 100,
+                "success": true,
+            })
+        );
+    }
+
+    #[test]
+    fn creating_logger_from_config() {
+        let config = ApiAuditLogConfig::default();
+        assert!(AuditLogger::new(&config, "http").unwrap().is_none());
+
+        let config = ApiAuditLogConfig {
+            sampling_ratio: Some(1.5),
+            ..ApiAuditLogConfig::default()
+        };
+        AuditLogger::new(&config, "http").unwrap_err();
+
+        let config = ApiAuditLogConfig {
+            sampling_ratio: Some(1.0),
+            ..ApiAuditLogConfig::default()
+        };
+        let logger = AuditLogger::new(&config, "http").unwrap().unwrap();
+        assert!(logger.should_sample());
+    }
+
+    #[tokio::test]
+    async fn writing_entries_to_file() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("audit.log");
+        let config = ApiAuditLogConfig {
+            sampling_ratio: Some(1.0),
+            file_path: Some(path.to_str().unwrap().to_owned()),
+            ..ApiAuditLogConfig::default()
+        };
+        let logger = AuditLogger::new(&config, "ws").unwrap().unwrap();
+        let client = ClientInfo::new(&ClientKey::Anonymous, true);
+        for method in ["eth_chainId", "eth_blockNumber"] {
+            logger.sink.record(&AuditLogEntry {
+                transport: logger.transport,
+                method,
+                params_hash: None,
+                client: &client,
+                latency_ms: 1,
+                response_size: 10,
+                success: true,
+            });
+        }
+        drop(logger);
+
+        let contents = loop {
+            let contents = fs::read_to_string(&path).unwrap();
+            if contents.lines().count() == 2 {
+                break contents;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        };
+        let methods: Vec<_> = contents
+            .lines()
+            .map(|line| {
+                let entry: serde_json::Value = serde_json::from_str(line).unwrap();
+                entry["method"].as_str().unwrap().to_owned()
+            })
+            .collect();
+        assert_eq!(methods, ["eth_chainId", "eth_blockNumber"]);
+    }
+}
@@ -0,0 +1,131 @@
+//! Limits on the number of concurrently executed calls of expensive RPC methods.
+//!
+//! Unlike [rate limits](super::rate_limit_middleware), these limits are shared among all clients of a server,
+//! so that a few clients cannot exhaust server resources (e.g., VM instances) by calling expensive methods
+//! concurrently. Calls exceeding the limit are rejected immediately rather than queued.
+
+use std::{collections::HashMap, sync::Arc};
+
+use anyhow::Context as _;
+use futures::{future::BoxFuture, FutureExt};
+use tokio::sync::Semaphore;
+use vise::{Counter, LabeledFamily, Metrics};
+use zksync_config::configs::api::ApiRateLimitConfig;
+use zksync_web3_decl::jsonrpsee::{
+    server::middleware::rpc::RpcServiceT,
+    types::{error::ErrorCode, ErrorObject, Request},
+    MethodResponse,
+};
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "api_jsonrpc_backend_concurrency_limit")]
+struct ConcurrencyLimitMetrics {
+    /// Number of RPC calls rejected because of the method concurrency limit.
+    #[metrics(labels = ["method"])]
+    rejected: LabeledFamily<String, Counter>,
+}
+
+#[vise::register]
+static METRICS: vise::Global<ConcurrencyLimitMetrics> = vise::Global::new();
+
+/// Per-method concurrency limits shared among all sessions of a JSON-RPC server.
+#[derive(Debug)]
+pub(crate) struct MethodConcurrencyLimits {
+    semaphores: HashMap<String, Arc<Semaphore>>,
+}
+
+impl MethodConcurrencyLimits {
+    /// Creates limits from the config. Returns `Ok(None)` if no limits are configured.
+    pub fn new(config: &ApiRateLimitConfig) -> anyhow::Result<Option<Self>> {
+        let limits = config
+            .method_concurrency_limits()
+            .context("invalid method concurrency limits")?;
+        if limits.is_empty() {
+            return Ok(None);
+        }
+        let semaphores = limits
+            .into_iter()
+            .map(|(method, limit)| (method, Arc::new(Semaphore::new(limit.get() as usize))))
+            .collect();
+        Ok(Some(Self { semaphores }))
+    }
+}
+
+/// RPC middleware applying [`MethodConcurrencyLimits`].
+///
+/// `jsonrpsee` will allocate the instance of this struct once per session.
+pub(crate) struct ConcurrencyLimitMiddleware<S> {
+    inner: S,
+    limits: Option<Arc<MethodConcurrencyLimits>>,
+}
+
+impl<S> ConcurrencyLimitMiddleware<S> {
+    pub(crate) fn new(inner: S, limits: Option<Arc<MethodConcurrencyLimits>>) -> Self {
+        Self { inner, limits }
+    }
+}
+
+impl<'a, S> RpcServiceT<'a> for ConcurrencyLimitMiddleware<S>
+where
+    S: Send + Sync + RpcServiceT<'a>,
+    S::Future: 'a,
+{
+    type Future = BoxFuture<'a, MethodResponse>;
+
+    fn call(&self, request: Request<'a>) -> Self::Future {
+        let semaphore = self
+            .limits
+            .as_ref()
+            .and_then(|limits| limits.semaphores.get(request.method_name()));
+        let Some(semaphore) = semaphore else {
+            return self.inner.call(request).boxed();
+        };
+
+        let Ok(permit) = semaphore.clone().try_acquire_owned() else {
+            METRICS.rejected[&request.method_name().to_owned()].inc();
+            let rp = MethodResponse::error(
+                request.id,
+                ErrorObject::borrowed(
+                    ErrorCode::ServerError(reqwest::StatusCode::TOO_MANY_REQUESTS.as_u16().into())
+                        .code(),
+                    "Too many concurrent calls of the method",
+                    None,
+                ),
+            );
+            return futures::future::ready(rp).boxed();
+        };
+        let response = self.inner.call(request);
+        async move {
+            let response = response.await;
+            drop(permit);
+            response
+        }
+        .boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn creating_limits_from_config() {
+        assert!(MethodConcurrencyLimits::new(&ApiRateLimitConfig::default())
+            .unwrap()
+            .is_none());
+
+        let config = ApiRateLimitConfig {
+            method_concurrency_limits: vec!["debug_traceCall:2".to_owned()],
+            ..ApiRateLimitConfig::default()
+        };
+        let limits = MethodConcurrencyLimits::new(&config).unwrap().unwrap();
+        let semaphore = &limits.semaphores["debug_traceCall"];
+        assert_eq!(semaphore.available_permits(), 2);
+
+        let config = ApiRateLimitConfig {
+            method_concurrency_limits: vec!["debug_traceCall".to_owned()],
+            ..ApiRateLimitConfig::default()
+        };
+        MethodConcurrencyLimits::new(&config).unwrap_err();
+    }
+}
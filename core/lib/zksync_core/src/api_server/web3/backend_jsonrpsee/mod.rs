@@ -11,12 +11,19 @@ use zksync_web3_decl::{
 
 use crate::api_server::{tx_sender::SubmitTxError, web3::metrics::API_METRICS};
 
+pub(crate) mod audit_log_middleware;
 pub mod batch_limiter_middleware;
+pub(crate) mod concurrency_limit_middleware;
 pub mod namespaces;
+pub(crate) mod rate_limit_middleware;
 
 pub(crate) fn into_jsrpc_error(err: Web3Error) -> ErrorObjectOwned {
     let data = match &err {
-        Web3Error::SubmitTransactionError(_, data) => Some(format!("0x{}", hex::encode(data))),
+        Web3Error::SubmitTransactionError(_, data) => Some(serde_json::Value::String(format!(
+            "0x{}",
+            hex::encode(data)
+        ))),
+        Web3Error::ExecutionFailed(_, diagnostics) => serde_json::to_value(diagnostics).ok(),
         _ => None,
     };
     ErrorObjectOwned::owned(
@@ -31,15 +38,21 @@ pub(crate) fn into_jsrpc_error(err: Web3Error) -> ErrorObjectOwned {
             | Web3Error::TooManyTopics
             | Web3Error::FilterNotFound
             | Web3Error::InvalidFeeParams(_)
+            | Web3Error::InvalidStateOverride(_)
             | Web3Error::InvalidFilterBlockHash
-            | Web3Error::LogsLimitExceeded(_, _, _) => ErrorCode::InvalidParams.code(),
-            Web3Error::SubmitTransactionError(_, _) | Web3Error::SerializationError(_) => 3,
+            | Web3Error::LogsLimitExceeded(_, _, _)
+            | Web3Error::TraceLimitExceeded(_) => ErrorCode::InvalidParams.code(),
+            Web3Error::SubmitTransactionError(_, _)
+            | Web3Error::ExecutionFailed(_, _)
+            | Web3Error::SerializationError(_) => 3,
             Web3Error::PubSubTimeout => 4,
             Web3Error::RequestTimeout => 5,
-            Web3Error::TreeApiUnavailable => 6,
+            Web3Error::TreeApiUnavailable | Web3Error::TreeDataUnavailable(_) => 6,
+            Web3Error::ProofStoreUnavailable => 7,
         },
         match err {
-            Web3Error::SubmitTransactionError(message, _) => message,
+            Web3Error::SubmitTransactionError(message, _)
+            | Web3Error::ExecutionFailed(message, _) => message,
             _ => err.to_string(),
         },
         data,
@@ -57,6 +70,9 @@ impl SubmitTxError {
                 tracing::warn!("Error proxying call to main node in method {method_name}: {err}");
                 Web3Error::SubmitTransactionError(err.as_ref().to_string(), self.data())
             }
+            Self::WithDiagnostics(err, diagnostics) => {
+                Web3Error::ExecutionFailed(err.to_string(), diagnostics)
+            }
             _ => Web3Error::SubmitTransactionError(self.to_string(), self.data()),
         }
     }
@@ -4,5 +4,8 @@ pub mod eth;
 pub mod eth_subscribe;
 pub mod net;
 pub mod snapshots;
+pub mod trace;
+pub mod txpool;
 pub mod web3;
 pub mod zks;
+pub mod zks_admin;
@@ -0,0 +1,18 @@
+use zksync_types::api::{TxpoolContent, TxpoolStatus};
+use zksync_web3_decl::{
+    jsonrpsee::core::{async_trait, RpcResult},
+    namespaces::txpool::TxpoolNamespaceServer,
+};
+
+use crate::api_server::web3::{backend_jsonrpsee::into_jsrpc_error, namespaces::TxpoolNamespace};
+
+#[async_trait]
+impl TxpoolNamespaceServer for TxpoolNamespace {
+    async fn content(&self) -> RpcResult<TxpoolContent> {
+        self.content_impl().await.map_err(into_jsrpc_error)
+    }
+
+    async fn status(&self) -> RpcResult<TxpoolStatus> {
+        self.status_impl().await.map_err(into_jsrpc_error)
+    }
+}
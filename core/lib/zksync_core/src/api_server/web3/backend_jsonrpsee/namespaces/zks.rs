@@ -2,11 +2,12 @@ use std::collections::HashMap;
 
 use zksync_types::{
     api::{
-        BlockDetails, BridgeAddresses, L1BatchDetails, L2ToL1LogProof, Proof, ProtocolVersion,
-        TransactionDetails,
+        en::SyncStatus, BlockDetails, BlockId, BridgeAddresses, L1BatchDetails, L1BatchProof,
+        L2ToL1LogProof, PriorityOpInfo, Proof, ProtocolVersion, TransactionDetails,
+        TransactionExecutionInfo,
     },
     fee::Fee,
-    fee_model::FeeParams,
+    fee_model::{BaseTokenConversionRatio, FeeParams},
     transaction_request::CallRequest,
     Address, L1BatchNumber, MiniblockNumber, H256, U256, U64,
 };
@@ -114,6 +115,15 @@ impl ZksNamespaceServer for ZksNamespace {
             .map_err(into_jsrpc_error)
     }
 
+    async fn get_transaction_execution_info(
+        &self,
+        hash: H256,
+    ) -> RpcResult<Option<TransactionExecutionInfo>> {
+        self.get_transaction_execution_info_impl(hash)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
     async fn get_raw_block_transactions(
         &self,
         block_number: MiniblockNumber,
@@ -146,6 +156,10 @@ impl ZksNamespaceServer for ZksNamespace {
         Ok(self.get_fee_params_impl())
     }
 
+    async fn get_base_token_ratio(&self) -> RpcResult<BaseTokenConversionRatio> {
+        Ok(self.get_base_token_ratio_impl())
+    }
+
     async fn get_protocol_version(
         &self,
         version_id: Option<u16>,
@@ -155,6 +169,15 @@ impl ZksNamespaceServer for ZksNamespace {
             .map_err(into_jsrpc_error)
     }
 
+    async fn get_block_protocol_version(
+        &self,
+        block: BlockId,
+    ) -> RpcResult<Option<ProtocolVersion>> {
+        self.get_block_protocol_version_impl(block)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
     async fn get_proof(
         &self,
         address: Address,
@@ -165,4 +188,20 @@ impl ZksNamespaceServer for ZksNamespace {
             .await
             .map_err(into_jsrpc_error)
     }
+
+    async fn get_batch_proof(&self, batch: L1BatchNumber) -> RpcResult<Option<L1BatchProof>> {
+        self.get_batch_proof_impl(batch)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
+    async fn get_queued_priority_ops(&self) -> RpcResult<Vec<PriorityOpInfo>> {
+        self.get_queued_priority_ops_impl()
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
+    async fn sync_status(&self) -> RpcResult<SyncStatus> {
+        self.sync_status_impl().await.map_err(into_jsrpc_error)
+    }
 }
@@ -0,0 +1,16 @@
+use zksync_types::api::InflightL1Tx;
+use zksync_web3_decl::{
+    jsonrpsee::core::{async_trait, RpcResult},
+    namespaces::zks_admin::ZksAdminNamespaceServer,
+};
+
+use crate::api_server::web3::{backend_jsonrpsee::into_jsrpc_error, namespaces::ZksAdminNamespace};
+
+#[async_trait]
+impl ZksAdminNamespaceServer for ZksAdminNamespace {
+    async fn get_inflight_l1_txs(&self) -> RpcResult<Vec<InflightL1Tx>> {
+        self.get_inflight_l1_txs_impl()
+            .await
+            .map_err(into_jsrpc_error)
+    }
+}
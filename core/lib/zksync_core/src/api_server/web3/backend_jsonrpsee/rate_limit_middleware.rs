@@ -0,0 +1,350 @@
+//! Per-client rate limiting for the JSON-RPC servers.
+//!
+//! Clients are identified by an HTTP layer ([`RateLimitKeyLayer`]) which extracts the client key from request headers
+//! and makes it available to the RPC middleware ([`RateLimitMiddleware`]) via a task-local variable. The client key is
+//! the API key (if the API key header is configured and present in the request) or the client IP address taken
+//! from the `X-Forwarded-For` / `X-Real-IP` headers set by a reverse proxy. Clients without a key share a single quota.
+//!
+//! Each RPC call consumes a number of units equal to the weight of the called method (1 by default).
+//! If the client key cannot be determined for a session (e.g., if the session is processed in a separate task),
+//! the session gets its own quota.
+
+use std::{
+    collections::HashMap,
+    fmt,
+    num::NonZeroU32,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    task::{Context, Poll},
+};
+
+use anyhow::Context as _;
+use axum::http::{header::HeaderName, HeaderMap, Request};
+use governor::{
+    clock::DefaultClock,
+    middleware::NoOpMiddleware,
+    state::{keyed::DefaultKeyedStateStore, InMemoryState, NotKeyed},
+    Quota, RateLimiter,
+};
+use tokio::task::futures::TaskLocalFuture;
+use vise::{Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Metrics};
+use zksync_config::configs::api::ApiRateLimitConfig;
+use zksync_web3_decl::jsonrpsee::{
+    server::middleware::rpc::{layer::ResponseFuture, RpcServiceT},
+    types::{error::ErrorCode, ErrorObject, Request as RpcRequest},
+    MethodResponse,
+};
+
+/// Number of RPC calls after which stale client quotas are pruned.
+const PRUNE_INTERVAL: u64 = 1_024;
+
+tokio::task_local! {
+    /// Key of the client making the HTTP request currently being processed.
+    static CLIENT_KEY: ClientKey;
+}
+
+/// Client identity used as the rate limiting key.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) enum ClientKey {
+    ApiKey(String),
+    Ip(String),
+    Anonymous,
+}
+
+impl ClientKey {
+    /// Determines the client key from HTTP request headers.
+    pub(super) fn from_headers(headers: &HeaderMap, api_key_header: Option<&HeaderName>) -> Self {
+        let header_value = |name: &HeaderName| {
+            let value = headers.get(name)?.to_str().ok()?;
+            let value = value.split(',').next()?.trim();
+            (!value.is_empty()).then(|| value.to_owned())
+        };
+
+        if let Some(api_key) = api_key_header.and_then(header_value) {
+            return Self::ApiKey(api_key);
+        }
+        header_value(&HeaderName::from_static("x-forwarded-for"))
+            .or_else(|| header_value(&HeaderName::from_static("x-real-ip")))
+            .map_or(Self::Anonymous, Self::Ip)
+    }
+
+    fn kind(&self) -> ClientKind {
+        match self {
+            Self::ApiKey(_) => ClientKind::ApiKey,
+            Self::Ip(_) => ClientKind::Ip,
+            Self::Anonymous => ClientKind::Anonymous,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "client", rename_all = "snake_case")]
+enum ClientKind {
+    ApiKey,
+    Ip,
+    Anonymous,
+    Session,
+}
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "api_jsonrpc_backend_rate_limit")]
+struct RateLimitMetrics {
+    /// Number of RPC calls rejected because of the client rate limit.
+    rejected: Family<ClientKind, Counter>,
+    /// Number of clients with tracked quotas.
+    tracked_clients: Gauge<usize>,
+}
+
+#[vise::register]
+static METRICS: vise::Global<RateLimitMetrics> = vise::Global::new();
+
+type KeyedRateLimiter = RateLimiter<ClientKey, DefaultKeyedStateStore<ClientKey>, DefaultClock>;
+type DirectRateLimiter = RateLimiter<NotKeyed, InMemoryState, DefaultClock, NoOpMiddleware>;
+
+/// Rate limits shared among all sessions of a JSON-RPC server.
+pub(crate) struct ApiRateLimiter {
+    quota: Quota,
+    api_key_header: Option<HeaderName>,
+    method_weights: HashMap<String, NonZeroU32>,
+    clients: KeyedRateLimiter,
+    calls_since_pruning: AtomicU64,
+}
+
+impl fmt::Debug for ApiRateLimiter {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("ApiRateLimiter")
+            .field("quota", &self.quota)
+            .field("api_key_header", &self.api_key_header)
+            .field("method_weights", &self.method_weights)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ApiRateLimiter {
+    /// Creates a rate limiter from the config. Returns `Ok(None)` if rate limiting is disabled.
+    pub fn new(config: &ApiRateLimitConfig) -> anyhow::Result<Option<Self>> {
+        let Some(requests_per_minute) = config.requests_per_minute else {
+            return Ok(None);
+        };
+        let api_key_header = config
+            .api_key_header
+            .as_ref()
+            .map(|header| HeaderName::from_bytes(header.as_bytes()))
+            .transpose()
+            .context("invalid API key header")?;
+        let method_weights = config.method_weights().context("invalid method weights")?;
+        let quota = Quota::per_minute(requests_per_minute);
+        Ok(Some(Self {
+            quota,
+            api_key_header,
+            method_weights,
+            clients: RateLimiter::keyed(quota),
+            calls_since_pruning: AtomicU64::new(0),
+        }))
+    }
+
+    fn client_key(&self, headers: &HeaderMap) -> ClientKey {
+        ClientKey::from_headers(headers, self.api_key_header.as_ref())
+    }
+
+    fn weight(&self, method: &str) -> NonZeroU32 {
+        self.method_weights
+            .get(method)
+            .copied()
+            .unwrap_or(NonZeroU32::MIN)
+    }
+
+    /// Returns `true` if the call is allowed.
+    fn check_client(&self, key: &ClientKey, weight: NonZeroU32) -> bool {
+        if self.calls_since_pruning.fetch_add(1, Ordering::Relaxed) >= PRUNE_INTERVAL {
+            self.calls_since_pruning.store(0, Ordering::Relaxed);
+            self.clients.retain_recent();
+            METRICS.tracked_clients.set(self.clients.len());
+        }
+
+        let is_allowed = self.clients.check_key_n(key, weight).is_ok();
+        if !is_allowed {
+            METRICS.rejected[&key.kind()].inc();
+        }
+        is_allowed
+    }
+}
+
+/// HTTP layer determining the client key for [`RateLimitMiddleware`].
+#[derive(Debug, Clone)]
+pub(crate) struct RateLimitKeyLayer(Arc<ApiRateLimiter>);
+
+impl RateLimitKeyLayer {
+    pub fn new(limiter: Arc<ApiRateLimiter>) -> Self {
+        Self(limiter)
+    }
+}
+
+impl<S> tower::Layer<S> for RateLimitKeyLayer {
+    type Service = RateLimitKeyService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RateLimitKeyService {
+            inner,
+            limiter: self.0.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct RateLimitKeyService<S> {
+    inner: S,
+    limiter: Arc<ApiRateLimiter>,
+}
+
+impl<S, B> tower::Service<Request<B>> for RateLimitKeyService<S>
+where
+    S: tower::Service<Request<B>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = TaskLocalFuture<ClientKey, S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<B>) -> Self::Future {
+        let key = self.limiter.client_key(request.headers());
+        CLIENT_KEY.scope(key, self.inner.call(request))
+    }
+}
+
+enum SessionLimiter {
+    Shared(Arc<ApiRateLimiter>, ClientKey),
+    Session(Arc<ApiRateLimiter>, DirectRateLimiter),
+    Disabled,
+}
+
+/// RPC middleware applying per-client rate limits.
+///
+/// `jsonrpsee` will allocate the instance of this struct once per session.
+pub(crate) struct RateLimitMiddleware<S> {
+    inner: S,
+    limiter: SessionLimiter,
+}
+
+impl<S> RateLimitMiddleware<S> {
+    pub(crate) fn new(inner: S, limiter: Option<Arc<ApiRateLimiter>>) -> Self {
+        let limiter = match limiter {
+            None => SessionLimiter::Disabled,
+            Some(limiter) => match CLIENT_KEY.try_with(ClientKey::clone) {
+                Ok(key) => SessionLimiter::Shared(limiter, key),
+                Err(_) => {
+                    let session_limiter = RateLimiter::direct(limiter.quota);
+                    SessionLimiter::Session(limiter, session_limiter)
+                }
+            },
+        };
+        Self { inner, limiter }
+    }
+
+    fn is_allowed(&self, method: &str) -> bool {
+        match &self.limiter {
+            SessionLimiter::Disabled => true,
+            SessionLimiter::Shared(limiter, key) => {
+                limiter.check_client(key, limiter.weight(method))
+            }
+            SessionLimiter::Session(limiter, session_limiter) => {
+                let is_allowed = session_limiter.check_n(limiter.weight(method)).is_ok();
+                if !is_allowed {
+                    METRICS.rejected[&ClientKind::Session].inc();
+                }
+                is_allowed
+            }
+        }
+    }
+}
+
+impl<'a, S> RpcServiceT<'a> for RateLimitMiddleware<S>
+where
+    S: Send + Sync + RpcServiceT<'a>,
+{
+    type Future = ResponseFuture<S::Future>;
+
+    fn call(&self, request: RpcRequest<'a>) -> Self::Future {
+        if !self.is_allowed(request.method_name()) {
+            let rp = MethodResponse::error(
+                request.id,
+                ErrorObject::borrowed(
+                    ErrorCode::ServerError(reqwest::StatusCode::TOO_MANY_REQUESTS.as_u16().into())
+                        .code(),
+                    "Too many requests",
+                    None,
+                ),
+            );
+            return ResponseFuture::ready(rp);
+        }
+        ResponseFuture::future(self.inner.call(request))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limiter(api_key_header: Option<&str>) -> ApiRateLimiter {
+        let config = ApiRateLimitConfig {
+            requests_per_minute: NonZeroU32::new(10),
+            api_key_header: api_key_header.map(str::to_owned),
+            method_weights: vec!["eth_getLogs:5".to_owned()],
+            ..ApiRateLimitConfig::default()
+        };
+        ApiRateLimiter::new(&config).unwrap().unwrap()
+    }
+
+    #[test]
+    fn extracting_client_key() {
+        let limiter = limiter(Some("X-Api-Key"));
+        let mut headers = HeaderMap::new();
+        assert_eq!(limiter.client_key(&headers), ClientKey::Anonymous);
+
+        headers.insert("x-real-ip", "10.0.0.2".parse().unwrap());
+        assert_eq!(
+            limiter.client_key(&headers),
+            ClientKey::Ip("10.0.0.2".to_owned())
+        );
+        headers.insert("x-forwarded-for", "10.0.0.1, 10.0.1.1".parse().unwrap());
+        assert_eq!(
+            limiter.client_key(&headers),
+            ClientKey::Ip("10.0.0.1".to_owned())
+        );
+        headers.insert("x-api-key", "secret".parse().unwrap());
+        assert_eq!(
+            limiter.client_key(&headers),
+            ClientKey::ApiKey("secret".to_owned())
+        );
+    }
+
+    #[test]
+    fn method_weights_are_applied() {
+        let limiter = limiter(None);
+        let alice = ClientKey::Ip("10.0.0.1".to_owned());
+        let bob = ClientKey::Ip("10.0.0.2".to_owned());
+
+        for _ in 0..2 {
+            assert!(limiter.check_client(&alice, limiter.weight("eth_getLogs")));
+        }
+        assert!(!limiter.check_client(&alice, limiter.weight("eth_chainId")));
+        // Other clients have separate quotas.
+        for _ in 0..10 {
+            assert!(limiter.check_client(&bob, limiter.weight("eth_chainId")));
+        }
+        assert!(!limiter.check_client(&bob, limiter.weight("eth_chainId")));
+    }
+
+    #[test]
+    fn rate_limiting_is_disabled_without_limit() {
+        assert!(ApiRateLimiter::new(&ApiRateLimitConfig::default())
+            .unwrap()
+            .is_none());
+    }
+}
@@ -0,0 +1,158 @@
+//! Filtering of RPC methods exposed by a Web3 API server.
+
+use anyhow::Context as _;
+use zksync_config::configs::api::RpcInterfaceConfig;
+
+/// Pattern matching RPC method names: either an exact method name (e.g., `eth_call`),
+/// or all methods in a namespace (e.g., `debug_*`).
+#[derive(Debug, Clone, PartialEq)]
+enum MethodPattern {
+    Exact(String),
+    /// Namespace prefix including the trailing underscore, e.g. `debug_`.
+    Namespace(String),
+}
+
+impl MethodPattern {
+    fn parse(pattern: &str) -> anyhow::Result<Self> {
+        let pattern = pattern.trim();
+        anyhow::ensure!(!pattern.is_empty(), "method pattern is empty");
+        if let Some(prefix) = pattern.strip_suffix('*') {
+            anyhow::ensure!(
+                prefix.ends_with('_')
+                    && prefix.len() > 1
+                    && !prefix[..prefix.len() - 1].contains('_'),
+                "wildcard pattern `{pattern}` must have `namespace_*` form"
+            );
+            Ok(Self::Namespace(prefix.to_owned()))
+        } else {
+            anyhow::ensure!(
+                !pattern.contains('*'),
+                "wildcards are only supported at the end of a pattern (`namespace_*`), got `{pattern}`"
+            );
+            Ok(Self::Exact(pattern.to_owned()))
+        }
+    }
+
+    fn matches(&self, method: &str) -> bool {
+        match self {
+            Self::Exact(name) => name == method,
+            Self::Namespace(prefix) => method.starts_with(prefix.as_str()),
+        }
+    }
+}
+
+fn parse_patterns(patterns: &[String]) -> anyhow::Result<Vec<MethodPattern>> {
+    patterns
+        .iter()
+        .map(|pattern| MethodPattern::parse(pattern))
+        .collect()
+}
+
+/// Filter of RPC methods built from [`RpcInterfaceConfig`]. A method is allowed if it matches
+/// the allowlist (or the allowlist is not set), and doesn't match the denylist.
+#[derive(Debug, Clone, Default)]
+pub struct MethodFilter {
+    /// If set, methods not matching any pattern are denied.
+    allowed: Option<Vec<MethodPattern>>,
+    denied: Vec<MethodPattern>,
+}
+
+impl MethodFilter {
+    pub fn new(config: &RpcInterfaceConfig) -> anyhow::Result<Self> {
+        let allowed = config
+            .allowed_methods
+            .as_deref()
+            .map(parse_patterns)
+            .transpose()
+            .context("allowed_methods")?;
+        let denied = parse_patterns(&config.denied_methods).context("denied_methods")?;
+        Ok(Self { allowed, denied })
+    }
+
+    /// Checks whether this filter doesn't restrict any methods.
+    pub fn is_trivial(&self) -> bool {
+        self.allowed.is_none() && self.denied.is_empty()
+    }
+
+    pub fn allows(&self, method: &str) -> bool {
+        let is_allowed = self.allowed.as_ref().map_or(true, |allowed| {
+            allowed.iter().any(|pattern| pattern.matches(method))
+        });
+        is_allowed && !self.denied.iter().any(|pattern| pattern.matches(method))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parsing_method_patterns() {
+        assert_eq!(
+            MethodPattern::parse("eth_call").unwrap(),
+            MethodPattern::Exact("eth_call".into())
+        );
+        assert_eq!(
+            MethodPattern::parse(" debug_* ").unwrap(),
+            MethodPattern::Namespace("debug_".into())
+        );
+
+        for invalid_pattern in ["", "*", "_*", "debug*", "eth_get*", "eth_*Logs", "a_b_*"] {
+            MethodPattern::parse(invalid_pattern).unwrap_err();
+        }
+    }
+
+    #[test]
+    fn default_filter_allows_everything() {
+        let filter = MethodFilter::new(&RpcInterfaceConfig::default()).unwrap();
+        assert!(filter.is_trivial());
+        assert!(filter.allows("eth_call"));
+        assert!(filter.allows("debug_traceCall"));
+    }
+
+    #[test]
+    fn filter_with_denylist() {
+        let config = RpcInterfaceConfig {
+            denied_methods: vec!["debug_*".into(), "eth_getLogs".into()],
+            ..RpcInterfaceConfig::default()
+        };
+        let filter = MethodFilter::new(&config).unwrap();
+        assert!(!filter.is_trivial());
+        assert!(filter.allows("eth_call"));
+        assert!(filter.allows("eth_getLogsAndMore"));
+        assert!(!filter.allows("eth_getLogs"));
+        assert!(!filter.allows("debug_traceCall"));
+    }
+
+    #[test]
+    fn filter_with_allowlist() {
+        let config = RpcInterfaceConfig {
+            allowed_methods: Some(vec!["eth_*".into(), "net_version".into()]),
+            denied_methods: vec!["eth_sendRawTransaction".into()],
+            ..RpcInterfaceConfig::default()
+        };
+        let filter = MethodFilter::new(&config).unwrap();
+        assert!(filter.allows("eth_call"));
+        assert!(filter.allows("net_version"));
+        assert!(!filter.allows("eth_sendRawTransaction"));
+        assert!(!filter.allows("net_peerCount"));
+        assert!(!filter.allows("zks_getProof"));
+
+        let config = RpcInterfaceConfig {
+            allowed_methods: Some(vec![]),
+            ..RpcInterfaceConfig::default()
+        };
+        let filter = MethodFilter::new(&config).unwrap();
+        assert!(!filter.allows("eth_call"));
+    }
+
+    #[test]
+    fn invalid_filter_config() {
+        let config = RpcInterfaceConfig {
+            denied_methods: vec!["eth_get*".into()],
+            ..RpcInterfaceConfig::default()
+        };
+        let err = MethodFilter::new(&config).unwrap_err();
+        assert!(format!("{err:#}").contains("denied_methods"), "{err:#}");
+    }
+}
@@ -179,6 +179,9 @@ pub(super) enum SubscriptionType {
     Blocks,
     Txs,
     Logs,
+    L1Batches,
+    L1BatchStatuses,
+    PriorityOps,
 }
 
 #[derive(Debug, Metrics)]
@@ -1,16 +1,22 @@
-use std::{net::SocketAddr, num::NonZeroU32, sync::Arc, time::Duration};
+use std::{net::SocketAddr, num::NonZeroU32, str::FromStr, sync::Arc, time::Duration};
 
 use anyhow::Context as _;
 use chrono::NaiveDateTime;
 use futures::future;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use tokio::{
     sync::{mpsc, oneshot, watch, Mutex},
     task::JoinHandle,
 };
-use tower_http::{cors::CorsLayer, metrics::InFlightRequestsLayer};
+use tower_http::{
+    compression::{predicate::SizeAbove, CompressionLayer},
+    cors::CorsLayer,
+    metrics::InFlightRequestsLayer,
+};
+use zksync_config::configs::api::{ApiAuditLogConfig, ApiRateLimitConfig};
 use zksync_dal::ConnectionPool;
 use zksync_health_check::{HealthStatus, HealthUpdater, ReactiveHealthCheck};
+use zksync_object_store::ObjectStore;
 use zksync_types::MiniblockNumber;
 use zksync_web3_decl::{
     jsonrpsee::{
@@ -19,41 +25,53 @@ use zksync_web3_decl::{
     },
     namespaces::{
         DebugNamespaceServer, EnNamespaceServer, EthNamespaceServer, EthPubSubServer,
-        NetNamespaceServer, SnapshotsNamespaceServer, Web3NamespaceServer, ZksNamespaceServer,
+        NetNamespaceServer, SnapshotsNamespaceServer, TraceNamespaceServer, TxpoolNamespaceServer,
+        Web3NamespaceServer, ZksAdminNamespaceServer, ZksNamespaceServer,
     },
     types::Filter,
 };
 
+pub use self::{method_filter::MethodFilter, response_cache::ResponseCache};
 use self::{
     metrics::API_METRICS,
     namespaces::{
-        DebugNamespace, EnNamespace, EthNamespace, NetNamespace, SnapshotsNamespace, Web3Namespace,
-        ZksNamespace,
+        DebugNamespace, EnNamespace, EthNamespace, NetNamespace, SnapshotsNamespace,
+        TraceNamespace, TxpoolNamespace, Web3Namespace, ZksAdminNamespace, ZksNamespace,
     },
     pubsub::{EthSubscribe, EthSubscriptionIdProvider, PubSubEvent},
-    state::{Filters, InternalApiConfig, RpcState, SealedMiniblockNumber},
+    state::{Filters, InternalApiConfig, RpcState, SealedMiniblockNumber, SharedBlockStartInfo},
 };
 use crate::{
     api_server::{
-        execution_sandbox::{BlockStartInfo, VmConcurrencyBarrier},
+        execution_sandbox::VmConcurrencyBarrier,
         tree::TreeApiHttpClient,
         tx_sender::TxSender,
-        web3::backend_jsonrpsee::batch_limiter_middleware::LimitMiddleware,
+        web3::backend_jsonrpsee::{
+            audit_log_middleware::{AuditLogKeyLayer, AuditLogMiddleware, AuditLogger},
+            batch_limiter_middleware::LimitMiddleware,
+            concurrency_limit_middleware::{ConcurrencyLimitMiddleware, MethodConcurrencyLimits},
+            rate_limit_middleware::{ApiRateLimiter, RateLimitKeyLayer, RateLimitMiddleware},
+        },
     },
     sync_layer::SyncState,
     utils::wait_for_l1_batch,
 };
 
 pub mod backend_jsonrpsee;
+mod method_filter;
 mod metrics;
 pub mod namespaces;
-mod pubsub;
+pub(crate) mod pubsub;
+mod response_cache;
 pub mod state;
 #[cfg(test)]
 pub(crate) mod tests;
 
 /// Timeout for graceful shutdown logic within API servers.
 const GRACEFUL_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+/// Minimum size of HTTP responses (in bytes) to be compressed. Compressing smaller responses doesn't save
+/// much bandwidth, but increases latency.
+const RESPONSE_COMPRESSION_MIN_SIZE: u16 = 1_024;
 
 /// Represents all kinds of `Filter`.
 #[derive(Debug, Clone)]
@@ -72,7 +90,7 @@ enum ApiTransport {
     Http(SocketAddr),
 }
 
-#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum Namespace {
     Eth,
@@ -83,6 +101,19 @@ pub enum Namespace {
     En,
     Pubsub,
     Snapshots,
+    Txpool,
+    Trace,
+    /// Operator-facing `zks` methods exposing internal server state (e.g., in-flight L1 transactions).
+    #[serde(rename = "zks_admin")]
+    ZksAdmin,
+}
+
+impl FromStr for Namespace {
+    type Err = serde_json::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        serde_json::from_value(serde_json::Value::String(s.to_owned()))
+    }
 }
 
 impl Namespace {
@@ -113,8 +144,15 @@ struct OptionalApiParams {
     subscriptions_limit: Option<usize>,
     batch_request_size_limit: Option<usize>,
     response_body_size_limit: Option<usize>,
+    request_body_size_limit: Option<usize>,
+    response_compression: bool,
     websocket_requests_per_minute_limit: Option<NonZeroU32>,
+    rate_limit: Option<ApiRateLimitConfig>,
+    audit_log: Option<ApiAuditLogConfig>,
     tree_api_url: Option<String>,
+    proof_store: Option<Arc<dyn ObjectStore>>,
+    response_cache: ResponseCache,
+    method_filter: MethodFilter,
     pub_sub_events_sender: Option<mpsc::UnboundedSender<PubSubEvent>>,
 }
 
@@ -210,6 +248,17 @@ impl ApiBuilder {
         self
     }
 
+    pub fn with_request_body_size_limit(mut self, request_body_size_limit: usize) -> Self {
+        self.optional.request_body_size_limit = Some(request_body_size_limit);
+        self
+    }
+
+    /// Enables gzip / brotli compression of large HTTP responses for clients supporting it.
+    pub fn with_response_compression(mut self, enabled: bool) -> Self {
+        self.optional.response_compression = enabled;
+        self
+    }
+
     pub fn with_websocket_requests_per_minute_limit(
         mut self,
         websocket_requests_per_minute_limit: NonZeroU32,
@@ -219,6 +268,19 @@ impl ApiBuilder {
         self
     }
 
+    /// Enables per-client rate limiting of RPC calls. Unlike [`Self::with_websocket_requests_per_minute_limit()`],
+    /// limits are shared among all connections of the same client. Also configures per-method limits
+    /// on the number of concurrent calls, which are shared among all clients.
+    pub fn with_rate_limit(mut self, config: ApiRateLimitConfig) -> Self {
+        self.optional.rate_limit = Some(config);
+        self
+    }
+
+    pub fn with_audit_log(mut self, config: ApiAuditLogConfig) -> Self {
+        self.optional.audit_log = Some(config);
+        self
+    }
+
     pub fn with_sync_state(mut self, sync_state: SyncState) -> Self {
         self.optional.sync_state = Some(sync_state);
         self
@@ -239,6 +301,25 @@ impl ApiBuilder {
         self
     }
 
+    /// Sets the object store with L1 batch proofs used by the `zks_getBatchProof` method.
+    pub fn with_proof_store(mut self, proof_store: Arc<dyn ObjectStore>) -> Self {
+        self.optional.proof_store = Some(proof_store);
+        self
+    }
+
+    /// Sets the cache for immutable API responses. The same cache can be shared by multiple servers.
+    pub fn with_response_cache(mut self, response_cache: ResponseCache) -> Self {
+        self.optional.response_cache = response_cache;
+        self
+    }
+
+    /// Sets the filter for RPC methods exposed by the server. Methods rejected by the filter are removed
+    /// from the server, so calling them results in the "method not found" error.
+    pub fn with_method_filter(mut self, method_filter: MethodFilter) -> Self {
+        self.optional.method_filter = method_filter;
+        self
+    }
+
     #[cfg(test)]
     fn with_pub_sub_events(mut self, sender: mpsc::UnboundedSender<PubSubEvent>) -> Self {
         self.optional.pub_sub_events_sender = Some(sender);
@@ -278,9 +359,10 @@ impl FullApiParams {
     async fn build_rpc_state(
         self,
         last_sealed_miniblock: SealedMiniblockNumber,
+        start_info: SharedBlockStartInfo,
     ) -> anyhow::Result<RpcState> {
         let mut storage = self.updaters_pool.access_storage_tagged("api").await?;
-        let start_info = BlockStartInfo::new(&mut storage).await?;
+        start_info.refresh(&mut storage).await?;
         drop(storage);
 
         Ok(RpcState {
@@ -295,6 +377,8 @@ impl FullApiParams {
                 .optional
                 .tree_api_url
                 .map(|url| TreeApiHttpClient::new(url.as_str())),
+            proof_store: self.optional.proof_store,
+            response_cache: self.optional.response_cache,
         })
     }
 
@@ -302,10 +386,14 @@ impl FullApiParams {
         self,
         pub_sub: Option<EthSubscribe>,
         last_sealed_miniblock: SealedMiniblockNumber,
+        start_info: SharedBlockStartInfo,
     ) -> anyhow::Result<RpcModule<()>> {
         let namespaces = self.namespaces.clone();
         let zksync_network_id = self.config.l2_chain_id;
-        let rpc_state = self.build_rpc_state(last_sealed_miniblock).await?;
+        let method_filter = self.optional.method_filter.clone();
+        let rpc_state = self
+            .build_rpc_state(last_sealed_miniblock, start_info)
+            .await?;
 
         // Collect all the methods into a single RPC module.
         let mut rpc = RpcModule::new(());
@@ -338,10 +426,32 @@ impl FullApiParams {
             rpc.merge(DebugNamespace::new(rpc_state.clone()).await.into_rpc())
                 .expect("Can't merge debug namespace");
         }
+        if namespaces.contains(&Namespace::Txpool) {
+            rpc.merge(TxpoolNamespace::new(rpc_state.clone()).into_rpc())
+                .expect("Can't merge txpool namespace");
+        }
+        if namespaces.contains(&Namespace::Trace) {
+            rpc.merge(TraceNamespace::new(rpc_state.clone()).into_rpc())
+                .expect("Can't merge trace namespace");
+        }
+        if namespaces.contains(&Namespace::ZksAdmin) {
+            rpc.merge(ZksAdminNamespace::new(rpc_state.clone()).into_rpc())
+                .expect("Can't merge zks_admin namespace");
+        }
         if namespaces.contains(&Namespace::Snapshots) {
             rpc.merge(SnapshotsNamespace::new(rpc_state).into_rpc())
                 .expect("Can't merge snapshots namespace");
         }
+
+        if !method_filter.is_trivial() {
+            let denied_methods: Vec<_> = rpc
+                .method_names()
+                .filter(|&name| !method_filter.allows(name))
+                .collect();
+            for name in denied_methods {
+                rpc.remove_method(name);
+            }
+        }
         Ok(rpc)
     }
 
@@ -398,6 +508,9 @@ impl FullApiParams {
         // processes enough requests, information about the latest sealed miniblock will be updated
         // by reporting block difference metrics, so the actual update lag would be much smaller than this value.
         const SEALED_MINIBLOCK_UPDATE_INTERVAL: Duration = Duration::from_millis(25);
+        // Should be significantly smaller than the delay between soft and hard pruning of the node storage,
+        // so that the API server doesn't try to access removed data.
+        const START_INFO_UPDATE_INTERVAL: Duration = Duration::from_secs(5);
 
         let transport = self.transport;
         let health_check_name = match transport {
@@ -412,7 +525,16 @@ impl FullApiParams {
             stop_receiver.clone(),
         );
 
-        let mut tasks = vec![tokio::spawn(update_task)];
+        let (start_info, start_info_update_task) = SharedBlockStartInfo::new(
+            self.updaters_pool.clone(),
+            START_INFO_UPDATE_INTERVAL,
+            stop_receiver.clone(),
+        );
+
+        let mut tasks = vec![
+            tokio::spawn(update_task),
+            tokio::spawn(start_info_update_task),
+        ];
         if let Some(tx_proxy) = &self.tx_sender.0.proxy {
             let task = tx_proxy
                 .run_account_nonce_sweeper(self.updaters_pool.clone(), stop_receiver.clone());
@@ -442,6 +564,7 @@ impl FullApiParams {
             stop_receiver,
             pub_sub,
             last_sealed_miniblock,
+            start_info,
             local_addr_sender,
             health_updater,
         ));
@@ -459,6 +582,7 @@ impl FullApiParams {
         mut stop_receiver: watch::Receiver<bool>,
         pub_sub: Option<EthSubscribe>,
         last_sealed_miniblock: SealedMiniblockNumber,
+        start_info: SharedBlockStartInfo,
         local_addr_sender: oneshot::Sender<SocketAddr>,
         health_updater: HealthUpdater,
     ) -> anyhow::Result<()> {
@@ -496,12 +620,43 @@ impl FullApiParams {
             .optional
             .response_body_size_limit
             .map_or(u32::MAX, |limit| limit as u32);
+        let request_body_size_limit = self
+            .optional
+            .request_body_size_limit
+            .map_or(u32::MAX, |limit| limit as u32);
         let websocket_requests_per_minute_limit = self.optional.websocket_requests_per_minute_limit;
+        let rate_limiter = self
+            .optional
+            .rate_limit
+            .as_ref()
+            .map(ApiRateLimiter::new)
+            .transpose()
+            .context("invalid rate limit config")?
+            .flatten()
+            .map(Arc::new);
+        let concurrency_limits = self
+            .optional
+            .rate_limit
+            .as_ref()
+            .map(MethodConcurrencyLimits::new)
+            .transpose()
+            .context("invalid method concurrency limits config")?
+            .flatten()
+            .map(Arc::new);
+        let audit_logger = self
+            .optional
+            .audit_log
+            .as_ref()
+            .map(|config| AuditLogger::new(config, if is_http { "http" } else { "ws" }))
+            .transpose()
+            .context("invalid audit log config")?
+            .flatten()
+            .map(Arc::new);
         let subscriptions_limit = self.optional.subscriptions_limit;
         let vm_barrier = self.vm_barrier.clone();
 
         let rpc = self
-            .build_rpc_module(pub_sub, last_sealed_miniblock)
+            .build_rpc_module(pub_sub, last_sealed_miniblock, start_info)
             .await?;
 
         // Setup CORS.
@@ -521,10 +676,20 @@ impl FullApiParams {
                 future::ready(())
             }),
         );
+        // Setup response compression. The layer is always present so that the middleware type doesn't depend
+        // on the config; if compression is disabled, the layer has no enabled algorithms and passes responses as is.
+        let mut compression =
+            CompressionLayer::new().compress_when(SizeAbove::new(RESPONSE_COMPRESSION_MIN_SIZE));
+        if !is_http || !self.optional.response_compression {
+            compression = compression.no_br().no_deflate().no_gzip().no_zstd();
+        }
         // Assemble server middleware.
         let middleware = tower::ServiceBuilder::new()
             .layer(in_flight_requests)
-            .option_layer(cors);
+            .layer(compression)
+            .option_layer(cors)
+            .option_layer(rate_limiter.clone().map(RateLimitKeyLayer::new))
+            .option_layer(audit_logger.clone().map(AuditLogKeyLayer::new));
 
         // Settings shared by HTTP and WS servers.
         let max_connections = !is_http
@@ -535,12 +700,21 @@ impl FullApiParams {
             .max_connections(max_connections as u32)
             .set_http_middleware(middleware)
             .max_response_body_size(response_body_size_limit)
+            .max_request_body_size(request_body_size_limit)
             .set_batch_request_config(batch_request_config);
 
         let (local_addr, server_handle) = if is_http {
             // HTTP-specific settings
             let server = server_builder
                 .http_only()
+                .set_rpc_middleware(
+                    RpcServiceBuilder::new()
+                        .layer_fn(move |a| AuditLogMiddleware::new(a, audit_logger.clone()))
+                        .layer_fn(move |a| RateLimitMiddleware::new(a, rate_limiter.clone()))
+                        .layer_fn(move |a| {
+                            ConcurrencyLimitMiddleware::new(a, concurrency_limits.clone())
+                        }),
+                )
                 .build(addr)
                 .await
                 .context("Failed building HTTP JSON-RPC server")?;
@@ -548,9 +722,17 @@ impl FullApiParams {
         } else {
             // WS specific settings
             let server = server_builder
-                .set_rpc_middleware(RpcServiceBuilder::new().layer_fn(move |a| {
-                    LimitMiddleware::new(a, websocket_requests_per_minute_limit)
-                }))
+                .set_rpc_middleware(
+                    RpcServiceBuilder::new()
+                        .layer_fn(move |a| AuditLogMiddleware::new(a, audit_logger.clone()))
+                        .layer_fn(move |a| RateLimitMiddleware::new(a, rate_limiter.clone()))
+                        .layer_fn(move |a| {
+                            ConcurrencyLimitMiddleware::new(a, concurrency_limits.clone())
+                        })
+                        .layer_fn(move |a| {
+                            LimitMiddleware::new(a, websocket_requests_per_minute_limit)
+                        }),
+                )
                 .set_id_provider(EthSubscriptionIdProvider)
                 .build(addr)
                 .await
@@ -1,10 +1,16 @@
 use std::sync::Arc;
 
-use multivm::{interface::ExecutionResult, vm_latest::constants::BLOCK_GAS_LIMIT};
+use multivm::{
+    interface::{ExecutionResult, VmExecutionResultAndLogs},
+    vm_latest::constants::BLOCK_GAS_LIMIT,
+};
 use once_cell::sync::OnceCell;
 use zksync_system_constants::MAX_ENCODED_TX_SIZE;
 use zksync_types::{
-    api::{BlockId, BlockNumber, DebugCall, ResultDebugCall, TracerConfig},
+    api::{
+        BlockId, BlockNumber, DebugCall, DebugTrace, ResultDebugCall, StructLog,
+        StructLoggerConfig, StructLogsTrace, SupportedTracers, TracerConfig,
+    },
     fee_model::BatchFeeInput,
     l2::L2Tx,
     transaction_request::CallRequest,
@@ -14,7 +20,7 @@ use zksync_types::{
 use zksync_web3_decl::error::Web3Error;
 
 use crate::api_server::{
-    execution_sandbox::{ApiTracer, TxSharedArgs},
+    execution_sandbox::{collect_diagnostics, ApiTracer, TxSharedArgs},
     tx_sender::{ApiContracts, TxSenderConfig},
     web3::{backend_jsonrpsee::internal_error, metrics::API_METRICS, state::RpcState},
 };
@@ -49,6 +55,28 @@ impl DebugNamespace {
         &self.state.tx_sender.0.sender_config
     }
 
+    fn struct_logger_config(options: Option<&TracerConfig>) -> Option<StructLoggerConfig> {
+        options.and_then(|options| {
+            matches!(options.tracer, SupportedTracers::StructLogger)
+                .then(|| options.struct_logger_config.clone())
+        })
+    }
+
+    /// Struct logs can only be produced by executing transactions. Re-executing all transactions in a block
+    /// is too expensive, so block tracing methods only return persisted call traces.
+    fn ensure_call_tracer(
+        options: Option<&TracerConfig>,
+        method_name: &'static str,
+    ) -> Result<(), Web3Error> {
+        match options.map(|options| &options.tracer) {
+            Some(SupportedTracers::StructLogger) => {
+                tracing::debug!("`{method_name}` does not support struct logger");
+                Err(Web3Error::NotImplemented)
+            }
+            Some(SupportedTracers::CallTracer) | None => Ok(()),
+        }
+    }
+
     #[tracing::instrument(skip(self))]
     pub async fn debug_trace_block_impl(
         &self,
@@ -57,6 +85,7 @@ impl DebugNamespace {
     ) -> Result<Vec<ResultDebugCall>, Web3Error> {
         const METHOD_NAME: &str = "debug_trace_block";
 
+        Self::ensure_call_tracer(options.as_ref(), METHOD_NAME)?;
         let method_latency = API_METRICS.start_block_call(METHOD_NAME, block_id);
         let only_top_call = options
             .map(|options| options.tracer_config.only_top_call)
@@ -78,12 +107,15 @@ impl DebugNamespace {
             .map_err(|err| internal_error(METHOD_NAME, err))?;
         let call_trace = call_traces
             .into_iter()
-            .map(|call_trace| {
+            .map(|(tx_hash, call_trace)| {
                 let mut result: DebugCall = call_trace.into();
                 if only_top_call {
                     result.calls = vec![];
                 }
-                ResultDebugCall { result }
+                ResultDebugCall {
+                    tx_hash: Some(tx_hash),
+                    result,
+                }
             })
             .collect();
 
@@ -97,9 +129,12 @@ impl DebugNamespace {
         &self,
         tx_hash: H256,
         options: Option<TracerConfig>,
-    ) -> Result<Option<DebugCall>, Web3Error> {
+    ) -> Result<Option<DebugTrace>, Web3Error> {
         const METHOD_NAME: &str = "debug_trace_transaction";
 
+        if let Some(config) = Self::struct_logger_config(options.as_ref()) {
+            return self.replay_transaction(tx_hash, config, METHOD_NAME).await;
+        }
         let only_top_call = options
             .map(|options| options.tracer_config.only_top_call)
             .unwrap_or(false);
@@ -119,21 +154,137 @@ impl DebugNamespace {
             if only_top_call {
                 result.calls = vec![];
             }
-            result
+            DebugTrace::Call(result)
         }))
     }
 
+    /// Re-executes a transaction from a sealed miniblock with the struct logger. The transaction is executed on top of
+    /// the state at the end of the previous miniblock after all preceding transactions from its miniblock.
+    async fn replay_transaction(
+        &self,
+        tx_hash: H256,
+        config: StructLoggerConfig,
+        method_name: &'static str,
+    ) -> Result<Option<DebugTrace>, Web3Error> {
+        let mut connection = self
+            .state
+            .connection_pool
+            .access_storage_tagged("api")
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        let location = connection
+            .transactions_dal()
+            .get_call_trace_with_location(tx_hash)
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        let Some((location, _)) = location else {
+            return Ok(None);
+        };
+        let miniblock_number = location.block_number;
+        let block_args = self
+            .state
+            .resolve_replay_block_args(&mut connection, miniblock_number, method_name)
+            .await?;
+        let method_latency =
+            API_METRICS.start_block_call(method_name, BlockId::Number(miniblock_number.0.into()));
+
+        let base_fee = connection
+            .blocks_dal()
+            .get_miniblock_header(miniblock_number)
+            .await
+            .map_err(|err| internal_error(method_name, err))?
+            .ok_or(Web3Error::NoBlock)?
+            .base_fee_per_gas;
+        let mut preceding_txs = connection
+            .transactions_web3_dal()
+            .get_raw_miniblock_transactions(miniblock_number)
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+        drop(connection);
+
+        let tx_position = preceding_txs
+            .iter()
+            .position(|tx| tx.hash() == tx_hash)
+            .ok_or_else(|| {
+                let err = format!("transaction is missing in miniblock #{miniblock_number}");
+                internal_error(method_name, err)
+            })?;
+        preceding_txs.truncate(tx_position + 1);
+        let tx = preceding_txs.pop().unwrap(); // `unwrap()` is safe: the vector is non-empty
+
+        let vm_permit = self
+            .state
+            .tx_sender
+            .vm_concurrency_limiter()
+            .acquire()
+            .await;
+        let vm_permit = vm_permit.ok_or(Web3Error::InternalError)?;
+        let struct_logger_result = Arc::new(OnceCell::default());
+        let custom_tracers = vec![ApiTracer::StructLogger(
+            config,
+            struct_logger_result.clone(),
+        )];
+
+        let executor = &self.state.tx_sender.0.executor;
+        let result = executor
+            .replay_tx_in_sandbox(
+                vm_permit,
+                self.shared_args(),
+                self.state.connection_pool.clone(),
+                preceding_txs,
+                tx,
+                block_args,
+                base_fee,
+                custom_tracers,
+            )
+            .await
+            .map_err(|err| internal_error(method_name, err))?;
+
+        let block_diff = self
+            .state
+            .last_sealed_miniblock
+            .diff_with_block_args(&block_args);
+        method_latency.observe(block_diff);
+        Ok(Some(DebugTrace::StructLogs(Self::struct_logs_trace(
+            result,
+            struct_logger_result,
+        ))))
+    }
+
+    fn struct_logs_trace(
+        result: VmExecutionResultAndLogs,
+        struct_logger_result: Arc<OnceCell<Vec<StructLog>>>,
+    ) -> StructLogsTrace {
+        // We had only one copy of Arc this arc is already dropped it's safe to unwrap
+        let struct_logs = Arc::try_unwrap(struct_logger_result)
+            .unwrap()
+            .take()
+            .unwrap_or_default();
+        let (failed, return_value) = match result.result {
+            ExecutionResult::Success { output } => (false, output),
+            ExecutionResult::Revert { output } => (true, output.encoded_data()),
+            ExecutionResult::Halt { .. } => (true, vec![]),
+        };
+        StructLogsTrace {
+            gas: result.statistics.gas_used.into(),
+            failed,
+            return_value: return_value.into(),
+            struct_logs,
+        }
+    }
+
     #[tracing::instrument(skip(self, request, block_id))]
     pub async fn debug_trace_call_impl(
         &self,
         request: CallRequest,
         block_id: Option<BlockId>,
         options: Option<TracerConfig>,
-    ) -> Result<DebugCall, Web3Error> {
+    ) -> Result<DebugTrace, Web3Error> {
         const METHOD_NAME: &str = "debug_trace_call";
 
         let block_id = block_id.unwrap_or(BlockId::Number(BlockNumber::Pending));
         let method_latency = API_METRICS.start_block_call(METHOD_NAME, block_id);
+        let struct_logger_config = Self::struct_logger_config(options.as_ref());
         let only_top_call = options
             .map(|options| options.tracer_config.only_top_call)
             .unwrap_or(false);
@@ -163,7 +314,13 @@ impl DebugNamespace {
 
         // We don't need properly trace if we only need top call
         let call_tracer_result = Arc::new(OnceCell::default());
-        let custom_tracers = if only_top_call {
+        let struct_logger_result = Arc::new(OnceCell::default());
+        let custom_tracers = if let Some(config) = struct_logger_config.clone() {
+            vec![ApiTracer::StructLogger(
+                config,
+                struct_logger_result.clone(),
+            )]
+        } else if only_top_call {
             vec![]
         } else {
             vec![ApiTracer::CallTracer(call_tracer_result.clone())]
@@ -179,26 +336,39 @@ impl DebugNamespace {
                 block_args,
                 self.sender_config().vm_execution_cache_misses_limit,
                 custom_tracers,
+                None,
             )
             .await
             .map_err(|err| internal_error(METHOD_NAME, err))?;
 
+        let block_diff = self
+            .state
+            .last_sealed_miniblock
+            .diff_with_block_args(&block_args);
+        if struct_logger_config.is_some() {
+            method_latency.observe(block_diff);
+            return Ok(DebugTrace::StructLogs(Self::struct_logs_trace(
+                result,
+                struct_logger_result,
+            )));
+        }
+
+        // We had only one copy of Arc this arc is already dropped it's safe to unwrap
+        let trace = Arc::try_unwrap(call_tracer_result)
+            .unwrap()
+            .take()
+            .unwrap_or_default();
+        let diagnostics = collect_diagnostics(&result, &trace);
         let (output, revert_reason) = match result.result {
             ExecutionResult::Success { output, .. } => (output, None),
             ExecutionResult::Revert { output } => (vec![], Some(output.to_string())),
             ExecutionResult::Halt { reason } => {
-                return Err(Web3Error::SubmitTransactionError(
+                return Err(Web3Error::ExecutionFailed(
                     reason.to_string(),
-                    vec![],
+                    Box::new(diagnostics),
                 ))
             }
         };
-
-        // We had only one copy of Arc this arc is already dropped it's safe to unwrap
-        let trace = Arc::try_unwrap(call_tracer_result)
-            .unwrap()
-            .take()
-            .unwrap_or_default();
         let call = Call::new_high_level(
             tx.common_data.fee.gas_limit.as_u32(),
             result.statistics.gas_used,
@@ -208,13 +378,8 @@ impl DebugNamespace {
             revert_reason,
             trace,
         );
-
-        let block_diff = self
-            .state
-            .last_sealed_miniblock
-            .diff_with_block_args(&block_args);
         method_latency.observe(block_diff);
-        Ok(call.into())
+        Ok(DebugTrace::Call(call.into()))
     }
 
     fn shared_args(&self) -> TxSharedArgs {
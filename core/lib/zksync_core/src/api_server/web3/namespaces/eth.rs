@@ -1,33 +1,41 @@
+use zksync_dal::blocks_web3_dal::BLOCK_GAS_LIMIT;
 use zksync_system_constants::DEFAULT_L2_TX_GAS_PER_PUBDATA_BYTE;
 use zksync_types::{
     api::{
-        BlockId, BlockNumber, GetLogsFilter, Transaction, TransactionId, TransactionReceipt,
-        TransactionVariant,
+        AccountProof, BlockId, BlockNumber, GetLogsFilter, StateOverride, StorageProof,
+        Transaction, TransactionId, TransactionReceipt, TransactionVariant,
     },
+    get_code_key, get_nonce_key,
     l2::{L2Tx, TransactionType},
     transaction_request::CallRequest,
-    utils::decompose_full_nonce,
+    utils::{decompose_full_nonce, storage_key_for_eth_balance},
     web3::{
         self,
         types::{FeeHistory, SyncInfo, SyncState},
     },
     AccountTreeId, Bytes, MiniblockNumber, StorageKey, H256, L2_ETH_TOKEN_ADDRESS, U256,
 };
-use zksync_utils::u256_to_h256;
+use zksync_utils::{h256_to_u256, u256_to_h256};
 use zksync_web3_decl::{
     error::Web3Error,
     types::{Address, Block, Filter, FilterChanges, Log, U64},
 };
 
-use crate::api_server::web3::{
-    backend_jsonrpsee::internal_error,
-    metrics::{BlockCallObserver, API_METRICS},
-    state::RpcState,
-    TypedFilter,
+use crate::api_server::{
+    execution_sandbox::validate_state_override,
+    tree::TreeApiClient,
+    web3::{
+        backend_jsonrpsee::internal_error,
+        metrics::{BlockCallObserver, API_METRICS},
+        state::RpcState,
+        TypedFilter,
+    },
 };
 
 pub const EVENT_TOPIC_NUMBER_LIMIT: usize = 4;
 pub const PROTOCOL_VERSION: &str = "zks/1";
+/// Maximum number of reward percentiles accepted by `eth_feeHistory`.
+const MAX_REWARD_PERCENTILES: usize = 100;
 
 #[derive(Debug)]
 pub struct EthNamespace {
@@ -61,14 +69,19 @@ impl EthNamespace {
         Ok(block_number.0.into())
     }
 
-    #[tracing::instrument(skip(self, request, block_id))]
+    #[tracing::instrument(skip(self, request, block_id, state_override))]
     pub async fn call_impl(
         &self,
         request: CallRequest,
         block_id: Option<BlockId>,
+        state_override: Option<StateOverride>,
     ) -> Result<Bytes, Web3Error> {
         const METHOD_NAME: &str = "call";
 
+        if let Some(state_override) = &state_override {
+            validate_state_override(state_override)
+                .map_err(|err| Web3Error::InvalidStateOverride(err.to_string()))?;
+        }
         let block_id = block_id.unwrap_or(BlockId::Number(BlockNumber::Pending));
         let method_latency = API_METRICS.start_block_call(METHOD_NAME, block_id);
         let mut connection = self
@@ -86,7 +99,11 @@ impl EthNamespace {
 
         let tx = L2Tx::from_request(request.into(), self.state.api_config.max_tx_size)?;
 
-        let call_result = self.state.tx_sender.eth_call(block_args, tx).await;
+        let call_result = self
+            .state
+            .tx_sender
+            .eth_call(block_args, tx, state_override)
+            .await;
         let res_bytes = call_result.map_err(|err| err.into_web3_error(METHOD_NAME))?;
 
         let block_diff = self
@@ -97,16 +114,30 @@ impl EthNamespace {
         Ok(res_bytes.into())
     }
 
-    #[tracing::instrument(skip(self, request, _block))]
+    #[tracing::instrument(skip(self, request, _block, state_override))]
     pub async fn estimate_gas_impl(
         &self,
         request: CallRequest,
         _block: Option<BlockNumber>,
+        state_override: Option<StateOverride>,
     ) -> Result<U256, Web3Error> {
         const METHOD_NAME: &str = "estimate_gas";
 
+        if let Some(state_override) = &state_override {
+            validate_state_override(state_override)
+                .map_err(|err| Web3Error::InvalidStateOverride(err.to_string()))?;
+        }
         let method_latency = API_METRICS.start_call(METHOD_NAME);
         let mut request_with_gas_per_pubdata_overridden = request;
+        // The overridden nonce of the sender takes precedence over the one in the storage.
+        if request_with_gas_per_pubdata_overridden.nonce.is_none() {
+            let from = request_with_gas_per_pubdata_overridden
+                .from
+                .unwrap_or_default();
+            request_with_gas_per_pubdata_overridden.nonce = state_override
+                .as_ref()
+                .and_then(|overrides| overrides.get(&from)?.nonce);
+        }
         self.state
             .set_nonce_for_call_request(&mut request_with_gas_per_pubdata_overridden)
             .await?;
@@ -147,7 +178,12 @@ impl EthNamespace {
         let fee = self
             .state
             .tx_sender
-            .get_txs_fee_in_wei(tx.into(), scale_factor, acceptable_overestimation)
+            .get_txs_fee_in_wei(
+                tx.into(),
+                scale_factor,
+                acceptable_overestimation,
+                state_override,
+            )
             .await
             .map_err(|err| err.into_web3_error(METHOD_NAME))?;
         method_latency.observe();
@@ -272,10 +308,22 @@ impl EthNamespace {
         let method_latency = API_METRICS.start_block_call(method_name, block_id);
 
         self.state.start_info.ensure_not_pruned(block_id)?;
+        let cached_number = match block_id {
+            BlockId::Number(BlockNumber::Number(number)) => Some(MiniblockNumber(number.as_u32())),
+            _ => None,
+        };
+        let response_cache = &self.state.response_cache;
+        if let Some(number) = cached_number {
+            if let Some(block) = response_cache.get_block(number, full_transactions) {
+                self.report_latency_with_block_id(method_latency, number);
+                return Ok(Some(block));
+            }
+        }
+
         let block = self
             .state
             .connection_pool
-            .access_storage_tagged("api")
+            .access_storage_for_reads_tagged("api")
             .await
             .map_err(|err| internal_error(method_name, err))?
             .blocks_web3_dal()
@@ -287,6 +335,12 @@ impl EthNamespace {
             .await
             .map_err(|err| internal_error(method_name, err));
 
+        if let (Some(_), Ok(Some(block))) = (cached_number, &block) {
+            let pool = &self.state.connection_pool;
+            response_cache
+                .insert_block(pool, full_transactions, block)
+                .await;
+        }
         if let Ok(Some(block)) = &block {
             let block_number = MiniblockNumber(block.number.as_u32());
             self.report_latency_with_block_id(method_latency, block_number);
@@ -309,7 +363,7 @@ impl EthNamespace {
         let tx_count = self
             .state
             .connection_pool
-            .access_storage_tagged("api")
+            .access_storage_for_reads_tagged("api")
             .await
             .map_err(|err| internal_error(METHOD_NAME, err))?
             .blocks_web3_dal()
@@ -519,7 +573,7 @@ impl EthNamespace {
         let mut transaction = self
             .state
             .connection_pool
-            .access_storage_tagged("api")
+            .access_storage_for_reads_tagged("api")
             .await
             .unwrap()
             .transactions_web3_dal()
@@ -562,6 +616,12 @@ impl EthNamespace {
         const METHOD_NAME: &str = "get_transaction_receipt";
 
         let method_latency = API_METRICS.start_call(METHOD_NAME);
+        let response_cache = &self.state.response_cache;
+        if let Some(receipt) = response_cache.get_receipt(hash) {
+            method_latency.observe();
+            return Ok(Some(receipt));
+        }
+
         let receipts = self
             .state
             .connection_pool
@@ -572,10 +632,14 @@ impl EthNamespace {
             .get_transaction_receipts(&[hash])
             .await
             .map_err(|err| internal_error(METHOD_NAME, err))?;
+        let receipt = receipts.into_iter().next();
+        if let Some(receipt) = &receipt {
+            let pool = &self.state.connection_pool;
+            response_cache.insert_receipt(pool, receipt).await;
+        }
 
         method_latency.observe();
-
-        Ok(receipts.into_iter().next())
+        Ok(receipt)
     }
 
     #[tracing::instrument(skip(self))]
@@ -697,12 +761,16 @@ impl EthNamespace {
         PROTOCOL_VERSION.to_string()
     }
 
-    #[tracing::instrument(skip(self, tx_bytes))]
+    #[tracing::instrument(
+        skip(self, tx_bytes),
+        fields(component = "api", tx_hash = tracing::field::Empty)
+    )]
     pub async fn send_raw_transaction_impl(&self, tx_bytes: Bytes) -> Result<H256, Web3Error> {
         const METHOD_NAME: &str = "send_raw_transaction";
 
         let method_latency = API_METRICS.start_call(METHOD_NAME);
         let (mut tx, hash) = self.state.parse_transaction_bytes(&tx_bytes.0)?;
+        tracing::Span::current().record("tx_hash", tracing::field::debug(&hash));
         tx.set_input(tx_bytes.0, hash);
 
         let submit_result = self.state.tx_sender.submit_tx(tx).await;
@@ -749,6 +817,7 @@ impl EthNamespace {
     ) -> Result<FeeHistory, Web3Error> {
         const METHOD_NAME: &str = "fee_history";
 
+        validate_reward_percentiles(&reward_percentiles)?;
         let method_latency =
             API_METRICS.start_block_call(METHOD_NAME, BlockId::Number(newest_block));
         // Limit `block_count`.
@@ -777,13 +846,35 @@ impl EthNamespace {
         base_fee_per_gas.reverse();
 
         let oldest_block = newest_miniblock.0 + 1 - base_fee_per_gas.len() as u32;
-        // We do not store gas used ratio for blocks, returns array of zeroes as a placeholder.
-        let gas_used_ratio = vec![0.0; base_fee_per_gas.len()];
-        // Effective priority gas price is currently 0.
-        let reward = Some(vec![
-            vec![U256::zero(); reward_percentiles.len()];
-            base_fee_per_gas.len()
-        ]);
+        let transactions = connection
+            .blocks_web3_dal()
+            .get_fee_history_transactions(MiniblockNumber(oldest_block), newest_miniblock)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+
+        let mut gas_used_ratio = Vec::with_capacity(base_fee_per_gas.len());
+        let mut reward = Vec::with_capacity(base_fee_per_gas.len());
+        let mut transactions = transactions.into_iter().peekable();
+        for (i, &base_fee) in base_fee_per_gas.iter().enumerate() {
+            let miniblock_number = MiniblockNumber(oldest_block + i as u32);
+            let mut tx_rewards = vec![];
+            while let Some(tx) = transactions.next_if(|tx| tx.miniblock_number == miniblock_number)
+            {
+                let tx_reward = tx
+                    .effective_gas_price
+                    .map_or_else(U256::zero, |price| price.saturating_sub(base_fee));
+                tx_rewards.push((tx_reward, tx.gas_used));
+            }
+            let gas_used = tx_rewards
+                .iter()
+                .fold(U256::zero(), |acc, (_, tx_gas_used)| acc + tx_gas_used);
+            let block_gas_limit = U256::from(BLOCK_GAS_LIMIT);
+            gas_used_ratio
+                .push(gas_used.min(block_gas_limit).as_u64() as f64 / f64::from(BLOCK_GAS_LIMIT));
+            reward.push(calculate_rewards(tx_rewards, gas_used, &reward_percentiles));
+        }
+        // Like in Geth, rewards are omitted if no percentiles are requested.
+        let reward = (!reward_percentiles.is_empty()).then_some(reward);
 
         // `base_fee_per_gas` for next miniblock cannot be calculated, appending last fee as a placeholder.
         base_fee_per_gas.push(*base_fee_per_gas.last().unwrap());
@@ -797,6 +888,106 @@ impl EthNamespace {
         })
     }
 
+    /// Returns proofs for the account fields and the specified storage slots of the account. Proofs are provided
+    /// for the state after the L1 batch containing the requested block, since the Merkle tree is only updated
+    /// once per L1 batch.
+    #[tracing::instrument(skip(self, keys))]
+    pub async fn get_proof_impl(
+        &self,
+        address: Address,
+        keys: Vec<H256>,
+        block_id: Option<BlockId>,
+    ) -> Result<AccountProof, Web3Error> {
+        const METHOD_NAME: &str = "get_proof";
+
+        let block_id = block_id.unwrap_or(BlockId::Number(BlockNumber::Latest));
+        let method_latency = API_METRICS.start_block_call(METHOD_NAME, block_id);
+        let tree_api = self
+            .state
+            .tree_api
+            .as_ref()
+            .ok_or(Web3Error::TreeApiUnavailable)?;
+        let mut connection = self
+            .state
+            .connection_pool
+            .access_storage_tagged("api")
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        let block_number = self
+            .state
+            .resolve_block(&mut connection, block_id, METHOD_NAME)
+            .await?;
+        let resolved_l1_batch = connection
+            .storage_web3_dal()
+            .resolve_l1_batch_number_of_miniblock(block_number)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        let l1_batch_number =
+            resolved_l1_batch
+                .miniblock_l1_batch
+                .ok_or(Web3Error::TreeDataUnavailable(
+                    resolved_l1_batch.pending_l1_batch,
+                ))?;
+        self.state.start_info.ensure_not_pruned(l1_batch_number)?;
+        let storage_hash = connection
+            .blocks_dal()
+            .get_l1_batch_state_root(l1_batch_number)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?
+            .ok_or(Web3Error::TreeDataUnavailable(l1_batch_number))?;
+        drop(connection);
+
+        const ACCOUNT_KEY_COUNT: usize = 3;
+
+        let account_keys: [_; ACCOUNT_KEY_COUNT] = [
+            get_nonce_key(&address),
+            storage_key_for_eth_balance(&address),
+            get_code_key(&address),
+        ];
+        let storage_keys = keys
+            .into_iter()
+            .map(|key| StorageKey::new(AccountTreeId::new(address), key));
+        let all_keys: Vec<_> = account_keys.into_iter().chain(storage_keys).collect();
+        let hashed_keys = all_keys.iter().map(StorageKey::hashed_key_u256).collect();
+        let entries = tree_api
+            .get_proofs(l1_batch_number, hashed_keys)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        if entries.len() != all_keys.len() {
+            let err = format!(
+                "unexpected number of proofs: expected {}, got {}",
+                all_keys.len(),
+                entries.len()
+            );
+            return Err(internal_error(METHOD_NAME, err));
+        }
+
+        let mut account_proof: Vec<_> = entries
+            .into_iter()
+            .zip(&all_keys)
+            .map(|(entry, key)| StorageProof {
+                key: *key.key(),
+                proof: entry.merkle_path,
+                value: entry.value,
+                index: entry.index,
+            })
+            .collect();
+        let storage_proof = account_proof.split_off(ACCOUNT_KEY_COUNT);
+        let (nonce, _) = decompose_full_nonce(h256_to_u256(account_proof[0].value));
+
+        self.report_latency_with_block_id(method_latency, block_number);
+        Ok(AccountProof {
+            address,
+            l1_batch_number,
+            storage_hash,
+            nonce,
+            balance: h256_to_u256(account_proof[1].value),
+            code_hash: account_proof[2].value,
+            account_proof,
+            storage_proof,
+        })
+    }
+
     #[tracing::instrument(skip(self, typed_filter))]
     async fn filter_changes(
         &self,
@@ -965,3 +1156,86 @@ impl EthNamespace {
     // - `compile_solidity`.
     // - `compile_serpent`.
 }
+
+fn validate_reward_percentiles(percentiles: &[f32]) -> Result<(), Web3Error> {
+    if percentiles.len() > MAX_REWARD_PERCENTILES {
+        return Err(Web3Error::InvalidFeeParams(format!(
+            "too many reward percentiles ({}); the maximum is {MAX_REWARD_PERCENTILES}",
+            percentiles.len()
+        )));
+    }
+    let mut prev_percentile = 0.0;
+    for &percentile in percentiles {
+        if !(prev_percentile..=100.0).contains(&percentile) {
+            return Err(Web3Error::InvalidFeeParams(format!(
+                "invalid reward percentile {percentile}; percentiles must be monotonically increasing \
+                 and lie in [0, 100]"
+            )));
+        }
+        prev_percentile = percentile;
+    }
+    Ok(())
+}
+
+/// Calculates priority fee rewards for a miniblock in the same way as Geth: transactions are sorted by their rewards,
+/// and for each percentile, the reward of the transaction at which the cumulative gas used reaches the percentile
+/// of the total gas used in the miniblock is returned.
+fn calculate_rewards(
+    mut tx_rewards: Vec<(U256, U256)>,
+    gas_used: U256,
+    percentiles: &[f32],
+) -> Vec<U256> {
+    if tx_rewards.is_empty() {
+        return vec![U256::zero(); percentiles.len()];
+    }
+    tx_rewards.sort_unstable_by_key(|(reward, _)| *reward);
+
+    let mut tx_index = 0;
+    let mut cumulative_gas_used = tx_rewards[0].1;
+    percentiles
+        .iter()
+        .map(|&percentile| {
+            // Percentiles are converted to basis points so that integer arithmetic can be used.
+            let basis_points = U256::from((f64::from(percentile) * 100.0) as u64);
+            let threshold = gas_used * basis_points / U256::from(10_000);
+            while cumulative_gas_used < threshold && tx_index < tx_rewards.len() - 1 {
+                tx_index += 1;
+                cumulative_gas_used += tx_rewards[tx_index].1;
+            }
+            tx_rewards[tx_index].0
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn calculating_rewards() {
+        let percentiles = [0.0, 10.0, 50.0, 90.0, 100.0];
+        let rewards = calculate_rewards(vec![], U256::zero(), &percentiles);
+        assert_eq!(rewards, [U256::zero(); 5]);
+
+        let tx_rewards = vec![
+            (U256::from(30), U256::from(100)),
+            (U256::from(10), U256::from(500)),
+            (U256::from(20), U256::from(400)),
+        ];
+        let rewards = calculate_rewards(tx_rewards, U256::from(1_000), &percentiles);
+        let expected_rewards = [10, 10, 10, 20, 30].map(U256::from);
+        assert_eq!(rewards, expected_rewards);
+    }
+
+    #[test]
+    fn validating_reward_percentiles() {
+        validate_reward_percentiles(&[]).unwrap();
+        validate_reward_percentiles(&[0.0, 25.5, 25.5, 100.0]).unwrap();
+
+        for invalid_percentiles in [&[-1.0][..], &[101.0], &[50.0, 10.0], &[f32::NAN]] {
+            let err = validate_reward_percentiles(invalid_percentiles).unwrap_err();
+            assert!(matches!(err, Web3Error::InvalidFeeParams(_)), "{err:?}");
+        }
+        validate_reward_percentiles(&[1.0; MAX_REWARD_PERCENTILES + 1]).unwrap_err();
+    }
+}
@@ -6,10 +6,14 @@ mod en;
 pub(crate) mod eth;
 mod net;
 mod snapshots;
+mod trace;
+mod txpool;
 mod web3;
 mod zks;
+pub(crate) mod zks_admin;
 
 pub use self::{
     debug::DebugNamespace, en::EnNamespace, eth::EthNamespace, net::NetNamespace,
-    snapshots::SnapshotsNamespace, web3::Web3Namespace, zks::ZksNamespace,
+    snapshots::SnapshotsNamespace, trace::TraceNamespace, txpool::TxpoolNamespace,
+    web3::Web3Namespace, zks::ZksNamespace, zks_admin::ZksAdminNamespace,
 };
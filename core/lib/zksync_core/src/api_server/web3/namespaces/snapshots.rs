@@ -59,21 +59,26 @@ impl SnapshotsNamespace {
             return Ok(None);
         };
 
-        let snapshot_files = snapshot_metadata.storage_logs_filepaths;
-        let is_complete = snapshot_files.iter().all(Option::is_some);
-        if !is_complete {
+        if !snapshot_metadata.is_complete() {
             // We don't return incomplete snapshots via API.
             method_latency.observe();
             return Ok(None);
         }
 
-        let chunks = snapshot_files
-            .into_iter()
+        let checksums = &snapshot_metadata.storage_logs_checksums;
+        let chunks = snapshot_metadata
+            .storage_logs_filepaths
+            .iter()
             .enumerate()
             .filter_map(|(chunk_id, filepath)| {
+                let chunk_id = chunk_id as u64;
+                let source_l1_batch_number = snapshot_metadata.storage_logs_chunk_source(chunk_id);
                 Some(SnapshotStorageLogsChunkMetadata {
-                    chunk_id: chunk_id as u64,
-                    filepath: filepath?,
+                    chunk_id,
+                    filepath: filepath.clone()?,
+                    checksum: checksums.get(chunk_id as usize).copied().flatten(),
+                    l1_batch_number: (source_l1_batch_number != l1_batch_number)
+                        .then_some(source_l1_batch_number),
                 })
             })
             .collect();
@@ -98,11 +103,16 @@ impl SnapshotsNamespace {
 
         method_latency.observe();
         Ok(Some(SnapshotHeader {
+            version: snapshot_metadata.version as u16,
             l1_batch_number: snapshot_metadata.l1_batch_number,
             miniblock_number,
             last_l1_batch_with_metadata: l1_batch_with_metadata,
             storage_logs_chunks: chunks,
             factory_deps_filepath: snapshot_metadata.factory_deps_filepath,
+            factory_deps_checksum: snapshot_metadata.factory_deps_checksum,
+            tokens_filepath: snapshot_metadata.tokens_filepath,
+            tokens_checksum: snapshot_metadata.tokens_checksum,
+            base_l1_batch_number: snapshot_metadata.base_l1_batch_number,
         }))
     }
 }
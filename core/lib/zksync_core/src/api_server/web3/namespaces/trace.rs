@@ -0,0 +1,138 @@
+use zksync_dal::StorageProcessor;
+use zksync_types::{
+    api::{
+        trace::{LocalizedTrace, TraceFilter},
+        BlockId, BlockNumber,
+    },
+    H256,
+};
+use zksync_web3_decl::error::Web3Error;
+
+use crate::api_server::web3::{
+    backend_jsonrpsee::internal_error, metrics::API_METRICS, state::RpcState,
+};
+
+/// `trace` namespace exposing call traces of executed transactions in the OpenEthereum-compatible format.
+/// Unlike `debug` methods, traces are flat; they are read from the stored call traces and are never re-executed.
+#[derive(Debug, Clone)]
+pub struct TraceNamespace {
+    state: RpcState,
+}
+
+impl TraceNamespace {
+    pub fn new(state: RpcState) -> Self {
+        Self { state }
+    }
+
+    async fn access_storage(
+        &self,
+        method_name: &'static str,
+    ) -> Result<StorageProcessor<'_>, Web3Error> {
+        self.state
+            .connection_pool
+            .access_storage_tagged("api")
+            .await
+            .map_err(|err| internal_error(method_name, err))
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn trace_block_impl(
+        &self,
+        block: BlockNumber,
+    ) -> Result<Option<Vec<LocalizedTrace>>, Web3Error> {
+        const METHOD_NAME: &str = "trace_block";
+
+        let block_id = BlockId::Number(block);
+        let method_latency = API_METRICS.start_block_call(METHOD_NAME, block_id);
+        let mut storage = self.access_storage(METHOD_NAME).await?;
+        let block_number = match self
+            .state
+            .resolve_block(&mut storage, block_id, METHOD_NAME)
+            .await
+        {
+            Ok(number) => number,
+            Err(Web3Error::NoBlock) => return Ok(None),
+            Err(err) => return Err(err),
+        };
+        let call_traces = storage
+            .blocks_web3_dal()
+            .get_traces_for_miniblock_range(block_number, block_number, i32::MAX as usize)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        drop(storage);
+
+        let traces = call_traces
+            .into_iter()
+            .flat_map(|(location, call)| LocalizedTrace::flatten(call, &location))
+            .collect();
+        let block_diff = self.state.last_sealed_miniblock.diff(block_number);
+        method_latency.observe(block_diff);
+        Ok(Some(traces))
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn trace_transaction_impl(
+        &self,
+        tx_hash: H256,
+    ) -> Result<Option<Vec<LocalizedTrace>>, Web3Error> {
+        const METHOD_NAME: &str = "trace_transaction";
+
+        let method_latency = API_METRICS.start_call(METHOD_NAME);
+        let mut storage = self.access_storage(METHOD_NAME).await?;
+        let call_trace = storage
+            .transactions_dal()
+            .get_call_trace_with_location(tx_hash)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        drop(storage);
+
+        let traces = call_trace.map(|(location, call)| LocalizedTrace::flatten(call, &location));
+        method_latency.observe();
+        Ok(traces)
+    }
+
+    /// Returns traces matching the filter. Traces are read for at most `req_entities_limit` transactions;
+    /// if the block range contains more traced transactions, an error is returned.
+    #[tracing::instrument(skip(self, filter))]
+    pub async fn trace_filter_impl(
+        &self,
+        filter: TraceFilter,
+    ) -> Result<Vec<LocalizedTrace>, Web3Error> {
+        const METHOD_NAME: &str = "trace_filter";
+
+        let method_latency = API_METRICS.start_call(METHOD_NAME);
+        let from_block = self
+            .state
+            .resolve_filter_block_number(filter.from_block)
+            .await?;
+        let to_block = self
+            .state
+            .resolve_filter_block_number(filter.to_block)
+            .await?;
+        self.state
+            .start_info
+            .ensure_not_pruned(BlockId::Number(BlockNumber::Number(from_block.0.into())))?;
+
+        let limit = self.state.api_config.req_entities_limit;
+        let mut storage = self.access_storage(METHOD_NAME).await?;
+        let call_traces = storage
+            .blocks_web3_dal()
+            .get_traces_for_miniblock_range(from_block, to_block, limit + 1)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        drop(storage);
+        if call_traces.len() > limit {
+            return Err(Web3Error::TraceLimitExceeded(limit));
+        }
+
+        let traces = call_traces
+            .into_iter()
+            .flat_map(|(location, call)| LocalizedTrace::flatten(call, &location))
+            .filter(|trace| filter.matches(trace))
+            .skip(filter.after.unwrap_or(0))
+            .take(filter.count.unwrap_or(usize::MAX))
+            .collect();
+        method_latency.observe();
+        Ok(traces)
+    }
+}
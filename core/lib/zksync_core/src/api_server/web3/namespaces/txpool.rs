@@ -0,0 +1,166 @@
+use std::collections::HashMap;
+
+use zksync_dal::StorageProcessor;
+use zksync_types::{
+    api::{TxpoolContent, TxpoolStatus},
+    Address, Nonce,
+};
+use zksync_web3_decl::error::Web3Error;
+
+use crate::api_server::web3::{
+    backend_jsonrpsee::internal_error, metrics::API_METRICS, state::RpcState,
+};
+
+/// `txpool` namespace providing introspection of the mempool, i.e. L2 transactions that are accepted by the server,
+/// but are not included into a miniblock yet.
+#[derive(Debug, Clone)]
+pub struct TxpoolNamespace {
+    state: RpcState,
+}
+
+impl TxpoolNamespace {
+    pub fn new(state: RpcState) -> Self {
+        Self { state }
+    }
+
+    async fn access_storage(
+        &self,
+        method_name: &'static str,
+    ) -> Result<StorageProcessor<'_>, Web3Error> {
+        self.state
+            .connection_pool
+            .access_storage_tagged("api")
+            .await
+            .map_err(|err| internal_error(method_name, err))
+    }
+
+    /// Returns up to `req_entities_limit` mempool transactions.
+    #[tracing::instrument(skip(self))]
+    pub async fn content_impl(&self) -> Result<TxpoolContent, Web3Error> {
+        const METHOD_NAME: &str = "txpool_content";
+
+        let method_latency = API_METRICS.start_call(METHOD_NAME);
+        let mut storage = self.access_storage(METHOD_NAME).await?;
+        let transactions = storage
+            .transactions_web3_dal()
+            .get_mempool_transactions(
+                self.state.api_config.req_entities_limit,
+                self.state.api_config.l2_chain_id,
+            )
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        let mut addresses: Vec<_> = transactions
+            .iter()
+            .map(|tx| tx.from.unwrap_or_default())
+            .collect();
+        addresses.dedup();
+        let account_nonces = storage
+            .storage_web3_dal()
+            .get_nonces_for_addresses(&addresses)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        drop(storage);
+
+        let transactions = transactions.into_iter().map(|tx| {
+            let nonce = Nonce(tx.nonce.as_u32());
+            (tx.from.unwrap_or_default(), nonce, tx)
+        });
+        let (pending, queued) = split_pending_and_queued(transactions, &account_nonces);
+        let mut content = TxpoolContent::default();
+        for (address, nonce, tx) in pending {
+            content
+                .pending
+                .entry(address)
+                .or_default()
+                .insert(nonce.0, tx);
+        }
+        for (address, nonce, tx) in queued {
+            content
+                .queued
+                .entry(address)
+                .or_default()
+                .insert(nonce.0, tx);
+        }
+        method_latency.observe();
+        Ok(content)
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn status_impl(&self) -> Result<TxpoolStatus, Web3Error> {
+        const METHOD_NAME: &str = "txpool_status";
+
+        let method_latency = API_METRICS.start_call(METHOD_NAME);
+        let mut storage = self.access_storage(METHOD_NAME).await?;
+        let mut nonces = storage
+            .transactions_web3_dal()
+            .get_mempool_nonces()
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        nonces.sort_unstable();
+        let mut addresses: Vec<_> = nonces.iter().map(|&(address, _)| address).collect();
+        addresses.dedup();
+        let account_nonces = storage
+            .storage_web3_dal()
+            .get_nonces_for_addresses(&addresses)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        drop(storage);
+
+        let nonces = nonces
+            .into_iter()
+            .map(|(address, nonce)| (address, nonce, ()));
+        let (pending, queued) = split_pending_and_queued(nonces, &account_nonces);
+        method_latency.observe();
+        Ok(TxpoolStatus {
+            pending: pending.len().into(),
+            queued: queued.len().into(),
+        })
+    }
+}
+
+/// Splits mempool transactions into pending ones (executable without nonce gaps after the stored account nonce)
+/// and queued ones. Transactions of each initiator must be sorted by nonce.
+fn split_pending_and_queued<T>(
+    transactions: impl IntoIterator<Item = (Address, Nonce, T)>,
+    account_nonces: &HashMap<Address, Nonce>,
+) -> (Vec<(Address, Nonce, T)>, Vec<(Address, Nonce, T)>) {
+    let mut next_nonces = HashMap::new();
+    let (mut pending, mut queued) = (vec![], vec![]);
+    for (address, nonce, tx) in transactions {
+        let next_nonce = next_nonces
+            .entry(address)
+            .or_insert_with(|| account_nonces.get(&address).copied().unwrap_or(Nonce(0)));
+        if nonce == *next_nonce {
+            *next_nonce = next_nonce.next();
+            pending.push((address, nonce, tx));
+        } else {
+            queued.push((address, nonce, tx));
+        }
+    }
+    (pending, queued)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splitting_pending_and_queued_transactions() {
+        let alice = Address::repeat_byte(1);
+        let bob = Address::repeat_byte(2);
+        let account_nonces = HashMap::from([(alice, Nonce(3))]);
+        let transactions = [
+            (alice, Nonce(3), "a3"),
+            (bob, Nonce(0), "b0"),
+            (alice, Nonce(4), "a4"),
+            (alice, Nonce(6), "a6"),
+            (bob, Nonce(2), "b2"),
+        ];
+
+        let (pending, queued) = split_pending_and_queued(transactions, &account_nonces);
+        let pending: Vec<_> = pending.into_iter().map(|(.., tx)| tx).collect();
+        let queued: Vec<_> = queued.into_iter().map(|(.., tx)| tx).collect();
+        assert_eq!(pending, ["a3", "b0", "a4"]);
+        assert_eq!(queued, ["a6", "b2"]);
+    }
+}
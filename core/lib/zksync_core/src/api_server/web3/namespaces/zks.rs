@@ -1,15 +1,24 @@
 use std::{collections::HashMap, convert::TryInto};
 
 use zksync_dal::StorageProcessor;
+use zksync_l1_contract_interface::{
+    i_executor::{methods::serialize_l1_batch_proof, structures::StoredBatchInfo},
+    Tokenizable,
+};
 use zksync_mini_merkle_tree::MiniMerkleTree;
+use zksync_object_store::ObjectStoreError;
+use zksync_prover_interface::outputs::L1BatchProofForL1;
 use zksync_system_constants::DEFAULT_L2_TX_GAS_PER_PUBDATA_BYTE;
 use zksync_types::{
     api::{
-        BlockDetails, BridgeAddresses, GetLogsFilter, L1BatchDetails, L2ToL1LogProof, Proof,
-        ProtocolVersion, StorageProof, TransactionDetails,
+        en::{SyncComponent, SyncStall, SyncStatus},
+        BlockDetails, BlockId, BridgeAddresses, GetLogsFilter, L1BatchDetails, L1BatchProof,
+        L2ToL1LogProof, PriorityOpInfo, Proof, ProtocolVersion, StorageProof, TransactionDetails,
+        TransactionExecutionInfo,
     },
+    ethabi,
     fee::Fee,
-    fee_model::FeeParams,
+    fee_model::{BaseTokenConversionRatio, FeeParams},
     l1::L1Tx,
     l2::L2Tx,
     l2_to_l1_log::L2ToL1Log,
@@ -51,6 +60,19 @@ impl ZksNamespace {
             .map_err(|err| internal_error(method_name, err))
     }
 
+    /// Accesses storage for read-only queries that tolerate slightly stale data. Such queries may be routed
+    /// to a read replica of the database.
+    async fn access_storage_for_reads(
+        &self,
+        method_name: &'static str,
+    ) -> Result<StorageProcessor<'_>, Web3Error> {
+        self.state
+            .connection_pool
+            .access_storage_for_reads_tagged("api")
+            .await
+            .map_err(|err| internal_error(method_name, err))
+    }
+
     #[tracing::instrument(skip(self, request))]
     pub async fn estimate_fee_impl(&self, request: CallRequest) -> Result<Fee, Web3Error> {
         const METHOD_NAME: &str = "estimate_fee";
@@ -118,7 +140,7 @@ impl ZksNamespace {
 
         self.state
             .tx_sender
-            .get_txs_fee_in_wei(tx, scale_factor, acceptable_overestimation)
+            .get_txs_fee_in_wei(tx, scale_factor, acceptable_overestimation, None)
             .await
             .map_err(|err| err.into_web3_error(method_name))
     }
@@ -412,7 +434,7 @@ impl ZksNamespace {
 
         let method_latency = API_METRICS.start_call(METHOD_NAME);
         self.state.start_info.ensure_not_pruned(batch)?;
-        let mut storage = self.access_storage(METHOD_NAME).await?;
+        let mut storage = self.access_storage_for_reads(METHOD_NAME).await?;
         let minmax = storage
             .blocks_web3_dal()
             .get_miniblock_range_of_l1_batch(batch)
@@ -433,7 +455,7 @@ impl ZksNamespace {
 
         let method_latency = API_METRICS.start_call(METHOD_NAME);
         self.state.start_info.ensure_not_pruned(block_number)?;
-        let mut storage = self.access_storage(METHOD_NAME).await?;
+        let mut storage = self.access_storage_for_reads(METHOD_NAME).await?;
         let block_details = storage
             .blocks_web3_dal()
             .get_block_details(block_number)
@@ -453,7 +475,7 @@ impl ZksNamespace {
 
         let method_latency = API_METRICS.start_call(METHOD_NAME);
         self.state.start_info.ensure_not_pruned(block_number)?;
-        let mut storage = self.access_storage(METHOD_NAME).await?;
+        let mut storage = self.access_storage_for_reads(METHOD_NAME).await?;
         let transactions = storage
             .transactions_web3_dal()
             .get_raw_miniblock_transactions(block_number)
@@ -472,7 +494,7 @@ impl ZksNamespace {
         const METHOD_NAME: &str = "get_transaction_details";
 
         let method_latency = API_METRICS.start_call(METHOD_NAME);
-        let mut storage = self.access_storage(METHOD_NAME).await?;
+        let mut storage = self.access_storage_for_reads(METHOD_NAME).await?;
         let mut tx_details = storage
             .transactions_web3_dal()
             .get_transaction_details(hash)
@@ -496,6 +518,24 @@ impl ZksNamespace {
         tx_details
     }
 
+    #[tracing::instrument(skip(self))]
+    pub async fn get_transaction_execution_info_impl(
+        &self,
+        hash: H256,
+    ) -> Result<Option<TransactionExecutionInfo>, Web3Error> {
+        const METHOD_NAME: &str = "get_transaction_execution_info";
+
+        let method_latency = API_METRICS.start_call(METHOD_NAME);
+        let mut storage = self.access_storage_for_reads(METHOD_NAME).await?;
+        let execution_info = storage
+            .transactions_web3_dal()
+            .get_transaction_execution_info(hash)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        method_latency.observe();
+        Ok(execution_info)
+    }
+
     #[tracing::instrument(skip(self))]
     pub async fn get_l1_batch_details_impl(
         &self,
@@ -505,15 +545,23 @@ impl ZksNamespace {
 
         let method_latency = API_METRICS.start_call(METHOD_NAME);
         self.state.start_info.ensure_not_pruned(batch_number)?;
-        let mut storage = self.access_storage(METHOD_NAME).await?;
+        if let Some(l1_batch) = self.state.response_cache.get_l1_batch_details(batch_number) {
+            method_latency.observe();
+            return Ok(Some(l1_batch));
+        }
+
+        let mut storage = self.access_storage_for_reads(METHOD_NAME).await?;
         let l1_batch = storage
             .blocks_web3_dal()
             .get_l1_batch_details(batch_number)
             .await
-            .map_err(|err| internal_error(METHOD_NAME, err));
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        if let Some(l1_batch) = &l1_batch {
+            self.state.response_cache.insert_l1_batch_details(l1_batch);
+        }
 
         method_latency.observe();
-        l1_batch
+        Ok(l1_batch)
     }
 
     #[tracing::instrument(skip(self))]
@@ -566,6 +614,22 @@ impl ZksNamespace {
         fee_model_params
     }
 
+    #[tracing::instrument(skip(self))]
+    pub fn get_base_token_ratio_impl(&self) -> BaseTokenConversionRatio {
+        const METHOD_NAME: &str = "get_base_token_ratio";
+
+        let method_latency = API_METRICS.start_call(METHOD_NAME);
+        let ratio = self
+            .state
+            .tx_sender
+            .0
+            .batch_fee_input_provider
+            .get_fee_model_params()
+            .conversion_ratio();
+        method_latency.observe();
+        ratio
+    }
+
     #[tracing::instrument(skip(self))]
     pub async fn get_protocol_version_impl(
         &self,
@@ -594,6 +658,36 @@ impl ZksNamespace {
         Ok(protocol_version)
     }
 
+    #[tracing::instrument(skip(self))]
+    pub async fn get_block_protocol_version_impl(
+        &self,
+        block_id: BlockId,
+    ) -> Result<Option<ProtocolVersion>, Web3Error> {
+        const METHOD_NAME: &str = "get_block_protocol_version";
+
+        let method_latency = API_METRICS.start_block_call(METHOD_NAME, block_id);
+        let mut storage = self.access_storage(METHOD_NAME).await?;
+        let block_args = self
+            .state
+            .resolve_block_args(&mut storage, block_id, METHOD_NAME)
+            .await?;
+        let version_id = block_args
+            .resolve_protocol_version(&mut storage)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        let protocol_version = storage
+            .protocol_versions_web3_dal()
+            .get_protocol_version_by_id(version_id as u16)
+            .await;
+
+        let block_diff = self
+            .state
+            .last_sealed_miniblock
+            .diff_with_block_args(&block_args);
+        method_latency.observe(block_diff);
+        Ok(protocol_version)
+    }
+
     #[tracing::instrument(skip_all)]
     pub async fn get_proofs_impl(
         &self,
@@ -631,4 +725,133 @@ impl ZksNamespace {
             storage_proof,
         })
     }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn get_batch_proof_impl(
+        &self,
+        batch_number: L1BatchNumber,
+    ) -> Result<Option<L1BatchProof>, Web3Error> {
+        const METHOD_NAME: &str = "get_batch_proof";
+
+        let method_latency = API_METRICS.start_call(METHOD_NAME);
+        self.state.start_info.ensure_not_pruned(batch_number)?;
+        let proof_store = self
+            .state
+            .proof_store
+            .as_ref()
+            .ok_or(Web3Error::ProofStoreUnavailable)?;
+
+        let mut storage = self.access_storage(METHOD_NAME).await?;
+        let l1_batch = storage
+            .blocks_dal()
+            .get_l1_batch_metadata(batch_number)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        let Some(l1_batch) = l1_batch else {
+            return Ok(None);
+        };
+        let prove_tx_hash = storage
+            .blocks_web3_dal()
+            .get_l1_batch_details(batch_number)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?
+            .and_then(|details| details.base.prove_tx_hash);
+        drop(storage);
+
+        let proof = match proof_store.get::<L1BatchProofForL1>(batch_number).await {
+            Ok(proof) => proof,
+            Err(ObjectStoreError::KeyNotFound(_)) => return Ok(None), // The proof is not generated yet
+            Err(err) => return Err(internal_error(METHOD_NAME, err)),
+        };
+
+        let stored_batch_info = ethabi::encode(&[StoredBatchInfo(&l1_batch).into_token()]);
+        let proof = L1BatchProof {
+            number: batch_number,
+            protocol_version: l1_batch.header.protocol_version,
+            root_hash: l1_batch.metadata.root_hash,
+            commitment: l1_batch.metadata.commitment,
+            stored_batch_info: stored_batch_info.into(),
+            aggregation_result_coords: proof
+                .aggregation_result_coords
+                .iter()
+                .map(|coord| H256(*coord))
+                .collect(),
+            serialized_proof: serialize_l1_batch_proof(&proof),
+            prove_tx_hash,
+        };
+        method_latency.observe();
+        Ok(Some(proof))
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn get_queued_priority_ops_impl(&self) -> Result<Vec<PriorityOpInfo>, Web3Error> {
+        const METHOD_NAME: &str = "get_queued_priority_ops";
+
+        let method_latency = API_METRICS.start_call(METHOD_NAME);
+        let mut storage = self.access_storage(METHOD_NAME).await?;
+        let priority_ops = storage
+            .transactions_web3_dal()
+            .get_queued_priority_ops(self.state.api_config.req_entities_limit)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        method_latency.observe();
+        Ok(priority_ops)
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn sync_status_impl(&self) -> Result<SyncStatus, Web3Error> {
+        const METHOD_NAME: &str = "sync_status";
+        /// Number of sealed L1 batches not processed by the Merkle tree after which the tree is reported as stalled.
+        const MAX_TREE_LAG: u32 = 10;
+
+        let Some(sync_state) = &self.state.sync_state else {
+            // Only external nodes sync with another node.
+            return Err(Web3Error::NotImplemented);
+        };
+        let method_latency = API_METRICS.start_call(METHOD_NAME);
+        let progress = sync_state.progress();
+
+        let mut storage = self.access_storage(METHOD_NAME).await?;
+        let mut blocks_dal = storage.blocks_dal();
+        let sealed_l1_batch = blocks_dal
+            .get_sealed_l1_batch_number()
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        let tree_l1_batch = blocks_dal
+            .get_last_l1_batch_number_with_metadata()
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        let last_verified_l1_batch = blocks_dal
+            .get_number_of_last_l1_batch_proven_on_eth()
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        drop(storage);
+
+        let tree_lag = match (sealed_l1_batch, tree_l1_batch) {
+            (Some(sealed), Some(tree)) => sealed.0.saturating_sub(tree.0),
+            (Some(sealed), None) => sealed.0 + 1,
+            (None, _) => 0,
+        };
+        let mut stalls = progress.stalls;
+        if tree_lag > MAX_TREE_LAG {
+            stalls.push(SyncStall {
+                component: SyncComponent::Tree,
+                reason: format!("Merkle tree lags behind by {tree_lag} sealed L1 batches"),
+            });
+        }
+
+        method_latency.observe();
+        Ok(SyncStatus {
+            is_synced: progress.is_synced,
+            main_node_miniblock: progress.main_node_block,
+            fetched_miniblock: progress.fetched_block,
+            applied_miniblock: progress.local_block,
+            sealed_l1_batch,
+            tree_l1_batch,
+            tree_lag,
+            last_verified_l1_batch,
+            estimated_secs_to_head: progress.estimated_time_to_head.map(|time| time.as_secs()),
+            stalls,
+        })
+    }
 }
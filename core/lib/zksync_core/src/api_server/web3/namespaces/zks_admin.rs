@@ -0,0 +1,82 @@
+use zksync_dal::{SqlxError, StorageProcessor};
+use zksync_types::api::InflightL1Tx;
+use zksync_web3_decl::error::Web3Error;
+
+use crate::api_server::web3::{
+    backend_jsonrpsee::internal_error, metrics::API_METRICS, state::RpcState,
+};
+
+/// Operator-facing `zks` methods providing introspection of the server components. These methods expose internal
+/// state of the server, so they are only available if the `zks_admin` namespace is explicitly enabled.
+#[derive(Debug, Clone)]
+pub struct ZksAdminNamespace {
+    state: RpcState,
+}
+
+impl ZksAdminNamespace {
+    pub fn new(state: RpcState) -> Self {
+        Self { state }
+    }
+
+    async fn access_storage(
+        &self,
+        method_name: &'static str,
+    ) -> Result<StorageProcessor<'_>, Web3Error> {
+        self.state
+            .connection_pool
+            .access_storage_tagged("api")
+            .await
+            .map_err(|err| internal_error(method_name, err))
+    }
+
+    /// Returns L1 transactions sent by the Ethereum sender that are not confirmed yet, together with
+    /// the information about their sent attempts.
+    #[tracing::instrument(skip(self))]
+    pub async fn get_inflight_l1_txs_impl(&self) -> Result<Vec<InflightL1Tx>, Web3Error> {
+        const METHOD_NAME: &str = "zks_getInflightL1Txs";
+
+        let method_latency = API_METRICS.start_call(METHOD_NAME);
+        let mut storage = self.access_storage(METHOD_NAME).await?;
+        let inflight_txs = get_inflight_l1_txs(&mut storage)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        method_latency.observe();
+        Ok(inflight_txs)
+    }
+}
+
+/// Loads L1 transactions sent by the Ethereum sender that are not confirmed yet, together with
+/// the information about their sent attempts.
+pub(crate) async fn get_inflight_l1_txs(
+    storage: &mut StorageProcessor<'_>,
+) -> Result<Vec<InflightL1Tx>, SqlxError> {
+    let txs = storage.eth_sender_dal().get_inflight_txs().await?;
+    let mut inflight_txs = Vec::with_capacity(txs.len());
+    for tx in txs {
+        let mut dal = storage.eth_sender_dal();
+        let attempts = dal.get_sent_attempts_count(tx.id).await?;
+        let first_sent_at_block = dal.get_block_number_on_first_sent_attempt(tx.id).await?;
+        let last_attempt = dal.get_last_sent_eth_tx(tx.id).await?;
+
+        inflight_txs.push(InflightL1Tx {
+            id: tx.id,
+            tx_type: tx.tx_type.as_str().to_owned(),
+            nonce: tx.nonce.0.into(),
+            created_at: tx.created_at_timestamp,
+            attempts,
+            first_sent_at_block,
+            last_tx_hash: last_attempt.as_ref().map(|attempt| attempt.tx_hash),
+            last_sent_at_block: last_attempt
+                .as_ref()
+                .and_then(|attempt| attempt.sent_at_block),
+            base_fee_per_gas: last_attempt
+                .as_ref()
+                .map(|attempt| attempt.base_fee_per_gas),
+            priority_fee_per_gas: last_attempt
+                .as_ref()
+                .map(|attempt| attempt.priority_fee_per_gas),
+            blob_base_fee_per_gas: last_attempt.and_then(|attempt| attempt.blob_base_fee_per_gas),
+        });
+    }
+    Ok(inflight_txs)
+}
@@ -8,7 +8,10 @@ use tokio::{
     time::{interval, Duration},
 };
 use zksync_dal::ConnectionPool;
-use zksync_types::{MiniblockNumber, H128, H256};
+use zksync_types::{
+    api::{L1BatchDetails, L1BatchStage, L1BatchStatusUpdate, PriorityOpInfo},
+    L1BatchNumber, MiniblockNumber, PriorityOpId, H128, H256,
+};
 use zksync_web3_decl::{
     jsonrpsee::{
         core::{server::SubscriptionMessage, SubscriptionResult},
@@ -28,6 +31,8 @@ use crate::api_server::execution_sandbox::BlockStartInfo;
 
 const BROADCAST_CHANNEL_CAPACITY: usize = 1024;
 const SUBSCRIPTION_SINK_SEND_TIMEOUT: Duration = Duration::from_secs(1);
+/// Maximum number of L1 batches / priority operations processed by a notifier in a single iteration.
+const MAX_ITEMS_PER_ITERATION: u32 = 100;
 
 #[derive(Debug, Clone, Copy)]
 pub struct EthSubscriptionIdProvider;
@@ -78,6 +83,27 @@ impl PubSubNotifier {
         })
     }
 
+    async fn get_starting_l1_batch_number(&self) -> anyhow::Result<L1BatchNumber> {
+        let mut storage = self
+            .connection_pool
+            .access_storage_tagged("api")
+            .await
+            .context("access_storage_tagged")?;
+        let sealed_l1_batch_number = storage
+            .blocks_dal()
+            .get_sealed_l1_batch_number()
+            .await
+            .context("get_sealed_l1_batch_number()")?;
+        Ok(match sealed_l1_batch_number {
+            Some(number) => number,
+            None => {
+                // We don't have L1 batches in the storage yet. Use the snapshot L1 batch number instead.
+                let start_info = BlockStartInfo::new(&mut storage).await?;
+                L1BatchNumber(start_info.first_l1_batch.saturating_sub(1))
+            }
+        })
+    }
+
     fn emit_event(&self, event: PubSubEvent) {
         if let Some(sender) = &self.events_sender {
             sender.send(event).ok();
@@ -213,13 +239,239 @@ impl PubSubNotifier {
             .await
             .context("events_web3_dal().get_all_logs()")
     }
+
+    async fn notify_l1_batches(self, stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        let mut last_l1_batch_number = self.get_starting_l1_batch_number().await?;
+        let mut timer = interval(self.polling_interval);
+        loop {
+            if *stop_receiver.borrow() {
+                tracing::info!("Stop signal received, pubsub_l1_batch_notifier is shutting down");
+                break;
+            }
+            timer.tick().await;
+
+            let db_latency = PUB_SUB_METRICS.db_poll_latency[&SubscriptionType::L1Batches].start();
+            let new_l1_batches = self.new_l1_batches(last_l1_batch_number).await?;
+            db_latency.observe();
+
+            if let Some(last_l1_batch) = new_l1_batches.last() {
+                last_l1_batch_number = last_l1_batch.number;
+                let new_l1_batches = new_l1_batches
+                    .into_iter()
+                    .map(PubSubResult::L1Batch)
+                    .collect();
+                self.send_pub_sub_results(new_l1_batches, SubscriptionType::L1Batches);
+            }
+            self.emit_event(PubSubEvent::NotifyIterationFinished(
+                SubscriptionType::L1Batches,
+            ));
+        }
+        Ok(())
+    }
+
+    async fn new_l1_batches(
+        &self,
+        last_l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<Vec<L1BatchDetails>> {
+        let mut storage = self
+            .connection_pool
+            .access_storage_tagged("api")
+            .await
+            .context("access_storage_tagged")?;
+        let Some(sealed_l1_batch_number) = storage
+            .blocks_dal()
+            .get_sealed_l1_batch_number()
+            .await
+            .context("get_sealed_l1_batch_number()")?
+        else {
+            return Ok(vec![]);
+        };
+
+        let last_number = sealed_l1_batch_number
+            .0
+            .min(last_l1_batch_number.0 + MAX_ITEMS_PER_ITERATION);
+        let mut l1_batches = vec![];
+        for number in (last_l1_batch_number.0 + 1)..=last_number {
+            let number = L1BatchNumber(number);
+            let details = storage
+                .blocks_web3_dal()
+                .get_l1_batch_details(number)
+                .await
+                .with_context(|| format!("get_l1_batch_details({number})"))?;
+            let Some(details) = details else {
+                break; // The batch may be not fully persisted yet; it will be reported on the next iteration
+            };
+            l1_batches.push(details);
+        }
+        Ok(l1_batches)
+    }
+
+    async fn notify_l1_batch_statuses(
+        self,
+        stop_receiver: watch::Receiver<bool>,
+    ) -> anyhow::Result<()> {
+        let mut last_numbers = self.last_l1_batch_numbers_by_stage().await?;
+        let mut timer = interval(self.polling_interval);
+        loop {
+            if *stop_receiver.borrow() {
+                tracing::info!(
+                    "Stop signal received, pubsub_l1_batch_status_notifier is shutting down"
+                );
+                break;
+            }
+            timer.tick().await;
+
+            let db_latency =
+                PUB_SUB_METRICS.db_poll_latency[&SubscriptionType::L1BatchStatuses].start();
+            let updates = self.l1_batch_status_updates(&mut last_numbers).await?;
+            db_latency.observe();
+
+            if !updates.is_empty() {
+                let updates = updates
+                    .into_iter()
+                    .map(PubSubResult::L1BatchStatus)
+                    .collect();
+                self.send_pub_sub_results(updates, SubscriptionType::L1BatchStatuses);
+            }
+            self.emit_event(PubSubEvent::NotifyIterationFinished(
+                SubscriptionType::L1BatchStatuses,
+            ));
+        }
+        Ok(())
+    }
+
+    /// Returns the numbers of the last L1 batches committed, proven and executed on L1, in this order.
+    async fn last_l1_batch_numbers_by_stage(&self) -> anyhow::Result<[L1BatchNumber; 3]> {
+        let mut storage = self
+            .connection_pool
+            .access_storage_tagged("api")
+            .await
+            .context("access_storage_tagged")?;
+        let start_info = BlockStartInfo::new(&mut storage).await?;
+        let default_number = L1BatchNumber(start_info.first_l1_batch.saturating_sub(1));
+
+        let mut blocks_dal = storage.blocks_dal();
+        let committed = blocks_dal
+            .get_number_of_last_l1_batch_committed_on_eth()
+            .await
+            .context("get_number_of_last_l1_batch_committed_on_eth()")?;
+        let proven = blocks_dal
+            .get_number_of_last_l1_batch_proven_on_eth()
+            .await
+            .context("get_number_of_last_l1_batch_proven_on_eth()")?;
+        let executed = blocks_dal
+            .get_number_of_last_l1_batch_executed_on_eth()
+            .await
+            .context("get_number_of_last_l1_batch_executed_on_eth()")?;
+        Ok([committed, proven, executed].map(|number| number.unwrap_or(default_number)))
+    }
+
+    async fn l1_batch_status_updates(
+        &self,
+        last_numbers: &mut [L1BatchNumber; 3],
+    ) -> anyhow::Result<Vec<L1BatchStatusUpdate>> {
+        const STAGES: [L1BatchStage; 3] = [
+            L1BatchStage::Committed,
+            L1BatchStage::Proven,
+            L1BatchStage::Executed,
+        ];
+
+        let new_numbers = self.last_l1_batch_numbers_by_stage().await?;
+        let mut storage = self
+            .connection_pool
+            .access_storage_tagged("api")
+            .await
+            .context("access_storage_tagged")?;
+        let mut updates = vec![];
+        for ((stage, last_number), new_number) in
+            STAGES.into_iter().zip(last_numbers).zip(new_numbers)
+        {
+            let new_number = new_number.0.min(last_number.0 + MAX_ITEMS_PER_ITERATION);
+            for number in (last_number.0 + 1)..=new_number {
+                let number = L1BatchNumber(number);
+                let details = storage
+                    .blocks_web3_dal()
+                    .get_l1_batch_details(number)
+                    .await
+                    .with_context(|| format!("get_l1_batch_details({number})"))?;
+                let eth_tx_hash = details.and_then(|details| match stage {
+                    L1BatchStage::Committed => details.base.commit_tx_hash,
+                    L1BatchStage::Proven => details.base.prove_tx_hash,
+                    L1BatchStage::Executed => details.base.execute_tx_hash,
+                });
+                updates.push(L1BatchStatusUpdate {
+                    number,
+                    stage,
+                    eth_tx_hash,
+                });
+            }
+            *last_number = L1BatchNumber(new_number.max(last_number.0));
+        }
+        Ok(updates)
+    }
+
+    async fn notify_priority_ops(self, stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        let mut last_priority_op_id = self
+            .connection_pool
+            .access_storage_tagged("api")
+            .await
+            .context("access_storage_tagged")?
+            .transactions_dal()
+            .last_priority_id()
+            .await;
+        let mut timer = interval(self.polling_interval);
+        loop {
+            if *stop_receiver.borrow() {
+                tracing::info!(
+                    "Stop signal received, pubsub_priority_op_notifier is shutting down"
+                );
+                break;
+            }
+            timer.tick().await;
+
+            let db_latency =
+                PUB_SUB_METRICS.db_poll_latency[&SubscriptionType::PriorityOps].start();
+            let new_priority_ops = self.new_priority_ops(last_priority_op_id).await?;
+            db_latency.observe();
+
+            if let Some(last_priority_op) = new_priority_ops.last() {
+                last_priority_op_id = Some(PriorityOpId(last_priority_op.serial_id));
+                let new_priority_ops = new_priority_ops
+                    .into_iter()
+                    .map(PubSubResult::PriorityOp)
+                    .collect();
+                self.send_pub_sub_results(new_priority_ops, SubscriptionType::PriorityOps);
+            }
+            self.emit_event(PubSubEvent::NotifyIterationFinished(
+                SubscriptionType::PriorityOps,
+            ));
+        }
+        Ok(())
+    }
+
+    async fn new_priority_ops(
+        &self,
+        last_priority_op_id: Option<PriorityOpId>,
+    ) -> anyhow::Result<Vec<PriorityOpInfo>> {
+        self.connection_pool
+            .access_storage_tagged("api")
+            .await
+            .context("access_storage_tagged")?
+            .transactions_web3_dal()
+            .get_priority_ops_after(last_priority_op_id, MAX_ITEMS_PER_ITERATION as usize)
+            .await
+            .context("get_priority_ops_after()")
+    }
 }
 
 /// Subscription support for Web3 APIs.
-pub(super) struct EthSubscribe {
+pub(crate) struct EthSubscribe {
     blocks: broadcast::Sender<Vec<PubSubResult>>,
     transactions: broadcast::Sender<Vec<PubSubResult>>,
     logs: broadcast::Sender<Vec<PubSubResult>>,
+    l1_batches: broadcast::Sender<Vec<PubSubResult>>,
+    l1_batch_statuses: broadcast::Sender<Vec<PubSubResult>>,
+    priority_ops: broadcast::Sender<Vec<PubSubResult>>,
     events_sender: Option<mpsc::UnboundedSender<PubSubEvent>>,
 }
 
@@ -228,19 +480,40 @@ impl EthSubscribe {
         let (blocks, _) = broadcast::channel(BROADCAST_CHANNEL_CAPACITY);
         let (transactions, _) = broadcast::channel(BROADCAST_CHANNEL_CAPACITY);
         let (logs, _) = broadcast::channel(BROADCAST_CHANNEL_CAPACITY);
+        let (l1_batches, _) = broadcast::channel(BROADCAST_CHANNEL_CAPACITY);
+        let (l1_batch_statuses, _) = broadcast::channel(BROADCAST_CHANNEL_CAPACITY);
+        let (priority_ops, _) = broadcast::channel(BROADCAST_CHANNEL_CAPACITY);
 
         Self {
             blocks,
             transactions,
             logs,
+            l1_batches,
+            l1_batch_statuses,
+            priority_ops,
             events_sender: None,
         }
     }
 
-    pub fn set_events_sender(&mut self, sender: mpsc::UnboundedSender<PubSubEvent>) {
+    pub(super) fn set_events_sender(&mut self, sender: mpsc::UnboundedSender<PubSubEvent>) {
         self.events_sender = Some(sender);
     }
 
+    /// Subscribes to headers of new miniblocks. Used by the gRPC streaming API.
+    pub(crate) fn subscribe_blocks(&self) -> broadcast::Receiver<Vec<PubSubResult>> {
+        self.blocks.subscribe()
+    }
+
+    /// Subscribes to logs in new miniblocks. Used by the gRPC streaming API.
+    pub(crate) fn subscribe_logs(&self) -> broadcast::Receiver<Vec<PubSubResult>> {
+        self.logs.subscribe()
+    }
+
+    /// Subscribes to L1 batch status updates. Used by the gRPC streaming API.
+    pub(crate) fn subscribe_l1_batch_statuses(&self) -> broadcast::Receiver<Vec<PubSubResult>> {
+        self.l1_batch_statuses.subscribe()
+    }
+
     async fn reject(sink: PendingSubscriptionSink) {
         sink.reject(ErrorObject::borrowed(
             ErrorCode::InvalidParams.code(),
@@ -385,6 +658,45 @@ impl EthSubscribe {
                     Some(SubscriptionType::Logs)
                 }
             }
+            "newL1Batches" => {
+                let Ok(sink) = pending_sink.accept().await else {
+                    return;
+                };
+                let l1_batches_rx = self.l1_batches.subscribe();
+                tokio::spawn(Self::run_subscriber(
+                    sink,
+                    SubscriptionType::L1Batches,
+                    l1_batches_rx,
+                    None,
+                ));
+                Some(SubscriptionType::L1Batches)
+            }
+            "l1BatchStatus" => {
+                let Ok(sink) = pending_sink.accept().await else {
+                    return;
+                };
+                let statuses_rx = self.l1_batch_statuses.subscribe();
+                tokio::spawn(Self::run_subscriber(
+                    sink,
+                    SubscriptionType::L1BatchStatuses,
+                    statuses_rx,
+                    None,
+                ));
+                Some(SubscriptionType::L1BatchStatuses)
+            }
+            "newPriorityOperations" => {
+                let Ok(sink) = pending_sink.accept().await else {
+                    return;
+                };
+                let priority_ops_rx = self.priority_ops.subscribe();
+                tokio::spawn(Self::run_subscriber(
+                    sink,
+                    SubscriptionType::PriorityOps,
+                    priority_ops_rx,
+                    None,
+                ));
+                Some(SubscriptionType::PriorityOps)
+            }
             "syncing" => {
                 let Ok(sink) = pending_sink.accept().await else {
                     return;
@@ -419,7 +731,7 @@ impl EthSubscribe {
         polling_interval: Duration,
         stop_receiver: watch::Receiver<bool>,
     ) -> Vec<JoinHandle<anyhow::Result<()>>> {
-        let mut notifier_tasks = Vec::with_capacity(3);
+        let mut notifier_tasks = Vec::with_capacity(6);
 
         let notifier = PubSubNotifier {
             sender: self.blocks.clone(),
@@ -441,11 +753,38 @@ impl EthSubscribe {
 
         let notifier = PubSubNotifier {
             sender: self.logs.clone(),
+            connection_pool: connection_pool.clone(),
+            polling_interval,
+            events_sender: self.events_sender.clone(),
+        };
+        let notifier_task = tokio::spawn(notifier.notify_logs(stop_receiver.clone()));
+        notifier_tasks.push(notifier_task);
+
+        let notifier = PubSubNotifier {
+            sender: self.l1_batches.clone(),
+            connection_pool: connection_pool.clone(),
+            polling_interval,
+            events_sender: self.events_sender.clone(),
+        };
+        let notifier_task = tokio::spawn(notifier.notify_l1_batches(stop_receiver.clone()));
+        notifier_tasks.push(notifier_task);
+
+        let notifier = PubSubNotifier {
+            sender: self.l1_batch_statuses.clone(),
+            connection_pool: connection_pool.clone(),
+            polling_interval,
+            events_sender: self.events_sender.clone(),
+        };
+        let notifier_task = tokio::spawn(notifier.notify_l1_batch_statuses(stop_receiver.clone()));
+        notifier_tasks.push(notifier_task);
+
+        let notifier = PubSubNotifier {
+            sender: self.priority_ops.clone(),
             connection_pool,
             polling_interval,
             events_sender: self.events_sender.clone(),
         };
-        let notifier_task = tokio::spawn(notifier.notify_logs(stop_receiver));
+        let notifier_task = tokio::spawn(notifier.notify_priority_ops(stop_receiver));
 
         notifier_tasks.push(notifier_task);
         notifier_tasks
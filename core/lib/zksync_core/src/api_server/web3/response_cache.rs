@@ -0,0 +1,327 @@
+//! In-memory cache for responses of the JSON-RPC methods that cannot change once the corresponding data
+//! is finalized on L1 (e.g., blocks and transaction receipts in finalized blocks).
+
+use std::{
+    sync::{Arc, Mutex, PoisonError},
+    time::{Duration, Instant},
+};
+
+use anyhow::Context as _;
+use vise::{Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Metrics};
+use zksync_dal::ConnectionPool;
+use zksync_types::{
+    api::{self, BlockStatus, L1BatchDetails, TransactionReceipt, TransactionVariant},
+    L1BatchNumber, MiniblockNumber, H256,
+};
+
+/// Minimum interval between queries for the last finalized miniblock.
+const FINALIZED_MINIBLOCK_REFRESH_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue)]
+#[metrics(rename_all = "snake_case")]
+enum ResponseKind {
+    Block,
+    Receipt,
+    L1BatchDetails,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue)]
+#[metrics(rename_all = "snake_case")]
+enum RequestOutcome {
+    Hit,
+    Miss,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelSet)]
+struct RequestLabels {
+    kind: ResponseKind,
+    outcome: RequestOutcome,
+}
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "api_web3_response_cache")]
+struct ResponseCacheMetrics {
+    /// Number of cache lookups.
+    requests: Family<RequestLabels, Counter>,
+    /// Number of entries in the cache.
+    len: Gauge<u64>,
+    /// Approximate memory usage of the cache.
+    used_memory: Gauge<u64>,
+}
+
+#[vise::register]
+static METRICS: vise::Global<ResponseCacheMetrics> = vise::Global::new();
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum CacheKey {
+    Block {
+        number: MiniblockNumber,
+        full_transactions: bool,
+    },
+    Receipt(H256),
+    L1BatchDetails(L1BatchNumber),
+}
+
+impl CacheKey {
+    fn kind(&self) -> ResponseKind {
+        match self {
+            Self::Block { .. } => ResponseKind::Block,
+            Self::Receipt(_) => ResponseKind::Receipt,
+            Self::L1BatchDetails(_) => ResponseKind::L1BatchDetails,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum CachedResponse {
+    Block(Arc<api::Block<TransactionVariant>>),
+    Receipt(Arc<TransactionReceipt>),
+    L1BatchDetails(Arc<L1BatchDetails>),
+}
+
+#[derive(Debug, Clone)]
+struct WeightedResponse {
+    response: CachedResponse,
+    weight: u32,
+}
+
+impl WeightedResponse {
+    /// Uses the serialized response size as an approximation of the memory used by the response.
+    fn new(response: CachedResponse, serialized: &impl serde::Serialize) -> Self {
+        let weight = serde_json::to_vec(serialized).map_or(0, |bytes| bytes.len());
+        Self {
+            response,
+            weight: weight.try_into().unwrap_or(u32::MAX),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct ResponseCacheInner {
+    cache: mini_moka::sync::Cache<CacheKey, WeightedResponse>,
+    last_finalized_miniblock: Mutex<Option<(MiniblockNumber, Instant)>>,
+}
+
+/// LRU cache for responses of the JSON-RPC methods that are immutable once finalized on L1. Only responses
+/// for finalized data are cached, so the cache never needs to be invalidated. Cloning the cache is cheap;
+/// all clones share the same state, so a single cache can be used by several servers.
+///
+/// Currently, the following responses are cached:
+///
+/// - `eth_getBlockByNumber` for finalized miniblocks requested by number
+/// - `eth_getTransactionReceipt` for transactions in finalized miniblocks
+/// - `zks_getL1BatchDetails` for executed L1 batches
+#[derive(Debug, Clone, Default)]
+pub struct ResponseCache(Option<Arc<ResponseCacheInner>>);
+
+impl ResponseCache {
+    /// Creates a cache with the specified memory budget in bytes. If the budget is 0, the cache is disabled.
+    pub fn new(capacity: u64) -> Self {
+        if capacity == 0 {
+            return Self(None);
+        }
+        let cache = mini_moka::sync::Cache::builder()
+            .weigher(|_, value: &WeightedResponse| value.weight)
+            .max_capacity(capacity)
+            .build();
+        Self(Some(Arc::new(ResponseCacheInner {
+            cache,
+            last_finalized_miniblock: Mutex::new(None),
+        })))
+    }
+
+    fn get(&self, key: &CacheKey) -> Option<CachedResponse> {
+        let inner = self.0.as_ref()?;
+        let response = inner.cache.get(key).map(|value| value.response);
+        let outcome = if response.is_some() {
+            RequestOutcome::Hit
+        } else {
+            RequestOutcome::Miss
+        };
+        let labels = RequestLabels {
+            kind: key.kind(),
+            outcome,
+        };
+        METRICS.requests[&labels].inc();
+        response
+    }
+
+    fn insert(&self, key: CacheKey, value: WeightedResponse) {
+        if let Some(inner) = &self.0 {
+            inner.cache.insert(key, value);
+            METRICS.len.set(inner.cache.entry_count());
+            METRICS.used_memory.set(inner.cache.weighted_size());
+        }
+    }
+
+    /// Checks whether the specified miniblock is finalized. The last finalized miniblock is queried from Postgres
+    /// at most once per [`FINALIZED_MINIBLOCK_REFRESH_INTERVAL`].
+    async fn is_finalized(
+        inner: &ResponseCacheInner,
+        pool: &ConnectionPool,
+        number: MiniblockNumber,
+    ) -> anyhow::Result<bool> {
+        let last_finalized = *inner
+            .last_finalized_miniblock
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        if let Some((last_finalized, refreshed_at)) = last_finalized {
+            if number <= last_finalized
+                || refreshed_at.elapsed() < FINALIZED_MINIBLOCK_REFRESH_INTERVAL
+            {
+                return Ok(number <= last_finalized);
+            }
+        }
+
+        let mut storage = pool.access_storage_tagged("api").await?;
+        let last_finalized = storage
+            .blocks_web3_dal()
+            .resolve_block_id(api::BlockId::Number(api::BlockNumber::Finalized))
+            .await
+            .context("failed resolving last finalized miniblock")?;
+        drop(storage);
+
+        let Some(last_finalized) = last_finalized else {
+            return Ok(false);
+        };
+        *inner
+            .last_finalized_miniblock
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) = Some((last_finalized, Instant::now()));
+        Ok(number <= last_finalized)
+    }
+
+    async fn insert_if_finalized(
+        &self,
+        pool: &ConnectionPool,
+        number: MiniblockNumber,
+        key: CacheKey,
+        value: impl FnOnce() -> WeightedResponse,
+    ) {
+        let Some(inner) = &self.0 else {
+            return;
+        };
+        match Self::is_finalized(inner, pool, number).await {
+            Ok(true) => self.insert(key, value()),
+            Ok(false) => { /* The response may change; do not cache it. */ }
+            Err(err) => {
+                tracing::warn!("Failed checking whether miniblock #{number} is finalized: {err:#}");
+            }
+        }
+    }
+
+    pub(crate) fn get_block(
+        &self,
+        number: MiniblockNumber,
+        full_transactions: bool,
+    ) -> Option<api::Block<TransactionVariant>> {
+        let key = CacheKey::Block {
+            number,
+            full_transactions,
+        };
+        match self.get(&key)? {
+            CachedResponse::Block(block) => Some((*block).clone()),
+            _ => None,
+        }
+    }
+
+    /// Caches the block if it is finalized.
+    pub(crate) async fn insert_block(
+        &self,
+        pool: &ConnectionPool,
+        full_transactions: bool,
+        block: &api::Block<TransactionVariant>,
+    ) {
+        let number = MiniblockNumber(block.number.as_u32());
+        let key = CacheKey::Block {
+            number,
+            full_transactions,
+        };
+        self.insert_if_finalized(pool, number, key, || {
+            WeightedResponse::new(CachedResponse::Block(Arc::new(block.clone())), block)
+        })
+        .await;
+    }
+
+    pub(crate) fn get_receipt(&self, tx_hash: H256) -> Option<TransactionReceipt> {
+        match self.get(&CacheKey::Receipt(tx_hash))? {
+            CachedResponse::Receipt(receipt) => Some((*receipt).clone()),
+            _ => None,
+        }
+    }
+
+    /// Caches the receipt if the transaction is included into a finalized block.
+    pub(crate) async fn insert_receipt(&self, pool: &ConnectionPool, receipt: &TransactionReceipt) {
+        let number = MiniblockNumber(receipt.block_number.as_u32());
+        let key = CacheKey::Receipt(receipt.transaction_hash);
+        self.insert_if_finalized(pool, number, key, || {
+            WeightedResponse::new(CachedResponse::Receipt(Arc::new(receipt.clone())), receipt)
+        })
+        .await;
+    }
+
+    pub(crate) fn get_l1_batch_details(&self, number: L1BatchNumber) -> Option<L1BatchDetails> {
+        match self.get(&CacheKey::L1BatchDetails(number))? {
+            CachedResponse::L1BatchDetails(details) => Some((*details).clone()),
+            _ => None,
+        }
+    }
+
+    /// Caches L1 batch details if the batch is executed on L1.
+    pub(crate) fn insert_l1_batch_details(&self, details: &L1BatchDetails) {
+        if !matches!(details.base.status, BlockStatus::Verified) {
+            return;
+        }
+        let key = CacheKey::L1BatchDetails(details.number);
+        let response = CachedResponse::L1BatchDetails(Arc::new(details.clone()));
+        self.insert(key, WeightedResponse::new(response, details));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set_last_finalized_miniblock(cache: &ResponseCache, number: MiniblockNumber) {
+        let inner = cache.0.as_ref().unwrap();
+        *inner.last_finalized_miniblock.lock().unwrap() = Some((number, Instant::now()));
+    }
+
+    #[tokio::test]
+    async fn caching_finalized_responses() {
+        let pool = ConnectionPool::test_pool().await;
+        let cache = ResponseCache::new(1 << 20);
+        set_last_finalized_miniblock(&cache, MiniblockNumber(5));
+
+        for number in [3_u32, 7] {
+            let block = api::Block::<TransactionVariant> {
+                number: number.into(),
+                ..api::Block::default()
+            };
+            cache.insert_block(&pool, false, &block).await;
+            let receipt = TransactionReceipt {
+                transaction_hash: H256::repeat_byte(number as u8),
+                block_number: number.into(),
+                ..TransactionReceipt::default()
+            };
+            cache.insert_receipt(&pool, &receipt).await;
+        }
+
+        let block = cache.get_block(MiniblockNumber(3), false).unwrap();
+        assert_eq!(block.number, 3_u32.into());
+        assert!(cache.get_block(MiniblockNumber(3), true).is_none());
+        assert!(cache.get_block(MiniblockNumber(7), false).is_none());
+        let receipt = cache.get_receipt(H256::repeat_byte(3)).unwrap();
+        assert_eq!(receipt.block_number, 3_u32.into());
+        assert!(cache.get_receipt(H256::repeat_byte(7)).is_none());
+    }
+
+    #[tokio::test]
+    async fn disabled_cache() {
+        let pool = ConnectionPool::test_pool().await;
+        let cache = ResponseCache::new(0);
+        let block = api::Block::<TransactionVariant>::default();
+        cache.insert_block(&pool, false, &block).await;
+        assert!(cache.get_block(MiniblockNumber(0), false).is_none());
+    }
+}
@@ -2,7 +2,7 @@ use std::{
     future::Future,
     sync::{
         atomic::{AtomicU32, Ordering},
-        Arc,
+        Arc, RwLock,
     },
     time::{Duration, Instant},
 };
@@ -12,6 +12,7 @@ use tokio::sync::{watch, Mutex};
 use vise::GaugeGuard;
 use zksync_config::configs::{api::Web3JsonRpcConfig, chain::NetworkConfig, ContractsConfig};
 use zksync_dal::{ConnectionPool, StorageProcessor};
+use zksync_object_store::ObjectStore;
 use zksync_types::{
     api, l2::L2Tx, transaction_request::CallRequest, Address, L1BatchNumber, L1ChainId, L2ChainId,
     MiniblockNumber, H256, U256, U64,
@@ -24,7 +25,7 @@ use crate::{
         execution_sandbox::{BlockArgs, BlockArgsError, BlockStartInfo},
         tree::TreeApiHttpClient,
         tx_sender::TxSender,
-        web3::{backend_jsonrpsee::internal_error, TypedFilter},
+        web3::{backend_jsonrpsee::internal_error, ResponseCache, TypedFilter},
     },
     sync_layer::SyncState,
 };
@@ -191,18 +192,79 @@ impl SealedMiniblockNumber {
     }
 }
 
+/// Thread-safe updatable information about the first locally available miniblock / L1 batch.
+///
+/// The information changes when the node storage is pruned. It is refreshed on an interval specified
+/// when creating an instance, so it may be temporarily outdated; pruning accounts for this by removing data
+/// with a delay after it's marked as pruned.
+#[derive(Debug, Clone)]
+pub(crate) struct SharedBlockStartInfo(Arc<RwLock<BlockStartInfo>>);
+
+impl SharedBlockStartInfo {
+    /// Creates a handle to the block start info together with a task that will update it on a schedule.
+    /// The returned info is not initialized; use [`Self::refresh()`] to initialize it.
+    pub fn new(
+        connection_pool: ConnectionPool,
+        update_interval: Duration,
+        stop_receiver: watch::Receiver<bool>,
+    ) -> (Self, impl Future<Output = anyhow::Result<()>>) {
+        let this = Self(Arc::default());
+        let info_updater = this.clone();
+
+        let update_task = async move {
+            loop {
+                if *stop_receiver.borrow() {
+                    tracing::debug!("Stopping block start info updates");
+                    return Ok(());
+                }
+
+                let mut connection = connection_pool.access_storage_tagged("api").await?;
+                info_updater.refresh(&mut connection).await?;
+                drop(connection);
+                tokio::time::sleep(update_interval).await;
+            }
+        };
+
+        (this, update_task)
+    }
+
+    /// Reloads the block start info from the storage. The numbers of the first available miniblock
+    /// and L1 batch never decrease.
+    pub async fn refresh(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<BlockStartInfo> {
+        let new_info = BlockStartInfo::new(storage).await?;
+        let mut info = self.0.write().expect("block start info is poisoned");
+        info.first_miniblock = info.first_miniblock.max(new_info.first_miniblock);
+        info.first_l1_batch = info.first_l1_batch.max(new_info.first_l1_batch);
+        Ok(*info)
+    }
+
+    pub fn get(&self) -> BlockStartInfo {
+        *self.0.read().expect("block start info is poisoned")
+    }
+
+    pub(super) fn ensure_not_pruned(&self, query: impl Into<PruneQuery>) -> Result<(), Web3Error> {
+        self.get().ensure_not_pruned(query)
+    }
+}
+
 /// Holder for the data required for the API to be functional.
 #[derive(Debug, Clone)]
 pub struct RpcState {
     pub(crate) installed_filters: Arc<Mutex<Filters>>,
     pub connection_pool: ConnectionPool,
     pub tree_api: Option<TreeApiHttpClient>,
+    /// Object store with L1 batch proofs. If not set, proofs cannot be retrieved via the API.
+    pub proof_store: Option<Arc<dyn ObjectStore>>,
+    pub(crate) response_cache: ResponseCache,
     pub tx_sender: TxSender,
     pub sync_state: Option<SyncState>,
     pub(super) api_config: InternalApiConfig,
     /// Number of the first locally available miniblock / L1 batch. May differ from 0 if the node state was recovered
-    /// from a snapshot.
-    pub(super) start_info: BlockStartInfo,
+    /// from a snapshot, or if the node storage is pruned.
+    pub(super) start_info: SharedBlockStartInfo,
     pub(super) last_sealed_miniblock: SealedMiniblockNumber,
 }
 
@@ -244,13 +306,29 @@ impl RpcState {
         block: api::BlockId,
         method_name: &'static str,
     ) -> Result<BlockArgs, Web3Error> {
-        BlockArgs::new(connection, block, self.start_info)
+        BlockArgs::new(connection, block, self.start_info.get())
             .await
-            .map_err(|err| match err {
-                BlockArgsError::Pruned(number) => Web3Error::PrunedBlock(number),
-                BlockArgsError::Missing => Web3Error::NoBlock,
-                BlockArgsError::Database(err) => internal_error(method_name, err),
-            })
+            .map_err(|err| Self::map_block_args_error(err, method_name))
+    }
+
+    /// Resolves block args for re-executing transactions from the specified sealed miniblock.
+    pub(crate) async fn resolve_replay_block_args(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        miniblock_number: MiniblockNumber,
+        method_name: &'static str,
+    ) -> Result<BlockArgs, Web3Error> {
+        BlockArgs::for_replay(connection, miniblock_number, self.start_info.get())
+            .await
+            .map_err(|err| Self::map_block_args_error(err, method_name))
+    }
+
+    fn map_block_args_error(err: BlockArgsError, method_name: &'static str) -> Web3Error {
+        match err {
+            BlockArgsError::Pruned(number) => Web3Error::PrunedBlock(number),
+            BlockArgsError::Missing => Web3Error::NoBlock,
+            BlockArgsError::Database(err) => internal_error(method_name, err),
+        }
     }
 
     pub async fn resolve_filter_block_number(
@@ -1,11 +1,12 @@
 //! Tests for the `debug` Web3 namespace.
 
+use multivm::interface::ExecutionResult;
 use zksync_types::{tx::TransactionExecutionResult, vm_trace::Call, BOOTLOADER_ADDRESS};
 use zksync_web3_decl::namespaces::DebugNamespaceClient;
 
 use super::*;
 
-fn execute_l2_transaction_with_traces(index_in_block: u8) -> TransactionExecutionResult {
+pub(super) fn execute_l2_transaction_with_traces(index_in_block: u8) -> TransactionExecutionResult {
     let first_call_trace = Call {
         from: Address::repeat_byte(index_in_block),
         to: Address::repeat_byte(index_in_block + 1),
@@ -54,7 +55,8 @@ impl HttpTest for TraceBlockTest {
 
             assert_eq!(block_traces.len(), tx_results.len()); // equals to the number of transactions in the block
             for (trace, tx_result) in block_traces.iter().zip(&tx_results) {
-                let api::ResultDebugCall { result } = trace;
+                let api::ResultDebugCall { tx_hash, result } = trace;
+                assert_eq!(*tx_hash, Some(tx_result.hash));
                 assert_eq!(result.from, Address::zero());
                 assert_eq!(result.to, BOOTLOADER_ADDRESS);
                 assert_eq!(result.gas, tx_result.transaction.gas_limit());
@@ -113,6 +115,9 @@ impl HttpTest for TraceTransactionTest {
             .trace_transaction(tx_results[0].hash, None)
             .await?
             .context("no transaction traces")?;
+        let api::DebugTrace::Call(result) = result else {
+            panic!("Unexpected trace: {result:?}");
+        };
         assert_eq!(result.from, Address::zero());
         assert_eq!(result.to, BOOTLOADER_ADDRESS);
         assert_eq!(result.gas, tx_results[0].transaction.gas_limit());
@@ -127,6 +132,63 @@ async fn tracing_transaction() {
     test_http_server(TraceTransactionTest).await;
 }
 
+#[derive(Debug)]
+struct TraceTransactionWithStructLoggerTest;
+
+impl TraceTransactionWithStructLoggerTest {
+    fn tx_results() -> [TransactionExecutionResult; 2] {
+        [0, 1].map(execute_l2_transaction_with_traces)
+    }
+}
+
+#[async_trait]
+impl HttpTest for TraceTransactionWithStructLoggerTest {
+    fn transaction_executor(&self) -> MockTransactionExecutor {
+        let mut tx_executor = MockTransactionExecutor::default();
+        tx_executor.set_tx_responses(|_, block_args| {
+            // Transactions are replayed in the context of their miniblock.
+            assert_eq!(block_args.resolved_block_number(), MiniblockNumber(1));
+            ExecutionResult::Success {
+                output: b"output".to_vec(),
+            }
+        });
+        tx_executor
+    }
+
+    async fn test(&self, client: &HttpClient, pool: &ConnectionPool) -> anyhow::Result<()> {
+        let tx_results = Self::tx_results();
+        let mut storage = pool.access_storage().await?;
+        store_miniblock(&mut storage, MiniblockNumber(1), &tx_results).await?;
+        drop(storage);
+
+        let options = api::TracerConfig {
+            tracer: api::SupportedTracers::StructLogger,
+            tracer_config: api::CallTracerConfig::default(),
+            struct_logger_config: api::StructLoggerConfig::default(),
+        };
+        let trace = client
+            .trace_transaction(tx_results[1].hash, Some(options.clone()))
+            .await?
+            .context("no transaction traces")?;
+        let api::DebugTrace::StructLogs(trace) = trace else {
+            panic!("Unexpected trace: {trace:?}");
+        };
+        assert!(!trace.failed);
+        assert_eq!(trace.return_value.0, b"output");
+
+        let missing_trace = client
+            .trace_transaction(H256::repeat_byte(0xff), Some(options))
+            .await?;
+        assert!(missing_trace.is_none(), "{missing_trace:?}");
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn tracing_transaction_with_struct_logger() {
+    test_http_server(TraceTransactionWithStructLoggerTest).await;
+}
+
 #[derive(Debug)]
 struct TraceBlockTestWithSnapshotRecovery;
 
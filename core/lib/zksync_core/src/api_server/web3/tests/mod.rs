@@ -13,6 +13,7 @@ use zksync_config::configs::{
 use zksync_dal::{transactions_dal::L2TxSubmissionResult, ConnectionPool, StorageProcessor};
 use zksync_health_check::CheckHealth;
 use zksync_types::{
+    aggregated_operations::AggregatedActionType,
     api,
     block::MiniblockHeader,
     fee::TransactionExecutionMetrics,
@@ -25,12 +26,15 @@ use zksync_types::{
         TransactionExecutionResult,
     },
     utils::{storage_key_for_eth_balance, storage_key_for_standard_token_balance},
-    AccountTreeId, Address, L1BatchNumber, Nonce, StorageKey, StorageLog, VmEvent, H256, U64,
+    AccountTreeId, Address, L1BatchNumber, Nonce, ProtocolVersion, ProtocolVersionId, StorageKey,
+    StorageLog, VmEvent, H256, U64,
 };
 use zksync_utils::u256_to_h256;
 use zksync_web3_decl::{
     jsonrpsee::{http_client::HttpClient, types::error::ErrorCode},
-    namespaces::{EthNamespaceClient, ZksNamespaceClient},
+    namespaces::{
+        EthNamespaceClient, TxpoolNamespaceClient, ZksAdminNamespaceClient, ZksNamespaceClient,
+    },
 };
 
 use super::{metrics::ApiTransportLabel, *};
@@ -49,11 +53,13 @@ use crate::{
 mod debug;
 mod filters;
 mod snapshots;
+mod trace;
 mod vm;
 mod ws;
 
 const TEST_TIMEOUT: Duration = Duration::from_secs(10);
 const POLL_INTERVAL: Duration = Duration::from_millis(50);
+const TEST_REQUEST_BODY_SIZE_LIMIT: usize = 1 << 16;
 
 impl ApiServerHandles {
     /// Waits until the server health check reports the ready state. Must be called once per server instance.
@@ -150,10 +156,19 @@ async fn spawn_server(
     let (pub_sub_events_sender, pub_sub_events_receiver) = mpsc::unbounded_channel();
 
     let mut namespaces = Namespace::DEFAULT.to_vec();
-    namespaces.extend([Namespace::Debug, Namespace::Snapshots]);
+    namespaces.extend([
+        Namespace::Debug,
+        Namespace::Snapshots,
+        Namespace::Txpool,
+        Namespace::Trace,
+        Namespace::ZksAdmin,
+    ]);
 
     let server_builder = match transport {
-        ApiTransportLabel::Http => ApiBuilder::jsonrpsee_backend(api_config, pool).http(0),
+        ApiTransportLabel::Http => ApiBuilder::jsonrpsee_backend(api_config, pool)
+            .http(0)
+            .with_request_body_size_limit(TEST_REQUEST_BODY_SIZE_LIMIT)
+            .with_response_compression(true),
         ApiTransportLabel::Ws => {
             let mut builder = ApiBuilder::jsonrpsee_backend(api_config, pool)
                 .ws(0)
@@ -516,6 +531,77 @@ fn assert_pruned_block_error(error: &ClientError, first_retained_block: Minibloc
     }
 }
 
+#[tokio::test]
+async fn http_response_compression_and_request_body_limit() {
+    let pool = ConnectionPool::test_pool().await;
+    let network_config = NetworkConfig::for_tests();
+    let mut storage = pool.access_storage().await.unwrap();
+    StorageInitialization::Genesis
+        .prepare_storage(&network_config, &mut storage)
+        .await
+        .unwrap();
+    drop(storage);
+
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let mut server_handles = spawn_http_server(
+        &network_config,
+        pool,
+        MockTransactionExecutor::default(),
+        stop_receiver,
+    )
+    .await;
+    let local_addr = server_handles.wait_until_ready().await;
+    let url = format!("http://{local_addr}/");
+    // Disable automatic decompression so that we can observe compressed responses.
+    let client = reqwest::Client::builder()
+        .no_gzip()
+        .no_brotli()
+        .no_deflate()
+        .build()
+        .unwrap();
+
+    let call = |id: usize| serde_json::json!({ "jsonrpc": "2.0", "id": id, "method": "eth_chainId", "params": [] });
+    let response = client
+        .post(&url)
+        .header(reqwest::header::ACCEPT_ENCODING, "gzip")
+        .json(&call(0))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    // The response is too small to be compressed.
+    assert!(!response
+        .headers()
+        .contains_key(reqwest::header::CONTENT_ENCODING));
+
+    let batch: Vec<_> = (0..100).map(call).collect();
+    let response = client
+        .post(&url)
+        .header(reqwest::header::ACCEPT_ENCODING, "gzip")
+        .json(&batch)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    assert_eq!(
+        response.headers()[reqwest::header::CONTENT_ENCODING],
+        "gzip"
+    );
+
+    let large_param = format!("0x{}", "0".repeat(TEST_REQUEST_BODY_SIZE_LIMIT));
+    let large_call = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 0,
+        "method": "eth_sendRawTransaction",
+        "params": [large_param],
+    });
+    let response = client.post(&url).json(&large_call).send().await.unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::PAYLOAD_TOO_LARGE);
+
+    stop_sender.send_replace(true);
+    server_handles.shutdown().await;
+}
+
 #[tokio::test]
 async fn block_methods_with_snapshot_recovery() {
     test_http_server(BlockMethodsWithSnapshotRecovery).await;
@@ -858,6 +944,57 @@ async fn transaction_receipts() {
     test_http_server(TransactionReceiptsTest).await;
 }
 
+#[derive(Debug)]
+struct FeeHistoryTest;
+
+#[async_trait]
+impl HttpTest for FeeHistoryTest {
+    async fn test(&self, client: &HttpClient, pool: &ConnectionPool) -> anyhow::Result<()> {
+        let mut storage = pool.access_storage().await?;
+        let tx_results = [
+            execute_l2_transaction(create_l2_transaction(10, 200)),
+            execute_l2_transaction(create_l2_transaction(10, 200)),
+        ];
+        let new_miniblock = store_miniblock(&mut storage, MiniblockNumber(1), &tx_results).await?;
+
+        let history = client
+            .fee_history(1.into(), api::BlockNumber::Latest, vec![0.0, 50.0, 100.0])
+            .await?;
+        assert_eq!(
+            history.oldest_block,
+            zksync_types::web3::types::BlockNumber::Number(1.into())
+        );
+        let base_fee = U256::from(new_miniblock.base_fee_per_gas);
+        assert_eq!(history.base_fee_per_gas, [base_fee; 2]);
+        // Test transactions have a 1,000 gas limit and no refunds.
+        let expected_gas_used_ratio = 2_000.0 / f64::from(u32::MAX);
+        assert_eq!(history.gas_used_ratio, [expected_gas_used_ratio]);
+        assert_eq!(history.reward, Some(vec![vec![U256::zero(); 3]]));
+
+        let history = client
+            .fee_history(1.into(), api::BlockNumber::Latest, vec![])
+            .await?;
+        assert_eq!(history.reward, None);
+
+        let err = client
+            .fee_history(1.into(), api::BlockNumber::Latest, vec![50.0, 10.0])
+            .await
+            .unwrap_err();
+        if let ClientError::Call(err) = err {
+            assert_eq!(err.code(), ErrorCode::InvalidParams.code());
+            assert!(err.message().contains("reward percentile"), "{err:?}");
+        } else {
+            panic!("Unexpected error: {err:?}");
+        }
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn getting_fee_history() {
+    test_http_server(FeeHistoryTest).await;
+}
+
 #[derive(Debug)]
 struct AllAccountBalancesTest;
 
@@ -927,3 +1064,204 @@ impl HttpTest for AllAccountBalancesTest {
 async fn getting_all_account_balances() {
     test_http_server(AllAccountBalancesTest).await;
 }
+
+#[derive(Debug)]
+struct BatchProofWithoutProofStoreTest;
+
+#[async_trait]
+impl HttpTest for BatchProofWithoutProofStoreTest {
+    async fn test(&self, client: &HttpClient, _pool: &ConnectionPool) -> anyhow::Result<()> {
+        let err = client.get_batch_proof(L1BatchNumber(0)).await.unwrap_err();
+        if let ClientError::Call(err) = err {
+            assert_eq!(err.code(), 7);
+            assert!(
+                err.message().contains("proofs are not available"),
+                "{err:?}"
+            );
+        } else {
+            panic!("Unexpected error: {err:?}");
+        }
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn getting_batch_proof_without_proof_store() {
+    test_http_server(BatchProofWithoutProofStoreTest).await;
+}
+
+#[derive(Debug)]
+struct AccountProofWithoutTreeApiTest;
+
+#[async_trait]
+impl HttpTest for AccountProofWithoutTreeApiTest {
+    async fn test(&self, client: &HttpClient, _pool: &ConnectionPool) -> anyhow::Result<()> {
+        let address = Address::repeat_byte(1);
+        // `zks_getProof` has the same method name, hence the fully qualified call.
+        let err = EthNamespaceClient::get_proof(client, address, vec![H256::zero()], None)
+            .await
+            .unwrap_err();
+        if let ClientError::Call(err) = err {
+            assert_eq!(err.code(), 6);
+            assert!(err.message().contains("Tree API"), "{err:?}");
+        } else {
+            panic!("Unexpected error: {err:?}");
+        }
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn getting_account_proof_without_tree_api() {
+    test_http_server(AccountProofWithoutTreeApiTest).await;
+}
+
+#[derive(Debug)]
+struct BlockProtocolVersionTest;
+
+#[async_trait]
+impl HttpTest for BlockProtocolVersionTest {
+    async fn test(&self, client: &HttpClient, pool: &ConnectionPool) -> anyhow::Result<()> {
+        let mut storage = pool.access_storage().await?;
+        storage
+            .protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion {
+                id: ProtocolVersionId::next(),
+                ..ProtocolVersion::default()
+            })
+            .await;
+        let new_miniblock = MiniblockHeader {
+            protocol_version: Some(ProtocolVersionId::next()),
+            ..create_miniblock(1)
+        };
+        storage
+            .blocks_dal()
+            .insert_miniblock(&new_miniblock)
+            .await?;
+        drop(storage);
+
+        let genesis_block = api::BlockId::Number(api::BlockNumber::Number(0.into()));
+        let version = client
+            .get_block_protocol_version(genesis_block)
+            .await?
+            .context("no protocol version for genesis")?;
+        assert_eq!(version.version_id, ProtocolVersionId::latest() as u16);
+
+        for block_number in [api::BlockNumber::Number(1.into()), api::BlockNumber::Latest] {
+            let version = client
+                .get_block_protocol_version(api::BlockId::Number(block_number))
+                .await?
+                .context("no protocol version for miniblock")?;
+            assert_eq!(version.version_id, ProtocolVersionId::next() as u16);
+        }
+
+        let missing_block = api::BlockId::Number(api::BlockNumber::Number(2.into()));
+        let err = client
+            .get_block_protocol_version(missing_block)
+            .await
+            .unwrap_err();
+        assert_matches!(err, ClientError::Call(_));
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn getting_block_protocol_version() {
+    test_http_server(BlockProtocolVersionTest).await;
+}
+
+#[derive(Debug)]
+struct TxpoolTest;
+
+#[async_trait]
+impl HttpTest for TxpoolTest {
+    async fn test(&self, client: &HttpClient, pool: &ConnectionPool) -> anyhow::Result<()> {
+        let status = client.status().await?;
+        assert_eq!(status, api::TxpoolStatus::default());
+
+        let pending_tx = create_l2_transaction(10, 200);
+        let mut queued_tx = create_l2_transaction(10, 200);
+        queued_tx.common_data.nonce = Nonce(2);
+        let mut storage = pool.access_storage().await?;
+        for tx in [&pending_tx, &queued_tx] {
+            let submission_result = storage
+                .transactions_dal()
+                .insert_transaction_l2(tx.clone(), TransactionExecutionMetrics::default())
+                .await;
+            assert_matches!(submission_result, L2TxSubmissionResult::Added);
+        }
+        drop(storage);
+
+        let status = client.status().await?;
+        assert_eq!(status.pending, 1.into());
+        assert_eq!(status.queued, 1.into());
+
+        let content = client.content().await?;
+        assert_eq!(content.pending.len(), 1);
+        let pending = &content.pending[&pending_tx.initiator_account()];
+        assert_eq!(pending[&0_u32].hash, pending_tx.hash());
+        assert_eq!(content.queued.len(), 1);
+        let queued = &content.queued[&queued_tx.initiator_account()];
+        assert_eq!(queued[&2_u32].hash, queued_tx.hash());
+
+        let priority_ops = client.get_queued_priority_ops().await?;
+        assert!(priority_ops.is_empty());
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn inspecting_txpool() {
+    test_http_server(TxpoolTest).await;
+}
+
+#[derive(Debug)]
+struct InflightL1TxsTest;
+
+#[async_trait]
+impl HttpTest for InflightL1TxsTest {
+    async fn test(&self, client: &HttpClient, pool: &ConnectionPool) -> anyhow::Result<()> {
+        let inflight_txs = client.get_inflight_l1_txs().await?;
+        assert!(inflight_txs.is_empty());
+
+        let mut storage = pool.access_storage().await?;
+        let mut dal = storage.eth_sender_dal();
+        let eth_tx = dal
+            .save_eth_tx(
+                0,
+                vec![1, 2, 3],
+                AggregatedActionType::Commit,
+                Address::repeat_byte(1),
+                100_000,
+                None,
+                Address::repeat_byte(0x11),
+            )
+            .await?;
+        let tx_hash = H256::repeat_byte(0x11);
+        let history_id = dal
+            .insert_tx_history(eth_tx.id, 100, 10, None, tx_hash, &[0])
+            .await?
+            .expect("history item was not inserted");
+        dal.set_sent_at_block(history_id, 42).await?;
+        drop(storage);
+
+        let inflight_txs = client.get_inflight_l1_txs().await?;
+        assert_eq!(inflight_txs.len(), 1);
+        let inflight_tx = &inflight_txs[0];
+        assert_eq!(inflight_tx.id, eth_tx.id);
+        assert_eq!(inflight_tx.tx_type, "CommitBlocks");
+        assert_eq!(inflight_tx.attempts, 1);
+        assert_eq!(inflight_tx.first_sent_at_block, Some(42));
+        assert_eq!(inflight_tx.last_sent_at_block, Some(42));
+        assert_eq!(inflight_tx.last_tx_hash, Some(tx_hash));
+        assert_eq!(inflight_tx.base_fee_per_gas, Some(100));
+        assert_eq!(inflight_tx.priority_fee_per_gas, Some(10));
+        assert_eq!(inflight_tx.blob_base_fee_per_gas, None);
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn getting_inflight_l1_txs() {
+    test_http_server(InflightL1TxsTest).await;
+}
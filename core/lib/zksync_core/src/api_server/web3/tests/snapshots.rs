@@ -2,6 +2,7 @@
 
 use std::collections::HashSet;
 
+use zksync_types::snapshots::SnapshotVersion;
 use zksync_web3_decl::namespaces::SnapshotsNamespaceClient;
 
 use super::*;
@@ -38,14 +39,34 @@ impl HttpTest for SnapshotBasicsTest {
         seal_l1_batch(&mut storage, L1BatchNumber(1)).await?;
         storage
             .snapshots_dal()
-            .add_snapshot(L1BatchNumber(1), Self::CHUNK_COUNT, "file:///factory_deps")
+            .add_snapshot(
+                SnapshotVersion::Version1,
+                L1BatchNumber(1),
+                Self::CHUNK_COUNT,
+                "file:///factory_deps",
+                Some(H256::repeat_byte(1)),
+            )
+            .await?;
+        storage
+            .snapshots_dal()
+            .add_tokens_filepath_for_snapshot(
+                L1BatchNumber(1),
+                "file:///tokens",
+                H256::repeat_byte(2),
+            )
             .await?;
 
         for &chunk_id in &self.chunk_ids {
             let path = format!("file:///storage_logs/chunk{chunk_id}");
+            let checksum = H256::from_low_u64_be(chunk_id);
             storage
                 .snapshots_dal()
-                .add_storage_logs_filepath_for_snapshot(L1BatchNumber(1), chunk_id, &path)
+                .add_storage_logs_filepath_for_snapshot(
+                    L1BatchNumber(1),
+                    chunk_id,
+                    &path,
+                    Some(checksum),
+                )
                 .await?;
         }
 
@@ -66,12 +87,22 @@ impl HttpTest for SnapshotBasicsTest {
             return Ok(());
         };
 
+        assert_eq!(snapshot_header.version, SnapshotVersion::Version1 as u16);
         assert_eq!(snapshot_header.l1_batch_number, L1BatchNumber(1));
         assert_eq!(snapshot_header.miniblock_number, MiniblockNumber(1));
         assert_eq!(
             snapshot_header.factory_deps_filepath,
             "file:///factory_deps"
         );
+        assert_eq!(
+            snapshot_header.factory_deps_checksum,
+            Some(H256::repeat_byte(1))
+        );
+        assert_eq!(
+            snapshot_header.tokens_filepath.as_deref(),
+            Some("file:///tokens")
+        );
+        assert_eq!(snapshot_header.tokens_checksum, Some(H256::repeat_byte(2)));
 
         assert_eq!(
             snapshot_header.storage_logs_chunks.len(),
@@ -80,6 +111,7 @@ impl HttpTest for SnapshotBasicsTest {
         for chunk in &snapshot_header.storage_logs_chunks {
             assert!(self.chunk_ids.contains(&chunk.chunk_id));
             assert!(chunk.filepath.starts_with("file:///storage_logs/"));
+            assert_eq!(chunk.checksum, Some(H256::from_low_u64_be(chunk.chunk_id)));
         }
         Ok(())
     }
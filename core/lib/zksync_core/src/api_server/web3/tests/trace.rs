@@ -0,0 +1,80 @@
+//! Tests for the `trace` Web3 namespace.
+
+use zksync_types::api::trace::{TraceAction, TraceFilter, TraceType};
+use zksync_web3_decl::namespaces::TraceNamespaceClient;
+
+use super::{debug::execute_l2_transaction_with_traces, *};
+
+#[derive(Debug)]
+struct TraceNamespaceTest;
+
+#[async_trait]
+impl HttpTest for TraceNamespaceTest {
+    async fn test(&self, client: &HttpClient, pool: &ConnectionPool) -> anyhow::Result<()> {
+        let tx_results = [0, 1].map(execute_l2_transaction_with_traces);
+        let mut storage = pool.access_storage().await?;
+        let new_miniblock = store_miniblock(&mut storage, MiniblockNumber(1), &tx_results).await?;
+        drop(storage);
+
+        let block_traces = client
+            .trace_block(api::BlockNumber::Number(1.into()))
+            .await?
+            .context("no block traces")?;
+        // Each transaction has a top-level call with 2 subcalls.
+        assert_eq!(block_traces.len(), 6);
+        for (i, tx_result) in tx_results.iter().enumerate() {
+            let tx_traces = &block_traces[i * 3..(i + 1) * 3];
+            assert_eq!(tx_traces[0].trace_address, [] as [usize; 0]);
+            assert_eq!(tx_traces[0].subtraces, 2);
+            assert_eq!(tx_traces[1].trace_address, [0]);
+            assert_eq!(tx_traces[2].trace_address, [1]);
+            for trace in tx_traces {
+                assert_eq!(trace.transaction_hash, tx_result.hash);
+                assert_eq!(trace.transaction_position, i as u64);
+                assert_eq!(trace.block_number, 1);
+                assert_eq!(trace.block_hash, new_miniblock.hash);
+                assert_eq!(trace.trace_type, TraceType::Call);
+            }
+            assert_matches!(
+                &tx_traces[1].action,
+                TraceAction::Call(action) if action.to == tx_result.call_traces[0].to
+            );
+        }
+
+        let missing_block_traces = client
+            .trace_block(api::BlockNumber::Number(100.into()))
+            .await?;
+        assert!(missing_block_traces.is_none());
+
+        let tx_traces = client
+            .trace_transaction(tx_results[1].hash)
+            .await?
+            .context("no transaction traces")?;
+        assert_eq!(tx_traces, block_traces[3..]);
+        let missing_tx_traces = client.trace_transaction(H256::repeat_byte(0xff)).await?;
+        assert!(missing_tx_traces.is_none());
+
+        let filter = TraceFilter {
+            from_block: Some(api::BlockNumber::Number(0.into())),
+            to_address: vec![tx_results[0].call_traces[0].to],
+            ..TraceFilter::default()
+        };
+        let filtered_traces = client.trace_filter(filter).await?;
+        assert_eq!(filtered_traces, [block_traces[1].clone()]);
+
+        let filter = TraceFilter {
+            from_block: Some(api::BlockNumber::Number(0.into())),
+            after: Some(1),
+            count: Some(2),
+            ..TraceFilter::default()
+        };
+        let filtered_traces = client.trace_filter(filter).await?;
+        assert_eq!(filtered_traces, block_traces[1..3]);
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn tracing_with_trace_namespace() {
+    test_http_server(TraceNamespaceTest).await;
+}
@@ -51,7 +51,9 @@ impl HttpTest for CallTest {
     }
 
     async fn test(&self, client: &HttpClient, _pool: &ConnectionPool) -> anyhow::Result<()> {
-        let call_result = client.call(Self::call_request(b"pending"), None).await?;
+        let call_result = client
+            .call(Self::call_request(b"pending"), None, None)
+            .await?;
         assert_eq!(call_result.0, b"output");
 
         let valid_block_numbers_and_calldata = [
@@ -62,7 +64,7 @@ impl HttpTest for CallTest {
         for (number, calldata) in valid_block_numbers_and_calldata {
             let number = api::BlockIdVariant::BlockNumber(number);
             let call_result = client
-                .call(Self::call_request(calldata), Some(number))
+                .call(Self::call_request(calldata), Some(number), None)
                 .await?;
             assert_eq!(call_result.0, b"output");
         }
@@ -70,11 +72,34 @@ impl HttpTest for CallTest {
         let invalid_block_number = api::BlockNumber::from(100);
         let number = api::BlockIdVariant::BlockNumber(invalid_block_number);
         let error = client
-            .call(Self::call_request(b"100"), Some(number))
+            .call(Self::call_request(b"100"), Some(number), None)
+            .await
+            .unwrap_err();
+        if let ClientError::Call(error) = error {
+            assert_eq!(error.code(), ErrorCode::InvalidParams.code());
+        } else {
+            panic!("Unexpected error: {error:?}");
+        }
+
+        // Bytecode with an even number of words is invalid.
+        let invalid_state_override = api::StateOverride::from([(
+            Address::repeat_byte(2),
+            api::OverrideAccount {
+                code: Some(vec![0; 64].into()),
+                ..api::OverrideAccount::default()
+            },
+        )]);
+        let error = client
+            .call(
+                Self::call_request(b"pending"),
+                None,
+                Some(invalid_state_override),
+            )
             .await
             .unwrap_err();
         if let ClientError::Call(error) = error {
             assert_eq!(error.code(), ErrorCode::InvalidParams.code());
+            assert!(error.message().contains("state override"), "{error:?}");
         } else {
             panic!("Unexpected error: {error:?}");
         }
@@ -104,7 +129,7 @@ impl HttpTest for CallTestAfterSnapshotRecovery {
 
     async fn test(&self, client: &HttpClient, _pool: &ConnectionPool) -> anyhow::Result<()> {
         let call_result = client
-            .call(CallTest::call_request(b"pending"), None)
+            .call(CallTest::call_request(b"pending"), None, None)
             .await?;
         assert_eq!(call_result.0, b"output");
         let pending_block_number = api::BlockIdVariant::BlockNumber(api::BlockNumber::Pending);
@@ -112,6 +137,7 @@ impl HttpTest for CallTestAfterSnapshotRecovery {
             .call(
                 CallTest::call_request(b"pending"),
                 Some(pending_block_number),
+                None,
             )
             .await?;
         assert_eq!(call_result.0, b"output");
@@ -121,7 +147,7 @@ impl HttpTest for CallTestAfterSnapshotRecovery {
         for number in pruned_block_numbers {
             let number = api::BlockIdVariant::BlockNumber(number.into());
             let error = client
-                .call(CallTest::call_request(b"pruned"), Some(number))
+                .call(CallTest::call_request(b"pruned"), Some(number), None)
                 .await
                 .unwrap_err();
             assert_pruned_block_error(&error, first_local_miniblock);
@@ -131,7 +157,7 @@ impl HttpTest for CallTestAfterSnapshotRecovery {
         for number in first_miniblock_numbers {
             let number = api::BlockIdVariant::BlockNumber(number);
             let call_result = client
-                .call(CallTest::call_request(b"first"), Some(number))
+                .call(CallTest::call_request(b"first"), Some(number), None)
                 .await?;
             assert_eq!(call_result.0, b"output");
         }
@@ -258,7 +284,10 @@ async fn send_raw_transaction_after_snapshot_recovery() {
 struct TraceCallTest;
 
 impl TraceCallTest {
-    fn assert_debug_call(call_request: &CallRequest, call_result: &api::DebugCall) {
+    fn assert_debug_call(call_request: &CallRequest, call_result: &api::DebugTrace) {
+        let api::DebugTrace::Call(call_result) = call_result else {
+            panic!("Unexpected trace: {call_result:?}");
+        };
         assert_eq!(call_result.from, Address::zero());
         assert_eq!(call_result.gas, call_request.gas.unwrap());
         assert_eq!(call_result.value, call_request.value.unwrap());
@@ -322,6 +351,25 @@ impl HttpTest for TraceCallTest {
             panic!("Unexpected error: {error:?}");
         }
 
+        let options = api::TracerConfig {
+            tracer: api::SupportedTracers::StructLogger,
+            tracer_config: api::CallTracerConfig::default(),
+            struct_logger_config: api::StructLoggerConfig {
+                enable_memory: true,
+                ..api::StructLoggerConfig::default()
+            },
+        };
+        let trace = client
+            .trace_call(CallTest::call_request(b"pending"), None, Some(options))
+            .await?;
+        let api::DebugTrace::StructLogs(trace) = trace else {
+            panic!("Unexpected trace: {trace:?}");
+        };
+        assert!(!trace.failed);
+        assert_eq!(trace.return_value.0, b"output");
+        // The mock executor doesn't run tracers.
+        assert!(trace.struct_logs.is_empty());
+
         Ok(())
     }
 }
@@ -360,7 +408,7 @@ impl HttpTest for TraceCallTestAfterSnapshotRecovery {
         for number in pruned_block_numbers {
             let number = api::BlockIdVariant::BlockNumber(number.into());
             let error = client
-                .call(CallTest::call_request(b"pruned"), Some(number))
+                .call(CallTest::call_request(b"pruned"), Some(number), None)
                 .await
                 .unwrap_err();
             assert_pruned_block_error(&error, first_local_miniblock);
@@ -436,7 +484,7 @@ impl HttpTest for EstimateGasTest {
         for threshold in [10_000, 50_000, 100_000, 1_000_000] {
             self.gas_limit_threshold.store(threshold, Ordering::Relaxed);
             let output = client
-                .estimate_gas(l2_transaction.clone().into(), None)
+                .estimate_gas(l2_transaction.clone().into(), None, None)
                 .await?;
             assert!(
                 output >= U256::from(threshold),
@@ -461,10 +509,15 @@ impl HttpTest for EstimateGasTest {
         let mut call_request = CallRequest::from(l2_transaction);
         call_request.from = Some(SendRawTransactionTest::private_key_and_address().1);
         call_request.value = Some(1_000_000.into());
-        client.estimate_gas(call_request.clone(), None).await?;
+        client
+            .estimate_gas(call_request.clone(), None, None)
+            .await?;
 
         call_request.value = Some(U256::max_value());
-        let error = client.estimate_gas(call_request, None).await.unwrap_err();
+        let error = client
+            .estimate_gas(call_request, None, None)
+            .await
+            .unwrap_err();
         if let ClientError::Call(error) = error {
             let error_msg = error.message();
             assert!(
@@ -313,6 +313,49 @@ async fn basic_subscriptions_after_snapshot_recovery() {
     .await;
 }
 
+#[derive(Debug)]
+struct L1BatchSubscriptionsTest;
+
+#[async_trait]
+impl WsTest for L1BatchSubscriptionsTest {
+    async fn test(
+        &self,
+        client: &WsClient,
+        pool: &ConnectionPool,
+        mut pub_sub_events: mpsc::UnboundedReceiver<PubSubEvent>,
+    ) -> anyhow::Result<()> {
+        wait_for_notifiers(&mut pub_sub_events, &[SubscriptionType::L1Batches]).await;
+
+        let params = rpc_params!["newL1Batches"];
+        let mut l1_batches_subscription = client
+            .subscribe::<api::L1BatchDetails, _>("eth_subscribe", params, "eth_unsubscribe")
+            .await?;
+        wait_for_subscription(&mut pub_sub_events, SubscriptionType::L1Batches).await;
+
+        let mut storage = pool.access_storage().await?;
+        store_miniblock(&mut storage, MiniblockNumber(1), &[]).await?;
+        seal_l1_batch(&mut storage, L1BatchNumber(1)).await?;
+        drop(storage);
+
+        let received_l1_batch = tokio::time::timeout(TEST_TIMEOUT, l1_batches_subscription.next())
+            .await
+            .context("Timed out waiting for new L1 batch")?
+            .context("New L1 batches subscription terminated")??;
+        assert_eq!(received_l1_batch.number, L1BatchNumber(1));
+        assert_eq!(
+            received_l1_batch.base.timestamp,
+            create_l1_batch(1).timestamp
+        );
+        l1_batches_subscription.unsubscribe().await?;
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn l1_batch_subscriptions() {
+    test_ws_server(L1BatchSubscriptionsTest).await;
+}
+
 #[derive(Debug)]
 struct LogSubscriptionsTest {
     snapshot_recovery: bool,
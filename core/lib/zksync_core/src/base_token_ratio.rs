@@ -0,0 +1,195 @@
+//! Conversion ratio between ETH and the base token used to denominate fees.
+
+use std::{
+    fmt,
+    num::NonZeroU64,
+    sync::{Arc, RwLock},
+    time::Duration,
+};
+
+use anyhow::Context as _;
+use serde::Deserialize;
+use tokio::sync::watch;
+use zksync_config::configs::chain::{BaseTokenConfig, BaseTokenRatioSource};
+use zksync_types::fee_model::BaseTokenConversionRatio;
+
+const RATIO_API_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Response of the ratio API.
+#[derive(Debug, Deserialize)]
+struct RatioResponse {
+    numerator: u64,
+    denominator: u64,
+}
+
+impl TryFrom<RatioResponse> for BaseTokenConversionRatio {
+    type Error = anyhow::Error;
+
+    fn try_from(response: RatioResponse) -> Result<Self, Self::Error> {
+        Ok(Self {
+            numerator: NonZeroU64::new(response.numerator)
+                .context("ratio numerator must be positive")?,
+            denominator: NonZeroU64::new(response.denominator)
+                .context("ratio denominator must be positive")?,
+        })
+    }
+}
+
+#[derive(Debug)]
+struct RatioApi {
+    client: reqwest::Client,
+    url: String,
+    poll_interval: Duration,
+}
+
+impl RatioApi {
+    async fn fetch(&self) -> anyhow::Result<BaseTokenConversionRatio> {
+        let response = self
+            .client
+            .get(&self.url)
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .map_err(reqwest::Error::without_url)?;
+        let response: RatioResponse = response.json().await.map_err(reqwest::Error::without_url)?;
+        response.try_into()
+    }
+}
+
+/// Provides the current conversion ratio between ETH and the base token, either fixed in the config
+/// or periodically fetched from an HTTP API.
+pub struct BaseTokenRatioFetcher {
+    api: Option<RatioApi>,
+    ratio: RwLock<BaseTokenConversionRatio>,
+}
+
+impl fmt::Debug for BaseTokenRatioFetcher {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // The URL is not output since it may contain an API key.
+        formatter
+            .debug_struct("BaseTokenRatioFetcher")
+            .field(
+                "poll_interval",
+                &self.api.as_ref().map(|api| api.poll_interval),
+            )
+            .field("ratio", &self.ratio)
+            .finish_non_exhaustive()
+    }
+}
+
+impl BaseTokenRatioFetcher {
+    /// Creates a fetcher based on the provided config. For the HTTP ratio source, the ratio specified
+    /// in the config is used until it's successfully fetched from the API.
+    pub fn new(config: &BaseTokenConfig) -> anyhow::Result<Self> {
+        let ratio = BaseTokenConversionRatio {
+            numerator: NonZeroU64::new(config.ratio_numerator)
+                .context("`ratio_numerator` must be positive")?,
+            denominator: NonZeroU64::new(config.ratio_denominator)
+                .context("`ratio_denominator` must be positive")?,
+        };
+        let api = match config.ratio_source {
+            BaseTokenRatioSource::Fixed => None,
+            BaseTokenRatioSource::Http => {
+                let url = config
+                    .ratio_url
+                    .clone()
+                    .context("`ratio_url` is required for the HTTP ratio source")?;
+                reqwest::Url::parse(&url).context("invalid `ratio_url`")?;
+                Some(RatioApi {
+                    client: reqwest::Client::builder()
+                        .timeout(RATIO_API_TIMEOUT)
+                        .build()
+                        .context("failed creating HTTP client")?,
+                    url,
+                    poll_interval: config.ratio_poll_interval(),
+                })
+            }
+        };
+        Ok(Self {
+            api,
+            ratio: RwLock::new(ratio),
+        })
+    }
+
+    /// Returns the latest known conversion ratio.
+    pub fn ratio(&self) -> BaseTokenConversionRatio {
+        *self.ratio.read().unwrap()
+    }
+
+    /// Checks whether the ratio needs to be updated by [`Self::run()`].
+    pub fn requires_polling(&self) -> bool {
+        self.api.is_some()
+    }
+
+    /// Periodically updates the ratio from the API. Returns immediately if the ratio is fixed.
+    pub async fn run(
+        self: Arc<Self>,
+        mut stop_receiver: watch::Receiver<bool>,
+    ) -> anyhow::Result<()> {
+        let Some(api) = &self.api else {
+            return Ok(());
+        };
+
+        while !*stop_receiver.borrow_and_update() {
+            match api.fetch().await {
+                Ok(ratio) => {
+                    tracing::debug!("Fetched base token conversion ratio: {ratio:?}");
+                    *self.ratio.write().unwrap() = ratio;
+                }
+                Err(err) => {
+                    tracing::warn!("Failed fetching base token conversion ratio: {err:#}");
+                }
+            }
+            // Error here corresponds to a timeout w/o `stop_receiver` changed; we're OK with this.
+            tokio::time::timeout(api.poll_interval, stop_receiver.changed())
+                .await
+                .ok();
+        }
+        tracing::info!("Stop signal received, base token ratio fetcher is shutting down");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parsing_ratio_response() {
+        let response: RatioResponse =
+            serde_json::from_str(r#"{ "numerator": 3, "denominator": 2 }"#).unwrap();
+        let ratio = BaseTokenConversionRatio::try_from(response).unwrap();
+        assert_eq!(ratio.numerator.get(), 3);
+        assert_eq!(ratio.denominator.get(), 2);
+        assert_eq!(ratio.convert(1_000), 1_500);
+
+        let response: RatioResponse =
+            serde_json::from_str(r#"{ "numerator": 1, "denominator": 0 }"#).unwrap();
+        let err = BaseTokenConversionRatio::try_from(response).unwrap_err();
+        assert!(err.to_string().contains("denominator"), "{err}");
+    }
+
+    #[test]
+    fn creating_fetcher_from_config() {
+        let mut config = BaseTokenConfig {
+            l1_address: None,
+            ratio_source: BaseTokenRatioSource::Fixed,
+            ratio_numerator: 5,
+            ratio_denominator: 4,
+            ratio_url: None,
+            ratio_poll_interval_ms: None,
+        };
+        let fetcher = BaseTokenRatioFetcher::new(&config).unwrap();
+        assert!(!fetcher.requires_polling());
+        assert_eq!(fetcher.ratio().convert(400), 500);
+
+        config.ratio_source = BaseTokenRatioSource::Http;
+        let err = BaseTokenRatioFetcher::new(&config).unwrap_err();
+        assert!(err.to_string().contains("ratio_url"), "{err}");
+
+        config.ratio_url = Some("http://127.0.0.1:8080/ratio".to_owned());
+        let fetcher = BaseTokenRatioFetcher::new(&config).unwrap();
+        assert!(fetcher.requires_polling());
+        assert_eq!(fetcher.ratio().convert(400), 500);
+    }
+}
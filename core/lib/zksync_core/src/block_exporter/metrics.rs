@@ -0,0 +1,35 @@
+use std::time::Duration;
+
+use vise::{
+    Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, Metrics, Unit,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "record_type", rename_all = "snake_case")]
+pub(super) enum RecordType {
+    Miniblock,
+    Transaction,
+    Receipt,
+    L2ToL1Log,
+}
+
+/// Metrics for the block exporter.
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "block_exporter")]
+pub(super) struct BlockExporterMetrics {
+    /// Last miniblock published to the stream and checkpointed.
+    pub last_exported_miniblock: Gauge<u64>,
+    /// Number of records published to the stream.
+    pub exported_records: Family<RecordType, Counter>,
+    /// Latency of loading a chunk of miniblocks from Postgres.
+    #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds)]
+    pub load_chunk_latency: Histogram<Duration>,
+    /// Latency of publishing a chunk of miniblocks, including waiting for delivery acknowledgements.
+    #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds)]
+    pub publish_chunk_latency: Histogram<Duration>,
+    /// Number of failed attempts to publish a chunk of miniblocks.
+    pub publish_errors: Counter,
+}
+
+#[vise::register]
+pub(super) static METRICS: vise::Global<BlockExporterMetrics> = vise::Global::new();
@@ -0,0 +1,257 @@
+//! Export of sealed miniblocks, transactions, receipts and L2-to-L1 logs to an external stream (e.g., a Kafka topic),
+//! so that downstream indexers don't need to poll the Web3 API.
+//!
+//! The exporter polls Postgres for new miniblocks and publishes them in chunks. After a chunk is acknowledged
+//! by the sink, the last miniblock in it is checkpointed in Postgres, and the exporter resumes from the checkpoint
+//! after a restart. Delivery is thus *at least once*: if a chunk is published but the node crashes before saving
+//! the checkpoint, the chunk will be published again. Consumers should deduplicate records by the miniblock number
+//! and transaction hashes.
+
+use std::{num::NonZeroU32, time::Duration};
+
+use anyhow::Context as _;
+use serde::Serialize;
+use tokio::sync::watch;
+use zksync_dal::{ConnectionPool, StorageProcessor};
+use zksync_types::{api, L2ChainId, MiniblockNumber};
+
+use self::metrics::{RecordType, METRICS};
+pub use self::sink::{BlockSink, KafkaSink};
+use crate::api_server::execution_sandbox::BlockStartInfo;
+
+mod metrics;
+mod sink;
+#[cfg(test)]
+mod tests;
+
+/// Record published by [`BlockExporter`]. Serialized as a JSON object with the `type` field specifying the record type
+/// and the `data` field containing the record in the same format as returned by the Web3 API.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", content = "data", rename_all = "snake_case")]
+pub enum ExportedRecord {
+    /// Miniblock header. Transactions in the miniblock are represented by their hashes.
+    Miniblock(api::Block<api::TransactionVariant>),
+    Transaction(api::Transaction),
+    /// Transaction receipt including emitted events.
+    Receipt(api::TransactionReceipt),
+    L2ToL1Log(api::L2ToL1Log),
+}
+
+impl ExportedRecord {
+    fn record_type(&self) -> RecordType {
+        match self {
+            Self::Miniblock(_) => RecordType::Miniblock,
+            Self::Transaction(_) => RecordType::Transaction,
+            Self::Receipt(_) => RecordType::Receipt,
+            Self::L2ToL1Log(_) => RecordType::L2ToL1Log,
+        }
+    }
+}
+
+/// Records for a single miniblock. Records are ordered as follows: the miniblock header, transactions,
+/// receipts and L2-to-L1 logs, with the last three groups ordered by the transaction index in the miniblock.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportedMiniblock {
+    pub number: MiniblockNumber,
+    pub records: Vec<ExportedRecord>,
+}
+
+/// Configuration of [`BlockExporter`].
+#[derive(Debug, Clone)]
+pub struct BlockExporterConfig {
+    /// L2 chain ID used to serialize transactions.
+    pub l2_chain_id: L2ChainId,
+    /// Interval between polling Postgres for new miniblocks. Also used as a retry interval if publishing fails.
+    pub poll_interval: Duration,
+    /// Maximum number of miniblocks published and checkpointed at a time.
+    pub chunk_size: NonZeroU32,
+    /// Miniblock to start exporting from if there's no checkpoint for the stream. If not specified, the export
+    /// starts from the first miniblock available in the node storage.
+    pub first_miniblock: Option<MiniblockNumber>,
+}
+
+/// Component exporting miniblock data to a [`BlockSink`].
+#[derive(Debug)]
+pub struct BlockExporter<S> {
+    config: BlockExporterConfig,
+    connection_pool: ConnectionPool,
+    sink: S,
+}
+
+impl<S: BlockSink> BlockExporter<S> {
+    pub fn new(config: BlockExporterConfig, connection_pool: ConnectionPool, sink: S) -> Self {
+        Self {
+            config,
+            connection_pool,
+            sink,
+        }
+    }
+
+    async fn next_miniblock_to_export(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<MiniblockNumber> {
+        let stream_name = self.sink.stream_name();
+        let checkpoint = storage
+            .block_exporter_dal()
+            .get_checkpoint(stream_name)
+            .await
+            .with_context(|| format!("failed getting checkpoint for stream `{stream_name}`"))?;
+        let start_info = BlockStartInfo::new(storage).await?;
+        let next_miniblock = match (checkpoint, self.config.first_miniblock) {
+            (Some(checkpoint), _) => checkpoint + 1,
+            (None, Some(first_miniblock)) => first_miniblock,
+            (None, None) => start_info.first_miniblock,
+        };
+        anyhow::ensure!(
+            next_miniblock >= start_info.first_miniblock,
+            "Miniblock #{next_miniblock} to be exported to stream `{stream_name}` is not available in the node storage \
+             (first available miniblock is #{}); it was either pruned or is before the snapshot the node was recovered from",
+            start_info.first_miniblock
+        );
+        Ok(next_miniblock)
+    }
+
+    /// Loads the next chunk of miniblocks to export. Returns `None` if there are no new miniblocks.
+    async fn load_next_chunk(&self) -> anyhow::Result<Option<Vec<ExportedMiniblock>>> {
+        let mut storage = self
+            .connection_pool
+            .access_storage_tagged("block_exporter")
+            .await?;
+        let first_miniblock = self.next_miniblock_to_export(&mut storage).await?;
+        let sealed_miniblock = storage
+            .blocks_dal()
+            .get_sealed_miniblock_number()
+            .await
+            .context("failed getting sealed miniblock number")?;
+        let Some(sealed_miniblock) = sealed_miniblock else {
+            return Ok(None);
+        };
+        if sealed_miniblock < first_miniblock {
+            return Ok(None);
+        }
+
+        let latency = METRICS.load_chunk_latency.start();
+        let chunk_end = first_miniblock + (self.config.chunk_size.get() - 1);
+        let last_miniblock = chunk_end.min(sealed_miniblock);
+        let mut miniblocks =
+            Vec::with_capacity((last_miniblock.0 - first_miniblock.0 + 1) as usize);
+        for number in first_miniblock.0..=last_miniblock.0 {
+            let miniblock = self
+                .load_miniblock(&mut storage, MiniblockNumber(number))
+                .await?;
+            miniblocks.push(miniblock);
+        }
+        latency.observe();
+        Ok(Some(miniblocks))
+    }
+
+    async fn load_miniblock(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        number: MiniblockNumber,
+    ) -> anyhow::Result<ExportedMiniblock> {
+        let block_id = api::BlockId::Number(api::BlockNumber::Number(number.0.into()));
+        let mut block = storage
+            .blocks_web3_dal()
+            .get_block_by_web3_block_id(block_id, true, self.config.l2_chain_id)
+            .await
+            .with_context(|| format!("failed loading miniblock #{number}"))?
+            .with_context(|| format!("miniblock #{number} disappeared from storage"))?;
+
+        let transactions = block
+            .transactions
+            .drain(..)
+            .map(|tx| match tx {
+                api::TransactionVariant::Full(tx) => Ok(tx),
+                api::TransactionVariant::Hash(hash) => Err(anyhow::anyhow!(
+                    "transaction {hash:?} in miniblock #{number} is not loaded"
+                )),
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let tx_hashes: Vec<_> = transactions.iter().map(|tx| tx.hash).collect();
+        block.transactions = tx_hashes
+            .iter()
+            .copied()
+            .map(api::TransactionVariant::Hash)
+            .collect();
+
+        let mut receipts = storage
+            .transactions_web3_dal()
+            .get_transaction_receipts(&tx_hashes)
+            .await
+            .with_context(|| {
+                format!("failed loading transaction receipts for miniblock #{number}")
+            })?;
+        receipts.sort_unstable_by_key(|receipt| receipt.transaction_index);
+        let l2_to_l1_logs: Vec<_> = receipts
+            .iter()
+            .flat_map(|receipt| receipt.l2_to_l1_logs.iter().cloned())
+            .collect();
+
+        let records = [ExportedRecord::Miniblock(block)]
+            .into_iter()
+            .chain(transactions.into_iter().map(ExportedRecord::Transaction))
+            .chain(receipts.into_iter().map(ExportedRecord::Receipt))
+            .chain(l2_to_l1_logs.into_iter().map(ExportedRecord::L2ToL1Log))
+            .collect();
+        Ok(ExportedMiniblock { number, records })
+    }
+
+    pub async fn run(mut self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        let stream_name = self.sink.stream_name().to_owned();
+        tracing::info!(
+            "Starting block exporter for stream `{stream_name}` with config {:?}",
+            self.config
+        );
+
+        loop {
+            if *stop_receiver.borrow() {
+                break;
+            }
+
+            let Some(miniblocks) = self.load_next_chunk().await? else {
+                tokio::time::timeout(self.config.poll_interval, stop_receiver.changed())
+                    .await
+                    .ok();
+                continue;
+            };
+            let first_miniblock = miniblocks[0].number;
+            let last_miniblock = miniblocks.last().unwrap().number;
+
+            let latency = METRICS.publish_chunk_latency.start();
+            if let Err(err) = self.sink.publish(&miniblocks).await {
+                METRICS.publish_errors.inc();
+                tracing::warn!(
+                    "Failed publishing miniblocks #{first_miniblock}..=#{last_miniblock} to stream `{stream_name}`, \
+                     will retry: {err:#}"
+                );
+                tokio::time::timeout(self.config.poll_interval, stop_receiver.changed())
+                    .await
+                    .ok();
+                continue;
+            }
+            let latency = latency.observe();
+
+            let mut storage = self
+                .connection_pool
+                .access_storage_tagged("block_exporter")
+                .await?;
+            storage
+                .block_exporter_dal()
+                .save_checkpoint(&stream_name, last_miniblock)
+                .await
+                .with_context(|| format!("failed saving checkpoint for stream `{stream_name}`"))?;
+
+            for record in miniblocks.iter().flat_map(|miniblock| &miniblock.records) {
+                METRICS.exported_records[&record.record_type()].inc();
+            }
+            METRICS.last_exported_miniblock.set(last_miniblock.0.into());
+            tracing::debug!(
+                "Exported miniblocks #{first_miniblock}..=#{last_miniblock} to stream `{stream_name}` in {latency:?}"
+            );
+        }
+        tracing::info!("Stop signal received, block exporter is shutting down");
+        Ok(())
+    }
+}
@@ -0,0 +1,104 @@
+//! Sinks for exported miniblock data.
+
+use std::{fmt, time::Duration};
+
+use anyhow::Context as _;
+use async_trait::async_trait;
+use rdkafka::{
+    config::ClientConfig,
+    producer::{FutureProducer, FutureRecord},
+};
+
+use super::ExportedMiniblock;
+
+/// Sink for exported miniblocks, such as a Kafka topic.
+#[async_trait]
+pub trait BlockSink: fmt::Debug + Send + Sync {
+    /// Name of the stream used to store the export checkpoint in Postgres. Sinks publishing to different streams
+    /// (e.g., different Kafka topics) must have different names.
+    fn stream_name(&self) -> &str;
+
+    /// Publishes the provided miniblocks in order. Must return only after all records are durably accepted
+    /// by the sink, since the miniblocks are checkpointed immediately after this method returns. If the method
+    /// returns an error, the miniblocks will be published again; thus, consumers may observe duplicate records.
+    async fn publish(&mut self, miniblocks: &[ExportedMiniblock]) -> anyhow::Result<()>;
+}
+
+/// Sink publishing JSON-serialized records to a Kafka topic. All records for a miniblock use the miniblock number
+/// as the message key, so they end up in the same topic partition in the order of publishing.
+pub struct KafkaSink {
+    producer: FutureProducer,
+    topic: String,
+}
+
+impl fmt::Debug for KafkaSink {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("KafkaSink")
+            .field("topic", &self.topic)
+            .finish_non_exhaustive()
+    }
+}
+
+impl KafkaSink {
+    /// Creates a sink connecting to the specified comma-separated list of Kafka brokers. Messages not delivered
+    /// within `message_timeout` are considered failed.
+    pub fn new(
+        bootstrap_servers: &str,
+        topic: String,
+        message_timeout: Duration,
+    ) -> anyhow::Result<Self> {
+        let producer = ClientConfig::new()
+            .set("bootstrap.servers", bootstrap_servers)
+            // Idempotence ensures that retries within the producer don't reorder or duplicate messages.
+            .set("enable.idempotence", "true")
+            .set("acks", "all")
+            .set(
+                "message.timeout.ms",
+                message_timeout.as_millis().to_string(),
+            )
+            .create()
+            .context("failed creating Kafka producer")?;
+        Ok(Self { producer, topic })
+    }
+}
+
+#[async_trait]
+impl BlockSink for KafkaSink {
+    fn stream_name(&self) -> &str {
+        &self.topic
+    }
+
+    async fn publish(&mut self, miniblocks: &[ExportedMiniblock]) -> anyhow::Result<()> {
+        let mut deliveries = vec![];
+        for miniblock in miniblocks {
+            let key = miniblock.number.0.to_string();
+            for record in &miniblock.records {
+                let payload = serde_json::to_vec(record).context("failed serializing record")?;
+                let message = FutureRecord::to(&self.topic).key(&key).payload(&payload);
+                let delivery = self
+                    .producer
+                    .send_result(message)
+                    .map_err(|(err, _)| err)
+                    .with_context(|| {
+                        format!(
+                            "failed enqueuing record for miniblock #{}",
+                            miniblock.number
+                        )
+                    })?;
+                deliveries.push((miniblock.number, delivery));
+            }
+        }
+
+        for (miniblock_number, delivery) in deliveries {
+            delivery
+                .await
+                .context("Kafka producer was dropped")?
+                .map_err(|(err, _)| err)
+                .with_context(|| {
+                    format!("failed delivering record for miniblock #{miniblock_number}")
+                })?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,228 @@
+//! Tests for the block exporter.
+
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use zksync_types::{fee::TransactionExecutionMetrics, L1BatchNumber};
+
+use super::*;
+use crate::{
+    genesis::{ensure_genesis_state, GenesisParams},
+    utils::testonly::{create_l2_transaction, create_miniblock, execute_l2_transaction},
+};
+
+const STREAM_NAME: &str = "blocks";
+
+fn mock_config(chunk_size: u32) -> BlockExporterConfig {
+    BlockExporterConfig {
+        l2_chain_id: L2ChainId::default(),
+        poll_interval: Duration::from_millis(10),
+        chunk_size: NonZeroU32::new(chunk_size).unwrap(),
+        first_miniblock: None,
+    }
+}
+
+#[derive(Debug, Default)]
+struct MockSinkState {
+    published_miniblocks: Vec<ExportedMiniblock>,
+    remaining_failures: usize,
+}
+
+#[derive(Debug, Clone, Default)]
+struct MockSink(Arc<Mutex<MockSinkState>>);
+
+impl MockSink {
+    fn failing(failures: usize) -> Self {
+        let state = MockSinkState {
+            remaining_failures: failures,
+            ..MockSinkState::default()
+        };
+        Self(Arc::new(Mutex::new(state)))
+    }
+
+    fn published_numbers(&self) -> Vec<u32> {
+        let state = self.0.lock().unwrap();
+        state
+            .published_miniblocks
+            .iter()
+            .map(|miniblock| miniblock.number.0)
+            .collect()
+    }
+}
+
+#[async_trait]
+impl BlockSink for MockSink {
+    fn stream_name(&self) -> &str {
+        STREAM_NAME
+    }
+
+    async fn publish(&mut self, miniblocks: &[ExportedMiniblock]) -> anyhow::Result<()> {
+        let mut state = self.0.lock().unwrap();
+        if state.remaining_failures > 0 {
+            state.remaining_failures -= 1;
+            anyhow::bail!("mock publishing error");
+        }
+        state.published_miniblocks.extend_from_slice(miniblocks);
+        Ok(())
+    }
+}
+
+/// Stores miniblocks with numbers `1..=count` after the genesis, each with a single transaction.
+async fn prepare_storage(pool: &ConnectionPool, count: u32) {
+    let mut storage = pool.access_storage().await.unwrap();
+    ensure_genesis_state(&mut storage, L2ChainId::default(), &GenesisParams::mock())
+        .await
+        .unwrap();
+    for number in 1..=count {
+        store_miniblock(&mut storage, number).await;
+    }
+}
+
+async fn store_miniblock(storage: &mut StorageProcessor<'_>, number: u32) {
+    let tx = create_l2_transaction(10, 100);
+    storage
+        .transactions_dal()
+        .insert_transaction_l2(tx.clone(), TransactionExecutionMetrics::default())
+        .await;
+    let miniblock = create_miniblock(number);
+    storage
+        .blocks_dal()
+        .insert_miniblock(&miniblock)
+        .await
+        .unwrap();
+    storage
+        .transactions_dal()
+        .mark_txs_as_executed_in_miniblock(
+            miniblock.number,
+            &[execute_l2_transaction(tx)],
+            1.into(),
+        )
+        .await;
+}
+
+async fn wait_for_checkpoint(pool: &ConnectionPool, expected: MiniblockNumber) {
+    loop {
+        let mut storage = pool.access_storage().await.unwrap();
+        let checkpoint = storage
+            .block_exporter_dal()
+            .get_checkpoint(STREAM_NAME)
+            .await
+            .unwrap();
+        if checkpoint == Some(expected) {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+}
+
+#[tokio::test]
+async fn loading_miniblock_records() {
+    let pool = ConnectionPool::test_pool().await;
+    prepare_storage(&pool, 1).await;
+    let exporter = BlockExporter::new(mock_config(10), pool.clone(), MockSink::default());
+
+    let miniblocks = exporter.load_next_chunk().await.unwrap().unwrap();
+    assert_eq!(miniblocks.len(), 2);
+    assert_eq!(miniblocks[0].number, MiniblockNumber(0));
+    assert_eq!(miniblocks[0].records.len(), 1);
+
+    let records = &miniblocks[1].records;
+    let [ExportedRecord::Miniblock(block), ExportedRecord::Transaction(tx), ExportedRecord::Receipt(receipt)] =
+        records.as_slice()
+    else {
+        panic!("unexpected records: {records:#?}");
+    };
+    assert_eq!(block.number, 1.into());
+    assert_eq!(block.transactions, [api::TransactionVariant::Hash(tx.hash)]);
+    assert_eq!(receipt.transaction_hash, tx.hash);
+    assert_eq!(receipt.block_number, 1.into());
+
+    let serialized = serde_json::to_value(&records[1]).unwrap();
+    assert_eq!(serialized["type"], "transaction");
+    assert_eq!(serialized["data"]["hash"], format!("{:?}", tx.hash));
+}
+
+#[tokio::test]
+async fn exporting_miniblocks_in_chunks() {
+    let pool = ConnectionPool::test_pool().await;
+    prepare_storage(&pool, 4).await;
+    let sink = MockSink::default();
+    let exporter = BlockExporter::new(mock_config(2), pool.clone(), sink.clone());
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let exporter_task = tokio::spawn(exporter.run(stop_receiver));
+
+    wait_for_checkpoint(&pool, MiniblockNumber(4)).await;
+    assert_eq!(sink.published_numbers(), [0, 1, 2, 3, 4]);
+
+    // New miniblocks should be picked up by the exporter.
+    let mut storage = pool.access_storage().await.unwrap();
+    store_miniblock(&mut storage, 5).await;
+    wait_for_checkpoint(&pool, MiniblockNumber(5)).await;
+    assert_eq!(sink.published_numbers(), [0, 1, 2, 3, 4, 5]);
+
+    stop_sender.send_replace(true);
+    exporter_task.await.unwrap().unwrap();
+}
+
+#[tokio::test]
+async fn exporter_retries_failed_publishing() {
+    let pool = ConnectionPool::test_pool().await;
+    prepare_storage(&pool, 2).await;
+    let sink = MockSink::failing(2);
+    let exporter = BlockExporter::new(mock_config(10), pool.clone(), sink.clone());
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let exporter_task = tokio::spawn(exporter.run(stop_receiver));
+
+    wait_for_checkpoint(&pool, MiniblockNumber(2)).await;
+    assert_eq!(sink.published_numbers(), [0, 1, 2]);
+    stop_sender.send_replace(true);
+    exporter_task.await.unwrap().unwrap();
+}
+
+#[tokio::test]
+async fn exporter_resumes_from_checkpoint() {
+    let pool = ConnectionPool::test_pool().await;
+    prepare_storage(&pool, 3).await;
+    let mut storage = pool.access_storage().await.unwrap();
+    storage
+        .block_exporter_dal()
+        .save_checkpoint(STREAM_NAME, MiniblockNumber(1))
+        .await
+        .unwrap();
+    drop(storage);
+
+    let sink = MockSink::default();
+    let exporter = BlockExporter::new(mock_config(10), pool.clone(), sink.clone());
+    let miniblocks = exporter.load_next_chunk().await.unwrap().unwrap();
+    let numbers: Vec<_> = miniblocks
+        .iter()
+        .map(|miniblock| miniblock.number.0)
+        .collect();
+    assert_eq!(numbers, [2, 3]);
+}
+
+#[tokio::test]
+async fn exporter_errors_if_next_miniblock_is_pruned() {
+    let pool = ConnectionPool::test_pool().await;
+    prepare_storage(&pool, 3).await;
+    let mut storage = pool.access_storage().await.unwrap();
+    storage
+        .pruning_dal()
+        .soft_prune_batches_range(L1BatchNumber(0), MiniblockNumber(1))
+        .await
+        .unwrap();
+    drop(storage);
+
+    let exporter = BlockExporter::new(mock_config(10), pool.clone(), MockSink::default());
+    let err = exporter.load_next_chunk().await.unwrap_err().to_string();
+    assert!(err.contains("not available"), "{err}");
+
+    // Explicitly specifying the first miniblock allows to start from the first available miniblock.
+    let config = BlockExporterConfig {
+        first_miniblock: Some(MiniblockNumber(2)),
+        ..mock_config(10)
+    };
+    let exporter = BlockExporter::new(config, pool, MockSink::default());
+    let miniblocks = exporter.load_next_chunk().await.unwrap().unwrap();
+    assert_eq!(miniblocks[0].number, MiniblockNumber(2));
+}
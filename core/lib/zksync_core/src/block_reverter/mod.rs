@@ -1,5 +1,6 @@
 use std::{path::Path, time::Duration};
 
+use anyhow::Context as _;
 use bitflags::bitflags;
 use serde::Serialize;
 use tokio::time::sleep;
@@ -19,7 +20,7 @@ use zksync_types::{
         types::{BlockId, BlockNumber},
         Web3,
     },
-    L1BatchNumber, PackedEthSignature, H160, H256, U256,
+    L1BatchNumber, MiniblockNumber, PackedEthSignature, H160, H256, U256,
 };
 
 bitflags! {
@@ -146,6 +147,156 @@ impl BlockReverter {
         }
     }
 
+    /// Reports what [`Self::rollback_db()`] with the same arguments would revert, without changing any data.
+    pub async fn revert_impact(
+        &self,
+        last_l1_batch_to_keep: L1BatchNumber,
+        flags: BlockReverterFlags,
+    ) -> anyhow::Result<RevertImpactReport> {
+        let mut storage = self
+            .connection_pool
+            .access_storage()
+            .await
+            .context("access_storage()")?;
+        let (_, last_miniblock_to_keep) = storage
+            .blocks_dal()
+            .get_miniblock_range_of_l1_batch(last_l1_batch_to_keep)
+            .await
+            .context("get_miniblock_range_of_l1_batch()")?
+            .with_context(|| format!("L1 batch #{last_l1_batch_to_keep} is not sealed"))?;
+        let sealed_l1_batch = storage
+            .blocks_dal()
+            .get_sealed_l1_batch_number()
+            .await
+            .context("get_sealed_l1_batch_number()")?
+            .context("no L1 batches in Postgres")?;
+        let sealed_miniblock = storage
+            .blocks_dal()
+            .get_sealed_miniblock_number()
+            .await
+            .context("get_sealed_miniblock_number()")?
+            .context("no miniblocks in Postgres")?;
+
+        let last_committed = storage
+            .blocks_dal()
+            .get_number_of_last_l1_batch_committed_on_eth()
+            .await
+            .context("get_number_of_last_l1_batch_committed_on_eth()")?;
+        let last_proven = storage
+            .blocks_dal()
+            .get_number_of_last_l1_batch_proven_on_eth()
+            .await
+            .context("get_number_of_last_l1_batch_proven_on_eth()")?;
+        let last_executed = storage
+            .blocks_dal()
+            .get_number_of_last_l1_batch_executed_on_eth()
+            .await
+            .context("get_number_of_last_l1_batch_executed_on_eth()")?;
+        let reverted_count = |last_on_l1: Option<L1BatchNumber>| {
+            last_on_l1.map_or(0, |last| last.0.saturating_sub(last_l1_batch_to_keep.0))
+        };
+        let reverted_on_l1 = RevertedOnL1Counts {
+            committed: reverted_count(last_committed),
+            proven: reverted_count(last_proven),
+            executed: reverted_count(last_executed),
+        };
+
+        let (reset_transactions, reset_l1_transactions) = storage
+            .transactions_dal()
+            .count_transactions_after_miniblock(last_miniblock_to_keep)
+            .await
+            .context("count_transactions_after_miniblock()")?;
+        let inflight_eth_txs = storage
+            .eth_sender_dal()
+            .get_inflight_txs_for_l1_batches_after(last_l1_batch_to_keep)
+            .await
+            .context("get_inflight_txs_for_l1_batches_after()")?
+            .into_iter()
+            .map(|tx| InflightEthTx {
+                id: tx.id,
+                tx_type: tx.tx_type.to_string(),
+                nonce: tx.nonce.0,
+            })
+            .collect();
+        let invalidated_snapshots = storage
+            .snapshots_dal()
+            .get_snapshots_after(last_l1_batch_to_keep)
+            .await
+            .context("get_snapshots_after()")?
+            .into_iter()
+            .map(|snapshot| {
+                let object_store_keys = snapshot
+                    .storage_logs_filepaths
+                    .into_iter()
+                    .flatten()
+                    .chain([snapshot.factory_deps_filepath])
+                    .chain(snapshot.tokens_filepath)
+                    .collect();
+                InvalidatedSnapshot {
+                    l1_batch_number: snapshot.l1_batch_number,
+                    object_store_keys,
+                }
+            })
+            .collect();
+        drop(storage);
+
+        let merkle_tree = if flags.contains(BlockReverterFlags::TREE) {
+            let path = Path::new(&self.merkle_tree_path);
+            let next_l1_batch_number = if path.exists() {
+                let db =
+                    RocksDB::new(path).context("failed initializing RocksDB for Merkle tree")?;
+                Some(ZkSyncTree::new_lightweight(db.into()).next_l1_batch_number())
+            } else {
+                None
+            };
+            Some(RocksdbRevertImpact::new(
+                &self.merkle_tree_path,
+                next_l1_batch_number,
+                last_l1_batch_to_keep,
+            ))
+        } else {
+            None
+        };
+        let state_keeper_cache = if flags.contains(BlockReverterFlags::SK_CACHE) {
+            let path = Path::new(&self.state_keeper_cache_path);
+            let next_l1_batch_number = if path.exists() {
+                RocksdbStorage::builder(path)
+                    .await
+                    .context("failed initializing state keeper cache")?
+                    .l1_batch_number()
+                    .await
+            } else {
+                None
+            };
+            Some(RocksdbRevertImpact::new(
+                &self.state_keeper_cache_path,
+                next_l1_batch_number,
+                last_l1_batch_to_keep,
+            ))
+        } else {
+            None
+        };
+
+        Ok(RevertImpactReport {
+            last_l1_batch_to_keep,
+            last_miniblock_to_keep,
+            reverts_postgres: flags.contains(BlockReverterFlags::POSTGRES),
+            l1_batches: RevertedRange::new(last_l1_batch_to_keep.0, sealed_l1_batch.0),
+            miniblocks: RevertedRange::new(last_miniblock_to_keep.0, sealed_miniblock.0),
+            reverted_on_l1,
+            executed_batches_revert_allowed: matches!(
+                self.executed_batches_revert_mode,
+                L1ExecutedBatchesRevert::Allowed
+            ),
+            reset_transactions,
+            reset_l1_transactions,
+            inflight_eth_txs,
+            merkle_tree,
+            state_keeper_cache,
+            invalidated_snapshots,
+        })
+    }
+
     async fn rollback_rocks_dbs(
         &self,
         last_l1_batch_to_keep: L1BatchNumber,
@@ -455,3 +606,128 @@ pub struct SuggestedRollbackValues {
     pub nonce: u64,
     pub priority_fee: u64,
 }
+
+/// Range of block numbers (L1 batches, miniblocks or tree versions) affected by a revert.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct RevertedRange {
+    pub first: u32,
+    pub last: u32,
+}
+
+impl RevertedRange {
+    /// Returns the range of numbers after `last_to_keep` up to and including `last`, or `None` if it's empty.
+    fn new(last_to_keep: u32, last: u32) -> Option<Self> {
+        (last > last_to_keep).then_some(Self {
+            first: last_to_keep + 1,
+            last,
+        })
+    }
+}
+
+/// Numbers of reverted L1 batches that have been committed, proven or executed on L1.
+#[derive(Debug, Serialize)]
+pub struct RevertedOnL1Counts {
+    pub committed: u32,
+    pub proven: u32,
+    pub executed: u32,
+}
+
+/// Unconfirmed L1 transaction for a reverted L1 batch.
+#[derive(Debug, Serialize)]
+pub struct InflightEthTx {
+    pub id: u32,
+    pub tx_type: String,
+    pub nonce: u32,
+}
+
+/// Snapshot that would be invalidated by the revert, together with its files in the object store.
+#[derive(Debug, Serialize)]
+pub struct InvalidatedSnapshot {
+    pub l1_batch_number: L1BatchNumber,
+    pub object_store_keys: Vec<String>,
+}
+
+/// Revert impact for a RocksDB instance (the Merkle tree or the state keeper cache).
+#[derive(Debug, Serialize)]
+pub struct RocksdbRevertImpact {
+    pub path: String,
+    /// Next L1 batch to be processed by the DB. `None` if the DB doesn't exist or is empty.
+    pub next_l1_batch_number: Option<L1BatchNumber>,
+    /// Reverted L1 batches; for the Merkle tree, these correspond to tree versions.
+    pub l1_batches: Option<RevertedRange>,
+}
+
+impl RocksdbRevertImpact {
+    fn new(
+        path: &str,
+        next_l1_batch_number: Option<L1BatchNumber>,
+        last_l1_batch_to_keep: L1BatchNumber,
+    ) -> Self {
+        let l1_batches = next_l1_batch_number.and_then(|next| {
+            let last = next.0.checked_sub(1)?;
+            RevertedRange::new(last_l1_batch_to_keep.0, last)
+        });
+        Self {
+            path: path.to_owned(),
+            next_l1_batch_number,
+            l1_batches,
+        }
+    }
+}
+
+/// Report on what [`BlockReverter::rollback_db()`] would revert. Used for dry runs of the block reverter.
+#[derive(Debug, Serialize)]
+pub struct RevertImpactReport {
+    pub last_l1_batch_to_keep: L1BatchNumber,
+    pub last_miniblock_to_keep: MiniblockNumber,
+    pub reverts_postgres: bool,
+    /// Reverted L1 batches in Postgres.
+    pub l1_batches: Option<RevertedRange>,
+    /// Reverted miniblocks in Postgres.
+    pub miniblocks: Option<RevertedRange>,
+    pub reverted_on_l1: RevertedOnL1Counts,
+    /// If this is `false` and some executed batches would be reverted, the revert will fail.
+    pub executed_batches_revert_allowed: bool,
+    /// Number of transactions from reverted miniblocks that would be returned to the mempool.
+    pub reset_transactions: usize,
+    /// Number of L1 (priority) transactions among [`Self::reset_transactions`].
+    pub reset_l1_transactions: usize,
+    /// Unconfirmed L1 transactions for reverted L1 batches. These need to be handled manually,
+    /// e.g. by clearing failed transactions after the revert.
+    pub inflight_eth_txs: Vec<InflightEthTx>,
+    /// Set if the Merkle tree is reverted.
+    pub merkle_tree: Option<RocksdbRevertImpact>,
+    /// Set if the state keeper cache is reverted.
+    pub state_keeper_cache: Option<RocksdbRevertImpact>,
+    /// Snapshots created for reverted L1 batches. The block reverter doesn't remove them.
+    pub invalidated_snapshots: Vec<InvalidatedSnapshot>,
+}
+
+impl RevertImpactReport {
+    /// Checks whether the revert affects L1 batches that are already executed on L1.
+    pub fn reverts_executed_batches(&self) -> bool {
+        self.reverted_on_l1.executed > 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reverted_ranges() {
+        assert_eq!(RevertedRange::new(5, 5), None);
+        assert_eq!(RevertedRange::new(5, 3), None);
+        assert_eq!(
+            RevertedRange::new(5, 8),
+            Some(RevertedRange { first: 6, last: 8 })
+        );
+
+        let impact = RocksdbRevertImpact::new("db", Some(L1BatchNumber(9)), L1BatchNumber(5));
+        assert_eq!(impact.l1_batches, Some(RevertedRange { first: 6, last: 8 }));
+        let impact = RocksdbRevertImpact::new("db", Some(L1BatchNumber(6)), L1BatchNumber(5));
+        assert_eq!(impact.l1_batches, None);
+        let impact = RocksdbRevertImpact::new("db", Some(L1BatchNumber(0)), L1BatchNumber(5));
+        assert_eq!(impact.l1_batches, None);
+    }
+}
@@ -0,0 +1,24 @@
+//! Metrics for the L1 consistency checker.
+
+use std::time::Duration;
+
+use vise::{Buckets, Counter, Gauge, Histogram, Metrics, Unit};
+
+/// Metrics for [`ConsistencyChecker`](super::ConsistencyChecker). Unlike general-purpose external node metrics,
+/// these metrics are reported both on the main node and on external nodes.
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "consistency_checker")]
+pub(super) struct ConsistencyCheckerMetrics {
+    /// Number of the last L1 batch successfully checked against L1.
+    pub last_checked_batch: Gauge<u64>,
+    /// Number of the first L1 batch found to be inconsistent with L1, or 0 if no inconsistencies were found.
+    pub first_inconsistent_batch: Gauge<u64>,
+    /// Total number of L1 batches found to be inconsistent with L1.
+    pub inconsistent_batches: Counter,
+    /// Latency of checking a single L1 batch against L1.
+    #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds)]
+    pub batch_check_latency: Histogram<Duration>,
+}
+
+#[vise::register]
+pub(super) static METRICS: vise::Global<ConsistencyCheckerMetrics> = vise::Global::new();
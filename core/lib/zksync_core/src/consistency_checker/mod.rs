@@ -8,13 +8,17 @@ use zksync_dal::{ConnectionPool, StorageProcessor};
 use zksync_eth_client::{clients::QueryClient, Error as L1ClientError, EthInterface};
 use zksync_health_check::{Health, HealthStatus, HealthUpdater, ReactiveHealthCheck};
 use zksync_l1_contract_interface::{i_executor::structures::CommitBatchInfo, Tokenizable};
-use zksync_types::{web3::ethabi, L1BatchNumber, H256};
+use zksync_types::{
+    commitment::L1BatchWithMetadata, pubdata_da::PubdataDA, web3::ethabi, L1BatchNumber, H256,
+};
 
+use self::metrics::METRICS;
 use crate::{
     metrics::{CheckerComponent, EN_METRICS},
     utils::wait_for_l1_batch_with_metadata,
 };
 
+mod metrics;
 #[cfg(test)]
 mod tests;
 
@@ -97,12 +101,22 @@ impl HandleConsistencyCheckerEvent for ConsistencyCheckerHealthUpdater {
         tracing::info!("L1 batch #{last_checked_batch} is consistent with L1");
         EN_METRICS.last_correct_batch[&CheckerComponent::ConsistencyChecker]
             .set(last_checked_batch.0.into());
+        METRICS.last_checked_batch.set(last_checked_batch.0.into());
         self.current_details.last_checked_batch = Some(last_checked_batch);
         self.inner.update(self.current_details.health());
     }
 
     fn report_inconsistent_batch(&mut self, number: L1BatchNumber) {
-        tracing::warn!("L1 batch #{number} is inconsistent with L1");
+        if self.current_details.inconsistent_batches.is_empty() {
+            tracing::error!(
+                "L1 batch #{number} is inconsistent with L1! This is the first detected inconsistency; \
+                 local state may have silently diverged from the state committed on L1"
+            );
+            METRICS.first_inconsistent_batch.set(number.0.into());
+        } else {
+            tracing::warn!("L1 batch #{number} is inconsistent with L1");
+        }
+        METRICS.inconsistent_batches.inc();
         self.current_details.inconsistent_batches.push(number);
         self.inner.update(self.current_details.health());
     }
@@ -123,7 +137,7 @@ enum L1DataMismatchBehavior {
 #[derive(Debug)]
 struct LocalL1BatchCommitData {
     is_pre_boojum: bool,
-    l1_commit_data: ethabi::Token,
+    l1_batch: L1BatchWithMetadata,
     commit_tx_hash: H256,
 }
 
@@ -180,12 +194,52 @@ impl LocalL1BatchCommitData {
 
         Ok(Some(Self {
             is_pre_boojum,
-            l1_commit_data: CommitBatchInfo(&l1_batch).into_token(),
+            l1_batch,
             commit_tx_hash,
         }))
     }
+
+    /// Encodes local commit data, taking pubdata source from the L1 commitment. The source
+    /// is chosen by the main node, so it cannot be inferred from Postgres data.
+    fn l1_commit_data(&self, l1_commitment: &ethabi::Token) -> anyhow::Result<ethabi::Token> {
+        let is_pre_1_4_2 = self
+            .l1_batch
+            .header
+            .protocol_version
+            .map_or(true, |version| version.is_pre_1_4_2());
+        let pubdata_da = if is_pre_1_4_2 {
+            PubdataDA::Calldata
+        } else {
+            Self::extract_pubdata_da(l1_commitment)?
+        };
+        Ok(CommitBatchInfo::new(&self.l1_batch, pubdata_da).into_token())
+    }
+
+    fn extract_pubdata_da(l1_commitment: &ethabi::Token) -> anyhow::Result<PubdataDA> {
+        let ethabi::Token::Tuple(fields) = l1_commitment else {
+            anyhow::bail!("Unexpected signature for L1 commit function");
+        };
+        let pubdata_commitments = fields
+            .last()
+            .cloned()
+            .and_then(ethabi::Token::into_bytes)
+            .context("Unexpected signature for L1 commit function")?;
+        match pubdata_commitments.first() {
+            Some(&byte) if byte == PubdataDA::Calldata as u8 => Ok(PubdataDA::Calldata),
+            Some(&byte) if byte == PubdataDA::Blobs as u8 => Ok(PubdataDA::Blobs),
+            Some(&byte) => anyhow::bail!("Unknown pubdata source in L1 commitment: {byte}"),
+            None => anyhow::bail!("Empty pubdata commitments in L1 commitment"),
+        }
+    }
 }
 
+/// Component verifying that L1 batches stored locally are consistent with L1. For each batch committed on L1,
+/// the checker recomputes its commitment data (including pubdata commitments, i.e., KZG commitments and proofs
+/// for the batches publishing pubdata in blobs) from Postgres and compares it with the data in the calldata
+/// of the commit transaction fetched from L1.
+///
+/// The checker can run both on the main node and on external nodes. Detected inconsistencies are reported
+/// via logs, metrics and the component health check.
 #[derive(Debug)]
 pub struct ConsistencyChecker {
     /// ABI of the zkSync contract
@@ -262,10 +316,11 @@ impl ConsistencyChecker {
                 .with_context(|| {
                     format!("Failed extracting commit data for transaction {commit_tx_hash:?}")
                 })?;
-        Ok(commitment == local.l1_commit_data)
+        let local_commitment = local.l1_commit_data(&commitment)?;
+        Ok(commitment == local_commitment)
     }
 
-    fn extract_commit_data(
+    pub(crate) fn extract_commit_data(
         commit_tx_input_data: &[u8],
         commit_function: &ethabi::Function,
         batch_number: L1BatchNumber,
@@ -370,7 +425,10 @@ impl ConsistencyChecker {
             };
             drop(storage);
 
-            match self.check_commitments(batch_number, &local).await {
+            let check_latency = METRICS.batch_check_latency.start();
+            let check_result = self.check_commitments(batch_number, &local).await;
+            check_latency.observe();
+            match check_result {
                 Ok(true) => {
                     self.event_handler.update_checked_batch(batch_number);
                     batch_number += 1;
@@ -7,6 +7,7 @@ use test_casing::{test_casing, Product};
 use tokio::sync::mpsc;
 use zksync_dal::StorageProcessor;
 use zksync_eth_client::clients::MockEthereum;
+use zksync_health_check::CheckHealth;
 use zksync_l1_contract_interface::i_executor::structures::StoredBatchInfo;
 use zksync_types::{
     aggregated_operations::AggregatedActionType, commitment::L1BatchWithMetadata,
@@ -47,7 +48,7 @@ fn create_pre_boojum_l1_batch_with_metadata(number: u32) -> L1BatchWithMetadata
 fn build_commit_tx_input_data(batches: &[L1BatchWithMetadata]) -> Vec<u8> {
     let commit_tokens = batches
         .iter()
-        .map(|batch| CommitBatchInfo(batch).into_token());
+        .map(|batch| CommitBatchInfo::new(batch, PubdataDA::Calldata).into_token());
     let commit_tokens = ethabi::Token::Array(commit_tokens.collect());
 
     let mut encoded = vec![];
@@ -93,6 +94,29 @@ impl HandleConsistencyCheckerEvent for mpsc::UnboundedSender<L1BatchNumber> {
     }
 }
 
+#[tokio::test]
+async fn health_updater_reports_inconsistent_batches() {
+    let (health_check, mut health_updater) = ConsistencyCheckerHealthUpdater::new();
+    health_updater.initialize();
+    health_updater.set_first_batch_to_check(L1BatchNumber(1));
+    health_updater.update_checked_batch(L1BatchNumber(1));
+    let health = health_check.check_health().await;
+    assert_matches!(health.status(), HealthStatus::Ready);
+
+    health_updater.report_inconsistent_batch(L1BatchNumber(2));
+    health_updater.report_inconsistent_batch(L1BatchNumber(3));
+    let health = health_check.check_health().await;
+    let expected_details = serde_json::json!({
+        "first_checked_batch": 1,
+        "last_checked_batch": 1,
+        "inconsistent_batches": [2, 3],
+    });
+    assert_eq!(
+        health,
+        Health::from(HealthStatus::Affected).with_details(expected_details)
+    );
+}
+
 #[test]
 fn build_commit_tx_input_data_is_correct() {
     let contract = zksync_contracts::zksync_contract();
@@ -111,10 +135,40 @@ fn build_commit_tx_input_data_is_correct() {
             batch.header.number,
         )
         .unwrap();
-        assert_eq!(commit_data, CommitBatchInfo(batch).into_token());
+        assert_eq!(
+            commit_data,
+            CommitBatchInfo::new(batch, PubdataDA::Calldata).into_token()
+        );
     }
 }
 
+#[test]
+fn local_commit_data_uses_pubdata_source_from_l1() {
+    let l1_batch = create_l1_batch_with_metadata(1);
+    assert!(!l1_batch.header.protocol_version.unwrap().is_pre_1_4_2());
+    let local = LocalL1BatchCommitData {
+        is_pre_boojum: false,
+        l1_batch: l1_batch.clone(),
+        commit_tx_hash: H256::zero(),
+    };
+
+    for pubdata_da in [PubdataDA::Calldata, PubdataDA::Blobs] {
+        let l1_commitment = CommitBatchInfo::new(&l1_batch, pubdata_da).into_token();
+        assert_eq!(local.l1_commit_data(&l1_commitment).unwrap(), l1_commitment);
+    }
+
+    let ethabi::Token::Tuple(mut fields) =
+        CommitBatchInfo::new(&l1_batch, PubdataDA::Blobs).into_token()
+    else {
+        unreachable!();
+    };
+    *fields.last_mut().unwrap() = ethabi::Token::Bytes(vec![0xff]);
+    let err = local
+        .l1_commit_data(&ethabi::Token::Tuple(fields))
+        .unwrap_err();
+    assert!(err.to_string().contains("Unknown pubdata source"), "{err}");
+}
+
 #[test]
 fn extracting_commit_data_for_boojum_batch() {
     let contract = zksync_contracts::zksync_contract();
@@ -0,0 +1,60 @@
+use std::time::Duration;
+
+use vise::{
+    Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, Metrics, Unit,
+};
+use zksync_dal::pruning_dal::HardPruningStats;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "prune_type", rename_all = "snake_case")]
+pub(super) enum MetricPruneType {
+    Soft,
+    Hard,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "table", rename_all = "snake_case")]
+pub(super) enum PrunedTable {
+    Miniblocks,
+    Transactions,
+    Events,
+    L2ToL1Logs,
+    StorageLogs,
+}
+
+/// Metrics for the Postgres data pruner.
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "db_pruner")]
+pub(super) struct DbPrunerMetrics {
+    /// Latency of pruning a chunk of L1 batches.
+    #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds)]
+    pub pruning_chunk_duration: Family<MetricPruneType, Histogram<Duration>>,
+    /// Last L1 batch pruned by the pruner.
+    pub last_pruned_l1_batch: Family<MetricPruneType, Gauge<u64>>,
+    /// Number of rows deleted from a table during the last hard pruning of a chunk of L1 batches.
+    pub deleted_rows: Family<PrunedTable, Gauge<u64>>,
+    /// Number of table partitions dropped during hard pruning.
+    pub dropped_partitions: Counter,
+}
+
+impl DbPrunerMetrics {
+    pub fn observe_hard_pruning(&self, stats: &HardPruningStats) {
+        let HardPruningStats {
+            deleted_miniblocks,
+            deleted_transactions,
+            deleted_events,
+            deleted_l2_to_l1_logs,
+            deleted_storage_logs,
+            dropped_partitions,
+        } = *stats;
+        self.deleted_rows[&PrunedTable::Miniblocks].set(deleted_miniblocks);
+        self.deleted_rows[&PrunedTable::Transactions].set(deleted_transactions);
+        self.deleted_rows[&PrunedTable::Events].set(deleted_events);
+        self.deleted_rows[&PrunedTable::L2ToL1Logs].set(deleted_l2_to_l1_logs);
+        self.deleted_rows[&PrunedTable::StorageLogs].set(deleted_storage_logs);
+        self.dropped_partitions.inc_by(dropped_partitions);
+    }
+}
+
+#[vise::register]
+pub(super) static METRICS: vise::Global<DbPrunerMetrics> = vise::Global::new();
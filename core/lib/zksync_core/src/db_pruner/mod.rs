@@ -0,0 +1,257 @@
+//! Pruning of historical node data in Postgres.
+//!
+//! Pruning is performed in two stages. First, a chunk of L1 batches is *soft-pruned*, i.e., marked as pruned
+//! in the storage; the API server stops serving data for soft-pruned L1 batches and miniblocks once it refreshes
+//! information about the first available block. After a configurable delay, the chunk is *hard-pruned*: miniblocks,
+//! transactions, events, L2-to-L1 logs and overwritten storage logs for the chunk are removed from Postgres.
+//! L1 batch headers are retained, since they are used by other node components (e.g., to compute
+//! the previous L1 batch hash).
+
+use std::{
+    num::NonZeroU32,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::Context as _;
+use tokio::sync::watch;
+use zksync_dal::{ConnectionPool, StorageProcessor};
+use zksync_types::L1BatchNumber;
+
+use self::metrics::{MetricPruneType, METRICS};
+
+mod metrics;
+#[cfg(test)]
+mod tests;
+
+/// Configuration of [`DbPruner`].
+#[derive(Debug, Clone)]
+pub struct DbPrunerConfig {
+    /// Delay between soft-pruning and hard-pruning a chunk of L1 batches. Should be significantly larger
+    /// than the interval with which API servers refresh information about pruned blocks.
+    pub soft_and_hard_pruning_time_delta: Duration,
+    /// Sleep interval between pruning iterations if there are no L1 batches to prune.
+    pub next_iterations_delay: Duration,
+    /// Maximum number of L1 batches pruned at a time.
+    pub pruned_batch_chunk_size: NonZeroU32,
+    /// Minimum age of an L1 batch for it to be pruned. The age is measured from the L1 batch timestamp.
+    /// Ignored if [`Self::retained_l1_batches`] is set.
+    pub minimum_l1_batch_age: Duration,
+    /// Number of the latest sealed L1 batches that are never pruned. If set, the node retains a rolling window
+    /// of recent L1 batches; L1 batches outside the window are pruned once they are executed on L1,
+    /// regardless of their age.
+    pub retained_l1_batches: Option<NonZeroU32>,
+}
+
+/// Postgres data pruner. Only L1 batches that are executed on L1 are pruned. At least one L1 batch
+/// with metadata is always retained so that node components relying on the previous L1 batch can proceed.
+#[derive(Debug)]
+pub struct DbPruner {
+    config: DbPrunerConfig,
+    connection_pool: ConnectionPool,
+}
+
+impl DbPruner {
+    pub fn new(config: DbPrunerConfig, connection_pool: ConnectionPool) -> Self {
+        Self {
+            config,
+            connection_pool,
+        }
+    }
+
+    /// Returns the last L1 batch in the next chunk to be soft-pruned, or `None` if there's nothing to prune.
+    async fn next_l1_batch_to_prune(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<Option<L1BatchNumber>> {
+        let pruning_info = storage
+            .pruning_dal()
+            .get_pruning_info()
+            .await
+            .context("failed getting pruning info")?;
+        let Some(earliest_l1_batch) = storage
+            .blocks_dal()
+            .get_earliest_l1_batch_number()
+            .await
+            .context("failed getting earliest L1 batch")?
+        else {
+            return Ok(None);
+        };
+        let first_l1_batch = pruning_info
+            .last_soft_pruned_l1_batch
+            .map_or(earliest_l1_batch, |number| {
+                earliest_l1_batch.max(number + 1)
+            });
+
+        let Some(last_executed_l1_batch) = storage
+            .blocks_dal()
+            .get_number_of_last_l1_batch_executed_on_eth()
+            .await
+            .context("failed getting last executed L1 batch")?
+        else {
+            return Ok(None);
+        };
+        let Some(last_l1_batch_with_metadata) = storage
+            .blocks_dal()
+            .get_last_l1_batch_number_with_metadata()
+            .await
+            .context("failed getting last L1 batch with metadata")?
+        else {
+            return Ok(None);
+        };
+        let Some(last_prunable_l1_batch) = last_l1_batch_with_metadata.checked_sub(1) else {
+            return Ok(None);
+        };
+        let mut last_prunable_l1_batch =
+            last_executed_l1_batch.min(L1BatchNumber(last_prunable_l1_batch));
+        if let Some(retained_l1_batches) = self.config.retained_l1_batches {
+            let sealed_l1_batch = storage
+                .blocks_dal()
+                .get_sealed_l1_batch_number()
+                .await
+                .context("failed getting sealed L1 batch")?
+                .context("no sealed L1 batches")?;
+            let Some(last_outside_window) =
+                sealed_l1_batch.0.checked_sub(retained_l1_batches.get())
+            else {
+                return Ok(None);
+            };
+            last_prunable_l1_batch = last_prunable_l1_batch.min(L1BatchNumber(last_outside_window));
+        }
+
+        let chunk_end = first_l1_batch + (self.config.pruned_batch_chunk_size.get() - 1);
+        let l1_batch = chunk_end.min(last_prunable_l1_batch);
+        if l1_batch < first_l1_batch {
+            return Ok(None);
+        }
+        if self.config.retained_l1_batches.is_some() {
+            // The retained window overrides the age condition.
+            return Ok(Some(l1_batch));
+        }
+
+        let header = storage
+            .blocks_dal()
+            .get_l1_batch_header(l1_batch)
+            .await
+            .with_context(|| format!("failed getting header for L1 batch #{l1_batch}"))?
+            .with_context(|| format!("header for L1 batch #{l1_batch} is missing"))?;
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .context("current time is before Unix epoch")?;
+        let l1_batch_age = now.saturating_sub(Duration::from_secs(header.timestamp));
+        if l1_batch_age < self.config.minimum_l1_batch_age {
+            tracing::debug!(
+                "L1 batch #{l1_batch} is too young to be pruned ({l1_batch_age:?} old, minimum age is {:?})",
+                self.config.minimum_l1_batch_age
+            );
+            return Ok(None);
+        }
+        Ok(Some(l1_batch))
+    }
+
+    /// Soft-prunes the next chunk of L1 batches. Returns `false` if there's nothing to prune.
+    async fn soft_prune(&self, storage: &mut StorageProcessor<'_>) -> anyhow::Result<bool> {
+        let mut transaction = storage.start_transaction().await?;
+        let Some(l1_batch) = self.next_l1_batch_to_prune(&mut transaction).await? else {
+            return Ok(false);
+        };
+
+        let latency = METRICS.pruning_chunk_duration[&MetricPruneType::Soft].start();
+        let (_, last_miniblock) = transaction
+            .blocks_dal()
+            .get_miniblock_range_of_l1_batch(l1_batch)
+            .await
+            .with_context(|| format!("failed getting miniblocks for L1 batch #{l1_batch}"))?
+            .with_context(|| format!("L1 batch #{l1_batch} doesn't contain miniblocks"))?;
+        transaction
+            .pruning_dal()
+            .soft_prune_batches_range(l1_batch, last_miniblock)
+            .await
+            .context("failed soft-pruning L1 batches")?;
+        transaction.commit().await?;
+
+        let latency = latency.observe();
+        METRICS.last_pruned_l1_batch[&MetricPruneType::Soft].set(l1_batch.0.into());
+        tracing::info!(
+            "Soft-pruned data up to L1 batch #{l1_batch} (miniblock #{last_miniblock}) in {latency:?}"
+        );
+        Ok(true)
+    }
+
+    /// Hard-prunes all soft-pruned L1 batches.
+    async fn hard_prune(&self, storage: &mut StorageProcessor<'_>) -> anyhow::Result<()> {
+        let latency = METRICS.pruning_chunk_duration[&MetricPruneType::Hard].start();
+        let pruning_info = storage
+            .pruning_dal()
+            .get_pruning_info()
+            .await
+            .context("failed getting pruning info")?;
+        let (Some(l1_batch), Some(last_miniblock)) = (
+            pruning_info.last_soft_pruned_l1_batch,
+            pruning_info.last_soft_pruned_miniblock,
+        ) else {
+            anyhow::bail!("no soft-pruned L1 batches to hard-prune: {pruning_info:?}");
+        };
+
+        let stats = storage
+            .pruning_dal()
+            .hard_prune_batches_range(l1_batch, last_miniblock)
+            .await
+            .context("failed hard-pruning L1 batches")?;
+
+        let latency = latency.observe();
+        METRICS.last_pruned_l1_batch[&MetricPruneType::Hard].set(l1_batch.0.into());
+        METRICS.observe_hard_pruning(&stats);
+        tracing::info!(
+            "Hard-pruned data up to L1 batch #{l1_batch} (miniblock #{last_miniblock}) in {latency:?}: {stats:?}"
+        );
+        Ok(())
+    }
+
+    pub async fn run(self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        loop {
+            if *stop_receiver.borrow() {
+                break;
+            }
+
+            let mut storage = self
+                .connection_pool
+                .access_storage_tagged("db_pruner")
+                .await?;
+            let pruning_info = storage
+                .pruning_dal()
+                .get_pruning_info()
+                .await
+                .context("failed getting pruning info")?;
+            // Hard pruning may be pending if the node was restarted after soft pruning.
+            let has_pending_hard_pruning =
+                pruning_info.last_soft_pruned_l1_batch > pruning_info.last_hard_pruned_l1_batch;
+            if !has_pending_hard_pruning && !self.soft_prune(&mut storage).await? {
+                drop(storage);
+                tokio::time::timeout(self.config.next_iterations_delay, stop_receiver.changed())
+                    .await
+                    .ok();
+                continue;
+            }
+            drop(storage);
+
+            // Give API servers time to observe soft pruning before the data is removed.
+            if tokio::time::timeout(
+                self.config.soft_and_hard_pruning_time_delta,
+                stop_receiver.changed(),
+            )
+            .await
+            .is_ok()
+            {
+                break;
+            }
+
+            let mut storage = self
+                .connection_pool
+                .access_storage_tagged("db_pruner")
+                .await?;
+            self.hard_prune(&mut storage).await?;
+        }
+        tracing::info!("Stop signal received, DB pruner is shutting down");
+        Ok(())
+    }
+}
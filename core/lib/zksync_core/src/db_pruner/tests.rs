@@ -0,0 +1,229 @@
+//! Tests for the Postgres data pruner.
+
+use zksync_dal::pruning_dal::PruningInfo;
+use zksync_types::{aggregated_operations::AggregatedActionType, L2ChainId, MiniblockNumber, H256};
+
+use super::*;
+use crate::{
+    genesis::{ensure_genesis_state, GenesisParams},
+    utils::testonly::{create_l1_batch, create_l1_batch_metadata, create_miniblock},
+};
+
+fn mock_config(chunk_size: u32) -> DbPrunerConfig {
+    DbPrunerConfig {
+        soft_and_hard_pruning_time_delta: Duration::ZERO,
+        next_iterations_delay: Duration::from_millis(10),
+        pruned_batch_chunk_size: NonZeroU32::new(chunk_size).unwrap(),
+        minimum_l1_batch_age: Duration::ZERO,
+        retained_l1_batches: None,
+    }
+}
+
+/// Inserts an L1 batch with a single miniblock after the genesis.
+async fn insert_l1_batch(storage: &mut StorageProcessor<'_>, number: u32, executed: bool) {
+    storage
+        .blocks_dal()
+        .insert_miniblock(&create_miniblock(number))
+        .await
+        .unwrap();
+    let l1_batch = create_l1_batch(number);
+    storage
+        .blocks_dal()
+        .insert_mock_l1_batch(&l1_batch)
+        .await
+        .unwrap();
+    storage
+        .blocks_dal()
+        .mark_miniblocks_as_executed_in_l1_batch(l1_batch.number)
+        .await
+        .unwrap();
+    storage
+        .blocks_dal()
+        .save_l1_batch_tree_data(
+            l1_batch.number,
+            &create_l1_batch_metadata(number).tree_data(),
+        )
+        .await
+        .unwrap();
+    if executed {
+        storage
+            .eth_sender_dal()
+            .insert_bogus_confirmed_eth_tx(
+                l1_batch.number,
+                AggregatedActionType::Execute,
+                H256::from_low_u64_be(number.into()),
+                chrono::Utc::now(),
+            )
+            .await
+            .unwrap();
+    }
+}
+
+async fn prepare_storage(pool: &ConnectionPool, executed_l1_batches: u32, l1_batches: u32) {
+    let mut storage = pool.access_storage().await.unwrap();
+    ensure_genesis_state(&mut storage, L2ChainId::default(), &GenesisParams::mock())
+        .await
+        .unwrap();
+    for number in 1..=l1_batches {
+        insert_l1_batch(&mut storage, number, number <= executed_l1_batches).await;
+    }
+}
+
+#[tokio::test]
+async fn pruning_chunks_of_l1_batches() {
+    let pool = ConnectionPool::test_pool().await;
+    prepare_storage(&pool, 5, 6).await;
+    let pruner = DbPruner::new(mock_config(2), pool.clone());
+    let mut storage = pool.access_storage().await.unwrap();
+
+    // Genesis and L1 batch #1 should be soft-pruned.
+    assert!(pruner.soft_prune(&mut storage).await.unwrap());
+    let info = storage.pruning_dal().get_pruning_info().await.unwrap();
+    assert_eq!(info.last_soft_pruned_l1_batch, Some(L1BatchNumber(1)));
+    assert_eq!(info.last_soft_pruned_miniblock, Some(MiniblockNumber(1)));
+    assert_eq!(info.last_hard_pruned_l1_batch, None);
+
+    pruner.hard_prune(&mut storage).await.unwrap();
+    let info = storage.pruning_dal().get_pruning_info().await.unwrap();
+    assert_eq!(info.last_hard_pruned_l1_batch, Some(L1BatchNumber(1)));
+    assert_eq!(info.last_hard_pruned_miniblock, Some(MiniblockNumber(1)));
+    let pruned_miniblock = storage
+        .blocks_dal()
+        .get_miniblock_header(MiniblockNumber(1))
+        .await
+        .unwrap();
+    assert!(pruned_miniblock.is_none());
+
+    assert!(pruner.soft_prune(&mut storage).await.unwrap());
+    let info = storage.pruning_dal().get_pruning_info().await.unwrap();
+    assert_eq!(info.last_soft_pruned_l1_batch, Some(L1BatchNumber(3)));
+
+    // L1 batch #5 is executed, but it's the last L1 batch with metadata, so it must be retained.
+    assert!(pruner.soft_prune(&mut storage).await.unwrap());
+    let info = storage.pruning_dal().get_pruning_info().await.unwrap();
+    assert_eq!(info.last_soft_pruned_l1_batch, Some(L1BatchNumber(4)));
+    assert!(!pruner.soft_prune(&mut storage).await.unwrap());
+}
+
+#[tokio::test]
+async fn unexecuted_l1_batches_are_not_pruned() {
+    let pool = ConnectionPool::test_pool().await;
+    prepare_storage(&pool, 0, 3).await;
+    let pruner = DbPruner::new(mock_config(10), pool.clone());
+    let mut storage = pool.access_storage().await.unwrap();
+
+    assert!(!pruner.soft_prune(&mut storage).await.unwrap());
+    let info = storage.pruning_dal().get_pruning_info().await.unwrap();
+    assert_eq!(info, PruningInfo::default());
+}
+
+#[tokio::test]
+async fn recent_l1_batches_are_not_pruned() {
+    let pool = ConnectionPool::test_pool().await;
+    prepare_storage(&pool, 3, 4).await;
+    let config = DbPrunerConfig {
+        minimum_l1_batch_age: Duration::from_secs(3_600),
+        ..mock_config(10)
+    };
+    // Mock L1 batches have timestamps close to the Unix epoch, so they are old enough.
+    let pruner = DbPruner::new(config.clone(), pool.clone());
+    let mut storage = pool.access_storage().await.unwrap();
+    assert!(pruner.soft_prune(&mut storage).await.unwrap());
+    let info = storage.pruning_dal().get_pruning_info().await.unwrap();
+    assert_eq!(info.last_soft_pruned_l1_batch, Some(L1BatchNumber(3)));
+
+    let config = DbPrunerConfig {
+        minimum_l1_batch_age: Duration::MAX,
+        ..config
+    };
+    let pool = ConnectionPool::test_pool().await;
+    prepare_storage(&pool, 3, 4).await;
+    let pruner = DbPruner::new(config, pool.clone());
+    let mut storage = pool.access_storage().await.unwrap();
+    assert!(!pruner.soft_prune(&mut storage).await.unwrap());
+}
+
+#[tokio::test]
+async fn pruning_retains_window_of_recent_l1_batches() {
+    let pool = ConnectionPool::test_pool().await;
+    prepare_storage(&pool, 8, 8).await;
+    let config = DbPrunerConfig {
+        retained_l1_batches: NonZeroU32::new(5),
+        ..mock_config(10)
+    };
+    let pruner = DbPruner::new(config, pool.clone());
+    let mut storage = pool.access_storage().await.unwrap();
+
+    // L1 batches #4..=8 are within the retained window.
+    assert!(pruner.soft_prune(&mut storage).await.unwrap());
+    let info = storage.pruning_dal().get_pruning_info().await.unwrap();
+    assert_eq!(info.last_soft_pruned_l1_batch, Some(L1BatchNumber(3)));
+    assert!(!pruner.soft_prune(&mut storage).await.unwrap());
+
+    // The window moves as new L1 batches are sealed.
+    insert_l1_batch(&mut storage, 9, true).await;
+    assert!(pruner.soft_prune(&mut storage).await.unwrap());
+    let info = storage.pruning_dal().get_pruning_info().await.unwrap();
+    assert_eq!(info.last_soft_pruned_l1_batch, Some(L1BatchNumber(4)));
+}
+
+#[tokio::test]
+async fn retained_window_overrides_minimum_l1_batch_age() {
+    let pool = ConnectionPool::test_pool().await;
+    prepare_storage(&pool, 8, 8).await;
+    let config = DbPrunerConfig {
+        minimum_l1_batch_age: Duration::MAX,
+        retained_l1_batches: NonZeroU32::new(5),
+        ..mock_config(10)
+    };
+    let pruner = DbPruner::new(config, pool.clone());
+    let mut storage = pool.access_storage().await.unwrap();
+
+    assert!(pruner.soft_prune(&mut storage).await.unwrap());
+    let info = storage.pruning_dal().get_pruning_info().await.unwrap();
+    assert_eq!(info.last_soft_pruned_l1_batch, Some(L1BatchNumber(3)));
+}
+
+#[tokio::test]
+async fn pruner_resumes_hard_pruning_after_restart() {
+    let pool = ConnectionPool::test_pool().await;
+    prepare_storage(&pool, 3, 4).await;
+    let mut storage = pool.access_storage().await.unwrap();
+    storage
+        .pruning_dal()
+        .soft_prune_batches_range(L1BatchNumber(2), MiniblockNumber(2))
+        .await
+        .unwrap();
+    drop(storage);
+
+    let pruner = DbPruner::new(mock_config(1), pool.clone());
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let pruner_task = tokio::spawn(pruner.run(stop_receiver));
+
+    loop {
+        let mut storage = pool.access_storage().await.unwrap();
+        let info = storage.pruning_dal().get_pruning_info().await.unwrap();
+        if info.last_hard_pruned_l1_batch == Some(L1BatchNumber(3)) {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    stop_sender.send_replace(true);
+    pruner_task.await.unwrap().unwrap();
+
+    let mut storage = pool.access_storage().await.unwrap();
+    for number in 0..=3 {
+        let pruned_miniblock = storage
+            .blocks_dal()
+            .get_miniblock_header(MiniblockNumber(number))
+            .await
+            .unwrap();
+        assert!(pruned_miniblock.is_none(), "{number}");
+    }
+    let retained_miniblock = storage
+        .blocks_dal()
+        .get_miniblock_header(MiniblockNumber(4))
+        .await
+        .unwrap();
+    assert!(retained_miniblock.is_some());
+}
@@ -1,6 +1,8 @@
 use std::sync::Arc;
 
-use zksync_config::configs::eth_sender::{ProofLoadingMode, ProofSendingMode, SenderConfig};
+use zksync_config::configs::eth_sender::{
+    ProofLoadingMode, ProofSendingMode, PubdataSendingMode, SenderConfig,
+};
 use zksync_contracts::BaseSystemContractsHashes;
 use zksync_dal::StorageProcessor;
 use zksync_l1_contract_interface::i_executor::methods::{
@@ -10,8 +12,8 @@ use zksync_object_store::{ObjectStore, ObjectStoreError};
 use zksync_prover_interface::outputs::L1BatchProofForL1;
 use zksync_types::{
     aggregated_operations::AggregatedActionType, commitment::L1BatchWithMetadata,
-    helpers::unix_timestamp_ms, protocol_version::L1VerifierConfig, L1BatchNumber,
-    ProtocolVersionId,
+    helpers::unix_timestamp_ms, protocol_version::L1VerifierConfig, pubdata_da::PubdataDA,
+    L1BatchNumber, ProtocolVersionId,
 };
 
 use super::{
@@ -33,11 +35,12 @@ pub struct Aggregator {
 
 impl Aggregator {
     pub fn new(config: SenderConfig, blob_store: Arc<dyn ObjectStore>) -> Self {
+        let max_l1_batches_to_commit = Self::max_l1_batches_to_commit(&config);
         Self {
             commit_criteria: vec![
                 Box::from(NumberCriterion {
                     op: AggregatedActionType::Commit,
-                    limit: config.max_aggregated_blocks_to_commit,
+                    limit: max_l1_batches_to_commit,
                 }),
                 Box::from(GasCriterion::new(
                     AggregatedActionType::Commit,
@@ -91,6 +94,15 @@ impl Aggregator {
         }
     }
 
+    /// The L1 contract expects blobs of each committed L1 batch to start from the first blob
+    /// of the transaction, so only a single L1 batch can be committed per blob transaction.
+    fn max_l1_batches_to_commit(config: &SenderConfig) -> u32 {
+        match config.pubdata_sending_mode {
+            PubdataSendingMode::Calldata => config.max_aggregated_blocks_to_commit,
+            PubdataSendingMode::Blobs => 1,
+        }
+    }
+
     pub async fn get_next_ready_operation(
         &mut self,
         storage: &mut StorageProcessor<'_>,
@@ -129,7 +141,7 @@ impl Aggregator {
         } else {
             self.get_commit_operation(
                 storage,
-                self.config.max_aggregated_blocks_to_commit as usize,
+                Self::max_l1_batches_to_commit(&self.config) as usize,
                 last_sealed_l1_batch_number,
                 base_system_contracts_hashes,
                 protocol_version_id,
@@ -220,9 +232,15 @@ impl Aggregator {
         )
         .await;
 
+        let pubdata_da = if protocol_version_id.is_pre_1_4_2() {
+            PubdataDA::Calldata
+        } else {
+            self.config.pubdata_sending_mode.into()
+        };
         batches.map(|batches| CommitBatches {
             last_committed_l1_batch,
             l1_batches: batches,
+            pubdata_da,
         })
     }
 
@@ -1,4 +1,6 @@
-use zksync_types::web3::contract;
+use zksync_types::{
+    aggregated_operations::AggregatedActionType, web3::contract, Address, Nonce, H256,
+};
 
 #[derive(Debug, thiserror::Error)]
 pub enum ETHSenderError {
@@ -6,4 +8,44 @@ pub enum ETHSenderError {
     EthereumGateWayError(#[from] zksync_eth_client::Error),
     #[error("Token parsing Error: {0}")]
     ParseError(#[from] contract::Error),
+    #[error("Transaction simulation reverted: {0}")]
+    SimulationReverted(String),
+    #[error("Irrecoverable nonce mismatch: {0}")]
+    NonceMismatch(#[from] NonceMismatch),
+    #[error("Operator rotation is in progress")]
+    OperatorRotationInProgress,
+    #[error(
+        "Operator was rotated to {0:?}, which is configured neither as the primary nor as the secondary operator"
+    )]
+    UnknownOperator(Address),
+}
+
+/// Mismatch between operator nonces tracked in the database and the on-chain state that cannot be repaired
+/// automatically. Such mismatches are usually caused by transactions sent from the operator account
+/// bypassing `eth_sender`; they require manual intervention.
+#[derive(Debug, thiserror::Error)]
+pub enum NonceMismatch {
+    #[error(
+        "eth_tx {eth_tx_id} ({tx_type}) with nonce {nonce} is orphaned: the operator nonce on the finalized L1 block \
+         is {finalized_nonce}, but none of the eth_tx attempts {tx_hashes:?} is mined. The eth_tx cannot be resent \
+         with a new nonce since eth_txs following it were already sent"
+    )]
+    OrphanedTx {
+        eth_tx_id: u32,
+        tx_type: AggregatedActionType,
+        nonce: Nonce,
+        finalized_nonce: Nonce,
+        tx_hashes: Vec<H256>,
+    },
+    #[error(
+        "eth_tx {eth_tx_id} ({tx_type}) with nonce {nonce} is the earliest eth_tx not mined on L1, but the pending \
+         operator nonce is {pending_nonce}; nonces {pending_nonce}..{nonce} are not used by any eth_tx, \
+         so sent eth_txs will never be mined"
+    )]
+    NonceGap {
+        eth_tx_id: u32,
+        tx_type: AggregatedActionType,
+        nonce: Nonce,
+        pending_nonce: Nonce,
+    },
 }
@@ -1,20 +1,23 @@
 use std::{convert::TryInto, sync::Arc};
 
+use anyhow::Context as _;
 use tokio::sync::watch;
 use zksync_config::configs::eth_sender::SenderConfig;
 use zksync_contracts::BaseSystemContractsHashes;
 use zksync_dal::{ConnectionPool, StorageProcessor};
 use zksync_eth_client::{BoundEthInterface, CallFunctionArgs};
 use zksync_l1_contract_interface::{
+    i_executor::{commit::kzg::KzgInfo, methods::CommitBatches},
     multicall3::{Multicall3Call, Multicall3Result},
     Detokenize, Tokenizable, Tokenize,
 };
 use zksync_types::{
     commitment::SerializeCommitment,
-    eth_sender::EthTx,
+    eth_sender::{EthTx, EthTxBlobSidecar, EthTxBlobSidecarV1, SidecarBlobV1},
     ethabi::Token,
     l2_to_l1_log::UserL2ToL1Log,
     protocol_version::{L1VerifierConfig, VerifierParams},
+    pubdata_da::PubdataDA,
     web3::contract::Error as Web3ContractError,
     Address, L2ChainId, ProtocolVersionId, H256, U256,
 };
@@ -23,6 +26,7 @@ use super::aggregated_operations::AggregatedOperation;
 use crate::{
     eth_sender::{
         metrics::{PubdataKind, METRICS},
+        operator_rotation::OperatorAccounts,
         zksync_functions::ZkSyncFunctions,
         Aggregator, ETHSenderError,
     },
@@ -45,13 +49,16 @@ pub struct MulticallData {
 #[derive(Debug)]
 pub struct EthTxAggregator {
     aggregator: Aggregator,
-    eth_client: Arc<dyn BoundEthInterface>,
+    operators: OperatorAccounts,
     config: SenderConfig,
     timelock_contract_address: Address,
     l1_multicall3_address: Address,
     pub(super) main_zksync_contract_address: Address,
     functions: ZkSyncFunctions,
+    /// Pending nonce of the active operator account at the start of the aggregator or after the operator rotation.
     base_nonce: u64,
+    /// Pending nonce of the blob operator account at the start of the aggregator.
+    blob_base_nonce: u64,
     rollup_chain_id: L2ChainId,
 }
 
@@ -74,21 +81,44 @@ impl EthTxAggregator {
         Self {
             config,
             aggregator,
-            eth_client,
+            operators: OperatorAccounts::new(eth_client),
             timelock_contract_address,
             l1_multicall3_address,
             main_zksync_contract_address,
             functions,
             base_nonce,
+            blob_base_nonce: 0,
             rollup_chain_id,
         }
     }
 
+    /// Sets the secondary operator account, which the operator can be rotated to.
+    pub fn with_secondary_operator(mut self, eth_client: Arc<dyn BoundEthInterface>) -> Self {
+        self.operators.set_secondary(eth_client);
+        self
+    }
+
+    /// Sets the blob operator account, which sends commit transactions instead of the operator account.
+    pub async fn with_blob_operator(mut self, eth_client: Arc<dyn BoundEthInterface>) -> Self {
+        self.blob_base_nonce = eth_client
+            .pending_nonce("eth_sender")
+            .await
+            .unwrap()
+            .as_u64();
+        self.operators.set_blob_operator(eth_client);
+        self
+    }
+
     pub async fn run(
         mut self,
         pool: ConnectionPool,
         stop_receiver: watch::Receiver<bool>,
     ) -> anyhow::Result<()> {
+        {
+            let mut storage = pool.access_storage_tagged("eth_sender").await.unwrap();
+            self.operators.attribute_untracked_txs(&mut storage).await;
+        }
+
         loop {
             let mut storage = pool.access_storage_tagged("eth_sender").await.unwrap();
 
@@ -98,6 +128,10 @@ impl EthTxAggregator {
             }
 
             if let Err(err) = self.loop_iteration(&mut storage).await {
+                if let ETHSenderError::UnknownOperator(_) = &err {
+                    tracing::error!("Halting eth_tx_aggregator: {err}");
+                    return Err(err).context("operator accounts are misconfigured");
+                }
                 // Web3 API request failures can cause this,
                 // and anything more important is already properly reported.
                 tracing::warn!("eth_sender error {err:?}");
@@ -114,7 +148,7 @@ impl EthTxAggregator {
             self.l1_multicall3_address,
             self.functions.multicall_contract.clone(),
         );
-        let aggregate3_result = self.eth_client.call_contract_function(args).await?;
+        let aggregate3_result = self.operators.active().call_contract_function(args).await?;
         self.parse_multicall_data(Token::from_tokens(aggregate3_result)?)
     }
 
@@ -306,15 +340,46 @@ impl EthTxAggregator {
         let get_vk_hash = &self.functions.verification_key_hash;
         let args = CallFunctionArgs::new(&get_vk_hash.name, ())
             .for_contract(verifier_address, self.functions.verifier_contract.clone());
-        let vk_hash = self.eth_client.call_contract_function(args).await?;
+        let vk_hash = self.operators.active().call_contract_function(args).await?;
         Ok(H256::from_tokens(vk_hash)?)
     }
 
+    /// Switches to the operator account selected by the latest completed operator rotation, if necessary.
+    pub(super) async fn sync_operator(
+        &mut self,
+        storage: &mut StorageProcessor<'_>,
+    ) -> Result<(), ETHSenderError> {
+        if self.operators.sync(storage).await? {
+            self.base_nonce = self
+                .operators
+                .active()
+                .pending_nonce("eth_sender")
+                .await?
+                .as_u64();
+        }
+        Ok(())
+    }
+
     #[tracing::instrument(skip(self, storage))]
     async fn loop_iteration(
         &mut self,
         storage: &mut StorageProcessor<'_>,
     ) -> Result<(), ETHSenderError> {
+        self.sync_operator(storage).await?;
+        let pending_rotation = storage
+            .eth_sender_dal()
+            .get_latest_operator_rotation()
+            .await
+            .unwrap()
+            .filter(|rotation| !rotation.is_completed());
+        if let Some(rotation) = pending_rotation {
+            tracing::info!(
+                "Operator rotation #{} is in progress; not creating new eth_txs until it's completed",
+                rotation.id
+            );
+            return Ok(());
+        }
+
         let MulticallData {
             base_system_contracts_hashes,
             verifier_params,
@@ -362,9 +427,10 @@ impl EthTxAggregator {
     ) {
         let l1_batch_number_range = aggregated_op.l1_batch_range();
         tracing::info!(
-            "eth_tx with ID {} for op {} was saved for L1 batches {l1_batch_number_range:?}",
-            tx.id,
-            aggregated_op.get_action_caption()
+            eth_tx_id = tx.id,
+            op = aggregated_op.get_action_caption(),
+            l1_batches = ?l1_batch_number_range,
+            "eth_tx was saved"
         );
 
         if let AggregatedOperation::Commit(commit_op) = &aggregated_op {
@@ -453,6 +519,13 @@ impl EthTxAggregator {
         }
     }
 
+    #[tracing::instrument(
+        skip_all,
+        fields(
+            l1_batches = ?aggregated_op.l1_batch_range(),
+            op_type = ?aggregated_op.get_action_type(),
+        )
+    )]
     pub(super) async fn save_eth_tx(
         &self,
         storage: &mut StorageProcessor<'_>,
@@ -460,10 +533,30 @@ impl EthTxAggregator {
         contracts_are_pre_shared_bridge: bool,
     ) -> Result<EthTx, ETHSenderError> {
         let mut transaction = storage.start_transaction().await.unwrap();
-        let nonce = self.get_next_nonce(&mut transaction).await?;
+        // Prevents races with nonces being reassigned by `EthTxManager`.
+        transaction
+            .eth_sender_dal()
+            .lock_nonce_assignment()
+            .await
+            .unwrap();
+        // Check that the operator wasn't rotated concurrently; `EthTxManager` completes rotations holding the same lock.
+        let operator_address = self.operators.active().sender_account();
+        let mut dal = transaction.eth_sender_dal();
+        let is_rotation_pending = dal
+            .get_latest_operator_rotation()
+            .await
+            .unwrap()
+            .map_or(false, |rotation| !rotation.is_completed());
+        let current_operator = dal.get_current_operator_address().await.unwrap();
+        if is_rotation_pending || current_operator.map_or(false, |addr| addr != operator_address) {
+            return Err(ETHSenderError::OperatorRotationInProgress);
+        }
+
+        let op_type = aggregated_op.get_action_type();
+        let from_address = self.operators.sender_for(op_type).sender_account();
+        let nonce = self.get_next_nonce(&mut transaction, from_address).await?;
         let calldata = self.encode_aggregated_op(aggregated_op, contracts_are_pre_shared_bridge);
         let l1_batch_number_range = aggregated_op.l1_batch_range();
-        let op_type = aggregated_op.get_action_type();
 
         let predicted_gas_for_batches = transaction
             .blocks_dal()
@@ -471,6 +564,12 @@ impl EthTxAggregator {
             .await
             .unwrap();
         let eth_tx_predicted_gas = agg_l1_batch_base_cost(op_type) + predicted_gas_for_batches;
+        let blob_sidecar = match aggregated_op {
+            AggregatedOperation::Commit(op) if op.pubdata_da == PubdataDA::Blobs => {
+                Some(Self::blob_sidecar(op))
+            }
+            _ => None,
+        };
 
         let eth_tx = transaction
             .eth_sender_dal()
@@ -480,6 +579,8 @@ impl EthTxAggregator {
                 op_type,
                 self.timelock_contract_address,
                 eth_tx_predicted_gas,
+                blob_sidecar,
+                from_address,
             )
             .await
             .unwrap();
@@ -493,18 +594,48 @@ impl EthTxAggregator {
         Ok(eth_tx)
     }
 
+    /// Builds the sidecar with pubdata blobs for a commit operation. The order of blobs must
+    /// correspond to the order of KZG commitments in the calldata.
+    fn blob_sidecar(op: &CommitBatches) -> EthTxBlobSidecar {
+        let blobs = op
+            .l1_batches
+            .iter()
+            .flat_map(|batch| {
+                let pubdata = batch
+                    .header
+                    .pubdata_input
+                    .clone()
+                    .unwrap_or_else(|| batch.construct_pubdata());
+                KzgInfo::for_pubdata(&pubdata)
+            })
+            .map(|kzg_info| SidecarBlobV1 {
+                blob: kzg_info.blob.to_vec(),
+                commitment: kzg_info.kzg_commitment.to_vec(),
+                proof: kzg_info.blob_proof.to_vec(),
+                versioned_hash: kzg_info.versioned_hash.to_vec(),
+            })
+            .collect();
+        EthTxBlobSidecarV1 { blobs }.into()
+    }
+
     async fn get_next_nonce(
         &self,
         storage: &mut StorageProcessor<'_>,
+        from_address: Address,
     ) -> Result<u64, ETHSenderError> {
         let db_nonce = storage
             .eth_sender_dal()
-            .get_next_nonce()
+            .get_next_nonce(from_address)
             .await
             .unwrap()
             .unwrap_or(0);
         // Between server starts we can execute some txs using operator account or remove some txs from the database
         // At the start we have to consider this fact and get the max nonce.
-        Ok(db_nonce.max(self.base_nonce))
+        let base_nonce = if from_address == self.operators.active().sender_account() {
+            self.base_nonce
+        } else {
+            self.blob_base_nonce
+        };
+        Ok(db_nonce.max(base_nonce))
     }
 }
@@ -2,29 +2,43 @@ use std::{sync::Arc, time::Duration};
 
 use anyhow::Context as _;
 use tokio::sync::watch;
-use zksync_config::configs::eth_sender::SenderConfig;
+use zksync_config::configs::eth_sender::{SenderConfig, TxSubmissionMode};
 use zksync_dal::{ConnectionPool, StorageProcessor};
 use zksync_eth_client::{
-    BoundEthInterface, Error, EthInterface, ExecutedTxStatus, RawTransactionBytes, SignedCallResult,
+    clients::PrivateRelayClient, BoundEthInterface, Error, EthInterface, ExecutedTxStatus,
+    RawTransactionBytes, SignedCallResult,
 };
 use zksync_types::{
     eth_sender::EthTx,
     web3::{
         contract::Options,
         error::Error as Web3Error,
-        types::{BlockId, BlockNumber},
+        types::{BlockId, BlockNumber, CallRequest},
     },
-    L1BlockNumber, Nonce, H256, U256,
+    L1BlockNumber, Nonce, EIP_4844_TX_TYPE, H256, U256,
 };
 use zksync_utils::time::seconds_since_epoch;
 
-use super::{metrics::METRICS, ETHSenderError};
+use super::{
+    fee_bumping::FeeBumpingPolicy,
+    metrics::{NonceRepairKind, RelaySubmissionResult, ResendSkipReason, METRICS},
+    operator_rotation::OperatorAccounts,
+    simulation::RevertDecoder,
+    ETHSenderError, NonceMismatch,
+};
 use crate::{l1_gas_price::L1TxParamsProvider, metrics::BlockL1Stage};
 
+/// Checks whether the signed transaction is a blob (EIP-4844) transaction.
+fn is_blob_tx(raw_tx: &RawTransactionBytes) -> bool {
+    raw_tx.as_ref().first() == Some(&EIP_4844_TX_TYPE)
+}
+
 #[derive(Debug)]
 struct EthFee {
     base_fee_per_gas: u64,
     priority_fee_per_gas: u64,
+    /// Only set for blob transactions.
+    blob_base_fee_per_gas: Option<u64>,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -46,12 +60,21 @@ pub(super) struct L1BlockNumbers {
 /// Based on eth_tx queue the component generates new attempt with the minimum possible fee,
 /// save it to the database, and send it to Ethereum.
 /// Based on eth_tx_history queue the component can mark txs as stuck and create the new attempt
-/// with higher gas price
+/// with higher gas price, according to the [`FeeBumpingPolicy`].
+/// If a [`PrivateRelayClient`] is provided, transactions are submitted via it instead of the public mempool
+/// (except for blob transactions, which are not supported by private relays).
+/// If a secondary operator account is provided, the component completes operator rotations requested
+/// via the admin API; see [`Self::process_operator_rotation()`].
+/// If a blob operator account is provided, commit transactions are signed and sent from it; nonces of each account
+/// are tracked independently.
 #[derive(Debug)]
 pub struct EthTxManager {
-    ethereum_gateway: Arc<dyn BoundEthInterface>,
+    operators: OperatorAccounts,
     config: SenderConfig,
+    fee_bumping_policy: FeeBumpingPolicy,
     gas_adjuster: Arc<dyn L1TxParamsProvider>,
+    revert_decoder: RevertDecoder,
+    private_relay: Option<PrivateRelayClient>,
 }
 
 impl EthTxManager {
@@ -61,17 +84,49 @@ impl EthTxManager {
         ethereum_gateway: Arc<dyn BoundEthInterface>,
     ) -> Self {
         Self {
-            ethereum_gateway,
+            operators: OperatorAccounts::new(ethereum_gateway),
+            fee_bumping_policy: FeeBumpingPolicy::from_config(&config),
             config,
             gas_adjuster,
+            revert_decoder: RevertDecoder::default(),
+            private_relay: None,
         }
     }
 
+    /// Submits transactions via the specified private relay instead of the public mempool.
+    pub fn with_private_relay(mut self, private_relay: Option<PrivateRelayClient>) -> Self {
+        self.private_relay = private_relay;
+        self
+    }
+
+    /// Sets the secondary operator account, which the operator can be rotated to.
+    pub fn with_secondary_operator(mut self, ethereum_gateway: Arc<dyn BoundEthInterface>) -> Self {
+        self.operators.set_secondary(ethereum_gateway);
+        self
+    }
+
+    /// Sets the blob operator account, which sends commit transactions instead of the operator account.
+    pub fn with_blob_operator(mut self, ethereum_gateway: Arc<dyn BoundEthInterface>) -> Self {
+        self.operators.set_blob_operator(ethereum_gateway);
+        self
+    }
+
+    /// Returns the operator account that sends `tx`.
+    fn tx_operator(&self, tx: &EthTx) -> Result<Arc<dyn BoundEthInterface>, ETHSenderError> {
+        let Some(address) = tx.from_addr else {
+            return Ok(self.operators.active().clone());
+        };
+        self.operators
+            .sender(address)
+            .cloned()
+            .ok_or(ETHSenderError::UnknownOperator(address))
+    }
+
     async fn get_tx_status(
-        &self,
+        operator: &dyn BoundEthInterface,
         tx_hash: H256,
     ) -> Result<Option<ExecutedTxStatus>, ETHSenderError> {
-        self.ethereum_gateway
+        operator
             .get_tx_status(tx_hash, "eth_tx_manager")
             .await
             .map_err(Into::into)
@@ -80,6 +135,7 @@ impl EthTxManager {
     async fn check_all_sending_attempts(
         &self,
         storage: &mut StorageProcessor<'_>,
+        operator: &dyn BoundEthInterface,
         op: &EthTx,
     ) -> Option<ExecutedTxStatus> {
         // Checking history items, starting from most recently sent.
@@ -92,7 +148,7 @@ impl EthTxManager {
             // `status` is a Result here and we don't unwrap it with `?`
             // because if we do and get an `Err`, we won't finish the for loop,
             // which means we might miss the transaction that actually succeeded.
-            match self.get_tx_status(history_item.tx_hash).await {
+            match Self::get_tx_status(operator, history_item.tx_hash).await {
                 Ok(Some(s)) => return Some(s),
                 Ok(_) => continue,
                 Err(err) => tracing::warn!(
@@ -113,7 +169,7 @@ impl EthTxManager {
     ) -> Result<EthFee, ETHSenderError> {
         let base_fee_per_gas = self.gas_adjuster.get_base_fee(time_in_mempool);
 
-        let priority_fee_per_gas = if time_in_mempool != 0 {
+        let mut priority_fee_per_gas = if time_in_mempool != 0 {
             METRICS.transaction_resent.inc();
             let priority_fee_per_gas = self
                 .increase_priority_fee(storage, tx.id, base_fee_per_gas)
@@ -129,6 +185,24 @@ impl EthTxManager {
             self.gas_adjuster.get_priority_fee()
         };
 
+        let (capped_base_fee_per_gas, capped_priority_fee_per_gas) = self
+            .fee_bumping_policy
+            .cap_fees(base_fee_per_gas, priority_fee_per_gas);
+        if (capped_base_fee_per_gas, capped_priority_fee_per_gas)
+            != (base_fee_per_gas, priority_fee_per_gas)
+        {
+            METRICS.capped_fees.inc();
+            tracing::warn!(
+                "Fees for operation {} are capped by `max_fee_per_gas` {:?}: \
+                 base_fee_per_gas {base_fee_per_gas} -> {capped_base_fee_per_gas}, \
+                 priority_fee_per_gas {priority_fee_per_gas} -> {capped_priority_fee_per_gas}",
+                tx.id,
+                self.fee_bumping_policy.max_fee_per_gas
+            );
+        }
+        let base_fee_per_gas = capped_base_fee_per_gas;
+        priority_fee_per_gas = capped_priority_fee_per_gas;
+
         // Extra check to prevent sending transaction will extremely high priority fee.
         if priority_fee_per_gas > self.config.max_acceptable_priority_fee_in_gwei {
             panic!(
@@ -138,12 +212,44 @@ impl EthTxManager {
             );
         }
 
+        let blob_base_fee_per_gas = if tx.blob_sidecar.is_some() {
+            Some(
+                self.calculate_blob_base_fee(storage, tx.id, time_in_mempool)
+                    .await?,
+            )
+        } else {
+            None
+        };
+
         Ok(EthFee {
             base_fee_per_gas,
             priority_fee_per_gas,
+            blob_base_fee_per_gas,
         })
     }
 
+    async fn calculate_blob_base_fee(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        eth_tx_id: u32,
+        time_in_mempool: u32,
+    ) -> Result<u64, ETHSenderError> {
+        let blob_base_fee_per_gas = self.gas_adjuster.get_blob_base_fee();
+        if time_in_mempool == 0 {
+            return Ok(blob_base_fee_per_gas);
+        }
+
+        let previous_sent_tx = storage
+            .eth_sender_dal()
+            .get_last_sent_eth_tx(eth_tx_id)
+            .await
+            .unwrap()
+            .unwrap();
+        // Replacing a blob transaction requires at least doubling its `max_fee_per_blob_gas`.
+        let previous_blob_base_fee = previous_sent_tx.blob_base_fee_per_gas.unwrap_or(0);
+        Ok(blob_base_fee_per_gas.max(previous_blob_base_fee * 2))
+    }
+
     async fn increase_priority_fee(
         &self,
         storage: &mut StorageProcessor<'_>,
@@ -175,11 +281,103 @@ impl EthTxManager {
             return Err(ETHSenderError::from(Error::from(Web3Error::Internal)));
         }
 
-        // Increase `priority_fee_per_gas` by at least 20% to prevent "replacement transaction under-priced" error.
-        Ok((previous_priority_fee + (previous_priority_fee / 5) + 1)
+        // Increase `priority_fee_per_gas` by at least the configured percentage (which is no less than 10%)
+        // to prevent "replacement transaction under-priced" error.
+        Ok(self
+            .fee_bumping_policy
+            .bump_priority_fee(previous_priority_fee)
             .max(self.gas_adjuster.get_priority_fee()))
     }
 
+    /// Checks whether the fee bumping policy allows to resend `tx` at `current_block`.
+    pub(super) async fn can_resend(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        tx: &EthTx,
+        current_block: L1BlockNumber,
+    ) -> bool {
+        let attempts = storage
+            .eth_sender_dal()
+            .get_sent_attempts_count(tx.id)
+            .await
+            .unwrap();
+        let last_sent_at_block = storage
+            .eth_sender_dal()
+            .get_last_sent_eth_tx(tx.id)
+            .await
+            .unwrap()
+            .and_then(|history_item| history_item.sent_at_block);
+        let blocks_since_last_attempt =
+            last_sent_at_block.map_or(u32::MAX, |block| current_block.0.saturating_sub(block));
+
+        let Err(reason) = self
+            .fee_bumping_policy
+            .check_resend(attempts, blocks_since_last_attempt)
+        else {
+            return true;
+        };
+        METRICS.resend_skipped[&reason].inc();
+        match reason {
+            ResendSkipReason::MaxAttempts => tracing::warn!(
+                "Operation {} is not mined after {attempts} attempts; it will not be resent anymore",
+                tx.id
+            ),
+            ResendSkipReason::TooEarly => tracing::debug!(
+                "Skipping resending operation {}: last attempt was sent {blocks_since_last_attempt} blocks ago",
+                tx.id
+            ),
+        }
+        false
+    }
+
+    /// Simulates `tx` using `eth_call` to detect transactions that would revert on L1 before sending them.
+    /// Returns an error if the simulation reverts.
+    async fn simulate_tx(
+        &self,
+        operator: &dyn BoundEthInterface,
+        tx: &EthTx,
+    ) -> Result<(), ETHSenderError> {
+        if tx.blob_sidecar.is_some() {
+            // `eth_call` cannot provide blobs, so the simulation would revert regardless of the payload.
+            return Ok(());
+        }
+        // Simulating against the latest block is only accurate if `tx` doesn't depend on transactions
+        // that are not mined yet.
+        let operator_nonce = operator.current_nonce("eth_tx_manager").await?;
+        if operator_nonce != tx.nonce.0.into() {
+            return Ok(());
+        }
+
+        let request = CallRequest {
+            from: Some(operator.sender_account()),
+            to: Some(tx.contract_address),
+            gas: Some(self.config.max_aggregated_tx_gas.into()),
+            data: Some(tx.raw_tx.clone().into()),
+            ..CallRequest::default()
+        };
+        let Err(err) = operator.call(request, None, "eth_tx_manager").await else {
+            return Ok(());
+        };
+        let Some(revert) = self.revert_decoder.revert_from_call_error(&err) else {
+            // Errors unrelated to the transaction should not prevent sending it.
+            tracing::warn!("Failed simulating operation {}: {err}", tx.id);
+            return Ok(());
+        };
+
+        METRICS.simulation_reverts[&tx.tx_type.into()].inc();
+        tracing::error!(
+            "Simulation of {} operation {} with nonce {} has reverted: {revert}; the operation will not be sent",
+            tx.tx_type,
+            tx.id,
+            tx.nonce
+        );
+        Err(ETHSenderError::SimulationReverted(revert.to_string()))
+    }
+
+    #[tracing::instrument(
+        skip(self, storage, tx),
+        fields(eth_tx_id = tx.id, op_type = ?tx.tx_type)
+    )]
     pub(crate) async fn send_eth_tx(
         &mut self,
         storage: &mut StorageProcessor<'_>,
@@ -187,18 +385,32 @@ impl EthTxManager {
         time_in_mempool: u32,
         current_block: L1BlockNumber,
     ) -> Result<H256, ETHSenderError> {
+        let operator = self.tx_operator(tx)?;
+        self.simulate_tx(operator.as_ref(), tx).await?;
         let EthFee {
             base_fee_per_gas,
             priority_fee_per_gas,
+            blob_base_fee_per_gas,
         } = self.calculate_fee(storage, tx, time_in_mempool).await?;
 
         METRICS.used_base_fee_per_gas.observe(base_fee_per_gas);
         METRICS
             .used_priority_fee_per_gas
             .observe(priority_fee_per_gas);
+        if let Some(blob_base_fee_per_gas) = blob_base_fee_per_gas {
+            METRICS
+                .used_blob_base_fee_per_gas
+                .observe(blob_base_fee_per_gas);
+        }
 
         let signed_tx = self
-            .sign_tx(tx, base_fee_per_gas, priority_fee_per_gas)
+            .sign_tx(
+                operator.as_ref(),
+                tx,
+                base_fee_per_gas,
+                priority_fee_per_gas,
+                blob_base_fee_per_gas,
+            )
             .await;
 
         if let Some(tx_history_id) = storage
@@ -207,23 +419,40 @@ impl EthTxManager {
                 tx.id,
                 base_fee_per_gas,
                 priority_fee_per_gas,
+                blob_base_fee_per_gas,
                 signed_tx.hash,
                 signed_tx.raw_tx.as_ref(),
             )
             .await
             .unwrap()
         {
-            if let Err(error) = self
-                .send_raw_transaction(storage, tx_history_id, signed_tx.raw_tx, current_block)
+            match self
+                .send_raw_transaction(
+                    storage,
+                    operator.as_ref(),
+                    tx_history_id,
+                    signed_tx.raw_tx,
+                    current_block,
+                )
                 .await
             {
-                tracing::warn!(
-                    "Error when sending new signed tx for tx {}, base_fee_per_gas {}, priority_fee_per_gas: {}: {}",
-                    tx.id,
-                    base_fee_per_gas,
-                    priority_fee_per_gas,
-                    error
-                );
+                Ok(tx_hash) => vlog::add_lifecycle_breadcrumb(
+                    "eth_tx_manager",
+                    format!("{} tx was sent", tx.tx_type),
+                    [
+                        ("eth_tx_id", tx.id.into()),
+                        ("op_type", tx.tx_type.as_str().into()),
+                        ("tx_hash", format!("{tx_hash:?}").into()),
+                        ("time_in_mempool", time_in_mempool.into()),
+                    ],
+                ),
+                Err(error) => {
+                    tracing::warn!(
+                        %base_fee_per_gas,
+                        %priority_fee_per_gas,
+                        "Error when sending new signed tx: {error}"
+                    );
+                }
             }
         }
         Ok(signed_tx.hash)
@@ -232,11 +461,18 @@ impl EthTxManager {
     async fn send_raw_transaction(
         &self,
         storage: &mut StorageProcessor<'_>,
+        operator: &dyn BoundEthInterface,
         tx_history_id: u32,
         raw_tx: RawTransactionBytes,
         current_block: L1BlockNumber,
     ) -> Result<H256, ETHSenderError> {
-        match self.ethereum_gateway.send_raw_tx(raw_tx).await {
+        let send_result = match &self.private_relay {
+            Some(relay) if !is_blob_tx(&raw_tx) => {
+                Self::send_via_relay(relay, &raw_tx, current_block).await
+            }
+            _ => operator.send_raw_tx(raw_tx).await,
+        };
+        match send_result {
             Ok(tx_hash) => {
                 storage
                     .eth_sender_dal()
@@ -256,19 +492,112 @@ impl EthTxManager {
         }
     }
 
-    async fn get_operator_nonce(
+    async fn send_via_relay(
+        relay: &PrivateRelayClient,
+        raw_tx: &RawTransactionBytes,
+        current_block: L1BlockNumber,
+    ) -> Result<H256, Error> {
+        let result = relay.send_raw_tx(raw_tx, current_block.0.into()).await;
+        let label = if result.is_ok() {
+            RelaySubmissionResult::Accepted
+        } else {
+            RelaySubmissionResult::Rejected
+        };
+        METRICS.relay_submissions[&label].inc();
+        result
+    }
+
+    /// Bundles only target a fixed number of L1 blocks following the one they were sent at, so in the bundle
+    /// submission mode, the latest attempts of all unmined transactions are resubmitted on each new L1 block.
+    async fn resubmit_bundles(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        l1_block_numbers: L1BlockNumbers,
+    ) -> Result<(), ETHSenderError> {
+        let Some(relay) = &self.private_relay else {
+            return Ok(());
+        };
+        if relay.mode() != TxSubmissionMode::Bundle {
+            return Ok(());
+        }
+
+        let inflight_txs = storage.eth_sender_dal().get_inflight_txs().await.unwrap();
+        for (_, operator) in self.operators.senders() {
+            let operator_nonce =
+                Self::get_operator_nonce(operator.as_ref(), l1_block_numbers).await?;
+            let operator_txs = Self::filter_operator_txs(&inflight_txs, operator.as_ref());
+            self.resubmit_operator_bundles(
+                storage,
+                relay,
+                operator_txs,
+                operator_nonce,
+                l1_block_numbers,
+            )
+            .await;
+        }
+        Ok(())
+    }
+
+    async fn resubmit_operator_bundles(
         &self,
+        storage: &mut StorageProcessor<'_>,
+        relay: &PrivateRelayClient,
+        operator_txs: Vec<&EthTx>,
+        operator_nonce: OperatorNonce,
+        l1_block_numbers: L1BlockNumbers,
+    ) {
+        for tx in operator_txs {
+            if tx.nonce < operator_nonce.latest {
+                continue; // The transaction is already mined.
+            }
+            let Some(last_attempt) = storage
+                .eth_sender_dal()
+                .get_last_sent_eth_tx(tx.id)
+                .await
+                .unwrap()
+            else {
+                continue;
+            };
+            let is_sent_before_current_block = last_attempt
+                .sent_at_block
+                .map_or(false, |block| block < l1_block_numbers.latest.0);
+            let raw_tx = RawTransactionBytes::new_unchecked(last_attempt.signed_raw_tx);
+            if !is_sent_before_current_block || is_blob_tx(&raw_tx) {
+                continue;
+            }
+
+            if let Err(err) = Self::send_via_relay(relay, &raw_tx, l1_block_numbers.latest).await {
+                tracing::warn!(
+                    "Error resubmitting bundle for tx {} (eth_tx_id {}): {err}",
+                    last_attempt.tx_hash,
+                    tx.id
+                );
+            }
+        }
+    }
+
+    /// Selects `eth_txs` sent from the specified operator account, preserving their order.
+    fn filter_operator_txs<'a>(
+        txs: &'a [EthTx],
+        operator: &dyn BoundEthInterface,
+    ) -> Vec<&'a EthTx> {
+        let address = operator.sender_account();
+        txs.iter()
+            .filter(|tx| tx.from_addr == Some(address))
+            .collect()
+    }
+
+    async fn get_operator_nonce(
+        operator: &dyn BoundEthInterface,
         block_numbers: L1BlockNumbers,
     ) -> Result<OperatorNonce, ETHSenderError> {
-        let finalized = self
-            .ethereum_gateway
+        let finalized = operator
             .nonce_at(block_numbers.finalized.0.into(), "eth_tx_manager")
             .await?
             .as_u32()
             .into();
 
-        let latest = self
-            .ethereum_gateway
+        let latest = operator
             .nonce_at(block_numbers.latest.0.into(), "eth_tx_manager")
             .await?
             .as_u32()
@@ -276,10 +605,257 @@ impl EthTxManager {
         Ok(OperatorNonce { finalized, latest })
     }
 
+    /// Tracks balances of operator accounts sending `eth_txs`.
+    async fn track_operator_balances(&self) {
+        for (role, operator) in self.operators.senders() {
+            match operator.sender_eth_balance("eth_tx_manager").await {
+                Ok(balance) => {
+                    let balance_in_eth = balance.as_u128() as f64 / 1e18;
+                    METRICS.operator_balance[&role].set(balance_in_eth);
+                }
+                Err(err) => tracing::warn!(
+                    "Failed getting balance of {role:?} operator {:?}: {err}",
+                    operator.sender_account()
+                ),
+            }
+        }
+    }
+
+    /// Returns hashes of all attempts of the specified `eth_tx` if none of them is mined, or `None` otherwise.
+    async fn get_unmined_attempts(
+        storage: &mut StorageProcessor<'_>,
+        operator: &dyn BoundEthInterface,
+        tx: &EthTx,
+    ) -> Result<Option<Vec<H256>>, ETHSenderError> {
+        let history = storage
+            .eth_sender_dal()
+            .get_tx_history_to_check(tx.id)
+            .await
+            .unwrap();
+        let mut tx_hashes = Vec::with_capacity(history.len());
+        for history_item in history {
+            if Self::get_tx_status(operator, history_item.tx_hash)
+                .await?
+                .is_some()
+            {
+                return Ok(None);
+            }
+            tx_hashes.push(history_item.tx_hash);
+        }
+        Ok(Some(tx_hashes))
+    }
+
+    /// Reconciles operator nonces tracked in the database with the on-chain state. Mismatches are caused by transactions
+    /// sent from the operator account bypassing `eth_sender` (e.g., manually); such transactions may consume nonces
+    /// assigned to `eth_txs`, or leave nonce gaps.
+    ///
+    /// - `eth_txs` that were not sent yet are renumbered to start from the next free nonce.
+    /// - Orphaned `eth_txs` (i.e., ones with nonces consumed by other transactions) are reset to be sent
+    ///   with new nonces, provided that no `eth_txs` following them were sent.
+    ///
+    /// Other mismatches cannot be repaired automatically and are returned as [`NonceMismatch`].
+    ///
+    /// Nonces of each operator account sending `eth_txs` are reconciled independently.
+    pub(super) async fn reconcile_nonces(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        l1_block_numbers: L1BlockNumbers,
+    ) -> Result<(), ETHSenderError> {
+        for (_, operator) in self.operators.senders() {
+            Self::reconcile_operator_nonces(storage, operator.as_ref(), l1_block_numbers).await?;
+        }
+        Ok(())
+    }
+
+    async fn reconcile_operator_nonces(
+        storage: &mut StorageProcessor<'_>,
+        operator: &dyn BoundEthInterface,
+        l1_block_numbers: L1BlockNumbers,
+    ) -> Result<(), ETHSenderError> {
+        let operator_nonce = Self::get_operator_nonce(operator, l1_block_numbers).await?;
+        let pending_nonce: Nonce = operator
+            .pending_nonce("eth_tx_manager")
+            .await?
+            .as_u32()
+            .into();
+        let inflight_txs = storage.eth_sender_dal().get_inflight_txs().await.unwrap();
+        let inflight_txs = Self::filter_operator_txs(&inflight_txs, operator);
+        let operator_address = operator.sender_account();
+        let last_attempted_tx = storage
+            .eth_sender_dal()
+            .get_last_attempted_eth_tx(operator_address)
+            .await
+            .unwrap();
+
+        // `eth_txs` with nonces below the operator nonce on the finalized block must be mined.
+        let mut orphaned_txs = vec![];
+        let mut has_later_sent_txs = false;
+        for &tx in inflight_txs
+            .iter()
+            .take_while(|tx| tx.nonce < operator_nonce.finalized)
+        {
+            if let Some(tx_hashes) = Self::get_unmined_attempts(storage, operator, tx).await? {
+                orphaned_txs.push((tx, tx_hashes));
+            } else if !orphaned_txs.is_empty() {
+                has_later_sent_txs = true;
+                break;
+            }
+        }
+        if let Some((last_orphaned_tx, _)) = orphaned_txs.last() {
+            has_later_sent_txs |=
+                last_attempted_tx.map_or(true, |last_tx| last_tx.id != last_orphaned_tx.id);
+        }
+        if has_later_sent_txs {
+            let (tx, tx_hashes) = &orphaned_txs[0];
+            return Err(NonceMismatch::OrphanedTx {
+                eth_tx_id: tx.id,
+                tx_type: tx.tx_type,
+                nonce: tx.nonce,
+                finalized_nonce: operator_nonce.finalized,
+                tx_hashes: tx_hashes.clone(),
+            }
+            .into());
+        }
+
+        // The earliest `eth_tx` not mined on L1 must have the next free nonce.
+        let earliest_unmined_tx = inflight_txs
+            .iter()
+            .find(|tx| tx.nonce >= operator_nonce.latest);
+        if let Some(tx) = earliest_unmined_tx {
+            if tx.nonce > pending_nonce {
+                return Err(NonceMismatch::NonceGap {
+                    eth_tx_id: tx.id,
+                    tx_type: tx.tx_type,
+                    nonce: tx.nonce,
+                    pending_nonce,
+                }
+                .into());
+            }
+        }
+
+        let mut transaction = storage.start_transaction().await.unwrap();
+        transaction
+            .eth_sender_dal()
+            .lock_nonce_assignment()
+            .await
+            .unwrap();
+        for (tx, tx_hashes) in &orphaned_txs {
+            tracing::warn!(
+                "eth_tx {} ({}) with nonce {} is orphaned: the operator nonce on the finalized L1 block is {}, \
+                 but none of the eth_tx attempts {tx_hashes:?} is mined. Resetting the eth_tx to be sent with a new nonce",
+                tx.id,
+                tx.tx_type,
+                tx.nonce,
+                operator_nonce.finalized
+            );
+            transaction
+                .eth_sender_dal()
+                .reset_eth_tx_attempts(tx.id)
+                .await
+                .unwrap();
+            METRICS.nonce_repairs[&NonceRepairKind::ResetOrphanedTx].inc();
+        }
+
+        let first_unsent_tx = transaction
+            .eth_sender_dal()
+            .get_first_unsent_eth_tx(operator_address)
+            .await
+            .unwrap();
+        if let Some(first_unsent_tx) = first_unsent_tx {
+            let last_attempted_tx = transaction
+                .eth_sender_dal()
+                .get_last_attempted_eth_tx(operator_address)
+                .await
+                .unwrap();
+            let expected_nonce =
+                last_attempted_tx.map_or(pending_nonce, |tx| pending_nonce.max(tx.nonce + 1));
+            if first_unsent_tx.nonce != expected_nonce {
+                tracing::warn!(
+                    "eth_tx {} is the first eth_tx that was not sent and has nonce {}, while the next free \
+                     operator nonce is {expected_nonce}. Renumbering unsent eth_txs",
+                    first_unsent_tx.id,
+                    first_unsent_tx.nonce
+                );
+                transaction
+                    .eth_sender_dal()
+                    .renumber_unsent_eth_txs(operator_address, expected_nonce.0.into())
+                    .await
+                    .unwrap();
+                METRICS.nonce_repairs[&NonceRepairKind::RenumberedUnsentTxs].inc();
+            }
+        }
+        transaction.commit().await.unwrap();
+        Ok(())
+    }
+
+    /// Completes the pending operator rotation once all `eth_txs` are confirmed (i.e., once no `eth_txs` signed
+    /// by the current operator can be resent) by switching to the standby operator account.
+    pub(super) async fn process_operator_rotation(
+        &mut self,
+        storage: &mut StorageProcessor<'_>,
+    ) -> Result<(), ETHSenderError> {
+        let latest_rotation = storage
+            .eth_sender_dal()
+            .get_latest_operator_rotation()
+            .await
+            .unwrap();
+        let Some(rotation) = latest_rotation.filter(|rotation| !rotation.is_completed()) else {
+            return Ok(());
+        };
+        let Some(new_operator) = self.operators.standby() else {
+            tracing::error!(
+                "Operator rotation #{} is requested, but the secondary operator is not configured; \
+                 the rotation should be cancelled using `DELETE /eth_sender/operator_rotation` in the admin API",
+                rotation.id
+            );
+            return Ok(());
+        };
+        let old_address = self.operators.active().sender_account();
+        let new_address = new_operator.sender_account();
+
+        let mut transaction = storage.start_transaction().await.unwrap();
+        // Prevents races with `EthTxAggregator` saving new `eth_txs`.
+        transaction
+            .eth_sender_dal()
+            .lock_nonce_assignment()
+            .await
+            .unwrap();
+        let unconfirmed_count = transaction
+            .eth_sender_dal()
+            .get_unconfirmed_txs_count()
+            .await
+            .unwrap();
+        if unconfirmed_count > 0 {
+            tracing::info!(
+                "Operator rotation #{} is waiting for {unconfirmed_count} eth_txs sent by {old_address:?} to be confirmed",
+                rotation.id
+            );
+            return Ok(());
+        }
+        let is_completed = transaction
+            .eth_sender_dal()
+            .complete_operator_rotation(rotation.id, new_address)
+            .await
+            .unwrap();
+        transaction.commit().await.unwrap();
+        if !is_completed {
+            return Ok(()); // The rotation was cancelled concurrently.
+        }
+
+        self.operators.activate(new_address)?;
+        METRICS.operator_rotations.inc();
+        tracing::info!(
+            "Completed operator rotation #{}: switched from {old_address:?} to {new_address:?}",
+            rotation.id
+        );
+        Ok(())
+    }
+
     async fn get_l1_block_numbers(&self) -> Result<L1BlockNumbers, ETHSenderError> {
         let (finalized, safe) = if let Some(confirmations) = self.config.wait_confirmations {
             let latest_block_number = self
-                .ethereum_gateway
+                .operators
+                .active()
                 .block_number("eth_tx_manager")
                 .await?
                 .as_u64();
@@ -288,7 +864,8 @@ impl EthTxManager {
             (finalized, finalized)
         } else {
             let finalized = self
-                .ethereum_gateway
+                .operators
+                .active()
                 .block(BlockId::Number(BlockNumber::Finalized), "eth_tx_manager")
                 .await?
                 .expect("Finalized block must be present on L1")
@@ -298,7 +875,8 @@ impl EthTxManager {
                 .into();
 
             let safe = self
-                .ethereum_gateway
+                .operators
+                .active()
                 .block(BlockId::Number(BlockNumber::Safe), "eth_tx_manager")
                 .await?
                 .expect("Safe block must be present on L1")
@@ -310,7 +888,8 @@ impl EthTxManager {
         };
 
         let latest = self
-            .ethereum_gateway
+            .operators
+            .active()
             .block_number("eth_tx_manager")
             .await?
             .as_u32()
@@ -324,23 +903,47 @@ impl EthTxManager {
     }
 
     // Monitors the in-flight transactions, marks mined ones as confirmed,
-    // returns the one that has to be resent (if there is one).
+    // returns the ones that have to be resent (at most one per operator account).
     pub(super) async fn monitor_inflight_transactions(
         &mut self,
         storage: &mut StorageProcessor<'_>,
         l1_block_numbers: L1BlockNumbers,
-    ) -> Result<Option<(EthTx, u32)>, ETHSenderError> {
+    ) -> Result<Vec<(EthTx, u32)>, ETHSenderError> {
         METRICS.track_block_numbers(&l1_block_numbers);
-        let operator_nonce = self.get_operator_nonce(l1_block_numbers).await?;
         let inflight_txs = storage.eth_sender_dal().get_inflight_txs().await.unwrap();
         METRICS.number_of_inflight_txs.set(inflight_txs.len());
 
+        let mut txs_to_resend = vec![];
+        for (_, operator) in self.operators.senders() {
+            let operator_txs = Self::filter_operator_txs(&inflight_txs, operator.as_ref());
+            let tx_to_resend = self
+                .monitor_operator_inflight_txs(
+                    storage,
+                    operator.as_ref(),
+                    operator_txs,
+                    l1_block_numbers,
+                )
+                .await?;
+            txs_to_resend.extend(tx_to_resend);
+        }
+        Ok(txs_to_resend)
+    }
+
+    async fn monitor_operator_inflight_txs(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        operator: &dyn BoundEthInterface,
+        inflight_txs: Vec<&EthTx>,
+        l1_block_numbers: L1BlockNumbers,
+    ) -> Result<Option<(EthTx, u32)>, ETHSenderError> {
+        let operator_nonce = Self::get_operator_nonce(operator, l1_block_numbers).await?;
         tracing::trace!(
             "Going through not confirmed txs. \
              Block numbers: latest {}, finalized {}, \
-             operator's nonce: latest {}, finalized {}",
+             nonce of operator {:?}: latest {}, finalized {}",
             l1_block_numbers.latest,
             l1_block_numbers.finalized,
+            operator.sender_account(),
             operator_nonce.latest,
             operator_nonce.finalized,
         );
@@ -360,7 +963,7 @@ impl EthTxManager {
                     .await
                     .unwrap()
                     .unwrap_or(l1_block_numbers.latest.0);
-                return Ok(Some((tx, first_sent_at_block)));
+                return Ok(Some((tx.clone(), first_sent_at_block)));
             }
 
             // If on finalized block sender's nonce was > tx.nonce,
@@ -378,9 +981,9 @@ impl EthTxManager {
                 tx.nonce,
             );
 
-            match self.check_all_sending_attempts(storage, &tx).await {
+            match self.check_all_sending_attempts(storage, operator, tx).await {
                 Some(tx_status) => {
-                    self.apply_tx_status(storage, &tx, tx_status, l1_block_numbers.finalized)
+                    self.apply_tx_status(storage, tx, tx_status, l1_block_numbers.finalized)
                         .await;
                 }
                 None => {
@@ -400,25 +1003,45 @@ impl EthTxManager {
 
     async fn sign_tx(
         &self,
+        operator: &dyn BoundEthInterface,
         tx: &EthTx,
         base_fee_per_gas: u64,
         priority_fee_per_gas: u64,
+        blob_base_fee_per_gas: Option<u64>,
     ) -> SignedCallResult {
-        self.ethereum_gateway
-            .sign_prepared_tx_for_addr(
-                tx.raw_tx.clone(),
-                tx.contract_address,
-                Options::with(|opt| {
-                    // TODO Calculate gas for every operation SMA-1436
-                    opt.gas = Some(self.config.max_aggregated_tx_gas.into());
-                    opt.max_fee_per_gas = Some(U256::from(base_fee_per_gas + priority_fee_per_gas));
-                    opt.max_priority_fee_per_gas = Some(U256::from(priority_fee_per_gas));
-                    opt.nonce = Some(tx.nonce.0.into());
-                }),
-                "eth_tx_manager",
-            )
-            .await
-            .expect("Failed to sign transaction")
+        let options = Options::with(|opt| {
+            // TODO Calculate gas for every operation SMA-1436
+            opt.gas = Some(self.config.max_aggregated_tx_gas.into());
+            opt.max_fee_per_gas = Some(U256::from(base_fee_per_gas + priority_fee_per_gas));
+            opt.max_priority_fee_per_gas = Some(U256::from(priority_fee_per_gas));
+            opt.nonce = Some(tx.nonce.0.into());
+        });
+
+        let signed_tx = match (&tx.blob_sidecar, blob_base_fee_per_gas) {
+            (Some(blob_sidecar), Some(blob_base_fee_per_gas)) => {
+                operator
+                    .sign_prepared_blob_tx_for_addr(
+                        tx.raw_tx.clone(),
+                        tx.contract_address,
+                        options,
+                        blob_base_fee_per_gas.into(),
+                        blob_sidecar.clone(),
+                        "eth_tx_manager",
+                    )
+                    .await
+            }
+            _ => {
+                operator
+                    .sign_prepared_tx_for_addr(
+                        tx.raw_tx.clone(),
+                        tx.contract_address,
+                        options,
+                        "eth_tx_manager",
+                    )
+                    .await
+            }
+        };
+        signed_tx.expect("Failed to sign transaction")
     }
 
     async fn send_unsent_txs(
@@ -427,10 +1050,24 @@ impl EthTxManager {
         l1_block_numbers: L1BlockNumbers,
     ) {
         for tx in storage.eth_sender_dal().get_unsent_txs().await.unwrap() {
+            let eth_tx = storage
+                .eth_sender_dal()
+                .get_eth_tx(tx.eth_tx_id)
+                .await
+                .unwrap()
+                .expect("Eth tx should exist");
+            let operator = match self.tx_operator(&eth_tx) {
+                Ok(operator) => operator,
+                Err(err) => {
+                    tracing::warn!("Cannot send transaction {tx:?}: {err}");
+                    continue;
+                }
+            };
+
             // Check already sent txs not marked as sent and mark them as sent.
             // The common reason for this behavior is that we sent tx and stop the server
             // before updating the database
-            let tx_status = self.get_tx_status(tx.tx_hash).await;
+            let tx_status = Self::get_tx_status(operator.as_ref(), tx.tx_hash).await;
 
             if let Ok(Some(tx_status)) = tx_status {
                 tracing::info!("The tx {:?} has been already sent", tx.tx_hash);
@@ -440,18 +1077,12 @@ impl EthTxManager {
                     .await
                     .unwrap();
 
-                let eth_tx = storage
-                    .eth_sender_dal()
-                    .get_eth_tx(tx.eth_tx_id)
-                    .await
-                    .unwrap()
-                    .expect("Eth tx should exist");
-
                 self.apply_tx_status(storage, &eth_tx, tx_status, l1_block_numbers.finalized)
                     .await;
             } else if let Err(error) = self
                 .send_raw_transaction(
                     storage,
+                    operator.as_ref(),
                     tx.id,
                     RawTransactionBytes::new_unchecked(tx.signed_raw_tx.clone()),
                     l1_block_numbers.latest,
@@ -498,7 +1129,8 @@ impl EthTxManager {
             .await
             .unwrap();
         let failure_reason = self
-            .ethereum_gateway
+            .operators
+            .active()
             .failure_reason(tx_status.receipt.transaction_hash)
             .await
             .expect(
@@ -514,13 +1146,16 @@ impl EthTxManager {
         panic!("We can't operate after tx fail");
     }
 
+    #[tracing::instrument(
+        skip_all,
+        fields(eth_tx_id = tx.id, op_type = ?tx.tx_type, tx_hash = ?tx_status.receipt.transaction_hash)
+    )]
     pub async fn confirm_tx(
         &self,
         storage: &mut StorageProcessor<'_>,
         tx: &EthTx,
         tx_status: ExecutedTxStatus,
     ) {
-        let tx_hash = tx_status.receipt.transaction_hash;
         let gas_used = tx_status
             .receipt
             .gas_used
@@ -538,16 +1173,21 @@ impl EthTxManager {
 
         if gas_used > U256::from(tx.predicted_gas_cost) {
             tracing::error!(
-                "Predicted gas {} lower than used gas {gas_used} for tx {:?} {}",
-                tx.predicted_gas_cost,
-                tx.tx_type,
-                tx.id
+                predicted_gas_cost = tx.predicted_gas_cost,
+                %gas_used,
+                "Predicted gas is lower than used gas"
             );
         }
-        tracing::info!(
-            "eth_tx {} with hash {tx_hash:?} for {} is confirmed. Gas spent: {gas_used:?}",
-            tx.id,
-            tx.tx_type
+        tracing::info!(%gas_used, "eth_tx is confirmed");
+        vlog::add_lifecycle_breadcrumb(
+            "eth_tx_manager",
+            format!("{} tx is confirmed", tx.tx_type),
+            [
+                ("eth_tx_id", tx.id.into()),
+                ("op_type", tx.tx_type.as_str().into()),
+                ("tx_hash", format!("{:?}", tx_status.tx_hash).into()),
+                ("gas_used", gas_used.low_u64().into()),
+            ],
         );
         let tx_type_label = tx.tx_type.into();
         METRICS.l1_gas_used[&tx_type_label].observe(gas_used.low_u128() as f64);
@@ -576,6 +1216,11 @@ impl EthTxManager {
                 .await
                 .context("get_l1_block_numbers()")?;
             let mut storage = pool.access_storage_tagged("eth_sender").await.unwrap();
+            self.operators.attribute_untracked_txs(&mut storage).await;
+            self.operators
+                .sync(&mut storage)
+                .await
+                .context("failed selecting active operator account")?;
             self.send_unsent_txs(&mut storage, l1_block_numbers).await;
         }
 
@@ -592,6 +1237,10 @@ impl EthTxManager {
 
             match self.loop_iteration(&mut storage, last_known_l1_block).await {
                 Ok(block) => last_known_l1_block = block,
+                Err(ETHSenderError::NonceMismatch(err)) => {
+                    tracing::error!("Halting eth_tx_manager: {err}");
+                    return Err(err).context("nonce mismatch requires manual intervention");
+                }
                 Err(e) => {
                     // Web3 API request failures can cause this,
                     // and anything more important is already properly reported.
@@ -629,7 +1278,11 @@ impl EthTxManager {
                 .unwrap();
 
             for tx in new_eth_tx {
-                let _ = self.send_eth_tx(storage, &tx, 0, current_block).await;
+                let result = self.send_eth_tx(storage, &tx, 0, current_block).await;
+                // Transactions following a reverting one would be stuck behind its nonce.
+                if let Err(ETHSenderError::SimulationReverted(_)) = result {
+                    break;
+                }
             }
         }
     }
@@ -641,6 +1294,10 @@ impl EthTxManager {
         previous_block: L1BlockNumber,
     ) -> Result<L1BlockNumber, ETHSenderError> {
         let l1_block_numbers = self.get_l1_block_numbers().await?;
+        if l1_block_numbers.latest > previous_block {
+            self.track_operator_balances().await;
+            self.reconcile_nonces(storage, l1_block_numbers).await?;
+        }
 
         self.send_new_eth_txs(storage, l1_block_numbers.latest)
             .await;
@@ -650,20 +1307,25 @@ impl EthTxManager {
             return Ok(previous_block);
         }
 
-        if let Some((tx, sent_at_block)) = self
+        let txs_to_resend = self
             .monitor_inflight_transactions(storage, l1_block_numbers)
-            .await?
-        {
+            .await?;
+        for (tx, sent_at_block) in txs_to_resend {
             // New gas price depends on the time this tx spent in mempool.
             let time_in_mempool = l1_block_numbers.latest.0 - sent_at_block;
 
-            // We don't want to return early in case resend does not succeed -
-            // the error is logged anyway, but early returns will prevent
-            // sending new operations.
-            let _ = self
-                .send_eth_tx(storage, &tx, time_in_mempool, l1_block_numbers.latest)
-                .await;
+            if time_in_mempool == 0 || self.can_resend(storage, &tx, l1_block_numbers.latest).await
+            {
+                // We don't want to return early in case resend does not succeed -
+                // the error is logged anyway, but early returns will prevent
+                // sending new operations.
+                let _ = self
+                    .send_eth_tx(storage, &tx, time_in_mempool, l1_block_numbers.latest)
+                    .await;
+            }
         }
+        self.resubmit_bundles(storage, l1_block_numbers).await?;
+        self.process_operator_rotation(storage).await?;
 
         Ok(l1_block_numbers.latest)
     }
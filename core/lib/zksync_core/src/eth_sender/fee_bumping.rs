@@ -0,0 +1,61 @@
+//! Policy for resending L1 transactions that are not mined in time.
+
+use zksync_config::configs::eth_sender::SenderConfig;
+
+use super::metrics::ResendSkipReason;
+
+/// Policy controlling how `EthTxManager` bumps fees of stuck L1 transactions and resends them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeeBumpingPolicy {
+    /// Maximum number of sent attempts (including the first one) for a transaction. `None` means unlimited.
+    pub max_attempts: Option<u32>,
+    /// Minimum increase of the priority fee for each resent attempt, in percent.
+    pub bump_percent: u64,
+    /// Cap on `max_fee_per_gas` (i.e., the sum of base and priority fees) of sent transactions, in wei.
+    pub max_fee_per_gas: Option<u64>,
+    /// Minimum number of L1 blocks between attempts for the same transaction.
+    pub min_blocks_between_attempts: u32,
+}
+
+impl FeeBumpingPolicy {
+    pub fn from_config(config: &SenderConfig) -> Self {
+        Self {
+            max_attempts: config.max_tx_attempts,
+            bump_percent: config.fee_bump_percent(),
+            max_fee_per_gas: config.max_fee_per_gas_cap,
+            min_blocks_between_attempts: config.min_blocks_between_resends(),
+        }
+    }
+
+    /// Checks whether a transaction with the specified number of sent attempts can be resent.
+    pub(super) fn check_resend(
+        &self,
+        attempts: u32,
+        blocks_since_last_attempt: u32,
+    ) -> Result<(), ResendSkipReason> {
+        if self.max_attempts.map_or(false, |max| attempts >= max) {
+            return Err(ResendSkipReason::MaxAttempts);
+        }
+        if blocks_since_last_attempt < self.min_blocks_between_attempts {
+            return Err(ResendSkipReason::TooEarly);
+        }
+        Ok(())
+    }
+
+    /// Returns the minimum priority fee for a resent attempt given the priority fee of the previous attempt.
+    pub(super) fn bump_priority_fee(&self, previous_priority_fee: u64) -> u64 {
+        // Add 1 wei so that the fee is increased even if rounding zeroes the bump.
+        previous_priority_fee + previous_priority_fee * self.bump_percent / 100 + 1
+    }
+
+    /// Applies the `max_fee_per_gas` cap to the specified fees. The base fee is reduced first, since
+    /// the priority fee is necessary for replacement transactions to be accepted.
+    pub(super) fn cap_fees(&self, base_fee_per_gas: u64, priority_fee_per_gas: u64) -> (u64, u64) {
+        let Some(max_fee_per_gas) = self.max_fee_per_gas else {
+            return (base_fee_per_gas, priority_fee_per_gas);
+        };
+        let priority_fee_per_gas = priority_fee_per_gas.min(max_fee_per_gas);
+        let base_fee_per_gas = base_fee_per_gas.min(max_fee_per_gas - priority_fee_per_gas);
+        (base_fee_per_gas, priority_fee_per_gas)
+    }
+}
@@ -31,6 +31,44 @@ pub(super) enum BlockNumberVariant {
     Safe,
 }
 
+/// Reason why a stuck L1 transaction was not resent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelSet, EncodeLabelValue)]
+#[metrics(label = "reason", rename_all = "snake_case")]
+pub(super) enum ResendSkipReason {
+    /// The transaction has reached the maximum number of attempts.
+    MaxAttempts,
+    /// Not enough L1 blocks were mined since the previous attempt.
+    TooEarly,
+}
+
+/// Kind of the repair applied by the Ethereum sender after detecting a nonce mismatch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelSet, EncodeLabelValue)]
+#[metrics(label = "kind", rename_all = "snake_case")]
+pub(super) enum NonceRepairKind {
+    /// Nonces of `eth_txs` that were not sent yet were reassigned.
+    RenumberedUnsentTxs,
+    /// An orphaned `eth_tx` was reset to be sent with a new nonce.
+    ResetOrphanedTx,
+}
+
+/// Result of submitting an L1 transaction via a private relay.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelSet, EncodeLabelValue)]
+#[metrics(label = "result", rename_all = "snake_case")]
+pub(super) enum RelaySubmissionResult {
+    Accepted,
+    Rejected,
+}
+
+/// Role of an operator account sending L1 transactions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelSet, EncodeLabelValue)]
+#[metrics(label = "operator", rename_all = "snake_case")]
+pub(super) enum OperatorRole {
+    /// Account sending all L1 transactions, or all transactions except for commit ones if the blob operator is configured.
+    Main,
+    /// Account sending commit transactions.
+    Blob,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelSet, EncodeLabelValue)]
 #[metrics(label = "type")]
 pub(super) struct ActionTypeLabel(AggregatedActionType);
@@ -84,10 +122,26 @@ pub(super) struct EthSenderMetrics {
     pub block_range_size: Family<ActionTypeLabel, Histogram<u64>>,
     /// Number of transactions resent by the Ethereum sender.
     pub transaction_resent: Counter,
+    /// Number of times resending a stuck transaction was skipped according to the fee bumping policy.
+    pub resend_skipped: Family<ResendSkipReason, Counter>,
+    /// Number of transactions with fees capped by the fee bumping policy.
+    pub capped_fees: Counter,
+    /// Number of L1 transactions not sent because their simulation has reverted.
+    pub simulation_reverts: Family<ActionTypeLabel, Counter>,
+    /// Number of repairs applied after detecting a mismatch between tracked and on-chain operator nonces.
+    pub nonce_repairs: Family<NonceRepairKind, Counter>,
+    /// Number of L1 transaction submissions via a private relay, including bundle resubmissions.
+    pub relay_submissions: Family<RelaySubmissionResult, Counter>,
+    /// Number of completed operator rotations.
+    pub operator_rotations: Counter,
+    /// Balance of operator accounts in ETH.
+    pub operator_balance: Family<OperatorRole, Gauge<f64>>,
     #[metrics(buckets = FEE_BUCKETS)]
     pub used_base_fee_per_gas: Histogram<u64>,
     #[metrics(buckets = FEE_BUCKETS)]
     pub used_priority_fee_per_gas: Histogram<u64>,
+    #[metrics(buckets = FEE_BUCKETS)]
+    pub used_blob_base_fee_per_gas: Histogram<u64>,
     /// Last L1 block observed by the Ethereum sender.
     pub last_known_l1_block: Family<BlockNumberVariant, Gauge<usize>>,
     /// Number of in-flight txs produced by the Ethereum sender.
@@ -3,14 +3,20 @@ mod aggregator;
 mod error;
 mod eth_tx_aggregator;
 mod eth_tx_manager;
+mod fee_bumping;
 mod metrics;
+mod operator_rotation;
 mod publish_criterion;
+mod simulation;
 mod zksync_functions;
 
 #[cfg(test)]
 mod tests;
 
 pub use self::{
-    aggregator::Aggregator, error::ETHSenderError, eth_tx_aggregator::EthTxAggregator,
+    aggregator::Aggregator,
+    error::{ETHSenderError, NonceMismatch},
+    eth_tx_aggregator::EthTxAggregator,
     eth_tx_manager::EthTxManager,
+    fee_bumping::FeeBumpingPolicy,
 };
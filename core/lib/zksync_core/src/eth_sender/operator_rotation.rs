@@ -0,0 +1,121 @@
+//! Rotation of the operator account signing L1 transactions.
+//!
+//! A rotation is requested via the admin API and recorded in the database. While the rotation is pending,
+//! [`EthTxAggregator`](super::EthTxAggregator) doesn't create new `eth_txs`. Once all `eth_txs` are confirmed,
+//! [`EthTxManager`](super::EthTxManager) completes the rotation by switching to the standby operator account,
+//! and both components sign and send new `eth_txs` from it.
+//!
+//! The blob operator account (if configured) sends commit `eth_txs` and is not affected by rotations.
+
+use std::{mem, sync::Arc};
+
+use zksync_dal::StorageProcessor;
+use zksync_eth_client::BoundEthInterface;
+use zksync_types::{aggregated_operations::AggregatedActionType, Address};
+
+use super::{metrics::OperatorRole, ETHSenderError};
+
+/// Operator accounts used by the Ethereum sender: the active account, an optional standby account
+/// the operator can be rotated to, and an optional blob operator account sending commit `eth_txs`.
+#[derive(Debug, Clone)]
+pub(super) struct OperatorAccounts {
+    active: Arc<dyn BoundEthInterface>,
+    standby: Option<Arc<dyn BoundEthInterface>>,
+    blob: Option<Arc<dyn BoundEthInterface>>,
+}
+
+impl OperatorAccounts {
+    pub fn new(primary: Arc<dyn BoundEthInterface>) -> Self {
+        Self {
+            active: primary,
+            standby: None,
+            blob: None,
+        }
+    }
+
+    pub fn set_secondary(&mut self, secondary: Arc<dyn BoundEthInterface>) {
+        self.standby = Some(secondary);
+    }
+
+    pub fn set_blob_operator(&mut self, blob_operator: Arc<dyn BoundEthInterface>) {
+        self.blob = Some(blob_operator);
+    }
+
+    pub fn active(&self) -> &Arc<dyn BoundEthInterface> {
+        &self.active
+    }
+
+    pub fn standby(&self) -> Option<&Arc<dyn BoundEthInterface>> {
+        self.standby.as_ref()
+    }
+
+    /// Returns the account sending `eth_txs` of the specified type.
+    pub fn sender_for(&self, op_type: AggregatedActionType) -> &Arc<dyn BoundEthInterface> {
+        match (op_type, &self.blob) {
+            (AggregatedActionType::Commit, Some(blob)) => blob,
+            _ => &self.active,
+        }
+    }
+
+    /// Returns the account sending `eth_txs` with the specified address, if any.
+    pub fn sender(&self, address: Address) -> Option<&Arc<dyn BoundEthInterface>> {
+        self.senders()
+            .find(|(_, operator)| operator.sender_account() == address)
+            .map(|(_, operator)| operator)
+    }
+
+    /// Iterates over accounts sending `eth_txs`, i.e., the active account and the blob operator account if it's configured.
+    pub fn senders(
+        &self,
+    ) -> impl Iterator<Item = (OperatorRole, &Arc<dyn BoundEthInterface>)> + '_ {
+        let blob = self.blob.iter().map(|blob| (OperatorRole::Blob, blob));
+        [(OperatorRole::Main, &self.active)].into_iter().chain(blob)
+    }
+
+    /// Attributes `eth_txs` created before operator accounts were tracked to the active account. This is only valid
+    /// if the operator was never rotated, since otherwise all such `eth_txs` are confirmed already.
+    pub async fn attribute_untracked_txs(&self, storage: &mut StorageProcessor<'_>) {
+        let mut dal = storage.eth_sender_dal();
+        if dal.get_current_operator_address().await.unwrap().is_some() {
+            return;
+        }
+        let address = self.active.sender_account();
+        let updated_count = dal.set_missing_eth_tx_senders(address).await.unwrap();
+        if updated_count > 0 {
+            tracing::info!("Attributed {updated_count} eth_txs to operator {address:?}");
+        }
+    }
+
+    /// Synchronizes the active account with operator rotations completed so far. Returns `true` if the active
+    /// account has changed.
+    pub async fn sync(
+        &mut self,
+        storage: &mut StorageProcessor<'_>,
+    ) -> Result<bool, ETHSenderError> {
+        let current_address = storage
+            .eth_sender_dal()
+            .get_current_operator_address()
+            .await
+            .unwrap();
+        match current_address {
+            Some(address) => self.activate(address),
+            // The operator was never rotated, so the primary account is still active.
+            None => Ok(false),
+        }
+    }
+
+    /// Makes the account with the specified address active. Returns `true` if the active account has changed.
+    pub fn activate(&mut self, address: Address) -> Result<bool, ETHSenderError> {
+        if self.active.sender_account() == address {
+            return Ok(false);
+        }
+        match &mut self.standby {
+            Some(standby) if standby.sender_account() == address => {
+                mem::swap(&mut self.active, standby);
+                tracing::info!("Switched to operator {address:?}");
+                Ok(true)
+            }
+            _ => Err(ETHSenderError::UnknownOperator(address)),
+        }
+    }
+}
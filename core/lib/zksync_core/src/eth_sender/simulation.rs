@@ -0,0 +1,127 @@
+//! Simulation of L1 transactions before sending them.
+
+use std::{collections::HashMap, fmt};
+
+use zksync_contracts::{verifier_contract, zksync_contract};
+use zksync_eth_client::Error;
+use zksync_types::{
+    ethabi::{self, AbiError, Contract, ParamType, Token},
+    web3::error::Error as Web3Error,
+};
+
+/// Selector of the standard `Error(string)` error.
+pub(super) const ERROR_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+/// Selector of the standard `Panic(uint256)` error.
+const PANIC_SELECTOR: [u8; 4] = [0x4e, 0x48, 0x7b, 0x71];
+
+/// Revert of a simulated L1 transaction.
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct SimulatedRevert {
+    /// Error message returned by the L1 node.
+    pub message: String,
+    /// Revert reason decoded from the revert data, if any.
+    pub reason: Option<String>,
+}
+
+impl fmt::Display for SimulatedRevert {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.reason {
+            Some(reason) => formatter.write_str(reason),
+            None => formatter.write_str(&self.message),
+        }
+    }
+}
+
+/// Decoder of revert reasons using errors declared in the bundled L1 contract ABIs.
+#[derive(Debug)]
+pub(super) struct RevertDecoder {
+    custom_errors: HashMap<[u8; 4], AbiError>,
+}
+
+impl Default for RevertDecoder {
+    fn default() -> Self {
+        Self::new(&[zksync_contract(), verifier_contract()])
+    }
+}
+
+impl RevertDecoder {
+    fn new(contracts: &[Contract]) -> Self {
+        let custom_errors = contracts
+            .iter()
+            .flat_map(|contract| contract.errors.values().flatten())
+            .map(|error| {
+                let param_types: Vec<_> = error
+                    .inputs
+                    .iter()
+                    .map(|param| param.kind.clone())
+                    .collect();
+                let selector = ethabi::short_signature(&error.name, &param_types);
+                (selector, error.clone())
+            })
+            .collect();
+        Self { custom_errors }
+    }
+
+    /// Extracts a revert from the `eth_call` error. Returns `None` if the error is not caused by a revert
+    /// (e.g., if it's a network error).
+    pub fn revert_from_call_error(&self, err: &Error) -> Option<SimulatedRevert> {
+        let Error::EthereumGateway(Web3Error::Rpc(rpc_error)) = err else {
+            return None;
+        };
+        let revert_data = rpc_error
+            .data
+            .as_ref()
+            .and_then(|data| data.as_str())
+            .and_then(|data| hex::decode(data.strip_prefix("0x").unwrap_or(data)).ok());
+        if revert_data.is_none() && !rpc_error.message.contains("revert") {
+            return None;
+        }
+        Some(SimulatedRevert {
+            message: rpc_error.message.clone(),
+            reason: revert_data.and_then(|data| self.decode(&data)),
+        })
+    }
+
+    /// Decodes revert data as a standard `Error(string)` / `Panic(uint256)` error, or as a custom error
+    /// declared in one of the contracts.
+    pub fn decode(&self, revert_data: &[u8]) -> Option<String> {
+        if revert_data.len() < 4 {
+            return None;
+        }
+        let (selector, payload) = revert_data.split_at(4);
+        let selector: [u8; 4] = selector.try_into().unwrap();
+        match selector {
+            ERROR_SELECTOR => {
+                let [Token::String(message)] = ethabi::decode(&[ParamType::String], payload)
+                    .ok()?
+                    .try_into()
+                    .ok()?
+                else {
+                    return None;
+                };
+                Some(format!("Error({message:?})"))
+            }
+            PANIC_SELECTOR => {
+                let [Token::Uint(code)] = ethabi::decode(&[ParamType::Uint(256)], payload)
+                    .ok()?
+                    .try_into()
+                    .ok()?
+                else {
+                    return None;
+                };
+                Some(format!("Panic({code:#x})"))
+            }
+            _ => {
+                let error = self.custom_errors.get(&selector)?;
+                let param_types: Vec<_> = error
+                    .inputs
+                    .iter()
+                    .map(|param| param.kind.clone())
+                    .collect();
+                let args = ethabi::decode(&param_types, payload).ok()?;
+                let args: Vec<_> = args.iter().map(Token::to_string).collect();
+                Some(format!("{}({})", error.name, args.join(", ")))
+            }
+        }
+    }
+}
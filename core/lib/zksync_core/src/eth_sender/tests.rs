@@ -7,7 +7,7 @@ use zksync_config::{
     ContractsConfig, ETHSenderConfig, GasAdjusterConfig,
 };
 use zksync_dal::{ConnectionPool, StorageProcessor};
-use zksync_eth_client::{clients::MockEthereum, EthInterface};
+use zksync_eth_client::{clients::MockEthereum, BoundEthInterface, EthInterface};
 use zksync_l1_contract_interface::i_executor::methods::{
     CommitBatches, ExecuteBatches, ProveBatches,
 };
@@ -15,16 +15,21 @@ use zksync_object_store::ObjectStoreFactory;
 use zksync_types::{
     block::L1BatchHeader,
     commitment::{L1BatchMetaParameters, L1BatchMetadata, L1BatchWithMetadata},
-    ethabi::Token,
+    ethabi::{self, Token},
     helpers::unix_timestamp_ms,
+    pubdata_da::PubdataDA,
     web3::contract::Error,
     Address, L1BatchNumber, L1BlockNumber, ProtocolVersionId, H256,
 };
 
 use crate::{
     eth_sender::{
-        aggregated_operations::AggregatedOperation, eth_tx_manager::L1BlockNumbers, Aggregator,
-        ETHSenderError, EthTxAggregator, EthTxManager,
+        aggregated_operations::AggregatedOperation,
+        eth_tx_manager::L1BlockNumbers,
+        fee_bumping::FeeBumpingPolicy,
+        metrics::ResendSkipReason,
+        simulation::{RevertDecoder, ERROR_SELECTOR},
+        Aggregator, ETHSenderError, EthTxAggregator, EthTxManager, NonceMismatch,
     },
     l1_gas_price::GasAdjuster,
     utils::testonly::{create_l1_batch, l1_batch_metadata_to_commitment_artifacts},
@@ -215,7 +220,7 @@ async fn confirm_many() -> anyhow::Result<()> {
     );
 
     // also check that we didn't try to resend it
-    assert!(to_resend.is_none());
+    assert!(to_resend.is_empty());
 
     Ok(())
 }
@@ -289,6 +294,7 @@ async fn resend_each_block() -> anyhow::Result<()> {
             block_numbers,
         )
         .await?
+        .pop()
         .unwrap();
 
     let resent_hash = tester
@@ -330,6 +336,281 @@ async fn resend_each_block() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[test]
+fn fee_bumping_policy() {
+    let policy = FeeBumpingPolicy {
+        max_attempts: Some(3),
+        bump_percent: 20,
+        max_fee_per_gas: Some(1_000),
+        min_blocks_between_attempts: 2,
+    };
+
+    assert_eq!(policy.check_resend(1, 2), Ok(()));
+    assert_eq!(policy.check_resend(1, 1), Err(ResendSkipReason::TooEarly));
+    assert_eq!(
+        policy.check_resend(3, 10),
+        Err(ResendSkipReason::MaxAttempts)
+    );
+
+    assert_eq!(policy.bump_priority_fee(100), 121);
+    assert_eq!(policy.bump_priority_fee(0), 1);
+
+    assert_eq!(policy.cap_fees(500, 100), (500, 100));
+    assert_eq!(policy.cap_fees(1_500, 100), (900, 100));
+    assert_eq!(policy.cap_fees(1_500, 2_000), (0, 1_000));
+
+    let unlimited_policy = FeeBumpingPolicy {
+        max_attempts: None,
+        max_fee_per_gas: None,
+        ..policy
+    };
+    assert_eq!(unlimited_policy.check_resend(1_000, 2), Ok(()));
+    assert_eq!(unlimited_policy.cap_fees(1_500, 2_000), (1_500, 2_000));
+}
+
+#[tokio::test]
+async fn resending_according_to_policy() -> anyhow::Result<()> {
+    let connection_pool = ConnectionPool::test_pool().await;
+    let mut tester = EthSenderTester::new(connection_pool, vec![7, 6, 5, 5, 5, 2, 1], false).await;
+    tester.manager = EthTxManager::new(
+        SenderConfig {
+            max_tx_attempts: Some(2),
+            min_blocks_between_resends: Some(2),
+            ..ETHSenderConfig::for_tests().sender
+        },
+        tester.gas_adjuster.clone(),
+        tester.gateway.clone(),
+    );
+    tester.gateway.advance_block_number(3);
+    tester.gas_adjuster.keep_updated().await?;
+
+    let mut storage = tester.conn.access_storage().await.unwrap();
+    let tx = tester
+        .aggregator
+        .save_eth_tx(&mut storage, &DUMMY_OPERATION, true)
+        .await?;
+    let block = L1BlockNumber(tester.gateway.block_number("").await?.as_u32());
+    tester
+        .manager
+        .send_eth_tx(&mut storage, &tx, 0, block)
+        .await?;
+
+    assert!(
+        !tester
+            .manager
+            .can_resend(&mut storage, &tx, block + 1)
+            .await
+    );
+    assert!(
+        tester
+            .manager
+            .can_resend(&mut storage, &tx, block + 2)
+            .await
+    );
+    tester
+        .manager
+        .send_eth_tx(&mut storage, &tx, 2, block + 2)
+        .await?;
+    assert_eq!(tester.gateway.sent_tx_count(), 2);
+    // The maximum number of attempts is reached.
+    assert!(
+        !tester
+            .manager
+            .can_resend(&mut storage, &tx, block + 10)
+            .await
+    );
+    Ok(())
+}
+
+// Tests that nonces of unsent txs are reassigned if the operator nonce is consumed by a tx sent bypassing `eth_sender`.
+#[tokio::test]
+async fn renumbering_unsent_txs_after_external_tx() -> anyhow::Result<()> {
+    let connection_pool = ConnectionPool::test_pool().await;
+    let tester = EthSenderTester::new(connection_pool, vec![10; 100], false).await;
+    let mut storage = tester.conn.access_storage().await.unwrap();
+    for _ in 0..2 {
+        tester
+            .aggregator
+            .save_eth_tx(&mut storage, &DUMMY_OPERATION, true)
+            .await?;
+    }
+
+    tester
+        .gateway
+        .execute_external_tx(EthSenderTester::WAIT_CONFIRMATIONS);
+    let block_numbers = tester.get_block_numbers().await;
+    tester
+        .manager
+        .reconcile_nonces(&mut storage, block_numbers)
+        .await?;
+
+    let unsent_txs = storage.eth_sender_dal().get_new_eth_txs(10).await?;
+    let nonces: Vec<_> = unsent_txs.iter().map(|tx| tx.nonce.0).collect();
+    assert_eq!(nonces, [1, 2]);
+    // New txs must get nonces following the renumbered ones.
+    let tx = tester
+        .aggregator
+        .save_eth_tx(&mut storage, &DUMMY_OPERATION, true)
+        .await?;
+    assert_eq!(tx.nonce.0, 3);
+    Ok(())
+}
+
+// Tests that a tx with the nonce consumed by a tx sent bypassing `eth_sender` is resent with a new nonce.
+#[tokio::test]
+async fn resetting_orphaned_tx() -> anyhow::Result<()> {
+    let connection_pool = ConnectionPool::test_pool().await;
+    let mut tester = EthSenderTester::new(connection_pool, vec![10; 100], false).await;
+    let mut storage = tester.conn.access_storage().await.unwrap();
+    let tx = tester
+        .aggregator
+        .save_eth_tx(&mut storage, &DUMMY_OPERATION, true)
+        .await?;
+    let block = L1BlockNumber(tester.gateway.block_number("").await?.as_u32());
+    let orphaned_hash = tester
+        .manager
+        .send_eth_tx(&mut storage, &tx, 0, block)
+        .await?;
+    let next_tx = tester
+        .aggregator
+        .save_eth_tx(&mut storage, &DUMMY_OPERATION, true)
+        .await?;
+
+    tester
+        .gateway
+        .execute_external_tx(EthSenderTester::WAIT_CONFIRMATIONS);
+    let block_numbers = tester.get_block_numbers().await;
+    tester
+        .manager
+        .reconcile_nonces(&mut storage, block_numbers)
+        .await?;
+
+    let unsent_txs = storage.eth_sender_dal().get_new_eth_txs(10).await?;
+    let unsent_txs: Vec<_> = unsent_txs.iter().map(|tx| (tx.id, tx.nonce.0)).collect();
+    assert_eq!(unsent_txs, [(tx.id, 1), (next_tx.id, 2)]);
+
+    let tx = storage.eth_sender_dal().get_eth_tx(tx.id).await?.unwrap();
+    let hash = tester
+        .manager
+        .send_eth_tx(&mut storage, &tx, 0, block_numbers.latest)
+        .await?;
+    assert_ne!(hash, orphaned_hash);
+    tester
+        .gateway
+        .execute_tx(hash, true, EthSenderTester::WAIT_CONFIRMATIONS);
+    let block_numbers = tester.get_block_numbers().await;
+    let to_resend = tester
+        .manager
+        .monitor_inflight_transactions(&mut storage, block_numbers)
+        .await?;
+    assert!(to_resend.is_empty());
+    let confirmed_hash = storage
+        .eth_sender_dal()
+        .get_confirmed_tx_hash_by_eth_tx_id(tx.id)
+        .await?;
+    assert_eq!(confirmed_hash, Some(hash));
+    Ok(())
+}
+
+// Tests that `eth_sender` halts if an orphaned tx cannot be resent since the following txs were already mined.
+#[tokio::test]
+async fn orphaned_tx_followed_by_mined_tx() -> anyhow::Result<()> {
+    let connection_pool = ConnectionPool::test_pool().await;
+    let mut tester = EthSenderTester::new(connection_pool, vec![10; 100], false).await;
+    let mut storage = tester.conn.access_storage().await.unwrap();
+    let block = L1BlockNumber(tester.gateway.block_number("").await?.as_u32());
+    let mut txs = vec![];
+    let mut hashes = vec![];
+    for _ in 0..2 {
+        let tx = tester
+            .aggregator
+            .save_eth_tx(&mut storage, &DUMMY_OPERATION, true)
+            .await?;
+        hashes.push(
+            tester
+                .manager
+                .send_eth_tx(&mut storage, &tx, 0, block)
+                .await?,
+        );
+        txs.push(tx);
+    }
+
+    tester.gateway.execute_external_tx(0);
+    tester
+        .gateway
+        .execute_tx(hashes[1], true, EthSenderTester::WAIT_CONFIRMATIONS);
+    let block_numbers = tester.get_block_numbers().await;
+    let err = tester
+        .manager
+        .reconcile_nonces(&mut storage, block_numbers)
+        .await
+        .unwrap_err();
+    assert_matches!(
+        err,
+        ETHSenderError::NonceMismatch(NonceMismatch::OrphanedTx { eth_tx_id, tx_hashes, .. })
+            if eth_tx_id == txs[0].id && tx_hashes == [hashes[0]]
+    );
+    Ok(())
+}
+
+#[test]
+fn decoding_revert_reasons() {
+    let decoder = RevertDecoder::default();
+    let revert_data = [
+        &ERROR_SELECTOR[..],
+        &ethabi::encode(&[Token::String("i".into())]),
+    ]
+    .concat();
+    assert_eq!(decoder.decode(&revert_data).unwrap(), "Error(\"i\")");
+
+    let panic_data =
+        hex::decode("4e487b710000000000000000000000000000000000000000000000000000000000000011")
+            .unwrap();
+    assert_eq!(decoder.decode(&panic_data).unwrap(), "Panic(0x11)");
+    assert_eq!(decoder.decode(&[0xde, 0xad, 0xbe, 0xef]), None);
+    assert_eq!(decoder.decode(&[1]), None);
+}
+
+// Tests that a tx is not sent if its simulation reverts.
+#[tokio::test]
+async fn reverting_tx_is_not_sent() -> anyhow::Result<()> {
+    let connection_pool = ConnectionPool::test_pool().await;
+    let mut tester = EthSenderTester::new(connection_pool, vec![10; 100], false).await;
+    let mut storage = tester.conn.access_storage().await.unwrap();
+    let tx = tester
+        .aggregator
+        .save_eth_tx(&mut storage, &DUMMY_OPERATION, true)
+        .await?;
+    let revert_data = [
+        &ERROR_SELECTOR[..],
+        &ethabi::encode(&[Token::String("Batch already committed".into())]),
+    ]
+    .concat();
+    tester.gateway.set_call_revert_data(Some(revert_data));
+
+    let block = L1BlockNumber(tester.gateway.block_number("").await?.as_u32());
+    let err = tester
+        .manager
+        .send_eth_tx(&mut storage, &tx, 0, block)
+        .await
+        .unwrap_err();
+    assert_matches!(
+        err,
+        ETHSenderError::SimulationReverted(reason) if reason == "Error(\"Batch already committed\")"
+    );
+    assert_eq!(tester.gateway.sent_tx_count(), 0);
+    let unsent_txs = storage.eth_sender_dal().get_new_eth_txs(10).await?;
+    assert_eq!(unsent_txs.len(), 1);
+
+    tester.gateway.set_call_revert_data(None);
+    tester
+        .manager
+        .send_eth_tx(&mut storage, &tx, 0, block)
+        .await?;
+    assert_eq!(tester.gateway.sent_tx_count(), 1);
+    Ok(())
+}
+
 // Tests that if transaction was mined, but not enough blocks has been mined since,
 // we won't mark it as confirmed but also won't resend it.
 #[tokio::test]
@@ -398,7 +679,7 @@ async fn dont_resend_already_mined() -> anyhow::Result<()> {
     );
 
     // also check that we didn't try to resend it
-    assert!(to_resend.is_none());
+    assert!(to_resend.is_empty());
 
     Ok(())
 }
@@ -453,6 +734,7 @@ async fn three_scenarios() -> anyhow::Result<()> {
             tester.get_block_numbers().await,
         )
         .await?
+        .pop()
         .expect("we should be trying to resend the last tx");
 
     // check that last 2 transactions are still considered in-flight
@@ -787,6 +1069,53 @@ async fn skipped_l1_batch_in_the_middle() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn committing_l1_batch_with_blobs() -> anyhow::Result<()> {
+    let connection_pool = ConnectionPool::test_pool().await;
+    let mut tester = EthSenderTester::new(connection_pool, vec![100; 100], false).await;
+    insert_genesis_protocol_version(&tester).await;
+    let genesis_l1_batch = insert_l1_batch(&tester, L1BatchNumber(0)).await;
+    let first_l1_batch = insert_l1_batch(&tester, L1BatchNumber(1)).await;
+
+    let operation = AggregatedOperation::Commit(CommitBatches {
+        last_committed_l1_batch: l1_batch_with_metadata(genesis_l1_batch),
+        l1_batches: vec![l1_batch_with_metadata(first_l1_batch)],
+        pubdata_da: PubdataDA::Blobs,
+    });
+    let tx = tester
+        .aggregator
+        .save_eth_tx(&mut tester.storage().await, &operation, true)
+        .await?;
+    let blob_sidecar = tx.blob_sidecar.clone().expect("no blob sidecar");
+    assert_eq!(blob_sidecar.versioned_hashes().len(), 1);
+
+    let saved_tx = tester
+        .storage()
+        .await
+        .eth_sender_dal()
+        .get_eth_tx(tx.id)
+        .await?
+        .unwrap();
+    assert_eq!(saved_tx.blob_sidecar, Some(blob_sidecar));
+
+    let block = L1BlockNumber(tester.gateway.block_number("").await?.as_u32());
+    tester
+        .manager
+        .send_eth_tx(&mut tester.conn.access_storage().await?, &tx, 0, block)
+        .await?;
+
+    let sent_tx = tester
+        .storage()
+        .await
+        .eth_sender_dal()
+        .get_last_sent_eth_tx(tx.id)
+        .await?
+        .unwrap();
+    // The mock L1 client reports zero blob base fees, so the minimum fee is used.
+    assert_eq!(sent_tx.blob_base_fee_per_gas, Some(1));
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_parse_multicall_data() {
     let connection_pool = ConnectionPool::test_pool().await;
@@ -867,6 +1196,181 @@ async fn test_parse_multicall_data() {
     }
 }
 
+#[tokio::test]
+async fn rotating_operator_after_confirming_txs() -> anyhow::Result<()> {
+    let connection_pool = ConnectionPool::test_pool().await;
+    let mut tester = EthSenderTester::new(connection_pool, vec![10; 100], false).await;
+    let new_address = Address::repeat_byte(0x33);
+    let secondary_gateway = Arc::new(
+        MockEthereum::default()
+            .with_sender_account(new_address)
+            .with_fee_history(vec![10; 100]),
+    );
+    secondary_gateway.advance_block_number(EthSenderTester::WAIT_CONFIRMATIONS);
+    tester.manager = EthTxManager::new(
+        ETHSenderConfig::for_tests().sender,
+        tester.gas_adjuster.clone(),
+        tester.gateway.clone(),
+    )
+    .with_secondary_operator(secondary_gateway.clone());
+
+    let hash = send_operation(&mut tester, DUMMY_OPERATION.clone(), false).await;
+    let rotation = tester
+        .storage()
+        .await
+        .eth_sender_dal()
+        .start_operator_rotation()
+        .await
+        .unwrap()
+        .expect("rotation is not started");
+
+    // New `eth_txs` cannot be created while the rotation is pending.
+    let mut storage = tester.conn.access_storage().await.unwrap();
+    let err = tester
+        .aggregator
+        .save_eth_tx(&mut storage, &DUMMY_OPERATION, true)
+        .await
+        .unwrap_err();
+    assert_matches!(err, ETHSenderError::OperatorRotationInProgress);
+    // The rotation cannot be completed while the `eth_tx` sent by the old operator is unconfirmed.
+    tester
+        .manager
+        .process_operator_rotation(&mut storage)
+        .await?;
+    let latest_rotation = storage
+        .eth_sender_dal()
+        .get_latest_operator_rotation()
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(latest_rotation.id, rotation.id);
+    assert!(!latest_rotation.is_completed());
+    drop(storage);
+
+    confirm_tx(&mut tester, hash).await;
+    let mut storage = tester.conn.access_storage().await.unwrap();
+    tester
+        .manager
+        .process_operator_rotation(&mut storage)
+        .await?;
+    let latest_rotation = storage
+        .eth_sender_dal()
+        .get_latest_operator_rotation()
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(latest_rotation.is_completed());
+    assert_eq!(latest_rotation.new_operator_address, Some(new_address));
+
+    // The aggregator switches to the new operator and tracks its nonces separately.
+    tester.aggregator.sync_operator(&mut storage).await?;
+    let tx = tester
+        .aggregator
+        .save_eth_tx(&mut storage, &DUMMY_OPERATION, true)
+        .await?;
+    assert_eq!(tx.from_addr, Some(new_address));
+    assert_eq!(tx.nonce.0, 0);
+
+    tester
+        .manager
+        .send_eth_tx(
+            &mut storage,
+            &tx,
+            0,
+            L1BlockNumber(secondary_gateway.block_number("").await?.as_u32()),
+        )
+        .await?;
+    assert_eq!(secondary_gateway.sent_tx_count(), 1);
+    assert_eq!(tester.gateway.sent_tx_count(), 1);
+    Ok(())
+}
+
+#[tokio::test]
+async fn sending_commit_txs_from_blob_operator() -> anyhow::Result<()> {
+    let connection_pool = ConnectionPool::test_pool().await;
+    let mut tester = EthSenderTester::new(connection_pool, vec![10; 100], false).await;
+    let operator_address = tester.gateway.sender_account();
+    let blob_operator_address = Address::repeat_byte(0x44);
+    let blob_gateway = Arc::new(
+        MockEthereum::default()
+            .with_sender_account(blob_operator_address)
+            .with_fee_history(vec![10; 100]),
+    );
+    blob_gateway.advance_block_number(EthSenderTester::WAIT_CONFIRMATIONS);
+    tester.aggregator = tester
+        .aggregator
+        .with_blob_operator(blob_gateway.clone())
+        .await;
+    tester.manager = EthTxManager::new(
+        ETHSenderConfig::for_tests().sender,
+        tester.gas_adjuster.clone(),
+        tester.gateway.clone(),
+    )
+    .with_blob_operator(blob_gateway.clone());
+
+    let commit_operation = AggregatedOperation::Commit(CommitBatches {
+        last_committed_l1_batch: l1_batch_with_metadata(create_l1_batch(0)),
+        l1_batches: vec![l1_batch_with_metadata(create_l1_batch(1))],
+        pubdata_da: PubdataDA::Calldata,
+    });
+    let mut storage = tester.conn.access_storage().await.unwrap();
+    let execute_tx = tester
+        .aggregator
+        .save_eth_tx(&mut storage, &DUMMY_OPERATION, true)
+        .await?;
+    let commit_txs = [
+        tester
+            .aggregator
+            .save_eth_tx(&mut storage, &commit_operation, true)
+            .await?,
+        tester
+            .aggregator
+            .save_eth_tx(&mut storage, &commit_operation, true)
+            .await?,
+    ];
+    // Nonces are assigned independently for each operator account.
+    assert_eq!(execute_tx.from_addr, Some(operator_address));
+    assert_eq!(execute_tx.nonce.0, 0);
+    for (i, commit_tx) in commit_txs.iter().enumerate() {
+        assert_eq!(commit_tx.from_addr, Some(blob_operator_address));
+        assert_eq!(commit_tx.nonce.0, i as u32);
+    }
+
+    let current_block = L1BlockNumber(tester.gateway.block_number("").await?.as_u32());
+    let execute_hash = tester
+        .manager
+        .send_eth_tx(&mut storage, &execute_tx, 0, current_block)
+        .await?;
+    let mut commit_hashes = vec![];
+    for commit_tx in &commit_txs {
+        let hash = tester
+            .manager
+            .send_eth_tx(&mut storage, commit_tx, 0, current_block)
+            .await?;
+        commit_hashes.push(hash);
+    }
+    assert_eq!(tester.gateway.sent_tx_count(), 1);
+    assert_eq!(blob_gateway.sent_tx_count(), 2);
+
+    // Only the first commit tx is mined; the second one should be resent.
+    tester
+        .gateway
+        .execute_tx(execute_hash, true, EthSenderTester::WAIT_CONFIRMATIONS);
+    blob_gateway.execute_tx(commit_hashes[0], true, EthSenderTester::WAIT_CONFIRMATIONS);
+    let block_numbers = tester.get_block_numbers().await;
+    let to_resend = tester
+        .manager
+        .monitor_inflight_transactions(&mut storage, block_numbers)
+        .await?;
+    assert_eq!(to_resend.len(), 1);
+    assert_eq!(to_resend[0].0.id, commit_txs[1].id);
+
+    let inflight_txs = storage.eth_sender_dal().get_inflight_txs().await?;
+    assert_eq!(inflight_txs.len(), 1);
+    assert_eq!(inflight_txs[0].id, commit_txs[1].id);
+    Ok(())
+}
+
 #[tokio::test]
 async fn get_multicall_data() {
     let connection_pool = ConnectionPool::test_pool().await;
@@ -951,6 +1455,7 @@ async fn commit_l1_batch(
     let operation = AggregatedOperation::Commit(CommitBatches {
         last_committed_l1_batch: l1_batch_with_metadata(last_committed_l1_batch),
         l1_batches: vec![l1_batch_with_metadata(l1_batch)],
+        pubdata_da: PubdataDA::Calldata,
     });
     send_operation(tester, operation, confirm).await
 }
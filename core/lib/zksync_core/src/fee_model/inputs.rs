@@ -0,0 +1,438 @@
+//! Configurable sources of the L1-dependent fee model inputs.
+
+use std::{
+    fmt,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+use anyhow::Context as _;
+use once_cell::sync::OnceCell;
+use tokio::{sync::watch, task::JoinHandle};
+use zksync_config::configs::chain::{FeeInputConfig, FeeInputParamConfig, FeeInputSource};
+
+use crate::l1_gas_price::{parse_base_fee, GasAdjuster};
+
+const HTTP_SOURCE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Provider of the L1-dependent inputs of the fee model used by [`MainNodeFeeInputProvider`](super::MainNodeFeeInputProvider).
+pub trait L1FeeInputProvider: fmt::Debug + Send + Sync + 'static {
+    /// Returns the L1 gas price in wei.
+    fn l1_gas_price(&self) -> u64;
+
+    /// Returns the price of publishing a single byte of pubdata on L1, in wei.
+    fn l1_pubdata_price(&self) -> u64;
+}
+
+impl L1FeeInputProvider for GasAdjuster {
+    fn l1_gas_price(&self) -> u64 {
+        self.estimate_effective_gas_price()
+    }
+
+    fn l1_pubdata_price(&self) -> u64 {
+        self.estimate_effective_pubdata_price()
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum FeeInputParam {
+    L1GasPrice,
+    L1PubdataPrice,
+}
+
+impl fmt::Display for FeeInputParam {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(match self {
+            Self::L1GasPrice => "l1_gas_price",
+            Self::L1PubdataPrice => "l1_pubdata_price",
+        })
+    }
+}
+
+impl FeeInputParam {
+    fn get(self, provider: &dyn L1FeeInputProvider) -> u64 {
+        match self {
+            Self::L1GasPrice => provider.l1_gas_price(),
+            Self::L1PubdataPrice => provider.l1_pubdata_price(),
+        }
+    }
+}
+
+enum ParamSource {
+    Oracle,
+    Fixed(u64),
+    Http {
+        client: reqwest::Client,
+        url: String,
+        json_pointer: String,
+        multiplier: f64,
+    },
+}
+
+impl fmt::Debug for ParamSource {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Oracle => formatter.write_str("Oracle"),
+            Self::Fixed(value) => formatter.debug_tuple("Fixed").field(value).finish(),
+            // The URL is not output since it may contain an API key.
+            Self::Http {
+                json_pointer,
+                multiplier,
+                ..
+            } => formatter
+                .debug_struct("Http")
+                .field("json_pointer", json_pointer)
+                .field("multiplier", multiplier)
+                .finish_non_exhaustive(),
+        }
+    }
+}
+
+/// Maintains the value of a single fee model parameter, applying smoothing and caps to the values
+/// provided by the source.
+#[derive(Debug)]
+struct ParamUpdater {
+    param: FeeInputParam,
+    source: ParamSource,
+    smoothing_alpha: Option<f64>,
+    min: u64,
+    max: u64,
+    smoothed_value: Mutex<Option<f64>>,
+    value: AtomicU64,
+}
+
+impl ParamUpdater {
+    fn new(param: FeeInputParam, config: FeeInputParamConfig) -> anyhow::Result<Self> {
+        let source = match config.source {
+            FeeInputSource::Oracle => ParamSource::Oracle,
+            FeeInputSource::Fixed => ParamSource::Fixed(
+                config
+                    .fixed_value
+                    .with_context(|| format!("`{param}_fixed_value` is required"))?,
+            ),
+            FeeInputSource::Http => {
+                let url = config
+                    .url
+                    .clone()
+                    .with_context(|| format!("`{param}_url` is required"))?;
+                reqwest::Url::parse(&url).with_context(|| format!("invalid `{param}_url`"))?;
+                let multiplier = config.multiplier();
+                anyhow::ensure!(
+                    multiplier.is_finite() && multiplier > 0.0,
+                    "`{param}_multiplier` must be positive, got {multiplier}"
+                );
+                ParamSource::Http {
+                    client: reqwest::Client::builder()
+                        .timeout(HTTP_SOURCE_TIMEOUT)
+                        .build()
+                        .context("failed creating HTTP client")?,
+                    url,
+                    json_pointer: config.json_pointer().to_owned(),
+                    multiplier,
+                }
+            }
+        };
+
+        if let Some(alpha) = config.smoothing_alpha {
+            anyhow::ensure!(
+                alpha > 0.0 && alpha <= 1.0,
+                "`{param}_smoothing_alpha` must be in the (0, 1] range, got {alpha}"
+            );
+        }
+        let min = config.min.unwrap_or(0);
+        let max = config.max.unwrap_or(u64::MAX);
+        anyhow::ensure!(
+            min <= max,
+            "`{param}_min` ({min}) must not exceed `{param}_max` ({max})"
+        );
+
+        Ok(Self {
+            param,
+            source,
+            smoothing_alpha: config.smoothing_alpha,
+            min,
+            max,
+            smoothed_value: Mutex::new(None),
+            value: AtomicU64::new(0),
+        })
+    }
+
+    /// Returns a value used before the first update. For the HTTP source, the oracle value is used.
+    fn initial_sample(&self, oracle: &dyn L1FeeInputProvider) -> u64 {
+        match &self.source {
+            ParamSource::Fixed(value) => *value,
+            ParamSource::Oracle | ParamSource::Http { .. } => self.param.get(oracle),
+        }
+    }
+
+    async fn sample(&self, oracle: &dyn L1FeeInputProvider) -> anyhow::Result<u64> {
+        match &self.source {
+            ParamSource::Oracle => Ok(self.param.get(oracle)),
+            ParamSource::Fixed(value) => Ok(*value),
+            ParamSource::Http {
+                client,
+                url,
+                json_pointer,
+                multiplier,
+            } => {
+                let response = client
+                    .get(url)
+                    .send()
+                    .await
+                    .and_then(reqwest::Response::error_for_status)
+                    .map_err(reqwest::Error::without_url)?;
+                let response: serde_json::Value =
+                    response.json().await.map_err(reqwest::Error::without_url)?;
+                parse_base_fee(&response, json_pointer, *multiplier)
+            }
+        }
+    }
+
+    fn update(&self, sample: u64) -> u64 {
+        let smoothed_value = {
+            let mut smoothed_value = self.smoothed_value.lock().unwrap();
+            let new_value = match (self.smoothing_alpha, *smoothed_value) {
+                (Some(alpha), Some(prev)) => alpha * sample as f64 + (1.0 - alpha) * prev,
+                _ => sample as f64,
+            };
+            *smoothed_value = Some(new_value);
+            new_value
+        };
+        let value = (smoothed_value as u64).clamp(self.min, self.max);
+        self.value.store(value, Ordering::Relaxed);
+        value
+    }
+
+    fn value(&self) -> u64 {
+        self.value.load(Ordering::Relaxed)
+    }
+}
+
+/// [`L1FeeInputProvider`] with configurable sources for each parameter. Parameter values
+/// are periodically updated from their sources by [`Self::run()`].
+#[derive(Debug)]
+pub struct ConfiguredL1FeeInputProvider {
+    oracle: Arc<dyn L1FeeInputProvider>,
+    poll_interval: Duration,
+    l1_gas_price: ParamUpdater,
+    l1_pubdata_price: ParamUpdater,
+}
+
+impl ConfiguredL1FeeInputProvider {
+    /// Creates a provider based on the config. `oracle` is used for parameters with the `Oracle` source,
+    /// and as the initial value for parameters with the `Http` source.
+    pub fn new(
+        config: &FeeInputConfig,
+        oracle: Arc<dyn L1FeeInputProvider>,
+    ) -> anyhow::Result<Self> {
+        let l1_gas_price = ParamUpdater::new(FeeInputParam::L1GasPrice, config.l1_gas_price())?;
+        let l1_pubdata_price =
+            ParamUpdater::new(FeeInputParam::L1PubdataPrice, config.l1_pubdata_price())?;
+        for updater in [&l1_gas_price, &l1_pubdata_price] {
+            updater.update(updater.initial_sample(oracle.as_ref()));
+        }
+        Ok(Self {
+            oracle,
+            poll_interval: config.poll_interval(),
+            l1_gas_price,
+            l1_pubdata_price,
+        })
+    }
+
+    async fn update(&self) {
+        for updater in [&self.l1_gas_price, &self.l1_pubdata_price] {
+            match updater.sample(self.oracle.as_ref()).await {
+                Ok(sample) => {
+                    let value = updater.update(sample);
+                    tracing::trace!("Updated `{}` to {value} (sample: {sample})", updater.param);
+                }
+                Err(err) => {
+                    tracing::warn!(
+                        "Failed getting `{}` from its source: {err:#}",
+                        updater.param
+                    );
+                }
+            }
+        }
+    }
+
+    pub async fn run(
+        self: Arc<Self>,
+        mut stop_receiver: watch::Receiver<bool>,
+    ) -> anyhow::Result<()> {
+        while !*stop_receiver.borrow_and_update() {
+            self.update().await;
+            // Error here corresponds to a timeout w/o `stop_receiver` changed; we're OK with this.
+            tokio::time::timeout(self.poll_interval, stop_receiver.changed())
+                .await
+                .ok();
+        }
+        tracing::info!("Stop signal received, L1 fee input provider is shutting down");
+        Ok(())
+    }
+}
+
+impl L1FeeInputProvider for ConfiguredL1FeeInputProvider {
+    fn l1_gas_price(&self) -> u64 {
+        self.l1_gas_price.value()
+    }
+
+    fn l1_pubdata_price(&self) -> u64 {
+        self.l1_pubdata_price.value()
+    }
+}
+
+/// Special struct for creating a singleton of [`ConfiguredL1FeeInputProvider`] shared among server components.
+/// This is needed only for running the server.
+#[derive(Debug)]
+pub struct L1FeeInputSingleton {
+    config: Option<FeeInputConfig>,
+    singleton: OnceCell<Arc<ConfiguredL1FeeInputProvider>>,
+}
+
+impl L1FeeInputSingleton {
+    pub fn new(config: Option<FeeInputConfig>) -> Self {
+        Self {
+            config,
+            singleton: OnceCell::new(),
+        }
+    }
+
+    /// Returns the configured provider. If the config is not specified, returns `gas_adjuster` as is.
+    pub fn get_or_init(
+        &self,
+        gas_adjuster: Arc<GasAdjuster>,
+    ) -> anyhow::Result<Arc<dyn L1FeeInputProvider>> {
+        let Some(config) = &self.config else {
+            return Ok(gas_adjuster);
+        };
+        let provider = self.singleton.get_or_try_init(|| {
+            ConfiguredL1FeeInputProvider::new(config, gas_adjuster).map(Arc::new)
+        })?;
+        Ok(provider.clone())
+    }
+
+    pub fn run_if_initialized(
+        self,
+        stop_receiver: watch::Receiver<bool>,
+    ) -> Option<JoinHandle<anyhow::Result<()>>> {
+        let provider = self.singleton.into_inner()?;
+        Some(tokio::spawn(provider.run(stop_receiver)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug)]
+    struct MockOracle(AtomicU64);
+
+    impl L1FeeInputProvider for MockOracle {
+        fn l1_gas_price(&self) -> u64 {
+            self.0.load(Ordering::Relaxed)
+        }
+
+        fn l1_pubdata_price(&self) -> u64 {
+            self.l1_gas_price() * 17
+        }
+    }
+
+    fn param_config(source: FeeInputSource) -> FeeInputParamConfig {
+        FeeInputParamConfig {
+            source,
+            fixed_value: None,
+            url: None,
+            json_pointer: None,
+            multiplier: None,
+            smoothing_alpha: None,
+            min: None,
+            max: None,
+        }
+    }
+
+    #[test]
+    fn smoothing_and_capping_param() {
+        let config = FeeInputParamConfig {
+            smoothing_alpha: Some(0.5),
+            min: Some(10),
+            max: Some(1_000),
+            ..param_config(FeeInputSource::Oracle)
+        };
+        let updater = ParamUpdater::new(FeeInputParam::L1GasPrice, config).unwrap();
+        assert_eq!(updater.update(100), 100);
+        assert_eq!(updater.update(200), 150);
+        assert_eq!(updater.update(0), 75);
+        assert_eq!(updater.update(0), 37);
+        assert_eq!(updater.update(0), 18);
+        assert_eq!(updater.update(0), 10); // capped by `min`
+        assert_eq!(updater.update(10_000), 1_000); // capped by `max`
+        assert_eq!(updater.value(), 1_000);
+    }
+
+    #[test]
+    fn validating_param_config() {
+        let param = FeeInputParam::L1PubdataPrice;
+        let err = ParamUpdater::new(param, param_config(FeeInputSource::Fixed)).unwrap_err();
+        assert!(
+            err.to_string().contains("l1_pubdata_price_fixed_value"),
+            "{err}"
+        );
+        let err = ParamUpdater::new(param, param_config(FeeInputSource::Http)).unwrap_err();
+        assert!(err.to_string().contains("l1_pubdata_price_url"), "{err}");
+
+        let config = FeeInputParamConfig {
+            smoothing_alpha: Some(0.0),
+            ..param_config(FeeInputSource::Oracle)
+        };
+        let err = ParamUpdater::new(param, config).unwrap_err();
+        assert!(err.to_string().contains("smoothing_alpha"), "{err}");
+
+        let config = FeeInputParamConfig {
+            min: Some(100),
+            max: Some(10),
+            ..param_config(FeeInputSource::Oracle)
+        };
+        let err = ParamUpdater::new(param, config).unwrap_err();
+        assert!(err.to_string().contains("must not exceed"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn configured_provider_basics() {
+        let config = FeeInputConfig {
+            l1_gas_price_source: FeeInputSource::Oracle,
+            l1_gas_price_fixed_value: None,
+            l1_gas_price_url: None,
+            l1_gas_price_json_pointer: None,
+            l1_gas_price_multiplier: None,
+            l1_gas_price_smoothing_alpha: Some(0.5),
+            l1_gas_price_min: None,
+            l1_gas_price_max: Some(150),
+            l1_pubdata_price_source: FeeInputSource::Fixed,
+            l1_pubdata_price_fixed_value: Some(1_000),
+            l1_pubdata_price_url: None,
+            l1_pubdata_price_json_pointer: None,
+            l1_pubdata_price_multiplier: None,
+            l1_pubdata_price_smoothing_alpha: None,
+            l1_pubdata_price_min: Some(2_000),
+            l1_pubdata_price_max: None,
+            poll_interval_ms: None,
+        };
+        let oracle = Arc::new(MockOracle(AtomicU64::new(100)));
+        let provider = ConfiguredL1FeeInputProvider::new(&config, oracle.clone()).unwrap();
+        assert_eq!(provider.l1_gas_price(), 100);
+        assert_eq!(provider.l1_pubdata_price(), 2_000);
+
+        oracle.0.store(120, Ordering::Relaxed);
+        provider.update().await;
+        assert_eq!(provider.l1_gas_price(), 110);
+        assert_eq!(provider.l1_pubdata_price(), 2_000);
+
+        oracle.0.store(1_000, Ordering::Relaxed);
+        provider.update().await;
+        assert_eq!(provider.l1_gas_price(), 150);
+    }
+}
@@ -3,14 +3,17 @@ use std::{fmt, sync::Arc};
 use zksync_dal::ConnectionPool;
 use zksync_types::{
     fee_model::{
-        BatchFeeInput, FeeModelConfig, FeeModelConfigV2, FeeParams, FeeParamsV1, FeeParamsV2,
-        L1PeggedBatchFeeModelInput, PubdataIndependentBatchFeeModelInput,
+        BaseTokenConversionRatio, BatchFeeInput, FeeModelConfig, FeeModelConfigV2, FeeParams,
+        FeeParamsV1, FeeParamsV2, L1PeggedBatchFeeModelInput, PubdataIndependentBatchFeeModelInput,
     },
     U256,
 };
 use zksync_utils::ceil_div_u256;
 
-use crate::l1_gas_price::GasAdjuster;
+pub use self::inputs::{ConfiguredL1FeeInputProvider, L1FeeInputProvider, L1FeeInputSingleton};
+use crate::base_token_ratio::BaseTokenRatioFetcher;
+
+mod inputs;
 
 /// Trait responsible for providing fee info for a batch
 #[async_trait::async_trait]
@@ -53,29 +56,47 @@ pub trait BatchFeeModelInputProvider: fmt::Debug + 'static + Send + Sync {
 /// it from other node.
 #[derive(Debug)]
 pub struct MainNodeFeeInputProvider {
-    provider: Arc<GasAdjuster>,
+    provider: Arc<dyn L1FeeInputProvider>,
     config: FeeModelConfig,
+    base_token_ratio: Option<Arc<BaseTokenRatioFetcher>>,
 }
 
 impl BatchFeeModelInputProvider for MainNodeFeeInputProvider {
     fn get_fee_model_params(&self) -> FeeParams {
+        let conversion_ratio = self
+            .base_token_ratio
+            .as_ref()
+            .map_or_else(BaseTokenConversionRatio::default, |fetcher| fetcher.ratio());
         match self.config {
             FeeModelConfig::V1(config) => FeeParams::V1(FeeParamsV1 {
                 config,
-                l1_gas_price: self.provider.estimate_effective_gas_price(),
+                l1_gas_price: self.provider.l1_gas_price(),
+                conversion_ratio,
             }),
             FeeModelConfig::V2(config) => FeeParams::V2(FeeParamsV2 {
                 config,
-                l1_gas_price: self.provider.estimate_effective_gas_price(),
-                l1_pubdata_price: self.provider.estimate_effective_pubdata_price(),
+                l1_gas_price: self.provider.l1_gas_price(),
+                l1_pubdata_price: self.provider.l1_pubdata_price(),
+                conversion_ratio,
             }),
         }
     }
 }
 
 impl MainNodeFeeInputProvider {
-    pub fn new(provider: Arc<GasAdjuster>, config: FeeModelConfig) -> Self {
-        Self { provider, config }
+    pub fn new(provider: Arc<dyn L1FeeInputProvider>, config: FeeModelConfig) -> Self {
+        Self {
+            provider,
+            config,
+            base_token_ratio: None,
+        }
+    }
+
+    /// Denominates fees in the base token using the ratio provided by `fetcher`. If `fetcher` is not set,
+    /// fees are denominated in ETH.
+    pub fn with_base_token_ratio(mut self, fetcher: Option<Arc<BaseTokenRatioFetcher>>) -> Self {
+        self.base_token_ratio = fetcher;
+        self
     }
 }
 
@@ -138,10 +159,11 @@ fn compute_batch_fee_model_input_v1(
     l1_gas_price_scale_factor: f64,
 ) -> L1PeggedBatchFeeModelInput {
     let l1_gas_price = (params.l1_gas_price as f64 * l1_gas_price_scale_factor) as u64;
+    let ratio = params.conversion_ratio;
 
     L1PeggedBatchFeeModelInput {
-        l1_gas_price,
-        fair_l2_gas_price: params.config.minimal_l2_gas_price,
+        l1_gas_price: ratio.convert(l1_gas_price),
+        fair_l2_gas_price: ratio.convert(params.config.minimal_l2_gas_price),
     }
 }
 
@@ -156,6 +178,7 @@ fn compute_batch_fee_model_input_v2(
         config,
         l1_gas_price,
         l1_pubdata_price,
+        conversion_ratio,
     } = params;
 
     let FeeModelConfigV2 {
@@ -171,6 +194,12 @@ fn compute_batch_fee_model_input_v2(
     let l1_gas_price = (l1_gas_price as f64 * l1_gas_price_scale_factor) as u64;
     let l1_pubdata_price = (l1_pubdata_price as f64 * l1_pubdata_price_scale_factor) as u64;
 
+    // Then, we convert prices from wei to the base token. All computations below are linear in these prices,
+    // so the resulting fee input is denominated in the base token as well.
+    let l1_gas_price = conversion_ratio.convert(l1_gas_price);
+    let l1_pubdata_price = conversion_ratio.convert(l1_pubdata_price);
+    let minimal_l2_gas_price = conversion_ratio.convert(minimal_l2_gas_price);
+
     // While the final results of the calculations are not expected to have any overflows, the intermediate computations
     // might, so we use U256 for them.
     let l1_batch_overhead_wei = U256::from(l1_gas_price) * U256::from(batch_overhead_l1_gas);
@@ -214,6 +243,8 @@ fn compute_batch_fee_model_input_v2(
 
 #[cfg(test)]
 mod tests {
+    use zksync_types::fee_model::FeeModelConfigV1;
+
     use super::*;
 
     // To test that overflow never happens, we'll use giant L1 gas price, i.e.
@@ -244,6 +275,7 @@ mod tests {
             config,
             l1_gas_price: GIANT_L1_GAS_PRICE,
             l1_pubdata_price: GIANT_L1_GAS_PRICE,
+            conversion_ratio: BaseTokenConversionRatio::default(),
         };
 
         // We'll use scale factor of 3.0
@@ -270,6 +302,7 @@ mod tests {
             config,
             l1_gas_price: SMALL_L1_GAS_PRICE,
             l1_pubdata_price: SMALL_L1_GAS_PRICE,
+            conversion_ratio: BaseTokenConversionRatio::default(),
         };
 
         let input = compute_batch_fee_model_input_v2(params, 1.0, 1.0);
@@ -295,6 +328,7 @@ mod tests {
             config,
             l1_gas_price: GIANT_L1_GAS_PRICE,
             l1_pubdata_price: GIANT_L1_GAS_PRICE,
+            conversion_ratio: BaseTokenConversionRatio::default(),
         };
 
         let input = compute_batch_fee_model_input_v2(params, 1.0, 1.0);
@@ -321,6 +355,7 @@ mod tests {
             config,
             l1_gas_price: GIANT_L1_GAS_PRICE,
             l1_pubdata_price: GIANT_L1_GAS_PRICE,
+            conversion_ratio: BaseTokenConversionRatio::default(),
         };
 
         let input = compute_batch_fee_model_input_v2(params, 1.0, 1.0);
@@ -347,6 +382,7 @@ mod tests {
             config: base_config,
             l1_gas_price: 1_000_000_000,
             l1_pubdata_price: 1_000_000_000,
+            conversion_ratio: BaseTokenConversionRatio::default(),
         };
 
         let base_input = compute_batch_fee_model_input_v2(base_params, 1.0, 1.0);
@@ -437,4 +473,51 @@ mod tests {
             "Max pubdata increase lowers pubdata price"
         );
     }
+
+    #[test]
+    fn test_compute_batch_fee_model_input_with_base_token() {
+        let config = FeeModelConfigV2 {
+            minimal_l2_gas_price: 100_000_000,
+            compute_overhead_part: 0.5,
+            pubdata_overhead_part: 0.5,
+            batch_overhead_l1_gas: 700_000,
+            max_gas_per_batch: 500_000_000,
+            max_pubdata_per_batch: 100_000,
+        };
+        let eth_params = FeeParamsV2 {
+            config,
+            l1_gas_price: 1_000_000_000,
+            l1_pubdata_price: 1_000_000_000,
+            conversion_ratio: BaseTokenConversionRatio::default(),
+        };
+        let eth_input = compute_batch_fee_model_input_v2(eth_params, 1.0, 1.0);
+
+        // 1 wei is worth 3 / 2 base token units.
+        let conversion_ratio = BaseTokenConversionRatio {
+            numerator: 3.try_into().unwrap(),
+            denominator: 2.try_into().unwrap(),
+        };
+        let base_token_params = FeeParamsV2 {
+            conversion_ratio,
+            ..eth_params
+        };
+        let input = compute_batch_fee_model_input_v2(base_token_params, 1.0, 1.0);
+        assert_eq!(input.l1_gas_price, eth_input.l1_gas_price * 3 / 2);
+        assert_eq!(input.fair_l2_gas_price, eth_input.fair_l2_gas_price * 3 / 2);
+        assert_eq!(
+            input.fair_pubdata_price,
+            eth_input.fair_pubdata_price * 3 / 2
+        );
+
+        let v1_params = FeeParamsV1 {
+            config: FeeModelConfigV1 {
+                minimal_l2_gas_price: 100_000_000,
+            },
+            l1_gas_price: 1_000_000_000,
+            conversion_ratio,
+        };
+        let input = compute_batch_fee_model_input_v1(v1_params, 1.0);
+        assert_eq!(input.l1_gas_price, 1_500_000_000);
+        assert_eq!(input.fair_l2_gas_price, 150_000_000);
+    }
 }
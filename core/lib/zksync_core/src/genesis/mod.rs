@@ -8,6 +8,7 @@ use multivm::{
     zk_evm_latest::aux_structures::{LogQuery as MultiVmLogQuery, Timestamp as MultiVMTimestamp},
     zkevm_test_harness_latest::witness::sort_storage_access::sort_storage_access_queries,
 };
+use serde::Deserialize;
 use zksync_contracts::{BaseSystemContracts, SET_CHAIN_ID_EVENT};
 use zksync_dal::StorageProcessor;
 use zksync_eth_client::{clients::QueryClient, EthInterface};
@@ -30,8 +31,11 @@ use zksync_types::{
 };
 use zksync_utils::{be_words_to_bytes, bytecode::hash_bytecode, h256_to_u256, u256_to_h256};
 
+pub use self::spec::{GenesisSpec, PredeployedContractSpec, SystemContractsSpec};
 use crate::metadata_calculator::L1BatchWithLogs;
 
+mod spec;
+
 #[derive(Debug, Clone)]
 pub struct GenesisParams {
     pub first_validator: Address,
@@ -40,6 +44,25 @@ pub struct GenesisParams {
     pub system_contracts: Vec<DeployedContract>,
     pub first_verifier_address: Address,
     pub first_l1_verifier_config: L1VerifierConfig,
+    pub custom_state: CustomGenesisState,
+}
+
+/// Fee parameters of the genesis miniblock.
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GenesisFeeParams {
+    pub l1_gas_price: u64,
+    pub fair_l2_gas_price: u64,
+}
+
+/// Chain-specific additions to the default genesis state, e.g. loaded from a [`GenesisSpec`].
+#[derive(Debug, Clone, Default)]
+pub struct CustomGenesisState {
+    /// Contracts deployed in addition to system contracts.
+    pub predeployed_contracts: Vec<DeployedContract>,
+    /// Storage writes applied after deploying contracts, such as contract storage and initial balances.
+    pub storage_logs: Vec<StorageLog>,
+    pub fee_params: GenesisFeeParams,
 }
 
 impl GenesisParams {
@@ -54,6 +77,7 @@ impl GenesisParams {
             system_contracts: get_system_smart_contracts(),
             first_l1_verifier_config: L1VerifierConfig::default(),
             first_verifier_address: Address::zero(),
+            custom_state: CustomGenesisState::default(),
         }
     }
 }
@@ -84,6 +108,7 @@ pub async fn ensure_genesis_state(
         system_contracts,
         first_verifier_address,
         first_l1_verifier_config,
+        custom_state,
     } = genesis_params;
 
     let base_system_contracts_hashes = base_system_contracts.hashes();
@@ -97,6 +122,7 @@ pub async fn ensure_genesis_state(
         system_contracts,
         *first_l1_verifier_config,
         *first_verifier_address,
+        custom_state,
     )
     .await?;
     tracing::info!("chain_schema_genesis is complete");
@@ -175,9 +201,16 @@ async fn insert_base_system_contracts_to_factory_deps(
 async fn insert_system_contracts(
     storage: &mut StorageProcessor<'_>,
     contracts: &[DeployedContract],
+    custom_state: &CustomGenesisState,
     chain_id: L2ChainId,
 ) -> anyhow::Result<()> {
     let system_context_init_logs = (H256::default(), get_system_context_init_logs(chain_id));
+    let custom_storage_logs = (!custom_state.storage_logs.is_empty())
+        .then(|| (H256::default(), custom_state.storage_logs.clone()));
+    let contracts: Vec<_> = contracts
+        .iter()
+        .chain(&custom_state.predeployed_contracts)
+        .collect();
 
     let storage_logs: Vec<_> = contracts
         .iter()
@@ -190,6 +223,7 @@ async fn insert_system_contracts(
             )
         })
         .chain(Some(system_context_init_logs))
+        .chain(custom_storage_logs)
         .collect();
 
     let mut transaction = storage.start_transaction().await?;
@@ -296,6 +330,7 @@ pub(crate) async fn create_genesis_l1_batch(
     system_contracts: &[DeployedContract],
     l1_verifier_config: L1VerifierConfig,
     verifier_address: Address,
+    custom_state: &CustomGenesisState,
 ) -> anyhow::Result<()> {
     let version = ProtocolVersion {
         id: protocol_version,
@@ -322,7 +357,10 @@ pub(crate) async fn create_genesis_l1_batch(
         fee_account_address: first_validator_address,
         base_fee_per_gas: 0,
         gas_per_pubdata_limit: get_max_gas_per_pubdata_byte(protocol_version.into()),
-        batch_fee_input: BatchFeeInput::l1_pegged(0, 0),
+        batch_fee_input: BatchFeeInput::l1_pegged(
+            custom_state.fee_params.l1_gas_price,
+            custom_state.fee_params.fair_l2_gas_price,
+        ),
         base_system_contracts_hashes: base_system_contracts.hashes(),
         protocol_version: Some(protocol_version),
         virtual_blocks: 0,
@@ -358,7 +396,7 @@ pub(crate) async fn create_genesis_l1_batch(
         .context("failed assigning genesis miniblock to L1 batch")?;
 
     insert_base_system_contracts_to_factory_deps(&mut transaction, base_system_contracts).await?;
-    insert_system_contracts(&mut transaction, system_contracts, chain_id)
+    insert_system_contracts(&mut transaction, system_contracts, custom_state, chain_id)
         .await
         .context("cannot insert system contracts")?;
     add_eth_token(&mut transaction).await?;
@@ -479,6 +517,7 @@ mod tests {
             system_contracts: get_system_smart_contracts(),
             first_l1_verifier_config: L1VerifierConfig::default(),
             first_verifier_address: Address::random(),
+            custom_state: CustomGenesisState::default(),
         };
         ensure_genesis_state(&mut conn, L2ChainId::from(270), &params)
             .await
@@ -512,6 +551,7 @@ mod tests {
             system_contracts: get_system_smart_contracts(),
             first_l1_verifier_config: L1VerifierConfig::default(),
             first_verifier_address: Address::random(),
+            custom_state: CustomGenesisState::default(),
         };
         ensure_genesis_state(&mut conn, L2ChainId::max(), &params)
             .await
@@ -526,6 +566,50 @@ mod tests {
         assert_ne!(root_hash, H256::zero());
     }
 
+    #[tokio::test]
+    async fn running_genesis_with_custom_state() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        let rich_account = Address::repeat_byte(0x23);
+        let balance_key = zksync_types::utils::storage_key_for_eth_balance(&rich_account);
+        let balance = H256::from_low_u64_be(1_000_000);
+        let contract =
+            DeployedContract::new(AccountTreeId::new(Address::repeat_byte(0x42)), vec![1; 32]);
+        let params = GenesisParams {
+            custom_state: CustomGenesisState {
+                predeployed_contracts: vec![contract.clone()],
+                storage_logs: vec![StorageLog::new_write_log(balance_key, balance)],
+                fee_params: GenesisFeeParams {
+                    l1_gas_price: 100,
+                    fair_l2_gas_price: 10,
+                },
+            },
+            ..GenesisParams::mock()
+        };
+        let root_hash = ensure_genesis_state(&mut conn, L2ChainId::default(), &params)
+            .await
+            .unwrap();
+
+        let default_root_hash = {
+            let pool = ConnectionPool::test_pool().await;
+            let mut conn = pool.access_storage().await.unwrap();
+            ensure_genesis_state(&mut conn, L2ChainId::default(), &GenesisParams::mock())
+                .await
+                .unwrap()
+        };
+        assert_ne!(root_hash, default_root_hash);
+
+        let stored_balance = conn
+            .storage_web3_dal()
+            .get_value(&balance_key)
+            .await
+            .unwrap();
+        assert_eq!(stored_balance, balance);
+        let code_key = get_code_key(contract.account_id.address());
+        let code_hash = conn.storage_web3_dal().get_value(&code_key).await.unwrap();
+        assert_eq!(code_hash, hash_bytecode(&contract.bytecode));
+    }
+
     #[tokio::test]
     async fn running_genesis_with_non_latest_protocol_version() {
         let pool = ConnectionPool::test_pool().await;
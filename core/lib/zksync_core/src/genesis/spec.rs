@@ -0,0 +1,393 @@
+//! Declarative specification of the genesis state for custom chains.
+//!
+//! A spec is a YAML or TOML file, for example:
+//!
+//! ```yaml
+//! chain_id: 270
+//! protocol_version: 20
+//! system_contracts:
+//!   dir: ./system-contracts
+//! predeployed_contracts:
+//!   - address: "0x0000000000000000000000000000000000010000"
+//!     bytecode: ./artifacts/Registry.json
+//!     storage:
+//!       "0x0000000000000000000000000000000000000000000000000000000000000000": "0x0000000000000000000000000000000000000000000000000000000000000001"
+//! balances:
+//!   "0x36615cf349d7f6344891b1e7ca7c72883f5dc049": "0xde0b6b3a7640000"
+//! fee_params:
+//!   l1_gas_price: 1000000000
+//!   fair_l2_gas_price: 250000000
+//! ```
+//!
+//! Relative paths are resolved relative to the directory containing the spec file.
+
+use std::{
+    collections::{BTreeMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Context as _;
+use serde::Deserialize;
+use zksync_contracts::{BaseSystemContracts, SystemContractCode};
+use zksync_env_config::ConfigFileFormat;
+use zksync_system_constants::L2_ETH_TOKEN_ADDRESS;
+use zksync_types::{
+    block::DeployedContract,
+    protocol_version::L1VerifierConfig,
+    system_contracts::{get_system_smart_contracts, get_system_smart_contracts_from_dir},
+    utils::storage_key_for_eth_balance,
+    AccountTreeId, Address, L2ChainId, ProtocolVersionId, StorageKey, StorageLog, H256, U256,
+};
+use zksync_utils::{
+    bytecode::{hash_bytecode, validate_bytecode},
+    bytes_to_be_words, u256_to_h256,
+};
+
+use super::{CustomGenesisState, GenesisFeeParams, GenesisParams};
+
+/// Storage slot of `totalSupply` in the `L2EthToken` system contract (slot 0 holds the balance mapping).
+const L2_ETH_TOKEN_TOTAL_SUPPLY_SLOT: u64 = 1;
+/// Addresses up to this value are reserved for system contracts (kernel space).
+const MAX_KERNEL_SPACE_ADDRESS: u64 = 0xffff;
+
+/// Specification of the genesis state loaded from a YAML or TOML file.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GenesisSpec {
+    /// L2 chain ID. Must match the chain ID in the network config.
+    pub chain_id: L2ChainId,
+    /// Protocol version of the genesis L1 batch. If not specified, the latest protocol version is used.
+    pub protocol_version: Option<u16>,
+    /// Fee account of the genesis miniblock. If not specified, the operator address is used.
+    pub fee_account: Option<Address>,
+    #[serde(default)]
+    pub system_contracts: SystemContractsSpec,
+    /// Contracts deployed at genesis in addition to system contracts.
+    #[serde(default)]
+    pub predeployed_contracts: Vec<PredeployedContractSpec>,
+    /// Initial ETH balances of L2 accounts in wei.
+    #[serde(default)]
+    pub balances: BTreeMap<Address, U256>,
+    /// Fee parameters of the genesis miniblock.
+    #[serde(default)]
+    pub fee_params: GenesisFeeParams,
+    /// Directory used to resolve relative paths.
+    #[serde(skip)]
+    base_dir: PathBuf,
+}
+
+/// System contracts used at genesis. Contracts that are not specified are loaded from `ZKSYNC_HOME`,
+/// same as for the default genesis.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SystemContractsSpec {
+    /// Path to the bootloader bytecode.
+    pub bootloader: Option<PathBuf>,
+    /// Path to the default account bytecode.
+    pub default_aa: Option<PathBuf>,
+    /// Directory with compiled system contracts, with the same layout as `contracts/system-contracts` in the repo.
+    pub dir: Option<PathBuf>,
+}
+
+/// Contract deployed at genesis.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PredeployedContractSpec {
+    /// Contract address. Must be outside the kernel space (i.e., above `0xffff`).
+    pub address: Address,
+    /// Path to the contract bytecode: either a raw binary file (e.g., `.zbin`), or a JSON artifact
+    /// with the `bytecode` field as produced by `zksolc` or `zkvyper`.
+    pub bytecode: PathBuf,
+    /// Initial contract storage.
+    #[serde(default)]
+    pub storage: BTreeMap<H256, H256>,
+}
+
+impl GenesisSpec {
+    /// Reads the spec from the specified file. The format is inferred from the file extension.
+    pub fn from_file(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let format = ConfigFileFormat::from_path(path)?;
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("cannot read genesis spec {path:?}"))?;
+        let base_dir = path.parent().unwrap_or(Path::new("."));
+        Self::parse(&contents, format, base_dir)
+            .with_context(|| format!("invalid genesis spec {path:?}"))
+    }
+
+    /// Parses the spec in the specified format. Relative paths in the spec are resolved relative to `base_dir`.
+    pub fn parse(
+        contents: &str,
+        format: ConfigFileFormat,
+        base_dir: &Path,
+    ) -> anyhow::Result<Self> {
+        let mut spec: Self = format.deserialize(contents)?;
+        spec.base_dir = base_dir.to_owned();
+        spec.validate()?;
+        Ok(spec)
+    }
+
+    fn validate(&self) -> anyhow::Result<()> {
+        self.protocol_version_id()?;
+
+        let mut addresses = HashSet::with_capacity(self.predeployed_contracts.len());
+        for contract in &self.predeployed_contracts {
+            let address = contract.address;
+            anyhow::ensure!(
+                address_to_u256(&address) > U256::from(MAX_KERNEL_SPACE_ADDRESS),
+                "predeployed contract address {address:?} is in the kernel space reserved for system contracts"
+            );
+            anyhow::ensure!(
+                addresses.insert(address),
+                "contract at {address:?} is predeployed more than once"
+            );
+        }
+        self.total_eth_supply()?;
+        Ok(())
+    }
+
+    fn protocol_version_id(&self) -> anyhow::Result<ProtocolVersionId> {
+        let Some(version) = self.protocol_version else {
+            return Ok(ProtocolVersionId::latest());
+        };
+        ProtocolVersionId::try_from(version)
+            .map_err(|_| anyhow::anyhow!("unsupported protocol version: {version}"))
+    }
+
+    fn total_eth_supply(&self) -> anyhow::Result<U256> {
+        self.balances
+            .values()
+            .try_fold(U256::zero(), |acc, &balance| {
+                acc.checked_add(balance)
+                    .context("total ETH supply at genesis overflows U256")
+            })
+    }
+
+    fn resolve_path(&self, path: &Path) -> PathBuf {
+        self.base_dir.join(path)
+    }
+
+    /// Reads and validates bytecode from a raw binary file or from a JSON compiler artifact.
+    fn read_bytecode(&self, path: &Path) -> anyhow::Result<Vec<u8>> {
+        let path = self.resolve_path(path);
+        let contents = fs::read(&path).with_context(|| format!("cannot read bytecode {path:?}"))?;
+        let bytecode = if path.extension().and_then(|ext| ext.to_str()) == Some("json") {
+            let artifact: serde_json::Value = serde_json::from_slice(&contents)
+                .with_context(|| format!("invalid JSON artifact {path:?}"))?;
+            let bytecode = artifact["bytecode"]
+                .as_str()
+                .with_context(|| format!("JSON artifact {path:?} has no `bytecode` field"))?;
+            hex::decode(bytecode.strip_prefix("0x").unwrap_or(bytecode))
+                .with_context(|| format!("invalid hex bytecode in {path:?}"))?
+        } else {
+            contents
+        };
+        validate_bytecode(&bytecode).with_context(|| format!("invalid bytecode {path:?}"))?;
+        Ok(bytecode)
+    }
+
+    fn read_system_contract_code(&self, path: &Path) -> anyhow::Result<SystemContractCode> {
+        let bytecode = self.read_bytecode(path)?;
+        Ok(SystemContractCode {
+            hash: hash_bytecode(&bytecode),
+            code: bytes_to_be_words(bytecode),
+        })
+    }
+
+    fn base_system_contracts(&self) -> anyhow::Result<BaseSystemContracts> {
+        let bootloader = self
+            .system_contracts
+            .bootloader
+            .as_deref()
+            .map(|path| self.read_system_contract_code(path))
+            .transpose()
+            .context("failed loading bootloader")?;
+        let default_aa = self
+            .system_contracts
+            .default_aa
+            .as_deref()
+            .map(|path| self.read_system_contract_code(path))
+            .transpose()
+            .context("failed loading default account")?;
+
+        let (bootloader, default_aa) = match (bootloader, default_aa) {
+            (Some(bootloader), Some(default_aa)) => (bootloader, default_aa),
+            (bootloader, default_aa) => {
+                let defaults = BaseSystemContracts::load_from_disk();
+                (
+                    bootloader.unwrap_or(defaults.bootloader),
+                    default_aa.unwrap_or(defaults.default_aa),
+                )
+            }
+        };
+        Ok(BaseSystemContracts {
+            bootloader,
+            default_aa,
+        })
+    }
+
+    fn custom_state(&self) -> anyhow::Result<CustomGenesisState> {
+        let predeployed_contracts = self
+            .predeployed_contracts
+            .iter()
+            .map(|contract| {
+                let bytecode = self.read_bytecode(&contract.bytecode).with_context(|| {
+                    format!(
+                        "failed loading contract predeployed at {:?}",
+                        contract.address
+                    )
+                })?;
+                Ok(DeployedContract::new(
+                    AccountTreeId::new(contract.address),
+                    bytecode,
+                ))
+            })
+            .collect::<anyhow::Result<_>>()?;
+
+        let contract_storage_logs = self.predeployed_contracts.iter().flat_map(|contract| {
+            let account = AccountTreeId::new(contract.address);
+            contract.storage.iter().map(move |(&slot, &value)| {
+                StorageLog::new_write_log(StorageKey::new(account, slot), value)
+            })
+        });
+        let balance_logs = self.balances.iter().map(|(address, &balance)| {
+            StorageLog::new_write_log(storage_key_for_eth_balance(address), u256_to_h256(balance))
+        });
+        let total_supply = self.total_eth_supply()?;
+        let total_supply_log = (!total_supply.is_zero()).then(|| {
+            let key = StorageKey::new(
+                AccountTreeId::new(L2_ETH_TOKEN_ADDRESS),
+                H256::from_low_u64_be(L2_ETH_TOKEN_TOTAL_SUPPLY_SLOT),
+            );
+            StorageLog::new_write_log(key, u256_to_h256(total_supply))
+        });
+        let storage_logs = contract_storage_logs
+            .chain(balance_logs)
+            .chain(total_supply_log)
+            .collect();
+
+        Ok(CustomGenesisState {
+            predeployed_contracts,
+            storage_logs,
+            fee_params: self.fee_params,
+        })
+    }
+
+    /// Creates genesis params according to this spec. `operator_address` is used as the fee account
+    /// if it's not specified in the spec.
+    pub fn to_genesis_params(
+        &self,
+        operator_address: Address,
+        first_verifier_address: Address,
+        first_l1_verifier_config: L1VerifierConfig,
+    ) -> anyhow::Result<GenesisParams> {
+        let system_contracts = match &self.system_contracts.dir {
+            Some(dir) => get_system_smart_contracts_from_dir(self.resolve_path(dir)),
+            None => get_system_smart_contracts(),
+        };
+        Ok(GenesisParams {
+            first_validator: self.fee_account.unwrap_or(operator_address),
+            protocol_version: self.protocol_version_id()?,
+            base_system_contracts: self.base_system_contracts()?,
+            system_contracts,
+            first_verifier_address,
+            first_l1_verifier_config,
+            custom_state: self.custom_state()?,
+        })
+    }
+}
+
+fn address_to_u256(address: &Address) -> U256 {
+    U256::from_big_endian(address.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SPEC: &str = r#"
+        chain_id: 777
+        protocol_version: 20
+        fee_account: "0x0000000000000000000000000000000000000123"
+        balances:
+          "0x36615cf349d7f6344891b1e7ca7c72883f5dc049": "0x100"
+          "0xa61464658afeaf65cccaafd3a512b69a83b77618": "0x200"
+        fee_params:
+          l1_gas_price: 1000000000
+          fair_l2_gas_price: 250000000
+    "#;
+
+    #[test]
+    fn parsing_genesis_spec() {
+        let spec = GenesisSpec::parse(SPEC, ConfigFileFormat::Yaml, Path::new("/spec")).unwrap();
+        assert_eq!(spec.chain_id, L2ChainId::from(777));
+        assert_eq!(
+            spec.protocol_version_id().unwrap(),
+            ProtocolVersionId::Version20
+        );
+        assert_eq!(spec.fee_account, Some(Address::from_low_u64_be(0x123)));
+        assert_eq!(spec.total_eth_supply().unwrap(), U256::from(0x300));
+        assert_eq!(spec.fee_params.l1_gas_price, 1_000_000_000);
+        assert_eq!(
+            spec.resolve_path(Path::new("contracts/Token.json")),
+            Path::new("/spec/contracts/Token.json")
+        );
+
+        let state = spec.custom_state().unwrap();
+        assert!(state.predeployed_contracts.is_empty());
+        // 2 balances + total supply
+        assert_eq!(state.storage_logs.len(), 3);
+        let total_supply_log = state.storage_logs.last().unwrap();
+        assert_eq!(*total_supply_log.key.address(), L2_ETH_TOKEN_ADDRESS);
+        assert_eq!(total_supply_log.value, H256::from_low_u64_be(0x300));
+    }
+
+    #[test]
+    fn invalid_genesis_specs() {
+        let unknown_field = "chain_id: 270\nunknown: 1";
+        GenesisSpec::parse(unknown_field, ConfigFileFormat::Yaml, Path::new(".")).unwrap_err();
+
+        let bogus_version = "chain_id: 270\nprotocol_version: 1000";
+        let err =
+            GenesisSpec::parse(bogus_version, ConfigFileFormat::Yaml, Path::new(".")).unwrap_err();
+        assert!(err.to_string().contains("protocol version"), "{err}");
+
+        let kernel_space_contract = r#"
+            chain_id = 270
+            [[predeployed_contracts]]
+            address = "0x0000000000000000000000000000000000008001"
+            bytecode = "Token.zbin"
+        "#;
+        let err = GenesisSpec::parse(
+            kernel_space_contract,
+            ConfigFileFormat::Toml,
+            Path::new("."),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("kernel space"), "{err}");
+    }
+
+    #[test]
+    fn loading_predeployed_contract() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let bytecode = vec![1_u8; 32];
+        let artifact = serde_json::json!({ "bytecode": format!("0x{}", hex::encode(&bytecode)) });
+        fs::write(dir.path().join("Token.json"), artifact.to_string()).unwrap();
+
+        let spec = r#"
+            chain_id = 270
+            [[predeployed_contracts]]
+            address = "0x0000000000000000000000000000000000010000"
+            bytecode = "Token.json"
+            [predeployed_contracts.storage]
+            "0x0000000000000000000000000000000000000000000000000000000000000001" = "0x0000000000000000000000000000000000000000000000000000000000000002"
+        "#;
+        let spec = GenesisSpec::parse(spec, ConfigFileFormat::Toml, dir.path()).unwrap();
+        let state = spec.custom_state().unwrap();
+        assert_eq!(state.predeployed_contracts.len(), 1);
+        assert_eq!(state.predeployed_contracts[0].bytecode, bytecode);
+        assert_eq!(state.storage_logs.len(), 1);
+        assert_eq!(state.storage_logs[0].value, H256::from_low_u64_be(2));
+    }
+}
@@ -1,5 +1,6 @@
 use std::time::Duration;
 
+use anyhow::Context as _;
 use async_trait::async_trait;
 use zksync_dal::ConnectionPool;
 
@@ -10,6 +11,7 @@ pub struct FriProverJobRetryManager {
     pool: ConnectionPool,
     max_attempts: u32,
     processing_timeout: Duration,
+    heartbeat_timeout: Duration,
     retry_interval_ms: u64,
 }
 
@@ -17,29 +19,43 @@ impl FriProverJobRetryManager {
     pub fn new(
         max_attempts: u32,
         processing_timeout: Duration,
+        heartbeat_timeout: Duration,
         retry_interval_ms: u64,
         pool: ConnectionPool,
     ) -> Self {
         Self {
             max_attempts,
             processing_timeout,
+            heartbeat_timeout,
             retry_interval_ms,
             pool,
         }
     }
 }
 
-/// Invoked periodically to re-queue stuck fri prover jobs.
+/// Invoked periodically to re-queue stuck fri prover jobs, re-queue jobs abandoned by their workers
+/// (i.e., ones without recent heartbeats), and quarantine jobs that have exhausted their attempts.
 #[async_trait]
 impl PeriodicJob for FriProverJobRetryManager {
     const SERVICE_NAME: &'static str = "FriProverJobRetryManager";
 
     async fn run_routine_task(&mut self) -> anyhow::Result<()> {
-        let stuck_jobs = self
-            .pool
-            .access_storage()
+        let mut storage = self.pool.access_storage().await.unwrap();
+        let abandoned_jobs = storage
+            .fri_prover_jobs_dal()
+            .requeue_abandoned_jobs(self.heartbeat_timeout, self.max_attempts)
             .await
-            .unwrap()
+            .context("requeue_abandoned_jobs()")?;
+        let job_len = abandoned_jobs.len();
+        for abandoned_job in abandoned_jobs {
+            tracing::info!(
+                "re-queuing fri prover job {abandoned_job:?} without heartbeats for {:?}",
+                self.heartbeat_timeout
+            );
+        }
+        metrics::counter!("server.prover_fri.abandoned_jobs", job_len as u64);
+
+        let stuck_jobs = storage
             .fri_prover_jobs_dal()
             .requeue_stuck_jobs(self.processing_timeout, self.max_attempts)
             .await;
@@ -48,6 +64,24 @@ impl PeriodicJob for FriProverJobRetryManager {
             tracing::info!("re-queuing fri prover job {:?}", stuck_job);
         }
         metrics::counter!("server.prover_fri.requeued_jobs", job_len as u64);
+
+        let poison_jobs = storage
+            .fri_prover_jobs_dal()
+            .quarantine_poison_jobs(
+                self.processing_timeout,
+                self.heartbeat_timeout,
+                self.max_attempts,
+            )
+            .await
+            .context("quarantine_poison_jobs()")?;
+        let job_len = poison_jobs.len();
+        for poison_job in poison_jobs {
+            tracing::error!(
+                "quarantining fri prover job {poison_job:?} that has exhausted {} attempts",
+                self.max_attempts
+            );
+        }
+        metrics::counter!("server.prover_fri.quarantined_jobs", job_len as u64);
         Ok(())
     }
 
@@ -7,4 +7,5 @@ pub mod fri_scheduler_circuit_queuer;
 pub mod fri_witness_generator_jobs_retry_manager;
 pub mod fri_witness_generator_queue_monitor;
 pub mod periodic_job;
+pub mod prover_artifacts_gc;
 pub mod waiting_to_queued_fri_witness_job_mover;
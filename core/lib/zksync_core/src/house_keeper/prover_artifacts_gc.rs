@@ -0,0 +1,298 @@
+use std::{collections::HashSet, sync::Arc, time::Duration};
+
+use anyhow::Context as _;
+use async_trait::async_trait;
+use futures::{stream, StreamExt};
+use vise::{Counter, Gauge, LabeledFamily, Metrics};
+use zksync_dal::{
+    prover_artifacts_dal::{ProverArtifact, ProverArtifactKind},
+    ConnectionPool,
+};
+use zksync_object_store::{Bucket, ObjectStore, ObjectStoreError};
+use zksync_types::L1BatchNumber;
+
+use crate::house_keeper::periodic_job::PeriodicJob;
+
+/// Maximum number of L1 batches processed in a single garbage collection run.
+const MAX_L1_BATCHES_PER_RUN: u32 = 10;
+/// Maximum number of concurrent removal requests to the object store.
+const MAX_CONCURRENT_REMOVALS: usize = 16;
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "house_keeper_prover_artifacts_gc")]
+struct ProverArtifactsGcMetrics {
+    /// Number of removed prover artifacts.
+    #[metrics(labels = ["kind"])]
+    removed_artifacts: LabeledFamily<&'static str, Counter>,
+    /// Number of prover artifacts that would be removed in the dry-run mode.
+    #[metrics(labels = ["kind"])]
+    dry_run_artifacts: LabeledFamily<&'static str, Counter>,
+    /// Number of failed artifact removals.
+    #[metrics(labels = ["kind"])]
+    failed_removals: LabeledFamily<&'static str, Counter>,
+    /// Last L1 batch for which prover artifacts were garbage-collected.
+    last_cleaned_l1_batch: Gauge<u64>,
+}
+
+#[vise::register]
+static METRICS: vise::Global<ProverArtifactsGcMetrics> = vise::Global::new();
+
+fn bucket_for(kind: ProverArtifactKind) -> Bucket {
+    match kind {
+        ProverArtifactKind::MerklePaths | ProverArtifactKind::WitnessBlockState => {
+            Bucket::WitnessInput
+        }
+        ProverArtifactKind::CircuitInputs => Bucket::ProverJobsFri,
+        ProverArtifactKind::CircuitProof | ProverArtifactKind::FinalProof => Bucket::ProofsFri,
+        ProverArtifactKind::LeafAggregationInputs => Bucket::LeafAggregationWitnessJobsFri,
+        ProverArtifactKind::NodeAggregationInputs => Bucket::NodeAggregationWitnessJobsFri,
+        ProverArtifactKind::SchedulerInputs => Bucket::SchedulerWitnessJobsFri,
+    }
+}
+
+/// Removes prover artifacts (witness inputs, aggregation artifacts and proofs) from the object store
+/// for L1 batches executed on L1 longer ago than the configured retention period. Removed L1 batches
+/// are recorded in Postgres, so that garbage collection resumes from the same place after a restart.
+///
+/// In the dry-run mode, artifacts are only logged and reported in metrics; progress is kept in memory.
+#[derive(Debug)]
+pub struct ProverArtifactsGarbageCollector {
+    retention_period: Duration,
+    gc_interval_ms: u64,
+    dry_run: bool,
+    pool: ConnectionPool,
+    prover_pool: ConnectionPool,
+    blob_store: Arc<dyn ObjectStore>,
+    dry_run_next_l1_batch: Option<L1BatchNumber>,
+}
+
+impl ProverArtifactsGarbageCollector {
+    pub fn new(
+        retention_period: Duration,
+        gc_interval_ms: u64,
+        dry_run: bool,
+        pool: ConnectionPool,
+        prover_pool: ConnectionPool,
+        blob_store: Arc<dyn ObjectStore>,
+    ) -> Self {
+        Self {
+            retention_period,
+            gc_interval_ms,
+            dry_run,
+            pool,
+            prover_pool,
+            blob_store,
+            dry_run_next_l1_batch: None,
+        }
+    }
+
+    async fn l1_batches_to_clean(&self) -> anyhow::Result<Option<(L1BatchNumber, L1BatchNumber)>> {
+        let retention_period = chrono::Duration::from_std(self.retention_period)
+            .context("retention period is too large")?;
+        let executed_before = chrono::Utc::now().naive_utc() - retention_period;
+        let mut conn = self.pool.access_storage().await?;
+        let Some(last_eligible_l1_batch) = conn
+            .blocks_dal()
+            .get_number_of_last_l1_batch_executed_before(executed_before)
+            .await?
+        else {
+            return Ok(None);
+        };
+        drop(conn);
+
+        let next_l1_batch = if let Some(number) = self.dry_run_next_l1_batch {
+            Some(number)
+        } else {
+            let mut prover_conn = self.prover_pool.access_storage().await?;
+            prover_conn
+                .prover_artifacts_dal()
+                .get_next_l1_batch_to_clean()
+                .await?
+        };
+        let Some(first_l1_batch) = next_l1_batch else {
+            return Ok(None);
+        };
+        if first_l1_batch > last_eligible_l1_batch {
+            return Ok(None);
+        }
+        let last_l1_batch =
+            last_eligible_l1_batch.min(first_l1_batch + (MAX_L1_BATCHES_PER_RUN - 1));
+        Ok(Some((first_l1_batch, last_l1_batch)))
+    }
+
+    async fn collect_artifacts(
+        &self,
+        first_l1_batch: L1BatchNumber,
+        last_l1_batch: L1BatchNumber,
+    ) -> anyhow::Result<Vec<ProverArtifact>> {
+        let l1_batches = first_l1_batch..=last_l1_batch;
+        let mut prover_conn = self.prover_pool.access_storage().await?;
+        let mut artifacts = prover_conn
+            .prover_artifacts_dal()
+            .get_fri_artifacts(l1_batches.clone())
+            .await?;
+        drop(prover_conn);
+
+        let mut conn = self.pool.access_storage().await?;
+        artifacts.extend(
+            conn.prover_artifacts_dal()
+                .get_server_artifacts(l1_batches)
+                .await?,
+        );
+
+        // Aux output witnesses aren't referenced in Postgres; their keys are defined
+        // by `AuxOutputWitnessWrapper` in `zksync_prover_fri_types`.
+        artifacts.extend(
+            (first_l1_batch.0..=last_l1_batch.0).map(|number| ProverArtifact {
+                l1_batch_number: L1BatchNumber(number),
+                kind: ProverArtifactKind::SchedulerInputs,
+                key: format!("aux_output_witness_{number}.bin"),
+            }),
+        );
+
+        // Some artifacts are referenced from multiple tables (e.g., final proofs).
+        let mut seen_keys = HashSet::new();
+        artifacts
+            .retain(|artifact| seen_keys.insert((bucket_for(artifact.kind), artifact.key.clone())));
+        Ok(artifacts)
+    }
+
+    /// Removes the provided artifacts. Returns the number of removed artifacts, or `None` if some
+    /// removals have failed.
+    async fn remove_artifacts(&self, artifacts: &[ProverArtifact]) -> Option<u64> {
+        let results = stream::iter(artifacts)
+            .map(|artifact| async move {
+                let result = self
+                    .blob_store
+                    .remove_raw(bucket_for(artifact.kind), &artifact.key)
+                    .await;
+                (artifact, result)
+            })
+            .buffer_unordered(MAX_CONCURRENT_REMOVALS)
+            .collect::<Vec<_>>()
+            .await;
+
+        let mut has_failures = false;
+        let mut removed_count = 0;
+        for (artifact, result) in results {
+            match result {
+                // The artifact may have been removed by a previous run that has failed midway.
+                Ok(()) | Err(ObjectStoreError::KeyNotFound(_)) => {
+                    METRICS.removed_artifacts[&artifact.kind.as_str()].inc();
+                    removed_count += 1;
+                }
+                Err(err) => {
+                    tracing::warn!(
+                        "Failed removing {:?} artifact `{}` for L1 batch #{}: {err}",
+                        artifact.kind,
+                        artifact.key,
+                        artifact.l1_batch_number
+                    );
+                    METRICS.failed_removals[&artifact.kind.as_str()].inc();
+                    has_failures = true;
+                }
+            }
+        }
+        (!has_failures).then_some(removed_count)
+    }
+}
+
+#[async_trait]
+impl PeriodicJob for ProverArtifactsGarbageCollector {
+    const SERVICE_NAME: &'static str = "ProverArtifactsGarbageCollector";
+
+    async fn run_routine_task(&mut self) -> anyhow::Result<()> {
+        let Some((first_l1_batch, last_l1_batch)) = self.l1_batches_to_clean().await? else {
+            return Ok(());
+        };
+        let artifacts = self
+            .collect_artifacts(first_l1_batch, last_l1_batch)
+            .await?;
+
+        if self.dry_run {
+            for artifact in &artifacts {
+                tracing::debug!(
+                    "Dry run: would remove {:?} artifact `{}` for L1 batch #{}",
+                    artifact.kind,
+                    artifact.key,
+                    artifact.l1_batch_number
+                );
+                METRICS.dry_run_artifacts[&artifact.kind.as_str()].inc();
+            }
+            tracing::info!(
+                "Dry run: would remove {} prover artifacts for L1 batches #{first_l1_batch}..=#{last_l1_batch}",
+                artifacts.len()
+            );
+            self.dry_run_next_l1_batch = Some(last_l1_batch + 1);
+            return Ok(());
+        }
+
+        let Some(removed_count) = self.remove_artifacts(&artifacts).await else {
+            tracing::warn!(
+                "Failed removing some prover artifacts for L1 batches #{first_l1_batch}..=#{last_l1_batch}; \
+                 will retry on the next run"
+            );
+            return Ok(());
+        };
+        let mut prover_conn = self.prover_pool.access_storage().await?;
+        prover_conn
+            .prover_artifacts_dal()
+            .save_cleaned_l1_batch(last_l1_batch, removed_count)
+            .await?;
+        tracing::info!(
+            "Removed {removed_count} prover artifacts for L1 batches #{first_l1_batch}..=#{last_l1_batch}"
+        );
+        METRICS.last_cleaned_l1_batch.set(last_l1_batch.0.into());
+        Ok(())
+    }
+
+    fn polling_interval_ms(&self) -> u64 {
+        self.gc_interval_ms
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_object_store::ObjectStoreFactory;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn removing_artifacts() {
+        let blob_store = ObjectStoreFactory::mock().create_store().await;
+        blob_store
+            .put_raw(Bucket::ProverJobsFri, "circuit.bin", vec![1, 2, 3])
+            .await
+            .unwrap();
+        let pool = ConnectionPool::test_pool().await;
+        let collector = ProverArtifactsGarbageCollector::new(
+            Duration::from_secs(3_600),
+            1_000,
+            false,
+            pool.clone(),
+            pool,
+            blob_store.clone(),
+        );
+
+        let artifacts = [
+            ProverArtifact {
+                l1_batch_number: L1BatchNumber(1),
+                kind: ProverArtifactKind::CircuitInputs,
+                key: "circuit.bin".to_owned(),
+            },
+            // Missing artifacts should be treated as removed.
+            ProverArtifact {
+                l1_batch_number: L1BatchNumber(1),
+                kind: ProverArtifactKind::FinalProof,
+                key: "missing.bin".to_owned(),
+            },
+        ];
+        let removed_count = collector.remove_artifacts(&artifacts).await;
+        assert_eq!(removed_count, Some(2));
+        let err = blob_store
+            .get_raw(Bucket::ProverJobsFri, "circuit.bin")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ObjectStoreError::KeyNotFound(_)), "{err}");
+    }
+}
@@ -0,0 +1,240 @@
+//! Estimators of the L1 base fee used by [`GasAdjuster`](super::GasAdjuster).
+
+use std::{
+    fmt,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use anyhow::Context as _;
+use async_trait::async_trait;
+use zksync_config::{configs::eth_sender::BaseFeeEstimationMode, GasAdjusterConfig};
+
+use super::metrics::METRICS;
+
+const EXTERNAL_API_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Estimates the L1 base fee based on the base fees of recent L1 blocks.
+#[async_trait]
+pub(super) trait BaseFeeEstimator: fmt::Debug + Send + Sync {
+    /// Updates the estimator after new L1 blocks are processed. `samples` are the base fees of blocks
+    /// in the current sample window, from the oldest to the newest one; the last `new_samples` of them
+    /// correspond to the newly processed blocks.
+    async fn update(&self, samples: &[u64], new_samples: usize);
+
+    /// Returns the current base fee estimate in wei.
+    fn estimate(&self) -> u64;
+}
+
+/// Creates an estimator according to the estimation mode specified in the config.
+pub(super) fn from_config(config: &GasAdjusterConfig) -> anyhow::Result<Box<dyn BaseFeeEstimator>> {
+    Ok(match config.base_fee_estimation_mode {
+        BaseFeeEstimationMode::Median => Box::new(PercentileEstimator::median()),
+        BaseFeeEstimationMode::Percentile => {
+            Box::new(PercentileEstimator::new(config.base_fee_percentile())?)
+        }
+        BaseFeeEstimationMode::Ewma => Box::new(EwmaEstimator::new(config.base_fee_ewma_alpha())?),
+        BaseFeeEstimationMode::ExternalApi => Box::new(ExternalApiEstimator::new(config)?),
+    })
+}
+
+/// Estimator returning a percentile of base fees in the sample window.
+#[derive(Debug)]
+pub(super) struct PercentileEstimator {
+    percentile: f64,
+    value: AtomicU64,
+}
+
+impl PercentileEstimator {
+    pub fn new(percentile: f64) -> anyhow::Result<Self> {
+        anyhow::ensure!(
+            (0.0..=100.0).contains(&percentile),
+            "base fee percentile must be in the 0..=100 range, got {percentile}"
+        );
+        Ok(Self {
+            percentile,
+            value: AtomicU64::new(0),
+        })
+    }
+
+    pub fn median() -> Self {
+        Self::new(50.0).unwrap()
+    }
+}
+
+#[async_trait]
+impl BaseFeeEstimator for PercentileEstimator {
+    async fn update(&self, samples: &[u64], _new_samples: usize) {
+        if samples.is_empty() {
+            return;
+        }
+        let mut samples = samples.to_vec();
+        // For the 50th percentile, this is consistent with the median used for other fee statistics.
+        let index = (samples.len() as f64 * self.percentile / 100.0) as usize;
+        let (_, &mut value, _) = samples.select_nth_unstable(index.min(samples.len() - 1));
+        self.value.store(value, Ordering::Relaxed);
+    }
+
+    fn estimate(&self) -> u64 {
+        self.value.load(Ordering::Relaxed)
+    }
+}
+
+/// Estimator returning an exponentially weighted moving average of base fees. Unlike percentiles,
+/// the average reacts to base fee spikes gradually, with the rate controlled by the smoothing factor.
+#[derive(Debug)]
+pub(super) struct EwmaEstimator {
+    alpha: f64,
+    value: Mutex<Option<f64>>,
+}
+
+impl EwmaEstimator {
+    pub fn new(alpha: f64) -> anyhow::Result<Self> {
+        anyhow::ensure!(
+            alpha > 0.0 && alpha <= 1.0,
+            "base fee EWMA smoothing factor must be in the (0, 1] range, got {alpha}"
+        );
+        Ok(Self {
+            alpha,
+            value: Mutex::new(None),
+        })
+    }
+}
+
+#[async_trait]
+impl BaseFeeEstimator for EwmaEstimator {
+    async fn update(&self, samples: &[u64], new_samples: usize) {
+        let new_samples = &samples[samples.len().saturating_sub(new_samples)..];
+        let mut value = self.value.lock().unwrap();
+        for &fee in new_samples {
+            let fee = fee as f64;
+            *value = Some(match *value {
+                Some(prev) => prev + self.alpha * (fee - prev),
+                None => fee,
+            });
+        }
+    }
+
+    fn estimate(&self) -> u64 {
+        self.value.lock().unwrap().map_or(0, |value| value as u64)
+    }
+}
+
+/// Estimator using the base fee reported by an external gas price API. The API is queried
+/// each time new L1 blocks are processed; if the latest reported value is too old, the estimator
+/// falls back to the median of base fees in the sample window.
+pub(super) struct ExternalApiEstimator {
+    client: reqwest::Client,
+    url: String,
+    json_pointer: String,
+    multiplier: f64,
+    max_age: Duration,
+    last_value: Mutex<Option<(u64, Instant)>>,
+    fallback: PercentileEstimator,
+}
+
+impl fmt::Debug for ExternalApiEstimator {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // The URL is not output since it may contain an API key.
+        formatter
+            .debug_struct("ExternalApiEstimator")
+            .field("json_pointer", &self.json_pointer)
+            .field("multiplier", &self.multiplier)
+            .field("max_age", &self.max_age)
+            .field("last_value", &self.last_value)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ExternalApiEstimator {
+    pub fn new(config: &GasAdjusterConfig) -> anyhow::Result<Self> {
+        let url = config
+            .external_price_api_url
+            .clone()
+            .context("`external_price_api_url` is required for the external API estimation mode")?;
+        reqwest::Url::parse(&url).context("invalid `external_price_api_url`")?;
+        let multiplier = config.external_price_api_multiplier();
+        anyhow::ensure!(
+            multiplier.is_finite() && multiplier > 0.0,
+            "`external_price_api_multiplier` must be positive, got {multiplier}"
+        );
+
+        Ok(Self {
+            client: reqwest::Client::builder()
+                .timeout(EXTERNAL_API_TIMEOUT)
+                .build()
+                .context("failed creating HTTP client")?,
+            url,
+            json_pointer: config.external_price_api_json_pointer().to_owned(),
+            multiplier,
+            max_age: config.external_price_api_max_age(),
+            last_value: Mutex::new(None),
+            fallback: PercentileEstimator::median(),
+        })
+    }
+
+    async fn fetch(&self) -> anyhow::Result<u64> {
+        let response = self
+            .client
+            .get(&self.url)
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .map_err(reqwest::Error::without_url)?;
+        let response: serde_json::Value =
+            response.json().await.map_err(reqwest::Error::without_url)?;
+        parse_base_fee(&response, &self.json_pointer, self.multiplier)
+    }
+}
+
+#[async_trait]
+impl BaseFeeEstimator for ExternalApiEstimator {
+    async fn update(&self, samples: &[u64], new_samples: usize) {
+        self.fallback.update(samples, new_samples).await;
+        match self.fetch().await {
+            Ok(base_fee) => {
+                METRICS.external_base_fee_per_gas.set(base_fee);
+                *self.last_value.lock().unwrap() = Some((base_fee, Instant::now()));
+            }
+            Err(err) => {
+                METRICS.external_api_errors.inc();
+                tracing::warn!("Failed getting base fee from the external gas price API: {err:#}");
+            }
+        }
+    }
+
+    fn estimate(&self) -> u64 {
+        let last_value = *self.last_value.lock().unwrap();
+        match last_value {
+            Some((base_fee, fetched_at)) if fetched_at.elapsed() <= self.max_age => base_fee,
+            _ => self.fallback.estimate(),
+        }
+    }
+}
+
+/// Extracts the base fee (in wei) from a gas price API response.
+pub(crate) fn parse_base_fee(
+    response: &serde_json::Value,
+    json_pointer: &str,
+    multiplier: f64,
+) -> anyhow::Result<u64> {
+    let value = response
+        .pointer(json_pointer)
+        .with_context(|| format!("no value at `{json_pointer}` in the API response"))?;
+    let base_fee = match value {
+        serde_json::Value::Number(number) => number.as_f64(),
+        serde_json::Value::String(s) => s.trim().parse::<f64>().ok(),
+        _ => None,
+    }
+    .with_context(|| format!("unexpected base fee in the API response: {value}"))?;
+
+    let base_fee = base_fee * multiplier;
+    anyhow::ensure!(
+        base_fee.is_finite() && base_fee >= 0.0,
+        "invalid base fee in the API response: {base_fee}"
+    );
+    Ok(base_fee as u64)
+}
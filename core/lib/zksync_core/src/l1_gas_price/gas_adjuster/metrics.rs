@@ -1,12 +1,20 @@
 //! Gas adjuster metrics.
 
-use vise::{Gauge, Metrics};
+use vise::{Counter, Gauge, Metrics};
 
 #[derive(Debug, Metrics)]
 #[metrics(prefix = "server_gas_adjuster")]
 pub(super) struct GasAdjusterMetrics {
     pub current_base_fee_per_gas: Gauge<u64>,
     pub median_base_fee_per_gas: Gauge<u64>,
+    /// Base fee estimate used for L1 transactions (before applying the pricing formula).
+    pub estimated_base_fee_per_gas: Gauge<u64>,
+    /// Latest base fee reported by the external gas price API.
+    pub external_base_fee_per_gas: Gauge<u64>,
+    /// Number of failed requests to the external gas price API.
+    pub external_api_errors: Counter,
+    pub current_blob_base_fee_per_gas: Gauge<u64>,
+    pub median_blob_base_fee_per_gas: Gauge<u64>,
 }
 
 #[vise::register]
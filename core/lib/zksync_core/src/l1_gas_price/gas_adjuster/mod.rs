@@ -5,24 +5,31 @@ use std::{
     sync::{Arc, RwLock},
 };
 
+use anyhow::Context as _;
 use tokio::sync::watch;
 use zksync_config::GasAdjusterConfig;
 use zksync_eth_client::{Error, EthInterface};
 use zksync_system_constants::L1_GAS_PER_PUBDATA_BYTE;
 
-use self::metrics::METRICS;
+pub(crate) use self::estimator::parse_base_fee;
+use self::{estimator::BaseFeeEstimator, metrics::METRICS};
 use super::L1TxParamsProvider;
 use crate::state_keeper::metrics::KEEPER_METRICS;
 
+mod estimator;
 mod metrics;
 #[cfg(test)]
 mod tests;
 
-/// This component keeps track of the median base_fee from the last `max_base_fee_samples` blocks.
-/// It is used to adjust the base_fee of transactions sent to L1.
+/// This component keeps track of the base_fee from the last `max_base_fee_samples` blocks.
+/// It is used to adjust the base_fee of transactions sent to L1. The base_fee estimate is computed
+/// by a [`BaseFeeEstimator`] selected in the config (by default, the median of collected samples).
 #[derive(Debug)]
 pub struct GasAdjuster {
     pub(super) statistics: GasStatistics,
+    base_fee_estimator: Box<dyn BaseFeeEstimator>,
+    /// Statistics of the blob base fee (EIP-4844), tracked over the same blocks as `statistics`.
+    pub(super) blob_base_fee_statistics: GasStatistics,
     pub(super) config: GasAdjusterConfig,
     eth_client: Arc<dyn EthInterface>,
 }
@@ -31,7 +38,9 @@ impl GasAdjuster {
     pub async fn new(
         eth_client: Arc<dyn EthInterface>,
         config: GasAdjusterConfig,
-    ) -> Result<Self, Error> {
+    ) -> anyhow::Result<Self> {
+        let base_fee_estimator =
+            estimator::from_config(&config).context("invalid base fee estimation config")?;
         // Subtracting 1 from the "latest" block number to prevent errors in case
         // the info about the latest block is not yet present on the node.
         // This sometimes happens on Infura.
@@ -43,8 +52,22 @@ impl GasAdjuster {
         let history = eth_client
             .base_fee_history(current_block, config.max_base_fee_samples, "gas_adjuster")
             .await?;
+        let blob_history = eth_client
+            .blob_base_fee_history(current_block, config.max_base_fee_samples, "gas_adjuster")
+            .await?;
+        let statistics = GasStatistics::new(config.max_base_fee_samples, current_block, &history);
+        base_fee_estimator
+            .update(&statistics.samples(), history.len())
+            .await;
+
         Ok(Self {
-            statistics: GasStatistics::new(config.max_base_fee_samples, current_block, &history),
+            statistics,
+            base_fee_estimator,
+            blob_base_fee_statistics: GasStatistics::new(
+                config.max_base_fee_samples,
+                current_block,
+                &blob_history,
+            ),
             eth_client,
             config,
         })
@@ -80,6 +103,24 @@ impl GasAdjuster {
                 .current_base_fee_per_gas
                 .set(*history.last().unwrap());
             self.statistics.add_samples(&history);
+            self.base_fee_estimator
+                .update(&self.statistics.samples(), history.len())
+                .await;
+
+            let blob_history = self
+                .eth_client
+                .blob_base_fee_history(
+                    current_block,
+                    current_block - last_processed_block,
+                    "gas_adjuster",
+                )
+                .await?;
+            if let Some(&current_blob_base_fee) = blob_history.last() {
+                METRICS
+                    .current_blob_base_fee_per_gas
+                    .set(current_blob_base_fee);
+            }
+            self.blob_base_fee_statistics.add_samples(&blob_history);
         }
         Ok(())
     }
@@ -150,12 +191,22 @@ impl L1TxParamsProvider for GasAdjuster {
         // The alternative is a linear one:
         // `let scale_factor = a + b * time_in_mempool as f64;`
         let scale_factor = a * b.powf(time_in_mempool as f64);
-        let median = self.statistics.median();
-        METRICS.median_base_fee_per_gas.set(median);
-        let new_fee = median as f64 * scale_factor;
+        METRICS
+            .median_base_fee_per_gas
+            .set(self.statistics.median());
+        let base_fee = self.base_fee_estimator.estimate();
+        METRICS.estimated_base_fee_per_gas.set(base_fee);
+        let new_fee = base_fee as f64 * scale_factor;
         new_fee as u64
     }
 
+    fn get_blob_base_fee(&self) -> u64 {
+        let median = self.blob_base_fee_statistics.median();
+        METRICS.median_blob_base_fee_per_gas.set(median);
+        // Blob base fee is 1 wei at minimum; it can be reported as 0 by nodes without EIP-4844 support.
+        median.max(1)
+    }
+
     fn get_next_block_minimal_base_fee(&self) -> u64 {
         let last_block_base_fee = self.statistics.last_added_value();
 
@@ -244,6 +295,10 @@ impl GasStatistics {
         self.0.read().unwrap().last_added_value()
     }
 
+    pub fn samples(&self) -> Vec<u64> {
+        self.0.read().unwrap().samples.iter().copied().collect()
+    }
+
     pub fn add_samples(&self, fees: &[u64]) {
         self.0.write().unwrap().add_samples(fees)
     }
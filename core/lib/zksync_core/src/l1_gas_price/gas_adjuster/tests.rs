@@ -1,9 +1,33 @@
 use std::{collections::VecDeque, sync::Arc};
 
-use zksync_config::GasAdjusterConfig;
+use zksync_config::{configs::eth_sender::BaseFeeEstimationMode, GasAdjusterConfig};
 use zksync_eth_client::clients::MockEthereum;
 
-use super::{GasAdjuster, GasStatisticsInner};
+use super::{
+    estimator::{parse_base_fee, BaseFeeEstimator, EwmaEstimator, PercentileEstimator},
+    GasAdjuster, GasStatisticsInner,
+};
+use crate::l1_gas_price::L1TxParamsProvider;
+
+fn test_config(base_fee_estimation_mode: BaseFeeEstimationMode) -> GasAdjusterConfig {
+    GasAdjusterConfig {
+        default_priority_fee_per_gas: 5,
+        max_base_fee_samples: 5,
+        pricing_formula_parameter_a: 1.5,
+        pricing_formula_parameter_b: 1.0005,
+        internal_l1_pricing_multiplier: 0.8,
+        internal_enforced_l1_gas_price: None,
+        poll_period: 5,
+        max_l1_gas_price: None,
+        base_fee_estimation_mode,
+        base_fee_percentile: None,
+        base_fee_ewma_alpha: None,
+        external_price_api_url: None,
+        external_price_api_json_pointer: None,
+        external_price_api_multiplier: None,
+        external_price_api_max_age_sec: None,
+    }
+}
 
 /// Check that we compute the median correctly
 #[test]
@@ -29,32 +53,104 @@ fn samples_queue() {
 /// Check that we properly fetch base fees as block are mined
 #[tokio::test]
 async fn kept_updated() {
-    let eth_client =
-        Arc::new(MockEthereum::default().with_fee_history(vec![0, 4, 6, 8, 7, 5, 5, 8, 10, 9]));
+    let eth_client = Arc::new(
+        MockEthereum::default()
+            .with_fee_history(vec![0, 4, 6, 8, 7, 5, 5, 8, 10, 9])
+            .with_blob_fee_history(vec![0, 2, 3, 4, 3, 9, 9, 9, 10, 11]),
+    );
     eth_client.advance_block_number(5);
 
     let adjuster = GasAdjuster::new(
         eth_client.clone(),
-        GasAdjusterConfig {
-            default_priority_fee_per_gas: 5,
-            max_base_fee_samples: 5,
-            pricing_formula_parameter_a: 1.5,
-            pricing_formula_parameter_b: 1.0005,
-            internal_l1_pricing_multiplier: 0.8,
-            internal_enforced_l1_gas_price: None,
-            poll_period: 5,
-            max_l1_gas_price: None,
-        },
+        test_config(BaseFeeEstimationMode::Median),
     )
     .await
     .unwrap();
 
     assert_eq!(adjuster.statistics.0.read().unwrap().samples.len(), 5);
     assert_eq!(adjuster.statistics.0.read().unwrap().median(), 6);
+    assert_eq!(adjuster.blob_base_fee_statistics.median(), 3);
 
     eth_client.advance_block_number(3);
     adjuster.keep_updated().await.unwrap();
 
     assert_eq!(adjuster.statistics.0.read().unwrap().samples.len(), 5);
     assert_eq!(adjuster.statistics.0.read().unwrap().median(), 7);
+    assert_eq!(adjuster.blob_base_fee_statistics.median(), 9);
+}
+
+#[tokio::test]
+async fn percentile_estimator() {
+    let estimator = PercentileEstimator::median();
+    estimator.update(&[6, 4, 7, 8, 4], 5).await;
+    assert_eq!(estimator.estimate(), 6);
+
+    let estimator = PercentileEstimator::new(80.0).unwrap();
+    estimator.update(&[6, 4, 7, 8, 4], 5).await;
+    assert_eq!(estimator.estimate(), 8);
+    let estimator = PercentileEstimator::new(0.0).unwrap();
+    estimator.update(&[6, 4, 7, 8, 4], 5).await;
+    assert_eq!(estimator.estimate(), 4);
+
+    PercentileEstimator::new(101.0).unwrap_err();
+}
+
+#[tokio::test]
+async fn ewma_estimator() {
+    let estimator = EwmaEstimator::new(0.5).unwrap();
+    estimator.update(&[10, 20], 2).await;
+    assert_eq!(estimator.estimate(), 15);
+    // Only new samples should be taken into account.
+    estimator.update(&[10, 20, 35], 1).await;
+    assert_eq!(estimator.estimate(), 25);
+    // The number of new samples may exceed the window size.
+    estimator.update(&[100], 3).await;
+    assert_eq!(estimator.estimate(), 62);
+
+    EwmaEstimator::new(0.0).unwrap_err();
+}
+
+#[test]
+fn parsing_base_fee_from_api_response() {
+    let response = serde_json::json!({
+        "result": { "suggestBaseFee": "12.5", "fastGasPrice": 20 },
+    });
+    let base_fee = parse_base_fee(&response, "/result/suggestBaseFee", 1e9).unwrap();
+    assert_eq!(base_fee, 12_500_000_000);
+    let base_fee = parse_base_fee(&response, "/result/fastGasPrice", 1.0).unwrap();
+    assert_eq!(base_fee, 20);
+    assert_eq!(parse_base_fee(&serde_json::json!(42), "", 1.0).unwrap(), 42);
+
+    parse_base_fee(&response, "/result/missing", 1.0).unwrap_err();
+    parse_base_fee(&response, "/result", 1.0).unwrap_err();
+    parse_base_fee(&serde_json::json!(-1), "", 1.0).unwrap_err();
+}
+
+/// Check that the configured estimator is used for base fees.
+#[tokio::test]
+async fn base_fee_estimation_modes() {
+    let eth_client =
+        Arc::new(MockEthereum::default().with_fee_history(vec![0, 4, 6, 8, 7, 5, 5, 8, 10, 9]));
+    eth_client.advance_block_number(5);
+
+    let mut config = test_config(BaseFeeEstimationMode::Percentile);
+    config.base_fee_percentile = Some(100.0);
+    config.pricing_formula_parameter_a = 1.0;
+    let adjuster = GasAdjuster::new(eth_client.clone(), config).await.unwrap();
+    // Samples: 0 4 6 8 7
+    assert_eq!(adjuster.get_base_fee(0), 8);
+
+    let mut config = test_config(BaseFeeEstimationMode::Ewma);
+    config.base_fee_ewma_alpha = Some(1.0);
+    config.pricing_formula_parameter_a = 1.0;
+    let adjuster = GasAdjuster::new(eth_client.clone(), config).await.unwrap();
+    assert_eq!(adjuster.get_base_fee(0), 7);
+
+    eth_client.advance_block_number(3);
+    adjuster.keep_updated().await.unwrap();
+    // New samples: 5 5 8
+    assert_eq!(adjuster.get_base_fee(0), 8);
+
+    let config = test_config(BaseFeeEstimationMode::ExternalApi);
+    GasAdjuster::new(eth_client, config).await.unwrap_err();
 }
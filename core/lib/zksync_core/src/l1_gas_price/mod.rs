@@ -2,6 +2,7 @@
 
 use std::fmt;
 
+pub(crate) use gas_adjuster::parse_base_fee;
 pub use gas_adjuster::GasAdjuster;
 pub use main_node_fetcher::MainNodeFeeParamsFetcher;
 pub use singleton::GasAdjusterSingleton;
@@ -21,6 +22,9 @@ pub trait L1TxParamsProvider: fmt::Debug + 'static + Send + Sync {
     /// Returns the recommended `max_priority_fee_per_gas` value (EIP1559).
     fn get_priority_fee(&self) -> u64;
 
+    /// Returns the recommended `max_fee_per_blob_gas` value (EIP4844).
+    fn get_blob_base_fee(&self) -> u64;
+
     /// Returns a lower bound for the `base_fee` value for the next L1 block.
     fn get_next_block_minimal_base_fee(&self) -> u64;
 }
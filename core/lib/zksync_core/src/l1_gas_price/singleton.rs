@@ -14,7 +14,7 @@ use crate::l1_gas_price::GasAdjuster;
 /// This is needed only for running the server.
 #[derive(Debug)]
 pub struct GasAdjusterSingleton {
-    web3_url: String,
+    query_client: QueryClient,
     gas_adjuster_config: GasAdjusterConfig,
     singleton: OnceCell<Result<Arc<GasAdjuster>, Error>>,
 }
@@ -30,9 +30,9 @@ impl From<anyhow::Error> for Error {
 }
 
 impl GasAdjusterSingleton {
-    pub fn new(web3_url: String, gas_adjuster_config: GasAdjusterConfig) -> Self {
+    pub fn new(query_client: QueryClient, gas_adjuster_config: GasAdjusterConfig) -> Self {
         Self {
-            web3_url,
+            query_client,
             gas_adjuster_config,
             singleton: OnceCell::new(),
         }
@@ -42,12 +42,12 @@ impl GasAdjusterSingleton {
         let adjuster = self
             .singleton
             .get_or_init(|| async {
-                let query_client =
-                    QueryClient::new(&self.web3_url).context("QueryClient::new()")?;
-                let adjuster =
-                    GasAdjuster::new(Arc::new(query_client.clone()), self.gas_adjuster_config)
-                        .await
-                        .context("GasAdjuster::new()")?;
+                let adjuster = GasAdjuster::new(
+                    Arc::new(self.query_client.clone()),
+                    self.gas_adjuster_config.clone(),
+                )
+                .await
+                .context("GasAdjuster::new()")?;
                 Ok(Arc::new(adjuster))
             })
             .await;
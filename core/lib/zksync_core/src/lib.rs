@@ -3,11 +3,14 @@
 use std::{net::Ipv4Addr, str::FromStr, sync::Arc, time::Instant};
 
 use anyhow::Context as _;
-use fee_model::{ApiFeeInputProvider, BatchFeeModelInputProvider, MainNodeFeeInputProvider};
+use fee_model::{
+    ApiFeeInputProvider, BatchFeeModelInputProvider, L1FeeInputSingleton, MainNodeFeeInputProvider,
+};
 use futures::channel::oneshot;
 use prometheus_exporter::PrometheusExporterConfig;
 use temp_config_store::TempConfigStore;
 use tokio::{sync::watch, task::JoinHandle};
+use tracing::Instrument as _;
 use zksync_circuit_breaker::{
     l1_txs::FailedL1TransactionChecker, replication_lag::ReplicationLagChecker, CircuitBreaker,
     CircuitBreakerChecker, CircuitBreakerError,
@@ -15,7 +18,7 @@ use zksync_circuit_breaker::{
 use zksync_concurrency::{ctx, scope};
 use zksync_config::{
     configs::{
-        api::{MerkleTreeApiConfig, Web3JsonRpcConfig},
+        api::{MerkleTreeApiConfig, RpcInterfaceConfig, Web3JsonRpcConfig},
         chain::{
             CircuitBreakerConfig, MempoolConfig, NetworkConfig, OperationsManagerConfig,
             StateKeeperConfig,
@@ -26,11 +29,15 @@ use zksync_config::{
     ApiConfig, ContractsConfig, DBConfig, ETHSenderConfig, PostgresConfig,
 };
 use zksync_contracts::{governance_contract, BaseSystemContracts};
-use zksync_dal::{healthcheck::ConnectionPoolHealthCheck, ConnectionPool};
+use zksync_dal::{
+    healthcheck::{ConnectionPoolHealthCheck, L1BatchHealthCheck, L1BatchHealthThresholds},
+    ConnectionPool,
+};
 use zksync_eth_client::{
-    clients::{PKSigningClient, QueryClient},
+    clients::{operator_signer, DynSigningClient, PrivateRelayClient, QueryClient},
     CallFunctionArgs, EthInterface,
 };
+use zksync_eth_signer::Signer as _;
 use zksync_health_check::{AppHealthCheck, HealthStatus, ReactiveHealthCheck};
 use zksync_object_store::{ObjectStore, ObjectStoreFactory};
 use zksync_queued_job_processor::JobProcessor;
@@ -40,20 +47,24 @@ use zksync_types::{
     protocol_version::{L1VerifierConfig, VerifierParams},
     system_contracts::get_system_smart_contracts,
     web3::contract::tokens::Detokenize,
-    L2ChainId, PackedEthSignature, ProtocolVersionId,
+    L2ChainId, ProtocolVersionId,
 };
 
 use crate::{
     api_server::{
+        admin::{self, AdminHandles},
         contract_verification,
         execution_sandbox::{VmConcurrencyBarrier, VmConcurrencyLimiter},
-        healthcheck::HealthCheckHandle,
+        grpc,
+        healthcheck::{HealthCheckHandle, L1ClientHealthCheck},
         tx_sender::{ApiContracts, TxSender, TxSenderBuilder, TxSenderConfig},
         web3,
         web3::{state::InternalApiConfig, ApiServerHandles, Namespace},
     },
+    base_token_ratio::BaseTokenRatioFetcher,
     basic_witness_input_producer::BasicWitnessInputProducer,
     commitment_generator::CommitmentGenerator,
+    consistency_checker::ConsistencyChecker,
     eth_sender::{Aggregator, EthTxAggregator, EthTxManager},
     eth_watch::start_eth_watch,
     house_keeper::{
@@ -65,23 +76,28 @@ use crate::{
         fri_scheduler_circuit_queuer::SchedulerCircuitQueuer,
         fri_witness_generator_jobs_retry_manager::FriWitnessGeneratorJobRetryManager,
         fri_witness_generator_queue_monitor::FriWitnessGeneratorStatsReporter,
-        periodic_job::PeriodicJob,
+        periodic_job::PeriodicJob, prover_artifacts_gc::ProverArtifactsGarbageCollector,
         waiting_to_queued_fri_witness_job_mover::WaitingToQueuedFriWitnessJobMover,
     },
     l1_gas_price::GasAdjusterSingleton,
     metadata_calculator::{MetadataCalculator, MetadataCalculatorConfig},
     metrics::{InitStage, APP_METRICS},
+    shutdown::ShutdownTracker,
     state_keeper::{
-        create_state_keeper, MempoolFetcher, MempoolGuard, MiniblockSealer, SequencerSealer,
+        create_state_keeper, L1BatchPause, MempoolFetcher, MempoolGuard, MiniblockSealer,
+        PartitionManager, SequencerSealer,
     },
 };
 
 pub mod api_server;
+pub mod base_token_ratio;
 pub mod basic_witness_input_producer;
+pub mod block_exporter;
 pub mod block_reverter;
 pub mod commitment_generator;
 pub mod consensus;
 pub mod consistency_checker;
+pub mod db_pruner;
 pub mod eth_sender;
 pub mod eth_watch;
 pub mod fee_model;
@@ -92,13 +108,17 @@ pub mod l1_gas_price;
 pub mod metadata_calculator;
 mod metrics;
 pub mod proof_data_handler;
+pub mod pubdata_export;
 pub mod reorg_detector;
+pub mod shutdown;
+pub mod startup;
 pub mod state_keeper;
 pub mod sync_layer;
 pub mod temp_config_store;
 mod utils;
 
-/// Inserts the initial information about zkSync tokens into the database.
+/// Inserts the initial information about zkSync tokens into the database. If `genesis_spec` is provided,
+/// the genesis state is created according to it; otherwise, the default genesis state is used.
 pub async fn genesis_init(
     postgres_config: &PostgresConfig,
     eth_sender: &ETHSenderConfig,
@@ -106,20 +126,29 @@ pub async fn genesis_init(
     contracts_config: &ContractsConfig,
     eth_client_url: &str,
     wait_for_set_chain_id: bool,
+    genesis_spec: Option<&genesis::GenesisSpec>,
 ) -> anyhow::Result<()> {
+    if let Some(spec) = genesis_spec {
+        anyhow::ensure!(
+            spec.chain_id == network_config.zksync_network_id,
+            "chain ID in the genesis spec ({:?}) differs from the network config ({:?})",
+            spec.chain_id,
+            network_config.zksync_network_id
+        );
+    }
+
     let db_url = postgres_config.master_url()?;
     let pool = ConnectionPool::singleton(db_url)
         .build()
         .await
         .context("failed to build connection_pool")?;
     let mut storage = pool.access_storage().await.context("access_storage()")?;
-    let operator_address = PackedEthSignature::address_from_private_key(
-        &eth_sender
-            .sender
-            .private_key()
-            .context("Private key is required for genesis init")?,
-    )
-    .context("Failed to restore operator address from private key")?;
+    let operator_address = operator_signer(eth_sender)
+        .await
+        .context("operator signer is required for genesis init")?
+        .address()
+        .await
+        .context("failed getting operator address from signer")?;
 
     // Select the first prover to be used during genesis.
     // Later we can change provers using the system upgrades, but for genesis
@@ -163,10 +192,15 @@ pub async fn genesis_init(
             }
         };
 
-    genesis::ensure_genesis_state(
-        &mut storage,
-        network_config.zksync_network_id,
-        &genesis::GenesisParams {
+    let genesis_params = match genesis_spec {
+        Some(spec) => spec
+            .to_genesis_params(
+                operator_address,
+                contracts_config.verifier_addr,
+                first_l1_verifier_config,
+            )
+            .context("failed creating genesis params from spec")?,
+        None => genesis::GenesisParams {
             // We consider the operator to be the first validator for now.
             first_validator: operator_address,
             protocol_version: ProtocolVersionId::latest(),
@@ -174,7 +208,13 @@ pub async fn genesis_init(
             system_contracts: get_system_smart_contracts(),
             first_verifier_address: contracts_config.verifier_addr,
             first_l1_verifier_config,
+            custom_state: genesis::CustomGenesisState::default(),
         },
+    };
+    genesis::ensure_genesis_state(
+        &mut storage,
+        network_config.zksync_network_id,
+        &genesis_params,
     )
     .await?;
 
@@ -222,6 +262,88 @@ pub fn setup_sigint_handler() -> oneshot::Receiver<()> {
     sigint_receiver
 }
 
+/// Reloads log directives (`RUST_LOG` and `MISC_LOG_DIRECTIVES`) and log format (`MISC_LOG_FORMAT`)
+/// from the specified config file each time the process receives SIGHUP. Since env variables of a running process cannot be changed,
+/// the reloaded values are taken from the file even if they were overridden by env variables on startup.
+///
+/// On non-Unix platforms, this function returns immediately.
+pub async fn reload_logs_on_sighup(
+    config_path: std::path::PathBuf,
+    log_reload_handle: vlog::LogReloadHandle,
+) -> anyhow::Result<()> {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        let mut sighup = signal(SignalKind::hangup()).context("cannot install SIGHUP handler")?;
+        while sighup.recv().await.is_some() {
+            tracing::info!("Received SIGHUP, reloading log configuration from {config_path:?}");
+            if let Err(err) = reload_logs(&config_path, &log_reload_handle) {
+                tracing::warn!("Failed reloading log configuration: {err:#}");
+            }
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = (config_path, log_reload_handle);
+        tracing::info!("Reloading log configuration on SIGHUP is not supported on this platform");
+    }
+    Ok(())
+}
+
+#[cfg_attr(not(unix), allow(dead_code))]
+fn reload_logs(
+    config_path: &std::path::Path,
+    log_reload_handle: &vlog::LogReloadHandle,
+) -> anyhow::Result<()> {
+    let source = zksync_env_config::ConfigSource::from_file(config_path)?;
+    apply_log_config(&source, log_reload_handle)
+}
+
+fn apply_log_config(
+    source: &zksync_env_config::ConfigSource,
+    log_reload_handle: &vlog::LogReloadHandle,
+) -> anyhow::Result<()> {
+    if let Some(rust_log) = source.vars().get("RUST_LOG") {
+        log_reload_handle.set_rust_log(rust_log)?;
+        tracing::info!("Changed RUST_LOG to `{rust_log}`");
+    }
+    if let Some(directives) = source.vars().get("MISC_LOG_DIRECTIVES") {
+        log_reload_handle.set_log_directives(Some(directives))?;
+        tracing::info!("Changed log directives to `{directives}`");
+    }
+    if let Some(log_format) = source.vars().get("MISC_LOG_FORMAT") {
+        log_reload_handle.set_log_format(log_format.parse()?)?;
+        tracing::info!("Changed log format to `{log_format}`");
+    }
+    Ok(())
+}
+
+/// Reloads log directives (`RUST_LOG` and `MISC_LOG_DIRECTIVES`) and log format (`MISC_LOG_FORMAT`)
+/// each time they change in the polled remote config. `initial` is the remote config fetched on startup. Similarly to
+/// [`reload_logs_on_sighup()`], the reloaded values are applied even if they were overridden on startup.
+///
+/// Returns immediately if polling is not enabled for `source`.
+pub async fn reload_logs_on_remote_update(
+    source: zksync_env_config::remote::RemoteConfigSource,
+    initial: zksync_env_config::ConfigSource,
+    log_reload_handle: vlog::LogReloadHandle,
+) {
+    source
+        .poll(initial, |config| {
+            if let Err(err) = apply_log_config(config, &log_reload_handle) {
+                tracing::warn!("Failed reloading log configuration: {err:#}");
+            }
+        })
+        .await;
+}
+
+/// Creates a span attaching the component name to logs of a component task (see [`vlog::COMPONENT_SPAN`]).
+pub fn component_span(component: &'static str) -> tracing::Span {
+    // The span name must be a literal; it is equal to `vlog::COMPONENT_SPAN`.
+    tracing::info_span!("component", component)
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Component {
     /// Public Web3 API running on HTTP server.
@@ -230,6 +352,8 @@ pub enum Component {
     WsApi,
     /// REST API for contract verification.
     ContractVerificationApi,
+    /// gRPC API streaming new miniblocks, logs and L1 batch status updates.
+    GrpcApi,
     /// Metadata calculator.
     Tree,
     /// Merkle tree API.
@@ -252,6 +376,35 @@ pub enum Component {
     Consensus,
     /// Component generating commitment for L1 batches.
     CommitmentGenerator,
+    /// Component verifying that locally stored L1 batches are consistent with the data committed on L1.
+    ConsistencyChecker,
+    /// Authenticated local API allowing node operators to control other components at runtime.
+    AdminApi,
+}
+
+impl Component {
+    /// Returns the name of this component, as used in the `--components` command-line arg.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::HttpApi => "http_api",
+            Self::WsApi => "ws_api",
+            Self::ContractVerificationApi => "contract_verification_api",
+            Self::GrpcApi => "grpc_api",
+            Self::Tree => "tree",
+            Self::TreeApi => "tree_api",
+            Self::EthWatcher => "eth_watcher",
+            Self::EthTxAggregator => "eth_tx_aggregator",
+            Self::EthTxManager => "eth_tx_manager",
+            Self::StateKeeper => "state_keeper",
+            Self::BasicWitnessInputProducer => "basic_witness_input_producer",
+            Self::Housekeeper => "housekeeper",
+            Self::ProofDataHandler => "proof_data_handler",
+            Self::Consensus => "consensus",
+            Self::CommitmentGenerator => "commitment_generator",
+            Self::ConsistencyChecker => "consistency_checker",
+            Self::AdminApi => "admin_api",
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -270,6 +423,7 @@ impl FromStr for Components {
             "http_api" => Ok(Components(vec![Component::HttpApi])),
             "ws_api" => Ok(Components(vec![Component::WsApi])),
             "contract_verification_api" => Ok(Components(vec![Component::ContractVerificationApi])),
+            "grpc_api" => Ok(Components(vec![Component::GrpcApi])),
             "tree" => Ok(Components(vec![Component::Tree])),
             "tree_api" => Ok(Components(vec![Component::TreeApi])),
             "state_keeper" => Ok(Components(vec![Component::StateKeeper])),
@@ -288,6 +442,8 @@ impl FromStr for Components {
             "proof_data_handler" => Ok(Components(vec![Component::ProofDataHandler])),
             "consensus" => Ok(Components(vec![Component::Consensus])),
             "commitment_generator" => Ok(Components(vec![Component::CommitmentGenerator])),
+            "consistency_checker" => Ok(Components(vec![Component::ConsistencyChecker])),
+            "admin_api" => Ok(Components(vec![Component::AdminApi])),
             other => Err(format!("{} is not a valid component name", other)),
         }
     }
@@ -296,13 +452,16 @@ impl FromStr for Components {
 pub async fn initialize_components(
     configs: &TempConfigStore,
     components: Vec<Component>,
+    log_reload_handle: Option<vlog::LogReloadHandle>,
 ) -> anyhow::Result<(
     Vec<JoinHandle<anyhow::Result<()>>>,
     watch::Sender<bool>,
     oneshot::Receiver<CircuitBreakerError>,
     HealthCheckHandle,
+    ShutdownTracker,
 )> {
-    tracing::info!("Starting the components: {components:?}");
+    let component_names: Vec<_> = components.iter().copied().map(Component::name).collect();
+    startup::report_enabled_components(&component_names);
 
     let db_config = configs.db_config.clone().context("db_config")?;
     let postgres_config = configs.postgres_config.clone().context("postgres_config")?;
@@ -318,12 +477,15 @@ pub async fn initialize_components(
         .build()
         .await
         .context("failed to build connection_pool")?;
+    startup::report_applied_migrations(&connection_pool).await;
     // We're most interested in setting acquire / statement timeouts for the API server, which puts the most load
     // on Postgres.
     let replica_connection_pool =
         ConnectionPool::builder(postgres_config.replica_url()?, pool_size)
             .set_acquire_timeout(postgres_config.acquire_timeout())
             .set_statement_timeout(postgres_config.statement_timeout())
+            .set_read_replicas(postgres_config.read_replica_urls.clone())
+            .set_max_replica_lag(postgres_config.max_replica_lag())
             .build()
             .await
             .context("failed to build replica_connection_pool")?;
@@ -352,12 +514,24 @@ pub async fn initialize_components(
         panic!("Circuit breaker triggered: {}", err);
     });
 
-    let query_client = QueryClient::new(&eth_client_config.web3_url).unwrap();
-    let gas_adjuster_config = configs.gas_adjuster_config.context("gas_adjuster_config")?;
-    let mut gas_adjuster =
-        GasAdjusterSingleton::new(eth_client_config.web3_url.clone(), gas_adjuster_config);
+    let query_client =
+        QueryClient::from_config(&eth_client_config).context("QueryClient::from_config()")?;
+    let gas_adjuster_config = configs
+        .gas_adjuster_config
+        .clone()
+        .context("gas_adjuster_config")?;
+    let mut gas_adjuster = GasAdjusterSingleton::new(query_client.clone(), gas_adjuster_config);
+    let l1_fee_input = L1FeeInputSingleton::new(configs.fee_input_config.clone());
+    let base_token_ratio = configs
+        .base_token_config
+        .as_ref()
+        .map(BaseTokenRatioFetcher::new)
+        .transpose()
+        .context("BaseTokenRatioFetcher::new()")?
+        .map(Arc::new);
 
     let (stop_sender, stop_receiver) = watch::channel(false);
+    let shutdown_tracker = ShutdownTracker::default();
     let (cb_sender, cb_receiver) = oneshot::channel();
 
     // Prometheus exporter and circuit breaker checker should run for every component configuration.
@@ -382,6 +556,10 @@ pub async fn initialize_components(
         prometheus_task,
         tokio::spawn(circuit_breaker_checker.run(cb_sender, stop_receiver.clone())),
     ];
+    let mut admin_handles = AdminHandles::default();
+    if let Some(handle) = log_reload_handle {
+        admin_handles.set_log_reload_handle(handle);
+    }
 
     if components.contains(&Component::WsApi)
         || components.contains(&Component::HttpApi)
@@ -403,6 +581,14 @@ pub async fn initialize_components(
             &api_config.web3_json_rpc,
             &contracts_config,
         );
+        // Proofs are stored in the same object store as used by the Ethereum sender.
+        let proof_store = match &configs.object_store_config {
+            Some(config) => Some(ObjectStoreFactory::new(config.clone()).create_store().await),
+            None => None,
+        };
+        // The response cache is shared by HTTP and WS servers.
+        let response_cache =
+            web3::ResponseCache::new(api_config.web3_json_rpc.response_cache_size() as u64);
 
         // Lazily initialize storage caches only when they are needed (e.g., skip their initialization
         // if we only run the explorer APIs). This is required because the cache update task will
@@ -422,10 +608,16 @@ pub async fn initialize_components(
                 .get_or_init()
                 .await
                 .context("gas_adjuster.get_or_init()")?;
-            let batch_fee_input_provider = Arc::new(MainNodeFeeInputProvider::new(
-                bounded_gas_adjuster,
-                FeeModelConfig::from_state_keeper_config(&state_keeper_config),
-            ));
+            let l1_fee_input_provider = l1_fee_input
+                .get_or_init(bounded_gas_adjuster)
+                .context("l1_fee_input.get_or_init()")?;
+            let batch_fee_input_provider = Arc::new(
+                MainNodeFeeInputProvider::new(
+                    l1_fee_input_provider,
+                    FeeModelConfig::from_state_keeper_config(&state_keeper_config),
+                )
+                .with_base_token_ratio(base_token_ratio.clone()),
+            );
             let server_handles = run_http_api(
                 &postgres_config,
                 &tx_sender_config,
@@ -438,11 +630,18 @@ pub async fn initialize_components(
                 batch_fee_input_provider,
                 state_keeper_config.save_call_traces,
                 storage_caches.clone().unwrap(),
+                proof_store.clone(),
+                response_cache.clone(),
             )
             .await
             .context("run_http_api")?;
 
-            task_futures.extend(server_handles.tasks);
+            task_futures.extend(
+                server_handles
+                    .tasks
+                    .into_iter()
+                    .map(|task| shutdown_tracker.track_task("http_api", task)),
+            );
             app_health.insert_component(server_handles.health_check);
             let elapsed = started_at.elapsed();
             APP_METRICS.init_latency[&InitStage::HttpApi].set(elapsed);
@@ -465,10 +664,16 @@ pub async fn initialize_components(
                 .get_or_init()
                 .await
                 .context("gas_adjuster.get_or_init()")?;
-            let batch_fee_input_provider = Arc::new(MainNodeFeeInputProvider::new(
-                bounded_gas_adjuster,
-                FeeModelConfig::from_state_keeper_config(&state_keeper_config),
-            ));
+            let l1_fee_input_provider = l1_fee_input
+                .get_or_init(bounded_gas_adjuster)
+                .context("l1_fee_input.get_or_init()")?;
+            let batch_fee_input_provider = Arc::new(
+                MainNodeFeeInputProvider::new(
+                    l1_fee_input_provider,
+                    FeeModelConfig::from_state_keeper_config(&state_keeper_config),
+                )
+                .with_base_token_ratio(base_token_ratio.clone()),
+            );
             let server_handles = run_ws_api(
                 &postgres_config,
                 &tx_sender_config,
@@ -480,11 +685,18 @@ pub async fn initialize_components(
                 replica_connection_pool.clone(),
                 stop_receiver.clone(),
                 storage_caches,
+                proof_store,
+                response_cache,
             )
             .await
             .context("run_ws_api")?;
 
-            task_futures.extend(server_handles.tasks);
+            task_futures.extend(
+                server_handles
+                    .tasks
+                    .into_iter()
+                    .map(|task| shutdown_tracker.track_task("ws_api", task)),
+            );
             app_health.insert_component(server_handles.health_check);
             let elapsed = started_at.elapsed();
             APP_METRICS.init_latency[&InitStage::WsApi].set(elapsed);
@@ -509,6 +721,20 @@ pub async fn initialize_components(
         }
     }
 
+    if components.contains(&Component::GrpcApi) {
+        let started_at = Instant::now();
+        tracing::info!("initializing gRPC streaming API");
+        let api_config = configs.api_config.as_ref().context("api_config")?;
+        task_futures.extend(grpc::spawn_server(
+            &api_config.grpc,
+            replica_connection_pool.clone(),
+            stop_receiver.clone(),
+        ));
+        let elapsed = started_at.elapsed();
+        APP_METRICS.init_latency[&InitStage::GrpcApi].set(elapsed);
+        tracing::info!("initialized gRPC streaming API in {elapsed:?}");
+    }
+
     let object_store_config = configs
         .object_store_config
         .clone()
@@ -522,14 +748,20 @@ pub async fn initialize_components(
             .get_or_init()
             .await
             .context("gas_adjuster.get_or_init()")?;
+        let l1_fee_input_provider = l1_fee_input
+            .get_or_init(bounded_gas_adjuster)
+            .context("l1_fee_input.get_or_init()")?;
         let state_keeper_config = configs
             .state_keeper_config
             .clone()
             .context("state_keeper_config")?;
-        let batch_fee_input_provider = Arc::new(MainNodeFeeInputProvider::new(
-            bounded_gas_adjuster,
-            FeeModelConfig::from_state_keeper_config(&state_keeper_config),
-        ));
+        let batch_fee_input_provider = Arc::new(
+            MainNodeFeeInputProvider::new(
+                l1_fee_input_provider,
+                FeeModelConfig::from_state_keeper_config(&state_keeper_config),
+            )
+            .with_base_token_ratio(base_token_ratio.clone()),
+        );
         add_state_keeper_to_task_futures(
             &mut task_futures,
             &postgres_config,
@@ -540,7 +772,9 @@ pub async fn initialize_components(
             &configs.mempool_config.clone().context("mempool_config")?,
             batch_fee_input_provider,
             store_factory.create_store().await,
+            &mut admin_handles,
             stop_receiver.clone(),
+            &shutdown_tracker,
         )
         .await
         .context("add_state_keeper_to_task_futures()")?;
@@ -597,18 +831,17 @@ pub async fn initialize_components(
             .eth_watch_config
             .clone()
             .context("eth_watch_config")?;
-        task_futures.push(
-            start_eth_watch(
-                eth_watch_config,
-                eth_watch_pool,
-                Arc::new(query_client.clone()),
-                main_zksync_contract_address,
-                governance,
-                stop_receiver.clone(),
-            )
-            .await
-            .context("start_eth_watch()")?,
-        );
+        let eth_watch_task = start_eth_watch(
+            eth_watch_config,
+            eth_watch_pool,
+            Arc::new(query_client.clone()),
+            main_zksync_contract_address,
+            governance,
+            stop_receiver.clone(),
+        )
+        .await
+        .context("start_eth_watch()")?;
+        task_futures.push(shutdown_tracker.track_task("eth_watch", eth_watch_task));
         let elapsed = started_at.elapsed();
         APP_METRICS.init_latency[&InitStage::EthWatcher].set(elapsed);
         tracing::info!("initialized ETH-Watcher in {elapsed:?}");
@@ -627,8 +860,21 @@ pub async fn initialize_components(
             .clone()
             .context("eth_sender_config")?;
         let eth_client =
-            PKSigningClient::from_config(&eth_sender, &contracts_config, &eth_client_config);
-        let eth_tx_aggregator_actor = EthTxAggregator::new(
+            DynSigningClient::from_config(&eth_sender, &contracts_config, &eth_client_config)
+                .await
+                .context("DynSigningClient::from_config()")?;
+        let secondary_eth_client = DynSigningClient::secondary_from_config(
+            &eth_sender,
+            &contracts_config,
+            &eth_client_config,
+        )
+        .await
+        .context("DynSigningClient::secondary_from_config()")?;
+        let blob_eth_client =
+            DynSigningClient::blob_from_config(&eth_sender, &contracts_config, &eth_client_config)
+                .await
+                .context("DynSigningClient::blob_from_config()")?;
+        let mut eth_tx_aggregator_actor = EthTxAggregator::new(
             eth_sender.sender.clone(),
             Aggregator::new(
                 eth_sender.sender.clone(),
@@ -645,8 +891,22 @@ pub async fn initialize_components(
                 .zksync_network_id,
         )
         .await;
+        if let Some(secondary_eth_client) = secondary_eth_client {
+            eth_tx_aggregator_actor =
+                eth_tx_aggregator_actor.with_secondary_operator(Arc::new(secondary_eth_client));
+        }
+        if let Some(blob_eth_client) = blob_eth_client {
+            eth_tx_aggregator_actor = eth_tx_aggregator_actor
+                .with_blob_operator(Arc::new(blob_eth_client))
+                .await;
+        }
         task_futures.push(tokio::spawn(
-            eth_tx_aggregator_actor.run(eth_sender_pool, stop_receiver.clone()),
+            shutdown_tracker
+                .track(
+                    "eth_tx_aggregator",
+                    eth_tx_aggregator_actor.run(eth_sender_pool, stop_receiver.clone()),
+                )
+                .instrument(component_span("eth_tx_aggregator")),
         ));
         let elapsed = started_at.elapsed();
         APP_METRICS.init_latency[&InitStage::EthTxAggregator].set(elapsed);
@@ -665,17 +925,46 @@ pub async fn initialize_components(
             .clone()
             .context("eth_sender_config")?;
         let eth_client =
-            PKSigningClient::from_config(&eth_sender, &contracts_config, &eth_client_config);
-        let eth_tx_manager_actor = EthTxManager::new(
+            DynSigningClient::from_config(&eth_sender, &contracts_config, &eth_client_config)
+                .await
+                .context("DynSigningClient::from_config()")?;
+        let secondary_eth_client = DynSigningClient::secondary_from_config(
+            &eth_sender,
+            &contracts_config,
+            &eth_client_config,
+        )
+        .await
+        .context("DynSigningClient::secondary_from_config()")?;
+        let blob_eth_client =
+            DynSigningClient::blob_from_config(&eth_sender, &contracts_config, &eth_client_config)
+                .await
+                .context("DynSigningClient::blob_from_config()")?;
+        let private_relay = PrivateRelayClient::from_config(&eth_sender.submission)
+            .context("PrivateRelayClient::from_config()")?;
+        let mut eth_tx_manager_actor = EthTxManager::new(
             eth_sender.sender,
             gas_adjuster
                 .get_or_init()
                 .await
                 .context("gas_adjuster.get_or_init()")?,
             Arc::new(eth_client),
-        );
+        )
+        .with_private_relay(private_relay);
+        if let Some(secondary_eth_client) = secondary_eth_client {
+            eth_tx_manager_actor =
+                eth_tx_manager_actor.with_secondary_operator(Arc::new(secondary_eth_client));
+        }
+        if let Some(blob_eth_client) = blob_eth_client {
+            eth_tx_manager_actor =
+                eth_tx_manager_actor.with_blob_operator(Arc::new(blob_eth_client));
+        }
         task_futures.extend([tokio::spawn(
-            eth_tx_manager_actor.run(eth_manager_pool, stop_receiver.clone()),
+            shutdown_tracker
+                .track(
+                    "eth_tx_manager",
+                    eth_tx_manager_actor.run(eth_manager_pool, stop_receiver.clone()),
+                )
+                .instrument(component_span("eth_tx_manager")),
         )]);
         let elapsed = started_at.elapsed();
         APP_METRICS.init_latency[&InitStage::EthTxManager].set(elapsed);
@@ -688,7 +977,9 @@ pub async fn initialize_components(
         &app_health,
         &components,
         &store_factory,
+        &mut admin_handles,
         stop_receiver.clone(),
+        &shutdown_tracker,
     )
     .await
     .context("add_trees_to_task_futures()")?;
@@ -711,7 +1002,7 @@ pub async fn initialize_components(
     }
 
     if components.contains(&Component::Housekeeper) {
-        add_house_keeper_to_task_futures(configs, &mut task_futures)
+        add_house_keeper_to_task_futures(configs, &store_factory, &mut task_futures)
             .await
             .context("add_house_keeper_to_task_futures()")?;
     }
@@ -739,26 +1030,78 @@ pub async fn initialize_components(
             .context("failed to build commitment_generator_pool")?;
         let commitment_generator = CommitmentGenerator::new(commitment_generator_pool);
         app_health.insert_component(commitment_generator.health_check());
-        task_futures.push(tokio::spawn(
+        task_futures.push(tokio::spawn(shutdown_tracker.track(
+            "commitment_generator",
             commitment_generator.run(stop_receiver.clone()),
-        ));
+        )));
     }
 
-    // Run healthcheck server for all components.
-    let db_health_check = ConnectionPoolHealthCheck::new(replica_connection_pool);
-    app_health.insert_custom_component(Arc::new(db_health_check));
+    if components.contains(&Component::ConsistencyChecker) {
+        let consistency_checker_pool = ConnectionPool::singleton(postgres_config.replica_url()?)
+            .build()
+            .await
+            .context("failed to build consistency_checker_pool")?;
+        let consistency_checker = ConsistencyChecker::new(
+            &eth_client_config.web3_url,
+            10, // Same number of rechecked batches as on external nodes
+            consistency_checker_pool,
+        );
+        app_health.insert_component(consistency_checker.health_check().clone());
+        task_futures.push(tokio::spawn(consistency_checker.run(stop_receiver.clone())));
+    }
+
+    if components.contains(&Component::AdminApi) {
+        let started_at = Instant::now();
+        let api_config = configs.api_config.as_ref().context("api_config")?;
+        let task = admin::spawn_server(
+            &api_config.admin,
+            admin_handles,
+            connection_pool.clone(),
+            stop_receiver.clone(),
+        )
+        .context("admin::spawn_server()")?;
+        task_futures.push(task);
 
+        let elapsed = started_at.elapsed();
+        APP_METRICS.init_latency[&InitStage::AdminApi].set(elapsed);
+        tracing::info!("Initialized admin API in {elapsed:?}");
+    }
+
+    // Run healthcheck server for all components.
     let healtcheck_api_config = configs
         .health_check_config
         .clone()
         .context("health_check_config")?;
+    let db_health_check = ConnectionPoolHealthCheck::new(replica_connection_pool.clone());
+    app_health.insert_custom_component(Arc::new(db_health_check));
+    let l1_batch_thresholds = L1BatchHealthThresholds {
+        max_sealed_l1_batch_age: healtcheck_api_config.max_sealed_l1_batch_age(),
+        max_tree_lag: healtcheck_api_config.max_tree_lag,
+    };
+    let l1_batch_health_check =
+        L1BatchHealthCheck::new(replica_connection_pool, l1_batch_thresholds);
+    app_health.insert_custom_component(Arc::new(l1_batch_health_check));
+    let l1_client: Arc<dyn EthInterface> = Arc::new(query_client.clone());
+    app_health.insert_custom_component(Arc::new(L1ClientHealthCheck::from(l1_client)));
     let health_check_handle =
         HealthCheckHandle::spawn_server(healtcheck_api_config.bind_addr(), app_health);
 
     if let Some(task) = gas_adjuster.run_if_initialized(stop_receiver.clone()) {
         task_futures.push(task);
     }
-    Ok((task_futures, stop_sender, cb_receiver, health_check_handle))
+    if let Some(task) = l1_fee_input.run_if_initialized(stop_receiver.clone()) {
+        task_futures.push(task);
+    }
+    if let Some(fetcher) = base_token_ratio.filter(|fetcher| fetcher.requires_polling()) {
+        task_futures.push(tokio::spawn(fetcher.run(stop_receiver.clone())));
+    }
+    Ok((
+        task_futures,
+        stop_sender,
+        cb_receiver,
+        health_check_handle,
+        shutdown_tracker,
+    ))
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -772,7 +1115,9 @@ async fn add_state_keeper_to_task_futures(
     mempool_config: &MempoolConfig,
     batch_fee_input_provider: Arc<dyn BatchFeeModelInputProvider>,
     object_store: Arc<dyn ObjectStore>,
+    admin_handles: &mut AdminHandles,
     stop_receiver: watch::Receiver<bool>,
+    shutdown_tracker: &ShutdownTracker,
 ) -> anyhow::Result<()> {
     let pool_builder = ConnectionPool::singleton(postgres_config.master_url()?);
     let state_keeper_pool = pool_builder
@@ -784,7 +1129,12 @@ async fn add_state_keeper_to_task_futures(
             .access_storage()
             .await
             .context("Access storage to build mempool")?;
-        let mempool = MempoolGuard::from_storage(&mut storage, mempool_config.capacity).await;
+        let mempool = MempoolGuard::from_storage(
+            &mut storage,
+            mempool_config,
+            state_keeper_config.transaction_ordering,
+        )
+        .await;
         mempool.register_metrics();
         mempool
     };
@@ -797,8 +1147,16 @@ async fn add_state_keeper_to_task_futures(
         miniblock_sealer_pool,
         state_keeper_config.miniblock_seal_queue_capacity,
     );
-    task_futures.push(tokio::spawn(miniblock_sealer.run()));
+    // The sealer stops once the state keeper (which holds the sealer handle) is stopped
+    // and all submitted miniblocks are persisted.
+    task_futures.push(tokio::spawn(
+        shutdown_tracker
+            .track("miniblock_sealer", miniblock_sealer.run())
+            .instrument(component_span("state_keeper")),
+    ));
 
+    let l1_batch_pause = L1BatchPause::default();
+    admin_handles.set_state_keeper(l1_batch_pause.clone(), mempool.clone());
     let state_keeper = create_state_keeper(
         contracts_config,
         state_keeper_config,
@@ -810,14 +1168,32 @@ async fn add_state_keeper_to_task_futures(
         batch_fee_input_provider.clone(),
         miniblock_sealer_handle,
         object_store,
+        l1_batch_pause,
         stop_receiver.clone(),
     )
     .await;
 
     task_futures.push(tokio::spawn(
-        state_keeper.run_fee_address_migration(state_keeper_pool),
+        state_keeper
+            .run_fee_address_migration(state_keeper_pool)
+            .instrument(component_span("state_keeper")),
+    ));
+    task_futures.push(tokio::spawn(
+        shutdown_tracker
+            .track("state_keeper", state_keeper.run())
+            .instrument(component_span("state_keeper")),
+    ));
+
+    let partition_manager_pool = pool_builder
+        .build()
+        .await
+        .context("failed to build partition_manager_pool")?;
+    let partition_manager = PartitionManager::new(partition_manager_pool);
+    task_futures.push(tokio::spawn(
+        partition_manager
+            .run(stop_receiver.clone())
+            .instrument(component_span("state_keeper")),
     ));
-    task_futures.push(tokio::spawn(state_keeper.run()));
 
     let mempool_fetcher_pool = pool_builder
         .build()
@@ -829,18 +1205,25 @@ async fn add_state_keeper_to_task_futures(
         mempool_config,
         mempool_fetcher_pool,
     );
-    let mempool_fetcher_handle = tokio::spawn(mempool_fetcher.run(stop_receiver));
+    let mempool_fetcher_handle = tokio::spawn(
+        shutdown_tracker
+            .track("mempool_fetcher", mempool_fetcher.run(stop_receiver))
+            .instrument(component_span("mempool_fetcher")),
+    );
     task_futures.push(mempool_fetcher_handle);
     Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn add_trees_to_task_futures(
     configs: &TempConfigStore,
     task_futures: &mut Vec<JoinHandle<anyhow::Result<()>>>,
     app_health: &AppHealthCheck,
     components: &[Component],
     store_factory: &ObjectStoreFactory,
+    admin_handles: &mut AdminHandles,
     stop_receiver: watch::Receiver<bool>,
+    shutdown_tracker: &ShutdownTracker,
 ) -> anyhow::Result<()> {
     if !components.contains(&Component::Tree) {
         anyhow::ensure!(
@@ -878,7 +1261,9 @@ async fn add_trees_to_task_futures(
         api_config,
         &operation_config,
         object_store,
+        admin_handles,
         stop_receiver,
+        shutdown_tracker,
     )
     .await
     .context("run_tree()")
@@ -893,7 +1278,9 @@ async fn run_tree(
     api_config: Option<&MerkleTreeApiConfig>,
     operation_manager: &OperationsManagerConfig,
     object_store: Option<Arc<dyn ObjectStore>>,
+    admin_handles: &mut AdminHandles,
     stop_receiver: watch::Receiver<bool>,
+    shutdown_tracker: &ShutdownTracker,
 ) -> anyhow::Result<()> {
     let started_at = Instant::now();
     let mode_str = if matches!(merkle_tree_config.mode, MerkleTreeMode::Full) {
@@ -919,13 +1306,16 @@ async fn run_tree(
         }));
     }
 
+    admin_handles.set_tree_checker(metadata_calculator.consistency_checker());
+
     let tree_health_check = metadata_calculator.tree_health_check();
     app_health.insert_component(tree_health_check);
     let pool = ConnectionPool::singleton(postgres_config.master_url()?)
         .build()
         .await
         .context("failed to build connection pool")?;
-    let tree_task = tokio::spawn(metadata_calculator.run(pool, stop_receiver));
+    let tree_task =
+        tokio::spawn(shutdown_tracker.track("tree", metadata_calculator.run(pool, stop_receiver)));
     task_futures.push(tree_task);
 
     let elapsed = started_at.elapsed();
@@ -962,6 +1352,7 @@ async fn add_basic_witness_input_producer_to_task_futures(
 
 async fn add_house_keeper_to_task_futures(
     configs: &TempConfigStore,
+    store_factory: &ObjectStoreFactory,
     task_futures: &mut Vec<JoinHandle<anyhow::Result<()>>>,
 ) -> anyhow::Result<()> {
     let house_keeper_config = configs
@@ -998,6 +1389,7 @@ async fn add_house_keeper_to_task_futures(
     let fri_prover_job_retry_manager = FriProverJobRetryManager::new(
         fri_prover_config.max_attempts,
         fri_prover_config.proof_generation_timeout(),
+        house_keeper_config.fri_prover_heartbeat_timeout(),
         house_keeper_config.fri_prover_job_retrying_interval_ms,
         prover_connection_pool.clone(),
     );
@@ -1062,6 +1454,20 @@ async fn add_house_keeper_to_task_futures(
         prover_connection_pool.clone(),
     );
     task_futures.push(tokio::spawn(fri_proof_compressor_retry_manager.run()));
+
+    if let Some(retention_period) = house_keeper_config.prover_artifacts_retention_period() {
+        let prover_artifacts_gc = ProverArtifactsGarbageCollector::new(
+            retention_period,
+            house_keeper_config
+                .prover_artifacts_gc_interval()
+                .as_millis() as u64,
+            house_keeper_config.prover_artifacts_gc_dry_run,
+            connection_pool,
+            prover_connection_pool,
+            store_factory.create_store().await,
+        );
+        task_futures.push(tokio::spawn(prover_artifacts_gc.run()));
+    }
     Ok(())
 }
 
@@ -1135,6 +1541,8 @@ async fn run_http_api(
     batch_fee_model_input_provider: Arc<dyn BatchFeeModelInputProvider>,
     with_debug_namespace: bool,
     storage_caches: PostgresStorageCaches,
+    proof_store: Option<Arc<dyn ObjectStore>>,
+    response_cache: web3::ResponseCache,
 ) -> anyhow::Result<ApiServerHandles> {
     let (tx_sender, vm_barrier) = build_tx_sender(
         tx_sender_config,
@@ -1147,18 +1555,22 @@ async fn run_http_api(
     )
     .await;
 
-    let mut namespaces = Namespace::DEFAULT.to_vec();
+    let mut default_namespaces = Namespace::DEFAULT.to_vec();
     if with_debug_namespace {
-        namespaces.push(Namespace::Debug)
+        default_namespaces.extend([Namespace::Debug, Namespace::Trace]);
     }
-    namespaces.push(Namespace::Snapshots);
+    default_namespaces.extend([Namespace::Snapshots, Namespace::Txpool]);
+    let namespaces = interface_namespaces(&api_config.http_interface, default_namespaces)
+        .context("api.http_interface")?;
+    let method_filter =
+        web3::MethodFilter::new(&api_config.http_interface).context("api.http_interface")?;
 
     let updaters_pool = ConnectionPool::builder(postgres_config.replica_url()?, 2)
         .build()
         .await
         .context("failed to build last_miniblock_pool")?;
 
-    let api_builder =
+    let mut api_builder =
         web3::ApiBuilder::jsonrpsee_backend(internal_api.clone(), replica_connection_pool)
             .http(api_config.web3_json_rpc.http_port)
             .with_updaters_pool(updaters_pool)
@@ -1166,8 +1578,17 @@ async fn run_http_api(
             .with_tree_api(api_config.web3_json_rpc.tree_api_url())
             .with_batch_request_size_limit(api_config.web3_json_rpc.max_batch_request_size())
             .with_response_body_size_limit(api_config.web3_json_rpc.max_response_body_size())
+            .with_request_body_size_limit(api_config.web3_json_rpc.max_request_body_size())
+            .with_response_compression(api_config.web3_json_rpc.compress_responses())
+            .with_rate_limit(api_config.rate_limit.clone())
+            .with_audit_log(api_config.audit_log.clone())
             .with_tx_sender(tx_sender, vm_barrier)
+            .with_response_cache(response_cache)
+            .with_method_filter(method_filter)
             .enable_api_namespaces(namespaces);
+    if let Some(proof_store) = proof_store {
+        api_builder = api_builder.with_proof_store(proof_store);
+    }
     api_builder.build(stop_receiver).await
 }
 
@@ -1183,6 +1604,8 @@ async fn run_ws_api(
     replica_connection_pool: ConnectionPool,
     stop_receiver: watch::Receiver<bool>,
     storage_caches: PostgresStorageCaches,
+    proof_store: Option<Arc<dyn ObjectStore>>,
+    response_cache: web3::ResponseCache,
 ) -> anyhow::Result<ApiServerHandles> {
     let (tx_sender, vm_barrier) = build_tx_sender(
         tx_sender_config,
@@ -1199,10 +1622,14 @@ async fn run_ws_api(
         .await
         .context("failed to build last_miniblock_pool")?;
 
-    let mut namespaces = Namespace::DEFAULT.to_vec();
-    namespaces.push(Namespace::Snapshots);
+    let mut default_namespaces = Namespace::DEFAULT.to_vec();
+    default_namespaces.extend([Namespace::Snapshots, Namespace::Txpool]);
+    let namespaces = interface_namespaces(&api_config.ws_interface, default_namespaces)
+        .context("api.ws_interface")?;
+    let method_filter =
+        web3::MethodFilter::new(&api_config.ws_interface).context("api.ws_interface")?;
 
-    let api_builder =
+    let mut api_builder =
         web3::ApiBuilder::jsonrpsee_backend(internal_api.clone(), replica_connection_pool)
             .ws(api_config.web3_json_rpc.ws_port)
             .with_updaters_pool(last_miniblock_pool)
@@ -1210,6 +1637,7 @@ async fn run_ws_api(
             .with_subscriptions_limit(api_config.web3_json_rpc.subscriptions_limit())
             .with_batch_request_size_limit(api_config.web3_json_rpc.max_batch_request_size())
             .with_response_body_size_limit(api_config.web3_json_rpc.max_response_body_size())
+            .with_request_body_size_limit(api_config.web3_json_rpc.max_request_body_size())
             .with_websocket_requests_per_minute_limit(
                 api_config
                     .web3_json_rpc
@@ -1217,12 +1645,36 @@ async fn run_ws_api(
             )
             .with_polling_interval(api_config.web3_json_rpc.pubsub_interval())
             .with_tree_api(api_config.web3_json_rpc.tree_api_url())
+            .with_rate_limit(api_config.rate_limit.clone())
+            .with_audit_log(api_config.audit_log.clone())
             .with_tx_sender(tx_sender, vm_barrier)
+            .with_response_cache(response_cache)
+            .with_method_filter(method_filter)
             .enable_api_namespaces(namespaces);
+    if let Some(proof_store) = proof_store {
+        api_builder = api_builder.with_proof_store(proof_store);
+    }
 
     api_builder.build(stop_receiver.clone()).await
 }
 
+/// Returns namespaces enabled for an API interface, falling back to `default_namespaces` if they are not configured.
+fn interface_namespaces(
+    interface_config: &RpcInterfaceConfig,
+    default_namespaces: Vec<Namespace>,
+) -> anyhow::Result<Vec<Namespace>> {
+    let Some(namespaces) = &interface_config.namespaces else {
+        return Ok(default_namespaces);
+    };
+    namespaces
+        .iter()
+        .map(|name| {
+            name.parse::<Namespace>()
+                .with_context(|| format!("unknown API namespace `{name}`"))
+        })
+        .collect()
+}
+
 async fn circuit_breakers_for_components(
     components: &[Component],
     postgres_config: &PostgresConfig,
@@ -1244,7 +1696,10 @@ async fn circuit_breakers_for_components(
     if components.iter().any(|c| {
         matches!(
             c,
-            Component::HttpApi | Component::WsApi | Component::ContractVerificationApi
+            Component::HttpApi
+                | Component::WsApi
+                | Component::ContractVerificationApi
+                | Component::GrpcApi
         )
     }) {
         let pool = ConnectionPool::singleton(postgres_config.replica_url()?)
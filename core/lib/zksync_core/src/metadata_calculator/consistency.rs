@@ -0,0 +1,342 @@
+//! Background verification of Merkle tree consistency.
+//!
+//! Data in the tree RocksDB may be silently corrupted (e.g., because of disk bit rot). Such corruption is detected
+//! by cross-checking tree root hashes with L1 batch root hashes in Postgres, and by verifying hashes of internal
+//! tree nodes. Verifying nodes traverses the entire tree, so it's only performed for the latest tree version
+//! on each check; if it fails, the earliest inconsistent version is found using binary search.
+
+use std::{
+    fmt, ops,
+    time::{Duration, Instant},
+};
+
+use anyhow::Context as _;
+use tokio::sync::watch;
+use zksync_dal::ConnectionPool;
+use zksync_merkle_tree::unstable::ConsistencyError;
+use zksync_types::L1BatchNumber;
+
+use super::{
+    helpers::{create_db, AsyncTreeReader, GenericAsyncTree},
+    metrics::CONSISTENCY_METRICS,
+    MetadataCalculatorConfig,
+};
+
+/// Inconsistency detected in the Merkle tree.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TreeInconsistency {
+    /// Earliest L1 batch for which the tree version is detected to be inconsistent.
+    pub l1_batch_number: L1BatchNumber,
+    /// Human-readable description of the inconsistency.
+    pub reason: String,
+}
+
+impl fmt::Display for TreeInconsistency {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            formatter,
+            "Merkle tree version for L1 batch #{} is inconsistent: {}",
+            self.l1_batch_number, self.reason
+        )
+    }
+}
+
+/// Checks consistency of the Merkle tree, both internal and with L1 batches in Postgres.
+#[derive(Debug, Clone)]
+pub struct TreeConsistencyChecker {
+    reader: AsyncTreeReader,
+}
+
+impl TreeConsistencyChecker {
+    /// Number of L1 batches for which root hashes are cross-checked using a single Postgres connection.
+    const ROOT_HASH_CHUNK_SIZE: u32 = 100;
+
+    /// Opens the tree with the specified `config`. The tree must be initialized and must not be recovering.
+    /// The tree RocksDB is opened exclusively, so the checker cannot be created this way while the metadata calculator
+    /// for the same RocksDB instance is running.
+    pub async fn new(config: &MetadataCalculatorConfig) -> anyhow::Result<Self> {
+        let db = create_db(
+            config.db_path.clone().into(),
+            config.block_cache_capacity,
+            config.memtable_capacity,
+            config.stalled_writes_timeout,
+            config.multi_get_chunk_size,
+            config.rocksdb_tuning,
+        )
+        .await
+        .with_context(|| {
+            format!("failed opening Merkle tree RocksDB with configuration {config:?}")
+        })?;
+        let GenericAsyncTree::Ready(tree) = GenericAsyncTree::new(db, config.mode).await else {
+            anyhow::bail!("Merkle tree is not initialized or is being recovered");
+        };
+        Ok(Self::from_reader(tree.reader()))
+    }
+
+    pub(super) fn from_reader(reader: AsyncTreeReader) -> Self {
+        Self { reader }
+    }
+
+    /// Returns the range of L1 batches for which the tree has versions, or `None` if the tree is empty.
+    pub async fn l1_batch_range(&self) -> Option<ops::RangeInclusive<L1BatchNumber>> {
+        let tree_info = self.reader.clone().info().await;
+        let last_l1_batch = tree_info.next_l1_batch_number.0.checked_sub(1)?;
+
+        // Tree versions form a contiguous range: pruning removes versions from its start, and a recovered tree
+        // doesn't have versions before the recovered one. Thus, we can use binary search to find the range start.
+        let (mut left, mut right) = (0, last_l1_batch);
+        while left < right {
+            let middle = left + (right - left) / 2;
+            let root_hash = self
+                .reader
+                .clone()
+                .root_hash_for_l1_batch(L1BatchNumber(middle))
+                .await;
+            if root_hash.is_some() {
+                right = middle;
+            } else {
+                left = middle + 1;
+            }
+        }
+        Some(L1BatchNumber(left)..=L1BatchNumber(last_l1_batch))
+    }
+
+    /// Cross-checks tree root hashes for the specified L1 batches with root hashes in Postgres, returning the first
+    /// detected mismatch. L1 batches without metadata in Postgres or without tree versions are skipped.
+    pub async fn check_root_hashes(
+        &self,
+        pool: &ConnectionPool,
+        l1_batches: ops::RangeInclusive<L1BatchNumber>,
+    ) -> anyhow::Result<Option<TreeInconsistency>> {
+        let (start, end) = (l1_batches.start().0, l1_batches.end().0);
+        let mut chunk_start = start;
+        while chunk_start <= end {
+            let chunk_end = end.min(chunk_start.saturating_add(Self::ROOT_HASH_CHUNK_SIZE - 1));
+            let mut storage = pool.access_storage_tagged("metadata_calculator").await?;
+            for number in chunk_start..=chunk_end {
+                let l1_batch_number = L1BatchNumber(number);
+                let expected_root_hash = storage
+                    .blocks_dal()
+                    .get_l1_batch_state_root(l1_batch_number)
+                    .await
+                    .with_context(|| {
+                        format!("failed getting root hash for L1 batch #{l1_batch_number}")
+                    })?;
+                let Some(expected_root_hash) = expected_root_hash else {
+                    continue;
+                };
+                let root_hash = self
+                    .reader
+                    .clone()
+                    .root_hash_for_l1_batch(l1_batch_number)
+                    .await;
+                let Some(root_hash) = root_hash else {
+                    continue;
+                };
+                if root_hash != expected_root_hash {
+                    return Ok(Some(TreeInconsistency {
+                        l1_batch_number,
+                        reason: format!(
+                            "tree root hash {root_hash:?} differs from the root hash {expected_root_hash:?} in Postgres"
+                        ),
+                    }));
+                }
+            }
+            drop(storage);
+            chunk_start = match chunk_end.checked_add(1) {
+                Some(next_start) => next_start,
+                None => break,
+            };
+        }
+        Ok(None)
+    }
+
+    /// Verifies hashes of internal nodes and uniqueness of leaf indices for the tree version after the specified
+    /// L1 batch. This is expensive since the entire tree is traversed.
+    pub async fn verify_nodes(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<Option<TreeInconsistency>> {
+        let latency = CONSISTENCY_METRICS.node_verification_latency.start();
+        let result = self
+            .reader
+            .clone()
+            .verify_consistency(l1_batch_number)
+            .await;
+        let latency = latency.observe();
+        tracing::info!("Verified Merkle tree nodes for L1 batch #{l1_batch_number} in {latency:?}");
+
+        match result {
+            Ok(()) => Ok(None),
+            // Roots of pruned tree versions are removed, so `MissingRoot` doesn't necessarily mean an inconsistency.
+            Err(ConsistencyError::MissingVersion(_) | ConsistencyError::MissingRoot(_)) => {
+                anyhow::bail!(
+                    "Merkle tree doesn't contain a version for L1 batch #{l1_batch_number}"
+                )
+            }
+            Err(err) => Ok(Some(TreeInconsistency {
+                l1_batch_number,
+                reason: err.to_string(),
+            })),
+        }
+    }
+
+    /// Finds the earliest inconsistent tree version starting from `first_l1_batch`, given a detected `inconsistency`
+    /// in a later version. This uses binary search, so the result is only guaranteed to be an inconsistent version
+    /// preceded by a consistent one (or by `first_l1_batch`). Since tree nodes are immutable, a corrupted node
+    /// affects all versions starting from the version it was created in, until it's replaced; thus, in practice,
+    /// the search produces the version where the corruption was introduced.
+    pub async fn find_earliest_inconsistency(
+        &self,
+        first_l1_batch: L1BatchNumber,
+        inconsistency: TreeInconsistency,
+    ) -> anyhow::Result<TreeInconsistency> {
+        let mut earliest_inconsistency = inconsistency;
+        let mut left = first_l1_batch.0;
+        let mut right = earliest_inconsistency.l1_batch_number.0;
+        while left < right {
+            let middle = L1BatchNumber(left + (right - left) / 2);
+            if let Some(inconsistency) = self.verify_nodes(middle).await? {
+                right = middle.0;
+                earliest_inconsistency = inconsistency;
+            } else {
+                left = middle.0 + 1;
+            }
+        }
+        Ok(earliest_inconsistency)
+    }
+
+    /// Performs a full check of the tree: cross-checks root hashes for all tree versions with Postgres,
+    /// and verifies internal nodes for the latest tree version. Returns the earliest detected inconsistency.
+    pub async fn check(&self, pool: &ConnectionPool) -> anyhow::Result<Option<TreeInconsistency>> {
+        let Some(l1_batches) = self.l1_batch_range().await else {
+            tracing::info!("Merkle tree is empty; nothing to check");
+            return Ok(None);
+        };
+        tracing::info!("Checking Merkle tree versions for L1 batches {l1_batches:?}");
+
+        let root_inconsistency = self.check_root_hashes(pool, l1_batches.clone()).await?;
+        let node_inconsistency = match self.verify_nodes(*l1_batches.end()).await? {
+            Some(inconsistency) => Some(
+                self.find_earliest_inconsistency(*l1_batches.start(), inconsistency)
+                    .await?,
+            ),
+            None => None,
+        };
+        Ok([root_inconsistency, node_inconsistency]
+            .into_iter()
+            .flatten()
+            .min_by_key(|inconsistency| inconsistency.l1_batch_number))
+    }
+}
+
+/// Low-priority task periodically checking consistency of the tree used by the metadata calculator. Root hashes
+/// of new tree versions are cross-checked with Postgres, and internal nodes of the latest tree version are verified
+/// once per check interval. Detected inconsistencies are logged and reported via metrics; the task doesn't stop
+/// the metadata calculator.
+#[derive(Debug)]
+pub(super) struct TreeConsistencyTask {
+    checker: TreeConsistencyChecker,
+    interval: Duration,
+}
+
+impl TreeConsistencyTask {
+    /// Maximum number of L1 batches for which root hashes are cross-checked in a single iteration.
+    const MAX_L1_BATCHES_PER_ITERATION: u32 = 1_000;
+
+    pub fn new(reader: AsyncTreeReader, interval: Duration) -> Self {
+        Self {
+            checker: TreeConsistencyChecker::from_reader(reader),
+            interval,
+        }
+    }
+
+    pub async fn run(
+        self,
+        pool: ConnectionPool,
+        mut stop_receiver: watch::Receiver<bool>,
+    ) -> anyhow::Result<()> {
+        let mut next_l1_batch_to_check = None::<L1BatchNumber>;
+        let mut last_node_verification = None::<Instant>;
+        while !*stop_receiver.borrow_and_update() {
+            let Some(l1_batches) = self.checker.l1_batch_range().await else {
+                if Self::wait(self.interval, &mut stop_receiver).await {
+                    break;
+                }
+                continue;
+            };
+
+            let mut storage = pool.access_storage_tagged("metadata_calculator").await?;
+            let last_l1_batch_with_metadata = storage
+                .blocks_dal()
+                .get_last_l1_batch_number_with_metadata()
+                .await
+                .context("failed getting last L1 batch with metadata")?;
+            drop(storage);
+
+            let start = next_l1_batch_to_check
+                .map_or(*l1_batches.start(), |next| next.max(*l1_batches.start()));
+            let end = last_l1_batch_with_metadata
+                .map_or(*l1_batches.end(), |last| last.min(*l1_batches.end()));
+            let end = end.min(start + (Self::MAX_L1_BATCHES_PER_ITERATION - 1));
+            let has_more_l1_batches = if start <= end {
+                let root_inconsistency = self.checker.check_root_hashes(&pool, start..=end).await?;
+                if let Some(inconsistency) = root_inconsistency {
+                    Self::report(&inconsistency);
+                }
+                CONSISTENCY_METRICS.last_checked_l1_batch.set(end.0.into());
+                next_l1_batch_to_check = Some(end + 1);
+                end < *l1_batches.end()
+            } else {
+                false
+            };
+
+            let should_verify_nodes =
+                last_node_verification.map_or(true, |at| at.elapsed() >= self.interval);
+            if !has_more_l1_batches && should_verify_nodes {
+                let last_l1_batch = *l1_batches.end();
+                if let Some(inconsistency) = self.checker.verify_nodes(last_l1_batch).await? {
+                    let first_l1_batch = self
+                        .checker
+                        .l1_batch_range()
+                        .await
+                        .map_or(*l1_batches.start(), |range| *range.start());
+                    // The search may fail if the searched versions are pruned concurrently; in this case,
+                    // we report the inconsistency for the latest version.
+                    let earliest_inconsistency = self
+                        .checker
+                        .find_earliest_inconsistency(first_l1_batch, inconsistency.clone())
+                        .await;
+                    let inconsistency = earliest_inconsistency.unwrap_or_else(|err| {
+                        tracing::warn!(
+                            "Failed finding earliest inconsistent Merkle tree version: {err:#}"
+                        );
+                        inconsistency
+                    });
+                    Self::report(&inconsistency);
+                }
+                last_node_verification = Some(Instant::now());
+            }
+
+            if !has_more_l1_batches && Self::wait(self.interval, &mut stop_receiver).await {
+                break;
+            }
+        }
+        tracing::info!("Stop signal received, Merkle tree consistency checker is shutting down");
+        Ok(())
+    }
+
+    fn report(inconsistency: &TreeInconsistency) {
+        tracing::error!("{inconsistency}");
+        CONSISTENCY_METRICS
+            .inconsistent_l1_batch
+            .set(inconsistency.l1_batch_number.0.into());
+    }
+
+    /// Waits for the specified interval. Returns `true` if a stop signal was received.
+    async fn wait(interval: Duration, stop_receiver: &mut watch::Receiver<bool>) -> bool {
+        tokio::time::timeout(interval, stop_receiver.changed())
+            .await
+            .is_ok()
+    }
+}
@@ -3,26 +3,30 @@
 use std::{
     collections::BTreeMap,
     future::Future,
+    mem,
+    ops::ControlFlow,
     path::{Path, PathBuf},
     time::Duration,
 };
 
 use anyhow::Context as _;
 use serde::{Deserialize, Serialize};
-#[cfg(test)]
 use tokio::sync::mpsc;
-use zksync_config::configs::database::MerkleTreeMode;
+use zksync_config::configs::database::{MerkleTreeMode, RocksdbTuning};
 use zksync_dal::StorageProcessor;
 use zksync_health_check::{Health, HealthStatus};
 use zksync_merkle_tree::{
     domain::{TreeMetadata, ZkSyncTree, ZkSyncTreeReader},
     recovery::MerkleTreeRecovery,
-    Database, Key, NoVersionError, RocksDBWrapper, TreeEntry, TreeEntryWithProof, TreeInstruction,
+    unstable::ConsistencyError,
+    Database, Key, MerkleTreePruner, MerkleTreePrunerHandle, NoVersionError, RocksDBWrapper,
+    TreeEntry, TreeEntryWithProof, TreeInstruction,
 };
 use zksync_storage::{RocksDB, RocksDBOptions, StalledWritesRetries};
 use zksync_types::{block::L1BatchHeader, L1BatchNumber, StorageKey, H256};
 
 use super::metrics::{LoadChangesStage, TreeUpdateStage, METRICS};
+use crate::utils::apply_rocksdb_tuning;
 
 /// General information about the Merkle tree.
 #[derive(Debug, Serialize, Deserialize)]
@@ -64,6 +68,7 @@ pub(super) async fn create_db(
     memtable_capacity: usize,
     stalled_writes_timeout: Duration,
     multi_get_chunk_size: usize,
+    rocksdb_tuning: RocksdbTuning,
 ) -> anyhow::Result<RocksDBWrapper> {
     tokio::task::spawn_blocking(move || {
         create_db_sync(
@@ -72,6 +77,7 @@ pub(super) async fn create_db(
             memtable_capacity,
             stalled_writes_timeout,
             multi_get_chunk_size,
+            &rocksdb_tuning,
         )
     })
     .await
@@ -84,22 +90,23 @@ fn create_db_sync(
     memtable_capacity: usize,
     stalled_writes_timeout: Duration,
     multi_get_chunk_size: usize,
+    rocksdb_tuning: &RocksdbTuning,
 ) -> anyhow::Result<RocksDBWrapper> {
     tracing::info!(
         "Initializing Merkle tree database at `{path}` with {multi_get_chunk_size} multi-get chunk size, \
          {block_cache_capacity}B block cache, {memtable_capacity}B memtable capacity, \
-         {stalled_writes_timeout:?} stalled writes timeout",
+         {stalled_writes_timeout:?} stalled writes timeout, {rocksdb_tuning:?}",
         path = path.display()
     );
 
-    let mut db = RocksDB::with_options(
-        path,
-        RocksDBOptions {
-            block_cache_capacity: Some(block_cache_capacity),
-            large_memtable_capacity: Some(memtable_capacity),
-            stalled_writes_retries: StalledWritesRetries::new(stalled_writes_timeout),
-        },
-    )?;
+    let mut options = RocksDBOptions {
+        block_cache_capacity: Some(block_cache_capacity),
+        large_memtable_capacity: Some(memtable_capacity),
+        stalled_writes_retries: StalledWritesRetries::new(stalled_writes_timeout),
+        ..RocksDBOptions::default()
+    };
+    apply_rocksdb_tuning(&mut options, rocksdb_tuning);
+    let mut db = RocksDB::with_options(path, options)?;
     if cfg!(test) {
         // We need sync writes for the unit tests to execute reliably. With the default config,
         // some writes to RocksDB may occur, but not be visible to the test code.
@@ -154,6 +161,17 @@ impl AsyncTree {
         }
     }
 
+    pub fn pruner(
+        &self,
+        past_versions_to_keep: u64,
+    ) -> (MerkleTreePruner<RocksDBWrapper>, MerkleTreePrunerHandle) {
+        self.as_ref().pruner(past_versions_to_keep)
+    }
+
+    pub fn use_dedicated_thread_pool(&mut self, thread_count: usize) {
+        self.as_mut().use_dedicated_thread_pool(thread_count);
+    }
+
     pub fn is_empty(&self) -> bool {
         self.as_ref().is_empty()
     }
@@ -228,6 +246,53 @@ impl AsyncTreeReader {
             .await
             .unwrap()
     }
+
+    /// Returns the root hash of the tree after processing the specified L1 batch.
+    pub async fn root_hash_for_l1_batch(self, l1_batch_number: L1BatchNumber) -> Option<H256> {
+        tokio::task::spawn_blocking(move || self.inner.root_hash_for_l1_batch(l1_batch_number))
+            .await
+            .unwrap()
+    }
+
+    /// Verifies consistency of the tree after processing the specified L1 batch.
+    pub async fn verify_consistency(
+        self,
+        l1_batch_number: L1BatchNumber,
+    ) -> Result<(), ConsistencyError> {
+        tokio::task::spawn_blocking(move || self.inner.verify_consistency(l1_batch_number))
+            .await
+            .unwrap()
+    }
+
+    /// Sends all entries of the tree after processing the specified L1 batch to `chunk_sender`, in chunks
+    /// of `chunk_size` entries ordered by key. Stops early if `chunk_sender` is closed.
+    pub async fn export_entries(
+        self,
+        l1_batch_number: L1BatchNumber,
+        chunk_size: usize,
+        chunk_sender: mpsc::Sender<Vec<TreeEntry>>,
+    ) -> Result<(), NoVersionError> {
+        tokio::task::spawn_blocking(move || {
+            let mut chunk = Vec::with_capacity(chunk_size);
+            self.inner.visit_entries(l1_batch_number, |entry| {
+                chunk.push(entry);
+                if chunk.len() < chunk_size {
+                    return ControlFlow::Continue(());
+                }
+                let full_chunk = mem::replace(&mut chunk, Vec::with_capacity(chunk_size));
+                match chunk_sender.blocking_send(full_chunk) {
+                    Ok(()) => ControlFlow::Continue(()),
+                    Err(_) => ControlFlow::Break(()),
+                }
+            })?;
+            if !chunk.is_empty() {
+                chunk_sender.blocking_send(chunk).ok();
+            }
+            Ok(())
+        })
+        .await
+        .unwrap()
+    }
 }
 
 /// Async wrapper for [`MerkleTreeRecovery`].
@@ -265,6 +330,16 @@ impl AsyncTreeRecovery {
         entry
     }
 
+    /// Returns the last key processed during recovery.
+    pub async fn last_processed_key(&mut self) -> Option<Key> {
+        let tree = self.inner.take().expect(Self::INCONSISTENT_MSG);
+        let (key, tree) = tokio::task::spawn_blocking(move || (tree.last_processed_key(), tree))
+            .await
+            .unwrap();
+        self.inner = Some(tree);
+        key
+    }
+
     /// Returns the current hash of the tree.
     pub async fn root_hash(&mut self) -> H256 {
         let tree = self.inner.take().expect(Self::INCONSISTENT_MSG);
@@ -587,6 +662,7 @@ mod tests {
             16 << 20,       // 16 MiB,
             Duration::ZERO, // writes should never be stalled in tests
             500,
+            RocksdbTuning::default(),
         )
         .await
         .unwrap();
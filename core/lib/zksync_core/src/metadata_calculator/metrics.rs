@@ -205,3 +205,20 @@ pub(super) struct MetadataCalculatorRecoveryMetrics {
 #[vise::register]
 pub(super) static RECOVERY_METRICS: vise::Global<MetadataCalculatorRecoveryMetrics> =
     vise::Global::new();
+
+/// Metrics for background consistency checks of the Merkle tree.
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "server_metadata_calculator_consistency")]
+pub(super) struct TreeConsistencyMetrics {
+    /// Last L1 batch for which the tree root hash was cross-checked with Postgres.
+    pub last_checked_l1_batch: Gauge<u64>,
+    /// Earliest L1 batch for which the tree was detected to be inconsistent. Only set if an inconsistency is detected.
+    pub inconsistent_l1_batch: Gauge<u64>,
+    /// Latency of verifying internal node hashes for a single tree version. Since verification traverses
+    /// the entire tree, it can take hours for large trees.
+    #[metrics(buckets = Buckets::exponential(1.0..=16_384.0, 2.0), unit = Unit::Seconds)]
+    pub node_verification_latency: Histogram<Duration>,
+}
+
+#[vise::register]
+pub(super) static CONSISTENCY_METRICS: vise::Global<TreeConsistencyMetrics> = vise::Global::new();
@@ -11,7 +11,7 @@ use anyhow::Context as _;
 use tokio::sync::watch;
 use zksync_config::configs::{
     chain::OperationsManagerConfig,
-    database::{MerkleTreeConfig, MerkleTreeMode},
+    database::{MerkleTreeConfig, MerkleTreeMode, RocksdbTuning},
 };
 use zksync_dal::ConnectionPool;
 use zksync_health_check::{HealthUpdater, ReactiveHealthCheck};
@@ -19,13 +19,23 @@ use zksync_object_store::ObjectStore;
 
 pub(crate) use self::helpers::{AsyncTreeReader, L1BatchWithLogs, MerkleTreeInfo};
 use self::{
+    consistency::TreeConsistencyTask,
     helpers::{create_db, Delayer, GenericAsyncTree, MerkleTreeHealth},
+    pruning::MerkleTreePruningTask,
     updater::TreeUpdater,
 };
+pub use self::{
+    consistency::{TreeConsistencyChecker, TreeInconsistency},
+    recovery::{TreeDbState, TreeRebuilder},
+    snapshot::{TreeSnapshotHeader, TreeSnapshotManager},
+};
 
+mod consistency;
 mod helpers;
 mod metrics;
+mod pruning;
 mod recovery;
+mod snapshot;
 #[cfg(test)]
 pub(crate) mod tests;
 mod updater;
@@ -51,6 +61,18 @@ pub struct MetadataCalculatorConfig {
     pub memtable_capacity: usize,
     /// Timeout to wait for the Merkle tree database to run compaction on stalled writes.
     pub stalled_writes_timeout: Duration,
+    /// Number of latest tree versions to retain. Older versions are pruned once the corresponding L1 batches
+    /// are executed on L1. If `None`, the tree is not pruned.
+    pub pruning_retained_versions: Option<u64>,
+    /// Delay between consecutive pruning iterations if the pruner has more work to do.
+    pub pruning_iteration_delay: Duration,
+    /// Number of threads in a dedicated thread pool used by the tree for parallel updates. If `None`,
+    /// the global `rayon` thread pool is used.
+    pub thread_count: Option<usize>,
+    /// Interval between background consistency checks of the tree. If `None`, background checks are disabled.
+    pub consistency_check_interval: Option<Duration>,
+    /// Tuning options for the tree RocksDB (compaction style, memtable size etc.).
+    pub rocksdb_tuning: RocksdbTuning,
 }
 
 impl MetadataCalculatorConfig {
@@ -67,6 +89,11 @@ impl MetadataCalculatorConfig {
             block_cache_capacity: merkle_tree_config.block_cache_size(),
             memtable_capacity: merkle_tree_config.memtable_capacity(),
             stalled_writes_timeout: merkle_tree_config.stalled_writes_timeout(),
+            pruning_retained_versions: merkle_tree_config.pruning_retained_versions,
+            pruning_iteration_delay: merkle_tree_config.pruning_iteration_delay(),
+            thread_count: merkle_tree_config.thread_count,
+            consistency_check_interval: merkle_tree_config.consistency_check_interval(),
+            rocksdb_tuning: merkle_tree_config.rocksdb_tuning(),
         }
     }
 }
@@ -124,6 +151,13 @@ impl MetadataCalculator {
         }
     }
 
+    /// Returns a consistency checker sharing the tree with this calculator. The returned future resolves
+    /// once the tree is initialized.
+    pub(crate) fn consistency_checker(&self) -> impl Future<Output = TreeConsistencyChecker> {
+        let tree_reader = self.tree_reader();
+        async move { TreeConsistencyChecker::from_reader(tree_reader.await) }
+    }
+
     async fn create_tree(&self) -> anyhow::Result<GenericAsyncTree> {
         self.health_updater
             .update(MerkleTreeHealth::Initialization.into());
@@ -135,6 +169,7 @@ impl MetadataCalculator {
             self.config.memtable_capacity,
             self.config.stalled_writes_timeout,
             self.config.multi_get_chunk_size,
+            self.config.rocksdb_tuning,
         )
         .await
         .with_context(|| {
@@ -161,19 +196,57 @@ impl MetadataCalculator {
         let tree = tree
             .ensure_ready(&pool, &stop_receiver, &self.health_updater)
             .await?;
-        let Some(tree) = tree else {
+        let Some(mut tree) = tree else {
             return Ok(()); // recovery was aborted because a stop signal was received
         };
+        if let Some(thread_count) = self.config.thread_count {
+            tracing::info!(
+                "Using dedicated thread pool with {thread_count} threads for Merkle tree"
+            );
+            tree.use_dedicated_thread_pool(thread_count);
+        }
         let tree_reader = tree.reader();
         tracing::info!(
             "Merkle tree is initialized and ready to process L1 batches: {:?}",
             tree_reader.clone().info().await
         );
-        self.tree_reader.send_replace(Some(tree_reader));
-
+        self.tree_reader.send_replace(Some(tree_reader.clone()));
+
+        let pruning_task = self
+            .config
+            .pruning_retained_versions
+            .map(|retained_versions| {
+                MerkleTreePruningTask::new(
+                    &tree,
+                    retained_versions,
+                    self.config.pruning_iteration_delay,
+                    self.config.delay_interval,
+                )
+            });
+        let consistency_check_interval = self.config.consistency_check_interval;
         let updater = TreeUpdater::new(tree, self.max_l1_batches_per_iter, self.object_store);
-        updater
-            .loop_updating_tree(self.delayer, &pool, stop_receiver, self.health_updater)
-            .await
+        let update_task = updater.loop_updating_tree(
+            self.delayer,
+            &pool,
+            stop_receiver.clone(),
+            self.health_updater,
+        );
+        let pruning_task = async {
+            match pruning_task {
+                Some(task) => task.run(pool.clone(), stop_receiver.clone()).await,
+                None => Ok(()),
+            }
+        };
+        let consistency_task = async {
+            match consistency_check_interval {
+                Some(interval) => {
+                    let task = TreeConsistencyTask::new(tree_reader, interval);
+                    task.run(pool.clone(), stop_receiver.clone()).await
+                }
+                None => Ok(()),
+            }
+        };
+        tokio::try_join!(update_task, pruning_task, consistency_task)?;
+        Ok(())
     }
 }
@@ -0,0 +1,77 @@
+//! Pruning of historical Merkle tree versions coordinated with L1 batch finality.
+
+use std::time::Duration;
+
+use anyhow::Context as _;
+use tokio::sync::watch;
+use zksync_dal::ConnectionPool;
+use zksync_merkle_tree::{MerkleTreePruner, MerkleTreePrunerHandle, RocksDBWrapper};
+
+use super::helpers::AsyncTree;
+
+/// Task running [`MerkleTreePruner`] on a blocking thread and notifying it about L1 batches executed on L1.
+/// The pruner never removes tree versions for L1 batches that are not executed yet, so that the tree can be reverted
+/// together with the rest of the node state.
+#[derive(Debug)]
+pub(super) struct MerkleTreePruningTask {
+    pruner: MerkleTreePruner<RocksDBWrapper>,
+    handle: MerkleTreePrunerHandle,
+    poll_interval: Duration,
+}
+
+impl MerkleTreePruningTask {
+    pub fn new(
+        tree: &AsyncTree,
+        retained_versions: u64,
+        iteration_delay: Duration,
+        poll_interval: Duration,
+    ) -> Self {
+        let (mut pruner, handle) = tree.pruner(retained_versions);
+        pruner.set_iteration_delay(iteration_delay);
+        // Until executed L1 batches are loaded from Postgres, all tree versions are retained.
+        handle.set_finalized_version(0);
+        Self {
+            pruner,
+            handle,
+            poll_interval,
+        }
+    }
+
+    pub async fn run(
+        self,
+        pool: ConnectionPool,
+        mut stop_receiver: watch::Receiver<bool>,
+    ) -> anyhow::Result<()> {
+        let Self {
+            pruner,
+            handle,
+            poll_interval,
+        } = self;
+        let pruner_task = tokio::task::spawn_blocking(|| pruner.run());
+
+        while !*stop_receiver.borrow_and_update() && !pruner_task.is_finished() {
+            let mut storage = pool.access_storage_tagged("metadata_calculator").await?;
+            let last_executed_l1_batch = storage
+                .blocks_dal()
+                .get_number_of_last_l1_batch_executed_on_eth()
+                .await
+                .context("failed getting last executed L1 batch")?;
+            drop(storage);
+
+            if let Some(number) = last_executed_l1_batch {
+                // Tree version `N` corresponds to the tree state after L1 batch #N.
+                handle.set_finalized_version(number.0.into());
+            }
+            if tokio::time::timeout(poll_interval, stop_receiver.changed())
+                .await
+                .is_ok()
+            {
+                break;
+            }
+        }
+
+        tracing::info!("Merkle tree pruner is shutting down");
+        handle.abort();
+        pruner_task.await.context("Merkle tree pruner panicked")
+    }
+}
@@ -24,6 +24,14 @@
 //! Recovery performs basic sanity checks to ensure that the tree won't end up containing garbage data.
 //! E.g., it's checked that the tree always recovers from the same snapshot; that the tree root hash
 //! after recovery matches one in the Postgres snapshot etc.
+//!
+//! # Rebuilding the tree
+//!
+//! The same procedure is used to rebuild a lost or corrupted tree from the storage history in Postgres
+//! (see [`TreeRebuilder`]). In this case, chunks are loaded from `storage_logs` and `initial_writes` tables
+//! as of the last L1 batch with metadata, and the expected root hash is taken from this batch. If a rebuild
+//! is interrupted, the tree remains in the recovering state, and the rebuild is resumed on the next
+//! run of the rebuilder or of Metadata calculator.
 
 use std::{
     fmt, ops,
@@ -39,14 +47,16 @@ use zksync_health_check::HealthUpdater;
 use zksync_merkle_tree::TreeEntry;
 use zksync_types::{
     snapshots::{uniform_hashed_keys_chunk, SnapshotRecoveryStatus},
-    MiniblockNumber, H256,
+    L1BatchNumber, MiniblockNumber, H256,
 };
 
+pub use self::rebuild::{TreeDbState, TreeRebuilder};
 use super::{
     helpers::{AsyncTree, AsyncTreeRecovery, GenericAsyncTree, MerkleTreeHealth},
     metrics::{ChunkRecoveryStage, RecoveryStage, RECOVERY_METRICS},
 };
 
+mod rebuild;
 #[cfg(test)]
 mod tests;
 
@@ -105,11 +115,26 @@ impl HandleRecoveryEvent for RecoveryHealthUpdater<'_> {
             recovered_chunk_count,
         };
         self.inner.update(health.into());
+        tracing::info!(
+            "Recovered {recovered_chunk_count} / {} Merkle tree chunks",
+            self.chunk_count
+        );
     }
 }
 
+/// Source of tree entries during recovery.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RecoverySource {
+    /// Storage logs snapshot applied to Postgres during snapshot recovery.
+    Snapshot,
+    /// Storage history in Postgres; used to rebuild the tree.
+    StorageHistory,
+}
+
 #[derive(Debug, Clone, Copy)]
 struct SnapshotParameters {
+    source: RecoverySource,
+    l1_batch: L1BatchNumber,
     miniblock: MiniblockNumber,
     expected_root_hash: H256,
     log_count: u64,
@@ -132,6 +157,73 @@ impl SnapshotParameters {
             .with_context(|| format!("Failed getting number of logs for miniblock #{miniblock}"))?;
 
         Ok(Self {
+            source: RecoverySource::Snapshot,
+            l1_batch: recovery.l1_batch_number,
+            miniblock,
+            expected_root_hash,
+            log_count,
+        })
+    }
+
+    /// Creates parameters to resume recovery of the tree with the specified recovered version.
+    async fn for_recovering_tree(
+        pool: &ConnectionPool,
+        recovered_version: u64,
+    ) -> anyhow::Result<Self> {
+        let snapshot_recovery = get_snapshot_recovery(pool).await?;
+        match snapshot_recovery {
+            Some(snapshot_recovery)
+                if u64::from(snapshot_recovery.l1_batch_number.0) == recovered_version =>
+            {
+                tracing::info!("Resuming tree recovery with status: {snapshot_recovery:?}");
+                Self::new(pool, &snapshot_recovery).await
+            }
+            Some(snapshot_recovery)
+                if u64::from(snapshot_recovery.l1_batch_number.0) > recovered_version =>
+            {
+                anyhow::bail!(
+                    "Snapshot L1 batch in Postgres ({snapshot_recovery:?}) is greater than the recovered Merkle tree version \
+                     ({recovered_version})"
+                );
+            }
+            _ => {
+                // The tree is being rebuilt from the storage history (see `TreeRebuilder`).
+                let l1_batch = u32::try_from(recovered_version)
+                    .map(L1BatchNumber)
+                    .context("recovered tree version doesn't fit into an L1 batch number")?;
+                tracing::info!("Resuming tree rebuild for L1 batch #{l1_batch}");
+                Self::for_l1_batch(pool, l1_batch).await
+            }
+        }
+    }
+
+    /// Creates parameters for rebuilding the tree as of the specified L1 batch from the storage history
+    /// in Postgres. The L1 batch must have metadata.
+    async fn for_l1_batch(pool: &ConnectionPool, l1_batch: L1BatchNumber) -> anyhow::Result<Self> {
+        let mut storage = pool.access_storage_tagged("metadata_calculator").await?;
+        let (_, miniblock) = storage
+            .blocks_dal()
+            .get_miniblock_range_of_l1_batch(l1_batch)
+            .await
+            .with_context(|| format!("Failed getting miniblocks for L1 batch #{l1_batch}"))?
+            .with_context(|| format!("L1 batch #{l1_batch} doesn't have miniblocks in Postgres"))?;
+        let expected_root_hash = storage
+            .blocks_dal()
+            .get_l1_batch_state_root(l1_batch)
+            .await
+            .with_context(|| format!("Failed getting root hash for L1 batch #{l1_batch}"))?
+            .with_context(|| format!("L1 batch #{l1_batch} doesn't have metadata in Postgres"))?;
+        let log_count = storage
+            .storage_logs_dal()
+            .count_tree_entries_for_l1_batch(l1_batch)
+            .await
+            .with_context(|| {
+                format!("Failed getting number of tree entries for L1 batch #{l1_batch}")
+            })?;
+
+        Ok(Self {
+            source: RecoverySource::StorageHistory,
+            l1_batch,
             miniblock,
             expected_root_hash,
             log_count,
@@ -160,20 +252,12 @@ impl GenericAsyncTree {
         stop_receiver: &watch::Receiver<bool>,
         health_updater: &HealthUpdater,
     ) -> anyhow::Result<Option<AsyncTree>> {
-        let (tree, snapshot_recovery) = match self {
+        let (tree, snapshot) = match self {
             Self::Ready(tree) => return Ok(Some(tree)),
             Self::Recovering(tree) => {
-                let snapshot_recovery = get_snapshot_recovery(pool).await?.context(
-                    "Merkle tree is recovering, but Postgres doesn't contain snapshot recovery information",
-                )?;
-                let recovered_version = tree.recovered_version();
-                anyhow::ensure!(
-                    u64::from(snapshot_recovery.l1_batch_number.0) == recovered_version,
-                    "Snapshot L1 batch in Postgres ({snapshot_recovery:?}) differs from the recovered Merkle tree version \
-                     ({recovered_version})"
-                );
-                tracing::info!("Resuming tree recovery with status: {snapshot_recovery:?}");
-                (tree, snapshot_recovery)
+                let snapshot =
+                    SnapshotParameters::for_recovering_tree(pool, tree.recovered_version()).await?;
+                (tree, snapshot)
             }
             Self::Empty { db, mode } => {
                 if let Some(snapshot_recovery) = get_snapshot_recovery(pool).await? {
@@ -182,7 +266,10 @@ impl GenericAsyncTree {
                     );
                     let l1_batch = snapshot_recovery.l1_batch_number;
                     let tree = AsyncTreeRecovery::new(db, l1_batch.0.into(), mode);
-                    (tree, snapshot_recovery)
+                    (
+                        tree,
+                        SnapshotParameters::new(pool, &snapshot_recovery).await?,
+                    )
                 } else {
                     // Start the tree from scratch. The genesis block will be filled in `TreeUpdater::loop_updating_tree()`.
                     return Ok(Some(AsyncTree::new(db, mode)));
@@ -190,19 +277,29 @@ impl GenericAsyncTree {
             }
         };
 
-        let snapshot = SnapshotParameters::new(pool, &snapshot_recovery).await?;
+        tree.recover_with_health_updater(snapshot, pool, stop_receiver, health_updater)
+            .await
+    }
+}
+
+impl AsyncTreeRecovery {
+    async fn recover_with_health_updater(
+        self,
+        snapshot: SnapshotParameters,
+        pool: &ConnectionPool,
+        stop_receiver: &watch::Receiver<bool>,
+        health_updater: &HealthUpdater,
+    ) -> anyhow::Result<Option<AsyncTree>> {
         tracing::debug!("Obtained snapshot parameters: {snapshot:?}");
         let recovery_options = RecoveryOptions {
             chunk_count: snapshot.chunk_count(),
             concurrency_limit: pool.max_size() as usize,
             events: Box::new(RecoveryHealthUpdater::new(health_updater)),
         };
-        tree.recover(snapshot, recovery_options, pool, stop_receiver)
+        self.recover(snapshot, recovery_options, pool, stop_receiver)
             .await
     }
-}
 
-impl AsyncTreeRecovery {
     async fn recover(
         mut self,
         snapshot: SnapshotParameters,
@@ -215,13 +312,13 @@ impl AsyncTreeRecovery {
             .map(|chunk_id| uniform_hashed_keys_chunk(chunk_id, chunk_count))
             .collect();
         tracing::info!(
-            "Recovering Merkle tree from Postgres snapshot in {chunk_count} concurrent chunks"
+            "Recovering Merkle tree from {:?} for L1 batch #{} in {chunk_count} concurrent chunks",
+            snapshot.source,
+            snapshot.l1_batch
         );
 
         let mut storage = pool.access_storage().await?;
-        let remaining_chunks = self
-            .filter_chunks(&mut storage, snapshot.miniblock, &chunks)
-            .await?;
+        let remaining_chunks = self.filter_chunks(&mut storage, &snapshot, &chunks).await?;
         drop(storage);
         options
             .events
@@ -239,7 +336,7 @@ impl AsyncTreeRecovery {
                 .await
                 .context("semaphore is never closed")?;
             options.events.chunk_started().await;
-            Self::recover_key_chunk(&tree, snapshot.miniblock, chunk, pool, stop_receiver).await?;
+            Self::recover_key_chunk(&tree, &snapshot, chunk, pool, stop_receiver).await?;
             options.events.chunk_recovered().await;
             anyhow::Ok(())
         });
@@ -269,16 +366,29 @@ impl AsyncTreeRecovery {
     async fn filter_chunks(
         &mut self,
         storage: &mut StorageProcessor<'_>,
-        snapshot_miniblock: MiniblockNumber,
+        snapshot: &SnapshotParameters,
         key_chunks: &[ops::RangeInclusive<H256>],
     ) -> anyhow::Result<Vec<ops::RangeInclusive<H256>>> {
         let chunk_starts_latency =
             RECOVERY_METRICS.latency[&RecoveryStage::LoadChunkStarts].start();
-        let chunk_starts = storage
-            .storage_logs_dal()
-            .get_chunk_starts_for_miniblock(snapshot_miniblock, key_chunks)
-            .await
-            .context("Failed getting chunk starts")?;
+        let mut storage_logs_dal = storage.storage_logs_dal();
+        let chunk_starts = match snapshot.source {
+            RecoverySource::Snapshot => {
+                storage_logs_dal
+                    .get_chunk_starts_for_miniblock(snapshot.miniblock, key_chunks)
+                    .await
+            }
+            RecoverySource::StorageHistory => {
+                storage_logs_dal
+                    .get_chunk_starts_for_l1_batch(
+                        snapshot.l1_batch,
+                        snapshot.miniblock,
+                        key_chunks,
+                    )
+                    .await
+            }
+        };
+        let chunk_starts = chunk_starts.context("Failed getting chunk starts")?;
         let chunk_starts_latency = chunk_starts_latency.observe();
         tracing::debug!(
             "Loaded start entries for {} chunks in {chunk_starts_latency:?}",
@@ -303,9 +413,10 @@ impl AsyncTreeRecovery {
             }
             anyhow::ensure!(
                 tree_entry.value == db_entry.value && tree_entry.leaf_index == db_entry.leaf_index,
-                "Mismatch between entry for key {:0>64x} in Postgres snapshot for miniblock #{snapshot_miniblock} \
+                "Mismatch between entry for key {:0>64x} in Postgres snapshot for miniblock #{} \
                  ({db_entry:?}) and tree ({tree_entry:?}); the recovery procedure may be corrupted",
-                db_entry.key
+                db_entry.key,
+                snapshot.miniblock
             );
         }
         Ok(output)
@@ -313,7 +424,7 @@ impl AsyncTreeRecovery {
 
     async fn recover_key_chunk(
         tree: &Mutex<AsyncTreeRecovery>,
-        snapshot_miniblock: MiniblockNumber,
+        snapshot: &SnapshotParameters,
         key_chunk: ops::RangeInclusive<H256>,
         pool: &ConnectionPool,
         stop_receiver: &watch::Receiver<bool>,
@@ -329,13 +440,29 @@ impl AsyncTreeRecovery {
 
         let entries_latency =
             RECOVERY_METRICS.chunk_latency[&ChunkRecoveryStage::LoadEntries].start();
-        let all_entries = storage
-            .storage_logs_dal()
-            .get_tree_entries_for_miniblock(snapshot_miniblock, key_chunk.clone())
-            .await
-            .with_context(|| {
-                format!("Failed getting entries for chunk {key_chunk:?} in snapshot for miniblock #{snapshot_miniblock}")
-            })?;
+        let mut storage_logs_dal = storage.storage_logs_dal();
+        let all_entries = match snapshot.source {
+            RecoverySource::Snapshot => {
+                storage_logs_dal
+                    .get_tree_entries_for_miniblock(snapshot.miniblock, key_chunk.clone())
+                    .await
+            }
+            RecoverySource::StorageHistory => {
+                storage_logs_dal
+                    .get_tree_entries_for_l1_batch(
+                        snapshot.l1_batch,
+                        snapshot.miniblock,
+                        key_chunk.clone(),
+                    )
+                    .await
+            }
+        };
+        let all_entries = all_entries.with_context(|| {
+            format!(
+                "Failed getting entries for chunk {key_chunk:?} in snapshot for miniblock #{}",
+                snapshot.miniblock
+            )
+        })?;
         drop(storage);
         let entries_latency = entries_latency.observe();
         tracing::debug!(
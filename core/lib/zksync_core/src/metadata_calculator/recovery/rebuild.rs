@@ -0,0 +1,242 @@
+//! Detection of Merkle tree RocksDB loss or corruption, and rebuilding the tree from the storage history in Postgres.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Context as _;
+use tokio::sync::watch;
+use zksync_dal::ConnectionPool;
+use zksync_health_check::ReactiveHealthCheck;
+use zksync_types::{L1BatchNumber, H256};
+
+use super::SnapshotParameters;
+use crate::metadata_calculator::{
+    helpers::{create_db, AsyncTreeRecovery, GenericAsyncTree},
+    MetadataCalculatorConfig,
+};
+
+/// State of the Merkle tree RocksDB as compared to Postgres.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TreeDbState {
+    /// The tree is consistent with Postgres.
+    Healthy {
+        /// Next L1 batch to be processed by the tree.
+        next_l1_batch_number: L1BatchNumber,
+        /// Current root hash of the tree.
+        root_hash: H256,
+    },
+    /// The tree RocksDB doesn't exist or is empty.
+    Missing,
+    /// The tree is being recovered or rebuilt for the specified L1 batch.
+    Recovering {
+        /// L1 batch the tree is recovered for.
+        l1_batch_number: L1BatchNumber,
+    },
+    /// The tree RocksDB cannot be opened, or the tree root hash doesn't match the one in Postgres.
+    Corrupted {
+        /// Human-readable description of the detected corruption.
+        reason: String,
+    },
+}
+
+impl TreeDbState {
+    /// Checks whether the tree needs to be rebuilt to be usable by the metadata calculator.
+    /// A missing tree can be built by the metadata calculator itself, but this is much slower
+    /// than a rebuild since the calculator processes L1 batches one by one.
+    pub fn needs_rebuild(&self) -> bool {
+        !matches!(self, Self::Healthy { .. })
+    }
+}
+
+/// Checks the Merkle tree RocksDB and rebuilds the tree from the storage history in Postgres if it's lost or corrupted.
+/// The tree is rebuilt as of the last L1 batch with metadata in Postgres by loading tree entries in parallel chunks;
+/// an interrupted rebuild is resumed from the remaining chunks.
+///
+/// The tree RocksDB is opened exclusively, so the rebuilder cannot run concurrently with the metadata calculator
+/// using the same RocksDB instance.
+#[derive(Debug)]
+pub struct TreeRebuilder {
+    config: MetadataCalculatorConfig,
+}
+
+impl TreeRebuilder {
+    /// Creates a rebuilder for the tree with the specified `config`.
+    pub fn new(config: MetadataCalculatorConfig) -> Self {
+        Self { config }
+    }
+
+    async fn open_tree(&self) -> anyhow::Result<GenericAsyncTree> {
+        let db = create_db(
+            self.config.db_path.clone().into(),
+            self.config.block_cache_capacity,
+            self.config.memtable_capacity,
+            self.config.stalled_writes_timeout,
+            self.config.multi_get_chunk_size,
+            self.config.rocksdb_tuning,
+        )
+        .await
+        .with_context(|| {
+            format!(
+                "failed opening Merkle tree RocksDB with configuration {:?}",
+                self.config
+            )
+        })?;
+        Ok(GenericAsyncTree::new(db, self.config.mode).await)
+    }
+
+    /// Checks the state of the tree RocksDB.
+    pub async fn check(&self, pool: &ConnectionPool) -> anyhow::Result<TreeDbState> {
+        if !Path::new(&self.config.db_path).exists() {
+            return Ok(TreeDbState::Missing);
+        }
+        let tree = match self.open_tree().await {
+            Ok(tree) => tree,
+            Err(err) => {
+                return Ok(TreeDbState::Corrupted {
+                    reason: format!("{err:#}"),
+                })
+            }
+        };
+        let tree = match tree {
+            GenericAsyncTree::Empty { .. } => return Ok(TreeDbState::Missing),
+            GenericAsyncTree::Recovering(recovery) => {
+                let l1_batch_number = u32::try_from(recovery.recovered_version())
+                    .map(L1BatchNumber)
+                    .context("recovered tree version doesn't fit into an L1 batch number")?;
+                return Ok(TreeDbState::Recovering { l1_batch_number });
+            }
+            GenericAsyncTree::Ready(tree) => tree,
+        };
+        if tree.is_empty() {
+            return Ok(TreeDbState::Missing);
+        }
+
+        let tree_info = tree.reader().info().await;
+        let last_tree_l1_batch = L1BatchNumber(tree_info.next_l1_batch_number.0 - 1);
+        let mut storage = pool.access_storage_tagged("metadata_calculator").await?;
+        let last_l1_batch_with_metadata = storage
+            .blocks_dal()
+            .get_last_l1_batch_number_with_metadata()
+            .await
+            .context("failed getting last L1 batch with metadata")?;
+        let healthy_state = TreeDbState::Healthy {
+            next_l1_batch_number: tree_info.next_l1_batch_number,
+            root_hash: tree_info.root_hash,
+        };
+        let Some(last_l1_batch_with_metadata) = last_l1_batch_with_metadata else {
+            return Ok(healthy_state);
+        };
+
+        // If the tree is ahead of Postgres, its extra versions will be reverted by the metadata calculator,
+        // so we compare root hashes for the last common L1 batch.
+        let checked_l1_batch = last_tree_l1_batch.min(last_l1_batch_with_metadata);
+        let expected_root_hash = storage
+            .blocks_dal()
+            .get_l1_batch_state_root(checked_l1_batch)
+            .await
+            .with_context(|| {
+                format!("failed getting root hash for L1 batch #{checked_l1_batch}")
+            })?;
+        drop(storage);
+        let (Some(expected_root_hash), Some(root_hash)) = (
+            expected_root_hash,
+            tree.reader().root_hash_for_l1_batch(checked_l1_batch).await,
+        ) else {
+            // The tree version may be pruned, or the tree may be recovered after the checked L1 batch.
+            tracing::warn!(
+                "Cannot compare Merkle tree root hash for L1 batch #{checked_l1_batch} with Postgres"
+            );
+            return Ok(healthy_state);
+        };
+
+        Ok(if root_hash == expected_root_hash {
+            healthy_state
+        } else {
+            TreeDbState::Corrupted {
+                reason: format!(
+                    "tree root hash {root_hash:?} for L1 batch #{checked_l1_batch} differs from \
+                     the root hash {expected_root_hash:?} in Postgres"
+                ),
+            }
+        })
+    }
+
+    /// Rebuilds the tree if it's lost or corrupted. An existing tree RocksDB is removed unless it's being recovered,
+    /// in which case the recovery is resumed. If `force` is set, the tree state is not checked, and the tree RocksDB
+    /// is removed unconditionally; this can be used if the check itself fails because of the corrupted RocksDB.
+    ///
+    /// Returns the L1 batch the tree was rebuilt for, or `None` if the rebuild wasn't necessary or was interrupted
+    /// by a stop signal.
+    pub async fn rebuild(
+        &self,
+        pool: &ConnectionPool,
+        stop_receiver: &watch::Receiver<bool>,
+        force: bool,
+    ) -> anyhow::Result<Option<L1BatchNumber>> {
+        if force {
+            self.remove_db().await?;
+        } else {
+            let state = self.check(pool).await?;
+            tracing::info!("Checked Merkle tree RocksDB: {state:?}");
+            match state {
+                TreeDbState::Healthy { .. } => {
+                    tracing::info!("Merkle tree is consistent with Postgres; not rebuilding it");
+                    return Ok(None);
+                }
+                TreeDbState::Recovering { .. } => { /* Recovery will be resumed below */ }
+                TreeDbState::Missing | TreeDbState::Corrupted { .. } => self.remove_db().await?,
+            }
+        }
+
+        let mut storage = pool.access_storage_tagged("metadata_calculator").await?;
+        let last_l1_batch_with_metadata = storage
+            .blocks_dal()
+            .get_last_l1_batch_number_with_metadata()
+            .await
+            .context("failed getting last L1 batch with metadata")?
+            .context(
+                "Postgres doesn't contain L1 batches with metadata; the tree cannot be rebuilt",
+            )?;
+        drop(storage);
+
+        let recovery = match self.open_tree().await? {
+            GenericAsyncTree::Empty { db, mode } => {
+                tracing::info!(
+                    "Starting Merkle tree rebuild for L1 batch #{last_l1_batch_with_metadata}"
+                );
+                AsyncTreeRecovery::new(db, last_l1_batch_with_metadata.0.into(), mode)
+            }
+            GenericAsyncTree::Recovering(recovery) => recovery,
+            GenericAsyncTree::Ready(_) => {
+                anyhow::bail!("Merkle tree was unexpectedly initialized during rebuild");
+            }
+        };
+        let recovered_version = recovery.recovered_version();
+        let snapshot = SnapshotParameters::for_recovering_tree(pool, recovered_version).await?;
+        let (_, health_updater) = ReactiveHealthCheck::new("tree");
+        let tree = recovery
+            .recover_with_health_updater(snapshot, pool, stop_receiver, &health_updater)
+            .await?;
+        Ok(tree.map(|_| snapshot.l1_batch))
+    }
+
+    async fn remove_db(&self) -> anyhow::Result<()> {
+        let db_path = PathBuf::from(&self.config.db_path);
+        if !db_path.exists() {
+            return Ok(());
+        }
+        tracing::warn!("Removing Merkle tree RocksDB at `{}`", db_path.display());
+        tokio::task::spawn_blocking(move || {
+            fs::remove_dir_all(&db_path).with_context(|| {
+                format!(
+                    "failed removing Merkle tree RocksDB at `{}`",
+                    db_path.display()
+                )
+            })
+        })
+        .await
+        .context("panicked removing Merkle tree RocksDB")?
+    }
+}
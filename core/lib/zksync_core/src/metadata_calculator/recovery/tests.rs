@@ -8,7 +8,7 @@ use test_casing::test_casing;
 use tokio::sync::mpsc;
 use zksync_config::configs::{
     chain::OperationsManagerConfig,
-    database::{MerkleTreeConfig, MerkleTreeMode},
+    database::{MerkleTreeConfig, MerkleTreeMode, RocksdbTuning},
 };
 use zksync_health_check::{CheckHealth, HealthStatus, ReactiveHealthCheck};
 use zksync_merkle_tree::{domain::ZkSyncTree, TreeInstruction};
@@ -31,6 +31,8 @@ use crate::{
 #[test]
 fn calculating_chunk_count() {
     let mut snapshot = SnapshotParameters {
+        source: RecoverySource::Snapshot,
+        l1_batch: L1BatchNumber(1),
         miniblock: MiniblockNumber(1),
         log_count: 160_000_000,
         expected_root_hash: H256::zero(),
@@ -51,6 +53,7 @@ async fn create_tree_recovery(path: PathBuf, l1_batch: L1BatchNumber) -> AsyncTr
         16 << 20,       // 16 MiB,
         Duration::ZERO, // writes should never be stalled in tests
         500,
+        RocksdbTuning::default(),
     )
     .await
     .unwrap();
@@ -322,3 +325,113 @@ async fn entire_recovery_workflow(case: RecoveryWorkflowCase) {
     stop_sender.send_replace(true);
     calculator_task.await.expect("calculator panicked").unwrap();
 }
+
+fn create_rebuilder(path: PathBuf) -> TreeRebuilder {
+    let merkle_tree_config = MerkleTreeConfig {
+        path: path.to_str().unwrap().to_owned(),
+        ..MerkleTreeConfig::default()
+    };
+    let calculator_config = MetadataCalculatorConfig::for_main_node(
+        &merkle_tree_config,
+        &OperationsManagerConfig { delay_interval: 50 },
+    );
+    TreeRebuilder::new(calculator_config)
+}
+
+#[tokio::test]
+async fn rebuilding_tree_from_storage_history() {
+    let pool = ConnectionPool::test_pool().await;
+    let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
+    let snapshot_recovery = prepare_recovery_snapshot_with_genesis(&pool, &temp_dir).await;
+    let expected_state = TreeDbState::Healthy {
+        next_l1_batch_number: L1BatchNumber(2),
+        root_hash: snapshot_recovery.l1_batch_root_hash,
+    };
+
+    // The tree built by the metadata calculator is consistent with Postgres.
+    let rebuilder = create_rebuilder(temp_dir.path().join("init/new"));
+    assert_eq!(rebuilder.check(&pool).await.unwrap(), expected_state);
+    let (_stop_sender, stop_receiver) = watch::channel(false);
+    let rebuilt_l1_batch = rebuilder.rebuild(&pool, &stop_receiver, false).await;
+    assert_eq!(rebuilt_l1_batch.unwrap(), None);
+
+    let rebuilder = create_rebuilder(temp_dir.path().join("rebuilt"));
+    let state = rebuilder.check(&pool).await.unwrap();
+    assert_eq!(state, TreeDbState::Missing);
+    assert!(state.needs_rebuild());
+    let rebuilt_l1_batch = rebuilder.rebuild(&pool, &stop_receiver, false).await;
+    assert_eq!(rebuilt_l1_batch.unwrap(), Some(L1BatchNumber(1)));
+    assert_eq!(rebuilder.check(&pool).await.unwrap(), expected_state);
+
+    // Forced rebuild should remove the existing tree.
+    let rebuilt_l1_batch = rebuilder.rebuild(&pool, &stop_receiver, true).await;
+    assert_eq!(rebuilt_l1_batch.unwrap(), Some(L1BatchNumber(1)));
+    assert_eq!(rebuilder.check(&pool).await.unwrap(), expected_state);
+}
+
+#[test_casing(3, [5, 7, 8])]
+#[tokio::test]
+async fn rebuild_fault_tolerance(chunk_count: u64) {
+    let pool = ConnectionPool::test_pool().await;
+    let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
+    let snapshot_recovery = prepare_recovery_snapshot_with_genesis(&pool, &temp_dir).await;
+    let snapshot = SnapshotParameters::for_l1_batch(&pool, L1BatchNumber(1))
+        .await
+        .unwrap();
+    assert_eq!(snapshot.source, RecoverySource::StorageHistory);
+    assert_eq!(snapshot.miniblock, MiniblockNumber(1));
+    assert_eq!(
+        snapshot.expected_root_hash,
+        snapshot_recovery.l1_batch_root_hash
+    );
+
+    let tree_path = temp_dir.path().join("rebuild");
+    let tree = create_tree_recovery(tree_path.clone(), L1BatchNumber(1)).await;
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let recovery_options = RecoveryOptions {
+        chunk_count,
+        concurrency_limit: 1,
+        events: Box::new(TestEventListener::new(1, stop_sender)),
+    };
+    assert!(tree
+        .recover(snapshot, recovery_options, &pool, &stop_receiver)
+        .await
+        .unwrap()
+        .is_none());
+
+    // Emulate a restart; the interrupted rebuild should be detected and resumed.
+    let rebuilder = create_rebuilder(tree_path.clone());
+    assert_eq!(
+        rebuilder.check(&pool).await.unwrap(),
+        TreeDbState::Recovering {
+            l1_batch_number: L1BatchNumber(1)
+        }
+    );
+    let snapshot = SnapshotParameters::for_recovering_tree(&pool, 1)
+        .await
+        .unwrap();
+    assert_eq!(snapshot.source, RecoverySource::StorageHistory);
+
+    let tree = create_tree_recovery(tree_path, L1BatchNumber(1)).await;
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let recovery_options = RecoveryOptions {
+        chunk_count,
+        concurrency_limit: 1,
+        events: Box::new(TestEventListener::new(u64::MAX, stop_sender).expect_recovered_chunks(1)),
+    };
+    let tree = tree
+        .recover(snapshot, recovery_options, &pool, &stop_receiver)
+        .await
+        .unwrap()
+        .expect("Tree rebuild unexpectedly aborted");
+    assert_eq!(tree.root_hash(), snapshot_recovery.l1_batch_root_hash);
+    drop(tree);
+
+    assert_eq!(
+        rebuilder.check(&pool).await.unwrap(),
+        TreeDbState::Healthy {
+            next_l1_batch_number: L1BatchNumber(2),
+            root_hash: snapshot_recovery.l1_batch_root_hash,
+        }
+    );
+}
@@ -0,0 +1,298 @@
+//! Export and import of Merkle tree snapshots via an object store.
+//!
+//! A tree snapshot consists of all tree entries after a certain L1 batch split into chunks ordered by key,
+//! and a [`TreeSnapshotHeader`] with the tree root hash. The header is uploaded after all chunks, so its presence
+//! signals that the snapshot is complete. Importing a snapshot recovers the tree on a fresh node, so that
+//! it doesn't need to recompute the tree from Postgres; once started, the metadata calculator continues
+//! from the L1 batch following the snapshot one.
+//!
+//! The tree RocksDB is opened exclusively, so neither export nor import can run concurrently with
+//! the metadata calculator using the same RocksDB instance.
+
+use std::sync::Arc;
+
+use anyhow::Context as _;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+use zksync_dal::ConnectionPool;
+use zksync_merkle_tree::TreeEntry;
+use zksync_object_store::{serialize_using_bincode, Bucket, ObjectStore, StoredObject};
+use zksync_types::{L1BatchNumber, H256, U256};
+
+use super::{
+    helpers::{create_db, AsyncTreeRecovery, GenericAsyncTree},
+    MetadataCalculatorConfig,
+};
+
+/// Header of a Merkle tree snapshot.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TreeSnapshotHeader {
+    /// Number of the L1 batch the snapshot is taken after.
+    pub l1_batch_number: L1BatchNumber,
+    /// Tree root hash after the L1 batch.
+    pub root_hash: H256,
+    /// Total number of entries in the snapshot.
+    pub leaf_count: u64,
+    /// Number of chunks in the snapshot.
+    pub chunk_count: u64,
+}
+
+impl StoredObject for TreeSnapshotHeader {
+    const BUCKET: Bucket = Bucket::MerkleTreeSnapshot;
+    type Key<'a> = L1BatchNumber;
+
+    fn encode_key(key: Self::Key<'_>) -> String {
+        format!("tree_snapshot_l1_batch_{key}_header.bin")
+    }
+
+    serialize_using_bincode!();
+}
+
+#[derive(Debug, Clone, Copy)]
+struct TreeSnapshotChunkKey {
+    l1_batch_number: L1BatchNumber,
+    chunk_id: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct TreeSnapshotEntry {
+    key: U256,
+    value: H256,
+    leaf_index: u64,
+}
+
+/// Chunk of a Merkle tree snapshot. Entries are ordered by key, and all keys in a chunk are greater
+/// than keys in the preceding chunks.
+#[derive(Debug, Serialize, Deserialize)]
+struct TreeSnapshotChunk {
+    entries: Vec<TreeSnapshotEntry>,
+}
+
+impl From<Vec<TreeEntry>> for TreeSnapshotChunk {
+    fn from(entries: Vec<TreeEntry>) -> Self {
+        let entries = entries
+            .into_iter()
+            .map(|entry| TreeSnapshotEntry {
+                key: entry.key,
+                value: entry.value,
+                leaf_index: entry.leaf_index,
+            })
+            .collect();
+        Self { entries }
+    }
+}
+
+impl StoredObject for TreeSnapshotChunk {
+    const BUCKET: Bucket = Bucket::MerkleTreeSnapshot;
+    type Key<'a> = TreeSnapshotChunkKey;
+
+    fn encode_key(key: Self::Key<'_>) -> String {
+        format!(
+            "tree_snapshot_l1_batch_{}_part_{:0>4}.bin",
+            key.l1_batch_number, key.chunk_id
+        )
+    }
+
+    serialize_using_bincode!();
+}
+
+/// Exports Merkle tree snapshots to an object store and imports them into a fresh tree.
+#[derive(Debug)]
+pub struct TreeSnapshotManager {
+    config: MetadataCalculatorConfig,
+    object_store: Arc<dyn ObjectStore>,
+    chunk_size: usize,
+}
+
+impl TreeSnapshotManager {
+    const DEFAULT_CHUNK_SIZE: usize = 200_000;
+
+    /// Creates a manager for the tree with the specified `config`.
+    pub fn new(config: MetadataCalculatorConfig, object_store: Arc<dyn ObjectStore>) -> Self {
+        Self {
+            config,
+            object_store,
+            chunk_size: Self::DEFAULT_CHUNK_SIZE,
+        }
+    }
+
+    /// Sets the number of entries in exported snapshot chunks.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunk_size` is zero.
+    pub fn set_chunk_size(&mut self, chunk_size: usize) {
+        assert!(chunk_size > 0, "Snapshot chunk size must be positive");
+        self.chunk_size = chunk_size;
+    }
+
+    async fn open_tree(&self) -> anyhow::Result<GenericAsyncTree> {
+        let db = create_db(
+            self.config.db_path.clone().into(),
+            self.config.block_cache_capacity,
+            self.config.memtable_capacity,
+            self.config.stalled_writes_timeout,
+            self.config.multi_get_chunk_size,
+            self.config.rocksdb_tuning,
+        )
+        .await
+        .with_context(|| {
+            format!(
+                "failed opening Merkle tree RocksDB with configuration {:?}",
+                self.config
+            )
+        })?;
+        Ok(GenericAsyncTree::new(db, self.config.mode).await)
+    }
+
+    /// Exports a snapshot of the tree after the specified L1 batch. The tree must contain the corresponding version,
+    /// i.e., it must have processed the L1 batch, and the version must not be pruned.
+    pub async fn export(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<TreeSnapshotHeader> {
+        let GenericAsyncTree::Ready(tree) = self.open_tree().await? else {
+            anyhow::bail!("Merkle tree is not initialized or is being recovered");
+        };
+        let reader = tree.reader();
+        let root_hash = reader
+            .clone()
+            .root_hash_for_l1_batch(l1_batch_number)
+            .await
+            .with_context(|| {
+                format!("Merkle tree doesn't contain a version for L1 batch #{l1_batch_number}")
+            })?;
+        tracing::info!(
+            "Exporting Merkle tree snapshot for L1 batch #{l1_batch_number} with root hash {root_hash:?}"
+        );
+
+        let (chunk_sender, mut chunk_receiver) = mpsc::channel(1);
+        let export_task = reader.export_entries(l1_batch_number, self.chunk_size, chunk_sender);
+        let upload_task = async {
+            let mut chunk_id = 0;
+            let mut leaf_count = 0;
+            while let Some(entries) = chunk_receiver.recv().await {
+                leaf_count += entries.len() as u64;
+                let key = TreeSnapshotChunkKey {
+                    l1_batch_number,
+                    chunk_id,
+                };
+                let chunk = TreeSnapshotChunk::from(entries);
+                self.object_store
+                    .put(key, &chunk)
+                    .await
+                    .with_context(|| format!("failed uploading snapshot chunk {chunk_id}"))?;
+                tracing::info!("Uploaded snapshot chunk {chunk_id} ({leaf_count} entries so far)");
+                chunk_id += 1;
+            }
+            anyhow::Ok((chunk_id, leaf_count))
+        };
+        let (export_result, upload_result) = tokio::join!(export_task, upload_task);
+        let (chunk_count, leaf_count) = upload_result?;
+        export_result.context("failed reading tree entries")?;
+
+        let header = TreeSnapshotHeader {
+            l1_batch_number,
+            root_hash,
+            leaf_count,
+            chunk_count,
+        };
+        self.object_store
+            .put(l1_batch_number, &header)
+            .await
+            .context("failed uploading snapshot header")?;
+        tracing::info!("Exported Merkle tree snapshot: {header:?}");
+        Ok(header)
+    }
+
+    /// Imports a snapshot of the tree after the specified L1 batch. The tree must be empty, or be recovering
+    /// from a snapshot for the same L1 batch (e.g., if a previous import was interrupted). If Postgres contains
+    /// the root hash for the L1 batch, it is checked against the snapshot.
+    pub async fn import(
+        &self,
+        pool: &ConnectionPool,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<()> {
+        let header: TreeSnapshotHeader = self
+            .object_store
+            .get(l1_batch_number)
+            .await
+            .with_context(|| {
+                format!("failed getting header of snapshot for L1 batch #{l1_batch_number}; is the snapshot complete?")
+            })?;
+        tracing::info!("Importing Merkle tree snapshot: {header:?}");
+
+        let mut storage = pool.access_storage_tagged("metadata_calculator").await?;
+        let expected_root_hash = storage
+            .blocks_dal()
+            .get_l1_batch_state_root(l1_batch_number)
+            .await
+            .with_context(|| format!("failed getting root hash for L1 batch #{l1_batch_number}"))?;
+        drop(storage);
+        if let Some(expected_root_hash) = expected_root_hash {
+            anyhow::ensure!(
+                expected_root_hash == header.root_hash,
+                "Snapshot root hash {:?} differs from the root hash {expected_root_hash:?} in Postgres",
+                header.root_hash
+            );
+        } else {
+            tracing::warn!(
+                "Postgres doesn't contain root hash for L1 batch #{l1_batch_number}; snapshot root hash cannot be verified"
+            );
+        }
+
+        let recovered_version = u64::from(l1_batch_number.0);
+        let mut recovery = match self.open_tree().await? {
+            GenericAsyncTree::Empty { db, mode } => {
+                AsyncTreeRecovery::new(db, recovered_version, mode)
+            }
+            GenericAsyncTree::Recovering(recovery) => {
+                anyhow::ensure!(
+                    recovery.recovered_version() == recovered_version,
+                    "Merkle tree is being recovered for version {}, while the snapshot is for L1 batch #{l1_batch_number}",
+                    recovery.recovered_version()
+                );
+                recovery
+            }
+            GenericAsyncTree::Ready(_) => {
+                anyhow::bail!("Merkle tree is already initialized; a snapshot can only be imported into an empty tree");
+            }
+        };
+
+        // Chunks are ordered by key, so entries that are not greater than the last processed key are already recovered.
+        let last_processed_key = recovery.last_processed_key().await;
+        for chunk_id in 0..header.chunk_count {
+            let key = TreeSnapshotChunkKey {
+                l1_batch_number,
+                chunk_id,
+            };
+            let chunk: TreeSnapshotChunk = self
+                .object_store
+                .get(key)
+                .await
+                .with_context(|| format!("failed getting snapshot chunk {chunk_id}"))?;
+            let entries: Vec<_> = chunk
+                .entries
+                .into_iter()
+                .filter(|entry| last_processed_key.map_or(true, |last_key| entry.key > last_key))
+                .map(|entry| TreeEntry::new(entry.key, entry.leaf_index, entry.value))
+                .collect();
+            if entries.is_empty() {
+                tracing::info!("Snapshot chunk {chunk_id} is already recovered; skipping");
+                continue;
+            }
+            recovery.extend(entries).await;
+            tracing::info!("Recovered snapshot chunk {chunk_id}/{}", header.chunk_count);
+        }
+
+        let root_hash = recovery.root_hash().await;
+        anyhow::ensure!(
+            root_hash == header.root_hash,
+            "Root hash of the recovered tree {root_hash:?} differs from the snapshot root hash {:?}",
+            header.root_hash
+        );
+        recovery.finalize().await;
+        tracing::info!("Imported Merkle tree snapshot for L1 batch #{l1_batch_number}");
+        Ok(())
+    }
+}
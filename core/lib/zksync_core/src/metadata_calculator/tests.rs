@@ -21,7 +21,10 @@ use zksync_types::{
 };
 use zksync_utils::u32_to_h256;
 
-use super::{GenericAsyncTree, L1BatchWithLogs, MetadataCalculator, MetadataCalculatorConfig};
+use super::{
+    GenericAsyncTree, L1BatchWithLogs, MetadataCalculator, MetadataCalculatorConfig,
+    TreeConsistencyChecker, TreeSnapshotManager,
+};
 use crate::{
     genesis::{ensure_genesis_state, GenesisParams},
     utils::testonly::{create_l1_batch, create_miniblock},
@@ -259,6 +262,104 @@ async fn shutting_down_calculator() {
         .unwrap();
 }
 
+#[tokio::test]
+async fn running_calculator_with_pruning() {
+    let pool = ConnectionPool::test_pool().await;
+    let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
+    let (calculator, _) = setup_calculator(temp_dir.path(), &pool).await;
+    reset_db_state(&pool, 5).await;
+    let root_hash = run_calculator(calculator, pool.clone()).await;
+
+    let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
+    let (mut merkle_tree_config, operation_config) =
+        create_config(temp_dir.path(), MerkleTreeMode::Full);
+    merkle_tree_config.pruning_retained_versions = Some(1);
+    merkle_tree_config.pruning_iteration_delay_ms = 0;
+    let calculator =
+        setup_calculator_with_options(&merkle_tree_config, &operation_config, &pool, None).await;
+    // Pruning should neither influence the tree root hash, nor prevent the calculator from shutting down.
+    let root_hash_with_pruning = run_calculator(calculator, pool).await;
+    assert_eq!(root_hash_with_pruning, root_hash);
+}
+
+#[tokio::test]
+async fn checking_tree_consistency() {
+    let pool = ConnectionPool::test_pool().await;
+    let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
+    let (mut merkle_tree_config, operation_config) =
+        create_config(temp_dir.path(), MerkleTreeMode::Full);
+    merkle_tree_config.consistency_check_interval_sec = Some(1);
+    let calculator =
+        setup_calculator_with_options(&merkle_tree_config, &operation_config, &pool, None).await;
+    reset_db_state(&pool, 5).await;
+    // Background consistency checks should not prevent the calculator from shutting down.
+    run_calculator(calculator, pool.clone()).await;
+
+    let config = MetadataCalculatorConfig::for_main_node(&merkle_tree_config, &operation_config);
+    let checker = TreeConsistencyChecker::new(&config).await.unwrap();
+    let l1_batches = checker.l1_batch_range().await.unwrap();
+    assert_eq!(l1_batches, L1BatchNumber(0)..=L1BatchNumber(5));
+    let inconsistency = checker
+        .check_root_hashes(&pool, l1_batches.clone())
+        .await
+        .unwrap();
+    assert_eq!(inconsistency, None);
+    for l1_batch_number in (0..=5).map(L1BatchNumber) {
+        let inconsistency = checker.verify_nodes(l1_batch_number).await.unwrap();
+        assert_eq!(inconsistency, None);
+    }
+    checker.verify_nodes(L1BatchNumber(6)).await.unwrap_err();
+    assert_eq!(checker.check(&pool).await.unwrap(), None);
+}
+
+#[tokio::test]
+async fn exporting_and_importing_tree_snapshot() {
+    let pool = ConnectionPool::test_pool().await;
+    let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
+    let (calculator, object_store) = setup_calculator(temp_dir.path(), &pool).await;
+    reset_db_state(&pool, 5).await;
+    let root_hash = run_calculator(calculator, pool.clone()).await;
+
+    let snapshot_l1_batch = L1BatchNumber(3);
+    let (merkle_tree_config, operation_config) =
+        create_config(temp_dir.path(), MerkleTreeMode::Full);
+    let config = MetadataCalculatorConfig::for_main_node(&merkle_tree_config, &operation_config);
+    let mut exporter = TreeSnapshotManager::new(config, object_store.clone());
+    exporter.set_chunk_size(7);
+    let header = exporter.export(snapshot_l1_batch).await.unwrap();
+    assert_eq!(header.l1_batch_number, snapshot_l1_batch);
+    assert!(header.chunk_count > 1, "{header:?}");
+    let expected_root_hash = pool
+        .access_storage()
+        .await
+        .unwrap()
+        .blocks_dal()
+        .get_l1_batch_state_root(snapshot_l1_batch)
+        .await
+        .unwrap();
+    assert_eq!(Some(header.root_hash), expected_root_hash);
+    // Importing into an initialized tree should fail.
+    exporter.import(&pool, snapshot_l1_batch).await.unwrap_err();
+
+    let new_temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
+    let (merkle_tree_config, operation_config) =
+        create_config(new_temp_dir.path(), MerkleTreeMode::Full);
+    let config = MetadataCalculatorConfig::for_main_node(&merkle_tree_config, &operation_config);
+    let importer = TreeSnapshotManager::new(config, object_store);
+    importer.import(&pool, snapshot_l1_batch).await.unwrap();
+
+    // The calculator should continue from the L1 batch following the snapshot one.
+    let (calculator, _) = setup_calculator(new_temp_dir.path(), &pool).await;
+    let tree = calculator.create_tree().await.unwrap();
+    let GenericAsyncTree::Ready(tree) = tree else {
+        panic!("Unexpected tree state: {tree:?}");
+    };
+    assert_eq!(tree.next_l1_batch_number(), snapshot_l1_batch + 1);
+    drop(tree);
+    let imported_root_hash = run_calculator(calculator, pool).await;
+    assert_eq!(imported_root_hash, root_hash);
+}
+
 async fn test_postgres_backup_recovery(
     sleep_between_batches: bool,
     insert_batch_without_metadata: bool,
@@ -2,7 +2,10 @@
 
 use std::{fmt, time::Duration};
 
-use vise::{Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, Metrics};
+use vise::{
+    Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, LabeledFamily,
+    Metrics,
+};
 use zksync_dal::transactions_dal::L2TxSubmissionResult;
 use zksync_types::aggregated_operations::AggregatedActionType;
 
@@ -12,6 +15,7 @@ pub(crate) enum InitStage {
     HttpApi,
     WsApi,
     ContractVerificationApi,
+    GrpcApi,
     StateKeeper,
     EthWatcher,
     EthTxAggregator,
@@ -19,6 +23,7 @@ pub(crate) enum InitStage {
     Tree,
     BasicWitnessInputProducer,
     Consensus,
+    AdminApi,
 }
 
 impl fmt::Display for InitStage {
@@ -27,6 +32,7 @@ impl fmt::Display for InitStage {
             Self::HttpApi => formatter.write_str("http_api"),
             Self::WsApi => formatter.write_str("ws_api"),
             Self::ContractVerificationApi => formatter.write_str("contract_verification_api"),
+            Self::GrpcApi => formatter.write_str("grpc_api"),
             Self::StateKeeper => formatter.write_str("state_keeper"),
             Self::EthWatcher => formatter.write_str("eth_watcher"),
             Self::EthTxAggregator => formatter.write_str("eth_tx_aggregator"),
@@ -34,6 +40,7 @@ impl fmt::Display for InitStage {
             Self::Tree => formatter.write_str("tree"),
             Self::BasicWitnessInputProducer => formatter.write_str("basic_witness_input_producer"),
             Self::Consensus => formatter.write_str("consensus"),
+            Self::AdminApi => formatter.write_str("admin_api"),
         }
     }
 }
@@ -175,6 +182,15 @@ pub(crate) struct ExternalNodeMetrics {
     pub synced: Gauge<u64>,
     /// Current sync lag of the external node.
     pub sync_lag: Gauge<u64>,
+    /// Last miniblock fetched from the main node and queued for execution.
+    pub fetched_miniblock: Gauge<u64>,
+    /// Last miniblock executed and sealed by the external node.
+    pub applied_miniblock: Gauge<u64>,
+    /// Estimated time to catch up with the main node based on the recent sync speed.
+    pub estimated_time_to_head: Gauge<Duration>,
+    /// Whether a component stalls syncing (1) or not (0).
+    #[metrics(labels = ["component"])]
+    pub sync_stalled: LabeledFamily<&'static str, Gauge<u64>>,
     /// Number of the last L1 batch checked by the re-org detector or consistency checker.
     pub last_correct_batch: Family<CheckerComponent, Gauge<u64>>,
     /// Number of the last miniblock checked by the re-org detector or consistency checker.
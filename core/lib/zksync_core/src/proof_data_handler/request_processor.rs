@@ -136,6 +136,11 @@ impl RequestProcessor {
         Json(payload): Json<SubmitProofRequest>,
     ) -> Result<Json<SubmitProofResponse>, RequestProcessorError> {
         tracing::info!("Received proof for block number: {:?}", l1_batch_number);
+        vlog::add_lifecycle_breadcrumb(
+            "proof_data_handler",
+            format!("Received proof for L1 batch {l1_batch_number}"),
+            [("l1_batch_number", l1_batch_number.0.into())],
+        );
         let l1_batch_number = L1BatchNumber(l1_batch_number);
         match payload {
             SubmitProofRequest::Proof(proof) => {
@@ -0,0 +1,585 @@
+//! Export of L1 batch commitment data and its offline verification.
+//!
+//! For a range of L1 batches, [`export_pubdata()`] produces the data the node posts to L1 when committing the batches:
+//! the commit transaction calldata, ABI-encoded `CommitBatchInfo` for each batch together with its decoded fields,
+//! raw pubdata and compressed state diffs, and pubdata blobs with KZG commitments and proofs for batches publishing
+//! pubdata in EIP-4844 blobs. The export is a self-contained JSON document ([`PubdataExport`]).
+//!
+//! [`PubdataVerifier`] checks an export without access to the node storage: it re-derives pubdata commitments
+//! and blobs from the exported pubdata, and (optionally) compares `CommitBatchInfo` for each batch with the calldata
+//! of its commit transaction on L1.
+
+use std::ops;
+
+use anyhow::Context as _;
+use serde::{Deserialize, Serialize};
+use zksync_dal::StorageProcessor;
+use zksync_eth_client::{clients::QueryClient, EthInterface};
+use zksync_l1_contract_interface::{
+    i_executor::{commit::kzg::KzgInfo, methods::CommitBatches, structures::CommitBatchInfo},
+    Tokenizable, Tokenize,
+};
+use zksync_types::{
+    commitment::{serialize_commitments, L1BatchMetaParameters, L1BatchWithMetadata},
+    pubdata_da::PubdataDA,
+    web3::ethabi,
+    Bytes, L1BatchNumber, ProtocolVersionId, H256,
+};
+
+use crate::consistency_checker::ConsistencyChecker;
+
+#[cfg(test)]
+mod tests;
+
+/// Names of `CommitBatchInfo` fields in the order they are encoded. The last field is called
+/// `totalL2ToL1Pubdata` for protocol versions before 1.4.2.
+const COMMIT_BATCH_INFO_FIELDS: [&str; 10] = [
+    "batchNumber",
+    "timestamp",
+    "indexRepeatedStorageChanges",
+    "newStateRoot",
+    "numberOfLayer1Txs",
+    "priorityOperationsHash",
+    "bootloaderHeapInitialContentsHash",
+    "eventsQueueStateHash",
+    "systemLogs",
+    "pubdataCommitments",
+];
+
+fn commit_function() -> ethabi::Function {
+    // TODO: Add support for post shared bridge commits
+    zksync_contracts::zksync_contract()
+        .function("commitBatches")
+        .expect("L1 contract does not have `commitBatches` function")
+        .clone()
+}
+
+fn commit_batch_info_type(commit_function: &ethabi::Function) -> anyhow::Result<ethabi::ParamType> {
+    let param = commit_function
+        .inputs
+        .last()
+        .context("Unexpected signature for L1 commit function")?;
+    match &param.kind {
+        ethabi::ParamType::Array(item) => Ok((**item).clone()),
+        _ => anyhow::bail!("Unexpected signature for L1 commit function"),
+    }
+}
+
+/// Export of commitment data for a range of L1 batches.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PubdataExport {
+    /// Exported L1 batches in the ascending order.
+    pub l1_batches: Vec<ExportedL1Batch>,
+    /// Calldata of the `commitBatches` transaction committing all exported batches at once. `None` if the batches
+    /// cannot be committed by a single transaction because they use different pubdata sources. Note that
+    /// the node may commit batches in smaller groups; this doesn't influence per-batch commitment data.
+    pub commit_calldata: Option<Bytes>,
+}
+
+/// Commitment data for a single L1 batch.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExportedL1Batch {
+    pub number: L1BatchNumber,
+    pub protocol_version: ProtocolVersionId,
+    pub pubdata_da: PubdataDA,
+    /// Hash of the confirmed L1 transaction committing the batch. `None` if the batch isn't committed yet.
+    pub commit_tx_hash: Option<H256>,
+    pub params: CommitParams,
+    pub meta_parameters: L1BatchMetaParameters,
+    /// ABI encoding of the `CommitBatchInfo` struct for the batch, as passed to the L1 commit function.
+    pub commit_batch_info: Bytes,
+    /// Pubdata of the batch: L2-to-L1 logs, L2-to-L1 messages, published bytecodes and compressed state diffs.
+    pub pubdata: Bytes,
+    /// Compressed state diffs; the trailing part of `pubdata`.
+    pub state_diffs_compressed: Bytes,
+    /// Blobs with the pubdata. Empty unless the pubdata is published in blobs.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub blobs: Vec<ExportedBlob>,
+}
+
+/// Fields of `CommitBatchInfo` not derived from pubdata, plus the resulting batch commitment.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CommitParams {
+    pub timestamp: u64,
+    pub index_repeated_storage_changes: u64,
+    pub new_state_root: H256,
+    pub number_of_l1_txs: u16,
+    pub priority_operations_hash: H256,
+    pub bootloader_heap_initial_contents_hash: H256,
+    pub events_queue_state_hash: H256,
+    pub system_logs: Bytes,
+    pub l2_l1_merkle_root: H256,
+    pub commitment: H256,
+}
+
+/// EIP-4844 blob with pubdata, in the same format as in the sidecar of the commit transaction.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExportedBlob {
+    pub versioned_hash: H256,
+    pub kzg_commitment: Bytes,
+    /// KZG proof that the blob matches the commitment.
+    pub proof: Bytes,
+    pub blob: Bytes,
+}
+
+impl From<KzgInfo> for ExportedBlob {
+    fn from(kzg_info: KzgInfo) -> Self {
+        Self {
+            versioned_hash: H256(kzg_info.versioned_hash),
+            kzg_commitment: kzg_info.kzg_commitment.to_vec().into(),
+            proof: kzg_info.blob_proof.to_vec().into(),
+            blob: kzg_info.blob.to_vec().into(),
+        }
+    }
+}
+
+impl ExportedL1Batch {
+    fn new(
+        l1_batch: &L1BatchWithMetadata,
+        pubdata_da: PubdataDA,
+        commit_tx_hash: Option<H256>,
+    ) -> anyhow::Result<Self> {
+        let number = l1_batch.header.number;
+        let protocol_version = l1_batch
+            .header
+            .protocol_version
+            .with_context(|| format!("L1 batch #{number} doesn't have protocol version"))?;
+        anyhow::ensure!(
+            !protocol_version.is_pre_boojum(),
+            "Exporting pre-Boojum L1 batch #{number} is not supported"
+        );
+        let metadata = &l1_batch.metadata;
+        let (Some(bootloader_heap_hash), Some(events_queue_hash)) = (
+            metadata.bootloader_initial_content_commitment,
+            metadata.events_queue_commitment,
+        ) else {
+            anyhow::bail!(
+                "L1 batch #{number} doesn't have commitment metadata; is the tree fully synced?"
+            );
+        };
+        let pubdata_da = if protocol_version.is_pre_1_4_2() {
+            PubdataDA::Calldata
+        } else {
+            pubdata_da
+        };
+
+        let token = CommitBatchInfo::new(l1_batch, pubdata_da).into_token();
+        let pubdata = l1_batch
+            .header
+            .pubdata_input
+            .clone()
+            .unwrap_or_else(|| l1_batch.construct_pubdata());
+        let blobs = match pubdata_da {
+            PubdataDA::Blobs => KzgInfo::for_pubdata(&pubdata)
+                .into_iter()
+                .map(ExportedBlob::from)
+                .collect(),
+            PubdataDA::Calldata => vec![],
+        };
+
+        let header = &l1_batch.header;
+        Ok(Self {
+            number,
+            protocol_version,
+            pubdata_da,
+            commit_tx_hash,
+            params: CommitParams {
+                timestamp: header.timestamp,
+                index_repeated_storage_changes: metadata.rollup_last_leaf_index,
+                new_state_root: metadata.merkle_root_hash,
+                number_of_l1_txs: header.l1_tx_count,
+                priority_operations_hash: header.priority_ops_onchain_data_hash(),
+                bootloader_heap_initial_contents_hash: bootloader_heap_hash,
+                events_queue_state_hash: events_queue_hash,
+                system_logs: serialize_commitments(&header.system_logs).into(),
+                l2_l1_merkle_root: metadata.l2_l1_merkle_root,
+                commitment: metadata.commitment,
+            },
+            meta_parameters: metadata.block_meta_params.clone(),
+            commit_batch_info: ethabi::encode(&[token]).into(),
+            pubdata: pubdata.into(),
+            state_diffs_compressed: metadata.state_diffs_compressed.clone().into(),
+            blobs,
+        })
+    }
+
+    fn decode_commit_batch_info(
+        &self,
+        commit_function: &ethabi::Function,
+    ) -> anyhow::Result<Vec<ethabi::Token>> {
+        let param_type = commit_batch_info_type(commit_function)?;
+        let mut tokens = ethabi::decode(&[param_type], &self.commit_batch_info.0)
+            .context("Failed decoding `CommitBatchInfo`")?;
+        match tokens.pop() {
+            Some(ethabi::Token::Tuple(fields))
+                if fields.len() == COMMIT_BATCH_INFO_FIELDS.len() =>
+            {
+                Ok(fields)
+            }
+            _ => anyhow::bail!("Unexpected `CommitBatchInfo` encoding"),
+        }
+    }
+
+    /// Recomputes `pubdataCommitments` from the exported pubdata.
+    fn expected_pubdata_commitments(&self) -> Vec<u8> {
+        let pubdata = &self.pubdata.0;
+        if self.protocol_version.is_pre_1_4_2() {
+            return pubdata.clone();
+        }
+
+        let mut commitments = vec![self.pubdata_da as u8];
+        match self.pubdata_da {
+            PubdataDA::Calldata => {
+                commitments.extend_from_slice(pubdata);
+                for kzg_info in KzgInfo::for_pubdata(pubdata) {
+                    commitments.extend_from_slice(&kzg_info.to_blob_commitment());
+                }
+            }
+            PubdataDA::Blobs => {
+                for kzg_info in KzgInfo::for_pubdata(pubdata) {
+                    commitments.extend_from_slice(&kzg_info.to_pubdata_commitment());
+                }
+            }
+        }
+        commitments
+    }
+
+    /// Checks that the commitment data is correctly derived from the exported pubdata and params.
+    /// Returns descriptions of all detected errors.
+    fn check_derivation(&self, commit_function: &ethabi::Function) -> Vec<String> {
+        let mut errors = vec![];
+        if !self.pubdata.0.ends_with(&self.state_diffs_compressed.0) {
+            errors.push("pubdata doesn't end with compressed state diffs".to_owned());
+        }
+
+        let fields = match self.decode_commit_batch_info(commit_function) {
+            Ok(fields) => fields,
+            Err(err) => {
+                errors.push(format!("{err:#}"));
+                return errors;
+            }
+        };
+        let params = &self.params;
+        let expected_fields = [
+            ethabi::Token::Uint(self.number.0.into()),
+            ethabi::Token::Uint(params.timestamp.into()),
+            ethabi::Token::Uint(params.index_repeated_storage_changes.into()),
+            ethabi::Token::FixedBytes(params.new_state_root.as_bytes().to_vec()),
+            ethabi::Token::Uint(params.number_of_l1_txs.into()),
+            ethabi::Token::FixedBytes(params.priority_operations_hash.as_bytes().to_vec()),
+            ethabi::Token::FixedBytes(
+                params
+                    .bootloader_heap_initial_contents_hash
+                    .as_bytes()
+                    .to_vec(),
+            ),
+            ethabi::Token::FixedBytes(params.events_queue_state_hash.as_bytes().to_vec()),
+            ethabi::Token::Bytes(params.system_logs.0.clone()),
+            ethabi::Token::Bytes(self.expected_pubdata_commitments()),
+        ];
+        for ((name, actual), expected) in COMMIT_BATCH_INFO_FIELDS
+            .iter()
+            .zip(&fields)
+            .zip(&expected_fields)
+        {
+            if actual != expected {
+                errors.push(format!(
+                    "`{name}` in `CommitBatchInfo` is not derived from exported data"
+                ));
+            }
+        }
+
+        let expected_blobs: Vec<ExportedBlob> =
+            if self.pubdata_da == PubdataDA::Blobs && !self.protocol_version.is_pre_1_4_2() {
+                KzgInfo::for_pubdata(&self.pubdata.0)
+                    .into_iter()
+                    .map(ExportedBlob::from)
+                    .collect()
+            } else {
+                vec![]
+            };
+        if self.blobs != expected_blobs {
+            errors
+                .push("blobs or KZG commitments / proofs are not derived from pubdata".to_owned());
+        }
+        errors
+    }
+}
+
+/// Exports commitment data for the specified L1 batches from Postgres. `default_pubdata_da` is used for batches
+/// that aren't committed yet; for committed batches, the pubdata source is taken from the commit transaction.
+pub async fn export_pubdata(
+    storage: &mut StorageProcessor<'_>,
+    l1_batch_numbers: ops::RangeInclusive<L1BatchNumber>,
+    default_pubdata_da: PubdataDA,
+) -> anyhow::Result<PubdataExport> {
+    let (first_l1_batch, last_l1_batch) = l1_batch_numbers.into_inner();
+    anyhow::ensure!(
+        first_l1_batch <= last_l1_batch,
+        "Invalid L1 batch range: #{first_l1_batch}..=#{last_l1_batch}"
+    );
+    anyhow::ensure!(
+        first_l1_batch > L1BatchNumber(0),
+        "The genesis L1 batch is not committed on L1"
+    );
+
+    let last_committed_l1_batch = storage
+        .blocks_dal()
+        .get_l1_batch_metadata(first_l1_batch - 1)
+        .await?
+        .with_context(|| format!("L1 batch #{} doesn't have metadata", first_l1_batch - 1))?;
+
+    let mut l1_batches = vec![];
+    let mut exported_l1_batches = vec![];
+    for number in first_l1_batch.0..=last_l1_batch.0 {
+        let number = L1BatchNumber(number);
+        let storage_l1_batch = storage
+            .blocks_dal()
+            .get_storage_l1_batch(number)
+            .await?
+            .with_context(|| format!("L1 batch #{number} not found in Postgres"))?;
+
+        let (pubdata_da, commit_tx_hash) = match storage_l1_batch.eth_commit_tx_id {
+            Some(commit_tx_id) => {
+                let commit_tx_id = commit_tx_id as u32;
+                let commit_tx = storage
+                    .eth_sender_dal()
+                    .get_eth_tx(commit_tx_id)
+                    .await?
+                    .with_context(|| format!("Commit tx #{commit_tx_id} not found in Postgres"))?;
+                let pubdata_da = if commit_tx.blob_sidecar.is_some() {
+                    PubdataDA::Blobs
+                } else {
+                    PubdataDA::Calldata
+                };
+                let commit_tx_hash = storage
+                    .eth_sender_dal()
+                    .get_confirmed_tx_hash_by_eth_tx_id(commit_tx_id)
+                    .await?;
+                (pubdata_da, commit_tx_hash)
+            }
+            None => (default_pubdata_da, None),
+        };
+
+        let l1_batch = storage
+            .blocks_dal()
+            .get_l1_batch_with_metadata(storage_l1_batch)
+            .await?
+            .with_context(|| format!("L1 batch #{number} doesn't have metadata"))?;
+        let exported = ExportedL1Batch::new(&l1_batch, pubdata_da, commit_tx_hash)
+            .with_context(|| format!("Failed exporting L1 batch #{number}"))?;
+        exported_l1_batches.push(exported);
+        l1_batches.push(l1_batch);
+    }
+
+    let pubdata_da = exported_l1_batches[0].pubdata_da;
+    let commit_calldata = exported_l1_batches
+        .iter()
+        .all(|batch| batch.pubdata_da == pubdata_da)
+        .then(|| {
+            let op = CommitBatches {
+                last_committed_l1_batch,
+                l1_batches,
+                pubdata_da,
+            };
+            commit_function().encode_input(&op.into_tokens())
+        })
+        .transpose()
+        .context("Failed encoding commit calldata")?;
+
+    Ok(PubdataExport {
+        l1_batches: exported_l1_batches,
+        commit_calldata: commit_calldata.map(Bytes),
+    })
+}
+
+/// Result of checking an exported L1 batch against L1.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum L1CheckResult {
+    /// The batch wasn't checked against L1.
+    Skipped,
+    /// The batch isn't committed on L1 according to the export.
+    NotCommitted,
+    Consistent {
+        commit_tx_hash: H256,
+    },
+    /// `CommitBatchInfo` for the batch differs from the one in the commit transaction calldata.
+    Inconsistent {
+        commit_tx_hash: H256,
+        mismatched_fields: Vec<&'static str>,
+    },
+    /// The commit transaction is missing, failed or cannot be decoded.
+    Failed {
+        commit_tx_hash: H256,
+        error: String,
+    },
+}
+
+/// Verification result for a single exported L1 batch.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct L1BatchVerification {
+    pub number: L1BatchNumber,
+    /// Errors detected when re-deriving commitment data from the exported pubdata.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub derivation_errors: Vec<String>,
+    pub l1_check: L1CheckResult,
+}
+
+/// Report produced by [`PubdataVerifier`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PubdataVerificationReport {
+    pub l1_batches: Vec<L1BatchVerification>,
+}
+
+impl PubdataVerificationReport {
+    /// Returns numbers of L1 batches that have failed verification.
+    pub fn failed_l1_batches(&self) -> Vec<L1BatchNumber> {
+        self.l1_batches
+            .iter()
+            .filter(|batch| {
+                !batch.derivation_errors.is_empty()
+                    || matches!(
+                        batch.l1_check,
+                        L1CheckResult::Inconsistent { .. } | L1CheckResult::Failed { .. }
+                    )
+            })
+            .map(|batch| batch.number)
+            .collect()
+    }
+}
+
+async fn check_on_l1(
+    l1_client: &dyn EthInterface,
+    commit_function: &ethabi::Function,
+    l1_batch: &ExportedL1Batch,
+) -> anyhow::Result<L1CheckResult> {
+    let Some(commit_tx_hash) = l1_batch.commit_tx_hash else {
+        return Ok(L1CheckResult::NotCommitted);
+    };
+    let failed = |error: String| L1CheckResult::Failed {
+        commit_tx_hash,
+        error,
+    };
+
+    let commit_tx_status = l1_client
+        .get_tx_status(commit_tx_hash, "pubdata_export")
+        .await
+        .with_context(|| format!("Failed getting status of tx {commit_tx_hash:?}"))?;
+    match commit_tx_status {
+        None => return Ok(failed("commit tx receipt not found on L1".to_owned())),
+        Some(status) if !status.success => return Ok(failed("commit tx failed on L1".to_owned())),
+        Some(_) => { /* fall through */ }
+    }
+    let commit_tx = l1_client
+        .get_tx(commit_tx_hash, "pubdata_export")
+        .await
+        .with_context(|| format!("Failed getting tx {commit_tx_hash:?}"))?;
+    let Some(commit_tx) = commit_tx else {
+        return Ok(failed("commit tx not found on L1".to_owned()));
+    };
+
+    let l1_commitment = ConsistencyChecker::extract_commit_data(
+        &commit_tx.input.0,
+        commit_function,
+        l1_batch.number,
+    );
+    let l1_fields = match l1_commitment {
+        Ok(ethabi::Token::Tuple(fields)) => fields,
+        Ok(_) => {
+            return Ok(failed(
+                "unexpected signature for L1 commit function".to_owned(),
+            ))
+        }
+        Err(err) => return Ok(failed(format!("{err:#}"))),
+    };
+    let local_fields = match l1_batch.decode_commit_batch_info(commit_function) {
+        Ok(fields) => fields,
+        Err(err) => return Ok(failed(format!("{err:#}"))),
+    };
+    if l1_fields.len() != local_fields.len() {
+        return Ok(failed(
+            "unexpected signature for L1 commit function".to_owned(),
+        ));
+    }
+
+    let mismatched_fields: Vec<_> = COMMIT_BATCH_INFO_FIELDS
+        .iter()
+        .zip(l1_fields.iter().zip(&local_fields))
+        .filter_map(|(&name, (l1_field, local_field))| (l1_field != local_field).then_some(name))
+        .collect();
+    Ok(if mismatched_fields.is_empty() {
+        L1CheckResult::Consistent { commit_tx_hash }
+    } else {
+        L1CheckResult::Inconsistent {
+            commit_tx_hash,
+            mismatched_fields,
+        }
+    })
+}
+
+/// Verifies exports produced by [`export_pubdata()`] without accessing the node storage.
+#[derive(Debug)]
+pub struct PubdataVerifier {
+    commit_function: ethabi::Function,
+    l1_client: Option<Box<dyn EthInterface>>,
+}
+
+impl PubdataVerifier {
+    /// Creates a verifier. If `web3_url` is provided, commitment data for each committed batch is additionally
+    /// compared with the calldata of its commit transaction on L1.
+    pub fn new(web3_url: Option<&str>) -> anyhow::Result<Self> {
+        let l1_client = web3_url
+            .map(QueryClient::new)
+            .transpose()
+            .context("Failed creating L1 client")?;
+        Ok(Self {
+            commit_function: commit_function(),
+            l1_client: l1_client.map(|client| Box::new(client) as Box<dyn EthInterface>),
+        })
+    }
+
+    pub async fn verify(
+        &self,
+        export: &PubdataExport,
+    ) -> anyhow::Result<PubdataVerificationReport> {
+        let mut l1_batches = Vec::with_capacity(export.l1_batches.len());
+        for l1_batch in &export.l1_batches {
+            let verification = self
+                .verify_l1_batch(l1_batch)
+                .await
+                .with_context(|| format!("Failed verifying L1 batch #{}", l1_batch.number))?;
+            l1_batches.push(verification);
+        }
+        Ok(PubdataVerificationReport { l1_batches })
+    }
+
+    async fn verify_l1_batch(
+        &self,
+        l1_batch: &ExportedL1Batch,
+    ) -> anyhow::Result<L1BatchVerification> {
+        let derivation_errors = l1_batch.check_derivation(&self.commit_function);
+        let l1_check = match &self.l1_client {
+            Some(client) => check_on_l1(client.as_ref(), &self.commit_function, l1_batch).await?,
+            None => L1CheckResult::Skipped,
+        };
+
+        if !derivation_errors.is_empty() {
+            tracing::warn!(
+                "L1 batch #{} has errors in exported data: {derivation_errors:?}",
+                l1_batch.number
+            );
+        }
+        if let L1CheckResult::Inconsistent { .. } | L1CheckResult::Failed { .. } = &l1_check {
+            tracing::warn!(
+                "L1 batch #{} is inconsistent with L1: {l1_check:?}",
+                l1_batch.number
+            );
+        }
+        Ok(L1BatchVerification {
+            number: l1_batch.number,
+            derivation_errors,
+            l1_check,
+        })
+    }
+}
@@ -0,0 +1,266 @@
+//! Tests for pubdata export and verification.
+
+use zksync_dal::ConnectionPool;
+use zksync_eth_client::clients::MockEthereum;
+use zksync_l1_contract_interface::i_executor::structures::StoredBatchInfo;
+use zksync_types::{
+    aggregated_operations::AggregatedActionType, web3::contract::Options, L2ChainId,
+};
+
+use super::*;
+use crate::{
+    genesis::{ensure_genesis_state, GenesisParams},
+    utils::testonly::{
+        create_l1_batch, create_l1_batch_metadata, l1_batch_metadata_to_commitment_artifacts,
+    },
+};
+
+fn create_l1_batch_with_metadata(number: u32) -> L1BatchWithMetadata {
+    L1BatchWithMetadata {
+        header: create_l1_batch(number),
+        metadata: create_l1_batch_metadata(number),
+        raw_published_factory_deps: vec![],
+    }
+}
+
+async fn save_l1_batch(storage: &mut StorageProcessor<'_>, l1_batch: &L1BatchWithMetadata) {
+    let number = l1_batch.header.number;
+    storage
+        .blocks_dal()
+        .insert_mock_l1_batch(&l1_batch.header)
+        .await
+        .unwrap();
+    storage
+        .blocks_dal()
+        .save_l1_batch_tree_data(number, &l1_batch.metadata.tree_data())
+        .await
+        .unwrap();
+    storage
+        .blocks_dal()
+        .save_l1_batch_commitment_artifacts(
+            number,
+            &l1_batch_metadata_to_commitment_artifacts(&l1_batch.metadata),
+        )
+        .await
+        .unwrap();
+}
+
+/// Sends a commit tx for `l1_batches` to the mock L1 and marks the batches as committed in Postgres.
+async fn commit_l1_batches(
+    client: &MockEthereum,
+    storage: &mut StorageProcessor<'_>,
+    l1_batches: &[L1BatchWithMetadata],
+    nonce: u64,
+) -> H256 {
+    let op = CommitBatches {
+        last_committed_l1_batch: l1_batches[0].clone(), // not checked
+        l1_batches: l1_batches.to_vec(),
+        pubdata_da: PubdataDA::Calldata,
+    };
+    let input_data = commit_function().encode_input(&op.into_tokens()).unwrap();
+    let options = Options {
+        nonce: Some(nonce.into()),
+        ..Options::default()
+    };
+    let signed_tx = client.sign_prepared_tx(input_data, options).unwrap();
+    client.send_raw_tx(signed_tx.raw_tx).await.unwrap();
+    client.execute_tx(signed_tx.hash, true, 1);
+
+    for l1_batch in l1_batches {
+        storage
+            .eth_sender_dal()
+            .insert_bogus_confirmed_eth_tx(
+                l1_batch.header.number,
+                AggregatedActionType::Commit,
+                signed_tx.hash,
+                chrono::Utc::now(),
+            )
+            .await
+            .unwrap();
+    }
+    signed_tx.hash
+}
+
+async fn prepare_storage(pool: &ConnectionPool) -> Vec<L1BatchWithMetadata> {
+    let mut storage = pool.access_storage().await.unwrap();
+    ensure_genesis_state(&mut storage, L2ChainId::default(), &GenesisParams::mock())
+        .await
+        .unwrap();
+    let l1_batches: Vec<_> = (1..=3).map(create_l1_batch_with_metadata).collect();
+    for l1_batch in &l1_batches {
+        save_l1_batch(&mut storage, l1_batch).await;
+    }
+    l1_batches
+}
+
+#[test]
+fn exported_commit_batch_info_matches_l1_encoding() {
+    let l1_batch = create_l1_batch_with_metadata(1);
+    for pubdata_da in [PubdataDA::Calldata, PubdataDA::Blobs] {
+        let exported = ExportedL1Batch::new(&l1_batch, pubdata_da, None).unwrap();
+        let fields = exported
+            .decode_commit_batch_info(&commit_function())
+            .unwrap();
+        assert_eq!(
+            ethabi::Token::Tuple(fields),
+            CommitBatchInfo::new(&l1_batch, pubdata_da).into_token()
+        );
+        let errors = exported.check_derivation(&commit_function());
+        assert!(errors.is_empty(), "{errors:?}");
+        assert_eq!(exported.blobs.is_empty(), pubdata_da == PubdataDA::Calldata);
+
+        let json = serde_json::to_value(&exported).unwrap();
+        let restored: ExportedL1Batch = serde_json::from_value(json).unwrap();
+        assert_eq!(restored, exported);
+    }
+}
+
+#[test]
+fn detecting_tampered_export() {
+    let l1_batch = create_l1_batch_with_metadata(1);
+    let exported = ExportedL1Batch::new(&l1_batch, PubdataDA::Blobs, None).unwrap();
+
+    let mut tampered = exported.clone();
+    tampered.params.timestamp += 1;
+    let errors = tampered.check_derivation(&commit_function());
+    assert_eq!(errors.len(), 1, "{errors:?}");
+    assert!(errors[0].contains("`timestamp`"), "{errors:?}");
+
+    let mut tampered = exported.clone();
+    tampered.state_diffs_compressed.0.push(1);
+    let errors = tampered.check_derivation(&commit_function());
+    assert_eq!(errors.len(), 1, "{errors:?}");
+    assert!(errors[0].contains("compressed state diffs"), "{errors:?}");
+
+    let mut tampered = exported.clone();
+    tampered.pubdata.0.push(1);
+    let errors = tampered.check_derivation(&commit_function());
+    assert_eq!(errors.len(), 2, "{errors:?}");
+    assert!(errors[0].contains("`pubdataCommitments`"), "{errors:?}");
+    assert!(errors[1].contains("blobs"), "{errors:?}");
+
+    let mut tampered = exported;
+    tampered.commit_batch_info.0.truncate(10);
+    let errors = tampered.check_derivation(&commit_function());
+    assert_eq!(errors.len(), 1, "{errors:?}");
+    assert!(errors[0].contains("Failed decoding"), "{errors:?}");
+}
+
+#[tokio::test]
+async fn exporting_and_verifying_pubdata() {
+    let pool = ConnectionPool::test_pool().await;
+    let l1_batches = prepare_storage(&pool).await;
+    let client = MockEthereum::default();
+    let mut storage = pool.access_storage().await.unwrap();
+    let commit_tx_hash = commit_l1_batches(&client, &mut storage, &l1_batches[..2], 0).await;
+
+    let export = export_pubdata(
+        &mut storage,
+        L1BatchNumber(1)..=L1BatchNumber(3),
+        PubdataDA::Blobs,
+    )
+    .await
+    .unwrap();
+    assert_eq!(export.l1_batches.len(), 3);
+    for (exported, l1_batch) in export.l1_batches.iter().zip(&l1_batches[..2]) {
+        assert_eq!(exported.number, l1_batch.header.number);
+        assert_eq!(exported.pubdata_da, PubdataDA::Calldata);
+        assert_eq!(exported.commit_tx_hash, Some(commit_tx_hash));
+        assert!(exported.blobs.is_empty());
+    }
+    // The last batch is not committed, so it uses the default pubdata source.
+    let uncommitted = &export.l1_batches[2];
+    assert_eq!(uncommitted.pubdata_da, PubdataDA::Blobs);
+    assert_eq!(uncommitted.commit_tx_hash, None);
+    assert_eq!(uncommitted.blobs.len(), 1);
+    // Batches use different pubdata sources, so they cannot be committed in a single tx.
+    assert_eq!(export.commit_calldata, None);
+
+    let verifier = PubdataVerifier {
+        l1_client: Some(Box::new(client)),
+        ..PubdataVerifier::new(None).unwrap()
+    };
+    let report = verifier.verify(&export).await.unwrap();
+    assert!(report.failed_l1_batches().is_empty(), "{report:?}");
+    let l1_checks: Vec<_> = report
+        .l1_batches
+        .iter()
+        .map(|batch| batch.l1_check.clone())
+        .collect();
+    assert_eq!(
+        l1_checks,
+        [
+            L1CheckResult::Consistent { commit_tx_hash },
+            L1CheckResult::Consistent { commit_tx_hash },
+            L1CheckResult::NotCommitted,
+        ]
+    );
+
+    let report = PubdataVerifier::new(None)
+        .unwrap()
+        .verify(&export)
+        .await
+        .unwrap();
+    assert!(report
+        .l1_batches
+        .iter()
+        .all(|batch| batch.l1_check == L1CheckResult::Skipped));
+}
+
+#[tokio::test]
+async fn exported_commit_calldata_is_correct() {
+    let pool = ConnectionPool::test_pool().await;
+    let l1_batches = prepare_storage(&pool).await;
+    let mut storage = pool.access_storage().await.unwrap();
+
+    let export = export_pubdata(
+        &mut storage,
+        L1BatchNumber(2)..=L1BatchNumber(3),
+        PubdataDA::Calldata,
+    )
+    .await
+    .unwrap();
+    let calldata = export.commit_calldata.unwrap();
+    let mut tokens = commit_function().decode_input(&calldata.0[4..]).unwrap();
+    let ethabi::Token::Array(commitments) = tokens.pop().unwrap() else {
+        panic!("unexpected tokens: {tokens:?}");
+    };
+    let expected_commitments: Vec<_> = l1_batches[1..]
+        .iter()
+        .map(|batch| CommitBatchInfo::new(batch, PubdataDA::Calldata).into_token())
+        .collect();
+    assert_eq!(commitments, expected_commitments);
+    assert_eq!(tokens, [StoredBatchInfo(&l1_batches[0]).into_token()]);
+}
+
+#[tokio::test]
+async fn detecting_inconsistency_with_l1() {
+    let pool = ConnectionPool::test_pool().await;
+    let mut l1_batches = prepare_storage(&pool).await;
+    let client = MockEthereum::default();
+    let mut storage = pool.access_storage().await.unwrap();
+    // Emulate the state diverged from the state committed on L1.
+    l1_batches[0].header.timestamp += 1;
+    let commit_tx_hash = commit_l1_batches(&client, &mut storage, &l1_batches[..1], 0).await;
+
+    let export = export_pubdata(
+        &mut storage,
+        L1BatchNumber(1)..=L1BatchNumber(1),
+        PubdataDA::Calldata,
+    )
+    .await
+    .unwrap();
+    let verifier = PubdataVerifier {
+        l1_client: Some(Box::new(client)),
+        ..PubdataVerifier::new(None).unwrap()
+    };
+    let report = verifier.verify(&export).await.unwrap();
+    assert_eq!(report.failed_l1_batches(), [L1BatchNumber(1)]);
+    assert_eq!(
+        report.l1_batches[0].l1_check,
+        L1CheckResult::Inconsistent {
+            commit_tx_hash,
+            mismatched_fields: vec!["timestamp"],
+        }
+    );
+}
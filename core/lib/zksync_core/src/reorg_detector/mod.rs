@@ -38,6 +38,18 @@ enum HashMatchError {
         Using an earlier snapshot could help."
     )]
     EarliestL1BatchTruncated(L1BatchNumber),
+    #[error(
+        "Unrecoverable error: reorg requires rolling back {depth} L1 batches (from #{sealed_l1_batch} \
+        to #{last_correct_l1_batch}), which exceeds the maximum allowed depth {max_depth}. \
+        Make sure you're connected to the right network; the rollback can be performed manually \
+        using the block reverter."
+    )]
+    RollbackTooDeep {
+        sealed_l1_batch: L1BatchNumber,
+        last_correct_l1_batch: L1BatchNumber,
+        depth: u32,
+        max_depth: u32,
+    },
     #[error("Internal error")]
     Internal(#[from] anyhow::Error),
 }
@@ -195,8 +207,11 @@ impl MatchOutput {
 /// We then perform a binary search to find the latest correct block
 /// and revert all batches after it, to keep being consistent with the main node.
 ///
+/// The rollback depth can be bounded using [`Self::with_max_rollback_depth()`]; deeper reorgs are treated
+/// as unrecoverable errors and require manual intervention.
+///
 /// This is the only component that is expected to finish its execution
-/// in the even of re-org, since we have to restart the node after a rollback is performed,
+/// in the even of re-org, since all node components have to be restarted after a rollback is performed,
 /// and is special-cased in the `zksync_external_node` crate.
 #[derive(Debug)]
 pub struct ReorgDetector {
@@ -204,6 +219,7 @@ pub struct ReorgDetector {
     event_handler: Box<dyn HandleReorgDetectorEvent>,
     pool: ConnectionPool,
     sleep_interval: Duration,
+    max_rollback_depth: Option<u32>,
     health_check: ReactiveHealthCheck,
 }
 
@@ -217,10 +233,18 @@ impl ReorgDetector {
             event_handler: Box::new(health_updater),
             pool,
             sleep_interval: Self::DEFAULT_SLEEP_INTERVAL,
+            max_rollback_depth: None,
             health_check,
         }
     }
 
+    /// Sets the maximum number of L1 batches that can be rolled back after a detected reorg.
+    #[must_use]
+    pub fn with_max_rollback_depth(mut self, max_rollback_depth: u32) -> Self {
+        self.max_rollback_depth = Some(max_rollback_depth);
+        self
+    }
+
     pub fn health_check(&self) -> &ReactiveHealthCheck {
         &self.health_check
     }
@@ -365,6 +389,34 @@ impl ReorgDetector {
         .map(L1BatchNumber)
     }
 
+    /// Checks that rolling back to `last_correct_l1_batch` doesn't exceed the maximum allowed rollback depth.
+    async fn check_rollback_depth(
+        &self,
+        last_correct_l1_batch: L1BatchNumber,
+    ) -> Result<(), HashMatchError> {
+        let Some(max_depth) = self.max_rollback_depth else {
+            return Ok(());
+        };
+        let mut storage = self.pool.access_storage().await?;
+        let sealed_l1_batch = storage
+            .blocks_dal()
+            .get_sealed_l1_batch_number()
+            .await?
+            .context("L1 batches table unexpectedly emptied")?;
+        drop(storage);
+
+        let depth = sealed_l1_batch.0.saturating_sub(last_correct_l1_batch.0);
+        if depth > max_depth {
+            return Err(HashMatchError::RollbackTooDeep {
+                sealed_l1_batch,
+                last_correct_l1_batch,
+                depth,
+                max_depth,
+            });
+        }
+        Ok(())
+    }
+
     pub async fn run(
         mut self,
         mut stop_receiver: watch::Receiver<bool>,
@@ -465,6 +517,7 @@ impl ReorgDetector {
                 tracing::info!(
                     "Reorg localized: last correct L1 batch is #{last_correct_l1_batch}"
                 );
+                self.check_rollback_depth(last_correct_l1_batch).await?;
                 return Ok(Some(last_correct_l1_batch));
             }
 
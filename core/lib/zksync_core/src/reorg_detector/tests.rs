@@ -171,6 +171,7 @@ fn create_mock_detector(client: MockMainNodeClient, pool: ConnectionPool) -> Reo
         event_handler: Box::new(health_updater),
         pool,
         sleep_interval: Duration::from_millis(10),
+        max_rollback_depth: None,
         health_check,
     }
 }
@@ -323,6 +324,48 @@ async fn reorg_is_detected_on_batch_hash_mismatch() {
     assert_eq!(last_correct_l1_batch, Some(L1BatchNumber(1)));
 }
 
+#[test_casing(2, [3, 2])]
+#[tokio::test]
+async fn reorg_rollback_depth_is_bounded(max_rollback_depth: u32) {
+    let pool = ConnectionPool::test_pool().await;
+    let mut storage = pool.access_storage().await.unwrap();
+    let genesis_root_hash =
+        ensure_genesis_state(&mut storage, L2ChainId::default(), &GenesisParams::mock())
+            .await
+            .unwrap();
+    let mut client = MockMainNodeClient::default();
+    client.miniblock_hash_responses.insert(
+        MiniblockNumber(0),
+        MiniblockHasher::legacy_hash(MiniblockNumber(0)),
+    );
+    client
+        .l1_batch_root_hash_responses
+        .insert(L1BatchNumber(0), genesis_root_hash);
+    for number in 1..=3 {
+        let miniblock_hash = H256::from_low_u64_be(number.into());
+        client
+            .miniblock_hash_responses
+            .insert(MiniblockNumber(number), miniblock_hash);
+        client
+            .l1_batch_root_hash_responses
+            .insert(L1BatchNumber(number), H256::repeat_byte(number as u8));
+        store_miniblock(&mut storage, number, miniblock_hash).await;
+        // L1 batches starting from #1 diverge from the main node.
+        seal_l1_batch(&mut storage, number, H256::repeat_byte(0xff)).await;
+    }
+
+    let (_stop_sender, stop_receiver) = watch::channel(false);
+    let detector =
+        create_mock_detector(client, pool.clone()).with_max_rollback_depth(max_rollback_depth);
+    let result = detector.run(stop_receiver).await;
+    if max_rollback_depth >= 3 {
+        assert_eq!(result.unwrap(), Some(L1BatchNumber(0)));
+    } else {
+        let err = result.unwrap_err().to_string();
+        assert!(err.contains("exceeds the maximum allowed depth"), "{err}");
+    }
+}
+
 #[tokio::test]
 async fn reorg_is_detected_on_miniblock_hash_mismatch() {
     let pool = ConnectionPool::test_pool().await;
@@ -0,0 +1,311 @@
+//! Coordinated graceful shutdown of the node.
+//!
+//! Components that must finish their work before the node process exits (e.g., the state keeper persisting
+//! the in-progress miniblock) are wrapped with [`ShutdownTracker::track()`]. On shutdown, the tracker sends the stop
+//! signal to all components and waits until tracked components stop and all RocksDB instances are closed, but no longer
+//! than the configured deadline. Per-component shutdown latency is reported both as metrics (`server_shutdown_*`)
+//! and as log events.
+
+use std::{
+    future::Future,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use tokio::{
+    sync::{oneshot, watch},
+    task::JoinHandle,
+};
+use vise::{Gauge, LabeledFamily, Metrics};
+use zksync_storage::RocksDB;
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "server_shutdown")]
+struct ShutdownMetrics {
+    /// Time since the stop signal until a tracked component has stopped.
+    #[metrics(labels = ["component"])]
+    component_latency: LabeledFamily<&'static str, Gauge<Duration>>,
+    /// Number of tracked components that haven't stopped by the shutdown deadline.
+    timed_out_components: Gauge<u64>,
+    /// Time since the stop signal until all RocksDB instances are closed.
+    rocksdb_latency: Gauge<Duration>,
+    /// Total duration of the graceful shutdown.
+    latency: Gauge<Duration>,
+}
+
+#[vise::register]
+static METRICS: vise::Global<ShutdownMetrics> = vise::Global::new();
+
+#[derive(Debug, Default)]
+struct ShutdownState {
+    started_at: Option<Instant>,
+    /// Tracked components together with their shutdown latency (`None` if the component is still running).
+    components: Vec<(&'static str, Option<Duration>)>,
+}
+
+impl ShutdownState {
+    fn all_stopped(&self) -> bool {
+        self.components.iter().all(|(_, latency)| latency.is_some())
+    }
+}
+
+/// Marks a tracked component as stopped when dropped. This covers both the component task finishing
+/// and it being dropped, e.g., on panic.
+#[derive(Debug)]
+struct StopGuard {
+    state: Arc<watch::Sender<ShutdownState>>,
+    index: usize,
+}
+
+impl Drop for StopGuard {
+    fn drop(&mut self) {
+        self.state.send_modify(|state| {
+            let latency = state
+                .started_at
+                .map_or(Duration::ZERO, |time| time.elapsed());
+            state.components[self.index].1.get_or_insert(latency);
+        });
+    }
+}
+
+/// Outcome of [`ShutdownTracker::shutdown()`].
+#[derive(Debug)]
+pub struct ShutdownReport {
+    /// Total latency of the shutdown.
+    pub latency: Duration,
+    /// Shutdown latency for tracked components that have stopped by the deadline.
+    pub stopped_components: Vec<(&'static str, Duration)>,
+    /// Tracked components that haven't stopped by the deadline.
+    pub timed_out_components: Vec<&'static str>,
+    /// Time until all RocksDB instances are closed, or `None` if they weren't closed by the deadline.
+    pub rocksdb_latency: Option<Duration>,
+}
+
+impl ShutdownReport {
+    /// Checks whether all tracked components and RocksDB instances were stopped by the deadline.
+    pub fn is_complete(&self) -> bool {
+        self.timed_out_components.is_empty() && self.rocksdb_latency.is_some()
+    }
+
+    fn report(&self) {
+        for &(component, latency) in &self.stopped_components {
+            METRICS.component_latency[&component].set(latency);
+            tracing::info!(
+                component,
+                latency_ms = latency.as_millis() as u64,
+                "Component `{component}` stopped in {latency:?}"
+            );
+        }
+        for &component in &self.timed_out_components {
+            tracing::warn!(
+                component,
+                "Component `{component}` didn't stop by the shutdown deadline"
+            );
+        }
+        METRICS
+            .timed_out_components
+            .set(self.timed_out_components.len() as u64);
+
+        if let Some(latency) = self.rocksdb_latency {
+            METRICS.rocksdb_latency.set(latency);
+            tracing::info!(
+                latency_ms = latency.as_millis() as u64,
+                "RocksDB instances closed in {latency:?}"
+            );
+        } else {
+            tracing::warn!("RocksDB instances weren't closed by the shutdown deadline");
+        }
+        METRICS.latency.set(self.latency);
+    }
+}
+
+/// Tracks graceful shutdown of node components. Cloning the tracker is cheap; all clones share the same state.
+#[derive(Debug, Clone)]
+pub struct ShutdownTracker {
+    state: Arc<watch::Sender<ShutdownState>>,
+}
+
+impl Default for ShutdownTracker {
+    fn default() -> Self {
+        let (state, _) = watch::channel(ShutdownState::default());
+        Self {
+            state: Arc::new(state),
+        }
+    }
+}
+
+impl ShutdownTracker {
+    /// Wraps a component task so that [`Self::shutdown()`] waits for it to finish.
+    pub fn track<F: Future>(
+        &self,
+        component: &'static str,
+        task: F,
+    ) -> impl Future<Output = F::Output> {
+        let mut index = 0;
+        self.state.send_modify(|state| {
+            index = state.components.len();
+            state.components.push((component, None));
+        });
+        let guard = StopGuard {
+            state: self.state.clone(),
+            index,
+        };
+        async move {
+            let output = task.await;
+            drop(guard);
+            output
+        }
+    }
+
+    /// Tracks an already spawned component task. Returns a handle that completes together with the original task;
+    /// panics in the original task are propagated.
+    pub fn track_task<T: Send + 'static>(
+        &self,
+        component: &'static str,
+        task: JoinHandle<T>,
+    ) -> JoinHandle<T> {
+        tokio::spawn(self.track(component, async move {
+            match task.await {
+                Ok(output) => output,
+                Err(err) => match err.try_into_panic() {
+                    Ok(panic) => std::panic::resume_unwind(panic),
+                    Err(err) => panic!("component `{component}` was cancelled: {err}"),
+                },
+            }
+        }))
+    }
+
+    /// Sends the stop signal to all components and waits until tracked components stop and all RocksDB instances
+    /// are closed, but no longer than `timeout`.
+    pub async fn shutdown(
+        &self,
+        stop_sender: &watch::Sender<bool>,
+        timeout: Duration,
+    ) -> ShutdownReport {
+        let started_at = Instant::now();
+        self.state
+            .send_modify(|state| state.started_at = Some(started_at));
+        stop_sender.send(true).ok();
+        tracing::info!("Waiting up to {timeout:?} for node components to stop");
+
+        let mut state_receiver = self.state.subscribe();
+        let components_stopped = state_receiver.wait_for(ShutdownState::all_stopped);
+        // The timeout error is reported below for specific components.
+        tokio::time::timeout(timeout, components_stopped).await.ok();
+
+        // Components may keep RocksDB instances after they have stopped, so we wait for them separately.
+        // `await_rocksdb_termination()` is run on a dedicated thread since it may block indefinitely.
+        let (rocksdb_sender, rocksdb_receiver) = oneshot::channel();
+        std::thread::spawn(move || {
+            RocksDB::await_rocksdb_termination();
+            rocksdb_sender.send(()).ok();
+        });
+        let remaining_time = timeout.saturating_sub(started_at.elapsed());
+        let rocksdb_latency = tokio::time::timeout(remaining_time, rocksdb_receiver)
+            .await
+            .ok()
+            .map(|_| started_at.elapsed());
+
+        let components = self.state.borrow().components.clone();
+        let report = ShutdownReport {
+            latency: started_at.elapsed(),
+            stopped_components: components
+                .iter()
+                .filter_map(|&(component, latency)| Some((component, latency?)))
+                .collect(),
+            timed_out_components: components
+                .iter()
+                .filter_map(|&(component, latency)| latency.is_none().then_some(component))
+                .collect(),
+            rocksdb_latency,
+        };
+        report.report();
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn stoppable_task(mut stop_receiver: watch::Receiver<bool>, delay: Duration) {
+        stop_receiver.changed().await.unwrap();
+        tokio::time::sleep(delay).await;
+    }
+
+    #[tokio::test]
+    async fn tracking_component_shutdown() {
+        let tracker = ShutdownTracker::default();
+        let (stop_sender, stop_receiver) = watch::channel(false);
+        let fast_task = tracker.track(
+            "fast",
+            stoppable_task(stop_receiver.clone(), Duration::ZERO),
+        );
+        let fast_task = tokio::spawn(fast_task);
+        let slow_task = tracker.track(
+            "slow",
+            stoppable_task(stop_receiver.clone(), Duration::from_millis(50)),
+        );
+        let slow_task = tokio::spawn(slow_task);
+        let finished_task = tracker.track("finished", async {});
+        finished_task.await;
+
+        let report = tracker.shutdown(&stop_sender, Duration::from_secs(2)).await;
+        assert!(report.timed_out_components.is_empty(), "{report:?}");
+        let stopped: Vec<_> = report
+            .stopped_components
+            .iter()
+            .map(|&(component, _)| component)
+            .collect();
+        assert_eq!(stopped, ["fast", "slow", "finished"]);
+        let (_, slow_latency) = report.stopped_components[1];
+        assert!(slow_latency >= Duration::from_millis(50), "{report:?}");
+        let (_, finished_latency) = report.stopped_components[2];
+        assert_eq!(finished_latency, Duration::ZERO);
+
+        fast_task.await.unwrap();
+        slow_task.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn reporting_components_not_stopped_by_deadline() {
+        let tracker = ShutdownTracker::default();
+        let (stop_sender, stop_receiver) = watch::channel(false);
+        let stuck_task = tracker.track("stuck", futures::future::pending::<()>());
+        let stuck_task = tokio::spawn(stuck_task);
+        let task = tracker.track("normal", stoppable_task(stop_receiver, Duration::ZERO));
+        tokio::spawn(task);
+
+        let report = tracker
+            .shutdown(&stop_sender, Duration::from_millis(100))
+            .await;
+        assert!(!report.is_complete());
+        assert_eq!(report.timed_out_components, ["stuck"]);
+        assert_eq!(report.stopped_components.len(), 1);
+        assert_eq!(report.stopped_components[0].0, "normal");
+
+        // The component is marked as stopped if its task is dropped, e.g. on panic.
+        stuck_task.abort();
+        stuck_task.await.unwrap_err();
+        assert!(tracker.state.borrow().all_stopped());
+    }
+
+    #[tokio::test]
+    async fn tracking_spawned_tasks() {
+        let tracker = ShutdownTracker::default();
+        let (stop_sender, stop_receiver) = watch::channel(false);
+        let task = tokio::spawn(async move {
+            stoppable_task(stop_receiver, Duration::from_millis(10)).await;
+            42
+        });
+        let task = tracker.track_task("spawned", task);
+        let panicking_task =
+            tracker.track_task("panicking", tokio::spawn(async { panic!("oops") }));
+
+        let report = tracker.shutdown(&stop_sender, Duration::from_secs(2)).await;
+        assert!(report.timed_out_components.is_empty(), "{report:?}");
+        assert_eq!(report.stopped_components.len(), 2);
+        assert_eq!(task.await.unwrap(), 42);
+        assert!(panicking_task.await.unwrap_err().is_panic());
+    }
+}
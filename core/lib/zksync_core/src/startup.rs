@@ -0,0 +1,226 @@
+//! Self-monitoring of the node startup sequence.
+//!
+//! Startup phases are reported both as metrics (`server_startup_*`) and as log events with structured fields,
+//! so that it's possible to find out which phase made a restart slow.
+
+use std::{
+    fmt,
+    str::FromStr,
+    sync::atomic::{AtomicBool, Ordering},
+    time::{Duration, Instant},
+};
+
+use anyhow::Context as _;
+use once_cell::sync::OnceCell;
+use vise::{EncodeLabelSet, EncodeLabelValue, Family, Gauge, LabeledFamily, Metrics};
+use zksync_dal::ConnectionPool;
+
+/// Phase of the node startup sequence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "phase", rename_all = "snake_case")]
+pub enum StartupPhase {
+    /// Loading and validating configs, including fetching remote configs and resolving secrets.
+    ConfigLoading,
+    /// Genesis or snapshot recovery.
+    StorageInitialization,
+    /// Initializing node components.
+    ComponentsInitialization,
+}
+
+impl fmt::Display for StartupPhase {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(match self {
+            Self::ConfigLoading => "config_loading",
+            Self::StorageInitialization => "storage_initialization",
+            Self::ComponentsInitialization => "components_initialization",
+        })
+    }
+}
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "server_startup")]
+struct StartupMetrics {
+    /// Duration of a startup phase.
+    phase_latency: Family<StartupPhase, Gauge<Duration>>,
+    /// Components run by the node; set to 1 for each enabled component.
+    #[metrics(labels = ["component"])]
+    enabled_components: LabeledFamily<&'static str, Gauge<u64>>,
+    /// Number of DB migrations applied to Postgres.
+    applied_migrations: Gauge<u64>,
+    /// Version of the latest DB migration applied to Postgres.
+    latest_migration_version: Gauge<u64>,
+    /// Number of DB migrations embedded into the binary, but not applied to Postgres.
+    pending_migrations: Gauge<u64>,
+    /// Time since the node start until the first miniblock is processed by the state keeper.
+    first_miniblock_latency: Gauge<Duration>,
+}
+
+#[vise::register]
+static METRICS: vise::Global<StartupMetrics> = vise::Global::new();
+
+static STARTED_AT: OnceCell<Instant> = OnceCell::new();
+static FIRST_MINIBLOCK_REPORTED: AtomicBool = AtomicBool::new(false);
+
+/// Marks the node start. Should be called as early as possible in `main()`; later calls have no effect.
+pub fn mark_started() {
+    STARTED_AT.get_or_init(Instant::now);
+}
+
+/// Reports the duration of a startup phase.
+pub fn report_phase(phase: StartupPhase, latency: Duration) {
+    METRICS.phase_latency[&phase].set(latency);
+    tracing::info!(
+        %phase,
+        latency_ms = latency.as_millis() as u64,
+        "Startup phase `{phase}` took {latency:?}"
+    );
+}
+
+/// Reports the set of components run by the node.
+pub fn report_enabled_components(components: &[&'static str]) {
+    for &component in components {
+        METRICS.enabled_components[&component].set(1);
+    }
+    tracing::info!(?components, "Enabled components: {components:?}");
+}
+
+/// Reports DB migrations applied to Postgres. Errors are logged and do not influence node startup.
+pub async fn report_applied_migrations(pool: &ConnectionPool) {
+    let migrations = match pool.access_storage().await {
+        Ok(mut storage) => storage
+            .system_dal()
+            .get_applied_migrations()
+            .await
+            .map_err(anyhow::Error::from),
+        Err(err) => Err(err),
+    };
+    match migrations {
+        Ok(migrations) => {
+            METRICS.applied_migrations.set(migrations.count);
+            let latest_version = migrations.latest_version.unwrap_or(0);
+            METRICS
+                .latest_migration_version
+                .set(latest_version.try_into().unwrap_or(0));
+            tracing::info!(
+                applied_migrations = migrations.count,
+                latest_migration_version = latest_version,
+                "Postgres has {} applied migrations; the latest one is {latest_version}",
+                migrations.count
+            );
+        }
+        Err(err) => {
+            tracing::warn!("Failed getting applied DB migrations: {err:#}");
+            return;
+        }
+    }
+
+    let plan = match pool.access_storage().await {
+        Ok(mut storage) => storage
+            .migrations_dal()
+            .plan()
+            .await
+            .map_err(anyhow::Error::from),
+        Err(err) => Err(err),
+    };
+    match plan {
+        Ok(plan) => {
+            METRICS.pending_migrations.set(plan.pending.len() as u64);
+            if !plan.pending.is_empty() || !plan.is_consistent() {
+                tracing::warn!(
+                    pending_migrations = plan.pending.len(),
+                    "Postgres schema differs from the one expected by the binary; \
+                     run the binary with `--migrations plan` to inspect the differences:\n{plan}"
+                );
+            }
+        }
+        Err(err) => {
+            tracing::warn!("Failed planning DB migrations: {err:#}");
+        }
+    }
+}
+
+/// Action on DB migrations requested via the `--migrations` command-line option.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MigrationsAction {
+    /// Print pending migrations together with the estimate of their lock impact, and exit.
+    Plan,
+    /// Apply all pending migrations, and exit.
+    Apply,
+    /// Revert all migrations applied after the specified version, and exit.
+    RevertTo(i64),
+}
+
+impl MigrationsAction {
+    /// Parses the action from command-line values, e.g. `["plan"]` or `["revert-to", "20240214120000"]`.
+    pub fn from_args(args: &[String]) -> anyhow::Result<Self> {
+        match args {
+            [action] if action == "plan" => Ok(Self::Plan),
+            [action] if action == "apply" => Ok(Self::Apply),
+            [action, version] if action == "revert-to" => {
+                let version = i64::from_str(version)
+                    .with_context(|| format!("invalid migration version: {version}"))?;
+                Ok(Self::RevertTo(version))
+            }
+            _ => anyhow::bail!(
+                "invalid migrations action {args:?}; expected `plan`, `apply` or `revert-to <version>`"
+            ),
+        }
+    }
+
+    /// Executes this action, printing its outcome to stdout.
+    pub async fn execute(self, pool: &ConnectionPool) -> anyhow::Result<()> {
+        let mut storage = pool.access_storage().await?;
+        let plan = storage.migrations_dal().plan().await?;
+        match self {
+            Self::Plan => {
+                print!("{plan}");
+                if plan.has_risky_migrations() {
+                    println!(
+                        "Some migrations may block writes to large tables for a long time; \
+                         consider applying them during a maintenance window"
+                    );
+                }
+            }
+            Self::Apply => {
+                anyhow::ensure!(
+                    plan.is_consistent(),
+                    "cannot apply migrations to inconsistent database:\n{plan}"
+                );
+                if plan.pending.is_empty() {
+                    println!("No pending migrations");
+                    return Ok(());
+                }
+                print!("{plan}");
+                storage.migrations_dal().apply().await?;
+                println!("Applied {} migrations", plan.pending.len());
+            }
+            Self::RevertTo(version) => {
+                let reverted = storage.migrations_dal().revert_to(version).await?;
+                for migration in &reverted {
+                    println!(
+                        "Reverted migration {} {}",
+                        migration.version, migration.description
+                    );
+                }
+                println!("Reverted {} migrations", reverted.len());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Reports that a miniblock was processed by the state keeper. Only the first call after the node start is reported.
+pub(crate) fn report_miniblock_processed() {
+    let Some(started_at) = STARTED_AT.get() else {
+        return;
+    };
+    if FIRST_MINIBLOCK_REPORTED.swap(true, Ordering::Relaxed) {
+        return;
+    }
+    let latency = started_at.elapsed();
+    METRICS.first_miniblock_latency.set(latency);
+    tracing::info!(
+        latency_ms = latency.as_millis() as u64,
+        "First miniblock was processed {latency:?} after the node start"
+    );
+}
@@ -12,8 +12,10 @@ use multivm::{
 };
 use once_cell::sync::OnceCell;
 use tokio::sync::{mpsc, watch};
+use zksync_config::configs::database::RocksdbTuning;
 use zksync_dal::ConnectionPool;
 use zksync_state::{RocksdbStorage, StorageView, WriteStorage};
+use zksync_storage::RocksDBOptions;
 use zksync_types::{vm_trace::Call, Transaction, U256};
 use zksync_utils::bytecode::CompressedBytecodeInfo;
 
@@ -24,6 +26,7 @@ use crate::{
         metrics::{TxExecutionStage, EXECUTOR_METRICS, KEEPER_METRICS},
         types::ExecutionMetricsForCriteria,
     },
+    utils::apply_rocksdb_tuning,
 };
 
 /// The default implementation of [`BatchExecutor`].
@@ -31,6 +34,7 @@ use crate::{
 #[derive(Debug, Clone)]
 pub struct MainBatchExecutor {
     state_keeper_db_path: String,
+    state_keeper_db_options: RocksDBOptions,
     pool: ConnectionPool,
     save_call_traces: bool,
     max_allowed_tx_gas_limit: U256,
@@ -51,6 +55,7 @@ impl MainBatchExecutor {
     ) -> Self {
         Self {
             state_keeper_db_path,
+            state_keeper_db_options: RocksDBOptions::default(),
             pool,
             save_call_traces,
             max_allowed_tx_gas_limit,
@@ -59,6 +64,12 @@ impl MainBatchExecutor {
             optional_bytecode_compression,
         }
     }
+
+    /// Sets the block cache capacity and tuning options for the state keeper RocksDB cache.
+    pub fn set_db_options(&mut self, block_cache_capacity: Option<usize>, tuning: &RocksdbTuning) {
+        self.state_keeper_db_options.block_cache_capacity = block_cache_capacity;
+        apply_rocksdb_tuning(&mut self.state_keeper_db_options, tuning);
+    }
 }
 
 #[async_trait]
@@ -69,9 +80,12 @@ impl BatchExecutor for MainBatchExecutor {
         system_env: SystemEnv,
         stop_receiver: &watch::Receiver<bool>,
     ) -> Option<BatchExecutorHandle> {
-        let mut secondary_storage = RocksdbStorage::builder(self.state_keeper_db_path.as_ref())
-            .await
-            .expect("Failed initializing state keeper storage");
+        let mut secondary_storage = RocksdbStorage::builder_with_options(
+            self.state_keeper_db_path.as_ref(),
+            self.state_keeper_db_options,
+        )
+        .await
+        .expect("Failed initializing state keeper storage");
         secondary_storage.enable_enum_index_migration(self.enum_index_migration_chunk_size);
         let mut conn = self
             .pool
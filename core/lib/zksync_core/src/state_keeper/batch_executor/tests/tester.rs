@@ -172,6 +172,7 @@ impl Tester {
                 &get_system_smart_contracts(),
                 Default::default(),
                 Default::default(),
+                &Default::default(),
             )
             .await
             .unwrap();
@@ -0,0 +1,145 @@
+//! Backpressure from components processing sealed L1 batches.
+
+use std::{
+    fmt,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+use anyhow::Context as _;
+use zksync_config::configs::chain::StateKeeperConfig;
+use zksync_dal::StorageProcessor;
+use zksync_types::L1BatchNumber;
+
+use crate::state_keeper::metrics::{DownstreamComponent, KEEPER_METRICS};
+
+/// Lag of a downstream component exceeding the configured threshold.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct ExceededLag {
+    pub component: DownstreamComponent,
+    /// Number of sealed L1 batches not processed by the component.
+    pub lag: u32,
+    pub threshold: u32,
+}
+
+impl fmt::Display for ExceededLag {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let action = match self.component {
+            DownstreamComponent::EthSender => "committed on L1",
+            DownstreamComponent::Prover => "proven",
+        };
+        write!(
+            formatter,
+            "{} sealed L1 batches are not {action} (threshold: {})",
+            self.lag, self.threshold
+        )
+    }
+}
+
+/// Handle allowing to manually pause opening new L1 batches, e.g. via the admin API. While paused, the state keeper
+/// still executes transactions and seals the L1 batch in progress according to the usual criteria,
+/// but doesn't open a new L1 batch until resumed. Cloning the handle is cheap; all clones share the same state.
+#[derive(Debug, Clone, Default)]
+pub struct L1BatchPause(Arc<AtomicBool>);
+
+impl L1BatchPause {
+    /// Pauses opening new L1 batches. Returns `false` if opening L1 batches was already paused.
+    pub fn pause(&self) -> bool {
+        let was_paused = self.0.swap(true, Ordering::SeqCst);
+        KEEPER_METRICS.l1_batches_paused.set(1);
+        !was_paused
+    }
+
+    /// Resumes opening new L1 batches. Returns `false` if opening L1 batches wasn't paused.
+    pub fn resume(&self) -> bool {
+        let was_paused = self.0.swap(false, Ordering::SeqCst);
+        KEEPER_METRICS.l1_batches_paused.set(0);
+        was_paused
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+/// Checks whether components processing sealed L1 batches (the Ethereum sender and provers) lag too much
+/// behind the state keeper. While a lag exceeds its threshold, the state keeper doesn't open new L1 batches.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct Backpressure {
+    max_l1_batches_not_committed: Option<u32>,
+    max_l1_batches_not_proven: Option<u32>,
+}
+
+impl Backpressure {
+    pub fn new(config: &StateKeeperConfig) -> Self {
+        Self {
+            max_l1_batches_not_committed: config.max_l1_batches_not_committed,
+            max_l1_batches_not_proven: config.max_l1_batches_not_proven,
+        }
+    }
+
+    /// Returns the first lag exceeding its threshold, or `None` if the state keeper can proceed.
+    pub async fn check(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<Option<ExceededLag>> {
+        if self.max_l1_batches_not_committed.is_none() && self.max_l1_batches_not_proven.is_none() {
+            return Ok(None);
+        }
+        let last_sealed = storage
+            .blocks_dal()
+            .get_sealed_l1_batch_number()
+            .await
+            .context("failed getting sealed L1 batch number")?;
+        let Some(last_sealed) = last_sealed else {
+            return Ok(None);
+        };
+
+        if let Some(threshold) = self.max_l1_batches_not_committed {
+            let last_committed = storage
+                .blocks_dal()
+                .get_number_of_last_l1_batch_committed_on_eth()
+                .await
+                .context("failed getting last committed L1 batch")?
+                .unwrap_or(L1BatchNumber(0));
+            let lag =
+                Self::observe_lag(DownstreamComponent::EthSender, last_sealed, last_committed);
+            if lag > threshold {
+                return Ok(Some(ExceededLag {
+                    component: DownstreamComponent::EthSender,
+                    lag,
+                    threshold,
+                }));
+            }
+        }
+
+        if let Some(threshold) = self.max_l1_batches_not_proven {
+            let last_proven = storage
+                .blocks_dal()
+                .get_last_l1_batch_with_prove_tx()
+                .await
+                .context("failed getting last proven L1 batch")?;
+            let lag = Self::observe_lag(DownstreamComponent::Prover, last_sealed, last_proven);
+            if lag > threshold {
+                return Ok(Some(ExceededLag {
+                    component: DownstreamComponent::Prover,
+                    lag,
+                    threshold,
+                }));
+            }
+        }
+        Ok(None)
+    }
+
+    fn observe_lag(
+        component: DownstreamComponent,
+        last_sealed: L1BatchNumber,
+        last_processed: L1BatchNumber,
+    ) -> u32 {
+        let lag = last_sealed.0.saturating_sub(last_processed.0);
+        KEEPER_METRICS.downstream_lag[&component].set(lag.into());
+        lag
+    }
+}
@@ -28,12 +28,13 @@ use crate::{
     state_keeper::{
         extractors,
         io::{
+            backpressure::{Backpressure, L1BatchPause},
             common::{load_pending_batch, poll_iters, IoCursor},
             fee_address_migration, MiniblockParams, MiniblockSealerHandle, PendingBatchData,
             StateKeeperIO,
         },
         mempool_actor::l2_tx_filter,
-        metrics::KEEPER_METRICS,
+        metrics::{DownstreamComponent, KEEPER_METRICS},
         seal_criteria::{IoSealCriteria, TimeoutSealer},
         updates::{MiniblockUpdates, UpdatesManager},
         MempoolGuard,
@@ -67,6 +68,13 @@ pub struct MempoolIO {
 
     virtual_blocks_interval: u32,
     virtual_blocks_per_miniblock: u32,
+
+    backpressure: Backpressure,
+    /// Downstream component currently preventing new L1 batches from being opened, and the start of the pause.
+    paused_by: Option<(DownstreamComponent, Instant)>,
+    l1_batch_pause: L1BatchPause,
+    /// Start of the manual pause of opening new L1 batches.
+    paused_manually_at: Option<Instant>,
 }
 
 impl IoSealCriteria for MempoolIO {
@@ -179,11 +187,52 @@ impl StateKeeperIO for MempoolIO {
                 self.current_l1_batch_number.0,
                 self.filter.fee_input
             );
+            if self.l1_batch_pause.is_paused() {
+                if self.paused_manually_at.is_none() {
+                    tracing::info!(
+                        "Not opening L1 batch #{} until resumed by the operator",
+                        self.current_l1_batch_number
+                    );
+                    self.paused_manually_at = Some(Instant::now());
+                }
+                tokio::time::sleep(self.delay_interval).await;
+                continue;
+            }
+            if let Some(paused_at) = self.paused_manually_at.take() {
+                tracing::info!(
+                    "Resumed by the operator after {:?}; opening L1 batch #{}",
+                    paused_at.elapsed(),
+                    self.current_l1_batch_number
+                );
+            }
+
             let mut storage = self
                 .pool
                 .access_storage_tagged("state_keeper")
                 .await
                 .unwrap();
+            let exceeded_lag = self.backpressure.check(&mut storage).await.unwrap();
+            if let Some(exceeded_lag) = exceeded_lag {
+                drop(storage);
+                if self.paused_by.is_none() {
+                    tracing::info!(
+                        "Not opening L1 batch #{} until downstream components catch up: {exceeded_lag}",
+                        self.current_l1_batch_number
+                    );
+                    self.paused_by = Some((exceeded_lag.component, Instant::now()));
+                }
+                tokio::time::sleep(self.delay_interval).await;
+                continue;
+            }
+            if let Some((component, paused_at)) = self.paused_by.take() {
+                let elapsed = paused_at.elapsed();
+                KEEPER_METRICS.backpressure_time[&component].observe(elapsed);
+                tracing::info!(
+                    "Downstream components caught up after {elapsed:?}; resuming L1 batch #{}",
+                    self.current_l1_batch_number
+                );
+            }
+
             let (base_system_contracts, protocol_version) = storage
                 .protocol_versions_dal()
                 .base_system_contracts_by_timestamp(current_timestamp)
@@ -305,6 +354,10 @@ impl StateKeeperIO for MempoolIO {
         self.update_miniblock_fields(&updates_manager.miniblock);
     }
 
+    fn should_seal_miniblock_on_shutdown(&self) -> bool {
+        true
+    }
+
     async fn seal_l1_batch(
         &mut self,
         witness_block_state: Option<WitnessBlockState>,
@@ -489,9 +542,20 @@ impl MempoolIO {
             chain_id,
             virtual_blocks_interval: config.virtual_blocks_interval,
             virtual_blocks_per_miniblock: config.virtual_blocks_per_miniblock,
+            backpressure: Backpressure::new(config),
+            paused_by: None,
+            l1_batch_pause: L1BatchPause::default(),
+            paused_manually_at: None,
         })
     }
 
+    /// Sets the handle allowing to manually pause opening new L1 batches.
+    #[must_use]
+    pub fn with_l1_batch_pause(mut self, pause: L1BatchPause) -> Self {
+        self.l1_batch_pause = pause;
+        self
+    }
+
     fn update_miniblock_fields(&mut self, miniblock: &MiniblockUpdates) {
         assert_eq!(
             miniblock.number, self.current_miniblock_number.0,
@@ -553,6 +617,10 @@ impl MempoolIO {
         &self.filter
     }
 
+    pub(super) fn set_backpressure(&mut self, backpressure: Backpressure) {
+        self.backpressure = backpressure;
+    }
+
     pub(super) fn set_prev_miniblock_timestamp(&mut self, timestamp: u64) {
         self.prev_miniblock_timestamp = timestamp;
     }
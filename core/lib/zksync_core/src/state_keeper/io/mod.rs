@@ -19,6 +19,7 @@ use super::{
     updates::{MiniblockSealCommand, UpdatesManager},
 };
 
+pub(crate) mod backpressure;
 pub(crate) mod common;
 pub(crate) mod fee_address_migration;
 pub(crate) mod mempool;
@@ -92,6 +93,12 @@ pub trait StateKeeperIO: 'static + Send + IoSealCriteria {
     /// Marks the miniblock (aka L2 block) as sealed.
     /// Returns the timestamp for the next miniblock.
     async fn seal_miniblock(&mut self, updates_manager: &UpdatesManager);
+    /// Checks whether the in-progress miniblock should be sealed if the state keeper is stopped. This is only safe
+    /// if the IO decides on miniblock boundaries itself (i.e., on the main node); by default, the in-progress miniblock
+    /// is discarded, and its transactions are re-executed after the restart.
+    fn should_seal_miniblock_on_shutdown(&self) -> bool {
+        false
+    }
     /// Marks the L1 batch as sealed.
     async fn seal_l1_batch(
         &mut self,
@@ -267,6 +274,9 @@ impl MiniblockSealer {
             completable.completion_sender.send(()).ok();
             // ^ We don't care whether anyone listens to the processing progress
         }
+        tracing::info!(
+            "Miniblock seal queue is closed and drained, miniblock sealer is shutting down"
+        );
         Ok(())
     }
 
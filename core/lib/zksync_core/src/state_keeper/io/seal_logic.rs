@@ -264,6 +264,15 @@ impl UpdatesManager {
         let elapsed = started_at.elapsed();
         L1_BATCH_METRICS.sealed_time.observe(elapsed);
         tracing::debug!("Sealed L1 batch {current_l1_batch_number} in {elapsed:?}");
+        vlog::add_lifecycle_breadcrumb(
+            "state_keeper",
+            format!("Sealed L1 batch {current_l1_batch_number}"),
+            [
+                ("l1_batch_number", current_l1_batch_number.0.into()),
+                ("tx_count", self.l1_batch.executed_transactions.len().into()),
+                ("latency_ms", (elapsed.as_millis() as u64).into()),
+            ],
+        );
     }
 }
 
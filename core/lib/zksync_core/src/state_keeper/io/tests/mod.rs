@@ -2,6 +2,7 @@ use std::time::Duration;
 
 use futures::FutureExt;
 use multivm::utils::derive_base_fee_and_gas_per_pubdata;
+use zksync_config::configs::chain::StateKeeperConfig;
 use zksync_contracts::BaseSystemContractsHashes;
 use zksync_dal::ConnectionPool;
 use zksync_mempool::L2TxFilter;
@@ -18,7 +19,10 @@ use zksync_utils::time::seconds_since_epoch;
 use self::tester::Tester;
 use crate::{
     state_keeper::{
-        io::{MiniblockParams, MiniblockSealer, StateKeeperIO},
+        io::{
+            backpressure::{Backpressure, L1BatchPause},
+            MiniblockParams, MiniblockSealer, StateKeeperIO,
+        },
         mempool_actor::l2_tx_filter,
         tests::{
             create_execution_result, create_transaction, create_updates_manager,
@@ -168,6 +172,81 @@ async fn test_timestamps_are_distinct(
     assert!(batch_params.1.timestamp > prev_miniblock_timestamp);
 }
 
+#[tokio::test]
+async fn backpressure_from_eth_sender() {
+    let connection_pool = ConnectionPool::constrained_test_pool(1).await;
+    let tester = Tester::new();
+    tester.genesis(&connection_pool).await;
+    let tx_result = tester
+        .insert_miniblock(&connection_pool, 1, 5, BatchFeeInput::l1_pegged(55, 555))
+        .await;
+    tester
+        .insert_sealed_batch(&connection_pool, 1, &[tx_result])
+        .await;
+
+    let (mut mempool, mut guard) = tester.create_test_mempool_io(connection_pool, 1).await;
+    let tx_filter = l2_tx_filter(
+        &tester.create_batch_fee_input_provider().await,
+        ProtocolVersionId::latest().into(),
+    )
+    .await;
+    tester.insert_tx(&mut guard, tx_filter.fee_per_gas, tx_filter.gas_per_pubdata);
+
+    // L1 batch #1 is sealed, but not committed.
+    let config = StateKeeperConfig {
+        max_l1_batches_not_committed: Some(0),
+        ..StateKeeperConfig::default()
+    };
+    mempool.set_backpressure(Backpressure::new(&config));
+    let batch_params = mempool
+        .wait_for_new_batch_params(Duration::from_millis(100))
+        .await;
+    assert!(batch_params.is_none());
+
+    let config = StateKeeperConfig {
+        max_l1_batches_not_committed: Some(1),
+        ..StateKeeperConfig::default()
+    };
+    mempool.set_backpressure(Backpressure::new(&config));
+    let (_, l1_batch_env) = mempool
+        .wait_for_new_batch_params(Duration::from_secs(10))
+        .await
+        .expect("No batch params in the test mempool");
+    assert_eq!(l1_batch_env.number, L1BatchNumber(2));
+}
+
+#[tokio::test]
+async fn manually_pausing_l1_batches() {
+    let connection_pool = ConnectionPool::constrained_test_pool(1).await;
+    let tester = Tester::new();
+    tester.genesis(&connection_pool).await;
+
+    let (mempool, mut guard) = tester.create_test_mempool_io(connection_pool, 1).await;
+    let pause = L1BatchPause::default();
+    let mut mempool = mempool.with_l1_batch_pause(pause.clone());
+    let tx_filter = l2_tx_filter(
+        &tester.create_batch_fee_input_provider().await,
+        ProtocolVersionId::latest().into(),
+    )
+    .await;
+    tester.insert_tx(&mut guard, tx_filter.fee_per_gas, tx_filter.gas_per_pubdata);
+
+    assert!(pause.pause());
+    assert!(!pause.pause());
+    let batch_params = mempool
+        .wait_for_new_batch_params(Duration::from_millis(100))
+        .await;
+    assert!(batch_params.is_none());
+
+    assert!(pause.resume());
+    assert!(!pause.is_paused());
+    let (_, l1_batch_env) = mempool
+        .wait_for_new_batch_params(Duration::from_secs(10))
+        .await
+        .expect("No batch params in the test mempool");
+    assert_eq!(l1_batch_env.number, L1BatchNumber(1));
+}
+
 #[tokio::test]
 async fn l1_batch_timestamp_basics() {
     let connection_pool = ConnectionPool::constrained_test_pool(1).await;
@@ -3,7 +3,10 @@
 use std::{slice, sync::Arc, time::Duration};
 
 use multivm::vm_latest::constants::BLOCK_GAS_LIMIT;
-use zksync_config::{configs::chain::StateKeeperConfig, GasAdjusterConfig};
+use zksync_config::{
+    configs::{chain::StateKeeperConfig, eth_sender::BaseFeeEstimationMode},
+    GasAdjusterConfig,
+};
 use zksync_contracts::BaseSystemContracts;
 use zksync_dal::ConnectionPool;
 use zksync_eth_client::clients::MockEthereum;
@@ -57,6 +60,13 @@ impl Tester {
             internal_enforced_l1_gas_price: None,
             poll_period: 10,
             max_l1_gas_price: None,
+            base_fee_estimation_mode: BaseFeeEstimationMode::Median,
+            base_fee_percentile: None,
+            base_fee_ewma_alpha: None,
+            external_price_api_url: None,
+            external_price_api_json_pointer: None,
+            external_price_api_multiplier: None,
+            external_price_api_max_age_sec: None,
         };
 
         GasAdjuster::new(Arc::new(eth_client), gas_adjuster_config)
@@ -140,6 +150,7 @@ impl Tester {
                 &get_system_smart_contracts(),
                 L1VerifierConfig::default(),
                 Address::zero(),
+                &Default::default(),
             )
             .await
             .unwrap();
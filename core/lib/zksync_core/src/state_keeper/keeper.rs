@@ -8,6 +8,7 @@ use std::{
 use anyhow::Context as _;
 use multivm::interface::{Halt, L1BatchEnv, SystemEnv};
 use tokio::sync::watch;
+use tracing::Instrument as _;
 use zksync_dal::ConnectionPool;
 use zksync_types::{
     block::MiniblockExecutionData,
@@ -26,7 +27,7 @@ use super::{
     types::ExecutionMetricsForCriteria,
     updates::UpdatesManager,
 };
-use crate::{gas_tracker::gas_count_from_writes, state_keeper::io::fee_address_migration};
+use crate::{gas_tracker::gas_count_from_writes, startup, state_keeper::io::fee_address_migration};
 
 /// Amount of time to block on waiting for some resource. The exact value is not really important,
 /// we only need it to not block on waiting indefinitely and be able to process cancellation requests.
@@ -177,6 +178,7 @@ impl ZkSyncStateKeeper {
             // Finish current batch.
             if !updates_manager.miniblock.executed_transactions.is_empty() {
                 self.io.seal_miniblock(&updates_manager).await;
+                startup::report_miniblock_processed();
                 // We've sealed the miniblock that we had, but we still need to setup the timestamp
                 // for the fictive miniblock.
                 let new_miniblock_params = self.wait_for_new_miniblock_params().await?;
@@ -189,6 +191,8 @@ impl ZkSyncStateKeeper {
             }
             let (finished_batch, witness_block_state) = batch_executor.finish_batch().await;
             let sealed_batch_protocol_version = updates_manager.protocol_version();
+            let seal_span =
+                tracing::info_span!("seal_l1_batch", l1_batch_number = %l1_batch_env.number);
             self.io
                 .seal_l1_batch(
                     witness_block_state,
@@ -196,6 +200,7 @@ impl ZkSyncStateKeeper {
                     &l1_batch_env,
                     finished_batch,
                 )
+                .instrument(seal_span)
                 .await
                 .context("seal_l1_batch")?;
             if let Some(delta) = l1_batch_seal_delta {
@@ -427,6 +432,10 @@ impl ZkSyncStateKeeper {
         Ok(())
     }
 
+    #[tracing::instrument(
+        skip_all,
+        fields(l1_batch_number = %self.io.current_l1_batch_number())
+    )]
     async fn process_l1_batch(
         &mut self,
         batch_executor: &BatchExecutorHandle,
@@ -443,30 +452,26 @@ impl ZkSyncStateKeeper {
                 .io
                 .should_seal_l1_batch_unconditionally(updates_manager)
             {
-                tracing::debug!(
-                    "L1 batch #{} should be sealed unconditionally as per sealing rules",
-                    self.io.current_l1_batch_number()
-                );
+                tracing::debug!("L1 batch should be sealed unconditionally as per sealing rules");
                 return Ok(());
             }
 
             if self.io.should_seal_miniblock(updates_manager) {
                 tracing::debug!(
-                    "Miniblock #{} (L1 batch #{}) should be sealed as per sealing rules",
-                    self.io.current_miniblock_number(),
-                    self.io.current_l1_batch_number()
+                    miniblock_number = %self.io.current_miniblock_number(),
+                    "Miniblock should be sealed as per sealing rules"
                 );
                 self.io.seal_miniblock(updates_manager).await;
+                startup::report_miniblock_processed();
 
                 let new_miniblock_params = self
                     .wait_for_new_miniblock_params()
                     .await
                     .map_err(|e| e.context("wait_for_new_miniblock_params"))?;
                 tracing::debug!(
-                    "Initialized new miniblock #{} (L1 batch #{}) with timestamp {}",
-                    self.io.current_miniblock_number(),
-                    self.io.current_l1_batch_number(),
-                    extractors::display_timestamp(new_miniblock_params.timestamp)
+                    miniblock_number = %self.io.current_miniblock_number(),
+                    timestamp = %extractors::display_timestamp(new_miniblock_params.timestamp),
+                    "Initialized new miniblock"
                 );
                 Self::start_next_miniblock(new_miniblock_params, updates_manager, batch_executor)
                     .await;
@@ -524,13 +529,24 @@ impl ZkSyncStateKeeper {
 
             if seal_resolution.should_seal() {
                 tracing::debug!(
-                    "L1 batch #{} should be sealed with resolution {seal_resolution:?} after executing \
-                     transaction {tx_hash}",
-                    self.io.current_l1_batch_number()
+                    ?seal_resolution,
+                    %tx_hash,
+                    "L1 batch should be sealed after executing transaction"
                 );
                 return Ok(());
             }
         }
+
+        if self.io.should_seal_miniblock_on_shutdown()
+            && !updates_manager.miniblock.executed_transactions.is_empty()
+        {
+            tracing::info!(
+                miniblock_number = %self.io.current_miniblock_number(),
+                "Sealing in-progress miniblock with {} transactions on shutdown",
+                updates_manager.miniblock.executed_transactions.len()
+            );
+            self.io.seal_miniblock(updates_manager).await;
+        }
         Err(Error::Canceled)
     }
 
@@ -602,6 +618,13 @@ impl ZkSyncStateKeeper {
     /// 2. Seal manager decided that batch is ready to be sealed.
     /// Note: this method doesn't mutate `updates_manager` in the end. However, reference should be mutable
     /// because we use `apply_and_rollback` method of `updates_manager.storage_writes_deduplicator`.
+    #[tracing::instrument(
+        skip_all,
+        fields(
+            tx_hash = ?tx.hash(),
+            miniblock_number = %self.io.current_miniblock_number(),
+        )
+    )]
     async fn process_one_tx(
         &mut self,
         batch_executor: &BatchExecutorHandle,
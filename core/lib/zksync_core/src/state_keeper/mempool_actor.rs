@@ -12,7 +12,10 @@ use zksync_mempool::L2TxFilter;
 use zksync_types::H256;
 use zksync_types::{get_nonce_key, Address, Nonce, Transaction, VmVersion};
 
-use super::{metrics::KEEPER_METRICS, types::MempoolGuard};
+use super::{
+    metrics::{MempoolEvictionReason, KEEPER_METRICS},
+    types::MempoolGuard,
+};
 use crate::{fee_model::BatchFeeModelInputProvider, utils::pending_protocol_version};
 
 /// Creates a mempool filter for L2 transactions based on the current L1 gas price.
@@ -64,14 +67,44 @@ impl MempoolFetcher {
     }
 
     pub async fn run(mut self, stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        self.restore_mempool(&stop_receiver).await?;
+
+        loop {
+            if *stop_receiver.borrow() {
+                tracing::info!("Stop signal received, mempool is shutting down");
+                break;
+            }
+            let all_transactions_loaded = self.sync_mempool().await? < self.sync_batch_size;
+            if all_transactions_loaded {
+                tokio::time::sleep(self.sync_interval).await;
+            }
+        }
+        Ok(())
+    }
+
+    /// Restores the mempool after a restart. Accepted L2 transactions are persisted in Postgres before
+    /// they are acknowledged over RPC, so this loads all pending transactions (including ones
+    /// that were in the mempool of the previous run, but weren't sealed into a miniblock).
+    async fn restore_mempool(
+        &mut self,
+        stop_receiver: &watch::Receiver<bool>,
+    ) -> anyhow::Result<()> {
         let mut storage = self.pool.access_storage_tagged("state_keeper").await?;
         if let Some(stuck_tx_timeout) = self.stuck_tx_timeout {
-            let removed_txs = storage
+            let removed_tx_hashes = storage
                 .transactions_dal()
                 .remove_stuck_txs(stuck_tx_timeout)
                 .await
                 .context("failed removing stuck transactions")?;
-            tracing::info!("Number of stuck txs was removed: {removed_txs}");
+            tracing::info!(
+                "Number of stuck txs was removed: {}",
+                removed_tx_hashes.len()
+            );
+            for tx_hash in &removed_tx_hashes {
+                tracing::warn!(
+                    "Removed transaction {tx_hash:?} not included into a miniblock for {stuck_tx_timeout:?}"
+                );
+            }
         }
         storage
             .transactions_dal()
@@ -80,52 +113,85 @@ impl MempoolFetcher {
             .context("failed resetting mempool")?;
         drop(storage);
 
-        loop {
-            if *stop_receiver.borrow() {
-                tracing::info!("Stop signal received, mempool is shutting down");
+        let mut restored_tx_count = 0;
+        while !*stop_receiver.borrow() {
+            let loaded_tx_count = self.sync_mempool().await?;
+            restored_tx_count += loaded_tx_count;
+            if loaded_tx_count < self.sync_batch_size {
                 break;
             }
-            let latency = KEEPER_METRICS.mempool_sync.start();
-            let mut storage = self.pool.access_storage_tagged("state_keeper").await?;
-            let mempool_info = self.mempool.get_mempool_info();
-            let protocol_version = pending_protocol_version(&mut storage)
-                .await
-                .context("failed getting pending protocol version")?;
-
-            let l2_tx_filter = l2_tx_filter(
-                self.batch_fee_input_provider.as_ref(),
-                protocol_version.into(),
-            )
-            .await;
+        }
+        KEEPER_METRICS
+            .mempool_restored_transactions
+            .inc_by(restored_tx_count as u64);
+        tracing::info!("Restored {restored_tx_count} pending transactions to mempool");
+        Ok(())
+    }
 
-            let transactions = storage
+    /// Loads the next batch of transactions from Postgres into the mempool. Returns the number of loaded transactions.
+    async fn sync_mempool(&mut self) -> anyhow::Result<usize> {
+        let latency = KEEPER_METRICS.mempool_sync.start();
+        let mut storage = self.pool.access_storage_tagged("state_keeper").await?;
+        let mempool_info = self.mempool.get_mempool_info();
+        if !mempool_info.evicted_transactions.is_empty() {
+            let evicted_txs: Vec<_> = mempool_info
+                .evicted_transactions
+                .iter()
+                .map(|&(hash, reason)| (hash, reason.error_message().to_owned()))
+                .collect();
+            let rejection_result = storage
                 .transactions_dal()
-                .sync_mempool(
-                    &mempool_info.stashed_accounts,
-                    &mempool_info.purged_accounts,
-                    l2_tx_filter.gas_per_pubdata,
-                    l2_tx_filter.fee_per_gas,
-                    self.sync_batch_size,
-                )
-                .await
-                .context("failed syncing mempool")?;
-            let nonces = get_transaction_nonces(&mut storage, &transactions).await?;
-            drop(storage);
-
-            #[cfg(test)]
-            {
-                let transaction_hashes = transactions.iter().map(Transaction::hash).collect();
-                self.transaction_hashes_sender.send(transaction_hashes).ok();
+                .mark_evicted_txs_as_rejected(&evicted_txs)
+                .await;
+            let rejected_count = match rejection_result {
+                Ok(count) => count,
+                Err(err) => {
+                    // Return evicted transactions to the mempool so that they are persisted on the next sync.
+                    self.mempool
+                        .requeue_evicted_transactions(mempool_info.evicted_transactions);
+                    return Err(err).context("failed marking evicted transactions as rejected");
+                }
+            };
+            for &(hash, reason) in &mempool_info.evicted_transactions {
+                tracing::debug!("Evicted transaction {hash:?} from mempool: {reason:?}");
+                KEEPER_METRICS.mempool_evicted_transactions[&MempoolEvictionReason::from(reason)]
+                    .inc();
             }
-            let all_transactions_loaded = transactions.len() < self.sync_batch_size;
-            self.mempool.insert(transactions, nonces);
-            latency.observe();
+            tracing::info!("Rejected {rejected_count} transactions evicted from mempool");
+        }
+        let protocol_version = pending_protocol_version(&mut storage)
+            .await
+            .context("failed getting pending protocol version")?;
 
-            if all_transactions_loaded {
-                tokio::time::sleep(self.sync_interval).await;
-            }
+        let l2_tx_filter = l2_tx_filter(
+            self.batch_fee_input_provider.as_ref(),
+            protocol_version.into(),
+        )
+        .await;
+
+        let transactions = storage
+            .transactions_dal()
+            .sync_mempool(
+                &mempool_info.stashed_accounts,
+                &mempool_info.purged_accounts,
+                l2_tx_filter.gas_per_pubdata,
+                l2_tx_filter.fee_per_gas,
+                self.sync_batch_size,
+            )
+            .await
+            .context("failed syncing mempool")?;
+        let nonces = get_transaction_nonces(&mut storage, &transactions).await?;
+        drop(storage);
+
+        #[cfg(test)]
+        {
+            let transaction_hashes = transactions.iter().map(Transaction::hash).collect();
+            self.transaction_hashes_sender.send(transaction_hashes).ok();
         }
-        Ok(())
+        let loaded_tx_count = transactions.len();
+        self.mempool.insert(transactions, nonces);
+        latency.observe();
+        Ok(loaded_tx_count)
     }
 }
 
@@ -179,6 +245,8 @@ mod tests {
         stuck_tx_timeout: 0,
         remove_stuck_txs: false,
         delay_interval: 10,
+        max_transactions_per_account: None,
+        max_memory_mb: None,
     };
 
     #[tokio::test]
@@ -272,6 +340,51 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn restoring_mempool_after_restart() {
+        let pool = ConnectionPool::constrained_test_pool(1).await;
+        let mut storage = pool.access_storage().await.unwrap();
+        ensure_genesis_state(&mut storage, L2ChainId::default(), &GenesisParams::mock())
+            .await
+            .unwrap();
+
+        let fee_params_provider = Arc::new(MockBatchFeeParamsProvider::default());
+        let fee_input = fee_params_provider.get_batch_fee_input().await;
+        let (base_fee, gas_per_pubdata) =
+            derive_base_fee_and_gas_per_pubdata(fee_input, ProtocolVersionId::latest().into());
+        let transaction = create_l2_transaction(base_fee, gas_per_pubdata);
+        let transaction_hash = transaction.hash();
+        storage
+            .transactions_dal()
+            .insert_transaction_l2(transaction, TransactionExecutionMetrics::default())
+            .await;
+        // Emulate the transaction being loaded into the mempool before a restart.
+        let loaded_txs = storage
+            .transactions_dal()
+            .sync_mempool(&[], &[], 0, 0, 100)
+            .await
+            .unwrap();
+        assert_eq!(loaded_txs.len(), 1);
+        drop(storage);
+
+        let mempool = MempoolGuard::new(PriorityOpId(0), 100);
+        let mut fetcher = MempoolFetcher::new(
+            mempool.clone(),
+            fee_params_provider,
+            &TEST_MEMPOOL_CONFIG,
+            pool.clone(),
+        );
+        let (tx_hashes_sender, mut tx_hashes_receiver) = mpsc::unbounded_channel();
+        fetcher.transaction_hashes_sender = tx_hashes_sender;
+        let (stop_sender, stop_receiver) = watch::channel(false);
+        fetcher.restore_mempool(&stop_receiver).await.unwrap();
+
+        let tx_hashes = tx_hashes_receiver.try_recv().unwrap();
+        assert_eq!(tx_hashes, [transaction_hash]);
+        assert_eq!(mempool.stats().l2_transaction_count, 1);
+        stop_sender.send_replace(true);
+    }
+
     #[tokio::test]
     async fn ignoring_transaction_with_insufficient_fee() {
         let pool = ConnectionPool::constrained_test_pool(1).await;
@@ -7,9 +7,9 @@ use std::{
 
 use vise::{
     Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, LatencyObserver,
-    Metrics,
+    Metrics, Unit,
 };
-use zksync_mempool::MempoolStore;
+use zksync_mempool::{EvictionReason, MempoolStore};
 
 use super::seal_criteria::SealResolution;
 use crate::metrics::InteractionType;
@@ -31,6 +31,29 @@ pub(crate) enum TxExecutionStage {
     DryRunRollback,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "component", rename_all = "snake_case")]
+pub(crate) enum DownstreamComponent {
+    EthSender,
+    Prover,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "reason", rename_all = "snake_case")]
+pub(super) enum MempoolEvictionReason {
+    AccountLimit,
+    MemoryLimit,
+}
+
+impl From<EvictionReason> for MempoolEvictionReason {
+    fn from(reason: EvictionReason) -> Self {
+        match reason {
+            EvictionReason::AccountLimit => Self::AccountLimit,
+            EvictionReason::MemoryLimit => Self::MemoryLimit,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
 #[metrics(label = "tx_execution_type", rename_all = "snake_case")]
 pub(crate) enum TxExecutionType {
@@ -59,6 +82,10 @@ pub(crate) struct StateKeeperMetrics {
     /// Latency to synchronize the mempool with Postgres.
     #[metrics(buckets = Buckets::LATENCIES)]
     pub mempool_sync: Histogram<Duration>,
+    /// Number of pending transactions restored to the mempool on startup.
+    pub mempool_restored_transactions: Counter,
+    /// Number of transactions evicted from the mempool.
+    pub mempool_evicted_transactions: Family<MempoolEvictionReason, Counter>,
     /// Latency of the state keeper waiting for a transaction.
     #[metrics(buckets = Buckets::LATENCIES)]
     pub waiting_for_tx: Histogram<Duration>,
@@ -81,6 +108,13 @@ pub(crate) struct StateKeeperMetrics {
     pub tx_execution_time: Family<TxExecutionStage, Histogram<Duration>>,
     /// Number of times gas price was reported as too high.
     pub gas_price_too_high: Counter,
+    /// Number of sealed L1 batches not yet processed by a downstream component.
+    pub downstream_lag: Family<DownstreamComponent, Gauge<u64>>,
+    /// Time spent by the state keeper waiting for downstream components to catch up before opening an L1 batch.
+    #[metrics(buckets = Buckets::LATENCIES)]
+    pub backpressure_time: Family<DownstreamComponent, Histogram<Duration>>,
+    /// Whether opening new L1 batches is paused manually (1) or not (0).
+    pub l1_batches_paused: Gauge<u64>,
 }
 
 #[vise::register]
@@ -94,6 +128,9 @@ pub(super) struct StateKeeperGauges {
     mempool_l1_size: Gauge<usize>,
     /// Current number of L2 transactions in the mempool.
     mempool_l2_size: Gauge<u64>,
+    /// Estimated memory occupied by L2 transactions in the mempool.
+    #[metrics(unit = Unit::Bytes)]
+    mempool_l2_memory_usage: Gauge<usize>,
     /// Current size of the L2 priority queue.
     l2_priority_queue_size: Gauge<usize>,
 }
@@ -111,6 +148,7 @@ impl StateKeeperGauges {
                 let gauges = StateKeeperGauges::default();
                 gauges.mempool_l1_size.set(stats.l1_transaction_count);
                 gauges.mempool_l2_size.set(stats.l2_transaction_count);
+                gauges.mempool_l2_memory_usage.set(stats.l2_memory_usage);
                 gauges
                     .l2_priority_queue_size
                     .set(stats.l2_priority_queue_size);
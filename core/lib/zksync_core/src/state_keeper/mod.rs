@@ -10,9 +10,13 @@ use zksync_object_store::ObjectStore;
 
 pub use self::{
     batch_executor::{main_executor::MainBatchExecutor, BatchExecutor},
-    io::{mempool::MempoolIO, MiniblockSealer, MiniblockSealerHandle, StateKeeperIO},
+    io::{
+        backpressure::L1BatchPause, mempool::MempoolIO, MiniblockSealer, MiniblockSealerHandle,
+        StateKeeperIO,
+    },
     keeper::ZkSyncStateKeeper,
     mempool_actor::MempoolFetcher,
+    partition_manager::PartitionManager,
     seal_criteria::SequencerSealer,
     types::MempoolGuard,
 };
@@ -24,6 +28,7 @@ pub(crate) mod io;
 mod keeper;
 mod mempool_actor;
 pub(crate) mod metrics;
+mod partition_manager;
 pub mod seal_criteria;
 #[cfg(test)]
 pub(crate) mod tests;
@@ -42,9 +47,10 @@ pub(crate) async fn create_state_keeper(
     batch_fee_input_provider: Arc<dyn BatchFeeModelInputProvider>,
     miniblock_sealer_handle: MiniblockSealerHandle,
     object_store: Arc<dyn ObjectStore>,
+    l1_batch_pause: L1BatchPause,
     stop_receiver: watch::Receiver<bool>,
 ) -> ZkSyncStateKeeper {
-    let batch_executor_base = MainBatchExecutor::new(
+    let mut batch_executor_base = MainBatchExecutor::new(
         db_config.state_keeper_db_path.clone(),
         pool.clone(),
         state_keeper_config.max_allowed_l2_tx_gas_limit.into(),
@@ -53,6 +59,10 @@ pub(crate) async fn create_state_keeper(
         state_keeper_config.enum_index_migration_chunk_size(),
         false,
     );
+    batch_executor_base.set_db_options(
+        db_config.state_keeper_db_block_cache_size(),
+        &db_config.state_keeper_db_tuning(),
+    );
 
     let io = MempoolIO::new(
         mempool,
@@ -67,7 +77,8 @@ pub(crate) async fn create_state_keeper(
         network_config.zksync_network_id,
     )
     .await
-    .expect("Failed initializing main node I/O for state keeper");
+    .expect("Failed initializing main node I/O for state keeper")
+    .with_l1_batch_pause(l1_batch_pause);
 
     let sealer = SequencerSealer::new(state_keeper_config);
     ZkSyncStateKeeper::new(
@@ -0,0 +1,114 @@
+//! Background creation of partitions for partitioned Postgres tables (`events` and `storage_logs`).
+
+use std::time::Duration;
+
+use anyhow::Context as _;
+use tokio::sync::watch;
+use zksync_dal::ConnectionPool;
+use zksync_types::MiniblockNumber;
+
+/// Creates partitions for the partitioned tables ahead of time, so that the state keeper never needs to create
+/// them when sealing miniblocks. [`PartitionsDal`](zksync_dal::partitions_dal::PartitionsDal) ensures that there's
+/// a spare partition after the current one, so the manager doesn't need to run often.
+#[derive(Debug)]
+pub struct PartitionManager {
+    pool: ConnectionPool,
+    poll_interval: Duration,
+}
+
+impl PartitionManager {
+    const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+    pub fn new(pool: ConnectionPool) -> Self {
+        Self {
+            pool,
+            poll_interval: Self::DEFAULT_POLL_INTERVAL,
+        }
+    }
+
+    async fn ensure_partitions(&self) -> anyhow::Result<()> {
+        let mut storage = self.pool.access_storage_tagged("partition_manager").await?;
+        let sealed_miniblock = storage
+            .blocks_dal()
+            .get_sealed_miniblock_number()
+            .await
+            .context("failed getting sealed miniblock number")?;
+        let snapshot_recovery = storage
+            .snapshot_recovery_dal()
+            .get_applied_snapshot_status()
+            .await
+            .context("failed getting snapshot recovery status")?;
+        let snapshot_miniblock = snapshot_recovery.map(|recovery| recovery.miniblock_number);
+        let current_miniblock = sealed_miniblock
+            .max(snapshot_miniblock)
+            .unwrap_or(MiniblockNumber(0));
+
+        storage
+            .partitions_dal()
+            .ensure_partitions(current_miniblock)
+            .await
+            .with_context(|| {
+                format!("failed creating partitions for miniblock #{current_miniblock}")
+            })
+    }
+
+    pub async fn run(self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        while !*stop_receiver.borrow_and_update() {
+            // Errors are not fatal: a spare partition is always available, so creating partitions can be retried.
+            if let Err(err) = self.ensure_partitions().await {
+                tracing::warn!("Failed ensuring table partitions: {err:#}");
+            }
+            tokio::time::timeout(self.poll_interval, stop_receiver.changed())
+                .await
+                .ok();
+        }
+        tracing::info!("Stop signal received, partition manager is shutting down");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_dal::partitions_dal::{PartitionedTable, PartitionsDal};
+    use zksync_types::L1BatchNumber;
+
+    use super::*;
+    use crate::utils::testonly::prepare_recovery_snapshot;
+
+    #[tokio::test]
+    async fn creating_partitions_after_snapshot_recovery() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut storage = pool.access_storage().await.unwrap();
+        let snapshot_miniblock = 3 * PartitionsDal::PARTITION_SIZE as u32 + 42;
+        prepare_recovery_snapshot(
+            &mut storage,
+            L1BatchNumber(23),
+            MiniblockNumber(snapshot_miniblock),
+            &[],
+        )
+        .await;
+
+        PartitionManager::new(pool.clone())
+            .ensure_partitions()
+            .await
+            .unwrap();
+        for table in PartitionedTable::ALL {
+            let partitions = storage
+                .partitions_dal()
+                .get_partitions(table)
+                .await
+                .unwrap();
+            let last_partitions: Vec<_> = partitions[partitions.len() - 2..]
+                .iter()
+                .map(|partition| partition.name.clone())
+                .collect();
+            assert_eq!(
+                last_partitions,
+                [
+                    format!("{}_p3", table.name()),
+                    format!("{}_p4", table.name())
+                ]
+            );
+        }
+    }
+}
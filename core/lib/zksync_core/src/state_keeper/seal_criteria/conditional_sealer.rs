@@ -36,6 +36,8 @@ pub trait ConditionalSealer: 'static + fmt::Debug + Send + Sync {
 
 /// Implementation of [`ConditionalSealer`] used by the main node.
 /// Internally uses a set of [`SealCriterion`]s to determine whether the batch should be sealed.
+/// Built-in criteria can be disabled via [`StateKeeperConfig::disabled_seal_criteria`], and custom criteria
+/// can be added using [`Self::with_criterion()`].
 ///
 /// The checks are deterministic, i.e., should depend solely on execution metrics and [`StateKeeperConfig`].
 /// Non-deterministic seal criteria are expressed using [`IoSealCriteria`](super::IoSealCriteria).
@@ -115,11 +117,44 @@ impl ConditionalSealer for SequencerSealer {
 }
 
 impl SequencerSealer {
+    /// Creates a sealer with built-in criteria, except for ones disabled in the `config`.
     pub fn new(config: StateKeeperConfig) -> Self {
-        let sealers = Self::default_sealers();
+        let mut sealers = Self::default_sealers();
+        for name in &config.disabled_seal_criteria {
+            if !sealers
+                .iter()
+                .any(|sealer| sealer.prom_criterion_name() == name)
+            {
+                tracing::warn!(
+                    "Unknown seal criterion `{name}` is disabled in state keeper config"
+                );
+            }
+        }
+        sealers.retain(|sealer| {
+            let name = sealer.prom_criterion_name();
+            let is_disabled = config.disabled_seal_criteria.iter().any(|disabled| disabled == name);
+            if is_disabled {
+                tracing::warn!("Seal criterion `{name}` is disabled; make sure that the limit it enforces is checked otherwise");
+            }
+            !is_disabled
+        });
         Self { config, sealers }
     }
 
+    /// Adds a custom seal criterion. The criterion is checked after all previously added ones.
+    #[must_use]
+    pub fn with_criterion(mut self, criterion: impl SealCriterion) -> Self {
+        self.sealers.push(Box::new(criterion));
+        self
+    }
+
+    /// Returns names of the used seal criteria.
+    pub fn criterion_names(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.sealers
+            .iter()
+            .map(|sealer| sealer.prom_criterion_name())
+    }
+
     #[cfg(test)]
     pub(in crate::state_keeper) fn with_sealers(
         config: StateKeeperConfig,
@@ -167,3 +202,71 @@ impl ConditionalSealer for NoopSealer {
         SealResolution::NoSeal
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Criterion sealing the batch after a fixed number of transactions.
+    #[derive(Debug)]
+    struct TxCountCriterion(usize);
+
+    impl SealCriterion for TxCountCriterion {
+        fn should_seal(
+            &self,
+            _config: &StateKeeperConfig,
+            _block_open_timestamp_ms: u128,
+            tx_count: usize,
+            _block_data: &SealData,
+            _tx_data: &SealData,
+            _protocol_version: ProtocolVersionId,
+        ) -> SealResolution {
+            if tx_count >= self.0 {
+                SealResolution::IncludeAndSeal
+            } else {
+                SealResolution::NoSeal
+            }
+        }
+
+        fn prom_criterion_name(&self) -> &'static str {
+            "tx_count"
+        }
+    }
+
+    #[test]
+    fn disabling_seal_criteria() {
+        let config = StateKeeperConfig {
+            disabled_seal_criteria: vec!["pub_data_size".to_owned(), "unknown".to_owned()],
+            ..StateKeeperConfig::for_tests()
+        };
+        let sealer = SequencerSealer::new(config);
+        let names: Vec<_> = sealer.criterion_names().collect();
+        assert_eq!(
+            names,
+            [
+                "slots",
+                "gas",
+                "circuits",
+                "tx_encoding_size",
+                "gas_for_batch_tip"
+            ]
+        );
+    }
+
+    #[test]
+    fn plugging_custom_seal_criterion() {
+        let config = StateKeeperConfig {
+            disabled_seal_criteria: vec!["slots".to_owned()],
+            ..StateKeeperConfig::for_tests()
+        };
+        let sealer = SequencerSealer::new(config).with_criterion(TxCountCriterion(2));
+        assert_eq!(sealer.criterion_names().last(), Some("tx_count"));
+
+        let data = SealData::default();
+        let protocol_version = ProtocolVersionId::latest();
+        let resolution = sealer.should_seal_l1_batch(1, 0, 1, &data, &data, protocol_version);
+        assert_eq!(resolution, SealResolution::NoSeal);
+        let resolution = sealer.should_seal_l1_batch(1, 0, 2, &data, &data, protocol_version);
+        assert_eq!(resolution, SealResolution::IncludeAndSeal);
+    }
+}
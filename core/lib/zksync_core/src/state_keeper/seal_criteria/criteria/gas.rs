@@ -12,7 +12,7 @@ use crate::{
 /// bytecode is by far the largest one and with high probability
 /// the slots will run out before the other pubdata becomes too big
 #[derive(Debug)]
-pub(crate) struct GasCriterion;
+pub struct GasCriterion;
 
 impl SealCriterion for GasCriterion {
     fn should_seal(
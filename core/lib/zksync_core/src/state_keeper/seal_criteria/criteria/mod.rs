@@ -5,7 +5,7 @@ mod pubdata_bytes;
 mod slots;
 mod tx_encoding_size;
 
-pub(in crate::state_keeper) use self::{
+pub use self::{
     gas::GasCriterion, gas_for_batch_tip::GasForBatchTipCriterion,
     geometry_seal_criteria::CircuitsCriterion, pubdata_bytes::PubDataBytesCriterion,
     slots::SlotsCriterion, tx_encoding_size::TxEncodingSizeCriterion,
@@ -25,7 +25,13 @@ use zksync_utils::time::millis_since;
 mod conditional_sealer;
 pub(super) mod criteria;
 
-pub use self::conditional_sealer::{ConditionalSealer, NoopSealer, SequencerSealer};
+pub use self::{
+    conditional_sealer::{ConditionalSealer, NoopSealer, SequencerSealer},
+    criteria::{
+        CircuitsCriterion, GasCriterion, GasForBatchTipCriterion, PubDataBytesCriterion,
+        SlotsCriterion, TxEncodingSizeCriterion,
+    },
+};
 use super::{extractors, metrics::AGGREGATION_METRICS, updates::UpdatesManager};
 use crate::gas_tracker::{gas_count_from_tx_and_metrics, gas_count_from_writes};
 
@@ -104,9 +110,35 @@ impl SealData {
             gas_remaining: tx_metrics.gas_remaining,
         }
     }
+
+    /// Returns execution metrics (pubdata, circuit statistics etc.).
+    pub fn execution_metrics(&self) -> &ExecutionMetrics {
+        &self.execution_metrics
+    }
+
+    /// Returns the gas spent by the block or transaction for L1 operations.
+    pub fn gas_count(&self) -> BlockGasCount {
+        self.gas_count
+    }
+
+    /// Returns the bootloader encoding size of transactions.
+    pub fn cumulative_size(&self) -> usize {
+        self.cumulative_size
+    }
+
+    /// Returns deduplicated storage write metrics.
+    pub fn writes_metrics(&self) -> &DeduplicatedWritesMetrics {
+        &self.writes_metrics
+    }
 }
 
-pub(super) trait SealCriterion: fmt::Debug + Send + Sync + 'static {
+/// Deterministic criterion deciding whether an L1 batch should be sealed after executing a transaction.
+///
+/// Criteria are combined by [`SequencerSealer`]; the strictest [`SealResolution`] returned by any criterion wins.
+/// Besides built-in criteria, custom ones can be plugged in using [`SequencerSealer::with_criterion()`].
+pub trait SealCriterion: fmt::Debug + Send + Sync + 'static {
+    /// Decides whether the L1 batch should be sealed. `block_data` contains data for the entire batch
+    /// including the last transaction, and `tx_data` contains data for the last transaction only.
     fn should_seal(
         &self,
         config: &StateKeeperConfig,
@@ -117,6 +149,7 @@ pub(super) trait SealCriterion: fmt::Debug + Send + Sync + 'static {
         protocol_version: ProtocolVersionId,
     ) -> SealResolution;
 
+    /// Name of the criterion used in metrics, logs and [`StateKeeperConfig::disabled_seal_criteria`].
     // We need self here only for rust restrictions for creating an object from trait
     // https://doc.rust-lang.org/reference/items/traits.html#object-safety
     fn prom_criterion_name(&self) -> &'static str;
@@ -329,6 +329,46 @@ async fn batch_sealed_before_miniblock_does() {
         .await;
 }
 
+#[tokio::test]
+async fn in_progress_miniblock_is_sealed_on_shutdown() {
+    let config = StateKeeperConfig {
+        transaction_slots: 10,
+        ..StateKeeperConfig::default()
+    };
+    let sealer = SequencerSealer::with_sealers(config, vec![Box::new(SlotsCriterion)]);
+
+    TestScenario::new()
+        .seal_miniblock_on_shutdown()
+        .next_tx("First tx", random_tx(1), successful_exec())
+        .next_tx("Second tx", random_tx(2), successful_exec())
+        .shutdown("Stop signal")
+        .miniblock_sealed_with("Miniblock sealed on shutdown", |updates| {
+            assert_eq!(
+                updates.miniblock.executed_transactions.len(),
+                2,
+                "The miniblock should have 2 txs"
+            );
+        })
+        .run(sealer)
+        .await;
+}
+
+#[tokio::test]
+async fn in_progress_miniblock_is_discarded_on_shutdown_by_default() {
+    let config = StateKeeperConfig {
+        transaction_slots: 10,
+        ..StateKeeperConfig::default()
+    };
+    let sealer = SequencerSealer::with_sealers(config, vec![Box::new(SlotsCriterion)]);
+
+    // The scenario would panic if the state keeper tried to seal the miniblock.
+    TestScenario::new()
+        .next_tx("First tx", random_tx(1), successful_exec())
+        .shutdown("Stop signal")
+        .run(sealer)
+        .await;
+}
+
 #[tokio::test]
 async fn rejected_tx() {
     let config = StateKeeperConfig {
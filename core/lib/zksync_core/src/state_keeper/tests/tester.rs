@@ -53,6 +53,7 @@ pub(crate) struct TestScenario {
     pending_batch: Option<PendingBatchData>,
     l1_batch_seal_fn: Box<SealFn>,
     miniblock_seal_fn: Box<SealFn>,
+    seal_miniblock_on_shutdown: bool,
 }
 
 type SealFn = dyn FnMut(&UpdatesManager) -> bool + Send;
@@ -74,6 +75,7 @@ impl TestScenario {
             pending_batch: None,
             l1_batch_seal_fn: Box::new(|_| false),
             miniblock_seal_fn: Box::new(|_| false),
+            seal_miniblock_on_shutdown: false,
         }
     }
 
@@ -113,6 +115,18 @@ impl TestScenario {
         self
     }
 
+    /// Sends the stop signal to the state keeper the next time it requests a transaction from IO.
+    pub(crate) fn shutdown(mut self, description: &'static str) -> Self {
+        self.actions.push_back(ScenarioItem::Shutdown(description));
+        self
+    }
+
+    /// Configures IO to seal the in-progress miniblock when the state keeper is stopped.
+    pub(crate) fn seal_miniblock_on_shutdown(mut self) -> Self {
+        self.seal_miniblock_on_shutdown = true;
+        self
+    }
+
     /// Expect the state keeper to rollback the transaction (i.e. return to the mempool).
     pub(crate) fn tx_rollback(mut self, description: &'static str, tx: Transaction) -> Self {
         self.actions
@@ -341,6 +355,8 @@ enum ScenarioItem {
     NoTxsUntilNextAction(&'static str),
     /// Increments protocol version in IO state.
     IncrementProtocolVersion(&'static str),
+    /// Sends the stop signal to the state keeper instead of returning a transaction.
+    Shutdown(&'static str),
     Tx(&'static str, Transaction, TxExecutionResult),
     Rollback(&'static str, Transaction),
     Reject(&'static str, Transaction, Option<String>),
@@ -364,6 +380,7 @@ impl std::fmt::Debug for ScenarioItem {
                 .debug_tuple("IncrementProtocolVersion")
                 .field(descr)
                 .finish(),
+            Self::Shutdown(descr) => f.debug_tuple("Shutdown").field(descr).finish(),
             Self::Tx(descr, tx, result) => f
                 .debug_tuple("Tx")
                 .field(descr)
@@ -691,6 +708,11 @@ impl StateKeeperIO for TestIO {
     }
 
     async fn wait_for_next_tx(&mut self, max_wait: Duration) -> Option<Transaction> {
+        if let Some(ScenarioItem::Shutdown(_)) = self.scenario.actions.front() {
+            self.scenario.actions.pop_front();
+            self.stop_sender.send_replace(true);
+            return None;
+        }
         let action = self.pop_next_item("wait_for_next_tx");
 
         // Check whether we should ignore tx requests.
@@ -751,6 +773,10 @@ impl StateKeeperIO for TestIO {
         self.skipping_txs = false;
     }
 
+    fn should_seal_miniblock_on_shutdown(&self) -> bool {
+        self.scenario.seal_miniblock_on_shutdown
+    }
+
     async fn seal_l1_batch(
         &mut self,
         _witness_block_state: Option<WitnessBlockState>,
@@ -4,10 +4,11 @@ use std::{
 };
 
 use multivm::interface::VmExecutionResultAndLogs;
+use zksync_config::configs::chain::{MempoolConfig, TransactionOrdering};
 use zksync_dal::StorageProcessor;
-use zksync_mempool::{L2TxFilter, MempoolInfo, MempoolStore};
+use zksync_mempool::{EvictionReason, L2TxFilter, MempoolInfo, MempoolStore};
 use zksync_types::{
-    block::BlockGasCount, tx::ExecutionMetrics, Address, Nonce, PriorityOpId, Transaction,
+    block::BlockGasCount, tx::ExecutionMetrics, Address, Nonce, PriorityOpId, Transaction, H256,
 };
 
 use super::metrics::StateKeeperGauges;
@@ -17,15 +18,27 @@ use crate::gas_tracker::{gas_count_from_metrics, gas_count_from_tx_and_metrics};
 pub struct MempoolGuard(Arc<Mutex<MempoolStore>>);
 
 impl MempoolGuard {
-    pub async fn from_storage(storage_processor: &mut StorageProcessor<'_>, capacity: u64) -> Self {
+    pub async fn from_storage(
+        storage_processor: &mut StorageProcessor<'_>,
+        config: &MempoolConfig,
+        ordering: TransactionOrdering,
+    ) -> Self {
         let next_priority_id = storage_processor
             .transactions_dal()
             .next_priority_id()
             .await;
-        Self::new(next_priority_id, capacity)
+        let mut store =
+            MempoolStore::new(next_priority_id, config.capacity).with_ordering(ordering);
+        if let Some(limit) = config.max_transactions_per_account {
+            store = store.with_account_limit(limit);
+        }
+        if let Some(limit) = config.max_memory() {
+            store = store.with_memory_limit(limit);
+        }
+        Self(Arc::new(Mutex::new(store)))
     }
 
-    pub(super) fn new(next_priority_id: PriorityOpId, capacity: u64) -> Self {
+    pub(crate) fn new(next_priority_id: PriorityOpId, capacity: u64) -> Self {
         let store = MempoolStore::new(next_priority_id, capacity);
         Self(Arc::new(Mutex::new(store)))
     }
@@ -58,6 +71,13 @@ impl MempoolGuard {
             .rollback(rejected);
     }
 
+    pub fn remove_l2_transaction(&self, account: Address, nonce: Nonce, hash: H256) -> bool {
+        self.0
+            .lock()
+            .expect("failed to acquire mempool lock")
+            .remove_l2_transaction(account, nonce, hash)
+    }
+
     pub fn get_mempool_info(&mut self) -> MempoolInfo {
         self.0
             .lock()
@@ -65,6 +85,13 @@ impl MempoolGuard {
             .get_mempool_info()
     }
 
+    pub fn requeue_evicted_transactions(&mut self, evicted: Vec<(H256, EvictionReason)>) {
+        self.0
+            .lock()
+            .expect("failed to acquire mempool lock")
+            .requeue_evicted_transactions(evicted);
+    }
+
     #[cfg(test)]
     pub fn stats(&self) -> zksync_mempool::MempoolStats {
         self.0
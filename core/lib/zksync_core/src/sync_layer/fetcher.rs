@@ -302,6 +302,7 @@ impl MainNodeFetcher {
         let prev_miniblock_number = MiniblockNumber(block_number.0.saturating_sub(1));
         self.client.forget_miniblock(prev_miniblock_number);
         self.actions.push_actions(new_actions).await;
+        self.sync_state.set_fetched_block(block_number);
 
         total_latency.observe();
         Ok(true)
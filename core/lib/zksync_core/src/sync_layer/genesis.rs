@@ -8,7 +8,7 @@ use zksync_types::{
 };
 
 use super::client::MainNodeClient;
-use crate::genesis::{ensure_genesis_state, GenesisParams};
+use crate::genesis::{ensure_genesis_state, CustomGenesisState, GenesisParams};
 
 pub async fn perform_genesis_if_needed(
     storage: &mut StorageProcessor<'_>,
@@ -95,6 +95,8 @@ async fn create_genesis_params(client: &dyn MainNodeClient) -> anyhow::Result<Ge
         first_validator,
         first_l1_verifier_config,
         first_verifier_address,
+        // Custom genesis state cannot be recovered from the main node; if it was used, the genesis root hash won't match.
+        custom_state: CustomGenesisState::default(),
     })
 }
 
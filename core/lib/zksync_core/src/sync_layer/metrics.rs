@@ -2,7 +2,10 @@
 
 use std::time::Duration;
 
-use vise::{Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, Metrics};
+use vise::{
+    Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, LabeledFamily,
+    Metrics,
+};
 use zksync_types::aggregated_operations::AggregatedActionType;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
@@ -70,3 +73,35 @@ pub(super) struct ActionQueueMetrics {
 
 #[vise::register]
 pub(super) static QUEUE_METRICS: vise::Global<ActionQueueMetrics> = vise::Global::new();
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "result", rename_all = "snake_case")]
+pub(super) enum RequestResult {
+    Success,
+    Error,
+}
+
+/// Metrics for main node endpoints used by [`FailoverMainNodeClient`](super::FailoverMainNodeClient).
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "external_node_main_node_endpoint")]
+pub(super) struct EndpointMetrics {
+    /// Number of requests sent to a main node endpoint, including health checks.
+    #[metrics(labels = ["endpoint", "result"])]
+    pub requests: LabeledFamily<(String, RequestResult), Counter, 2>,
+    /// Latency of requests to a main node endpoint.
+    #[metrics(buckets = Buckets::LATENCIES, labels = ["endpoint"])]
+    pub latency: LabeledFamily<String, Histogram<Duration>>,
+    /// Number of miniblocks the head of an endpoint lags behind the most recent head among all endpoints.
+    #[metrics(labels = ["endpoint"])]
+    pub head_lag: LabeledFamily<String, Gauge<u64>>,
+    /// Whether an endpoint is considered healthy (1) or not (0).
+    #[metrics(labels = ["endpoint"])]
+    pub healthy: LabeledFamily<String, Gauge<u64>>,
+    /// Index of the endpoint requests are currently sent to; 0 corresponds to the main node URL.
+    pub active: Gauge<usize>,
+    /// Number of times requests were switched to another endpoint.
+    pub switches: Counter,
+}
+
+#[vise::register]
+pub(super) static ENDPOINT_METRICS: vise::Global<EndpointMetrics> = vise::Global::new();
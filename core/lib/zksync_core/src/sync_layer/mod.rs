@@ -1,6 +1,7 @@
 pub mod batch_status_updater;
 mod client;
 pub mod external_io;
+mod failover;
 pub mod fetcher;
 pub mod genesis;
 mod metrics;
@@ -10,6 +11,9 @@ mod sync_state;
 mod tests;
 
 pub use self::{
-    client::MainNodeClient, external_io::ExternalIO, sync_action::ActionQueue,
+    client::MainNodeClient,
+    external_io::ExternalIO,
+    failover::{FailoverMainNodeClient, MainNodeFailoverConfig},
+    sync_action::ActionQueue,
     sync_state::SyncState,
 };
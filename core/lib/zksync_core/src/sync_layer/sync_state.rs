@@ -1,9 +1,16 @@
-use std::sync::{Arc, RwLock};
+use std::{
+    collections::VecDeque,
+    sync::{Arc, RwLock},
+    time::{Duration, Instant},
+};
 
 use async_trait::async_trait;
 use serde::Serialize;
 use zksync_health_check::{CheckHealth, Health, HealthStatus};
-use zksync_types::MiniblockNumber;
+use zksync_types::{
+    api::en::{SyncComponent, SyncStall},
+    MiniblockNumber,
+};
 
 use crate::metrics::EN_METRICS;
 
@@ -21,6 +28,21 @@ pub struct SyncState {
 /// A threshold constant intended to keep the sync status less flaky.
 /// This gives the external node some room to fetch new miniblocks without losing the sync status.
 const SYNC_MINIBLOCK_DELTA: u32 = 10;
+/// Time window used to estimate the sync speed.
+const SYNC_SPEED_WINDOW: Duration = Duration::from_secs(60);
+/// Time without progress after which a component is considered stalled.
+const STALL_THRESHOLD: Duration = Duration::from_secs(60);
+
+/// Snapshot of the syncing progress returned by [`SyncState::progress()`].
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct SyncProgress {
+    pub is_synced: bool,
+    pub main_node_block: Option<MiniblockNumber>,
+    pub fetched_block: Option<MiniblockNumber>,
+    pub local_block: Option<MiniblockNumber>,
+    pub estimated_time_to_head: Option<Duration>,
+    pub stalls: Vec<SyncStall>,
+}
 
 impl SyncState {
     pub(crate) fn get_main_node_block(&self) -> MiniblockNumber {
@@ -51,6 +73,14 @@ impl SyncState {
         inner.update_sync_metric();
     }
 
+    pub(crate) fn set_fetched_block(&self, block: MiniblockNumber) {
+        let mut inner = self.inner.write().unwrap();
+        inner.fetched_block = Some(block);
+        inner.last_fetched_at = Some(Instant::now());
+        EN_METRICS.fetched_miniblock.set(block.0.into());
+        inner.update_sync_metric();
+    }
+
     pub(super) fn set_local_block(&self, block: MiniblockNumber) {
         let mut inner = self.inner.write().unwrap();
         if let Some(main_node_block) = inner.main_node_block {
@@ -63,7 +93,8 @@ impl SyncState {
                 );
             }
         }
-        inner.local_block = Some(block);
+        inner.record_local_block(block, Instant::now());
+        EN_METRICS.applied_miniblock.set(block.0.into());
         inner.update_sync_metric();
     }
 
@@ -71,6 +102,11 @@ impl SyncState {
         let inner = self.inner.read().unwrap();
         inner.is_synced().0
     }
+
+    /// Returns the detailed syncing progress, including stalled components.
+    pub(crate) fn progress(&self) -> SyncProgress {
+        self.inner.read().unwrap().progress(Instant::now())
+    }
 }
 
 #[async_trait]
@@ -87,7 +123,12 @@ impl CheckHealth for SyncState {
 #[derive(Debug, Default)]
 struct SyncStateInner {
     main_node_block: Option<MiniblockNumber>,
+    fetched_block: Option<MiniblockNumber>,
     local_block: Option<MiniblockNumber>,
+    last_fetched_at: Option<Instant>,
+    last_applied_at: Option<Instant>,
+    /// Recently applied miniblocks used to estimate the sync speed.
+    applied_samples: VecDeque<(Instant, MiniblockNumber)>,
 }
 
 impl SyncStateInner {
@@ -104,12 +145,123 @@ impl SyncStateInner {
         }
     }
 
+    fn record_local_block(&mut self, block: MiniblockNumber, now: Instant) {
+        if self
+            .local_block
+            .map_or(true, |local_block| block > local_block)
+        {
+            self.last_applied_at = Some(now);
+            self.applied_samples.push_back((now, block));
+        }
+        self.local_block = Some(block);
+        while let Some(&(sampled_at, _)) = self.applied_samples.front() {
+            if now.duration_since(sampled_at) <= SYNC_SPEED_WINDOW {
+                break;
+            }
+            self.applied_samples.pop_front();
+        }
+    }
+
+    fn estimated_time_to_head(&self, now: Instant) -> Option<Duration> {
+        let (main_node_block, local_block) = (self.main_node_block?, self.local_block?);
+        let remaining_blocks = main_node_block.0.checked_sub(local_block.0)?;
+        let mut recent_samples = self
+            .applied_samples
+            .iter()
+            .filter(|(sampled_at, _)| now.duration_since(*sampled_at) <= SYNC_SPEED_WINDOW);
+        let &(first_sampled_at, first_block) = recent_samples.next()?;
+        let &(last_sampled_at, last_block) = recent_samples.last()?;
+        let elapsed = last_sampled_at.duration_since(first_sampled_at);
+        let applied_blocks = last_block.0 - first_block.0;
+        if elapsed.is_zero() || applied_blocks == 0 {
+            return None;
+        }
+        let blocks_per_sec = f64::from(applied_blocks) / elapsed.as_secs_f64();
+        Some(Duration::from_secs_f64(
+            f64::from(remaining_blocks) / blocks_per_sec,
+        ))
+    }
+
+    fn stalls(&self, now: Instant) -> Vec<SyncStall> {
+        let mut stalls = vec![];
+        let local_block = self.local_block.unwrap_or_default();
+        let fetched_block = self.fetched_block.unwrap_or(local_block);
+        let main_node_block = self.main_node_block.unwrap_or_default();
+
+        // If the state keeper lags behind, the fetcher may be blocked by the full action queue,
+        // so it's not considered stalled in this case.
+        if let Some(last_applied_at) = self.last_applied_at {
+            let idle_time = now.duration_since(last_applied_at);
+            if fetched_block > local_block && idle_time > STALL_THRESHOLD {
+                stalls.push(SyncStall {
+                    component: SyncComponent::StateKeeper,
+                    reason: format!(
+                        "no miniblocks were applied for {}s; {} fetched miniblocks are pending",
+                        idle_time.as_secs(),
+                        fetched_block.0 - local_block.0
+                    ),
+                });
+                return stalls;
+            }
+        }
+        if let Some(last_fetched_at) = self.last_fetched_at {
+            let idle_time = now.duration_since(last_fetched_at);
+            if main_node_block > fetched_block && idle_time > STALL_THRESHOLD {
+                stalls.push(SyncStall {
+                    component: SyncComponent::Fetcher,
+                    reason: format!(
+                        "no miniblocks were fetched for {}s; main node is at miniblock #{main_node_block}, \
+                         last fetched miniblock is #{fetched_block}",
+                        idle_time.as_secs()
+                    ),
+                });
+            }
+        }
+        stalls
+    }
+
+    fn progress(&self, now: Instant) -> SyncProgress {
+        let is_synced = self.is_synced().0;
+        SyncProgress {
+            is_synced,
+            main_node_block: self.main_node_block,
+            fetched_block: self.fetched_block,
+            local_block: self.local_block,
+            estimated_time_to_head: if is_synced {
+                None
+            } else {
+                self.estimated_time_to_head(now)
+            },
+            stalls: self.stalls(now),
+        }
+    }
+
     fn update_sync_metric(&self) {
         let (is_synced, lag) = self.is_synced();
         EN_METRICS.synced.set(is_synced.into());
         if let Some(lag) = lag {
             EN_METRICS.sync_lag.set(lag.into());
         }
+
+        let progress = self.progress(Instant::now());
+        EN_METRICS
+            .estimated_time_to_head
+            .set(progress.estimated_time_to_head.unwrap_or_default());
+        for component in [SyncComponent::Fetcher, SyncComponent::StateKeeper] {
+            let is_stalled = progress
+                .stalls
+                .iter()
+                .any(|stall| stall.component == component);
+            EN_METRICS.sync_stalled[&component_label(component)].set(is_stalled.into());
+        }
+    }
+}
+
+fn component_label(component: SyncComponent) -> &'static str {
+    match component {
+        SyncComponent::Fetcher => "fetcher",
+        SyncComponent::StateKeeper => "state_keeper",
+        SyncComponent::Tree => "tree",
     }
 }
 
@@ -192,6 +344,64 @@ mod tests {
         assert!(sync_state.is_synced());
     }
 
+    #[test]
+    fn estimating_time_to_head() {
+        let start = Instant::now();
+        let mut inner = SyncStateInner {
+            main_node_block: Some(MiniblockNumber(1_000)),
+            ..SyncStateInner::default()
+        };
+        inner.record_local_block(MiniblockNumber(0), start);
+        assert_eq!(inner.estimated_time_to_head(start), None);
+
+        inner.record_local_block(MiniblockNumber(100), start + Duration::from_secs(10));
+        let time_to_head = inner.estimated_time_to_head(start + Duration::from_secs(10));
+        assert_eq!(time_to_head, Some(Duration::from_secs(90)));
+
+        // Old samples are not taken into account.
+        let now = start + SYNC_SPEED_WINDOW + Duration::from_secs(5);
+        inner.record_local_block(MiniblockNumber(500), now);
+        let time_to_head = inner.estimated_time_to_head(now).unwrap();
+        let expected_secs = 500.0 / (400.0 / (SYNC_SPEED_WINDOW.as_secs_f64() - 5.0));
+        assert!(
+            (time_to_head.as_secs_f64() - expected_secs).abs() < 1.0,
+            "{time_to_head:?}"
+        );
+    }
+
+    #[test]
+    fn detecting_stalls() {
+        let start = Instant::now();
+        let mut inner = SyncStateInner {
+            main_node_block: Some(MiniblockNumber(100)),
+            fetched_block: Some(MiniblockNumber(50)),
+            last_fetched_at: Some(start),
+            ..SyncStateInner::default()
+        };
+        inner.record_local_block(MiniblockNumber(10), start);
+        assert!(inner.stalls(start + Duration::from_secs(1)).is_empty());
+
+        let stalls = inner.stalls(start + STALL_THRESHOLD * 2);
+        assert_eq!(stalls.len(), 1);
+        assert_eq!(stalls[0].component, SyncComponent::StateKeeper);
+        assert!(
+            stalls[0].reason.contains("40 fetched miniblocks"),
+            "{stalls:?}"
+        );
+
+        // The state keeper has processed all fetched miniblocks; the fetcher is stalled.
+        let now = start + STALL_THRESHOLD * 2;
+        inner.record_local_block(MiniblockNumber(50), now);
+        let stalls = inner.stalls(now);
+        assert_eq!(stalls.len(), 1);
+        assert_eq!(stalls[0].component, SyncComponent::Fetcher);
+
+        let progress = inner.progress(now);
+        assert!(!progress.is_synced);
+        assert_eq!(progress.fetched_block, Some(MiniblockNumber(50)));
+        assert_eq!(progress.local_block, Some(MiniblockNumber(50)));
+    }
+
     #[test]
     fn test_sync_state_doesnt_panic_on_main_node_block() {
         let sync_state = SyncState::default();
@@ -2,8 +2,8 @@ use zksync_config::{
     configs::{
         api::{HealthCheckConfig, MerkleTreeApiConfig, Web3JsonRpcConfig},
         chain::{
-            CircuitBreakerConfig, MempoolConfig, NetworkConfig, OperationsManagerConfig,
-            StateKeeperConfig,
+            BaseTokenConfig, CircuitBreakerConfig, FeeInputConfig, MempoolConfig, NetworkConfig,
+            OperationsManagerConfig, StateKeeperConfig,
         },
         fri_prover_group::FriProverGroupConfig,
         house_keeper::HouseKeeperConfig,
@@ -27,6 +27,8 @@ pub struct TempConfigStore {
     pub web3_json_rpc_config: Option<Web3JsonRpcConfig>,
     pub circuit_breaker_config: Option<CircuitBreakerConfig>,
     pub mempool_config: Option<MempoolConfig>,
+    pub base_token_config: Option<BaseTokenConfig>,
+    pub fee_input_config: Option<FeeInputConfig>,
     pub network_config: Option<NetworkConfig>,
     pub operations_manager_config: Option<OperationsManagerConfig>,
     pub state_keeper_config: Option<StateKeeperConfig>,
@@ -9,7 +9,9 @@ use std::{
 use anyhow::Context as _;
 use async_trait::async_trait;
 use tokio::sync::watch;
+use zksync_config::configs::database::{RocksdbCompactionStyle, RocksdbTuning};
 use zksync_dal::{ConnectionPool, StorageProcessor};
+use zksync_storage::{rocksdb::DBCompactionStyle, RocksDBOptions};
 use zksync_types::{L1BatchNumber, ProtocolVersionId};
 
 #[cfg(test)]
@@ -163,6 +165,18 @@ pub(crate) async fn pending_protocol_version(
     Ok(snapshot_recovery.protocol_version)
 }
 
+/// Applies RocksDB tuning options from the config to the provided RocksDB `options`.
+pub(crate) fn apply_rocksdb_tuning(options: &mut RocksDBOptions, tuning: &RocksdbTuning) {
+    options.compaction_style = tuning.compaction_style.map(|style| match style {
+        RocksdbCompactionStyle::Level => DBCompactionStyle::Level,
+        RocksdbCompactionStyle::Universal => DBCompactionStyle::Universal,
+        RocksdbCompactionStyle::Fifo => DBCompactionStyle::Fifo,
+    });
+    options.write_buffer_size = tuning.write_buffer_size;
+    options.max_background_jobs = tuning.max_background_jobs;
+    options.enable_statistics = tuning.statistics;
+}
+
 #[cfg(test)]
 mod tests {
     use zksync_types::L2ChainId;
@@ -122,7 +122,7 @@ fn main() -> anyhow::Result<()> {
         .context("Invalid log format")?;
     let _guard = vlog::ObservabilityBuilder::new()
         .with_log_format(log_format)
-        .build();
+        .build()?;
 
     MainNodeBuilder::new()
         .add_pools_layer()?
@@ -32,8 +32,8 @@ impl WiringLayer for MainBatchExecutorLayer {
     async fn wire(self: Box<Self>, mut context: ServiceContext<'_>) -> Result<(), WiringError> {
         let master_pool = context.get_resource::<MasterPoolResource>().await?;
 
-        let builder = MainBatchExecutor::new(
-            self.db_config.state_keeper_db_path,
+        let mut builder = MainBatchExecutor::new(
+            self.db_config.state_keeper_db_path.clone(),
             master_pool.get_singleton().await?,
             self.state_keeper_config.max_allowed_l2_tx_gas_limit.into(),
             self.state_keeper_config.save_call_traces,
@@ -41,6 +41,10 @@ impl WiringLayer for MainBatchExecutorLayer {
             self.state_keeper_config.enum_index_migration_chunk_size(),
             false,
         );
+        builder.set_db_options(
+            self.db_config.state_keeper_db_block_cache_size(),
+            &self.db_config.state_keeper_db_tuning(),
+        );
 
         context.insert_resource(BatchExecutorResource(Unique::new(Box::new(builder))))?;
         Ok(())
@@ -57,7 +57,12 @@ impl MempoolIOLayer {
             .access_storage()
             .await
             .context("Access storage to build mempool")?;
-        let mempool = MempoolGuard::from_storage(&mut storage, self.mempool_config.capacity).await;
+        let mempool = MempoolGuard::from_storage(
+            &mut storage,
+            &self.mempool_config,
+            self.state_keeper_config.transaction_ordering,
+        )
+        .await;
         mempool.register_metrics();
         Ok(mempool)
     }
@@ -46,7 +46,7 @@ async fn main() -> anyhow::Result<()> {
             .expect("Invalid Sentry URL")
             .with_sentry_environment(environment);
     }
-    let _guard = builder.build();
+    let _guard = builder.build()?;
 
     let config = LoadtestConfig::from_env()
         .expect("Config parameters should be loaded from env or from default values");
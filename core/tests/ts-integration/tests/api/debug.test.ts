@@ -48,7 +48,7 @@ describe('Debug methods', () => {
             output: '0x',
             revertReason: 'Error function_selector = 0x, data = 0x',
             to: BOOTLOADER_FORMAL_ADDRESS,
-            type: 'Call',
+            type: 'CALL',
             value: expect.any(String),
             calls: expect.any(Array)
         };
@@ -73,14 +73,14 @@ describe('Debug methods', () => {
             input: expect.any(String),
             output: '0x',
             to: BOOTLOADER_FORMAL_ADDRESS,
-            type: 'Call',
+            type: 'CALL',
             value: expect.any(String),
             calls: expect.any(Array)
             // We intentionally skip `error` and `revertReason` fields: the block may contain failing txs
             // generated by other tests.
         };
         for (let i = 0; i < blockCallTrace.length; i++) {
-            expect(blockCallTrace[i]).toMatchObject({ result: expectedTraceInBlock });
+            expect(blockCallTrace[i]).toMatchObject({ txHash: expect.any(String), result: expectedTraceInBlock });
             expect(blockCallTrace[i]).toEqual(blockCallTraceWithTracer[i]);
         }
 
@@ -97,7 +97,7 @@ describe('Debug methods', () => {
             output: '0x',
             revertReason: null,
             to: BOOTLOADER_FORMAL_ADDRESS,
-            type: 'Call',
+            type: 'CALL',
             value: '0x0',
             calls: expect.any(Array)
         };
@@ -0,0 +1,127 @@
+//! Selection of the proving backend on prover startup.
+
+use std::{env, fs, thread};
+
+use anyhow::Context as _;
+use zksync_config::configs::fri_prover::ProverBackend;
+
+/// Directory exposed by the NVIDIA kernel driver with an entry per installed GPU.
+const NVIDIA_GPUS_PATH: &str = "/proc/driver/nvidia/gpus";
+
+/// Proving backend resolved from [`ProverBackend`] and the available hardware.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum ResolvedBackend {
+    Gpu { device_count: usize },
+    Cpu { thread_count: usize },
+}
+
+impl ResolvedBackend {
+    pub(crate) fn new(backend: ProverBackend) -> anyhow::Result<Self> {
+        let thread_count = thread::available_parallelism()
+            .context("cannot determine available parallelism")?
+            .get();
+        let device_count = detect_cuda_devices();
+        tracing::info!(
+            "Detected {device_count} CUDA device(s) and {thread_count} CPU thread(s); \
+             prover is built with GPU support: {}",
+            cfg!(feature = "gpu")
+        );
+        Self::resolve(backend, cfg!(feature = "gpu"), device_count, thread_count)
+    }
+
+    fn resolve(
+        backend: ProverBackend,
+        supports_gpu: bool,
+        device_count: usize,
+        thread_count: usize,
+    ) -> anyhow::Result<Self> {
+        let cpu = Self::Cpu { thread_count };
+        match backend {
+            ProverBackend::Cpu => Ok(cpu),
+            ProverBackend::Gpu => {
+                anyhow::ensure!(
+                    supports_gpu,
+                    "GPU proving is requested, but the prover is built without the `gpu` feature"
+                );
+                anyhow::ensure!(
+                    device_count > 0,
+                    "GPU proving is requested, but no CUDA devices are available"
+                );
+                Ok(Self::Gpu { device_count })
+            }
+            ProverBackend::Auto if supports_gpu && device_count > 0 => {
+                Ok(Self::Gpu { device_count })
+            }
+            ProverBackend::Auto => {
+                if supports_gpu {
+                    tracing::warn!("No CUDA devices are available; falling back to CPU proving");
+                }
+                Ok(cpu)
+            }
+        }
+    }
+}
+
+fn detect_cuda_devices() -> usize {
+    let installed_count = fs::read_dir(NVIDIA_GPUS_PATH).map_or(0, |entries| entries.count());
+    let visible_devices = env::var("CUDA_VISIBLE_DEVICES").ok();
+    visible_device_count(installed_count, visible_devices.as_deref())
+}
+
+/// Applies `CUDA_VISIBLE_DEVICES` to the number of installed devices. Like the CUDA runtime, stops parsing
+/// the device list on the first invalid entry (e.g., `-1`, which is commonly used to hide all devices).
+fn visible_device_count(installed_count: usize, visible_devices: Option<&str>) -> usize {
+    let Some(visible_devices) = visible_devices else {
+        return installed_count;
+    };
+    let visible_count = visible_devices
+        .split(',')
+        .map(str::trim)
+        .take_while(|device| {
+            if let Ok(index) = device.parse::<usize>() {
+                index < installed_count
+            } else {
+                device.starts_with("GPU-") || device.starts_with("MIG-")
+            }
+        })
+        .count();
+    visible_count.min(installed_count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn applying_visible_devices() {
+        assert_eq!(visible_device_count(2, None), 2);
+        assert_eq!(visible_device_count(2, Some("0")), 1);
+        assert_eq!(visible_device_count(2, Some("0,1")), 2);
+        assert_eq!(visible_device_count(2, Some("")), 0);
+        assert_eq!(visible_device_count(2, Some("-1")), 0);
+        assert_eq!(visible_device_count(2, Some("1,-1,0")), 1);
+        assert_eq!(visible_device_count(2, Some("5")), 0);
+        assert_eq!(visible_device_count(1, Some("GPU-8932f937-d72c")), 1);
+        assert_eq!(visible_device_count(0, Some("0")), 0);
+    }
+
+    #[test]
+    fn resolving_backend() {
+        let cpu = ResolvedBackend::Cpu { thread_count: 16 };
+        let gpu = ResolvedBackend::Gpu { device_count: 1 };
+        let resolve = |backend, supports_gpu, device_count| {
+            ResolvedBackend::resolve(backend, supports_gpu, device_count, 16)
+        };
+
+        assert_eq!(resolve(ProverBackend::Auto, true, 1).unwrap(), gpu);
+        assert_eq!(resolve(ProverBackend::Auto, true, 0).unwrap(), cpu);
+        assert_eq!(resolve(ProverBackend::Auto, false, 1).unwrap(), cpu);
+        assert_eq!(resolve(ProverBackend::Cpu, true, 1).unwrap(), cpu);
+        assert_eq!(resolve(ProverBackend::Gpu, true, 1).unwrap(), gpu);
+
+        let err = resolve(ProverBackend::Gpu, true, 0).unwrap_err();
+        assert!(err.to_string().contains("no CUDA devices"), "{err}");
+        let err = resolve(ProverBackend::Gpu, false, 1).unwrap_err();
+        assert!(err.to_string().contains("`gpu` feature"), "{err}");
+    }
+}
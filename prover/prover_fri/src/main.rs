@@ -25,6 +25,9 @@ use zksync_queued_job_processor::JobProcessor;
 use zksync_types::basic_fri_types::CircuitIdRoundTuple;
 use zksync_utils::wait_for_tasks::wait_for_tasks;
 
+use crate::backend::ResolvedBackend;
+
+mod backend;
 mod gpu_prover_job_processor;
 mod metrics;
 mod prover_job_processor;
@@ -69,7 +72,7 @@ async fn main() -> anyhow::Result<()> {
             .expect("Invalid Sentry URL")
             .with_sentry_environment(observability_config.sentry_environment);
     }
-    let _guard = builder.build();
+    let _guard = builder.build()?;
 
     // Report whether sentry is running after the logging subsystem was initialized.
     if let Some(sentry_url) = observability_config.sentry_url {
@@ -79,6 +82,8 @@ async fn main() -> anyhow::Result<()> {
     }
 
     let prover_config = FriProverConfig::from_env().context("FriProverConfig::from_env()")?;
+    let backend = ResolvedBackend::new(prover_config.backend).context("ResolvedBackend::new()")?;
+    tracing::info!("Using {backend:?} proving backend");
     let exporter_config = PrometheusExporterConfig::pull(prover_config.prometheus_port);
 
     let (stop_signal_sender, stop_signal_receiver) = oneshot::channel();
@@ -120,38 +125,57 @@ async fn main() -> anyhow::Result<()> {
     );
     let postgres_config = PostgresConfig::from_env().context("PostgresConfig::from_env()")?;
 
-    // There are 2 threads using the connection pool:
+    // The GPU prover has 2 threads using the connection pool:
     // 1. The prover thread, which is used to update the prover job status.
     // 2. The socket listener thread, which is used to update the prover instance status.
-    const MAX_POOL_SIZE_FOR_PROVER: u32 = 2;
+    // The CPU prover only has the prover thread.
+    let max_pool_size = match backend {
+        ResolvedBackend::Gpu { .. } => 2,
+        ResolvedBackend::Cpu { .. } => 1,
+    };
 
-    let pool = ConnectionPool::builder(postgres_config.prover_url()?, MAX_POOL_SIZE_FOR_PROVER)
+    let pool = ConnectionPool::builder(postgres_config.prover_url()?, max_pool_size)
         .build()
         .await
         .context("failed to build a connection pool")?;
     let port = prover_config.witness_vector_receiver_port;
-    let prover_tasks = get_prover_tasks(
-        prover_config,
-        stop_receiver.clone(),
-        object_store_factory,
-        public_blob_store,
-        pool,
-        circuit_ids_for_round_to_be_proven,
-    )
-    .await
-    .context("get_prover_tasks()")?;
+    let prover_tasks = match backend {
+        ResolvedBackend::Gpu { .. } => get_gpu_prover_tasks(
+            prover_config,
+            stop_receiver.clone(),
+            object_store_factory,
+            public_blob_store,
+            pool,
+            circuit_ids_for_round_to_be_proven,
+        )
+        .await
+        .context("get_gpu_prover_tasks()")?,
+        ResolvedBackend::Cpu { thread_count } => {
+            tracing::info!("CPU proving will use {thread_count} thread(s)");
+            get_cpu_prover_tasks(
+                prover_config,
+                stop_receiver.clone(),
+                object_store_factory,
+                public_blob_store,
+                pool,
+                circuit_ids_for_round_to_be_proven,
+            )
+            .await
+            .context("get_cpu_prover_tasks()")?
+        }
+    };
 
     let mut tasks = vec![tokio::spawn(exporter_config.run(stop_receiver))];
     tasks.extend(prover_tasks);
 
     let particular_crypto_alerts = None;
-    let graceful_shutdown = match cfg!(feature = "gpu") {
-        true => Some(
+    let graceful_shutdown = match backend {
+        ResolvedBackend::Gpu { .. } => Some(
             graceful_shutdown(port)
                 .await
                 .context("failed to prepare graceful shutdown future")?,
         ),
-        false => None,
+        ResolvedBackend::Cpu { .. } => None,
     };
     let tasks_allowed_to_finish = false;
     tokio::select! {
@@ -165,8 +189,7 @@ async fn main() -> anyhow::Result<()> {
     Ok(())
 }
 
-#[cfg(not(feature = "gpu"))]
-async fn get_prover_tasks(
+async fn get_cpu_prover_tasks(
     prover_config: FriProverConfig,
     stop_receiver: Receiver<bool>,
     store_factory: ObjectStoreFactory,
@@ -199,8 +222,21 @@ async fn get_prover_tasks(
     Ok(vec![tokio::spawn(prover.run(stop_receiver, None))])
 }
 
+#[cfg(not(feature = "gpu"))]
+async fn get_gpu_prover_tasks(
+    _prover_config: FriProverConfig,
+    _stop_receiver: Receiver<bool>,
+    _store_factory: ObjectStoreFactory,
+    _public_blob_store: Option<Arc<dyn ObjectStore>>,
+    _pool: ConnectionPool,
+    _circuit_ids_for_round_to_be_proven: Vec<CircuitIdRoundTuple>,
+) -> anyhow::Result<Vec<JoinHandle<anyhow::Result<()>>>> {
+    // Unreachable in practice: the GPU backend is never resolved if the prover is built without GPU support.
+    anyhow::bail!("prover is built without the `gpu` feature")
+}
+
 #[cfg(feature = "gpu")]
-async fn get_prover_tasks(
+async fn get_gpu_prover_tasks(
     prover_config: FriProverConfig,
     stop_receiver: Receiver<bool>,
     store_factory: ObjectStoreFactory,
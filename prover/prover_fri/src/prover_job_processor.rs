@@ -23,7 +23,7 @@ use zksync_prover_fri_types::{
         },
         recursion_layer_proof_config, ZkSyncDefaultRoundFunction,
     },
-    CircuitWrapper, FriProofWrapper, ProverJob, ProverServiceDataKey,
+    get_current_pod_name, CircuitWrapper, FriProofWrapper, ProverJob, ProverServiceDataKey,
 };
 use zksync_prover_fri_utils::fetch_next_circuit;
 use zksync_queued_job_processor::{async_trait, JobProcessor};
@@ -53,10 +53,11 @@ pub struct Prover {
     // Empty means all jobs are picked.
     circuit_ids_for_round_to_be_proven: Vec<CircuitIdRoundTuple>,
     vk_commitments: L1VerifierConfig,
+    // Used to identify jobs picked by this prover when sending heartbeats.
+    pod_name: String,
 }
 
 impl Prover {
-    #[allow(dead_code)]
     pub fn new(
         blob_store: Arc<dyn ObjectStore>,
         public_blob_store: Option<Arc<dyn ObjectStore>>,
@@ -74,6 +75,7 @@ impl Prover {
             setup_load_mode,
             circuit_ids_for_round_to_be_proven,
             vk_commitments,
+            pod_name: get_current_pod_name(),
         }
     }
 
@@ -317,9 +319,55 @@ impl JobProcessor for Prover {
             .map(|attempts| attempts.unwrap_or(0))
             .context("failed to get job attempts for Prover")
     }
+
+    async fn send_heartbeat(&self, job_id: &u32) -> anyhow::Result<bool> {
+        let mut storage = self
+            .prover_connection_pool
+            .access_storage()
+            .await
+            .context("failed to acquire DB connection for Prover")?;
+        storage
+            .fri_prover_jobs_dal()
+            .record_heartbeat(*job_id, &self.pod_name)
+            .await
+            .context("record_heartbeat()")
+    }
+
+    async fn should_preempt(&self, job_id: &u32) -> anyhow::Result<bool> {
+        let mut storage = self
+            .prover_connection_pool
+            .access_storage()
+            .await
+            .context("failed to acquire DB connection for Prover")?;
+        let protocol_versions = storage
+            .fri_protocol_versions_dal()
+            .protocol_version_for(&self.vk_commitments)
+            .await;
+        storage
+            .fri_prover_jobs_dal()
+            .should_preempt_job(
+                *job_id,
+                &self.circuit_ids_for_round_to_be_proven,
+                &protocol_versions,
+            )
+            .await
+            .context("should_preempt_job()")
+    }
+
+    async fn save_preempted(&self, job_id: u32) -> anyhow::Result<()> {
+        let mut storage = self
+            .prover_connection_pool
+            .access_storage()
+            .await
+            .context("failed to acquire DB connection for Prover")?;
+        storage
+            .fri_prover_jobs_dal()
+            .requeue_preempted_job(job_id)
+            .await
+            .context("requeue_preempted_job()")
+    }
 }
 
-#[allow(dead_code)]
 pub fn load_setup_data_cache(config: &FriProverConfig) -> anyhow::Result<SetupLoadMode> {
     Ok(match config.setup_load_mode {
         zksync_config::configs::fri_prover::SetupLoadMode::FromDisk => SetupLoadMode::FromDisk,
@@ -2,7 +2,10 @@ use std::sync::Arc;
 
 use anyhow::Context as _;
 use serde::Serialize;
-use zksync_config::configs::{object_store::ObjectStoreMode, FriProverConfig, ObjectStoreConfig};
+use zksync_config::configs::{
+    object_store::{ObjectStoreCompression, ObjectStoreEncryption, ObjectStoreMode},
+    FriProverConfig, ObjectStoreConfig,
+};
 use zksync_env_config::FromEnv;
 use zksync_object_store::{bincode, ObjectStoreFactory};
 use zksync_prover_fri::prover_job_processor::Prover;
@@ -31,6 +34,19 @@ async fn prover_and_assert_base_layer(
             file_backed_base_path: "./tests/data/".to_owned(),
         },
         max_retries: 5,
+        compression: ObjectStoreCompression::None,
+        compression_level: None,
+        retry_initial_backoff_ms: None,
+        retry_max_backoff_ms: None,
+        retry_budget: None,
+        circuit_breaker_failure_threshold: None,
+        circuit_breaker_reset_timeout_ms: None,
+        encryption: ObjectStoreEncryption::None,
+        encrypted_buckets: None,
+        encryption_key: None,
+        encryption_kms_key_id: None,
+        encryption_kms_region: None,
+        encryption_strict: false,
     };
     let object_store = ObjectStoreFactory::new(object_store_config)
         .create_store()
@@ -0,0 +1,263 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    net::SocketAddr,
+    time::Duration,
+};
+
+use anyhow::Context as _;
+use axum::{extract::State, routing::get, Json, Router};
+use chrono::{NaiveDateTime, Utc};
+use serde::Serialize;
+use tokio::{sync::watch, time::sleep};
+use zksync_dal::{fri_prover_dal::types::OldestUnprovenL1Batch, ConnectionPool};
+use zksync_types::basic_fri_types::AggregationRound;
+
+use crate::metrics::METRICS;
+
+/// Window used to compute average proving times for the time-to-proof estimate.
+const PROVING_TIME_WINDOW: Duration = Duration::from_secs(3_600);
+/// Maximum number of proved jobs observed in proving time histograms per poll.
+const PROVED_JOBS_BATCH_SIZE: usize = 10_000;
+
+/// Number of prover jobs in a certain aggregation round, grouped by status.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub(crate) struct RoundQueueDepth {
+    pub queued: usize,
+    pub in_progress: usize,
+    pub failed: usize,
+}
+
+/// Prover queue state served by the capacity API.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub(crate) struct CapacitySnapshot {
+    /// Queue depth keyed by the aggregation round.
+    pub queue: BTreeMap<String, RoundQueueDepth>,
+    /// Number of jobs that are currently being proved. Used as the number of busy provers.
+    pub busy_provers: usize,
+    pub oldest_unproven_l1_batch: Option<u32>,
+    pub oldest_unproven_l1_batch_age_secs: Option<u64>,
+    pub estimated_time_to_proof_secs: Option<u64>,
+}
+
+/// Periodically reports prover queue depth, proving times and the estimated time to proof
+/// for the oldest unproven L1 batch as metrics, and publishes them for the capacity API.
+#[derive(Debug)]
+pub(crate) struct CapacityReporter {
+    pool: ConnectionPool,
+    poll_duration: Duration,
+    snapshot_sender: watch::Sender<CapacitySnapshot>,
+    /// Time of the latest proved job observed in proving time histograms.
+    last_proved_at: NaiveDateTime,
+}
+
+impl CapacityReporter {
+    pub(crate) fn new(pool: ConnectionPool, poll_duration: Duration) -> Self {
+        let (snapshot_sender, _) = watch::channel(CapacitySnapshot::default());
+        Self {
+            pool,
+            poll_duration,
+            snapshot_sender,
+            last_proved_at: Utc::now().naive_utc(),
+        }
+    }
+
+    pub(crate) fn subscribe(&self) -> watch::Receiver<CapacitySnapshot> {
+        self.snapshot_sender.subscribe()
+    }
+
+    async fn report(&mut self) -> anyhow::Result<()> {
+        let mut storage = self
+            .pool
+            .access_storage()
+            .await
+            .context("access_storage()")?;
+
+        let proved_jobs = storage
+            .fri_prover_jobs_dal()
+            .get_proved_jobs_since(self.last_proved_at, PROVED_JOBS_BATCH_SIZE)
+            .await
+            .context("get_proved_jobs_since()")?;
+        for job in &proved_jobs {
+            let labels = (job.circuit_id, job.aggregation_round.to_string());
+            METRICS.proving_time[&labels].observe(job.time_taken);
+        }
+        if let Some(last_job) = proved_jobs.last() {
+            self.last_proved_at = last_job.proved_at;
+        }
+
+        let stats = storage.fri_prover_jobs_dal().get_prover_jobs_stats().await;
+        let average_proving_times = storage
+            .fri_prover_jobs_dal()
+            .get_average_proving_times(PROVING_TIME_WINDOW)
+            .await
+            .context("get_average_proving_times()")?;
+        let oldest_batch = storage
+            .fri_prover_jobs_dal()
+            .get_oldest_unproven_l1_batch()
+            .await
+            .context("get_oldest_unproven_l1_batch()")?;
+        drop(storage);
+
+        let mut queue = BTreeMap::<_, RoundQueueDepth>::new();
+        for ((_, aggregation_round), job_stats) in stats {
+            let round = AggregationRound::from(aggregation_round);
+            let depth = queue.entry(round.to_string()).or_default();
+            depth.queued += job_stats.queued;
+            depth.in_progress += job_stats.in_progress;
+            depth.failed += job_stats.failed;
+        }
+        let busy_provers = queue.values().map(|depth| depth.in_progress).sum();
+
+        let estimated_time_to_proof = oldest_batch
+            .as_ref()
+            .and_then(|batch| estimate_time_to_proof(batch, &average_proving_times, busy_provers));
+        let snapshot = CapacitySnapshot {
+            queue,
+            busy_provers,
+            oldest_unproven_l1_batch: oldest_batch.as_ref().map(|batch| batch.l1_batch_number.0),
+            oldest_unproven_l1_batch_age_secs: oldest_batch
+                .and_then(|batch| batch.age)
+                .map(|age| age.as_secs()),
+            estimated_time_to_proof_secs: estimated_time_to_proof.map(|eta| eta.as_secs()),
+        };
+        Self::report_metrics(&snapshot, estimated_time_to_proof);
+        self.snapshot_sender.send_replace(snapshot);
+        Ok(())
+    }
+
+    fn report_metrics(snapshot: &CapacitySnapshot, estimated_time_to_proof: Option<Duration>) {
+        for (round, depth) in &snapshot.queue {
+            METRICS.queue_depth[&(round.clone(), "queued")].set(depth.queued as u64);
+            METRICS.queue_depth[&(round.clone(), "in_progress")].set(depth.in_progress as u64);
+            METRICS.queue_depth[&(round.clone(), "failed")].set(depth.failed as u64);
+        }
+        METRICS.busy_provers.set(snapshot.busy_provers as u64);
+        if let Some(l1_batch_number) = snapshot.oldest_unproven_l1_batch {
+            METRICS.oldest_unproven_l1_batch.set(l1_batch_number.into());
+        }
+        let age = snapshot
+            .oldest_unproven_l1_batch_age_secs
+            .map_or(Duration::ZERO, Duration::from_secs);
+        METRICS.oldest_unproven_l1_batch_age.set(age);
+        METRICS
+            .estimated_time_to_proof
+            .set(estimated_time_to_proof.unwrap_or_default());
+    }
+
+    pub(crate) async fn run(
+        mut self,
+        mut stop_receiver: watch::Receiver<bool>,
+    ) -> anyhow::Result<()> {
+        tracing::info!("Starting prover capacity reporter");
+        loop {
+            if *stop_receiver.borrow() {
+                break;
+            }
+            if let Err(err) = self.report().await {
+                tracing::error!("Failed reporting prover capacity: {err:#}");
+            }
+            tokio::select! {
+                _ = stop_receiver.changed() => break,
+                _ = sleep(self.poll_duration) => {}
+            }
+        }
+        tracing::info!("Stop signal received, shutting down prover capacity reporter");
+        Ok(())
+    }
+}
+
+/// Estimates the time to prove all unfinished jobs for the oldest unproven L1 batch, assuming that
+/// jobs are distributed evenly among `busy_provers` (or a single prover if no provers are busy).
+/// Only jobs that are already created are accounted for, i.e., the estimate doesn't include
+/// aggregation rounds that haven't started for the batch yet.
+///
+/// Returns `None` if there is no proving time data to base the estimate on.
+fn estimate_time_to_proof(
+    batch: &OldestUnprovenL1Batch,
+    average_proving_times: &HashMap<(u8, u8), Duration>,
+    busy_provers: usize,
+) -> Option<Duration> {
+    if average_proving_times.is_empty() {
+        return None;
+    }
+    // Used for circuits without recent proving time data.
+    let fallback_time =
+        average_proving_times.values().sum::<Duration>() / average_proving_times.len() as u32;
+
+    let mut total_time = Duration::ZERO;
+    let mut max_job_time = Duration::ZERO;
+    for (key, &job_count) in &batch.unfinished_jobs {
+        let job_time = average_proving_times
+            .get(key)
+            .copied()
+            .unwrap_or(fallback_time);
+        total_time += job_time * job_count as u32;
+        max_job_time = max_job_time.max(job_time);
+    }
+    // Jobs cannot be split among provers, so the batch cannot be proved faster than its slowest job.
+    Some((total_time / busy_provers.max(1) as u32).max(max_job_time))
+}
+
+async fn get_capacity(
+    snapshot_receiver: State<watch::Receiver<CapacitySnapshot>>,
+) -> Json<CapacitySnapshot> {
+    Json(snapshot_receiver.borrow().clone())
+}
+
+/// Runs an HTTP server serving the latest [`CapacitySnapshot`] on `GET /capacity`.
+pub(crate) async fn run_capacity_api(
+    port: u16,
+    snapshot_receiver: watch::Receiver<CapacitySnapshot>,
+    mut stop_receiver: watch::Receiver<bool>,
+) -> anyhow::Result<()> {
+    let bind_address = SocketAddr::from(([0, 0, 0, 0], port));
+    tracing::info!("Starting prover capacity API on {bind_address}");
+
+    let app = Router::new()
+        .route("/capacity", get(get_capacity))
+        .with_state(snapshot_receiver);
+    axum::Server::bind(&bind_address)
+        .serve(app.into_make_service())
+        .with_graceful_shutdown(async move {
+            stop_receiver.changed().await.ok();
+            tracing::info!("Stop signal received, prover capacity API is shutting down");
+        })
+        .await
+        .context("prover capacity API failed")
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::L1BatchNumber;
+
+    use super::*;
+
+    #[test]
+    fn estimating_time_to_proof() {
+        let batch = OldestUnprovenL1Batch {
+            l1_batch_number: L1BatchNumber(1),
+            age: None,
+            unfinished_jobs: HashMap::from([((1, 0), 10), ((2, 0), 2)]),
+        };
+        let secs = Duration::from_secs;
+
+        let no_times = HashMap::new();
+        assert_eq!(estimate_time_to_proof(&batch, &no_times, 4), None);
+
+        let times = HashMap::from([((1, 0), secs(30)), ((2, 0), secs(150))]);
+        // 10 * 30s + 2 * 150s = 600s
+        assert_eq!(estimate_time_to_proof(&batch, &times, 0), Some(secs(600)));
+        assert_eq!(estimate_time_to_proof(&batch, &times, 1), Some(secs(600)));
+        assert_eq!(estimate_time_to_proof(&batch, &times, 2), Some(secs(300)));
+        // Bounded by the slowest job
+        assert_eq!(estimate_time_to_proof(&batch, &times, 100), Some(secs(150)));
+
+        // Circuit 2 uses the average over known circuits.
+        let partial_times = HashMap::from([((1, 0), secs(30)), ((3, 1), secs(90))]);
+        // 10 * 30s + 2 * 60s = 420s
+        assert_eq!(
+            estimate_time_to_proof(&batch, &partial_times, 1),
+            Some(secs(420))
+        );
+    }
+}
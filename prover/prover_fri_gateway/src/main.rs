@@ -7,12 +7,19 @@ use zksync_dal::ConnectionPool;
 use zksync_env_config::{object_store::ProverObjectStoreConfig, FromEnv};
 use zksync_object_store::ObjectStoreFactory;
 use zksync_prover_interface::api::{ProofGenerationDataRequest, SubmitProofRequest};
+use zksync_types::L1BatchNumber;
 use zksync_utils::wait_for_tasks::wait_for_tasks;
 
-use crate::api_data_fetcher::{PeriodicApiStruct, PROOF_GENERATION_DATA_PATH, SUBMIT_PROOF_PATH};
+use crate::{
+    api_data_fetcher::{PeriodicApiStruct, PROOF_GENERATION_DATA_PATH, SUBMIT_PROOF_PATH},
+    capacity_reporter::{run_capacity_api, CapacityReporter},
+    priority_updater::PriorityUpdater,
+};
 
 mod api_data_fetcher;
+mod capacity_reporter;
 mod metrics;
+mod priority_updater;
 mod proof_gen_data_fetcher;
 mod proof_submitter;
 
@@ -32,7 +39,7 @@ async fn main() -> anyhow::Result<()> {
             .expect("Invalid Sentry URL")
             .with_sentry_environment(observability_config.sentry_environment);
     }
-    let _guard = builder.build();
+    let _guard = builder.build()?;
 
     let config =
         FriProverGatewayConfig::from_env().context("FriProverGatewayConfig::from_env()")?;
@@ -55,6 +62,18 @@ async fn main() -> anyhow::Result<()> {
         poll_duration: config.api_poll_duration(),
         client: Client::new(),
     };
+    let priority_updater = PriorityUpdater {
+        pool: pool.clone(),
+        proof_deadline: config.proof_deadline(),
+        urgent_l1_batches: config
+            .urgent_l1_batches
+            .iter()
+            .copied()
+            .map(L1BatchNumber)
+            .collect(),
+        poll_duration: config.api_poll_duration(),
+    };
+    let capacity_reporter = CapacityReporter::new(pool.clone(), config.api_poll_duration());
     let proof_gen_data_fetcher = PeriodicApiStruct {
         blob_store: store_factory.create_store().await,
         pool,
@@ -76,7 +95,7 @@ async fn main() -> anyhow::Result<()> {
 
     tracing::info!("Starting Fri Prover Gateway");
 
-    let tasks = vec![
+    let mut tasks = vec![
         tokio::spawn(
             PrometheusExporterConfig::pull(config.prometheus_listener_port)
                 .run(stop_receiver.clone()),
@@ -84,8 +103,22 @@ async fn main() -> anyhow::Result<()> {
         tokio::spawn(
             proof_gen_data_fetcher.run::<ProofGenerationDataRequest>(stop_receiver.clone()),
         ),
-        tokio::spawn(proof_submitter.run::<SubmitProofRequest>(stop_receiver)),
+        tokio::spawn(proof_submitter.run::<SubmitProofRequest>(stop_receiver.clone())),
     ];
+    if let Some(port) = config.capacity_api_port {
+        let snapshot_receiver = capacity_reporter.subscribe();
+        tasks.push(tokio::spawn(run_capacity_api(
+            port,
+            snapshot_receiver,
+            stop_receiver.clone(),
+        )));
+    }
+    tasks.push(tokio::spawn(capacity_reporter.run(stop_receiver.clone())));
+    if priority_updater.is_enabled() {
+        tasks.push(tokio::spawn(priority_updater.run(stop_receiver)));
+    } else {
+        tracing::info!("Prover job priorities are not configured; priority updater is not started");
+    }
 
     let graceful_shutdown = None::<futures::future::Ready<()>>;
     let tasks_allowed_to_finish = false;
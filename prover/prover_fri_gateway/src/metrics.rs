@@ -1,10 +1,29 @@
-use vise::{Counter, LabeledFamily, Metrics};
+use std::time::Duration;
+
+use vise::{Buckets, Counter, Gauge, Histogram, LabeledFamily, Metrics};
 
 #[derive(Debug, Metrics)]
 #[metrics(prefix = "prover_fri_prover_fri_gateway")]
 pub(crate) struct ProverFriGatewayMetrics {
     #[metrics(labels = ["service_name"])]
     pub http_error: LabeledFamily<&'static str, Counter>,
+    /// Number of L1 batches which prover jobs were prioritized because of the approaching proof deadline.
+    pub prioritized_l1_batches: Counter,
+
+    /// Number of prover jobs in each aggregation round, grouped by status.
+    #[metrics(labels = ["aggregation_round", "status"])]
+    pub queue_depth: LabeledFamily<(String, &'static str), Gauge<u64>, 2>,
+    /// Number of prover jobs that are currently being proved.
+    pub busy_provers: Gauge<u64>,
+    /// Time taken to prove jobs, as recorded by provers.
+    #[metrics(buckets = Buckets::exponential(1.0..=4_096.0, 2.0), labels = ["circuit_id", "aggregation_round"])]
+    pub proving_time: LabeledFamily<(u8, String), Histogram<Duration>, 2>,
+    /// Number of the oldest L1 batch with unfinished prover jobs.
+    pub oldest_unproven_l1_batch: Gauge<u64>,
+    /// Time elapsed since the oldest unproven L1 batch has been received by the prover subsystem.
+    pub oldest_unproven_l1_batch_age: Gauge<Duration>,
+    /// Estimated time to prove all unfinished jobs for the oldest unproven L1 batch.
+    pub estimated_time_to_proof: Gauge<Duration>,
 }
 
 #[vise::register]
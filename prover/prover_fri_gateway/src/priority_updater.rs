@@ -0,0 +1,80 @@
+use std::time::Duration;
+
+use anyhow::Context as _;
+use tokio::{sync::watch, time::sleep};
+use zksync_dal::{fri_prover_dal::types::ProverJobPriority, ConnectionPool};
+use zksync_types::L1BatchNumber;
+
+use crate::metrics::METRICS;
+
+/// Periodically raises priority of prover jobs for L1 batches flagged as urgent by the operator,
+/// and for L1 batches that are close to the proof generation deadline.
+#[derive(Debug)]
+pub(crate) struct PriorityUpdater {
+    pub(crate) pool: ConnectionPool,
+    pub(crate) proof_deadline: Option<Duration>,
+    pub(crate) urgent_l1_batches: Vec<L1BatchNumber>,
+    pub(crate) poll_duration: Duration,
+}
+
+impl PriorityUpdater {
+    async fn update_priorities(&self) -> anyhow::Result<()> {
+        let mut storage = self
+            .pool
+            .access_storage()
+            .await
+            .context("access_storage()")?;
+        for &l1_batch_number in &self.urgent_l1_batches {
+            storage
+                .fri_prover_jobs_dal()
+                .prioritize_l1_batch(l1_batch_number, ProverJobPriority::Urgent)
+                .await
+                .with_context(|| format!("prioritize_l1_batch({l1_batch_number})"))?;
+        }
+
+        if let Some(proof_deadline) = self.proof_deadline {
+            let prioritized_batches = storage
+                .fri_prover_jobs_dal()
+                .prioritize_l1_batches_older_than(proof_deadline, ProverJobPriority::NearDeadline)
+                .await
+                .context("prioritize_l1_batches_older_than()")?;
+            if !prioritized_batches.is_empty() {
+                tracing::info!(
+                    "Prioritized prover jobs for L1 batches close to proof deadline: {prioritized_batches:?}"
+                );
+                METRICS
+                    .prioritized_l1_batches
+                    .inc_by(prioritized_batches.len() as u64);
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks whether any prover job priorities are configured.
+    pub(crate) fn is_enabled(&self) -> bool {
+        !self.urgent_l1_batches.is_empty() || self.proof_deadline.is_some()
+    }
+
+    pub(crate) async fn run(self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        tracing::info!(
+            "Starting prover job priority updater with urgent L1 batches {:?} and proof deadline {:?}",
+            self.urgent_l1_batches,
+            self.proof_deadline
+        );
+
+        loop {
+            if *stop_receiver.borrow() {
+                break;
+            }
+            if let Err(err) = self.update_priorities().await {
+                tracing::error!("Failed updating prover job priorities: {err:#}");
+            }
+            tokio::select! {
+                _ = stop_receiver.changed() => break,
+                _ = sleep(self.poll_duration) => {}
+            }
+        }
+        tracing::info!("Stop signal received, shutting down prover job priority updater");
+        Ok(())
+    }
+}
@@ -1,7 +1,10 @@
 use std::time::Instant;
 
 use serde::Serialize;
-use zksync_config::{configs::object_store::ObjectStoreMode, ObjectStoreConfig};
+use zksync_config::{
+    configs::object_store::{ObjectStoreCompression, ObjectStoreEncryption, ObjectStoreMode},
+    ObjectStoreConfig,
+};
 use zksync_dal::fri_prover_dal::types::{LeafAggregationJobMetadata, NodeAggregationJobMetadata};
 use zksync_object_store::ObjectStoreFactory;
 use zksync_prover_fri_types::{
@@ -33,6 +36,19 @@ async fn test_leaf_witness_gen() {
             file_backed_base_path: "./tests/data/leaf/".to_owned(),
         },
         max_retries: 5,
+        compression: ObjectStoreCompression::None,
+        compression_level: None,
+        retry_initial_backoff_ms: None,
+        retry_max_backoff_ms: None,
+        retry_budget: None,
+        circuit_breaker_failure_threshold: None,
+        circuit_breaker_reset_timeout_ms: None,
+        encryption: ObjectStoreEncryption::None,
+        encrypted_buckets: None,
+        encryption_key: None,
+        encryption_kms_key_id: None,
+        encryption_kms_region: None,
+        encryption_strict: false,
     };
     let object_store = ObjectStoreFactory::new(object_store_config)
         .create_store()
@@ -73,6 +89,19 @@ async fn test_node_witness_gen() {
             file_backed_base_path: "./tests/data/node/".to_owned(),
         },
         max_retries: 5,
+        compression: ObjectStoreCompression::None,
+        compression_level: None,
+        retry_initial_backoff_ms: None,
+        retry_max_backoff_ms: None,
+        retry_budget: None,
+        circuit_breaker_failure_threshold: None,
+        circuit_breaker_reset_timeout_ms: None,
+        encryption: ObjectStoreEncryption::None,
+        encrypted_buckets: None,
+        encryption_key: None,
+        encryption_kms_key_id: None,
+        encryption_kms_region: None,
+        encryption_strict: false,
     };
     let object_store = ObjectStoreFactory::new(object_store_config)
         .create_store()
@@ -114,6 +143,19 @@ async fn test_scheduler_witness_gen() {
             file_backed_base_path: "./tests/data/scheduler/".to_owned(),
         },
         max_retries: 5,
+        compression: ObjectStoreCompression::None,
+        compression_level: None,
+        retry_initial_backoff_ms: None,
+        retry_max_backoff_ms: None,
+        retry_budget: None,
+        circuit_breaker_failure_threshold: None,
+        circuit_breaker_reset_timeout_ms: None,
+        encryption: ObjectStoreEncryption::None,
+        encrypted_buckets: None,
+        encryption_key: None,
+        encryption_kms_key_id: None,
+        encryption_kms_region: None,
+        encryption_strict: false,
     };
     let object_store = ObjectStoreFactory::new(object_store_config)
         .create_store()
@@ -48,7 +48,7 @@ async fn main() -> anyhow::Result<()> {
             .expect("Invalid Sentry URL")
             .with_sentry_environment(observability_config.sentry_environment);
     }
-    let _guard = builder.build();
+    let _guard = builder.build()?;
 
     let opt = Opt::from_args();
     let config = FriWitnessVectorGeneratorConfig::from_env()